@@ -0,0 +1,441 @@
+//! Changes the withdrawal credentials of a validator from a BLS withdrawal key to an execution
+//! address, by signing and publishing a `BLSToExecutionChange`.
+//!
+//! The withdrawal key is accessed through a `WithdrawalSigner`, so that it may be held by a
+//! hardware wallet or HSM and never be written to disk. Two signers are provided:
+//!
+//! - `mnemonic`: derives the withdrawal key from a BIP-39 mnemonic, holding it in memory only.
+//! - `command`: delegates to an external program which talks to the device. The program is run
+//!   as `<COMMAND> pubkey`, printing the 0x-prefixed withdrawal public key, and as
+//!   `<COMMAND> sign <SIGNING_ROOT>`, printing the 0x-prefixed signature over the signing root.
+use crate::validator::recover::MNEMONIC_FLAG;
+use account_utils::{read_mnemonic_from_cli, STDIN_INPUTS_FLAG};
+use bls::{get_withdrawal_credentials, PublicKey, SecretKey, Signature};
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
+use environment::Environment;
+use eth2::{
+    types::{StateId, ValidatorId},
+    BeaconNodeHttpClient, Timeouts,
+};
+use eth2_wallet::bip39::Seed;
+use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType};
+use sensitive_url::SensitiveUrl;
+use std::fs::File;
+use std::path::PathBuf;
+use std::process::Command as ProcessCommand;
+use std::time::Duration;
+use types::{
+    Address, BlsToExecutionChange, ChainSpec, Domain, EthSpec, Hash256, PublicKeyBytes,
+    SignatureBytes, SignedBlsToExecutionChange, SignedRoot,
+};
+
+pub const CMD: &str = "bls-change";
+pub const VALIDATOR_INDEX_FLAG: &str = "validator-index";
+pub const EXECUTION_ADDRESS_FLAG: &str = "execution-address";
+pub const SIGNER_FLAG: &str = "signer";
+pub const WITHDRAWAL_KEY_INDEX_FLAG: &str = "withdrawal-key-index";
+pub const SIGNER_COMMAND_FLAG: &str = "signer-command";
+pub const BEACON_SERVER_FLAG: &str = "beacon-node";
+pub const OUTPUT_PATH_FLAG: &str = "output-path";
+pub const NO_BROADCAST_FLAG: &str = "no-broadcast";
+pub const NO_CONFIRMATION: &str = "no-confirmation";
+
+pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
+pub const CONFIRMATION_PHRASE: &str = "Change my withdrawal credentials";
+
+/// Provides signatures from a BLS withdrawal key without exposing the key itself.
+pub trait WithdrawalSigner {
+    /// Returns the public key of the withdrawal key.
+    fn public_key(&self) -> Result<PublicKey, String>;
+
+    /// Signs `signing_root` with the withdrawal key.
+    fn sign(&self, signing_root: Hash256) -> Result<Signature, String>;
+}
+
+/// Signs with a withdrawal key derived from a mnemonic, which is held in memory only.
+pub struct MnemonicSigner {
+    secret_key: SecretKey,
+}
+
+impl MnemonicSigner {
+    pub fn new(seed: &[u8], index: u32) -> Result<Self, String> {
+        let (secret, _) = recover_validator_secret_from_mnemonic(seed, index, KeyType::Withdrawal)
+            .map_err(|e| format!("Unable to derive withdrawal key: {:?}", e))?;
+        let secret_key = SecretKey::deserialize(secret.as_bytes())
+            .map_err(|e| format!("Invalid withdrawal key: {:?}", e))?;
+        Ok(Self { secret_key })
+    }
+}
+
+impl WithdrawalSigner for MnemonicSigner {
+    fn public_key(&self) -> Result<PublicKey, String> {
+        Ok(self.secret_key.public_key())
+    }
+
+    fn sign(&self, signing_root: Hash256) -> Result<Signature, String> {
+        Ok(self.secret_key.sign(signing_root))
+    }
+}
+
+/// Signs by running an external program, such as a hardware wallet client.
+pub struct CommandSigner {
+    command: String,
+}
+
+impl CommandSigner {
+    pub fn new(command: String) -> Self {
+        Self { command }
+    }
+
+    /// Runs the command with `args` appended, returning its trimmed standard output.
+    fn run(&self, args: &[&str]) -> Result<String, String> {
+        let mut words = self.command.split_whitespace();
+        let program = words.next().ok_or("The signer command is empty")?;
+        let output = ProcessCommand::new(program)
+            .args(words)
+            .args(args)
+            .output()
+            .map_err(|e| format!("Unable to run signer command: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Signer command failed with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        String::from_utf8(output.stdout)
+            .map(|stdout| stdout.trim().to_string())
+            .map_err(|e| format!("Signer command output is not UTF-8: {}", e))
+    }
+}
+
+impl WithdrawalSigner for CommandSigner {
+    fn public_key(&self) -> Result<PublicKey, String> {
+        self.run(&["pubkey"])?
+            .parse::<PublicKeyBytes>()
+            .and_then(|pubkey| {
+                pubkey
+                    .decompress()
+                    .map_err(|e| format!("Invalid public key: {:?}", e))
+            })
+            .map_err(|e| format!("Signer command returned an invalid public key: {}", e))
+    }
+
+    fn sign(&self, signing_root: Hash256) -> Result<Signature, String> {
+        self.run(&["sign", &format!("{:?}", signing_root)])?
+            .parse::<SignatureBytes>()
+            .and_then(|signature| {
+                signature
+                    .decompress()
+                    .map_err(|e| format!("Invalid signature: {:?}", e))
+            })
+            .map_err(|e| format!("Signer command returned an invalid signature: {}", e))
+    }
+}
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Signs and publishes a BLSToExecutionChange, permanently changing the withdrawal \
+            credentials of a validator from its BLS withdrawal key to an execution address. \
+            The withdrawal key may be held by a hardware wallet or HSM via --signer command.",
+        )
+        .arg(
+            Arg::new(VALIDATOR_INDEX_FLAG)
+                .long(VALIDATOR_INDEX_FLAG)
+                .value_name("VALIDATOR_INDEX")
+                .help("The index of the validator whose withdrawal credentials will be changed.")
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(EXECUTION_ADDRESS_FLAG)
+                .long(EXECUTION_ADDRESS_FLAG)
+                .value_name("EXECUTION_ADDRESS")
+                .help(
+                    "The execution address to which all withdrawals will be sent. This cannot \
+                    be changed later.",
+                )
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(SIGNER_FLAG)
+                .long(SIGNER_FLAG)
+                .value_name("SIGNER")
+                .help(
+                    "How to sign with the withdrawal key. \"mnemonic\" derives the key from a \
+                    mnemonic without storing it. \"command\" runs --signer-command as \
+                    `<COMMAND> pubkey` to print the withdrawal public key and \
+                    `<COMMAND> sign <SIGNING_ROOT>` to print a signature, allowing the key to \
+                    remain on a hardware device.",
+                )
+                .action(ArgAction::Set)
+                .value_parser(["mnemonic", "command"])
+                .default_value("mnemonic")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(MNEMONIC_FLAG)
+                .long(MNEMONIC_FLAG)
+                .value_name("MNEMONIC_PATH")
+                .help(
+                    "If present and using the mnemonic signer, the mnemonic will be read in from \
+                    this file.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(WITHDRAWAL_KEY_INDEX_FLAG)
+                .long(WITHDRAWAL_KEY_INDEX_FLAG)
+                .value_name("KEY_INDEX")
+                .help(
+                    "The EIP-2334 index of the withdrawal key derived from the mnemonic. This is \
+                    the position of the validator in the wallet, not its validator index.",
+                )
+                .action(ArgAction::Set)
+                .default_value("0")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(SIGNER_COMMAND_FLAG)
+                .long(SIGNER_COMMAND_FLAG)
+                .value_name("COMMAND")
+                .help("The command to run when using the command signer.")
+                .action(ArgAction::Set)
+                .required_if_eq(SIGNER_FLAG, "command")
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address to a beacon node HTTP API")
+                .default_value(DEFAULT_BEACON_NODE)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(OUTPUT_PATH_FLAG)
+                .long(OUTPUT_PATH_FLAG)
+                .value_name("PATH")
+                .help("Write the signed BLSToExecutionChange to this file as JSON.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(NO_BROADCAST_FLAG)
+                .long(NO_BROADCAST_FLAG)
+                .help(
+                    "Do not publish the signed BLSToExecutionChange to the beacon node, for \
+                    example to publish it later or from another machine.",
+                )
+                .requires(OUTPUT_PATH_FLAG)
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(NO_CONFIRMATION)
+                .long(NO_CONFIRMATION)
+                .help(
+                    "Signs without prompting for confirmation that you understand that the \
+                    change is irreversible. This should be used with caution",
+                )
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+}
+
+pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
+    let validator_index: u64 = clap_utils::parse_required(matches, VALIDATOR_INDEX_FLAG)?;
+    let execution_address: Address = clap_utils::parse_required(matches, EXECUTION_ADDRESS_FLAG)?;
+    let signer_kind: String = clap_utils::parse_required(matches, SIGNER_FLAG)?;
+    let output_path: Option<PathBuf> = clap_utils::parse_optional(matches, OUTPUT_PATH_FLAG)?;
+    let no_broadcast = matches.get_flag(NO_BROADCAST_FLAG);
+    let no_confirmation = matches.get_flag(NO_CONFIRMATION);
+    let stdin_inputs = cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG);
+
+    let spec = env.eth2_config().spec.clone();
+    let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&server_url)
+            .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+        Timeouts::set_all(Duration::from_secs(spec.seconds_per_slot)),
+    );
+
+    let signer: Box<dyn WithdrawalSigner> = match signer_kind.as_str() {
+        "command" => Box::new(CommandSigner::new(clap_utils::parse_required(
+            matches,
+            SIGNER_COMMAND_FLAG,
+        )?)),
+        _ => {
+            let mnemonic_path: Option<PathBuf> =
+                clap_utils::parse_optional(matches, MNEMONIC_FLAG)?;
+            let key_index: u32 = clap_utils::parse_required(matches, WITHDRAWAL_KEY_INDEX_FLAG)?;
+            let mnemonic = read_mnemonic_from_cli(mnemonic_path, stdin_inputs)?;
+            let seed = Seed::new(&mnemonic, "");
+            Box::new(MnemonicSigner::new(seed.as_bytes(), key_index)?)
+        }
+    };
+
+    let signed_change = env.runtime().block_on(sign_bls_change(
+        &client,
+        signer.as_ref(),
+        validator_index,
+        execution_address,
+        &spec,
+        stdin_inputs,
+        no_confirmation,
+    ))?;
+    let Some(signed_change) = signed_change else {
+        return Ok(());
+    };
+
+    if let Some(output_path) = output_path {
+        let file = File::create(&output_path)
+            .map_err(|e| format!("Unable to create {:?}: {:?}", output_path, e))?;
+        serde_json::to_writer_pretty(file, &[&signed_change])
+            .map_err(|e| format!("Unable to write {:?}: {:?}", output_path, e))?;
+        eprintln!("Wrote signed BLSToExecutionChange to {:?}", output_path);
+    }
+
+    if !no_broadcast {
+        env.runtime()
+            .block_on(client.post_beacon_pool_bls_to_execution_changes(&[signed_change]))
+            .map_err(|e| format!("Failed to publish BLSToExecutionChange: {}", e))?;
+        eprintln!(
+            "Successfully published BLSToExecutionChange for validator {}",
+            validator_index
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that the withdrawal key of `signer` controls the withdrawal credentials of the
+/// validator, then signs the change once the user has confirmed it.
+///
+/// Returns `None` if the user did not confirm the change.
+async fn sign_bls_change(
+    client: &BeaconNodeHttpClient,
+    signer: &dyn WithdrawalSigner,
+    validator_index: u64,
+    execution_address: Address,
+    spec: &ChainSpec,
+    stdin_inputs: bool,
+    no_confirmation: bool,
+) -> Result<Option<SignedBlsToExecutionChange>, String> {
+    let genesis_validators_root = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Failed to get beacon genesis: {}", e))?
+        .data
+        .genesis_validators_root;
+    let validator = client
+        .get_beacon_states_validator_id(StateId::Head, &ValidatorId::Index(validator_index))
+        .await
+        .map_err(|e| format!("Failed to get validator details: {:?}", e))?
+        .ok_or_else(|| {
+            format!(
+                "Validator {} is unknown to the beacon node",
+                validator_index
+            )
+        })?
+        .data
+        .validator;
+
+    let withdrawal_pubkey = signer.public_key()?;
+    let expected_credentials = Hash256::from_slice(&get_withdrawal_credentials(
+        &withdrawal_pubkey,
+        spec.bls_withdrawal_prefix_byte,
+    ));
+    if validator.withdrawal_credentials.as_slice().first() != Some(&spec.bls_withdrawal_prefix_byte)
+    {
+        return Err(format!(
+            "Validator {} does not have BLS withdrawal credentials: {:?}",
+            validator_index, validator.withdrawal_credentials
+        ));
+    }
+    if validator.withdrawal_credentials != expected_credentials {
+        return Err(format!(
+            "Withdrawal key {} does not match the withdrawal credentials of validator {}",
+            withdrawal_pubkey, validator_index
+        ));
+    }
+
+    let change = BlsToExecutionChange {
+        validator_index,
+        from_bls_pubkey: withdrawal_pubkey.compress(),
+        to_execution_address: execution_address,
+    };
+
+    eprintln!(
+        "Changing the withdrawal address of validator {} to {:?}\n",
+        validator_index, execution_address
+    );
+    if !no_confirmation {
+        eprintln!("WARNING: THIS IS AN IRREVERSIBLE OPERATION\n");
+        eprintln!(
+            "All withdrawals of this validator will be sent to the address above. Please check \
+            it carefully."
+        );
+        eprintln!("Enter the phrase \"{}\" to confirm: ", CONFIRMATION_PHRASE);
+        if account_utils::read_input_from_user(stdin_inputs)? != CONFIRMATION_PHRASE {
+            eprintln!("Did not sign the BLSToExecutionChange. The phrase did not match.");
+            return Ok(None);
+        }
+    }
+
+    // The change is signed with the genesis fork version, so it is valid in every fork.
+    let domain = spec.compute_domain(
+        Domain::BlsToExecutionChange,
+        spec.genesis_fork_version,
+        genesis_validators_root,
+    );
+    let signing_root = change.signing_root(domain);
+    let signature = signer.sign(signing_root)?;
+    if !signature.verify(&withdrawal_pubkey, signing_root) {
+        return Err("The signer returned a signature which is not valid for the change".into());
+    }
+
+    Ok(Some(SignedBlsToExecutionChange {
+        message: change,
+        signature,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{FixedBytesExtended, MainnetEthSpec};
+
+    #[test]
+    fn mnemonic_signer_matches_direct_signing() {
+        let spec = MainnetEthSpec::default_spec();
+        let signer = MnemonicSigner::new(&[42; 64], 3).unwrap();
+        let change = BlsToExecutionChange {
+            validator_index: 7,
+            from_bls_pubkey: signer.public_key().unwrap().compress(),
+            to_execution_address: Address::repeat_byte(0x42),
+        };
+        let genesis_validators_root = Hash256::zero();
+
+        let expected = change
+            .clone()
+            .sign(&signer.secret_key, genesis_validators_root, &spec);
+        let domain = spec.compute_domain(
+            Domain::BlsToExecutionChange,
+            spec.genesis_fork_version,
+            genesis_validators_root,
+        );
+        let signature = signer.sign(change.signing_root(domain)).unwrap();
+
+        assert_eq!(signature, expected.signature);
+    }
+}
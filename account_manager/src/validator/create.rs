@@ -11,12 +11,15 @@ use directory::{
 };
 use environment::Environment;
 use eth2_wallet_manager::WalletManager;
+use rayon::prelude::*;
 use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use std::ffi::OsStr;
-use std::fs;
+use std::fs::{self, File};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use types::EthSpec;
 use validator_dir::Builder as ValidatorDirBuilder;
+use validator_manager::common::StandardDepositDataJson;
 
 pub const CMD: &str = "create";
 pub const WALLET_NAME_FLAG: &str = "wallet-name";
@@ -25,8 +28,16 @@ pub const DEPOSIT_GWEI_FLAG: &str = "deposit-gwei";
 pub const STORE_WITHDRAW_FLAG: &str = "store-withdrawal-keystore";
 pub const COUNT_FLAG: &str = "count";
 pub const AT_MOST_FLAG: &str = "at-most";
+pub const SHARDS_FLAG: &str = "shards";
+pub const SHARDS_DIR_FLAG: &str = "shards-dir";
+pub const DEPOSIT_DATA_DIR_FLAG: &str = "deposit-data-dir";
+pub const DEPOSIT_BATCH_SIZE_FLAG: &str = "deposit-batch-size";
 pub const WALLET_PASSWORD_PROMPT: &str = "Enter your wallet's password:";
 
+/// The number of validators whose keystores are generated in parallel before being written to
+/// disk and recorded in the wallet.
+const KEYGEN_BATCH_SIZE: usize = 256;
+
 pub fn cli_app() -> Command {
     Command::new(CMD)
         .about(
@@ -114,6 +125,93 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new(SHARDS_FLAG)
+                .long(SHARDS_FLAG)
+                .value_name("SHARD_COUNT")
+                .help(
+                    "Split the new validators into this many shards of consecutive validators, \
+                    one per validator client host. Each shard is written to \
+                    <shards-dir>/shard-<n> with its own validators dir, secrets dir and \
+                    slashing protection database, instead of --validator-dir and --secrets-dir.",
+                )
+                .requires(SHARDS_DIR_FLAG)
+                .conflicts_with(AT_MOST_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new(SHARDS_DIR_FLAG)
+                .long(SHARDS_DIR_FLAG)
+                .value_name("SHARDS_DIR")
+                .help("The directory in which to create the shards.")
+                .requires(SHARDS_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new(DEPOSIT_DATA_DIR_FLAG)
+                .long(DEPOSIT_DATA_DIR_FLAG)
+                .value_name("DEPOSIT_DATA_DIR")
+                .help(
+                    "Write the deposit data of the new validators to this directory, as JSON \
+                    files compatible with the Staking Launchpad and as CSV files for batch \
+                    deposit contracts. The deposits are split into files of at most \
+                    --deposit-batch-size validators, and by shard when using --shards.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new(DEPOSIT_BATCH_SIZE_FLAG)
+                .long(DEPOSIT_BATCH_SIZE_FLAG)
+                .value_name("DEPOSIT_BATCH_SIZE")
+                .help("The maximum number of deposits in each deposit data file.")
+                .requires(DEPOSIT_DATA_DIR_FLAG)
+                .default_value("100")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+}
+
+/// The destination of a subset of the new validators.
+struct Shard {
+    validator_dir: PathBuf,
+    secrets_dir: PathBuf,
+    slashing_protection: SlashingDatabase,
+    deposits: Vec<StandardDepositDataJson>,
+}
+
+impl Shard {
+    fn open(validator_dir: PathBuf, secrets_dir: PathBuf) -> Result<Self, String> {
+        ensure_dir_exists(&validator_dir)?;
+        ensure_dir_exists(&secrets_dir)?;
+
+        let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
+        let slashing_protection = SlashingDatabase::open_or_create(&slashing_protection_path)
+            .map_err(|e| {
+                format!(
+                    "Unable to open or create slashing protection database at {}: {:?}",
+                    slashing_protection_path.display(),
+                    e
+                )
+            })?;
+
+        // Create an empty transaction and drops it. Used to test if the database is locked.
+        slashing_protection.test_transaction().map_err(|e| {
+            format!(
+                "Cannot create keys while the validator client is running: {:?}",
+                e
+            )
+        })?;
+
+        Ok(Self {
+            validator_dir,
+            secrets_dir,
+            slashing_protection,
+            deposits: vec![],
+        })
+    }
 }
 
 pub fn cli_run<E: EthSpec>(
@@ -156,13 +254,42 @@ pub fn cli_run<E: EthSpec>(
         ));
     }
 
-    ensure_dir_exists(&validator_dir)?;
-    ensure_dir_exists(&secrets_dir)?;
+    let shard_count: Option<usize> = clap_utils::parse_optional(matches, SHARDS_FLAG)?;
+    let shards_dir: Option<PathBuf> = clap_utils::parse_optional(matches, SHARDS_DIR_FLAG)?;
+    let deposit_data_dir: Option<PathBuf> =
+        clap_utils::parse_optional(matches, DEPOSIT_DATA_DIR_FLAG)?;
+    let deposit_batch_size: usize = clap_utils::parse_required(matches, DEPOSIT_BATCH_SIZE_FLAG)?;
+
+    if shard_count == Some(0) {
+        return Err(format!("--{} must be greater than zero", SHARDS_FLAG));
+    }
+    if deposit_batch_size == 0 {
+        return Err(format!(
+            "--{} must be greater than zero",
+            DEPOSIT_BATCH_SIZE_FLAG
+        ));
+    }
+    if deposit_data_dir.is_some() && spec.config_name.is_none() {
+        return Err(format!(
+            "--{} requires a network with a CONFIG_NAME",
+            DEPOSIT_DATA_DIR_FLAG
+        ));
+    }
+
+    let starting_validator_count = if shard_count.is_none() {
+        ensure_dir_exists(&validator_dir)?;
+        ensure_dir_exists(&secrets_dir)?;
 
-    eprintln!("secrets-dir path {:?}", secrets_dir);
-    eprintln!("wallets-dir path {:?}", wallet_base_dir);
+        eprintln!("secrets-dir path {:?}", secrets_dir);
+        eprintln!("wallets-dir path {:?}", wallet_base_dir);
 
-    let starting_validator_count = existing_validator_count(&validator_dir)?;
+        existing_validator_count(&validator_dir)?
+    } else {
+        eprintln!("shards-dir path {:?}", shards_dir);
+        eprintln!("wallets-dir path {:?}", wallet_base_dir);
+
+        0
+    };
 
     let n = match (count, at_most) {
         (Some(_), Some(_)) => Err(format!(
@@ -198,64 +325,153 @@ pub fn cli_run<E: EthSpec>(
         .wallet_by_name(&wallet_name)
         .map_err(|e| format!("Unable to open wallet: {:?}", e))?;
 
-    let slashing_protection_path = validator_dir.join(SLASHING_PROTECTION_FILENAME);
-    let slashing_protection =
-        SlashingDatabase::open_or_create(&slashing_protection_path).map_err(|e| {
-            format!(
-                "Unable to open or create slashing protection database at {}: {:?}",
-                slashing_protection_path.display(),
-                e
-            )
-        })?;
-
-    // Create an empty transaction and drops it. Used to test if the database is locked.
-    slashing_protection.test_transaction().map_err(|e| {
-        format!(
-            "Cannot create keys while the validator client is running: {:?}",
-            e
-        )
-    })?;
+    let mut shards = match (shard_count, shards_dir) {
+        (Some(shard_count), Some(shards_dir)) => (0..shard_count)
+            .map(|i| {
+                let shard_dir = shards_dir.join(format!("shard-{}", i));
+                Shard::open(shard_dir.join("validators"), shard_dir.join("secrets"))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        _ => vec![Shard::open(validator_dir, secrets_dir)?],
+    };
+    // Assign consecutive validators to each shard, spreading any remainder across the shards.
+    let num_shards = shards.len();
+    let shard_index = |i: usize| i * num_shards / n;
+    let shard_dirs = shards
+        .iter()
+        .map(|shard| (shard.validator_dir.clone(), shard.secrets_dir.clone()))
+        .collect::<Vec<_>>();
 
-    for i in 0..n {
-        let voting_password = random_password();
-        let withdrawal_password = random_password();
+    let store_withdrawal_keystore = matches.get_flag(STORE_WITHDRAW_FLAG);
+    let mut created = 0;
+    while created < n {
+        let batch_size = std::cmp::min(KEYGEN_BATCH_SIZE, n - created);
+        let passwords = (0..batch_size)
+            .map(|_| (random_password(), random_password()))
+            .collect::<Vec<_>>();
+        let password_bytes = passwords
+            .iter()
+            .map(|(voting, withdrawal)| (voting.as_bytes(), withdrawal.as_bytes()))
+            .collect::<Vec<_>>();
 
         let keystores = wallet
-            .next_validator(
-                wallet_password.as_bytes(),
-                voting_password.as_bytes(),
-                withdrawal_password.as_bytes(),
-            )
+            .next_validators(wallet_password.as_bytes(), &password_bytes)
             .map_err(|e| format!("Unable to create validator keys: {:?}", e))?;
 
-        let voting_pubkey = keystores.voting.public_key().ok_or_else(|| {
-            format!(
-                "Keystore public key is invalid: {}",
-                keystores.voting.pubkey()
-            )
-        })?;
-
-        slashing_protection
-            .register_validator(voting_pubkey.compress())
-            .map_err(|e| {
+        let mut voting_pubkeys = Vec::with_capacity(batch_size);
+        for (j, keystores) in keystores.iter().enumerate() {
+            let voting_pubkey = keystores.voting.public_key().ok_or_else(|| {
                 format!(
-                    "Error registering validator {}: {:?}",
-                    voting_pubkey.as_hex_string(),
-                    e
+                    "Keystore public key is invalid: {}",
+                    keystores.voting.pubkey()
                 )
             })?;
 
-        ValidatorDirBuilder::new(validator_dir.clone())
-            .password_dir(secrets_dir.clone())
-            .voting_keystore(keystores.voting, voting_password.as_bytes())
-            .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
-            .create_eth1_tx_data(deposit_gwei, &spec)
-            .store_withdrawal_keystore(matches.get_flag(STORE_WITHDRAW_FLAG))
-            .build()
-            .map_err(|e| format!("Unable to build validator directory: {:?}", e))?;
+            shards[shard_index(created + j)]
+                .slashing_protection
+                .register_validator(voting_pubkey.compress())
+                .map_err(|e| {
+                    format!(
+                        "Error registering validator {}: {:?}",
+                        voting_pubkey.as_hex_string(),
+                        e
+                    )
+                })?;
+            voting_pubkeys.push(voting_pubkey);
+        }
+
+        // Building the directories decrypts both keystores, so it is done in parallel.
+        let validator_dirs = keystores
+            .into_par_iter()
+            .zip(passwords.par_iter())
+            .enumerate()
+            .map(|(j, (keystores, (voting_password, withdrawal_password)))| {
+                let (validator_dir, secrets_dir) = &shard_dirs[shard_index(created + j)];
+                ValidatorDirBuilder::new(validator_dir.clone())
+                    .password_dir(secrets_dir.clone())
+                    .voting_keystore(keystores.voting, voting_password.as_bytes())
+                    .withdrawal_keystore(keystores.withdrawal, withdrawal_password.as_bytes())
+                    .create_eth1_tx_data(deposit_gwei, &spec)
+                    .store_withdrawal_keystore(store_withdrawal_keystore)
+                    .build()
+                    .map_err(|e| format!("Unable to build validator directory: {:?}", e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for (j, (validator_dir, voting_pubkey)) in
+            validator_dirs.iter().zip(&voting_pubkeys).enumerate()
+        {
+            let i = created + j;
+            if deposit_data_dir.is_some() {
+                let deposit_data = validator_dir
+                    .eth1_deposit_data()
+                    .map_err(|e| format!("Unable to read deposit data: {:?}", e))?
+                    .ok_or("Validator directory is missing deposit data")?
+                    .deposit_data;
+                shards[shard_index(i)]
+                    .deposits
+                    .push(StandardDepositDataJson::from_deposit_data(
+                        deposit_data,
+                        &spec,
+                    )?);
+            }
+
+            println!("{}/{}\t{}", i + 1, n, voting_pubkey.as_hex_string());
+        }
+
+        created += batch_size;
+    }
+
+    if let Some(deposit_data_dir) = deposit_data_dir {
+        ensure_dir_exists(&deposit_data_dir)?;
+        let sharded = num_shards > 1;
+        for (i, shard) in shards.iter().enumerate() {
+            for (batch, deposits) in shard.deposits.chunks(deposit_batch_size).enumerate() {
+                let file_stem = if sharded {
+                    format!("deposit_data-shard-{}-{}", i, batch)
+                } else {
+                    format!("deposit_data-{}", batch)
+                };
+                write_deposit_data(&deposit_data_dir, &file_stem, deposits)?;
+            }
+        }
+        eprintln!("Deposit data written to {:?}", deposit_data_dir);
+    }
+
+    Ok(())
+}
 
-        println!("{}/{}\t{}", i + 1, n, voting_pubkey.as_hex_string());
+/// Writes `deposits` to `<file_stem>.json`, in the format of the `staking-deposit-cli`, and to
+/// `<file_stem>.csv`, with the arguments of a deposit contract call for each deposit.
+fn write_deposit_data(
+    dir: &Path,
+    file_stem: &str,
+    deposits: &[StandardDepositDataJson],
+) -> Result<(), String> {
+    let json_path = dir.join(format!("{}.json", file_stem));
+    let json_file = File::create(&json_path)
+        .map_err(|e| format!("Unable to create {:?}: {:?}", json_path, e))?;
+    serde_json::to_writer(json_file, deposits)
+        .map_err(|e| format!("Unable to write {:?}: {:?}", json_path, e))?;
+
+    let csv_path = dir.join(format!("{}.csv", file_stem));
+    let mut csv_file =
+        File::create(&csv_path).map_err(|e| format!("Unable to create {:?}: {:?}", csv_path, e))?;
+    let mut csv =
+        String::from("pubkey,withdrawal_credentials,signature,deposit_data_root,amount\n");
+    for deposit in deposits {
+        csv.push_str(&format!(
+            "{:?},{:?},{:?},{:?},{}\n",
+            deposit.pubkey,
+            deposit.withdrawal_credentials,
+            deposit.signature,
+            deposit.deposit_data_root,
+            deposit.amount
+        ));
     }
+    csv_file
+        .write_all(csv.as_bytes())
+        .map_err(|e| format!("Unable to write {:?}: {:?}", csv_path, e))?;
 
     Ok(())
 }
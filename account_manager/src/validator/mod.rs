@@ -1,3 +1,4 @@
+pub mod bls_change;
 pub mod create;
 pub mod exit;
 pub mod import;
@@ -49,6 +50,7 @@ pub fn cli_app() -> Command {
         .subcommand(recover::cli_app())
         .subcommand(slashing_protection::cli_app())
         .subcommand(exit::cli_app())
+        .subcommand(bls_change::cli_app())
 }
 
 pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
@@ -70,6 +72,7 @@ pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
         Some((exit::CMD, matches)) => exit::cli_run(matches, env),
+        Some((bls_change::CMD, matches)) => bls_change::cli_run(matches, env),
         Some((unknown, _)) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
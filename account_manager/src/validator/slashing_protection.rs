@@ -1,26 +1,36 @@
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
 use environment::Environment;
+use serde_json::json;
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
-    SLASHING_PROTECTION_FILENAME,
+    InterchangeError, InterchangeImportOutcome, SlashingDatabase, SLASHING_PROTECTION_FILENAME,
+    SLASHING_PROTECTION_HISTORY_EPOCHS,
 };
 use std::fs::File;
-use std::path::PathBuf;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
 pub const EXPORT_CMD: &str = "export";
+pub const PRUNE_CMD: &str = "prune";
 
 pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
 
 pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const REPORT_FLAG: &str = "report";
+pub const BEFORE_EPOCH_FLAG: &str = "before-epoch";
+pub const SKIP_COMPACTION_FLAG: &str = "skip-compaction";
 
 pub fn cli_app() -> Command {
     Command::new(CMD)
-        .about("Import or export slashing protection data to or from another client")
+        .about(
+            "Import or export slashing protection data to or from another client, or prune \
+             old records from the database",
+        )
         .display_order(0)
         .subcommand(
             Command::new(IMPORT_CMD)
@@ -30,7 +40,18 @@ pub fn cli_app() -> Command {
                         .action(ArgAction::Set)
                         .value_name("FILE")
                          .display_order(0)
-                        .help("The slashing protection interchange file to import (.json)"),
+                        .help("The slashing protection interchange file to import (.json or .json.gz)"),
+                )
+                .arg(
+                    Arg::new(REPORT_FLAG)
+                        .long(REPORT_FLAG)
+                        .action(ArgAction::Set)
+                        .value_name("FILE")
+                        .help(
+                            "Write the result of the import for each validator to FILE as JSON, \
+                             instead of printing it",
+                        )
+                        .display_order(0)
                 )
         )
         .subcommand(
@@ -55,6 +76,37 @@ pub fn cli_app() -> Command {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new(PRUNE_CMD)
+                .about(
+                    "Remove old records from the slashing protection database and compact it. \
+                     The validator client must not be running.",
+                )
+                .arg(
+                    Arg::new(BEFORE_EPOCH_FLAG)
+                        .long(BEFORE_EPOCH_FLAG)
+                        .action(ArgAction::Set)
+                        .value_name("EPOCH")
+                        .required(true)
+                        .help(
+                            "Remove attestations with target epochs prior to EPOCH, and blocks \
+                             with slots prior to the start of EPOCH. The latest attestation and \
+                             block of each validator are always kept",
+                        )
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new(SKIP_COMPACTION_FLAG)
+                        .long(SKIP_COMPACTION_FLAG)
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help(
+                            "Don't rebuild the database file after pruning. The space freed by \
+                             pruning will be reused, but not returned to the filesystem",
+                        )
+                        .display_order(0)
+                )
+        )
 }
 
 pub fn cli_run<E: EthSpec>(
@@ -74,6 +126,8 @@ pub fn cli_run<E: EthSpec>(
     match matches.subcommand() {
         Some((IMPORT_CMD, matches)) => {
             let import_filename: PathBuf = clap_utils::parse_required(matches, IMPORT_FILE_ARG)?;
+            let report_filename: Option<PathBuf> =
+                clap_utils::parse_optional(matches, REPORT_FLAG)?;
             let import_file = File::open(&import_filename).map_err(|e| {
                 format!(
                     "Unable to open import file at {}: {:?}",
@@ -82,11 +136,6 @@ pub fn cli_run<E: EthSpec>(
                 )
             })?;
 
-            eprint!("Loading JSON file into memory & deserializing");
-            let interchange = Interchange::from_json_reader(&import_file)
-                .map_err(|e| format!("Error parsing file for import: {:?}", e))?;
-            eprintln!(" [done].");
-
             let slashing_protection_database =
                 SlashingDatabase::open_or_create(&slashing_protection_db_path).map_err(|e| {
                     format!(
@@ -109,9 +158,31 @@ pub fn cli_run<E: EthSpec>(
                 }
             };
 
-            match slashing_protection_database
-                .import_interchange_info(interchange, genesis_validators_root)
-            {
+            eprint!("Importing records");
+            let result = slashing_protection_database
+                .import_interchange_from_reader(import_file, genesis_validators_root);
+            eprintln!(" [done].");
+
+            if let Some(report_filename) = &report_filename {
+                let (outcomes, imported) = match &result {
+                    Ok(outcomes) => (outcomes.as_slice(), true),
+                    Err(InterchangeError::AtomicBatchAborted(outcomes)) => {
+                        (outcomes.as_slice(), false)
+                    }
+                    Err(_) => (&[] as &[_], false),
+                };
+                write_import_report(report_filename, outcomes, imported)?;
+                eprintln!(
+                    "Results for {} validators written to {}",
+                    outcomes.len(),
+                    report_filename.display()
+                );
+            }
+
+            match result {
+                Ok(outcomes) if report_filename.is_some() => {
+                    eprintln!("All {} records imported successfully.", outcomes.len());
+                }
                 Ok(outcomes) => {
                     eprintln!("All records imported successfully:");
                     for outcome in &outcomes {
@@ -140,11 +211,18 @@ pub fn cli_run<E: EthSpec>(
                     }
                 }
                 Err(InterchangeError::AtomicBatchAborted(outcomes)) => {
-                    eprintln!("ERROR: import aborted due to one or more errors");
-                    for outcome in &outcomes {
-                        if let InterchangeImportOutcome::Failure { pubkey, error } = outcome {
-                            eprintln!("- {:?}", pubkey);
-                            eprintln!("    - error: {:?}", error);
+                    let num_failed = outcomes.iter().filter(|outcome| outcome.failed()).count();
+                    eprintln!(
+                        "ERROR: import aborted due to errors for {} of {} validators",
+                        num_failed,
+                        outcomes.len()
+                    );
+                    if report_filename.is_none() {
+                        for outcome in &outcomes {
+                            if let InterchangeImportOutcome::Failure { pubkey, error } = outcome {
+                                eprintln!("- {:?}", pubkey);
+                                eprintln!("    - error: {:?}", error);
+                            }
                         }
                     }
                     return Err("ERROR: import aborted due to errors, see above.\n\
@@ -204,18 +282,96 @@ pub fn cli_run<E: EthSpec>(
                     )
                 })?;
 
-            let interchange = slashing_protection_database
-                .export_interchange_info(genesis_validators_root, selected_pubkeys.as_deref())
-                .map_err(|e| format!("Error during export: {:?}", e))?;
-
             let output_file = File::create(export_filename)
                 .map_err(|e| format!("Error creating output file: {:?}", e))?;
 
-            interchange
-                .write_to(&output_file)
-                .map_err(|e| format!("Error writing output file: {:?}", e))?;
+            let num_records = slashing_protection_database
+                .export_interchange_info_to_writer(
+                    genesis_validators_root,
+                    selected_pubkeys.as_deref(),
+                    BufWriter::new(output_file),
+                )
+                .map_err(|e| format!("Error during export: {:?}", e))?;
 
-            eprintln!("Export completed successfully");
+            eprintln!(
+                "Export of {} validators completed successfully",
+                num_records
+            );
+
+            Ok(())
+        }
+        Some((PRUNE_CMD, matches)) => {
+            let before_epoch = Epoch::new(clap_utils::parse_required(matches, BEFORE_EPOCH_FLAG)?);
+            let skip_compaction = matches.get_flag(SKIP_COMPACTION_FLAG);
+
+            if !slashing_protection_db_path.exists() {
+                return Err(format!(
+                    "No slashing protection database exists at: {}",
+                    slashing_protection_db_path.display()
+                ));
+            }
+
+            let slashing_protection_database = SlashingDatabase::open(&slashing_protection_db_path)
+                .map_err(|e| {
+                    format!(
+                        "Unable to open database at {}: {:?}",
+                        slashing_protection_db_path.display(),
+                        e
+                    )
+                })?;
+
+            slashing_protection_database
+                .test_transaction()
+                .map_err(|e| {
+                    format!(
+                        "Unable to lock the database, the validator client may be running: {:?}",
+                        e
+                    )
+                })?;
+
+            // Refuse to prune records which the validator client would still retain, so that
+            // the pruned database is never less useful than one pruned automatically.
+            let max_target = slashing_protection_database
+                .max_attestation_target()
+                .map_err(|e| format!("Unable to read database: {:?}", e))?
+                .unwrap_or_else(|| Epoch::new(0));
+            let max_before_epoch =
+                max_target.saturating_sub(Epoch::new(SLASHING_PROTECTION_HISTORY_EPOCHS));
+            if before_epoch > max_before_epoch {
+                return Err(format!(
+                    "--{} must be at most {}, which is {} epochs prior to the latest \
+                     attestation in the database (epoch {})",
+                    BEFORE_EPOCH_FLAG,
+                    max_before_epoch,
+                    SLASHING_PROTECTION_HISTORY_EPOCHS,
+                    max_target
+                ));
+            }
+
+            let size_before = database_size(&slashing_protection_db_path);
+
+            eprint!("Pruning records prior to epoch {}", before_epoch);
+            let summary = slashing_protection_database
+                .prune_all_validators(before_epoch, E::slots_per_epoch())
+                .map_err(|e| format!("Error during pruning: {:?}", e))?;
+            eprintln!(" [done].");
+            eprintln!("- attestations removed: {}", summary.attestations_removed);
+            eprintln!("- blocks removed: {}", summary.blocks_removed);
+
+            if !skip_compaction {
+                eprint!("Compacting database");
+                slashing_protection_database
+                    .compact()
+                    .map_err(|e| format!("Error during compaction: {:?}", e))?;
+                eprintln!(" [done].");
+                eprintln!(
+                    "Database size reduced from {} to {} bytes",
+                    size_before,
+                    database_size(&slashing_protection_db_path)
+                );
+            }
+
+            eprintln!("Prune completed successfully");
 
             Ok(())
         }
@@ -223,3 +379,38 @@ pub fn cli_run<E: EthSpec>(
         _ => Err("No subcommand provided, see --help for options".to_string()),
     }
 }
+
+/// Write the outcome of importing each record to `path`.
+///
+/// If `imported` is false then the import was aborted, and no records were imported.
+fn write_import_report(
+    path: &Path,
+    outcomes: &[InterchangeImportOutcome],
+    imported: bool,
+) -> Result<(), String> {
+    let report = outcomes
+        .iter()
+        .map(|outcome| match outcome {
+            InterchangeImportOutcome::Success { pubkey, summary } => json!({
+                "pubkey": pubkey,
+                "status": if imported { "imported" } else { "not_imported" },
+                "max_block_slot": summary.max_block_slot,
+                "max_attestation_source": summary.max_attestation_source,
+                "max_attestation_target": summary.max_attestation_target,
+            }),
+            InterchangeImportOutcome::Failure { pubkey, error } => json!({
+                "pubkey": pubkey,
+                "status": "failed",
+                "error": format!("{:?}", error),
+            }),
+        })
+        .collect::<Vec<_>>();
+
+    let file = File::create(path).map_err(|e| format!("Error creating report file: {:?}", e))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), &report)
+        .map_err(|e| format!("Error writing report file: {:?}", e))
+}
+
+fn database_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map_or(0, |metadata| metadata.len())
+}
@@ -0,0 +1,207 @@
+//! Caches the committees of `AttestationData` which has already been verified on gossip.
+//!
+//! All members of a committee attest to the same `AttestationData` when they agree on the head,
+//! so without this cache unaggregated attestation verification repeats the same committee
+//! computation for every validator. Once an `AttestationData` passes the checks that depend only
+//! on it, its committee is cached so that attestations from the remaining committee members don't
+//! need to compute it again. The fork choice checks are still repeated for every attestation,
+//! since the block voted for may be pruned or invalidated after it's cached.
+use parking_lot::Mutex;
+use state_processing::common::attesting_indices_base;
+use state_processing::per_block_processing::errors::{
+    AttestationInvalid as Invalid, BlockOperationError,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use types::{
+    AttestationRef, BeaconStateError, CommitteeIndex, EthSpec, Hash256, IndexedAttestation,
+    IndexedAttestationElectra, Slot, VariableList,
+};
+
+/// Unaggregated attestations are only propagated for `ATTESTATION_PROPAGATION_SLOT_RANGE` slots,
+/// so there is no use keeping entries beyond that.
+const SLOTS_RETAINED: u64 = 32;
+
+/// Bounds the number of distinct `AttestationData` cached for each slot.
+///
+/// Honest validators produce a handful of `AttestationData` per committee, whilst an attacker can
+/// produce arbitrarily many (e.g., by varying the source checkpoint), so entries beyond this limit
+/// are not cached.
+const MAX_ENTRIES_PER_SLOT: usize = 1_024;
+
+type CacheKey = (Hash256, CommitteeIndex);
+
+/// The outcome of verifying an `AttestationData` for a single committee.
+#[derive(Debug, PartialEq)]
+pub struct VerifiedAttestationData {
+    committee: Vec<usize>,
+    committees_per_slot: u64,
+}
+
+impl VerifiedAttestationData {
+    pub fn new(committee: Vec<usize>, committees_per_slot: u64) -> Self {
+        Self {
+            committee,
+            committees_per_slot,
+        }
+    }
+
+    pub fn committees_per_slot(&self) -> u64 {
+        self.committees_per_slot
+    }
+
+    /// Returns the `IndexedAttestation` for an `attestation` from this committee.
+    pub fn get_indexed_attestation<E: EthSpec>(
+        &self,
+        attestation: AttestationRef<E>,
+    ) -> Result<IndexedAttestation<E>, BlockOperationError<Invalid>> {
+        match attestation {
+            AttestationRef::Base(att) => {
+                attesting_indices_base::get_indexed_attestation(&self.committee, att)
+            }
+            AttestationRef::Electra(att) => {
+                // The attestation is for a single committee, so the aggregation bits index
+                // directly into it.
+                if att.aggregation_bits.len() != self.committee.len() {
+                    return Err(BeaconStateError::InvalidBitfield.into());
+                }
+
+                let mut attesting_indices = self
+                    .committee
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| att.aggregation_bits.get(*i).unwrap_or(false))
+                    .map(|(_, &validator_index)| validator_index as u64)
+                    .collect::<Vec<_>>();
+                attesting_indices.sort_unstable();
+
+                Ok(IndexedAttestation::Electra(IndexedAttestationElectra {
+                    attesting_indices: VariableList::new(attesting_indices)?,
+                    data: att.data.clone(),
+                    signature: att.signature.clone(),
+                }))
+            }
+        }
+    }
+}
+
+/// Maps `(attestation_data_root, committee_index)` to the committee, for recent slots.
+#[derive(Default)]
+pub struct AttestationDataCache {
+    slots: Mutex<BTreeMap<Slot, HashMap<CacheKey, Arc<VerifiedAttestationData>>>>,
+}
+
+impl AttestationDataCache {
+    pub fn get(
+        &self,
+        slot: Slot,
+        data_root: Hash256,
+        committee_index: CommitteeIndex,
+    ) -> Option<Arc<VerifiedAttestationData>> {
+        self.slots
+            .lock()
+            .get(&slot)
+            .and_then(|entries| entries.get(&(data_root, committee_index)))
+            .cloned()
+    }
+
+    /// Caches the `verified_data`, pruning any slots which are too old to be relevant.
+    pub fn insert(
+        &self,
+        slot: Slot,
+        data_root: Hash256,
+        committee_index: CommitteeIndex,
+        verified_data: Arc<VerifiedAttestationData>,
+    ) {
+        let mut slots = self.slots.lock();
+
+        let oldest_slot = slot.saturating_sub(SLOTS_RETAINED);
+        if slots
+            .keys()
+            .next()
+            .is_some_and(|&first| first < oldest_slot)
+        {
+            *slots = slots.split_off(&oldest_slot);
+        }
+
+        let entries = slots.entry(slot).or_default();
+        if entries.len() < MAX_ENTRIES_PER_SLOT {
+            entries.insert((data_root, committee_index), verified_data);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{
+        AggregateSignature, AttestationBase, AttestationData, BitList, FixedBytesExtended,
+        MainnetEthSpec,
+    };
+
+    type E = MainnetEthSpec;
+
+    fn num_entries(cache: &AttestationDataCache) -> usize {
+        cache.slots.lock().values().map(HashMap::len).sum()
+    }
+
+    fn verified_data() -> Arc<VerifiedAttestationData> {
+        Arc::new(VerifiedAttestationData::new(vec![7, 3, 5], 4))
+    }
+
+    #[test]
+    fn get_and_prune() {
+        let cache = AttestationDataCache::default();
+        let root = Hash256::repeat_byte(1);
+
+        cache.insert(Slot::new(1), root, 0, verified_data());
+        assert_eq!(cache.get(Slot::new(1), root, 0), Some(verified_data()));
+        assert_eq!(cache.get(Slot::new(1), root, 1), None);
+        assert_eq!(cache.get(Slot::new(2), root, 0), None);
+
+        cache.insert(Slot::new(1 + SLOTS_RETAINED), root, 0, verified_data());
+        assert_eq!(num_entries(&cache), 2);
+
+        cache.insert(Slot::new(2 + SLOTS_RETAINED), root, 0, verified_data());
+        assert_eq!(num_entries(&cache), 2);
+        assert_eq!(cache.get(Slot::new(1), root, 0), None);
+    }
+
+    #[test]
+    fn limits_entries_per_slot() {
+        let cache = AttestationDataCache::default();
+        for i in 0..=MAX_ENTRIES_PER_SLOT {
+            cache.insert(
+                Slot::new(1),
+                Hash256::from_low_u64_be(i as u64),
+                0,
+                verified_data(),
+            );
+        }
+        assert_eq!(num_entries(&cache), MAX_ENTRIES_PER_SLOT);
+    }
+
+    #[test]
+    fn indexes_attestation() {
+        let mut aggregation_bits = BitList::with_capacity(3).unwrap();
+        aggregation_bits.set(1, true).unwrap();
+        let attestation = AttestationBase::<E> {
+            aggregation_bits,
+            data: AttestationData::default(),
+            signature: AggregateSignature::empty(),
+        };
+
+        let indexed = verified_data()
+            .get_indexed_attestation(AttestationRef::Base(&attestation))
+            .unwrap();
+        assert_eq!(indexed.attesting_indices_to_vec(), vec![3]);
+
+        let short_bits = AttestationBase::<E> {
+            aggregation_bits: BitList::with_capacity(2).unwrap(),
+            ..attestation
+        };
+        assert!(verified_data()
+            .get_indexed_attestation(AttestationRef::Base(&short_bits))
+            .is_err());
+    }
+}
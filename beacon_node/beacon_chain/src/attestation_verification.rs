@@ -35,9 +35,11 @@
 mod batch;
 
 use crate::{
+    attestation_data_cache::VerifiedAttestationData,
     metrics,
     observed_aggregates::{ObserveOutcome, ObservedAttestationKey},
     observed_attesters::Error as ObservedAttestersError,
+    own_key_guard::OwnKeyConflict,
     BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use bls::verify_signature_sets;
@@ -57,6 +59,7 @@ use state_processing::{
     },
 };
 use std::borrow::Cow;
+use std::sync::Arc;
 use strum::AsRefStr;
 use tree_hash::TreeHash;
 use types::{
@@ -212,6 +215,14 @@ pub enum Error {
     /// single-participant attestation from this validator for this epoch and should not observe
     /// another.
     PriorAttestationKnown { validator_index: u64, epoch: Epoch },
+    /// The attestation is signed by a validator guarded by the `OwnKeyGuard` and conflicts with an
+    /// attestation already seen from it.
+    ///
+    /// ## Peer scoring
+    ///
+    /// The peer is not necessarily faulty, but the attestation is slashable and must not be
+    /// propagated.
+    OwnKeyConflict(OwnKeyConflict),
     /// The attestation is attesting to a state that is later than itself. (Viz., attesting to the
     /// future).
     ///
@@ -661,12 +672,20 @@ impl<'a, T: BeaconChainTypes> VerifiedAggregatedAttestation<'a, T> {
     /// Run the checks that happen after the indexed attestation and signature have been checked.
     fn verify_late_checks(
         signed_aggregate: &SignedAggregateAndProof<T::EthSpec>,
+        indexed_attestation: &IndexedAttestation<T::EthSpec>,
         observed_attestation_key_root: Hash256,
         chain: &BeaconChain<T>,
     ) -> Result<(), Error> {
         let attestation = signed_aggregate.message().aggregate();
         let aggregator_index = signed_aggregate.message().aggregator_index();
 
+        chain
+            .observe_own_key_attestation(
+                indexed_attestation.attesting_indices_iter().copied(),
+                attestation.data(),
+            )
+            .map_err(Error::OwnKeyConflict)?;
+
         // Observe the valid attestation so we do not re-process it.
         //
         // It's important to double check that the attestation is not already known, otherwise two
@@ -764,9 +783,12 @@ impl<'a, T: BeaconChainTypes> VerifiedAggregatedAttestation<'a, T> {
             CheckAttestationSignature::No => (),
         };
 
-        if let Err(e) =
-            Self::verify_late_checks(signed_aggregate, observed_attestation_key_root, chain)
-        {
+        if let Err(e) = Self::verify_late_checks(
+            signed_aggregate,
+            &indexed_attestation,
+            observed_attestation_key_root,
+            chain,
+        ) {
             return Err(SignatureValid(indexed_attestation, e));
         }
 
@@ -819,6 +841,17 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
         // [New in Electra:EIP7549]
         verify_committee_index(attestation)?;
 
+        Ok(())
+    }
+
+    /// Run the checks which only depend on the `AttestationData` and fork choice.
+    ///
+    /// These checks are run even if the data is in `chain.attestation_data_cache`, since the head
+    /// block may have been pruned or invalidated since the data was cached.
+    fn verify_attestation_data(
+        attestation: AttestationRef<T::EthSpec>,
+        chain: &BeaconChain<T>,
+    ) -> Result<(), Error> {
         // Attestations must be for a known block. If the block is unknown, we simply drop the
         // attestation and do not delay consideration for later.
         //
@@ -832,6 +865,68 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
         Ok(())
     }
 
+    /// Returns the `indexed_attestation` and committee count per slot for the `attestation`.
+    ///
+    /// The attestation data is verified against fork choice, then the committee is read from
+    /// `chain.attestation_data_cache` if another member of the committee has already attested to
+    /// the same `AttestationData`. Otherwise the committee is computed and added to the cache.
+    fn obtain_cached_indexed_attestation_and_committees_per_slot(
+        attestation: AttestationRef<T::EthSpec>,
+        chain: &BeaconChain<T>,
+    ) -> Result<(IndexedAttestation<T::EthSpec>, CommitteesPerSlot), Error> {
+        let slot = attestation.data().slot;
+        let committee_index = attestation
+            .committee_index()
+            .ok_or(Error::NotExactlyOneCommitteeBitSet(0))?;
+        let data_root = attestation.data().tree_hash_root();
+
+        Self::verify_attestation_data(attestation, chain)?;
+
+        let verified_data = if let Some(verified_data) =
+            chain
+                .attestation_data_cache
+                .get(slot, data_root, committee_index)
+        {
+            metrics::inc_counter(&metrics::ATTESTATION_DATA_CACHE_HITS);
+            verified_data
+        } else {
+            metrics::inc_counter(&metrics::ATTESTATION_DATA_CACHE_MISSES);
+
+            let verified_data = map_attestation_committees(
+                chain,
+                attestation,
+                |(committees, committees_per_slot)| {
+                    committees
+                        .iter()
+                        .find(|committee| committee.index == committee_index)
+                        .map(|committee| {
+                            Arc::new(VerifiedAttestationData::new(
+                                committee.committee.to_vec(),
+                                committees_per_slot,
+                            ))
+                        })
+                        .ok_or(Error::NoCommitteeForSlotAndIndex {
+                            slot,
+                            index: committee_index,
+                        })
+                },
+            )?;
+
+            chain.attestation_data_cache.insert(
+                slot,
+                data_root,
+                committee_index,
+                verified_data.clone(),
+            );
+            verified_data
+        };
+
+        verified_data
+            .get_indexed_attestation(attestation)
+            .map(|indexed_attestation| (indexed_attestation, verified_data.committees_per_slot()))
+            .map_err(Error::Invalid)
+    }
+
     /// Run the checks that apply to the indexed attestation before the signature is checked.
     pub fn verify_middle_checks(
         attestation: AttestationRef<T::EthSpec>,
@@ -912,7 +1007,10 @@ impl<'a, T: BeaconChainTypes> IndexedUnaggregatedAttestation<'a, T> {
         }
 
         let (indexed_attestation, committees_per_slot) =
-            match obtain_indexed_attestation_and_committees_per_slot(chain, attestation) {
+            match Self::obtain_cached_indexed_attestation_and_committees_per_slot(
+                attestation,
+                chain,
+            ) {
                 Ok(x) => x,
                 Err(e) => {
                     return Err(SignatureNotChecked(attestation, e));
@@ -954,6 +1052,10 @@ impl<'a, T: BeaconChainTypes> VerifiedUnaggregatedAttestation<'a, T> {
         validator_index: u64,
         chain: &BeaconChain<T>,
     ) -> Result<(), Error> {
+        chain
+            .observe_own_key_attestation([validator_index], attestation.data())
+            .map_err(Error::OwnKeyConflict)?;
+
         // Now that the attestation has been fully verified, store that we have received a valid
         // attestation from this validator.
         //
@@ -1436,3 +1538,80 @@ where
         })
         .map_err(BeaconChainError::from)?
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
+    use types::MinimalEthSpec;
+
+    const VALIDATOR_COUNT: usize = 32;
+
+    /// Attestations whose data is in the attestation data cache must still be checked against
+    /// fork choice, since the block they vote for may be pruned or invalidated after the data is
+    /// cached.
+    #[tokio::test]
+    async fn cached_attestation_data_is_reverified() {
+        let harness = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(VALIDATOR_COUNT)
+            .fresh_ephemeral_store()
+            .mock_execution_layer()
+            .build();
+        harness.advance_slot();
+        harness
+            .extend_chain(
+                MinimalEthSpec::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::SomeValidators(vec![]),
+            )
+            .await;
+        harness.advance_slot();
+
+        let chain = &harness.chain;
+        let (state, state_root) = harness.get_current_state_and_root();
+        let attesters = (0..VALIDATOR_COUNT).collect::<Vec<_>>();
+        let (valid_attestation, subnet_id) = harness
+            .make_unaggregated_attestations(
+                &attesters,
+                &state,
+                state_root,
+                harness.head_block_root().into(),
+                harness.get_current_slot(),
+            )
+            .first()
+            .and_then(|committee_attestations| committee_attestations.first())
+            .cloned()
+            .expect("should produce an attestation");
+        chain
+            .verify_unaggregated_attestation_for_gossip(&valid_attestation, Some(subnet_id))
+            .expect("valid attestation should verify");
+
+        let slot = valid_attestation.data().slot;
+        let committee_index = valid_attestation.committee_index().unwrap();
+        let verified_data = chain
+            .attestation_data_cache
+            .get(
+                slot,
+                valid_attestation.data().tree_hash_root(),
+                committee_index,
+            )
+            .expect("attestation data should be cached");
+
+        // Cache the committee for data which votes for a block unknown to fork choice.
+        let unknown_root = Hash256::repeat_byte(42);
+        let mut attestation = valid_attestation.clone();
+        attestation.data_mut().beacon_block_root = unknown_root;
+        chain.attestation_data_cache.insert(
+            slot,
+            attestation.data().tree_hash_root(),
+            committee_index,
+            verified_data,
+        );
+
+        assert!(matches!(
+            chain.verify_unaggregated_attestation_for_gossip(&attestation, Some(subnet_id)),
+            Err(Error::UnknownHeadBlock { beacon_block_root }) if beacon_block_root == unknown_root
+        ));
+    }
+}
@@ -4,7 +4,8 @@
 //! In each function, attestations are "indexed" (i.e., the `IndexedAttestation` is computed), to
 //! determine if they should progress to signature verification. Then, all attestations which were
 //! successfully indexed have their signatures verified in a batch. If that signature batch fails
-//! then all attestation signatures are verified independently.
+//! then all attestation signatures are verified independently. Unaggregated attestations are
+//! batched per slot, so that a failed batch only affects the attestations from that slot.
 //!
 //! The outcome of each function is a `Vec<Result>` with a one-to-one mapping to the attestations
 //! supplied as input. Each result provides the exact success or failure result of the corresponding
@@ -14,12 +15,13 @@ use super::{
     VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
 };
 use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
-use bls::verify_signature_sets;
+use bls::{verify_signature_sets_batched, SignatureSet};
 use state_processing::signature_sets::{
     indexed_attestation_signature_set_from_pubkeys, signed_aggregate_selection_proof_signature_set,
     signed_aggregate_signature_set,
 };
 use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use types::*;
 
 /// Verify aggregated attestations using batch BLS signature verification.
@@ -106,7 +108,7 @@ where
         let _signature_verification_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_AGG_SIGNATURE_TIMES);
 
-        if verify_signature_sets(signature_sets.iter()) {
+        if verify_signature_sets_batched(&signature_sets) {
             // Since all the signatures verified in a batch, there's no reason for them to be
             // checked again later.
             check_signatures = CheckAttestationSignature::No
@@ -154,8 +156,8 @@ where
         })
         .collect::<Vec<_>>();
 
-    // May be set to `No` if batch verification succeeds.
-    let mut check_signatures = CheckAttestationSignature::Yes;
+    // The slots whose signatures all verified in a batch.
+    let mut verified_slots = HashSet::new();
 
     // Perform batch BLS verification, if any attestation signatures are worth checking.
     if num_partially_verified > 0 {
@@ -165,7 +167,10 @@ where
 
         let pubkey_cache = chain.validator_pubkey_cache.read();
 
-        let mut signature_sets = Vec::with_capacity(num_partially_verified);
+        // Batch the signatures by slot. Attestations from the same slot mostly share their
+        // `AttestationData`, and an invalid signature only causes the attestations from its own
+        // slot to be verified independently.
+        let mut signature_sets_by_slot: BTreeMap<Slot, Vec<SignatureSet>> = BTreeMap::new();
 
         // Iterate, flattening to get only the `Ok` values.
         for partially_verified in partial_results.iter().flatten() {
@@ -184,7 +189,10 @@ where
             )
             .map_err(BeaconChainError::SignatureSetError)?;
 
-            signature_sets.push(signature_set);
+            signature_sets_by_slot
+                .entry(indexed_attestation.data().slot)
+                .or_default()
+                .push(signature_set);
         }
 
         metrics::stop_timer(signature_setup_timer);
@@ -192,18 +200,26 @@ where
         let _signature_verification_timer =
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_TIMES);
 
-        if verify_signature_sets(signature_sets.iter()) {
-            // Since all the signatures verified in a batch, there's no reason for them to be
-            // checked again later.
-            check_signatures = CheckAttestationSignature::No
+        for (slot, signature_sets) in signature_sets_by_slot {
+            if verify_signature_sets_batched(&signature_sets) {
+                verified_slots.insert(slot);
+            }
         }
     }
 
-    // Complete the attestation verification, potentially verifying all signatures independently.
+    // Complete the attestation verification, potentially verifying signatures independently.
     let final_results = partial_results
         .into_iter()
         .map(|result| match result {
             Ok(partial) => {
+                // Since all the signatures from the slot verified in a batch, there's no reason
+                // for them to be checked again later.
+                let check_signatures =
+                    if verified_slots.contains(&partial.indexed_attestation.data().slot) {
+                        CheckAttestationSignature::No
+                    } else {
+                        CheckAttestationSignature::Yes
+                    };
                 VerifiedUnaggregatedAttestation::from_indexed(partial, chain, check_signatures)
             }
             Err(e) => Err(e),
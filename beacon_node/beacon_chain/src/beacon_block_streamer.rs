@@ -1,14 +1,14 @@
 use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes, BlockProcessStatus};
-use execution_layer::{ExecutionLayer, ExecutionPayloadBodyV1};
+use execution_layer::ExecutionLayer;
 use slog::{crit, debug, error, Logger};
 use std::collections::HashMap;
 use std::sync::Arc;
 use store::{DatabaseBlock, ExecutionPayloadDeneb};
 use tokio::sync::{
-    mpsc::{self, UnboundedSender},
+    mpsc::{self, Sender},
     RwLock,
 };
-use tokio_stream::{wrappers::UnboundedReceiverStream, Stream};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
 use types::{
     ChainSpec, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, Hash256, SignedBeaconBlock,
     SignedBlindedBeaconBlock, Slot,
@@ -18,6 +18,13 @@ use types::{
     ExecutionPayloadHeader,
 };
 
+/// The number of blocks loaded from the database and reconstructed together, which is also the
+/// number of streamed blocks buffered for the consumer.
+///
+/// Matches the number of by-range response chunks the network allows in flight on a connection,
+/// so that serving a peer which reads slowly holds at most a few chunks of blocks in memory.
+pub const BLOCK_STREAM_CHUNK_SIZE: usize = 16;
+
 #[derive(PartialEq)]
 pub enum CheckCaches {
     Yes,
@@ -57,7 +64,6 @@ struct BodiesByRange<E: EthSpec> {
 struct BlockParts<E: EthSpec> {
     blinded_block: Box<SignedBlindedBeaconBlock<E>>,
     header: Box<ExecutionPayloadHeader<E>>,
-    body: Option<Box<ExecutionPayloadBodyV1<E>>>,
 }
 
 impl<E: EthSpec> BlockParts<E> {
@@ -68,7 +74,6 @@ impl<E: EthSpec> BlockParts<E> {
         Self {
             blinded_block: blinded,
             header: Box::new(header),
-            body: None,
         }
     }
 
@@ -127,53 +132,44 @@ fn reconstruct_default_header_block<E: EthSpec>(
 
 fn reconstruct_blocks<E: EthSpec>(
     block_map: &mut HashMap<Hash256, Arc<BlockResult<E>>>,
-    block_parts_with_bodies: HashMap<Hash256, BlockParts<E>>,
+    block_parts_with_payloads: Vec<(
+        BlockParts<E>,
+        Result<Option<ExecutionPayload<E>>, execution_layer::Error>,
+    )>,
     log: &Logger,
 ) {
-    for (root, block_parts) in block_parts_with_bodies {
-        if let Some(payload_body) = block_parts.body {
-            match payload_body.to_payload(block_parts.header.as_ref().clone()) {
-                Ok(payload) => {
-                    let header_from_payload = ExecutionPayloadHeader::from(payload.to_ref());
-                    if header_from_payload == *block_parts.header {
-                        block_map.insert(
-                            root,
-                            Arc::new(
-                                block_parts
-                                    .blinded_block
-                                    .try_into_full_block(Some(payload))
-                                    .ok_or(BeaconChainError::AddPayloadLogicError)
-                                    .map(Arc::new)
-                                    .map(Some),
-                            ),
-                        );
-                    } else {
-                        let error = BeaconChainError::InconsistentPayloadReconstructed {
-                            slot: block_parts.blinded_block.slot(),
-                            exec_block_hash: block_parts.header.block_hash(),
-                            canonical_transactions_root: block_parts.header.transactions_root(),
-                            reconstructed_transactions_root: header_from_payload
-                                .transactions_root(),
-                        };
-                        debug!(log, "Failed to reconstruct block"; "root" => ?root, "error" => ?error);
-                        block_map.insert(root, Arc::new(Err(error)));
-                    }
-                }
-                Err(string) => {
-                    block_map.insert(
-                        root,
-                        Arc::new(Err(Error::PayloadReconstruction(string).into())),
-                    );
+    for (block_parts, payload_result) in block_parts_with_payloads {
+        let root = block_parts.root();
+        let block_result = match payload_result {
+            Ok(Some(payload)) => {
+                let header_from_payload = ExecutionPayloadHeader::from(payload.to_ref());
+                if header_from_payload == *block_parts.header {
+                    block_parts
+                        .blinded_block
+                        .try_into_full_block(Some(payload))
+                        .ok_or(BeaconChainError::AddPayloadLogicError)
+                        .map(Arc::new)
+                        .map(Some)
+                } else {
+                    let error = BeaconChainError::InconsistentPayloadReconstructed {
+                        slot: block_parts.blinded_block.slot(),
+                        exec_block_hash: block_parts.header.block_hash(),
+                        canonical_transactions_root: block_parts.header.transactions_root(),
+                        reconstructed_transactions_root: header_from_payload.transactions_root(),
+                    };
+                    debug!(log, "Failed to reconstruct block"; "root" => ?root, "error" => ?error);
+                    Err(error)
                 }
             }
-        } else {
-            block_map.insert(
-                root,
-                Arc::new(Err(BeaconChainError::BlockHashMissingFromExecutionLayer(
-                    block_parts.block_hash(),
-                ))),
-            );
-        }
+            Ok(None) => Err(BeaconChainError::BlockHashMissingFromExecutionLayer(
+                block_parts.block_hash(),
+            )),
+            Err(e) => {
+                debug!(log, "Failed to reconstruct block"; "root" => ?root, "error" => ?e);
+                Err(Error::PayloadReconstruction(format!("{:?}", e)).into())
+            }
+        };
+        block_map.insert(root, Arc::new(block_result));
     }
 }
 
@@ -235,32 +231,27 @@ impl<E: EthSpec> BodiesByRange<E> {
         if let RequestState::UnSent(blocks_parts_ref) = &mut self.state {
             let block_parts_vec = std::mem::take(blocks_parts_ref);
 
-            let mut block_map = HashMap::new();
-            match execution_layer
-                .get_payload_bodies_by_range(self.start, self.count)
-                .await
-            {
-                Ok(bodies) => {
-                    let mut range_map = (self.start..(self.start + self.count))
-                        .zip(bodies.into_iter().chain(std::iter::repeat(None)))
-                        .collect::<HashMap<_, _>>();
-
-                    let mut with_bodies = HashMap::new();
-                    for mut block_parts in block_parts_vec {
-                        with_bodies
-                            // it's possible the same block is requested twice, using
-                            // or_insert_with() skips duplicates
-                            .entry(block_parts.root())
-                            .or_insert_with(|| {
-                                let block_number = block_parts.header.block_number();
-                                block_parts.body =
-                                    range_map.remove(&block_number).flatten().map(Box::new);
-
-                                block_parts
-                            });
-                    }
+            // it's possible the same block is requested twice, skip duplicates
+            let mut unique_block_parts = HashMap::new();
+            for block_parts in block_parts_vec {
+                unique_block_parts
+                    .entry(block_parts.root())
+                    .or_insert(block_parts);
+            }
+            let block_parts_vec = unique_block_parts.into_values().collect::<Vec<_>>();
+            let headers = block_parts_vec
+                .iter()
+                .map(|block_parts| block_parts.header.as_ref().clone())
+                .collect::<Vec<_>>();
 
-                    reconstruct_blocks(&mut block_map, with_bodies, log);
+            let mut block_map = HashMap::new();
+            match execution_layer.reconstruct_payloads(&headers).await {
+                Ok(payloads) => {
+                    reconstruct_blocks(
+                        &mut block_map,
+                        block_parts_vec.into_iter().zip(payloads).collect(),
+                        log,
+                    );
                 }
                 Err(e) => {
                     let block_result =
@@ -563,7 +554,7 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
     async fn stream_blocks_fallback(
         self: Arc<Self>,
         block_roots: Vec<Hash256>,
-        sender: UnboundedSender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
+        sender: Sender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
     ) {
         debug!(
             self.beacon_chain.log,
@@ -580,7 +571,7 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
                     .map(|opt_block| opt_block.map(Arc::new))
             };
 
-            if sender.send((root, Arc::new(block_result))).is_err() {
+            if sender.send((root, Arc::new(block_result))).await.is_err() {
                 break;
             }
         }
@@ -589,47 +580,52 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
     async fn stream_blocks(
         self: Arc<Self>,
         block_roots: Vec<Hash256>,
-        sender: UnboundedSender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
+        sender: Sender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
     ) {
         let n_roots = block_roots.len();
         let mut n_success = 0usize;
         let mut n_sent = 0usize;
         let mut engine_requests = 0usize;
 
-        let payloads = match self.load_payloads(block_roots).await {
-            Ok(payloads) => payloads,
-            Err(e) => {
-                error!(
-                    self.beacon_chain.log,
-                    "BeaconBlockStreamer: Failed to load payloads";
-                    "error" => ?e
-                );
-                return;
-            }
-        };
-        let requests = self.get_requests(payloads).await;
+        // Load and reconstruct the blocks a chunk at a time, so that only the blocks the consumer
+        // is about to read are held in memory.
+        'chunks: for chunk in block_roots.chunks(BLOCK_STREAM_CHUNK_SIZE) {
+            let payloads = match self.load_payloads(chunk.to_vec()).await {
+                Ok(payloads) => payloads,
+                Err(e) => {
+                    error!(
+                        self.beacon_chain.log,
+                        "BeaconBlockStreamer: Failed to load payloads";
+                        "error" => ?e
+                    );
+                    return;
+                }
+            };
+            let requests = self.get_requests(payloads).await;
 
-        for (root, request) in requests {
-            if request.is_unsent().await {
-                engine_requests += 1;
-            }
+            for (root, request) in requests {
+                if request.is_unsent().await {
+                    engine_requests += 1;
+                }
 
-            let result = request
-                .get_block_result(&root, &self.execution_layer, &self.beacon_chain.log)
-                .await;
+                let result = request
+                    .get_block_result(&root, &self.execution_layer, &self.beacon_chain.log)
+                    .await;
 
-            let successful = result
-                .as_ref()
-                .as_ref()
-                .map(|opt| opt.is_some())
-                .unwrap_or(false);
+                let successful = result
+                    .as_ref()
+                    .as_ref()
+                    .map(|opt| opt.is_some())
+                    .unwrap_or(false);
 
-            if sender.send((root, result)).is_err() {
-                break;
-            } else {
-                n_sent += 1;
-                if successful {
-                    n_success += 1;
+                // Waits for the consumer to read earlier blocks if the channel is full.
+                if sender.send((root, result)).await.is_err() {
+                    break 'chunks;
+                } else {
+                    n_sent += 1;
+                    if successful {
+                        n_success += 1;
+                    }
                 }
             }
         }
@@ -648,7 +644,7 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
     pub async fn stream(
         self: Arc<Self>,
         block_roots: Vec<Hash256>,
-        sender: UnboundedSender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
+        sender: Sender<(Hash256, Arc<BlockResult<T::EthSpec>>)>,
     ) {
         match self
             .execution_layer
@@ -658,7 +654,9 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
             .map_err(BeaconChainError::EngineGetCapabilititesFailed)
         {
             Ok(engine_capabilities) => {
-                if engine_capabilities.get_payload_bodies_by_range_v1 {
+                if engine_capabilities.get_payload_bodies_by_range_v1
+                    || engine_capabilities.get_payload_bodies_by_hash_v1
+                {
                     self.stream_blocks(block_roots, sender).await;
                 } else {
                     // use the fallback method
@@ -675,7 +673,7 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
         self: Arc<Self>,
         block_roots: Vec<Hash256>,
     ) -> impl Stream<Item = (Hash256, Arc<BlockResult<T::EthSpec>>)> {
-        let (block_tx, block_rx) = mpsc::unbounded_channel();
+        let (block_tx, block_rx) = mpsc::channel(BLOCK_STREAM_CHUNK_SIZE);
         debug!(
             self.beacon_chain.log,
             "Launching a BeaconBlockStreamer";
@@ -683,18 +681,18 @@ impl<T: BeaconChainTypes> BeaconBlockStreamer<T> {
         );
         let executor = self.beacon_chain.task_executor.clone();
         executor.spawn(self.stream(block_roots, block_tx), "get_blocks_sender");
-        UnboundedReceiverStream::new(block_rx)
+        ReceiverStream::new(block_rx)
     }
 }
 
 async fn send_errors<E: EthSpec>(
     block_roots: Vec<Hash256>,
-    sender: UnboundedSender<(Hash256, Arc<BlockResult<E>>)>,
+    sender: Sender<(Hash256, Arc<BlockResult<E>>)>,
     beacon_chain_error: BeaconChainError,
 ) {
     let result = Arc::new(Err(beacon_chain_error));
     for root in block_roots {
-        if sender.send((root, result.clone())).is_err() {
+        if sender.send((root, result.clone())).await.is_err() {
             break;
         }
     }
@@ -836,7 +834,7 @@ mod tests {
             epoch_roots[..].clone_from_slice(&block_roots[start..(start + slots_per_epoch)]);
             let streamer = BeaconBlockStreamer::new(&harness.chain, CheckCaches::No)
                 .expect("should create streamer");
-            let (block_tx, mut block_rx) = mpsc::unbounded_channel();
+            let (block_tx, mut block_rx) = mpsc::channel(slots_per_epoch);
             streamer.stream(epoch_roots.clone(), block_tx).await;
 
             for (i, expected_root) in epoch_roots.into_iter().enumerate() {
@@ -1,3 +1,4 @@
+use crate::attestation_data_cache::AttestationDataCache;
 use crate::attestation_verification::{
     batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations,
     Error as AttestationError, VerifiedAggregatedAttestation, VerifiedAttestation,
@@ -8,6 +9,7 @@ use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckCaches};
 use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
 use crate::blob_verification::{GossipBlobError, GossipVerifiedBlob};
+use crate::block_production_budget::{BlockProductionStage, BlockProductionTimings};
 use crate::block_times_cache::BlockTimesCache;
 use crate::block_verification::POS_PANDA_BANNER;
 use crate::block_verification::{
@@ -30,9 +32,14 @@ use crate::errors::{BeaconChainError as Error, BlockProductionError};
 use crate::eth1_chain::{Eth1Chain, Eth1ChainBackend};
 use crate::eth1_finalization_cache::{Eth1FinalizationCache, Eth1FinalizationData};
 use crate::events::ServerSentEventHandler;
-use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
+use crate::execution_payload::{
+    get_execution_payload, placeholder_block_contents, NotifyExecutionLayer, PreparePayloadHandle,
+};
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::graffiti_calculator::GraffitiCalculator;
+use crate::graffiti_file::GraffitiContext;
+use crate::head_state_pool::HeadStatePool;
+use crate::head_timeline::HeadTimeline;
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
 use crate::light_client_finality_update_verification::{
     Error as LightClientFinalityUpdateError, VerifiedLightClientFinalityUpdate,
@@ -41,6 +48,7 @@ use crate::light_client_optimistic_update_verification::{
     Error as LightClientOptimisticUpdateError, VerifiedLightClientOptimisticUpdate,
 };
 use crate::light_client_server_cache::LightClientServerCache;
+use crate::light_mode::TrustedPayloadProvider;
 use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{
     AggregatedAttestationMap, Error as NaiveAggregationError, NaiveAggregationPool,
@@ -56,9 +64,15 @@ use crate::observed_block_producers::ObservedBlockProducers;
 use crate::observed_data_sidecars::ObservedDataSidecars;
 use crate::observed_operations::{ObservationOutcome, ObservedOperations};
 use crate::observed_slashable::ObservedSlashable;
+use crate::optimistic_status::{OptimisticBlocks, OptimisticTransitions};
+use crate::own_key_guard::{OwnKeyConflict, OwnKeyGuard};
+use crate::payload_attributes_policy::{PayloadAttributesPolicy, PayloadPreparation};
+use crate::payload_forensics::write_forensic_bundle_for_head;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
+use crate::persisted_head_state::{PersistedHeadState, HEAD_STATE_DB_KEY};
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
+use crate::re_org_policy::{ReOrgCandidate, ReOrgPolicy};
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::sync_committee_verification::{
     Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
@@ -67,15 +81,17 @@ use crate::validator_monitor::{
     get_slot_delay_ms, timestamp_now, ValidatorMonitor,
     HISTORIC_EPOCHS as VALIDATOR_MONITOR_HISTORIC_EPOCHS,
 };
+use crate::validator_proof_cache::ValidatorProofCache;
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
 use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
+use eth2::lighthouse::{ForkChoiceGraph, ForkChoiceGraphNode, HeadTimelineEventKind};
 use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
 use execution_layer::{
-    BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
-    FailedCondition, PayloadAttributes, PayloadStatus,
+    recorder::capture_exchanges, BlockProposalContents, BlockProposalContentsType, BuilderParams,
+    ChainHealth, ExecutionLayer, FailedCondition, PayloadAttributes, PayloadStatus,
 };
 use fork_choice::{
     AttestationFromBlock, ExecutionStatus, ForkChoice, ForkchoiceUpdateParameters,
@@ -89,7 +105,7 @@ use operation_pool::{
     CompactAttestationRef, OperationPool, PersistedOperationPool, ReceivedPreCapella,
 };
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
-use proto_array::{DoNotReOrg, ProposerHeadError};
+use proto_array::{DoNotReOrg, ProposerHeadError, ProposerHeadInfo};
 use safe_arith::SafeArith;
 use slasher::Slasher;
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -108,20 +124,23 @@ use state_processing::{
     BlockSignatureStrategy, ConsensusContext, SigVerifiedOp, VerifyBlockRoot, VerifyOperation,
 };
 use std::borrow::Cow;
+use std::cell::Cell;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::io::prelude::*;
 use std::marker::PhantomData;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use store::iter::{BlockRootsIterator, ParentRootBlockIterator, StateRootsIterator};
+use store::state_load_plan::{StateLoadPlan, StateLoadSource};
 use store::{
     DatabaseBlock, Error as DBError, HotColdDB, KeyValueStore, KeyValueStoreOp, StoreItem, StoreOp,
 };
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio::sync::mpsc::Receiver;
 use tokio_stream::Stream;
+use tracing::info_span;
 use tree_hash::TreeHash;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::data_column_sidecar::{ColumnIndex, DataColumnIdentifier};
@@ -330,6 +349,7 @@ struct PartialBeaconBlock<E: EthSpec> {
     sync_aggregate: Option<SyncAggregate<E>>,
     prepare_payload_handle: Option<PreparePayloadHandle<E>>,
     bls_to_execution_changes: Vec<SignedBlsToExecutionChange>,
+    timings: BlockProductionTimings,
 }
 
 pub enum BlockProcessStatus<E: EthSpec> {
@@ -374,6 +394,10 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub spec: Arc<ChainSpec>,
     /// Configuration for `BeaconChain` runtime behaviour.
     pub config: ChainConfig,
+    /// Decides whether proposer re-orgs which pass the fork choice conditions are attempted.
+    pub re_org_policy: Arc<dyn ReOrgPolicy>,
+    /// Decides which proposals payload attributes are sent to the execution layer for.
+    pub payload_attributes_policy: Arc<dyn PayloadAttributesPolicy>,
     /// Persistent storage for blocks, states, etc. Typically an on-disk store, such as LevelDB.
     pub store: BeaconStore<T>,
     /// Used for spawning async and blocking tasks.
@@ -434,13 +458,20 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     /// Maintains a record of which validators we've seen BLS to execution changes for.
     pub observed_bls_to_execution_changes:
         Mutex<ObservedOperations<SignedBlsToExecutionChange, T::EthSpec>>,
+    /// Refuses to publish messages from the operator's validators which conflict with messages
+    /// already seen from them.
+    pub own_key_guard: OwnKeyGuard,
     /// Provides information from the Ethereum 1 (PoW) chain.
     pub eth1_chain: Option<Eth1Chain<T::Eth1Chain, T::EthSpec>>,
     /// Interfaces with the execution client.
     pub execution_layer: Option<ExecutionLayer<T::EthSpec>>,
+    /// Determines payload validity in place of `execution_layer` when running in light mode.
+    pub light_mode: Option<TrustedPayloadProvider>,
     /// Stores information about the canonical head and finalized/justified checkpoints of the
     /// chain. Also contains the fork choice struct, for computing the canonical head.
     pub canonical_head: CanonicalHead<T>,
+    /// Clones of the head state made in advance, for callers which need a mutable head state.
+    pub head_state_pool: Arc<HeadStatePool<T::EthSpec>>,
     /// The root of the genesis block.
     pub genesis_block_root: Hash256,
     /// The root of the genesis state.
@@ -468,14 +499,22 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) validator_pubkey_cache: RwLock<ValidatorPubkeyCache<T>>,
     /// A cache used when producing attestations.
     pub(crate) attester_cache: Arc<AttesterCache>,
+    /// Caches the committees of attestation data already verified on gossip.
+    pub(crate) attestation_data_cache: AttestationDataCache,
     /// A cache used when producing attestations whilst the head block is still being imported.
     pub early_attester_cache: EarlyAttesterCache<T::EthSpec>,
     /// Cache gossip verified blocks to serve over ReqResp before they are imported
     pub reqresp_pre_import_cache: Arc<RwLock<ReqRespPreImportCache<T::EthSpec>>>,
     /// A cache used to keep track of various block timings.
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
+    /// The most recent execution status transitions of optimistic blocks.
+    pub optimistic_transitions: OptimisticTransitions,
+    /// The events which led to the selection of the head in recent slots.
+    pub head_timeline: HeadTimeline,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// A cache of validator registry trees used to produce proofs of validator records.
+    pub validator_proof_cache: ValidatorProofCache,
     /// A cache used to produce light_client server messages
     pub light_client_server_cache: LightClientServerCache<T>,
     /// Sender to signal the light_client server to produce new updates
@@ -548,6 +587,24 @@ pub struct BeaconBlockResponse<E: EthSpec, Payload: AbstractExecPayload<E>> {
     pub execution_payload_value: Uint256,
     /// The consensus layer reward to the proposer
     pub consensus_block_value: u64,
+    /// The time spent in each stage of producing the block
+    pub timings: BlockProductionTimings,
+}
+
+/// A block produced by `BeaconChain::produce_block_dry_run`.
+///
+/// The block is unsigned, and if its payload was not fetched from the execution layer it contains
+/// a placeholder payload, so it must never be published.
+pub struct DryRunBlock<E: EthSpec> {
+    pub block: BeaconBlock<E>,
+    /// `true` if the execution payload was fetched from the execution layer.
+    pub payload_fetched: bool,
+    /// The consensus layer reward to the proposer
+    pub consensus_block_value: u64,
+    /// The time spent loading the parent state.
+    pub state_load: Duration,
+    /// The time spent in each stage of producing the block on the parent state.
+    pub timings: BlockProductionTimings,
 }
 
 impl FinalizationAndCanonicity {
@@ -718,6 +775,47 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Persists the head state, advanced towards the current slot if such a state is cached, so
+    /// that it can be loaded on restart without replaying blocks.
+    pub fn persist_head_state(&self) -> Result<(), Error> {
+        let head_snapshot = self.head_snapshot();
+        let block_root = head_snapshot.beacon_block_root;
+
+        let (state_root, state) = match self.slot().ok().and_then(|slot| {
+            self.store
+                .get_advanced_hot_state_from_cache(block_root, slot)
+        }) {
+            Some(advanced) => advanced,
+            None => {
+                // The head snapshot state may have been advanced at start-up, so its root isn't
+                // necessarily the state root of the head block.
+                let mut state = head_snapshot.beacon_state.clone();
+                (state.canonical_root()?, state)
+            }
+        };
+        drop(head_snapshot);
+
+        self.persist_state_for_restart(block_root, state_root, &state)
+    }
+
+    /// Persists `state`, which must be the head state or an advanced head state, so that it can be
+    /// loaded on restart without replaying blocks.
+    pub fn persist_state_for_restart(
+        &self,
+        block_root: Hash256,
+        state_root: Hash256,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::PERSIST_HEAD_STATE);
+
+        self.store.put_item(
+            &HEAD_STATE_DB_KEY,
+            &PersistedHeadState::new(block_root, state_root, state),
+        )?;
+
+        Ok(())
+    }
+
     /// Returns the slot _right now_ according to `self.slot_clock`. Returns `Err` if the slot is
     /// unavailable.
     ///
@@ -1393,12 +1491,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         sync_committee_period: u64,
         count: u64,
     ) -> Result<Vec<LightClientUpdate<T::EthSpec>>, Error> {
-        self.light_client_server_cache.get_light_client_updates(
-            &self.store,
-            sync_committee_period,
-            count,
-            &self.spec,
-        )
+        Ok(self
+            .store
+            .get_light_client_updates(sync_committee_period, count)?)
     }
 
     /// Returns the current heads of the `BeaconChain`. For the canonical head, see `Self::head`.
@@ -1408,6 +1503,43 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.head_tracker.heads()
     }
 
+    /// Returns a snapshot of the fork choice block tree, for debugging and visualisation.
+    pub fn fork_choice_graph(&self) -> ForkChoiceGraph {
+        let head_root = self.canonical_head.cached_head().head_block_root();
+
+        let fork_choice = self.canonical_head.fork_choice_read_lock();
+        let justified_checkpoint = fork_choice.justified_checkpoint();
+        let finalized_checkpoint = fork_choice.finalized_checkpoint();
+        let proto_array = fork_choice.proto_array().core_proto_array();
+
+        let nodes = proto_array
+            .nodes
+            .iter()
+            .map(|node| ForkChoiceGraphNode {
+                slot: node.slot,
+                block_root: node.root,
+                parent_root: node
+                    .parent
+                    .and_then(|index| proto_array.nodes.get(index))
+                    .map(|parent| parent.root),
+                weight: node.weight,
+                execution_status: node.execution_status.to_string(),
+                justified_epoch: node.justified_checkpoint.epoch,
+                finalized_epoch: node.finalized_checkpoint.epoch,
+                is_justified: node.root == justified_checkpoint.root,
+                is_finalized: node.root == finalized_checkpoint.root,
+                is_head: node.root == head_root,
+            })
+            .collect();
+
+        ForkChoiceGraph {
+            head_root,
+            justified_checkpoint,
+            finalized_checkpoint,
+            nodes,
+        }
+    }
+
     /// Only used in tests.
     pub fn knows_head(&self, block_hash: &SignedBeaconBlockHash) -> bool {
         self.head_tracker.contains_head((*block_hash).into())
@@ -1479,6 +1611,85 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Returns the state at the given slot along with the plan that was used to load it.
+    ///
+    /// Like `Self::state_at_slot`, states later than the head are obtained by advancing the head
+    /// state. Earlier states are loaded along the cheapest path chosen by the store's planner, which
+    /// weighs the hot state cache, the historic state cache, the freezer's diff hierarchy and block
+    /// replay against each other.
+    pub fn state_at_slot_planned(
+        &self,
+        slot: Slot,
+        config: StateSkipConfig,
+    ) -> Result<(BeaconState<T::EthSpec>, StateLoadPlan), Error> {
+        let head_slot = self.canonical_head.cached_head().head_slot();
+
+        if slot >= head_slot {
+            let plan = StateLoadPlan::new(StateLoadSource::Head, slot, head_slot, 0, 0);
+            let state = self.state_at_slot(slot, config)?;
+            self.record_state_load_plan(&plan);
+            return Ok((state, plan));
+        }
+
+        let state_root = self
+            .state_root_at_slot(slot)?
+            .ok_or(Error::NoStateForSlot(slot))?;
+        self.get_state_planned(&state_root, Some(slot))?
+            .ok_or(Error::NoStateForSlot(slot))
+    }
+
+    /// Returns the state with `state_root` along with the plan that was followed to load it.
+    ///
+    /// As with `Self::get_state`, the `slot` is a hint as to which database holds the state. The
+    /// state is loaded along the cheapest path chosen by the store's planner.
+    pub fn get_state_planned(
+        &self,
+        state_root: &Hash256,
+        slot: Option<Slot>,
+    ) -> Result<Option<(BeaconState<T::EthSpec>, StateLoadPlan)>, Error> {
+        let split_slot = self.store.get_split_slot();
+
+        if slot.map_or(true, |slot| slot >= split_slot) {
+            if let Some(plan) = self.store.plan_hot_state_load(state_root)? {
+                let loaded = self.store.load_hot_state_with_plan(state_root, &plan)?;
+                if let Some((_, plan)) = &loaded {
+                    self.record_state_load_plan(plan);
+                }
+                return Ok(loaded);
+            }
+            if slot.is_some() {
+                return Ok(None);
+            }
+        }
+
+        let Some(cold_slot) = self.store.load_cold_state_slot(state_root)? else {
+            return Ok(None);
+        };
+        let plan = self.store.plan_cold_state_load(cold_slot)?;
+        let state = self.store.load_cold_state_with_plan(&plan)?;
+        self.record_state_load_plan(&plan);
+        Ok(Some((state, plan)))
+    }
+
+    /// Record the `plan` that was followed to load a state in the metrics and logs.
+    fn record_state_load_plan(&self, plan: &StateLoadPlan) {
+        metrics::inc_counter_vec(&metrics::STATE_LOAD_PLANS, &[plan.source.as_str()]);
+        metrics::observe(
+            &metrics::STATE_LOAD_PLAN_REPLAY_SLOTS,
+            plan.replay_slots as f64,
+        );
+        debug!(
+            self.log,
+            "Loaded state using plan";
+            "slot" => plan.target_slot,
+            "source" => plan.source.as_str(),
+            "base_slot" => plan.base_slot,
+            "diffs" => plan.diffs,
+            "replay_slots" => plan.replay_slots,
+            "cost" => plan.cost,
+        );
+    }
+
     /// Returns the `BeaconState` the current slot (viz., `self.slot()`).
     ///
     ///  - A reference to the head state (note: this keeps a read lock on the head, try to use
@@ -1509,6 +1720,83 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(pubkey_cache.get_index(pubkey))
     }
 
+    /// Records the attestation with `data` for each of the `attesting_indices` guarded by the
+    /// `OwnKeyGuard`.
+    ///
+    /// Returns an error, and alerts the operator, if the attestation conflicts with an attestation
+    /// already seen from one of the guarded validators. The attestation must not be published.
+    pub fn observe_own_key_attestation(
+        &self,
+        attesting_indices: impl IntoIterator<Item = u64>,
+        data: &AttestationData,
+    ) -> Result<(), OwnKeyConflict> {
+        if !self.own_key_guard.is_enabled() {
+            return Ok(());
+        }
+
+        let pubkey_cache = self.validator_pubkey_cache.read();
+        for validator_index in attesting_indices {
+            let is_guarded = pubkey_cache
+                .get_pubkey_bytes(validator_index as usize)
+                .is_some_and(|pubkey| self.own_key_guard.is_guarded(pubkey));
+            if is_guarded {
+                self.own_key_guard
+                    .observe_attestation(validator_index, data)
+                    .inspect_err(|conflict| self.alert_own_key_conflict(conflict))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns an error, and alerts the operator, if the block with `block_root` was proposed by a
+    /// validator guarded by the `OwnKeyGuard` which has already been seen proposing a different
+    /// block at the same slot. The block must not be published.
+    pub fn check_own_key_block(
+        &self,
+        block_root: Hash256,
+        block: BeaconBlockRef<T::EthSpec>,
+    ) -> Result<(), OwnKeyConflict> {
+        if !self.own_key_guard.is_enabled() {
+            return Ok(());
+        }
+
+        let proposer_index = block.proposer_index();
+        let is_guarded = self
+            .validator_pubkey_cache
+            .read()
+            .get_pubkey_bytes(proposer_index as usize)
+            .is_some_and(|pubkey| self.own_key_guard.is_guarded(pubkey));
+        // An error from the cache means that the block is finalized or invalid, in which case
+        // it's not slashable.
+        let is_slashable = is_guarded
+            && self
+                .observed_slashable
+                .read()
+                .is_slashable(block.slot(), proposer_index, block_root)
+                .unwrap_or(false);
+
+        if is_slashable {
+            let conflict = OwnKeyConflict::DoubleProposal {
+                validator_index: proposer_index,
+                slot: block.slot(),
+            };
+            self.alert_own_key_conflict(&conflict);
+            return Err(conflict);
+        }
+        Ok(())
+    }
+
+    fn alert_own_key_conflict(&self, conflict: &OwnKeyConflict) {
+        metrics::inc_counter_vec(&metrics::OWN_KEY_GUARD_CONFLICTS, &[conflict.kind()]);
+        crit!(
+            self.log,
+            "Refusing to publish slashable message";
+            "conflict" => ?conflict,
+            "advice" => "a validator key may be running in more than one validator client, \
+                check your setup immediately",
+        );
+    }
+
     /// Return the validator indices of all public keys fetched from an iterator.
     ///
     /// If any public key doesn't belong to a known validator then an error will be returned.
@@ -3312,16 +3600,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 None,
                 None,
             );
+            self.record_head_timeline_event_at(
+                seen_timestamp,
+                HeadTimelineEventKind::BlockObserved {
+                    block_root,
+                    slot: block_slot,
+                },
+            );
         }
 
         // A small closure to group the verification and import errors.
         let chain = self.clone();
         let import_block = async move {
-            let execution_pending = unverified_block.into_execution_pending_block(
-                block_root,
-                &chain,
-                notify_execution_layer,
-            )?;
+            let execution_pending = info_span!("consensus_verification").in_scope(|| {
+                unverified_block.into_execution_pending_block(
+                    block_root,
+                    &chain,
+                    notify_execution_layer,
+                )
+            })?;
             publish_fn()?;
 
             // Record the time it took to complete consensus verification.
@@ -3471,6 +3768,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         block: AvailabilityPendingExecutedBlock<T::EthSpec>,
     ) -> Result<AvailabilityProcessingStatus, BlockError> {
         let slot = block.block.slot();
+        if self.config.early_attester_cache_pending_blobs {
+            self.add_availability_pending_block_to_early_attester_cache(&block)?;
+        }
         let availability = self
             .data_availability_checker
             .put_pending_executed_block(block)?;
@@ -3478,6 +3778,60 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .await
     }
 
+    /// Adds a block which is still awaiting its blobs to the early attester cache, allowing it to
+    /// be attested to during its slot before it becomes available.
+    ///
+    /// Only blocks from the current slot which were fully verified by the execution layer and
+    /// build upon the current head are added, since these are the blocks which would become the
+    /// head once imported.
+    fn add_availability_pending_block_to_early_attester_cache(
+        &self,
+        block: &AvailabilityPendingExecutedBlock<T::EthSpec>,
+    ) -> Result<(), BlockError> {
+        let block_root = block.import_data.block_root;
+        let block_slot = block.block.slot();
+
+        if block.num_blobs_expected() == 0
+            || block
+                .payload_verification_outcome
+                .payload_verification_status
+                .is_optimistic()
+            || block_slot != self.slot()?
+            || block.block.parent_root() != self.canonical_head.cached_head().head_block_root()
+        {
+            return Ok(());
+        }
+
+        if let Err(e) = self.early_attester_cache.add_availability_pending_block(
+            block_root,
+            block.block.clone(),
+            &block.import_data.state,
+            &self.spec,
+        ) {
+            warn!(
+                self.log,
+                "Early attester cache insert failed";
+                "error" => ?e,
+                "block_root" => ?block_root,
+            );
+        } else {
+            debug!(
+                self.log,
+                "Block attestable whilst awaiting blobs";
+                "block_root" => ?block_root,
+                "slot" => block_slot,
+            );
+            let attestable_timestamp = self.slot_clock.now_duration().unwrap_or_default();
+            self.block_times_cache.write().set_time_attestable(
+                block_root,
+                block_slot,
+                attestable_timestamp,
+            );
+        }
+
+        Ok(())
+    }
+
     /// Checks if the provided blob can make any cached blocks available, and imports immediately
     /// if so, otherwise caches the blob in the data availability checker.
     async fn check_gossip_blob_availability_and_import(
@@ -3673,9 +4027,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // import
         let chain = self.clone();
+        // The import runs on a blocking thread, so the span must be entered explicitly.
+        let import_span = info_span!("import_block", block_root = %block_root);
         let block_root = self
             .spawn_blocking_handle(
                 move || {
+                    let _span = import_span.enter();
                     chain.import_block(
                         block,
                         block_root,
@@ -3782,24 +4139,50 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let block = signed_block.message();
 
         // Register the new block with the fork choice service.
-        {
+        //
+        // A verified payload also validates any optimistic ancestors, so take note of them in
+        // order to report their transitions.
+        let optimistic_transitions = {
             let block_delay = self
                 .slot_clock
                 .seconds_from_current_slot_start()
                 .ok_or(Error::UnableToComputeTimeAtSlot)?;
 
-            fork_choice
-                .on_block(
-                    current_slot,
-                    block,
-                    block_root,
-                    block_delay,
-                    &state,
-                    payload_verification_status,
-                    &self.spec,
-                )
+            let optimistic_ancestors = (payload_verification_status
+                == PayloadVerificationStatus::Verified
+                && fork_choice
+                    .get_block(&block.parent_root())
+                    .is_some_and(|parent| parent.execution_status.is_strictly_optimistic()))
+            .then(|| {
+                OptimisticBlocks::from_proto_array(fork_choice.proto_array().core_proto_array())
+            });
+
+            info_span!("fork_choice_on_block")
+                .in_scope(|| {
+                    fork_choice.on_block(
+                        current_slot,
+                        block,
+                        block_root,
+                        block_delay,
+                        &state,
+                        payload_verification_status,
+                        &self.spec,
+                    )
+                })
                 .map_err(|e| BlockError::BeaconChainError(e.into()))?;
-        }
+
+            self.record_head_timeline_event(HeadTimelineEventKind::BlockImported {
+                block_root,
+                slot: block.slot(),
+            });
+            self.observe_head_timeline_proposer_boost(fork_choice.proposer_boost_root());
+
+            optimistic_ancestors
+                .map(|before| {
+                    before.transitions(fork_choice.proto_array().core_proto_array(), current_slot)
+                })
+                .unwrap_or_default()
+        };
 
         // If the block is recent enough and it was not optimistically imported, check to see if it
         // becomes the head block. If so, apply it to the early attester cache. This will allow
@@ -3985,6 +4368,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // This prevents inconsistency between the two at the expense of concurrency.
         drop(fork_choice);
 
+        self.record_optimistic_transitions(optimistic_transitions);
+
         // We're declaring the block "imported" at this point, since fork choice and the DB know
         // about it.
         let block_time_imported = timestamp_now();
@@ -4578,17 +4963,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Part 2/2 (async, with some blocking components)
         //
         // Produce the block upon the state
-        self.produce_block_on_state(
-            state,
-            state_root_opt,
-            slot,
-            randao_reveal,
-            validator_graffiti,
-            verification,
-            builder_boost_factor,
-            block_production_version,
-        )
-        .await
+        let block = self
+            .produce_block_on_state(
+                state,
+                state_root_opt,
+                slot,
+                randao_reveal,
+                validator_graffiti,
+                verification,
+                builder_boost_factor,
+                block_production_version,
+            )
+            .await?;
+
+        // Blocks produced for a validator client keep the payload attributes window open.
+        self.beacon_proposer_cache
+            .lock()
+            .record_local_proposal_epoch(slot.epoch(T::EthSpec::slots_per_epoch()));
+
+        Ok(block)
     }
 
     /// Load a beacon state from the database for block production. This is a long-running process
@@ -4601,6 +4994,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.wait_for_fork_choice_before_block_production(slot)?;
         drop(fork_choice_timer);
 
+        self.load_parent_state_for_block_production(slot)
+    }
+
+    /// Load the state to build a block at `slot` upon, without waiting for fork choice to run.
+    fn load_parent_state_for_block_production(
+        self: &Arc<Self>,
+        slot: Slot,
+    ) -> Result<(BeaconState<T::EthSpec>, Option<Hash256>), BlockProductionError> {
         let state_load_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_LOAD_TIMES);
 
         // Atomically read some values from the head whilst avoiding holding cached head `Arc` any
@@ -4692,7 +5093,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // 1. It seems we have time to propagate and still receive the proposer boost.
         // 2. The current head block was seen late.
         // 3. The `get_proposer_head` conditions from fork choice pass.
-        let proposing_on_time = slot_delay < self.config.re_org_cutoff(self.spec.seconds_per_slot);
+        let proposing_on_time = slot_delay < self.re_org_policy.re_org_cutoff(&self.spec);
         if !proposing_on_time {
             debug!(
                 self.log,
@@ -4746,6 +5147,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         drop(proposer_head_timer);
         let re_org_parent_block = proposer_head.parent_node.root;
 
+        // Does the re-org policy permit this re-org?
+        let candidate = self.re_org_candidate(slot, &proposer_head);
+        if let Err(reason) = self.re_org_policy.check_re_org(&candidate, &self.spec) {
+            debug!(
+                self.log,
+                "Not attempting re-org";
+                "reason" => %reason,
+            );
+            return None;
+        }
+
         let (state_root, state) = self
             .store
             .get_advanced_hot_state_from_cache(re_org_parent_block, slot)
@@ -4770,6 +5182,25 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Some((state, state_root))
     }
 
+    /// Describe a proposer re-org at `proposal_slot` for the `re_org_policy`.
+    fn re_org_candidate(&self, proposal_slot: Slot, info: &ProposerHeadInfo) -> ReOrgCandidate {
+        let head_node = &info.head_node;
+        // The proposer index is only readily available if the block is the cached head, which is
+        // always the case when a re-org is being considered.
+        let cached_head = self.canonical_head.cached_head();
+        let head_proposer_index = (cached_head.head_block_root() == head_node.root)
+            .then(|| cached_head.snapshot.beacon_block.message().proposer_index());
+
+        ReOrgCandidate {
+            proposal_slot,
+            proposal_epoch: proposal_slot.epoch(T::EthSpec::slots_per_epoch()),
+            head_block_root: head_node.root,
+            head_slot: head_node.slot,
+            head_proposer_index,
+            parent_block_root: info.parent_node.root,
+        }
+    }
+
     /// Get the proposer index and `prev_randao` value for a proposal at slot `proposal_slot`.
     ///
     /// The `proposer_head` may be the head block of `cached_head` or its parent. An error will
@@ -5010,7 +5441,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 .and_then(|slot_start| {
                     let now = self.slot_clock.now_duration()?;
                     let slot_delay = now.saturating_sub(slot_start);
-                    Some(slot_delay <= self.config.re_org_cutoff(self.spec.seconds_per_slot))
+                    Some(slot_delay <= self.re_org_policy.re_org_cutoff(&self.spec))
                 })
                 .unwrap_or(false)
         } else {
@@ -5054,6 +5485,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Err(DoNotReOrg::NotProposing.into());
         }
 
+        // Check that the re-org policy permits this re-org.
+        let candidate = self.re_org_candidate(re_org_block_slot, &info);
+        self.re_org_policy.check_re_org(&candidate, &self.spec)?;
+
         // If the current slot is already equal to the proposal slot (or we are in the tail end of
         // the prior slot), then check the actual weight of the head against the head re-org threshold
         // and the actual weight of the parent against the parent re-org threshold.
@@ -5123,6 +5558,37 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Returns the public key of the proposer at `slot` if it is in the beacon proposer cache.
+    ///
+    /// The proposer is never computed, as this is called on the block production path. The cache
+    /// is populated when proposer duties are requested, which validator clients do every epoch.
+    fn cached_proposer_pubkey(
+        &self,
+        state: &BeaconState<T::EthSpec>,
+        state_root_opt: Option<Hash256>,
+        slot: Slot,
+    ) -> Option<PublicKeyBytes> {
+        let epoch = slot.epoch(T::EthSpec::slots_per_epoch());
+        // Computing the latest block root of an unadvanced state requires its state root, so give
+        // up rather than hashing the state if it is unknown.
+        let latest_block_root = if !state.latest_block_header().state_root.is_zero() {
+            state.latest_block_header().canonical_root()
+        } else {
+            state.get_latest_block_root(state_root_opt?)
+        };
+        let shuffling_decision_root = state
+            .proposer_shuffling_decision_root_at_epoch(epoch, latest_block_root)
+            .ok()?;
+        let proposer = self
+            .beacon_proposer_cache
+            .lock()
+            .get_slot::<T::EthSpec>(shuffling_decision_root, slot)?;
+        state
+            .validators()
+            .get(proposer.index)
+            .map(|validator| validator.pubkey)
+    }
+
     /// Produce a block for some `slot` upon the given `state`.
     ///
     /// Typically the `self.produce_block()` function should be used, instead of calling this
@@ -5151,9 +5617,18 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         //
         // Perform the state advance and block-packing functions.
         let chain = self.clone();
+        let graffiti_context = GraffitiContext {
+            proposer_pubkey: self.cached_proposer_pubkey(&state, state_root_opt, produce_at_slot),
+            slot: produce_at_slot,
+            epoch: produce_at_slot.epoch(T::EthSpec::slots_per_epoch()),
+            timestamp: self
+                .slot_clock
+                .start_of(produce_at_slot)
+                .map(|start| start.as_secs()),
+        };
         let graffiti = self
             .graffiti_calculator
-            .get_graffiti(validator_graffiti)
+            .get_graffiti(validator_graffiti, Some(&graffiti_context))
             .await;
         let mut partial_beacon_block = self
             .task_executor
@@ -5167,6 +5642,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         graffiti,
                         builder_boost_factor,
                         block_production_version,
+                        true,
                     )
                 },
                 "produce_partial_beacon_block",
@@ -5180,12 +5656,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let prepare_payload_handle = partial_beacon_block.prepare_payload_handle.take();
         let block_contents_type_option =
             if let Some(prepare_payload_handle) = prepare_payload_handle {
-                Some(
-                    prepare_payload_handle
-                        .await
-                        .map_err(BlockProductionError::TokioJoin)?
-                        .ok_or(BlockProductionError::ShuttingDown)??,
-                )
+                let payload_fetch_timer =
+                    metrics::start_timer(&metrics::BLOCK_PRODUCTION_PAYLOAD_FETCH_TIMES);
+                let payload_fetch_start = Instant::now();
+                let block_contents_type = prepare_payload_handle
+                    .await
+                    .map_err(BlockProductionError::TokioJoin)?
+                    .ok_or(BlockProductionError::ShuttingDown)??;
+                drop(payload_fetch_timer);
+                partial_beacon_block.timings.record(
+                    BlockProductionStage::PayloadFetch,
+                    payload_fetch_start.elapsed(),
+                );
+                Some(block_contents_type)
             } else {
                 None
             };
@@ -5255,6 +5738,114 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Produce a block at `slot` without signing or publishing it, in order to check the health
+    /// of block production and measure the time taken by each stage.
+    ///
+    /// If `fetch_payload` is `false`, no payload is requested from the execution layer and the
+    /// block contains a placeholder payload instead.
+    pub async fn produce_block_dry_run(
+        self: &Arc<Self>,
+        slot: Slot,
+        fetch_payload: bool,
+    ) -> Result<DryRunBlock<T::EthSpec>, BlockProductionError> {
+        let state_load_start = Instant::now();
+        let chain = self.clone();
+        let (state, state_root_opt) = self
+            .task_executor
+            .spawn_blocking_handle(
+                // Don't wait for fork choice, as the dry run may be well ahead of the current slot.
+                move || chain.load_parent_state_for_block_production(slot),
+                "dry_run_load_state_for_block_production",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+        let state_load = state_load_start.elapsed();
+
+        let graffiti = self.graffiti_calculator.get_graffiti(None, None).await;
+        let chain = self.clone();
+        let mut partial_beacon_block = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    chain.produce_partial_beacon_block(
+                        state,
+                        state_root_opt,
+                        slot,
+                        // The block is never signed, so there is no randao reveal.
+                        Signature::empty(),
+                        graffiti,
+                        None,
+                        BlockProductionVersion::FullV2,
+                        fetch_payload,
+                    )
+                },
+                "dry_run_produce_partial_beacon_block",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+
+        let block_contents = match partial_beacon_block.prepare_payload_handle.take() {
+            Some(prepare_payload_handle) => {
+                let payload_fetch_start = Instant::now();
+                let block_contents_type = prepare_payload_handle
+                    .await
+                    .map_err(BlockProductionError::TokioJoin)?
+                    .ok_or(BlockProductionError::ShuttingDown)??;
+                partial_beacon_block.timings.record(
+                    BlockProductionStage::PayloadFetch,
+                    payload_fetch_start.elapsed(),
+                );
+                match block_contents_type {
+                    BlockProposalContentsType::Full(block_contents) => Some(block_contents),
+                    BlockProposalContentsType::Blinded(_) => {
+                        return Err(BlockProductionError::InvalidBlockVariant(
+                            "dry run received a blinded payload".to_string(),
+                        ))
+                    }
+                }
+            }
+            None if partial_beacon_block
+                .state
+                .fork_name_unchecked()
+                .bellatrix_enabled() =>
+            {
+                Some(placeholder_block_contents(
+                    &partial_beacon_block.state,
+                    &self.spec,
+                )?)
+            }
+            None => None,
+        };
+        let payload_fetched = fetch_payload && block_contents.is_some();
+
+        let chain = self.clone();
+        let response = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || {
+                    chain.complete_partial_beacon_block(
+                        partial_beacon_block,
+                        block_contents,
+                        ProduceBlockVerification::NoVerification,
+                    )
+                },
+                "dry_run_complete_partial_beacon_block",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+
+        Ok(DryRunBlock {
+            block: response.block,
+            payload_fetched,
+            consensus_block_value: response.consensus_block_value,
+            state_load,
+            timings: response.timings,
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn produce_partial_beacon_block(
         self: &Arc<Self>,
@@ -5265,6 +5856,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         graffiti: Graffiti,
         builder_boost_factor: Option<u64>,
         block_production_version: BlockProductionVersion,
+        fetch_payload: bool,
     ) -> Result<PartialBeaconBlock<T::EthSpec>, BlockProductionError> {
         let eth1_chain = self
             .eth1_chain
@@ -5279,12 +5871,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             });
         }
 
+        let mut timings = BlockProductionTimings::new(self.config.block_production_budget.clone());
+
         let slot_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_SLOT_PROCESS_TIMES);
+        let slot_process_start = Instant::now();
 
         // Ensure the state has performed a complete transition into the required slot.
         complete_state_advance(&mut state, state_root_opt, produce_at_slot, &self.spec)?;
 
         drop(slot_timer);
+        timings.record(
+            BlockProductionStage::SlotProcessing,
+            slot_process_start.elapsed(),
+        );
 
         state.build_committee_cache(RelativeEpoch::Current, &self.spec)?;
         state.apply_pending_mutations()?;
@@ -5319,6 +5918,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // allows it to run concurrently with things like attestation packing.
         let prepare_payload_handle = match &state {
             BeaconState::Base(_) | BeaconState::Altair(_) => None,
+            _ if !fetch_payload => None,
             BeaconState::Bellatrix(_)
             | BeaconState::Capella(_)
             | BeaconState::Deneb(_)
@@ -5371,17 +5971,30 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         let attestation_packing_timer =
             metrics::start_timer(&metrics::BLOCK_PRODUCTION_ATTESTATION_TIMES);
+        let attestation_packing_start = Instant::now();
 
         // Epoch cache and total balance cache are required for op pool packing.
         state.build_total_active_balance_cache(&self.spec)?;
         initialize_epoch_cache(&mut state, &self.spec)?;
 
+        // Once the packing budget is spent, stop considering further candidates. This produces a
+        // block with fewer attestations rather than risking a missed proposal.
+        let packing_deadline = timings.deadline(BlockProductionStage::AttestationPacking);
+        let packing_cut_short = Cell::new(false);
         let mut prev_filter_cache = HashMap::new();
         let prev_attestation_filter = |att: &CompactAttestationRef<T::EthSpec>| {
+            if packing_deadline.is_expired() {
+                packing_cut_short.set(true);
+                return false;
+            }
             self.filter_op_pool_attestation(&mut prev_filter_cache, att, &state)
         };
         let mut curr_filter_cache = HashMap::new();
         let curr_attestation_filter = |att: &CompactAttestationRef<T::EthSpec>| {
+            if packing_deadline.is_expired() {
+                packing_cut_short.set(true);
+                return false;
+            }
             self.filter_op_pool_attestation(&mut curr_filter_cache, att, &state)
         };
 
@@ -5395,6 +6008,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             )
             .map_err(BlockProductionError::OpPoolError)?;
         drop(attestation_packing_timer);
+        timings.record(
+            BlockProductionStage::AttestationPacking,
+            attestation_packing_start.elapsed(),
+        );
+        if packing_cut_short.get() {
+            warn!(
+                self.log,
+                "Attestation packing exceeded budget";
+                "slot" => state.slot(),
+                "attestations" => attestations.len(),
+            );
+            timings.mark_degraded(BlockProductionStage::AttestationPacking);
+        }
 
         // If paranoid mode is enabled re-check the signatures of every included message.
         // This will be a lot slower but guards against bugs in block production and can be
@@ -5505,6 +6131,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             sync_aggregate,
             prepare_payload_handle,
             bls_to_execution_changes,
+            timings,
         })
     }
 
@@ -5533,6 +6160,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // produce said `execution_payload`.
             prepare_payload_handle: _,
             bls_to_execution_changes,
+            mut timings,
         } = partial_beacon_block;
 
         let (attester_slashings_base, attester_slashings_electra) =
@@ -5797,8 +6425,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         drop(process_timer);
 
         let state_root_timer = metrics::start_timer(&metrics::BLOCK_PRODUCTION_STATE_ROOT_TIMES);
+        let state_root_start = Instant::now();
         let state_root = state.update_tree_hash_cache()?;
         drop(state_root_timer);
+        timings.record(BlockProductionStage::StateRoot, state_root_start.elapsed());
 
         let (mut block, _) = block.deconstruct();
         *block.state_root_mut() = state_root;
@@ -5844,6 +6474,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
+        if let Some(event_handler) = self.event_handler.as_ref() {
+            if event_handler.has_block_production_subscribers() {
+                event_handler.register(EventKind::BlockProduction(Box::new(
+                    timings.to_sse_event(block.slot(), block.proposer_index()),
+                )));
+            }
+        }
+
         trace!(
             self.log,
             "Produced beacon block";
@@ -5858,6 +6496,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             blob_items,
             execution_payload_value,
             consensus_block_value,
+            timings,
         })
     }
 
@@ -5884,17 +6523,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // on the core executor is bad.
         let chain = self.clone();
         let inner_op = op.clone();
-        let fork_choice_result = self
+        let (fork_choice_result, optimistic_transitions) = self
             .spawn_blocking_handle(
                 move || {
-                    chain
-                        .canonical_head
-                        .fork_choice_write_lock()
-                        .on_invalid_execution_payload(&inner_op)
+                    let current_slot = chain.slot().unwrap_or(chain.spec.genesis_slot);
+                    let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+                    let before = OptimisticBlocks::from_proto_array(
+                        fork_choice.proto_array().core_proto_array(),
+                    );
+                    let result = fork_choice.on_invalid_execution_payload(&inner_op);
+                    let transitions = before
+                        .transitions(fork_choice.proto_array().core_proto_array(), current_slot);
+                    (result, transitions)
                 },
                 "invalid_payload_fork_choice_update",
             )
             .await?;
+        self.record_optimistic_transitions(optimistic_transitions);
 
         // Update fork choice.
         if let Err(e) = fork_choice_result {
@@ -5987,14 +6632,20 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(None);
         }
 
-        let execution_layer = self
-            .execution_layer
-            .clone()
-            .ok_or(Error::ExecutionLayerMissing)?;
+        let Some(execution_layer) = self.execution_layer.clone() else {
+            // Blocks are never proposed in light mode.
+            return if self.light_mode.is_some() {
+                Ok(None)
+            } else {
+                Err(Error::ExecutionLayerMissing)
+            };
+        };
 
         // Nothing to do if there are no proposers registered with the EL, exit early to avoid
         // wasting cycles.
-        if !self.config.always_prepare_payload
+        if !self
+            .payload_attributes_policy
+            .prepare_unregistered_proposers()
             && !execution_layer.has_any_proposer_preparation_data().await
         {
             return Ok(None);
@@ -6048,13 +6699,34 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         };
 
         // If the execution layer doesn't have any proposer data for this validator then we assume
-        // it's not connected to this BN and no action is required.
+        // it's not connected to this BN and no action is required. The policy may also rule out
+        // preparing proposals which this node is unlikely to produce.
         let proposer = pre_payload_attributes.proposer_index;
-        if !self.config.always_prepare_payload
-            && !execution_layer
+        let preparation = PayloadPreparation {
+            proposal_slot: prepare_slot,
+            proposal_epoch: prepare_slot.epoch(T::EthSpec::slots_per_epoch()),
+            proposer_index: proposer,
+            proposer_registered: execution_layer
                 .has_proposer_preparation_data(proposer)
-                .await
+                .await,
+            latest_local_proposal_epoch: self
+                .beacon_proposer_cache
+                .lock()
+                .latest_local_proposal_epoch(),
+        };
+        if let Err(reason) = self
+            .payload_attributes_policy
+            .check_preparation(&preparation)
         {
+            if preparation.proposer_registered {
+                debug!(
+                    self.log,
+                    "Not sending payload attributes";
+                    "reason" => ?reason,
+                    "prepare_slot" => prepare_slot,
+                    "validator" => proposer,
+                );
+            }
             return Ok(None);
         }
 
@@ -6176,6 +6848,53 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(Some(head_root))
     }
 
+    /// Inform fork choice that the payload of the head block, and therefore all of its
+    /// ancestors, is valid.
+    pub(crate) async fn on_valid_head_payload(
+        self: &Arc<Self>,
+        head_block_root: Hash256,
+        current_slot: Slot,
+    ) -> Result<(), Error> {
+        // Ensure that fork choice knows that the block is no longer optimistic.
+        let chain = self.clone();
+        let (fork_choice_update_result, optimistic_transitions) = self
+            .spawn_blocking_handle(
+                move || {
+                    let mut fork_choice = chain.canonical_head.fork_choice_write_lock();
+                    // Transitions are only possible if the head was optimistic.
+                    let before = fork_choice
+                        .get_block(&head_block_root)
+                        .is_some_and(|head| head.execution_status.is_strictly_optimistic())
+                        .then(|| {
+                            OptimisticBlocks::from_proto_array(
+                                fork_choice.proto_array().core_proto_array(),
+                            )
+                        });
+                    let result = fork_choice.on_valid_execution_payload(head_block_root);
+                    let transitions = before
+                        .map(|before| {
+                            before.transitions(
+                                fork_choice.proto_array().core_proto_array(),
+                                current_slot,
+                            )
+                        })
+                        .unwrap_or_default();
+                    (result, transitions)
+                },
+                "update_execution_engine_valid_payload",
+            )
+            .await?;
+        self.record_optimistic_transitions(optimistic_transitions);
+        if let Err(e) = fork_choice_update_result {
+            error!(
+                self.log,
+                "Failed to validate payload";
+                "error" => ?e
+            )
+        };
+        Ok(())
+    }
+
     pub async fn update_execution_engine_forkchoice(
         self: &Arc<Self>,
         current_slot: Slot,
@@ -6197,10 +6916,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             return Ok(());
         }
 
-        let execution_layer = self
-            .execution_layer
-            .as_ref()
-            .ok_or(Error::ExecutionLayerMissing)?;
+        let Some(execution_layer) = self.execution_layer.as_ref() else {
+            if let Some(provider) = &self.light_mode {
+                return self
+                    .update_light_mode_head(provider, input_params.head_root, current_slot)
+                    .await;
+            }
+            return Err(Error::ExecutionLayerMissing);
+        };
 
         // Determine whether to override the forkchoiceUpdated message if we want to re-org
         // the current head at the next slot.
@@ -6290,16 +7013,22 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }
             };
 
-        let forkchoice_updated_response = execution_layer
-            .notify_forkchoice_updated(
-                head_hash,
-                justified_hash,
-                finalized_hash,
-                current_slot,
-                head_block_root,
-            )
-            .await
-            .map_err(Error::ExecutionForkChoiceUpdateFailed);
+        let forkchoice_updated = execution_layer.notify_forkchoice_updated(
+            head_hash,
+            justified_hash,
+            finalized_hash,
+            current_slot,
+            head_block_root,
+        );
+        // Capture the exchange with the engine for the forensic bundle of an invalid payload.
+        let (forkchoice_updated_response, engine_exchange) =
+            if self.config.payload_forensics_dir.is_some() {
+                capture_exchanges(forkchoice_updated).await
+            } else {
+                (forkchoice_updated.await, vec![])
+            };
+        let forkchoice_updated_response =
+            forkchoice_updated_response.map_err(Error::ExecutionForkChoiceUpdateFailed);
 
         // The head has been read and the execution layer has been updated. It is now valid to send
         // another fork choice update.
@@ -6308,27 +7037,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         match forkchoice_updated_response {
             Ok(status) => match status {
                 PayloadStatus::Valid => {
-                    // Ensure that fork choice knows that the block is no longer optimistic.
-                    let chain = self.clone();
-                    let fork_choice_update_result = self
-                        .spawn_blocking_handle(
-                            move || {
-                                chain
-                                    .canonical_head
-                                    .fork_choice_write_lock()
-                                    .on_valid_execution_payload(head_block_root)
-                            },
-                            "update_execution_engine_valid_payload",
-                        )
-                        .await?;
-                    if let Err(e) = fork_choice_update_result {
-                        error!(
-                            self.log,
-                            "Failed to validate payload";
-                            "error" => ?e
-                        )
-                    };
-                    Ok(())
+                    self.on_valid_head_payload(head_block_root, current_slot)
+                        .await
                 }
                 // There's nothing to be done for a syncing response. If the block is already
                 // `SYNCING` in fork choice, there's nothing to do. If already known to be `VALID`
@@ -6361,6 +7071,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         "method" => "fcU",
                     );
 
+                    write_forensic_bundle_for_head(self, head_block_root, engine_exchange);
+
                     match latest_valid_hash {
                         // The `latest_valid_hash` is set to `None` when the EE
                         // "cannot determine the ancestor of the invalid
@@ -6413,6 +7125,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         "head_block_root" => ?head_block_root,
                         "method" => "fcU",
                     );
+
+                    write_forensic_bundle_for_head(self, head_block_root, engine_exchange);
+
                     // The execution engine has stated that the head block is invalid, however it
                     // hasn't returned a latest valid ancestor.
                     //
@@ -6727,7 +7442,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // to copy the head is liable to race-conditions.
             let head_state_opt = self.with_head(|head| {
                 if head.beacon_block_root == head_block_root {
-                    Ok(Some((head.beacon_state.clone(), head.beacon_state_root())))
+                    Ok(Some((
+                        self.clone_head_state(head),
+                        head.beacon_state_root(),
+                    )))
                 } else {
                     Ok::<_, Error>(None)
                 }
@@ -7106,12 +7824,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     }
 
     /// Returns true if we should issue a sampling request for this block
-    /// TODO(das): check if the block is still within the da_window
     pub fn should_sample_slot(&self, slot: Slot) -> bool {
-        self.config.enable_sampling
-            && self
-                .spec
-                .is_peer_das_enabled_for_epoch(slot.epoch(T::EthSpec::slots_per_epoch()))
+        let block_epoch = slot.epoch(T::EthSpec::slots_per_epoch());
+        self.config.enable_sampling && self.should_fetch_custody_columns(block_epoch)
     }
 
     pub fn logger(&self) -> &Logger {
@@ -7151,7 +7866,11 @@ impl<T: BeaconChainTypes> Drop for BeaconChain<T> {
         let drop = || -> Result<(), Error> {
             self.persist_head_and_fork_choice()?;
             self.persist_op_pool()?;
-            self.persist_eth1_cache()
+            self.persist_eth1_cache()?;
+            if self.config.fast_restart {
+                self.persist_head_state()?;
+            }
+            Ok(())
         };
 
         if let Err(e) = drop() {
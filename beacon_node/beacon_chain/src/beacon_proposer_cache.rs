@@ -54,12 +54,16 @@ pub struct EpochBlockProposers {
 /// See the module-level documentation for more information.
 pub struct BeaconProposerCache {
     cache: LruCache<(Epoch, Hash256), EpochBlockProposers>,
+    /// The latest epoch of a proposal by a proposer registered with this node, or of a block
+    /// produced by this node.
+    latest_local_proposal_epoch: Option<Epoch>,
 }
 
 impl Default for BeaconProposerCache {
     fn default() -> Self {
         Self {
             cache: LruCache::new(CACHE_SIZE),
+            latest_local_proposal_epoch: None,
         }
     }
 }
@@ -132,6 +136,17 @@ impl BeaconProposerCache {
 
         Ok(())
     }
+
+    /// Records that a proposer registered with this node is due to propose, or that this node has
+    /// produced a block, in `epoch`.
+    pub fn record_local_proposal_epoch(&mut self, epoch: Epoch) {
+        self.latest_local_proposal_epoch = self.latest_local_proposal_epoch.max(Some(epoch));
+    }
+
+    /// Returns the latest epoch recorded with `Self::record_local_proposal_epoch`.
+    pub fn latest_local_proposal_epoch(&self) -> Option<Epoch> {
+        self.latest_local_proposal_epoch
+    }
 }
 
 /// Compute the proposer duties using the head state without cache.
@@ -144,7 +159,7 @@ pub fn compute_proposer_duties_from_head<T: BeaconChainTypes>(
     let (mut state, head_state_root, head_block_root) = {
         let head = chain.canonical_head.cached_head();
         // Take a copy of the head state.
-        let head_state = head.snapshot.beacon_state.clone();
+        let head_state = chain.clone_head_state(&head.snapshot);
         let head_state_root = head.head_state_root();
         let head_block_root = head.head_block_root();
         (head_state, head_state_root, head_block_root)
@@ -0,0 +1,267 @@
+//! Per-stage time budgets for block production.
+//!
+//! Block production is split into a handful of stages (state advance, attestation packing, payload
+//! fetch and state root computation). Each stage may be assigned a soft budget. Attestation packing
+//! degrades gracefully rather than missing the proposal: it stops accepting new candidate
+//! attestations once its budget is spent, which results in a block with fewer attestations.
+//!
+//! The other stages are only measured, with overruns counted in metrics and reported in the
+//! `block_production` event. In particular there is no fallback when the payload fetch overruns its
+//! budget: the execution payload is mandatory post-merge, and falling back from a slow builder to
+//! the locally built payload is governed by the execution layer's builder timeouts instead.
+use crate::metrics;
+use eth2::types::{SseBlockProduction, SseBlockProductionStage};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use types::Slot;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockProductionStage {
+    SlotProcessing,
+    AttestationPacking,
+    PayloadFetch,
+    StateRoot,
+}
+
+impl BlockProductionStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockProductionStage::SlotProcessing => "slot_processing",
+            BlockProductionStage::AttestationPacking => "attestation_packing",
+            BlockProductionStage::PayloadFetch => "payload_fetch",
+            BlockProductionStage::StateRoot => "state_root",
+        }
+    }
+}
+
+impl FromStr for BlockProductionStage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "slot_processing" => Ok(BlockProductionStage::SlotProcessing),
+            "attestation_packing" => Ok(BlockProductionStage::AttestationPacking),
+            "payload_fetch" => Ok(BlockProductionStage::PayloadFetch),
+            "state_root" => Ok(BlockProductionStage::StateRoot),
+            other => Err(format!("unknown block production stage: {other}")),
+        }
+    }
+}
+
+impl fmt::Display for BlockProductionStage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Soft time budgets for each stage of block production.
+///
+/// A budget of `None` means the stage is unbounded.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+pub struct BlockProductionBudget {
+    pub slot_processing: Option<Duration>,
+    pub attestation_packing: Option<Duration>,
+    pub payload_fetch: Option<Duration>,
+    pub state_root: Option<Duration>,
+}
+
+impl BlockProductionBudget {
+    pub fn for_stage(&self, stage: BlockProductionStage) -> Option<Duration> {
+        match stage {
+            BlockProductionStage::SlotProcessing => self.slot_processing,
+            BlockProductionStage::AttestationPacking => self.attestation_packing,
+            BlockProductionStage::PayloadFetch => self.payload_fetch,
+            BlockProductionStage::StateRoot => self.state_root,
+        }
+    }
+
+    fn for_stage_mut(&mut self, stage: BlockProductionStage) -> &mut Option<Duration> {
+        match stage {
+            BlockProductionStage::SlotProcessing => &mut self.slot_processing,
+            BlockProductionStage::AttestationPacking => &mut self.attestation_packing,
+            BlockProductionStage::PayloadFetch => &mut self.payload_fetch,
+            BlockProductionStage::StateRoot => &mut self.state_root,
+        }
+    }
+}
+
+/// Parses a comma-separated list of `stage=millis` pairs, e.g.
+/// `attestation_packing=500,state_root=250`.
+impl FromStr for BlockProductionBudget {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut budget = Self::default();
+        for pair in s.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+            let (stage, millis) = pair
+                .split_once('=')
+                .ok_or_else(|| format!("expected stage=millis, got: {pair}"))?;
+            let stage = stage.trim().parse::<BlockProductionStage>()?;
+            let millis = millis
+                .trim()
+                .parse::<u64>()
+                .map_err(|e| format!("invalid budget for {stage}: {e:?}"))?;
+            *budget.for_stage_mut(stage) = Some(Duration::from_millis(millis));
+        }
+        Ok(budget)
+    }
+}
+
+/// A deadline for a single stage, used to cut work short once the stage budget is spent.
+#[derive(Debug, Clone, Copy)]
+pub struct StageDeadline {
+    deadline: Option<Instant>,
+}
+
+impl StageDeadline {
+    pub fn new(budget: Option<Duration>) -> Self {
+        Self {
+            deadline: budget.and_then(|budget| Instant::now().checked_add(budget)),
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
+/// Records the time spent in each stage of a single block production.
+#[derive(Debug, Clone)]
+pub struct BlockProductionTimings {
+    budget: BlockProductionBudget,
+    started: Instant,
+    stages: Vec<(BlockProductionStage, Duration)>,
+    degraded: Vec<BlockProductionStage>,
+}
+
+impl BlockProductionTimings {
+    pub fn new(budget: BlockProductionBudget) -> Self {
+        Self {
+            budget,
+            started: Instant::now(),
+            stages: vec![],
+            degraded: vec![],
+        }
+    }
+
+    pub fn budget(&self) -> &BlockProductionBudget {
+        &self.budget
+    }
+
+    /// Start a deadline for `stage` based on its configured budget.
+    pub fn deadline(&self, stage: BlockProductionStage) -> StageDeadline {
+        StageDeadline::new(self.budget.for_stage(stage))
+    }
+
+    /// Record the `duration` of `stage`, updating metrics if it overran its budget.
+    pub fn record(&mut self, stage: BlockProductionStage, duration: Duration) {
+        if self
+            .budget
+            .for_stage(stage)
+            .is_some_and(|budget| duration > budget)
+        {
+            metrics::inc_counter_vec(
+                &metrics::BLOCK_PRODUCTION_STAGE_BUDGET_EXCEEDED,
+                &[stage.as_str()],
+            );
+        }
+        self.stages.push((stage, duration));
+    }
+
+    /// Note that `stage` produced a degraded result in order to stay within budget.
+    pub fn mark_degraded(&mut self, stage: BlockProductionStage) {
+        if !self.degraded.contains(&stage) {
+            metrics::inc_counter_vec(&metrics::BLOCK_PRODUCTION_DEGRADED, &[stage.as_str()]);
+            self.degraded.push(stage);
+        }
+    }
+
+    pub fn stage_duration(&self, stage: BlockProductionStage) -> Option<Duration> {
+        self.stages
+            .iter()
+            .find(|(s, _)| *s == stage)
+            .map(|(_, duration)| *duration)
+    }
+
+    pub fn is_degraded(&self, stage: BlockProductionStage) -> bool {
+        self.degraded.contains(&stage)
+    }
+
+    pub fn to_sse_event(&self, slot: Slot, proposer_index: u64) -> SseBlockProduction {
+        SseBlockProduction {
+            slot,
+            proposer_index,
+            total_ms: self.started.elapsed().as_millis() as u64,
+            stages: self
+                .stages
+                .iter()
+                .map(|(stage, duration)| SseBlockProductionStage {
+                    stage: stage.as_str().to_string(),
+                    duration_ms: duration.as_millis() as u64,
+                    budget_ms: self
+                        .budget
+                        .for_stage(*stage)
+                        .map(|budget| budget.as_millis() as u64),
+                    degraded: self.degraded.contains(stage),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_budget() {
+        let budget = "attestation_packing=500, state_root=250"
+            .parse::<BlockProductionBudget>()
+            .unwrap();
+        assert_eq!(budget.slot_processing, None);
+        assert_eq!(budget.attestation_packing, Some(Duration::from_millis(500)));
+        assert_eq!(budget.payload_fetch, None);
+        assert_eq!(budget.state_root, Some(Duration::from_millis(250)));
+
+        assert!("attestation_packing"
+            .parse::<BlockProductionBudget>()
+            .is_err());
+        assert!("unknown=5".parse::<BlockProductionBudget>().is_err());
+        assert!("state_root=x".parse::<BlockProductionBudget>().is_err());
+    }
+
+    #[test]
+    fn unbounded_deadline_never_expires() {
+        assert!(!StageDeadline::new(None).is_expired());
+        assert!(StageDeadline::new(Some(Duration::ZERO)).is_expired());
+    }
+
+    #[test]
+    fn record_and_degrade() {
+        let mut timings = BlockProductionTimings::new(BlockProductionBudget {
+            attestation_packing: Some(Duration::from_millis(10)),
+            ..Default::default()
+        });
+        timings.record(
+            BlockProductionStage::AttestationPacking,
+            Duration::from_millis(20),
+        );
+        timings.mark_degraded(BlockProductionStage::AttestationPacking);
+        timings.mark_degraded(BlockProductionStage::AttestationPacking);
+
+        assert_eq!(
+            timings.stage_duration(BlockProductionStage::AttestationPacking),
+            Some(Duration::from_millis(20))
+        );
+        assert!(timings.is_degraded(BlockProductionStage::AttestationPacking));
+        assert!(!timings.is_degraded(BlockProductionStage::PayloadFetch));
+
+        let event = timings.to_sse_event(Slot::new(1), 7);
+        assert_eq!(event.stages.len(), 1);
+        assert_eq!(event.stages[0].stage, "attestation_packing");
+        assert!(event.stages[0].degraded);
+    }
+}
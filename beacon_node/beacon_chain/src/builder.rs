@@ -8,11 +8,21 @@ use crate::eth1_finalization_cache::Eth1FinalizationCache;
 use crate::fork_choice_signal::ForkChoiceSignalTx;
 use crate::fork_revert::{reset_fork_choice_to_finalization, revert_to_fork_boundary};
 use crate::graffiti_calculator::{GraffitiCalculator, GraffitiOrigin};
+use crate::graffiti_file::GraffitiFile;
+use crate::head_state_pool::HeadStatePool;
 use crate::head_tracker::HeadTracker;
 use crate::light_client_server_cache::LightClientServerCache;
+use crate::light_mode::TrustedPayloadProvider;
+use crate::metrics;
 use crate::migrate::{BackgroundMigrator, MigratorConfig};
 use crate::observed_data_sidecars::ObservedDataSidecars;
+use crate::own_key_guard::OwnKeyGuard;
+use crate::payload_attributes_policy::{
+    ChainConfigPayloadAttributesPolicy, PayloadAttributesPolicy,
+};
 use crate::persisted_beacon_chain::PersistedBeaconChain;
+use crate::persisted_head_state::{PersistedHeadState, HEAD_STATE_DB_KEY};
+use crate::re_org_policy::{ChainConfigReOrgPolicy, ReOrgPolicy};
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
 use crate::validator_monitor::{ValidatorMonitor, ValidatorMonitorConfig};
 use crate::validator_pubkey_cache::ValidatorPubkeyCache;
@@ -30,10 +40,11 @@ use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, o, Logger};
+use slog::{crit, debug, error, info, o, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::{per_slot_processing, AllCaches};
 use std::marker::PhantomData;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 use store::{Error as StoreError, HotColdDB, ItemStore, KeyValueStoreOp};
@@ -97,6 +108,7 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     chain_config: ChainConfig,
     log: Option<Logger>,
     beacon_graffiti: GraffitiOrigin,
+    graffiti_file: Option<PathBuf>,
     slasher: Option<Arc<Slasher<T::EthSpec>>>,
     // Pending I/O batch that is constructed during building and should be executed atomically
     // alongside `PersistedBeaconChain` storage when `BeaconChainBuilder::build` is called.
@@ -105,6 +117,8 @@ pub struct BeaconChainBuilder<T: BeaconChainTypes> {
     task_executor: Option<TaskExecutor>,
     validator_monitor_config: Option<ValidatorMonitorConfig>,
     import_all_data_columns: bool,
+    re_org_policy: Option<Arc<dyn ReOrgPolicy>>,
+    payload_attributes_policy: Option<Arc<dyn PayloadAttributesPolicy>>,
 }
 
 impl<TSlotClock, TEth1Backend, E, THotStore, TColdStore>
@@ -141,12 +155,15 @@ where
             chain_config: ChainConfig::default(),
             log: None,
             beacon_graffiti: GraffitiOrigin::default(),
+            graffiti_file: None,
             slasher: None,
             pending_io_batch: vec![],
             kzg,
             task_executor: None,
             validator_monitor_config: None,
             import_all_data_columns: false,
+            re_org_policy: None,
+            payload_attributes_policy: None,
         }
     }
 
@@ -197,6 +214,25 @@ where
         self
     }
 
+    /// Sets the policy used to decide whether to attempt proposer re-orgs.
+    ///
+    /// If not set, a `ChainConfigReOrgPolicy` is derived from the chain config.
+    pub fn re_org_policy(mut self, re_org_policy: Arc<dyn ReOrgPolicy>) -> Self {
+        self.re_org_policy = Some(re_org_policy);
+        self
+    }
+
+    /// Sets the policy which decides the proposals that payload attributes are sent for.
+    ///
+    /// If not set, a `ChainConfigPayloadAttributesPolicy` is derived from the chain config.
+    pub fn payload_attributes_policy(
+        mut self,
+        payload_attributes_policy: Arc<dyn PayloadAttributesPolicy>,
+    ) -> Self {
+        self.payload_attributes_policy = Some(payload_attributes_policy);
+        self
+    }
+
     /// Sets the store (database).
     ///
     /// Should generally be called early in the build chain.
@@ -684,6 +720,12 @@ where
         self
     }
 
+    /// Sets the path of the graffiti file, which is reloaded whenever it changes.
+    pub fn graffiti_file(mut self, graffiti_file: Option<PathBuf>) -> Self {
+        self.graffiti_file = graffiti_file;
+        self
+    }
+
     /// Sets the `ChainConfig` that determines `BeaconChain` runtime behaviour.
     pub fn chain_config(mut self, config: ChainConfig) -> Self {
         self.chain_config = config;
@@ -776,10 +818,31 @@ where
                 Err(e) => return Err(descriptive_db_error("head block", &e)),
             };
 
-        let (_head_state_root, head_state) = store
-            .get_advanced_hot_state(head_block_root, current_slot, head_block.state_root())
-            .map_err(|e| descriptive_db_error("head state", &e))?
-            .ok_or("Head state not found in store")?;
+        let persisted_head_state = if self.chain_config.fast_restart {
+            load_persisted_head_state(
+                &store,
+                head_block_root,
+                &head_block,
+                current_slot,
+                &self.spec,
+                &log,
+            )?
+        } else {
+            // Remove any state persisted while fast restarts were enabled, as it won't be updated.
+            store
+                .hot_db
+                .delete::<PersistedHeadState>(&HEAD_STATE_DB_KEY)
+                .map_err(|e| descriptive_db_error("persisted head state", &e))?;
+            None
+        };
+
+        let (_head_state_root, head_state) = match persisted_head_state {
+            Some(persisted_head_state) => persisted_head_state,
+            None => store
+                .get_advanced_hot_state(head_block_root, current_slot, head_block.state_root())
+                .map_err(|e| descriptive_db_error("head state", &e))?
+                .ok_or("Head state not found in store")?,
+        };
 
         // If the head reverted then we need to reset fork choice using the new head's finalized
         // checkpoint.
@@ -877,6 +940,18 @@ where
         let genesis_time = head_snapshot.beacon_state.genesis_time();
         let canonical_head = CanonicalHead::new(fork_choice, Arc::new(head_snapshot));
         let shuffling_cache_size = self.chain_config.shuffling_cache_size;
+        let own_key_guard = OwnKeyGuard::new(self.chain_config.own_key_guard_pubkeys.clone());
+        let head_state_pool = Arc::new(HeadStatePool::new(self.chain_config.head_state_pool_size));
+        let light_mode = self
+            .chain_config
+            .light_mode_provider
+            .clone()
+            .map(TrustedPayloadProvider::new);
+
+        let graffiti_file = self
+            .graffiti_file
+            .map(|path| GraffitiFile::new(path, log.new(o!("service" => "graffiti_file"))))
+            .transpose()?;
 
         // Calculate the weak subjectivity point in which to backfill blocks to.
         let genesis_backfill_slot = if self.chain_config.genesis_backfill {
@@ -905,9 +980,18 @@ where
             }
         };
 
+        let re_org_policy = self
+            .re_org_policy
+            .unwrap_or_else(|| Arc::new(ChainConfigReOrgPolicy::new(&self.chain_config)));
+        let payload_attributes_policy = self.payload_attributes_policy.unwrap_or_else(|| {
+            Arc::new(ChainConfigPayloadAttributesPolicy::new(&self.chain_config))
+        });
+
         let beacon_chain = BeaconChain {
             spec: self.spec.clone(),
             config: self.chain_config,
+            re_org_policy,
+            payload_attributes_policy,
             store: store.clone(),
             task_executor: self
                 .task_executor
@@ -942,11 +1026,14 @@ where
             observed_proposer_slashings: <_>::default(),
             observed_attester_slashings: <_>::default(),
             observed_bls_to_execution_changes: <_>::default(),
+            own_key_guard,
             eth1_chain: self.eth1_chain,
             execution_layer: self.execution_layer.clone(),
+            light_mode,
             genesis_validators_root,
             genesis_time,
             canonical_head,
+            head_state_pool,
             genesis_block_root,
             genesis_state_root,
             fork_choice_signal_tx,
@@ -961,9 +1048,13 @@ where
             eth1_finalization_cache: RwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache,
             block_times_cache: <_>::default(),
+            optimistic_transitions: <_>::default(),
+            head_timeline: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
+            validator_proof_cache: <_>::default(),
             validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
+            attestation_data_cache: <_>::default(),
             early_attester_cache: <_>::default(),
             reqresp_pre_import_cache: <_>::default(),
             light_client_server_cache: LightClientServerCache::new(),
@@ -974,6 +1065,7 @@ where
             log: log.clone(),
             graffiti_calculator: GraffitiCalculator::new(
                 self.beacon_graffiti,
+                graffiti_file,
                 self.execution_layer,
                 slot_clock.slot_duration() * E::slots_per_epoch() as u32,
                 log.clone(),
@@ -1140,6 +1232,179 @@ fn genesis_block<E: EthSpec>(
     ))
 }
 
+/// Loads the head state persisted for a fast restart, if it is a state of `head_block_root` or of
+/// one of its ancestors, and isn't ahead of `current_slot`.
+///
+/// A state persisted for an ancestor of the head (e.g. by the periodic persist prior to a crash)
+/// has the blocks since it replayed on top of it, which is much cheaper than replaying from the
+/// last epoch boundary state and rebuilding every cache.
+///
+/// The loaded state is added to the state cache with all of its caches built. `None` is returned
+/// if the head state should be loaded from the hot database as usual.
+fn load_persisted_head_state<E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    head_block_root: Hash256,
+    head_block: &SignedBeaconBlock<E>,
+    current_slot: Slot,
+    spec: &ChainSpec,
+    log: &Logger,
+) -> Result<Option<(Hash256, BeaconState<E>)>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let _timer = metrics::start_timer(&metrics::LOAD_PERSISTED_HEAD_STATE);
+
+    let Some(persisted) = store
+        .get_item::<PersistedHeadState>(&HEAD_STATE_DB_KEY)
+        .map_err(|e| descriptive_db_error("persisted head state", &e))?
+    else {
+        return Ok(None);
+    };
+
+    let (block_root, state_root) = (persisted.block_root, persisted.state_root);
+
+    let mut state = match persisted.into_state::<E>(spec) {
+        Ok(state) => state,
+        Err(e) => {
+            warn!(
+                log,
+                "Unable to load persisted head state";
+                "error" => e,
+            );
+            return Ok(None);
+        }
+    };
+
+    if state.slot() > current_slot {
+        info!(
+            log,
+            "Ignoring persisted head state from a future slot";
+            "state_slot" => state.slot(),
+            "current_slot" => current_slot,
+        );
+        return Ok(None);
+    }
+
+    // Check the state against its persisted root, which also builds the tree hash cache.
+    let computed_state_root = state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to hash persisted head state: {:?}", e))?;
+    if computed_state_root != state_root {
+        warn!(
+            log,
+            "Persisted head state is corrupt";
+            "expected_state_root" => ?state_root,
+            "state_root" => ?computed_state_root,
+        );
+        return Ok(None);
+    }
+
+    let (state_root, mut state) = if block_root == head_block_root {
+        (state_root, state)
+    } else {
+        let Some(state) =
+            replay_to_head_block(store, block_root, state, head_block_root, head_block, log)?
+        else {
+            return Ok(None);
+        };
+        (head_block.state_root(), state)
+    };
+
+    state
+        .build_all_caches(spec)
+        .map_err(|e| format!("Failed to build persisted head state caches: {:?}", e))?;
+    store
+        .put_restored_hot_state(&state_root, &state)
+        .map_err(|e| descriptive_db_error("persisted head state", &e))?;
+
+    info!(
+        log,
+        "Loaded persisted head state";
+        "slot" => state.slot(),
+        "state_root" => ?state_root,
+    );
+
+    Ok(Some((state_root, state)))
+}
+
+/// Replays the blocks from `state`, a state of `block_root`, up to and including `head_block`.
+///
+/// Returns `None` if `block_root` isn't an ancestor of the head block or the replayed state doesn't
+/// match the head block's state root.
+fn replay_to_head_block<E, Hot, Cold>(
+    store: &HotColdDB<E, Hot, Cold>,
+    block_root: Hash256,
+    state: BeaconState<E>,
+    head_block_root: Hash256,
+    head_block: &SignedBeaconBlock<E>,
+    log: &Logger,
+) -> Result<Option<BeaconState<E>>, String>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    let stale = |reason: &str| {
+        info!(
+            log,
+            "Ignoring stale persisted head state";
+            "reason" => reason,
+            "persisted_block_root" => ?block_root,
+            "head_block_root" => ?head_block_root,
+        );
+    };
+
+    if state.slot() >= head_block.slot() {
+        stale("not an ancestor of the head");
+        return Ok(None);
+    }
+
+    let blocks = store
+        .load_blocks_to_replay(state.slot(), head_block.slot(), head_block_root)
+        .map_err(|e| descriptive_db_error("blocks to replay", &e))?;
+
+    // The first block after the state must build on the persisted block for the state to be an
+    // ancestor of the head.
+    let first_parent_root = blocks
+        .iter()
+        .find(|block| block.slot() > state.slot())
+        .map(|block| block.parent_root());
+    if first_parent_root != Some(block_root) {
+        stale("not an ancestor of the head");
+        return Ok(None);
+    }
+
+    let num_blocks = blocks.len();
+    let mut state = store
+        .replay_blocks(
+            state,
+            blocks,
+            head_block.slot(),
+            None::<std::iter::Empty<_>>,
+            None,
+        )
+        .map_err(|e| format!("Unable to replay blocks on persisted head state: {:?}", e))?;
+
+    let state_root = state
+        .update_tree_hash_cache()
+        .map_err(|e| format!("Unable to hash persisted head state: {:?}", e))?;
+    if state_root != head_block.state_root() {
+        stale("replayed state does not match the head block");
+        return Ok(None);
+    }
+
+    debug!(
+        log,
+        "Replayed blocks on persisted head state";
+        "num_blocks" => num_blocks,
+        "head_slot" => head_block.slot(),
+    );
+
+    Ok(Some(state))
+}
+
 // Helper function to return more useful errors when reading from the database.
 fn descriptive_db_error(item: &str, error: &StoreError) -> String {
     let additional_info = if let StoreError::SszDecodeError(_) = error {
@@ -41,6 +41,7 @@ use crate::{
     validator_monitor::{get_slot_delay_ms, timestamp_now},
     BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconSnapshot,
 };
+use eth2::lighthouse::HeadTimelineEventKind;
 use eth2::types::{EventKind, SseChainReorg, SseFinalizedCheckpoint, SseHead, SseLateHead};
 use fork_choice::{
     ExecutionStatus, ForkChoiceStore, ForkChoiceView, ForkchoiceUpdateParameters, ProtoBlock,
@@ -465,7 +466,43 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
     pub fn head_beacon_state_cloned(&self) -> BeaconState<T::EthSpec> {
         // Don't clone whilst holding the read-lock, take an Arc-clone to reduce lock contention.
         let snapshot: Arc<_> = self.head_snapshot();
-        snapshot.beacon_state.clone()
+        self.clone_head_state(&snapshot)
+    }
+
+    /// Returns a clone of the state in `snapshot`, which should be a recent head snapshot.
+    ///
+    /// The clone is taken from the `head_state_pool` if possible, which is then refilled on a
+    /// blocking thread. Otherwise the state is cloned on demand.
+    pub fn clone_head_state(
+        &self,
+        snapshot: &BeaconSnapshot<T::EthSpec>,
+    ) -> BeaconState<T::EthSpec> {
+        if !self.head_state_pool.is_enabled() {
+            return snapshot.beacon_state.clone();
+        }
+
+        let state = if let Some(state) = self.head_state_pool.take(snapshot) {
+            metrics::inc_counter(&metrics::HEAD_STATE_POOL_HITS);
+            state
+        } else {
+            metrics::inc_counter(&metrics::HEAD_STATE_POOL_MISSES);
+            snapshot.beacon_state.clone()
+        };
+        self.refill_head_state_pool();
+        state
+    }
+
+    /// Refills the `head_state_pool` with clones of the current head state on a blocking thread.
+    fn refill_head_state_pool(&self) {
+        if !self.head_state_pool.is_enabled() {
+            return;
+        }
+        let head_state_pool = self.head_state_pool.clone();
+        let snapshot = self.head_snapshot();
+        self.task_executor.spawn_blocking(
+            move || head_state_pool.refill(&snapshot),
+            "head_state_pool_refill",
+        );
     }
 
     /// Execute the fork choice algorithm and enthrone the result as the canonical head.
@@ -580,6 +617,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Recompute the current head via the fork choice algorithm.
         fork_choice_write_lock.get_head(current_slot, &self.spec)?;
 
+        // Proposer boost is removed when fork choice is advanced to a new slot.
+        self.observe_head_timeline_proposer_boost(fork_choice_write_lock.proposer_boost_root());
+
         // Downgrade the fork choice write-lock to a read lock, without allowing access to any
         // other writers.
         let fork_choice_read_lock = RwLockWriteGuard::downgrade(fork_choice_write_lock);
@@ -641,6 +681,24 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         perform_debug_logging::<T>(&old_view, &new_view, &fork_choice_read_lock, &self.log);
 
+        if new_view.head_block_root != old_view.head_block_root {
+            let block_weight = |block_root| {
+                fork_choice_read_lock
+                    .get_block_weight(block_root)
+                    .unwrap_or(0)
+            };
+            self.record_head_timeline_event(HeadTimelineEventKind::HeadChanged {
+                previous_head_root: old_view.head_block_root,
+                head_root: new_view.head_block_root,
+                head_slot: new_head_proto_block.slot,
+                reorg: !fork_choice_read_lock
+                    .proto_array()
+                    .is_descendant(old_view.head_block_root, new_view.head_block_root),
+                previous_head_weight: block_weight(&old_view.head_block_root),
+                head_weight: block_weight(&new_view.head_block_root),
+            });
+        }
+
         // Drop the read lock, it's no longer required and holding it any longer than necessary
         // will just cause lock contention.
         drop(fork_choice_read_lock);
@@ -728,6 +786,8 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         // If the head changed, perform some updates.
         if new_snapshot.beacon_block_root != old_snapshot.beacon_block_root {
+            self.refill_head_state_pool();
+
             if let Err(e) =
                 self.after_new_head(&old_cached_head, &new_cached_head, new_head_proto_block)
             {
@@ -951,6 +1011,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         self.attester_cache
             .prune_below(new_view.finalized_checkpoint.epoch);
 
+        self.own_key_guard
+            .prune(new_view.finalized_checkpoint.epoch);
+
         if let Some(event_handler) = self.event_handler.as_ref() {
             if event_handler.has_finalized_subscribers() {
                 event_handler.register(EventKind::FinalizedCheckpoint(SseFinalizedCheckpoint {
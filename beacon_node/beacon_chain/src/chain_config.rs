@@ -1,7 +1,10 @@
+pub use crate::block_production_budget::BlockProductionBudget;
 pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
-use types::{Checkpoint, Epoch};
+use types::{Checkpoint, Epoch, PublicKeyBytes};
 
 pub const DEFAULT_RE_ORG_HEAD_THRESHOLD: ReOrgThreshold = ReOrgThreshold(20);
 pub const DEFAULT_RE_ORG_PARENT_THRESHOLD: ReOrgThreshold = ReOrgThreshold(160);
@@ -43,10 +46,19 @@ pub struct ChainConfig {
     /// By default this list is empty, but it can be useful for reacting to network conditions, e.g.
     /// slow gossip of re-org blocks at slot 1 in the epoch.
     pub re_org_disallowed_offsets: DisallowedReOrgOffsets,
+    /// Number of epochs either side of a scheduled fork at which re-orging block proposals are not
+    /// permitted.
+    pub re_org_fork_boundary_epochs: Option<u64>,
+    /// Validator indices whose blocks should never be re-orged.
+    pub re_org_protected_proposers: Vec<u64>,
     /// Number of milliseconds to wait for fork choice before proposing a block.
     ///
     /// If set to 0 then block proposal will not wait for fork choice at all.
     pub fork_choice_before_proposal_timeout_ms: u64,
+    /// Soft time budgets for each stage of block production.
+    ///
+    /// Stages which overrun their budget degrade their output rather than failing the proposal.
+    pub block_production_budget: BlockProductionBudget,
     /// Number of skip slots in a row before the BN refuses to use connected builders during payload construction.
     pub builder_fallback_skips: usize,
     /// Number of skip slots in the past `SLOTS_PER_EPOCH` before the BN refuses to use connected
@@ -71,8 +83,19 @@ pub struct ChainConfig {
     pub prepare_payload_lookahead: Duration,
     /// Use EL-free optimistic sync for the finalized part of the chain.
     pub optimistic_finalized_sync: bool,
+    /// A trusted beacon node which determines payload validity in place of an execution engine.
+    ///
+    /// If set, the node runs in light mode: it follows the chain optimistically and never serves
+    /// validator duties.
+    pub light_mode_provider: Option<SensitiveUrl>,
     /// The size of the shuffling cache,
     pub shuffling_cache_size: usize,
+    /// Whether to pre-compute attester shufflings for likely heads during the quiet part of each
+    /// slot.
+    pub shuffling_cache_warming: bool,
+    /// Whether to allow attesting to a block which has been verified but is still awaiting its
+    /// blobs, for the remainder of the block's slot.
+    pub early_attester_cache_pending_blobs: bool,
     /// If using a weak-subjectivity sync, whether we should download blocks all the way back to
     /// genesis.
     pub genesis_backfill: bool,
@@ -80,6 +103,12 @@ pub struct ChainConfig {
     ///
     /// This is useful for block builders and testing.
     pub always_prepare_payload: bool,
+    /// Whether to never send payload attributes, e.g. on a node which is not used for block
+    /// production.
+    pub suppress_payload_attributes: bool,
+    /// If set, payload attributes are only sent if a registered proposer was due to propose, or
+    /// this node produced a block, within this many epochs of the proposal.
+    pub payload_attributes_window_epochs: Option<u64>,
     /// Number of epochs between each migration of data from the hot database to the freezer.
     pub epochs_per_migration: u64,
     /// When set to true Light client server computes and caches state proofs for serving updates
@@ -94,6 +123,20 @@ pub struct ChainConfig {
     /// The delay in milliseconds applied by the node between sending each blob or data column batch.
     /// This doesn't apply if the node is the block proposer.
     pub blob_publication_batch_interval: Duration,
+    /// Whether to persist the head state at shutdown and load it at start-up, so that the node
+    /// doesn't need to replay blocks and rebuild caches before it can serve validator duties.
+    pub fast_restart: bool,
+    /// If set (and `fast_restart` is enabled), also persist the head state every this many epochs
+    /// so that restarts after a crash are fast.
+    pub fast_restart_interval_epochs: Option<u64>,
+    /// The validators whose attestations and blocks are refused publication if they conflict with
+    /// a message already seen from the same validator, see `OwnKeyGuard`.
+    pub own_key_guard_pubkeys: Vec<PublicKeyBytes>,
+    /// The directory to write a forensic bundle to when the execution engine returns INVALID for a
+    /// payload, see `payload_forensics`.
+    pub payload_forensics_dir: Option<PathBuf>,
+    /// The number of clones of the head state to make in advance, see `HeadStatePool`.
+    pub head_state_pool_size: usize,
 }
 
 impl Default for ChainConfig {
@@ -108,7 +151,10 @@ impl Default for ChainConfig {
             re_org_max_epochs_since_finalization: DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
             re_org_cutoff_millis: None,
             re_org_disallowed_offsets: DisallowedReOrgOffsets::default(),
+            re_org_fork_boundary_epochs: None,
+            re_org_protected_proposers: vec![],
             fork_choice_before_proposal_timeout_ms: DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT,
+            block_production_budget: BlockProductionBudget::default(),
             // Builder fallback configs that are set in `clap` will override these.
             builder_fallback_skips: 3,
             builder_fallback_skips_per_epoch: 8,
@@ -120,15 +166,25 @@ impl Default for ChainConfig {
             prepare_payload_lookahead: Duration::from_secs(4),
             // This value isn't actually read except in tests.
             optimistic_finalized_sync: true,
+            light_mode_provider: None,
             shuffling_cache_size: crate::shuffling_cache::DEFAULT_CACHE_SIZE,
+            shuffling_cache_warming: true,
+            early_attester_cache_pending_blobs: false,
             genesis_backfill: false,
             always_prepare_payload: false,
+            suppress_payload_attributes: false,
+            payload_attributes_window_epochs: None,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
             enable_light_client_server: false,
             malicious_withhold_count: 0,
             enable_sampling: false,
             blob_publication_batches: 4,
             blob_publication_batch_interval: Duration::from_millis(300),
+            fast_restart: false,
+            fast_restart_interval_epochs: None,
+            own_key_guard_pubkeys: vec![],
+            payload_forensics_dir: None,
+            head_state_pool_size: crate::head_state_pool::DEFAULT_HEAD_STATE_POOL_SIZE,
         }
     }
 }
@@ -136,10 +192,16 @@ impl Default for ChainConfig {
 impl ChainConfig {
     /// The latest delay from the start of the slot at which to attempt a 1-slot re-org.
     pub fn re_org_cutoff(&self, seconds_per_slot: u64) -> Duration {
-        self.re_org_cutoff_millis
-            .map(Duration::from_millis)
-            .unwrap_or_else(|| {
-                Duration::from_secs(seconds_per_slot) / DEFAULT_RE_ORG_CUTOFF_DENOMINATOR
-            })
+        Self::re_org_cutoff_from_millis(self.re_org_cutoff_millis, seconds_per_slot)
+    }
+
+    /// The re-org cutoff for an optional `cutoff_millis`, defaulting to a fraction of the slot.
+    pub fn re_org_cutoff_from_millis(
+        cutoff_millis: Option<u64>,
+        seconds_per_slot: u64,
+    ) -> Duration {
+        cutoff_millis.map(Duration::from_millis).unwrap_or_else(|| {
+            Duration::from_secs(seconds_per_slot) / DEFAULT_RE_ORG_CUTOFF_DENOMINATOR
+        })
     }
 }
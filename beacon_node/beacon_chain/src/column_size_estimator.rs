@@ -0,0 +1,38 @@
+//! Provides a routine which periodically estimates the size of each database column.
+//!
+//! The estimates are published as metrics, so that operators can attribute disk growth to blocks,
+//! states, blobs and so on. Each estimate iterates over every key in the database, so the routine
+//! only runs if `StoreConfig::column_size_estimate_interval` is set.
+use crate::{BeaconChain, BeaconChainTypes};
+use slog::debug;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+
+/// Spawns a routine which estimates the column sizes at the configured interval, if any.
+pub fn start_column_size_estimator<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    let Some(interval) = chain.store.get_config().column_size_estimate_interval else {
+        return;
+    };
+
+    executor.clone().spawn(
+        async move {
+            loop {
+                let store = chain.store.clone();
+                // Wait for each estimate to complete, so that slow estimates never overlap.
+                if let Some(handle) = executor.spawn_blocking_handle(
+                    move || store.scrape_column_sizes(),
+                    "column_size_estimator",
+                ) {
+                    let _ = handle.await;
+                }
+                debug!(chain.log, "Estimated database column sizes");
+                sleep(interval).await;
+            }
+        },
+        "column_size_estimator",
+    );
+}
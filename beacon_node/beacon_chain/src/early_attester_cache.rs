@@ -23,7 +23,51 @@ pub struct CacheItem<E: EthSpec> {
     block: Arc<SignedBeaconBlock<E>>,
     blobs: Option<BlobSidecarList<E>>,
     data_columns: Option<DataColumnSidecarList<E>>,
-    proto_block: ProtoBlock,
+    /// The fork choice block, or `None` if the block is still awaiting its blobs or data columns.
+    proto_block: Option<ProtoBlock>,
+}
+
+impl<E: EthSpec> CacheItem<E> {
+    /// Returns `true` if the block has passed consensus and execution verification but has not
+    /// yet been made available (i.e., it is not yet in fork choice).
+    fn is_availability_pending(&self) -> bool {
+        self.proto_block.is_none()
+    }
+
+    fn new(
+        beacon_block_root: Hash256,
+        block: Arc<SignedBeaconBlock<E>>,
+        blobs: Option<BlobSidecarList<E>>,
+        data_columns: Option<DataColumnSidecarList<E>>,
+        proto_block: Option<ProtoBlock>,
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<Self, Error> {
+        let epoch = state.current_epoch();
+        let committee_lengths = CommitteeLengths::new(state, spec)?;
+        let source = state.current_justified_checkpoint();
+        let target_slot = epoch.start_slot(E::slots_per_epoch());
+        let target = Checkpoint {
+            epoch,
+            root: if state.slot() <= target_slot {
+                beacon_block_root
+            } else {
+                *state.get_block_root(target_slot)?
+            },
+        };
+
+        Ok(Self {
+            epoch,
+            committee_lengths,
+            beacon_block_root,
+            source,
+            target,
+            block,
+            blobs,
+            data_columns,
+            proto_block,
+        })
+    }
 }
 
 /// Provides a single-item cache which allows for attesting to blocks before those blocks have
@@ -35,6 +79,11 @@ pub struct CacheItem<E: EthSpec> {
 /// - Verify that a block root exists (i.e., will be imported in the future) during attestation
 ///     verification.
 /// - Provide a block which can be sent to peers via RPC.
+///
+/// A block which is still awaiting its blobs may also be added to the cache (see
+/// `Self::add_availability_pending_block`). Such a block is only used to produce attestations in
+/// its own slot; it is not treated as known for attestation verification or served to peers until
+/// it becomes available and is imported.
 #[derive(Default)]
 pub struct EarlyAttesterCache<E: EthSpec> {
     item: RwLock<Option<CacheItem<E>>>,
@@ -57,37 +106,50 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
         state: &BeaconState<E>,
         spec: &ChainSpec,
     ) -> Result<(), Error> {
-        let epoch = state.current_epoch();
-        let committee_lengths = CommitteeLengths::new(state, spec)?;
-        let source = state.current_justified_checkpoint();
-        let target_slot = epoch.start_slot(E::slots_per_epoch());
-        let target = Checkpoint {
-            epoch,
-            root: if state.slot() <= target_slot {
-                beacon_block_root
-            } else {
-                *state.get_block_root(target_slot)?
-            },
-        };
-
         let (_, block, blobs, data_columns) = block.deconstruct();
-        let item = CacheItem {
-            epoch,
-            committee_lengths,
+        let item = CacheItem::new(
             beacon_block_root,
-            source,
-            target,
             block,
             blobs,
             data_columns,
-            proto_block,
-        };
+            Some(proto_block),
+            state,
+            spec,
+        )?;
 
         *self.item.write() = Some(item);
 
         Ok(())
     }
 
+    /// Updates the cache item with a block which has been verified but is still awaiting its blobs
+    /// or data columns, so that `Self::try_attest` will return `Some` for the block's slot.
+    ///
+    /// The block will be replaced by `Self::add_head_block` once it becomes available and is
+    /// imported.
+    pub fn add_availability_pending_block(
+        &self,
+        beacon_block_root: Hash256,
+        block: Arc<SignedBeaconBlock<E>>,
+        state: &BeaconState<E>,
+        spec: &ChainSpec,
+    ) -> Result<(), Error> {
+        let item = CacheItem::new(beacon_block_root, block, None, None, None, state, spec)?;
+
+        let mut lock = self.item.write();
+        // Never replace an available block with a pending block from the same or a later slot.
+        if lock.as_ref().map_or(false, |existing| {
+            !existing.is_availability_pending() && existing.block.slot() >= item.block.slot()
+        }) {
+            return Ok(());
+        }
+        *lock = Some(item);
+
+        metrics::inc_counter(&metrics::BEACON_EARLY_ATTESTER_CACHE_PENDING_BLOCKS);
+
+        Ok(())
+    }
+
     /// Will return `Some(attestation)` if all the following conditions are met:
     ///
     /// - There is a cache `item` present.
@@ -113,6 +175,12 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
             return Ok(None);
         }
 
+        // If the blobs for a pending block have not arrived by the end of its slot then it is not
+        // safe to keep attesting to it.
+        if item.is_availability_pending() && request_slot != item.block.slot() {
+            return Ok(None);
+        }
+
         let committee_count = item
             .committee_lengths
             .get_committee_count_per_slot::<E>(spec)?;
@@ -140,23 +208,31 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
         Ok(Some(attestation))
     }
 
-    /// Returns `true` if `block_root` matches the cached item.
+    /// Returns `true` if `block_root` matches the cached item and is available.
     pub fn contains_block(&self, block_root: Hash256) -> bool {
-        self.item
-            .read()
-            .as_ref()
-            .map_or(false, |item| item.beacon_block_root == block_root)
+        self.item.read().as_ref().map_or(false, |item| {
+            item.beacon_block_root == block_root && !item.is_availability_pending()
+        })
     }
 
-    /// Returns the block, if `block_root` matches the cached item.
+    /// Returns the block, if `block_root` matches the cached item and is available.
     pub fn get_block(&self, block_root: Hash256) -> Option<Arc<SignedBeaconBlock<E>>> {
         self.item
             .read()
             .as_ref()
             .filter(|item| item.beacon_block_root == block_root)
+            .filter(|item| !item.is_availability_pending())
             .map(|item| item.block.clone())
     }
 
+    /// Returns `true` if `block_root` matches the cached item and is awaiting its blobs or data
+    /// columns.
+    pub fn contains_availability_pending_block(&self, block_root: Hash256) -> bool {
+        self.item.read().as_ref().map_or(false, |item| {
+            item.beacon_block_root == block_root && item.is_availability_pending()
+        })
+    }
+
     /// Returns the blobs, if `block_root` matches the cached item.
     pub fn get_blobs(&self, block_root: Hash256) -> Option<BlobSidecarList<E>> {
         self.item
@@ -181,6 +257,6 @@ impl<E: EthSpec> EarlyAttesterCache<E> {
             .read()
             .as_ref()
             .filter(|item| item.beacon_block_root == block_root)
-            .map(|item| item.proto_block.clone())
+            .and_then(|item| item.proto_block.clone())
     }
 }
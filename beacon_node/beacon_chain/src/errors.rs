@@ -226,6 +226,8 @@ pub enum BeaconChainError {
     EmptyRpcCustodyColumns,
     AttestationError(AttestationError),
     AttestationCommitteeIndexNotSet,
+    /// Another validator registry tree was being built, see `ValidatorProofCache`.
+    ValidatorProofTreeBusy,
 }
 
 easy_from_to!(SlotProcessingError, BeaconChainError);
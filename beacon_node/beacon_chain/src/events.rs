@@ -1,11 +1,105 @@
+use eth2::types::EventTopic;
 pub use eth2::types::{EventKind, SseBlock, SseFinalizedCheckpoint, SseHead};
+use parking_lot::Mutex;
 use slog::{trace, Logger};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::{error::SendError, Receiver, Sender};
 use types::EthSpec;
 
 const DEFAULT_CHANNEL_CAPACITY: usize = 16;
 
+/// The maximum number of events of each topic retained for replay, regardless of the replay window.
+const MAX_REPLAY_EVENTS: usize = 4_096;
+
+/// Topics whose events are retained for replay to clients which reconnect with `Last-Event-ID`.
+pub const REPLAYABLE_TOPICS: [EventTopic; 4] = [
+    EventTopic::Head,
+    EventTopic::FinalizedCheckpoint,
+    EventTopic::ChainReorg,
+    EventTopic::BlobSidecar,
+];
+
+/// Returns the topic of `kind` if it is one of the `REPLAYABLE_TOPICS`.
+fn replayable_topic<E: EthSpec>(kind: &EventKind<E>) -> Option<EventTopic> {
+    match kind {
+        EventKind::Head(_) => Some(EventTopic::Head),
+        EventKind::FinalizedCheckpoint(_) => Some(EventTopic::FinalizedCheckpoint),
+        EventKind::ChainReorg(_) => Some(EventTopic::ChainReorg),
+        EventKind::BlobSidecar(_) => Some(EventTopic::BlobSidecar),
+        _ => None,
+    }
+}
+
+/// Returns the first event ID to use, seeded with the time the node started.
+///
+/// IDs issued before a restart are therefore lower than any ID issued after it, so a client which
+/// reconnects to a restarted node is sent all of the retained events rather than skipping events
+/// whose IDs happen to be reused.
+fn event_id_seed() -> u64 {
+    let boot_nonce = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs());
+    boot_nonce << 32
+}
+
+/// An event of one of the `REPLAYABLE_TOPICS`, tagged with a unique and increasing ID.
+#[derive(Debug, Clone)]
+pub struct ReplayableEvent<E: EthSpec> {
+    pub id: u64,
+    pub event: EventKind<E>,
+}
+
+/// A buffer of recent events of a single replayable topic.
+struct EventReplayBuffer<E: EthSpec> {
+    window: Duration,
+    events: VecDeque<(Instant, ReplayableEvent<E>)>,
+}
+
+impl<E: EthSpec> EventReplayBuffer<E> {
+    /// Drop events which are older than the replay window.
+    fn prune(&mut self, now: Instant) {
+        while self
+            .events
+            .front()
+            .is_some_and(|(time, _)| now.duration_since(*time) > self.window)
+        {
+            self.events.pop_front();
+        }
+    }
+
+    fn push(&mut self, event: ReplayableEvent<E>) {
+        let now = Instant::now();
+        self.prune(now);
+        while self.events.len() >= MAX_REPLAY_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back((now, event));
+    }
+}
+
+/// The retained events and live subscribers of a single replayable topic.
+struct ReplayTopic<E: EthSpec> {
+    topic: EventTopic,
+    tx: Sender<ReplayableEvent<E>>,
+    buffer: Mutex<EventReplayBuffer<E>>,
+}
+
+/// Retains events of the `REPLAYABLE_TOPICS`, with a separate buffer per topic so that a busy
+/// topic can't evict the events of a quiet one.
+struct EventReplay<E: EthSpec> {
+    next_id: AtomicU64,
+    topics: Vec<ReplayTopic<E>>,
+}
+
+impl<E: EthSpec> EventReplay<E> {
+    fn topic(&self, topic: &EventTopic) -> Option<&ReplayTopic<E>> {
+        self.topics.iter().find(|replay| replay.topic == *topic)
+    }
+}
+
 pub struct ServerSentEventHandler<E: EthSpec> {
     attestation_tx: Sender<EventKind<E>>,
     block_tx: Sender<EventKind<E>>,
@@ -24,7 +118,12 @@ pub struct ServerSentEventHandler<E: EthSpec> {
     attester_slashing_tx: Sender<EventKind<E>>,
     bls_to_execution_change_tx: Sender<EventKind<E>>,
     block_gossip_tx: Sender<EventKind<E>>,
+    block_production_tx: Sender<EventKind<E>>,
+    optimistic_transition_tx: Sender<EventKind<E>>,
+    slasher_alert_tx: Sender<EventKind<E>>,
+    replay: Option<EventReplay<E>>,
     log: Logger,
+    capacity: usize,
 }
 
 impl<E: EthSpec> ServerSentEventHandler<E> {
@@ -53,6 +152,9 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         let (attester_slashing_tx, _) = broadcast::channel(capacity);
         let (bls_to_execution_change_tx, _) = broadcast::channel(capacity);
         let (block_gossip_tx, _) = broadcast::channel(capacity);
+        let (block_production_tx, _) = broadcast::channel(capacity);
+        let (optimistic_transition_tx, _) = broadcast::channel(capacity);
+        let (slasher_alert_tx, _) = broadcast::channel(capacity);
 
         Self {
             attestation_tx,
@@ -72,11 +174,55 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
             attester_slashing_tx,
             bls_to_execution_change_tx,
             block_gossip_tx,
+            block_production_tx,
+            optimistic_transition_tx,
+            slasher_alert_tx,
+            replay: None,
             log,
+            capacity,
         }
     }
 
+    /// Retain events of the `REPLAYABLE_TOPICS` for `window`, so that they can be replayed to
+    /// clients which reconnect.
+    ///
+    /// Replayable events are retained even when there are no subscribers.
+    pub fn with_replay_window(mut self, window: Duration) -> Self {
+        let topics = REPLAYABLE_TOPICS
+            .iter()
+            .map(|topic| ReplayTopic {
+                topic: *topic,
+                tx: broadcast::channel(self.capacity).0,
+                buffer: Mutex::new(EventReplayBuffer {
+                    window,
+                    events: VecDeque::new(),
+                }),
+            })
+            .collect();
+        self.replay = Some(EventReplay {
+            next_id: AtomicU64::new(event_id_seed()),
+            topics,
+        });
+        self
+    }
+
     pub fn register(&self, kind: EventKind<E>) {
+        let replay = self.replay.as_ref().and_then(|replay| {
+            let replay_topic = replay.topic(&replayable_topic(&kind)?)?;
+            Some((replay, replay_topic))
+        });
+        if let Some((replay, replay_topic)) = replay {
+            // Hold the lock while assigning the ID and sending so that the events of each topic
+            // are sent in ID order.
+            let mut buffer = replay_topic.buffer.lock();
+            let event = ReplayableEvent {
+                id: replay.next_id.fetch_add(1, Ordering::Relaxed),
+                event: kind.clone(),
+            };
+            buffer.push(event.clone());
+            let _ = replay_topic.tx.send(event);
+        }
+
         let log_count = |name, count| {
             trace!(
                 self.log,
@@ -154,12 +300,50 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
                 .block_gossip_tx
                 .send(kind)
                 .map(|count| log_count("block gossip", count)),
+            EventKind::BlockProduction(_) => self
+                .block_production_tx
+                .send(kind)
+                .map(|count| log_count("block production", count)),
+            EventKind::OptimisticTransition(_) => self
+                .optimistic_transition_tx
+                .send(kind)
+                .map(|count| log_count("optimistic transition", count)),
+            EventKind::SlasherAlert(_) => self
+                .slasher_alert_tx
+                .send(kind)
+                .map(|count| log_count("slasher alert", count)),
         };
         if let Err(SendError(event)) = result {
             trace!(self.log, "No receivers registered to listen for event"; "event" => ?event);
         }
     }
 
+    pub fn replay_enabled(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    /// Subscribe to the events of `topic`, tagged with their IDs.
+    ///
+    /// Returns `None` unless replay is enabled and `topic` is one of the `REPLAYABLE_TOPICS`.
+    pub fn subscribe_replayable(&self, topic: &EventTopic) -> Option<Receiver<ReplayableEvent<E>>> {
+        Some(self.replay.as_ref()?.topic(topic)?.tx.subscribe())
+    }
+
+    /// Returns the retained events of `topic` with IDs greater than `last_event_id`, in ID order.
+    pub fn replay_since(&self, topic: &EventTopic, last_event_id: u64) -> Vec<ReplayableEvent<E>> {
+        let Some(replay_topic) = self.replay.as_ref().and_then(|replay| replay.topic(topic)) else {
+            return vec![];
+        };
+        let mut buffer = replay_topic.buffer.lock();
+        buffer.prune(Instant::now());
+        buffer
+            .events
+            .iter()
+            .filter(|(_, event)| event.id > last_event_id)
+            .map(|(_, event)| event.clone())
+            .collect()
+    }
+
     pub fn subscribe_attestation(&self) -> Receiver<EventKind<E>> {
         self.attestation_tx.subscribe()
     }
@@ -228,6 +412,18 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
         self.block_gossip_tx.subscribe()
     }
 
+    pub fn subscribe_block_production(&self) -> Receiver<EventKind<E>> {
+        self.block_production_tx.subscribe()
+    }
+
+    pub fn subscribe_optimistic_transition(&self) -> Receiver<EventKind<E>> {
+        self.optimistic_transition_tx.subscribe()
+    }
+
+    pub fn subscribe_slasher_alert(&self) -> Receiver<EventKind<E>> {
+        self.slasher_alert_tx.subscribe()
+    }
+
     pub fn has_attestation_subscribers(&self) -> bool {
         self.attestation_tx.receiver_count() > 0
     }
@@ -237,15 +433,15 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     }
 
     pub fn has_blob_sidecar_subscribers(&self) -> bool {
-        self.blob_sidecar_tx.receiver_count() > 0
+        self.blob_sidecar_tx.receiver_count() > 0 || self.replay_enabled()
     }
 
     pub fn has_finalized_subscribers(&self) -> bool {
-        self.finalized_tx.receiver_count() > 0
+        self.finalized_tx.receiver_count() > 0 || self.replay_enabled()
     }
 
     pub fn has_head_subscribers(&self) -> bool {
-        self.head_tx.receiver_count() > 0
+        self.head_tx.receiver_count() > 0 || self.replay_enabled()
     }
 
     pub fn has_exit_subscribers(&self) -> bool {
@@ -253,7 +449,7 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     }
 
     pub fn has_reorg_subscribers(&self) -> bool {
-        self.chain_reorg_tx.receiver_count() > 0
+        self.chain_reorg_tx.receiver_count() > 0 || self.replay_enabled()
     }
 
     pub fn has_contribution_subscribers(&self) -> bool {
@@ -287,4 +483,16 @@ impl<E: EthSpec> ServerSentEventHandler<E> {
     pub fn has_block_gossip_subscribers(&self) -> bool {
         self.block_gossip_tx.receiver_count() > 0
     }
+
+    pub fn has_block_production_subscribers(&self) -> bool {
+        self.block_production_tx.receiver_count() > 0
+    }
+
+    pub fn has_optimistic_transition_subscribers(&self) -> bool {
+        self.optimistic_transition_tx.receiver_count() > 0
+    }
+
+    pub fn has_slasher_alert_subscribers(&self) -> bool {
+        self.slasher_alert_tx.receiver_count() > 0
+    }
 }
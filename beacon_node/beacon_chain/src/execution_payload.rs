@@ -8,13 +8,14 @@
 //! here for good reason.
 
 use crate::otb_verification_service::OptimisticTransitionBlock;
+use crate::payload_forensics::write_forensic_bundle;
 use crate::{
     BeaconChain, BeaconChainError, BeaconChainTypes, BlockError, BlockProductionError,
     ExecutionPayloadError,
 };
 use execution_layer::{
-    BlockProposalContents, BlockProposalContentsType, BuilderParams, NewPayloadRequest,
-    PayloadAttributes, PayloadStatus,
+    recorder::capture_exchanges, BlockProposalContents, BlockProposalContentsType, BuilderParams,
+    NewPayloadRequest, PayloadAttributes, PayloadStatus,
 };
 use fork_choice::{InvalidationOperation, PayloadVerificationStatus};
 use proto_array::{Block as ProtoBlock, ExecutionStatus};
@@ -114,7 +115,7 @@ impl<T: BeaconChainTypes> PayloadNotifier<T> {
         if let Some(precomputed_status) = self.payload_verification_status {
             Ok(precomputed_status)
         } else {
-            notify_new_payload(&self.chain, self.block.message()).await
+            notify_new_payload(&self.chain, &self.block).await
         }
     }
 }
@@ -128,17 +129,33 @@ impl<T: BeaconChainTypes> PayloadNotifier<T> {
 /// contains a few extra checks by running `partially_verify_execution_payload` first:
 ///
 /// https://github.com/ethereum/consensus-specs/blob/v1.1.9/specs/bellatrix/beacon-chain.md#notify_new_payload
-async fn notify_new_payload<'a, T: BeaconChainTypes>(
+async fn notify_new_payload<T: BeaconChainTypes>(
     chain: &Arc<BeaconChain<T>>,
-    block: BeaconBlockRef<'a, T::EthSpec>,
+    signed_block: &Arc<SignedBeaconBlock<T::EthSpec>>,
 ) -> Result<PayloadVerificationStatus, BlockError> {
-    let execution_layer = chain
-        .execution_layer
-        .as_ref()
-        .ok_or(ExecutionPayloadError::NoExecutionConnection)?;
+    let block = signed_block.message();
+    let Some(execution_layer) = chain.execution_layer.as_ref() else {
+        // In light mode the trusted node is only consulted about the head, after import, so that
+        // block import never waits on it.
+        if chain.light_mode.is_some() {
+            return Ok(PayloadVerificationStatus::Optimistic);
+        }
+        return Err(ExecutionPayloadError::NoExecutionConnection.into());
+    };
 
     let execution_block_hash = block.execution_payload()?.block_hash();
-    let new_payload_response = execution_layer.notify_new_payload(block.try_into()?).await;
+    let new_payload_request: NewPayloadRequest<T::EthSpec> = block.try_into()?;
+    // Capture the exchange with the engine for the forensic bundle of an invalid payload.
+    let (new_payload_response, engine_exchange) = if chain.config.payload_forensics_dir.is_some() {
+        capture_exchanges(execution_layer.notify_new_payload(new_payload_request)).await
+    } else {
+        (
+            execution_layer
+                .notify_new_payload(new_payload_request)
+                .await,
+            vec![],
+        )
+    };
 
     match new_payload_response {
         Ok(status) => match status {
@@ -163,6 +180,8 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                     "method" => "new_payload",
                 );
 
+                write_forensic_bundle(chain, signed_block.clone(), engine_exchange);
+
                 // Only trigger payload invalidation in fork choice if the
                 // `latest_valid_hash` is `Some` and non-zero.
                 //
@@ -209,6 +228,8 @@ async fn notify_new_payload<'a, T: BeaconChainTypes>(
                     "method" => "new_payload",
                 );
 
+                write_forensic_bundle(chain, signed_block.clone(), engine_exchange);
+
                 // Returning an error here should be sufficient to invalidate the block. We have no
                 // information to indicate its parent is invalid, so no need to run
                 // `BeaconChain::process_invalid_execution_payload`.
@@ -260,15 +281,17 @@ pub async fn validate_merge_block<'a, T: BeaconChainTypes>(
         return Ok(());
     }
 
-    let execution_layer = chain
-        .execution_layer
-        .as_ref()
-        .ok_or(ExecutionPayloadError::NoExecutionConnection)?;
-
-    let is_valid_terminal_pow_block = execution_layer
-        .is_valid_terminal_pow_block_hash(execution_payload.parent_hash(), spec)
-        .await
-        .map_err(ExecutionPayloadError::from)?;
+    let is_valid_terminal_pow_block = if let Some(execution_layer) = &chain.execution_layer {
+        execution_layer
+            .is_valid_terminal_pow_block_hash(execution_payload.parent_hash(), spec)
+            .await
+            .map_err(ExecutionPayloadError::from)?
+    } else if chain.light_mode.is_some() {
+        // The terminal block can't be checked without an execution engine.
+        None
+    } else {
+        return Err(ExecutionPayloadError::NoExecutionConnection.into());
+    };
 
     match is_valid_terminal_pow_block {
         Some(true) => Ok(()),
@@ -346,6 +369,47 @@ pub fn validate_execution_payload_for_gossip<T: BeaconChainTypes>(
     Ok(())
 }
 
+/// Returns block contents with a placeholder execution payload, for producing a block on `state`
+/// without requesting a payload from the execution layer.
+///
+/// The payload satisfies the consensus checks made during block processing, but it is not a valid
+/// execution block, so a block containing it must never be published.
+pub fn placeholder_block_contents<E: EthSpec>(
+    state: &BeaconState<E>,
+    spec: &ChainSpec,
+) -> Result<BlockProposalContents<E, FullPayload<E>>, BlockProductionError> {
+    let fork = state.fork_name_unchecked();
+    let mut payload = FullPayload::<E>::default_at_fork(fork)?.execution_payload();
+
+    // Prior to the merge a default payload is valid.
+    if is_merge_transition_complete(state) {
+        *payload.parent_hash_mut() = state.latest_execution_payload_header()?.block_hash();
+        *payload.prev_randao_mut() = *state.get_randao_mix(state.current_epoch())?;
+        *payload.timestamp_mut() =
+            compute_timestamp_at_slot(state, state.slot(), spec).map_err(BeaconStateError::from)?;
+        if let Ok(withdrawals) = payload.withdrawals_mut() {
+            *withdrawals = get_expected_withdrawals(state, spec)?.0;
+        }
+    }
+
+    let payload = FullPayload::from(payload);
+    let block_value = Uint256::ZERO;
+    if fork.deneb_enabled() {
+        Ok(BlockProposalContents::PayloadAndBlobs {
+            payload,
+            block_value,
+            kzg_commitments: <_>::default(),
+            blobs_and_proofs: None,
+            requests: fork.electra_enabled().then(ExecutionRequests::default),
+        })
+    } else {
+        Ok(BlockProposalContents::Payload {
+            payload,
+            block_value,
+        })
+    }
+}
+
 /// Gets an execution payload for inclusion in a block.
 ///
 /// ## Errors
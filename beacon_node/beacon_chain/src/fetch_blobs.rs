@@ -7,6 +7,10 @@
 //! on P2P gossip to the network. From PeerDAS onwards, together with the increase in blob count,
 //! broadcasting blobs requires a much higher bandwidth, and is only done by high capacity
 //! supernodes.
+//!
+//! Prior to PeerDAS, only the blobs which are not yet in the data availability checker are
+//! requested from the EL, so that a block which is missing only some of its blobs (e.g., because
+//! they were not received on gossip) can be made available without requesting them from peers.
 use crate::blob_verification::{GossipBlobError, GossipVerifiedBlob};
 use crate::kzg_utils::blobs_to_data_column_sidecars;
 use crate::observed_data_sidecars::DoNotObserve;
@@ -22,7 +26,7 @@ use tokio::sync::mpsc::Receiver;
 use types::blob_sidecar::{BlobSidecarError, FixedBlobSidecarList};
 use types::{
     BeaconStateError, BlobSidecar, DataColumnSidecar, DataColumnSidecarList, EthSpec, FullPayload,
-    Hash256, SignedBeaconBlock, SignedBeaconBlockHeader,
+    Hash256, SignedBeaconBlock, SignedBeaconBlockHeader, VersionedHash,
 };
 
 pub enum BlobsOrDataColumns<T: BeaconChainTypes> {
@@ -65,6 +69,7 @@ pub async fn fetch_and_process_engine_blobs<T: BeaconChainTypes>(
         kzg_commitments
             .iter()
             .map(kzg_commitment_to_versioned_hash)
+            .enumerate()
             .collect::<Vec<_>>()
     } else {
         debug!(
@@ -74,6 +79,29 @@ pub async fn fetch_and_process_engine_blobs<T: BeaconChainTypes>(
         return Ok(None);
     };
 
+    let peer_das_enabled = chain.spec.is_peer_das_enabled_for_epoch(block.epoch());
+
+    // Data columns can only be computed from the full set of blobs, whereas blobs are imported
+    // individually so there is no need to fetch those that are already cached.
+    let cached_blob_indexes = if peer_das_enabled {
+        vec![]
+    } else {
+        chain
+            .data_availability_checker
+            .cached_blob_indexes(&block_root)
+            .unwrap_or_default()
+    };
+    let (indices, versioned_hashes) =
+        versioned_hashes_to_fetch(versioned_hashes, &cached_blob_indexes);
+
+    if versioned_hashes.is_empty() {
+        debug!(
+            log,
+            "Fetch blobs not triggered - all blobs already received";
+        );
+        return Ok(None);
+    }
+
     let num_expected_blobs = versioned_hashes.len();
 
     let execution_layer = chain
@@ -109,7 +137,7 @@ pub async fn fetch_and_process_engine_blobs<T: BeaconChainTypes>(
 
     let fixed_blob_sidecar_list = build_blob_sidecars(
         &block,
-        response,
+        indices.into_iter().zip(response).collect(),
         signed_block_header,
         &kzg_commitments_proof,
     )?;
@@ -147,8 +175,6 @@ pub async fn fetch_and_process_engine_blobs<T: BeaconChainTypes>(
         .collect::<Result<Vec<_>, _>>()
         .map_err(FetchEngineBlobError::GossipBlob)?;
 
-    let peer_das_enabled = chain.spec.is_peer_das_enabled_for_epoch(block.epoch());
-
     let data_columns_receiver_opt = if peer_das_enabled {
         // Partial blobs response isn't useful for PeerDAS, so we don't bother building and publishing data columns.
         if num_fetched_blobs != num_expected_blobs {
@@ -270,16 +296,28 @@ fn spawn_compute_and_publish_data_columns_task<T: BeaconChainTypes>(
     data_columns_receiver
 }
 
+/// Splits the `versioned_hashes` of a block's blobs, paired with their indices, into the indices
+/// and versioned hashes of the blobs which aren't at one of the `cached_blob_indexes`.
+fn versioned_hashes_to_fetch(
+    versioned_hashes: Vec<(usize, VersionedHash)>,
+    cached_blob_indexes: &[u64],
+) -> (Vec<usize>, Vec<VersionedHash>) {
+    versioned_hashes
+        .into_iter()
+        .filter(|(index, _)| !cached_blob_indexes.contains(&(*index as u64)))
+        .unzip()
+}
+
+/// Builds the sidecars for the blobs returned by the EL, each paired with its index in the block.
 fn build_blob_sidecars<E: EthSpec>(
     block: &Arc<SignedBeaconBlock<E, FullPayload<E>>>,
-    response: Vec<Option<BlobAndProofV1<E>>>,
+    response: Vec<(usize, Option<BlobAndProofV1<E>>)>,
     signed_block_header: SignedBeaconBlockHeader,
     kzg_commitments_inclusion_proof: &FixedVector<Hash256, E::KzgCommitmentsInclusionProofDepth>,
 ) -> Result<FixedBlobSidecarList<E>, FetchEngineBlobError> {
     let mut fixed_blob_sidecar_list = FixedBlobSidecarList::default();
     for (index, blob_and_proof) in response
         .into_iter()
-        .enumerate()
         .filter_map(|(i, opt_blob)| Some((i, opt_blob?)))
     {
         match BlobSidecar::new_with_existing_proof(
@@ -306,3 +344,70 @@ fn build_blob_sidecars<E: EthSpec>(
     }
     Ok(fixed_blob_sidecar_list)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{generate_rand_block_and_blobs, NumBlobs};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use types::{ForkName, MainnetEthSpec};
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn cached_blobs_are_not_fetched() {
+        let versioned_hashes = (0..4)
+            .map(|index| (index, Hash256::repeat_byte(index as u8)))
+            .collect::<Vec<_>>();
+
+        let (indices, hashes) = versioned_hashes_to_fetch(versioned_hashes.clone(), &[0, 2]);
+        assert_eq!(indices, vec![1, 3]);
+        assert_eq!(
+            hashes,
+            vec![Hash256::repeat_byte(1), Hash256::repeat_byte(3)]
+        );
+
+        let (indices, hashes) = versioned_hashes_to_fetch(versioned_hashes.clone(), &[]);
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert_eq!(hashes.len(), 4);
+
+        let (indices, hashes) = versioned_hashes_to_fetch(versioned_hashes, &[0, 1, 2, 3]);
+        assert!(indices.is_empty());
+        assert!(hashes.is_empty());
+    }
+
+    #[test]
+    fn blob_sidecars_keep_their_block_indices() {
+        let mut rng = StdRng::seed_from_u64(0xF37C4B10B5);
+        let (block, blobs) =
+            generate_rand_block_and_blobs::<E>(ForkName::Deneb, NumBlobs::Number(4), &mut rng);
+        let block = Arc::new(block);
+        let (signed_block_header, kzg_commitments_proof) = block
+            .signed_block_header_and_kzg_commitments_proof()
+            .unwrap();
+
+        // Blobs 0 and 2 are cached, the EL only has blob 3.
+        let blob_and_proof = |index: usize| BlobAndProofV1 {
+            blob: blobs[index].blob.clone(),
+            proof: blobs[index].kzg_proof,
+        };
+        let response = vec![(1, None), (3, Some(blob_and_proof(3)))];
+
+        let sidecars = build_blob_sidecars(
+            &block,
+            response,
+            signed_block_header,
+            &kzg_commitments_proof,
+        )
+        .unwrap();
+
+        for (index, sidecar) in sidecars.iter().enumerate() {
+            if index == 3 {
+                assert_eq!(sidecar.as_deref(), Some(&blobs[3]));
+            } else {
+                assert!(sidecar.is_none(), "unexpected sidecar at index {index}");
+            }
+        }
+    }
+}
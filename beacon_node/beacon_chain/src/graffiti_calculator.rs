@@ -1,10 +1,11 @@
+use crate::graffiti_file::{GraffitiContext, GraffitiFile};
 use crate::BeaconChain;
 use crate::BeaconChainTypes;
 use execution_layer::{http::ENGINE_GET_CLIENT_VERSION_V1, CommitPrefix, ExecutionLayer};
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, warn, Logger};
 use slot_clock::SlotClock;
-use std::{fmt::Debug, time::Duration};
+use std::{fmt::Debug, sync::Arc, time::Duration};
 use task_executor::TaskExecutor;
 use types::{EthSpec, Graffiti, GRAFFITI_BYTES_LEN};
 
@@ -49,6 +50,7 @@ impl Debug for GraffitiOrigin {
 
 pub struct GraffitiCalculator<T: BeaconChainTypes> {
     pub beacon_graffiti: GraffitiOrigin,
+    graffiti_file: Option<Arc<GraffitiFile>>,
     execution_layer: Option<ExecutionLayer<T::EthSpec>>,
     pub epoch_duration: Duration,
     log: Logger,
@@ -57,12 +59,14 @@ pub struct GraffitiCalculator<T: BeaconChainTypes> {
 impl<T: BeaconChainTypes> GraffitiCalculator<T> {
     pub fn new(
         beacon_graffiti: GraffitiOrigin,
+        graffiti_file: Option<GraffitiFile>,
         execution_layer: Option<ExecutionLayer<T::EthSpec>>,
         epoch_duration: Duration,
         log: Logger,
     ) -> Self {
         Self {
             beacon_graffiti,
+            graffiti_file: graffiti_file.map(Arc::new),
             execution_layer,
             epoch_duration,
             log,
@@ -72,14 +76,28 @@ impl<T: BeaconChainTypes> GraffitiCalculator<T> {
     /// Returns the appropriate graffiti to use for block production, prioritizing
     /// sources in the following order:
     /// 1. Graffiti specified by the validator client.
-    /// 2. Graffiti specified by the user via beacon node CLI options.
-    /// 3. The EL & CL client version string, applicable when the EL supports version specification.
-    /// 4. The default lighthouse version string, used if the EL lacks version specification support.
-    pub async fn get_graffiti(&self, validator_graffiti: Option<Graffiti>) -> Graffiti {
+    /// 2. Graffiti from the beacon node's graffiti file, for the proposer described by `context`.
+    /// 3. Graffiti specified by the user via beacon node CLI options.
+    /// 4. The EL & CL client version string, applicable when the EL supports version specification.
+    /// 5. The default lighthouse version string, used if the EL lacks version specification support.
+    pub async fn get_graffiti(
+        &self,
+        validator_graffiti: Option<Graffiti>,
+        context: Option<&GraffitiContext>,
+    ) -> Graffiti {
         if let Some(graffiti) = validator_graffiti {
             return graffiti;
         }
 
+        if let Some(graffiti) = self
+            .graffiti_file
+            .as_ref()
+            .zip(context)
+            .and_then(|(graffiti_file, context)| graffiti_file.graffiti(context))
+        {
+            return graffiti;
+        }
+
         match self.beacon_graffiti {
             GraffitiOrigin::UserSpecified(graffiti) => graffiti,
             GraffitiOrigin::Calculated(default_graffiti) => {
@@ -179,6 +197,44 @@ pub fn start_engine_version_cache_refresh_service<T: BeaconChainTypes>(
     );
 }
 
+/// Spawns a service which reloads the graffiti file at the start of every slot if it has changed.
+pub fn start_graffiti_file_reload_service<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    executor: TaskExecutor,
+) {
+    let Some(graffiti_file) = chain.graffiti_calculator.graffiti_file.clone() else {
+        return;
+    };
+
+    let log = chain.log.clone();
+    let slot_clock = chain.slot_clock.clone();
+    let inner_executor = executor.clone();
+    executor.spawn(
+        async move {
+            loop {
+                match slot_clock.duration_to_next_slot() {
+                    Some(duration_to_next_slot) => {
+                        tokio::time::sleep(duration_to_next_slot).await;
+                        let graffiti_file = graffiti_file.clone();
+                        if let Some(handle) = inner_executor.spawn_blocking_handle(
+                            move || graffiti_file.reload_if_modified(),
+                            "graffiti_file_reload",
+                        ) {
+                            let _ = handle.await;
+                        }
+                    }
+                    None => {
+                        error!(log, "Failed to read slot clock");
+                        // If we can't read the slot clock, just wait another slot.
+                        tokio::time::sleep(slot_clock.slot_duration()).await;
+                    }
+                }
+            }
+        },
+        "graffiti_file_reload_service",
+    );
+}
+
 async fn engine_version_cache_refresh_service<T: BeaconChainTypes>(
     execution_layer: ExecutionLayer<T::EthSpec>,
     slot_clock: T::SlotClock,
@@ -298,8 +354,12 @@ mod tests {
             GRAFFITI_BYTES_LEN,
         );
         // grab the slice of the graffiti that corresponds to the lighthouse version
-        let graffiti_slice =
-            &harness.chain.graffiti_calculator.get_graffiti(None).await.0[..version_bytes];
+        let graffiti_slice = &harness
+            .chain
+            .graffiti_calculator
+            .get_graffiti(None, None)
+            .await
+            .0[..version_bytes];
 
         // convert graffiti bytes slice to ascii for easy debugging if this test should fail
         let graffiti_str =
@@ -317,7 +377,12 @@ mod tests {
         let spec = Arc::new(test_spec::<MinimalEthSpec>());
         let harness = get_harness(VALIDATOR_COUNT, spec, None);
 
-        let found_graffiti_bytes = harness.chain.graffiti_calculator.get_graffiti(None).await.0;
+        let found_graffiti_bytes = harness
+            .chain
+            .graffiti_calculator
+            .get_graffiti(None, None)
+            .await
+            .0;
 
         let mock_commit = DEFAULT_CLIENT_VERSION.commit.clone();
         let expected_graffiti_string = format!(
@@ -366,7 +431,7 @@ mod tests {
         let found_graffiti = harness
             .chain
             .graffiti_calculator
-            .get_graffiti(Some(Graffiti::from(graffiti_bytes)))
+            .get_graffiti(Some(Graffiti::from(graffiti_bytes)), None)
             .await;
 
         assert_eq!(
@@ -0,0 +1,341 @@
+//! Per-validator graffiti for block production, loaded from a file which is reloaded whenever it
+//! is modified on disk.
+//!
+//! The file uses the same format as the validator client's graffiti file:
+//!
+//! ```text
+//! default: Lighthouse
+//! 0xa5e7...: graffiti for this validator
+//! ```
+//!
+//! Listing the same public key (or `default`) more than once creates a rotation: successive
+//! proposals by that validator use the messages in turn, in round-robin order. Messages may contain
+//! the placeholders `{slot}`, `{epoch}` and `{timestamp}` (the Unix time of the start of the
+//! proposal slot, in seconds) which are interpolated at proposal time. Interpolated graffiti is
+//! truncated to 32 bytes.
+//!
+//! The modification time of the file is checked once per slot by the graffiti file reload service,
+//! so operators can change their graffiti without restarting the beacon node and block production
+//! never touches the filesystem. If a modified file is missing, empty or fails to parse, a warning
+//! is logged and the previous contents continue to be used.
+use parking_lot::Mutex;
+use slog::{info, warn, Logger};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::SystemTime;
+use types::{Epoch, Graffiti, PublicKeyBytes, Slot, GRAFFITI_BYTES_LEN};
+
+/// Details of a block proposal which are used to select and interpolate graffiti.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraffitiContext {
+    /// The public key of the proposer, if known.
+    pub proposer_pubkey: Option<PublicKeyBytes>,
+    pub slot: Slot,
+    pub epoch: Epoch,
+    /// The Unix time of the start of the slot, in seconds.
+    pub timestamp: Option<u64>,
+}
+
+/// The parsed contents of a graffiti file.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct GraffitiFileContents {
+    graffitis: HashMap<PublicKeyBytes, Vec<String>>,
+    default: Vec<String>,
+}
+
+impl FromStr for GraffitiFileContents {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut contents = Self::default();
+        for line in s.lines().filter(|line| !line.trim().is_empty()) {
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("Missing delimiter: {line}"))?;
+            let message = value.trim().to_string();
+            if message.len() > GRAFFITI_BYTES_LEN {
+                return Err(format!(
+                    "Graffiti exceeds max length of {GRAFFITI_BYTES_LEN} bytes: {message}"
+                ));
+            }
+
+            if key.trim() == "default" {
+                contents.default.push(message);
+            } else {
+                let pubkey = PublicKeyBytes::from_str(key.trim())?;
+                contents.graffitis.entry(pubkey).or_default().push(message);
+            }
+        }
+        // An empty file is most likely one which is part-way through being written.
+        if contents.graffitis.is_empty() && contents.default.is_empty() {
+            return Err("Graffiti file has no entries".to_string());
+        }
+        Ok(contents)
+    }
+}
+
+impl GraffitiFileContents {
+    /// Returns the messages for `pubkey`, falling back to the default messages.
+    fn messages(&self, pubkey: Option<&PublicKeyBytes>) -> Option<&[String]> {
+        pubkey
+            .and_then(|pubkey| self.graffitis.get(pubkey))
+            .or(Some(&self.default))
+            .filter(|messages| !messages.is_empty())
+            .map(Vec::as_slice)
+    }
+}
+
+struct GraffitiFileState {
+    contents: GraffitiFileContents,
+    modified: Option<SystemTime>,
+    /// The number of proposals which have used each rotation, keyed by public key (`None` for the
+    /// default rotation).
+    rotations: HashMap<Option<PublicKeyBytes>, usize>,
+}
+
+pub struct GraffitiFile {
+    path: PathBuf,
+    state: Mutex<GraffitiFileState>,
+    log: Logger,
+}
+
+impl GraffitiFile {
+    /// Load the graffiti file at `path`, returning an error if it can't be read or is invalid.
+    pub fn new(path: PathBuf, log: Logger) -> Result<Self, String> {
+        let modified = Self::modified(&path);
+        let contents = Self::read(&path)?;
+        Ok(Self {
+            path,
+            state: Mutex::new(GraffitiFileState {
+                contents,
+                modified,
+                rotations: HashMap::new(),
+            }),
+            log,
+        })
+    }
+
+    fn modified(path: &PathBuf) -> Option<SystemTime> {
+        std::fs::metadata(path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn read(path: &PathBuf) -> Result<GraffitiFileContents, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| format!("Unable to read graffiti file {}: {e:?}", path.display()))?
+            .parse()
+    }
+
+    /// Reload the file if its modification time has changed since it was last read.
+    ///
+    /// The file is read without holding the lock, so lookups are never blocked on the filesystem.
+    pub fn reload_if_modified(&self) {
+        let modified = Self::modified(&self.path);
+        if modified == self.state.lock().modified {
+            return;
+        }
+
+        let contents = Self::read(&self.path);
+        let mut state = self.state.lock();
+        state.modified = modified;
+        match contents {
+            Ok(contents) => {
+                info!(
+                    self.log,
+                    "Reloaded graffiti file";
+                    "path" => %self.path.display(),
+                    "validators" => contents.graffitis.len(),
+                );
+                state.contents = contents;
+            }
+            Err(e) => warn!(
+                self.log,
+                "Failed to reload graffiti file";
+                "msg" => "continuing to use the previous graffiti",
+                "path" => %self.path.display(),
+                "error" => e,
+            ),
+        }
+    }
+
+    /// Returns the graffiti for the proposal described by `context`, or `None` if the file has no
+    /// entry for the proposer and no default.
+    pub fn graffiti(&self, context: &GraffitiContext) -> Option<Graffiti> {
+        let mut state = self.state.lock();
+        let key = context
+            .proposer_pubkey
+            .filter(|pubkey| state.contents.graffitis.contains_key(pubkey));
+        let messages = state.contents.messages(key.as_ref())?.to_vec();
+
+        let count = state.rotations.entry(key).or_default();
+        let message = &messages[*count % messages.len()];
+        *count = count.wrapping_add(1);
+
+        Some(interpolate(message, context))
+    }
+}
+
+/// Substitute the placeholders in `message` and convert it to graffiti, truncating if necessary.
+fn interpolate(message: &str, context: &GraffitiContext) -> Graffiti {
+    let timestamp = context
+        .timestamp
+        .map(|timestamp| timestamp.to_string())
+        .unwrap_or_default();
+    let message = message
+        .replace("{slot}", &context.slot.to_string())
+        .replace("{epoch}", &context.epoch.to_string())
+        .replace("{timestamp}", &timestamp);
+
+    let mut bytes = [0u8; GRAFFITI_BYTES_LEN];
+    let len = std::cmp::min(message.len(), GRAFFITI_BYTES_LEN);
+    bytes[..len].copy_from_slice(&message.as_bytes()[..len]);
+    Graffiti::from(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    fn pubkey(seed: u64) -> PublicKeyBytes {
+        types::test_utils::generate_deterministic_keypair(seed as usize)
+            .pk
+            .compress()
+    }
+
+    fn context(proposer: Option<PublicKeyBytes>, slot: u64) -> GraffitiContext {
+        GraffitiContext {
+            proposer_pubkey: proposer,
+            slot: Slot::new(slot),
+            epoch: Slot::new(slot).epoch(32),
+            timestamp: Some(1_606_824_023 + slot * 12),
+        }
+    }
+
+    fn graffiti_string(graffiti: Option<Graffiti>) -> String {
+        graffiti.unwrap().as_utf8_lossy()
+    }
+
+    fn write_file(contents: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::new().unwrap();
+        file.write_all(contents.as_bytes()).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn parse_and_rotate() {
+        let file = write_file(&format!(
+            "default: default\n{0}: first\n{0}: second\n\n",
+            pubkey(0)
+        ));
+        let graffiti_file =
+            GraffitiFile::new(file.path().to_path_buf(), logging::test_logger()).unwrap();
+
+        let proposer = Some(pubkey(0));
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(proposer, 1))),
+            "first"
+        );
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(proposer, 2))),
+            "second"
+        );
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(proposer, 3))),
+            "first"
+        );
+
+        // Validators without an entry use the default, as do proposals with an unknown proposer.
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(Some(pubkey(1)), 4))),
+            "default"
+        );
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 5))),
+            "default"
+        );
+    }
+
+    #[test]
+    fn interpolation() {
+        let graffiti = interpolate("s{slot} e{epoch} t{timestamp}", &context(None, 64));
+        assert_eq!(graffiti.as_utf8_lossy(), "s64 e2 t1606824791");
+
+        // Interpolated graffiti is truncated.
+        let graffiti = interpolate("{timestamp}{timestamp}{timestamp}", &context(None, 0));
+        assert_eq!(graffiti.as_utf8_lossy(), "16068240231606824023160682402316");
+    }
+
+    #[test]
+    fn invalid_contents() {
+        assert!("no delimiter".parse::<GraffitiFileContents>().is_err());
+        assert!("\n \n".parse::<GraffitiFileContents>().is_err());
+        assert!("0x1234: bad key".parse::<GraffitiFileContents>().is_err());
+        assert!(format!("default: {}", "a".repeat(33))
+            .parse::<GraffitiFileContents>()
+            .is_err());
+    }
+
+    #[test]
+    fn hot_reload() {
+        let file = write_file("default: before\n");
+        let graffiti_file =
+            GraffitiFile::new(file.path().to_path_buf(), logging::test_logger()).unwrap();
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 1))),
+            "before"
+        );
+
+        // Changes are only picked up on reload, and invalid contents are ignored.
+        std::fs::write(file.path(), "invalid").unwrap();
+        set_modified(file.path(), 1);
+        graffiti_file.reload_if_modified();
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 2))),
+            "before"
+        );
+
+        // Neither is an empty file.
+        std::fs::write(file.path(), "").unwrap();
+        set_modified(file.path(), 2);
+        graffiti_file.reload_if_modified();
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 3))),
+            "before"
+        );
+
+        // Nor a missing file.
+        std::fs::remove_file(file.path()).unwrap();
+        graffiti_file.reload_if_modified();
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 3))),
+            "before"
+        );
+
+        std::fs::write(file.path(), "default: after\n").unwrap();
+        set_modified(file.path(), 3);
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 3))),
+            "before"
+        );
+        graffiti_file.reload_if_modified();
+        assert_eq!(
+            graffiti_string(graffiti_file.graffiti(&context(None, 4))),
+            "after"
+        );
+    }
+
+    /// Set a distinct modification time, since writes in quick succession may share a timestamp.
+    fn set_modified(path: &std::path::Path, seconds: u64) {
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+            .unwrap();
+    }
+}
@@ -0,0 +1,93 @@
+//! Provides the `HeadStatePool`, a small pool of clones of the head state made in advance.
+//!
+//! HTTP queries and duty computations which need a mutable head state, e.g. to advance it to a
+//! later epoch, would otherwise clone the head state on demand. As the head state grows, so does
+//! the cost of cloning it, which is paid while the request waits. The pool is refilled on a
+//! blocking thread whenever the head changes or a clone is taken from it.
+use crate::BeaconSnapshot;
+use parking_lot::Mutex;
+use types::{BeaconState, EthSpec, Hash256};
+
+/// The default number of clones of the head state to keep.
+pub const DEFAULT_HEAD_STATE_POOL_SIZE: usize = 2;
+
+/// Clones of the head state at the block with `head_block_root`.
+struct PooledStates<E: EthSpec> {
+    head_block_root: Hash256,
+    states: Vec<BeaconState<E>>,
+    /// The number of clones being made for the pool, so that concurrent refills don't make more
+    /// clones than necessary.
+    cloning: usize,
+}
+
+pub struct HeadStatePool<E: EthSpec> {
+    size: usize,
+    pooled: Mutex<PooledStates<E>>,
+}
+
+impl<E: EthSpec> HeadStatePool<E> {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            pooled: Mutex::new(PooledStates {
+                head_block_root: Hash256::zero(),
+                states: vec![],
+                cloning: 0,
+            }),
+        }
+    }
+
+    /// Returns `true` if the pool is configured to keep any states.
+    pub fn is_enabled(&self) -> bool {
+        self.size > 0
+    }
+
+    /// Takes a clone of the state in `snapshot` from the pool, if one is available.
+    pub fn take(&self, snapshot: &BeaconSnapshot<E>) -> Option<BeaconState<E>> {
+        let mut pooled = self.pooled.lock();
+        if pooled.head_block_root == snapshot.beacon_block_root {
+            pooled.states.pop()
+        } else {
+            None
+        }
+    }
+
+    /// Clones the state in `snapshot`, which should be the current head, until the pool is full.
+    /// Clones of any other head state are discarded.
+    ///
+    /// States are cloned without holding the lock, so this function can be called on a blocking
+    /// thread without delaying `Self::take`.
+    pub fn refill(&self, snapshot: &BeaconSnapshot<E>) {
+        let head_block_root = snapshot.beacon_block_root;
+        {
+            let mut pooled = self.pooled.lock();
+            if pooled.head_block_root != head_block_root {
+                pooled.head_block_root = head_block_root;
+                pooled.states.clear();
+                pooled.cloning = 0;
+            }
+        }
+
+        loop {
+            {
+                let mut pooled = self.pooled.lock();
+                // Stop if the pool has been refilled for another head in the meantime.
+                if pooled.head_block_root != head_block_root
+                    || pooled.states.len() + pooled.cloning >= self.size
+                {
+                    return;
+                }
+                pooled.cloning += 1;
+            }
+
+            let state = snapshot.beacon_state.clone();
+
+            let mut pooled = self.pooled.lock();
+            if pooled.head_block_root != head_block_root {
+                return;
+            }
+            pooled.cloning -= 1;
+            pooled.states.push(state);
+        }
+    }
+}
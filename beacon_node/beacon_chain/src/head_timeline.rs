@@ -0,0 +1,249 @@
+//! Records, for each recent slot, the events which led to the selection of the head: the arrival
+//! and import of blocks, the application and expiry of proposer boost and changes of head.
+//!
+//! This allows the head which was attested to (or missed) in a slot to be explained after the
+//! fact, via the `/lighthouse/head_timeline/{slot}` HTTP API.
+use crate::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{HeadTimelineEvent, HeadTimelineEventKind};
+use parking_lot::Mutex;
+use slot_clock::SlotClock;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use types::{FixedBytesExtended, Hash256, Slot};
+
+/// The number of slots for which events are retained.
+pub const SLOTS_RETAINED: u64 = 64;
+
+/// Bounds the number of events recorded per slot, e.g., whilst many blocks are imported.
+const MAX_EVENTS_PER_SLOT: usize = 256;
+
+#[derive(Default)]
+struct Inner {
+    slots: BTreeMap<Slot, Vec<HeadTimelineEvent>>,
+    /// The proposer boost root most recently observed in fork choice.
+    proposer_boost_root: Hash256,
+}
+
+/// The events of the last `SLOTS_RETAINED` slots, keyed by the slot during which they occurred.
+#[derive(Default)]
+pub struct HeadTimeline {
+    inner: Mutex<Inner>,
+}
+
+impl HeadTimeline {
+    /// Records an event which happened `delay` after the start of `slot`.
+    pub fn record(&self, slot: Slot, delay: Duration, kind: HeadTimelineEventKind) {
+        let mut inner = self.inner.lock();
+        Self::record_inner(&mut inner, slot, delay, kind);
+    }
+
+    /// Records the application or expiry of proposer boost, if `proposer_boost_root` differs from
+    /// the boost root which was last observed.
+    pub fn observe_proposer_boost(
+        &self,
+        slot: Slot,
+        delay: Duration,
+        proposer_boost_root: Hash256,
+    ) {
+        let mut inner = self.inner.lock();
+        let previous_root = inner.proposer_boost_root;
+        if previous_root == proposer_boost_root {
+            return;
+        }
+        inner.proposer_boost_root = proposer_boost_root;
+
+        if previous_root != Hash256::zero() {
+            Self::record_inner(
+                &mut inner,
+                slot,
+                delay,
+                HeadTimelineEventKind::ProposerBoostExpired {
+                    block_root: previous_root,
+                },
+            );
+        }
+        if proposer_boost_root != Hash256::zero() {
+            Self::record_inner(
+                &mut inner,
+                slot,
+                delay,
+                HeadTimelineEventKind::ProposerBoostApplied {
+                    block_root: proposer_boost_root,
+                },
+            );
+        }
+    }
+
+    /// Returns the events recorded during `slot`, in the order in which they were recorded.
+    pub fn get(&self, slot: Slot) -> Vec<HeadTimelineEvent> {
+        self.inner
+            .lock()
+            .slots
+            .get(&slot)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    fn record_inner(inner: &mut Inner, slot: Slot, delay: Duration, kind: HeadTimelineEventKind) {
+        let oldest_slot = slot.saturating_sub(SLOTS_RETAINED - 1);
+        if inner
+            .slots
+            .keys()
+            .next()
+            .is_some_and(|&first| first < oldest_slot)
+        {
+            inner.slots = inner.slots.split_off(&oldest_slot);
+        }
+
+        let events = inner.slots.entry(slot).or_default();
+        if events.len() >= MAX_EVENTS_PER_SLOT {
+            return;
+        }
+        // Blocks can be observed several times (e.g., on gossip and when processed), only the
+        // first observation is of interest.
+        if let HeadTimelineEventKind::BlockObserved { block_root, .. } = &kind {
+            if events.iter().any(|event| {
+                matches!(
+                    &event.kind,
+                    HeadTimelineEventKind::BlockObserved { block_root: root, .. } if root == block_root
+                )
+            }) {
+                return;
+            }
+        }
+        events.push(HeadTimelineEvent {
+            delay_ms: delay.as_millis() as u64,
+            kind,
+        });
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Records an event in the head timeline at the current time.
+    pub fn record_head_timeline_event(&self, kind: HeadTimelineEventKind) {
+        if let Some(now) = self.slot_clock.now_duration() {
+            self.record_head_timeline_event_at(now, kind);
+        }
+    }
+
+    /// Records an event in the head timeline which happened at `timestamp` (the duration since
+    /// the UNIX epoch).
+    ///
+    /// Blocks are only recorded if they are for the current or previous slot, so that the timeline
+    /// isn't flooded during sync.
+    pub fn record_head_timeline_event_at(&self, timestamp: Duration, kind: HeadTimelineEventKind) {
+        let Some((slot, delay)) = self.head_timeline_position(timestamp) else {
+            return;
+        };
+        if let HeadTimelineEventKind::BlockObserved {
+            slot: block_slot, ..
+        }
+        | HeadTimelineEventKind::BlockImported {
+            slot: block_slot, ..
+        } = &kind
+        {
+            if *block_slot + 1 < slot {
+                return;
+            }
+        }
+        self.head_timeline.record(slot, delay, kind);
+    }
+
+    /// Records the application or expiry of proposer boost, if the `proposer_boost_root` of fork
+    /// choice has changed.
+    pub(crate) fn observe_head_timeline_proposer_boost(&self, proposer_boost_root: Hash256) {
+        if let Some((slot, delay)) = self
+            .slot_clock
+            .now_duration()
+            .and_then(|now| self.head_timeline_position(now))
+        {
+            self.head_timeline
+                .observe_proposer_boost(slot, delay, proposer_boost_root);
+        }
+    }
+
+    /// Returns the head timeline of `slot`, or `None` if `slot` is not one of the last
+    /// `SLOTS_RETAINED` slots.
+    pub fn head_timeline(&self, slot: Slot) -> Option<Vec<HeadTimelineEvent>> {
+        let current_slot = self.slot_clock.now()?;
+        if slot > current_slot || slot + SLOTS_RETAINED <= current_slot {
+            return None;
+        }
+        Some(self.head_timeline.get(slot))
+    }
+
+    fn head_timeline_position(&self, timestamp: Duration) -> Option<(Slot, Duration)> {
+        let slot = self.slot_clock.slot_of(timestamp)?;
+        let delay = timestamp.saturating_sub(self.slot_clock.start_of(slot)?);
+        Some((slot, delay))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observed(block_root: Hash256) -> HeadTimelineEventKind {
+        HeadTimelineEventKind::BlockObserved {
+            block_root,
+            slot: Slot::new(1),
+        }
+    }
+
+    #[test]
+    fn records_and_prunes() {
+        let timeline = HeadTimeline::default();
+        let root = Hash256::repeat_byte(1);
+
+        timeline.record(Slot::new(1), Duration::from_millis(500), observed(root));
+        timeline.record(Slot::new(1), Duration::from_millis(600), observed(root));
+        assert_eq!(
+            timeline.get(Slot::new(1)),
+            vec![HeadTimelineEvent {
+                delay_ms: 500,
+                kind: observed(root),
+            }]
+        );
+        assert!(timeline.get(Slot::new(2)).is_empty());
+
+        timeline.record(
+            Slot::new(SLOTS_RETAINED),
+            Duration::ZERO,
+            observed(Hash256::from_low_u64_be(2)),
+        );
+        assert_eq!(timeline.get(Slot::new(1)).len(), 1);
+
+        timeline.record(
+            Slot::new(SLOTS_RETAINED + 1),
+            Duration::ZERO,
+            observed(Hash256::from_low_u64_be(3)),
+        );
+        assert!(timeline.get(Slot::new(1)).is_empty());
+    }
+
+    #[test]
+    fn observes_proposer_boost() {
+        let timeline = HeadTimeline::default();
+        let slot = Slot::new(1);
+        let root = Hash256::repeat_byte(1);
+
+        timeline.observe_proposer_boost(slot, Duration::from_secs(1), root);
+        timeline.observe_proposer_boost(slot, Duration::from_secs(2), root);
+        timeline.observe_proposer_boost(slot + 1, Duration::ZERO, Hash256::zero());
+
+        assert_eq!(
+            timeline.get(slot),
+            vec![HeadTimelineEvent {
+                delay_ms: 1_000,
+                kind: HeadTimelineEventKind::ProposerBoostApplied { block_root: root },
+            }]
+        );
+        assert_eq!(
+            timeline.get(slot + 1),
+            vec![HeadTimelineEvent {
+                delay_ms: 0,
+                kind: HeadTimelineEventKind::ProposerBoostExpired { block_root: root },
+            }]
+        );
+    }
+}
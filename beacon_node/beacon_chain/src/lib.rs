@@ -1,3 +1,4 @@
+mod attestation_data_cache;
 pub mod attestation_rewards;
 pub mod attestation_simulator;
 pub mod attestation_verification;
@@ -10,6 +11,7 @@ pub mod beacon_proposer_cache;
 mod beacon_snapshot;
 pub mod bellatrix_readiness;
 pub mod blob_verification;
+pub mod block_production_budget;
 pub mod block_reward;
 mod block_times_cache;
 mod block_verification;
@@ -18,6 +20,7 @@ pub mod builder;
 pub mod canonical_head;
 pub mod capella_readiness;
 pub mod chain_config;
+pub mod column_size_estimator;
 pub mod data_availability_checker;
 pub mod data_column_verification;
 pub mod deneb_readiness;
@@ -32,12 +35,16 @@ pub mod fetch_blobs;
 pub mod fork_choice_signal;
 pub mod fork_revert;
 pub mod graffiti_calculator;
+pub mod graffiti_file;
+pub mod head_state_pool;
+pub mod head_timeline;
 mod head_tracker;
 pub mod historical_blocks;
 pub mod kzg_utils;
 pub mod light_client_finality_update_verification;
 pub mod light_client_optimistic_update_verification;
 mod light_client_server_cache;
+pub mod light_mode;
 pub mod metrics;
 pub mod migrate;
 mod naive_aggregation_pool;
@@ -47,25 +54,34 @@ pub mod observed_block_producers;
 pub mod observed_data_sidecars;
 pub mod observed_operations;
 mod observed_slashable;
+pub mod optimistic_status;
 pub mod otb_verification_service;
+pub mod own_key_guard;
+pub mod payload_attributes_policy;
+pub mod payload_forensics;
 mod persisted_beacon_chain;
 mod persisted_fork_choice;
+mod persisted_head_state;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
+pub mod re_org_policy;
 pub mod schema_change;
 pub mod shuffling_cache;
+pub mod shuffling_cache_warmer;
 pub mod state_advance_timer;
 pub mod sync_committee_rewards;
 pub mod sync_committee_verification;
 pub mod test_utils;
 pub mod validator_monitor;
+pub mod validator_proof_cache;
 pub mod validator_pubkey_cache;
+pub mod weak_subjectivity;
 
 pub use self::beacon_chain::{
     AttestationProcessingOutcome, AvailabilityProcessingStatus, BeaconBlockResponse,
     BeaconBlockResponseWrapper, BeaconChain, BeaconChainTypes, BeaconStore, BlockProcessStatus,
-    ChainSegmentResult, ForkChoiceError, LightClientProducerEvent, OverrideForkchoiceUpdate,
-    ProduceBlockVerification, StateSkipConfig, WhenSlotSkipped,
+    ChainSegmentResult, DryRunBlock, ForkChoiceError, LightClientProducerEvent,
+    OverrideForkchoiceUpdate, ProduceBlockVerification, StateSkipConfig, WhenSlotSkipped,
     INVALID_FINALIZED_MERGE_TRANSITION_BLOCK_SHUTDOWN_REASON,
     INVALID_JUSTIFIED_PAYLOAD_SHUTDOWN_REASON,
 };
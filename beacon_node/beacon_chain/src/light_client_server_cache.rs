@@ -1,13 +1,9 @@
 use crate::errors::BeaconChainError;
 use crate::{metrics, BeaconChainTypes, BeaconStore};
 use parking_lot::{Mutex, RwLock};
-use safe_arith::SafeArith;
 use slog::{debug, Logger};
-use ssz::Decode;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
-use store::DBColumn;
-use store::KeyValueStore;
 use tree_hash::TreeHash;
 use types::non_zero_usize::new_non_zero_usize;
 use types::{
@@ -40,6 +36,18 @@ pub struct LightClientServerCache<T: BeaconChainTypes> {
     latest_written_current_sync_committee: RwLock<Option<Arc<SyncCommittee<T::EthSpec>>>>,
     /// Caches state proofs by block root
     prev_block_cache: Mutex<lru::LruCache<Hash256, LightClientCachedData<T::EthSpec>>>,
+    /// Tracks the updates published on gossip, so that each is published at most once.
+    published_updates: Mutex<PublishedUpdates>,
+}
+
+/// The most recent light client updates published on gossip.
+#[derive(Default)]
+struct PublishedUpdates {
+    /// The `attested_header` slot of the latest optimistic update.
+    optimistic_attested_slot: Option<Slot>,
+    /// The `finalized_header` slot of the latest finality update, and whether its sync aggregate
+    /// had supermajority participation.
+    finality: Option<(Slot, bool)>,
 }
 
 impl<T: BeaconChainTypes> LightClientServerCache<T> {
@@ -50,6 +58,7 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
             latest_light_client_update: None.into(),
             latest_written_current_sync_committee: None.into(),
             prev_block_cache: lru::LruCache::new(PREV_BLOCK_CACHE_SIZE).into(),
+            published_updates: <_>::default(),
         }
     }
 
@@ -266,41 +275,6 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
         Ok(None)
     }
 
-    pub fn get_light_client_updates(
-        &self,
-        store: &BeaconStore<T>,
-        start_period: u64,
-        count: u64,
-        chain_spec: &ChainSpec,
-    ) -> Result<Vec<LightClientUpdate<T::EthSpec>>, BeaconChainError> {
-        let column = DBColumn::LightClientUpdate;
-        let mut light_client_updates = vec![];
-        for res in store
-            .hot_db
-            .iter_column_from::<Vec<u8>>(column, &start_period.to_le_bytes())
-        {
-            let (sync_committee_bytes, light_client_update_bytes) = res?;
-            let sync_committee_period = u64::from_ssz_bytes(&sync_committee_bytes)
-                .map_err(store::errors::Error::SszDecodeError)?;
-
-            if sync_committee_period >= start_period + count {
-                break;
-            }
-
-            let epoch = sync_committee_period
-                .safe_mul(chain_spec.epochs_per_sync_committee_period.into())?;
-
-            let fork_name = chain_spec.fork_name_at_epoch(epoch.into());
-
-            let light_client_update =
-                LightClientUpdate::from_ssz_bytes(&light_client_update_bytes, &fork_name)
-                    .map_err(store::errors::Error::SszDecodeError)?;
-
-            light_client_updates.push(light_client_update);
-        }
-        Ok(light_client_updates)
-    }
-
     /// Retrieves prev block cached data from cache. If not present re-computes by retrieving the
     /// parent state, and inserts an entry to the cache.
     ///
@@ -341,12 +315,66 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
         self.latest_optimistic_update.read().clone()
     }
 
+    /// Returns the latest optimistic and finality updates which are yet to be published on gossip,
+    /// marking them as published.
+    ///
+    /// Peers ignore updates which don't supersede those they have already forwarded, so an
+    /// optimistic update is only returned if it attests to a later slot than the previously
+    /// published one. A finality update is only returned if it finalizes a later slot, or the same
+    /// slot with supermajority sync committee participation where the previously published one
+    /// lacked it. Updates without the minimum sync committee participation are never returned.
+    ///
+    /// The caller is responsible for waiting until one-third of the way through the signature slot
+    /// before publishing.
+    pub fn take_updates_to_publish(
+        &self,
+        chain_spec: &ChainSpec,
+    ) -> (
+        Option<LightClientOptimisticUpdate<T::EthSpec>>,
+        Option<LightClientFinalityUpdate<T::EthSpec>>,
+    ) {
+        let min_participants = chain_spec.min_sync_committee_participants as usize;
+        let mut published_updates = self.published_updates.lock();
+
+        let optimistic_update = self.get_latest_optimistic_update().filter(|update| {
+            update.sync_aggregate().num_set_bits() >= min_participants
+                && published_updates
+                    .optimistic_attested_slot
+                    .map_or(true, |slot| update.get_slot() > slot)
+        });
+        if let Some(update) = &optimistic_update {
+            published_updates.optimistic_attested_slot = Some(update.get_slot());
+        }
+
+        let finality_update = self.get_latest_finality_update().and_then(|update| {
+            let participants = update.sync_aggregate().num_set_bits();
+            if participants < min_participants {
+                return None;
+            }
+            let finalized_slot = update.get_finalized_header_slot();
+            let supermajority = participants * 3 > T::EthSpec::sync_committee_size() * 2;
+            let is_new = match published_updates.finality {
+                None => true,
+                Some((prev_slot, prev_supermajority)) => {
+                    finalized_slot > prev_slot
+                        || (finalized_slot == prev_slot && supermajority && !prev_supermajority)
+                }
+            };
+            is_new.then(|| {
+                published_updates.finality = Some((finalized_slot, supermajority));
+                update
+            })
+        });
+
+        (optimistic_update, finality_update)
+    }
+
     /// Fetches a light client bootstrap for a given finalized checkpoint `block_root`. We eagerly persist
     /// `sync_committee_branch and `sync_committee` to allow for a more efficient bootstrap construction.
     ///
-    /// Note: It should be the case that a `sync_committee_branch` and `sync_committee` exist in the db
-    /// for a finalized checkpoint block root. However, we currently have no backfill mechanism for these values.
-    /// Therefore, `sync_committee_branch` and `sync_committee` are only persisted while a node is synced.
+    /// Note: `sync_committee_branch` and `sync_committee` are only persisted while a node is synced.
+    /// For other blocks, such as those imported during sync, they are computed from the block's
+    /// state on demand and persisted for subsequent requests.
     #[allow(clippy::type_complexity)]
     pub fn get_light_client_bootstrap(
         &self,
@@ -370,29 +398,34 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
             .epoch(T::EthSpec::slots_per_epoch())
             .sync_committee_period(chain_spec)?;
 
-        let Some(current_sync_committee_branch) = store.get_sync_committee_branch(block_root)?
-        else {
-            return Err(BeaconChainError::LightClientBootstrapError(format!(
-                "Sync committee branch for block root {:?} not found",
-                block_root
-            )));
-        };
-
         if sync_committee_period > finalized_period {
             return Err(BeaconChainError::LightClientBootstrapError(
                 format!("The blocks sync committee period {sync_committee_period} is greater than the current finalized period {finalized_period}"),
             ));
         }
 
-        let Some(current_sync_committee) = store.get_sync_committee(sync_committee_period)? else {
-            return Err(BeaconChainError::LightClientBootstrapError(format!(
-                "Sync committee for period {sync_committee_period} not found"
-            )));
+        let (current_sync_committee_branch, current_sync_committee) = match (
+            store.get_sync_committee_branch(block_root)?,
+            store.get_sync_committee(sync_committee_period)?,
+        ) {
+            (Some(branch), Some(sync_committee)) => (branch, Arc::new(sync_committee)),
+            _ => {
+                let Some(state) = store.get_state(&block.state_root(), Some(block.slot()))? else {
+                    return Err(BeaconChainError::LightClientBootstrapError(format!(
+                        "State for block root {block_root:?} not found"
+                    )));
+                };
+                let branch = state.compute_current_sync_committee_proof()?;
+                let sync_committee = state.current_sync_committee()?.clone();
+                store.store_sync_committee_branch(*block_root, &branch)?;
+                store.store_sync_committee(sync_committee_period, &sync_committee)?;
+                (branch, sync_committee)
+            }
         };
 
         let light_client_bootstrap = LightClientBootstrap::new(
             &block,
-            Arc::new(current_sync_committee),
+            current_sync_committee,
             current_sync_committee_branch,
             chain_spec,
         )?;
@@ -420,7 +453,7 @@ struct LightClientCachedData<E: EthSpec> {
 
 impl<E: EthSpec> LightClientCachedData<E> {
     fn from_state(state: &mut BeaconState<E>) -> Result<Self, BeaconChainError> {
-        let (finality_branch, next_sync_committee_branch, current_sync_committee_branch) = (
+        let (finality_branch, current_sync_committee_branch, next_sync_committee_branch) = (
             state.compute_finalized_root_proof()?,
             state.compute_current_sync_committee_proof()?,
             state.compute_next_sync_committee_proof()?,
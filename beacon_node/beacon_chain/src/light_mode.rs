@@ -0,0 +1,113 @@
+//! Execution-free "light mode", in which the validity of execution payloads is taken from a
+//! trusted beacon node rather than a local execution engine.
+//!
+//! The chain is followed optimistically: blocks are imported as optimistic without contacting the
+//! trusted node, which is asked about the head whenever it is updated. Once the trusted node has
+//! verified the payload of the head, the head and its ancestors are marked valid. Nothing is ever
+//! marked invalid, so a node in light mode is only as correct as the node it trusts. It is
+//! intended for serving data and never serves validator duties.
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::types::BlockId;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use fork_choice::PayloadVerificationStatus;
+use sensitive_url::SensitiveUrl;
+use slog::debug;
+use std::sync::Arc;
+use std::time::Duration;
+use types::{Hash256, Slot};
+
+/// Timeout for requests to the trusted beacon node.
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A trusted beacon node which reports the validity of execution payloads.
+pub struct TrustedPayloadProvider {
+    client: BeaconNodeHttpClient,
+}
+
+impl TrustedPayloadProvider {
+    pub fn new(url: SensitiveUrl) -> Self {
+        Self {
+            client: BeaconNodeHttpClient::new(url, Timeouts::set_all(PROVIDER_TIMEOUT)),
+        }
+    }
+
+    /// Returns the status of the payload of `block_root` according to the trusted node.
+    ///
+    /// The payload is optimistic unless the trusted node has imported the block and verified its
+    /// payload.
+    pub async fn payload_status(
+        &self,
+        block_root: Hash256,
+    ) -> Result<PayloadVerificationStatus, eth2::Error> {
+        let header = self
+            .client
+            .get_beacon_headers_block_id(BlockId::Root(block_root))
+            .await?;
+        Ok(match header {
+            Some(header) if header.execution_optimistic == Some(false) => {
+                PayloadVerificationStatus::Verified
+            }
+            _ => PayloadVerificationStatus::Optimistic,
+        })
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the status of the payload of `block_root` according to `provider`, treating it as
+    /// optimistic if the provider can't be reached.
+    pub(crate) async fn light_mode_payload_status(
+        &self,
+        provider: &TrustedPayloadProvider,
+        block_root: Hash256,
+    ) -> PayloadVerificationStatus {
+        let status = provider
+            .payload_status(block_root)
+            .await
+            .unwrap_or_else(|e| {
+                debug!(
+                    self.log,
+                    "Unable to reach light mode provider";
+                    "block_root" => ?block_root,
+                    "error" => ?e,
+                );
+                PayloadVerificationStatus::Optimistic
+            });
+        let label = match status {
+            PayloadVerificationStatus::Verified => "verified",
+            PayloadVerificationStatus::Optimistic => "optimistic",
+            PayloadVerificationStatus::Irrelevant => "irrelevant",
+        };
+        metrics::inc_counter_vec(&metrics::LIGHT_MODE_PAYLOAD_STATUS, &[label]);
+        status
+    }
+
+    /// Mark the head as valid once the trusted node has verified its payload.
+    ///
+    /// This takes the place of the `forkchoiceUpdated` call to the execution engine.
+    pub(crate) async fn update_light_mode_head(
+        self: &Arc<Self>,
+        provider: &TrustedPayloadProvider,
+        head_block_root: Hash256,
+        current_slot: Slot,
+    ) -> Result<(), BeaconChainError> {
+        let head_is_optimistic = self
+            .canonical_head
+            .fork_choice_read_lock()
+            .get_block_execution_status(&head_block_root)
+            .is_some_and(|status| status.is_strictly_optimistic());
+        if !head_is_optimistic {
+            return Ok(());
+        }
+
+        match self
+            .light_mode_payload_status(provider, head_block_root)
+            .await
+        {
+            PayloadVerificationStatus::Verified => {
+                self.on_valid_head_payload(head_block_root, current_slot)
+                    .await
+            }
+            PayloadVerificationStatus::Optimistic | PayloadVerificationStatus::Irrelevant => Ok(()),
+        }
+    }
+}
@@ -20,6 +20,8 @@ pub const VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_SOURCE_ATTESTER_HIT_TOTAL: &st
     "validator_monitor_attestation_simulator_source_attester_hit_total";
 pub const VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_SOURCE_ATTESTER_MISS_TOTAL: &str =
     "validator_monitor_attestation_simulator_source_attester_miss_total";
+pub const VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_EXPECTED_EFFECTIVENESS_PERCENT: &str =
+    "validator_monitor_attestation_simulator_expected_effectiveness_percent";
 
 /*
  * Block Processing
@@ -222,6 +224,28 @@ pub static BLOCK_PRODUCTION_STATE_ROOT_TIMES: LazyLock<Result<Histogram>> = Lazy
         "Time taken to calculate the block's state root",
     )
 });
+pub static BLOCK_PRODUCTION_PAYLOAD_FETCH_TIMES: LazyLock<Result<Histogram>> =
+    LazyLock::new(|| {
+        try_create_histogram(
+            "beacon_block_production_payload_fetch_seconds",
+            "Time spent waiting for the execution payload after packing the block",
+        )
+    });
+pub static BLOCK_PRODUCTION_STAGE_BUDGET_EXCEEDED: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_block_production_stage_budget_exceeded_total",
+            "Count of block production stages which overran their time budget",
+            &["stage"],
+        )
+    });
+pub static BLOCK_PRODUCTION_DEGRADED: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_block_production_degraded_total",
+        "Count of block production stages which degraded their output to stay within budget",
+        &["stage"],
+    )
+});
 
 /*
  * Block Statistics
@@ -426,6 +450,22 @@ pub static ATTESTATION_PROCESSING_BATCH_UNAGG_SIGNATURE_TIMES: LazyLock<Result<H
         )
     });
 
+/*
+ * Attestation data cache
+ */
+pub static ATTESTATION_DATA_CACHE_HITS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_attestation_data_cache_hits_total",
+        "Count of unaggregated attestations whose attestation data was already verified",
+    )
+});
+pub static ATTESTATION_DATA_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_attestation_data_cache_misses_total",
+        "Count of unaggregated attestations whose attestation data had to be verified",
+    )
+});
+
 /*
  * Shuffling cache
  */
@@ -453,6 +493,34 @@ pub static SHUFFLING_CACHE_PROMISE_FAILS: LazyLock<Result<IntCounter>> = LazyLoc
         "Count of times shuffling cache detects a failed promise",
     )
 });
+pub static SHUFFLING_CACHE_WARMED: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_shuffling_cache_warmed_total",
+        "Count of shufflings pre-computed by the shuffling cache warmer",
+    )
+});
+pub static SHUFFLING_CACHE_WARMING_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "beacon_shuffling_cache_warming_seconds",
+        "Time taken by each run of the shuffling cache warmer",
+    )
+});
+
+/*
+ * Head state pool
+ */
+pub static HEAD_STATE_POOL_HITS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_head_state_pool_hits_total",
+        "Count of head state clones taken from the head state pool",
+    )
+});
+pub static HEAD_STATE_POOL_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_head_state_pool_misses_total",
+        "Count of head state clones made on demand because the head state pool was empty",
+    )
+});
 
 /*
  * Early attester cache
@@ -463,6 +531,13 @@ pub static BEACON_EARLY_ATTESTER_CACHE_HITS: LazyLock<Result<IntCounter>> = Lazy
         "Count of times the early attester cache returns an attestation",
     )
 });
+pub static BEACON_EARLY_ATTESTER_CACHE_PENDING_BLOCKS: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "beacon_early_attester_cache_pending_blocks_total",
+            "Count of blocks added to the early attester cache whilst awaiting blobs",
+        )
+    });
 
 pub static BEACON_REQRESP_PRE_IMPORT_CACHE_SIZE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
@@ -569,6 +644,20 @@ pub static FORK_CHOICE_AFTER_NEW_HEAD_TIMES: LazyLock<Result<Histogram>> = LazyL
         exponential_buckets(1e-3, 2.0, 10),
     )
 });
+pub static OPTIMISTIC_TRANSITIONS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_fork_choice_optimistic_transitions_total",
+        "Count of optimistic blocks whose execution status changed, by new status",
+        &["status"],
+    )
+});
+pub static LIGHT_MODE_PAYLOAD_STATUS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_light_mode_payload_status_total",
+        "Count of payload statuses from the trusted light mode provider, by status",
+        &["status"],
+    )
+});
 pub static FORK_CHOICE_AFTER_FINALIZATION_TIMES: LazyLock<Result<Histogram>> =
     LazyLock::new(|| {
         try_create_histogram_with_buckets(
@@ -610,6 +699,48 @@ pub static BALANCES_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|
     )
 });
 
+/*
+ * Historical state loading
+ */
+pub static STATE_LOAD_PLANS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_state_load_plans_total",
+        "Count of planned state loads by the source chosen by the planner",
+        &["source"],
+    )
+});
+pub static STATE_LOAD_PLAN_REPLAY_SLOTS: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "beacon_state_load_plan_replay_slots",
+        "Number of slots replayed by planned state loads",
+        Ok(vec![
+            0.0, 1.0, 2.0, 4.0, 8.0, 16.0, 32.0, 64.0, 128.0, 256.0,
+        ]),
+    )
+});
+
+/*
+ * Weak subjectivity
+ */
+pub static WEAK_SUBJECTIVITY_PERIOD: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_weak_subjectivity_period_epochs",
+        "The weak subjectivity period computed from the head state's validator set",
+    )
+});
+pub static WEAK_SUBJECTIVITY_ANCHOR_AGE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_weak_subjectivity_anchor_age_epochs",
+        "The number of epochs between the finalized checkpoint and the current epoch",
+    )
+});
+pub static WEAK_SUBJECTIVITY_OUTSIDE_PERIOD: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_weak_subjectivity_outside_period",
+        "Set to 1 if the finalized checkpoint is older than the weak subjectivity period",
+    )
+});
+
 /*
  * Persisting BeaconChain components to disk
  */
@@ -637,6 +768,29 @@ pub static PERSIST_FORK_CHOICE: LazyLock<Result<Histogram>> = LazyLock::new(|| {
         "Time taken to persist the fork choice struct",
     )
 });
+pub static PERSIST_HEAD_STATE: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "beacon_persist_head_state",
+        "Time taken to persist the head state for a fast restart",
+    )
+});
+pub static LOAD_PERSISTED_HEAD_STATE: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "beacon_load_persisted_head_state",
+        "Time taken to load the head state persisted for a fast restart",
+    )
+});
+
+/*
+ * Own key guard
+ */
+pub static OWN_KEY_GUARD_CONFLICTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_own_key_guard_conflicts_total",
+        "Count of slashable messages from guarded validators which were refused publication",
+        &["kind"],
+    )
+});
 
 /*
  * Eth1
@@ -949,6 +1103,14 @@ pub static VALIDATOR_WITHDRAWABLE_EPOCH: LazyLock<Result<IntGaugeVec>> = LazyLoc
 /*
  * Validator Monitor Metrics (per-epoch summaries)
  */
+pub static VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_EFFECTIVENESS: LazyLock<Result<Gauge>> =
+    LazyLock::new(|| {
+        try_create_float_gauge(
+            "validator_monitor_prev_epoch_attestation_effectiveness_percent",
+            "Percentage of the maximum attestation reward earned by the active monitored \
+            validators in the previous epoch",
+        )
+    });
 pub static VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_HIT: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
         try_create_int_counter_vec(
@@ -1817,6 +1979,14 @@ pub static VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_SOURCE_ATTESTER_MISS: LazyLoc
         during per slot processing",
     )
 });
+pub static VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_EXPECTED_EFFECTIVENESS: LazyLock<Result<Gauge>> =
+    LazyLock::new(|| {
+        try_create_float_gauge(
+            VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_EXPECTED_EFFECTIVENESS_PERCENT,
+            "Percentage of the maximum attestation reward that a perfect validator attesting at \
+        every slot of the previous epoch would have earned",
+        )
+    });
 /*
  * Missed block metrics
  */
@@ -1829,6 +1999,27 @@ pub static VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL: LazyLock<Result<IntCounterVec>
         )
     });
 
+/*
+ * Client fingerprint metrics
+ */
+pub static VALIDATOR_MONITOR_CLIENT_ATTESTATION_MISS_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "validator_monitor_client_attestation_miss_total",
+            "Number of missed attestations by the client inferred from block graffiti",
+            &["client"],
+        )
+    });
+pub static VALIDATOR_MONITOR_CLIENT_ATTESTATION_LATE_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "validator_monitor_client_attestation_late_total",
+            "Number of attestations with a sub-optimal inclusion distance by the client inferred \
+             from block graffiti",
+            &["client"],
+        )
+    });
+
 /*
  * Kzg related metrics
  */
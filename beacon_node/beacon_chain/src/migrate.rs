@@ -11,6 +11,7 @@ use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::{migrate_database, HotColdDBError};
 use store::iter::RootsIterator;
+use store::orphaned_blocks::{OrphanReason, OrphanedBlock};
 use store::{Error, ItemStore, StoreItem, StoreOp};
 pub use store::{HotColdDB, MemoryStore};
 use types::{
@@ -124,6 +125,10 @@ pub enum Notification {
     Finalization(FinalizationNotification),
     Reconstruction,
     PruneBlobs(Epoch),
+    /// Prune all prunable data, e.g. at the request of an operator.
+    ///
+    /// Blobs are only pruned if the data availability boundary is known.
+    PruneAll(Option<Epoch>),
 }
 
 pub struct FinalizationNotification {
@@ -208,6 +213,14 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         }
     }
 
+    pub fn process_prune_all(&self, data_availability_boundary: Option<Epoch>) {
+        if let Some(Notification::PruneAll(data_availability_boundary)) =
+            self.send_background_notification(Notification::PruneAll(data_availability_boundary))
+        {
+            Self::run_prune_all(self.db.clone(), data_availability_boundary, &self.log);
+        }
+    }
+
     pub fn run_reconstruction(
         db: Arc<HotColdDB<E, Hot, Cold>>,
         opt_tx: Option<mpsc::Sender<Notification>>,
@@ -253,6 +266,32 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         }
     }
 
+    pub fn run_prune_all(
+        db: Arc<HotColdDB<E, Hot, Cold>>,
+        data_availability_boundary: Option<Epoch>,
+        log: &Logger,
+    ) {
+        if let Some(data_availability_boundary) = data_availability_boundary {
+            Self::run_prune_blobs(db.clone(), data_availability_boundary, log);
+        }
+        if db.get_config().prune_payloads {
+            if let Err(e) = db.try_prune_execution_payloads(false) {
+                error!(
+                    log,
+                    "Execution payload pruning failed";
+                    "error" => ?e,
+                );
+            }
+        }
+        if let Err(e) = db.prune_old_hot_states() {
+            error!(
+                log,
+                "Hot state pruning failed";
+                "error" => ?e,
+            );
+        }
+    }
+
     /// If configured to run in the background, send `notif` to the background thread.
     ///
     /// Return `None` if the message was sent to the background thread, `Some(notif)` otherwise.
@@ -423,10 +462,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 let mut reconstruction_notif = None;
                 let mut finalization_notif = None;
                 let mut prune_blobs_notif = None;
+                let mut prune_all_notif = None;
                 match notif {
                     Notification::Reconstruction => reconstruction_notif = Some(notif),
                     Notification::Finalization(fin) => finalization_notif = Some(fin),
                     Notification::PruneBlobs(dab) => prune_blobs_notif = Some(dab),
+                    Notification::PruneAll(dab) => prune_all_notif = Some(dab),
                 }
                 // Read the rest of the messages in the channel, taking the best of each type.
                 for notif in rx.try_iter() {
@@ -446,6 +487,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                         Notification::PruneBlobs(dab) => {
                             prune_blobs_notif = std::cmp::max(prune_blobs_notif, Some(dab));
                         }
+                        Notification::PruneAll(dab) => {
+                            prune_all_notif = std::cmp::max(prune_all_notif, Some(dab));
+                        }
                     }
                 }
                 // Run finalization and blob pruning migrations first, then a reconstruction batch.
@@ -457,6 +501,9 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 if let Some(dab) = prune_blobs_notif {
                     Self::run_prune_blobs(db.clone(), dab, &log);
                 }
+                if let Some(dab) = prune_all_notif {
+                    Self::run_prune_all(db.clone(), dab, &log);
+                }
                 if reconstruction_notif.is_some() {
                     Self::run_reconstruction(db.clone(), Some(inner_tx.clone()), &log);
                 }
@@ -680,6 +727,25 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         }
 
+        let mut archive_ops = if store.get_config().archive_orphaned_blocks {
+            Self::orphaned_block_archive_ops(&store, &abandoned_blocks, &newly_finalized_chain)?
+        } else {
+            vec![]
+        };
+
+        // Delete archived orphans which have passed the retention period, including any archived
+        // before the archive was disabled.
+        let retention_slots = store
+            .get_config()
+            .orphan_archive_retention_epochs
+            .saturating_mul(E::slots_per_epoch());
+        archive_ops.extend(
+            store
+                .orphan_archive_prune_ops(new_finalized_slot.saturating_sub(retention_slots))?
+                .into_iter()
+                .map(StoreOp::KeyValueOp),
+        );
+
         // Update the head tracker before the database, so that we maintain the invariant
         // that a block present in the head tracker is present in the database.
         // See https://github.com/sigp/lighthouse/issues/1557
@@ -715,6 +781,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                     .into_iter()
                     .map(|(slot, state_hash)| StoreOp::DeleteState(state_hash.into(), Some(slot))),
             )
+            .chain(archive_ops)
             .collect();
 
         // Persist the head in case the process is killed or crashes here. This prevents
@@ -747,6 +814,55 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         })
     }
 
+    /// Create ops which copy the `abandoned_blocks` and their attestations into the orphan archive.
+    ///
+    /// A block is marked as an equivocation if the newly finalized chain contains a different
+    /// block from the same proposer at the same slot.
+    fn orphaned_block_archive_ops(
+        store: &HotColdDB<E, Hot, Cold>,
+        abandoned_blocks: &HashSet<SignedBeaconBlockHash>,
+        newly_finalized_chain: &HashMap<Slot, (SignedBeaconBlockHash, BeaconStateHash)>,
+    ) -> Result<Vec<StoreOp<'static, E>>, BeaconChainError> {
+        let mut ops = Vec::with_capacity(abandoned_blocks.len());
+        for block_hash in abandoned_blocks {
+            let block_root = Hash256::from(*block_hash);
+            let Some(block) = store.get_blinded_block(&block_root)? else {
+                continue;
+            };
+
+            let is_equivocation = match newly_finalized_chain.get(&block.slot()) {
+                Some((canonical_block_hash, _)) => store
+                    .get_blinded_block(&(*canonical_block_hash).into())?
+                    .is_some_and(|canonical_block| {
+                        canonical_block.slot() == block.slot()
+                            && canonical_block.message().proposer_index()
+                                == block.message().proposer_index()
+                    }),
+                None => false,
+            };
+            let reason = if is_equivocation {
+                OrphanReason::Equivocation
+            } else {
+                OrphanReason::Orphaned
+            };
+
+            ops.extend(block.message().body().attestations().enumerate().map(
+                |(index, attestation)| {
+                    StoreOp::KeyValueOp(store.orphaned_attestation_archive_op(
+                        &block_root,
+                        index as u64,
+                        attestation,
+                    ))
+                },
+            ));
+            ops.push(StoreOp::KeyValueOp(store.orphaned_block_archive_op(
+                &block_root,
+                &OrphanedBlock { reason, block },
+            )));
+        }
+        Ok(ops)
+    }
+
     /// Compact the database if it has been more than `COMPACTION_PERIOD_SECONDS` since it
     /// was last compacted.
     pub fn run_compaction(
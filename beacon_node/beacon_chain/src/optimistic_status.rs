@@ -0,0 +1,168 @@
+//! Tracks changes to the execution status of blocks in fork choice, so that the optimistic status
+//! of a block can be explained via the HTTP API and changes can be streamed as server-sent events.
+//!
+//! A transition is recorded whenever a strictly optimistic block becomes valid or invalid, either
+//! because the execution engine returned a verdict for it or for one of its descendants.
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::OptimisticStatus;
+use eth2::types::{EventKind, SseOptimisticTransition};
+use fork_choice::ExecutionStatus;
+use lru::LruCache;
+use parking_lot::Mutex;
+use proto_array::core::{ProtoArray, ProtoNode};
+use slog::debug;
+use std::num::NonZeroUsize;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{Hash256, Slot};
+
+/// The number of blocks for which the most recent transition is retained.
+const TRANSITION_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(256);
+
+/// The strictly optimistic blocks in fork choice at some point in time.
+pub struct OptimisticBlocks(Vec<(Hash256, ExecutionStatus)>);
+
+impl OptimisticBlocks {
+    pub fn from_proto_array(proto_array: &ProtoArray) -> Self {
+        Self(
+            proto_array
+                .nodes
+                .iter()
+                .filter(|node| node.execution_status.is_strictly_optimistic())
+                .map(|node| (node.root, node.execution_status))
+                .collect(),
+        )
+    }
+
+    /// Returns the transitions of the blocks whose execution status in `proto_array` differs
+    /// from when `self` was created.
+    pub fn transitions(
+        self,
+        proto_array: &ProtoArray,
+        observed_slot: Slot,
+    ) -> Vec<SseOptimisticTransition> {
+        self.0
+            .into_iter()
+            .filter_map(|(block_root, previous_status)| {
+                let node = get_node(proto_array, &block_root)?;
+                if node.execution_status == previous_status {
+                    return None;
+                }
+                Some(SseOptimisticTransition {
+                    block_root,
+                    slot: node.slot,
+                    execution_block_hash: node.execution_status.block_hash()?,
+                    previous_status: previous_status.to_string(),
+                    status: node.execution_status.to_string(),
+                    observed_slot,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The most recent transition of recently transitioned blocks.
+pub struct OptimisticTransitions {
+    latest: Mutex<LruCache<Hash256, SseOptimisticTransition>>,
+}
+
+impl Default for OptimisticTransitions {
+    fn default() -> Self {
+        Self {
+            latest: Mutex::new(LruCache::new(TRANSITION_CACHE_SIZE)),
+        }
+    }
+}
+
+impl OptimisticTransitions {
+    pub fn record(&self, transition: SseOptimisticTransition) {
+        self.latest.lock().put(transition.block_root, transition);
+    }
+
+    pub fn get(&self, block_root: &Hash256) -> Option<SseOptimisticTransition> {
+        self.latest.lock().get(block_root).cloned()
+    }
+}
+
+fn get_node<'a>(proto_array: &'a ProtoArray, block_root: &Hash256) -> Option<&'a ProtoNode> {
+    proto_array
+        .indices
+        .get(block_root)
+        .and_then(|index| proto_array.nodes.get(*index))
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the execution status of `block_root` (or the head block if `None`), or `None` if
+    /// the block is not in fork choice.
+    pub fn optimistic_status(&self, block_root: Option<Hash256>) -> Option<OptimisticStatus> {
+        let head_root = self.canonical_head.cached_head().head_block_root();
+        let block_root = block_root.unwrap_or(head_root);
+
+        let fork_choice = self.canonical_head.fork_choice_read_lock();
+        let proto_array = fork_choice.proto_array().core_proto_array();
+        let node = get_node(proto_array, &block_root)?;
+
+        // Walk back to the first ancestor which is known to be valid. If it has been pruned from
+        // fork choice the distance is measured to the oldest ancestor that remains.
+        let mut oldest = node;
+        let mut latest_valid_ancestor = None;
+        let mut ancestor = Some(node);
+        while let Some(current) = ancestor {
+            if current.execution_status.is_valid_or_irrelevant() {
+                latest_valid_ancestor = Some(current);
+                break;
+            }
+            oldest = current;
+            ancestor = current
+                .parent
+                .and_then(|index| proto_array.nodes.get(index));
+        }
+
+        let optimistic_distance = if node.execution_status.is_strictly_optimistic() {
+            let since = latest_valid_ancestor.map_or(oldest.slot, |ancestor| ancestor.slot);
+            node.slot.saturating_sub(since).as_u64()
+        } else {
+            0
+        };
+
+        Some(OptimisticStatus {
+            block_root,
+            slot: node.slot,
+            execution_block_hash: node.execution_status.block_hash(),
+            execution_status: node.execution_status.to_string(),
+            is_head: block_root == head_root,
+            latest_valid_ancestor_root: latest_valid_ancestor.map(|ancestor| ancestor.root),
+            latest_valid_ancestor_slot: latest_valid_ancestor.map(|ancestor| ancestor.slot),
+            optimistic_distance,
+            last_transition: self.optimistic_transitions.get(&block_root),
+        })
+    }
+
+    /// Records `transitions` and emits them as server-sent events.
+    ///
+    /// This must not be called whilst holding the fork choice lock.
+    pub(crate) fn record_optimistic_transitions(&self, transitions: Vec<SseOptimisticTransition>) {
+        for transition in transitions {
+            debug!(
+                self.log,
+                "Block execution status changed";
+                "block_root" => ?transition.block_root,
+                "slot" => transition.slot,
+                "previous_status" => &transition.previous_status,
+                "status" => &transition.status,
+            );
+            metrics::inc_counter_vec(
+                &metrics::OPTIMISTIC_TRANSITIONS,
+                &[transition.status.as_str()],
+            );
+
+            if let Some(event_handler) = self.event_handler.as_ref() {
+                if event_handler.has_optimistic_transition_subscribers() {
+                    event_handler.register(EventKind::OptimisticTransition(Box::new(
+                        transition.clone(),
+                    )));
+                }
+            }
+            self.optimistic_transitions.record(transition);
+        }
+    }
+}
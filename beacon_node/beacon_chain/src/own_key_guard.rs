@@ -0,0 +1,216 @@
+//! Provides the `OwnKeyGuard`, a last line of defence against publishing slashable messages signed
+//! by the operator's own validators.
+//!
+//! The validator client's slashing protection database is the primary defence against slashing.
+//! The guard is independent of it: it remembers the attestations signed by a configured set of
+//! validators which the beacon node has seen, either on gossip or via the HTTP API, and refuses to
+//! publish or propagate any attestation which conflicts with them. This catches, for example, the
+//! same keys running in two validator clients with separate slashing protection databases.
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tree_hash::TreeHash;
+use types::{AttestationData, Epoch, Hash256, PublicKeyBytes, Slot};
+
+/// A message signed by a guarded validator which conflicts with one it has already signed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OwnKeyConflict {
+    /// The validator has already signed a different attestation with the same target epoch.
+    DoubleVote {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+    /// The attestation surrounds, or is surrounded by, an attestation the validator has already
+    /// signed with the given source and target.
+    SurroundVote {
+        validator_index: u64,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+    },
+    /// The validator has already signed a different block at the same slot.
+    DoubleProposal { validator_index: u64, slot: Slot },
+}
+
+impl OwnKeyConflict {
+    /// A short name for the kind of conflict, for use in metrics.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OwnKeyConflict::DoubleVote { .. } => "double_vote",
+            OwnKeyConflict::SurroundVote { .. } => "surround_vote",
+            OwnKeyConflict::DoubleProposal { .. } => "double_proposal",
+        }
+    }
+}
+
+/// An attestation seen from a guarded validator.
+struct Vote {
+    source_epoch: Epoch,
+    data_root: Hash256,
+}
+
+pub struct OwnKeyGuard {
+    pubkeys: HashSet<PublicKeyBytes>,
+    /// The votes of guarded validators, by validator index and then target epoch.
+    votes: Mutex<HashMap<u64, BTreeMap<Epoch, Vote>>>,
+}
+
+impl OwnKeyGuard {
+    pub fn new(pubkeys: impl IntoIterator<Item = PublicKeyBytes>) -> Self {
+        Self {
+            pubkeys: pubkeys.into_iter().collect(),
+            votes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `true` if any validators are guarded.
+    pub fn is_enabled(&self) -> bool {
+        !self.pubkeys.is_empty()
+    }
+
+    /// Returns `true` if the validator with `pubkey` is guarded.
+    pub fn is_guarded(&self, pubkey: &PublicKeyBytes) -> bool {
+        self.pubkeys.contains(pubkey)
+    }
+
+    /// Records that the guarded validator with `validator_index` signed an attestation with
+    /// `data`.
+    ///
+    /// Returns an error, without recording the attestation, if it conflicts with an attestation
+    /// already recorded for the validator. Signing the same attestation data again is not a
+    /// conflict.
+    pub fn observe_attestation(
+        &self,
+        validator_index: u64,
+        data: &AttestationData,
+    ) -> Result<(), OwnKeyConflict> {
+        let source_epoch = data.source.epoch;
+        let target_epoch = data.target.epoch;
+        let data_root = data.tree_hash_root();
+
+        let mut votes = self.votes.lock();
+        let validator_votes = votes.entry(validator_index).or_default();
+
+        if let Some(vote) = validator_votes.get(&target_epoch) {
+            return if vote.data_root == data_root {
+                Ok(())
+            } else {
+                Err(OwnKeyConflict::DoubleVote {
+                    validator_index,
+                    target_epoch,
+                })
+            };
+        }
+
+        // A prior vote with a later target and an earlier source surrounds this one.
+        if let Some((&prior_target, prior)) = validator_votes
+            .range(target_epoch..)
+            .find(|(_, prior)| prior.source_epoch < source_epoch)
+        {
+            return Err(OwnKeyConflict::SurroundVote {
+                validator_index,
+                source_epoch: prior.source_epoch,
+                target_epoch: prior_target,
+            });
+        }
+        // A prior vote with an earlier target and a later source is surrounded by this one.
+        if let Some((&prior_target, prior)) = validator_votes
+            .range(..target_epoch)
+            .find(|(_, prior)| prior.source_epoch > source_epoch)
+        {
+            return Err(OwnKeyConflict::SurroundVote {
+                validator_index,
+                source_epoch: prior.source_epoch,
+                target_epoch: prior_target,
+            });
+        }
+
+        validator_votes.insert(
+            target_epoch,
+            Vote {
+                source_epoch,
+                data_root,
+            },
+        );
+        Ok(())
+    }
+
+    /// Removes the votes with a target epoch earlier than `finalized_epoch`.
+    pub fn prune(&self, finalized_epoch: Epoch) {
+        self.votes.lock().retain(|_, validator_votes| {
+            validator_votes.retain(|&target_epoch, _| target_epoch >= finalized_epoch);
+            !validator_votes.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::Checkpoint;
+
+    fn attestation_data(source: u64, target: u64, block_root: u8) -> AttestationData {
+        AttestationData {
+            slot: Epoch::new(target).start_slot(32),
+            index: 0,
+            beacon_block_root: Hash256::repeat_byte(block_root),
+            source: Checkpoint {
+                epoch: Epoch::new(source),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target),
+                root: Hash256::zero(),
+            },
+        }
+    }
+
+    #[test]
+    fn detects_conflicts() {
+        let guard = OwnKeyGuard::new([PublicKeyBytes::empty()]);
+
+        guard
+            .observe_attestation(0, &attestation_data(2, 4, 1))
+            .unwrap();
+        // The same vote again, and a vote from another validator, are fine.
+        guard
+            .observe_attestation(0, &attestation_data(2, 4, 1))
+            .unwrap();
+        guard
+            .observe_attestation(1, &attestation_data(2, 4, 2))
+            .unwrap();
+
+        assert_eq!(
+            guard.observe_attestation(0, &attestation_data(2, 4, 2)),
+            Err(OwnKeyConflict::DoubleVote {
+                validator_index: 0,
+                target_epoch: Epoch::new(4),
+            })
+        );
+        // Surrounded by the vote from 2 to 4.
+        assert_eq!(
+            guard.observe_attestation(0, &attestation_data(3, 3, 1)),
+            Err(OwnKeyConflict::SurroundVote {
+                validator_index: 0,
+                source_epoch: Epoch::new(2),
+                target_epoch: Epoch::new(4),
+            })
+        );
+        // Surrounds the vote from 2 to 4.
+        assert_eq!(
+            guard.observe_attestation(0, &attestation_data(1, 5, 1)),
+            Err(OwnKeyConflict::SurroundVote {
+                validator_index: 0,
+                source_epoch: Epoch::new(2),
+                target_epoch: Epoch::new(4),
+            })
+        );
+        // Rejected votes are not recorded.
+        guard
+            .observe_attestation(0, &attestation_data(4, 5, 1))
+            .unwrap();
+
+        guard.prune(Epoch::new(5));
+        guard
+            .observe_attestation(0, &attestation_data(1, 4, 3))
+            .unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+//! Provides an extension point for deciding which proposals payload attributes are sent for.
+//!
+//! Payload attributes ask the execution layer to start building a payload ahead of a proposal.
+//! By default they are sent for every proposer registered via the `prepare_beacon_proposer`
+//! endpoint. In multi-node setups a validator client typically registers its proposers with all
+//! of its beacon nodes, so that every execution node builds payloads even though only one of them
+//! is used. A `PayloadAttributesPolicy` may veto the preparation of such proposals.
+//!
+//! Suppressing payload attributes does not prevent block production, the payload is then built
+//! when the block is requested, at the cost of a less optimal payload.
+use crate::ChainConfig;
+use types::{Epoch, Slot};
+
+/// A proposal which payload attributes could be sent for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PayloadPreparation {
+    pub proposal_slot: Slot,
+    pub proposal_epoch: Epoch,
+    pub proposer_index: u64,
+    /// `true` if the proposer was registered with `prepare_beacon_proposer`.
+    pub proposer_registered: bool,
+    /// The latest epoch of a proposal by a registered proposer or of a block produced by this
+    /// node, if any.
+    pub latest_local_proposal_epoch: Option<Epoch>,
+}
+
+/// The reason that payload attributes were not sent for a proposal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DoNotPrepare {
+    ProposerNotRegistered,
+    Suppressed,
+    NoRecentLocalProposals {
+        latest_local_proposal_epoch: Option<Epoch>,
+    },
+}
+
+/// Decides whether payload attributes should be sent for a proposal.
+pub trait PayloadAttributesPolicy: Send + Sync {
+    /// Returns `true` if payload attributes may be sent when no proposers are registered.
+    fn prepare_unregistered_proposers(&self) -> bool;
+
+    /// Returns `Ok(())` if payload attributes should be sent for the `preparation`.
+    fn check_preparation(&self, preparation: &PayloadPreparation) -> Result<(), DoNotPrepare>;
+}
+
+/// The default payload attributes policy, configured by the `ChainConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainConfigPayloadAttributesPolicy {
+    always_prepare_payload: bool,
+    suppress_payload_attributes: bool,
+    window_epochs: Option<u64>,
+}
+
+impl ChainConfigPayloadAttributesPolicy {
+    pub fn new(config: &ChainConfig) -> Self {
+        Self {
+            always_prepare_payload: config.always_prepare_payload,
+            suppress_payload_attributes: config.suppress_payload_attributes,
+            window_epochs: config.payload_attributes_window_epochs,
+        }
+    }
+}
+
+impl PayloadAttributesPolicy for ChainConfigPayloadAttributesPolicy {
+    fn prepare_unregistered_proposers(&self) -> bool {
+        self.always_prepare_payload && !self.suppress_payload_attributes
+    }
+
+    fn check_preparation(&self, preparation: &PayloadPreparation) -> Result<(), DoNotPrepare> {
+        if self.suppress_payload_attributes {
+            return Err(DoNotPrepare::Suppressed);
+        }
+
+        if self.always_prepare_payload {
+            return Ok(());
+        }
+
+        if !preparation.proposer_registered {
+            return Err(DoNotPrepare::ProposerNotRegistered);
+        }
+
+        if let Some(window_epochs) = self.window_epochs {
+            let latest_local_proposal_epoch = preparation.latest_local_proposal_epoch;
+            if latest_local_proposal_epoch.map_or(true, |epoch| {
+                epoch + window_epochs < preparation.proposal_epoch
+            }) {
+                return Err(DoNotPrepare::NoRecentLocalProposals {
+                    latest_local_proposal_epoch,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preparation(
+        proposer_registered: bool,
+        latest_local_proposal_epoch: Option<u64>,
+    ) -> PayloadPreparation {
+        PayloadPreparation {
+            proposal_slot: Slot::new(3200),
+            proposal_epoch: Epoch::new(100),
+            proposer_index: 7,
+            proposer_registered,
+            latest_local_proposal_epoch: latest_local_proposal_epoch.map(Epoch::new),
+        }
+    }
+
+    #[test]
+    fn default_policy_prepares_registered_proposers() {
+        let policy = ChainConfigPayloadAttributesPolicy::new(&ChainConfig::default());
+
+        assert!(!policy.prepare_unregistered_proposers());
+        assert_eq!(policy.check_preparation(&preparation(true, None)), Ok(()));
+        assert_eq!(
+            policy.check_preparation(&preparation(false, None)),
+            Err(DoNotPrepare::ProposerNotRegistered)
+        );
+    }
+
+    #[test]
+    fn always_prepare_payload() {
+        let policy = ChainConfigPayloadAttributesPolicy::new(&ChainConfig {
+            always_prepare_payload: true,
+            ..ChainConfig::default()
+        });
+
+        assert!(policy.prepare_unregistered_proposers());
+        assert_eq!(policy.check_preparation(&preparation(false, None)), Ok(()));
+    }
+
+    #[test]
+    fn suppressed() {
+        let policy = ChainConfigPayloadAttributesPolicy::new(&ChainConfig {
+            suppress_payload_attributes: true,
+            ..ChainConfig::default()
+        });
+
+        assert!(!policy.prepare_unregistered_proposers());
+        assert_eq!(
+            policy.check_preparation(&preparation(true, Some(100))),
+            Err(DoNotPrepare::Suppressed)
+        );
+    }
+
+    #[test]
+    fn window_of_local_proposals() {
+        let policy = ChainConfigPayloadAttributesPolicy::new(&ChainConfig {
+            payload_attributes_window_epochs: Some(10),
+            ..ChainConfig::default()
+        });
+
+        assert_eq!(
+            policy.check_preparation(&preparation(true, Some(90))),
+            Ok(())
+        );
+        assert_eq!(
+            policy.check_preparation(&preparation(true, Some(89))),
+            Err(DoNotPrepare::NoRecentLocalProposals {
+                latest_local_proposal_epoch: Some(Epoch::new(89))
+            })
+        );
+        assert_eq!(
+            policy.check_preparation(&preparation(true, None)),
+            Err(DoNotPrepare::NoRecentLocalProposals {
+                latest_local_proposal_epoch: None
+            })
+        );
+        assert_eq!(
+            policy.check_preparation(&preparation(false, Some(100))),
+            Err(DoNotPrepare::ProposerNotRegistered)
+        );
+    }
+}
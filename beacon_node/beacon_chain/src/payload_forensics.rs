@@ -0,0 +1,265 @@
+//! Writes a forensic bundle whenever the execution engine returns INVALID for the payload of a
+//! block, either from `engine_newPayload` or from `engine_forkchoiceUpdated` for the head block, so
+//! that invalid block incidents can be reported with full context.
+//!
+//! Each bundle is a directory named `<unix seconds>_<slot>_<block root>` in the forensics
+//! directory, containing the files in `BUNDLE_FILES`:
+//!
+//! - `block.ssz` and `block.json`: the signed block.
+//! - `parent.json`: fork choice's record of the parent block, including its execution status.
+//! - `engine_exchange.json`: the JSON-RPC requests sent to the execution engine while processing
+//!   the payload, exactly as sent, and the responses, including the endpoint of the engine which
+//!   answered. The `params_hash` matches the record in the engine call recording, if enabled.
+//! - `fork_choice.json`: a snapshot of the fork choice proto-array, taken before any blocks were
+//!   invalidated as a result of the response.
+//!
+//! Only the most recent `MAX_BUNDLES` bundles are kept. Other entries in the forensics directory
+//! are never listed nor deleted.
+use crate::{BeaconChain, BeaconChainTypes};
+use execution_layer::recorder::EngineCallRecord;
+use proto_array::{core::ProtoArray, ExecutionStatus};
+use serde::Serialize;
+use slog::{error, info};
+use ssz::Encode;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{Checkpoint, Hash256, SignedBeaconBlock, Slot};
+
+/// The number of bundles to keep, after which the oldest are deleted.
+pub const MAX_BUNDLES: usize = 64;
+
+/// The files written to each bundle.
+pub const BUNDLE_FILES: &[&str] = &[
+    "block.ssz",
+    "block.json",
+    "parent.json",
+    "engine_exchange.json",
+    "fork_choice.json",
+];
+
+/// Fork choice's record of the parent of the invalid block.
+#[derive(Serialize)]
+struct ParentInfo {
+    block_root: Hash256,
+    /// `None` if the parent is unknown to fork choice.
+    slot: Option<Slot>,
+    state_root: Option<Hash256>,
+    execution_status: Option<ExecutionStatus>,
+    justified_checkpoint: Option<Checkpoint>,
+    finalized_checkpoint: Option<Checkpoint>,
+}
+
+/// The contents of a bundle, gathered before it is written to disk.
+struct Bundle<E: types::EthSpec> {
+    block: Arc<SignedBeaconBlock<E>>,
+    parent: ParentInfo,
+    engine_exchange: Vec<EngineCallRecord>,
+    fork_choice: ProtoArray,
+}
+
+/// Writes a forensic bundle for `block`, whose payload the execution engine responded to with
+/// INVALID or INVALID_BLOCK_HASH in the captured `engine_exchange`.
+///
+/// The bundle is gathered immediately, so that the fork choice snapshot precedes any invalidation,
+/// and written to disk on a blocking thread. Does nothing if no forensics directory is configured.
+pub fn write_forensic_bundle<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    block: Arc<SignedBeaconBlock<T::EthSpec>>,
+    engine_exchange: Vec<EngineCallRecord>,
+) {
+    let parent_root = block.parent_root();
+    write_bundle(chain, parent_root, engine_exchange, move || Ok(block));
+}
+
+/// Writes a forensic bundle for the head block `head_block_root`, whose payload the execution
+/// engine responded to with INVALID or INVALID_BLOCK_HASH in the captured `engine_exchange` for
+/// `engine_forkchoiceUpdated`.
+///
+/// The block is loaded from the database on the blocking thread which writes the bundle.
+pub fn write_forensic_bundle_for_head<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    head_block_root: Hash256,
+    engine_exchange: Vec<EngineCallRecord>,
+) {
+    if chain.config.payload_forensics_dir.is_none() {
+        return;
+    }
+    let Some(parent_root) = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&head_block_root)
+        .and_then(|block| block.parent_root)
+    else {
+        error!(
+            chain.log,
+            "Unable to write payload forensics";
+            "error" => "head block unknown to fork choice",
+            "block_root" => ?head_block_root,
+        );
+        return;
+    };
+
+    let store = chain.store.clone();
+    write_bundle(chain, parent_root, engine_exchange, move || {
+        store
+            .get_full_block(&head_block_root)
+            .map_err(|e| format!("Unable to load block: {:?}", e))?
+            .map(Arc::new)
+            .ok_or_else(|| format!("Block {:?} not found", head_block_root))
+    });
+}
+
+fn write_bundle<T, F>(
+    chain: &BeaconChain<T>,
+    parent_root: Hash256,
+    engine_exchange: Vec<EngineCallRecord>,
+    load_block: F,
+) where
+    T: BeaconChainTypes,
+    F: FnOnce() -> Result<Arc<SignedBeaconBlock<T::EthSpec>>, String> + Send + 'static,
+{
+    let Some(dir) = chain.config.payload_forensics_dir.clone() else {
+        return;
+    };
+
+    let (parent, fork_choice) = {
+        let fork_choice = chain.canonical_head.fork_choice_read_lock();
+        let parent = fork_choice.get_block(&parent_root);
+        (
+            ParentInfo {
+                block_root: parent_root,
+                slot: parent.as_ref().map(|parent| parent.slot),
+                state_root: parent.as_ref().map(|parent| parent.state_root),
+                execution_status: parent.as_ref().map(|parent| parent.execution_status),
+                justified_checkpoint: parent.as_ref().map(|parent| parent.justified_checkpoint),
+                finalized_checkpoint: parent.as_ref().map(|parent| parent.finalized_checkpoint),
+            },
+            fork_choice.proto_array().core_proto_array().clone(),
+        )
+    };
+
+    let log = chain.log.clone();
+    chain.task_executor.spawn_blocking(
+        move || {
+            let result = load_block().and_then(|block| {
+                Bundle {
+                    block,
+                    parent,
+                    engine_exchange,
+                    fork_choice,
+                }
+                .write(&dir)
+                .map_err(|e| format!("{:?}", e))
+            });
+            match result {
+                Ok(path) => info!(
+                    log,
+                    "Wrote invalid payload forensics";
+                    "path" => %path.display(),
+                ),
+                Err(e) => error!(
+                    log,
+                    "Unable to write payload forensics";
+                    "error" => e,
+                    "dir" => %dir.display(),
+                ),
+            }
+        },
+        "payload_forensics",
+    );
+}
+
+impl<E: types::EthSpec> Bundle<E> {
+    /// Writes the bundle to a new directory in `dir` and deletes the oldest bundles.
+    ///
+    /// The bundle is written to a temporary directory which is then renamed, so that incomplete
+    /// bundles are never listed.
+    fn write(&self, dir: &Path) -> io::Result<PathBuf> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs());
+        let name = format!(
+            "{:010}_{}_{:?}",
+            timestamp,
+            self.block.slot(),
+            self.block.canonical_root()
+        );
+        let tmp_path = dir.join(format!(".{name}"));
+        let path = dir.join(&name);
+
+        fs::create_dir_all(&tmp_path)?;
+        fs::write(tmp_path.join("block.ssz"), self.block.as_ssz_bytes())?;
+        write_json(&tmp_path.join("block.json"), &self.block)?;
+        write_json(&tmp_path.join("parent.json"), &self.parent)?;
+        write_json(
+            &tmp_path.join("engine_exchange.json"),
+            &self.engine_exchange,
+        )?;
+        write_json(&tmp_path.join("fork_choice.json"), &self.fork_choice)?;
+        fs::rename(&tmp_path, &path)?;
+
+        let bundles = list_bundles(dir)?;
+        for old in bundles
+            .iter()
+            .take(bundles.len().saturating_sub(MAX_BUNDLES))
+        {
+            fs::remove_dir_all(dir.join(old))?;
+        }
+
+        Ok(path)
+    }
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let bytes = serde_json::to_vec_pretty(value)?;
+    fs::write(path, bytes)
+}
+
+/// Returns the names of the bundles in `dir`, oldest first.
+pub fn list_bundles(dir: &Path) -> io::Result<Vec<String>> {
+    let mut bundles = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| is_bundle_name(name))
+            .collect::<Vec<_>>(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+        Err(e) => return Err(e),
+    };
+    bundles.sort();
+    Ok(bundles)
+}
+
+/// Returns `true` if `name` is a bundle name, i.e. `<unix seconds>_<slot>_<block root>`.
+fn is_bundle_name(name: &str) -> bool {
+    let is_decimal = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match name.split('_').collect::<Vec<_>>().as_slice() {
+        [timestamp, slot, block_root] => {
+            timestamp.len() >= 10
+                && is_decimal(timestamp)
+                && is_decimal(slot)
+                && block_root.len() == 66
+                && block_root.starts_with("0x")
+                && block_root[2..].bytes().all(|b| b.is_ascii_hexdigit())
+        }
+        _ => false,
+    }
+}
+
+/// Reads `file` from the bundle named `bundle` in `dir`.
+///
+/// Returns `Ok(None)` if there is no such bundle or `file` isn't one of the `BUNDLE_FILES`.
+pub fn read_bundle_file(dir: &Path, bundle: &str, file: &str) -> io::Result<Option<Vec<u8>>> {
+    if !BUNDLE_FILES.contains(&file) || !list_bundles(dir)?.iter().any(|name| name == bundle) {
+        return Ok(None);
+    }
+    match fs::read(dir.join(bundle).join(file)) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
@@ -0,0 +1,79 @@
+//! Persists the head state so that it can be loaded on restart without replaying blocks.
+//!
+//! Without this, the head state is loaded on restart by replaying the blocks since the last epoch
+//! boundary state in the hot database, after which all of its caches must be rebuilt from scratch.
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, StoreItem};
+use types::{BeaconState, ChainSpec, CommitteeCache, EthSpec, Hash256};
+
+/// The key of the persisted head state in the `BeaconChain` column.
+///
+/// Distinct from `BEACON_CHAIN_DB_KEY`, which uses the same column.
+pub const HEAD_STATE_DB_KEY: Hash256 = Hash256::repeat_byte(1);
+
+#[derive(Encode, Decode)]
+pub struct PersistedHeadState {
+    pub block_root: Hash256,
+    pub state_root: Hash256,
+    /// The SSZ bytes of the state, which can only be decoded with the `ChainSpec`.
+    pub state_bytes: Vec<u8>,
+    /// The previous, current and next epoch committee caches of the state.
+    pub committee_caches: Vec<CommitteeCache>,
+}
+
+impl PersistedHeadState {
+    pub fn new<E: EthSpec>(
+        block_root: Hash256,
+        state_root: Hash256,
+        state: &BeaconState<E>,
+    ) -> Self {
+        Self {
+            block_root,
+            state_root,
+            state_bytes: state.as_ssz_bytes(),
+            committee_caches: state
+                .committee_caches()
+                .iter()
+                .map(|cache| (**cache).clone())
+                .collect(),
+        }
+    }
+
+    /// Decodes the state, restoring its committee caches.
+    ///
+    /// The tree hash cache and the remaining caches must be built by the caller.
+    pub fn into_state<E: EthSpec>(self, spec: &ChainSpec) -> Result<BeaconState<E>, String> {
+        let mut state = BeaconState::from_ssz_bytes(&self.state_bytes, spec)
+            .map_err(|e| format!("Unable to decode persisted head state: {:?}", e))?;
+
+        let committee_caches = state.committee_caches_mut();
+        if self.committee_caches.len() != committee_caches.len() {
+            return Err(format!(
+                "Persisted head state has {} committee caches, expected {}",
+                self.committee_caches.len(),
+                committee_caches.len()
+            ));
+        }
+        for (cache, persisted_cache) in committee_caches.iter_mut().zip(self.committee_caches) {
+            *cache = Arc::new(persisted_cache);
+        }
+
+        Ok(state)
+    }
+}
+
+impl StoreItem for PersistedHeadState {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconChain
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Self::from_ssz_bytes(bytes).map_err(Into::into)
+    }
+}
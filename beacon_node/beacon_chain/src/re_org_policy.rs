@@ -0,0 +1,160 @@
+//! Provides an extension point for the proposer re-org (late block re-org) logic.
+//!
+//! The weight-based conditions for a re-org are checked by fork choice. A `ReOrgPolicy` decides the
+//! latest time at which a re-orging block may be proposed, and may veto any re-org which passes
+//! the fork choice conditions. The default `ChainConfigReOrgPolicy` is derived from the
+//! `ChainConfig`, while alternative policies can be supplied to the `BeaconChainBuilder`.
+use crate::ChainConfig;
+use proto_array::DoNotReOrg;
+use std::collections::HashSet;
+use std::time::Duration;
+use types::{ChainSpec, Epoch, ForkName, Hash256, Slot};
+
+/// A proposer re-org which has passed the fork choice conditions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReOrgCandidate {
+    /// The slot of the block which would be proposed.
+    pub proposal_slot: Slot,
+    pub proposal_epoch: Epoch,
+    /// The block which would be re-orged.
+    pub head_block_root: Hash256,
+    pub head_slot: Slot,
+    /// The proposer of the block which would be re-orged, if known.
+    pub head_proposer_index: Option<u64>,
+    /// The block which would become the parent of the proposal.
+    pub parent_block_root: Hash256,
+}
+
+/// Decides whether a proposer re-org which passes the fork choice conditions may be attempted.
+pub trait ReOrgPolicy: Send + Sync {
+    /// The latest delay from the start of the slot at which to propose a re-orging block.
+    fn re_org_cutoff(&self, spec: &ChainSpec) -> Duration;
+
+    /// Returns `Ok(())` if the `candidate` re-org may be attempted, or
+    /// `DoNotReOrg::RejectedByPolicy` with the reason for vetoing it.
+    fn check_re_org(&self, candidate: &ReOrgCandidate, spec: &ChainSpec) -> Result<(), DoNotReOrg>;
+}
+
+/// The default re-org policy, configured by the `ChainConfig`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainConfigReOrgPolicy {
+    cutoff_millis: Option<u64>,
+    fork_boundary_epochs: Option<u64>,
+    protected_proposers: HashSet<u64>,
+}
+
+impl ChainConfigReOrgPolicy {
+    pub fn new(config: &ChainConfig) -> Self {
+        Self {
+            cutoff_millis: config.re_org_cutoff_millis,
+            fork_boundary_epochs: config.re_org_fork_boundary_epochs,
+            protected_proposers: config.re_org_protected_proposers.iter().copied().collect(),
+        }
+    }
+
+    /// Returns the epoch of a scheduled fork within `fork_boundary_epochs` of `epoch`, if any.
+    fn nearby_fork_epoch(&self, epoch: Epoch, spec: &ChainSpec) -> Option<Epoch> {
+        let margin = self.fork_boundary_epochs?;
+        ForkName::list_all()
+            .into_iter()
+            .filter_map(|fork_name| spec.fork_epoch(fork_name))
+            // Forks at genesis don't have a boundary worth protecting.
+            .filter(|fork_epoch| *fork_epoch != 0)
+            .find(|fork_epoch| fork_epoch.as_u64().abs_diff(epoch.as_u64()) <= margin)
+    }
+}
+
+impl ReOrgPolicy for ChainConfigReOrgPolicy {
+    fn re_org_cutoff(&self, spec: &ChainSpec) -> Duration {
+        ChainConfig::re_org_cutoff_from_millis(self.cutoff_millis, spec.seconds_per_slot)
+    }
+
+    fn check_re_org(&self, candidate: &ReOrgCandidate, spec: &ChainSpec) -> Result<(), DoNotReOrg> {
+        if let Some(fork_epoch) = self.nearby_fork_epoch(candidate.proposal_epoch, spec) {
+            return Err(DoNotReOrg::RejectedByPolicy {
+                reason: format!("too close to fork at epoch {fork_epoch}"),
+            });
+        }
+
+        if let Some(proposer_index) = candidate
+            .head_proposer_index
+            .filter(|index| self.protected_proposers.contains(index))
+        {
+            return Err(DoNotReOrg::RejectedByPolicy {
+                reason: format!("head proposer {proposer_index} is protected from re-orgs"),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{EthSpec, MainnetEthSpec};
+
+    fn candidate(proposal_epoch: u64, head_proposer_index: Option<u64>) -> ReOrgCandidate {
+        let proposal_epoch = Epoch::new(proposal_epoch);
+        let proposal_slot = proposal_epoch.start_slot(MainnetEthSpec::slots_per_epoch()) + 1;
+        ReOrgCandidate {
+            proposal_slot,
+            proposal_epoch,
+            head_block_root: Hash256::repeat_byte(1),
+            head_slot: proposal_slot - 1,
+            head_proposer_index,
+            parent_block_root: Hash256::repeat_byte(2),
+        }
+    }
+
+    #[test]
+    fn default_policy_allows_re_orgs() {
+        let spec = MainnetEthSpec::default_spec();
+        let policy = ChainConfigReOrgPolicy::new(&ChainConfig::default());
+
+        assert_eq!(policy.check_re_org(&candidate(100, Some(7)), &spec), Ok(()));
+        assert_eq!(
+            policy.re_org_cutoff(&spec),
+            ChainConfig::default().re_org_cutoff(spec.seconds_per_slot)
+        );
+    }
+
+    #[test]
+    fn near_fork_boundary() {
+        let mut spec = MainnetEthSpec::default_spec();
+        spec.altair_fork_epoch = Some(Epoch::new(100));
+        let policy = ChainConfigReOrgPolicy::new(&ChainConfig {
+            re_org_fork_boundary_epochs: Some(1),
+            ..ChainConfig::default()
+        });
+
+        for epoch in [99, 100, 101] {
+            assert_eq!(
+                policy.check_re_org(&candidate(epoch, None), &spec),
+                Err(DoNotReOrg::RejectedByPolicy {
+                    reason: "too close to fork at epoch 100".to_string()
+                })
+            );
+        }
+        assert_eq!(policy.check_re_org(&candidate(98, None), &spec), Ok(()));
+        assert_eq!(policy.check_re_org(&candidate(102, None), &spec), Ok(()));
+    }
+
+    #[test]
+    fn protected_proposers() {
+        let spec = MainnetEthSpec::default_spec();
+        let policy = ChainConfigReOrgPolicy::new(&ChainConfig {
+            re_org_protected_proposers: vec![7, 9],
+            ..ChainConfig::default()
+        });
+
+        assert_eq!(
+            policy.check_re_org(&candidate(100, Some(7)), &spec),
+            Err(DoNotReOrg::RejectedByPolicy {
+                reason: "head proposer 7 is protected from re-orgs".to_string()
+            })
+        );
+        assert_eq!(policy.check_re_org(&candidate(100, Some(8)), &spec), Ok(()));
+        assert_eq!(policy.check_re_org(&candidate(100, None), &spec), Ok(()));
+    }
+}
@@ -0,0 +1,73 @@
+use crate::beacon_chain::{BeaconChainTypes, ETH1_CACHE_DB_KEY};
+use crate::eth1_chain::SszEth1;
+use eth1::{SszEth1CacheV13, SszEth1CacheV23};
+use slog::{debug, info, Logger};
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{Error, HotColdDB, KeyValueStoreOp, StoreItem};
+
+pub fn upgrade_to_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v22 to v23");
+
+    // Load a V13 eth1 cache and transform it to V23. The deposit cursors are re-established by the
+    // first deposit cache update.
+    update_eth1_cache::<T>(db, log, |bytes| {
+        let SszEth1CacheV13 {
+            block_cache,
+            deposit_cache,
+            last_processed_block,
+        } = SszEth1CacheV13::from_ssz_bytes(bytes)?;
+        Ok(SszEth1CacheV23 {
+            block_cache,
+            deposit_cache,
+            last_processed_block,
+            deposit_cursors: vec![],
+        }
+        .as_ssz_bytes())
+    })
+}
+
+pub fn downgrade_from_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v23 to v22");
+
+    // Load a V23 eth1 cache and transform it to V13, dropping the deposit cursors.
+    update_eth1_cache::<T>(db, log, |bytes| {
+        let SszEth1CacheV23 {
+            block_cache,
+            deposit_cache,
+            last_processed_block,
+            deposit_cursors: _,
+        } = SszEth1CacheV23::from_ssz_bytes(bytes)?;
+        Ok(SszEth1CacheV13 {
+            block_cache,
+            deposit_cache,
+            last_processed_block,
+        }
+        .as_ssz_bytes())
+    })
+}
+
+fn update_eth1_cache<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+    convert: impl FnOnce(&[u8]) -> Result<Vec<u8>, Error>,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    let Some(mut persisted) = db.get_item::<SszEth1>(&ETH1_CACHE_DB_KEY)? else {
+        debug!(log, "Nothing to do, no eth1 cache stored");
+        return Ok(vec![]);
+    };
+    // The dummy backend doesn't persist a cache.
+    if persisted.backend_bytes.is_empty() {
+        debug!(log, "Nothing to do, eth1 cache is empty");
+        return Ok(vec![]);
+    }
+
+    persisted.backend_bytes = convert(&persisted.backend_bytes)?;
+    Ok(vec![persisted.as_kv_store_op(ETH1_CACHE_DB_KEY)])
+}
@@ -0,0 +1,188 @@
+//! Provides a routine which pre-computes attester shufflings during the quiet part of each slot.
+//!
+//! The first attestation and aggregation duties requests after an epoch transition often require
+//! a fresh committee cache, which can take several hundred milliseconds to compute. This routine
+//! computes the shufflings for the current and next epoch of the canonical head and a small number
+//! of recent alternative heads ahead of time, so they are already present in the
+//! `ShufflingCache` when they are needed.
+//!
+//! Shufflings are keyed by their decision root, so heads which share a decision block only cause
+//! a single computation.
+use crate::shuffling_cache::BlockShufflingIds;
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use slog::{debug, error};
+use slot_clock::SlotClock;
+use std::collections::HashSet;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{AttestationShufflingId, Epoch, EthSpec, Hash256, Slot};
+
+/// The fraction of the slot at which to warm the shuffling cache (half-way through the slot).
+///
+/// This falls between the attestation and aggregation deadlines and before the state advance
+/// timer, which is typically a quiet part of the slot.
+const WARMING_SLOT_OFFSET_DENOMINATOR: u32 = 2;
+
+/// The maximum number of non-canonical heads to warm shufflings for.
+const MAX_ALTERNATIVE_HEADS: usize = 2;
+
+/// If the head is more than `MAX_WARMING_DISTANCE` slots behind the current slot then don't warm
+/// the cache, since the node is most likely syncing.
+const MAX_WARMING_DISTANCE: u64 = 4;
+
+/// Spawns a routine which warms the shuffling cache once per slot.
+///
+/// The routine will not be started if shuffling cache warming is disabled in the chain config.
+pub fn start_shuffling_cache_warmer<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    if chain.config.shuffling_cache_warming {
+        executor.clone().spawn(
+            async move { shuffling_cache_warmer(executor, chain).await },
+            "shuffling_cache_warmer",
+        );
+    }
+}
+
+/// Loop indefinitely, warming the shuffling cache part-way through each slot.
+async fn shuffling_cache_warmer<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    let slot_duration = chain.slot_clock.slot_duration();
+
+    loop {
+        match chain.slot_clock.duration_to_next_slot() {
+            Some(duration) => {
+                sleep(duration + slot_duration / WARMING_SLOT_OFFSET_DENOMINATOR).await;
+
+                let inner_chain = chain.clone();
+                executor.spawn_blocking(
+                    move || {
+                        let Ok(current_slot) = inner_chain.slot() else {
+                            debug!(inner_chain.log, "No slot for shuffling cache warmer");
+                            return;
+                        };
+                        let _timer = metrics::start_timer(&metrics::SHUFFLING_CACHE_WARMING_TIMES);
+                        let warmed = warm_shuffling_cache(&inner_chain, current_slot);
+                        if warmed > 0 {
+                            debug!(
+                                inner_chain.log,
+                                "Warmed shuffling cache";
+                                "shufflings" => warmed,
+                                "slot" => current_slot,
+                            );
+                        }
+                    },
+                    "shuffling_cache_warmer",
+                );
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
+
+/// Computes any missing shufflings for the current and next epoch of the candidate heads.
+///
+/// Returns the number of shufflings which were computed.
+pub fn warm_shuffling_cache<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    current_slot: Slot,
+) -> usize {
+    // Don't warm the cache whilst syncing.
+    if chain.best_slot() + MAX_WARMING_DISTANCE < current_slot {
+        return 0;
+    }
+
+    let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
+    let mut seen = HashSet::new();
+    let mut warmed = 0;
+
+    for head_block_root in candidate_heads(chain, current_epoch) {
+        for shuffling_epoch in [current_epoch, current_epoch + 1] {
+            let Some(shuffling_id) = shuffling_id_for_head(chain, head_block_root, shuffling_epoch)
+            else {
+                continue;
+            };
+
+            // Heads which share a decision block share a shuffling.
+            if !seen.insert(shuffling_id.clone())
+                || chain.shuffling_cache.read().contains(&shuffling_id)
+            {
+                continue;
+            }
+
+            match chain.with_committee_cache(head_block_root, shuffling_epoch, |_, _| Ok(())) {
+                Ok(()) => {
+                    metrics::inc_counter(&metrics::SHUFFLING_CACHE_WARMED);
+                    warmed += 1;
+                }
+                Err(e) => debug!(
+                    chain.log,
+                    "Unable to warm shuffling cache";
+                    "error" => ?e,
+                    "head_block_root" => ?head_block_root,
+                    "shuffling_epoch" => shuffling_epoch,
+                ),
+            }
+        }
+    }
+
+    warmed
+}
+
+/// Returns the canonical head followed by the most recent alternative heads which are still
+/// present in fork choice and are not too old to serve the current epoch.
+fn candidate_heads<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    current_epoch: Epoch,
+) -> Vec<Hash256> {
+    let canonical_head = chain.canonical_head.cached_head().head_block_root();
+    let min_epoch = current_epoch.saturating_sub(1_u64);
+
+    let mut alternatives = chain
+        .heads()
+        .into_iter()
+        .filter(|(root, slot)| {
+            *root != canonical_head && slot.epoch(T::EthSpec::slots_per_epoch()) >= min_epoch
+        })
+        .collect::<Vec<_>>();
+    alternatives.sort_unstable_by(|(_, a), (_, b)| b.cmp(a));
+
+    let fork_choice = chain.canonical_head.fork_choice_read_lock();
+    std::iter::once(canonical_head)
+        .chain(
+            alternatives
+                .into_iter()
+                .map(|(root, _)| root)
+                // Only heads which descend from finality are present in fork choice.
+                .filter(|root| fork_choice.contains_block(root))
+                .take(MAX_ALTERNATIVE_HEADS),
+        )
+        .collect()
+}
+
+fn shuffling_id_for_head<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    head_block_root: Hash256,
+    shuffling_epoch: Epoch,
+) -> Option<AttestationShufflingId> {
+    let head_block = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&head_block_root)?;
+
+    BlockShufflingIds {
+        current: head_block.current_epoch_shuffling_id,
+        next: head_block.next_epoch_shuffling_id,
+        previous: None,
+        block_root: head_block.root,
+    }
+    .id_for_epoch(shuffling_epoch)
+}
@@ -472,6 +472,42 @@ fn advance_head<T: BeaconChainTypes>(
         .put_state_possibly_temporary(&advanced_state_root, &state, temporary)?;
     drop(txn_lock);
 
+    // Periodically persist the state advanced into a new epoch, so that a restart after a crash
+    // only needs to replay the blocks since. Encoding and writing the full state is slow, so it's
+    // done on a separate blocking task rather than delaying the state advance.
+    if let Some(interval) = beacon_chain
+        .config
+        .fast_restart_interval_epochs
+        .filter(|_| beacon_chain.config.fast_restart)
+    {
+        let epoch = state.current_epoch();
+        if initial_epoch < epoch && epoch % interval == 0 {
+            let chain = beacon_chain.clone();
+            let log = log.clone();
+            beacon_chain.task_executor.spawn_blocking(
+                move || match chain.persist_state_for_restart(
+                    head_block_root,
+                    advanced_state_root,
+                    &state,
+                ) {
+                    Ok(()) => debug!(
+                        log,
+                        "Persisted head state for restart";
+                        "head_block_root" => ?head_block_root,
+                        "epoch" => epoch,
+                    ),
+                    Err(e) => warn!(
+                        log,
+                        "Failed to persist head state for restart";
+                        "error" => ?e,
+                        "head_block_root" => ?head_block_root,
+                    ),
+                },
+                "persist_head_state_for_restart",
+            );
+        }
+    }
+
     debug!(
         log,
         "Completed state advance";
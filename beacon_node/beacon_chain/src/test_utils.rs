@@ -726,15 +726,13 @@ where
         self.mock_builder = Some(Arc::new(mock_builder));
 
         // Sanity check.
-        let el_builder = self
-            .chain
-            .execution_layer
-            .as_ref()
-            .unwrap()
-            .builder()
-            .unwrap();
-        let mock_el_builder = mock_el.el.builder().unwrap();
-        assert!(Arc::ptr_eq(&el_builder, &mock_el_builder));
+        let el_builders = self.chain.execution_layer.as_ref().unwrap().builders();
+        let mock_el_builders = mock_el.el.builders();
+        assert_eq!(el_builders.len(), 1);
+        assert!(el_builders
+            .iter()
+            .zip(&mock_el_builders)
+            .all(|(a, b)| Arc::ptr_eq(a, b)));
 
         mock_builder_server
     }
@@ -14,7 +14,7 @@ use state_processing::common::get_attestation_participation_flag_indices;
 use state_processing::per_epoch_processing::{
     errors::EpochProcessingError, EpochProcessingSummary,
 };
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io;
 use std::marker::PhantomData;
 use std::str::Utf8Error;
@@ -22,11 +22,12 @@ use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use store::AbstractExecPayload;
 use types::consts::altair::{
-    TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX, TIMELY_TARGET_FLAG_INDEX,
+    TIMELY_HEAD_FLAG_INDEX, TIMELY_HEAD_WEIGHT, TIMELY_SOURCE_FLAG_INDEX, TIMELY_SOURCE_WEIGHT,
+    TIMELY_TARGET_FLAG_INDEX, TIMELY_TARGET_WEIGHT,
 };
 use types::{
     Attestation, AttestationData, AttesterSlashingRef, BeaconBlockRef, BeaconState,
-    BeaconStateError, ChainSpec, Epoch, EthSpec, Hash256, IndexedAttestation,
+    BeaconStateError, ChainSpec, Epoch, EthSpec, Graffiti, Hash256, IndexedAttestation,
     IndexedAttestationRef, ProposerSlashing, PublicKeyBytes, SignedAggregateAndProof,
     SignedContributionAndProof, Slot, SyncCommitteeMessage, VoluntaryExit,
 };
@@ -37,6 +38,21 @@ use types::{
 /// https://github.com/sigp/lighthouse/pull/3728#issuecomment-1375173063
 const TOTAL_LABEL: &str = "total";
 
+/// Used for Prometheus labels when a validator's client has not been fingerprinted.
+const UNKNOWN_CLIENT_LABEL: &str = "unknown";
+
+/// Known consensus clients and the graffiti fragments which identify them.
+///
+/// The two-letter codes are those used by the client version graffiti, e.g. `GE1234LH5678`.
+const CLIENT_GRAFFITI_FINGERPRINTS: &[(&str, &[&str])] = &[
+    ("lighthouse", &["lighthouse", "LH"]),
+    ("teku", &["teku", "TK"]),
+    ("prysm", &["prysm", "PM"]),
+    ("nimbus", &["nimbus", "NB"]),
+    ("lodestar", &["lodestar", "LS"]),
+    ("grandine", &["grandine", "GR"]),
+];
+
 /// The validator monitor collects per-epoch data about each monitored validator. Historical data
 /// will be kept around for `HISTORIC_EPOCHS` before it is pruned.
 pub const HISTORIC_EPOCHS: usize = 10;
@@ -46,6 +62,12 @@ pub const HISTORIC_EPOCHS: usize = 10;
 /// Prometheus cardinality and log volume.
 const DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD: usize = 64;
 
+/// The smallest bucket of validators that metrics may be aggregated into.
+///
+/// Smaller buckets approach the cardinality of per-validator metrics, which aggregation exists to
+/// avoid.
+pub const MIN_AGGREGATE_BUCKET_SIZE: u64 = 64;
+
 /// Lag slots used in detecting missed blocks for the monitored validators
 pub const MISSED_BLOCK_LAG_SLOTS: usize = 4;
 
@@ -61,6 +83,13 @@ pub struct ValidatorMonitorConfig {
     pub auto_register: bool,
     pub validators: Vec<PublicKeyBytes>,
     pub individual_tracking_threshold: usize,
+    /// When individual tracking is disabled, additionally aggregate metrics into buckets of
+    /// validators with contiguous indices of this size, which is at least
+    /// `MIN_AGGREGATE_BUCKET_SIZE`.
+    pub aggregate_bucket_size: Option<u64>,
+    /// Infer the client of each validator from the graffiti of its blocks and report missed and
+    /// late attestations per client.
+    pub client_fingerprinting: bool,
 }
 
 impl Default for ValidatorMonitorConfig {
@@ -69,6 +98,8 @@ impl Default for ValidatorMonitorConfig {
             auto_register: false,
             validators: vec![],
             individual_tracking_threshold: DEFAULT_INDIVIDUAL_TRACKING_THRESHOLD,
+            aggregate_bucket_size: None,
+            client_fingerprinting: false,
         }
     }
 }
@@ -82,6 +113,51 @@ pub const UNAGGREGATED_ATTESTATION_LAG_SLOTS: usize = 16;
 /// from the current and previous epoch.
 pub const MAX_UNAGGREGATED_ATTESTATION_HASHMAP_LENGTH: usize = 64;
 
+/// The number of epochs for which the effectiveness of simulated attestations is retained.
+pub const SIMULATED_ATTESTATION_EFFECTIVENESS_EPOCHS: usize = 4;
+
+/// The sum of the participation flag weights earned by a timely source, target and head vote.
+const MAX_ATTESTATION_WEIGHT: u64 =
+    TIMELY_SOURCE_WEIGHT + TIMELY_TARGET_WEIGHT + TIMELY_HEAD_WEIGHT;
+
+/// The attestation rewards earned over an epoch, relative to the maximum attainable.
+///
+/// Computed both for the attestations of the attestation simulator, which reflect the rewards
+/// that a perfect validator would have earned given the blocks which made it on chain, and for the
+/// monitored validators. A shortfall in the former indicates a network-level issue (e.g., late or
+/// missing blocks), whilst a shortfall in only the latter indicates an issue with the monitored
+/// validators.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AttestationEffectiveness {
+    /// The number of attestations that were expected.
+    pub attestations: u64,
+    /// The sum of the participation flag weights earned by those attestations.
+    pub earned_weight: u64,
+}
+
+impl AttestationEffectiveness {
+    fn register(&mut self, head_hit: bool, target_hit: bool, source_hit: bool) {
+        self.attestations += 1;
+        for (hit, weight) in [
+            (source_hit, TIMELY_SOURCE_WEIGHT),
+            (target_hit, TIMELY_TARGET_WEIGHT),
+            (head_hit, TIMELY_HEAD_WEIGHT),
+        ] {
+            if hit {
+                self.earned_weight += weight;
+            }
+        }
+    }
+
+    /// Returns the percentage of the maximum attestation reward that was earned, or `None` if no
+    /// attestations were expected.
+    pub fn percentage(&self) -> Option<f64> {
+        (self.attestations > 0).then(|| {
+            self.earned_weight as f64 * 100.0 / (self.attestations * MAX_ATTESTATION_WEIGHT) as f64
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     InvalidPubkey(String),
@@ -287,6 +363,8 @@ pub struct MonitoredValidator {
     pub summaries: RwLock<SummaryMap>,
     /// Validator metrics to be exposed over the HTTP API.
     pub metrics: RwLock<ValidatorMetrics>,
+    /// The client inferred from the graffiti of the validator's most recent block.
+    pub client: RwLock<Option<&'static str>>,
 }
 
 impl MonitoredValidator {
@@ -298,6 +376,7 @@ impl MonitoredValidator {
             index,
             summaries: <_>::default(),
             metrics: <_>::default(),
+            client: <_>::default(),
         }
     }
 
@@ -395,12 +474,19 @@ pub struct ValidatorMonitor<E: EthSpec> {
     /// large validator counts causing infeasibly high cardinailty for
     /// Prometheus and high log volumes.
     individual_tracking_threshold: usize,
+    /// The size of the index buckets used to aggregate metrics when individual tracking is
+    /// disabled.
+    aggregate_bucket_size: Option<u64>,
+    /// If true, report missed and late attestations per client inferred from block graffiti.
+    client_fingerprinting: bool,
     /// A Map representing the (non-finalized) missed blocks by epoch, validator_index(state.validators) and slot
     missed_blocks: HashSet<MissedBlock>,
     // A beacon proposer cache
     beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>>,
     // Unaggregated attestations generated by the committee index at each slot.
     unaggregated_attestations: HashMap<Slot, Attestation<E>>,
+    // The effectiveness of the evaluated unaggregated attestations, by the epoch of their slot.
+    simulated_attestation_effectiveness: BTreeMap<Epoch, AttestationEffectiveness>,
     log: Logger,
     _phantom: PhantomData<E>,
 }
@@ -415,6 +501,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             auto_register,
             validators,
             individual_tracking_threshold,
+            aggregate_bucket_size,
+            client_fingerprinting,
         } = config;
 
         let mut s = Self {
@@ -422,9 +510,12 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             indices: <_>::default(),
             auto_register,
             individual_tracking_threshold,
+            aggregate_bucket_size,
+            client_fingerprinting,
             missed_blocks: <_>::default(),
             beacon_proposer_cache,
             unaggregated_attestations: <_>::default(),
+            simulated_attestation_effectiveness: <_>::default(),
             log,
             _phantom: PhantomData,
         };
@@ -478,6 +569,34 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         self.unaggregated_attestations.get(&slot)
     }
 
+    /// Returns the effectiveness of the unaggregated attestations produced by the attestation
+    /// simulator during `epoch`, if any have been evaluated.
+    pub fn get_simulated_attestation_effectiveness(
+        &self,
+        epoch: Epoch,
+    ) -> Option<AttestationEffectiveness> {
+        self.simulated_attestation_effectiveness
+            .get(&epoch)
+            .copied()
+    }
+
+    fn register_simulated_attestation_effectiveness(
+        &mut self,
+        slot: Slot,
+        head_hit: bool,
+        target_hit: bool,
+        source_hit: bool,
+    ) {
+        let effectiveness = &mut self.simulated_attestation_effectiveness;
+        effectiveness
+            .entry(slot.epoch(E::slots_per_epoch()))
+            .or_default()
+            .register(head_hit, target_hit, source_hit);
+        while effectiveness.len() > SIMULATED_ATTESTATION_EFFECTIVENESS_EPOCHS {
+            effectiveness.pop_first();
+        }
+    }
+
     /// Reads information from the given `state`. The `state` *must* be valid (i.e, able to be
     /// imported).
     pub fn process_valid_state(
@@ -654,7 +773,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                                     };
                                     // Incr missed block counter for the validator only if it doesn't already exist in the hashset
                                     if self.missed_blocks.insert(missed_block) {
-                                        self.aggregatable_metric(&validator.id, |label| {
+                                        self.aggregatable_metric(validator, |label| {
                                             metrics::inc_counter_vec(
                                                 &metrics::VALIDATOR_MONITOR_MISSED_BLOCKS_TOTAL,
                                                 &[label],
@@ -718,9 +837,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             .cloned()
             .collect();
 
-        let unaggregated_attestations = &mut self.unaggregated_attestations;
         for slot in attested_slots {
-            if let Some(unaggregated_attestation) = unaggregated_attestations.remove(&slot) {
+            if let Some(unaggregated_attestation) = self.unaggregated_attestations.remove(&slot) {
                 // Don't process this attestation, it's too old to be processed by this state.
                 if slot.epoch(E::slots_per_epoch()) < state.previous_epoch() {
                     continue;
@@ -746,10 +864,16 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                         let source_hit = flag_indices.contains(&TIMELY_SOURCE_FLAG_INDEX);
                         register_simulated_attestation(
                             data, head_hit, target_hit, source_hit, &self.log,
-                        )
+                        );
+                        self.register_simulated_attestation_effectiveness(
+                            slot, head_hit, target_hit, source_hit,
+                        );
                     }
                     Err(BeaconStateError::IncorrectAttestationSource) => {
-                        register_simulated_attestation(data, false, false, false, &self.log)
+                        register_simulated_attestation(data, false, false, false, &self.log);
+                        self.register_simulated_attestation_effectiveness(
+                            slot, false, false, false,
+                        );
                     }
                     Err(err) => {
                         error!(
@@ -770,8 +894,8 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         }
     }
 
-    /// Run `func` with the `TOTAL_LABEL` and optionally the
-    /// `individual_id`.
+    /// Run `func` with the `TOTAL_LABEL` and optionally the `id` of the
+    /// `validator` (or its bucket label).
     ///
     /// This function is used for registering metrics that can be applied to
     /// both all validators and an indivdual validator. For example, the count
@@ -780,11 +904,39 @@ impl<E: EthSpec> ValidatorMonitor<E> {
     ///
     /// We allow disabling tracking metrics on an individual validator basis
     /// since it can result in untenable cardinality with high validator counts.
-    fn aggregatable_metric<F: Fn(&str)>(&self, individual_id: &str, func: F) {
+    /// In that case, metrics may instead be aggregated into buckets of
+    /// validators, which bounds the cardinality by the number of buckets.
+    fn aggregatable_metric<F: Fn(&str)>(&self, validator: &MonitoredValidator, func: F) {
         func(TOTAL_LABEL);
 
         if self.individual_tracking() {
-            func(individual_id);
+            func(&validator.id);
+        } else if let Some(label) = self.bucket_label(validator) {
+            func(&label);
+        }
+    }
+
+    /// Returns the label of the bucket containing `validator`, if bucketing is enabled and the
+    /// validator index is known.
+    ///
+    /// Labels are the inclusive range of indices in the bucket, e.g. `128-191`.
+    fn bucket_label(&self, validator: &MonitoredValidator) -> Option<String> {
+        let bucket_size = self.aggregate_bucket_size?.max(MIN_AGGREGATE_BUCKET_SIZE);
+        let index = validator.index?;
+        let start = index / bucket_size * bucket_size;
+        Some(format!("{}-{}", start, start + bucket_size - 1))
+    }
+
+    /// Increment `metric` for the client which `validator` was fingerprinted as running, if
+    /// client fingerprinting is enabled.
+    fn client_metric(
+        &self,
+        validator: &MonitoredValidator,
+        metric: &metrics::Result<metrics::IntCounterVec>,
+    ) {
+        if self.client_fingerprinting {
+            let client = validator.client.read().unwrap_or(UNKNOWN_CLIENT_LABEL);
+            metrics::inc_counter_vec(metric, &[client]);
         }
     }
 
@@ -799,6 +951,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         let mut head_miss = Vec::new();
         let mut target_miss = Vec::new();
         let mut suboptimal_inclusion = Vec::new();
+        let mut monitored_effectiveness = AttestationEffectiveness::default();
 
         // We subtract two from the state of the epoch that generated these summaries.
         //
@@ -835,6 +988,12 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                     continue;
                 }
 
+                monitored_effectiveness.register(
+                    previous_epoch_matched_head,
+                    previous_epoch_matched_target,
+                    previous_epoch_matched_source,
+                );
+
                 // Store some metrics directly to be re-exposed on the HTTP API.
                 let mut validator_metrics = monitored_validator.metrics.write();
                 if previous_epoch_matched_any {
@@ -858,7 +1017,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 // For Base states, this will be *any* attestation whatsoever. For Altair states,
                 // this will be any attestation that matched a "timely" flag.
                 if previous_epoch_matched_any {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_HIT,
                             &[label],
@@ -877,13 +1036,17 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                         )
                     }
                 } else {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_ATTESTER_MISS,
                             &[label],
                         );
                     });
                     attestation_miss.push(id);
+                    self.client_metric(
+                        monitored_validator,
+                        &metrics::VALIDATOR_MONITOR_CLIENT_ATTESTATION_MISS_TOTAL,
+                    );
                     if self.individual_tracking() {
                         debug!(
                             self.log,
@@ -896,14 +1059,14 @@ impl<E: EthSpec> ValidatorMonitor<E> {
 
                 // Indicates if any on-chain attestation hit the head.
                 if previous_epoch_matched_head {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_HIT,
                             &[label],
                         );
                     });
                 } else {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_HEAD_ATTESTER_MISS,
                             &[label],
@@ -922,14 +1085,14 @@ impl<E: EthSpec> ValidatorMonitor<E> {
 
                 // Indicates if any on-chain attestation hit the target.
                 if previous_epoch_matched_target {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_HIT,
                             &[label],
                         );
                     });
                 } else {
-                    self.aggregatable_metric(id, |label| {
+                    self.aggregatable_metric(monitored_validator, |label| {
                         metrics::inc_counter_vec(
                             &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ON_CHAIN_TARGET_ATTESTER_MISS,
                             &[label],
@@ -958,6 +1121,10 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 if let Some(inclusion_delay) = min_inclusion_distance {
                     if inclusion_delay > spec.min_attestation_inclusion_delay {
                         suboptimal_inclusion.push(id);
+                        self.client_metric(
+                            monitored_validator,
+                            &metrics::VALIDATOR_MONITOR_CLIENT_ATTESTATION_LATE_TOTAL,
+                        );
                         if self.individual_tracking() {
                             debug!(
                                 self.log,
@@ -1074,6 +1241,32 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             );
         }
 
+        // Compare the effectiveness of the monitored validators with that of a perfect validator,
+        // to distinguish issues with the monitored validators from network-level issues.
+        let expected_effectiveness = self
+            .get_simulated_attestation_effectiveness(prev_epoch)
+            .and_then(|effectiveness| effectiveness.percentage());
+        if let Some(expected) = expected_effectiveness {
+            metrics::set_float_gauge(
+                &metrics::VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_EXPECTED_EFFECTIVENESS,
+                expected,
+            );
+        }
+        if let Some(monitored) = monitored_effectiveness.percentage() {
+            metrics::set_float_gauge(
+                &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATION_EFFECTIVENESS,
+                monitored,
+            );
+            info!(
+                self.log,
+                "Previous epoch attestation effectiveness";
+                "epoch" => prev_epoch,
+                "monitored_validators" => format!("{:.1}%", monitored),
+                "expected" => expected_effectiveness
+                    .map_or_else(|| "unknown".to_string(), |expected| format!("{:.1}%", expected)),
+            );
+        }
+
         Ok(())
     }
 
@@ -1112,6 +1305,22 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         self.beacon_proposer_cache.clone()
     }
 
+    pub fn auto_register(&self) -> bool {
+        self.auto_register
+    }
+
+    /// Enable or disable the automatic registration of local validators.
+    pub fn set_auto_register(&mut self, auto_register: bool) {
+        if auto_register != self.auto_register {
+            info!(
+                self.log,
+                "Updated validator monitor";
+                "auto_register" => auto_register,
+            );
+        }
+        self.auto_register = auto_register;
+    }
+
     /// If `self.auto_register == true`, add the `validator_index` to `self.monitored_validators`.
     /// Otherwise, do nothing.
     pub fn auto_register_local_validator(&mut self, validator_index: u64) {
@@ -1171,7 +1380,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             let id = &validator.id;
             let delay = get_block_delay_ms(seen_timestamp, block, slot_clock);
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_BEACON_BLOCK_TOTAL,
                     &[src, label],
@@ -1194,6 +1403,12 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             );
 
             validator.with_epoch_summary(epoch, |summary| summary.register_block(delay));
+
+            if self.client_fingerprinting {
+                if let Some(client) = client_from_graffiti(block.body().graffiti()) {
+                    *validator.client.write() = Some(client);
+                }
+            }
         }
     }
 
@@ -1247,7 +1462,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_UNAGGREGATED_ATTESTATION_TOTAL,
                         &[src, label],
@@ -1335,7 +1550,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         if let Some(validator) = self.get_validator(aggregator_index) {
             let id = &validator.id;
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_AGGREGATED_ATTESTATION_TOTAL,
                     &[src, label],
@@ -1370,7 +1585,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_AGGREGATE_TOTAL,
                         &[src, label],
@@ -1454,7 +1669,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             if let Some(validator) = self.get_validator(*i) {
                 let id = &validator.id;
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_ATTESTATION_IN_BLOCK_TOTAL,
                         &["block", label],
@@ -1560,7 +1775,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 slot_clock,
             );
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGES_TOTAL,
                     &[src, label],
@@ -1648,7 +1863,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         if let Some(validator) = self.get_validator(aggregator_index) {
             let id = &validator.id;
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_SYNC_CONTRIBUTIONS_TOTAL,
                     &[src, label],
@@ -1682,7 +1897,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             if let Some(validator) = self.validators.get(validator_pubkey) {
                 let id = &validator.id;
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_CONTRIBUTION_TOTAL,
                         &[src, label],
@@ -1722,7 +1937,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             if let Some(validator) = self.validators.get(validator_pubkey) {
                 let id = &validator.id;
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_SYNC_COMMITTEE_MESSAGE_IN_BLOCK_TOTAL,
                         &["block", label],
@@ -1767,7 +1982,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
             let id = &validator.id;
             let epoch = exit.epoch;
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(&metrics::VALIDATOR_MONITOR_EXIT_TOTAL, &[src, label]);
             });
 
@@ -1810,7 +2025,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
         if let Some(validator) = self.get_validator(proposer) {
             let id = &validator.id;
 
-            self.aggregatable_metric(id, |label| {
+            self.aggregatable_metric(validator, |label| {
                 metrics::inc_counter_vec(
                     &metrics::VALIDATOR_MONITOR_PROPOSER_SLASHING_TOTAL,
                     &[src, label],
@@ -1865,7 +2080,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                 let id = &validator.id;
                 let epoch = data.slot.epoch(E::slots_per_epoch());
 
-                self.aggregatable_metric(id, |label| {
+                self.aggregatable_metric(validator, |label| {
                     metrics::inc_counter_vec(
                         &metrics::VALIDATOR_MONITOR_ATTESTER_SLASHING_TOTAL,
                         &[src, label],
@@ -1924,7 +2139,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                      * Attestations
                      */
                     if let Some(delay) = summary.attestation_min_delay {
-                        self.aggregatable_metric(id, |tag| {
+                        self.aggregatable_metric(validator, |tag| {
                             metrics::observe_timer_vec(
                                 &metrics::VALIDATOR_MONITOR_PREV_EPOCH_ATTESTATIONS_MIN_DELAY_SECONDS,
                                 &[tag],
@@ -1961,7 +2176,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                      * Sync committee messages
                      */
                     if let Some(delay) = summary.sync_committee_message_min_delay {
-                        self.aggregatable_metric(id, |tag| {
+                        self.aggregatable_metric(validator, |tag| {
                             metrics::observe_timer_vec(
                                 &metrics::VALIDATOR_MONITOR_PREV_EPOCH_SYNC_COMMITTEE_MESSAGES_MIN_DELAY_SECONDS,
                                 &[tag],
@@ -2016,7 +2231,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                         );
                     }
                     if let Some(delay) = summary.block_min_delay {
-                        self.aggregatable_metric(id, |tag| {
+                        self.aggregatable_metric(validator, |tag| {
                             metrics::observe_timer_vec(
                                 &metrics::VALIDATOR_MONITOR_PREV_EPOCH_BEACON_BLOCKS_MIN_DELAY_SECONDS,
                                 &[tag],
@@ -2035,7 +2250,7 @@ impl<E: EthSpec> ValidatorMonitor<E> {
                         );
                     }
                     if let Some(delay) = summary.aggregate_min_delay {
-                        self.aggregatable_metric(id, |tag| {
+                        self.aggregatable_metric(validator, |tag| {
                             metrics::observe_timer_vec(
                                 &metrics::VALIDATOR_MONITOR_PREV_EPOCH_AGGREGATES_MIN_DELAY_SECONDS,
                                 &[tag],
@@ -2116,6 +2331,31 @@ pub fn timestamp_now() -> Duration {
         .unwrap_or_else(|_| Duration::from_secs(0))
 }
 
+/// Attempts to infer the consensus client which produced a block from its `graffiti`.
+///
+/// Full client names are matched case-insensitively anywhere in the graffiti, whilst the
+/// two-letter client version codes are only matched as the consensus half of a client version
+/// graffiti (e.g. `GE1234LH5678`).
+pub fn client_from_graffiti(graffiti: &Graffiti) -> Option<&'static str> {
+    let graffiti = graffiti.as_utf8_lossy();
+    let lowercase = graffiti.to_lowercase();
+
+    CLIENT_GRAFFITI_FINGERPRINTS
+        .iter()
+        .find(|(_, fragments)| {
+            fragments.iter().any(|fragment| {
+                if fragment.len() == 2 {
+                    graffiti
+                        .get(6..8)
+                        .is_some_and(|code| code == *fragment && graffiti.len() >= 12)
+                } else {
+                    lowercase.contains(fragment)
+                }
+            })
+        })
+        .map(|(client, _)| *client)
+}
+
 fn u64_to_i64(n: impl Into<u64>) -> i64 {
     i64::try_from(n.into()).unwrap_or(i64::MAX)
 }
@@ -0,0 +1,95 @@
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::Duration;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{BeaconState, CollectionProofTree, EthSpec, Hash256, StateProof};
+
+/// The number of states whose registry trees are kept. Proofs are almost always requested against
+/// the head state, so this only needs to cover a head change or two.
+const CACHE_LIMIT: NonZeroUsize = new_non_zero_usize(4);
+/// The time to wait for another tree to be built before rejecting a request for an uncached tree.
+const BUILD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Cache of the Merkle trees of the validator registry, keyed by state root, from which proofs of
+/// validator records are generated.
+///
+/// Building a tree hashes every validator record, so it's cached to stop each proof request from
+/// re-hashing the registry, and at most one tree is built at a time to bound the cost of requests
+/// for many distinct states.
+pub struct ValidatorProofCache {
+    trees: Mutex<LruCache<Hash256, Arc<CollectionProofTree>>>,
+    /// Held while a tree is built.
+    build_lock: Mutex<()>,
+}
+
+impl Default for ValidatorProofCache {
+    fn default() -> Self {
+        Self {
+            trees: Mutex::new(LruCache::new(CACHE_LIMIT)),
+            build_lock: Mutex::new(()),
+        }
+    }
+}
+
+impl ValidatorProofCache {
+    /// Returns the tree of the registry of `state`, building it if it isn't cached.
+    ///
+    /// Returns `BeaconChainError::ValidatorProofTreeBusy` if another tree is being built for longer
+    /// than `BUILD_TIMEOUT`.
+    fn get_or_build<E: EthSpec>(
+        &self,
+        state_root: Hash256,
+        state: &BeaconState<E>,
+    ) -> Result<Arc<CollectionProofTree>, BeaconChainError> {
+        if let Some(tree) = self.trees.lock().get(&state_root) {
+            return Ok(tree.clone());
+        }
+
+        let _build_guard = self
+            .build_lock
+            .try_lock_for(BUILD_TIMEOUT)
+            .ok_or(BeaconChainError::ValidatorProofTreeBusy)?;
+
+        // The tree may have been built while waiting for the lock.
+        if let Some(tree) = self.trees.lock().get(&state_root) {
+            return Ok(tree.clone());
+        }
+
+        let tree = Arc::new(state.validators_proof_tree()?);
+        self.trees.lock().put(state_root, tree.clone());
+        Ok(tree)
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Returns the tree of the validator registry of `state`, whose root is `state_root`, from the
+    /// `validator_proof_cache`.
+    pub fn validators_proof_tree(
+        &self,
+        state_root: Hash256,
+        state: &BeaconState<T::EthSpec>,
+    ) -> Result<Arc<CollectionProofTree>, BeaconChainError> {
+        self.validator_proof_cache.get_or_build(state_root, state)
+    }
+
+    /// Computes proofs of the validator records at each of `indices` against `state_root`, which
+    /// must be the root of `state`.
+    pub fn validator_state_proofs(
+        &self,
+        state_root: Hash256,
+        state: &BeaconState<T::EthSpec>,
+        indices: &[usize],
+    ) -> Result<Vec<StateProof>, BeaconChainError> {
+        let validators_len = state.validators().len();
+        if let Some(&index) = indices.iter().find(|&&index| index >= validators_len) {
+            return Err(BeaconChainError::BeaconStateError(
+                types::BeaconStateError::UnknownValidator(index),
+            ));
+        }
+        let tree = self.validators_proof_tree(state_root, state)?;
+        Ok(tree.proofs(indices)?)
+    }
+}
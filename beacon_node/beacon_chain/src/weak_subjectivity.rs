@@ -0,0 +1,245 @@
+//! Provides a routine which periodically checks that the node is within its weak subjectivity
+//! period.
+//!
+//! A node whose finalized checkpoint is older than the weak subjectivity period can no longer
+//! safely determine the canonical chain from the information available on the network, since
+//! enough validators may have exited for a long-range attack to be possible. This is usually the
+//! result of a node which has been offline for a long time, or of a chain which has not finalized
+//! for a long time. Once per epoch this routine computes the weak subjectivity period from the
+//! head state's validator set as per the consensus specs, compares it to the age of the finalized
+//! checkpoint, and reports the result via logs and metrics.
+use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::WeakSubjectivityStatus;
+use slog::{crit, debug, error};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use task_executor::TaskExecutor;
+use tokio::time::sleep;
+use types::{ChainSpec, EthSpec, Unsigned};
+
+/// The `SAFETY_DECAY` from the consensus specs: the maximum tolerable loss in the one-third safety
+/// margin of FFG finality, as a percentage.
+pub const SAFETY_DECAY: u64 = 10;
+
+const ETH_TO_GWEI: u64 = 1_000_000_000;
+
+/// Don't log an outdated anchor as critical if the head is more than this many epochs behind the
+/// wall-clock epoch, since the node is most likely syncing.
+const SYNCING_TOLERANCE_EPOCHS: u64 = 2;
+
+/// Spawns a routine which audits the weak subjectivity period once per epoch.
+pub fn start_weak_subjectivity_audit<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    executor.clone().spawn(
+        async move { weak_subjectivity_audit_service(executor, chain).await },
+        "weak_subjectivity_audit",
+    );
+}
+
+/// Loop indefinitely, auditing the weak subjectivity period at the start of each epoch.
+async fn weak_subjectivity_audit_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    let slot_duration = chain.slot_clock.slot_duration();
+
+    loop {
+        match chain
+            .slot_clock
+            .duration_to_next_epoch(T::EthSpec::slots_per_epoch())
+        {
+            Some(duration) => {
+                // Wait until part-way through the first slot so that the head is likely updated.
+                sleep(duration + slot_duration / 2).await;
+
+                let inner_chain = chain.clone();
+                executor.spawn_blocking(
+                    move || audit_weak_subjectivity(&inner_chain),
+                    "weak_subjectivity_audit",
+                );
+            }
+            None => {
+                error!(chain.log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+            }
+        }
+    }
+}
+
+/// Compute the weak subjectivity status of the node and report it via logs and metrics.
+pub fn audit_weak_subjectivity<T: BeaconChainTypes>(chain: &BeaconChain<T>) {
+    let status = match weak_subjectivity_status(chain) {
+        Ok(status) => status,
+        Err(e) => {
+            debug!(
+                chain.log,
+                "Unable to compute weak subjectivity status";
+                "error" => ?e,
+            );
+            return;
+        }
+    };
+
+    metrics::set_gauge(
+        &metrics::WEAK_SUBJECTIVITY_PERIOD,
+        status.weak_subjectivity_period as i64,
+    );
+    metrics::set_gauge(
+        &metrics::WEAK_SUBJECTIVITY_ANCHOR_AGE,
+        status.anchor_age_epochs as i64,
+    );
+    metrics::set_gauge(
+        &metrics::WEAK_SUBJECTIVITY_OUTSIDE_PERIOD,
+        i64::from(!status.within_weak_subjectivity_period),
+    );
+
+    let syncing_tolerance_slots = SYNCING_TOLERANCE_EPOCHS * T::EthSpec::slots_per_epoch();
+    let is_syncing = status
+        .current_epoch
+        .start_slot(T::EthSpec::slots_per_epoch())
+        > chain.best_slot() + syncing_tolerance_slots;
+
+    if !status.within_weak_subjectivity_period && !is_syncing {
+        crit!(
+            chain.log,
+            "Node is outside its weak subjectivity period";
+            "msg" => "the finalized checkpoint is too old to safely follow the chain, consider \
+                      restarting with checkpoint sync from a trusted source",
+            "anchor_epoch" => status.anchor_epoch,
+            "anchor_age_epochs" => status.anchor_age_epochs,
+            "weak_subjectivity_period" => status.weak_subjectivity_period,
+        );
+    } else {
+        debug!(
+            chain.log,
+            "Audited weak subjectivity period";
+            "anchor_epoch" => status.anchor_epoch,
+            "anchor_age_epochs" => status.anchor_age_epochs,
+            "weak_subjectivity_period" => status.weak_subjectivity_period,
+            "within_period" => status.within_weak_subjectivity_period,
+        );
+    }
+}
+
+/// Compare the age of the finalized checkpoint to the weak subjectivity period of the head state.
+pub fn weak_subjectivity_status<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<WeakSubjectivityStatus, BeaconChainError> {
+    let current_epoch = chain.epoch()?;
+    let cached_head = chain.canonical_head.cached_head();
+    let anchor_epoch = cached_head.finalized_checkpoint().epoch;
+
+    let head_state = &cached_head.snapshot.beacon_state;
+    let head_epoch = head_state.current_epoch();
+    let (active_validator_count, total_active_balance) = head_state
+        .validators()
+        .iter()
+        .filter(|validator| validator.is_active_at(head_epoch))
+        .fold((0_u64, 0_u64), |(count, balance), validator| {
+            (
+                count.saturating_add(1),
+                balance.saturating_add(validator.effective_balance),
+            )
+        });
+
+    let weak_subjectivity_period = compute_weak_subjectivity_period(
+        active_validator_count,
+        total_active_balance,
+        T::EthSpec::MaxDeposits::to_u64() * T::EthSpec::slots_per_epoch(),
+        &chain.spec,
+    );
+    let anchor_age_epochs = current_epoch.saturating_sub(anchor_epoch).as_u64();
+
+    Ok(WeakSubjectivityStatus {
+        current_epoch,
+        anchor_epoch,
+        anchor_age_epochs,
+        weak_subjectivity_period,
+        active_validator_count,
+        within_weak_subjectivity_period: anchor_age_epochs <= weak_subjectivity_period,
+    })
+}
+
+/// Compute the weak subjectivity period in epochs, as per `compute_weak_subjectivity_period` in
+/// the consensus specs.
+///
+/// `max_deposits_per_epoch` is `MAX_DEPOSITS * SLOTS_PER_EPOCH`, the maximum number of balance
+/// top-ups per epoch.
+pub fn compute_weak_subjectivity_period(
+    active_validator_count: u64,
+    total_active_balance: u64,
+    max_deposits_per_epoch: u64,
+    spec: &ChainSpec,
+) -> u64 {
+    let mut ws_period = spec.min_validator_withdrawability_delay.as_u64();
+
+    let n = active_validator_count;
+    if n == 0 {
+        return ws_period;
+    }
+    let t = total_active_balance / n / ETH_TO_GWEI;
+    let max_t = spec.max_effective_balance / ETH_TO_GWEI;
+    let churn_limit = std::cmp::max(
+        spec.min_per_epoch_churn_limit,
+        n / spec.churn_limit_quotient,
+    );
+    let d = SAFETY_DECAY;
+
+    if max_t * (200 + 3 * d) < t * (200 + 12 * d) {
+        let epochs_for_validator_set_churn = n
+            .saturating_mul(t * (200 + 12 * d) - max_t * (200 + 3 * d))
+            / (600 * churn_limit * (2 * t + max_t));
+        let epochs_for_balance_top_ups =
+            n.saturating_mul(200 + 3 * d) / (600 * max_deposits_per_epoch);
+        ws_period += std::cmp::max(epochs_for_validator_set_churn, epochs_for_balance_top_ups);
+    } else {
+        ws_period += n.saturating_mul(3 * d * t) / (200 * max_deposits_per_epoch * (max_t - t));
+    }
+
+    ws_period
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    fn ws_period(validator_count: u64, average_balance_eth: u64) -> u64 {
+        let spec = MainnetEthSpec::default_spec();
+        compute_weak_subjectivity_period(
+            validator_count,
+            validator_count * average_balance_eth * ETH_TO_GWEI,
+            <MainnetEthSpec as EthSpec>::MaxDeposits::to_u64() * MainnetEthSpec::slots_per_epoch(),
+            &spec,
+        )
+    }
+
+    /// Values from the table in the consensus specs' weak subjectivity guide.
+    #[test]
+    fn spec_examples() {
+        assert_eq!(ws_period(32768, 28), 504);
+        assert_eq!(ws_period(65536, 28), 752);
+        assert_eq!(ws_period(131072, 28), 1248);
+        assert_eq!(ws_period(262144, 28), 2241);
+        assert_eq!(ws_period(524288, 28), 2241);
+        assert_eq!(ws_period(1048576, 28), 2241);
+        assert_eq!(ws_period(32768, 32), 665);
+        assert_eq!(ws_period(65536, 32), 1075);
+        assert_eq!(ws_period(131072, 32), 1894);
+        assert_eq!(ws_period(262144, 32), 3532);
+        assert_eq!(ws_period(524288, 32), 3532);
+        assert_eq!(ws_period(1048576, 32), 3532);
+    }
+
+    #[test]
+    fn no_active_validators() {
+        let spec = MainnetEthSpec::default_spec();
+        assert_eq!(
+            compute_weak_subjectivity_period(0, 0, 512, &spec),
+            spec.min_validator_withdrawability_delay.as_u64()
+        );
+    }
+}
@@ -2,13 +2,15 @@
 
 use beacon_chain::attestation_simulator::produce_unaggregated_attestation;
 use beacon_chain::block_verification_types::RpcBlock;
+use beacon_chain::shuffling_cache::{BlockShufflingIds, ShufflingCache};
+use beacon_chain::shuffling_cache_warmer::warm_shuffling_cache;
 use beacon_chain::test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy};
 use beacon_chain::validator_monitor::UNAGGREGATED_ATTESTATION_LAG_SLOTS;
 use beacon_chain::{metrics, StateSkipConfig, WhenSlotSkipped};
 use std::sync::{Arc, LazyLock};
 use tree_hash::TreeHash;
 use types::{
-    AggregateSignature, Attestation, EthSpec, Keypair, MainnetEthSpec, RelativeEpoch, Slot,
+    AggregateSignature, Attestation, Epoch, EthSpec, Keypair, MainnetEthSpec, RelativeEpoch, Slot,
 };
 
 pub const VALIDATOR_COUNT: usize = 16;
@@ -100,6 +102,53 @@ async fn produces_attestations_from_attestation_simulator_service() {
             );
         }
     });
+
+    // Every simulated attestation was rewarded, so a perfect validator would have earned the
+    // maximum reward in each epoch.
+    let validator_monitor = chain.validator_monitor.read();
+    let mut evaluated_attestations = 0;
+    for epoch in [Epoch::new(0), Epoch::new(1)] {
+        let effectiveness = validator_monitor
+            .get_simulated_attestation_effectiveness(epoch)
+            .expect("should have evaluated simulated attestations");
+        assert_eq!(effectiveness.percentage(), Some(100.0));
+        evaluated_attestations += effectiveness.attestations;
+    }
+    assert_eq!(evaluated_attestations, expected_hit_metrics_count);
+}
+
+/// Checks that the shuffling cache warmer computes missing shufflings for the head ahead of time
+/// and does not repeat work for shufflings which are already cached.
+#[tokio::test]
+async fn shuffling_cache_warmer_primes_head_shufflings() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain = &harness.chain;
+    let current_slot = chain.slot().unwrap();
+
+    // Start from an empty cache so that the warmer has something to do.
+    let head = chain.head_snapshot();
+    *chain.shuffling_cache.write() = ShufflingCache::new(
+        chain.config.shuffling_cache_size,
+        BlockShufflingIds::try_from_head(head.beacon_block_root, &head.beacon_state).unwrap(),
+        harness.logger().clone(),
+    );
+
+    assert!(warm_shuffling_cache(chain, current_slot) > 0);
+    assert_eq!(warm_shuffling_cache(chain, current_slot), 0);
 }
 
 /// This test builds a chain that is just long enough to finalize an epoch then it produces an
@@ -339,3 +388,101 @@ async fn early_attester_cache_old_request() {
         .unwrap();
     assert_eq!(attested_block.slot(), attest_slot);
 }
+
+/// Ensures that a block which is awaiting its blobs can only be attested to during its own slot
+/// and is not treated as known until it becomes available.
+#[tokio::test]
+async fn early_attester_cache_availability_pending_block() {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .keypairs(KEYPAIRS[..].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    harness.advance_slot();
+
+    harness
+        .extend_chain(
+            2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain = &harness.chain;
+    let head = chain.head_snapshot();
+    let head_slot = head.beacon_block.slot();
+    let block_root = head.beacon_block_root;
+
+    chain.early_attester_cache.clear();
+    chain
+        .early_attester_cache
+        .add_availability_pending_block(
+            block_root,
+            head.beacon_block.clone(),
+            &head.beacon_state,
+            &chain.spec,
+        )
+        .unwrap();
+
+    let attestation = chain
+        .early_attester_cache
+        .try_attest(head_slot, 0, &chain.spec)
+        .unwrap()
+        .expect("should attest to the pending block in its slot");
+    assert_eq!(attestation.data().beacon_block_root, block_root);
+    assert!(chain
+        .early_attester_cache
+        .try_attest(head_slot + 1, 0, &chain.spec)
+        .unwrap()
+        .is_none());
+
+    assert!(chain
+        .early_attester_cache
+        .contains_availability_pending_block(block_root));
+    assert!(!chain.early_attester_cache.contains_block(block_root));
+    assert!(chain.early_attester_cache.get_block(block_root).is_none());
+    assert!(chain
+        .early_attester_cache
+        .get_proto_block(block_root)
+        .is_none());
+
+    // Once the block becomes available it replaces the pending block.
+    let head_blobs = chain.get_blobs(&block_root).expect("should get blobs");
+    let rpc_block =
+        RpcBlock::<MainnetEthSpec>::new(None, head.beacon_block.clone(), Some(head_blobs)).unwrap();
+    let beacon_chain::data_availability_checker::MaybeAvailableBlock::Available(available_block) =
+        chain
+            .data_availability_checker
+            .verify_kzg_for_rpc_block(rpc_block)
+            .unwrap()
+    else {
+        panic!("block should be available")
+    };
+    let proto_block = chain
+        .canonical_head
+        .fork_choice_read_lock()
+        .get_block(&block_root)
+        .unwrap();
+    chain
+        .early_attester_cache
+        .add_head_block(
+            block_root,
+            available_block,
+            proto_block,
+            &head.beacon_state,
+            &chain.spec,
+        )
+        .unwrap();
+
+    assert!(chain.early_attester_cache.contains_block(block_root));
+    assert!(!chain
+        .early_attester_cache
+        .contains_availability_pending_block(block_root));
+    assert!(chain
+        .early_attester_cache
+        .try_attest(head_slot + 1, 0, &chain.spec)
+        .unwrap()
+        .is_some());
+}
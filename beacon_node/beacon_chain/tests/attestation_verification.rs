@@ -1189,6 +1189,62 @@ async fn unaggregated_gossip_verification() {
         );
 }
 
+/// Ensures that an invalid signature in a batch of unaggregated attestations only causes the
+/// attestations from its own slot to fail signature verification.
+#[tokio::test]
+async fn batch_verify_unaggregated_attestations_per_slot() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize / 2,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(vec![]),
+        )
+        .await;
+    harness.advance_slot();
+
+    let (state, state_root) = harness.get_current_state_and_root();
+    let head_block_root = harness.head_block_root();
+    let current_slot = harness.get_current_slot();
+    let attesters = (0..VALIDATOR_COUNT).collect::<Vec<_>>();
+    let committee_attestations = |slot| {
+        harness
+            .make_unaggregated_attestations(
+                &attesters,
+                &state,
+                state_root,
+                head_block_root.into(),
+                slot,
+            )
+            .first()
+            .cloned()
+            .expect("should have at least one committee")
+    };
+
+    let mut previous_slot_attestations = committee_attestations(current_slot - 1);
+    let current_slot_attestations = committee_attestations(current_slot);
+
+    let mut bad_signature = AggregateSignature::infinity();
+    bad_signature.add_assign(&KEYPAIRS[0].sk.sign(Hash256::repeat_byte(42)));
+    *previous_slot_attestations[0].0.signature_mut() = bad_signature;
+
+    let attestations_and_subnets = previous_slot_attestations
+        .iter()
+        .chain(&current_slot_attestations)
+        .map(|(attestation, subnet_id)| (attestation, Some(*subnet_id)));
+    let results = batch_verify_unaggregated_attestations(attestations_and_subnets, &harness.chain)
+        .expect("should return attestation results");
+
+    let (previous_slot_results, current_slot_results) =
+        results.split_at(previous_slot_attestations.len());
+    assert!(matches!(
+        previous_slot_results[0],
+        Err(Error::InvalidSignature)
+    ));
+    assert!(previous_slot_results[1..].iter().all(Result::is_ok));
+    assert!(current_slot_results.iter().all(Result::is_ok));
+}
+
 /// Ensures that an attestation that skips epochs can still be processed.
 ///
 /// This also checks that we can do a state lookup if we don't get a hit from the shuffling cache.
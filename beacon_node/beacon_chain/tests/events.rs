@@ -1,11 +1,15 @@
 use beacon_chain::blob_verification::GossipVerifiedBlob;
 use beacon_chain::test_utils::BeaconChainHarness;
-use eth2::types::{EventKind, SseBlobSidecar};
+use beacon_chain::ServerSentEventHandler;
+use eth2::types::{
+    EventKind, EventTopic, SseBlobSidecar, SseBlock, SseFinalizedCheckpoint, SseHead,
+};
 use rand::rngs::StdRng;
 use rand::SeedableRng;
 use std::sync::Arc;
+use std::time::Duration;
 use types::blob_sidecar::FixedBlobSidecarList;
-use types::{BlobSidecar, EthSpec, ForkName, MinimalEthSpec};
+use types::{BlobSidecar, Epoch, EthSpec, ForkName, Hash256, MinimalEthSpec, Slot};
 
 type E = MinimalEthSpec;
 
@@ -95,3 +99,121 @@ async fn blob_sidecar_event_on_process_rpc_blobs() {
     }
     assert_eq!(sse_blobs, expected_sse_blobs);
 }
+
+fn head_event(slot: u64) -> EventKind<E> {
+    EventKind::Head(SseHead {
+        slot: Slot::new(slot),
+        block: Hash256::repeat_byte(slot as u8),
+        state: Hash256::repeat_byte(slot as u8),
+        current_duty_dependent_root: Hash256::zero(),
+        previous_duty_dependent_root: Hash256::zero(),
+        epoch_transition: false,
+        execution_optimistic: false,
+    })
+}
+
+fn finalized_event(epoch: u64) -> EventKind<E> {
+    EventKind::FinalizedCheckpoint(SseFinalizedCheckpoint {
+        block: Hash256::repeat_byte(epoch as u8),
+        state: Hash256::repeat_byte(epoch as u8),
+        epoch: Epoch::new(epoch),
+        execution_optimistic: false,
+    })
+}
+
+/// Verifies that replayable events are retained without subscribers and replayed after an ID.
+#[test]
+fn replay_events_since_last_event_id() {
+    let event_handler = ServerSentEventHandler::<E>::new(logging::test_logger(), 1)
+        .with_replay_window(Duration::from_secs(300));
+    assert!(event_handler.has_head_subscribers());
+
+    let mut replay_receiver = event_handler
+        .subscribe_replayable(&EventTopic::Head)
+        .unwrap();
+    for slot in 0..4 {
+        event_handler.register(head_event(slot));
+    }
+    // Events of other topics are not retained.
+    event_handler.register(EventKind::Block(SseBlock {
+        slot: Slot::new(4),
+        block: Hash256::zero(),
+        execution_optimistic: false,
+    }));
+    assert!(event_handler
+        .subscribe_replayable(&EventTopic::Block)
+        .is_none());
+
+    let ids = event_handler
+        .replay_since(&EventTopic::Head, 0)
+        .iter()
+        .map(|event| event.id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids.len(), 4);
+    // IDs are seeded with the boot time, so that they aren't reused after a restart.
+    assert!(ids[0] > 0);
+    assert!(ids.windows(2).all(|pair| pair[1] == pair[0] + 1));
+
+    let replayed = event_handler.replay_since(&EventTopic::Head, ids[1]);
+    assert_eq!(
+        replayed.iter().map(|event| event.id).collect::<Vec<_>>(),
+        ids[2..]
+    );
+    assert_eq!(replayed[0].event, head_event(2));
+    assert!(event_handler
+        .replay_since(&EventTopic::Head, ids[3])
+        .is_empty());
+
+    // Live subscribers receive the same IDs.
+    for id in ids {
+        assert_eq!(replay_receiver.try_recv().unwrap().id, id);
+    }
+    assert!(replay_receiver.try_recv().is_err());
+}
+
+/// Verifies that the events of each topic are buffered separately, with IDs shared across topics.
+#[test]
+fn replay_buffers_are_per_topic() {
+    let event_handler = ServerSentEventHandler::<E>::new(logging::test_logger(), 1)
+        .with_replay_window(Duration::from_secs(300));
+
+    event_handler.register(finalized_event(1));
+    // Far more head events than are retained for a single topic.
+    for slot in 0..5_000 {
+        event_handler.register(head_event(slot));
+    }
+    event_handler.register(finalized_event(2));
+
+    let finalized = event_handler.replay_since(&EventTopic::FinalizedCheckpoint, 0);
+    assert_eq!(
+        finalized
+            .iter()
+            .map(|event| event.event.clone())
+            .collect::<Vec<_>>(),
+        vec![finalized_event(1), finalized_event(2)]
+    );
+    assert_eq!(finalized[1].id, finalized[0].id + 5_001);
+
+    let heads = event_handler.replay_since(&EventTopic::Head, 0);
+    assert!(heads.len() < 5_000);
+    assert!(heads
+        .iter()
+        .all(|event| event.id > finalized[0].id && event.id < finalized[1].id));
+    assert!(event_handler
+        .replay_since(&EventTopic::ChainReorg, 0)
+        .is_empty());
+}
+
+/// Verifies that nothing is retained when replay is disabled.
+#[test]
+fn replay_disabled() {
+    let event_handler = ServerSentEventHandler::<E>::new(logging::test_logger(), 1);
+    assert!(!event_handler.replay_enabled());
+    assert!(!event_handler.has_head_subscribers());
+    assert!(event_handler
+        .subscribe_replayable(&EventTopic::Head)
+        .is_none());
+
+    event_handler.register(head_event(0));
+    assert!(event_handler.replay_since(&EventTopic::Head, 0).is_empty());
+}
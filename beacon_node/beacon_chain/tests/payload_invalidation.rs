@@ -2,6 +2,7 @@
 
 use beacon_chain::{
     canonical_head::{CachedHead, CanonicalHead},
+    payload_forensics,
     test_utils::{BeaconChainHarness, EphemeralHarnessType},
     BeaconChainError, BlockError, ChainConfig, ExecutionPayloadError, NotifyExecutionLayer,
     OverrideForkchoiceUpdate, StateSkipConfig, WhenSlotSkipped,
@@ -9,6 +10,7 @@ use beacon_chain::{
 };
 use execution_layer::{
     json_structures::{JsonForkchoiceStateV1, JsonPayloadAttributes, JsonPayloadAttributesV1},
+    recorder::{EngineCallRecord, RecordedResult},
     ExecutionLayer, ForkchoiceState, PayloadAttributes,
 };
 use fork_choice::{Error as ForkChoiceError, InvalidationOperation, PayloadVerificationStatus};
@@ -16,6 +18,7 @@ use logging::test_logger;
 use proto_array::{Error as ProtoArrayError, ExecutionStatus};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use task_executor::ShutdownReason;
@@ -46,16 +49,23 @@ impl InvalidPayloadRig {
         Self::new_with_spec(spec)
     }
 
-    fn new_with_spec(mut spec: ChainSpec) -> Self {
+    fn new_with_spec(spec: ChainSpec) -> Self {
+        Self::new_with_spec_and_config(
+            spec,
+            ChainConfig {
+                reconstruct_historic_states: true,
+                ..ChainConfig::default()
+            },
+        )
+    }
+
+    fn new_with_spec_and_config(mut spec: ChainSpec, chain_config: ChainConfig) -> Self {
         spec.altair_fork_epoch = Some(Epoch::new(0));
         spec.bellatrix_fork_epoch = Some(Epoch::new(0));
 
         let harness = BeaconChainHarness::builder(MainnetEthSpec)
             .spec(spec.into())
-            .chain_config(ChainConfig {
-                reconstruct_historic_states: true,
-                ..ChainConfig::default()
-            })
+            .chain_config(chain_config)
             .logger(test_logger())
             .deterministic_keypairs(VALIDATOR_COUNT)
             .mock_execution_layer()
@@ -433,6 +443,148 @@ async fn invalid_payload_invalidates_parent() {
     assert_eq!(rig.harness.head_block_root(), roots[0]);
 }
 
+/// Ensure that a forensic bundle is written for a block with an invalid payload.
+#[tokio::test]
+async fn invalid_payload_writes_forensic_bundle() {
+    let forensics_dir = tempfile::tempdir().unwrap();
+    let mut rig = InvalidPayloadRig::new_with_spec_and_config(
+        E::default_spec(),
+        ChainConfig {
+            payload_forensics_dir: Some(forensics_dir.path().to_path_buf()),
+            ..ChainConfig::default()
+        },
+    )
+    .enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+    rig.move_to_first_justification(Payload::Syncing).await;
+
+    // Directories which aren't bundles are neither listed nor pruned.
+    let unrelated_dir = forensics_dir.path().join("unrelated");
+    std::fs::create_dir(&unrelated_dir).unwrap();
+
+    let parent_root = rig.import_block(Payload::Syncing).await;
+    let latest_valid_hash = rig.block_hash(parent_root);
+    rig.import_block(Payload::Invalid {
+        latest_valid_hash: Some(latest_valid_hash),
+    })
+    .await;
+
+    let bundles = wait_for_forensic_bundles(forensics_dir.path()).await;
+    assert_eq!(bundles.len(), 1);
+    assert!(unrelated_dir.exists());
+
+    let read = |file| {
+        payload_forensics::read_bundle_file(forensics_dir.path(), &bundles[0], file)
+            .unwrap()
+            .unwrap()
+    };
+    for file in payload_forensics::BUNDLE_FILES {
+        read(file);
+    }
+
+    let block =
+        SignedBeaconBlock::<E>::from_ssz_bytes(&read("block.ssz"), &rig.harness.spec).unwrap();
+    assert_eq!(block.parent_root(), parent_root);
+    assert!(bundles[0].ends_with(&format!("{:?}", block.canonical_root())));
+
+    let parent: serde_json::Value = serde_json::from_slice(&read("parent.json")).unwrap();
+    assert_eq!(parent["block_root"], serde_json::json!(parent_root));
+
+    let exchange: Vec<EngineCallRecord> =
+        serde_json::from_slice(&read("engine_exchange.json")).unwrap();
+    let new_payload = exchange
+        .iter()
+        .find(|record| record.method.starts_with("engine_newPayload"))
+        .expect("bundle should contain the newPayload exchange");
+    assert!(!new_payload.endpoint.is_empty());
+    let RecordedResult::Ok(response) = &new_payload.result else {
+        panic!("newPayload should have succeeded: {:?}", new_payload.result);
+    };
+    assert_eq!(response["status"], "INVALID");
+    assert_eq!(
+        response["latestValidHash"],
+        serde_json::json!(latest_valid_hash)
+    );
+
+    // Unknown files and bundles aren't served.
+    assert!(
+        payload_forensics::read_bundle_file(forensics_dir.path(), &bundles[0], "../block.ssz")
+            .unwrap()
+            .is_none()
+    );
+    assert!(
+        payload_forensics::read_bundle_file(forensics_dir.path(), "..", "block.ssz")
+            .unwrap()
+            .is_none()
+    );
+}
+
+/// Ensure that a forensic bundle is written when the head is invalidated by a forkchoice update.
+#[tokio::test]
+async fn invalid_forkchoice_update_writes_forensic_bundle() {
+    let forensics_dir = tempfile::tempdir().unwrap();
+    let mut rig = InvalidPayloadRig::new_with_spec_and_config(
+        E::default_spec(),
+        ChainConfig {
+            payload_forensics_dir: Some(forensics_dir.path().to_path_buf()),
+            ..ChainConfig::default()
+        },
+    )
+    .enable_attestations();
+    rig.move_to_terminal_block();
+    rig.import_block(Payload::Valid).await; // Import a valid transition block.
+    rig.move_to_first_justification(Payload::Syncing).await;
+
+    let valid_head_root = rig.import_block(Payload::Valid).await;
+    let latest_valid_hash = Some(rig.block_hash(valid_head_root));
+
+    // Import a block which returns syncing when supplied via newPayload, and then
+    // invalid when the forkchoice update is sent.
+    rig.import_block_parametric(
+        Payload::Syncing,
+        Payload::Invalid { latest_valid_hash },
+        None,
+        |_| false,
+    )
+    .await;
+    assert_eq!(rig.harness.head_block_root(), valid_head_root);
+
+    // Later forkchoice updates may be invalidated by the mock engine as well.
+    let bundles = wait_for_forensic_bundles(forensics_dir.path()).await;
+    assert!(!bundles.is_empty());
+
+    let exchange = payload_forensics::read_bundle_file(
+        forensics_dir.path(),
+        &bundles[0],
+        "engine_exchange.json",
+    )
+    .unwrap()
+    .unwrap();
+    let exchange: Vec<EngineCallRecord> = serde_json::from_slice(&exchange).unwrap();
+    let forkchoice_updated = exchange
+        .iter()
+        .find(|record| record.method.starts_with("engine_forkchoiceUpdated"))
+        .expect("bundle should contain the forkchoiceUpdated exchange");
+    let RecordedResult::Ok(response) = &forkchoice_updated.result else {
+        panic!("fcU should have succeeded: {:?}", forkchoice_updated.result);
+    };
+    assert_eq!(response["payloadStatus"]["status"], "INVALID");
+}
+
+/// Waits for the forensic bundles in `dir`, which are written on a blocking thread.
+async fn wait_for_forensic_bundles(dir: &Path) -> Vec<String> {
+    let mut bundles = vec![];
+    for _ in 0..50 {
+        bundles = payload_forensics::list_bundles(dir).unwrap();
+        if !bundles.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    bundles
+}
+
 /// Test invalidation of a payload via the fork choice updated message.
 ///
 /// The `invalid_payload` argument determines the type of invalid payload: `Invalid`,
@@ -13,7 +13,8 @@ use beacon_chain::test_utils::{
 use beacon_chain::{
     data_availability_checker::MaybeAvailableBlock, historical_blocks::HistoricalBlockError,
     migrate::MigratorConfig, BeaconChain, BeaconChainError, BeaconChainTypes, BeaconSnapshot,
-    BlockError, ChainConfig, NotifyExecutionLayer, ServerSentEventHandler, WhenSlotSkipped,
+    BlockError, ChainConfig, NotifyExecutionLayer, ServerSentEventHandler, StateSkipConfig,
+    WhenSlotSkipped,
 };
 use logging::test_logger;
 use maplit::hashset;
@@ -25,13 +26,18 @@ use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
+use store::consistency::ConsistencyIssue;
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
+use store::orphaned_blocks::OrphanReason;
+use store::state_load_plan::StateLoadSource;
 use store::{
+    get_key_for_col,
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, LevelDB, StoreConfig,
+    BlobInfo, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
+use tree_hash::TreeHash;
 use types::test_utils::{SeedableRng, XorShiftRng};
 use types::*;
 
@@ -227,6 +233,141 @@ async fn light_client_updates_test() {
         .unwrap();
 
     assert_eq!(lc_updates.len(), 2);
+
+    // Updates are keyed by little-endian period, which doesn't sort in period order. Check that
+    // ranges spanning such keys are still returned in order, and stop at the first missing period.
+    let lc_update = lc_updates[0].clone();
+    for period in [1, 255, 256, 257] {
+        store.store_light_client_update(period, &lc_update).unwrap();
+    }
+    let lc_updates = harness.chain.get_light_client_updates(255, 10).unwrap();
+    assert_eq!(lc_updates.len(), 3);
+}
+
+#[tokio::test]
+async fn light_client_updates_published_once() {
+    let spec = test_spec::<E>();
+    let Some(_) = spec.altair_fork_epoch else {
+        // No-op prior to Altair.
+        return;
+    };
+
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(&db_path, StoreConfig::default(), spec.clone());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let cache = &harness.chain.light_client_server_cache;
+
+    harness
+        .extend_chain_with_light_client_data(
+            (E::slots_per_epoch() * 5) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let (optimistic_update, finality_update) = cache.take_updates_to_publish(&spec);
+    let optimistic_update = optimistic_update.expect("should publish optimistic update");
+    assert!(finality_update.is_some());
+
+    // Nothing new to publish until the head advances.
+    let (optimistic_update_again, finality_update_again) = cache.take_updates_to_publish(&spec);
+    assert!(optimistic_update_again.is_none());
+    assert!(finality_update_again.is_none());
+
+    harness.advance_slot();
+    harness
+        .extend_chain_with_light_client_data(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let (next_optimistic_update, _) = cache.take_updates_to_publish(&spec);
+    assert!(next_optimistic_update.unwrap().get_slot() > optimistic_update.get_slot());
+}
+
+#[tokio::test]
+async fn light_client_bootstrap_without_persisted_data() {
+    let spec = test_spec::<E>();
+    let Some(_) = spec.altair_fork_epoch else {
+        // No-op prior to Altair.
+        return;
+    };
+
+    let db_path = tempdir().unwrap();
+    let store = get_store_generic(&db_path, StoreConfig::default(), spec.clone());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let all_validators = (0..LOW_VALIDATOR_COUNT).collect::<Vec<_>>();
+    let num_initial_slots = E::slots_per_epoch() * 7;
+    let slots: Vec<Slot> = (1..num_initial_slots).map(Slot::new).collect();
+
+    // Import blocks without computing light client data, as happens during sync.
+    let (genesis_state, genesis_state_root) = harness.get_current_state_and_root();
+    harness
+        .add_attested_blocks_at_slots(
+            genesis_state.clone(),
+            genesis_state_root,
+            &slots,
+            &all_validators,
+        )
+        .await;
+
+    let block_root = harness
+        .chain
+        .canonical_head
+        .cached_head()
+        .finalized_checkpoint()
+        .root;
+    assert!(store
+        .get_sync_committee_branch(&block_root)
+        .unwrap()
+        .is_none());
+
+    // The bootstrap is computed from the block's state, and its data is persisted.
+    let (lc_bootstrap, _) = harness
+        .chain
+        .get_light_client_bootstrap(&block_root)
+        .unwrap()
+        .unwrap();
+    assert!(store
+        .get_sync_committee_branch(&block_root)
+        .unwrap()
+        .is_some());
+
+    let (state_root, branch, depth, generalized_index) = match &lc_bootstrap {
+        LightClientBootstrap::Altair(lc_bootstrap) => (
+            lc_bootstrap.header.beacon.state_root,
+            lc_bootstrap.current_sync_committee_branch.to_vec(),
+            light_client_update::CURRENT_SYNC_COMMITTEE_PROOF_LEN,
+            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX,
+        ),
+        LightClientBootstrap::Capella(lc_bootstrap) => (
+            lc_bootstrap.header.beacon.state_root,
+            lc_bootstrap.current_sync_committee_branch.to_vec(),
+            light_client_update::CURRENT_SYNC_COMMITTEE_PROOF_LEN,
+            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX,
+        ),
+        LightClientBootstrap::Deneb(lc_bootstrap) => (
+            lc_bootstrap.header.beacon.state_root,
+            lc_bootstrap.current_sync_committee_branch.to_vec(),
+            light_client_update::CURRENT_SYNC_COMMITTEE_PROOF_LEN,
+            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX,
+        ),
+        LightClientBootstrap::Electra(lc_bootstrap) => (
+            lc_bootstrap.header.beacon.state_root,
+            lc_bootstrap.current_sync_committee_branch.to_vec(),
+            light_client_update::CURRENT_SYNC_COMMITTEE_PROOF_LEN_ELECTRA,
+            light_client_update::CURRENT_SYNC_COMMITTEE_INDEX_ELECTRA,
+        ),
+    };
+    assert!(merkle_proof::verify_merkle_proof(
+        lc_bootstrap.current_sync_committee().tree_hash_root(),
+        &branch,
+        depth,
+        generalized_index % (1 << depth),
+        state_root,
+    ));
 }
 
 #[tokio::test]
@@ -250,6 +391,135 @@ async fn full_participation_no_skips() {
     check_iterators(&harness);
 }
 
+#[tokio::test]
+async fn column_size_estimates() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    store.scrape_column_sizes();
+
+    let gauge = |gauge, db, column: DBColumn| {
+        store::metrics::get_int_gauge(gauge, &[db, column.as_str()])
+            .unwrap()
+            .get()
+    };
+    let hot_blocks = store
+        .hot_db
+        .iter_column_keys::<Hash256>(DBColumn::BeaconBlock)
+        .count();
+    assert_eq!(
+        gauge(
+            &store::metrics::DISK_DB_COLUMN_KEYS,
+            "hot",
+            DBColumn::BeaconBlock
+        ),
+        hot_blocks as i64
+    );
+    assert!(
+        gauge(
+            &store::metrics::DISK_DB_COLUMN_SIZE,
+            "hot",
+            DBColumn::BeaconBlock
+        ) > 0
+    );
+    assert!(
+        gauge(
+            &store::metrics::DISK_DB_COLUMN_KEYS,
+            "cold",
+            DBColumn::BeaconBlockRoots
+        ) > 0
+    );
+    assert_eq!(
+        gauge(
+            &store::metrics::DISK_DB_COLUMN_KEYS,
+            "blobs",
+            DBColumn::BeaconBlock
+        ),
+        0
+    );
+}
+
+#[tokio::test]
+async fn state_at_slot_planned() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain = &harness.chain;
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > E::slots_per_epoch());
+
+    // The head state is served directly.
+    let head_slot = chain.head_snapshot().beacon_state.slot();
+    let (state, plan) = chain
+        .state_at_slot_planned(head_slot, StateSkipConfig::WithStateRoots)
+        .unwrap();
+    assert_eq!(state.slot(), head_slot);
+    assert_eq!(plan.source, StateLoadSource::Head);
+    assert_eq!(plan.replay_slots, 0);
+
+    // Hot states are served from the state cache or by replay from an epoch boundary.
+    let hot_slot = split_slot + 1;
+    let (state, plan) = chain
+        .state_at_slot_planned(hot_slot, StateSkipConfig::WithStateRoots)
+        .unwrap();
+    assert_eq!(state.slot(), hot_slot);
+    assert!(matches!(
+        plan.source,
+        StateLoadSource::HotCache | StateLoadSource::HotReplay
+    ));
+
+    // Once a cold state has been loaded, the state at the next slot should be obtained by
+    // replaying a single slot on top of the cached state rather than using the freezer.
+    let cold_slot = split_slot - 3;
+    let (state, _) = chain
+        .state_at_slot_planned(cold_slot, StateSkipConfig::WithStateRoots)
+        .unwrap();
+    assert_eq!(state.slot(), cold_slot);
+
+    let (state, plan) = chain
+        .state_at_slot_planned(cold_slot + 1, StateSkipConfig::WithStateRoots)
+        .unwrap();
+    assert_eq!(state.slot(), cold_slot + 1);
+    assert_eq!(plan.source, StateLoadSource::HistoricCache);
+    assert_eq!(plan.replay_slots, 1);
+    assert_eq!(
+        state.canonical_root().unwrap(),
+        chain.state_root_at_slot(cold_slot + 1).unwrap().unwrap()
+    );
+
+    // States looked up by root without a slot hint are found in either database.
+    for slot in [hot_slot, cold_slot] {
+        let state_root = chain.state_root_at_slot(slot).unwrap().unwrap();
+        let (state, plan) = chain.get_state_planned(&state_root, None).unwrap().unwrap();
+        assert_eq!(state.canonical_root().unwrap(), state_root);
+        assert_eq!(plan.target_slot, slot);
+    }
+    assert!(chain
+        .get_state_planned(&Hash256::repeat_byte(0xff), None)
+        .unwrap()
+        .is_none());
+}
+
 #[tokio::test]
 async fn randomised_skips() {
     let num_slots = E::slots_per_epoch() * 5;
@@ -437,6 +707,78 @@ async fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+// Check that missing frozen roots and states are detected and repaired when the store is opened.
+#[tokio::test]
+async fn repair_missing_frozen_data_on_init() {
+    let db_path = tempdir().unwrap();
+    let diff_slot = Slot::new(32);
+    let roots_range = (Slot::new(36), Slot::new(40));
+
+    let expected_state_root = {
+        let store = get_store(&db_path);
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness
+            .extend_chain(
+                8 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        assert!(store.get_split_slot() > roots_range.1);
+        assert_eq!(store.check_consistency().unwrap(), vec![]);
+
+        // Delete the diff at `diff_slot` and the block and state roots in `roots_range`.
+        let slot_key = |column: DBColumn, slot: u64| {
+            KeyValueStoreOp::DeleteKey(get_key_for_col(column.into(), &slot.to_be_bytes()))
+        };
+        let mut ops = vec![slot_key(DBColumn::BeaconStateDiff, diff_slot.as_u64())];
+        for slot in roots_range.0.as_u64()..roots_range.1.as_u64() {
+            ops.push(slot_key(DBColumn::BeaconBlockRoots, slot));
+            ops.push(slot_key(DBColumn::BeaconStateRoots, slot));
+        }
+        store.cold_db.do_atomically(ops).unwrap();
+
+        let issues = store.check_consistency().unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ConsistencyIssue::MissingFrozenBlockRoots {
+                    start_slot: roots_range.0,
+                    end_slot: roots_range.1,
+                },
+                ConsistencyIssue::MissingFrozenStateRoots {
+                    start_slot: roots_range.0,
+                    end_slot: roots_range.1,
+                },
+                ConsistencyIssue::MissingFrozenState { slot: diff_slot },
+            ]
+        );
+
+        harness
+            .chain
+            .state_root_at_slot(diff_slot)
+            .unwrap()
+            .unwrap()
+    };
+
+    // Re-open the store without repair, the issues remain.
+    let store = get_store(&db_path);
+    assert_eq!(store.check_consistency().unwrap().len(), 3);
+    drop(store);
+
+    // Re-open the store with repair enabled.
+    let config = StoreConfig {
+        repair_on_init: true,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    assert_eq!(store.check_consistency().unwrap(), vec![]);
+
+    let mut state = store.load_cold_state_by_slot(diff_slot).unwrap();
+    assert_eq!(state.canonical_root().unwrap(), expected_state_root);
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -1252,6 +1594,130 @@ async fn prunes_abandoned_fork_between_two_finalized_checkpoints() {
     assert!(!rig.chain.knows_head(&stray_head));
 }
 
+#[tokio::test]
+async fn archives_abandoned_fork_blocks() {
+    const HONEST_VALIDATOR_COUNT: usize = 32;
+    const ADVERSARIAL_VALIDATOR_COUNT: usize = 16;
+    const VALIDATOR_COUNT: usize = HONEST_VALIDATOR_COUNT + ADVERSARIAL_VALIDATOR_COUNT;
+    let honest_validators: Vec<usize> = (0..HONEST_VALIDATOR_COUNT).collect();
+    let adversarial_validators: Vec<usize> = (HONEST_VALIDATOR_COUNT..VALIDATOR_COUNT).collect();
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        archive_orphaned_blocks: true,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let rig = get_harness(store.clone(), VALIDATOR_COUNT);
+    let slots_per_epoch = rig.slots_per_epoch();
+    let (mut state, state_root) = rig.get_current_state_and_root();
+
+    let canonical_chain_slots: Vec<Slot> = (1..=rig.epoch_start_slot(1)).map(Slot::new).collect();
+    let (_, _, _, new_state) = rig
+        .add_attested_blocks_at_slots(
+            state,
+            state_root,
+            &canonical_chain_slots,
+            &honest_validators,
+        )
+        .await;
+    state = new_state;
+    let canonical_chain_slot: u64 = rig.get_current_slot().into();
+
+    let stray_slots: Vec<Slot> = (canonical_chain_slot + 1..rig.epoch_start_slot(2))
+        .map(Slot::new)
+        .collect();
+    let (current_state, current_state_root) = rig.get_current_state_and_root();
+    let (stray_blocks, _, _, _) = rig
+        .add_attested_blocks_at_slots(
+            current_state,
+            current_state_root,
+            &stray_slots,
+            &adversarial_validators,
+        )
+        .await;
+
+    // Trigger finalization, pruning the stray blocks.
+    let finalization_slots: Vec<Slot> = ((canonical_chain_slot + 1)
+        ..=(canonical_chain_slot + slots_per_epoch * 5))
+        .map(Slot::new)
+        .collect();
+    let state_root = state.update_tree_hash_cache().unwrap();
+    rig.add_attested_blocks_at_slots(state, state_root, &finalization_slots, &honest_validators)
+        .await;
+
+    let archived = store
+        .get_orphaned_blocks(Slot::new(0), Slot::new(u64::MAX))
+        .unwrap();
+    assert_eq!(archived.len(), stray_blocks.len());
+
+    for (block_root, orphaned_block) in archived {
+        let slot = orphaned_block.block.slot();
+        assert_eq!(Hash256::from(stray_blocks[&slot]), block_root);
+        assert!(!rig.block_exists(block_root.into()));
+        // The canonical chain has a block from the same proposer at each stray slot.
+        assert_eq!(orphaned_block.reason, OrphanReason::Equivocation);
+    }
+
+    // Slot range queries only return blocks within the range.
+    let first_stray_slot = stray_slots[0];
+    let archived = store
+        .get_orphaned_blocks(first_stray_slot, first_stray_slot)
+        .unwrap();
+    assert_eq!(archived.len(), 1);
+    assert_eq!(archived[0].1.block.slot(), first_stray_slot);
+
+    // The attestations of the stray blocks are archived individually.
+    let archived_blocks = store
+        .get_orphaned_blocks(Slot::new(0), Slot::new(u64::MAX))
+        .unwrap();
+    let mut expected_attestations = archived_blocks
+        .iter()
+        .flat_map(|(block_root, orphaned_block)| {
+            orphaned_block
+                .block
+                .message()
+                .body()
+                .attestations()
+                .map(|attestation| (*block_root, attestation.clone_as_attestation()))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    assert!(!expected_attestations.is_empty());
+    let mut archived_attestations = store
+        .get_orphaned_attestations(Slot::new(0), Slot::new(u64::MAX))
+        .unwrap();
+    assert!(archived_attestations
+        .windows(2)
+        .all(|pair| pair[0].1.data().slot <= pair[1].1.data().slot));
+    let sort_key = |(block_root, attestation): &(Hash256, Attestation<E>)| {
+        (
+            attestation.data().slot,
+            *block_root,
+            attestation.tree_hash_root(),
+        )
+    };
+    expected_attestations.sort_by_key(sort_key);
+    archived_attestations.sort_by_key(sort_key);
+    assert_eq!(archived_attestations, expected_attestations);
+
+    // Pruning removes archived entries for slots prior to the cutoff only.
+    let cutoff = first_stray_slot + 1;
+    let ops = store.orphan_archive_prune_ops(cutoff).unwrap();
+    store.hot_db.do_atomically(ops).unwrap();
+    let remaining = store
+        .get_orphaned_blocks(Slot::new(0), Slot::new(u64::MAX))
+        .unwrap();
+    assert_eq!(remaining.len(), stray_blocks.len() - 1);
+    assert!(remaining
+        .iter()
+        .all(|(_, orphaned_block)| orphaned_block.block.slot() >= cutoff));
+    assert!(store
+        .get_orphaned_attestations(Slot::new(0), Slot::new(u64::MAX))
+        .unwrap()
+        .iter()
+        .all(|(_, attestation)| attestation.data().slot >= cutoff));
+}
+
 #[tokio::test]
 async fn pruning_does_not_touch_abandoned_block_shared_with_canonical_chain() {
     const HONEST_VALIDATOR_COUNT: usize = 32;
@@ -2686,6 +3152,166 @@ async fn process_blocks_and_attestations_for_unaligned_checkpoint() {
     }
 }
 
+// Check that an advanced head state persisted at shutdown is loaded on restart, even though the
+// advanced state is garbage collected from the hot database when the store is opened.
+#[tokio::test]
+async fn resume_from_persisted_head_state() {
+    let num_blocks_produced = E::slots_per_epoch() * 5;
+    let chain_config = ChainConfig {
+        fast_restart: true,
+        ..ChainConfig::default()
+    };
+
+    let db_path = tempdir().unwrap();
+    let harness = get_harness_generic(
+        get_store(&db_path),
+        LOW_VALIDATOR_COUNT,
+        chain_config.clone(),
+    );
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // Advance the head state into the next slot, as the state advance timer would.
+    harness.advance_slot();
+    let head_block_root = harness.head_block_root();
+    let mut advanced_state = harness.get_current_state();
+    let head_state_root = advanced_state.update_tree_hash_cache().unwrap();
+    complete_state_advance(
+        &mut advanced_state,
+        Some(head_state_root),
+        harness.get_current_slot(),
+        &harness.spec,
+    )
+    .unwrap();
+    let advanced_state_root = advanced_state.update_tree_hash_cache().unwrap();
+    harness
+        .chain
+        .store
+        .put_state_possibly_temporary(&advanced_state_root, &advanced_state, true)
+        .unwrap();
+
+    // Dropping the chain persists the advanced head state.
+    let slot_clock = harness.chain.slot_clock.clone();
+    drop(harness);
+
+    let store = get_store(&db_path);
+    assert!(store
+        .load_hot_state_summary(&advanced_state_root)
+        .unwrap()
+        .is_none());
+
+    let resumed_harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .logger(store.logger().clone())
+        .resumed_disk_store(store.clone())
+        .testing_slot_clock(slot_clock)
+        .mock_execution_layer()
+        .chain_config(chain_config)
+        .build();
+
+    // The persisted state is restored to the database and used as the head state.
+    assert!(store
+        .load_hot_state_summary(&advanced_state_root)
+        .unwrap()
+        .is_some());
+    let resumed_head = resumed_harness.chain.head_snapshot();
+    assert_eq!(resumed_head.beacon_block_root, head_block_root);
+    assert_eq!(
+        resumed_head.beacon_state.clone().canonical_root().unwrap(),
+        advanced_state_root
+    );
+    assert!(resumed_head
+        .beacon_state
+        .committee_cache_is_initialized(RelativeEpoch::Current));
+    drop(resumed_head);
+
+    // The chain continues from the restored state.
+    resumed_harness
+        .extend_chain(
+            E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    assert_eq!(
+        resumed_harness.head_slot(),
+        num_blocks_produced + 1 + E::slots_per_epoch()
+    );
+}
+
+// Check that a state persisted for an ancestor of the head, as by the periodic persist prior to a
+// crash, has the blocks since replayed on top of it on restart.
+#[tokio::test]
+async fn resume_from_persisted_ancestor_state() {
+    let db_path = tempdir().unwrap();
+    // Disable fast restarts for the first chain so that dropping it doesn't overwrite the
+    // persisted ancestor state, as in a crash.
+    let harness = get_harness_generic(
+        get_store(&db_path),
+        LOW_VALIDATOR_COUNT,
+        ChainConfig::default(),
+    );
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 3) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let ancestor_block_root = harness.head_block_root();
+    let mut ancestor_state = harness.get_current_state();
+    let ancestor_state_root = ancestor_state.update_tree_hash_cache().unwrap();
+    harness
+        .chain
+        .persist_state_for_restart(ancestor_block_root, ancestor_state_root, &ancestor_state)
+        .unwrap();
+
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            E::slots_per_epoch() as usize + 3,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let head_block_root = harness.head_block_root();
+    let head_state_root = harness.head_snapshot().beacon_block.state_root();
+
+    let slot_clock = harness.chain.slot_clock.clone();
+    drop(harness);
+
+    let store = get_store(&db_path);
+    let resumed_harness = BeaconChainHarness::<DiskHarnessType<E>>::builder(MinimalEthSpec)
+        .spec(store.get_chain_spec().clone())
+        .keypairs(KEYPAIRS[0..LOW_VALIDATOR_COUNT].to_vec())
+        .logger(store.logger().clone())
+        .resumed_disk_store(store.clone())
+        .testing_slot_clock(slot_clock)
+        .mock_execution_layer()
+        .chain_config(ChainConfig {
+            fast_restart: true,
+            ..ChainConfig::default()
+        })
+        .build();
+
+    let resumed_head = resumed_harness.chain.head_snapshot();
+    assert_eq!(resumed_head.beacon_block_root, head_block_root);
+    assert_eq!(
+        resumed_head.beacon_state.clone().canonical_root().unwrap(),
+        head_state_root
+    );
+    assert!(resumed_head
+        .beacon_state
+        .committee_cache_is_initialized(RelativeEpoch::Current));
+}
+
 #[tokio::test]
 async fn finalizes_after_resuming_from_db() {
     let validator_count = 16;
@@ -3627,3 +4253,207 @@ fn get_blocks(
         .map(|checkpoint| checkpoint.beacon_block_root.into())
         .collect()
 }
+
+#[tokio::test]
+async fn export_era_file() {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let num_blocks_produced = slots_per_era + 4 * E::slots_per_epoch();
+    let db_path = tempdir().unwrap();
+    let era_dir = tempdir().unwrap();
+    let store_config = StoreConfig {
+        prune_payloads: false,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, store_config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    assert!(store.get_split_slot() > slots_per_era);
+
+    let path = store.export_era(1, era_dir.path()).unwrap();
+    let bytes = std::fs::read(&path).unwrap();
+    let era = store::era::Era::<E>::from_bytes(&bytes, &harness.spec).unwrap();
+
+    assert_eq!(era.state.slot(), slots_per_era);
+    // Every slot of the era except genesis has a block.
+    assert_eq!(era.blocks.len() as u64, slots_per_era - 1);
+    for block in &era.blocks {
+        let expected_block = harness
+            .chain
+            .get_block(&block.canonical_root())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(block, &*expected_block);
+    }
+
+    // The next era is not finalized.
+    assert!(store.export_era(2, era_dir.path()).is_err());
+}
+
+#[tokio::test]
+async fn import_era_files() {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    // Checkpoint sync at an era boundary, so that the exported eras reach the checkpoint.
+    let checkpoint_slot = Slot::new(2 * slots_per_era);
+    let full_db_path = tempdir().unwrap();
+    let era_dir = tempdir().unwrap();
+    let store_config = StoreConfig {
+        prune_payloads: false,
+        ..StoreConfig::default()
+    };
+    let full_store = get_store_generic(&full_db_path, store_config, test_spec::<E>());
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+    let genesis_state = harness.get_current_state();
+
+    harness
+        .extend_chain(
+            (checkpoint_slot + 4 * E::slots_per_epoch()).as_usize(),
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let era_1_path = full_store.export_era(1, era_dir.path()).unwrap();
+    let era_2_path = full_store.export_era(2, era_dir.path()).unwrap();
+
+    // Initialise a new beacon chain from the checkpoint.
+    let wss_block_root = harness
+        .chain
+        .block_root_at_slot(checkpoint_slot, WhenSlotSkipped::None)
+        .unwrap()
+        .unwrap();
+    let wss_state_root = harness
+        .chain
+        .state_root_at_slot(checkpoint_slot)
+        .unwrap()
+        .unwrap();
+    let wss_block = full_store.get_full_block(&wss_block_root).unwrap().unwrap();
+    let wss_state = full_store
+        .get_state(&wss_state_root, Some(checkpoint_slot))
+        .unwrap()
+        .unwrap();
+
+    let (shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+    let log = harness.chain.logger().clone();
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let spec = test_spec::<E>();
+    let kzg = get_kzg(&spec);
+    let mock =
+        mock_execution_layer_from_parts(&harness.spec, harness.runtime.task_executor.clone());
+    let slot_clock = TestingSlotClock::new(
+        Slot::new(0),
+        Duration::from_secs(harness.chain.genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+    slot_clock.set_slot(harness.get_current_slot().as_u64());
+
+    let beacon_chain = BeaconChainBuilder::<DiskHarnessType<E>>::new(MinimalEthSpec, kzg)
+        .store(store.clone())
+        .custom_spec(test_spec::<E>().into())
+        .task_executor(harness.chain.task_executor.clone())
+        .logger(log.clone())
+        .weak_subjectivity_state(wss_state, wss_block, None, genesis_state)
+        .unwrap()
+        .store_migrator_config(MigratorConfig::default().blocking())
+        .dummy_eth1_backend()
+        .expect("should build dummy backend")
+        .slot_clock(slot_clock)
+        .shutdown_sender(shutdown_tx)
+        .chain_config(ChainConfig::default())
+        .event_handler(Some(ServerSentEventHandler::new_with_capacity(log, 1)))
+        .execution_layer(Some(mock.el))
+        .build()
+        .expect("should build");
+    assert_eq!(store.get_oldest_block_slot(), checkpoint_slot);
+
+    // An era state which doesn't match the historical summaries of the checkpoint is rejected.
+    let split = store.get_split_info();
+    let trusted_state = store
+        .get_state(&split.state_root, Some(split.slot))
+        .unwrap()
+        .unwrap();
+    let mut era_2 =
+        store::era::Era::<E>::from_bytes(&std::fs::read(&era_2_path).unwrap(), &spec).unwrap();
+    store::era::verify_era_state(&mut era_2.state, &trusted_state).unwrap();
+    era_2
+        .state
+        .set_state_root(checkpoint_slot - 1, Hash256::repeat_byte(42))
+        .unwrap();
+    assert!(matches!(
+        store::era::verify_era_state(&mut era_2.state, &trusted_state),
+        Err(store::Error::Era(store::era::EraError::EraRootMismatch {
+            era: 2,
+            ..
+        }))
+    ));
+
+    // Eras must be imported from newest to oldest.
+    assert!(matches!(
+        store.import_era(&era_1_path),
+        Err(store::Error::Era(
+            store::era::EraError::DisjointHistory { .. }
+        ))
+    ));
+    assert_eq!(store.get_oldest_block_slot(), checkpoint_slot);
+
+    // A file which can't be decoded is skipped without preventing the import of the others.
+    let era_2_file_name = era_2_path.file_name().unwrap().to_str().unwrap();
+    let bad_path = era_dir
+        .path()
+        .join(era_2_file_name.replace("-00002-", "-00003-"));
+    std::fs::write(&bad_path, [0; 16]).unwrap();
+
+    // Every slot up to the checkpoint except genesis has a block. The state at the end of era 1 is
+    // imported, the state at the end of era 2 is the checkpoint state.
+    let summary = store.import_era_dir(era_dir.path()).unwrap();
+    assert_eq!(summary.blocks as u64, checkpoint_slot.as_u64() - 1);
+    assert_eq!(summary.states, 1);
+    assert_eq!(summary.skipped.len(), 1);
+    assert_eq!(summary.skipped[0].0, bad_path);
+    assert_eq!(store.get_oldest_block_slot(), 0);
+
+    // Historic states which aren't stored are served from the era states.
+    for slot in [
+        1,
+        slots_per_era - 1,
+        slots_per_era,
+        checkpoint_slot.as_u64() - 1,
+    ]
+    .map(Slot::new)
+    {
+        let state_root = harness.chain.state_root_at_slot(slot).unwrap().unwrap();
+        assert_eq!(
+            beacon_chain.state_root_at_slot(slot).unwrap(),
+            Some(state_root)
+        );
+        let mut state = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.canonical_root().unwrap(), state_root);
+    }
+
+    for slot in (0..checkpoint_slot.as_u64()).map(Slot::new) {
+        let block_root = beacon_chain
+            .block_root_at_slot(slot, WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            Some(block_root),
+            harness
+                .chain
+                .block_root_at_slot(slot, WhenSlotSkipped::None)
+                .unwrap()
+        );
+        assert!(store.get_blinded_block(&block_root).unwrap().is_some());
+    }
+
+    // Importing again once history is complete is a no-op.
+    let summary = store.import_era_dir(era_dir.path()).unwrap();
+    assert_eq!((summary.blocks, summary.states), (0, 0));
+}
@@ -2,18 +2,20 @@
 
 use beacon_chain::{
     attestation_verification::Error as AttnError,
+    head_state_pool::HeadStatePool,
     test_utils::{
         AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
         OP_POOL_DB_KEY,
     },
-    BeaconChain, ChainConfig, NotifyExecutionLayer, StateSkipConfig, WhenSlotSkipped,
+    BeaconChain, BeaconChainError, ChainConfig, NotifyExecutionLayer, StateSkipConfig,
+    WhenSlotSkipped,
 };
 use operation_pool::PersistedOperationPool;
 use state_processing::{per_slot_processing, per_slot_processing::Error as SlotProcessingError};
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use types::{
-    BeaconState, BeaconStateError, BlockImportSource, EthSpec, Hash256, Keypair, MinimalEthSpec,
-    RelativeEpoch, Slot,
+    BeaconState, BeaconStateError, BlockImportSource, Epoch, EthSpec, ForkName, Hash256, Keypair,
+    MinimalEthSpec, RelativeEpoch, Slot,
 };
 
 // Should ideally be divisible by 3.
@@ -62,6 +64,37 @@ fn massive_skips() {
     )
 }
 
+#[tokio::test]
+async fn head_state_pool() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    let pool = HeadStatePool::new(2);
+
+    let old_head = harness.chain.head_snapshot();
+    assert!(pool.take(&old_head).is_none());
+    pool.refill(&old_head);
+
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    let new_head = harness.chain.head_snapshot();
+
+    // Clones of another head state are never returned.
+    assert!(pool.take(&new_head).is_none());
+    assert_eq!(pool.take(&old_head).unwrap(), old_head.beacon_state);
+    assert_eq!(pool.take(&old_head).unwrap(), old_head.beacon_state);
+    assert!(pool.take(&old_head).is_none());
+
+    // Refilling for a new head discards clones of the old head state.
+    pool.refill(&old_head);
+    pool.refill(&new_head);
+    assert!(pool.take(&old_head).is_none());
+    assert_eq!(pool.take(&new_head).unwrap(), new_head.beacon_state);
+}
+
 #[tokio::test]
 async fn iterators() {
     let num_blocks_produced = MinimalEthSpec::slots_per_epoch() * 2 - 1;
@@ -870,3 +903,72 @@ async fn block_roots_skip_slot_behaviour() {
         "WhenSlotSkipped::Prev should return None on a future slot"
     );
 }
+
+#[tokio::test]
+async fn validator_state_proofs_are_cached() {
+    let harness = get_harness(VALIDATOR_COUNT);
+    harness
+        .extend_chain(
+            MinimalEthSpec::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let chain = &harness.chain;
+    let mut state = harness.get_current_state();
+    let state_root = state.canonical_root().unwrap();
+
+    let indices = [0, VALIDATOR_COUNT - 1];
+    let proofs = chain
+        .validator_state_proofs(state_root, &state, &indices)
+        .unwrap();
+    assert_eq!(
+        proofs,
+        state.compute_validator_state_proofs(&indices).unwrap()
+    );
+    assert!(proofs.iter().all(|proof| proof.verify(state_root)));
+
+    // The registry tree is built once per state.
+    let tree = chain.validators_proof_tree(state_root, &state).unwrap();
+    assert!(Arc::ptr_eq(
+        &tree,
+        &chain.validators_proof_tree(state_root, &state).unwrap()
+    ));
+
+    assert!(matches!(
+        chain.validator_state_proofs(state_root, &state, &[VALIDATOR_COUNT]),
+        Err(BeaconChainError::BeaconStateError(
+            BeaconStateError::UnknownValidator(_)
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn sampling_is_limited_to_data_availability_window() {
+    let mut spec = ForkName::Deneb.make_genesis_spec(MinimalEthSpec::default_spec());
+    spec.eip7594_fork_epoch = Some(Epoch::new(0));
+    spec.min_epochs_for_blob_sidecars_requests = 2;
+
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(Arc::new(spec))
+        .chain_config(ChainConfig {
+            enable_sampling: true,
+            ..ChainConfig::default()
+        })
+        .keypairs(KEYPAIRS[0..VALIDATOR_COUNT].to_vec())
+        .fresh_ephemeral_store()
+        .mock_execution_layer()
+        .build();
+
+    let slots_per_epoch = MinimalEthSpec::slots_per_epoch();
+    harness.set_current_slot(Epoch::new(5).start_slot(slots_per_epoch));
+
+    // The window starts `min_epochs_for_blob_sidecars_requests` epochs before the current epoch.
+    let chain = &harness.chain;
+    let boundary_slot = Epoch::new(3).start_slot(slots_per_epoch);
+    assert_eq!(chain.data_availability_boundary(), Some(Epoch::new(3)));
+    assert!(chain.should_sample_slot(boundary_slot));
+    assert!(!chain.should_sample_slot(boundary_slot - 1));
+    assert!(chain.should_sample_slot(harness.get_current_slot()));
+}
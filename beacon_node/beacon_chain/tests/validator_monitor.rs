@@ -1,10 +1,16 @@
 use beacon_chain::test_utils::{
     AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType,
 };
-use beacon_chain::validator_monitor::{ValidatorMonitorConfig, MISSED_BLOCK_LAG_SLOTS};
+use beacon_chain::validator_monitor::{
+    client_from_graffiti, ValidatorMonitorConfig, MISSED_BLOCK_LAG_SLOTS,
+};
 use logging::test_logger;
+use std::str::FromStr;
 use std::sync::LazyLock;
-use types::{Epoch, EthSpec, ForkName, Keypair, MainnetEthSpec, PublicKeyBytes, Slot};
+use types::{
+    Epoch, EthSpec, ForkName, Graffiti, GraffitiString, Keypair, MainnetEthSpec, PublicKeyBytes,
+    Slot,
+};
 
 // Should ideally be divisible by 3.
 pub const VALIDATOR_COUNT: usize = 48;
@@ -39,6 +45,22 @@ fn get_harness(
     harness
 }
 
+#[test]
+fn client_fingerprinting_from_graffiti() {
+    let fingerprint = |graffiti: &str| {
+        let graffiti: Graffiti = GraffitiString::from_str(graffiti).unwrap().into();
+        client_from_graffiti(&graffiti)
+    };
+
+    assert_eq!(fingerprint("Lighthouse/v5.3.0"), Some("lighthouse"));
+    assert_eq!(fingerprint("teku/v24.8.0"), Some("teku"));
+    assert_eq!(fingerprint("GE1234LH5678"), Some("lighthouse"));
+    assert_eq!(fingerprint("NMabcdPMef01"), Some("prysm"));
+    assert_eq!(fingerprint("hello world"), None);
+    // Two-letter codes are only matched in the client version position.
+    assert_eq!(fingerprint("LH"), None);
+}
+
 // Regression test for off-by-one caching issue in missed block detection.
 #[tokio::test]
 async fn missed_blocks_across_epochs() {
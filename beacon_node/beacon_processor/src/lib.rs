@@ -37,6 +37,15 @@
 //! Whenever the manager receives a notification that a worker has finished a parcel of work, it
 //! checks the queues to see if there are more parcels of work that can be spawned in a new worker
 //! task.
+//!
+//! ## Gossip validation
+//!
+//! Gossip validation is split into priority classes: blocks > blobs and data columns > aggregates >
+//! unaggregated attestations. All classes except blocks are validated by a pool of at most
+//! `max_gossip_workers` workers, which is smaller than `n` by default. This ensures a flood of
+//! attestations can never occupy every worker and delay the import of blocks. When the queue for a
+//! class is full, messages are shed and counted in the `beacon_processor_gossip_shed_total`
+//! metric.
 
 use crate::work_reprocessing_queue::{
     QueuedBackfillBatch, QueuedGossipBlock, ReprocessQueueMessage,
@@ -46,6 +55,7 @@ use futures::task::Poll;
 use lighthouse_network::{MessageId, NetworkGlobals, PeerId};
 use logging::TimeLatch;
 use parking_lot::Mutex;
+use queue_monitor::QueueStats;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, trace, warn, Logger};
 use slot_clock::SlotClock;
@@ -56,7 +66,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use strum::IntoStaticStr;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
@@ -72,8 +82,11 @@ use work_reprocessing_queue::{
 use work_reprocessing_queue::{IgnoredRpcBlock, QueuedSamplingRequest};
 
 mod metrics;
+mod queue_monitor;
 pub mod work_reprocessing_queue;
 
+pub use queue_monitor::{QueueMonitor, QueueStatus};
+
 /// The maximum size of the channel for work events to the `BeaconProcessor`.
 ///
 /// Setting this too low will cause consensus messages to be dropped.
@@ -237,6 +250,9 @@ pub const NOTHING_TO_DO: &str = "nothing_to_do";
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct BeaconProcessorConfig {
     pub max_workers: usize,
+    /// The maximum number of workers that may concurrently validate gossip blobs, data columns,
+    /// aggregates and attestations. See `Self::gossip_worker_limit` for the default.
+    pub max_gossip_workers: Option<usize>,
     pub max_work_event_queue_len: usize,
     pub max_scheduled_work_queue_len: usize,
     pub max_gossip_attestation_batch_size: usize,
@@ -248,6 +264,7 @@ impl Default for BeaconProcessorConfig {
     fn default() -> Self {
         Self {
             max_workers: cmp::max(1, num_cpus::get()),
+            max_gossip_workers: None,
             max_work_event_queue_len: DEFAULT_MAX_WORK_EVENT_QUEUE_LEN,
             max_scheduled_work_queue_len: DEFAULT_MAX_SCHEDULED_WORK_QUEUE_LEN,
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
@@ -257,12 +274,25 @@ impl Default for BeaconProcessorConfig {
     }
 }
 
+impl BeaconProcessorConfig {
+    /// Returns the size of the gossip validation pool, which never exceeds `max_workers`.
+    ///
+    /// By default, one worker is reserved for gossip blocks and other work when possible.
+    pub fn gossip_worker_limit(&self) -> usize {
+        let limit = self
+            .max_gossip_workers
+            .unwrap_or_else(|| self.max_workers.saturating_sub(1));
+        cmp::max(1, cmp::min(limit, self.max_workers))
+    }
+}
+
 // The channels necessary to instantiate a `BeaconProcessor`.
 pub struct BeaconProcessorChannels<E: EthSpec> {
     pub beacon_processor_tx: BeaconProcessorSend<E>,
     pub beacon_processor_rx: mpsc::Receiver<WorkEvent<E>>,
     pub work_reprocessing_tx: mpsc::Sender<ReprocessQueueMessage>,
     pub work_reprocessing_rx: mpsc::Receiver<ReprocessQueueMessage>,
+    pub queue_monitor: QueueMonitor,
 }
 
 impl<E: EthSpec> BeaconProcessorChannels<E> {
@@ -277,6 +307,7 @@ impl<E: EthSpec> BeaconProcessorChannels<E> {
             beacon_processor_rx,
             work_reprocessing_rx,
             work_reprocessing_tx,
+            queue_monitor: QueueMonitor::default(),
         }
     }
 }
@@ -288,17 +319,21 @@ impl<E: EthSpec> Default for BeaconProcessorChannels<E> {
 }
 
 /// A simple first-in-first-out queue with a maximum length.
+///
+/// The maximum length and whether the queue is paused are controlled via a `QueueMonitor`.
 struct FifoQueue<T> {
-    queue: VecDeque<T>,
-    max_length: usize,
+    name: &'static str,
+    queue: VecDeque<(T, Instant)>,
+    stats: Arc<QueueStats>,
 }
 
 impl<T> FifoQueue<T> {
-    /// Create a new, empty queue with the given length.
-    pub fn new(max_length: usize) -> Self {
+    /// Create a new, empty queue with the given length and register it with the `monitor`.
+    pub fn new(work_type: WorkType, max_length: usize, monitor: &QueueMonitor) -> Self {
         Self {
+            name: work_type.into(),
             queue: VecDeque::default(),
-            max_length,
+            stats: monitor.register(work_type, max_length),
         }
     }
 
@@ -306,69 +341,135 @@ impl<T> FifoQueue<T> {
     ///
     /// Drops `item` if the queue is full.
     pub fn push(&mut self, item: T, item_desc: &str, log: &Logger) {
-        if self.queue.len() == self.max_length {
+        if self.is_full() {
             error!(
                 log,
                 "Work queue is full";
                 "msg" => "the system has insufficient resources for load",
-                "queue_len" => self.max_length,
+                "queue_len" => self.max_length(),
                 "queue" => item_desc,
-            )
+            );
+            observe_queue_drop(self.name, &self.stats);
         } else {
-            self.queue.push_back(item);
+            self.queue.push_back((item, Instant::now()));
+            self.stats.set_length(self.queue.len());
         }
     }
 
-    /// Remove the next item from the queue.
+    /// Remove the next item from the queue, unless the queue is paused.
     pub fn pop(&mut self) -> Option<T> {
-        self.queue.pop_front()
+        if self.stats.is_paused() {
+            return None;
+        }
+        let (item, queued_at) = self.queue.pop_front()?;
+        observe_queue_pop(self.name, &self.stats, queued_at, self.queue.len());
+        Some(item)
+    }
+
+    /// Returns `true` if the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.queue.len() >= self.max_length()
     }
 
-    /// Returns the current length of the queue.
+    /// Returns the number of items in the queue, including whilst the queue is paused.
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    /// Returns the number of items that can be removed from the queue, which is zero whilst the
+    /// queue is paused.
+    pub fn ready_len(&self) -> usize {
+        if self.stats.is_paused() {
+            0
+        } else {
+            self.queue.len()
+        }
+    }
+
+    /// Returns the current maximum length of the queue.
+    pub fn max_length(&self) -> usize {
+        self.stats.max_length()
+    }
 }
 
 /// A simple last-in-first-out queue with a maximum length.
+///
+/// The maximum length and whether the queue is paused are controlled via a `QueueMonitor`.
 struct LifoQueue<T> {
-    queue: VecDeque<T>,
-    max_length: usize,
+    name: &'static str,
+    queue: VecDeque<(T, Instant)>,
+    stats: Arc<QueueStats>,
 }
 
 impl<T> LifoQueue<T> {
-    /// Create a new, empty queue with the given length.
-    pub fn new(max_length: usize) -> Self {
+    /// Create a new, empty queue with the given length and register it with the `monitor`.
+    pub fn new(work_type: WorkType, max_length: usize, monitor: &QueueMonitor) -> Self {
         Self {
+            name: work_type.into(),
             queue: VecDeque::default(),
-            max_length,
+            stats: monitor.register(work_type, max_length),
         }
     }
 
     /// Add a new item to the front of the queue.
     ///
-    /// If the queue is full, the item at the back of the queue is dropped.
+    /// If the queue is full, items at the back of the queue are dropped.
     pub fn push(&mut self, item: T) {
-        if self.queue.len() == self.max_length {
-            self.queue.pop_back();
+        while self.is_full() && self.queue.pop_back().is_some() {
+            observe_queue_drop(self.name, &self.stats);
         }
-        self.queue.push_front(item);
+        self.queue.push_front((item, Instant::now()));
+        self.stats.set_length(self.queue.len());
     }
 
-    /// Remove the next item from the queue.
+    /// Remove the next item from the queue, unless the queue is paused.
     pub fn pop(&mut self) -> Option<T> {
-        self.queue.pop_front()
+        if self.stats.is_paused() {
+            return None;
+        }
+        let (item, queued_at) = self.queue.pop_front()?;
+        observe_queue_pop(self.name, &self.stats, queued_at, self.queue.len());
+        Some(item)
     }
 
     /// Returns `true` if the queue is full.
     pub fn is_full(&self) -> bool {
-        self.queue.len() >= self.max_length
+        self.queue.len() >= self.max_length()
     }
 
-    /// Returns the current length of the queue.
+    /// Returns the number of items in the queue, including whilst the queue is paused.
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    /// Returns the number of items that can be removed from the queue, which is zero whilst the
+    /// queue is paused.
+    pub fn ready_len(&self) -> usize {
+        if self.stats.is_paused() {
+            0
+        } else {
+            self.queue.len()
+        }
+    }
+
+    /// Returns the current maximum length of the queue.
+    pub fn max_length(&self) -> usize {
+        self.stats.max_length()
+    }
+}
+
+/// Records that an item was dropped from the queue called `name` because it was full.
+fn observe_queue_drop(name: &'static str, stats: &QueueStats) {
+    stats.observe_drop();
+    metrics::inc_counter_vec(&metrics::BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL, &[name]);
+}
+
+/// Records that an item which was queued at `queued_at` was removed from the queue called `name`.
+fn observe_queue_pop(name: &'static str, stats: &QueueStats, queued_at: Instant, length: usize) {
+    let wait = queued_at.elapsed();
+    stats.observe_processed(wait);
+    stats.set_length(length);
+    metrics::observe_timer_vec(&metrics::BEACON_PROCESSOR_QUEUE_WAIT_SECONDS, &[name], wait);
 }
 
 /// A handle that sends a message on the provided channel to a receiver when it gets dropped.
@@ -635,7 +736,7 @@ impl<E: EthSpec> fmt::Debug for Work<E> {
     }
 }
 
-#[derive(IntoStaticStr, PartialEq, Eq, Debug)]
+#[derive(IntoStaticStr, Clone, Copy, PartialEq, Eq, Debug)]
 #[strum(serialize_all = "snake_case")]
 pub enum WorkType {
     GossipAttestation,
@@ -681,11 +782,58 @@ pub enum WorkType {
     ApiRequestP1,
 }
 
+/// The priority classes of gossip validation work, from highest to lowest priority.
+#[derive(IntoStaticStr, Clone, Copy, PartialEq, Eq, Debug)]
+#[strum(serialize_all = "snake_case")]
+pub enum GossipClass {
+    Block,
+    Blob,
+    Aggregate,
+    Attestation,
+}
+
+/// The pool of workers that processes a parcel of work.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum WorkerPool {
+    /// Limited to `BeaconProcessorConfig::max_gossip_workers` workers.
+    GossipValidation,
+    /// Limited only by `BeaconProcessorConfig::max_workers`.
+    General,
+}
+
 impl<E: EthSpec> Work<E> {
     fn str_id(&self) -> &'static str {
         self.to_type().into()
     }
 
+    /// Returns the gossip validation priority class of the work, if it is gossip validation.
+    fn gossip_class(&self) -> Option<GossipClass> {
+        match self {
+            Work::GossipBlock(_) => Some(GossipClass::Block),
+            Work::GossipAggregate { .. } | Work::GossipAggregateBatch { .. } => {
+                Some(GossipClass::Aggregate)
+            }
+            Work::GossipAttestation { .. } | Work::GossipAttestationBatch { .. } => {
+                Some(GossipClass::Attestation)
+            }
+            Work::GossipBlobSidecar(_) | Work::GossipDataColumnSidecar(_) => {
+                Some(GossipClass::Blob)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the pool of workers that should process the work.
+    ///
+    /// Gossip blocks are not limited to the gossip validation pool so that they are never delayed
+    /// by lower priority gossip.
+    fn worker_pool(&self) -> WorkerPool {
+        match self.gossip_class() {
+            Some(GossipClass::Block) | None => WorkerPool::General,
+            Some(_) => WorkerPool::GossipValidation,
+        }
+    }
+
     /// Provides a `&str` that uniquely identifies each enum variant.
     fn to_type(&self) -> WorkType {
         match self {
@@ -742,8 +890,8 @@ impl<E: EthSpec> Work<E> {
 
 /// Unifies all the messages processed by the `BeaconProcessor`.
 enum InboundEvent<E: EthSpec> {
-    /// A worker has completed a task and is free.
-    WorkerIdle,
+    /// A worker from the given pool has completed a task and is free.
+    WorkerIdle(WorkerPool),
     /// There is new work to be done.
     WorkEvent(WorkEvent<E>),
     /// A work event that was queued for re-processing has become ready.
@@ -756,7 +904,7 @@ enum InboundEvent<E: EthSpec> {
 /// control (specifically in the ordering of event processing).
 struct InboundEvents<E: EthSpec> {
     /// Used by workers when they finish a task.
-    idle_rx: mpsc::Receiver<()>,
+    idle_rx: mpsc::Receiver<WorkerPool>,
     /// Used by upstream processes to send new work to the `BeaconProcessor`.
     event_rx: mpsc::Receiver<WorkEvent<E>>,
     /// Used internally for queuing work ready to be re-processed.
@@ -770,8 +918,8 @@ impl<E: EthSpec> Stream for InboundEvents<E> {
         // Always check for idle workers before anything else. This allows us to ensure that a big
         // stream of new events doesn't suppress the processing of existing events.
         match self.idle_rx.poll_recv(cx) {
-            Poll::Ready(Some(())) => {
-                return Poll::Ready(Some(InboundEvent::WorkerIdle));
+            Poll::Ready(Some(pool)) => {
+                return Poll::Ready(Some(InboundEvent::WorkerIdle(pool)));
             }
             Poll::Ready(None) => {
                 return Poll::Ready(None);
@@ -814,6 +962,8 @@ pub struct BeaconProcessor<E: EthSpec> {
     pub executor: TaskExecutor,
     pub current_workers: usize,
     pub config: BeaconProcessorConfig,
+    /// Provides the state of the queues to other services, and allows them to be adjusted.
+    pub queue_monitor: QueueMonitor,
     pub log: Logger,
 }
 
@@ -841,76 +991,217 @@ impl<E: EthSpec> BeaconProcessor<E> {
         queue_lengths: BeaconProcessorQueueLengths,
     ) -> Result<(), String> {
         // Used by workers to communicate that they are finished a task.
-        let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
+        let (idle_tx, idle_rx) = mpsc::channel::<WorkerPool>(MAX_IDLE_QUEUE_LEN);
+
+        let queue_monitor = self.queue_monitor.clone();
 
         // Using LIFO queues for attestations since validator profits rely upon getting fresh
         // attestations into blocks. Additionally, later attestations contain more information than
         // earlier ones, so we consider them more valuable.
-        let mut aggregate_queue = LifoQueue::new(queue_lengths.aggregate_queue);
+        let mut aggregate_queue = LifoQueue::new(
+            WorkType::GossipAggregate,
+            queue_lengths.aggregate_queue,
+            &queue_monitor,
+        );
         let mut aggregate_debounce = TimeLatch::default();
-        let mut attestation_queue = LifoQueue::new(queue_lengths.attestation_queue);
+        let mut attestation_queue = LifoQueue::new(
+            WorkType::GossipAttestation,
+            queue_lengths.attestation_queue,
+            &queue_monitor,
+        );
         let mut attestation_debounce = TimeLatch::default();
-        let mut unknown_block_aggregate_queue =
-            LifoQueue::new(queue_lengths.unknown_block_aggregate_queue);
-        let mut unknown_block_attestation_queue =
-            LifoQueue::new(queue_lengths.unknown_block_attestation_queue);
+        let mut unknown_block_aggregate_queue = LifoQueue::new(
+            WorkType::UnknownBlockAggregate,
+            queue_lengths.unknown_block_aggregate_queue,
+            &queue_monitor,
+        );
+        let mut unknown_block_attestation_queue = LifoQueue::new(
+            WorkType::UnknownBlockAttestation,
+            queue_lengths.unknown_block_attestation_queue,
+            &queue_monitor,
+        );
 
-        let mut sync_message_queue = LifoQueue::new(queue_lengths.sync_message_queue);
-        let mut sync_contribution_queue = LifoQueue::new(queue_lengths.sync_contribution_queue);
+        let mut sync_message_queue = LifoQueue::new(
+            WorkType::GossipSyncSignature,
+            queue_lengths.sync_message_queue,
+            &queue_monitor,
+        );
+        let mut sync_contribution_queue = LifoQueue::new(
+            WorkType::GossipSyncContribution,
+            queue_lengths.sync_contribution_queue,
+            &queue_monitor,
+        );
 
         // Using a FIFO queue for voluntary exits since it prevents exit censoring. I don't have
         // a strong feeling about queue type for exits.
-        let mut gossip_voluntary_exit_queue =
-            FifoQueue::new(queue_lengths.gossip_voluntary_exit_queue);
+        let mut gossip_voluntary_exit_queue = FifoQueue::new(
+            WorkType::GossipVoluntaryExit,
+            queue_lengths.gossip_voluntary_exit_queue,
+            &queue_monitor,
+        );
 
         // Using a FIFO queue for slashing to prevent people from flushing their slashings from the
         // queues with lots of junk messages.
-        let mut gossip_proposer_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_proposer_slashing_queue);
-        let mut gossip_attester_slashing_queue =
-            FifoQueue::new(queue_lengths.gossip_attester_slashing_queue);
+        let mut gossip_proposer_slashing_queue = FifoQueue::new(
+            WorkType::GossipProposerSlashing,
+            queue_lengths.gossip_proposer_slashing_queue,
+            &queue_monitor,
+        );
+        let mut gossip_attester_slashing_queue = FifoQueue::new(
+            WorkType::GossipAttesterSlashing,
+            queue_lengths.gossip_attester_slashing_queue,
+            &queue_monitor,
+        );
 
         // Using a FIFO queue for light client updates to maintain sequence order.
-        let mut finality_update_queue = FifoQueue::new(queue_lengths.finality_update_queue);
-        let mut optimistic_update_queue = FifoQueue::new(queue_lengths.optimistic_update_queue);
-        let mut unknown_light_client_update_queue =
-            FifoQueue::new(queue_lengths.unknown_light_client_update_queue);
-        let mut unknown_block_sampling_request_queue =
-            FifoQueue::new(queue_lengths.unknown_block_sampling_request_queue);
+        let mut finality_update_queue = FifoQueue::new(
+            WorkType::GossipLightClientFinalityUpdate,
+            queue_lengths.finality_update_queue,
+            &queue_monitor,
+        );
+        let mut optimistic_update_queue = FifoQueue::new(
+            WorkType::GossipLightClientOptimisticUpdate,
+            queue_lengths.optimistic_update_queue,
+            &queue_monitor,
+        );
+        let mut unknown_light_client_update_queue = FifoQueue::new(
+            WorkType::UnknownLightClientOptimisticUpdate,
+            queue_lengths.unknown_light_client_update_queue,
+            &queue_monitor,
+        );
+        let mut unknown_block_sampling_request_queue = FifoQueue::new(
+            WorkType::UnknownBlockSamplingRequest,
+            queue_lengths.unknown_block_sampling_request_queue,
+            &queue_monitor,
+        );
 
         // Using a FIFO queue since blocks need to be imported sequentially.
-        let mut rpc_block_queue = FifoQueue::new(queue_lengths.rpc_block_queue);
-        let mut rpc_blob_queue = FifoQueue::new(queue_lengths.rpc_blob_queue);
-        let mut rpc_custody_column_queue = FifoQueue::new(queue_lengths.rpc_custody_column_queue);
-        let mut rpc_verify_data_column_queue =
-            FifoQueue::new(queue_lengths.rpc_verify_data_column_queue);
-        let mut sampling_result_queue = FifoQueue::new(queue_lengths.sampling_result_queue);
-        let mut chain_segment_queue = FifoQueue::new(queue_lengths.chain_segment_queue);
-        let mut backfill_chain_segment = FifoQueue::new(queue_lengths.backfill_chain_segment);
-        let mut gossip_block_queue = FifoQueue::new(queue_lengths.gossip_block_queue);
-        let mut gossip_blob_queue = FifoQueue::new(queue_lengths.gossip_blob_queue);
-        let mut gossip_data_column_queue = FifoQueue::new(queue_lengths.gossip_data_column_queue);
-        let mut delayed_block_queue = FifoQueue::new(queue_lengths.delayed_block_queue);
-
-        let mut status_queue = FifoQueue::new(queue_lengths.status_queue);
-        let mut bbrange_queue = FifoQueue::new(queue_lengths.bbrange_queue);
-        let mut bbroots_queue = FifoQueue::new(queue_lengths.bbroots_queue);
-        let mut blbroots_queue = FifoQueue::new(queue_lengths.blbroots_queue);
-        let mut blbrange_queue = FifoQueue::new(queue_lengths.blbrange_queue);
-        let mut dcbroots_queue = FifoQueue::new(queue_lengths.dcbroots_queue);
-        let mut dcbrange_queue = FifoQueue::new(queue_lengths.dcbrange_queue);
-
-        let mut gossip_bls_to_execution_change_queue =
-            FifoQueue::new(queue_lengths.gossip_bls_to_execution_change_queue);
-
-        let mut lc_bootstrap_queue = FifoQueue::new(queue_lengths.lc_bootstrap_queue);
-        let mut lc_optimistic_update_queue =
-            FifoQueue::new(queue_lengths.lc_optimistic_update_queue);
-        let mut lc_finality_update_queue = FifoQueue::new(queue_lengths.lc_finality_update_queue);
-        let mut lc_update_range_queue = FifoQueue::new(queue_lengths.lc_update_range_queue);
-
-        let mut api_request_p0_queue = FifoQueue::new(queue_lengths.api_request_p0_queue);
-        let mut api_request_p1_queue = FifoQueue::new(queue_lengths.api_request_p1_queue);
+        let mut rpc_block_queue = FifoQueue::new(
+            WorkType::RpcBlock,
+            queue_lengths.rpc_block_queue,
+            &queue_monitor,
+        );
+        let mut rpc_blob_queue = FifoQueue::new(
+            WorkType::RpcBlobs,
+            queue_lengths.rpc_blob_queue,
+            &queue_monitor,
+        );
+        let mut rpc_custody_column_queue = FifoQueue::new(
+            WorkType::RpcCustodyColumn,
+            queue_lengths.rpc_custody_column_queue,
+            &queue_monitor,
+        );
+        let mut rpc_verify_data_column_queue = FifoQueue::new(
+            WorkType::RpcVerifyDataColumn,
+            queue_lengths.rpc_verify_data_column_queue,
+            &queue_monitor,
+        );
+        let mut sampling_result_queue = FifoQueue::new(
+            WorkType::SamplingResult,
+            queue_lengths.sampling_result_queue,
+            &queue_monitor,
+        );
+        let mut chain_segment_queue = FifoQueue::new(
+            WorkType::ChainSegment,
+            queue_lengths.chain_segment_queue,
+            &queue_monitor,
+        );
+        let mut backfill_chain_segment = FifoQueue::new(
+            WorkType::ChainSegmentBackfill,
+            queue_lengths.backfill_chain_segment,
+            &queue_monitor,
+        );
+        let mut gossip_block_queue = FifoQueue::new(
+            WorkType::GossipBlock,
+            queue_lengths.gossip_block_queue,
+            &queue_monitor,
+        );
+        let mut gossip_blob_queue = FifoQueue::new(
+            WorkType::GossipBlobSidecar,
+            queue_lengths.gossip_blob_queue,
+            &queue_monitor,
+        );
+        let mut gossip_data_column_queue = FifoQueue::new(
+            WorkType::GossipDataColumnSidecar,
+            queue_lengths.gossip_data_column_queue,
+            &queue_monitor,
+        );
+        let mut delayed_block_queue = FifoQueue::new(
+            WorkType::DelayedImportBlock,
+            queue_lengths.delayed_block_queue,
+            &queue_monitor,
+        );
+
+        let mut status_queue =
+            FifoQueue::new(WorkType::Status, queue_lengths.status_queue, &queue_monitor);
+        let mut bbrange_queue = FifoQueue::new(
+            WorkType::BlocksByRangeRequest,
+            queue_lengths.bbrange_queue,
+            &queue_monitor,
+        );
+        let mut bbroots_queue = FifoQueue::new(
+            WorkType::BlocksByRootsRequest,
+            queue_lengths.bbroots_queue,
+            &queue_monitor,
+        );
+        let mut blbroots_queue = FifoQueue::new(
+            WorkType::BlobsByRootsRequest,
+            queue_lengths.blbroots_queue,
+            &queue_monitor,
+        );
+        let mut blbrange_queue = FifoQueue::new(
+            WorkType::BlobsByRangeRequest,
+            queue_lengths.blbrange_queue,
+            &queue_monitor,
+        );
+        let mut dcbroots_queue = FifoQueue::new(
+            WorkType::DataColumnsByRootsRequest,
+            queue_lengths.dcbroots_queue,
+            &queue_monitor,
+        );
+        let mut dcbrange_queue = FifoQueue::new(
+            WorkType::DataColumnsByRangeRequest,
+            queue_lengths.dcbrange_queue,
+            &queue_monitor,
+        );
+
+        let mut gossip_bls_to_execution_change_queue = FifoQueue::new(
+            WorkType::GossipBlsToExecutionChange,
+            queue_lengths.gossip_bls_to_execution_change_queue,
+            &queue_monitor,
+        );
+
+        let mut lc_bootstrap_queue = FifoQueue::new(
+            WorkType::LightClientBootstrapRequest,
+            queue_lengths.lc_bootstrap_queue,
+            &queue_monitor,
+        );
+        let mut lc_optimistic_update_queue = FifoQueue::new(
+            WorkType::LightClientOptimisticUpdateRequest,
+            queue_lengths.lc_optimistic_update_queue,
+            &queue_monitor,
+        );
+        let mut lc_finality_update_queue = FifoQueue::new(
+            WorkType::LightClientFinalityUpdateRequest,
+            queue_lengths.lc_finality_update_queue,
+            &queue_monitor,
+        );
+        let mut lc_update_range_queue = FifoQueue::new(
+            WorkType::LightClientUpdatesByRangeRequest,
+            queue_lengths.lc_update_range_queue,
+            &queue_monitor,
+        );
+
+        let mut api_request_p0_queue = FifoQueue::new(
+            WorkType::ApiRequestP0,
+            queue_lengths.api_request_p0_queue,
+            &queue_monitor,
+        );
+        let mut api_request_p1_queue = FifoQueue::new(
+            WorkType::ApiRequestP1,
+            queue_lengths.api_request_p1_queue,
+            &queue_monitor,
+        );
 
         // Channels for sending work to the re-process scheduler (`work_reprocessing_tx`) and to
         // receive them back once they are ready (`ready_work_rx`).
@@ -937,11 +1228,16 @@ impl<E: EthSpec> BeaconProcessor<E> {
             };
 
             let enable_backfill_rate_limiting = self.config.enable_backfill_rate_limiting;
+            let max_gossip_workers = self.config.gossip_worker_limit();
+            let mut current_gossip_workers: usize = 0;
 
             loop {
                 let work_event = match inbound_events.next().await {
-                    Some(InboundEvent::WorkerIdle) => {
+                    Some(InboundEvent::WorkerIdle(pool)) => {
                         self.current_workers = self.current_workers.saturating_sub(1);
+                        if pool == WorkerPool::GossipValidation {
+                            current_gossip_workers = current_gossip_workers.saturating_sub(1);
+                        }
                         None
                     }
                     Some(InboundEvent::WorkEvent(event)) if enable_backfill_rate_limiting => {
@@ -1020,6 +1316,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 }
 
                 let can_spawn = self.current_workers < self.config.max_workers;
+                let can_spawn_gossip = can_spawn && current_gossip_workers < max_gossip_workers;
                 let drop_during_sync = work_event
                     .as_ref()
                     .map_or(false, |event| event.drop_during_sync);
@@ -1031,11 +1328,88 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
+                        // Check the queues prioritized via the admin API before all others, in
+                        // the order in which they were prioritized. Items are not batched, and
+                        // gossip validation work is still limited by `can_spawn_gossip`.
+                        let prioritized_work =
+                            queue_monitor
+                                .prioritized()
+                                .into_iter()
+                                .find_map(|work_type| match work_type {
+                                    WorkType::GossipAttestation if can_spawn_gossip => {
+                                        attestation_queue.pop()
+                                    }
+                                    WorkType::GossipAggregate if can_spawn_gossip => {
+                                        aggregate_queue.pop()
+                                    }
+                                    WorkType::GossipBlobSidecar if can_spawn_gossip => {
+                                        gossip_blob_queue.pop()
+                                    }
+                                    WorkType::GossipDataColumnSidecar if can_spawn_gossip => {
+                                        gossip_data_column_queue.pop()
+                                    }
+                                    WorkType::UnknownBlockAttestation => {
+                                        unknown_block_attestation_queue.pop()
+                                    }
+                                    WorkType::UnknownBlockAggregate => {
+                                        unknown_block_aggregate_queue.pop()
+                                    }
+                                    WorkType::UnknownBlockSamplingRequest => {
+                                        unknown_block_sampling_request_queue.pop()
+                                    }
+                                    WorkType::GossipBlock => gossip_block_queue.pop(),
+                                    WorkType::DelayedImportBlock => delayed_block_queue.pop(),
+                                    WorkType::GossipVoluntaryExit => {
+                                        gossip_voluntary_exit_queue.pop()
+                                    }
+                                    WorkType::GossipProposerSlashing => {
+                                        gossip_proposer_slashing_queue.pop()
+                                    }
+                                    WorkType::GossipAttesterSlashing => {
+                                        gossip_attester_slashing_queue.pop()
+                                    }
+                                    WorkType::GossipSyncSignature => sync_message_queue.pop(),
+                                    WorkType::GossipSyncContribution => {
+                                        sync_contribution_queue.pop()
+                                    }
+                                    WorkType::RpcBlock => rpc_block_queue.pop(),
+                                    WorkType::RpcBlobs => rpc_blob_queue.pop(),
+                                    WorkType::RpcCustodyColumn => rpc_custody_column_queue.pop(),
+                                    WorkType::RpcVerifyDataColumn => {
+                                        rpc_verify_data_column_queue.pop()
+                                    }
+                                    WorkType::SamplingResult => sampling_result_queue.pop(),
+                                    WorkType::ChainSegment => chain_segment_queue.pop(),
+                                    WorkType::ChainSegmentBackfill => backfill_chain_segment.pop(),
+                                    WorkType::Status => status_queue.pop(),
+                                    WorkType::BlocksByRangeRequest => bbrange_queue.pop(),
+                                    WorkType::BlocksByRootsRequest => bbroots_queue.pop(),
+                                    WorkType::BlobsByRangeRequest => blbrange_queue.pop(),
+                                    WorkType::BlobsByRootsRequest => blbroots_queue.pop(),
+                                    WorkType::DataColumnsByRootsRequest => dcbroots_queue.pop(),
+                                    WorkType::DataColumnsByRangeRequest => dcbrange_queue.pop(),
+                                    WorkType::GossipBlsToExecutionChange => {
+                                        gossip_bls_to_execution_change_queue.pop()
+                                    }
+                                    WorkType::LightClientBootstrapRequest => {
+                                        lc_bootstrap_queue.pop()
+                                    }
+                                    WorkType::LightClientOptimisticUpdateRequest => {
+                                        lc_optimistic_update_queue.pop()
+                                    }
+                                    WorkType::LightClientFinalityUpdateRequest => {
+                                        lc_finality_update_queue.pop()
+                                    }
+                                    WorkType::ApiRequestP0 => api_request_p0_queue.pop(),
+                                    WorkType::ApiRequestP1 => api_request_p1_queue.pop(),
+                                    _ => None,
+                                });
+
+                        let work_event: Option<Work<E>> = if let Some(item) = prioritized_work {
+                            Some(item)
                         // Check for chain segments first, they're the most efficient way to get
                         // blocks into the system.
-                        let work_event: Option<Work<E>> = if let Some(item) =
-                            chain_segment_queue.pop()
-                        {
+                        } else if let Some(item) = chain_segment_queue.pop() {
                             Some(item)
                         // Check sync blocks before gossip blocks, since we've already explicitly
                         // requested these blocks.
@@ -1060,19 +1434,25 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         // required to verify some attestations.
                         } else if let Some(item) = gossip_block_queue.pop() {
                             Some(item)
-                        } else if let Some(item) = gossip_blob_queue.pop() {
-                            Some(item)
-                        } else if let Some(item) = gossip_data_column_queue.pop() {
-                            Some(item)
+                        // Check gossip blobs and data columns directly after gossip blocks, since
+                        // blocks can't be imported until they're available. They're processed in
+                        // the gossip validation pool, but ahead of every other class in it.
+                        } else if can_spawn_gossip && gossip_blob_queue.ready_len() > 0 {
+                            gossip_blob_queue.pop()
+                        } else if can_spawn_gossip && gossip_data_column_queue.ready_len() > 0 {
+                            gossip_data_column_queue.pop()
                         // Check the priority 0 API requests after blocks and blobs, but before attestations.
                         } else if let Some(item) = api_request_p0_queue.pop() {
                             Some(item)
                         // Check the aggregates, *then* the unaggregates since we assume that
                         // aggregates are more valuable to local validators and effectively give us
                         // more information with less signature verification time.
-                        } else if aggregate_queue.len() > 0 {
+                        //
+                        // Aggregates and unaggregates are only processed if there is room in the
+                        // gossip validation pool.
+                        } else if can_spawn_gossip && aggregate_queue.ready_len() > 0 {
                             let batch_size = cmp::min(
-                                aggregate_queue.len(),
+                                aggregate_queue.ready_len(),
                                 self.config.max_gossip_aggregate_batch_size,
                             );
 
@@ -1126,9 +1506,9 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         // Check the unaggregated attestation queue.
                         //
                         // Potentially use batching.
-                        } else if attestation_queue.len() > 0 {
+                        } else if can_spawn_gossip && attestation_queue.ready_len() > 0 {
                             let batch_size = cmp::min(
-                                attestation_queue.len(),
+                                attestation_queue.ready_len(),
                                 self.config.max_gossip_attestation_batch_size,
                             );
 
@@ -1257,7 +1637,11 @@ impl<E: EthSpec> BeaconProcessor<E> {
 
                         if let Some(work_event) = work_event {
                             let work_type = work_event.to_type();
-                            self.spawn_worker(work_event, idle_tx);
+                            let pool = work_event.worker_pool();
+                            if pool == WorkerPool::GossipValidation {
+                                current_gossip_workers += 1;
+                            }
+                            self.spawn_worker(work_event, pool, idle_tx);
                             Some(work_type)
                         } else {
                             None
@@ -1297,10 +1681,25 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     Some(WorkEvent { work, .. }) => {
                         let work_id = work.str_id();
                         let work_type = work.to_type();
+                        let pool = work.worker_pool();
+                        let can_spawn_in_pool = match pool {
+                            WorkerPool::GossipValidation => can_spawn_gossip,
+                            WorkerPool::General => can_spawn,
+                        };
 
                         match work {
-                            _ if can_spawn => self.spawn_worker(work, idle_tx),
-                            Work::GossipAttestation { .. } => attestation_queue.push(work),
+                            _ if can_spawn_in_pool => {
+                                if pool == WorkerPool::GossipValidation {
+                                    current_gossip_workers += 1;
+                                }
+                                self.spawn_worker(work, pool, idle_tx)
+                            }
+                            Work::GossipAttestation { .. } => {
+                                if attestation_queue.is_full() {
+                                    observe_gossip_shed(GossipClass::Attestation);
+                                }
+                                attestation_queue.push(work)
+                            }
                             // Attestation batches are formed internally within the
                             // `BeaconProcessor`, they are not sent from external services.
                             Work::GossipAttestationBatch { .. } => crit!(
@@ -1308,7 +1707,12 @@ impl<E: EthSpec> BeaconProcessor<E> {
                                     "Unsupported inbound event";
                                     "type" => "GossipAttestationBatch"
                             ),
-                            Work::GossipAggregate { .. } => aggregate_queue.push(work),
+                            Work::GossipAggregate { .. } => {
+                                if aggregate_queue.is_full() {
+                                    observe_gossip_shed(GossipClass::Aggregate);
+                                }
+                                aggregate_queue.push(work)
+                            }
                             // Aggregate batches are formed internally within the `BeaconProcessor`,
                             // they are not sent from external services.
                             Work::GossipAggregateBatch { .. } => crit!(
@@ -1317,12 +1721,21 @@ impl<E: EthSpec> BeaconProcessor<E> {
                                     "type" => "GossipAggregateBatch"
                             ),
                             Work::GossipBlock { .. } => {
+                                if gossip_block_queue.is_full() {
+                                    observe_gossip_shed(GossipClass::Block);
+                                }
                                 gossip_block_queue.push(work, work_id, &self.log)
                             }
                             Work::GossipBlobSidecar { .. } => {
+                                if gossip_blob_queue.is_full() {
+                                    observe_gossip_shed(GossipClass::Blob);
+                                }
                                 gossip_blob_queue.push(work, work_id, &self.log)
                             }
                             Work::GossipDataColumnSidecar { .. } => {
+                                if gossip_data_column_queue.is_full() {
+                                    observe_gossip_shed(GossipClass::Blob);
+                                }
                                 gossip_data_column_queue.push(work, work_id, &self.log)
                             }
                             Work::DelayedImportBlock { .. } => {
@@ -1427,6 +1840,10 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     &metrics::BEACON_PROCESSOR_WORKERS_ACTIVE_TOTAL,
                     self.current_workers as i64,
                 );
+                metrics::set_gauge(
+                    &metrics::BEACON_PROCESSOR_GOSSIP_WORKERS_ACTIVE_TOTAL,
+                    current_gossip_workers as i64,
+                );
 
                 if let Some(modified_queue_id) = modified_queue_id {
                     let queue_len = match modified_queue_id {
@@ -1495,7 +1912,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         self.log,
                         "Aggregate attestation queue full";
                         "msg" => "the system has insufficient resources for load",
-                        "queue_len" => aggregate_queue.max_length,
+                        "queue_len" => aggregate_queue.max_length(),
                     )
                 }
 
@@ -1504,7 +1921,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
                         self.log,
                         "Attestation queue full";
                         "msg" => "the system has insufficient resources for load",
-                        "queue_len" => attestation_queue.max_length,
+                        "queue_len" => attestation_queue.max_length(),
                     )
                 }
             }
@@ -1517,8 +1934,8 @@ impl<E: EthSpec> BeaconProcessor<E> {
 
     /// Spawns a blocking worker thread to process some `Work`.
     ///
-    /// Sends an message on `idle_tx` when the work is complete and the task is stopping.
-    fn spawn_worker(&mut self, work: Work<E>, idle_tx: mpsc::Sender<()>) {
+    /// Sends the worker's `pool` on `idle_tx` when the work is complete and the task is stopping.
+    fn spawn_worker(&mut self, work: Work<E>, pool: WorkerPool, idle_tx: mpsc::Sender<WorkerPool>) {
         let work_id = work.str_id();
         let worker_timer =
             metrics::start_timer_vec(&metrics::BEACON_PROCESSOR_WORKER_TIME, &[work_id]);
@@ -1534,6 +1951,7 @@ impl<E: EthSpec> BeaconProcessor<E> {
         // As such, this instantiation should happen as early in the function as possible.
         let send_idle_on_drop = SendOnDrop {
             tx: idle_tx,
+            pool,
             _worker_timer: worker_timer,
             log: self.log.clone(),
         };
@@ -1640,6 +2058,14 @@ impl<E: EthSpec> BeaconProcessor<E> {
     }
 }
 
+/// Records that gossip of the given class is being shed because its queue is full.
+fn observe_gossip_shed(class: GossipClass) {
+    metrics::inc_counter_vec(
+        &metrics::BEACON_PROCESSOR_GOSSIP_SHED_TOTAL,
+        &[class.into()],
+    );
+}
+
 /// Spawns tasks that are either:
 ///
 /// - Blocking (i.e. intensive methods that shouldn't run on the core `tokio` executor)
@@ -1690,7 +2116,8 @@ impl TaskSpawner {
 ///
 /// https://doc.rust-lang.org/std/ops/trait.Drop.html#panics
 pub struct SendOnDrop {
-    tx: mpsc::Sender<()>,
+    tx: mpsc::Sender<WorkerPool>,
+    pool: WorkerPool,
     // The field is unused, but it's here to ensure the timer is dropped once the task has finished.
     _worker_timer: Option<metrics::HistogramTimer>,
     log: Logger,
@@ -1698,7 +2125,7 @@ pub struct SendOnDrop {
 
 impl Drop for SendOnDrop {
     fn drop(&mut self) {
-        if let Err(e) = self.tx.try_send(()) {
+        if let Err(e) = self.tx.try_send(self.pool) {
             warn!(
                 self.log,
                 "Unable to free worker";
@@ -1712,6 +2139,9 @@ impl Drop for SendOnDrop {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lighthouse_network::NetworkConfig;
+    use slot_clock::TestingSlotClock;
+    use task_executor::test_utils::TestRuntime;
     use types::{BeaconState, ChainSpec, Eth1Data, ForkName, MainnetEthSpec};
 
     #[test]
@@ -1725,4 +2155,231 @@ mod tests {
         assert_eq!(queue_lengths.attestation_queue, MIN_QUEUE_LEN);
         assert_eq!(queue_lengths.unknown_block_attestation_queue, MIN_QUEUE_LEN);
     }
+
+    #[test]
+    fn queue_monitor_adjusts_queues() {
+        let log = logging::test_logger();
+        let monitor = QueueMonitor::default();
+        let mut fifo = FifoQueue::new(WorkType::Status, 2, &monitor);
+        let mut lifo = LifoQueue::new(WorkType::GossipAttestation, 2, &monitor);
+        for i in 0..3 {
+            fifo.push(i, "status", &log);
+            lifo.push(i);
+        }
+
+        let status = monitor.queue("status").unwrap();
+        assert_eq!(
+            (status.length, status.max_length, status.dropped),
+            (2, 2, 1)
+        );
+        let attestations = monitor.queue("gossip_attestation").unwrap();
+        assert_eq!(attestations.length, 2);
+        assert_eq!(attestations.dropped, 1);
+
+        // Paused queues hold their items, but don't provide them to workers.
+        monitor.set_paused("status", true).unwrap();
+        assert_eq!(fifo.len(), 2);
+        assert_eq!(fifo.ready_len(), 0);
+        assert_eq!(fifo.pop(), None);
+        assert_eq!(monitor.queue("status").unwrap().length, 2);
+        monitor.set_paused("status", false).unwrap();
+        assert_eq!(fifo.pop(), Some(0));
+        assert_eq!(monitor.queue("status").unwrap().processed, 1);
+
+        // Reducing the capacity doesn't drop queued items, but no more are accepted until the
+        // queue has drained.
+        monitor.set_max_length("status", 1).unwrap();
+        assert!(fifo.is_full());
+        fifo.push(3, "status", &log);
+        assert_eq!(fifo.pop(), Some(1));
+        assert_eq!(fifo.pop(), Some(2));
+        assert_eq!(fifo.pop(), None);
+
+        // A full LIFO queue drops its oldest items to make room.
+        monitor.set_max_length("gossip_attestation", 1).unwrap();
+        lifo.push(3);
+        assert_eq!(lifo.pop(), Some(3));
+        assert_eq!(lifo.pop(), None);
+
+        assert!(monitor.set_max_length("gossip_attestation", 0).is_err());
+        assert!(monitor.set_paused("unknown", true).is_err());
+    }
+
+    /// Spawns a beacon processor with a single worker, so that work is executed in the order it's
+    /// scheduled.
+    fn spawn_single_worker_processor() -> (
+        TestRuntime,
+        BeaconProcessorSend<MainnetEthSpec>,
+        mpsc::Receiver<&'static str>,
+        QueueMonitor,
+    ) {
+        let runtime = TestRuntime::default();
+        let log = runtime.log.clone();
+        let spec = Arc::new(ForkName::latest().make_genesis_spec(ChainSpec::mainnet()));
+        let state = BeaconState::<MainnetEthSpec>::new(0, Eth1Data::default(), &spec);
+
+        let config = BeaconProcessorConfig {
+            max_workers: 1,
+            ..Default::default()
+        };
+        let BeaconProcessorChannels {
+            beacon_processor_tx,
+            beacon_processor_rx,
+            work_reprocessing_tx,
+            work_reprocessing_rx,
+            queue_monitor,
+        } = BeaconProcessorChannels::<MainnetEthSpec>::new(&config);
+        let network_globals = Arc::new(NetworkGlobals::new_test_globals(
+            vec![],
+            &log,
+            Arc::new(NetworkConfig::default()),
+            spec.clone(),
+        ));
+        let (work_journal_tx, work_journal_rx) = mpsc::channel(64);
+        BeaconProcessor {
+            network_globals,
+            executor: runtime.task_executor.clone(),
+            current_workers: 0,
+            config,
+            queue_monitor: queue_monitor.clone(),
+            log,
+        }
+        .spawn_manager(
+            beacon_processor_rx,
+            work_reprocessing_tx,
+            work_reprocessing_rx,
+            Some(work_journal_tx),
+            TestingSlotClock::new(
+                Slot::new(0),
+                Duration::from_secs(0),
+                Duration::from_secs(spec.seconds_per_slot),
+            ),
+            spec.maximum_gossip_clock_disparity(),
+            BeaconProcessorQueueLengths::from_state(&state, &spec).unwrap(),
+        )
+        .unwrap();
+
+        (runtime, beacon_processor_tx, work_journal_rx, queue_monitor)
+    }
+
+    /// Gossip blobs and data columns are scheduled directly after gossip blocks, ahead of priority
+    /// 0 API requests, so that a burst of other work can't delay block imports.
+    #[tokio::test]
+    async fn gossip_sidecars_scheduled_after_blocks() {
+        let (_runtime, beacon_processor_tx, mut work_journal_rx, _) =
+            spawn_single_worker_processor();
+
+        let send = |work| {
+            beacon_processor_tx
+                .try_send(WorkEvent {
+                    drop_during_sync: false,
+                    work,
+                })
+                .unwrap()
+        };
+        let executed = Arc::new(Mutex::new(vec![]));
+        let record = |name: &'static str| -> AsyncFn {
+            let executed = executed.clone();
+            Box::pin(async move { executed.lock().push(name) })
+        };
+
+        // Occupy the only worker until all of the other work has been queued.
+        let (release_tx, release_rx) = std::sync::mpsc::sync_channel::<()>(1);
+        let release_rx = Mutex::new(release_rx);
+        send(Work::ApiRequestP1(BlockingOrAsync::Blocking(Box::new(
+            move || {
+                let _ = release_rx.lock().recv();
+            },
+        ))));
+        send(Work::ApiRequestP0(BlockingOrAsync::Async(record(
+            "api_request_p0",
+        ))));
+        send(Work::GossipDataColumnSidecar(record("data_column")));
+        send(Work::GossipBlobSidecar(record("blob")));
+        send(Work::GossipBlock(record("block")));
+
+        let mut received = 0;
+        while received < 5 {
+            if work_journal_rx.recv().await.unwrap() != WORKER_FREED {
+                received += 1;
+            }
+        }
+        release_tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while executed.lock().len() < 4 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            *executed.lock(),
+            vec!["block", "blob", "data_column", "api_request_p0"]
+        );
+    }
+
+    /// Queues prioritized via the `QueueMonitor` are checked for work before all others.
+    #[tokio::test]
+    async fn prioritized_queue_scheduled_first() {
+        let (_runtime, beacon_processor_tx, mut work_journal_rx, queue_monitor) =
+            spawn_single_worker_processor();
+
+        let send = |work| {
+            beacon_processor_tx
+                .try_send(WorkEvent {
+                    drop_during_sync: false,
+                    work,
+                })
+                .unwrap()
+        };
+        let executed = Arc::new(Mutex::new(vec![]));
+        let record = |name: &'static str| -> AsyncFn {
+            let executed = executed.clone();
+            Box::pin(async move { executed.lock().push(name) })
+        };
+
+        // Occupy the only worker until all of the other work has been queued.
+        let (release_tx, release_rx) = std::sync::mpsc::sync_channel::<()>(1);
+        let release_rx = Mutex::new(release_rx);
+        send(Work::ApiRequestP1(BlockingOrAsync::Blocking(Box::new(
+            move || {
+                let _ = release_rx.lock().recv();
+            },
+        ))));
+        send(Work::GossipBlock(record("block")));
+        send(Work::ApiRequestP0(BlockingOrAsync::Async(record(
+            "api_request_p0",
+        ))));
+        send(Work::ApiRequestP1(BlockingOrAsync::Async(record(
+            "api_request_p1",
+        ))));
+
+        let mut received = 0;
+        while received < 4 {
+            if work_journal_rx.recv().await.unwrap() != WORKER_FREED {
+                received += 1;
+            }
+        }
+        queue_monitor
+            .set_prioritized("api_request_p1", true)
+            .unwrap();
+        queue_monitor
+            .set_prioritized("api_request_p0", true)
+            .unwrap();
+        assert!(queue_monitor.queue("api_request_p1").unwrap().prioritized);
+        release_tx.send(()).unwrap();
+
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while executed.lock().len() < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            *executed.lock(),
+            vec!["api_request_p1", "api_request_p0", "block"]
+        );
+    }
 }
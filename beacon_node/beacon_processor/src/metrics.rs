@@ -49,6 +49,21 @@ pub static BEACON_PROCESSOR_WORKERS_ACTIVE_TOTAL: LazyLock<Result<IntGauge>> =
             "Count of active workers in the gossip processing pool.",
         )
     });
+pub static BEACON_PROCESSOR_GOSSIP_WORKERS_ACTIVE_TOTAL: LazyLock<Result<IntGauge>> =
+    LazyLock::new(|| {
+        try_create_int_gauge(
+            "beacon_processor_gossip_workers_active_total",
+            "Count of active workers in the gossip validation pool.",
+        )
+    });
+pub static BEACON_PROCESSOR_GOSSIP_SHED_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_processor_gossip_shed_total",
+            "Count of gossip messages dropped because the queue for their priority class was full.",
+            &["class"],
+        )
+    });
 pub static BEACON_PROCESSOR_IDLE_EVENTS_TOTAL: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
         "beacon_processor_idle_events_total",
@@ -72,6 +87,22 @@ pub static BEACON_PROCESSOR_QUEUE_LENGTH: LazyLock<Result<HistogramVec>> = LazyL
         &["type"],
     )
 });
+pub static BEACON_PROCESSOR_QUEUE_DROPPED_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_processor_queue_dropped_total",
+            "Count of work events dropped because their queue was full.",
+            &["type"],
+        )
+    });
+pub static BEACON_PROCESSOR_QUEUE_WAIT_SECONDS: LazyLock<Result<HistogramVec>> =
+    LazyLock::new(|| {
+        try_create_histogram_vec(
+            "beacon_processor_queue_wait_seconds",
+            "Time a work event spent in its queue before being started by a worker.",
+            &["type"],
+        )
+    });
 
 /*
  * Attestation reprocessing queue metrics.
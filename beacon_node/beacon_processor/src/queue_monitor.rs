@@ -0,0 +1,183 @@
+//! Shares the state of the `BeaconProcessor` queues with other services (e.g., the HTTP API).
+//!
+//! The capacity of each queue can be changed at runtime, a queue can be paused so that the workers
+//! it would otherwise occupy are left for the other queues, and a queue can be prioritized so that
+//! it is checked for work before all others. This allows an operator to mitigate an incident
+//! (e.g., a flood of a particular message) without restarting the node.
+use crate::WorkType;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The live counters for a single queue, shared between the queue and the `QueueMonitor`.
+#[derive(Debug)]
+pub(crate) struct QueueStats {
+    work_type: WorkType,
+    length: AtomicUsize,
+    max_length: AtomicUsize,
+    paused: AtomicBool,
+    dropped: AtomicU64,
+    processed: AtomicU64,
+    total_wait_micros: AtomicU64,
+}
+
+impl QueueStats {
+    fn new(work_type: WorkType, max_length: usize) -> Self {
+        Self {
+            work_type,
+            length: AtomicUsize::new(0),
+            max_length: AtomicUsize::new(max_length),
+            paused: AtomicBool::new(false),
+            dropped: AtomicU64::new(0),
+            processed: AtomicU64::new(0),
+            total_wait_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.max_length.load(Ordering::Relaxed)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    pub fn set_length(&self, length: usize) {
+        self.length.store(length, Ordering::Relaxed);
+    }
+
+    pub fn observe_drop(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn observe_processed(&self, wait: Duration) {
+        self.processed.fetch_add(1, Ordering::Relaxed);
+        self.total_wait_micros
+            .fetch_add(wait.as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+/// A snapshot of the state of a queue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueueStatus {
+    pub name: &'static str,
+    /// The number of items in the queue, including while it is paused.
+    pub length: usize,
+    pub max_length: usize,
+    pub paused: bool,
+    /// Whether the queue is checked for work before the queues which are not prioritized.
+    pub prioritized: bool,
+    /// The number of items dropped because the queue was full.
+    pub dropped: u64,
+    /// The number of items taken from the queue by a worker.
+    pub processed: u64,
+    /// The mean time that processed items spent waiting in the queue.
+    pub mean_wait: Duration,
+}
+
+impl QueueStatus {
+    fn new(name: &'static str, stats: &QueueStats, prioritized: &[WorkType]) -> Self {
+        let processed = stats.processed.load(Ordering::Relaxed);
+        let total_wait_micros = stats.total_wait_micros.load(Ordering::Relaxed);
+        let mean_wait_micros = total_wait_micros.checked_div(processed).unwrap_or(0);
+        Self {
+            name,
+            length: stats.length.load(Ordering::Relaxed),
+            max_length: stats.max_length(),
+            paused: stats.is_paused(),
+            prioritized: prioritized.contains(&stats.work_type),
+            dropped: stats.dropped.load(Ordering::Relaxed),
+            processed,
+            mean_wait: Duration::from_micros(mean_wait_micros),
+        }
+    }
+}
+
+/// Provides access to the queues of a `BeaconProcessor`, keyed by the name of their work type.
+#[derive(Clone, Default)]
+pub struct QueueMonitor {
+    queues: Arc<RwLock<BTreeMap<&'static str, Arc<QueueStats>>>>,
+    /// The prioritized queues, in the order in which they are checked for work.
+    prioritized: Arc<RwLock<Vec<WorkType>>>,
+}
+
+impl QueueMonitor {
+    /// Registers the queue for `work_type`, returning the counters it should update.
+    pub(crate) fn register(&self, work_type: WorkType, max_length: usize) -> Arc<QueueStats> {
+        let stats = Arc::new(QueueStats::new(work_type, max_length));
+        self.queues.write().insert(work_type.into(), stats.clone());
+        stats
+    }
+
+    /// Returns the status of all queues, sorted by name.
+    pub fn queues(&self) -> Vec<QueueStatus> {
+        let prioritized = self.prioritized.read();
+        self.queues
+            .read()
+            .iter()
+            .map(|(name, stats)| QueueStatus::new(*name, stats, &prioritized))
+            .collect()
+    }
+
+    /// Returns the status of the queue with the given `name`.
+    pub fn queue(&self, name: &str) -> Option<QueueStatus> {
+        let prioritized = self.prioritized.read();
+        self.queues
+            .read()
+            .get_key_value(name)
+            .map(|(name, stats)| QueueStatus::new(*name, stats, &prioritized))
+    }
+
+    /// Sets the capacity of the queue with the given `name`.
+    ///
+    /// Reducing the capacity below the length of the queue doesn't drop any items immediately. A
+    /// FIFO queue rejects new items until it has drained, whilst a LIFO queue drops its oldest
+    /// items to make room for new ones.
+    pub fn set_max_length(&self, name: &str, max_length: usize) -> Result<(), String> {
+        if max_length == 0 {
+            return Err("max_length must be at least 1".to_string());
+        }
+        self.stats(name)?
+            .max_length
+            .store(max_length, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Pauses or resumes the queue with the given `name`.
+    ///
+    /// Items are still added to a paused queue (and dropped once it is full), but they are not
+    /// processed until it is resumed.
+    pub fn set_paused(&self, name: &str, paused: bool) -> Result<(), String> {
+        self.stats(name)?.paused.store(paused, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Prioritizes or deprioritizes the queue with the given `name`.
+    ///
+    /// Prioritized queues are checked for work before all other queues, in the order in which they
+    /// were prioritized. Gossip validation work is still limited to the gossip validation workers.
+    pub fn set_prioritized(&self, name: &str, prioritized: bool) -> Result<(), String> {
+        let work_type = self.stats(name)?.work_type;
+        let mut queues = self.prioritized.write();
+        queues.retain(|queue| *queue != work_type);
+        if prioritized {
+            queues.push(work_type);
+        }
+        Ok(())
+    }
+
+    /// Returns the prioritized queues, in the order in which they should be checked for work.
+    pub(crate) fn prioritized(&self) -> Vec<WorkType> {
+        self.prioritized.read().clone()
+    }
+
+    fn stats(&self, name: &str) -> Result<Arc<QueueStats>, String> {
+        self.queues
+            .read()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| format!("unknown queue: {name}"))
+    }
+}
@@ -1,15 +1,21 @@
 use crate::compute_light_client_updates::{
     compute_light_client_updates, LIGHT_CLIENT_SERVER_CHANNEL_CAPACITY,
 };
-use crate::config::{ClientGenesis, Config as ClientConfig};
+use crate::config::{ClientGenesis, Config as ClientConfig, FailoverConfig};
+use crate::failover::spawn_failover_service;
 use crate::notifier::spawn_notifier;
 use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
+use beacon_chain::column_size_estimator::start_column_size_estimator;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
-use beacon_chain::graffiti_calculator::start_engine_version_cache_refresh_service;
+use beacon_chain::graffiti_calculator::{
+    start_engine_version_cache_refresh_service, start_graffiti_file_reload_service,
+};
 use beacon_chain::otb_verification_service::start_otb_verification_service;
 use beacon_chain::proposer_prep_service::start_proposer_prep_service;
 use beacon_chain::schema_change::migrate_schema;
+use beacon_chain::shuffling_cache_warmer::start_shuffling_cache_warmer;
+use beacon_chain::weak_subjectivity::start_weak_subjectivity_audit;
 use beacon_chain::{
     builder::{BeaconChainBuilder, Witness},
     eth1_chain::{CachingEth1Backend, Eth1Chain},
@@ -36,7 +42,7 @@ use monitoring_api::{MonitoringHttpClient, ProcessType};
 use network::{NetworkConfig, NetworkSenders, NetworkService};
 use slasher::Slasher;
 use slasher_service::SlasherService;
-use slog::{debug, info, warn, Logger};
+use slog::{debug, error, info, warn, Logger};
 use ssz::Decode;
 use std::net::TcpListener;
 use std::path::{Path, PathBuf};
@@ -166,6 +172,7 @@ where
         let eth_spec_instance = self.eth_spec_instance.clone();
         let chain_config = config.chain.clone();
         let beacon_graffiti = config.beacon_graffiti;
+        let graffiti_file = config.graffiti_file.clone();
 
         let store = store.ok_or("beacon_chain_start_method requires a store")?;
         let runtime_context =
@@ -174,10 +181,16 @@ where
         let log = context.log();
         let spec = chain_spec.ok_or("beacon_chain_start_method requires a chain spec")?;
         let event_handler = if self.http_api_config.enabled {
-            Some(ServerSentEventHandler::new(
+            let event_handler = ServerSentEventHandler::new(
                 context.log().clone(),
                 self.http_api_config.sse_capacity_multiplier,
-            ))
+            );
+            let replay_window_secs = self.http_api_config.sse_replay_window_secs;
+            if replay_window_secs > 0 {
+                Some(event_handler.with_replay_window(Duration::from_secs(replay_window_secs)))
+            } else {
+                Some(event_handler)
+            }
         } else {
             None
         };
@@ -215,6 +228,7 @@ where
             )
             .chain_config(chain_config)
             .beacon_graffiti(beacon_graffiti)
+            .graffiti_file(graffiti_file)
             .event_handler(event_handler)
             .execution_layer(execution_layer)
             .import_all_data_columns(config.network.subscribe_all_data_column_subnets)
@@ -574,9 +588,11 @@ where
                         network_globals: None,
                         beacon_processor_send: None,
                         beacon_processor_reprocess_send: None,
+                        beacon_processor_queue_monitor: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
                         log: context.log().clone(),
                         sse_logging_components: runtime_context.sse_logging_components.clone(),
+                        log_level: runtime_context.log_level.clone(),
                     });
 
                     // Discard the error from the oneshot.
@@ -746,6 +762,101 @@ where
         Ok(self)
     }
 
+    /// Imports the history prior to the oldest block in the database from the era files in `dir`
+    /// on a blocking thread, and waits for the import to complete.
+    ///
+    /// This must be called before the network is started, so that backfill sync doesn't move the
+    /// anchor concurrently and only has to fill in the history that the era files don't cover.
+    ///
+    /// Historic states are reconstructed in the background if the import reaches genesis and the
+    /// chain is configured to reconstruct them.
+    pub async fn import_era_dir(self, dir: &Path) -> Result<Self, String> {
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or("import_era_dir requires a runtime_context")?;
+        let chain = self
+            .beacon_chain
+            .clone()
+            .ok_or("import_era_dir requires a beacon chain")?;
+        if self.network_globals.is_some() {
+            return Err("import_era_dir must be called before the network is started".into());
+        }
+        let log = context.log().clone();
+        let dir = dir.to_path_buf();
+
+        info!(
+            log,
+            "Importing era files";
+            "info" => "this may take a while, the node will start syncing afterwards",
+            "dir" => %dir.display(),
+            "oldest_block_slot" => chain.store.get_oldest_block_slot(),
+        );
+        let import_chain = chain.clone();
+        let result = context
+            .executor
+            .spawn_blocking_handle(
+                move || import_chain.store.import_era_dir(&dir),
+                "import_era_dir",
+            )
+            .ok_or("Runtime shutdown during era import")?
+            .await
+            .map_err(|e| format!("Era import failed: {e:?}"))?;
+
+        match result {
+            Ok(summary) => {
+                let oldest_block_slot = chain.store.get_oldest_block_slot();
+                info!(
+                    log,
+                    "Imported era files";
+                    "blocks" => summary.blocks,
+                    "states" => summary.states,
+                    "skipped_files" => summary.skipped.len(),
+                    "oldest_block_slot" => oldest_block_slot,
+                );
+                if oldest_block_slot == 0 && chain.config.reconstruct_historic_states {
+                    chain.store_migrator.process_reconstruction();
+                }
+            }
+            Err(e) => {
+                error!(
+                    log,
+                    "Unable to import era files";
+                    "error" => ?e,
+                    "oldest_block_slot" => chain.store.get_oldest_block_slot(),
+                );
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Immediately starts the service that elects which node of a failover pair is active.
+    pub fn failover_service(self, config: &FailoverConfig) -> Result<Self, String> {
+        let context = self
+            .runtime_context
+            .as_ref()
+            .ok_or("failover_service requires a runtime_context")?
+            .service_context("failover".into());
+        let beacon_chain = self
+            .beacon_chain
+            .clone()
+            .ok_or("failover_service requires a beacon chain")?;
+        let network_globals = self
+            .network_globals
+            .clone()
+            .ok_or("failover_service requires a libp2p network")?;
+
+        spawn_failover_service(
+            context.executor,
+            beacon_chain,
+            network_globals,
+            config.clone(),
+        );
+
+        Ok(self)
+    }
+
     /// Immediately starts the service that periodically logs information each slot.
     pub fn notifier(self) -> Result<Self, String> {
         let context = self
@@ -811,7 +922,11 @@ where
                 beacon_processor_reprocess_send: Some(
                     beacon_processor_channels.work_reprocessing_tx.clone(),
                 ),
+                beacon_processor_queue_monitor: Some(
+                    beacon_processor_channels.queue_monitor.clone(),
+                ),
                 sse_logging_components: runtime_context.sse_logging_components.clone(),
+                log_level: runtime_context.log_level.clone(),
                 log: log.clone(),
             });
 
@@ -874,6 +989,7 @@ where
                     executor: beacon_processor_context.executor.clone(),
                     current_workers: 0,
                     config: beacon_processor_config,
+                    queue_monitor: beacon_processor_channels.queue_monitor,
                     log: beacon_processor_context.log().clone(),
                 }
                 .spawn_manager(
@@ -955,12 +1071,17 @@ where
                 let light_client_update_context =
                     runtime_context.service_context("lc_update".to_string());
                 let log = light_client_update_context.log().clone();
+                let network_send = self
+                    .network_senders
+                    .as_ref()
+                    .map(|senders| senders.network_send());
                 light_client_update_context.executor.spawn(
                     async move {
                         compute_light_client_updates(
                             &inner_chain,
                             light_client_server_rv,
                             beacon_processor_channels.work_reprocessing_tx,
+                            network_send,
                             &log,
                         )
                         .await
@@ -971,6 +1092,9 @@ where
 
             start_proposer_prep_service(runtime_context.executor.clone(), beacon_chain.clone());
             start_otb_verification_service(runtime_context.executor.clone(), beacon_chain.clone());
+            start_shuffling_cache_warmer(runtime_context.executor.clone(), beacon_chain.clone());
+            start_weak_subjectivity_audit(runtime_context.executor.clone(), beacon_chain.clone());
+            start_column_size_estimator(runtime_context.executor.clone(), beacon_chain.clone());
             start_availability_cache_maintenance_service(
                 runtime_context.executor.clone(),
                 beacon_chain.clone(),
@@ -979,6 +1103,10 @@ where
                 beacon_chain.as_ref(),
                 runtime_context.executor.clone(),
             );
+            start_graffiti_file_reload_service(
+                beacon_chain.as_ref(),
+                runtime_context.executor.clone(),
+            );
             start_attestation_simulator_service(
                 beacon_chain.task_executor.clone(),
                 beacon_chain.clone(),
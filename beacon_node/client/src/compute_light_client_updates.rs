@@ -2,8 +2,13 @@ use beacon_chain::{BeaconChain, BeaconChainTypes, LightClientProducerEvent};
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
 use futures::channel::mpsc::Receiver;
 use futures::StreamExt;
-use slog::{error, Logger};
-use tokio::sync::mpsc::Sender;
+use lighthouse_network::PubsubMessage;
+use network::NetworkMessage;
+use slog::{debug, error, Logger};
+use slot_clock::SlotClock;
+use std::time::Duration;
+use tokio::sync::mpsc::{Sender, UnboundedSender};
+use types::Slot;
 
 // Each `LightClientProducerEvent` is ~200 bytes. With the light_client server producing only recent
 // updates it is okay to drop some events in case of overloading. In normal network conditions
@@ -15,6 +20,7 @@ pub async fn compute_light_client_updates<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     mut light_client_server_rv: Receiver<LightClientProducerEvent<T::EthSpec>>,
     reprocess_tx: Sender<ReprocessQueueMessage>,
+    network_send: Option<UnboundedSender<NetworkMessage<T::EthSpec>>>,
     log: &Logger,
 ) {
     // Should only receive events for recent blocks, import_block filters by blocks close to clock.
@@ -24,6 +30,7 @@ pub async fn compute_light_client_updates<T: BeaconChainTypes>(
     // since only the most recent updates have value.
     while let Some(event) = light_client_server_rv.next().await {
         let parent_root = event.0;
+        let signature_slot = event.1;
 
         chain
             .recompute_and_cache_light_client_updates(event)
@@ -35,5 +42,61 @@ pub async fn compute_light_client_updates<T: BeaconChainTypes>(
         if reprocess_tx.try_send(msg).is_err() {
             error!(log, "Failed to inform light client update"; "parent_root" => %parent_root)
         };
+
+        if let Some(network_send) = &network_send {
+            publish_light_client_updates(chain, signature_slot, network_send, log).await;
+        }
+    }
+}
+
+/// Publishes the latest light client updates on gossip once one-third of the `signature_slot` has
+/// elapsed, as peers ignore updates received before then.
+async fn publish_light_client_updates<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    signature_slot: Slot,
+    network_send: &UnboundedSender<NetworkMessage<T::EthSpec>>,
+    log: &Logger,
+) {
+    let one_third_slot_duration = Duration::from_secs(chain.spec.seconds_per_slot / 3);
+    if let (Some(slot_start), Some(now)) = (
+        chain.slot_clock.start_of(signature_slot),
+        chain.slot_clock.now_duration(),
+    ) {
+        let publish_time = slot_start + one_third_slot_duration;
+        if let Some(delay) = publish_time.checked_sub(now) {
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    let (optimistic_update, finality_update) = chain
+        .light_client_server_cache
+        .take_updates_to_publish(&chain.spec);
+
+    let mut messages = vec![];
+    if let Some(update) = optimistic_update {
+        debug!(
+            log,
+            "Publishing light client optimistic update";
+            "attested_slot" => update.get_slot(),
+            "signature_slot" => update.signature_slot(),
+        );
+        messages.push(PubsubMessage::LightClientOptimisticUpdate(Box::new(update)));
+    }
+    if let Some(update) = finality_update {
+        debug!(
+            log,
+            "Publishing light client finality update";
+            "finalized_slot" => update.get_finalized_header_slot(),
+            "signature_slot" => update.signature_slot(),
+        );
+        messages.push(PubsubMessage::LightClientFinalityUpdate(Box::new(update)));
+    }
+
+    if !messages.is_empty()
+        && network_send
+            .send(NetworkMessage::Publish { messages })
+            .is_err()
+    {
+        error!(log, "Failed to publish light client updates"; "signature_slot" => signature_slot);
     }
 }
@@ -5,6 +5,7 @@ use beacon_processor::BeaconProcessorConfig;
 use directory::DEFAULT_ROOT_DIR;
 use environment::LoggerConfig;
 use kzg::trusted_setup::get_trusted_setup;
+use logging::TelemetryConfig;
 use network::NetworkConfig;
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
@@ -48,6 +49,20 @@ pub enum ClientGenesis {
     },
 }
 
+/// Configuration for running as one of a pair of beacon nodes, only one of which subscribes to
+/// subnets and publishes at a time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailoverConfig {
+    /// The HTTP API of the other node of the pair.
+    pub peer: SensitiveUrl,
+    /// Of two healthy nodes, the one with the lowest priority is active.
+    pub priority: u64,
+    /// The time between checks of the peer's health.
+    pub check_interval: Duration,
+    /// The number of consecutive failed checks after which the peer is considered down.
+    pub failure_threshold: u64,
+}
+
 /// The core configuration of a Lighthouse beacon node.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -62,6 +77,8 @@ pub struct Config {
     pub sync_eth1_chain: bool,
     /// Graffiti to be inserted everytime we create a block if the validator doesn't specify.
     pub beacon_graffiti: GraffitiOrigin,
+    /// Path to a file of per-validator graffiti, which takes precedence over `beacon_graffiti`.
+    pub graffiti_file: Option<PathBuf>,
     pub validator_monitor: ValidatorMonitorConfig,
     #[serde(skip)]
     /// The `genesis` field is not serialized or deserialized by `serde` to ensure it is defined
@@ -76,12 +93,17 @@ pub struct Config {
     pub http_api: http_api::Config,
     pub http_metrics: http_metrics::Config,
     pub monitoring_api: Option<monitoring_api::Config>,
+    pub failover: Option<FailoverConfig>,
     pub slasher: Option<slasher::Config>,
     pub logger_config: LoggerConfig,
+    /// If set, block import spans are exported to an OpenTelemetry collector.
+    pub telemetry: Option<TelemetryConfig>,
     pub beacon_processor: BeaconProcessorConfig,
     pub genesis_state_url: Option<String>,
     pub genesis_state_url_timeout: Duration,
     pub allow_insecure_genesis_sync: bool,
+    /// Directory of era files from which to import the history prior to the checkpoint.
+    pub import_era_dir: Option<PathBuf>,
 }
 
 impl Default for Config {
@@ -104,17 +126,21 @@ impl Default for Config {
             execution_layer: None,
             trusted_setup,
             beacon_graffiti: GraffitiOrigin::default(),
+            graffiti_file: None,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             monitoring_api: None,
+            failover: None,
             slasher: None,
             validator_monitor: <_>::default(),
             logger_config: LoggerConfig::default(),
+            telemetry: None,
             beacon_processor: <_>::default(),
             genesis_state_url: <_>::default(),
             // This default value should always be overwritten by the CLI default value.
             genesis_state_url_timeout: Duration::from_secs(60),
             allow_insecure_genesis_sync: false,
+            import_era_dir: None,
         }
     }
 }
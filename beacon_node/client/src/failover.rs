@@ -0,0 +1,217 @@
+//! Elects which node of a failover pair is active.
+//!
+//! Two beacon nodes can be configured as a failover pair, each pointing at the other's HTTP API.
+//! Both nodes stay synced, but only the active node publishes messages and subscribes to subnets
+//! on behalf of validator clients. The standby node rejects messages to publish with a 503, so
+//! validator clients configured with both nodes fall back to the active one, and ignores subnet
+//! subscriptions.
+//!
+//! Each node periodically polls its peer's `lighthouse/failover` endpoint:
+//!
+//! - If the peer is healthy, the healthy node with the lowest priority is active.
+//! - If the peer is unreachable or unhealthy for `failure_threshold` consecutive checks, the
+//!   node takes over.
+use crate::config::FailoverConfig;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::FailoverStatus;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use lighthouse_network::{types::FailoverRole, NetworkGlobals};
+use slog::{debug, info, warn, Logger};
+use std::sync::Arc;
+use tokio::time::sleep;
+
+/// Spawns a service which polls the failover peer and updates the role of the node.
+pub fn spawn_failover_service<T: BeaconChainTypes>(
+    executor: task_executor::TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+    config: FailoverConfig,
+) {
+    let log = executor.log().clone();
+    let peer = BeaconNodeHttpClient::new(
+        config.peer.clone(),
+        Timeouts::set_all(config.check_interval),
+    );
+
+    info!(
+        log,
+        "Failover enabled";
+        "peer" => %config.peer,
+        "priority" => config.priority,
+    );
+
+    let failover_future = async move {
+        let mut consecutive_failures = 0;
+
+        loop {
+            let peer_status = match peer.get_lighthouse_failover().await {
+                Ok(response) if response.data.is_healthy => {
+                    consecutive_failures = 0;
+                    Some(response.data)
+                }
+                Ok(_) => {
+                    consecutive_failures += 1;
+                    debug!(log, "Failover peer is unhealthy"; "failures" => consecutive_failures);
+                    None
+                }
+                Err(e) => {
+                    consecutive_failures += 1;
+                    debug!(
+                        log,
+                        "Failover peer is unreachable";
+                        "failures" => consecutive_failures,
+                        "error" => %e,
+                    );
+                    None
+                }
+            };
+
+            let is_healthy = network_globals.sync_state().is_synced()
+                && match &beacon_chain.execution_layer {
+                    Some(el) => !el.is_offline_or_erroring().await,
+                    None => false,
+                };
+
+            let current_role = network_globals.failover_role();
+            let role = elect_role(
+                current_role,
+                config.priority,
+                is_healthy,
+                peer_status.as_ref(),
+                consecutive_failures >= config.failure_threshold,
+                &log,
+            );
+
+            if role != current_role {
+                info!(
+                    log,
+                    "Failover role changed";
+                    "role" => role.as_ref(),
+                    "healthy" => is_healthy,
+                    "peer_failures" => consecutive_failures,
+                );
+                *network_globals.failover_role.write() = role;
+            }
+
+            sleep(config.check_interval).await;
+        }
+    };
+
+    executor.spawn(failover_future, "failover");
+}
+
+/// Returns the role of the node, given the status of its peer.
+///
+/// `peer` is `None` if the last check of the peer failed, in which case the current role is kept
+/// until `peer_down` indicates the peer has failed enough checks to be considered down.
+fn elect_role(
+    current_role: FailoverRole,
+    priority: u64,
+    is_healthy: bool,
+    peer: Option<&FailoverStatus>,
+    peer_down: bool,
+    log: &Logger,
+) -> FailoverRole {
+    let Some(peer) = peer else {
+        return if peer_down {
+            FailoverRole::Active
+        } else {
+            current_role
+        };
+    };
+
+    // Let the healthy peer lead until this node has caught up.
+    if !is_healthy {
+        return FailoverRole::Standby;
+    }
+
+    match peer.priority {
+        Some(peer_priority) if priority < peer_priority => FailoverRole::Active,
+        Some(peer_priority) if priority > peer_priority => FailoverRole::Standby,
+        peer_priority => {
+            // Both nodes being active only costs bandwidth, which is safer than neither being
+            // active.
+            warn!(
+                log,
+                "Failover peer misconfigured";
+                "info" => "both nodes will be active, use a distinct --failover-priority on each",
+                "priority" => priority,
+                "peer_priority" => ?peer_priority,
+            );
+            FailoverRole::Active
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_logger() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    fn peer(priority: Option<u64>) -> FailoverStatus {
+        FailoverStatus {
+            role: FailoverRole::Active,
+            priority,
+            is_healthy: true,
+        }
+    }
+
+    #[test]
+    fn lowest_priority_is_active() {
+        let log = test_logger();
+        let elect = |priority, peer_priority| {
+            elect_role(
+                FailoverRole::Active,
+                priority,
+                true,
+                Some(&peer(Some(peer_priority))),
+                false,
+                &log,
+            )
+        };
+        assert_eq!(elect(0, 1), FailoverRole::Active);
+        assert_eq!(elect(1, 0), FailoverRole::Standby);
+    }
+
+    #[test]
+    fn misconfigured_peer_is_active() {
+        let log = test_logger();
+        for peer_priority in [None, Some(3)] {
+            let role = elect_role(
+                FailoverRole::Standby,
+                3,
+                true,
+                Some(&peer(peer_priority)),
+                false,
+                &log,
+            );
+            assert_eq!(role, FailoverRole::Active);
+        }
+    }
+
+    #[test]
+    fn unhealthy_node_is_standby() {
+        let log = test_logger();
+        let role = elect_role(
+            FailoverRole::Active,
+            0,
+            false,
+            Some(&peer(Some(1))),
+            false,
+            &log,
+        );
+        assert_eq!(role, FailoverRole::Standby);
+    }
+
+    #[test]
+    fn takes_over_once_peer_is_down() {
+        let log = test_logger();
+        let role = elect_role(FailoverRole::Standby, 1, true, None, false, &log);
+        assert_eq!(role, FailoverRole::Standby);
+        let role = elect_role(FailoverRole::Standby, 1, true, None, true, &log);
+        assert_eq!(role, FailoverRole::Active);
+    }
+}
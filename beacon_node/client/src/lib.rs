@@ -1,5 +1,6 @@
 mod compute_light_client_updates;
 pub mod config;
+mod failover;
 mod metrics;
 mod notifier;
 
@@ -12,7 +13,7 @@ use std::sync::Arc;
 
 pub use beacon_chain::{BeaconChainTypes, Eth1ChainBackend};
 pub use builder::ClientBuilder;
-pub use config::{ClientGenesis, Config as ClientConfig};
+pub use config::{ClientGenesis, Config as ClientConfig, FailoverConfig};
 pub use eth2_config::Eth2Config;
 
 /// The core "beacon node" client.
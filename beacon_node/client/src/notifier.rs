@@ -311,10 +311,13 @@ pub fn spawn_notifier<T: BeaconChainTypes>(
             }
 
             eth1_logging(&beacon_chain, &log);
-            bellatrix_readiness_logging(current_slot, &beacon_chain, &log).await;
-            capella_readiness_logging(current_slot, &beacon_chain, &log).await;
-            deneb_readiness_logging(current_slot, &beacon_chain, &log).await;
-            electra_readiness_logging(current_slot, &beacon_chain, &log).await;
+            // Fork readiness depends on the execution engine, which is absent in light mode.
+            if beacon_chain.light_mode.is_none() {
+                bellatrix_readiness_logging(current_slot, &beacon_chain, &log).await;
+                capella_readiness_logging(current_slot, &beacon_chain, &log).await;
+                deneb_readiness_logging(current_slot, &beacon_chain, &log).await;
+                electra_readiness_logging(current_slot, &beacon_chain, &log).await;
+            }
         }
     };
 
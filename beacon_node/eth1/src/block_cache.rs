@@ -93,6 +93,17 @@ impl BlockCache {
         }
     }
 
+    /// Removes the blocks with a number greater than `block_number`, e.g. because they were reorged
+    /// out.
+    pub fn remove_after(&mut self, block_number: u64) {
+        let keep = self
+            .blocks
+            .partition_point(|block| block.number <= block_number);
+        for block in self.blocks.drain(keep..) {
+            self.by_hash.remove(&block.hash);
+        }
+    }
+
     /// Returns the range of block numbers stored in the block cache. All blocks in this range can
     /// be accessed.
     fn available_block_numbers(&self) -> Option<RangeInclusive<u64>> {
@@ -250,6 +261,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remove_after() {
+        let mut cache = BlockCache::default();
+        for block in get_blocks(16, 10) {
+            insert(&mut cache, block).expect("should add consecutive blocks");
+        }
+
+        cache.remove_after(9);
+        assert_eq!(cache.highest_block_number(), Some(9));
+        assert!(cache.block_by_hash(&Hash256::from_low_u64_be(10)).is_none());
+
+        // The blocks of the new chain can be inserted.
+        assert!(insert(&mut cache, get_block(10, 11)).is_ok());
+
+        cache.remove_after(20);
+        assert_eq!(cache.len(), 11);
+    }
+
     #[test]
     fn inserts() {
         let n = 16;
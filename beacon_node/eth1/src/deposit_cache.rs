@@ -43,6 +43,11 @@ pub enum Error {
         currently_finalized: u64,
         deposit_count: u64,
     },
+    /// Attempted to remove logs at or before the finalized block height.
+    FinalizedLogRemoval {
+        block_number: u64,
+        finalized_block_height: u64,
+    },
     /// Error with the merkle tree for deposits.
     DepositTree(merkle_proof::MerkleTreeError),
     /// An unexpected condition was encountered.
@@ -244,6 +249,11 @@ impl DepositCache {
         self.finalized_deposit_count
     }
 
+    /// Returns the number of deposits held in memory, i.e. those above the finalized deposit count.
+    pub fn unfinalized_len(&self) -> usize {
+        self.logs.len()
+    }
+
     /// Finalizes the cache up to `eth1_block.deposit_count`.
     pub fn finalize(&mut self, eth1_block: Eth1Block) -> Result<(), Error> {
         let deposits_to_finalize = eth1_block.deposit_count.ok_or_else(|| {
@@ -281,6 +291,43 @@ impl DepositCache {
         self.deposit_tree.get_snapshot()
     }
 
+    /// Removes the logs from blocks after `block_number`, e.g. because those blocks were reorged
+    /// out, rebuilding the deposit tree from the remaining leaves.
+    ///
+    /// Returns the number of removed logs.
+    pub fn remove_logs_after(&mut self, block_number: u64) -> Result<usize, Error> {
+        if block_number < self.finalized_block_height {
+            return Err(Error::FinalizedLogRemoval {
+                block_number,
+                finalized_block_height: self.finalized_block_height,
+            });
+        }
+        let keep = self
+            .logs
+            .partition_point(|log| log.block_number <= block_number);
+        let removed = self.logs.len() - keep;
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        self.logs.truncate(keep);
+        self.leaves.truncate(keep);
+        // `deposit_roots` also includes the root before the first unfinalized deposit.
+        self.deposit_roots.truncate(keep + 1);
+        self.deposit_tree = match self.deposit_tree.get_snapshot() {
+            Some(snapshot) => {
+                let mut tree = DepositDataTree::from_snapshot(&snapshot, DEPOSIT_TREE_DEPTH)
+                    .map_err(Error::DepositTree)?;
+                for leaf in &self.leaves {
+                    tree.push_leaf(*leaf).map_err(Error::DepositTree)?;
+                }
+                tree
+            }
+            None => DepositDataTree::create(&self.leaves, self.leaves.len(), DEPOSIT_TREE_DEPTH),
+        };
+        Ok(removed)
+    }
+
     /// Adds `log` to self.
     ///
     /// This function enforces that `logs` are imported one-by-one with no gaps between
@@ -504,6 +551,7 @@ pub mod tests {
 
         let log = Log {
             block_number: 42,
+            block_hash: Hash256::zero(),
             data: EXAMPLE_LOG.to_vec(),
         };
         log.to_deposit_log(&spec).expect("should decode log")
@@ -669,6 +717,41 @@ pub mod tests {
         })
     }
 
+    #[test]
+    fn remove_reorged_logs() {
+        let mut deposit_cache = get_cache_with_deposits(64);
+        let mut expected = get_cache_with_deposits(40);
+        for cache in [&mut deposit_cache, &mut expected] {
+            let block = fake_eth1_block(cache, 15).expect("should create fake eth1 block");
+            cache.finalize(block).expect("should finalize");
+        }
+
+        assert_eq!(deposit_cache.remove_logs_after(39), Ok(24));
+        verify_equality(&expected, &deposit_cache);
+        assert_eq!(deposit_cache.remove_logs_after(39), Ok(0));
+
+        assert_eq!(
+            deposit_cache.remove_logs_after(14),
+            Err(Error::FinalizedLogRemoval {
+                block_number: 14,
+                finalized_block_height: 15,
+            })
+        );
+
+        // The rebuilt tree accepts the logs of the new chain.
+        let mut log = example_log();
+        log.index = 40;
+        log.block_number = 40;
+        assert_eq!(
+            deposit_cache.insert_log(log.clone()),
+            Ok(DepositCacheInsertOutcome::Inserted)
+        );
+        expected
+            .insert_log(log)
+            .expect("should add consecutive logs");
+        verify_equality(&expected, &deposit_cache);
+    }
+
     #[test]
     fn test_finalization_boundaries() {
         let n = 8;
@@ -699,6 +782,10 @@ pub mod tests {
             .finalize(half_block)
             .expect("tree should_finalize");
 
+        // only the deposits above the finalized deposit count should be held in memory
+        assert_eq!(deposit_cache.unfinalized_len(), n - (half + 1));
+        assert_eq!(deposit_cache.len(), n);
+
         // check boundary conditions for get_log
         assert!(
             deposit_cache.get_log(half).is_none(),
@@ -2,25 +2,41 @@ use crate::service::endpoint_from_config;
 use crate::Config;
 use crate::{
     block_cache::{BlockCache, Eth1Block},
-    deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13},
+    deposit_cache::{self, DepositCache, SszDepositCache, SszDepositCacheV13},
 };
 use execution_layer::HttpJsonRpc;
 use parking_lot::RwLock;
 use ssz::four_byte_option_impl;
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
+use std::collections::VecDeque;
 use std::sync::Arc;
 use superstruct::superstruct;
-use types::{ChainSpec, DepositTreeSnapshot, Eth1Data};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, Hash256};
 
 // Define "legacy" implementations of `Option<u64>` which use four bytes for encoding the union
 // selector.
 four_byte_option_impl!(four_byte_option_u64, u64);
 
+/// The maximum number of cursors kept to rewind to after a reorg.
+pub const MAX_DEPOSIT_CURSORS: usize = 64;
+
+/// An execution block that deposit logs have been imported up to, inclusive.
+///
+/// Cursors are compared against the canonical chain by hash, so that a reorg of blocks that have
+/// already been scanned for logs is detected rather than silently skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Encode, Decode)]
+pub struct DepositCursor {
+    pub block_number: u64,
+    pub block_hash: Hash256,
+}
+
 #[derive(Default)]
 pub struct DepositUpdater {
     pub cache: DepositCache,
     pub last_processed_block: Option<u64>,
+    /// The most recent cursors, oldest first. The last cursor is at `last_processed_block`.
+    pub cursors: VecDeque<DepositCursor>,
 }
 
 impl DepositUpdater {
@@ -29,6 +45,7 @@ impl DepositUpdater {
         DepositUpdater {
             cache,
             last_processed_block: None,
+            cursors: VecDeque::new(),
         }
     }
 
@@ -40,8 +57,33 @@ impl DepositUpdater {
         Ok(Self {
             cache: DepositCache::from_deposit_snapshot(deposit_contract_deploy_block, snapshot)?,
             last_processed_block,
+            cursors: VecDeque::from([DepositCursor {
+                block_number: snapshot.execution_block_height,
+                block_hash: snapshot.execution_block_hash,
+            }]),
         })
     }
+
+    /// Records that deposit logs have been imported up to and including `cursor`.
+    pub fn advance(&mut self, cursor: DepositCursor) {
+        self.last_processed_block = Some(cursor.block_number);
+        self.cursors.push_back(cursor);
+        while self.cursors.len() > MAX_DEPOSIT_CURSORS {
+            self.cursors.pop_front();
+        }
+    }
+
+    /// Rewinds `last_processed_block` to `block_number`, dropping any later cursors and the logs
+    /// imported from later blocks so that the following blocks are scanned again.
+    ///
+    /// Returns the number of removed logs.
+    pub fn rewind(&mut self, block_number: u64) -> Result<usize, deposit_cache::Error> {
+        let removed = self.cache.remove_logs_after(block_number)?;
+        self.last_processed_block = Some(block_number);
+        self.cursors
+            .retain(|cursor| cursor.block_number <= block_number);
+        Ok(removed)
+    }
 }
 
 pub struct Inner {
@@ -85,10 +127,10 @@ impl Inner {
     }
 }
 
-pub type SszEth1Cache = SszEth1CacheV13;
+pub type SszEth1Cache = SszEth1CacheV23;
 
 #[superstruct(
-    variants(V13),
+    variants(V13, V23),
     variant_attributes(derive(Encode, Decode, Clone)),
     no_enum
 )]
@@ -97,6 +139,8 @@ pub struct SszEth1Cache {
     pub deposit_cache: SszDepositCacheV13,
     #[ssz(with = "four_byte_option_u64")]
     pub last_processed_block: Option<u64>,
+    #[superstruct(only(V23))]
+    pub deposit_cursors: Vec<DepositCursor>,
 }
 
 impl SszEth1Cache {
@@ -107,6 +151,7 @@ impl SszEth1Cache {
             block_cache: (*block_cache).clone(),
             deposit_cache: SszDepositCache::from_deposit_cache(&deposit_updater.cache),
             last_processed_block: deposit_updater.last_processed_block,
+            deposit_cursors: deposit_updater.cursors.iter().copied().collect(),
         }
     }
 
@@ -116,6 +161,7 @@ impl SszEth1Cache {
             deposit_cache: RwLock::new(DepositUpdater {
                 cache: self.deposit_cache.to_deposit_cache()?,
                 last_processed_block: self.last_processed_block,
+                cursors: self.deposit_cursors.iter().copied().collect(),
             }),
             endpoint: endpoint_from_config(&config)
                 .map_err(|e| format!("Failed to create endpoint: {:?}", e))?,
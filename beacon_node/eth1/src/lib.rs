@@ -7,7 +7,7 @@ mod service;
 pub use block_cache::{BlockCache, Eth1Block};
 pub use deposit_cache::{DepositCache, SszDepositCache, SszDepositCacheV13};
 pub use execution_layer::http::deposit_log::DepositLog;
-pub use inner::{SszEth1Cache, SszEth1CacheV13};
+pub use inner::{DepositCursor, SszEth1Cache, SszEth1CacheV13, SszEth1CacheV23};
 pub use service::{
     BlockCacheUpdateOutcome, Config, DepositCacheUpdateOutcome, Error, Eth1Endpoint, Service,
     DEFAULT_CHAIN_ID,
@@ -22,12 +22,30 @@ pub static DEPOSIT_CACHE_LEN: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
         "Number of deposits in the eth1 cache",
     )
 });
+pub static DEPOSIT_CACHE_UNFINALIZED_LEN: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "eth1_deposit_cache_unfinalized_len",
+        "Number of deposits above the finalized deposit count held in the eth1 cache",
+    )
+});
+pub static DEPOSIT_CACHE_FINALIZED_COUNT: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "eth1_deposit_cache_finalized_count",
+        "Number of deposits pruned from the eth1 cache into the finalized deposit tree snapshot",
+    )
+});
 pub static HIGHEST_PROCESSED_DEPOSIT_BLOCK: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
         "eth1_highest_processed_deposit_block",
         "Number of the last block checked for deposits",
     )
 });
+pub static DEPOSIT_CURSOR_REORGS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "eth1_deposit_cursor_reorgs_total",
+        "Count of deposit cursors found to be reorged out of the execution chain",
+    )
+});
 
 /*
  * Eth1 rpc connection
@@ -2,7 +2,7 @@ use crate::metrics;
 use crate::{
     block_cache::{BlockCache, Error as BlockCacheError, Eth1Block},
     deposit_cache::{DepositCacheInsertOutcome, Error as DepositCacheError},
-    inner::{DepositUpdater, Inner},
+    inner::{DepositCursor, DepositUpdater, Inner},
 };
 use execution_layer::auth::Auth;
 use execution_layer::http::{
@@ -20,7 +20,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{interval_at, Duration, Instant};
-use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Unsigned};
+use types::{ChainSpec, DepositTreeSnapshot, Eth1Data, EthSpec, Hash256, Unsigned};
 
 /// Indicates the default eth1 chain id we use for the deposit contract.
 pub const DEFAULT_CHAIN_ID: Eth1Id = Eth1Id::Mainnet;
@@ -217,6 +217,15 @@ pub enum Error {
     GetDepositCountFailed(String),
     /// Failed to read the deposit contract root from the eth1 node.
     GetDepositLogsFailed(String),
+    /// The block deposit logs were imported up to is no longer on the canonical chain of the
+    /// execution node, and there is no earlier cursor to rewind to.
+    DepositCursorReorged {
+        block_number: u64,
+        expected: Hash256,
+        found: Hash256,
+    },
+    /// Failed to remove the deposit logs imported from reorged blocks.
+    FailedToRewindDeposits(DepositCacheError),
     /// There was an unexpected internal error.
     Internal(String),
     /// Error finalizing deposit
@@ -639,6 +648,13 @@ impl Service {
 
         *self.inner.remote_head_block.write() = Some(remote_head_block);
 
+        // After the merge deposits are imported up to the finalized block of the execution node,
+        // rather than up to the follow distance.
+        let new_block_numbers_deposit = self
+            .finalized_deposit_block_numbers()
+            .await
+            .unwrap_or(new_block_numbers_deposit);
+
         let update_deposit_cache = async {
             let outcome_result = self
                 .update_deposit_cache(Some(new_block_numbers_deposit))
@@ -660,8 +676,10 @@ impl Service {
                     "old_block_number" => deposit_cache.last_processed_block,
                     "new_block_number" => deposit_cache.cache.latest_block_number(),
                 );
-                deposit_cache.last_processed_block =
-                    Some(deposit_cache.cache.latest_block_number());
+                let latest_block_number = deposit_cache.cache.latest_block_number();
+                deposit_cache
+                    .rewind(latest_block_number)
+                    .map_err(|e| format!("Failed to rewind deposit cache: {:?}", e))?;
             }
 
             let outcome =
@@ -750,13 +768,21 @@ impl Service {
                 .finalized_deposit_count();
             let deposit_count_to_finalize = eth1data_to_finalize.deposit_count;
             if deposit_count_to_finalize > already_finalized {
-                match self.finalize_deposits(eth1data_to_finalize) {
-                    Err(e) => warn!(
-                        self.log,
-                        "Failed to finalize deposit cache";
-                        "error" => ?e,
-                        "info" => "this should resolve on its own"
-                    ),
+                match self.finalize_deposits(eth1data_to_finalize.clone()).await {
+                    Err(e) => {
+                        // Retry on the next update, unless a more recent finalization has been
+                        // requested in the meantime.
+                        self.inner
+                            .to_finalize
+                            .write()
+                            .get_or_insert(eth1data_to_finalize);
+                        warn!(
+                            self.log,
+                            "Failed to finalize deposit cache";
+                            "error" => ?e,
+                            "info" => "this should resolve on its own"
+                        )
+                    }
                     Ok(()) => info!(
                         self.log,
                         "Successfully finalized deposit tree";
@@ -807,33 +833,149 @@ impl Service {
         )
     }
 
-    pub fn finalize_deposits(&self, eth1_data: Eth1Data) -> Result<(), Error> {
-        let eth1_block = self
+    /// Prunes the deposits up to `eth1_data.deposit_count` from the deposit cache, replacing them
+    /// with an EIP-4881 snapshot of the deposit tree.
+    ///
+    /// The execution block of `eth1_data` is downloaded if it has already been pruned from the
+    /// block cache, so that the memory used by the deposit cache remains bounded even if
+    /// finalization lags far behind the block cache.
+    pub async fn finalize_deposits(&self, eth1_data: Eth1Data) -> Result<(), Error> {
+        let cached_block = self
             .inner
             .block_cache
             .read()
             .block_by_hash(&eth1_data.block_hash)
-            .cloned()
-            .ok_or_else(|| {
-                Error::FailedToFinalizeDeposit(format!(
-                    "Finalized block not found in block cache: {:?}",
-                    eth1_data.block_hash
-                ))
-            })?;
-        self.inner
-            .deposit_cache
-            .write()
+            .cloned();
+        let eth1_block = match cached_block {
+            Some(block) => block,
+            None => {
+                let http_block = self
+                    .client()
+                    .get_block(
+                        BlockQuery::Hash(eth1_data.block_hash),
+                        Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+                    )
+                    .await
+                    .map_err(|e| {
+                        Error::FailedToFinalizeDeposit(format!(
+                            "Finalized block {:?} not found in block cache or execution node: {}",
+                            eth1_data.block_hash, e
+                        ))
+                    })?;
+                Eth1Block {
+                    hash: http_block.hash,
+                    number: http_block.number,
+                    timestamp: http_block.timestamp,
+                    deposit_root: Some(eth1_data.deposit_root),
+                    deposit_count: Some(eth1_data.deposit_count),
+                }
+            }
+        };
+
+        let mut deposits = self.inner.deposit_cache.write();
+        deposits
             .cache
             .finalize(eth1_block)
-            .map_err(|e| Error::FailedToFinalizeDeposit(format!("{:?}", e)))
+            .map_err(|e| Error::FailedToFinalizeDeposit(format!("{:?}", e)))?;
+
+        metrics::set_gauge(
+            &metrics::DEPOSIT_CACHE_UNFINALIZED_LEN,
+            deposits.cache.unfinalized_len() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::DEPOSIT_CACHE_FINALIZED_COUNT,
+            deposits.cache.finalized_deposit_count() as i64,
+        );
+        Ok(())
     }
 
     pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
         self.inner.deposit_cache.read().cache.get_deposit_snapshot()
     }
 
+    /// Returns the range of blocks to scan for deposit logs up to the finalized block of the
+    /// execution node.
+    ///
+    /// Returns `None` if the execution node does not have a finalized block, i.e. prior to the
+    /// merge.
+    async fn finalized_deposit_block_numbers(&self) -> Option<Option<RangeInclusive<u64>>> {
+        let finalized_block = self
+            .client()
+            .get_block(
+                BlockQuery::Finalized,
+                Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+            )
+            .await
+            .ok()?;
+        let next_required_block = self
+            .deposits()
+            .read()
+            .last_processed_block
+            .map(|n| n + 1)
+            .unwrap_or_else(|| self.config().deposit_contract_deploy_block);
+
+        Some(
+            (next_required_block <= finalized_block.number)
+                .then_some(next_required_block..=finalized_block.number),
+        )
+    }
+
+    /// Checks that the latest deposit cursor is still on the canonical chain of the execution
+    /// node. If it has been reorged out, rewinds to the most recent cursor that is still canonical
+    /// so that the reorged blocks are scanned again.
+    ///
+    /// Returns `true` if the cursor was rewound.
+    async fn reconcile_deposit_cursors(&self) -> Result<bool, Error> {
+        let mut rewound = false;
+        loop {
+            let Some(cursor) = self.deposits().read().cursors.back().copied() else {
+                return Ok(rewound);
+            };
+            let block = self
+                .client()
+                .get_block(
+                    BlockQuery::Number(cursor.block_number),
+                    Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+                )
+                .await
+                .map_err(Error::BlockDownloadFailed)?;
+            if block.hash == cursor.block_hash {
+                return Ok(rewound);
+            }
+
+            metrics::inc_counter(&metrics::DEPOSIT_CURSOR_REORGS);
+            let mut deposits = self.deposits().write();
+            let Some(previous) = deposits.cursors.iter().rev().nth(1).copied() else {
+                return Err(Error::DepositCursorReorged {
+                    block_number: cursor.block_number,
+                    expected: cursor.block_hash,
+                    found: block.hash,
+                });
+            };
+            // The logs and blocks after the previous cursor may be from the reorged chain, so they
+            // are removed and fetched again from the canonical chain.
+            let removed_logs = deposits
+                .rewind(previous.block_number)
+                .map_err(Error::FailedToRewindDeposits)?;
+            drop(deposits);
+            self.blocks().write().remove_after(previous.block_number);
+            warn!(
+                self.log,
+                "Rewound deposit cache after an execution chain reorg";
+                "reorged_block_number" => cursor.block_number,
+                "reorged_block_hash" => ?cursor.block_hash,
+                "rewind_to" => previous.block_number,
+                "removed_logs" => removed_logs,
+            );
+            rewound = true;
+        }
+    }
+
     /// Contacts the remote eth1 node and attempts to import deposit logs up to the configured
-    /// follow-distance block.
+    /// follow-distance block, or the block range given in `new_block_numbers`.
+    ///
+    /// Each imported chunk of blocks advances the deposit cursor to the last block of the chunk,
+    /// identified by its hash. A cursor that has since been reorged out is rewound first.
     ///
     /// Will process no more than `BLOCKS_PER_LOG_QUERY * MAX_LOG_REQUESTS_PER_UPDATE` blocks in a
     /// single update.
@@ -854,6 +996,9 @@ impl Service {
         let client = self.client();
         let deposit_contract_address = self.config().deposit_contract_address.clone();
 
+        // A range computed before rewinding would skip the reorged blocks.
+        let rewound = self.reconcile_deposit_cursors().await?;
+
         let blocks_per_log_query = self.config().blocks_per_log_query;
         let max_log_requests_per_update = self
             .config()
@@ -862,8 +1007,8 @@ impl Service {
 
         let range = {
             match new_block_numbers {
-                Some(range) => range,
-                None => {
+                Some(range) if !rewound => range,
+                _ => {
                     relevant_new_block_numbers_from_endpoint(client, self, HeadType::Deposit)
                         .await?
                 }
@@ -897,8 +1042,16 @@ impl Service {
             }
 
             /*
-             * Step 1. Download logs.
+             * Step 1. Download the last block of the range and the logs.
              */
+            let last_block_number = block_range.end.saturating_sub(1);
+            let last_block = client
+                .get_block(
+                    BlockQuery::Number(last_block_number),
+                    Duration::from_millis(GET_BLOCK_TIMEOUT_MILLIS),
+                )
+                .await
+                .map_err(Error::BlockDownloadFailed)?;
             let block_range_ref = &block_range;
             let logs = client
                 .get_deposit_logs_in_range(
@@ -907,7 +1060,24 @@ impl Service {
                     Duration::from_millis(GET_DEPOSIT_LOG_TIMEOUT_MILLIS),
                 )
                 .await
-                .map_err(Error::GetDepositLogsFailed)?;
+                .map_err(Error::GetDepositLogsFailed)?
+                .into_iter()
+                // Logs after the range are imported with the next range, once the cursor can
+                // account for them.
+                .filter(|log| block_range.contains(&log.block_number))
+                .collect::<Vec<_>>();
+
+            // Logs from a different last block than the one downloaded above indicate that the
+            // chain was reorged in between the two requests.
+            if let Some(log) = logs.iter().find(|log| {
+                log.block_number == last_block_number && log.block_hash != last_block.hash
+            }) {
+                return Err(Error::DepositCursorReorged {
+                    block_number: last_block_number,
+                    expected: last_block.hash,
+                    found: log.block_hash,
+                });
+            }
 
             /*
              * Step 2. Import logs to cache.
@@ -952,9 +1122,16 @@ impl Service {
                 "logs" => logs.len(),
             );
 
-            cache.last_processed_block = Some(block_range.end.saturating_sub(1));
+            cache.advance(DepositCursor {
+                block_number: last_block_number,
+                block_hash: last_block.hash,
+            });
 
             metrics::set_gauge(&metrics::DEPOSIT_CACHE_LEN, cache.cache.len() as i64);
+            metrics::set_gauge(
+                &metrics::DEPOSIT_CACHE_UNFINALIZED_LEN,
+                cache.cache.unfinalized_len() as i64,
+            );
             metrics::set_gauge(
                 &metrics::HIGHEST_PROCESSED_DEPOSIT_BLOCK,
                 cache.last_processed_block.unwrap_or(0) as i64,
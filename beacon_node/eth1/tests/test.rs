@@ -425,6 +425,161 @@ mod deposit_tree {
         .await;
     }
 
+    #[tokio::test]
+    async fn cursor_tracks_block_hash() {
+        async {
+            let log = test_logger();
+
+            let eth1 = new_anvil_instance()
+                .await
+                .expect("should start eth1 environment");
+            let deposit_contract = &eth1.deposit_contract;
+            let anvil_client = eth1.json_rpc_client();
+
+            let start_block = get_block_number(&anvil_client).await;
+
+            let service = Service::new(
+                Config {
+                    endpoint: Eth1Endpoint::NoAuth(
+                        SensitiveUrl::parse(eth1.endpoint().as_str()).unwrap(),
+                    ),
+                    deposit_contract_address: deposit_contract.address(),
+                    deposit_contract_deploy_block: start_block,
+                    follow_distance: 0,
+                    ..Config::default()
+                },
+                log,
+                Arc::new(MainnetEthSpec::default_spec()),
+            )
+            .unwrap();
+
+            for _ in 0..2 {
+                deposit_contract
+                    .deposit(random_deposit_data())
+                    .await
+                    .expect("should perform a deposit");
+
+                service
+                    .update_deposit_cache(None)
+                    .await
+                    .expect("should perform update");
+
+                let last_processed_block = service
+                    .deposits()
+                    .read()
+                    .last_processed_block
+                    .expect("should have processed blocks");
+                let block = service
+                    .client()
+                    .get_block(BlockQuery::Number(last_processed_block), timeout())
+                    .await
+                    .expect("should get block");
+                let cursor = service
+                    .deposits()
+                    .read()
+                    .cursors
+                    .back()
+                    .copied()
+                    .expect("should have a cursor");
+
+                assert_eq!(cursor.block_number, last_processed_block);
+                assert_eq!(cursor.block_hash, block.hash);
+            }
+        }
+        .await;
+    }
+
+    #[tokio::test]
+    async fn cursor_rewinds_reorged_logs() {
+        async {
+            let log = test_logger();
+
+            let eth1 = new_anvil_instance()
+                .await
+                .expect("should start eth1 environment");
+            let deposit_contract = &eth1.deposit_contract;
+
+            let start_block = get_block_number(&eth1.json_rpc_client()).await;
+
+            let service = Service::new(
+                Config {
+                    endpoint: Eth1Endpoint::NoAuth(
+                        SensitiveUrl::parse(eth1.endpoint().as_str()).unwrap(),
+                    ),
+                    deposit_contract_address: deposit_contract.address(),
+                    deposit_contract_deploy_block: start_block,
+                    follow_distance: 0,
+                    ..Config::default()
+                },
+                log,
+                Arc::new(MainnetEthSpec::default_spec()),
+            )
+            .unwrap();
+
+            let service = &service;
+            let update = || async move {
+                service
+                    .update_deposit_cache(None)
+                    .await
+                    .expect("should perform update");
+            };
+
+            deposit_contract
+                .deposit(random_deposit_data())
+                .await
+                .expect("should perform a deposit");
+            update().await;
+
+            // Import a deposit from a block which is then reorged out.
+            let snapshot = eth1
+                .anvil
+                .evm_snapshot()
+                .await
+                .expect("should snapshot chain");
+            deposit_contract
+                .deposit(random_deposit_data())
+                .await
+                .expect("should perform a deposit");
+            update().await;
+            assert_eq!(service.deposit_cache_len(), 2);
+            let reorged_cursor = service
+                .deposits()
+                .read()
+                .cursors
+                .back()
+                .copied()
+                .expect("should have a cursor");
+
+            eth1.anvil
+                .evm_revert(snapshot)
+                .await
+                .expect("should revert chain");
+            let canonical_deposit = random_deposit_data();
+            deposit_contract
+                .deposit(canonical_deposit.clone())
+                .await
+                .expect("should perform a deposit");
+            update().await;
+
+            let deposits = service.deposits().read();
+            assert_eq!(deposits.cache.len(), 2, "reorged deposit should be removed");
+            assert_eq!(
+                deposits
+                    .cache
+                    .get_log(1)
+                    .expect("should have log")
+                    .deposit_data,
+                canonical_deposit,
+                "canonical deposit should replace the reorged deposit"
+            );
+            assert!(
+                !deposits.cursors.contains(&reorged_cursor),
+                "reorged cursor should be removed"
+            );
+        }
+        .await;
+    }
+
     #[tokio::test]
     async fn double_update() {
         async {
@@ -831,6 +986,7 @@ mod persist {
             );
 
             let block_count = service.block_cache_len();
+            let cursors = service.deposits().read().cursors.clone();
 
             let eth1_bytes = service.as_bytes();
 
@@ -854,6 +1010,11 @@ mod persist {
                 deposit_count,
                 "Should have equal cached deposits as before recovery"
             );
+            assert_eq!(
+                recovered_service.deposits().read().cursors,
+                cursors,
+                "Should have equal deposit cursors as before recovery"
+            );
         }
         .await;
     }
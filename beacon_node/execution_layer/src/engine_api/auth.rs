@@ -1,6 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
+use crate::metrics;
 use jsonwebtoken::{encode, get_current_timestamp, Algorithm, EncodingKey, Header};
+use parking_lot::RwLock;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
@@ -11,6 +14,12 @@ const DEFAULT_ALGORITHM: Algorithm = Algorithm::HS256;
 /// JWT secret length in bytes.
 pub const JWT_SECRET_LENGTH: usize = 32;
 
+/// The maximum number of secrets accepted at once, including the current one.
+pub const MAX_ACCEPTED_SECRETS: usize = 3;
+
+/// The minimum time between checks for changes to the secret file.
+const SECRET_FILE_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
 #[derive(Debug)]
 pub enum Error {
     JWT(jsonwebtoken::errors::Error),
@@ -25,7 +34,7 @@ impl From<jsonwebtoken::errors::Error> for Error {
 }
 
 /// Provides wrapper around `[u8; JWT_SECRET_LENGTH]` that implements `Zeroize`.
-#[derive(Zeroize, Clone)]
+#[derive(Zeroize, Clone, PartialEq)]
 #[zeroize(drop)]
 pub struct JwtKey([u8; JWT_SECRET_LENGTH]);
 
@@ -68,54 +77,202 @@ pub fn strip_prefix(s: &str) -> &str {
     }
 }
 
-/// Contains the JWT secret and claims parameters.
+/// Reads the hex encoded jwt key in `jwt_path`.
+pub fn read_jwt_key(jwt_path: &Path) -> Result<JwtKey, Error> {
+    let s = std::fs::read_to_string(jwt_path).map_err(|e| {
+        Error::InvalidKey(format!(
+            "Failed to read JWT secret file {:?}, error: {:?}",
+            jwt_path, e
+        ))
+    })?;
+    let secret_bytes = hex::decode(strip_prefix(s.trim_end()))
+        .map_err(|e| Error::InvalidKey(format!("Invalid hex string: {:?}", e)))?;
+    JwtKey::from_slice(&secret_bytes).map_err(Error::InvalidKey)
+}
+
+/// The file the current secret is read from.
+struct SecretFile {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_checked: Instant,
+}
+
+/// The secrets accepted by the execution engine, in order of preference.
+struct Secrets {
+    keys: Vec<(JwtKey, EncodingKey)>,
+    file: Option<SecretFile>,
+    /// Incremented whenever the current secret changes.
+    generation: u64,
+}
+
+impl Secrets {
+    /// Makes `secret` the current secret, keeping the previous secrets as fallbacks. Returns
+    /// `false` if `secret` was already the current secret.
+    fn set_current(&mut self, secret: JwtKey) -> bool {
+        if self.keys.first().is_some_and(|(key, _)| *key == secret) {
+            return false;
+        }
+        self.keys.retain(|(key, _)| *key != secret);
+        let encoding_key = EncodingKey::from_secret(secret.as_bytes());
+        self.keys.insert(0, (secret, encoding_key));
+        self.keys.truncate(MAX_ACCEPTED_SECRETS);
+        self.generation += 1;
+        true
+    }
+}
+
+/// Contains the JWT secrets and claims parameters.
+///
+/// Tokens are signed with the current secret. To allow the secret to be rotated without
+/// restarting the execution and consensus clients at the same time, the execution engine may
+/// also be known to accept other secrets: when it rejects a token, the next secret becomes the
+/// current one (see `rotate`). If the secret was read from a file, changes to the file are picked
+/// up when tokens are generated, and the previous secret is kept as a fallback.
 pub struct Auth {
-    key: EncodingKey,
+    secrets: RwLock<Secrets>,
     id: Option<String>,
     clv: Option<String>,
 }
 
 impl Auth {
     pub fn new(secret: JwtKey, id: Option<String>, clv: Option<String>) -> Self {
+        let encoding_key = EncodingKey::from_secret(secret.as_bytes());
         Self {
-            key: EncodingKey::from_secret(secret.as_bytes()),
+            secrets: RwLock::new(Secrets {
+                keys: vec![(secret, encoding_key)],
+                file: None,
+                generation: 0,
+            }),
             id,
             clv,
         }
     }
 
     /// Create a new `Auth` struct given the path to the file containing the hex
-    /// encoded jwt key.
+    /// encoded jwt key. The file is reloaded when it changes.
     pub fn new_with_path(
         jwt_path: PathBuf,
         id: Option<String>,
         clv: Option<String>,
     ) -> Result<Self, Error> {
-        std::fs::read_to_string(&jwt_path)
-            .map_err(|e| {
-                Error::InvalidKey(format!(
-                    "Failed to read JWT secret file {:?}, error: {:?}",
-                    jwt_path, e
-                ))
-            })
-            .and_then(|ref s| {
-                let secret_bytes = hex::decode(strip_prefix(s.trim_end()))
-                    .map_err(|e| Error::InvalidKey(format!("Invalid hex string: {:?}", e)))?;
-                let secret = JwtKey::from_slice(&secret_bytes).map_err(Error::InvalidKey)?;
-                Ok(Self::new(secret, id, clv))
-            })
+        let secret = read_jwt_key(&jwt_path)?;
+        Ok(Self::new(secret, id, clv).with_secret_file(jwt_path))
+    }
+
+    /// Reload the current secret from `secret_file` whenever the file is modified.
+    pub fn with_secret_file(self, secret_file: PathBuf) -> Self {
+        let modified = std::fs::metadata(&secret_file)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        self.secrets.write().file = Some(SecretFile {
+            path: secret_file,
+            modified,
+            last_checked: Instant::now(),
+        });
+        self
+    }
+
+    /// Also accept `secret` if the execution engine rejects the current secret.
+    pub fn with_next_secret(self, secret: JwtKey) -> Self {
+        {
+            let mut secrets = self.secrets.write();
+            if secrets.keys.iter().all(|(key, _)| *key != secret)
+                && secrets.keys.len() < MAX_ACCEPTED_SECRETS
+            {
+                let encoding_key = EncodingKey::from_secret(secret.as_bytes());
+                secrets.keys.push((secret, encoding_key));
+            }
+        }
+        self
+    }
+
+    /// The number of secrets that may be accepted by the execution engine.
+    pub fn secret_count(&self) -> usize {
+        self.secrets.read().keys.len()
+    }
+
+    /// Reloads the secret file if it has been modified since it was last read.
+    ///
+    /// Returns `true` if the current secret changed.
+    pub fn reload_secret_file(&self) -> Result<bool, Error> {
+        let mut secrets = self.secrets.write();
+        let Some(file) = &mut secrets.file else {
+            return Ok(false);
+        };
+        file.last_checked = Instant::now();
+        let modified = std::fs::metadata(&file.path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        if modified == file.modified {
+            return Ok(false);
+        }
+        let secret = read_jwt_key(&file.path)?;
+        file.modified = modified;
+
+        let changed = secrets.set_current(secret);
+        if changed {
+            metrics::inc_counter_vec(
+                &metrics::EXECUTION_LAYER_JWT_SECRET_CHANGES,
+                &[metrics::JWT_SECRET_FILE_RELOADED],
+            );
+        }
+        Ok(changed)
+    }
+
+    /// Switches to the next accepted secret, after a token signed with the secret of
+    /// `rejected_generation` has been rejected by the execution engine. The rejected secret is
+    /// kept as the last fallback. Nothing changes if the secret has already been switched since,
+    /// e.g. by a concurrent request.
+    ///
+    /// Returns `false` if there is no other secret to try.
+    pub fn rotate(&self, rejected_generation: u64) -> bool {
+        let mut secrets = self.secrets.write();
+        if secrets.generation != rejected_generation {
+            return true;
+        }
+        if secrets.keys.len() < 2 {
+            return false;
+        }
+        secrets.keys.rotate_left(1);
+        secrets.generation += 1;
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_JWT_SECRET_CHANGES,
+            &[metrics::JWT_SECRET_REJECTED],
+        );
+        true
     }
 
     /// Generate a JWT token with `claims.iat` set to current time.
     pub fn generate_token(&self) -> Result<String, Error> {
+        self.generate_token_with_generation()
+            .map(|(token, _)| token)
+    }
+
+    /// Generate a JWT token with `claims.iat` set to current time, along with the generation of
+    /// the secret it was signed with (see `rotate`).
+    pub fn generate_token_with_generation(&self) -> Result<(String, u64), Error> {
+        let check_file = self
+            .secrets
+            .read()
+            .file
+            .as_ref()
+            .is_some_and(|file| file.last_checked.elapsed() >= SECRET_FILE_CHECK_INTERVAL);
+        // A secret file that can't be read (e.g. while it is being rewritten) is retried on the
+        // next check, the current secret remains in use until then.
+        if check_file {
+            let _ = self.reload_secret_file();
+        }
+
         let claims = self.generate_claims_at_timestamp();
         self.generate_token_with_claims(&claims)
     }
 
     /// Generate a JWT token with the given claims.
-    fn generate_token_with_claims(&self, claims: &Claims) -> Result<String, Error> {
+    fn generate_token_with_claims(&self, claims: &Claims) -> Result<(String, u64), Error> {
         let header = Header::new(DEFAULT_ALGORITHM);
-        Ok(encode(&header, claims, &self.key)?)
+        let secrets = self.secrets.read();
+        let (_, key) = secrets.keys.first().ok_or(Error::InvalidToken)?;
+        Ok((encode(&header, claims, key)?, secrets.generation))
     }
 
     /// Generate a `Claims` struct with `iat` set to current time
@@ -169,7 +326,7 @@ mod tests {
             Some("Lighthouse".into()),
         );
         let claims = auth.generate_claims_at_timestamp();
-        let token = auth.generate_token_with_claims(&claims).unwrap();
+        let (token, _) = auth.generate_token_with_claims(&claims).unwrap();
 
         assert_eq!(
             Auth::validate_token(&token, &JwtKey::from_slice(&DEFAULT_JWT_SECRET).unwrap())
@@ -178,4 +335,58 @@ mod tests {
             claims
         );
     }
+
+    fn token_validates_with(auth: &Auth, secret: &JwtKey) -> bool {
+        Auth::validate_token(&auth.generate_token().unwrap(), secret).is_ok()
+    }
+
+    #[test]
+    fn rotates_to_next_secret_once_per_rejection() {
+        let current = JwtKey::random();
+        let next = JwtKey::random();
+        let auth = Auth::new(current.clone(), None, None).with_next_secret(next.clone());
+        assert_eq!(auth.secret_count(), 2);
+
+        let (_, generation) = auth.generate_token_with_generation().unwrap();
+        assert!(auth.rotate(generation));
+        assert!(token_validates_with(&auth, &next));
+
+        // A second rejection of the same token must not switch back to the rejected secret.
+        assert!(auth.rotate(generation));
+        assert!(token_validates_with(&auth, &next));
+
+        // With a single secret there is nothing to rotate to.
+        let auth = Auth::new(current, None, None);
+        let (_, generation) = auth.generate_token_with_generation().unwrap();
+        assert!(!auth.rotate(generation));
+    }
+
+    #[test]
+    fn reloads_secret_file_and_keeps_previous_secret() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("jwt.hex");
+        let old = JwtKey::random();
+        let new = JwtKey::random();
+
+        std::fs::write(&path, old.hex_string()).unwrap();
+        let auth = Auth::new_with_path(path.clone(), None, None).unwrap();
+        assert!(!auth.reload_secret_file().unwrap());
+        assert!(token_validates_with(&auth, &old));
+
+        std::fs::write(&path, new.hex_string()).unwrap();
+        // Make sure the change is detected on file systems with a coarse timestamp resolution.
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(10))
+            .unwrap();
+        assert!(auth.reload_secret_file().unwrap());
+        assert!(token_validates_with(&auth, &new));
+
+        // The execution engine may not have been restarted with the new secret yet.
+        let (_, generation) = auth.generate_token_with_generation().unwrap();
+        assert!(auth.rotate(generation));
+        assert!(token_validates_with(&auth, &old));
+    }
 }
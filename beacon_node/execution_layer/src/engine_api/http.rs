@@ -3,16 +3,17 @@
 use super::*;
 use crate::auth::Auth;
 use crate::json_structures::*;
+use crate::recorder::{self, EngineCallRecord, EngineCallRecorder, RecordedResult};
 use lighthouse_version::{COMMIT_PREFIX, VERSION};
 use reqwest::header::CONTENT_TYPE;
 use sensitive_url::SensitiveUrl;
 use serde::de::DeserializeOwned;
 use serde_json::json;
 use std::collections::HashSet;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use tokio::sync::Mutex;
 
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 pub use deposit_log::{DepositLog, Log};
 pub use reqwest::Client;
@@ -122,6 +123,7 @@ pub mod deposit_log {
     #[derive(Debug, PartialEq, Clone)]
     pub struct Log {
         pub block_number: u64,
+        pub block_hash: Hash256,
         pub data: Vec<u8>,
     }
 
@@ -208,6 +210,7 @@ pub mod deposit_log {
         fn can_parse_example_log() {
             let log = Log {
                 block_number: 42,
+                block_hash: Hash256::zero(),
                 data: EXAMPLE_LOG.to_vec(),
             };
             log.to_deposit_log(&MainnetEthSpec::default_spec())
@@ -262,6 +265,8 @@ pub mod deposit_methods {
         Number(u64),
         Hash(Hash256),
         Latest,
+        /// The latest block finalized by the consensus layer, only known after the merge.
+        Finalized,
     }
 
     impl From<Eth1Id> for u64 {
@@ -379,6 +384,7 @@ pub mod deposit_methods {
                 }
                 BlockQuery::Hash(block_hash) => ("eth_getBlockByHash", format!("{:?}", block_hash)),
                 BlockQuery::Latest => ("eth_getBlockByNumber", "latest".to_string()),
+                BlockQuery::Finalized => ("eth_getBlockByNumber", "finalized".to_string()),
             };
             let params = json!([
                 query_param,
@@ -552,6 +558,17 @@ pub mod deposit_methods {
                         .as_str()
                         .ok_or("Block number was not string")?;
 
+                    let block_hash = hex_to_bytes(
+                        value
+                            .get("blockHash")
+                            .ok_or("No block hash field in log")?
+                            .as_str()
+                            .ok_or("Block hash was not string")?,
+                    )?;
+                    if block_hash.len() != 32 {
+                        return Err(format!("Block hash was not 32 bytes: {:?}", block_hash));
+                    }
+
                     let data = value
                         .get("data")
                         .ok_or("No block number field in log")?
@@ -560,6 +577,7 @@ pub mod deposit_methods {
 
                     Ok(Log {
                         block_number: hex_to_u64_be(block_number)?,
+                        block_hash: Hash256::from_slice(&block_hash),
                         data: hex_to_bytes(data)?,
                     })
                 })
@@ -603,6 +621,7 @@ pub struct HttpJsonRpc {
     pub engine_capabilities_cache: Mutex<Option<CachedResponse<EngineCapabilities>>>,
     pub engine_version_cache: Mutex<Option<CachedResponse<Vec<ClientVersionV1>>>>,
     auth: Option<Auth>,
+    recorder: Option<Arc<EngineCallRecorder>>,
 }
 
 impl HttpJsonRpc {
@@ -617,6 +636,7 @@ impl HttpJsonRpc {
             engine_capabilities_cache: Mutex::new(None),
             engine_version_cache: Mutex::new(None),
             auth: None,
+            recorder: None,
         })
     }
 
@@ -632,14 +652,55 @@ impl HttpJsonRpc {
             engine_capabilities_cache: Mutex::new(None),
             engine_version_cache: Mutex::new(None),
             auth: Some(auth),
+            recorder: None,
         })
     }
 
+    /// Records every request made to the engine, and its response, with `recorder`.
+    pub fn with_recorder(mut self, recorder: Arc<EngineCallRecorder>) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
     pub async fn rpc_request<D: DeserializeOwned>(
         &self,
         method: &str,
         params: serde_json::Value,
         timeout: Duration,
+    ) -> Result<D, Error> {
+        if self.recorder.is_none() && !recorder::is_capturing() {
+            return self.send_rpc_request(method, params, timeout).await;
+        }
+
+        let sent_at = SystemTime::now();
+        let start = Instant::now();
+        let result = self
+            .send_rpc_request::<serde_json::Value>(method, params.clone(), timeout)
+            .await;
+        let record = EngineCallRecord::new(
+            sent_at,
+            self.url.to_string(),
+            method,
+            params,
+            start.elapsed(),
+            match &result {
+                Ok(value) => RecordedResult::Ok(value.clone()),
+                Err(e) => RecordedResult::Err(format!("{:?}", e)),
+            },
+        );
+        if let Some(recorder) = &self.recorder {
+            recorder.record(&record);
+        }
+        recorder::capture(&record);
+
+        result.and_then(|value| serde_json::from_value(value).map_err(Into::into))
+    }
+
+    async fn send_rpc_request<D: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        timeout: Duration,
     ) -> Result<D, Error> {
         let body = JsonRequestBody {
             jsonrpc: JSONRPC_VERSION,
@@ -648,20 +709,37 @@ impl HttpJsonRpc {
             id: json!(STATIC_ID),
         };
 
-        let mut request = self
-            .client
-            .post(self.url.full.clone())
-            .timeout(timeout)
-            .header(CONTENT_TYPE, "application/json")
-            .json(&body);
+        // If the token is rejected, retry with each of the other secrets the engine may accept.
+        let mut attempts = self.auth.as_ref().map_or(1, Auth::secret_count);
+        let body: JsonResponseBody = loop {
+            let mut request = self
+                .client
+                .post(self.url.full.clone())
+                .timeout(timeout)
+                .header(CONTENT_TYPE, "application/json")
+                .json(&body);
+
+            // Generate and add a jwt token to the header if auth is defined.
+            let mut secret_generation = 0;
+            if let Some(auth) = &self.auth {
+                let (token, generation) = auth.generate_token_with_generation()?;
+                request = request.bearer_auth(token);
+                secret_generation = generation;
+            };
 
-        // Generate and add a jwt token to the header if auth is defined.
-        if let Some(auth) = &self.auth {
-            request = request.bearer_auth(auth.generate_token()?);
+            let response = request
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(Error::from);
+            attempts -= 1;
+            match (response, &self.auth) {
+                (Err(Error::Auth(auth::Error::InvalidToken)), Some(auth))
+                    if attempts > 0 && auth.rotate(secret_generation) => {}
+                (response, _) => break response?.json().await?,
+            }
         };
 
-        let body: JsonResponseBody = request.send().await?.error_for_status()?.json().await?;
-
         match (body.result, body.error) {
             (result, None) => serde_json::from_value(result).map_err(Into::into),
             (_, Some(error)) => {
@@ -786,11 +864,7 @@ impl HttpJsonRpc {
         &self,
         new_payload_request_deneb: NewPayloadRequestDeneb<'_, E>,
     ) -> Result<PayloadStatusV1, Error> {
-        let params = json!([
-            JsonExecutionPayload::V3(new_payload_request_deneb.execution_payload.clone().into()),
-            new_payload_request_deneb.versioned_hashes,
-            new_payload_request_deneb.parent_beacon_block_root,
-        ]);
+        let (_, params) = NewPayloadRequest::Deneb(new_payload_request_deneb).json_rpc_request();
 
         let response: JsonPayloadStatusV1 = self
             .rpc_request(
@@ -807,14 +881,8 @@ impl HttpJsonRpc {
         &self,
         new_payload_request_electra: NewPayloadRequestElectra<'_, E>,
     ) -> Result<PayloadStatusV1, Error> {
-        let params = json!([
-            JsonExecutionPayload::V4(new_payload_request_electra.execution_payload.clone().into()),
-            new_payload_request_electra.versioned_hashes,
-            new_payload_request_electra.parent_beacon_block_root,
-            new_payload_request_electra
-                .execution_requests_list
-                .get_execution_requests_list(),
-        ]);
+        let (_, params) =
+            NewPayloadRequest::Electra(new_payload_request_electra).json_rpc_request();
 
         let response: JsonPayloadStatusV1 = self
             .rpc_request(
@@ -1,6 +1,9 @@
+use super::http::{ENGINE_NEW_PAYLOAD_V2, ENGINE_NEW_PAYLOAD_V3, ENGINE_NEW_PAYLOAD_V4};
+use super::json_structures::JsonExecutionPayload;
 use crate::{block_hash::calculate_execution_block_hash, metrics, Error};
 
 use crate::versioned_hashes::verify_versioned_hashes;
+use serde_json::json;
 use state_processing::per_block_processing::deneb::kzg_commitment_to_versioned_hash;
 use superstruct::superstruct;
 use types::{
@@ -95,6 +98,40 @@ impl<'block, E: EthSpec> NewPayloadRequest<'block, E> {
         }
     }
 
+    /// Returns the method and parameters of the `engine_newPayload` request for this payload.
+    ///
+    /// Payloads before Deneb are sent with `engine_newPayloadV1` instead if the engine doesn't
+    /// support `engine_newPayloadV2`, with the same parameters.
+    pub fn json_rpc_request(&self) -> (&'static str, serde_json::Value) {
+        match self {
+            Self::Bellatrix(_) | Self::Capella(_) => (
+                ENGINE_NEW_PAYLOAD_V2,
+                json!([JsonExecutionPayload::from(
+                    self.clone().into_execution_payload()
+                )]),
+            ),
+            Self::Deneb(request) => (
+                ENGINE_NEW_PAYLOAD_V3,
+                json!([
+                    JsonExecutionPayload::V3(request.execution_payload.clone().into()),
+                    request.versioned_hashes,
+                    request.parent_beacon_block_root,
+                ]),
+            ),
+            Self::Electra(request) => (
+                ENGINE_NEW_PAYLOAD_V4,
+                json!([
+                    JsonExecutionPayload::V4(request.execution_payload.clone().into()),
+                    request.versioned_hashes,
+                    request.parent_beacon_block_root,
+                    request
+                        .execution_requests_list
+                        .get_execution_requests_list(),
+                ]),
+            ),
+        }
+    }
+
     /// Performs the required verifications of the payload when the chain is optimistically syncing.
     ///
     /// ## Specification
@@ -150,6 +187,61 @@ impl<'block, E: EthSpec> NewPayloadRequest<'block, E> {
     }
 }
 
+/// An owned copy of a `NewPayloadRequest`, for sending the payload after the block it was borrowed
+/// from may have been dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OwnedNewPayloadRequest<E: EthSpec> {
+    execution_payload: ExecutionPayload<E>,
+    versioned_hashes: Vec<VersionedHash>,
+    parent_beacon_block_root: Hash256,
+    execution_requests: ExecutionRequests<E>,
+}
+
+impl<E: EthSpec> OwnedNewPayloadRequest<E> {
+    pub fn as_request(&self) -> NewPayloadRequest<'_, E> {
+        match &self.execution_payload {
+            ExecutionPayload::Bellatrix(execution_payload) => {
+                NewPayloadRequest::Bellatrix(NewPayloadRequestBellatrix { execution_payload })
+            }
+            ExecutionPayload::Capella(execution_payload) => {
+                NewPayloadRequest::Capella(NewPayloadRequestCapella { execution_payload })
+            }
+            ExecutionPayload::Deneb(execution_payload) => {
+                NewPayloadRequest::Deneb(NewPayloadRequestDeneb {
+                    execution_payload,
+                    versioned_hashes: self.versioned_hashes.clone(),
+                    parent_beacon_block_root: self.parent_beacon_block_root,
+                })
+            }
+            ExecutionPayload::Electra(execution_payload) => {
+                NewPayloadRequest::Electra(NewPayloadRequestElectra {
+                    execution_payload,
+                    versioned_hashes: self.versioned_hashes.clone(),
+                    parent_beacon_block_root: self.parent_beacon_block_root,
+                    execution_requests_list: &self.execution_requests,
+                })
+            }
+        }
+    }
+}
+
+impl<E: EthSpec> From<&NewPayloadRequest<'_, E>> for OwnedNewPayloadRequest<E> {
+    fn from(request: &NewPayloadRequest<'_, E>) -> Self {
+        Self {
+            execution_payload: request.clone().into_execution_payload(),
+            versioned_hashes: request.versioned_hashes().cloned().unwrap_or_default(),
+            parent_beacon_block_root: request
+                .parent_beacon_block_root()
+                .copied()
+                .unwrap_or_default(),
+            execution_requests: request
+                .execution_requests_list()
+                .map(|requests| (*requests).clone())
+                .unwrap_or_default(),
+        }
+    }
+}
+
 impl<'a, E: EthSpec> TryFrom<BeaconBlockRef<'a, E>> for NewPayloadRequest<'a, E> {
     type Error = BeaconStateError;
 
@@ -0,0 +1,367 @@
+//! Records the JSON-RPC exchanges with an execution engine so that they can be inspected or
+//! replayed against another engine (see `lcli replay-engine-calls`).
+//!
+//! Each exchange is appended as one JSON object per line to `engine_calls.jsonl` in the recording
+//! directory. Once that file exceeds the configured size it is rotated to `engine_calls.jsonl.1`,
+//! shifting older files up, and files beyond the configured count are deleted. The files are
+//! written by a dedicated thread, and records are dropped with a warning if it falls behind.
+//!
+//! Independently of the recording, the exchanges made by a future can be captured with
+//! `capture_exchanges`, e.g. to report the exact request that an engine rejected.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use slog::{warn, Logger};
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The name of the file currently being written to.
+pub const RECORDING_FILE_NAME: &str = "engine_calls.jsonl";
+
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+pub const DEFAULT_MAX_FILES: usize = 8;
+
+/// The outcome of a recorded exchange.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedResult {
+    /// The `result` returned by the engine.
+    Ok(serde_json::Value),
+    /// The error returned by the engine, or the reason the request failed.
+    Err(String),
+}
+
+/// A single JSON-RPC exchange with an execution engine.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EngineCallRecord {
+    /// Milliseconds since the UNIX epoch at which the request was sent.
+    pub timestamp_ms: u64,
+    /// The engine the request was sent to, with any credentials redacted.
+    pub endpoint: String,
+    pub method: String,
+    pub params: serde_json::Value,
+    /// The hex-encoded SHA256 hash of the serialized `params`, to find identical requests.
+    pub params_hash: String,
+    pub latency_ms: u64,
+    pub result: RecordedResult,
+}
+
+impl EngineCallRecord {
+    pub fn new(
+        sent_at: SystemTime,
+        endpoint: String,
+        method: &str,
+        params: serde_json::Value,
+        latency: Duration,
+        result: RecordedResult,
+    ) -> Self {
+        Self {
+            timestamp_ms: sent_at
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis() as u64),
+            endpoint,
+            method: method.to_string(),
+            params_hash: params_hash(&params),
+            params,
+            latency_ms: latency.as_millis() as u64,
+            result,
+        }
+    }
+}
+
+tokio::task_local! {
+    static CAPTURED_EXCHANGES: RefCell<Vec<EngineCallRecord>>;
+}
+
+/// Runs `future`, returning its output along with every exchange it made with an execution engine,
+/// in the order they were made.
+///
+/// Only exchanges made on the task running `future` are captured, so the calls sent to redundant
+/// engines in the background are not.
+pub async fn capture_exchanges<F: Future>(future: F) -> (F::Output, Vec<EngineCallRecord>) {
+    CAPTURED_EXCHANGES
+        .scope(RefCell::new(vec![]), async move {
+            let output = future.await;
+            (output, CAPTURED_EXCHANGES.with(RefCell::take))
+        })
+        .await
+}
+
+/// Returns `true` if the current task is within `capture_exchanges`.
+pub fn is_capturing() -> bool {
+    CAPTURED_EXCHANGES.try_with(|_| ()).is_ok()
+}
+
+/// Adds `record` to the exchanges of the enclosing `capture_exchanges`, if any.
+pub fn capture(record: &EngineCallRecord) {
+    let _ = CAPTURED_EXCHANGES.try_with(|exchanges| exchanges.borrow_mut().push(record.clone()));
+}
+
+/// Returns the hex-encoded SHA256 hash of the serialized `params`.
+pub fn params_hash(params: &serde_json::Value) -> String {
+    hex::encode(Sha256::digest(params.to_string().as_bytes()))
+}
+
+/// Reads the records of a recording file, in the order they were written.
+pub fn read_records(path: &Path) -> Result<Vec<EngineCallRecord>, String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+        .map(|(index, line)| {
+            let line = line.map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?;
+            serde_json::from_str(&line)
+                .map_err(|e| format!("Invalid record on line {}: {:?}", index + 1, e))
+        })
+        .collect()
+}
+
+/// The number of records which may be waiting to be written before new records are dropped.
+const RECORDING_QUEUE_SIZE: usize = 1024;
+
+/// Appends [`EngineCallRecord`]s to a set of rotating files.
+///
+/// Records are written by a dedicated thread, so that recording never blocks a request on the
+/// filesystem.
+pub struct EngineCallRecorder {
+    sender: Option<SyncSender<EngineCallRecord>>,
+    writer: Option<JoinHandle<()>>,
+    log: Logger,
+}
+
+impl EngineCallRecorder {
+    /// Opens (or creates) the recording in `dir`, appending to any existing recording.
+    pub fn open(
+        dir: PathBuf,
+        max_file_size: u64,
+        max_files: usize,
+        log: Logger,
+    ) -> Result<Self, String> {
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Unable to create engine call recording dir: {:?}", e))?;
+        let current = RecordingFile::open(&dir)
+            .map_err(|e| format!("Unable to open engine call recording: {:?}", e))?;
+        let mut writer = RecordingWriter {
+            dir,
+            max_file_size,
+            max_files: max_files.max(1),
+            current,
+        };
+
+        let (sender, receiver) = mpsc::sync_channel::<EngineCallRecord>(RECORDING_QUEUE_SIZE);
+        let writer_log = log.clone();
+        let writer = thread::Builder::new()
+            .name("engine_call_recorder".to_string())
+            .spawn(move || {
+                for record in receiver {
+                    if let Err(e) = writer.write(&record) {
+                        warn!(
+                            writer_log,
+                            "Failed to record engine call";
+                            "method" => &record.method,
+                            "error" => e,
+                        );
+                    }
+                }
+            })
+            .map_err(|e| format!("Unable to spawn engine call recorder: {:?}", e))?;
+
+        Ok(Self {
+            sender: Some(sender),
+            writer: Some(writer),
+            log,
+        })
+    }
+
+    /// Queues `record` to be appended to the recording.
+    ///
+    /// Failures are logged rather than returned, so that recording never affects the request. If
+    /// the writer has fallen too far behind, the record is dropped.
+    pub fn record(&self, record: &EngineCallRecord) {
+        let Some(sender) = &self.sender else {
+            return;
+        };
+        let error = match sender.try_send(record.clone()) {
+            Ok(()) => return,
+            Err(TrySendError::Full(_)) => "recording queue is full",
+            Err(TrySendError::Disconnected(_)) => "recording writer has stopped",
+        };
+        warn!(
+            self.log,
+            "Failed to record engine call";
+            "method" => &record.method,
+            "error" => error,
+        );
+    }
+}
+
+impl Drop for EngineCallRecorder {
+    /// Waits for the queued records to be written.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+struct RecordingFile {
+    file: File,
+    size: u64,
+}
+
+impl RecordingFile {
+    fn open(dir: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(RECORDING_FILE_NAME))?;
+        let size = file.metadata()?.len();
+        Ok(Self { file, size })
+    }
+}
+
+fn rotated_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("{}.{}", RECORDING_FILE_NAME, index))
+}
+
+/// Writes records on the recorder's thread.
+struct RecordingWriter {
+    dir: PathBuf,
+    max_file_size: u64,
+    max_files: usize,
+    current: RecordingFile,
+}
+
+impl RecordingWriter {
+    /// Moves the current file to `engine_calls.jsonl.1`, shifting older files up and deleting the
+    /// oldest, then opens a new current file.
+    fn rotate(&mut self) -> io::Result<()> {
+        let oldest = rotated_path(&self.dir, self.max_files - 1);
+        if self.max_files > 1 {
+            if oldest.exists() {
+                fs::remove_file(&oldest)?;
+            }
+            for index in (1..self.max_files - 1).rev() {
+                let path = rotated_path(&self.dir, index);
+                if path.exists() {
+                    fs::rename(&path, rotated_path(&self.dir, index + 1))?;
+                }
+            }
+            fs::rename(
+                self.dir.join(RECORDING_FILE_NAME),
+                rotated_path(&self.dir, 1),
+            )?;
+        } else {
+            fs::remove_file(self.dir.join(RECORDING_FILE_NAME))?;
+        }
+        self.current = RecordingFile::open(&self.dir)?;
+        Ok(())
+    }
+
+    /// Appends `record` to the recording, rotating the files if the current one is full.
+    fn write(&mut self, record: &EngineCallRecord) -> Result<(), String> {
+        let mut line = serde_json::to_vec(record)
+            .map_err(|e| format!("Unable to serialize engine call: {:?}", e))?;
+        line.push(b'\n');
+
+        if self.current.size > 0 && self.current.size + line.len() as u64 > self.max_file_size {
+            self.rotate()
+                .map_err(|e| format!("Unable to rotate engine call recording: {:?}", e))?;
+        }
+        self.current
+            .file
+            .write_all(&line)
+            .map_err(|e| format!("Unable to write engine call recording: {:?}", e))?;
+        self.current.size += line.len() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(method: &str) -> EngineCallRecord {
+        EngineCallRecord::new(
+            SystemTime::now(),
+            "http://localhost:8551/".to_string(),
+            method,
+            json!([{"blockHash": "0x00"}]),
+            Duration::from_millis(5),
+            RecordedResult::Ok(json!({"status": "VALID"})),
+        )
+    }
+
+    #[tokio::test]
+    async fn captures_exchanges_of_the_future() {
+        assert!(!is_capturing());
+        capture(&record("engine_forkchoiceUpdatedV3"));
+
+        let (output, exchanges) = capture_exchanges(async {
+            assert!(is_capturing());
+            capture(&record("engine_newPayloadV3"));
+            // Exchanges made on other tasks aren't captured.
+            tokio::spawn(async { capture(&record("engine_newPayloadV2")) })
+                .await
+                .unwrap();
+            capture(&record("engine_newPayloadV1"));
+            42
+        })
+        .await;
+
+        assert_eq!(output, 42);
+        let methods = exchanges
+            .iter()
+            .map(|exchange| exchange.method.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(methods, ["engine_newPayloadV3", "engine_newPayloadV1"]);
+    }
+
+    #[test]
+    fn records_round_trip_and_rotate() {
+        let dir = tempfile::tempdir().unwrap();
+        let line_len = serde_json::to_vec(&record("engine_newPayloadV3"))
+            .unwrap()
+            .len() as u64
+            + 1;
+        let recorder = EngineCallRecorder::open(
+            dir.path().to_path_buf(),
+            line_len * 2,
+            2,
+            logging::test_logger(),
+        )
+        .unwrap();
+
+        for method in [
+            "engine_newPayloadV3",
+            "engine_newPayloadV2",
+            "engine_newPayloadV1",
+        ] {
+            recorder.record(&record(method));
+        }
+        // Wait for the records to be written.
+        drop(recorder);
+
+        // The first two records were rotated out of the current file.
+        let current = read_records(&dir.path().join(RECORDING_FILE_NAME)).unwrap();
+        assert_eq!(current.len(), 1);
+        assert_eq!(current[0].method, "engine_newPayloadV1");
+        let rotated = read_records(&rotated_path(dir.path(), 1)).unwrap();
+        assert_eq!(rotated.len(), 2);
+        assert_eq!(rotated[0], {
+            let mut expected = record("engine_newPayloadV3");
+            expected.timestamp_ms = rotated[0].timestamp_ms;
+            expected
+        });
+        assert_eq!(rotated[0].params_hash, params_hash(&rotated[1].params));
+    }
+}
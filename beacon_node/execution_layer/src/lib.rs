@@ -5,7 +5,7 @@
 //! deposit-contract functionality that the `beacon_node/eth1` crate already provides.
 
 use crate::json_structures::BlobAndProofV1;
-use crate::payload_cache::PayloadCache;
+use crate::payload_cache::{PayloadCache, ReconstructedPayloadCache};
 use arc_swap::ArcSwapOption;
 use auth::{strip_prefix, Auth, JwtKey};
 pub use block_hash::calculate_execution_block_hash;
@@ -21,9 +21,16 @@ use eth2::types::{builder_bid::SignedBuilderBid, BlobsBundle, ForkVersionedRespo
 use ethers_core::types::Transaction as EthersTransaction;
 use fixed_bytes::UintExtended;
 use fork_choice::ForkchoiceUpdateParameters;
+use futures::future::join_all;
 use lru::LruCache;
-use payload_status::process_payload_status;
-pub use payload_status::PayloadStatus;
+use payload_status::{
+    cross_check_payload_status, must_resolve_with_redundant_engines, process_payload_status,
+    resolve_payload_statuses,
+};
+pub use payload_status::{PayloadStatus, PayloadStatusPolicy};
+use recorder::EngineCallRecorder;
+use relays::{rank_bids, LATE_REVEAL_THRESHOLD};
+pub use relays::{Relay, RelayStats, Relays};
 use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
@@ -33,7 +40,7 @@ use std::fmt;
 use std::future::Future;
 use std::io::Write;
 use std::num::NonZeroUsize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use strum::AsRefStr;
@@ -65,6 +72,7 @@ mod keccak;
 mod metrics;
 pub mod payload_cache;
 mod payload_status;
+mod relays;
 pub mod test_utils;
 pub mod versioned_hashes;
 
@@ -78,6 +86,9 @@ pub const DEFAULT_JWT_FILE: &str = "jwt.hex";
 /// in an LRU cache to avoid redundant lookups. This is the size of that cache.
 const EXECUTION_BLOCKS_LRU_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 
+/// The maximum number of payload bodies requested from the execution node at once.
+const PAYLOAD_BODIES_BATCH_SIZE: u64 = 32;
+
 /// A fee recipient address for use during block production. Only used as a very last resort if
 /// there is no address provided by the user.
 ///
@@ -135,6 +146,9 @@ impl<E: EthSpec> TryFrom<BuilderBid<E>> for ProvenancedPayload<BlockProposalCont
 pub enum Error {
     NoEngine,
     NoPayloadBuilder,
+    UnknownBidRelay {
+        block_hash: ExecutionBlockHash,
+    },
     ApiError(ApiError),
     Builder(builder_client::Error),
     NoHeaderFromBuilder,
@@ -153,11 +167,20 @@ pub enum Error {
     InvalidJWTSecret(String),
     InvalidForkForPayload,
     InvalidPayloadBody(String),
+    InconsistentPayloadBody {
+        block_hash: ExecutionBlockHash,
+    },
+    PayloadBodiesUnavailable(String),
     InvalidPayloadConversion,
     InvalidBlobConversion(String),
     BeaconStateError(BeaconStateError),
     PayloadTypeMismatch,
     VerifyingVersionedHashes(versioned_hashes::Error),
+    InvalidPayloadStatusQuorum {
+        quorum: usize,
+        engines: usize,
+    },
+    EngineCallRecording(String),
 }
 
 impl From<BeaconStateError> for Error {
@@ -361,9 +384,23 @@ pub enum FailedCondition {
 
 type PayloadContentsRefTuple<'a, E> = (ExecutionPayloadRef<'a, E>, Option<&'a BlobsBundle<E>>);
 
+/// The response of a relay to a request for a bid, along with the time it took.
+type RelayResponse<E> = (
+    Arc<Relay>,
+    Result<Option<ForkVersionedResponse<SignedBuilderBid<E>>>, builder_client::Error>,
+    Duration,
+);
+
+/// A verified bid, along with the relay that provided it.
+type RelayBid<E> = (Arc<Relay>, ForkVersionedResponse<SignedBuilderBid<E>>);
+
 struct Inner<E: EthSpec> {
     engine: Arc<Engine>,
-    builder: ArcSwapOption<BuilderHttpClient>,
+    /// Engines that are sent the same `newPayload` and `forkchoiceUpdated` calls as `engine`, in
+    /// order of priority, to cross-check the payload status it returns.
+    redundant_engines: Vec<Arc<Engine>>,
+    payload_status_policy: PayloadStatusPolicy,
+    relays: ArcSwapOption<Relays>,
     execution_engine_forkchoice_lock: Mutex<()>,
     suggested_fee_recipient: Option<Address>,
     proposer_preparation_data: Mutex<HashMap<u64, ProposerPreparationDataEntry>>,
@@ -371,6 +408,7 @@ struct Inner<E: EthSpec> {
     proposers: RwLock<HashMap<ProposerKey, Proposer>>,
     executor: TaskExecutor,
     payload_cache: PayloadCache<E>,
+    reconstructed_payloads: ReconstructedPayloadCache<E>,
     log: Logger,
     /// Track whether the last `newPayload` call errored.
     ///
@@ -384,7 +422,7 @@ pub struct Config {
     /// Endpoint url for EL nodes that are running the engine api.
     pub execution_endpoint: Option<SensitiveUrl>,
     /// Endpoint urls for services providing the builder api.
-    pub builder_url: Option<SensitiveUrl>,
+    pub builder_urls: Vec<SensitiveUrl>,
     /// The timeout value used when making a request to fetch a block header
     /// from the builder api.
     pub builder_header_timeout: Option<Duration>,
@@ -392,6 +430,8 @@ pub struct Config {
     pub builder_user_agent: Option<String>,
     /// JWT secret for the above endpoint running the engine api.
     pub secret_file: Option<PathBuf>,
+    /// JWT secret the above endpoint will accept once its secret is rotated.
+    pub next_secret_file: Option<PathBuf>,
     /// The default fee recipient to use on the beacon node if none if provided from
     /// the validator client during block preparation.
     pub suggested_fee_recipient: Option<Address>,
@@ -402,6 +442,54 @@ pub struct Config {
     /// Default directory for the jwt secret if not provided through cli.
     pub default_datadir: PathBuf,
     pub execution_timeout_multiplier: Option<u32>,
+    /// Additional EL nodes that are used to cross-check the payload statuses returned by the
+    /// node at `execution_endpoint`.
+    pub redundant_execution_endpoints: Vec<RedundantExecutionEndpoint>,
+    /// How the payload statuses returned by all EL nodes are combined.
+    pub payload_status_policy: PayloadStatusPolicy,
+    /// Directory in which every request to the EL nodes, and its response, is recorded.
+    pub engine_call_recording_dir: Option<PathBuf>,
+}
+
+/// An EL node used to cross-check payload statuses.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RedundantExecutionEndpoint {
+    pub url: SensitiveUrl,
+    /// JWT secret for this endpoint. The secret of the primary endpoint is used if `None`.
+    pub secret_file: Option<PathBuf>,
+}
+
+/// Reads the JWT secret from `secret_file`, generating and persisting a new one if the file does
+/// not exist.
+fn load_jwt_key(secret_file: &Path, log: &Logger) -> Result<JwtKey, Error> {
+    if secret_file.exists() {
+        // Read secret from file if it already exists
+        std::fs::read_to_string(secret_file)
+            .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
+            .and_then(|ref s| {
+                let secret = JwtKey::from_slice(
+                    &hex::decode(strip_prefix(s.trim_end()))
+                        .map_err(|e| format!("Invalid hex string: {:?}", e))?,
+                )?;
+                Ok(secret)
+            })
+            .map_err(Error::InvalidJWTSecret)
+    } else {
+        // Create a new file and write a randomly generated secret to it if file does not exist
+        warn!(log, "No JWT found on disk. Generating"; "path" => %secret_file.display());
+        std::fs::File::options()
+            .write(true)
+            .create_new(true)
+            .open(secret_file)
+            .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
+            .and_then(|mut f| {
+                let secret = auth::JwtKey::random();
+                f.write_all(secret.hex_string().as_bytes())
+                    .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))?;
+                Ok(secret)
+            })
+            .map_err(Error::InvalidJWTSecret)
+    }
 }
 
 /// Provides access to one execution engine and provides a neat interface for consumption by the
@@ -416,15 +504,19 @@ impl<E: EthSpec> ExecutionLayer<E> {
     pub fn from_config(config: Config, executor: TaskExecutor, log: Logger) -> Result<Self, Error> {
         let Config {
             execution_endpoint: url,
-            builder_url,
+            builder_urls,
             builder_user_agent,
             builder_header_timeout,
             secret_file,
+            next_secret_file,
             suggested_fee_recipient,
             jwt_id,
             jwt_version,
             default_datadir,
             execution_timeout_multiplier,
+            redundant_execution_endpoints,
+            payload_status_policy,
+            engine_call_recording_dir,
         } = config;
 
         let execution_url = url.ok_or(Error::NoEngine)?;
@@ -432,46 +524,86 @@ impl<E: EthSpec> ExecutionLayer<E> {
         // Use the default jwt secret path if not provided via cli.
         let secret_file = secret_file.unwrap_or_else(|| default_datadir.join(DEFAULT_JWT_FILE));
 
-        let jwt_key = if secret_file.exists() {
-            // Read secret from file if it already exists
-            std::fs::read_to_string(&secret_file)
-                .map_err(|e| format!("Failed to read JWT secret file. Error: {:?}", e))
-                .and_then(|ref s| {
-                    let secret = JwtKey::from_slice(
-                        &hex::decode(strip_prefix(s.trim_end()))
-                            .map_err(|e| format!("Invalid hex string: {:?}", e))?,
-                    )?;
-                    Ok(secret)
-                })
-                .map_err(Error::InvalidJWTSecret)
-        } else {
-            // Create a new file and write a randomly generated secret to it if file does not exist
-            warn!(log, "No JWT found on disk. Generating"; "path" => %secret_file.display());
-            std::fs::File::options()
-                .write(true)
-                .create_new(true)
-                .open(&secret_file)
-                .map_err(|e| format!("Failed to open JWT secret file. Error: {:?}", e))
-                .and_then(|mut f| {
-                    let secret = auth::JwtKey::random();
-                    f.write_all(secret.hex_string().as_bytes())
-                        .map_err(|e| format!("Failed to write to JWT secret file: {:?}", e))?;
-                    Ok(secret)
-                })
-                .map_err(Error::InvalidJWTSecret)
-        }?;
+        let jwt_key = load_jwt_key(&secret_file, &log)?;
+        let next_jwt_key = next_secret_file
+            .as_ref()
+            .map(|next_secret_file| {
+                auth::read_jwt_key(next_secret_file)
+                    .map_err(|e| Error::InvalidJWTSecret(format!("{:?}", e)))
+            })
+            .transpose()?;
+        // Tokens are signed with the secret in `secret_file`, which is reloaded when it changes,
+        // falling back to the next secret if the engine rejects them.
+        let new_auth = |jwt_key: JwtKey, secret_file: PathBuf, accepts_next: bool| {
+            let auth = Auth::new(jwt_key, jwt_id.clone(), jwt_version.clone())
+                .with_secret_file(secret_file);
+            match &next_jwt_key {
+                Some(next_jwt_key) if accepts_next => auth.with_next_secret(next_jwt_key.clone()),
+                _ => auth,
+            }
+        };
+
+        let recorder = engine_call_recording_dir
+            .map(|dir| {
+                info!(log, "Recording engine API calls"; "dir" => %dir.display());
+                EngineCallRecorder::open(
+                    dir,
+                    recorder::DEFAULT_MAX_FILE_SIZE,
+                    recorder::DEFAULT_MAX_FILES,
+                    log.clone(),
+                )
+                .map(Arc::new)
+                .map_err(Error::EngineCallRecording)
+            })
+            .transpose()?;
+        let with_recorder = |api: HttpJsonRpc| match &recorder {
+            Some(recorder) => api.with_recorder(recorder.clone()),
+            None => api,
+        };
 
         let engine: Engine = {
-            let auth = Auth::new(jwt_key, jwt_id, jwt_version);
-            debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path());
+            let auth = new_auth(jwt_key.clone(), secret_file.clone(), true);
+            debug!(log, "Loaded execution endpoint"; "endpoint" => %execution_url, "jwt_path" => ?secret_file.as_path(), "next_jwt_path" => ?next_secret_file);
             let api = HttpJsonRpc::new_with_auth(execution_url, auth, execution_timeout_multiplier)
                 .map_err(Error::ApiError)?;
-            Engine::new(api, executor.clone(), &log)
+            Engine::new(with_recorder(api), executor.clone(), &log)
         };
 
+        let redundant_engines = redundant_execution_endpoints
+            .into_iter()
+            .map(|endpoint| {
+                let auth = match &endpoint.secret_file {
+                    Some(endpoint_secret_file) => new_auth(
+                        load_jwt_key(endpoint_secret_file, &log)?,
+                        endpoint_secret_file.clone(),
+                        false,
+                    ),
+                    None => new_auth(jwt_key.clone(), secret_file.clone(), true),
+                };
+                debug!(log, "Loaded redundant execution endpoint"; "endpoint" => %endpoint.url, "jwt_path" => ?endpoint.secret_file);
+                let api =
+                    HttpJsonRpc::new_with_auth(endpoint.url, auth, execution_timeout_multiplier)
+                        .map_err(Error::ApiError)?;
+                Ok(Arc::new(Engine::new(
+                    with_recorder(api),
+                    executor.clone(),
+                    &log,
+                )))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        if let PayloadStatusPolicy::Quorum(quorum) = payload_status_policy {
+            let engines = redundant_engines.len() + 1;
+            if quorum == 0 || quorum > engines {
+                return Err(Error::InvalidPayloadStatusQuorum { quorum, engines });
+            }
+        }
+
         let inner = Inner {
             engine: Arc::new(engine),
-            builder: ArcSwapOption::empty(),
+            redundant_engines,
+            payload_status_policy,
+            relays: ArcSwapOption::empty(),
             execution_engine_forkchoice_lock: <_>::default(),
             suggested_fee_recipient,
             proposer_preparation_data: Mutex::new(HashMap::new()),
@@ -479,6 +611,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
             execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
             executor,
             payload_cache: PayloadCache::default(),
+            reconstructed_payloads: ReconstructedPayloadCache::default(),
             log,
             last_new_payload_errored: RwLock::new(false),
         };
@@ -487,8 +620,8 @@ impl<E: EthSpec> ExecutionLayer<E> {
             inner: Arc::new(inner),
         };
 
-        if let Some(builder_url) = builder_url {
-            el.set_builder_url(builder_url, builder_user_agent, builder_header_timeout)?;
+        if !builder_urls.is_empty() {
+            el.set_builder_urls(builder_urls, builder_user_agent, builder_header_timeout)?;
         }
 
         Ok(el)
@@ -498,8 +631,22 @@ impl<E: EthSpec> ExecutionLayer<E> {
         &self.inner.engine
     }
 
-    pub fn builder(&self) -> Option<Arc<BuilderHttpClient>> {
-        self.inner.builder.load_full()
+    /// Returns the clients of all configured builders, in the order they were configured.
+    pub fn builders(&self) -> Vec<Arc<BuilderHttpClient>> {
+        self.relays()
+            .map(|relays| {
+                relays
+                    .all()
+                    .iter()
+                    .map(|relay| relay.client().clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns all configured builders, along with their history.
+    pub fn relays(&self) -> Option<Arc<Relays>> {
+        self.inner.relays.load_full()
     }
 
     /// Set the builder URL after initialization.
@@ -512,19 +659,43 @@ impl<E: EthSpec> ExecutionLayer<E> {
         builder_user_agent: Option<String>,
         builder_header_timeout: Option<Duration>,
     ) -> Result<(), Error> {
-        let builder_client = BuilderHttpClient::new(
-            builder_url.clone(),
+        self.set_builder_urls(
+            vec![builder_url],
             builder_user_agent,
             builder_header_timeout,
         )
-        .map_err(Error::Builder)?;
-        info!(
-            self.log(),
-            "Using external block builder";
-            "builder_url" => ?builder_url,
-            "local_user_agent" => builder_client.get_user_agent(),
-        );
-        self.inner.builder.swap(Some(Arc::new(builder_client)));
+    }
+
+    /// Set the builder URLs, replacing any previously configured builders and their history.
+    pub fn set_builder_urls(
+        &self,
+        builder_urls: Vec<SensitiveUrl>,
+        builder_user_agent: Option<String>,
+        builder_header_timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        let relays = builder_urls
+            .into_iter()
+            .map(|builder_url| {
+                let builder_client = BuilderHttpClient::new(
+                    builder_url.clone(),
+                    builder_user_agent.clone(),
+                    builder_header_timeout,
+                )
+                .map_err(Error::Builder)?;
+                info!(
+                    self.log(),
+                    "Using external block builder";
+                    "builder_url" => ?builder_url,
+                    "local_user_agent" => builder_client.get_user_agent(),
+                );
+                Ok(Arc::new(Relay::new(
+                    builder_url,
+                    Arc::new(builder_client),
+                    self.log().clone(),
+                )))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        self.inner.relays.swap(Some(Arc::new(Relays::new(relays))));
         Ok(())
     }
 
@@ -631,7 +802,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
 
     /// Performs a single execution of the watchdog routine.
     pub async fn watchdog_task(&self) {
-        self.engine().upcheck().await;
+        self.upcheck().await;
     }
 
     /// Spawns a routine which cleans the cached proposer data periodically.
@@ -921,16 +1092,13 @@ impl<E: EthSpec> ExecutionLayer<E> {
     /// Fetches local and builder paylaods concurrently, Logs and returns results.
     async fn fetch_builder_and_local_payloads(
         &self,
-        builder: &BuilderHttpClient,
+        relays: &[Arc<Relay>],
         parent_hash: ExecutionBlockHash,
         builder_params: &BuilderParams,
         payload_attributes: &PayloadAttributes,
         forkchoice_update_params: ForkchoiceUpdateParameters,
         current_fork: ForkName,
-    ) -> (
-        Result<Option<ForkVersionedResponse<SignedBuilderBid<E>>>, builder_client::Error>,
-        Result<GetPayloadResponse<E>, Error>,
-    ) {
+    ) -> (Vec<RelayResponse<E>>, Result<GetPayloadResponse<E>, Error>) {
         let slot = builder_params.slot;
         let pubkey = &builder_params.pubkey;
 
@@ -940,15 +1108,22 @@ impl<E: EthSpec> ExecutionLayer<E> {
             "slot" => ?slot,
             "pubkey" => ?pubkey,
             "parent_hash" => ?parent_hash,
+            "relays" => relays.len(),
         );
 
-        // Wait for the builder *and* local EL to produce a payload (or return an error).
-        let ((relay_result, relay_duration), (local_result, local_duration)) = tokio::join!(
-            timed_future(metrics::GET_BLINDED_PAYLOAD_BUILDER, async {
-                builder
-                    .get_builder_header::<E>(slot, parent_hash, pubkey)
-                    .await
-            }),
+        // Wait for the builders *and* local EL to produce a payload (or return an error).
+        let (relay_responses, (local_result, local_duration)) = tokio::join!(
+            join_all(relays.iter().map(|relay| async move {
+                let (result, duration) =
+                    timed_future(metrics::GET_BLINDED_PAYLOAD_BUILDER, async {
+                        relay
+                            .client()
+                            .get_builder_header::<E>(slot, parent_hash, pubkey)
+                            .await
+                    })
+                    .await;
+                (relay.clone(), result, duration)
+            })),
             timed_future(metrics::GET_BLINDED_PAYLOAD_LOCAL, async {
                 self.get_full_payload_caching(
                     parent_hash,
@@ -964,15 +1139,23 @@ impl<E: EthSpec> ExecutionLayer<E> {
             })
         );
 
+        for (relay, relay_result, relay_duration) in &relay_responses {
+            info!(
+                self.log(),
+                "Requested blinded execution payload";
+                "relay" => relay.name(),
+                "relay_fee_recipient" => match relay_result {
+                    Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
+                    Ok(None) => "empty response".to_string(),
+                    Err(_) => "request failed".to_string(),
+                },
+                "relay_response_ms" => relay_duration.as_millis(),
+                "parent_hash" => ?parent_hash,
+            );
+        }
         info!(
             self.log(),
-            "Requested blinded execution payload";
-            "relay_fee_recipient" => match &relay_result {
-                Ok(Some(r)) => format!("{:?}", r.data.message.header().fee_recipient()),
-                Ok(None) => "empty response".to_string(),
-                Err(_) => "request failed".to_string(),
-            },
-            "relay_response_ms" => relay_duration.as_millis(),
+            "Requested local execution payload";
             "local_fee_recipient" => match &local_result {
                 Ok(get_payload_response) => format!("{:?}", get_payload_response.fee_recipient()),
                 Err(_) => "request failed".to_string()
@@ -981,7 +1164,104 @@ impl<E: EthSpec> ExecutionLayer<E> {
             "parent_hash" => ?parent_hash,
         );
 
-        (relay_result, local_result)
+        (relay_responses, local_result)
+    }
+
+    /// Verifies the bids returned by the relays, records the outcome against each relay and
+    /// returns the most valuable valid bid.
+    ///
+    /// An error is only returned if every relay failed to respond.
+    fn select_relay_bid(
+        &self,
+        relay_responses: Vec<RelayResponse<E>>,
+        parent_hash: ExecutionBlockHash,
+        payload_attributes: &PayloadAttributes,
+        block_number: Option<u64>,
+        current_fork: ForkName,
+        spec: &ChainSpec,
+    ) -> Result<Option<RelayBid<E>>, builder_client::Error> {
+        let mut bids = vec![];
+        let mut responded = false;
+        let mut last_error = None;
+
+        for (relay, relay_result, relay_duration) in relay_responses {
+            let bid = match relay_result {
+                Ok(Some(bid)) => bid,
+                Ok(None) => {
+                    responded = true;
+                    relay.record_no_bid(relay_duration);
+                    continue;
+                }
+                Err(e) => {
+                    relay.record_failed_request(relay_duration);
+                    warn!(
+                        self.log(),
+                        "Relay error when requesting bid";
+                        "relay" => relay.name(),
+                        "relay_error" => ?e,
+                        "parent_hash" => ?parent_hash,
+                    );
+                    last_error = Some(e);
+                    continue;
+                }
+            };
+            responded = true;
+
+            if let Err(reason) = verify_builder_bid(
+                &bid,
+                parent_hash,
+                payload_attributes,
+                block_number,
+                current_fork,
+                spec,
+            ) {
+                relay.record_invalid_bid(relay_duration);
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
+                    &[reason.as_ref().as_ref()],
+                );
+                warn!(
+                    self.log(),
+                    "Builder returned invalid payload";
+                    "relay" => relay.name(),
+                    "reason" => %reason,
+                    "relay_block_hash" => ?bid.data.message.header().block_hash(),
+                    "parent_hash" => ?parent_hash,
+                );
+                continue;
+            }
+
+            relay.record_bid(relay_duration);
+            bids.push((relay, bid));
+        }
+
+        if !responded {
+            if let Some(e) = last_error {
+                return Err(e);
+            }
+        }
+
+        rank_bids(&mut bids, |bid| *bid.data.message.value());
+        let mut bids = bids.into_iter();
+        let Some((relay, bid)) = bids.next() else {
+            return Ok(None);
+        };
+        let runner_up = bids.next();
+
+        metrics::inc_counter_vec(&metrics::EXECUTION_LAYER_RELAY_BEST_BIDS, &[relay.name()]);
+        info!(
+            self.log(),
+            "Selected most valuable relay bid";
+            "relay" => relay.name(),
+            "relay_value" => %bid.data.message.value(),
+            "relay_score" => relay.score(),
+            "relay_block_hash" => ?bid.data.message.header().block_hash(),
+            "valid_bids" => 1 + runner_up.iter().count() + bids.len(),
+            "runner_up_relay" => runner_up.as_ref().map(|(relay, _)| relay.name()),
+            "runner_up_value" => runner_up.as_ref().map(|(_, bid)| bid.data.message.value().to_string()),
+        );
+
+        Ok(Some((relay, bid)))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -995,7 +1275,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
         builder_boost_factor: Option<u64>,
         spec: &ChainSpec,
     ) -> Result<ProvenancedPayload<BlockProposalContentsType<E>>, Error> {
-        let Some(builder) = self.builder() else {
+        let Some(relays) = self.relays() else {
             // no builder.. return local payload
             return self
                 .get_full_payload_caching(
@@ -1045,9 +1325,30 @@ impl<E: EthSpec> ExecutionLayer<E> {
                 .map(ProvenancedPayload::Local);
         }
 
-        let (relay_result, local_result) = self
+        let available_relays = relays.available();
+        if available_relays.is_empty() {
+            warn!(
+                self.log(),
+                "All builders are blacklisted, using local payload";
+                "info" => "builders are blacklisted after returning invalid bids or failing to \
+                    reveal payloads",
+                "relays" => relays.all().len(),
+            );
+            return self
+                .get_full_payload_caching(
+                    parent_hash,
+                    payload_attributes,
+                    forkchoice_update_params,
+                    current_fork,
+                )
+                .await
+                .and_then(GetPayloadResponseType::try_into)
+                .map(ProvenancedPayload::Local);
+        }
+
+        let (relay_responses, local_result) = self
             .fetch_builder_and_local_payloads(
-                builder.as_ref(),
+                &available_relays,
                 parent_hash,
                 &builder_params,
                 payload_attributes,
@@ -1056,6 +1357,15 @@ impl<E: EthSpec> ExecutionLayer<E> {
             )
             .await;
 
+        let relay_result = self.select_relay_bid(
+            relay_responses,
+            parent_hash,
+            payload_attributes,
+            local_result.as_ref().ok().map(|local| local.block_number()),
+            current_fork,
+            spec,
+        );
+
         match (relay_result, local_result) {
             (Err(e), Ok(local)) => {
                 warn!(
@@ -1106,44 +1416,18 @@ impl<E: EthSpec> ExecutionLayer<E> {
 
                 Err(Error::CannotProduceHeader)
             }
-            (Ok(Some(relay)), Ok(local)) => {
+            (Ok(Some((relay_source, relay))), Ok(local)) => {
                 let header = &relay.data.message.header();
 
                 info!(
                     self.log(),
                     "Received local and builder payloads";
+                    "relay" => relay_source.name(),
                     "relay_block_hash" => ?header.block_hash(),
                     "local_block_hash" => ?local.block_hash(),
                     "parent_hash" => ?parent_hash,
                 );
 
-                // check relay payload validity
-                if let Err(reason) = verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    Some(local.block_number()),
-                    current_fork,
-                    spec,
-                ) {
-                    // relay payload invalid -> return local
-                    metrics::inc_counter_vec(
-                        &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                        &[reason.as_ref().as_ref()],
-                    );
-                    warn!(
-                        self.log(),
-                        "Builder returned invalid payload";
-                        "info" => "using local payload",
-                        "reason" => %reason,
-                        "relay_block_hash" => ?header.block_hash(),
-                        "parent_hash" => ?parent_hash,
-                    );
-                    return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
-                        local.try_into()?,
-                    )));
-                }
-
                 let relay_value = *relay.data.message.value();
 
                 let boosted_relay_value = match builder_boost_factor {
@@ -1183,50 +1467,30 @@ impl<E: EthSpec> ExecutionLayer<E> {
                 info!(
                     self.log(),
                     "Relay block is more profitable than local block";
+                    "relay" => relay_source.name(),
                     "local_block_value" => %local_value,
                     "relay_value" => %relay_value,
                     "boosted_relay_value" => %boosted_relay_value,
                     "builder_boost_factor" => ?builder_boost_factor
                 );
 
+                relays.record_selected(header.block_hash(), relay_source);
                 Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
-            (Ok(Some(relay)), Err(local_error)) => {
+            (Ok(Some((relay_source, relay))), Err(local_error)) => {
                 let header = &relay.data.message.header();
 
                 info!(
                     self.log(),
                     "Received builder payload with local error";
+                    "relay" => relay_source.name(),
                     "relay_block_hash" => ?header.block_hash(),
                     "local_error" => ?local_error,
                     "parent_hash" => ?parent_hash,
                 );
 
-                match verify_builder_bid(
-                    &relay,
-                    parent_hash,
-                    payload_attributes,
-                    None,
-                    current_fork,
-                    spec,
-                ) {
-                    Ok(()) => Ok(ProvenancedPayload::try_from(relay.data.message)?),
-                    Err(reason) => {
-                        metrics::inc_counter_vec(
-                            &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
-                            &[reason.as_ref().as_ref()],
-                        );
-                        crit!(
-                            self.log(),
-                            "Builder returned invalid payload";
-                            "info" => "no local payload either - unable to propose block",
-                            "reason" => %reason,
-                            "relay_block_hash" => ?header.block_hash(),
-                            "parent_hash" => ?parent_hash,
-                        );
-                        Err(Error::CannotProduceHeader)
-                    }
-                }
+                relays.record_selected(header.block_hash(), relay_source);
+                Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
         }
     }
@@ -1371,6 +1635,11 @@ impl<E: EthSpec> ExecutionLayer<E> {
         let block_hash = new_payload_request.block_hash();
         let parent_hash = new_payload_request.parent_hash();
 
+        // The redundant engines may be sent the payload once the primary engine has responded,
+        // so they need their own copy of it.
+        let redundant_request = (!self.inner.redundant_engines.is_empty())
+            .then(|| Arc::new(OwnedNewPayloadRequest::from(&new_payload_request)));
+
         let result = self
             .engine()
             .request(|engine| engine.api.new_payload(new_payload_request))
@@ -1394,14 +1663,89 @@ impl<E: EthSpec> ExecutionLayer<E> {
         }
         *self.inner.last_new_payload_errored.write().await = result.is_err();
 
-        process_payload_status(block_hash, result, self.log())
-            .map_err(Box::new)
-            .map_err(Error::EngineError)
+        let mut status = process_payload_status(block_hash, result, self.log());
+        if let Some(request) = redundant_request {
+            status = self
+                .check_redundant_engines(metrics::NEW_PAYLOAD, block_hash, status, |engine| {
+                    let request = request.clone();
+                    async move {
+                        engine
+                            .request(|engine| engine.api.new_payload(request.as_request()))
+                            .await
+                    }
+                })
+                .await;
+        }
+        status.map_err(Box::new).map_err(Error::EngineError)
+    }
+
+    /// Sends a call that the primary engine has already answered with `primary_status` to each
+    /// redundant engine, and returns the status to act upon.
+    ///
+    /// If `payload_status_policy` requires it, e.g. because the primary engine returned INVALID,
+    /// the redundant engines are awaited and all statuses are resolved according to the policy.
+    /// Otherwise the primary status is returned immediately and the redundant engines are only
+    /// cross-checked in the background, so that they never delay block import.
+    async fn check_redundant_engines<F, G>(
+        &self,
+        method: &'static str,
+        block_hash: ExecutionBlockHash,
+        primary_status: Result<PayloadStatus, EngineError>,
+        call: F,
+    ) -> Result<PayloadStatus, EngineError>
+    where
+        F: Fn(Arc<Engine>) -> G,
+        G: Future<Output = Result<PayloadStatusV1, EngineError>> + Send + 'static,
+    {
+        let policy = self.inner.payload_status_policy;
+        if must_resolve_with_redundant_engines(policy, &primary_status) {
+            let redundant_statuses =
+                join_all(
+                    self.inner.redundant_engines.iter().map(|engine| {
+                        let redundant_call = call(engine.clone());
+                        async move {
+                            process_payload_status(block_hash, redundant_call.await, self.log())
+                        }
+                    }),
+                )
+                .await;
+            let mut statuses = vec![primary_status];
+            statuses.extend(redundant_statuses);
+            return resolve_payload_statuses(method, policy, statuses, self.log());
+        }
+
+        for (engine_index, engine) in self.inner.redundant_engines.iter().enumerate() {
+            let redundant_call = call(engine.clone());
+            let primary_status = primary_status.as_ref().ok().cloned();
+            let log = self.log().clone();
+            self.executor().spawn(
+                async move {
+                    let status = process_payload_status(block_hash, redundant_call.await, &log);
+                    cross_check_payload_status(
+                        method,
+                        engine_index,
+                        block_hash,
+                        primary_status.as_ref(),
+                        &status,
+                        &log,
+                    );
+                },
+                "redundant_engine_cross_check",
+            );
+        }
+        primary_status
     }
 
     /// Update engine sync status.
     pub async fn upcheck(&self) {
         self.engine().upcheck().await;
+        join_all(
+            self.inner
+                .redundant_engines
+                .iter()
+                .map(|engine| engine.upcheck()),
+        )
+        .await;
     }
 
     /// Register that the given `validator_index` is going to produce a block at `slot`.
@@ -1535,11 +1879,33 @@ impl<E: EthSpec> ExecutionLayer<E> {
             );
         }
 
-        process_payload_status(
+        let status = process_payload_status(
             head_block_hash,
             result.map(|response| response.payload_status),
             self.log(),
+        );
+        // Only the primary engine is asked to build payloads.
+        let log = self.log().clone();
+        self.check_redundant_engines(
+            metrics::FORKCHOICE_UPDATED,
+            head_block_hash,
+            status,
+            |engine| {
+                let log = log.clone();
+                async move {
+                    engine.set_latest_forkchoice_state(forkchoice_state).await;
+                    engine
+                        .request(|engine| async move {
+                            engine
+                                .notify_forkchoice_updated(forkchoice_state, None, &log)
+                                .await
+                        })
+                        .await
+                        .map(|response| response.payload_status)
+                }
+            },
         )
+        .await
         .map_err(Box::new)
         .map_err(Error::EngineError)
     }
@@ -1786,6 +2152,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
         &self,
         hashes: Vec<ExecutionBlockHash>,
     ) -> Result<Vec<Option<ExecutionPayloadBodyV1<E>>>, Error> {
+        let _timer = metrics::start_timer(&metrics::EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_HASH);
         self.engine()
             .request(|engine: &Engine| async move {
                 engine.api.get_payload_bodies_by_hash_v1(hashes).await
@@ -1815,14 +2182,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
 
     /// Fetch a full payload from the execution node.
     ///
-    /// This will fail if the payload is not from the finalized portion of the chain.
+    /// See `reconstruct_payloads`.
     pub async fn get_payload_for_header(
         &self,
         header: &ExecutionPayloadHeader<E>,
         fork: ForkName,
     ) -> Result<Option<ExecutionPayload<E>>, Error> {
-        let block_number = header.block_number();
-
         // Handle default payload body.
         if header.block_hash() == ExecutionBlockHash::zero() {
             let payload = match fork {
@@ -1837,25 +2202,167 @@ impl<E: EthSpec> ExecutionLayer<E> {
             return Ok(Some(payload));
         }
 
-        // Use efficient payload bodies by range method if supported.
+        self.reconstruct_payloads(std::slice::from_ref(header))
+            .await?
+            .pop()
+            .unwrap_or(Ok(None))
+    }
+
+    /// Reconstructs the full payloads of `headers` from the payload bodies stored by the
+    /// execution node, for blocks whose payloads have been pruned from the database.
+    ///
+    /// Recently reconstructed payloads are served from a cache. The remaining bodies are
+    /// requested with `engine_getPayloadBodiesByRange` in batches of nearby block numbers, and
+    /// bodies that are missing or belong to a different block at the same height (e.g. because
+    /// the block is not canonical on the execution chain) are requested again with
+    /// `engine_getPayloadBodiesByHash`.
+    ///
+    /// Each header has its own result, so that one payload failing to be reconstructed does not
+    /// prevent the others from being served. A payload unknown to the execution node is
+    /// `Ok(None)`.
+    pub async fn reconstruct_payloads(
+        &self,
+        headers: &[ExecutionPayloadHeader<E>],
+    ) -> Result<Vec<Result<Option<ExecutionPayload<E>>, Error>>, Error> {
+        let mut results = headers
+            .iter()
+            .map(|header| {
+                if header.block_hash() == ExecutionBlockHash::zero() {
+                    return Some(Ok(Some(default_payload(header))));
+                }
+                let payload = self
+                    .inner
+                    .reconstructed_payloads
+                    .get(&header.block_hash())?;
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_RECONSTRUCTED_PAYLOADS,
+                    &[metrics::HIT],
+                );
+                Some(Ok(Some(payload)))
+            })
+            .collect::<Vec<_>>();
+        let mut pending = (0..headers.len())
+            .filter(|&i| results[i].is_none())
+            .collect::<Vec<_>>();
+        if pending.is_empty() {
+            return Ok(results.into_iter().flatten().collect());
+        }
+
         let capabilities = self.get_engine_capabilities(None).await?;
+        if !capabilities.get_payload_bodies_by_range_v1
+            && !capabilities.get_payload_bodies_by_hash_v1
+        {
+            return Err(Error::PayloadBodiesByRangeNotSupported);
+        }
+
         if capabilities.get_payload_bodies_by_range_v1 {
-            let mut payload_bodies = self.get_payload_bodies_by_range(block_number, 1).await?;
+            pending.sort_by_key(|&i| headers[i].block_number());
+            let mut batches: Vec<Vec<usize>> = vec![];
+            for i in pending {
+                match batches.last_mut() {
+                    Some(batch)
+                        if headers[i].block_number()
+                            < headers[batch[0]].block_number() + PAYLOAD_BODIES_BATCH_SIZE =>
+                    {
+                        batch.push(i)
+                    }
+                    _ => batches.push(vec![i]),
+                }
+            }
 
-            if payload_bodies.len() != 1 {
-                return Ok(None);
+            pending = vec![];
+            for batch in batches {
+                let start = headers[batch[0]].block_number();
+                let count = headers[batch[batch.len() - 1]].block_number() - start + 1;
+                match self.get_payload_bodies_by_range(start, count).await {
+                    Ok(mut bodies) => {
+                        for i in batch {
+                            let offset = (headers[i].block_number() - start) as usize;
+                            match bodies
+                                .get_mut(offset)
+                                .and_then(Option::take)
+                                .map(|body| reconstruct_payload(body, &headers[i]))
+                            {
+                                Some(Ok(payload)) => {
+                                    metrics::inc_counter_vec(
+                                        &metrics::EXECUTION_LAYER_RECONSTRUCTED_PAYLOADS,
+                                        &[metrics::BY_RANGE],
+                                    );
+                                    results[i] = Some(Ok(Some(payload)));
+                                }
+                                // The body is missing or belongs to another block at the same
+                                // height, try again by hash.
+                                _ => pending.push(i),
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        debug!(
+                            self.log(),
+                            "Failed to fetch payload bodies by range";
+                            "start" => start,
+                            "count" => count,
+                            "error" => ?e,
+                        );
+                        let error = format!("{:?}", e);
+                        for i in batch {
+                            results[i] = Some(Err(Error::PayloadBodiesUnavailable(error.clone())));
+                            pending.push(i);
+                        }
+                    }
+                }
             }
+        }
 
-            let opt_payload_body = payload_bodies.pop().flatten();
-            opt_payload_body
-                .map(|body| {
-                    body.to_payload(header.clone())
-                        .map_err(Error::InvalidPayloadBody)
-                })
-                .transpose()
-        } else {
-            Err(Error::PayloadBodiesByRangeNotSupported)
+        if capabilities.get_payload_bodies_by_hash_v1 {
+            for batch in pending.chunks(PAYLOAD_BODIES_BATCH_SIZE as usize) {
+                let hashes = batch.iter().map(|&i| headers[i].block_hash()).collect();
+                match self.get_payload_bodies_by_hash(hashes).await {
+                    Ok(bodies) => {
+                        let mut bodies = bodies.into_iter();
+                        for &i in batch {
+                            results[i] = Some(match bodies.next().flatten() {
+                                Some(body) => reconstruct_payload(body, &headers[i]).map(Some),
+                                None => Ok(None),
+                            });
+                            if let Some(Ok(Some(_))) = &results[i] {
+                                metrics::inc_counter_vec(
+                                    &metrics::EXECUTION_LAYER_RECONSTRUCTED_PAYLOADS,
+                                    &[metrics::BY_HASH],
+                                );
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        debug!(
+                            self.log(),
+                            "Failed to fetch payload bodies by hash";
+                            "count" => batch.len(),
+                            "error" => ?e,
+                        );
+                        let error = format!("{:?}", e);
+                        for &i in batch {
+                            results[i] = Some(Err(Error::PayloadBodiesUnavailable(error.clone())));
+                        }
+                    }
+                }
+            }
         }
+
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                let result = result.unwrap_or(Ok(None));
+                match &result {
+                    Ok(Some(payload)) => self.inner.reconstructed_payloads.put(payload.clone()),
+                    _ => metrics::inc_counter_vec(
+                        &metrics::EXECUTION_LAYER_RECONSTRUCTED_PAYLOADS,
+                        &[metrics::UNAVAILABLE],
+                    ),
+                }
+                result
+            })
+            .collect())
     }
 
     pub async fn get_blobs(
@@ -1897,59 +2404,99 @@ impl<E: EthSpec> ExecutionLayer<E> {
             "root" => ?block_root,
         );
 
-        if let Some(builder) = self.builder() {
-            let (payload_result, duration) =
-                timed_future(metrics::POST_BLINDED_PAYLOAD_BUILDER, async {
-                    builder
-                        .post_builder_blinded_blocks(block)
-                        .await
-                        .map_err(Error::Builder)
-                        .map(|d| d.data)
-                })
-                .await;
+        let Some(relays) = self.relays() else {
+            return Err(Error::NoPayloadBuilder);
+        };
+        if relays.all().is_empty() {
+            return Err(Error::NoPayloadBuilder);
+        }
+        // Only send the block to the relay that provided the bid. The signed blinded block commits
+        // the proposer to the payload, so it must not be revealed to relays which don't know it.
+        let block_hash = block
+            .message()
+            .execution_payload()
+            .map_err(|_| Error::InvalidForkForPayload)?
+            .block_hash();
+        let Some(relay) = relays.selected(block_hash) else {
+            error!(
+                self.log(),
+                "Relay of builder bid is unknown";
+                "info" => "the block may have been produced by another beacon node",
+                "block_root" => ?block_root,
+                "block_hash" => ?block_hash,
+            );
+            return Err(Error::UnknownBidRelay { block_hash });
+        };
+        let relay_name = relay.name().to_string();
 
-            match &payload_result {
-                Ok(unblinded_response) => {
-                    metrics::inc_counter_vec(
-                        &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
-                        &[metrics::SUCCESS],
-                    );
-                    let payload = unblinded_response.payload_ref();
-                    info!(
+        let (payload_result, duration) =
+            timed_future(metrics::POST_BLINDED_PAYLOAD_BUILDER, async {
+                relay
+                    .client()
+                    .post_builder_blinded_blocks(block)
+                    .await
+                    .map_err(Error::Builder)
+                    .map(|d| d.data)
+            })
+            .await;
+
+        match &payload_result {
+            Ok(unblinded_response) => {
+                relay.record_reveal(duration);
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
+                    &[metrics::SUCCESS],
+                );
+                let payload = unblinded_response.payload_ref();
+                if duration > LATE_REVEAL_THRESHOLD {
+                    warn!(
                         self.log(),
-                        "Builder successfully revealed payload";
+                        "Builder revealed payload late";
+                        "info" => "late reveals put the block at risk of being orphaned",
+                        "relay" => &relay_name,
                         "relay_response_ms" => duration.as_millis(),
                         "block_root" => ?block_root,
                         "fee_recipient" => ?payload.fee_recipient(),
                         "block_hash" => ?payload.block_hash(),
                         "parent_hash" => ?payload.parent_hash()
-                    )
-                }
-                Err(e) => {
-                    metrics::inc_counter_vec(
-                        &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
-                        &[metrics::FAILURE],
                     );
-                    warn!(
+                } else {
+                    info!(
                         self.log(),
-                        "Builder failed to reveal payload";
-                        "info" => "this is common behaviour for some builders and may not indicate an issue",
-                        "error" => ?e,
+                        "Builder successfully revealed payload";
+                        "relay" => &relay_name,
                         "relay_response_ms" => duration.as_millis(),
                         "block_root" => ?block_root,
-                        "parent_hash" => ?block
-                            .message()
-                            .execution_payload()
-                            .map(|payload| format!("{}", payload.parent_hash()))
-                            .unwrap_or_else(|_| "unknown".to_string())
+                        "fee_recipient" => ?payload.fee_recipient(),
+                        "block_hash" => ?payload.block_hash(),
+                        "parent_hash" => ?payload.parent_hash()
                     )
                 }
             }
-
-            payload_result
-        } else {
-            Err(Error::NoPayloadBuilder)
+            Err(e) => {
+                relay.record_failed_reveal(duration);
+                metrics::inc_counter_vec(
+                    &metrics::EXECUTION_LAYER_BUILDER_REVEAL_PAYLOAD_OUTCOME,
+                    &[metrics::FAILURE],
+                );
+                warn!(
+                    self.log(),
+                    "Builder failed to reveal payload";
+                    "info" => "this is common behaviour for some builders and may not indicate an issue",
+                    "relay" => &relay_name,
+                    "error" => ?e,
+                    "relay_response_ms" => duration.as_millis(),
+                    "block_root" => ?block_root,
+                    "parent_hash" => ?block
+                        .message()
+                        .execution_payload()
+                        .map(|payload| format!("{}", payload.parent_hash()))
+                        .unwrap_or_else(|_| "unknown".to_string())
+                )
+            }
         }
+
+        payload_result
     }
 }
 
@@ -2026,6 +2573,33 @@ impl fmt::Display for InvalidBuilderPayload {
     }
 }
 
+/// Returns the default payload of the fork of `header`, for blocks from before the merge.
+fn default_payload<E: EthSpec>(header: &ExecutionPayloadHeader<E>) -> ExecutionPayload<E> {
+    match header {
+        ExecutionPayloadHeader::Bellatrix(_) => ExecutionPayloadBellatrix::default().into(),
+        ExecutionPayloadHeader::Capella(_) => ExecutionPayloadCapella::default().into(),
+        ExecutionPayloadHeader::Deneb(_) => ExecutionPayloadDeneb::default().into(),
+        ExecutionPayloadHeader::Electra(_) => ExecutionPayloadElectra::default().into(),
+    }
+}
+
+/// Combines `body` with `header`, checking that the body belongs to the block of `header`.
+fn reconstruct_payload<E: EthSpec>(
+    body: ExecutionPayloadBodyV1<E>,
+    header: &ExecutionPayloadHeader<E>,
+) -> Result<ExecutionPayload<E>, Error> {
+    let payload = body
+        .to_payload(header.clone())
+        .map_err(Error::InvalidPayloadBody)?;
+    if ExecutionPayloadHeader::from(payload.to_ref()) == *header {
+        Ok(payload)
+    } else {
+        Err(Error::InconsistentPayloadBody {
+            block_hash: header.block_hash(),
+        })
+    }
+}
+
 /// Perform some cursory, non-exhaustive validation of the bid returned from the builder.
 fn verify_builder_bid<E: EthSpec>(
     bid: &ForkVersionedResponse<SignedBuilderBid<E>>,
@@ -2138,6 +2712,52 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn reconstructs_payloads_by_range_and_hash() {
+        let runtime = TestRuntime::default();
+        let mock = MockExecutionLayer::default_params(runtime.task_executor.clone())
+            .move_to_terminal_block()
+            .produce_valid_execution_payload_on_head()
+            .await
+            .produce_valid_execution_payload_on_head()
+            .await;
+
+        let head_number = mock
+            .server
+            .execution_block_generator()
+            .latest_block()
+            .unwrap()
+            .block_number();
+        let mut headers = (head_number - 1..=head_number)
+            .map(|number| {
+                let payload = mock
+                    .server
+                    .execution_block_generator()
+                    .execution_payload_by_number(number)
+                    .expect("payload should exist");
+                ExecutionPayloadHeader::from(payload.to_ref())
+            })
+            .collect::<Vec<_>>();
+        // A block at the same height as a known block, but which the EE does not have.
+        let mut unknown = headers[1].clone();
+        *unknown.block_hash_mut() = ExecutionBlockHash::repeat_byte(42);
+        *unknown.transactions_root_mut() = Hash256::repeat_byte(42);
+        headers.push(unknown);
+
+        let results = mock.el.reconstruct_payloads(&headers).await.unwrap();
+        assert_eq!(results.len(), 3);
+        for (header, result) in headers.iter().zip(&results).take(2) {
+            let payload = result.as_ref().unwrap().as_ref().unwrap();
+            assert_eq!(ExecutionPayloadHeader::from(payload.to_ref()), *header);
+        }
+        assert!(matches!(results[2], Ok(None)));
+
+        // Reconstructed payloads are served from the cache.
+        mock.server.drop_all_blocks();
+        let results = mock.el.reconstruct_payloads(&headers[..2]).await.unwrap();
+        assert!(results.iter().all(|result| matches!(result, Ok(Some(_)))));
+    }
+
     #[tokio::test]
     async fn test_forked_terminal_block() {
         let runtime = TestRuntime::default();
@@ -16,6 +16,17 @@ pub const LOCAL: &str = "local";
 pub const BUILDER: &str = "builder";
 pub const SUCCESS: &str = "success";
 pub const FAILURE: &str = "failure";
+pub const BY_RANGE: &str = "by_range";
+pub const JWT_SECRET_FILE_RELOADED: &str = "file_reloaded";
+pub const JWT_SECRET_REJECTED: &str = "rejected";
+pub const BY_HASH: &str = "by_hash";
+pub const UNAVAILABLE: &str = "unavailable";
+pub const RELAY_BID: &str = "bid";
+pub const RELAY_NO_BID: &str = "no_bid";
+pub const RELAY_INVALID_BID: &str = "invalid_bid";
+pub const RELAY_REVEAL: &str = "reveal";
+pub const RELAY_LATE_REVEAL: &str = "late_reveal";
+pub const RELAY_FAILED_REVEAL: &str = "failed_reveal";
 
 pub static EXECUTION_LAYER_PROPOSER_INSERTED: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
@@ -61,6 +72,29 @@ pub static EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_RANGE: LazyLock<Result<Histogra
             "Time to fetch a range of payload bodies from the EE",
         )
     });
+pub static EXECUTION_LAYER_GET_PAYLOAD_BODIES_BY_HASH: LazyLock<Result<Histogram>> =
+    LazyLock::new(|| {
+        try_create_histogram(
+            "execution_layer_get_payload_bodies_by_hash_time",
+            "Time to fetch a list of payload bodies by block hash from the EE",
+        )
+    });
+pub static EXECUTION_LAYER_RECONSTRUCTED_PAYLOADS: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "execution_layer_reconstructed_payloads_total",
+            "Count of payloads reconstructed from payload bodies, by where the body was found",
+            &["source"],
+        )
+    });
+pub static EXECUTION_LAYER_JWT_SECRET_CHANGES: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "execution_layer_jwt_secret_changes_total",
+            "Count of changes to the JWT secret used to authenticate with the EE, by reason",
+            &["reason"],
+        )
+    });
 pub static EXECUTION_LAYER_VERIFY_BLOCK_HASH: LazyLock<Result<Histogram>> = LazyLock::new(|| {
     try_create_histogram_with_buckets(
         "execution_layer_verify_block_hash_time",
@@ -77,6 +111,14 @@ pub static EXECUTION_LAYER_PAYLOAD_STATUS: LazyLock<Result<IntCounterVec>> = Laz
         &["method", "status"],
     )
 });
+pub static EXECUTION_LAYER_PAYLOAD_STATUS_CONFLICTS: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "execution_layer_payload_status_conflicts_total",
+            "Count of payload statuses on which the execution engines did not agree",
+            &["method"],
+        )
+    });
 pub static EXECUTION_LAYER_GET_PAYLOAD_OUTCOME: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
         try_create_int_counter_vec(
@@ -116,3 +158,40 @@ pub static EXECUTION_LAYER_PAYLOAD_BIDS: LazyLock<Result<IntGaugeVec>> = LazyLoc
         &["source"]
     )
 });
+pub static EXECUTION_LAYER_RELAY_OUTCOMES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "execution_layer_relay_outcomes_total",
+        "Count of the outcomes of requests for bids and payload reveals sent to each relay",
+        &["relay", "outcome"],
+    )
+});
+pub static EXECUTION_LAYER_RELAY_RESPONSE_TIMES: LazyLock<Result<HistogramVec>> =
+    LazyLock::new(|| {
+        try_create_histogram_vec_with_buckets(
+            "execution_layer_relay_response_times",
+            "Duration of requests for bids and payload reveals sent to each relay",
+            decimal_buckets(-2, 1),
+            &["relay"],
+        )
+    });
+pub static EXECUTION_LAYER_RELAY_SCORE: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "execution_layer_relay_score",
+        "The reliability score of each relay, relays are blacklisted below zero",
+        &["relay"],
+    )
+});
+pub static EXECUTION_LAYER_RELAY_BLACKLISTED: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "execution_layer_relay_blacklisted",
+        "Set to 1 if the relay is blacklisted and not asked for bids",
+        &["relay"],
+    )
+});
+pub static EXECUTION_LAYER_RELAY_BEST_BIDS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "execution_layer_relay_best_bids_total",
+        "Count of times each relay provided the highest valid bid",
+        &["relay"],
+    )
+});
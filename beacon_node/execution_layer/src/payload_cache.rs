@@ -4,9 +4,10 @@ use parking_lot::Mutex;
 use std::num::NonZeroUsize;
 use tree_hash::TreeHash;
 use types::non_zero_usize::new_non_zero_usize;
-use types::{EthSpec, Hash256};
+use types::{EthSpec, ExecutionBlockHash, ExecutionPayload, Hash256};
 
 pub const DEFAULT_PAYLOAD_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(10);
+pub const DEFAULT_RECONSTRUCTED_PAYLOAD_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(64);
 
 /// A cache mapping execution payloads by tree hash roots.
 pub struct PayloadCache<E: EthSpec> {
@@ -38,3 +39,27 @@ impl<E: EthSpec> PayloadCache<E> {
         self.payloads.lock().get(&PayloadCacheId(*hash)).cloned()
     }
 }
+
+/// A cache of payloads reconstructed from the payload bodies returned by the EE, keyed by block
+/// hash.
+pub struct ReconstructedPayloadCache<E: EthSpec> {
+    payloads: Mutex<LruCache<ExecutionBlockHash, ExecutionPayload<E>>>,
+}
+
+impl<E: EthSpec> Default for ReconstructedPayloadCache<E> {
+    fn default() -> Self {
+        ReconstructedPayloadCache {
+            payloads: Mutex::new(LruCache::new(DEFAULT_RECONSTRUCTED_PAYLOAD_CACHE_SIZE)),
+        }
+    }
+}
+
+impl<E: EthSpec> ReconstructedPayloadCache<E> {
+    pub fn put(&self, payload: ExecutionPayload<E>) {
+        self.payloads.lock().put(payload.block_hash(), payload);
+    }
+
+    pub fn get(&self, block_hash: &ExecutionBlockHash) -> Option<ExecutionPayload<E>> {
+        self.payloads.lock().get(block_hash).cloned()
+    }
+}
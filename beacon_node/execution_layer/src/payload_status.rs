@@ -1,6 +1,8 @@
 use crate::engine_api::{Error as ApiError, PayloadStatusV1, PayloadStatusV1Status};
 use crate::engines::EngineError;
-use slog::{warn, Logger};
+use crate::metrics;
+use serde::{Deserialize, Serialize};
+use slog::{debug, warn, Logger};
 use types::ExecutionBlockHash;
 
 /// Provides a simpler, easier to parse version of `PayloadStatusV1` for upstream users.
@@ -108,3 +110,316 @@ pub fn process_payload_status(
         },
     }
 }
+
+/// How the payload statuses returned by redundant execution engines are combined into one.
+///
+/// Only VALID and INVALID (including INVALID_BLOCK_HASH) are verdicts. SYNCING, ACCEPTED and
+/// errors abstain. Whenever the verdicts cannot be reconciled the payload is treated as SYNCING,
+/// so that a single faulty engine can neither invalidate nor validate a payload on its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum PayloadStatusPolicy {
+    /// Use the verdict of the first engine (in configuration order) that returned one. An INVALID
+    /// verdict contradicted by a VALID verdict from any other engine is treated as SYNCING.
+    #[default]
+    Priority,
+    /// A verdict is only adopted if at least this many engines returned it, and fewer engines
+    /// returned the opposite verdict.
+    Quorum(usize),
+}
+
+/// Combines the statuses returned by each engine for the same payload, ordered by priority,
+/// according to `policy`.
+///
+/// If no engine returned a verdict, the first non-error status is returned, or the first error if
+/// every engine failed.
+pub fn resolve_payload_statuses(
+    method: &'static str,
+    policy: PayloadStatusPolicy,
+    mut statuses: Vec<Result<PayloadStatus, EngineError>>,
+    log: &Logger,
+) -> Result<PayloadStatus, EngineError> {
+    if statuses.len() <= 1 {
+        return statuses.pop().unwrap_or(Err(EngineError::Offline));
+    }
+
+    let is_valid =
+        |status: &Result<PayloadStatus, EngineError>| matches!(status, Ok(PayloadStatus::Valid));
+    let is_invalid = |status: &Result<PayloadStatus, EngineError>| {
+        matches!(
+            status,
+            Ok(PayloadStatus::Invalid { .. } | PayloadStatus::InvalidBlockHash { .. })
+        )
+    };
+    let valid_count = statuses.iter().filter(|status| is_valid(status)).count();
+    let invalid_count = statuses.iter().filter(|status| is_invalid(status)).count();
+
+    let first_valid = statuses.iter().position(is_valid);
+    let first_invalid = statuses.iter().position(is_invalid);
+
+    let adopted = match policy {
+        PayloadStatusPolicy::Priority => {
+            match statuses
+                .iter()
+                .position(|status| is_valid(status) || is_invalid(status))
+            {
+                Some(index) if is_invalid(&statuses[index]) && valid_count > 0 => None,
+                index => index,
+            }
+        }
+        PayloadStatusPolicy::Quorum(quorum) => {
+            if valid_count >= quorum && invalid_count < quorum {
+                first_valid
+            } else if invalid_count >= quorum && valid_count < quorum {
+                first_invalid
+            } else {
+                None
+            }
+        }
+    };
+
+    if let Some(index) = adopted {
+        return statuses.swap_remove(index);
+    }
+
+    if valid_count + invalid_count > 0 {
+        warn!(
+            log,
+            "Execution engines did not agree on payload status";
+            "info" => "treating the payload as optimistic",
+            "method" => method,
+            "valid" => valid_count,
+            "invalid" => invalid_count,
+            "engines" => statuses.len(),
+            "policy" => ?policy,
+        );
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_PAYLOAD_STATUS_CONFLICTS,
+            &[method],
+        );
+        return Ok(PayloadStatus::Syncing);
+    }
+
+    // No engine returned a verdict.
+    let index = statuses.iter().position(Result::is_ok).unwrap_or(0);
+    statuses.swap_remove(index)
+}
+
+/// Returns `true` if the redundant engines must be awaited and their statuses resolved with
+/// `primary` according to `policy` before acting on a payload.
+///
+/// With a quorum every engine must be heard. Otherwise the primary engine is trusted unless it
+/// returned INVALID, as a single faulty engine must never be able to invalidate the chain.
+pub fn must_resolve_with_redundant_engines(
+    policy: PayloadStatusPolicy,
+    primary: &Result<PayloadStatus, EngineError>,
+) -> bool {
+    match policy {
+        PayloadStatusPolicy::Quorum(_) => true,
+        PayloadStatusPolicy::Priority => matches!(
+            primary,
+            Ok(PayloadStatus::Invalid { .. } | PayloadStatus::InvalidBlockHash { .. })
+        ),
+    }
+}
+
+/// Returns `true` if two engines returned opposite verdicts for the same payload, i.e. one
+/// returned VALID and the other INVALID (or INVALID_BLOCK_HASH).
+pub fn is_conflicting(a: &PayloadStatus, b: &PayloadStatus) -> bool {
+    let is_invalid = |status: &PayloadStatus| {
+        matches!(
+            status,
+            PayloadStatus::Invalid { .. } | PayloadStatus::InvalidBlockHash { .. }
+        )
+    };
+    match (a, b) {
+        (PayloadStatus::Valid, other) | (other, PayloadStatus::Valid) => is_invalid(other),
+        _ => false,
+    }
+}
+
+/// Compares the status returned by the redundant engine at `engine_index` with the status the
+/// primary engine returned for the same call, once the primary status has already been acted
+/// upon. A contradiction is only reported.
+pub fn cross_check_payload_status(
+    method: &'static str,
+    engine_index: usize,
+    block_hash: ExecutionBlockHash,
+    primary: Option<&PayloadStatus>,
+    redundant: &Result<PayloadStatus, EngineError>,
+    log: &Logger,
+) {
+    match (primary, redundant) {
+        (Some(primary), Ok(redundant)) if is_conflicting(primary, redundant) => {
+            warn!(
+                log,
+                "Execution engines did not agree on payload status";
+                "info" => "the status of the primary execution engine was used",
+                "method" => method,
+                "block_hash" => ?block_hash,
+                "primary_status" => ?primary,
+                "redundant_status" => ?redundant,
+                "redundant_engine" => engine_index,
+            );
+            metrics::inc_counter_vec(
+                &metrics::EXECUTION_LAYER_PAYLOAD_STATUS_CONFLICTS,
+                &[method],
+            );
+        }
+        (primary, redundant) => {
+            debug!(
+                log,
+                "Cross-checked payload status";
+                "method" => method,
+                "block_hash" => ?block_hash,
+                "primary_status" => ?primary,
+                "redundant_status" => ?redundant,
+                "redundant_engine" => engine_index,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn invalid() -> Result<PayloadStatus, EngineError> {
+        Ok(PayloadStatus::Invalid {
+            latest_valid_hash: None,
+            validation_error: None,
+        })
+    }
+
+    fn resolve(
+        policy: PayloadStatusPolicy,
+        statuses: Vec<Result<PayloadStatus, EngineError>>,
+    ) -> Result<PayloadStatus, EngineError> {
+        resolve_payload_statuses("test", policy, statuses, &logging::test_logger())
+    }
+
+    #[test]
+    fn priority_does_not_invalidate_on_conflict() {
+        let policy = PayloadStatusPolicy::Priority;
+        assert_eq!(
+            resolve(policy, vec![invalid(), Ok(PayloadStatus::Valid)]).unwrap(),
+            PayloadStatus::Syncing
+        );
+        assert_eq!(
+            resolve(policy, vec![Ok(PayloadStatus::Valid), invalid()]).unwrap(),
+            PayloadStatus::Valid
+        );
+        assert_eq!(
+            resolve(policy, vec![Err(EngineError::Offline), invalid()]).unwrap(),
+            invalid().unwrap()
+        );
+        assert_eq!(
+            resolve(
+                policy,
+                vec![Err(EngineError::Offline), Ok(PayloadStatus::Accepted)]
+            )
+            .unwrap(),
+            PayloadStatus::Accepted
+        );
+    }
+
+    #[test]
+    fn quorum_requires_agreement() {
+        let policy = PayloadStatusPolicy::Quorum(2);
+        assert_eq!(
+            resolve(
+                policy,
+                vec![
+                    Ok(PayloadStatus::Valid),
+                    invalid(),
+                    Ok(PayloadStatus::Valid)
+                ]
+            )
+            .unwrap(),
+            PayloadStatus::Valid
+        );
+        assert_eq!(
+            resolve(
+                policy,
+                vec![invalid(), Ok(PayloadStatus::Syncing), invalid()]
+            )
+            .unwrap(),
+            invalid().unwrap()
+        );
+        assert_eq!(
+            resolve(
+                policy,
+                vec![
+                    invalid(),
+                    Ok(PayloadStatus::Syncing),
+                    Err(EngineError::Offline)
+                ]
+            )
+            .unwrap(),
+            PayloadStatus::Syncing
+        );
+        assert!(matches!(
+            resolve(
+                policy,
+                vec![Err(EngineError::Offline), Err(EngineError::Offline)]
+            ),
+            Err(EngineError::Offline)
+        ));
+    }
+
+    #[test]
+    fn only_opposite_verdicts_conflict() {
+        let invalid_block_hash = PayloadStatus::InvalidBlockHash {
+            validation_error: None,
+        };
+        assert!(is_conflicting(&PayloadStatus::Valid, &invalid().unwrap()));
+        assert!(is_conflicting(&invalid().unwrap(), &PayloadStatus::Valid));
+        assert!(is_conflicting(&invalid_block_hash, &PayloadStatus::Valid));
+
+        assert!(!is_conflicting(
+            &PayloadStatus::Valid,
+            &PayloadStatus::Valid
+        ));
+        assert!(!is_conflicting(&invalid().unwrap(), &invalid_block_hash));
+        assert!(!is_conflicting(
+            &PayloadStatus::Valid,
+            &PayloadStatus::Syncing
+        ));
+        assert!(!is_conflicting(
+            &invalid().unwrap(),
+            &PayloadStatus::Accepted
+        ));
+    }
+
+    #[test]
+    fn engines_disagree_on_invalid_payload() {
+        let policy = PayloadStatusPolicy::Priority;
+        // The primary engine says INVALID, so the redundant engines are heard, and one of them
+        // saying VALID causes the payload to be imported optimistically.
+        assert!(must_resolve_with_redundant_engines(policy, &invalid()));
+        assert_eq!(
+            resolve(
+                policy,
+                vec![
+                    invalid(),
+                    Ok(PayloadStatus::Syncing),
+                    Ok(PayloadStatus::Valid)
+                ]
+            )
+            .unwrap(),
+            PayloadStatus::Syncing
+        );
+        // A VALID primary status is acted upon immediately and only cross-checked afterwards.
+        assert!(!must_resolve_with_redundant_engines(
+            policy,
+            &Ok(PayloadStatus::Valid)
+        ));
+        assert!(!must_resolve_with_redundant_engines(
+            policy,
+            &Err(EngineError::Offline)
+        ));
+        assert!(must_resolve_with_redundant_engines(
+            PayloadStatusPolicy::Quorum(2),
+            &Ok(PayloadStatus::Valid)
+        ));
+    }
+}
@@ -0,0 +1,374 @@
+//! Tracks the reliability of the builder relays that bids are requested from.
+//!
+//! Every relay starts with a score of [`MAX_SCORE`]. Valid bids and timely payload reveals raise
+//! the score, while failed requests, invalid bids and failed or late reveals lower it. A relay
+//! whose score drops below zero is blacklisted: it is not asked for bids until
+//! [`BLACKLIST_DURATION`] has passed, after which it gets another chance with a score of
+//! [`SCORE_AFTER_BLACKLIST`].
+
+use crate::metrics;
+use builder_client::BuilderHttpClient;
+use lru::LruCache;
+use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
+use slog::{info, warn, Logger};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use types::non_zero_usize::new_non_zero_usize;
+use types::{ExecutionBlockHash, Uint256};
+
+pub const MAX_SCORE: i64 = 100;
+pub const SCORE_AFTER_BLACKLIST: i64 = 50;
+pub const BLACKLIST_DURATION: Duration = Duration::from_secs(60 * 60);
+/// A relay taking longer than this to reveal a payload puts the proposal at risk of being
+/// orphaned.
+pub const LATE_REVEAL_THRESHOLD: Duration = Duration::from_secs(2);
+
+const VALID_BID_REWARD: i64 = 1;
+const REVEAL_REWARD: i64 = 5;
+const FAILED_REQUEST_PENALTY: i64 = 5;
+const INVALID_BID_PENALTY: i64 = 25;
+const LATE_REVEAL_PENALTY: i64 = 25;
+const FAILED_REVEAL_PENALTY: i64 = 50;
+
+/// Weight of the most recent response time in a relay's average latency.
+const LATENCY_WEIGHT: f64 = 0.2;
+
+/// The number of recently used bids for which the relay that provided them is remembered.
+const SELECTED_BIDS_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(32);
+
+/// A snapshot of the history of a relay.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RelayStats {
+    pub score: i64,
+    pub bids: u64,
+    pub invalid_bids: u64,
+    pub failed_requests: u64,
+    pub reveals: u64,
+    pub late_reveals: u64,
+    pub failed_reveals: u64,
+    /// Moving average of the time taken to respond to requests.
+    pub average_latency: Option<Duration>,
+    pub blacklisted_until: Option<Instant>,
+}
+
+impl Default for RelayStats {
+    fn default() -> Self {
+        Self {
+            score: MAX_SCORE,
+            bids: 0,
+            invalid_bids: 0,
+            failed_requests: 0,
+            reveals: 0,
+            late_reveals: 0,
+            failed_reveals: 0,
+            average_latency: None,
+            blacklisted_until: None,
+        }
+    }
+}
+
+/// A builder relay, along with its history.
+pub struct Relay {
+    url: SensitiveUrl,
+    /// The redacted URL, used to identify the relay in logs and metrics.
+    name: String,
+    client: Arc<BuilderHttpClient>,
+    stats: Mutex<RelayStats>,
+    log: Logger,
+}
+
+impl Relay {
+    pub fn new(url: SensitiveUrl, client: Arc<BuilderHttpClient>, log: Logger) -> Self {
+        let relay = Self {
+            name: url.to_string(),
+            url,
+            client,
+            stats: Mutex::new(RelayStats::default()),
+            log,
+        };
+        relay.update_metrics(&relay.stats.lock());
+        relay
+    }
+
+    pub fn url(&self) -> &SensitiveUrl {
+        &self.url
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn client(&self) -> &Arc<BuilderHttpClient> {
+        &self.client
+    }
+
+    pub fn stats(&self) -> RelayStats {
+        self.stats.lock().clone()
+    }
+
+    pub fn score(&self) -> i64 {
+        self.stats.lock().score
+    }
+
+    /// Returns `true` if the relay may be asked for bids at `now`, lifting its blacklisting if
+    /// it has expired.
+    pub fn is_available(&self, now: Instant) -> bool {
+        let mut stats = self.stats.lock();
+        match stats.blacklisted_until {
+            Some(until) if until > now => false,
+            Some(_) => {
+                stats.blacklisted_until = None;
+                stats.score = SCORE_AFTER_BLACKLIST;
+                info!(
+                    self.log,
+                    "Builder relay is no longer blacklisted";
+                    "relay" => &self.name,
+                    "score" => stats.score,
+                );
+                self.update_metrics(&stats);
+                true
+            }
+            None => true,
+        }
+    }
+
+    pub fn record_bid(&self, latency: Duration) {
+        self.record_outcome(latency, metrics::RELAY_BID, VALID_BID_REWARD, |stats| {
+            stats.bids += 1
+        });
+    }
+
+    pub fn record_no_bid(&self, latency: Duration) {
+        self.record_outcome(latency, metrics::RELAY_NO_BID, 0, |_| ());
+    }
+
+    pub fn record_invalid_bid(&self, latency: Duration) {
+        self.record_outcome(
+            latency,
+            metrics::RELAY_INVALID_BID,
+            -INVALID_BID_PENALTY,
+            |stats| stats.invalid_bids += 1,
+        );
+    }
+
+    pub fn record_failed_request(&self, latency: Duration) {
+        self.record_outcome(
+            latency,
+            metrics::FAILURE,
+            -FAILED_REQUEST_PENALTY,
+            |stats| stats.failed_requests += 1,
+        );
+    }
+
+    /// Records a revealed payload, penalising the relay if it took longer than
+    /// [`LATE_REVEAL_THRESHOLD`].
+    pub fn record_reveal(&self, latency: Duration) {
+        if latency > LATE_REVEAL_THRESHOLD {
+            self.record_outcome(
+                latency,
+                metrics::RELAY_LATE_REVEAL,
+                -LATE_REVEAL_PENALTY,
+                |stats| stats.late_reveals += 1,
+            );
+        } else {
+            self.record_outcome(latency, metrics::RELAY_REVEAL, REVEAL_REWARD, |stats| {
+                stats.reveals += 1
+            });
+        }
+    }
+
+    pub fn record_failed_reveal(&self, latency: Duration) {
+        self.record_outcome(
+            latency,
+            metrics::RELAY_FAILED_REVEAL,
+            -FAILED_REVEAL_PENALTY,
+            |stats| stats.failed_reveals += 1,
+        );
+    }
+
+    fn record_outcome(
+        &self,
+        latency: Duration,
+        outcome: &str,
+        score_delta: i64,
+        update: impl FnOnce(&mut RelayStats),
+    ) {
+        metrics::inc_counter_vec(
+            &metrics::EXECUTION_LAYER_RELAY_OUTCOMES,
+            &[&self.name, outcome],
+        );
+        metrics::observe_timer_vec(
+            &metrics::EXECUTION_LAYER_RELAY_RESPONSE_TIMES,
+            &[&self.name],
+            latency,
+        );
+
+        let mut stats = self.stats.lock();
+        update(&mut stats);
+        stats.average_latency = Some(match stats.average_latency {
+            Some(average) => {
+                average.mul_f64(1.0 - LATENCY_WEIGHT) + latency.mul_f64(LATENCY_WEIGHT)
+            }
+            None => latency,
+        });
+        stats.score = (stats.score + score_delta).min(MAX_SCORE);
+
+        if stats.score < 0 && stats.blacklisted_until.is_none() {
+            stats.blacklisted_until = Some(Instant::now() + BLACKLIST_DURATION);
+            warn!(
+                self.log,
+                "Blacklisting builder relay";
+                "info" => "the relay will not be asked for bids until the blacklisting expires",
+                "relay" => &self.name,
+                "last_outcome" => outcome,
+                "invalid_bids" => stats.invalid_bids,
+                "failed_requests" => stats.failed_requests,
+                "late_reveals" => stats.late_reveals,
+                "failed_reveals" => stats.failed_reveals,
+                "duration_secs" => BLACKLIST_DURATION.as_secs(),
+            );
+        }
+        self.update_metrics(&stats);
+    }
+
+    fn update_metrics(&self, stats: &RelayStats) {
+        metrics::set_gauge_vec(
+            &metrics::EXECUTION_LAYER_RELAY_SCORE,
+            &[&self.name],
+            stats.score,
+        );
+        metrics::set_gauge_vec(
+            &metrics::EXECUTION_LAYER_RELAY_BLACKLISTED,
+            &[&self.name],
+            stats.blacklisted_until.is_some() as i64,
+        );
+    }
+}
+
+/// The set of relays that bids are requested from.
+pub struct Relays {
+    relays: Vec<Arc<Relay>>,
+    /// The relay that provided each recently used bid, keyed by the block hash of the bid.
+    selected: Mutex<LruCache<ExecutionBlockHash, Arc<Relay>>>,
+}
+
+impl Relays {
+    pub fn new(relays: Vec<Arc<Relay>>) -> Self {
+        Self {
+            relays,
+            selected: Mutex::new(LruCache::new(SELECTED_BIDS_CACHE_SIZE)),
+        }
+    }
+
+    /// Returns all relays, in the order they were configured.
+    pub fn all(&self) -> &[Arc<Relay>] {
+        &self.relays
+    }
+
+    /// Returns the relays that are not blacklisted.
+    pub fn available(&self) -> Vec<Arc<Relay>> {
+        let now = Instant::now();
+        self.relays
+            .iter()
+            .filter(|relay| relay.is_available(now))
+            .cloned()
+            .collect()
+    }
+
+    /// Remembers that the bid for `block_hash` was provided by `relay`, so that the signed blinded
+    /// block is sent back to it.
+    pub fn record_selected(&self, block_hash: ExecutionBlockHash, relay: Arc<Relay>) {
+        self.selected.lock().put(block_hash, relay);
+    }
+
+    /// Returns the relay that provided the bid for `block_hash`, if it is known.
+    pub fn selected(&self, block_hash: ExecutionBlockHash) -> Option<Arc<Relay>> {
+        self.selected.lock().get(&block_hash).cloned()
+    }
+
+    /// Returns the relay with the highest score, preferring the earliest configured on ties.
+    pub fn best(&self) -> Option<Arc<Relay>> {
+        self.relays
+            .iter()
+            .rev()
+            .max_by_key(|relay| relay.score())
+            .cloned()
+    }
+}
+
+/// Orders `bids` from best to worst: by value, then by the score of the relay that provided them.
+pub fn rank_bids<B>(bids: &mut [(Arc<Relay>, B)], value: impl Fn(&B) -> Uint256) {
+    bids.sort_by_cached_key(|(relay, bid)| std::cmp::Reverse((value(bid), relay.score())));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relay(url: &str) -> Arc<Relay> {
+        let url = SensitiveUrl::parse(url).unwrap();
+        let client = BuilderHttpClient::new(url.clone(), None, None).unwrap();
+        Arc::new(Relay::new(url, Arc::new(client), logging::test_logger()))
+    }
+
+    #[test]
+    fn invalid_bids_blacklist_relay_until_expiry() {
+        let relay = relay("http://relay.test");
+        let latency = Duration::from_millis(100);
+
+        relay.record_invalid_bid(latency);
+        relay.record_invalid_bid(latency);
+        relay.record_invalid_bid(latency);
+        assert!(relay.is_available(Instant::now()));
+
+        relay.record_invalid_bid(latency);
+        relay.record_invalid_bid(latency);
+        assert!(!relay.is_available(Instant::now()));
+        assert_eq!(relay.stats().invalid_bids, 5);
+
+        assert!(relay.is_available(Instant::now() + BLACKLIST_DURATION));
+        assert_eq!(relay.score(), SCORE_AFTER_BLACKLIST);
+        assert_eq!(relay.stats().blacklisted_until, None);
+    }
+
+    #[test]
+    fn late_reveals_are_penalised() {
+        let relay = relay("http://relay.test");
+
+        relay.record_reveal(Duration::from_millis(500));
+        assert_eq!(relay.score(), MAX_SCORE);
+
+        relay.record_reveal(LATE_REVEAL_THRESHOLD + Duration::from_millis(1));
+        assert_eq!(relay.score(), MAX_SCORE - LATE_REVEAL_PENALTY);
+        assert_eq!(relay.stats().late_reveals, 1);
+        assert_eq!(relay.stats().reveals, 1);
+    }
+
+    #[test]
+    fn bids_ranked_by_value_then_score() {
+        let a = relay("http://a.test");
+        let b = relay("http://b.test");
+        let c = relay("http://c.test");
+        b.record_failed_request(Duration::ZERO);
+
+        let mut bids = vec![(a.clone(), 10), (b.clone(), 20), (c.clone(), 20)];
+        rank_bids(&mut bids, |value| Uint256::from(*value));
+
+        let ranked: Vec<_> = bids.iter().map(|(relay, _)| relay.name()).collect();
+        assert_eq!(ranked, vec![c.name(), b.name(), a.name()]);
+    }
+
+    #[test]
+    fn best_relay_prefers_score_then_order() {
+        let a = relay("http://a.test");
+        let b = relay("http://b.test");
+        let relays = Relays::new(vec![a.clone(), b.clone()]);
+        assert_eq!(relays.best().unwrap().name(), a.name());
+
+        a.record_failed_reveal(Duration::ZERO);
+        assert_eq!(relays.best().unwrap().name(), b.name());
+        assert_eq!(relays.available().len(), 2);
+    }
+}
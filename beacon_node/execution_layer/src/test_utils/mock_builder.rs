@@ -5,7 +5,7 @@ use eth2::{BeaconNodeHttpClient, Timeouts, CONSENSUS_VERSION_HEADER};
 use fork_choice::ForkchoiceUpdateParameters;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::future::Future;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
@@ -214,6 +214,9 @@ pub struct MockBuilder<E: EthSpec> {
     builder_sk: SecretKey,
     operations: Arc<RwLock<Vec<Operation>>>,
     invalidate_signatures: Arc<RwLock<bool>>,
+    bid_value: Arc<RwLock<Uint256>>,
+    censored_validators: Arc<RwLock<HashSet<PublicKeyBytes>>>,
+    withhold_payloads: Arc<RwLock<bool>>,
 }
 
 impl<E: EthSpec> MockBuilder<E> {
@@ -264,6 +267,11 @@ impl<E: EthSpec> MockBuilder<E> {
             builder_sk: sk,
             operations: Arc::new(RwLock::new(vec![])),
             invalidate_signatures: Arc::new(RwLock::new(false)),
+            bid_value: Arc::new(RwLock::new(Uint256::from(
+                DEFAULT_BUILDER_PAYLOAD_VALUE_WEI,
+            ))),
+            censored_validators: Arc::new(RwLock::new(HashSet::new())),
+            withhold_payloads: Arc::new(RwLock::new(false)),
         }
     }
 
@@ -281,6 +289,25 @@ impl<E: EthSpec> MockBuilder<E> {
         *self.invalidate_signatures.write() = false;
     }
 
+    /// Set the value of all future bids. A `Value` operation takes precedence for a single bid.
+    pub fn set_bid_value(&self, value: Uint256) {
+        *self.bid_value.write() = value;
+    }
+
+    /// Never return a bid to the proposer with `pubkey`, as if all of its blocks were censored.
+    pub fn censor_validator(&self, pubkey: PublicKeyBytes) {
+        self.censored_validators.write().insert(pubkey);
+    }
+
+    /// Refuse to reveal the payloads of signed blinded blocks, as if the builder had withheld them.
+    pub fn withhold_payloads(&self) {
+        *self.withhold_payloads.write() = true;
+    }
+
+    pub fn reveal_payloads(&self) {
+        *self.withhold_payloads.write() = false;
+    }
+
     fn apply_operations<B: BidStuff<E>>(&self, bid: &mut B) {
         let mut guard = self.operations.write();
         while let Some(op) = guard.pop() {
@@ -332,6 +359,9 @@ pub fn serve<E: EthSpec>(
                 |block: SignedBlindedBeaconBlock<E>,
                  fork_name: ForkName,
                  builder: MockBuilder<E>| async move {
+                    if *builder.withhold_payloads.read() {
+                        return Err(reject("payload withheld"));
+                    }
                     let root = match block {
                         SignedBlindedBeaconBlock::Base(_) | types::SignedBeaconBlock::Altair(_) => {
                             return Err(reject("invalid fork"));
@@ -396,6 +426,16 @@ pub fn serve<E: EthSpec>(
              pubkey: PublicKeyBytes,
              builder: MockBuilder<E>| async move {
                 let fork = builder.spec.fork_name_at_slot::<E>(slot);
+                if builder.censored_validators.read().contains(&pubkey) {
+                    // No bid is available, as if the builder had no blocks for this proposer.
+                    return Ok::<_, Rejection>(
+                        warp::http::Response::builder()
+                            .status(204)
+                            .body(String::new())
+                            .unwrap(),
+                    );
+                }
+                let bid_value = *builder.bid_value.read();
                 let signed_cached_data = builder
                     .val_registration_cache
                     .read()
@@ -559,7 +599,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Deneb => BuilderBid::Deneb(BuilderBidDeneb {
@@ -570,7 +610,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Capella => BuilderBid::Capella(BuilderBidCapella {
@@ -578,7 +618,7 @@ pub fn serve<E: EthSpec>(
                                     .as_capella()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Bellatrix => BuilderBid::Bellatrix(BuilderBidBellatrix {
@@ -586,7 +626,7 @@ pub fn serve<E: EthSpec>(
                                     .as_bellatrix()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Base | ForkName::Altair => {
@@ -611,7 +651,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Deneb => BuilderBid::Deneb(BuilderBidDeneb {
@@ -622,7 +662,7 @@ pub fn serve<E: EthSpec>(
                                 blob_kzg_commitments: maybe_blobs_bundle
                                     .map(|b| b.commitments)
                                     .unwrap_or_default(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Capella => BuilderBid::Capella(BuilderBidCapella {
@@ -630,7 +670,7 @@ pub fn serve<E: EthSpec>(
                                     .as_capella()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Bellatrix => BuilderBid::Bellatrix(BuilderBidBellatrix {
@@ -638,7 +678,7 @@ pub fn serve<E: EthSpec>(
                                     .as_bellatrix()
                                     .map_err(|_| reject("incorrect payload variant"))?
                                     .into(),
-                                value: Uint256::from(DEFAULT_BUILDER_PAYLOAD_VALUE_WEI),
+                                value: bid_value,
                                 pubkey: builder.builder_sk.public_key().compress(),
                             }),
                             ForkName::Base | ForkName::Altair => {
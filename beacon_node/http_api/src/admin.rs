@@ -0,0 +1,83 @@
+//! Helpers for the token-authenticated `/lighthouse/admin` endpoints.
+//!
+//! The admin endpoints are disabled unless a token file is configured. Requests must include the
+//! contents of the file in an `Authorization: Bearer <token>` header.
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use slog::Level;
+use std::path::Path;
+use warp::{Filter, Rejection};
+
+/// Read the admin token from `path`, ignoring surrounding whitespace.
+pub fn read_token(path: &Path) -> Result<String, String> {
+    let token = std::fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read admin token file {}: {e:?}", path.display()))?;
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(format!("Admin token file {} is empty", path.display()));
+    }
+    Ok(token.to_string())
+}
+
+/// A filter which rejects requests without a valid admin token, or all requests if `token` is
+/// `None`.
+pub fn auth_filter(token: Option<String>) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    let expected = token.map(|token| format!("Bearer {token}"));
+    warp::any()
+        .map(move || expected.clone())
+        .and(warp::header::optional::<String>("Authorization"))
+        .and_then(
+            |expected: Option<String>, header: Option<String>| async move {
+                let Some(expected) = expected else {
+                    return Err(warp_utils::reject::custom_not_found(
+                        "the admin API is disabled, see --http-admin-token-file".to_string(),
+                    ));
+                };
+                match header {
+                    Some(header) if tokens_match(&header, &expected) => Ok(()),
+                    Some(_) => Err(warp_utils::reject::invalid_auth(
+                        "incorrect admin token".to_string(),
+                    )),
+                    None => Err(warp_utils::reject::invalid_auth(
+                        "missing Authorization header".to_string(),
+                    )),
+                }
+            },
+        )
+        .untuple_one()
+}
+
+/// Compares the `Authorization` header with the expected value in constant time, so that the token
+/// can't be recovered from the response times.
+fn tokens_match(header: &str, expected: &str) -> bool {
+    ring::constant_time::verify_slices_are_equal(header.as_bytes(), expected.as_bytes()).is_ok()
+}
+
+/// Returns the directory that payload forensic bundles are written to.
+pub fn payload_forensics_dir<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+) -> Result<&Path, Rejection> {
+    chain
+        .config
+        .payload_forensics_dir
+        .as_deref()
+        .ok_or_else(|| {
+            warp_utils::reject::custom_not_found(
+                "payload forensics are disabled, see --disable-payload-forensics".to_string(),
+            )
+        })
+}
+
+/// Parse a log level using the same names as the `--debug-level` flag.
+pub fn parse_log_level(level: &str) -> Result<Level, Rejection> {
+    match level {
+        "info" => Ok(Level::Info),
+        "debug" => Ok(Level::Debug),
+        "trace" => Ok(Level::Trace),
+        "warn" => Ok(Level::Warning),
+        "error" => Ok(Level::Error),
+        "crit" => Ok(Level::Critical),
+        unknown => Err(warp_utils::reject::custom_bad_request(format!(
+            "unknown log level: {unknown}"
+        ))),
+    }
+}
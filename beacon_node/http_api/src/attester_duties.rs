@@ -88,7 +88,7 @@ fn compute_historic_attester_duties<T: BeaconChainTypes>(
         if head.beacon_state.current_epoch() <= request_epoch {
             Some((
                 head.beacon_state_root(),
-                head.beacon_state.clone(),
+                chain.clone_head_state(head),
                 execution_status.is_optimistic_or_invalid(),
             ))
         } else {
@@ -1,10 +1,12 @@
-use crate::{state_id::checkpoint_slot_and_execution_optimistic, ExecutionOptimistic};
+use crate::{query_trace, state_id::checkpoint_slot_and_execution_optimistic, ExecutionOptimistic};
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
 use eth2::types::BlobIndicesQuery;
 use eth2::types::BlockId as CoreBlockId;
+use ssz::Encode;
 use std::fmt;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Instant;
 use types::{
     BlobSidecarList, EthSpec, FixedBytesExtended, Hash256, SignedBeaconBlock,
     SignedBlindedBeaconBlock, Slot,
@@ -147,7 +149,8 @@ impl BlockId {
         ),
         warp::Rejection,
     > {
-        match &self.0 {
+        let started = Instant::now();
+        let result = match &self.0 {
             CoreBlockId::Head => {
                 let (cached_head, execution_status) = chain
                     .canonical_head
@@ -189,7 +192,12 @@ impl BlockId {
                 })?;
                 Ok((block, execution_optimistic, finalized))
             }
+        };
+
+        if result.is_ok() {
+            query_trace::record("load_block", self.trace_source(), None, started);
         }
+        result
     }
 
     /// Return the `SignedBeaconBlock` identified by `self`.
@@ -204,7 +212,8 @@ impl BlockId {
         ),
         warp::Rejection,
     > {
-        match &self.0 {
+        let started = Instant::now();
+        let result = match &self.0 {
             CoreBlockId::Head => {
                 let (cached_head, execution_status) = chain
                     .canonical_head
@@ -255,6 +264,19 @@ impl BlockId {
                             })
                     })
             }
+        };
+
+        if result.is_ok() {
+            query_trace::record("load_block", self.trace_source(), None, started);
+        }
+        result
+    }
+
+    /// The source of the block identified by `self`, for tracing.
+    fn trace_source(&self) -> &'static str {
+        match &self.0 {
+            CoreBlockId::Head => "head",
+            _ => "database",
         }
     }
 
@@ -272,20 +294,11 @@ impl BlockId {
         ),
         warp::Rejection,
     > {
-        let (root, execution_optimistic, finalized) = self.root(chain)?;
-        let block = BlockId::blinded_block_by_root(&root, chain)?.ok_or_else(|| {
-            warp_utils::reject::custom_not_found(format!("beacon block with root {}", root))
-        })?;
-
-        // Error if the block is pre-Deneb and lacks blobs.
-        let blob_kzg_commitments = block.message().body().blob_kzg_commitments().map_err(|_| {
-            warp_utils::reject::custom_bad_request(
-                "block is pre-Deneb and has no blobs".to_string(),
-            )
-        })?;
+        let (root, block, execution_optimistic, finalized) =
+            self.blinded_block_with_blobs(chain)?;
 
         // Return the `BlobSidecarList` identified by `self`.
-        let blob_sidecar_list = if !blob_kzg_commitments.is_empty() {
+        let blob_sidecar_list = if block.num_expected_blobs() > 0 {
             chain
                 .store
                 .get_blobs(&root)
@@ -317,6 +330,72 @@ impl BlockId {
             finalized,
         ))
     }
+
+    /// Return the SSZ bytes of the `BlobSidecarList` identified by `self`, read from the store
+    /// without decoding them.
+    pub fn get_blinded_block_and_blob_list_ssz_bytes<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<
+        (
+            SignedBlindedBeaconBlock<T::EthSpec>,
+            Vec<u8>,
+            ExecutionOptimistic,
+            Finalized,
+        ),
+        warp::Rejection,
+    > {
+        let (root, block, execution_optimistic, finalized) =
+            self.blinded_block_with_blobs(chain)?;
+
+        let blob_sidecar_list_bytes = if block.num_expected_blobs() > 0 {
+            chain
+                .store
+                .get_blobs_ssz_bytes(&root)
+                .map_err(|e| warp_utils::reject::beacon_chain_error(e.into()))?
+                .ok_or_else(|| {
+                    warp_utils::reject::custom_not_found(format!(
+                        "no blobs stored for block {root}"
+                    ))
+                })?
+        } else {
+            BlobSidecarList::<T::EthSpec>::default().as_ssz_bytes()
+        };
+
+        Ok((
+            block,
+            blob_sidecar_list_bytes,
+            execution_optimistic,
+            finalized,
+        ))
+    }
+
+    /// Return the blinded block identified by `self`, erroring if it is pre-Deneb and lacks blobs.
+    fn blinded_block_with_blobs<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<
+        (
+            Hash256,
+            SignedBlindedBeaconBlock<T::EthSpec>,
+            ExecutionOptimistic,
+            Finalized,
+        ),
+        warp::Rejection,
+    > {
+        let (root, execution_optimistic, finalized) = self.root(chain)?;
+        let block = BlockId::blinded_block_by_root(&root, chain)?.ok_or_else(|| {
+            warp_utils::reject::custom_not_found(format!("beacon block with root {}", root))
+        })?;
+
+        if block.message().body().blob_kzg_commitments().is_err() {
+            return Err(warp_utils::reject::custom_bad_request(
+                "block is pre-Deneb and has no blobs".to_string(),
+            ));
+        }
+
+        Ok((root, block, execution_optimistic, finalized))
+    }
 }
 
 impl FromStr for BlockId {
@@ -1,10 +1,14 @@
 use crate::StateId;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::types::ExpectedWithdrawalsWithProof;
 use safe_arith::SafeArith;
 use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::state_advance::partial_state_advance;
 use std::sync::Arc;
-use types::{BeaconState, EthSpec, Slot, Withdrawals};
+use types::state_proof::{
+    NEXT_WITHDRAWAL_INDEX_FIELD_INDEX, NEXT_WITHDRAWAL_VALIDATOR_INDEX_FIELD_INDEX,
+};
+use types::{BeaconState, BeaconStateError, EthSpec, Slot, Withdrawals};
 
 const MAX_EPOCH_LOOKAHEAD: u64 = 2;
 
@@ -41,6 +45,63 @@ pub fn get_next_withdrawals<T: BeaconChainTypes>(
     }
 }
 
+/// Get the withdrawals computed from the specified state, along with Merkle proofs against the
+/// state root of the fields that they are computed from.
+///
+/// The proofs are only meaningful for the specified state, so the state can't be advanced to the
+/// epoch of the proposal slot.
+pub fn get_next_withdrawals_with_proof<T: BeaconChainTypes>(
+    chain: &Arc<BeaconChain<T>>,
+    mut state: BeaconState<T::EthSpec>,
+    proposal_slot: Slot,
+) -> Result<ExpectedWithdrawalsWithProof, warp::Rejection> {
+    get_next_withdrawals_sanity_checks(chain, &state, proposal_slot)?;
+
+    if proposal_slot.epoch(T::EthSpec::slots_per_epoch()) != state.current_epoch() {
+        return Err(warp_utils::reject::custom_bad_request(
+            "withdrawal proofs are only available for proposal slots in the epoch of the state"
+                .to_string(),
+        ));
+    }
+
+    let withdrawals = match get_expected_withdrawals(&state, &chain.spec) {
+        Ok((withdrawals, _)) => withdrawals,
+        Err(e) => {
+            return Err(warp_utils::reject::custom_server_error(format!(
+                "failed to get expected withdrawal: {:?}",
+                e
+            )))
+        }
+    };
+    let validator_indices = withdrawals
+        .iter()
+        .map(|withdrawal| withdrawal.validator_index as usize)
+        .collect::<Vec<_>>();
+
+    let state_root = state.canonical_root().map_err(proof_error)?;
+    Ok(ExpectedWithdrawalsWithProof {
+        state_root,
+        state_slot: state.slot(),
+        next_withdrawal_index: state
+            .compute_field_state_proof(NEXT_WITHDRAWAL_INDEX_FIELD_INDEX)
+            .map_err(proof_error)?,
+        next_withdrawal_validator_index: state
+            .compute_field_state_proof(NEXT_WITHDRAWAL_VALIDATOR_INDEX_FIELD_INDEX)
+            .map_err(proof_error)?,
+        validators: chain
+            .validator_state_proofs(state_root, &state, &validator_indices)
+            .map_err(crate::proof_tree_error)?,
+        balances: state
+            .compute_balance_state_proofs(&validator_indices)
+            .map_err(proof_error)?,
+        withdrawals: withdrawals.to_vec(),
+    })
+}
+
+fn proof_error(e: BeaconStateError) -> warp::Rejection {
+    warp_utils::reject::custom_server_error(format!("failed to compute proof: {:?}", e))
+}
+
 fn get_next_withdrawals_sanity_checks<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
     state: &BeaconState<T::EthSpec>,
@@ -0,0 +1,75 @@
+//! Contains the handler for the `GET lighthouse/committee_assignment/{epoch}/{validator_id}`
+//! endpoint.
+
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::CommitteeAssignmentWithProof;
+use eth2::types::ValidatorId;
+use state_processing::committee_assignment::get_committee_assignment;
+use types::{Epoch, RelativeEpoch};
+
+/// Returns the committee assignment of the validator for `epoch`, with Merkle proofs against the
+/// root of the head state.
+///
+/// See `state_processing::committee_assignment` for what the proofs cover. The tree of the validator
+/// registry is shared with other proof requests against the same state, via the
+/// `ValidatorProofCache`.
+pub fn committee_assignment<T: BeaconChainTypes>(
+    epoch: Epoch,
+    validator_id: &ValidatorId,
+    chain: &BeaconChain<T>,
+) -> Result<CommitteeAssignmentWithProof, warp::reject::Rejection> {
+    let mut state = chain.head_beacon_state_cloned();
+
+    if RelativeEpoch::from_epoch(state.current_epoch(), epoch).is_err() {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "epoch must be the previous, current or next epoch of the head state ({})",
+            state.current_epoch()
+        )));
+    }
+
+    let validator_index = match validator_id {
+        ValidatorId::Index(index) => *index,
+        ValidatorId::PublicKey(pubkey) => state
+            .get_validator_index(pubkey)
+            .map_err(warp_utils::reject::beacon_state_error)?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_bad_request(format!("unknown validator {pubkey}"))
+            })? as u64,
+    };
+    if validator_index as usize >= state.validators().len() {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "unknown validator {validator_index}"
+        )));
+    }
+
+    let state_root = state
+        .canonical_root()
+        .map_err(warp_utils::reject::beacon_state_error)?;
+    let validators_proof_tree = chain
+        .validators_proof_tree(state_root, &state)
+        .map_err(crate::proof_tree_error)?;
+
+    let assignment = get_committee_assignment(
+        &mut state,
+        validator_index,
+        epoch,
+        &validators_proof_tree,
+        &chain.spec,
+    )
+    .map_err(|e| {
+        warp_utils::reject::custom_server_error(format!(
+            "unable to compute committee assignment: {e:?}"
+        ))
+    })?;
+
+    Ok(CommitteeAssignmentWithProof {
+        validator_index: assignment.validator_index,
+        epoch: assignment.epoch,
+        duty: assignment.duty,
+        state_root: assignment.state_root,
+        state_slot: assignment.state_slot,
+        seed: assignment.seed,
+        randao_mix: assignment.randao_mix_proof,
+        validator: assignment.validator_proof,
+    })
+}
@@ -0,0 +1,305 @@
+//! Negotiated compression of HTTP API responses.
+//!
+//! When enabled via `Config::compression_level`, responses are compressed using the encoding the
+//! client most prefers out of `zstd`, `gzip` and `deflate`, as indicated by its `Accept-Encoding`
+//! header. Bodies are compressed as they are streamed, so large streamed responses are never
+//! buffered in full. Compression runs on the blocking thread pool, so that compressing a large
+//! response doesn't stall the async workers. Small responses and server-sent event streams are
+//! left uncompressed.
+use bytes::Bytes;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use futures::{Stream, StreamExt};
+use std::io::{self, Write};
+use warp::http::header::{
+    HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, VARY,
+};
+use warp::http::StatusCode;
+use warp::hyper::body::HttpBody;
+use warp::hyper::Body;
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+/// Responses with a known length smaller than this are not worth compressing.
+pub const MIN_COMPRESSION_SIZE: u64 = 1_024;
+
+/// The highest supported compression level.
+pub const MAX_COMPRESSION_LEVEL: u32 = 9;
+
+/// A content encoding supported by the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Zstd,
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "zstd" => Some(Encoding::Zstd),
+            "gzip" | "x-gzip" => Some(Encoding::Gzip),
+            "deflate" => Some(Encoding::Deflate),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Zstd => "zstd",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+
+    /// The server's preference between encodings the client likes equally, highest first.
+    fn rank(&self) -> u8 {
+        match self {
+            Encoding::Zstd => 2,
+            Encoding::Gzip => 1,
+            Encoding::Deflate => 0,
+        }
+    }
+
+    /// Select the encoding to use from an `Accept-Encoding` header, if any.
+    ///
+    /// Encodings are ordered by their quality value, with ties broken by the server's preference.
+    /// Encodings with a quality of zero are never selected.
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        accept_encoding
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';').map(str::trim);
+                let name = parts.next()?.to_ascii_lowercase();
+                let quality = parts
+                    .find_map(|param| param.strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.parse::<f32>().ok())?;
+                let encoding = Encoding::from_name(&name)?;
+                (quality > 0.0).then_some((encoding, quality))
+            })
+            .max_by(|(a, a_quality), (b, b_quality)| {
+                a_quality.total_cmp(b_quality).then(a.rank().cmp(&b.rank()))
+            })
+            .map(|(encoding, _)| encoding)
+    }
+}
+
+/// An encoder which compresses a body incrementally.
+enum Encoder {
+    Zstd(zstd::stream::write::Encoder<'static, Vec<u8>>),
+    Gzip(GzEncoder<Vec<u8>>),
+    /// The `deflate` content encoding is the zlib format (RFC 1950), not a raw deflate stream.
+    Deflate(ZlibEncoder<Vec<u8>>),
+}
+
+impl Encoder {
+    fn new(encoding: Encoding, level: u32) -> io::Result<Self> {
+        Ok(match encoding {
+            Encoding::Zstd => {
+                Encoder::Zstd(zstd::stream::write::Encoder::new(Vec::new(), level as i32)?)
+            }
+            Encoding::Gzip => {
+                Encoder::Gzip(GzEncoder::new(Vec::new(), flate2::Compression::new(level)))
+            }
+            Encoding::Deflate => Encoder::Deflate(ZlibEncoder::new(
+                Vec::new(),
+                flate2::Compression::new(level),
+            )),
+        })
+    }
+
+    /// Compress `chunk`, returning any compressed output which is ready to be sent.
+    fn write(&mut self, chunk: &[u8]) -> io::Result<Bytes> {
+        let output = match self {
+            Encoder::Zstd(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.get_mut()
+            }
+            Encoder::Gzip(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.get_mut()
+            }
+            Encoder::Deflate(encoder) => {
+                encoder.write_all(chunk)?;
+                encoder.get_mut()
+            }
+        };
+        Ok(Bytes::from(std::mem::take(output)))
+    }
+
+    /// Complete the compressed stream, returning the remaining output.
+    fn finish(self) -> io::Result<Bytes> {
+        let output = match self {
+            Encoder::Zstd(encoder) => encoder.finish()?,
+            Encoder::Gzip(encoder) => encoder.finish()?,
+            Encoder::Deflate(encoder) => encoder.finish()?,
+        };
+        Ok(Bytes::from(output))
+    }
+}
+
+/// Compress `chunk` with `encoder` on the blocking thread pool, returning the encoder for reuse.
+async fn write_blocking(mut encoder: Encoder, chunk: Bytes) -> io::Result<(Encoder, Bytes)> {
+    tokio::task::spawn_blocking(move || {
+        let output = encoder.write(&chunk)?;
+        Ok((encoder, output))
+    })
+    .await
+    .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
+
+/// Complete the compressed stream of `encoder` on the blocking thread pool.
+async fn finish_blocking(encoder: Encoder) -> io::Result<Bytes> {
+    tokio::task::spawn_blocking(move || encoder.finish())
+        .await
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?
+}
+
+/// Compress a stream of chunks with `encoder`, skipping empty outputs.
+fn compress_stream(
+    body: Body,
+    encoder: Encoder,
+) -> impl Stream<Item = Result<Bytes, io::Error>> + Send {
+    futures::stream::unfold(Some((body, encoder)), |state| async move {
+        let (mut body, mut encoder) = state?;
+        loop {
+            match body.next().await {
+                Some(Ok(chunk)) => match write_blocking(encoder, chunk).await {
+                    Ok((next_encoder, output)) if output.is_empty() => encoder = next_encoder,
+                    Ok((encoder, output)) => return Some((Ok(output), Some((body, encoder)))),
+                    Err(e) => return Some((Err(e), None)),
+                },
+                Some(Err(e)) => return Some((Err(io::Error::new(io::ErrorKind::Other, e)), None)),
+                None => return Some((finish_blocking(encoder).await, None)),
+            }
+        }
+    })
+}
+
+/// Returns `true` if `response` should be sent uncompressed regardless of the client's preference.
+fn is_exempt(response: &Response) -> bool {
+    let headers = response.headers();
+    let is_event_stream = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+    let is_small = response
+        .body()
+        .size_hint()
+        .exact()
+        .is_some_and(|size| size < MIN_COMPRESSION_SIZE);
+
+    headers.contains_key(CONTENT_ENCODING)
+        || is_event_stream
+        || is_small
+        || response.status() == StatusCode::NO_CONTENT
+        || response.status() == StatusCode::NOT_MODIFIED
+}
+
+/// Compress `reply` with the given encoding and level, unless it is exempt from compression.
+pub fn compress(reply: impl Reply, compression: Option<(Encoding, u32)>) -> Response {
+    let response = reply.into_response();
+    let Some((encoding, level)) = compression else {
+        return response;
+    };
+    if is_exempt(&response) {
+        return response;
+    }
+    let Ok(encoder) = Encoder::new(encoding, level) else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    parts.headers.remove(CONTENT_LENGTH);
+    parts.headers.insert(
+        CONTENT_ENCODING,
+        HeaderValue::from_static(encoding.as_str()),
+    );
+    parts
+        .headers
+        .append(VARY, HeaderValue::from_static("accept-encoding"));
+
+    Response::from_parts(parts, Body::wrap_stream(compress_stream(body, encoder)))
+}
+
+/// A filter which extracts the encoding and level to compress the response with, if compression
+/// is enabled and the client supports one of the available encodings.
+pub fn compression_filter(
+    level: Option<u32>,
+) -> impl Filter<Extract = (Option<(Encoding, u32)>,), Error = Rejection> + Clone {
+    warp::header::headers_cloned().map(move |headers: HeaderMap| {
+        let level = level?;
+        let encoding = headers
+            .get(ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .and_then(Encoding::negotiate)?;
+        Some((encoding, level))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn negotiate_encoding() {
+        assert_eq!(
+            Encoding::negotiate("gzip, deflate, br"),
+            Some(Encoding::Gzip)
+        );
+        assert_eq!(
+            Encoding::negotiate("gzip, deflate, zstd"),
+            Some(Encoding::Zstd)
+        );
+        assert_eq!(
+            Encoding::negotiate("zstd;q=0.5, deflate;q=0.8"),
+            Some(Encoding::Deflate)
+        );
+        assert_eq!(Encoding::negotiate("gzip;q=0, identity"), None);
+        assert_eq!(Encoding::negotiate("br"), None);
+        assert_eq!(Encoding::negotiate(""), None);
+    }
+
+    #[tokio::test]
+    async fn compress_round_trip() {
+        let json = serde_json::to_vec(&vec!["validator"; 1_000]).unwrap();
+
+        for encoding in [Encoding::Zstd, Encoding::Gzip, Encoding::Deflate] {
+            let reply = Response::new(Body::from(json.clone()));
+            let response = compress(reply, Some((encoding, 6)));
+            assert_eq!(
+                response.headers().get(CONTENT_ENCODING).unwrap(),
+                encoding.as_str()
+            );
+
+            let compressed = warp::hyper::body::to_bytes(response.into_body())
+                .await
+                .unwrap();
+            assert!(compressed.len() < json.len());
+
+            let mut decompressed = vec![];
+            match encoding {
+                Encoding::Zstd => {
+                    decompressed = zstd::stream::decode_all(compressed.as_ref()).unwrap();
+                }
+                Encoding::Gzip => {
+                    flate2::read::GzDecoder::new(compressed.as_ref())
+                        .read_to_end(&mut decompressed)
+                        .unwrap();
+                }
+                Encoding::Deflate => {
+                    flate2::read::ZlibDecoder::new(compressed.as_ref())
+                        .read_to_end(&mut decompressed)
+                        .unwrap();
+                }
+            }
+            assert_eq!(decompressed, json);
+        }
+    }
+
+    #[test]
+    fn small_responses_are_not_compressed() {
+        let response = compress(Response::new(Body::from("{}")), Some((Encoding::Gzip, 6)));
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+    }
+}
@@ -0,0 +1,51 @@
+//! Contains the handler for the `GET lighthouse/validator/dry_run_block/{slot}` endpoint.
+
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{DryRunBlock, DryRunBlockQuery};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use types::{EthSpec, Slot};
+use warp_utils::reject::{block_production_error, custom_bad_request, custom_server_error};
+
+/// Produces a block at `slot` without signing or publishing it, returning the block along with
+/// the time taken by each stage of production.
+pub async fn dry_run_block<T: BeaconChainTypes>(
+    slot: Slot,
+    query: DryRunBlockQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<DryRunBlock<T::EthSpec>, warp::Rejection> {
+    let current_slot = chain
+        .slot_clock
+        .now_or_genesis()
+        .ok_or_else(|| custom_server_error("unable to read slot clock".to_string()))?;
+    let max_slot = current_slot + T::EthSpec::slots_per_epoch();
+    if slot > max_slot {
+        return Err(custom_bad_request(format!(
+            "slot {slot} is more than one epoch after the current slot {current_slot}"
+        )));
+    }
+    let head_slot = chain.canonical_head.cached_head().head_slot();
+    if slot <= head_slot {
+        return Err(custom_bad_request(format!(
+            "slot {slot} is not after the head slot {head_slot}"
+        )));
+    }
+
+    let dry_run = chain
+        .produce_block_dry_run(slot, !query.skip_payload)
+        .await
+        .map_err(block_production_error)?;
+
+    let production = dry_run
+        .timings
+        .to_sse_event(dry_run.block.slot(), dry_run.block.proposer_index());
+
+    Ok(DryRunBlock {
+        block: dry_run.block,
+        payload_fetched: dry_run.payload_fetched,
+        consensus_block_value: dry_run.consensus_block_value,
+        state_load_ms: dry_run.state_load.as_millis() as u64,
+        total_ms: dry_run.state_load.as_millis() as u64 + production.total_ms,
+        stages: production.stages,
+    })
+}
@@ -5,6 +5,7 @@
 //! There are also some additional, non-standard endpoints behind the `/lighthouse/` path which are
 //! used for development.
 
+mod admin;
 mod attestation_performance;
 mod attester_duties;
 mod block_id;
@@ -12,13 +13,20 @@ mod block_packing_efficiency;
 mod block_rewards;
 mod build_block_contents;
 mod builder_states;
+mod committee_assignment;
+mod compression;
 mod database;
+mod dry_run_block;
 mod light_client;
+mod liveness;
 mod metrics;
+mod orphans;
 mod produce_block;
 mod proposer_duties;
 mod publish_attestations;
 mod publish_blocks;
+mod query_trace;
+mod rate_limit;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -27,7 +35,9 @@ mod task_spawner;
 pub mod test_utils;
 mod ui;
 mod validator;
+mod validator_duties;
 mod validator_inclusion;
+mod validator_projection;
 mod validators;
 mod version;
 
@@ -35,14 +45,22 @@ use crate::light_client::{get_light_client_bootstrap, get_light_client_updates};
 use crate::produce_block::{produce_blinded_block_v2, produce_block_v2, produce_block_v3};
 use crate::version::fork_versioned_response;
 use beacon_chain::{
-    attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
-    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, WhenSlotSkipped,
+    attestation_verification::VerifiedAttestation,
+    events::{ReplayableEvent, REPLAYABLE_TOPICS},
+    observed_operations::ObservationOutcome,
+    payload_forensics,
+    validator_monitor::timestamp_now,
+    weak_subjectivity::weak_subjectivity_status,
+    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
+    WhenSlotSkipped,
+};
+use beacon_processor::{
+    work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend, QueueMonitor, QueueStatus,
 };
-use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
 pub use block_id::BlockId;
-use builder_states::get_next_withdrawals;
+use builder_states::{get_next_withdrawals, get_next_withdrawals_with_proof};
 use bytes::Bytes;
+pub use compression::MAX_COMPRESSION_LEVEL;
 use directory::DEFAULT_ROOT_DIR;
 use eth2::types::{
     self as api_types, BroadcastValidation, EndpointVersion, ForkChoice, ForkChoiceNode,
@@ -50,20 +68,24 @@ use eth2::types::{
     ValidatorStatus, ValidatorsRequestBody,
 };
 use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use lighthouse_network::{
+    types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage, ScoreState,
+};
 use lighthouse_version::version_with_platform;
-use logging::SSELoggingComponents;
+use logging::{RuntimeLogLevel, SSELoggingComponents};
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
 use operation_pool::ReceivedPreCapella;
 use parking_lot::RwLock;
 pub use publish_blocks::{
     publish_blinded_block, publish_block, reconstruct_block, ProvenancedBlock,
 };
+use rate_limit::{RequestLimiter, RequestPermit};
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
 pub use state_id::StateId;
+use std::convert::Infallible;
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
@@ -78,7 +100,7 @@ use tokio::sync::{
 };
 use tokio_stream::{
     wrappers::{errors::BroadcastStreamRecvError, BroadcastStream},
-    StreamExt,
+    Stream, StreamExt,
 };
 use types::{
     fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
@@ -92,7 +114,7 @@ use validator::pubkey_to_validator_index;
 use version::{
     add_consensus_version_header, add_ssz_content_type_header,
     execution_optimistic_finalized_fork_versioned_response, inconsistent_fork_rejection,
-    unsupported_version_rejection, V1, V2, V3,
+    ssz_response, unsupported_version_rejection, V1, V2, V3,
 };
 use warp::http::StatusCode;
 use warp::hyper::Body;
@@ -133,8 +155,11 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub beacon_processor_send: Option<BeaconProcessorSend<T::EthSpec>>,
     pub beacon_processor_reprocess_send: Option<Sender<ReprocessQueueMessage>>,
+    pub beacon_processor_queue_monitor: Option<QueueMonitor>,
     pub eth1_service: Option<eth1::Service>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    /// The level of the terminal logs, which can be changed via the admin API.
+    pub log_level: Option<RuntimeLogLevel>,
     pub log: Logger,
 }
 
@@ -148,6 +173,27 @@ pub struct Config {
     pub tls_config: Option<TlsConfig>,
     pub data_dir: PathBuf,
     pub sse_capacity_multiplier: usize,
+    /// The number of seconds for which head, finalized checkpoint, reorg and blob sidecar events
+    /// are retained for replay to reconnecting clients. Zero (the default) disables replay.
+    pub sse_replay_window_secs: u64,
+    /// The number of requests per second permitted from each client IP address, excluding
+    /// validator duty requests.
+    pub rate_limit: Option<u32>,
+    /// The number of requests a client may burst above `rate_limit`, defaults to `rate_limit`.
+    pub rate_limit_burst: Option<u32>,
+    /// The maximum number of state queries processed concurrently.
+    pub max_concurrent_state_requests: Option<usize>,
+    /// The maximum number of other requests (excluding state queries and validator duty
+    /// requests) processed concurrently.
+    pub max_concurrent_requests: Option<usize>,
+    /// A file containing the token required by the `/lighthouse/admin` endpoints. The admin
+    /// endpoints are disabled if this is `None`.
+    pub admin_token_file: Option<PathBuf>,
+    /// Compress responses for clients which support it, at this level. Compression is disabled if
+    /// this is `None`.
+    pub compression_level: Option<u32>,
+    /// The priority advertised to the failover peer, if the node is one of a failover pair.
+    pub failover_priority: Option<u64>,
     pub enable_beacon_processor: bool,
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
@@ -165,6 +211,14 @@ impl Default for Config {
             tls_config: None,
             data_dir: PathBuf::from(DEFAULT_ROOT_DIR),
             sse_capacity_multiplier: 1,
+            sse_replay_window_secs: 0,
+            rate_limit: None,
+            rate_limit_burst: None,
+            max_concurrent_state_requests: None,
+            max_concurrent_requests: None,
+            admin_token_file: None,
+            compression_level: None,
+            failover_priority: None,
             enable_beacon_processor: true,
             duplicate_block_status_code: StatusCode::ACCEPTED,
             enable_light_client_server: false,
@@ -330,7 +384,11 @@ pub fn serve<T: BeaconChainTypes>(
     let cors_builder = {
         let builder = warp::cors()
             .allow_methods(vec!["GET", "POST"])
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec![
+                "Content-Type",
+                "Authorization",
+                query_trace::TRACE_HEADER,
+            ]);
 
         warp_utils::cors::set_builder_origins(
             builder,
@@ -408,7 +466,7 @@ pub fn serve<T: BeaconChainTypes>(
         .network_senders
         .as_ref()
         .map(|senders| senders.network_send());
-    let network_tx_filter =
+    let admin_network_tx_filter =
         warp::any()
             .map(move || network_tx.clone())
             .and_then(|network_tx| async move {
@@ -420,11 +478,35 @@ pub fn serve<T: BeaconChainTypes>(
                 }
             });
 
+    // Create a `warp` filter that rejects requests while the node is on standby for its failover
+    // peer, so that validator clients publish via the active node instead.
+    let not_standby_filter = network_globals
+        .clone()
+        .and_then(
+            |network_globals: Arc<NetworkGlobals<T::EthSpec>>| async move {
+                if network_globals.is_standby() {
+                    Err(warp_utils::reject::standby(
+                        "use the active node of the failover pair".to_string(),
+                    ))
+                } else {
+                    Ok(())
+                }
+            },
+        )
+        .untuple_one();
+    let network_tx_filter = not_standby_filter
+        .clone()
+        .and(admin_network_tx_filter.clone());
+
     // Create a `warp` filter that provides access to the network attestation subscription channel.
     let validator_subscriptions_tx = ctx
         .network_senders
         .as_ref()
         .map(|senders| senders.validator_subscription_send());
+    let optional_validator_subscription_tx_filter = {
+        let validator_subscriptions_tx = validator_subscriptions_tx.clone();
+        warp::any().map(move || validator_subscriptions_tx.clone())
+    };
     let validator_subscription_tx_filter = warp::any()
         .map(move || validator_subscriptions_tx.clone())
         .and_then(|validator_subscriptions_tx| async move {
@@ -489,6 +571,28 @@ pub fn serve<T: BeaconChainTypes>(
                 },
             );
 
+    // Create a `warp` filter that rejects validator duties in light mode, and whilst syncing.
+    //
+    // A node in light mode relies on a trusted node for payload validity, so it must never be
+    // used to sign messages.
+    let validator_duties_filter = chain_filter
+        .clone()
+        .map(|chain: Arc<BeaconChain<T>>| {
+            if chain.light_mode.is_some() {
+                Err(warp_utils::reject::custom_bad_request(
+                    "validator duties are disabled in light mode".to_string(),
+                ))
+            } else {
+                Ok(())
+            }
+        })
+        .and(not_while_syncing_filter.clone())
+        .map(
+            |light_mode: Result<(), Rejection>, not_synced: Result<(), Rejection>| {
+                light_mode.and(not_synced)
+            },
+        );
+
     // Create a `warp` filter that provides access to the logger.
     let inner_ctx = ctx.clone();
     let log_filter = warp::any().map(move || inner_ctx.log.clone());
@@ -532,14 +636,33 @@ pub fn serve<T: BeaconChainTypes>(
         .beacon_processor_send
         .clone()
         .filter(|_| config.enable_beacon_processor);
-    let task_spawner_filter =
-        warp::any().map(move || TaskSpawner::new(beacon_processor_send.clone()));
+    let task_spawner_filter = query_trace::trace_filter()
+        .map(move |trace| TaskSpawner::new(beacon_processor_send.clone()).with_trace(trace));
     let beacon_processor_reprocess_send = ctx
         .beacon_processor_reprocess_send
         .clone()
         .filter(|_| config.enable_beacon_processor);
     let reprocess_send_filter = warp::any().map(move || beacon_processor_reprocess_send.clone());
 
+    // Create a `warp` filter that provides access to the beacon processor queues.
+    let beacon_processor_queue_monitor = ctx.beacon_processor_queue_monitor.clone();
+    let queue_monitor_filter = warp::any()
+        .map(move || beacon_processor_queue_monitor.clone())
+        .and_then(|queue_monitor| async move {
+            match queue_monitor {
+                Some(queue_monitor) => Ok(queue_monitor),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "The beacon processor is not started.".to_string(),
+                )),
+            }
+        });
+
+    // Create a `warp` filter that provides access to the cached validator inclusion summaries.
+    let validator_inclusion_cache =
+        Arc::new(validator_inclusion::ValidatorInclusionCache::<T::EthSpec>::default());
+    let validator_inclusion_cache_filter =
+        warp::any().map(move || validator_inclusion_cache.clone());
+
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
 
     /*
@@ -588,18 +711,27 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("root"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let (root, execution_optimistic, finalized) = state_id.root(&chain)?;
-                    Ok(api_types::GenericResponse::from(api_types::RootData::from(
-                        root,
-                    )))
-                    .map(|resp| {
-                        resp.add_execution_optimistic_finalized(execution_optimistic, finalized)
-                    })
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(root.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
+                        _ => Ok(warp::reply::json(
+                            &api_types::GenericResponse::from(api_types::RootData::from(root))
+                                .add_execution_optimistic_finalized(
+                                    execution_optimistic,
+                                    finalized,
+                                ),
+                        )
+                        .into_response()),
+                    }
                 })
             },
         );
@@ -609,18 +741,28 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("fork"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let (fork, execution_optimistic, finalized) =
                         state_id.fork_and_execution_optimistic_and_finalized(&chain)?;
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        data: fork,
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                    })
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(fork.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
+                        _ => Ok(warp::reply::json(
+                            &api_types::ExecutionOptimisticFinalizedResponse {
+                                data: fork,
+                                execution_optimistic: Some(execution_optimistic),
+                                finalized: Some(finalized),
+                            },
+                        )
+                        .into_response()),
+                    }
                 })
             },
         );
@@ -733,6 +875,32 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET v2/beacon/states/{state_id}/validators?id,status,page_token,limit
+    let get_beacon_state_validators_v2 = eth_v2
+        .and(warp::path("beacon"))
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid state ID".to_string(),
+            ))
+        }))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(warp::path("validators"))
+        .and(warp::path::end())
+        .and(multi_key_query::<api_types::ValidatorsPageQuery>())
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             query_res: Result<api_types::ValidatorsPageQuery, warp::Rejection>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let query = query_res?;
+                    crate::validators::get_beacon_state_validators_page(state_id, chain, query)
+                })
+            },
+        );
+
     // POST beacon/states/{state_id}/validators
     let post_beacon_state_validators = beacon_states_path
         .clone()
@@ -1093,12 +1261,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("randao"))
         .and(warp::query::<api_types::RandaoQuery>())
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |state_id: StateId,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             query: api_types::RandaoQuery| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             query: api_types::RandaoQuery,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let (randao, execution_optimistic, finalized) = state_id
                         .map_state_and_execution_optimistic_and_finalized(
                             &chain,
@@ -1113,10 +1283,19 @@ pub fn serve<T: BeaconChainTypes>(
                             },
                         )?;
 
-                    Ok(
-                        api_types::GenericResponse::from(api_types::RandaoMix { randao })
-                            .add_execution_optimistic_finalized(execution_optimistic, finalized),
-                    )
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(randao.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
+                        _ => Ok(warp::reply::json(
+                            &api_types::GenericResponse::from(api_types::RandaoMix { randao })
+                                .add_execution_optimistic_finalized(
+                                    execution_optimistic,
+                                    finalized,
+                                ),
+                        )
+                        .into_response()),
+                    }
                 })
             },
         );
@@ -1235,17 +1414,27 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |block_id: BlockId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
                     let (root, execution_optimistic, finalized) = block_id.root(&chain)?;
                     // Ignore the second `execution_optimistic` since the first one has more
                     // information about the original request.
                     let (block, _execution_optimistic, _finalized) =
                         BlockId::from_root(root).blinded_block(&chain)?;
 
+                    if let Some(api_types::Accept::Ssz) = accept_header {
+                        return ssz_response(
+                            block.signed_block_header().as_ssz_bytes(),
+                            execution_optimistic,
+                            finalized,
+                        );
+                    }
+
                     let canonical = chain
                         .block_root_at_slot(block.slot(), WhenSlotSkipped::None)
                         .map_err(warp_utils::reject::beacon_chain_error)?
@@ -1260,11 +1449,14 @@ pub fn serve<T: BeaconChainTypes>(
                         },
                     };
 
-                    Ok(api_types::ExecutionOptimisticFinalizedResponse {
-                        execution_optimistic: Some(execution_optimistic),
-                        finalized: Some(finalized),
-                        data,
-                    })
+                    Ok(
+                        warp::reply::json(&api_types::ExecutionOptimisticFinalizedResponse {
+                            execution_optimistic: Some(execution_optimistic),
+                            finalized: Some(finalized),
+                            data,
+                        })
+                        .into_response(),
+                    )
                 })
             },
         );
@@ -1623,16 +1815,9 @@ pub fn serve<T: BeaconChainTypes>(
                         .map_err(inconsistent_fork_rejection)?;
 
                     match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .body(block.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(block.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
                         _ => execution_optimistic_finalized_fork_versioned_response(
                             endpoint_version,
                             fork_name,
@@ -1652,10 +1837,12 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("root"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |block_id: BlockId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
                 // Prioritise requests for the head block root, as it is used by some VCs (including
                 // the Lighthouse VC) to create sync committee messages.
                 let priority = if let BlockId(eth2::types::BlockId::Head) = block_id {
@@ -1663,12 +1850,20 @@ pub fn serve<T: BeaconChainTypes>(
                 } else {
                     Priority::P1
                 };
-                task_spawner.blocking_json_task(priority, move || {
+                task_spawner.blocking_response_task(priority, move || {
                     let (block_root, execution_optimistic, finalized) = block_id.root(&chain)?;
-                    Ok(
-                        api_types::GenericResponse::from(api_types::RootData::from(block_root))
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(block_root.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
+                        _ => Ok(warp::reply::json(
+                            &api_types::GenericResponse::from(api_types::RootData::from(
+                                block_root,
+                            ))
                             .add_execution_optimistic_finalized(execution_optimistic, finalized),
-                    )
+                        )
+                        .into_response()),
+                    }
                 })
             },
         );
@@ -1678,11 +1873,13 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attestations"))
         .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
         .then(
             |endpoint_version: EndpointVersion,
              block_id: BlockId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
+             chain: Arc<BeaconChain<T>>,
+             accept_header: Option<api_types::Accept>| {
                 task_spawner.blocking_response_task(Priority::P1, move || {
                     let (block, execution_optimistic, finalized) =
                         block_id.blinded_block(&chain)?;
@@ -1695,6 +1892,10 @@ pub fn serve<T: BeaconChainTypes>(
                         .attestations()
                         .map(|att| att.clone_as_attestation())
                         .collect::<Vec<_>>();
+                    if let Some(api_types::Accept::Ssz) = accept_header {
+                        return ssz_response(atts.as_ssz_bytes(), execution_optimistic, finalized)
+                            .map(|res| add_consensus_version_header(res, fork_name));
+                    }
                     let res = execution_optimistic_finalized_fork_versioned_response(
                         endpoint_version,
                         fork_name,
@@ -1732,16 +1933,9 @@ pub fn serve<T: BeaconChainTypes>(
                         .map_err(inconsistent_fork_rejection)?;
 
                     match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .body(block.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
+                        Some(api_types::Accept::Ssz) => {
+                            ssz_response(block.as_ssz_bytes(), execution_optimistic, finalized)
+                        }
                         _ => {
                             // Post as a V2 endpoint so we return the fork version.
                             execution_optimistic_finalized_fork_versioned_response(
@@ -1781,6 +1975,24 @@ pub fn serve<T: BeaconChainTypes>(
              accept_header: Option<api_types::Accept>| {
                 task_spawner.blocking_response_task(Priority::P1, move || {
                     let indices = indices_res?;
+
+                    // Serve the full list straight from the store, without decoding it.
+                    if indices.indices.is_none()
+                        && matches!(accept_header, Some(api_types::Accept::Ssz))
+                    {
+                        let (block, blob_sidecar_list_bytes, execution_optimistic, finalized) =
+                            block_id.get_blinded_block_and_blob_list_ssz_bytes(&chain)?;
+                        let fork_name = block
+                            .fork_name(&chain.spec)
+                            .map_err(inconsistent_fork_rejection)?;
+                        return ssz_response(
+                            blob_sidecar_list_bytes,
+                            execution_optimistic,
+                            finalized,
+                        )
+                        .map(|resp| add_consensus_version_header(resp, fork_name));
+                    }
+
                     let (block, blob_sidecar_list_filtered, execution_optimistic, finalized) =
                         block_id.get_blinded_block_and_blob_list_filtered(indices, &chain)?;
                     let fork_name = block
@@ -1788,16 +2000,11 @@ pub fn serve<T: BeaconChainTypes>(
                         .map_err(inconsistent_fork_rejection)?;
 
                     match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .body(blob_sidecar_list_filtered.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
+                        Some(api_types::Accept::Ssz) => ssz_response(
+                            blob_sidecar_list_filtered.as_ssz_bytes(),
+                            execution_optimistic,
+                            finalized,
+                        ),
                         _ => {
                             // Post as a V2 endpoint so we return the fork version.
                             let res = execution_optimistic_finalized_fork_versioned_response(
@@ -2357,20 +2564,34 @@ pub fn serve<T: BeaconChainTypes>(
                 task_spawner.blocking_response_task(Priority::P1, move || {
                     let (state, execution_optimistic, finalized) = state_id.state(&chain)?;
                     let proposal_slot = query.proposal_slot.unwrap_or(state.slot() + 1);
+
+                    if query.proof == Some(true) {
+                        if matches!(accept_header, Some(api_types::Accept::Ssz)) {
+                            return Err(warp_utils::reject::custom_bad_request(
+                                "withdrawal proofs are only available as JSON".to_string(),
+                            ));
+                        }
+                        let withdrawals_with_proof =
+                            get_next_withdrawals_with_proof::<T>(&chain, state, proposal_slot)?;
+                        return Ok(warp::reply::json(
+                            &api_types::ExecutionOptimisticFinalizedResponse {
+                                data: withdrawals_with_proof,
+                                execution_optimistic: Some(execution_optimistic),
+                                finalized: Some(finalized),
+                            },
+                        )
+                        .into_response());
+                    }
+
                     let withdrawals =
                         get_next_withdrawals::<T>(&chain, state, state_id, proposal_slot)?;
 
                     match accept_header {
-                        Some(api_types::Accept::Ssz) => Response::builder()
-                            .status(200)
-                            .body(withdrawals.as_ssz_bytes().into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            }),
+                        Some(api_types::Accept::Ssz) => ssz_response(
+                            withdrawals.as_ssz_bytes(),
+                            execution_optimistic,
+                            finalized,
+                        ),
                         _ => Ok(warp::reply::json(
                             &api_types::ExecutionOptimisticFinalizedResponse {
                                 data: withdrawals,
@@ -2703,11 +2924,8 @@ pub fn serve<T: BeaconChainTypes>(
              log: Logger| {
                 task_spawner.blocking_response_task(Priority::P1, move || match accept_header {
                     Some(api_types::Accept::Ssz) => {
-                        // We can ignore the optimistic status for the "fork" since it's a
-                        // specification constant that doesn't change across competing heads of the
-                        // beacon chain.
                         let t = std::time::Instant::now();
-                        let (state, _execution_optimistic, _finalized) = state_id.state(&chain)?;
+                        let (state, execution_optimistic, finalized) = state_id.state(&chain)?;
                         let fork_name = state
                             .fork_name(&chain.spec)
                             .map_err(inconsistent_fork_rejection)?;
@@ -2721,19 +2939,8 @@ pub fn serve<T: BeaconChainTypes>(
                             "target_slot" => state.slot()
                         );
 
-                        Response::builder()
-                            .status(200)
-                            .body(response_bytes.into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map(|resp: warp::reply::Response| {
-                                add_consensus_version_header(resp, fork_name)
-                            })
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
-                                ))
-                            })
+                        ssz_response(response_bytes, execution_optimistic, finalized)
+                            .map(|resp| add_consensus_version_header(resp, fork_name))
                     }
                     _ => state_id.map_state_and_execution_optimistic_and_finalized(
                         &chain,
@@ -2890,6 +3097,9 @@ pub fn serve<T: BeaconChainTypes>(
                                 )
                             ),
                         },
+                        reachability: api_types::Reachability::from_network_reachability(
+                            network_globals.reachability(),
+                        ),
                     }))
                 })
             },
@@ -3195,7 +3405,7 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(log_filter.clone())
@@ -3223,7 +3433,7 @@ pub fn serve<T: BeaconChainTypes>(
         }))
         .and(warp::path::end())
         .and(warp::header::optional::<api_types::Accept>("accept"))
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(warp::query::<api_types::ValidatorBlocksQuery>())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -3265,7 +3475,7 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(warp::query::<api_types::ValidatorBlocksQuery>())
         .and(warp::header::optional::<api_types::Accept>("accept"))
         .and(task_spawner_filter.clone())
@@ -3291,7 +3501,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("attestation_data"))
         .and(warp::path::end())
         .and(warp::query::<api_types::ValidatorAttestationDataQuery>())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -3329,7 +3539,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("aggregate_attestation"))
         .and(warp::path::end())
         .and(warp::query::<api_types::ValidatorAggregateAttestationQuery>())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -3387,7 +3597,7 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(warp_utils::json::json())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -3415,7 +3625,7 @@ pub fn serve<T: BeaconChainTypes>(
             ))
         }))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(warp_utils::json::json())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
@@ -3438,7 +3648,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("sync_committee_contribution"))
         .and(warp::path::end())
         .and(warp::query::<SyncContributionData>())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -3471,7 +3681,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("aggregate_and_proofs"))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp_utils::json::json())
@@ -3590,7 +3800,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("contribution_and_proofs"))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp_utils::json::json())
@@ -3673,15 +3883,17 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("validator"))
         .and(warp::path("prepare_beacon_proposer"))
         .and(warp::path::end())
-        .and(not_while_syncing_filter.clone())
+        .and(validator_duties_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(optional_validator_subscription_tx_filter)
         .and(log_filter.clone())
         .and(warp_utils::json::json())
         .then(
             |not_synced_filter: Result<(), Rejection>,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
+             validator_subscription_tx: Option<Sender<ValidatorSubscriptionMessage>>,
              log: Logger,
              preparation_data: Vec<ProposerPreparationData>| {
                 task_spawner.spawn_async_with_rejection(Priority::P0, async move {
@@ -3707,6 +3919,24 @@ pub fn serve<T: BeaconChainTypes>(
                         .update_proposer_preparation(current_epoch, &preparation_data)
                         .await;
 
+                    // Let the network discover peers on the subnets of the validators' upcoming
+                    // duties. This is best-effort, so failing to queue the message is not an error.
+                    if let Some(validator_subscription_tx) = validator_subscription_tx {
+                        let message = ValidatorSubscriptionMessage::LocalValidators {
+                            validator_indices: preparation_data
+                                .iter()
+                                .map(|data| data.validator_index)
+                                .collect(),
+                        };
+                        if let Err(e) = validator_subscription_tx.try_send(message) {
+                            debug!(
+                                log,
+                                "Unable to register local validators for subnet discovery";
+                                "error" => ?e,
+                            );
+                        }
+                    }
+
                     chain
                         .prepare_beacon_proposer(current_slot)
                         .await
@@ -3835,44 +4065,62 @@ pub fn serve<T: BeaconChainTypes>(
                         // send the response back to our original HTTP request
                         // task via a channel.
                         let builder_future = async move {
-                            let arc_builder = chain
+                            let relays = chain
                                 .execution_layer
                                 .as_ref()
                                 .ok_or(BeaconChainError::ExecutionLayerMissing)
                                 .map_err(warp_utils::reject::beacon_chain_error)?
-                                .builder();
-                            let builder = arc_builder
-                                .as_ref()
+                                .relays()
                                 .ok_or(BeaconChainError::BuilderMissing)
                                 .map_err(warp_utils::reject::beacon_chain_error)?;
-                            builder
-                                .post_builder_validators(&filtered_registration_data)
-                                .await
-                                .map(|resp| warp::reply::json(&resp).into_response())
-                                .map_err(|e| {
+                            // Register with every relay, including blacklisted ones, so that they
+                            // can be used again once their blacklisting expires.
+                            let results =
+                                futures::future::join_all(relays.all().iter().map(|relay| {
+                                    relay
+                                        .client()
+                                        .post_builder_validators(&filtered_registration_data)
+                                }))
+                                .await;
+
+                            let mut first_error = None;
+                            let mut registered = false;
+                            for (relay, result) in relays.all().iter().zip(results) {
+                                if let Err(e) = result {
                                     warn!(
                                         log,
                                         "Relay error when registering validator(s)";
+                                        "relay" => relay.name(),
                                         "num_registrations" => filtered_registration_data.len(),
                                         "error" => ?e
                                     );
+                                    first_error.get_or_insert(e);
+                                } else {
+                                    registered = true;
+                                }
+                            }
+
+                            match first_error {
+                                Some(e) if !registered => {
                                     // Forward the HTTP status code if we are able to, otherwise fall back
                                     // to a server error.
                                     if let eth2::Error::ServerMessage(message) = e {
                                         if message.code == StatusCode::BAD_REQUEST.as_u16() {
-                                            return warp_utils::reject::custom_bad_request(
+                                            return Err(warp_utils::reject::custom_bad_request(
                                                 message.message,
-                                            );
+                                            ));
                                         } else {
                                             // According to the spec this response should only be a 400 or 500,
                                             // so we fall back to a 500 here.
-                                            return warp_utils::reject::custom_server_error(
+                                            return Err(warp_utils::reject::custom_server_error(
                                                 message.message,
-                                            );
+                                            ));
                                         }
                                     }
-                                    warp_utils::reject::custom_server_error(format!("{e:?}"))
-                                })
+                                    Err(warp_utils::reject::custom_server_error(format!("{e:?}")))
+                                }
+                                _ => Ok(warp::reply::json(&()).into_response()),
+                            }
                         };
                         tokio::task::spawn(async move { tx.send(builder_future.await) });
 
@@ -3959,29 +4207,15 @@ pub fn serve<T: BeaconChainTypes>(
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P0, move || {
-                    // Ensure the request is for either the current, previous or next epoch.
-                    let current_epoch = chain
-                        .epoch()
-                        .map_err(warp_utils::reject::beacon_chain_error)?;
-                    let prev_epoch = current_epoch.saturating_sub(Epoch::new(1));
-                    let next_epoch = current_epoch.saturating_add(Epoch::new(1));
-
-                    if epoch < prev_epoch || epoch > next_epoch {
-                        return Err(warp_utils::reject::custom_bad_request(format!(
-                            "request epoch {} is more than one epoch from the current epoch {}",
-                            epoch, current_epoch
-                        )));
-                    }
-
-                    let liveness: Vec<api_types::StandardLivenessResponseData> = indices
-                        .0
-                        .iter()
-                        .cloned()
-                        .map(|index| {
-                            let is_live = chain.validator_seen_at_epoch(index as usize, epoch);
-                            api_types::StandardLivenessResponseData { index, is_live }
-                        })
-                        .collect();
+                    let liveness: Vec<api_types::StandardLivenessResponseData> =
+                        liveness::validator_liveness(&chain, epoch, &indices.0)?
+                            .into_iter()
+                            .zip(indices.0)
+                            .map(|(is_live, index)| api_types::StandardLivenessResponseData {
+                                index,
+                                is_live,
+                            })
+                            .collect();
 
                     Ok(api_types::GenericResponse::from(liveness))
                 })
@@ -4000,34 +4234,17 @@ pub fn serve<T: BeaconChainTypes>(
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P0, move || {
-                    // Ensure the request is for either the current, previous or next epoch.
-                    let current_epoch = chain
-                        .epoch()
-                        .map_err(warp_utils::reject::beacon_chain_error)?;
-                    let prev_epoch = current_epoch.saturating_sub(Epoch::new(1));
-                    let next_epoch = current_epoch.saturating_add(Epoch::new(1));
-
-                    if request_data.epoch < prev_epoch || request_data.epoch > next_epoch {
-                        return Err(warp_utils::reject::custom_bad_request(format!(
-                            "request epoch {} is more than one epoch from the current epoch {}",
-                            request_data.epoch, current_epoch
-                        )));
-                    }
-
-                    let liveness: Vec<api_types::LivenessResponseData> = request_data
-                        .indices
-                        .iter()
-                        .cloned()
-                        .map(|index| {
-                            let is_live =
-                                chain.validator_seen_at_epoch(index as usize, request_data.epoch);
-                            api_types::LivenessResponseData {
+                    let epoch = request_data.epoch;
+                    let liveness: Vec<api_types::LivenessResponseData> =
+                        liveness::validator_liveness(&chain, epoch, &request_data.indices)?
+                            .into_iter()
+                            .zip(request_data.indices)
+                            .map(|(is_live, index)| api_types::LivenessResponseData {
                                 index,
-                                epoch: request_data.epoch,
+                                epoch,
                                 is_live,
-                            }
-                        })
-                        .collect();
+                            })
+                            .collect();
 
                     Ok(api_types::GenericResponse::from(liveness))
                 })
@@ -4145,6 +4362,57 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/failover
+    let failover_priority = ctx.config.failover_priority;
+    let get_lighthouse_failover = warp::path("lighthouse")
+        .and(warp::path("failover"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .and(chain_filter.clone())
+        .then(
+            move |task_spawner: TaskSpawner<T::EthSpec>,
+                  network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+                  chain: Arc<BeaconChain<T>>| {
+                async move {
+                    let el_offline = if let Some(el) = &chain.execution_layer {
+                        el.is_offline_or_erroring().await
+                    } else {
+                        true
+                    };
+
+                    task_spawner
+                        .blocking_json_task(Priority::P0, move || {
+                            Ok(api_types::GenericResponse::from(
+                                eth2::lighthouse::FailoverStatus {
+                                    role: network_globals.failover_role(),
+                                    priority: failover_priority,
+                                    is_healthy: !el_offline
+                                        && network_globals.sync_state().is_synced(),
+                                },
+                            ))
+                        })
+                        .await
+                }
+            },
+        );
+
+    // GET lighthouse/beacon_processor
+    let get_lighthouse_beacon_processor = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path::end())
+        .and(queue_monitor_filter.clone())
+        // Bypass the `task_spawner` so that the queues can be inspected whilst the beacon
+        // processor is overloaded.
+        .then(|queue_monitor: QueueMonitor| async move {
+            let queues = queue_monitor
+                .queues()
+                .into_iter()
+                .map(beacon_processor_queue)
+                .collect::<Vec<_>>();
+            warp::reply::json(&api_types::GenericResponse::from(queues)).into_response()
+        });
+
     // GET lighthouse/nat
     let get_lighthouse_nat = warp::path("lighthouse")
         .and(warp::path("nat"))
@@ -4179,6 +4447,92 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/peers/score_summary
+    let get_lighthouse_peers_score_summary = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("score_summary"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peers = network_globals.peers.read();
+                    let mut summary = eth2::lighthouse::PeerScoreSummary::default();
+                    let mut connected_score_sum = 0.0;
+                    let mut scored_peers = 0;
+
+                    for (_, peer_info) in peers.peers() {
+                        match peer_info.score_state() {
+                            ScoreState::Healthy => summary.healthy += 1,
+                            ScoreState::ForcedDisconnect => summary.disconnect += 1,
+                            ScoreState::Banned => summary.banned += 1,
+                        }
+
+                        if !peer_info.is_connected() {
+                            continue;
+                        }
+                        summary.connected += 1;
+
+                        // Trusted peers have an infinite score, which would skew the statistics.
+                        let score = peer_info.score().score();
+                        if score.is_finite() {
+                            summary.min_connected_score = Some(
+                                summary
+                                    .min_connected_score
+                                    .map_or(score, |min| min.min(score)),
+                            );
+                            connected_score_sum += score;
+                            scored_peers += 1;
+                        }
+                    }
+
+                    if scored_peers > 0 {
+                        summary.mean_connected_score =
+                            Some(connected_score_sum / scored_peers as f64);
+                    }
+
+                    Ok(api_types::GenericResponse::from(summary))
+                })
+            },
+        );
+
+    // GET lighthouse/peers/{peer_id}/score_breakdown
+    let get_lighthouse_peer_score_breakdown = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("score_breakdown"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |requested_peer_id: String,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peer_id = requested_peer_id.parse::<PeerId>().map_err(|e| {
+                        warp_utils::reject::custom_bad_request(format!("invalid peer id: {e}"))
+                    })?;
+                    let peers = network_globals.peers.read();
+                    let peer_info = peers.peer_info(&peer_id).ok_or_else(|| {
+                        warp_utils::reject::custom_not_found("peer is not known".to_string())
+                    })?;
+
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::PeerScoreBreakdown {
+                            peer_id: peer_id.to_string(),
+                            score: peer_info.score().clone(),
+                            score_state: peer_info.score_state(),
+                            gossipsub: peer_info.gossipsub_score_breakdown().cloned(),
+                            penalties: peer_info.penalties().cloned().collect(),
+                            subnets: peer_info.subnets().copied().collect(),
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/peers/connected
     let get_lighthouse_peers_connected = warp::path("lighthouse")
         .and(warp::path("peers"))
@@ -4225,6 +4579,225 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/fork_choice/graph
+    let get_lighthouse_fork_choice_graph = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path("graph"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::ForkChoiceGraphQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ForkChoiceGraphQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let graph = chain.fork_choice_graph();
+                    let response = match query.format {
+                        eth2::lighthouse::ForkChoiceGraphFormat::Json => {
+                            warp::reply::json(&api_types::GenericResponse::from(graph))
+                                .into_response()
+                        }
+                        eth2::lighthouse::ForkChoiceGraphFormat::Dot => warp::reply::with_header(
+                            graph.to_dot(),
+                            CONTENT_TYPE_HEADER,
+                            "text/vnd.graphviz",
+                        )
+                        .into_response(),
+                    };
+                    Ok::<_, warp::Rejection>(response)
+                })
+            },
+        );
+
+    // GET lighthouse/optimistic_status
+    let get_lighthouse_optimistic_status = warp::path("lighthouse")
+        .and(warp::path("optimistic_status"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::OptimisticStatusQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::OptimisticStatusQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .optimistic_status(query.block_root)
+                        .map(api_types::GenericResponse::from)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "block {:?} is not in fork choice",
+                                query.block_root
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/head_timeline/{slot}
+    let get_lighthouse_head_timeline = warp::path("lighthouse")
+        .and(warp::path("head_timeline"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .head_timeline(slot)
+                        .map(api_types::GenericResponse::from)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "the head timeline of slot {} is not retained",
+                                slot
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/validator/dry_run_block/{slot}
+    let get_lighthouse_validator_dry_run_block = warp::path("lighthouse")
+        .and(warp::path("validator"))
+        .and(warp::path("dry_run_block"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::DryRunBlockQuery>())
+        .and(validator_duties_filter.clone())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot,
+             query: eth2::lighthouse::DryRunBlockQuery,
+             not_synced_filter: Result<(), Rejection>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    not_synced_filter?;
+                    dry_run_block::dry_run_block(slot, query, chain)
+                        .await
+                        .map(api_types::GenericResponse::from)
+                        .map(|response| warp::reply::json(&response).into_response())
+                })
+            },
+        );
+
+    // GET lighthouse/weak_subjectivity
+    let get_lighthouse_weak_subjectivity = warp::path("lighthouse")
+        .and(warp::path("weak_subjectivity"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    weak_subjectivity_status(&chain)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
+    // GET lighthouse/orphans
+    let get_lighthouse_orphans = warp::path("lighthouse")
+        .and(warp::path("orphans"))
+        .and(warp::query::<eth2::lighthouse::OrphansQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(|query, task_spawner: TaskSpawner<T::EthSpec>, chain| {
+            task_spawner.blocking_json_task(Priority::P1, move || {
+                orphans::get_orphans(query, chain).map(api_types::GenericResponse::from)
+            })
+        });
+
+    // GET lighthouse/orphans/attestations
+    let get_lighthouse_orphaned_attestations = warp::path("lighthouse")
+        .and(warp::path("orphans"))
+        .and(warp::path("attestations"))
+        .and(warp::query::<eth2::lighthouse::OrphansQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(|query, task_spawner: TaskSpawner<T::EthSpec>, chain| {
+            task_spawner.blocking_json_task(Priority::P1, move || {
+                orphans::get_orphaned_attestations(query, chain)
+                    .map(api_types::GenericResponse::from)
+            })
+        });
+
+    // POST lighthouse/validators/duties
+    let post_lighthouse_validators_duties = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("duties"))
+        .and(warp::path::end())
+        .and(validator_duties_filter.clone())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |not_synced_filter: Result<(), Rejection>,
+             request: eth2::lighthouse::ValidatorDutiesRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             log: Logger| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    not_synced_filter?;
+                    validator_duties::validator_duties(request, &chain, &log)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/validator_projection
+    let post_lighthouse_validator_projection = warp::path("lighthouse")
+        .and(warp::path("validator_projection"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::ValidatorProjectionRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    validator_projection::validator_projection(request, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/committee_assignment/{epoch}/{validator_id}
+    let get_lighthouse_committee_assignment = warp::path("lighthouse")
+        .and(warp::path("committee_assignment"))
+        .and(warp::path::param::<Epoch>())
+        .and(warp::path::param::<ValidatorId>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |epoch: Epoch,
+             validator_id: ValidatorId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    committee_assignment::committee_assignment(epoch, &validator_id, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
     // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
     let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
         .and(warp::path("validator_inclusion"))
@@ -4233,14 +4806,21 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(validator_inclusion_cache_filter.clone())
         .then(
             |epoch: Epoch,
              validator_id: ValidatorId,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
+             chain: Arc<BeaconChain<T>>,
+             cache: Arc<validator_inclusion::ValidatorInclusionCache<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
-                    validator_inclusion::validator_inclusion_data(epoch, &validator_id, &chain)
-                        .map(api_types::GenericResponse::from)
+                    validator_inclusion::validator_inclusion_data(
+                        epoch,
+                        &validator_id,
+                        &chain,
+                        &cache,
+                    )
+                    .map(api_types::GenericResponse::from)
                 })
             },
         );
@@ -4253,10 +4833,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
+        .and(validator_inclusion_cache_filter.clone())
         .then(
-            |epoch: Epoch, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+            |epoch: Epoch,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             cache: Arc<validator_inclusion::ValidatorInclusionCache<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
-                    validator_inclusion::global_validator_inclusion_data(epoch, &chain)
+                    validator_inclusion::global_validator_inclusion_data(epoch, &chain, &cache)
                         .map(api_types::GenericResponse::from)
                 })
             },
@@ -4395,6 +4979,252 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    /*
+     * lighthouse/admin
+     */
+
+    let admin_token = config
+        .admin_token_file
+        .as_deref()
+        .map(admin::read_token)
+        .transpose()
+        .map_err(Error::Other)?;
+    let admin_path = warp::path("lighthouse")
+        .and(warp::path("admin"))
+        .and(admin::auth_filter(admin_token));
+
+    // POST lighthouse/admin/prune
+    let post_lighthouse_admin_prune = admin_path
+        .clone()
+        .and(warp::path("prune"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .store_migrator
+                        .process_prune_all(chain.data_availability_boundary());
+                    Ok("success")
+                })
+            },
+        );
+
+    // POST lighthouse/admin/peers/{peer_id}/disconnect
+    let post_lighthouse_admin_peer_disconnect = admin_path
+        .clone()
+        .and(warp::path("peers"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("disconnect"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .and(admin_network_tx_filter.clone())
+        .then(
+            |requested_peer_id: String,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peer_id = requested_peer_id.parse::<PeerId>().map_err(|e| {
+                        warp_utils::reject::custom_bad_request(format!("invalid peer id: {e}"))
+                    })?;
+                    if !network_globals.peers.read().is_connected(&peer_id) {
+                        return Err(warp_utils::reject::custom_not_found(
+                            "peer is not connected".to_string(),
+                        ));
+                    }
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::DisconnectPeer { peer_id },
+                    )?;
+                    Ok("success")
+                })
+            },
+        );
+
+    // POST lighthouse/admin/resubscribe
+    let post_lighthouse_admin_resubscribe = admin_path
+        .clone()
+        .and(warp::path("resubscribe"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(admin_network_tx_filter)
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    publish_network_message(&network_tx, NetworkMessage::ResubscribeTopics)?;
+                    Ok("success")
+                })
+            },
+        );
+
+    // POST lighthouse/admin/flush_caches
+    let post_lighthouse_admin_flush_caches = admin_path
+        .clone()
+        .and(warp::path("flush_caches"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain.store.flush_caches();
+                    Ok("success")
+                })
+            },
+        );
+
+    // POST lighthouse/admin/log_level
+    let inner_log_level = ctx.log_level.clone();
+    let post_lighthouse_admin_log_level = admin_path
+        .clone()
+        .and(warp::path("log_level"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            move |request: eth2::lighthouse::LogLevel,
+                  task_spawner: TaskSpawner<T::EthSpec>,
+                  log: Logger| {
+                let log_level = inner_log_level.clone();
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let log_level = log_level.ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "the log level can't be changed at runtime".to_string(),
+                        )
+                    })?;
+                    match &request.module {
+                        Some(module) if request.level == "default" => {
+                            log_level.set_module(module, None)
+                        }
+                        Some(module) => log_level
+                            .set_module(module, Some(admin::parse_log_level(&request.level)?)),
+                        None => log_level.set(admin::parse_log_level(&request.level)?),
+                    }
+                    info!(
+                        log,
+                        "Updated log level";
+                        "level" => &request.level,
+                        "module" => request.module.as_deref().unwrap_or("all"),
+                    );
+                    Ok(api_types::GenericResponse::from(request))
+                })
+            },
+        );
+
+    // POST lighthouse/admin/beacon_processor
+    let post_lighthouse_admin_beacon_processor = admin_path
+        .clone()
+        .and(warp::path("beacon_processor"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(queue_monitor_filter)
+        .and(log_filter.clone())
+        // Bypass the `task_spawner` so that the queues can be adjusted whilst the beacon
+        // processor is overloaded.
+        .then(
+            |update: eth2::lighthouse::BeaconProcessorQueueUpdate,
+             queue_monitor: QueueMonitor,
+             log: Logger| async move {
+                let result =
+                    update_beacon_processor_queue(&queue_monitor, update, &log).map(|queue| {
+                        warp::reply::json(&api_types::GenericResponse::from(
+                            beacon_processor_queue(queue),
+                        ))
+                    });
+                convert_rejection(result).await
+            },
+        );
+
+    // GET lighthouse/admin/payload_forensics
+    let get_lighthouse_admin_payload_forensics = admin_path
+        .clone()
+        .and(warp::path("payload_forensics"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let dir = admin::payload_forensics_dir(&chain)?;
+                    let bundles = payload_forensics::list_bundles(dir).map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "unable to list payload forensics: {e:?}"
+                        ))
+                    })?;
+                    Ok(api_types::GenericResponse::from(bundles))
+                })
+            },
+        );
+
+    // GET lighthouse/admin/payload_forensics/{bundle}/{file}
+    let get_lighthouse_admin_payload_forensics_file = admin_path
+        .clone()
+        .and(warp::path("payload_forensics"))
+        .and(warp::path::param::<String>())
+        .and(warp::path::param::<String>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |bundle: String,
+             file: String,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let dir = admin::payload_forensics_dir(&chain)?;
+                    let bytes = payload_forensics::read_bundle_file(dir, &bundle, &file)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to read payload forensics: {e:?}"
+                            ))
+                        })?
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no file {file} in bundle {bundle}"
+                            ))
+                        })?;
+                    let content_type = if file.ends_with(".ssz") {
+                        SSZ_CONTENT_TYPE_HEADER
+                    } else {
+                        "application/json"
+                    };
+                    Ok::<_, warp::Rejection>(
+                        warp::reply::with_header(bytes, CONTENT_TYPE_HEADER, content_type)
+                            .into_response(),
+                    )
+                })
+            },
+        );
+
+    // POST lighthouse/admin/validator_monitor
+    let post_lighthouse_admin_validator_monitor = admin_path
+        .and(warp::path("validator_monitor"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::ValidatorMonitorRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let mut validator_monitor = chain.validator_monitor.write();
+                    validator_monitor.set_auto_register(request.auto_register);
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::ValidatorMonitorStatus {
+                            auto_register: validator_monitor.auto_register(),
+                            monitored_validators: validator_monitor.num_validators(),
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4483,19 +5313,77 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("events"))
         .and(warp::path::end())
         .and(multi_key_query::<api_types::EventQuery>())
+        .and(warp::header::optional::<u64>("last-event-id"))
         .and(task_spawner_filter.clone())
         .and(chain_filter)
         .then(
             |topics_res: Result<api_types::EventQuery, warp::Rejection>,
+             last_event_id: Option<u64>,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_response_task(Priority::P0, move || {
                     let topics = topics_res?;
                     // for each topic subscribed spawn a new subscription
-                    let mut receivers = Vec::with_capacity(topics.topics.len());
+                    let mut receivers: Vec<
+                        Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>>,
+                    > = Vec::with_capacity(topics.topics.len() + 1);
 
                     if let Some(event_handler) = chain.event_handler.as_ref() {
-                        for topic in topics.topics {
+                        // When replay is enabled, replayable topics are served with events
+                        // tagged with IDs, starting with any events the client missed since
+                        // `Last-Event-ID`.
+                        let (replay_topics, live_topics): (Vec<_>, Vec<_>) =
+                            topics.topics.into_iter().partition(|topic| {
+                                event_handler.replay_enabled() && REPLAYABLE_TOPICS.contains(topic)
+                            });
+
+                        if !replay_topics.is_empty() {
+                            let mut replayed = vec![];
+                            let mut live_streams = vec![];
+                            for topic in &replay_topics {
+                                // Subscribe before reading the buffer so that no events are
+                                // missed in between.
+                                let Some(receiver) = event_handler.subscribe_replayable(topic)
+                                else {
+                                    continue;
+                                };
+                                let topic_replayed = last_event_id
+                                    .map(|id| event_handler.replay_since(topic, id))
+                                    .unwrap_or_default();
+                                let last_replayed_id = topic_replayed.last().map(|event| event.id);
+                                replayed.extend(topic_replayed);
+
+                                live_streams.push(BroadcastStream::new(receiver).filter(
+                                    move |msg| {
+                                        // Skip events which were also replayed from the buffer.
+                                        msg.as_ref().map_or(true, |event| {
+                                            last_replayed_id.map_or(true, |id| event.id > id)
+                                        })
+                                    },
+                                ));
+                            }
+                            // Missed events are sent first, in the order in which they occurred.
+                            replayed.sort_by_key(|event| event.id);
+
+                            let stream = tokio_stream::iter(replayed.into_iter().map(Ok))
+                                .chain(futures::stream::select_all(live_streams))
+                                .map(|msg| match msg {
+                                    Ok(ReplayableEvent { id, event }) => Event::default()
+                                        .id(id.to_string())
+                                        .event(event.topic_name())
+                                        .json_data(event)
+                                        .unwrap_or_else(|e| {
+                                            Event::default()
+                                                .comment(format!("error - bad json: {e:?}"))
+                                        }),
+                                    Err(BroadcastStreamRecvError::Lagged(n)) => Event::default()
+                                        .comment(format!("error - dropped {n} messages")),
+                                })
+                                .map(Ok::<_, Infallible>);
+                            receivers.push(Box::pin(stream));
+                        }
+
+                        for topic in live_topics {
                             let receiver = match topic {
                                 api_types::EventTopic::Head => event_handler.subscribe_head(),
                                 api_types::EventTopic::Block => event_handler.subscribe_block(),
@@ -4544,9 +5432,18 @@ pub fn serve<T: BeaconChainTypes>(
                                 api_types::EventTopic::BlockGossip => {
                                     event_handler.subscribe_block_gossip()
                                 }
+                                api_types::EventTopic::BlockProduction => {
+                                    event_handler.subscribe_block_production()
+                                }
+                                api_types::EventTopic::OptimisticTransition => {
+                                    event_handler.subscribe_optimistic_transition()
+                                }
+                                api_types::EventTopic::SlasherAlert => {
+                                    event_handler.subscribe_slasher_alert()
+                                }
                             };
 
-                            receivers.push(
+                            receivers.push(Box::pin(
                                 BroadcastStream::new(receiver)
                                     .map(|msg| {
                                         match msg {
@@ -4567,8 +5464,8 @@ pub fn serve<T: BeaconChainTypes>(
                                             }
                                         }
                                     })
-                                    .map(Ok::<_, std::convert::Infallible>),
-                            );
+                                    .map(Ok::<_, Infallible>),
+                            ));
                         }
                     } else {
                         return Err(warp_utils::reject::custom_server_error(
@@ -4629,136 +5526,187 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // Routes used by validator clients to perform their duties. These are matched before all
+    // other routes and are exempt from the rate limit and concurrency caps, so that a misbehaving
+    // client can't starve them.
+    let validator_get_routes = get_validator_duties_proposer
+        .uor(get_validator_blocks)
+        .uor(get_validator_blinded_blocks)
+        .uor(get_validator_attestation_data)
+        .uor(get_validator_aggregate_attestation)
+        .uor(get_validator_sync_committee_contribution);
+
+    let validator_post_routes = warp::header::exact(CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER)
+        // Routes which expect `application/octet-stream` go within this `and`.
+        .and(
+            post_beacon_blocks_ssz
+                .uor(post_beacon_blocks_v2_ssz)
+                .uor(post_beacon_blinded_blocks_ssz)
+                .uor(post_beacon_blinded_blocks_v2_ssz),
+        )
+        .uor(post_beacon_blocks)
+        .uor(post_beacon_blinded_blocks)
+        .uor(post_beacon_blocks_v2)
+        .uor(post_beacon_blinded_blocks_v2)
+        .uor(post_beacon_pool_attestations)
+        .uor(post_beacon_pool_attester_slashings)
+        .uor(post_beacon_pool_proposer_slashings)
+        .uor(post_beacon_pool_voluntary_exits)
+        .uor(post_beacon_pool_sync_committees)
+        .uor(post_beacon_pool_bls_to_execution_changes)
+        .uor(post_validator_duties_attester)
+        .uor(post_validator_duties_sync)
+        .uor(post_validator_aggregate_and_proofs)
+        .uor(post_validator_contribution_and_proofs)
+        .uor(post_validator_beacon_committee_subscriptions)
+        .uor(post_validator_sync_committee_subscriptions)
+        .uor(post_validator_prepare_beacon_proposer)
+        .uor(post_validator_register_validator)
+        .uor(post_validator_liveness_epoch)
+        .uor(post_lighthouse_liveness)
+        .uor(post_lighthouse_validators_duties);
+
+    // Apply the rate limit and concurrency caps to all other routes, releasing the request's permit
+    // once the response has been produced.
+    let request_limit =
+        rate_limit::request_limit_filter(Arc::new(RequestLimiter::new(&ctx.config)));
+
     // Define the ultimate set of routes that will be provided to the server.
     // Use `uor` rather than `or` in order to simplify types (see `UnifyingOrFilter`).
     let routes = warp::get()
         .and(
-            get_beacon_genesis
-                .uor(get_beacon_state_root)
-                .uor(get_beacon_state_fork)
-                .uor(get_beacon_state_finality_checkpoints)
-                .uor(get_beacon_state_validator_balances)
-                .uor(get_beacon_state_validators_id)
-                .uor(get_beacon_state_validators)
-                .uor(get_beacon_state_committees)
-                .uor(get_beacon_state_sync_committees)
-                .uor(get_beacon_state_randao)
-                .uor(get_beacon_headers)
-                .uor(get_beacon_headers_block_id)
-                .uor(get_beacon_block)
-                .uor(get_beacon_block_attestations)
-                .uor(get_beacon_blinded_block)
-                .uor(get_beacon_block_root)
-                .uor(get_blobs)
-                .uor(get_beacon_pool_attestations)
-                .uor(get_beacon_pool_attester_slashings)
-                .uor(get_beacon_pool_proposer_slashings)
-                .uor(get_beacon_pool_voluntary_exits)
-                .uor(get_beacon_pool_bls_to_execution_changes)
-                .uor(get_beacon_deposit_snapshot)
-                .uor(get_beacon_rewards_blocks)
-                .uor(get_config_fork_schedule)
-                .uor(get_config_spec)
-                .uor(get_config_deposit_contract)
-                .uor(get_debug_beacon_states)
-                .uor(get_debug_beacon_heads)
-                .uor(get_debug_fork_choice)
-                .uor(get_node_identity)
-                .uor(get_node_version)
-                .uor(get_node_syncing)
-                .uor(get_node_health)
-                .uor(get_node_peers_by_id)
-                .uor(get_node_peers)
-                .uor(get_node_peer_count)
-                .uor(get_validator_duties_proposer)
-                .uor(get_validator_blocks)
-                .uor(get_validator_blinded_blocks)
-                .uor(get_validator_attestation_data)
-                .uor(get_validator_aggregate_attestation)
-                .uor(get_validator_sync_committee_contribution)
-                .uor(get_lighthouse_health)
-                .uor(get_lighthouse_ui_health)
-                .uor(get_lighthouse_ui_validator_count)
-                .uor(get_lighthouse_syncing)
-                .uor(get_lighthouse_nat)
-                .uor(get_lighthouse_peers)
-                .uor(get_lighthouse_peers_connected)
-                .uor(get_lighthouse_proto_array)
-                .uor(get_lighthouse_validator_inclusion_global)
-                .uor(get_lighthouse_validator_inclusion)
-                .uor(get_lighthouse_eth1_syncing)
-                .uor(get_lighthouse_eth1_block_cache)
-                .uor(get_lighthouse_eth1_deposit_cache)
-                .uor(get_lighthouse_staking)
-                .uor(get_lighthouse_database_info)
-                .uor(get_lighthouse_block_rewards)
-                .uor(get_lighthouse_attestation_performance)
+            validator_get_routes
                 .uor(
-                    enable(ctx.config.enable_light_client_server)
-                        .and(get_beacon_light_client_optimistic_update),
-                )
-                .uor(
-                    enable(ctx.config.enable_light_client_server)
-                        .and(get_beacon_light_client_finality_update),
-                )
-                .uor(
-                    enable(ctx.config.enable_light_client_server)
-                        .and(get_beacon_light_client_bootstrap),
-                )
-                .uor(
-                    enable(ctx.config.enable_light_client_server)
-                        .and(get_beacon_light_client_updates),
+                    request_limit
+                        .clone()
+                        .and(
+                            get_beacon_genesis
+                                .uor(get_beacon_state_root)
+                                .uor(get_beacon_state_fork)
+                                .uor(get_beacon_state_finality_checkpoints)
+                                .uor(get_beacon_state_validator_balances)
+                                .uor(get_beacon_state_validators_id)
+                                .uor(get_beacon_state_validators)
+                                .uor(get_beacon_state_validators_v2)
+                                .uor(get_beacon_state_committees)
+                                .uor(get_beacon_state_sync_committees)
+                                .uor(get_beacon_state_randao)
+                                .uor(get_beacon_headers)
+                                .uor(get_beacon_headers_block_id)
+                                .uor(get_beacon_block)
+                                .uor(get_beacon_block_attestations)
+                                .uor(get_beacon_blinded_block)
+                                .uor(get_beacon_block_root)
+                                .uor(get_blobs)
+                                .uor(get_beacon_pool_attestations)
+                                .uor(get_beacon_pool_attester_slashings)
+                                .uor(get_beacon_pool_proposer_slashings)
+                                .uor(get_beacon_pool_voluntary_exits)
+                                .uor(get_beacon_pool_bls_to_execution_changes)
+                                .uor(get_beacon_deposit_snapshot)
+                                .uor(get_beacon_rewards_blocks)
+                                .uor(get_config_fork_schedule)
+                                .uor(get_config_spec)
+                                .uor(get_config_deposit_contract)
+                                .uor(get_debug_beacon_states)
+                                .uor(get_debug_beacon_heads)
+                                .uor(get_debug_fork_choice)
+                                .uor(get_node_identity)
+                                .uor(get_node_version)
+                                .uor(get_node_syncing)
+                                .uor(get_node_health)
+                                .uor(get_node_peers_by_id)
+                                .uor(get_node_peers)
+                                .uor(get_node_peer_count)
+                                .uor(get_lighthouse_health)
+                                .uor(get_lighthouse_ui_health)
+                                .uor(get_lighthouse_ui_validator_count)
+                                .uor(get_lighthouse_syncing)
+                                .uor(get_lighthouse_failover)
+                                .uor(get_lighthouse_beacon_processor)
+                                .uor(get_lighthouse_nat)
+                                .uor(get_lighthouse_peers)
+                                .uor(get_lighthouse_peers_connected)
+                                .uor(get_lighthouse_peers_score_summary)
+                                .uor(get_lighthouse_peer_score_breakdown)
+                                .uor(get_lighthouse_proto_array)
+                                .uor(get_lighthouse_fork_choice_graph)
+                                .uor(get_lighthouse_orphans)
+                                .uor(get_lighthouse_orphaned_attestations)
+                                .uor(get_lighthouse_optimistic_status)
+                                .uor(get_lighthouse_head_timeline)
+                                .uor(get_lighthouse_validator_dry_run_block)
+                                .uor(get_lighthouse_weak_subjectivity)
+                                .uor(get_lighthouse_validator_inclusion_global)
+                                .uor(get_lighthouse_committee_assignment)
+                                .uor(get_lighthouse_admin_payload_forensics)
+                                .uor(get_lighthouse_admin_payload_forensics_file)
+                                .uor(get_lighthouse_validator_inclusion)
+                                .uor(get_lighthouse_eth1_syncing)
+                                .uor(get_lighthouse_eth1_block_cache)
+                                .uor(get_lighthouse_eth1_deposit_cache)
+                                .uor(get_lighthouse_staking)
+                                .uor(get_lighthouse_database_info)
+                                .uor(get_lighthouse_block_rewards)
+                                .uor(get_lighthouse_attestation_performance)
+                                .uor(
+                                    enable(ctx.config.enable_light_client_server)
+                                        .and(get_beacon_light_client_optimistic_update),
+                                )
+                                .uor(
+                                    enable(ctx.config.enable_light_client_server)
+                                        .and(get_beacon_light_client_finality_update),
+                                )
+                                .uor(
+                                    enable(ctx.config.enable_light_client_server)
+                                        .and(get_beacon_light_client_bootstrap),
+                                )
+                                .uor(
+                                    enable(ctx.config.enable_light_client_server)
+                                        .and(get_beacon_light_client_updates),
+                                )
+                                .uor(get_lighthouse_block_packing_efficiency)
+                                .uor(get_lighthouse_merge_readiness)
+                                .uor(get_events)
+                                .uor(get_expected_withdrawals)
+                                .uor(lighthouse_log_events.boxed()),
+                        )
+                        .map(|_permit: RequestPermit, reply| reply),
                 )
-                .uor(get_lighthouse_block_packing_efficiency)
-                .uor(get_lighthouse_merge_readiness)
-                .uor(get_events)
-                .uor(get_expected_withdrawals)
-                .uor(lighthouse_log_events.boxed())
                 .recover(warp_utils::reject::handle_rejection),
         )
         .boxed()
         .uor(
             warp::post().and(
-                warp::header::exact(CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER)
-                    // Routes which expect `application/octet-stream` go within this `and`.
-                    .and(
-                        post_beacon_blocks_ssz
-                            .uor(post_beacon_blocks_v2_ssz)
-                            .uor(post_beacon_blinded_blocks_ssz)
-                            .uor(post_beacon_blinded_blocks_v2_ssz),
+                validator_post_routes
+                    .uor(
+                        request_limit
+                            .and(
+                                post_beacon_state_validators
+                                    .uor(post_beacon_state_validator_balances)
+                                    .uor(post_beacon_rewards_attestations)
+                                    .uor(post_beacon_rewards_sync_committee)
+                                    .uor(post_lighthouse_validator_projection)
+                                    .uor(post_lighthouse_database_reconstruct)
+                                    .uor(post_lighthouse_admin_prune)
+                                    .uor(post_lighthouse_admin_peer_disconnect)
+                                    .uor(post_lighthouse_admin_resubscribe)
+                                    .uor(post_lighthouse_admin_flush_caches)
+                                    .uor(post_lighthouse_admin_log_level)
+                                    .uor(post_lighthouse_admin_beacon_processor)
+                                    .uor(post_lighthouse_admin_validator_monitor)
+                                    .uor(post_lighthouse_block_rewards)
+                                    .uor(post_lighthouse_ui_validator_metrics)
+                                    .uor(post_lighthouse_ui_validator_info),
+                            )
+                            .map(|_permit: RequestPermit, reply| reply),
                     )
-                    .uor(post_beacon_blocks)
-                    .uor(post_beacon_blinded_blocks)
-                    .uor(post_beacon_blocks_v2)
-                    .uor(post_beacon_blinded_blocks_v2)
-                    .uor(post_beacon_pool_attestations)
-                    .uor(post_beacon_pool_attester_slashings)
-                    .uor(post_beacon_pool_proposer_slashings)
-                    .uor(post_beacon_pool_voluntary_exits)
-                    .uor(post_beacon_pool_sync_committees)
-                    .uor(post_beacon_pool_bls_to_execution_changes)
-                    .uor(post_beacon_state_validators)
-                    .uor(post_beacon_state_validator_balances)
-                    .uor(post_beacon_rewards_attestations)
-                    .uor(post_beacon_rewards_sync_committee)
-                    .uor(post_validator_duties_attester)
-                    .uor(post_validator_duties_sync)
-                    .uor(post_validator_aggregate_and_proofs)
-                    .uor(post_validator_contribution_and_proofs)
-                    .uor(post_validator_beacon_committee_subscriptions)
-                    .uor(post_validator_sync_committee_subscriptions)
-                    .uor(post_validator_prepare_beacon_proposer)
-                    .uor(post_validator_register_validator)
-                    .uor(post_validator_liveness_epoch)
-                    .uor(post_lighthouse_liveness)
-                    .uor(post_lighthouse_database_reconstruct)
-                    .uor(post_lighthouse_block_rewards)
-                    .uor(post_lighthouse_ui_validator_metrics)
-                    .uor(post_lighthouse_ui_validator_info)
                     .recover(warp_utils::reject::handle_rejection),
             ),
         )
         .recover(warp_utils::reject::handle_rejection)
+        .and(compression::compression_filter(config.compression_level))
+        .map(compression::compress)
         .with(slog_logging(log.clone()))
         .with(prometheus_metrics())
         // Add a `Server` header.
@@ -4832,3 +5780,64 @@ fn publish_network_message<E: EthSpec>(
         ))
     })
 }
+
+/// Maps an error from generating proofs of validator records, rejecting the request with a 429 if
+/// it has to wait for another validator registry tree to be built.
+fn proof_tree_error(e: BeaconChainError) -> warp::Rejection {
+    match e {
+        BeaconChainError::ValidatorProofTreeBusy => warp_utils::reject::too_many_requests(
+            "validator proofs are being computed for another state, retry later".to_string(),
+        ),
+        e => warp_utils::reject::custom_server_error(format!("failed to compute proof: {e:?}")),
+    }
+}
+
+/// Applies an update to a beacon processor queue from the admin API.
+fn update_beacon_processor_queue(
+    queue_monitor: &QueueMonitor,
+    update: eth2::lighthouse::BeaconProcessorQueueUpdate,
+    log: &Logger,
+) -> Result<QueueStatus, warp::Rejection> {
+    if let Some(max_length) = update.max_length {
+        queue_monitor
+            .set_max_length(&update.name, max_length)
+            .map_err(warp_utils::reject::custom_bad_request)?;
+    }
+    if let Some(paused) = update.paused {
+        queue_monitor
+            .set_paused(&update.name, paused)
+            .map_err(warp_utils::reject::custom_bad_request)?;
+    }
+    if let Some(prioritized) = update.prioritized {
+        queue_monitor
+            .set_prioritized(&update.name, prioritized)
+            .map_err(warp_utils::reject::custom_bad_request)?;
+    }
+    let queue = queue_monitor.queue(&update.name).ok_or_else(|| {
+        warp_utils::reject::custom_bad_request(format!("unknown queue: {}", update.name))
+    })?;
+
+    info!(
+        log,
+        "Updated beacon processor queue";
+        "queue" => queue.name,
+        "max_length" => queue.max_length,
+        "paused" => queue.paused,
+        "prioritized" => queue.prioritized,
+    );
+    Ok(queue)
+}
+
+/// Converts the status of a beacon processor queue into its API representation.
+fn beacon_processor_queue(status: QueueStatus) -> eth2::lighthouse::BeaconProcessorQueue {
+    eth2::lighthouse::BeaconProcessorQueue {
+        name: status.name.to_string(),
+        length: status.length,
+        max_length: status.max_length,
+        paused: status.paused,
+        prioritized: status.prioritized,
+        dropped: status.dropped,
+        processed: status.processed,
+        mean_wait_ms: status.mean_wait.as_secs_f64() * 1_000.0,
+    }
+}
@@ -0,0 +1,88 @@
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use std::collections::HashSet;
+use types::{Epoch, EthSpec};
+
+/// The number of epochs before the previous epoch for which liveness can be determined from the
+/// participation recorded on chain.
+pub const MAX_HISTORICAL_LIVENESS_EPOCHS: u64 = 32;
+
+/// Returns whether each of the `indices` was live at `epoch`.
+///
+/// The current, previous and next epochs are answered from the in-memory observation caches, which
+/// include messages seen on gossip but not (yet) included on chain. Older epochs are answered from
+/// the attestation participation and block proposals recorded on chain, so that liveness can still
+/// be determined for the epochs prior to a restart.
+pub fn validator_liveness<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    epoch: Epoch,
+    indices: &[u64],
+) -> Result<Vec<bool>, warp::Rejection> {
+    let current_epoch = chain
+        .epoch()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+    let prev_epoch = current_epoch.saturating_sub(Epoch::new(1));
+    let next_epoch = current_epoch.saturating_add(Epoch::new(1));
+    let oldest_epoch = prev_epoch.saturating_sub(MAX_HISTORICAL_LIVENESS_EPOCHS);
+
+    if epoch < oldest_epoch || epoch > next_epoch {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "request epoch {} is outside of the range {}..={} at the current epoch {}",
+            epoch, oldest_epoch, next_epoch, current_epoch
+        )));
+    }
+
+    if epoch >= prev_epoch {
+        return Ok(indices
+            .iter()
+            .map(|&index| chain.validator_seen_at_epoch(index as usize, epoch))
+            .collect());
+    }
+
+    // Attestations can be included until the end of the epoch after their target, so the
+    // participation of `epoch` is final in the last state of the following epoch.
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let (state, _execution_optimistic, _finalized) =
+        StateId::from_slot((epoch + 1).end_slot(slots_per_epoch)).state(chain)?;
+    let participation = state.previous_epoch_participation().map_err(|_| {
+        warp_utils::reject::custom_bad_request(format!(
+            "participation is not recorded for epoch {}, prior to Altair",
+            epoch
+        ))
+    })?;
+
+    let mut proposers = HashSet::new();
+    let mut prev_block_root = None;
+    for slot in epoch.slot_iter(slots_per_epoch) {
+        let block_root = *state
+            .get_block_root(slot)
+            .map_err(warp_utils::reject::beacon_state_error)?;
+        if prev_block_root.is_none() && slot > 0 {
+            prev_block_root = Some(
+                *state
+                    .get_block_root(slot - 1)
+                    .map_err(warp_utils::reject::beacon_state_error)?,
+            );
+        }
+        // Skipped slots repeat the root of the previous block.
+        if prev_block_root != Some(block_root) {
+            if let Some(block) = chain
+                .get_blinded_block(&block_root)
+                .map_err(warp_utils::reject::beacon_chain_error)?
+            {
+                proposers.insert(block.message().proposer_index());
+            }
+        }
+        prev_block_root = Some(block_root);
+    }
+
+    Ok(indices
+        .iter()
+        .map(|&index| {
+            proposers.contains(&index)
+                || participation
+                    .get(index as usize)
+                    .map_or(false, |flags| flags.into_u8() != 0)
+        })
+        .collect())
+}
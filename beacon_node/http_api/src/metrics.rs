@@ -51,3 +51,24 @@ pub static HTTP_API_STATE_ROOT_TIMES: LazyLock<Result<Histogram>> = LazyLock::ne
         "Time to load a state root for a request",
     )
 });
+pub static HTTP_API_REQUEST_QUEUE_DEPTH: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "http_api_request_queue_depth",
+        "Number of HTTP requests waiting for a concurrency permit",
+        &["class"],
+    )
+});
+pub static HTTP_API_ACTIVE_REQUESTS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "http_api_active_requests",
+        "Number of HTTP requests subject to the request limits currently being processed",
+        &["class"],
+    )
+});
+pub static HTTP_API_LIMITED_REQUESTS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "http_api_limited_requests_total",
+        "Count of HTTP requests rejected by the rate limiter or concurrency caps",
+        &["class", "reason"],
+    )
+});
@@ -0,0 +1,76 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{OrphanedAttestationInfo, OrphanedBlockInfo, OrphansQuery};
+use std::sync::Arc;
+use warp_utils::reject::{beacon_chain_error, custom_bad_request};
+
+/// The maximum number of slots which can be queried at once.
+const MAX_REQUEST_RANGE_SLOTS: u64 = 1024;
+
+/// Fetch blocks from the orphaned block archive with slots in the queried range.
+pub fn get_orphans<T: BeaconChainTypes>(
+    query: OrphansQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OrphanedBlockInfo>, warp::Rejection> {
+    check_query(&query)?;
+
+    let orphaned_blocks = chain
+        .store
+        .get_orphaned_blocks(query.start_slot, query.end_slot)
+        .map_err(|e| beacon_chain_error(e.into()))?;
+
+    Ok(orphaned_blocks
+        .into_iter()
+        .map(|(block_root, orphaned_block)| OrphanedBlockInfo {
+            block_root,
+            reason: orphaned_block.reason,
+            signed_header: orphaned_block.block.signed_block_header(),
+            attestations: orphaned_block
+                .block
+                .message()
+                .body()
+                .attestations()
+                .map(|attestation| attestation.data().clone())
+                .collect(),
+        })
+        .collect())
+}
+
+/// Fetch attestations from the orphaned attestation archive for slots in the queried range.
+pub fn get_orphaned_attestations<T: BeaconChainTypes>(
+    query: OrphansQuery,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<OrphanedAttestationInfo<T::EthSpec>>, warp::Rejection> {
+    check_query(&query)?;
+
+    let orphaned_attestations = chain
+        .store
+        .get_orphaned_attestations(query.start_slot, query.end_slot)
+        .map_err(|e| beacon_chain_error(e.into()))?;
+
+    Ok(orphaned_attestations
+        .into_iter()
+        .map(|(block_root, attestation)| OrphanedAttestationInfo {
+            block_root,
+            attestation,
+        })
+        .collect())
+}
+
+fn check_query(query: &OrphansQuery) -> Result<(), warp::Rejection> {
+    if query.start_slot > query.end_slot {
+        return Err(custom_bad_request(format!(
+            "invalid start and end: {}, {}",
+            query.start_slot, query.end_slot
+        )));
+    }
+
+    // The archive can hold many entries per slot, so limit the size of the response.
+    if query.end_slot - query.start_slot >= MAX_REQUEST_RANGE_SLOTS {
+        return Err(custom_bad_request(format!(
+            "range must not span more than {} slots. start: {}, end: {}",
+            MAX_REQUEST_RANGE_SLOTS, query.start_slot, query.end_slot
+        )));
+    }
+
+    Ok(())
+}
@@ -46,7 +46,7 @@ pub fn proposer_duties<T: BeaconChainTypes>(
             .epoch(T::EthSpec::slots_per_epoch())
     };
 
-    if request_epoch == current_epoch || request_epoch == tolerant_current_epoch {
+    let duties = if request_epoch == current_epoch || request_epoch == tolerant_current_epoch {
         // If we could consider ourselves in the `request_epoch` when allowing for clock disparity
         // tolerance then serve this request from the cache.
         if let Some(duties) = try_proposer_duties_from_cache(request_epoch, chain)? {
@@ -89,7 +89,34 @@ pub fn proposer_duties<T: BeaconChainTypes>(
         //
         // Queries about the past are handled with a slow path.
         compute_historic_proposer_duties(request_epoch, chain)
+    };
+
+    // Record upcoming proposals by proposers registered with this node, which the payload
+    // attributes policy uses to decide which proposals to prepare. Duties fetched by other
+    // clients, e.g. dashboards, are not recorded.
+    if let Ok(duties) = &duties {
+        if request_epoch >= current_epoch && has_local_proposer(chain, duties) {
+            chain
+                .beacon_proposer_cache
+                .lock()
+                .record_local_proposal_epoch(request_epoch);
+        }
     }
+
+    duties
+}
+
+/// Returns `true` if any of the `duties` belongs to a proposer registered with the execution layer
+/// via `prepare_beacon_proposer`.
+///
+/// This function MUST NOT be called from an async task.
+fn has_local_proposer<T: BeaconChainTypes>(chain: &BeaconChain<T>, duties: &ApiDuties) -> bool {
+    chain.execution_layer.as_ref().map_or(false, |el| {
+        duties
+            .data
+            .iter()
+            .any(|duty| el.has_proposer_preparation_data_blocking(duty.validator_index))
+    })
 }
 
 /// Attempt to load the proposer duties from the `chain.beacon_proposer_cache`, returning `Ok(None)`
@@ -201,7 +228,7 @@ fn compute_historic_proposer_duties<T: BeaconChainTypes>(
         if head.beacon_state.current_epoch() <= epoch {
             Some((
                 head.beacon_state_root(),
-                head.beacon_state.clone(),
+                chain.clone_head_state(head),
                 execution_status.is_optimistic_or_invalid(),
             ))
         } else {
@@ -99,6 +99,18 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     let block = unverified_block.inner_block();
     debug!(log, "Signed block received in HTTP API"; "slot" => block.slot());
 
+    // Refuse to publish the block or its sidecars if it conflicts with a block already seen from
+    // one of the operator's own validators, regardless of the broadcast validation level.
+    if chain.own_key_guard.is_enabled() {
+        let block_root = block_root.unwrap_or_else(|| block.canonical_root());
+        if let Err(conflict) = chain.check_own_key_block(block_root, block.message()) {
+            return Err(warp_utils::reject::custom_bad_request(format!(
+                "block refused by own key guard: {:?}",
+                conflict
+            )));
+        }
+    }
+
     /* actually publish a block */
     let publish_block_p2p = move |block: Arc<SignedBeaconBlock<T::EthSpec>>,
                                   sender,
@@ -0,0 +1,203 @@
+//! Opt-in tracing of where the time is spent serving a request.
+//!
+//! Requests with a `trace=true` query parameter or an `X-Lighthouse-Trace: true` header are traced.
+//! Each state or block load made while serving the request records a `TraceStep` describing where
+//! the data came from (e.g. the head, a cache, the freezer database or block replay) and how long
+//! it took. The steps are returned as JSON in the `X-Lighthouse-Trace` response header, alongside
+//! the total time taken to produce the response.
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use store::state_load_plan::StateLoadPlan;
+use warp::http::{HeaderMap, HeaderValue};
+use warp::reply::Response;
+use warp::Filter;
+
+/// The request header which enables tracing, and the response header containing the trace.
+pub const TRACE_HEADER: &str = "X-Lighthouse-Trace";
+
+tokio::task_local! {
+    static QUERY_TRACE: Arc<QueryTrace>;
+}
+
+/// A single operation performed while serving a traced request.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStep {
+    /// The operation performed, e.g. `load_state`.
+    pub operation: &'static str,
+    /// Where the data was loaded from.
+    pub source: &'static str,
+    /// The plan used to load a state, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<StateLoadPlan>,
+    pub duration_ms: f64,
+}
+
+#[derive(Serialize)]
+struct TraceSummary<'a> {
+    total_ms: f64,
+    steps: &'a [TraceStep],
+}
+
+/// The trace of a single request.
+pub struct QueryTrace {
+    started: Instant,
+    steps: Mutex<Vec<TraceStep>>,
+}
+
+impl QueryTrace {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            steps: Mutex::new(vec![]),
+        }
+    }
+
+    fn header_value(&self) -> Option<HeaderValue> {
+        let steps = self.steps.lock();
+        let summary = TraceSummary {
+            total_ms: elapsed_ms(self.started),
+            steps: &steps,
+        };
+        serde_json::to_string(&summary)
+            .ok()
+            .and_then(|summary| HeaderValue::from_str(&summary).ok())
+    }
+}
+
+impl Default for QueryTrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn elapsed_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1_000.0
+}
+
+/// Returns `true` if the current request is being traced.
+pub fn is_enabled() -> bool {
+    QUERY_TRACE.try_with(|_| ()).is_ok()
+}
+
+/// Record an operation which began at `started`, if the current request is being traced.
+pub fn record(
+    operation: &'static str,
+    source: &'static str,
+    plan: Option<StateLoadPlan>,
+    started: Instant,
+) {
+    let _ = QUERY_TRACE.try_with(|trace| {
+        trace.steps.lock().push(TraceStep {
+            operation,
+            source,
+            plan,
+            duration_ms: elapsed_ms(started),
+        })
+    });
+}
+
+/// Run `func`, recording its operations in `trace`.
+pub fn sync_scope<R>(trace: Option<Arc<QueryTrace>>, func: impl FnOnce() -> R) -> R {
+    match trace {
+        Some(trace) => QUERY_TRACE.sync_scope(trace, func),
+        None => func(),
+    }
+}
+
+/// Run `func`, recording its operations in `trace`.
+pub async fn scope<F: Future>(trace: Option<Arc<QueryTrace>>, func: F) -> F::Output {
+    match trace {
+        Some(trace) => QUERY_TRACE.scope(trace, func).await,
+        None => func.await,
+    }
+}
+
+/// Add the contents of `trace` to the headers of `response`.
+pub fn add_trace_header(trace: Option<&QueryTrace>, mut response: Response) -> Response {
+    if let Some(value) = trace.and_then(QueryTrace::header_value) {
+        response.headers_mut().insert(TRACE_HEADER, value);
+    }
+    response
+}
+
+/// A filter which creates a `QueryTrace` if the request asks to be traced.
+pub fn trace_filter(
+) -> impl Filter<Extract = (Option<Arc<QueryTrace>>,), Error = Infallible> + Clone {
+    warp::header::headers_cloned()
+        .and(
+            warp::filters::query::raw()
+                .or(warp::any().map(String::default))
+                .unify(),
+        )
+        .map(|headers: HeaderMap, query: String| {
+            let header_enabled = headers
+                .get(TRACE_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.eq_ignore_ascii_case("true"));
+            let query_enabled = query.split('&').any(|pair| pair == "trace=true");
+            (header_enabled || query_enabled).then(|| Arc::new(QueryTrace::new()))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn trace_filter_opt_in() {
+        let traced = |request: warp::test::RequestBuilder| async move {
+            request.filter(&trace_filter()).await.unwrap().is_some()
+        };
+
+        assert!(!traced(warp::test::request().path("/eth/v1/beacon/states/head/root")).await);
+        assert!(
+            traced(warp::test::request().path("/eth/v1/beacon/states/head/root?trace=true")).await
+        );
+        assert!(
+            traced(
+                warp::test::request().path("/eth/v1/beacon/states/head/validators?id=1&trace=true")
+            )
+            .await
+        );
+        assert!(
+            !traced(warp::test::request().path("/eth/v1/beacon/states/head/root?trace=false"))
+                .await
+        );
+        assert!(
+            traced(
+                warp::test::request()
+                    .path("/eth/v1/beacon/states/head/root")
+                    .header(TRACE_HEADER, "true")
+            )
+            .await
+        );
+    }
+
+    #[test]
+    fn record_in_scope() {
+        let trace = Arc::new(QueryTrace::new());
+
+        // Operations outside of a traced request are not recorded.
+        assert!(!is_enabled());
+        record("load_state", "head", None, Instant::now());
+
+        sync_scope(Some(trace.clone()), || {
+            assert!(is_enabled());
+            record("load_state", "freezer", None, Instant::now());
+            record("load_block", "database", None, Instant::now());
+        });
+        assert_eq!(trace.steps.lock().len(), 2);
+
+        let response = add_trace_header(Some(&trace), Response::default());
+        let header = response.headers().get(TRACE_HEADER).unwrap();
+        let summary: serde_json::Value = serde_json::from_slice(header.as_bytes()).unwrap();
+        assert_eq!(summary["steps"][0]["operation"], "load_state");
+        assert_eq!(summary["steps"][0]["source"], "freezer");
+        assert_eq!(summary["steps"][1]["source"], "database");
+        assert!(summary["total_ms"].is_number());
+    }
+}
@@ -0,0 +1,361 @@
+//! Rate limiting and concurrency caps for the HTTP API.
+//!
+//! The limits are applied to all routes except those used by validator clients to perform their
+//! duties, which are served by a separate set of routes in `serve` so that a misbehaving client
+//! can't starve them. Each limited request is assigned a `RouteClass` based on its path, and is
+//! subject to:
+//!
+//! - A token bucket per client IP address, refilled at `Config::rate_limit` requests per second.
+//!   Buckets are kept for the `MAX_TRACKED_CLIENTS` most recently seen clients.
+//! - A cap on the number of requests being processed concurrently, with separate budgets for
+//!   expensive state queries and for everything else. Requests beyond the cap wait in a queue of up
+//!   to `QUEUE_DEPTH_MULTIPLIER` times the cap, after which they are rejected.
+use crate::{metrics, Config};
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::net::{IpAddr, SocketAddr};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use types::non_zero_usize::new_non_zero_usize;
+use warp::filters::path::FullPath;
+use warp::{Filter, Rejection};
+
+/// The maximum number of requests waiting for a permit, as a multiple of the concurrency cap.
+const QUEUE_DEPTH_MULTIPLIER: usize = 4;
+
+/// The number of clients tracked by the rate limiter. The least recently seen client is forgotten
+/// when a new client is seen, and starts again with a full bucket.
+const MAX_TRACKED_CLIENTS: NonZeroUsize = new_non_zero_usize(4_096);
+
+/// The budget which a request is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteClass {
+    /// Queries which may require loading or processing a `BeaconState`.
+    State,
+    /// All other requests, which are typically cheap queries of the head.
+    Other,
+}
+
+impl RouteClass {
+    pub fn from_path(path: &str) -> Self {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+
+        match segments.as_slice() {
+            ["eth", _, "beacon", "rewards", ..]
+            | ["lighthouse", "analysis" | "validator_inclusion" | "committee_assignment", ..] => {
+                RouteClass::State
+            }
+            _ if segments.contains(&"states") => RouteClass::State,
+            _ => RouteClass::Other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RouteClass::State => "state",
+            RouteClass::Other => "other",
+        }
+    }
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// A token bucket for each client IP address.
+struct RateLimiter {
+    requests_per_second: f64,
+    burst: f64,
+    buckets: Mutex<LruCache<Option<IpAddr>, TokenBucket>>,
+}
+
+impl RateLimiter {
+    fn new(requests_per_second: f64, burst: f64, max_clients: NonZeroUsize) -> Self {
+        Self {
+            requests_per_second,
+            burst,
+            buckets: Mutex::new(LruCache::new(max_clients)),
+        }
+    }
+
+    /// Take a token from the bucket of `client`, returning `false` if it is empty.
+    fn allow(&self, client: Option<IpAddr>, now: Instant) -> bool {
+        let mut buckets = self.buckets.lock();
+        let bucket = buckets.get_or_insert_mut(client, || TokenBucket {
+            tokens: self.burst,
+            last_refill: now,
+        });
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * self.requests_per_second).min(self.burst);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// A cap on the number of requests of a `RouteClass` being processed concurrently.
+struct ConcurrencyLimit {
+    semaphore: Arc<Semaphore>,
+    max_queue_depth: usize,
+    queue_depth: AtomicUsize,
+}
+
+impl ConcurrencyLimit {
+    fn new(max_concurrent: usize) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(max_concurrent)),
+            max_queue_depth: max_concurrent.saturating_mul(QUEUE_DEPTH_MULTIPLIER),
+            queue_depth: AtomicUsize::new(0),
+        }
+    }
+
+    async fn acquire(&self, class: RouteClass) -> Result<OwnedSemaphorePermit, Rejection> {
+        if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
+            return Ok(permit);
+        }
+
+        // Leaves the queue when dropped, including when the request is cancelled while waiting.
+        let (_queued, queue_depth) = QueuedRequest::enter(self, class);
+        if queue_depth >= self.max_queue_depth {
+            metrics::inc_counter_vec(
+                &metrics::HTTP_API_LIMITED_REQUESTS_TOTAL,
+                &[class.as_str(), "queue_full"],
+            );
+            return Err(warp_utils::reject::too_many_requests(format!(
+                "too many concurrent {} requests",
+                class.as_str()
+            )));
+        }
+
+        self.semaphore.clone().acquire_owned().await.map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("request limiter closed: {e:?}"))
+        })
+    }
+}
+
+/// A request waiting for a `ConcurrencyLimit` permit, which leaves the queue when dropped.
+struct QueuedRequest<'a> {
+    limit: &'a ConcurrencyLimit,
+    class: RouteClass,
+}
+
+impl<'a> QueuedRequest<'a> {
+    /// Joins the queue, returning the guard and the number of requests that were already queued.
+    fn enter(limit: &'a ConcurrencyLimit, class: RouteClass) -> (Self, usize) {
+        let queue_depth = limit.queue_depth.fetch_add(1, Ordering::Relaxed);
+        metrics::inc_gauge_vec(&metrics::HTTP_API_REQUEST_QUEUE_DEPTH, &[class.as_str()]);
+        (Self { limit, class }, queue_depth)
+    }
+}
+
+impl Drop for QueuedRequest<'_> {
+    fn drop(&mut self) {
+        self.limit.queue_depth.fetch_sub(1, Ordering::Relaxed);
+        metrics::dec_gauge_vec(
+            &metrics::HTTP_API_REQUEST_QUEUE_DEPTH,
+            &[self.class.as_str()],
+        );
+    }
+}
+
+/// Held for the duration of a request, releasing its concurrency permit when dropped.
+pub struct RequestPermit {
+    class: RouteClass,
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        metrics::dec_gauge_vec(&metrics::HTTP_API_ACTIVE_REQUESTS, &[self.class.as_str()]);
+    }
+}
+
+/// Applies the rate limit and concurrency caps from the HTTP API `Config` to the routes which aren't
+/// used by validator clients to perform their duties.
+pub struct RequestLimiter {
+    rate_limiter: Option<RateLimiter>,
+    state_limit: Option<ConcurrencyLimit>,
+    other_limit: Option<ConcurrencyLimit>,
+}
+
+impl RequestLimiter {
+    pub fn new(config: &Config) -> Self {
+        let rate_limiter = config.rate_limit.map(|rate_limit| {
+            RateLimiter::new(
+                f64::from(rate_limit),
+                f64::from(config.rate_limit_burst.unwrap_or(rate_limit).max(1)),
+                MAX_TRACKED_CLIENTS,
+            )
+        });
+        Self {
+            rate_limiter,
+            state_limit: config
+                .max_concurrent_state_requests
+                .map(ConcurrencyLimit::new),
+            other_limit: config.max_concurrent_requests.map(ConcurrencyLimit::new),
+        }
+    }
+
+    async fn check(&self, path: &str, client: Option<IpAddr>) -> Result<RequestPermit, Rejection> {
+        let class = RouteClass::from_path(path);
+        let limit = match class {
+            RouteClass::State => self.state_limit.as_ref(),
+            RouteClass::Other => self.other_limit.as_ref(),
+        };
+
+        if let Some(rate_limiter) = &self.rate_limiter {
+            if !rate_limiter.allow(client, Instant::now()) {
+                metrics::inc_counter_vec(
+                    &metrics::HTTP_API_LIMITED_REQUESTS_TOTAL,
+                    &[class.as_str(), "rate_limit"],
+                );
+                return Err(warp_utils::reject::too_many_requests(
+                    "rate limit exceeded".to_string(),
+                ));
+            }
+        }
+
+        let permit = match limit {
+            Some(limit) => Some(limit.acquire(class).await?),
+            None => None,
+        };
+        metrics::inc_gauge_vec(&metrics::HTTP_API_ACTIVE_REQUESTS, &[class.as_str()]);
+
+        Ok(RequestPermit {
+            class,
+            _permit: permit,
+        })
+    }
+}
+
+/// A filter which waits for a `RequestPermit` or rejects the request if it is rate limited.
+pub fn request_limit_filter(
+    limiter: Arc<RequestLimiter>,
+) -> impl Filter<Extract = (RequestPermit,), Error = Rejection> + Clone {
+    warp::path::full().and(warp::addr::remote()).and_then(
+        move |path: FullPath, remote: Option<SocketAddr>| {
+            let limiter = limiter.clone();
+            async move {
+                limiter
+                    .check(path.as_str(), remote.map(|addr| addr.ip()))
+                    .await
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+    use std::time::Duration;
+
+    #[test]
+    fn route_classes() {
+        let class = RouteClass::from_path;
+        assert_eq!(class("/eth/v2/beacon/blocks/head"), RouteClass::Other);
+        assert_eq!(
+            class("/eth/v1/beacon/states/head/validators"),
+            RouteClass::State
+        );
+        assert_eq!(
+            class("/eth/v2/debug/beacon/states/finalized"),
+            RouteClass::State
+        );
+        assert_eq!(
+            class("/lighthouse/committee_assignment/1/2"),
+            RouteClass::State
+        );
+        assert_eq!(class("/eth/v1/node/syncing"), RouteClass::Other);
+    }
+
+    #[test]
+    fn token_bucket() {
+        let rate_limiter = RateLimiter::new(2.0, 3.0, MAX_TRACKED_CLIENTS);
+        let client = Some(IpAddr::from([127, 0, 0, 1]));
+        let other_client = Some(IpAddr::from([10, 0, 0, 1]));
+        let now = Instant::now();
+
+        // The burst is available immediately.
+        for _ in 0..3 {
+            assert!(rate_limiter.allow(client, now));
+        }
+        assert!(!rate_limiter.allow(client, now));
+
+        // Other clients have their own budget.
+        assert!(rate_limiter.allow(other_client, now));
+
+        // Tokens are refilled over time.
+        assert!(rate_limiter.allow(client, now + Duration::from_millis(500)));
+        assert!(!rate_limiter.allow(client, now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn token_bucket_clients_bounded() {
+        let rate_limiter = RateLimiter::new(1.0, 1.0, new_non_zero_usize(2));
+        let now = Instant::now();
+
+        for i in 0..4 {
+            assert!(rate_limiter.allow(Some(IpAddr::from([10, 0, 0, i])), now));
+        }
+        assert_eq!(rate_limiter.buckets.lock().len(), 2);
+
+        // The least recently seen client was forgotten and has a full bucket again.
+        assert!(rate_limiter.allow(Some(IpAddr::from([10, 0, 0, 0])), now));
+        assert!(!rate_limiter.allow(Some(IpAddr::from([10, 0, 0, 3])), now));
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_queue() {
+        let limit = ConcurrencyLimit::new(1);
+        let permit = limit.acquire(RouteClass::State).await.unwrap();
+
+        // Requests beyond the cap wait for a permit, up to the maximum queue depth.
+        let mut waiting = Box::pin(limit.acquire(RouteClass::State));
+        assert!((&mut waiting).now_or_never().is_none());
+        assert_eq!(limit.queue_depth.load(Ordering::Relaxed), 1);
+
+        drop(permit);
+        assert!(waiting.await.is_ok());
+        assert_eq!(limit.queue_depth.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_cancelled_requests_leave_queue() {
+        let limit = ConcurrencyLimit::new(1);
+        let _permit = limit.acquire(RouteClass::State).await.unwrap();
+
+        // Fill the queue, then cancel the waiting requests, as happens when clients disconnect.
+        let mut waiting = (0..limit.max_queue_depth)
+            .map(|_| Box::pin(limit.acquire(RouteClass::State)))
+            .collect::<Vec<_>>();
+        for request in &mut waiting {
+            assert!(request.now_or_never().is_none());
+        }
+        assert_eq!(
+            limit.queue_depth.load(Ordering::Relaxed),
+            limit.max_queue_depth
+        );
+        assert!(limit.acquire(RouteClass::State).await.is_err());
+
+        drop(waiting);
+        assert_eq!(limit.queue_depth.load(Ordering::Relaxed), 0);
+
+        // The queue has room again.
+        let mut request = Box::pin(limit.acquire(RouteClass::State));
+        assert!((&mut request).now_or_never().is_none());
+        assert_eq!(limit.queue_depth.load(Ordering::Relaxed), 1);
+    }
+}
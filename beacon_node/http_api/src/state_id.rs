@@ -1,9 +1,11 @@
 use crate::metrics;
+use crate::query_trace;
 use crate::ExecutionOptimistic;
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2::types::StateId as CoreStateId;
 use std::fmt;
 use std::str::FromStr;
+use std::time::Instant;
 use types::{BeaconState, Checkpoint, EthSpec, Fork, Hash256, Slot};
 
 /// Wraps `eth2::types::StateId` and provides common state-access functionality. E.g., reading
@@ -173,14 +175,16 @@ impl StateId {
         &self,
         chain: &BeaconChain<T>,
     ) -> Result<(BeaconState<T::EthSpec>, ExecutionOptimistic, Finalized), warp::Rejection> {
+        let started = Instant::now();
         let ((state_root, execution_optimistic, finalized), slot_opt) = match &self.0 {
             CoreStateId::Head => {
                 let (cached_head, execution_status) = chain
                     .canonical_head
                     .head_and_execution_status()
                     .map_err(warp_utils::reject::beacon_chain_error)?;
+                query_trace::record("load_state", "head", None, started);
                 return Ok((
-                    cached_head.snapshot.beacon_state.clone(),
+                    chain.clone_head_state(&cached_head.snapshot),
                     execution_status.is_optimistic_or_invalid(),
                     false,
                 ));
@@ -189,8 +193,8 @@ impl StateId {
             _ => (self.root(chain)?, None),
         };
 
-        let state = chain
-            .get_state(&state_root, slot_opt)
+        let (state, plan) = chain
+            .get_state_planned(&state_root, slot_opt)
             .map_err(warp_utils::reject::beacon_chain_error)
             .and_then(|opt| {
                 opt.ok_or_else(|| {
@@ -201,6 +205,8 @@ impl StateId {
                 })
             })?;
 
+        query_trace::record("load_state", plan.source.as_str(), Some(plan), started);
+
         Ok((state, execution_optimistic, finalized))
     }
 
@@ -221,10 +227,12 @@ impl StateId {
     {
         let (state, execution_optimistic, finalized) = match &self.0 {
             CoreStateId::Head => {
+                let started = Instant::now();
                 let (head, execution_status) = chain
                     .canonical_head
                     .head_and_execution_status()
                     .map_err(warp_utils::reject::beacon_chain_error)?;
+                query_trace::record("load_state", "head", None, started);
                 return func(
                     &head.snapshot.beacon_state,
                     execution_status.is_optimistic_or_invalid(),
@@ -1,11 +1,14 @@
-use crate::{BlockId, ExecutionOptimistic};
-use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use crate::{query_trace, BlockId, ExecutionOptimistic};
+use beacon_chain::{
+    BeaconChain, BeaconChainError, BeaconChainTypes, StateSkipConfig, WhenSlotSkipped,
+};
 use eth2::lighthouse::SyncCommitteeReward;
 use eth2::types::ValidatorId;
 use slog::{debug, Logger};
 use state_processing::BlockReplayer;
 use std::sync::Arc;
-use types::{BeaconState, SignedBlindedBeaconBlock};
+use std::time::Instant;
+use types::{BeaconState, Hash256, SignedBlindedBeaconBlock, Slot};
 use warp_utils::reject::{beacon_chain_error, custom_not_found};
 
 pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
@@ -47,10 +50,40 @@ pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
     Ok((data, execution_optimistic, finalized))
 }
 
+/// Returns the state at the slot of `block`, prior to `block` being applied.
+///
+/// The pre-states of canonical blocks are loaded along the cheapest path chosen by the store's
+/// planner, which allows rewards to be computed for arbitrarily old blocks from the freezer
+/// database. The pre-states of non-canonical blocks are obtained by advancing the parent state.
 pub fn get_state_before_applying_block<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     block: &SignedBlindedBeaconBlock<T::EthSpec>,
 ) -> Result<BeaconState<T::EthSpec>, warp::reject::Rejection> {
+    let is_canonical = chain
+        .block_root_at_slot(block.slot(), WhenSlotSkipped::None)
+        .map_err(beacon_chain_error)?
+        == Some(block.canonical_root());
+
+    if is_canonical && block.slot() > 0 {
+        let prior_slot = block.slot() - 1;
+        let prior_state_root = chain
+            .state_root_at_slot(prior_slot)
+            .map_err(beacon_chain_error)?
+            .ok_or_else(|| {
+                custom_not_found(format!(
+                    "Prior state at slot {} is not available!",
+                    prior_slot
+                ))
+            })?;
+        let started = Instant::now();
+        let (prior_state, plan) = chain
+            .state_at_slot_planned(prior_slot, StateSkipConfig::WithStateRoots)
+            .map_err(|e| custom_not_found(format!("Prior state is not available! {:?}", e)))?;
+        query_trace::record("load_state", plan.source.as_str(), Some(plan), started);
+
+        return advance_to_block_slot(&chain, prior_state, prior_state_root, prior_slot, block);
+    }
+
     let parent_block: SignedBlindedBeaconBlock<T::EthSpec> = chain
         .get_blinded_block(&block.parent_root())
         .and_then(|maybe_block| {
@@ -66,9 +99,26 @@ pub fn get_state_before_applying_block<T: BeaconChainTypes>(
         })
         .map_err(|e| custom_not_found(format!("Parent state is not available! {:?}", e)))?;
 
-    let replayer = BlockReplayer::new(parent_state, &chain.spec)
+    advance_to_block_slot(
+        &chain,
+        parent_state,
+        parent_block.state_root(),
+        parent_block.slot(),
+        block,
+    )
+}
+
+/// Advance `state` through the empty slots prior to `block`.
+fn advance_to_block_slot<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    state: BeaconState<T::EthSpec>,
+    state_root: Hash256,
+    state_slot: Slot,
+    block: &SignedBlindedBeaconBlock<T::EthSpec>,
+) -> Result<BeaconState<T::EthSpec>, warp::reject::Rejection> {
+    let replayer = BlockReplayer::new(state, &chain.spec)
         .no_signature_verification()
-        .state_root_iter([Ok((parent_block.state_root(), parent_block.slot()))].into_iter())
+        .state_root_iter([Ok((state_root, state_slot))].into_iter())
         .minimal_block_root_verification()
         .apply_blocks(vec![], Some(block.slot()))
         .map_err(beacon_chain_error)?;
@@ -1,6 +1,8 @@
+use crate::query_trace::{self, QueryTrace};
 use beacon_processor::{BeaconProcessorSend, BlockingOrAsync, Work, WorkEvent};
 use serde::Serialize;
 use std::future::Future;
+use std::sync::Arc;
 use tokio::sync::{mpsc::error::TrySendError, oneshot};
 use types::EthSpec;
 use warp::reply::{Reply, Response};
@@ -34,15 +36,24 @@ pub struct TaskSpawner<E: EthSpec> {
     /// Used to send tasks to the `BeaconProcessor`. The tokio executor will be
     /// used if this is `None`.
     beacon_processor_send: Option<BeaconProcessorSend<E>>,
+    /// Records the operations performed by the task, if the request is being traced.
+    trace: Option<Arc<QueryTrace>>,
 }
 
 impl<E: EthSpec> TaskSpawner<E> {
     pub fn new(beacon_processor_send: Option<BeaconProcessorSend<E>>) -> Self {
         Self {
             beacon_processor_send,
+            trace: None,
         }
     }
 
+    /// Record the operations performed by tasks in `trace`.
+    pub fn with_trace(mut self, trace: Option<Arc<QueryTrace>>) -> Self {
+        self.trace = trace;
+        self
+    }
+
     /// Executes a "blocking" (non-async) task which returns an arbitrary value.
     pub async fn blocking_task<F, T>(
         self,
@@ -53,6 +64,9 @@ impl<E: EthSpec> TaskSpawner<E> {
         F: FnOnce() -> Result<T, warp::Rejection> + Send + Sync + 'static,
         T: Send + 'static,
     {
+        let trace = self.trace.clone();
+        let func = move || query_trace::sync_scope(trace, func);
+
         if let Some(beacon_processor_send) = &self.beacon_processor_send {
             // Create a closure that will execute `func` and send the result to
             // a channel held by this thread.
@@ -87,8 +101,9 @@ impl<E: EthSpec> TaskSpawner<E> {
         F: FnOnce() -> Result<T, warp::Rejection> + Send + Sync + 'static,
         T: Reply + Send + 'static,
     {
+        let trace = self.trace.clone();
         let result = self.blocking_task(priority, func).await;
-        convert_rejection(result).await
+        query_trace::add_trace_header(trace.as_deref(), convert_rejection(result).await)
     }
 
     /// Executes a "blocking" (non-async) task which returns a JSON-serializable
@@ -108,10 +123,11 @@ impl<E: EthSpec> TaskSpawner<E> {
         priority: Priority,
         func: impl Future<Output = Result<Response, warp::Rejection>> + Send + Sync + 'static,
     ) -> Response {
+        let trace = self.trace.clone();
         let result = self
             .spawn_async_with_rejection_no_conversion(priority, func)
             .await;
-        convert_rejection(result).await
+        query_trace::add_trace_header(trace.as_deref(), convert_rejection(result).await)
     }
 
     /// Same as `spawn_async_with_rejection` but returning a result with the unhandled rejection.
@@ -123,6 +139,8 @@ impl<E: EthSpec> TaskSpawner<E> {
         priority: Priority,
         func: impl Future<Output = Result<Response, warp::Rejection>> + Send + Sync + 'static,
     ) -> Result<Response, warp::Rejection> {
+        let func = query_trace::scope(self.trace.clone(), func);
+
         if let Some(beacon_processor_send) = &self.beacon_processor_send {
             // Create a wrapper future that will execute `func` and send the
             // result to a channel held by this thread.
@@ -205,6 +205,7 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
         beacon_processor_rx,
         work_reprocessing_tx,
         work_reprocessing_rx,
+        queue_monitor,
     } = BeaconProcessorChannels::new(&beacon_processor_config);
 
     let beacon_processor_send = beacon_processor_tx;
@@ -214,6 +215,7 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
         executor: test_runtime.task_executor.clone(),
         current_workers: 0,
         config: beacon_processor_config,
+        queue_monitor: queue_monitor.clone(),
         log: log.clone(),
     }
     .spawn_manager(
@@ -246,8 +248,10 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
         network_globals: Some(network_globals),
         beacon_processor_send: Some(beacon_processor_send),
         beacon_processor_reprocess_send: Some(reprocess_send),
+        beacon_processor_queue_monitor: Some(queue_monitor),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
+        log_level: None,
         log,
     });
 
@@ -0,0 +1,78 @@
+//! Contains the handler for the `POST lighthouse/validators/duties` endpoint.
+
+use crate::{attester_duties, proposer_duties, sync_committees};
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{EpochDuties, ValidatorDutiesRequest};
+use slog::Logger;
+use slot_clock::SlotClock;
+use std::collections::HashSet;
+use types::{Epoch, EthSpec};
+
+/// The maximum number of epochs which may be requested at once.
+pub const MAX_DUTIES_EPOCHS: u64 = 8;
+
+/// Returns the attester, proposer and sync committee duties of the requested validators for each
+/// epoch in the requested range.
+///
+/// The range may end at most one epoch past the current epoch, since the shuffling of later epochs
+/// depends on RANDAO reveals that haven't been made yet. Only ranges of past epochs can use the
+/// full `MAX_DUTIES_EPOCHS`.
+///
+/// Each epoch is subject to the same restrictions as the standard duties endpoints, and the request
+/// fails if the duties for any epoch can't be computed.
+pub fn validator_duties<T: BeaconChainTypes>(
+    request: ValidatorDutiesRequest,
+    chain: &BeaconChain<T>,
+    log: &Logger,
+) -> Result<Vec<EpochDuties>, warp::reject::Rejection> {
+    let ValidatorDutiesRequest {
+        indices,
+        start_epoch,
+        end_epoch,
+    } = request;
+
+    if end_epoch < start_epoch {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "end_epoch {end_epoch} is before start_epoch {start_epoch}"
+        )));
+    }
+    if (end_epoch - start_epoch).as_u64() >= MAX_DUTIES_EPOCHS {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "at most {MAX_DUTIES_EPOCHS} epochs may be requested"
+        )));
+    }
+
+    // Allow for clock disparity in the same way as the attester duties endpoint.
+    let tolerant_current_epoch = chain
+        .slot_clock
+        .now_with_future_tolerance(chain.spec.maximum_gossip_clock_disparity())
+        .ok_or_else(|| warp_utils::reject::custom_server_error("unable to read slot clock".into()))?
+        .epoch(T::EthSpec::slots_per_epoch());
+    let latest_epoch = tolerant_current_epoch + 1;
+    if end_epoch > latest_epoch {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "end_epoch {end_epoch} is past {latest_epoch}, the latest epoch with known duties"
+        )));
+    }
+
+    let requested_indices = indices.iter().copied().collect::<HashSet<_>>();
+
+    (start_epoch.as_u64()..=end_epoch.as_u64())
+        .map(|epoch| {
+            let epoch = Epoch::new(epoch);
+            let attester = attester_duties::attester_duties(epoch, &indices, chain)?;
+            let mut proposer = proposer_duties::proposer_duties(epoch, chain, log)?;
+            proposer
+                .data
+                .retain(|duty| requested_indices.contains(&duty.validator_index));
+            let sync = sync_committees::sync_committee_duties(epoch, &indices, chain)?;
+
+            Ok(EpochDuties {
+                epoch,
+                attester,
+                proposer,
+                sync,
+            })
+        })
+        .collect()
+}
@@ -1,18 +1,109 @@
+//! Contains the handlers for the `GET lighthouse/validator_inclusion/{epoch}/...` endpoints.
+//!
+//! The inclusion of validators during an epoch is determined by running epoch processing on the
+//! state at the end of the epoch. Once the epoch is finalized the result can't change, so the
+//! summaries of finalized epochs are cached. This allows dashboards to query many validators of
+//! an old epoch without reloading its state (possibly from the freezer) for each of them.
 use crate::state_id::StateId;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2::{
     lighthouse::{GlobalValidatorInclusionData, ValidatorInclusionData},
     types::ValidatorId,
 };
+use lru::LruCache;
+use parking_lot::Mutex;
 use state_processing::per_epoch_processing::{process_epoch, EpochProcessingSummary};
-use types::{BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec};
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{BeaconState, BeaconStateError, ChainSpec, Epoch, EthSpec, List, Validator};
+
+/// The number of finalized epochs for which inclusion summaries are cached.
+const CACHED_EPOCHS: NonZeroUsize = new_non_zero_usize(8);
+
+/// The states of finalized epochs which are estimated to cost more than this to load are not
+/// served. See `store::state_load_plan::cost` for the units.
+///
+/// With the default diff hierarchy any state in the freezer can be loaded well within this bound.
+pub const MAX_STATE_LOAD_COST: u64 = 20_000;
+
+/// The inclusion of all validators during an epoch.
+pub struct EpochInclusion<E: EthSpec> {
+    /// The validator registry at the end of the epoch, prior to epoch processing.
+    validators: List<Validator, E::ValidatorRegistryLimit>,
+    summary: EpochProcessingSummary<E>,
+}
+
+/// Caches the `EpochInclusion` of recently requested finalized epochs.
+pub struct ValidatorInclusionCache<E: EthSpec> {
+    epochs: Mutex<LruCache<Epoch, Arc<EpochInclusion<E>>>>,
+}
+
+impl<E: EthSpec> Default for ValidatorInclusionCache<E> {
+    fn default() -> Self {
+        Self {
+            epochs: Mutex::new(LruCache::new(CACHED_EPOCHS)),
+        }
+    }
+}
+
+impl<E: EthSpec> ValidatorInclusionCache<E> {
+    /// Returns the inclusion of all validators during `epoch`, from the cache if possible.
+    fn get_or_compute<T: BeaconChainTypes<EthSpec = E>>(
+        &self,
+        epoch: Epoch,
+        chain: &BeaconChain<T>,
+    ) -> Result<Arc<EpochInclusion<E>>, warp::Rejection> {
+        if let Some(inclusion) = self.epochs.lock().get(&epoch) {
+            return Ok(inclusion.clone());
+        }
+
+        let inclusion = Arc::new(epoch_inclusion(epoch, chain)?);
+
+        // The end of the epoch is finalized once a later epoch is finalized.
+        let finalized_epoch = chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch;
+        if epoch < finalized_epoch {
+            self.epochs.lock().put(epoch, inclusion.clone());
+        }
+
+        Ok(inclusion)
+    }
+}
 
 /// Returns the state in the last slot of `epoch`.
+///
+/// Requests for finalized epochs whose state has been pruned, or would be too costly to load, are
+/// rejected.
 fn end_of_epoch_state<T: BeaconChainTypes>(
     epoch: Epoch,
     chain: &BeaconChain<T>,
 ) -> Result<BeaconState<T::EthSpec>, warp::reject::Rejection> {
     let target_slot = epoch.end_slot(T::EthSpec::slots_per_epoch());
+
+    if target_slot < chain.store.get_split_slot() {
+        let plan = chain
+            .store
+            .plan_cold_state_load(target_slot)
+            .map_err(|e| match e {
+                store::Error::Era(store::era::EraError::MissingEraState { .. }) => {
+                    warp_utils::reject::custom_not_found(format!(
+                        "the state at the end of epoch {epoch} is not stored"
+                    ))
+                }
+                e => warp_utils::reject::custom_server_error(format!("{e:?}")),
+            })?;
+        if plan.cost > MAX_STATE_LOAD_COST {
+            return Err(warp_utils::reject::custom_bad_request(format!(
+                "the state at the end of epoch {epoch} is too costly to load (cost {})",
+                plan.cost
+            )));
+        }
+    }
+
     // The execution status is not returned, any functions which rely upon this method might return
     // optimistic information without explicitly declaring so.
     let (state, _execution_status, _finalized) = StateId::from_slot(target_slot).state(chain)?;
@@ -32,6 +123,21 @@ fn get_epoch_processing_summary<E: EthSpec>(
         .map_err(|e| warp_utils::reject::custom_server_error(format!("{:?}", e)))
 }
 
+/// Computes the inclusion of all validators during `epoch`.
+fn epoch_inclusion<T: BeaconChainTypes>(
+    epoch: Epoch,
+    chain: &BeaconChain<T>,
+) -> Result<EpochInclusion<T::EthSpec>, warp::Rejection> {
+    let mut state = end_of_epoch_state(epoch, chain)?;
+    // Obtain the validators *before* transitioning the state into the next epoch.
+    let validators = state.validators().clone();
+    let summary = get_epoch_processing_summary(&mut state, &chain.spec)?;
+    Ok(EpochInclusion {
+        validators,
+        summary,
+    })
+}
+
 fn convert_cache_error(error: BeaconStateError) -> warp::reject::Rejection {
     warp_utils::reject::custom_server_error(format!("{:?}", error))
 }
@@ -41,9 +147,10 @@ fn convert_cache_error(error: BeaconStateError) -> warp::reject::Rejection {
 pub fn global_validator_inclusion_data<T: BeaconChainTypes>(
     epoch: Epoch,
     chain: &BeaconChain<T>,
+    cache: &ValidatorInclusionCache<T::EthSpec>,
 ) -> Result<GlobalValidatorInclusionData, warp::Rejection> {
-    let mut state = end_of_epoch_state(epoch, chain)?;
-    let summary = get_epoch_processing_summary(&mut state, &chain.spec)?;
+    let inclusion = cache.get_or_compute(epoch, chain)?;
+    let summary = &inclusion.summary;
 
     Ok(GlobalValidatorInclusionData {
         current_epoch_active_gwei: summary.current_epoch_total_active_balance(),
@@ -64,19 +171,16 @@ pub fn validator_inclusion_data<T: BeaconChainTypes>(
     epoch: Epoch,
     validator_id: &ValidatorId,
     chain: &BeaconChain<T>,
+    cache: &ValidatorInclusionCache<T::EthSpec>,
 ) -> Result<Option<ValidatorInclusionData>, warp::Rejection> {
-    let mut state = end_of_epoch_state(epoch, chain)?;
-
-    state
-        .update_pubkey_cache()
-        .map_err(warp_utils::reject::beacon_state_error)?;
-
     let validator_index = match validator_id {
         ValidatorId::Index(index) => *index as usize,
         ValidatorId::PublicKey(pubkey) => {
-            if let Some(index) = state
-                .get_validator_index(pubkey)
-                .map_err(warp_utils::reject::beacon_state_error)?
+            // Indices are never reassigned, so the index known to the head is valid for any
+            // epoch in which the validator exists.
+            if let Some(index) = chain
+                .validator_index(pubkey)
+                .map_err(warp_utils::reject::beacon_chain_error)?
             {
                 index
             } else {
@@ -85,14 +189,11 @@ pub fn validator_inclusion_data<T: BeaconChainTypes>(
         }
     };
 
-    // Obtain the validator *before* transitioning the state into the next epoch.
-    let validator = if let Ok(validator) = state.get_validator(validator_index) {
-        validator.clone()
-    } else {
+    let inclusion = cache.get_or_compute(epoch, chain)?;
+    let Some(validator) = inclusion.validators.get(validator_index) else {
         return Ok(None);
     };
-
-    let summary = get_epoch_processing_summary(&mut state, &chain.spec)?;
+    let summary = &inclusion.summary;
 
     Ok(Some(ValidatorInclusionData {
         is_slashed: validator.slashed,
@@ -0,0 +1,59 @@
+//! Contains the handler for the `POST lighthouse/validator_projection` endpoint.
+
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{ProjectedBalance, ValidatorProjection, ValidatorProjectionRequest};
+use state_processing::validator_projection::project_validator_balances;
+
+/// The maximum number of epochs which may be projected at once.
+pub const MAX_PROJECTION_EPOCHS: u64 = 256;
+
+/// Returns the projected balances of the requested validators, starting from the head state.
+///
+/// See `state_processing::validator_projection` for the assumptions made by the projection.
+pub fn validator_projection<T: BeaconChainTypes>(
+    request: ValidatorProjectionRequest,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<ValidatorProjection>, warp::reject::Rejection> {
+    let ValidatorProjectionRequest { indices, epochs } = request;
+
+    if epochs > MAX_PROJECTION_EPOCHS {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "at most {MAX_PROJECTION_EPOCHS} epochs may be projected"
+        )));
+    }
+
+    let mut state = chain.head_beacon_state_cloned();
+    if let Some(index) = indices
+        .iter()
+        .find(|&&index| index as usize >= state.validators().len())
+    {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "unknown validator index {index}"
+        )));
+    }
+    state
+        .build_total_active_balance_cache(&chain.spec)
+        .map_err(warp_utils::reject::beacon_state_error)?;
+
+    let projections =
+        project_validator_balances(&state, &indices, epochs, &chain.spec).map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("unable to project balances: {e:?}"))
+        })?;
+
+    Ok(projections
+        .into_iter()
+        .map(|projection| ValidatorProjection {
+            validator_index: projection.validator_index,
+            balances: projection
+                .balances
+                .into_iter()
+                .map(|projected| ProjectedBalance {
+                    epoch: projected.epoch,
+                    balance: projected.balance,
+                    effective_balance: projected.effective_balance,
+                    pending_deposits: projected.pending_deposits,
+                })
+                .collect(),
+        })
+        .collect())
+}
@@ -2,9 +2,21 @@ use crate::state_id::StateId;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2::types::{
     self as api_types, ExecutionOptimisticFinalizedResponse, ValidatorBalanceData, ValidatorData,
-    ValidatorId, ValidatorStatus,
+    ValidatorId, ValidatorStatus, ValidatorsPageQuery,
 };
+use futures::stream::{self, StreamExt};
 use std::{collections::HashSet, sync::Arc};
+use types::{BeaconState, Epoch, EthSpec};
+use warp::http::header::CONTENT_TYPE;
+use warp::http::Response;
+use warp::hyper::Body;
+
+/// The number of validators returned per page if the request doesn't specify a limit.
+pub const DEFAULT_VALIDATORS_PAGE_LIMIT: usize = 1_000;
+/// The maximum number of validators which may be requested in a single page.
+pub const MAX_VALIDATORS_PAGE_LIMIT: usize = 100_000;
+/// The number of validators serialized into each chunk of a streamed page.
+const VALIDATORS_PAGE_CHUNK_SIZE: usize = 1_000;
 
 pub fn get_beacon_state_validators<T: BeaconChainTypes>(
     state_id: StateId,
@@ -114,3 +126,117 @@ pub fn get_beacon_state_validator_balances<T: BeaconChainTypes>(
         finalized: Some(finalized),
     })
 }
+
+/// Returns a single page of the validators in a state, serialized to JSON as the response body is
+/// streamed so that the whole page is never buffered in memory.
+///
+/// The page token is the index of the first validator which has not yet been considered, so
+/// paging is stable even if the state changes between requests.
+pub fn get_beacon_state_validators_page<T: BeaconChainTypes>(
+    state_id: StateId,
+    chain: Arc<BeaconChain<T>>,
+    query: ValidatorsPageQuery,
+) -> Result<Response<Body>, warp::Rejection> {
+    let limit = query.limit.unwrap_or(DEFAULT_VALIDATORS_PAGE_LIMIT);
+    if limit == 0 || limit > MAX_VALIDATORS_PAGE_LIMIT {
+        return Err(warp_utils::reject::custom_bad_request(format!(
+            "limit must be between 1 and {MAX_VALIDATORS_PAGE_LIMIT}"
+        )));
+    }
+    let start_index = query
+        .page_token
+        .map(|page_token| {
+            page_token.parse::<usize>().map_err(|_| {
+                warp_utils::reject::custom_bad_request(format!("invalid page token: {page_token}"))
+            })
+        })
+        .transpose()?
+        .unwrap_or(0);
+
+    let (state, execution_optimistic, finalized) = state_id.state(&chain)?;
+    let epoch = state.current_epoch();
+    let far_future_epoch = chain.spec.far_future_epoch;
+    let ids_filter_set: Option<HashSet<&ValidatorId>> = query.id.as_ref().map(HashSet::from_iter);
+
+    // Find the indices of the validators on this page before serializing any of them, so that the
+    // next page token can be included ahead of the data.
+    let mut indices = Vec::with_capacity(std::cmp::min(limit, state.validators().len()));
+    let mut next_page_token = None;
+    for (index, validator) in state.validators().iter().enumerate().skip(start_index) {
+        if indices.len() == limit {
+            next_page_token = Some(index.to_string());
+            break;
+        }
+
+        let id_matches = ids_filter_set.as_ref().map_or(true, |ids_set| {
+            ids_set.contains(&ValidatorId::PublicKey(validator.pubkey))
+                || ids_set.contains(&ValidatorId::Index(index as u64))
+        });
+        let status_matches = query.status.as_ref().map_or(true, |statuses| {
+            let status = ValidatorStatus::from_validator(validator, epoch, far_future_epoch);
+            statuses.contains(&status) || statuses.contains(&status.superstatus())
+        });
+
+        if id_matches && status_matches {
+            indices.push(index);
+        }
+    }
+
+    let prefix = format!(
+        r#"{{"execution_optimistic":{execution_optimistic},"finalized":{finalized},"next_page_token":{},"data":["#,
+        serde_json::to_string(&next_page_token).map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("failed to serialize page: {e}"))
+        })?
+    );
+    let chunks = indices
+        .chunks(VALIDATORS_PAGE_CHUNK_SIZE)
+        .map(<[usize]>::to_vec)
+        .collect::<Vec<_>>();
+
+    let body = stream::iter([Ok(prefix.into_bytes())])
+        .chain(
+            stream::iter(chunks.into_iter().enumerate()).map(move |(i, chunk)| {
+                serialize_validators_chunk(&state, &chunk, i == 0, epoch, far_future_epoch)
+            }),
+        )
+        .chain(stream::iter([Ok(b"]}".to_vec())]));
+
+    Response::builder()
+        .status(200)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::wrap_stream(body))
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("failed to create response: {e}"))
+        })
+}
+
+/// Serialize the validators at `indices` as comma-separated JSON objects.
+fn serialize_validators_chunk<E: EthSpec>(
+    state: &BeaconState<E>,
+    indices: &[usize],
+    first_chunk: bool,
+    epoch: Epoch,
+    far_future_epoch: Epoch,
+) -> Result<Vec<u8>, serde_json::Error> {
+    let mut bytes = vec![];
+    for (i, &index) in indices.iter().enumerate() {
+        let (Some(validator), Some(balance)) =
+            (state.validators().get(index), state.balances().get(index))
+        else {
+            continue;
+        };
+        if !(first_chunk && i == 0) {
+            bytes.push(b',');
+        }
+        serde_json::to_writer(
+            &mut bytes,
+            &ValidatorData {
+                index: index as u64,
+                balance: *balance,
+                status: ValidatorStatus::from_validator(validator, epoch, far_future_epoch),
+                validator: validator.clone(),
+            },
+        )?;
+    }
+    Ok(bytes)
+}
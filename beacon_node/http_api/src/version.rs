@@ -1,7 +1,8 @@
 use crate::api_types::EndpointVersion;
 use eth2::{
     CONSENSUS_BLOCK_VALUE_HEADER, CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER,
-    EXECUTION_PAYLOAD_BLINDED_HEADER, EXECUTION_PAYLOAD_VALUE_HEADER, SSZ_CONTENT_TYPE_HEADER,
+    EXECUTION_OPTIMISTIC_HEADER, EXECUTION_PAYLOAD_BLINDED_HEADER, EXECUTION_PAYLOAD_VALUE_HEADER,
+    FINALIZED_HEADER, SSZ_CONTENT_TYPE_HEADER,
 };
 use serde::Serialize;
 use types::{
@@ -64,11 +65,42 @@ pub fn add_ssz_content_type_header<T: Reply>(reply: T) -> Response {
     reply::with_header(reply, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER).into_response()
 }
 
+/// Build a `200 OK` response with the given SSZ bytes as its body, carrying the
+/// execution-optimistic and finalized status that JSON responses include as metadata.
+pub fn ssz_response(
+    bytes: Vec<u8>,
+    execution_optimistic: bool,
+    finalized: bool,
+) -> Result<Response, warp::reject::Rejection> {
+    warp::http::Response::builder()
+        .status(200)
+        .body(bytes.into())
+        .map(add_ssz_content_type_header)
+        .map(|res| add_execution_optimistic_finalized_headers(res, execution_optimistic, finalized))
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!("failed to create response: {}", e))
+        })
+}
+
 /// Add the `Eth-Consensus-Version` header to a response.
 pub fn add_consensus_version_header<T: Reply>(reply: T, fork_name: ForkName) -> Response {
     reply::with_header(reply, CONSENSUS_VERSION_HEADER, fork_name.to_string()).into_response()
 }
 
+/// Add the `Eth-Execution-Optimistic` and `Eth-Finalized` headers to a response.
+pub fn add_execution_optimistic_finalized_headers<T: Reply>(
+    reply: T,
+    execution_optimistic: bool,
+    finalized: bool,
+) -> Response {
+    let reply = reply::with_header(
+        reply,
+        EXECUTION_OPTIMISTIC_HEADER,
+        execution_optimistic.to_string(),
+    );
+    reply::with_header(reply, FINALIZED_HEADER, finalized.to_string()).into_response()
+}
+
 /// Add the `Eth-Execution-Payload-Blinded` header to a response.
 pub fn add_execution_payload_blinded_header<T: Reply>(
     reply: T,
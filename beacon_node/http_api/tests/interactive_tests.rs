@@ -2,11 +2,13 @@
 use beacon_chain::{
     chain_config::{DisallowedReOrgOffsets, ReOrgThreshold},
     test_utils::{AttestationStrategy, BlockStrategy, LightClientStrategy, SyncCommitteeStrategy},
-    ChainConfig,
+    ChainConfig, WhenSlotSkipped,
 };
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
+use eth2::lighthouse::BeaconProcessorQueueUpdate;
 use eth2::types::ProduceBlockV3Response;
-use eth2::types::{DepositContractData, StateId};
+use eth2::types::{BlockId, DepositContractData, StateId};
+use eth2::StatusCode;
 use execution_layer::{ForkchoiceState, PayloadAttributes};
 use http_api::test_utils::InteractiveTester;
 use parking_lot::Mutex;
@@ -122,6 +124,60 @@ async fn state_by_root_pruned_from_fork_choice() {
     }
 }
 
+// Test that block and sync committee rewards can be computed for blocks which have been migrated to
+// the freezer database, and that they match the rewards computed while the blocks were recent.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn rewards_for_historical_blocks() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 24;
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let client = &tester.client;
+    let harness = &tester.harness;
+
+    let extend_chain = |num_slots: u64| {
+        harness.extend_chain_with_sync(
+            num_slots as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+            LightClientStrategy::Disabled,
+        )
+    };
+
+    harness.advance_slot();
+    extend_chain(E::slots_per_epoch() + 2).await;
+
+    // Compute rewards for a block while its pre-state is still in the hot database.
+    let block_id = BlockId::Slot(Slot::new(E::slots_per_epoch() + 1));
+    let recent_block_rewards = client.get_beacon_rewards_blocks(block_id).await.unwrap();
+    let recent_sync_committee_rewards = client
+        .post_beacon_rewards_sync_committee(block_id, &[])
+        .await
+        .unwrap();
+    assert!(!recent_block_rewards.finalized.unwrap());
+    assert!(recent_block_rewards.data.total > 0);
+    assert!(recent_sync_committee_rewards.data.is_some());
+
+    // Finalize the block and migrate its pre-state to the freezer.
+    extend_chain(4 * E::slots_per_epoch()).await;
+    assert!(harness.chain.store.get_split_slot() > E::slots_per_epoch() + 1);
+
+    let historical_block_rewards = client.get_beacon_rewards_blocks(block_id).await.unwrap();
+    let historical_sync_committee_rewards = client
+        .post_beacon_rewards_sync_committee(block_id, &[])
+        .await
+        .unwrap();
+    assert!(historical_block_rewards.finalized.unwrap());
+    assert_eq!(historical_block_rewards.data, recent_block_rewards.data);
+    assert_eq!(
+        historical_sync_committee_rewards.data,
+        recent_sync_committee_rewards.data
+    );
+}
+
 /// Data structure for tracking fork choice updates received by the mock execution layer.
 #[derive(Debug, Default)]
 struct ForkChoiceUpdates {
@@ -928,3 +984,219 @@ async fn queue_attestations_from_http() {
 
     attestation_future.await.unwrap();
 }
+
+// Test that the admin endpoints require the configured token.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_endpoints_require_token() {
+    let validator_count = 24;
+    let token_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(token_file.path(), "secret\n").unwrap();
+
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        None,
+        validator_count,
+        None,
+        None,
+        http_api::Config {
+            admin_token_file: Some(token_file.path().to_path_buf()),
+            ..Default::default()
+        },
+    )
+    .await;
+    let client = &tester.client;
+
+    // Requests without the correct token are rejected.
+    for token in ["", "wrong"] {
+        let err = client
+            .post_lighthouse_admin_flush_caches(token)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+    }
+
+    client
+        .post_lighthouse_admin_flush_caches("secret")
+        .await
+        .unwrap();
+
+    // Pruning is scheduled on the migrator, whether or not blobs can be pruned yet.
+    client.post_lighthouse_admin_prune("secret").await.unwrap();
+
+    let status = client
+        .post_lighthouse_admin_validator_monitor("secret", true)
+        .await
+        .unwrap()
+        .data;
+    assert!(status.auto_register);
+    assert!(tester
+        .harness
+        .chain
+        .validator_monitor
+        .read()
+        .auto_register());
+
+    // The log level can't be changed because the tester doesn't use a runtime log level.
+    let err = client
+        .post_lighthouse_admin_log_level("secret", "debug")
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+// Test that the beacon processor queues can be inspected and adjusted via the admin API.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_processor_queues() {
+    let validator_count = 24;
+    let token_file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(token_file.path(), "secret\n").unwrap();
+
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        None,
+        validator_count,
+        None,
+        None,
+        http_api::Config {
+            admin_token_file: Some(token_file.path().to_path_buf()),
+            ..Default::default()
+        },
+    )
+    .await;
+    let client = &tester.client;
+
+    let queues = client.get_lighthouse_beacon_processor().await.unwrap().data;
+    let attestation_queue = queues
+        .iter()
+        .find(|queue| queue.name == "gossip_attestation")
+        .unwrap();
+    assert!(!attestation_queue.paused);
+    assert!(!attestation_queue.prioritized);
+    assert!(attestation_queue.max_length > 0);
+
+    let update = BeaconProcessorQueueUpdate {
+        name: "gossip_attestation".to_string(),
+        max_length: Some(16),
+        paused: Some(true),
+        prioritized: Some(true),
+    };
+    let err = client
+        .post_lighthouse_admin_beacon_processor("wrong", &update)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+    let queue = client
+        .post_lighthouse_admin_beacon_processor("secret", &update)
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(queue.max_length, 16);
+    assert!(queue.paused);
+    assert!(queue.prioritized);
+
+    // Fields which aren't provided are left unchanged.
+    let queue = client
+        .post_lighthouse_admin_beacon_processor(
+            "secret",
+            &BeaconProcessorQueueUpdate {
+                name: "gossip_attestation".to_string(),
+                paused: Some(false),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap()
+        .data;
+    assert_eq!(queue.max_length, 16);
+    assert!(!queue.paused);
+    assert!(queue.prioritized);
+
+    for update in [
+        BeaconProcessorQueueUpdate {
+            name: "unknown".to_string(),
+            paused: Some(true),
+            ..Default::default()
+        },
+        BeaconProcessorQueueUpdate {
+            name: "gossip_attestation".to_string(),
+            max_length: Some(0),
+            ..Default::default()
+        },
+    ] {
+        let err = client
+            .post_lighthouse_admin_beacon_processor("secret", &update)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+    }
+}
+
+// Test that the admin endpoints are disabled without a token file.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_endpoints_disabled_by_default() {
+    let tester = InteractiveTester::<E>::new(None, 24).await;
+    let err = tester
+        .client
+        .post_lighthouse_admin_flush_caches("secret")
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
+
+// Test that liveness is determined from on-chain participation for epochs which are no longer in
+// the observation caches.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn historical_validator_liveness() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 24;
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+    let client = &tester.client;
+    let harness = &tester.harness;
+
+    let attesters = (0..validator_count / 2).collect::<Vec<_>>();
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            4 * E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::SomeValidators(attesters.clone()),
+            SyncCommitteeStrategy::AllValidators,
+            LightClientStrategy::Disabled,
+        )
+        .await;
+
+    let epoch = Epoch::new(1);
+    assert!(epoch + 1 < harness.chain.epoch().unwrap());
+    let proposers = epoch
+        .slot_iter(E::slots_per_epoch())
+        .map(|slot| {
+            harness
+                .chain
+                .block_at_slot(slot, WhenSlotSkipped::None)
+                .unwrap()
+                .unwrap()
+                .message()
+                .proposer_index() as usize
+        })
+        .collect::<Vec<_>>();
+
+    let indices = (0..validator_count as u64 + 1).collect::<Vec<_>>();
+    let liveness = client
+        .post_validator_liveness_epoch(epoch, &indices)
+        .await
+        .unwrap()
+        .data;
+    for data in liveness {
+        let index = data.index as usize;
+        let expected = attesters.contains(&index) || proposers.contains(&index);
+        assert_eq!(data.is_live, expected, "validator {index}");
+    }
+
+    let err = client
+        .post_lighthouse_liveness(&indices, harness.chain.epoch().unwrap() + 2)
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+}
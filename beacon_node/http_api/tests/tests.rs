@@ -1,9 +1,10 @@
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
-    BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
+    BeaconChain, ChainConfig, ServerSentEventHandler, StateSkipConfig, WhenSlotSkipped,
 };
 use eth2::{
+    lighthouse::HeadTimelineEventKind,
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{
@@ -11,7 +12,7 @@ use eth2::{
     },
     BeaconNodeHttpClient, Error,
     Error::ServerMessage,
-    StatusCode, Timeouts,
+    StatusCode, Timeouts, EXECUTION_OPTIMISTIC_HEADER, FINALIZED_HEADER,
 };
 use execution_layer::test_utils::{
     MockBuilder, Operation, DEFAULT_BUILDER_PAYLOAD_VALUE_WEI, DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
@@ -22,7 +23,10 @@ use http_api::{
     test_utils::{create_api_server, ApiServer},
     BlockId, StateId,
 };
-use lighthouse_network::{types::SyncState, Enr, EnrExt, PeerId};
+use lighthouse_network::{
+    types::{FailoverRole, SyncState},
+    Enr, EnrExt, PeerId,
+};
 use logging::test_logger;
 use network::NetworkReceivers;
 use proto_array::ExecutionStatus;
@@ -83,6 +87,7 @@ struct ApiTester {
 struct ApiTesterConfig {
     spec: ChainSpec,
     retain_historic_states: bool,
+    sse_replay_window: Option<Duration>,
 }
 
 impl Default for ApiTesterConfig {
@@ -92,6 +97,7 @@ impl Default for ApiTesterConfig {
         Self {
             spec,
             retain_historic_states: false,
+            sse_replay_window: None,
         }
     }
 }
@@ -101,6 +107,11 @@ impl ApiTesterConfig {
         self.retain_historic_states = true;
         self
     }
+
+    fn sse_replay_window(mut self, window: Duration) -> Self {
+        self.sse_replay_window = Some(window);
+        self
+    }
 }
 
 impl ApiTester {
@@ -124,7 +135,7 @@ impl ApiTester {
     pub async fn new_from_config(config: ApiTesterConfig) -> Self {
         let spec = Arc::new(config.spec);
 
-        let mut harness = BeaconChainHarness::builder(MainnetEthSpec)
+        let mut harness_builder = BeaconChainHarness::builder(MainnetEthSpec)
             .spec(spec.clone())
             .chain_config(ChainConfig {
                 reconstruct_historic_states: config.retain_historic_states,
@@ -134,8 +145,16 @@ impl ApiTester {
             .deterministic_keypairs(VALIDATOR_COUNT)
             .deterministic_withdrawal_keypairs(VALIDATOR_COUNT)
             .fresh_ephemeral_store()
-            .mock_execution_layer_with_config()
-            .build();
+            .mock_execution_layer_with_config();
+        if let Some(window) = config.sse_replay_window {
+            harness_builder = harness_builder.initial_mutator(Box::new(move |builder| {
+                builder.event_handler(Some(
+                    ServerSentEventHandler::new_with_capacity(test_logger(), 5)
+                        .with_replay_window(window),
+                ))
+            }));
+        }
+        let mut harness = harness_builder.build();
 
         harness
             .mock_execution_layer
@@ -410,6 +429,7 @@ impl ApiTester {
         let mut config = ApiTesterConfig {
             retain_historic_states: false,
             spec: E::default_spec(),
+            sse_replay_window: None,
         };
         config.spec.altair_fork_epoch = Some(Epoch::new(0));
         config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -1033,6 +1053,60 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_states_validators_page(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            for statuses in self.interesting_validator_statuses() {
+                let statuses = (!statuses.is_empty()).then_some(statuses.as_slice());
+                let expected = self
+                    .client
+                    .get_beacon_states_validators(state_id.0, None, statuses)
+                    .await
+                    .unwrap()
+                    .map(|res| res.data);
+
+                // Page through the validators three at a time.
+                let mut pages = vec![];
+                let mut page_token = None;
+                let result = loop {
+                    let Some(page) = self
+                        .client
+                        .get_beacon_states_validators_page(
+                            state_id.0,
+                            None,
+                            statuses,
+                            page_token.as_deref(),
+                            Some(3),
+                        )
+                        .await
+                        .unwrap()
+                    else {
+                        break None;
+                    };
+                    assert!(page.data.len() <= 3);
+                    pages.extend(page.data);
+                    page_token = page.next_page_token;
+                    if page_token.is_none() {
+                        break Some(pages);
+                    }
+                };
+
+                assert_eq!(result, expected, "{:?}", state_id);
+            }
+        }
+
+        // Out of range limits and malformed page tokens are rejected.
+        for (page_token, limit) in [(None, Some(0)), (Some("not-an-index"), None)] {
+            let error = self
+                .client
+                .get_beacon_states_validators_page(CoreStateId::Head, None, None, page_token, limit)
+                .await
+                .unwrap_err();
+            assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        }
+
+        self
+    }
+
     pub async fn test_beacon_states_validator_id(self) -> Self {
         for state_id in self.interesting_state_ids() {
             let state_opt = state_id
@@ -1664,6 +1738,14 @@ impl ApiTester {
         let expected = block.slot();
         assert_eq!(result.get(0).unwrap().slot(), expected);
 
+        let result_ssz = self
+            .client
+            .get_blobs_ssz::<E>(CoreBlockId::Root(block_root), blob_indices.as_deref())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(result_ssz, result);
+
         self
     }
 
@@ -1789,6 +1871,112 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_beacon_ssz_responses(self) -> Self {
+        for state_id in self.interesting_state_ids() {
+            let root = self
+                .client
+                .get_beacon_states_root(state_id.0)
+                .await
+                .unwrap()
+                .map(|res| res.data.root);
+            let root_ssz = self
+                .client
+                .get_beacon_states_root_ssz(state_id.0)
+                .await
+                .unwrap();
+            assert_eq!(root_ssz, root, "{:?}", state_id);
+
+            let fork = self
+                .client
+                .get_beacon_states_fork(state_id.0)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+            let fork_ssz = self
+                .client
+                .get_beacon_states_fork_ssz(state_id.0)
+                .await
+                .unwrap();
+            assert_eq!(fork_ssz, fork, "{:?}", state_id);
+        }
+
+        for block_id in self.interesting_block_ids() {
+            let root = self
+                .client
+                .get_beacon_blocks_root(block_id.0)
+                .await
+                .unwrap()
+                .map(|res| res.data.root);
+            let root_ssz = self
+                .client
+                .get_beacon_blocks_root_ssz(block_id.0)
+                .await
+                .unwrap();
+            assert_eq!(root_ssz, root, "{:?}", block_id);
+
+            let header = self
+                .client
+                .get_beacon_headers_block_id(block_id.0)
+                .await
+                .unwrap()
+                .map(|res| SignedBeaconBlockHeader {
+                    message: res.data.header.message,
+                    signature: res.data.header.signature.decompress().unwrap(),
+                });
+            let header_ssz = self
+                .client
+                .get_beacon_headers_block_id_ssz(block_id.0)
+                .await
+                .unwrap();
+            assert_eq!(header_ssz, header, "{:?}", block_id);
+
+            let attestations = self
+                .client
+                .get_beacon_blocks_attestations_v2::<E>(block_id.0)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+            let attestations_ssz = self
+                .client
+                .get_beacon_blocks_attestations_ssz::<E>(block_id.0)
+                .await
+                .unwrap();
+            assert_eq!(attestations_ssz, attestations, "{:?}", block_id);
+
+            let json_metadata = self
+                .client
+                .get_beacon_blocks::<E>(block_id.0)
+                .await
+                .unwrap()
+                .map(|res| (res.metadata.execution_optimistic, res.metadata.finalized));
+            let ssz_headers = self
+                .client
+                .get_response_with_response_headers(
+                    self.client.get_beacon_blocks_path(block_id.0).unwrap(),
+                    Accept::Ssz,
+                    Duration::from_secs(SECONDS_PER_SLOT),
+                    |_, headers| async move { Ok(headers) },
+                )
+                .await
+                .unwrap();
+            let ssz_metadata = ssz_headers.map(|headers| {
+                let header_value = |name| {
+                    headers
+                        .get(name)
+                        .and_then(|value| value.to_str().ok())
+                        .and_then(|value| value.parse::<bool>().ok())
+                };
+                (
+                    header_value(EXECUTION_OPTIMISTIC_HEADER),
+                    header_value(FINALIZED_HEADER),
+                )
+            });
+            assert_eq!(ssz_metadata, json_metadata, "{:?}", block_id);
+        }
+
+        self
+    }
+
     pub async fn test_post_beacon_pool_attestations_valid_v1(mut self) -> Self {
         self.client
             .post_beacon_pool_attestations_v1(self.attestations.as_slice())
@@ -1917,13 +2105,7 @@ impl ApiTester {
 
         let expected = self
             .chain
-            .light_client_server_cache
-            .get_light_client_updates(
-                &self.chain.store,
-                current_sync_committee_period as u64,
-                1,
-                &self.chain.spec,
-            )
+            .get_light_client_updates(current_sync_committee_period, 1)
             .unwrap();
 
         assert_eq!(1, expected.len());
@@ -2188,6 +2370,27 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_beacon_pool_voluntary_exits_standby(mut self) -> Self {
+        let network_globals = self.ctx.network_globals.clone().unwrap();
+        *network_globals.failover_role.write() = FailoverRole::Standby;
+
+        let error = self
+            .client
+            .post_beacon_pool_voluntary_exits(&self.voluntary_exit)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status().unwrap(), 503);
+
+        assert!(
+            self.network_rx.network_recv.try_recv().is_err(),
+            "exit should not be sent to network on standby"
+        );
+
+        *network_globals.failover_role.write() = FailoverRole::Active;
+
+        self
+    }
+
     pub async fn test_post_beacon_pool_voluntary_exits_invalid(mut self) -> Self {
         let mut exit = self.voluntary_exit.clone();
         exit.message.epoch += 1;
@@ -2340,6 +2543,7 @@ impl ApiTester {
                 attnets: "0x0000000000000000".to_string(),
                 syncnets: "0x00".to_string(),
             },
+            reachability: eth2::types::Reachability::Unknown,
         };
 
         assert_eq!(result, expected);
@@ -4673,6 +4877,33 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_payload_v3_censored_validator(self) -> Self {
+        let mock_builder = self.mock_builder.as_ref().unwrap();
+        for validator in self.chain.head_beacon_state_cloned().validators() {
+            mock_builder.censor_validator(validator.pubkey);
+        }
+
+        let slot = self.chain.slot().unwrap();
+        let epoch = self.chain.epoch().unwrap();
+
+        let (_, randao_reveal) = self.get_test_randao(slot, epoch).await;
+
+        let (payload_type, metadata) = self
+            .client
+            .get_validator_blocks_v3::<E>(slot, &randao_reveal, None, None)
+            .await
+            .unwrap();
+        Self::check_block_v3_metadata(&metadata, &payload_type);
+
+        // The builder offers no bid, so the local payload is used.
+        match payload_type.data {
+            ProduceBlockV3Response::Full(_) => (),
+            ProduceBlockV3Response::Blinded(_) => panic!("Expecting a full payload"),
+        };
+
+        self
+    }
+
     pub async fn test_builder_chain_health_skips(self) -> Self {
         let slot = self.chain.slot().unwrap();
 
@@ -5467,12 +5698,241 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_failover(self) -> Self {
+        let status = self.client.get_lighthouse_failover().await.unwrap().data;
+        assert_eq!(status.role, FailoverRole::Active);
+        assert_eq!(status.priority, None);
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
         self
     }
 
+    pub async fn test_get_lighthouse_fork_choice_graph(self) -> Self {
+        let graph = self
+            .client
+            .get_lighthouse_fork_choice_graph()
+            .await
+            .unwrap()
+            .data;
+
+        let head_root = self.chain.head_beacon_block_root();
+        assert_eq!(graph.head_root, head_root);
+        assert_eq!(
+            graph.nodes.len(),
+            self.chain
+                .canonical_head
+                .fork_choice_read_lock()
+                .proto_array()
+                .core_proto_array()
+                .nodes
+                .len()
+        );
+        let head_nodes = graph
+            .nodes
+            .iter()
+            .filter(|node| node.is_head)
+            .collect::<Vec<_>>();
+        assert_eq!(head_nodes.len(), 1);
+        assert_eq!(head_nodes[0].block_root, head_root);
+
+        let dot = self
+            .client
+            .get_lighthouse_fork_choice_graph_dot()
+            .await
+            .unwrap();
+        assert!(dot.starts_with("digraph fork_choice {"));
+        assert!(dot.contains(&format!("\"{head_root:?}\"")));
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_optimistic_status(self) -> Self {
+        let head_root = self.chain.head_beacon_block_root();
+        let status = self
+            .client
+            .get_lighthouse_optimistic_status(None)
+            .await
+            .unwrap()
+            .data;
+
+        // The test chain is never optimistic, so the head is its own latest valid ancestor.
+        assert_eq!(status.block_root, head_root);
+        assert!(status.is_head);
+        assert_eq!(status.latest_valid_ancestor_root, Some(head_root));
+        assert_eq!(status.optimistic_distance, 0);
+        assert_eq!(status.last_transition, None);
+
+        let parent_root = self.chain.head_snapshot().beacon_block.parent_root();
+        let status = self
+            .client
+            .get_lighthouse_optimistic_status(Some(parent_root))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(status.block_root, parent_root);
+        assert!(!status.is_head);
+
+        // Blocks which are not in fork choice are not found.
+        assert!(self
+            .client
+            .get_lighthouse_optimistic_status(Some(Hash256::repeat_byte(0x42)))
+            .await
+            .is_err());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_head_timeline(self) -> Self {
+        let head_root = self.chain.head_beacon_block_root();
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+        let current_slot = self.chain.slot().unwrap();
+
+        // The head was selected either in its own slot or, if it was imported late, afterwards.
+        let mut events = vec![];
+        for slot in head_slot.as_u64()..=current_slot.as_u64() {
+            events.extend(
+                self.client
+                    .get_lighthouse_head_timeline(Slot::new(slot))
+                    .await
+                    .unwrap()
+                    .data,
+            );
+        }
+        assert!(events.iter().any(|event| matches!(
+            event.kind,
+            HeadTimelineEventKind::BlockImported { block_root, .. } if block_root == head_root
+        )));
+        assert!(events.iter().any(|event| matches!(
+            event.kind,
+            HeadTimelineEventKind::HeadChanged { head_root: root, .. } if root == head_root
+        )));
+
+        // Future slots are not retained.
+        assert!(self
+            .client
+            .get_lighthouse_head_timeline(current_slot + 1)
+            .await
+            .is_err());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_dry_run_block(self) -> Self {
+        let head_root = self.chain.head_beacon_block_root();
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+        let slot = self.chain.slot().unwrap() + 1;
+
+        for skip_payload in [false, true] {
+            let dry_run = self
+                .client
+                .get_lighthouse_validator_dry_run_block::<E>(slot, skip_payload)
+                .await
+                .unwrap()
+                .data;
+
+            assert_eq!(dry_run.block.slot(), slot);
+            assert_eq!(dry_run.block.parent_root(), head_root);
+            assert_ne!(dry_run.block.state_root(), Hash256::zero());
+            assert!(!dry_run.payload_fetched || !skip_payload);
+            assert!(!dry_run.stages.is_empty());
+            assert!(dry_run.total_ms >= dry_run.state_load_ms);
+        }
+
+        // The block is not published.
+        assert_eq!(self.chain.head_beacon_block_root(), head_root);
+
+        // Blocks can't be produced on top of the head.
+        assert!(self
+            .client
+            .get_lighthouse_validator_dry_run_block::<E>(head_slot, false)
+            .await
+            .is_err());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_weak_subjectivity(self) -> Self {
+        let status = self
+            .client
+            .get_lighthouse_weak_subjectivity()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(status.current_epoch, self.chain.epoch().unwrap());
+        assert_eq!(
+            status.anchor_epoch,
+            self.chain
+                .canonical_head
+                .cached_head()
+                .finalized_checkpoint()
+                .epoch
+        );
+        assert_eq!(
+            status.active_validator_count,
+            self.chain.head_snapshot().beacon_state.validators().len() as u64
+        );
+        assert!(
+            status.weak_subjectivity_period
+                >= self.chain.spec.min_validator_withdrawability_delay.as_u64()
+        );
+        assert!(status.within_weak_subjectivity_period);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_orphans(self) -> Self {
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+
+        // The archive is disabled by default, so no blocks are archived.
+        let orphans = self
+            .client
+            .get_lighthouse_orphans(Slot::new(0), head_slot)
+            .await
+            .unwrap()
+            .data;
+        assert!(orphans.is_empty());
+
+        // An inverted range is rejected.
+        assert!(self
+            .client
+            .get_lighthouse_orphans(head_slot + 1, head_slot)
+            .await
+            .is_err());
+
+        // Ranges wider than 1024 slots are rejected.
+        assert!(self
+            .client
+            .get_lighthouse_orphans(Slot::new(0), Slot::new(1024))
+            .await
+            .is_err());
+        assert!(self
+            .client
+            .get_lighthouse_orphans(Slot::new(0), Slot::new(1023))
+            .await
+            .is_ok());
+
+        let attestations = self
+            .client
+            .get_lighthouse_orphaned_attestations::<E>(Slot::new(0), head_slot)
+            .await
+            .unwrap()
+            .data;
+        assert!(attestations.is_empty());
+        assert!(self
+            .client
+            .get_lighthouse_orphaned_attestations::<E>(Slot::new(0), Slot::new(1024))
+            .await
+            .is_err());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
         self.client
@@ -5485,10 +5945,57 @@ impl ApiTester {
 
     pub async fn test_get_lighthouse_validator_inclusion(self) -> Self {
         let epoch = self.chain.epoch().unwrap() - 1;
-        self.client
+        let by_index = self
+            .client
             .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
             .await
-            .unwrap();
+            .unwrap()
+            .data;
+        assert!(by_index.is_some());
+
+        // Lookups by pubkey resolve to the same validator.
+        let pubkey = self.validator_keypairs()[0].pk.compress();
+        let by_pubkey = self
+            .client
+            .get_lighthouse_validator_inclusion(epoch, ValidatorId::PublicKey(pubkey))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(by_pubkey, by_index);
+
+        // Unknown validators are absent.
+        let unknown = self
+            .client
+            .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(u64::MAX))
+            .await
+            .unwrap()
+            .data;
+        assert!(unknown.is_none());
+
+        // Finalized epochs are served (from the cache on repeated requests).
+        let finalized_epoch = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch;
+        if finalized_epoch > 0 {
+            let epoch = finalized_epoch - 1;
+            let first = self
+                .client
+                .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
+                .await
+                .unwrap()
+                .data;
+            let second = self
+                .client
+                .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
+                .await
+                .unwrap()
+                .data;
+            assert!(first.is_some());
+            assert_eq!(first, second);
+        }
 
         self
     }
@@ -5550,6 +6057,184 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_validators_duties(self) -> Self {
+        let current_epoch = self.chain.epoch().unwrap();
+        let next_epoch = current_epoch + 1;
+        let indices = (0..self.validator_keypairs().len() as u64)
+            .step_by(2)
+            .collect::<Vec<_>>();
+
+        let result = self
+            .client
+            .post_lighthouse_validators_duties(&indices, current_epoch, next_epoch)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result.len(), 2);
+
+        for (epoch_duties, epoch) in result.into_iter().zip([current_epoch, next_epoch]) {
+            assert_eq!(epoch_duties.epoch, epoch);
+
+            let attester = self
+                .client
+                .post_validator_duties_attester(epoch, &indices)
+                .await
+                .unwrap();
+            assert_eq!(epoch_duties.attester, attester);
+
+            let mut proposer = self
+                .client
+                .get_validator_duties_proposer(epoch)
+                .await
+                .unwrap();
+            proposer
+                .data
+                .retain(|duty| indices.contains(&duty.validator_index));
+            assert_eq!(epoch_duties.proposer, proposer);
+
+            let sync = self
+                .client
+                .post_validator_duties_sync(epoch, &indices)
+                .await
+                .unwrap();
+            assert_eq!(epoch_duties.sync.data, sync.data);
+        }
+
+        // Reversed and overly long ranges are rejected, as are ranges which extend past the next
+        // epoch, whose duties aren't known yet.
+        for (start_epoch, end_epoch) in [
+            (next_epoch, current_epoch),
+            (Epoch::new(0), Epoch::new(100)),
+            (current_epoch, current_epoch + 2),
+            (next_epoch, next_epoch + 3),
+        ] {
+            let error = self
+                .client
+                .post_lighthouse_validators_duties(&indices, start_epoch, end_epoch)
+                .await
+                .unwrap_err();
+            assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        }
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_validator_projection(self) -> Self {
+        let head_state = self.chain.head_beacon_state_cloned();
+        let current_epoch = head_state.current_epoch();
+        let indices = vec![0, 1];
+
+        let result = self
+            .client
+            .post_lighthouse_validator_projection(&indices, 3)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result.len(), indices.len());
+
+        for (projection, &index) in result.iter().zip(&indices) {
+            assert_eq!(projection.validator_index, index);
+            assert_eq!(projection.balances.len(), 4);
+
+            let first = &projection.balances[0];
+            assert_eq!(first.epoch, current_epoch);
+            assert_eq!(
+                first.balance,
+                *head_state.balances().get(index as usize).unwrap()
+            );
+            assert_eq!(
+                first.effective_balance,
+                head_state
+                    .validators()
+                    .get(index as usize)
+                    .unwrap()
+                    .effective_balance
+            );
+        }
+
+        // Unknown validators and overly long projections are rejected.
+        for (indices, epochs) in [(vec![u64::MAX], 1), (vec![0], 1_000)] {
+            let error = self
+                .client
+                .post_lighthouse_validator_projection(&indices, epochs)
+                .await
+                .unwrap_err();
+            assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_committee_assignment(self) -> Self {
+        let mut head_state = self.chain.head_beacon_state_cloned();
+        let state_root = head_state.canonical_root().unwrap();
+        let current_epoch = head_state.current_epoch();
+
+        for epoch in [current_epoch, current_epoch + 1] {
+            let relative_epoch = RelativeEpoch::from_epoch(current_epoch, epoch).unwrap();
+            head_state
+                .build_committee_cache(relative_epoch, &self.chain.spec)
+                .unwrap();
+
+            for validator_index in [0, 1] {
+                let assignment = self
+                    .client
+                    .get_lighthouse_committee_assignment(
+                        epoch,
+                        &ValidatorId::Index(validator_index),
+                    )
+                    .await
+                    .unwrap()
+                    .data;
+
+                assert_eq!(assignment.validator_index, validator_index);
+                assert_eq!(
+                    assignment.duty,
+                    head_state
+                        .get_attestation_duties(validator_index as usize, relative_epoch)
+                        .unwrap()
+                );
+                assert_eq!(assignment.state_root, state_root);
+                assert_eq!(assignment.state_slot, head_state.slot());
+                assert_eq!(
+                    assignment.seed,
+                    head_state
+                        .get_seed(epoch, Domain::BeaconAttester, &self.chain.spec)
+                        .unwrap()
+                );
+
+                let mix_index = head_state
+                    .get_seed_randao_mix_index(epoch, &self.chain.spec)
+                    .unwrap();
+                assert!(assignment.randao_mix.verify(state_root));
+                assert_eq!(
+                    assignment.randao_mix.leaf,
+                    *head_state.randao_mixes().get(mix_index).unwrap()
+                );
+                assert!(assignment.validator.verify(state_root));
+                assert_eq!(
+                    assignment.validator.leaf,
+                    head_state
+                        .get_validator(validator_index as usize)
+                        .unwrap()
+                        .tree_hash_root()
+                );
+            }
+        }
+
+        // Unknown validators and epochs beyond the next are rejected.
+        for (epoch, validator_index) in [(current_epoch, u64::MAX), (current_epoch + 2, 0)] {
+            let error = self
+                .client
+                .get_lighthouse_committee_assignment(epoch, &ValidatorId::Index(validator_index))
+                .await
+                .unwrap_err();
+            assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        }
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -5880,6 +6565,80 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_expected_withdrawals_with_proof_capella(self) -> Self {
+        // Use a state whose next slot is in the same epoch, so that it doesn't need advancing.
+        let mut slot = self.chain.slot().unwrap();
+        if (slot.as_u64() + 1) % E::slots_per_epoch() == 0 {
+            slot = slot - 1;
+        }
+        let state_id = CoreStateId::Slot(slot);
+
+        let (mut state, _, _) = StateId(state_id).state(&self.chain).unwrap();
+        let state_root = state.canonical_root().unwrap();
+        let expected_withdrawals = get_expected_withdrawals(&state, &self.chain.spec)
+            .unwrap()
+            .0;
+
+        let response = self
+            .client
+            .get_expected_withdrawals_with_proof(&state_id, None)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(response.withdrawals, expected_withdrawals.to_vec());
+        assert_eq!(response.state_root, state_root);
+        assert_eq!(response.state_slot, slot);
+
+        assert!(response.next_withdrawal_index.verify(state_root));
+        assert_eq!(
+            response.next_withdrawal_index.leaf,
+            state.next_withdrawal_index().unwrap().tree_hash_root()
+        );
+        assert!(response.next_withdrawal_validator_index.verify(state_root));
+        assert_eq!(
+            response.next_withdrawal_validator_index.leaf,
+            state
+                .next_withdrawal_validator_index()
+                .unwrap()
+                .tree_hash_root()
+        );
+
+        assert_eq!(response.validators.len(), response.withdrawals.len());
+        assert_eq!(response.balances.len(), response.withdrawals.len());
+        for ((withdrawal, validator_proof), balance_proof) in response
+            .withdrawals
+            .iter()
+            .zip(&response.validators)
+            .zip(&response.balances)
+        {
+            let index = withdrawal.validator_index as usize;
+            assert!(validator_proof.verify(state_root));
+            assert_eq!(
+                validator_proof.leaf,
+                state.get_validator(index).unwrap().tree_hash_root()
+            );
+            assert!(balance_proof.verify(state_root));
+            let offset = (index % 4) * 8;
+            let balance = u64::from_le_bytes(
+                balance_proof.leaf.as_slice()[offset..offset + 8]
+                    .try_into()
+                    .unwrap(),
+            );
+            assert_eq!(balance, state.get_balance(index).unwrap());
+        }
+
+        // Proofs are not available for states which must be advanced to the proposal epoch.
+        let next_epoch_slot =
+            (slot.epoch(E::slots_per_epoch()) + 1).start_slot(E::slots_per_epoch());
+        let result = self
+            .client
+            .get_expected_withdrawals_with_proof(&state_id, Some(next_epoch_slot))
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        self
+    }
+
     pub async fn test_get_expected_withdrawals_pre_capella(self) -> Self {
         let state_id = CoreStateId::Head;
 
@@ -5967,6 +6726,72 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_events_replay(self) -> Self {
+        let event_handler = self.chain.event_handler.as_ref().unwrap();
+        let head_event = |slot: u64| {
+            EventKind::Head(SseHead {
+                slot: Slot::new(slot),
+                block: Hash256::repeat_byte(slot as u8),
+                state: Hash256::repeat_byte(slot as u8),
+                current_duty_dependent_root: Hash256::zero(),
+                previous_duty_dependent_root: Hash256::zero(),
+                epoch_transition: false,
+                execution_optimistic: false,
+            })
+        };
+        let timeout = Duration::from_secs(10);
+
+        let mut events = self
+            .client
+            .get_events_since::<E>(&[EventTopic::Head], None)
+            .await
+            .unwrap();
+        event_handler.register(head_event(1));
+        let (last_event_id, event) = tokio::time::timeout(timeout, events.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event, head_event(1));
+        let last_event_id = last_event_id.expect("replayable events have an ID");
+
+        // The client disconnects and misses some events.
+        drop(events);
+        event_handler.register(head_event(2));
+        event_handler.register(head_event(3));
+
+        // The missed events are replayed when reconnecting, followed by live events.
+        let mut events = self
+            .client
+            .get_events_since::<E>(&[EventTopic::Head], Some(last_event_id))
+            .await
+            .unwrap();
+        event_handler.register(head_event(4));
+
+        let mut received = vec![];
+        while received.len() < 3 {
+            let (id, event) = tokio::time::timeout(timeout, events.next())
+                .await
+                .unwrap()
+                .unwrap()
+                .unwrap();
+            received.push((id.unwrap(), event));
+        }
+        assert_eq!(
+            received
+                .iter()
+                .map(|(_, event)| event.clone())
+                .collect::<Vec<_>>(),
+            vec![head_event(2), head_event(3), head_event(4)]
+        );
+        assert!(received
+            .iter()
+            .zip(1..)
+            .all(|((id, _), offset)| *id == last_event_id + offset));
+
+        self
+    }
+
     pub async fn test_check_optimistic_responses(&mut self) {
         // Check responses are not optimistic.
         let result = self
@@ -6031,6 +6856,15 @@ async fn get_events() {
     ApiTester::new().await.test_get_events().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_events_replay() {
+    let config = ApiTesterConfig::default().sse_replay_window(Duration::from_secs(300));
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_events_replay()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_altair() {
     let mut config = ApiTesterConfig::default();
@@ -6085,6 +6919,8 @@ async fn beacon_get() {
         .await
         .test_beacon_states_validators()
         .await
+        .test_beacon_states_validators_page()
+        .await
         .test_beacon_states_validator_balances()
         .await
         .test_beacon_states_committees()
@@ -6105,6 +6941,8 @@ async fn beacon_get() {
         .await
         .test_beacon_blocks_attestations()
         .await
+        .test_beacon_ssz_responses()
+        .await
         .test_beacon_blocks_root()
         .await
         .test_get_beacon_pool_attestations()
@@ -6242,6 +7080,14 @@ async fn beacon_pools_post_voluntary_exits_valid() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_pools_post_voluntary_exits_standby() {
+    ApiTester::new()
+        .await
+        .test_post_beacon_pool_voluntary_exits_standby()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn beacon_pools_post_voluntary_exits_invalid() {
     ApiTester::new()
@@ -6791,6 +7637,14 @@ async fn get_full_block_invalid_signature_v3() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_full_block_censored_validator_v3() {
+    ApiTester::new_mev_tester()
+        .await
+        .test_payload_v3_censored_validator()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn builder_chain_health_skips() {
     ApiTester::new_mev_tester()
@@ -6884,6 +7738,7 @@ async fn builder_works_post_capella() {
     let mut config = ApiTesterConfig {
         retain_historic_states: false,
         spec: E::default_spec(),
+        sse_replay_window: None,
     };
     config.spec.altair_fork_epoch = Some(Epoch::new(0));
     config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -6904,6 +7759,7 @@ async fn builder_works_post_deneb() {
     let mut config = ApiTesterConfig {
         retain_historic_states: false,
         spec: E::default_spec(),
+        sse_replay_window: None,
     };
     config.spec.altair_fork_epoch = Some(Epoch::new(0));
     config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -6925,6 +7781,7 @@ async fn get_blob_sidecars() {
     let mut config = ApiTesterConfig {
         retain_historic_states: false,
         spec: E::default_spec(),
+        sse_replay_window: None,
     };
     config.spec.altair_fork_epoch = Some(Epoch::new(0));
     config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
@@ -6987,8 +7844,22 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_failover()
+        .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_fork_choice_graph()
+        .await
+        .test_get_lighthouse_orphans()
+        .await
+        .test_get_lighthouse_optimistic_status()
+        .await
+        .test_get_lighthouse_head_timeline()
+        .await
+        .test_get_lighthouse_validator_dry_run_block()
+        .await
+        .test_get_lighthouse_weak_subjectivity()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
         .test_get_lighthouse_validator_inclusion_global()
@@ -7006,6 +7877,12 @@ async fn lighthouse_endpoints() {
         .test_post_lighthouse_database_reconstruct()
         .await
         .test_post_lighthouse_liveness()
+        .await
+        .test_post_lighthouse_validators_duties()
+        .await
+        .test_post_lighthouse_validator_projection()
+        .await
+        .test_get_lighthouse_committee_assignment()
         .await;
 }
 
@@ -7050,3 +7927,15 @@ async fn expected_withdrawals_valid_capella() {
         .test_get_expected_withdrawals_capella()
         .await;
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn expected_withdrawals_with_proof_capella() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_expected_withdrawals_with_proof_capella()
+        .await;
+}
@@ -56,7 +56,9 @@ use super::gossip_promises::GossipPromises;
 use super::handler::{Handler, HandlerEvent, HandlerIn};
 use super::mcache::MessageCache;
 use super::metrics::{Churn, Config as MetricsConfig, Inclusion, Metrics, Penalty};
-use super::peer_score::{PeerScore, PeerScoreParams, PeerScoreThresholds, RejectReason};
+use super::peer_score::{
+    PeerScore, PeerScoreBreakdown, PeerScoreParams, PeerScoreThresholds, RejectReason,
+};
 use super::protocol::SIGNING_PREFIX;
 use super::rpc_proto::proto;
 use super::subscription_filter::{AllowAllSubscriptionFilter, TopicSubscriptionFilter};
@@ -529,6 +531,13 @@ where
             .map(|(score, ..)| score.score(peer_id))
     }
 
+    /// Returns the components of the gossipsub score for a given peer, if peer scoring is enabled.
+    pub fn peer_score_breakdown(&self, peer_id: &PeerId) -> Option<PeerScoreBreakdown> {
+        self.peer_score
+            .as_ref()
+            .and_then(|(score, ..)| score.score_breakdown(peer_id))
+    }
+
     /// Subscribe to a topic.
     ///
     /// Returns [`Ok(true)`] if the subscription worked. Returns [`Ok(false)`] if we were already
@@ -890,6 +899,11 @@ where
         self.check_explicit_peer_connection(peer_id);
     }
 
+    /// Lists the peers which are explicitly connected.
+    pub fn explicit_peers(&self) -> impl Iterator<Item = &PeerId> {
+        self.explicit_peers.iter()
+    }
+
     /// This removes the peer from explicitly connected peers, note that this does not disconnect
     /// the peer.
     pub fn remove_explicit_peer(&mut self, peer_id: &PeerId) {
@@ -55,6 +55,51 @@ pub(crate) struct PeerScore {
     message_delivery_time_callback: Option<fn(&PeerId, &TopicHash, f64)>,
 }
 
+/// The components of a peer's score, for diagnostics.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PeerScoreBreakdown {
+    /// The components of the score of each scored topic the peer has statistics for.
+    pub topics: Vec<(TopicHash, TopicScoreBreakdown)>,
+    /// The sum of the topic scores, after applying each topic's weight and the topic score cap.
+    pub topic_score: f64,
+    /// P5: the weighted application-specific score.
+    pub application_score: f64,
+    /// P6: the weighted IP colocation penalty.
+    pub ip_colocation_penalty: f64,
+    /// P7: the weighted behavioural penalty.
+    pub behaviour_penalty: f64,
+    /// The weighted penalty for being slow to receive messages.
+    pub slow_peer_penalty: f64,
+    /// The peer's score.
+    pub score: f64,
+}
+
+/// The weighted components of a peer's score in a single topic, before the topic weight is
+/// applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TopicScoreBreakdown {
+    /// P1: time in mesh.
+    pub time_in_mesh: f64,
+    /// P2: first message deliveries.
+    pub first_message_deliveries: f64,
+    /// P3: mesh message delivery deficit.
+    pub mesh_message_deliveries: f64,
+    /// P3b: mesh failure penalty.
+    pub mesh_failure_penalty: f64,
+    /// P4: invalid messages.
+    pub invalid_message_deliveries: f64,
+}
+
+impl TopicScoreBreakdown {
+    fn score(&self) -> f64 {
+        self.time_in_mesh
+            + self.first_message_deliveries
+            + self.mesh_message_deliveries
+            + self.mesh_failure_penalty
+            + self.invalid_message_deliveries
+    }
+}
+
 /// General statistics for a given gossipsub peer.
 struct PeerStats {
     /// Connection status of the peer.
@@ -222,10 +267,27 @@ impl PeerScore {
 
     /// Returns the score for a peer, logging metrics. This is called from the heartbeat and
     /// increments the metric counts for penalties.
-    pub(crate) fn metric_score(&self, peer_id: &PeerId, mut metrics: Option<&mut Metrics>) -> f64 {
-        let Some(peer_stats) = self.peer_stats.get(peer_id) else {
-            return 0.0;
-        };
+    pub(crate) fn metric_score(&self, peer_id: &PeerId, metrics: Option<&mut Metrics>) -> f64 {
+        self.compute_score(peer_id, metrics, false)
+            .map_or(0.0, |breakdown| breakdown.score)
+    }
+
+    /// Returns the components of the score for a peer, or `None` if there are no statistics for
+    /// the peer.
+    pub(crate) fn score_breakdown(&self, peer_id: &PeerId) -> Option<PeerScoreBreakdown> {
+        self.compute_score(peer_id, None, true)
+    }
+
+    /// Computes the score for a peer. The components of each topic score are only retained if
+    /// `with_topics` is true, to avoid allocating in the heartbeat.
+    fn compute_score(
+        &self,
+        peer_id: &PeerId,
+        mut metrics: Option<&mut Metrics>,
+        with_topics: bool,
+    ) -> Option<PeerScoreBreakdown> {
+        let peer_stats = self.peer_stats.get(peer_id)?;
+        let mut breakdown = PeerScoreBreakdown::default();
         let mut score = 0.0;
 
         // topic scores
@@ -235,7 +297,7 @@ impl PeerScore {
                 // we are tracking the topic
 
                 // the topic score
-                let mut topic_score = 0.0;
+                let mut topic_score = TopicScoreBreakdown::default();
 
                 // P1: time in mesh
                 if let MeshStatus::Active { mesh_time, .. } = topic_stats.mesh_status {
@@ -248,7 +310,7 @@ impl PeerScore {
                             topic_params.time_in_mesh_cap
                         }
                     };
-                    topic_score += p1 * topic_params.time_in_mesh_weight;
+                    topic_score.time_in_mesh = p1 * topic_params.time_in_mesh_weight;
                 }
 
                 // P2: first message deliveries
@@ -260,7 +322,8 @@ impl PeerScore {
                         topic_params.first_message_deliveries_cap
                     }
                 };
-                topic_score += p2 * topic_params.first_message_deliveries_weight;
+                topic_score.first_message_deliveries =
+                    p2 * topic_params.first_message_deliveries_weight;
 
                 // P3: mesh message deliveries
                 if topic_stats.mesh_message_deliveries_active
@@ -270,7 +333,8 @@ impl PeerScore {
                     let deficit = topic_params.mesh_message_deliveries_threshold
                         - topic_stats.mesh_message_deliveries;
                     let p3 = deficit * deficit;
-                    topic_score += p3 * topic_params.mesh_message_deliveries_weight;
+                    topic_score.mesh_message_deliveries =
+                        p3 * topic_params.mesh_message_deliveries_weight;
                     if let Some(metrics) = metrics.as_mut() {
                         metrics.register_score_penalty(Penalty::MessageDeficit);
                     }
@@ -278,7 +342,7 @@ impl PeerScore {
                         peer=%peer_id,
                         %topic,
                         %deficit,
-                        penalty=%topic_score,
+                        penalty=%topic_score.score(),
                         "[Penalty] The peer has a mesh deliveries deficit and will be penalized"
                     );
                 }
@@ -286,16 +350,21 @@ impl PeerScore {
                 // P3b:
                 // NOTE: the weight of P3b is negative (validated in TopicScoreParams.validate), so this detracts.
                 let p3b = topic_stats.mesh_failure_penalty;
-                topic_score += p3b * topic_params.mesh_failure_penalty_weight;
+                topic_score.mesh_failure_penalty = p3b * topic_params.mesh_failure_penalty_weight;
 
                 // P4: invalid messages
                 // NOTE: the weight of P4 is negative (validated in TopicScoreParams.validate), so this detracts.
                 let p4 =
                     topic_stats.invalid_message_deliveries * topic_stats.invalid_message_deliveries;
-                topic_score += p4 * topic_params.invalid_message_deliveries_weight;
+                topic_score.invalid_message_deliveries =
+                    p4 * topic_params.invalid_message_deliveries_weight;
 
                 // update score, mixing with topic weight
-                score += topic_score * topic_params.topic_weight;
+                score += topic_score.score() * topic_params.topic_weight;
+
+                if with_topics {
+                    breakdown.topics.push((topic.clone(), topic_score));
+                }
             }
         }
 
@@ -303,10 +372,12 @@ impl PeerScore {
         if self.params.topic_score_cap > 0f64 && score > self.params.topic_score_cap {
             score = self.params.topic_score_cap;
         }
+        breakdown.topic_score = score;
 
         // P5: application-specific score
         let p5 = peer_stats.application_score;
-        score += p5 * self.params.app_specific_weight;
+        breakdown.application_score = p5 * self.params.app_specific_weight;
+        score += breakdown.application_score;
 
         // P6: IP collocation factor
         for ip in peer_stats.known_ips.iter() {
@@ -331,25 +402,29 @@ impl PeerScore {
                         surplus=%surplus,
                         "[Penalty] The peer gets penalized because of too many peers with the same ip"
                     );
-                    score += p6 * self.params.ip_colocation_factor_weight;
+                    breakdown.ip_colocation_penalty += p6 * self.params.ip_colocation_factor_weight;
                 }
             }
         }
+        score += breakdown.ip_colocation_penalty;
 
         // P7: behavioural pattern penalty
         if peer_stats.behaviour_penalty > self.params.behaviour_penalty_threshold {
             let excess = peer_stats.behaviour_penalty - self.params.behaviour_penalty_threshold;
             let p7 = excess * excess;
-            score += p7 * self.params.behaviour_penalty_weight;
+            breakdown.behaviour_penalty = p7 * self.params.behaviour_penalty_weight;
+            score += breakdown.behaviour_penalty;
         }
 
         // Slow peer weighting
         if peer_stats.slow_peer_penalty > self.params.slow_peer_threshold {
             let excess = peer_stats.slow_peer_penalty - self.params.slow_peer_threshold;
-            score += excess * self.params.slow_peer_weight;
+            breakdown.slow_peer_penalty = excess * self.params.slow_peer_weight;
+            score += breakdown.slow_peer_penalty;
         }
 
-        score
+        breakdown.score = score;
+        Some(breakdown)
     }
 
     pub(crate) fn add_penalty(&mut self, peer_id: &PeerId, count: usize) {
@@ -976,3 +976,47 @@ fn test_score_retention() {
         "Score should be the application specific score"
     );
 }
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn test_score_breakdown() {
+    let topic = Topic::new("test");
+    let topic_hash = topic.hash();
+    let mut params = PeerScoreParams {
+        app_specific_weight: 2.0,
+        ..Default::default()
+    };
+
+    let topic_params = TopicScoreParams {
+        topic_weight: 0.5,
+        invalid_message_deliveries_weight: -1.0,
+        ..Default::default()
+    };
+
+    params.topics.insert(topic_hash.clone(), topic_params);
+
+    let peer_id = PeerId::random();
+    let mut peer_score = PeerScore::new(params);
+    assert!(peer_score.score_breakdown(&peer_id).is_none());
+
+    peer_score.add_peer(peer_id);
+    peer_score.set_application_score(&peer_id, 3.0);
+    peer_score.reject_invalid_message(&peer_id, &topic_hash);
+    peer_score.reject_invalid_message(&peer_id, &topic_hash);
+
+    let breakdown = peer_score.score_breakdown(&peer_id).unwrap();
+    assert_eq!(
+        breakdown.topics,
+        vec![(
+            topic_hash,
+            TopicScoreBreakdown {
+                invalid_message_deliveries: -4.0,
+                ..Default::default()
+            }
+        )]
+    );
+    assert_eq!(breakdown.topic_score, -2.0);
+    assert_eq!(breakdown.application_score, 6.0);
+    assert_eq!(breakdown.score, 4.0);
+    assert_eq!(breakdown.score, peer_score.score(&peer_id));
+}
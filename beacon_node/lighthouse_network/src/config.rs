@@ -1,11 +1,11 @@
 use crate::listen_addr::{ListenAddr, ListenAddress};
 use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
-use crate::types::GossipKind;
-use crate::{Enr, PeerIdSerialized};
+use crate::types::{GossipKind, Subnet};
+use crate::{Enr, EnrExt, PeerIdSerialized};
 use directory::{
     DEFAULT_BEACON_NODE_DIR, DEFAULT_HARDCODED_NETWORK, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR,
 };
-use libp2p::Multiaddr;
+use libp2p::{Multiaddr, PeerId};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::net::{Ipv4Addr, Ipv6Addr};
@@ -13,13 +13,15 @@ use std::num::NonZeroU16;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use types::{ForkContext, ForkName};
+use types::{ForkContext, ForkName, SubnetId, SyncSubnetId};
 
 pub const DEFAULT_IPV4_ADDRESS: Ipv4Addr = Ipv4Addr::UNSPECIFIED;
 pub const DEFAULT_TCP_PORT: u16 = 9000u16;
 pub const DEFAULT_DISC_PORT: u16 = 9000u16;
 pub const DEFAULT_QUIC_PORT: u16 = 9001u16;
 pub const DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD: usize = 1000usize;
+pub const DEFAULT_GOSSIP_TRACE_SAMPLE_RATE: f64 = 0.01;
+pub const DEFAULT_BACKBONE_SUBNET_PEERS: usize = 6;
 
 /// The maximum size of gossip messages.
 pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize {
@@ -86,6 +88,13 @@ pub struct Config {
     /// List of trusted libp2p nodes which are not scored and marked as explicit.
     pub trusted_peers: Vec<PeerIdSerialized>,
 
+    /// Trusted nodes that are always dialed and redialed whenever they disconnect.
+    pub trusted_peers_enr: Vec<Enr>,
+
+    /// Trusted nodes that are always dialed and redialed whenever they disconnect, on Multiaddr
+    /// format. Each address must end with the node's `/p2p/<peer-id>`.
+    pub trusted_peers_multiaddr: Vec<Multiaddr>,
+
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
@@ -107,6 +116,16 @@ pub struct Config {
     /// Subscribe to all subnets for the duration of the runtime.
     pub subscribe_all_subnets: bool,
 
+    /// Attestation subnets to subscribe to and advertise for the duration of the runtime, in
+    /// addition to those computed from the node id.
+    pub backbone_attestation_subnets: Vec<u64>,
+
+    /// Sync committee subnets to subscribe to and advertise for the duration of the runtime.
+    pub backbone_sync_committee_subnets: Vec<u64>,
+
+    /// The number of peers to maintain on each of the backbone subnets.
+    pub backbone_subnet_peers: usize,
+
     /// Import/aggregate all attestations received on subscribed subnets for the duration of the
     /// runtime.
     pub import_all_attestations: bool,
@@ -140,6 +159,12 @@ pub struct Config {
     /// Configures if/where invalid blocks should be stored.
     pub invalid_block_storage: Option<PathBuf>,
 
+    /// If set, a sample of received gossip messages are traced to files in this directory.
+    pub gossip_trace_dir: Option<PathBuf>,
+
+    /// The fraction of received gossip messages to trace.
+    pub gossip_trace_sample_rate: f64,
+
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 
@@ -268,6 +293,46 @@ impl Config {
     pub fn listen_addrs(&self) -> &ListenAddress {
         &self.listen_addresses
     }
+
+    /// Returns the attestation and sync committee subnets which this node serves as a backbone
+    /// for, subscribing to them and advertising them for the duration of the runtime.
+    pub fn backbone_subnets(&self) -> impl Iterator<Item = Subnet> + '_ {
+        let attestation_subnets = self
+            .backbone_attestation_subnets
+            .iter()
+            .map(|subnet_id| Subnet::Attestation(SubnetId::new(*subnet_id)));
+        let sync_committee_subnets = self
+            .backbone_sync_committee_subnets
+            .iter()
+            .map(|subnet_id| Subnet::SyncCommittee(SyncSubnetId::new(*subnet_id)));
+        attestation_subnets.chain(sync_committee_subnets)
+    }
+
+    /// Returns `true` if this node serves as a backbone for `subnet`.
+    pub fn is_backbone_subnet(&self, subnet: &Subnet) -> bool {
+        self.backbone_subnets().any(|backbone| backbone == *subnet)
+    }
+
+    /// Returns the ids of all trusted peers, whether they were given as a peer id, an ENR or a
+    /// multiaddr.
+    pub fn trusted_peer_ids(&self) -> Vec<PeerId> {
+        let mut peer_ids: Vec<PeerId> = self
+            .trusted_peers
+            .iter()
+            .map(|peer_id| PeerId::from(peer_id.clone()))
+            .collect();
+        let enr_peer_ids = self.trusted_peers_enr.iter().map(|enr| enr.peer_id());
+        let multiaddr_peer_ids = self
+            .trusted_peers_multiaddr
+            .iter()
+            .filter_map(PeerId::try_from_multiaddr);
+        for peer_id in enr_peer_ids.chain(multiaddr_peer_ids) {
+            if !peer_ids.contains(&peer_id) {
+                peer_ids.push(peer_id);
+            }
+        }
+        peer_ids
+    }
 }
 
 impl Default for Config {
@@ -339,6 +404,8 @@ impl Default for Config {
             boot_nodes_multiaddr: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
+            trusted_peers_enr: vec![],
+            trusted_peers_multiaddr: vec![],
             disable_peer_scoring: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
@@ -348,6 +415,9 @@ impl Default for Config {
             private: false,
             subscribe_all_data_column_subnets: false,
             subscribe_all_subnets: false,
+            backbone_attestation_subnets: vec![],
+            backbone_sync_committee_subnets: vec![],
+            backbone_subnet_peers: DEFAULT_BACKBONE_SUBNET_PEERS,
             import_all_attestations: false,
             shutdown_after_sync: false,
             topics: Vec::new(),
@@ -356,6 +426,8 @@ impl Default for Config {
             enable_light_client_server: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
+            gossip_trace_dir: None,
+            gossip_trace_sample_rate: DEFAULT_GOSSIP_TRACE_SAMPLE_RATE,
             inbound_rate_limiter_config: None,
             idontwant_message_size_threshold: DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD,
         }
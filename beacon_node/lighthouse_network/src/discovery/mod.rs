@@ -7,7 +7,6 @@ pub(crate) mod enr;
 pub mod enr_ext;
 
 // Allow external use of the lighthouse ENR builder
-use crate::service::TARGET_SUBNET_PEERS;
 use crate::{metrics, ClearDialError};
 use crate::{Enr, NetworkConfig, NetworkGlobals, Subnet, SubnetDiscovery};
 use discv5::{enr::NodeId, Discv5};
@@ -433,7 +432,7 @@ impl<E: EthSpec> Discovery<E> {
 
     // TODO: Group these functions here once the ENR is shared across discv5 and lighthouse and
     // Lighthouse can modify the ENR directly.
-    // This currently doesn't support ipv6. All of these functions should be removed and
+    // All of these functions should be removed and
     // addressed properly in the following issue.
     // https://github.com/sigp/lighthouse/issues/4706
     pub fn update_enr_quic_port(&mut self, port: u16, v6: bool) -> Result<bool, String> {
@@ -450,12 +449,6 @@ impl<E: EthSpec> Discovery<E> {
             }
             "quic"
         };
-        let current_field = self.discv5.external_enr().read().quic4();
-        if current_field == Some(port) {
-            // The current field is already set, no need to update.
-            return Ok(false);
-        }
-
         self.discv5
             .enr_insert(enr_field, &port)
             .map_err(|e| format!("{:?}", e))?;
@@ -735,17 +728,20 @@ impl<E: EthSpec> Discovery<E> {
                     .read()
                     .good_peers_on_subnet(subnet_query.subnet)
                     .count();
+                let target_subnet_peers = self
+                    .network_globals
+                    .target_subnet_peers(&subnet_query.subnet);
 
-                if peers_on_subnet >= TARGET_SUBNET_PEERS {
+                if peers_on_subnet >= target_subnet_peers {
                     debug!(self.log, "Discovery ignored";
                         "reason" => "Already connected to desired peers",
                         "connected_peers_on_subnet" => peers_on_subnet,
-                        "target_subnet_peers" => TARGET_SUBNET_PEERS,
+                        "target_subnet_peers" => target_subnet_peers,
                     );
                     return false;
                 }
 
-                let target_peers = TARGET_SUBNET_PEERS.saturating_sub(peers_on_subnet);
+                let target_peers = target_subnet_peers.saturating_sub(peers_on_subnet);
                 trace!(self.log, "Discovery query started for subnet";
                     "subnet_query" => ?subnet_query,
                     "connected_peers_on_subnet" => peers_on_subnet,
@@ -794,13 +790,20 @@ impl<E: EthSpec> Discovery<E> {
                 return;
             }
         };
+        // Only peers with a TCP address in an address family we listen on can be dialed. This
+        // keeps IPv6-only nodes from filling their queries with IPv4-only peers and vice versa.
+        let listen_addrs = self.network_globals.config.listen_addrs();
+        let dial_ipv4 = listen_addrs.v4().is_some();
+        let dial_ipv6 = listen_addrs.v6().is_some();
+
         // predicate for finding nodes with a matching fork and valid tcp port
         let eth2_fork_predicate = move |enr: &Enr| {
             // `next_fork_epoch` and `next_fork_version` can be different so that
             // we can connect to peers who aren't compatible with an upcoming fork.
             // `fork_digest` **must** be same.
             enr.eth2().map(|e| e.fork_digest) == Ok(enr_fork_id.fork_digest)
-                && (enr.tcp4().is_some() || enr.tcp6().is_some())
+                && ((dial_ipv4 && enr.ip4().is_some() && enr.tcp4().is_some())
+                    || (dial_ipv6 && enr.ip6().is_some() && enr.tcp6().is_some()))
         };
 
         // General predicate
@@ -1165,9 +1168,8 @@ impl<E: EthSpec> Discovery<E> {
                 DialError::Denied { .. } => {
                     if self.network_globals.peers.read().is_connected(&peer_id) {
                         // There's an active connection, so we don’t disconnect the peer.
-                        // Lighthouse dials to a peer twice using TCP and QUIC (if QUIC is not
-                        // disabled). Usually, one establishes a connection, and the other fails
-                        // because the peer allows only one connection per peer.
+                        // The peer may have connected to us while we were dialing it, in which
+                        // case the dial fails because only one connection per peer is allowed.
                         return;
                     }
                     // set peer as disconnected in discovery DHT
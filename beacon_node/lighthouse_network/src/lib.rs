@@ -29,6 +29,12 @@ impl From<PeerIdSerialized> for PeerId {
     }
 }
 
+impl From<PeerId> for PeerIdSerialized {
+    fn from(peer_id: PeerId) -> Self {
+        Self(peer_id)
+    }
+}
+
 impl FromStr for PeerIdSerialized {
     type Err = String;
 
@@ -117,11 +123,16 @@ pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
     peerdb::client::Client,
-    peerdb::score::{PeerAction, ReportSource},
+    peerdb::score::{
+        GossipsubScoreBreakdown, PeerAction, PenaltyRecord, ReportSource, Score, ScoreState,
+        TopicScoreBreakdown,
+    },
     peerdb::PeerDB,
     ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Response};
+#[cfg(feature = "simulated-link")]
+pub use service::simulated_link::{LinkConditions, SimulatedNetwork, SimulatedTransport};
 pub use service::utils::*;
 pub use service::{Gossipsub, NetworkEvent};
@@ -8,6 +8,13 @@ pub static NAT_OPEN: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
         &["protocol"],
     )
 });
+pub static NAT_REACHABILITY: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "nat_reachability",
+        "Set to 1 for the current estimate of whether the local node is reachable from external nodes",
+        &["status"],
+    )
+});
 
 pub static ADDRESS_UPDATE_COUNT: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
@@ -41,6 +48,50 @@ pub static QUIC_PEERS_CONNECTED: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     )
 });
 
+pub static CONNECTIONS_ESTABLISHED_PER_TRANSPORT: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "libp2p_connections_established_per_transport_total",
+            "Count of libp2p connections established, by transport",
+            &["transport"],
+        )
+    });
+
+pub static DIAL_FAILURES_PER_TRANSPORT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_dial_failures_per_transport_total",
+        "Count of failed attempts to dial a peer address, by transport",
+        &["transport"],
+    )
+});
+
+pub static PEERS_CONNECTED_PER_ADDRESS_FAMILY: LazyLock<Result<IntGaugeVec>> =
+    LazyLock::new(|| {
+        try_create_int_gauge_vec(
+            "libp2p_peers_per_address_family",
+            "Count of libp2p connections currently open, by IP address family",
+            &["family"],
+        )
+    });
+
+pub static CONNECTIONS_ESTABLISHED_PER_ADDRESS_FAMILY: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "libp2p_connections_established_per_address_family_total",
+            "Count of libp2p connections established, by IP address family",
+            &["family"],
+        )
+    });
+
+pub static DIAL_FAILURES_PER_ADDRESS_FAMILY: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "libp2p_dial_failures_per_address_family_total",
+            "Count of failed attempts to dial a peer address, by IP address family",
+            &["family"],
+        )
+    });
+
 pub static PEER_CONNECT_EVENT_COUNT: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
         "libp2p_peer_connect_event_total",
@@ -126,6 +177,20 @@ pub static TOTAL_RPC_ERRORS_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLo
 pub static TOTAL_RPC_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec("libp2p_rpc_requests_total", "RPC requests total", &["type"])
 });
+pub static RPC_RANGE_RESPONSE_BYTES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_rpc_range_response_bytes_total",
+        "SSZ bytes of by-range response chunks written to peers",
+        &["protocol"],
+    )
+});
+pub static RPC_RANGE_RESPONSE_FLOW_CONTROL_TIMEOUTS: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "libp2p_rpc_range_response_flow_control_timeouts_total",
+            "By-range responses aborted because the peer did not read the in-flight chunks",
+        )
+    });
 pub static PEER_ACTION_EVENTS_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "libp2p_peer_actions_per_client",
@@ -216,3 +281,10 @@ pub fn scrape_discovery_metrics() {
     set_gauge_vec(&NAT_OPEN, &["discv5_ipv4"], metrics.ipv4_contactable as i64);
     set_gauge_vec(&NAT_OPEN, &["discv5_ipv6"], metrics.ipv6_contactable as i64);
 }
+
+/// Returns true if any of the `NAT_OPEN` estimates indicate that external nodes can contact us.
+pub fn is_nat_open() -> bool {
+    ["discv5_ipv4", "discv5_ipv6", "libp2p_ipv4", "libp2p_ipv6"]
+        .into_iter()
+        .any(|protocol| get_int_gauge(&NAT_OPEN, &[protocol]).is_some_and(|gauge| gauge.get() == 1))
+}
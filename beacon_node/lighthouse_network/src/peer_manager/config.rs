@@ -1,3 +1,6 @@
+use libp2p::{Multiaddr, PeerId};
+use std::collections::HashMap;
+
 /// The time in seconds between re-status's peers.
 pub const DEFAULT_STATUS_INTERVAL: u64 = 300;
 
@@ -22,6 +25,8 @@ pub struct Config {
     pub quic_enabled: bool,
     /// Target number of peers to connect to.
     pub target_peer_count: usize,
+    /// The addresses of trusted peers, which are always dialed when disconnected.
+    pub trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>>,
 
     /* RPC related configurations */
     /// Time in seconds between status requests sent to peers.
@@ -41,6 +46,7 @@ impl Default for Config {
             metrics_enabled: false,
             quic_enabled: true,
             target_peer_count: DEFAULT_TARGET_PEERS,
+            trusted_peer_addresses: HashMap::new(),
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
@@ -3,10 +3,11 @@
 use crate::discovery::enr_ext::EnrExt;
 use crate::discovery::peer_id_to_node_id;
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RpcErrorResponse};
-use crate::service::TARGET_SUBNET_PEERS;
+use crate::types::Reachability;
 use crate::{metrics, Gossipsub, NetworkGlobals, PeerId, Subnet, SubnetDiscovery};
 use delay_map::HashSetDelay;
 use discv5::Enr;
+use libp2p::autonat::NatStatus;
 use libp2p::identify::Info as IdentifyInfo;
 use lru_cache::LRUTimeCache;
 use peerdb::{BanOperation, BanResult, ScoreUpdateResult};
@@ -33,11 +34,104 @@ use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
 use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::net::IpAddr;
-use strum::IntoEnumIterator;
+use strum::{IntoEnumIterator, IntoStaticStr};
 
 pub mod config;
 mod network_behaviour;
 
+/// Returns the name of the transport used by `multiaddr`, for metrics.
+pub(crate) fn multiaddr_transport(multiaddr: &Multiaddr) -> Option<&'static str> {
+    multiaddr.iter().find_map(|proto| match proto {
+        multiaddr::Protocol::QuicV1 => Some("quic"),
+        multiaddr::Protocol::Tcp(_) => Some("tcp"),
+        _ => None,
+    })
+}
+
+/// The IP address families a peer can be dialed on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum AddressFamily {
+    Ipv4,
+    Ipv6,
+}
+
+impl AddressFamily {
+    /// Returns the address family of `multiaddr`, if it has an IP or DNS component.
+    pub(crate) fn of(multiaddr: &Multiaddr) -> Option<Self> {
+        multiaddr.iter().find_map(|proto| match proto {
+            multiaddr::Protocol::Ip4(_) | multiaddr::Protocol::Dns4(_) => Some(Self::Ipv4),
+            multiaddr::Protocol::Ip6(_) | multiaddr::Protocol::Dns6(_) => Some(Self::Ipv6),
+            _ => None,
+        })
+    }
+
+    fn other(self) -> Self {
+        match self {
+            Self::Ipv4 => Self::Ipv6,
+            Self::Ipv6 => Self::Ipv4,
+        }
+    }
+}
+
+/// Returns the addresses to dial `enr` on, in order of preference.
+///
+/// QUIC addresses are preferred as they establish connections with fewer round trips and traverse
+/// NATs more reliably. The swarm dials addresses one at a time, so the TCP addresses are only
+/// dialed if no QUIC connection can be established.
+///
+/// Within each transport the addresses are ordered as in `families`, alternating between address
+/// families in the style of happy eyeballs (RFC 8305), so a broken family costs at most one failed
+/// attempt before the other family is tried. Addresses in families not listed are not dialed.
+pub(crate) fn dial_multiaddrs(
+    enr: &Enr,
+    quic_enabled: bool,
+    families: &[AddressFamily],
+) -> Vec<Multiaddr> {
+    let family_rank = |multiaddr: &Multiaddr| {
+        AddressFamily::of(multiaddr)
+            .and_then(|family| families.iter().position(|preferred| *preferred == family))
+    };
+    let quic_multiaddrs = if quic_enabled {
+        enr.multiaddr_quic()
+    } else {
+        vec![]
+    };
+
+    let mut multiaddrs = Vec::new();
+    for mut transport_multiaddrs in [quic_multiaddrs, enr.multiaddr_tcp()] {
+        transport_multiaddrs.retain(|multiaddr| family_rank(multiaddr).is_some());
+        transport_multiaddrs.sort_by_key(family_rank);
+        multiaddrs.extend(transport_multiaddrs);
+    }
+    multiaddrs
+}
+
+/// Estimates whether other nodes can contact us.
+///
+/// A verdict from AutoNAT, which asks peers to dial our listening addresses back, takes precedence.
+/// Without one, we are publicly reachable if a node has contacted us over discovery or libp2p. If
+/// nobody has contacted us after we have been connected to enough peers for long enough, we are
+/// most likely behind a NAT.
+fn estimate_reachability(
+    autonat: Option<Reachability>,
+    nat_open: bool,
+    elapsed: Duration,
+    connected_peers: usize,
+) -> Reachability {
+    if let Some(reachability) = autonat {
+        reachability
+    } else if nat_open {
+        Reachability::Public
+    } else if elapsed >= REACHABILITY_PROBE_DURATION
+        && connected_peers >= MIN_REACHABILITY_PROBE_PEERS
+    {
+        Reachability::BehindNat
+    } else {
+        Reachability::Unknown
+    }
+}
+
 /// The heartbeat performs regular updates such as updating reputations and performing discovery
 /// requests. This defines the interval in seconds.
 const HEARTBEAT_INTERVAL: u64 = 30;
@@ -63,6 +157,13 @@ pub const MIN_OUTBOUND_ONLY_FACTOR: f32 = 0.2;
 /// limit is 55, and we are at 55 peers, the following parameter provisions a few more slots of
 /// dialing priority peers we need for validator duties.
 pub const PRIORITY_PEER_EXCESS: f32 = 0.2;
+/// The time we wait after startup for other nodes to contact us before considering ourselves to be
+/// behind a NAT.
+const REACHABILITY_PROBE_DURATION: Duration = Duration::from_secs(600);
+/// The number of outbound peers we need before considering ourselves to be behind a NAT. These
+/// peers learn our addresses through identify and discovery, so if we are reachable some of them
+/// (or their peers) are expected to contact us.
+const MIN_REACHABILITY_PROBE_PEERS: usize = 5;
 
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<E: EthSpec> {
@@ -80,6 +181,10 @@ pub struct PeerManager<E: EthSpec> {
     target_peers: usize,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
+    /// The addresses of trusted peers, which are always dialed when disconnected.
+    trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>>,
+    /// Trusted peers queued to be dialed.
+    trusted_peers_to_dial: Vec<PeerId>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
     /// reconnection.
     // NOTE: This just prevents re-connections. The state of the peer is otherwise unaffected. A
@@ -107,6 +212,13 @@ pub struct PeerManager<E: EthSpec> {
     metrics_enabled: bool,
     /// Keeps track of whether the QUIC protocol is enabled or not.
     quic_enabled: bool,
+    /// The address family dialed first, which is the family of the most recent successful
+    /// outbound connection.
+    preferred_address_family: AddressFamily,
+    /// The time the `PeerManager` was created, used to estimate our reachability.
+    started: Instant,
+    /// The reachability confirmed by AutoNAT probes, if any.
+    autonat_reachability: Option<Reachability>,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
@@ -153,6 +265,7 @@ impl<E: EthSpec> PeerManager<E> {
             ping_interval_inbound,
             ping_interval_outbound,
             quic_enabled,
+            trusted_peer_addresses,
         } = cfg;
 
         // Set up the peer manager heartbeat interval
@@ -162,6 +275,8 @@ impl<E: EthSpec> PeerManager<E> {
             network_globals,
             events: SmallVec::new(),
             peers_to_dial: Default::default(),
+            trusted_peer_addresses,
+            trusted_peers_to_dial: Default::default(),
             inbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_inbound)),
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
@@ -172,6 +287,9 @@ impl<E: EthSpec> PeerManager<E> {
             discovery_enabled,
             metrics_enabled,
             quic_enabled,
+            preferred_address_family: AddressFamily::Ipv6,
+            started: Instant::now(),
+            autonat_reachability: None,
             log: log.clone(),
         })
     }
@@ -436,6 +554,24 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Returns the address families we can dial peers on, the preferred family first.
+    ///
+    /// A family is only dialed if we listen on it, as a node without a socket in that family
+    /// generally has no connectivity over it either.
+    fn dial_address_families(&self) -> Vec<AddressFamily> {
+        let listen_addrs = self.network_globals.config.listen_addrs();
+        [
+            self.preferred_address_family,
+            self.preferred_address_family.other(),
+        ]
+        .into_iter()
+        .filter(|family| match family {
+            AddressFamily::Ipv4 => listen_addrs.v4().is_some(),
+            AddressFamily::Ipv6 => listen_addrs.v6().is_some(),
+        })
+        .collect()
+    }
+
     /// Reports if a peer is banned or not.
     ///
     /// This is used to determine if we should accept incoming connections.
@@ -853,8 +989,8 @@ impl<E: EthSpec> PeerManager<E> {
         true
     }
 
-    // Gracefully disconnects a peer without banning them.
-    fn disconnect_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
+    /// Gracefully disconnects a peer without banning them.
+    pub fn disconnect_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
         self.events
             .push(PeerManagerEvent::DisconnectPeer(peer_id, reason));
         self.network_globals
@@ -879,7 +1015,9 @@ impl<E: EthSpec> PeerManager<E> {
                     .read()
                     .good_peers_on_subnet(Subnet::SyncCommittee(*k))
                     .count()
-                    < TARGET_SUBNET_PEERS
+                    < self
+                        .network_globals
+                        .target_subnet_peers(&Subnet::SyncCommittee(*k))
                 {
                     Some(SubnetDiscovery {
                         subnet: Subnet::SyncCommittee(*k),
@@ -903,6 +1041,36 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Run discovery queries for the backbone subnets which have fewer than their target number
+    /// of peers.
+    fn maintain_backbone_peers(&mut self) {
+        let subnets_to_discover: Vec<SubnetDiscovery> = {
+            let peers = self.network_globals.peers.read();
+            self.network_globals
+                .config
+                .backbone_subnets()
+                .filter(|subnet| {
+                    peers.good_peers_on_subnet(*subnet).count()
+                        < self.network_globals.target_subnet_peers(subnet)
+                })
+                .map(|subnet| SubnetDiscovery {
+                    subnet,
+                    min_ttl: None,
+                })
+                .collect()
+        };
+
+        if !subnets_to_discover.is_empty() {
+            debug!(
+                self.log,
+                "Making subnet queries for maintaining backbone peers";
+                "subnets" => ?subnets_to_discover.iter().map(|s| s.subnet).collect::<Vec<_>>()
+            );
+            self.events
+                .push(PeerManagerEvent::DiscoverSubnetPeers(subnets_to_discover));
+        }
+    }
+
     /// This function checks the status of our current peers and optionally requests a discovery
     /// query if we need to find more peers to maintain the current number of peers
     fn maintain_peer_count(&mut self, dialing_peers: usize) {
@@ -956,6 +1124,8 @@ impl<E: EthSpec> PeerManager<E> {
     ///     MIN_SYNC_COMMITTEE_PEERS
     ///     number should be set low as an absolute lower bound to maintain peers on the sync
     ///     committees.
+    /// - Do not prune peers on a backbone subnet which has no more than its target number of peers
+    ///     when pruning peers based on subnet count.
     /// - Do not prune trusted peers. NOTE: This means if a user has more trusted peers than the
     ///     excess peer limit, all of the following logic is subverted as we will not prune any peers.
     ///     Also, the more trusted peers a user has, the less room Lighthouse has to efficiently manage
@@ -1041,9 +1211,35 @@ impl<E: EthSpec> PeerManager<E> {
                 std::collections::HashSet<SyncSubnetId>,
             > = HashMap::new();
 
+            // Peers on backbone subnets that are at or below their target are kept.
+            let backbone_peers: HashSet<PeerId> = {
+                let peers = self.network_globals.peers.read();
+                self.network_globals
+                    .config
+                    .backbone_subnets()
+                    .flat_map(|subnet| {
+                        let peers_on_subnet = peers
+                            .good_peers_on_subnet(subnet)
+                            .copied()
+                            .collect::<Vec<_>>();
+                        if peers_on_subnet.len()
+                            <= self.network_globals.target_subnet_peers(&subnet)
+                        {
+                            peers_on_subnet
+                        } else {
+                            vec![]
+                        }
+                    })
+                    .collect()
+            };
+
             for (peer_id, info) in self.network_globals.peers.read().connected_peers() {
-                // Ignore peers we trust or that we are already pruning
-                if info.is_trusted() || peers_to_prune.contains(peer_id) {
+                // Ignore peers we trust, that we are already pruning or that we need on a
+                // backbone subnet
+                if info.is_trusted()
+                    || peers_to_prune.contains(peer_id)
+                    || backbone_peers.contains(peer_id)
+                {
                     continue;
                 }
 
@@ -1214,6 +1410,12 @@ impl<E: EthSpec> PeerManager<E> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Maintain the target count of peers on backbone subnets.
+        self.maintain_backbone_peers();
+
+        // Redial any trusted peers we have lost connection to.
+        self.maintain_trusted_peers();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
@@ -1223,6 +1425,63 @@ impl<E: EthSpec> PeerManager<E> {
 
         // Maintains memory by shrinking mappings
         self.shrink_mappings();
+
+        // Re-estimate whether other nodes can contact us.
+        self.update_reachability();
+    }
+
+    /// Queues a dial to every trusted peer with known addresses that is not connected or being
+    /// dialed.
+    fn maintain_trusted_peers(&mut self) {
+        let peerdb = self.network_globals.peers.read();
+        for peer_id in self.trusted_peer_addresses.keys() {
+            if peerdb.should_dial(peer_id) && !self.trusted_peers_to_dial.contains(peer_id) {
+                debug!(self.log, "Dialing disconnected trusted peer"; "peer_id" => %peer_id);
+                self.trusted_peers_to_dial.push(*peer_id);
+            }
+        }
+    }
+
+    /// Records the outcome of the AutoNAT probes of our listening addresses.
+    pub fn autonat_status_changed(&mut self, status: &NatStatus) {
+        self.autonat_reachability = match status {
+            NatStatus::Public(_) => Some(Reachability::Public),
+            NatStatus::Private => Some(Reachability::BehindNat),
+            NatStatus::Unknown => None,
+        };
+        self.update_reachability();
+    }
+
+    /// Estimates whether other nodes can contact us.
+    fn update_reachability(&mut self) {
+        if self.discovery_enabled {
+            // Refresh the discovery `NAT_OPEN` estimates, which are otherwise only updated when
+            // metrics are scraped.
+            metrics::scrape_discovery_metrics();
+        }
+
+        let reachability = estimate_reachability(
+            self.autonat_reachability,
+            metrics::is_nat_open(),
+            self.started.elapsed(),
+            self.network_globals.connected_peers(),
+        );
+
+        let previous = std::mem::replace(
+            &mut *self.network_globals.reachability.write(),
+            reachability,
+        );
+        if previous != reachability {
+            debug!(self.log, "Reachability updated"; "reachability" => reachability.as_ref());
+        }
+
+        for status in Reachability::iter() {
+            metrics::set_gauge_vec(
+                &metrics::NAT_REACHABILITY,
+                &[status.as_ref()],
+                (status == reachability) as i64,
+            );
+        }
     }
 
     // Reduce memory footprint by routinely shrinking associating mappings.
@@ -1328,13 +1587,7 @@ impl<E: EthSpec> PeerManager<E> {
             let transport = peer_info
                 .listening_addresses()
                 .iter()
-                .find_map(|addr| {
-                    addr.iter().find_map(|proto| match proto {
-                        multiaddr::Protocol::QuicV1 => Some("quic"),
-                        multiaddr::Protocol::Tcp(_) => Some("tcp"),
-                        _ => None,
-                    })
-                })
+                .find_map(multiaddr_transport)
                 .unwrap_or("unknown");
             *peers_connected_mutli
                 .entry((direction, transport))
@@ -1571,6 +1824,31 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_redials_disconnected_trusted_peers() {
+        let trusted_peer = PeerId::random();
+        let mut peer_manager = build_peer_manager_with_trusted_peers(vec![trusted_peer], 3).await;
+        peer_manager
+            .trusted_peer_addresses
+            .insert(trusted_peer, vec!["/ip4/0.0.0.0/tcp/9000".parse().unwrap()]);
+
+        // The trusted peer is dialed on the first heartbeat, and only queued once.
+        peer_manager.heartbeat();
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.trusted_peers_to_dial, vec![trusted_peer]);
+
+        // A connected trusted peer is not redialed.
+        peer_manager.trusted_peers_to_dial.clear();
+        peer_manager.inject_connect_outgoing(&trusted_peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.heartbeat();
+        assert!(peer_manager.trusted_peers_to_dial.is_empty());
+
+        // Once it disconnects, it is redialed.
+        peer_manager.inject_disconnect(&trusted_peer);
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.trusted_peers_to_dial, vec![trusted_peer]);
+    }
+
     #[tokio::test]
     async fn test_peer_manager_not_enough_outbound_peers_no_panic_during_heartbeat() {
         let mut peer_manager = build_peer_manager(20).await;
@@ -2307,6 +2585,135 @@ mod tests {
         assert!(connected_peers.contains(&peers[7]));
     }
 
+    #[test]
+    fn reachability_without_autonat() {
+        let waited = REACHABILITY_PROBE_DURATION;
+        let enough_peers = MIN_REACHABILITY_PROBE_PEERS;
+
+        assert_eq!(
+            estimate_reachability(None, false, Duration::ZERO, enough_peers),
+            Reachability::Unknown
+        );
+        assert_eq!(
+            estimate_reachability(None, false, waited, enough_peers - 1),
+            Reachability::Unknown
+        );
+        assert_eq!(
+            estimate_reachability(None, false, waited, enough_peers),
+            Reachability::BehindNat
+        );
+        assert_eq!(
+            estimate_reachability(None, true, Duration::ZERO, 0),
+            Reachability::Public
+        );
+        assert_eq!(
+            estimate_reachability(None, true, waited, enough_peers),
+            Reachability::Public
+        );
+    }
+
+    #[test]
+    fn reachability_autonat_takes_precedence() {
+        let waited = REACHABILITY_PROBE_DURATION;
+        let enough_peers = MIN_REACHABILITY_PROBE_PEERS;
+
+        assert_eq!(
+            estimate_reachability(Some(Reachability::Public), false, waited, enough_peers),
+            Reachability::Public
+        );
+        assert_eq!(
+            estimate_reachability(Some(Reachability::BehindNat), true, Duration::ZERO, 0),
+            Reachability::BehindNat
+        );
+    }
+
+    #[tokio::test]
+    async fn autonat_status_transitions() {
+        let mut peer_manager = build_peer_manager(3).await;
+        let reachability =
+            |peer_manager: &PeerManager<E>| *peer_manager.network_globals.reachability.read();
+        assert_eq!(reachability(&peer_manager), Reachability::Unknown);
+
+        peer_manager
+            .autonat_status_changed(&NatStatus::Public("/ip4/1.2.3.4/tcp/9000".parse().unwrap()));
+        assert_eq!(reachability(&peer_manager), Reachability::Public);
+
+        peer_manager.autonat_status_changed(&NatStatus::Private);
+        assert_eq!(reachability(&peer_manager), Reachability::BehindNat);
+
+        // Once the probes are inconclusive again, we fall back to the heuristic, which has no
+        // evidence either way so soon after startup.
+        peer_manager.autonat_status_changed(&NatStatus::Unknown);
+        assert_eq!(reachability(&peer_manager), Reachability::Unknown);
+    }
+
+    #[test]
+    fn dial_multiaddrs_prefer_quic() {
+        let key = discv5::enr::CombinedKey::generate_secp256k1();
+        let enr = discv5::enr::Enr::builder()
+            .ip4(std::net::Ipv4Addr::LOCALHOST)
+            .tcp4(9000)
+            .add_value(crate::discovery::enr_ext::QUIC_ENR_KEY, &9001u16)
+            .build(&key)
+            .unwrap();
+
+        let transports = |quic_enabled| {
+            dial_multiaddrs(&enr, quic_enabled, &[AddressFamily::Ipv4])
+                .iter()
+                .map(|multiaddr| multiaddr_transport(multiaddr).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(transports(true), vec!["quic", "tcp"]);
+        assert_eq!(transports(false), vec!["tcp"]);
+    }
+
+    #[test]
+    fn dial_multiaddrs_interleave_address_families() {
+        let key = discv5::enr::CombinedKey::generate_secp256k1();
+        let enr = discv5::enr::Enr::builder()
+            .ip4(std::net::Ipv4Addr::LOCALHOST)
+            .ip6(std::net::Ipv6Addr::LOCALHOST)
+            .tcp4(9000)
+            .tcp6(9000)
+            .add_value(crate::discovery::enr_ext::QUIC_ENR_KEY, &9001u16)
+            .add_value(crate::discovery::enr_ext::QUIC6_ENR_KEY, &9001u16)
+            .build(&key)
+            .unwrap();
+
+        let dial_order = |families: &[AddressFamily]| {
+            dial_multiaddrs(&enr, true, families)
+                .iter()
+                .map(|multiaddr| {
+                    let family: &'static str = AddressFamily::of(multiaddr).unwrap().into();
+                    (multiaddr_transport(multiaddr).unwrap(), family)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            dial_order(&[AddressFamily::Ipv6, AddressFamily::Ipv4]),
+            vec![
+                ("quic", "ipv6"),
+                ("quic", "ipv4"),
+                ("tcp", "ipv6"),
+                ("tcp", "ipv4")
+            ]
+        );
+        assert_eq!(
+            dial_order(&[AddressFamily::Ipv4, AddressFamily::Ipv6]),
+            vec![
+                ("quic", "ipv4"),
+                ("quic", "ipv6"),
+                ("tcp", "ipv4"),
+                ("tcp", "ipv6")
+            ]
+        );
+        // An IPv6-only node does not dial IPv4 addresses.
+        assert_eq!(
+            dial_order(&[AddressFamily::Ipv6]),
+            vec![("quic", "ipv6"), ("tcp", "ipv6")]
+        );
+    }
+
     // Test properties PeerManager should have using randomly generated input.
     #[cfg(test)]
     mod property_based_tests {
@@ -11,7 +11,7 @@ use libp2p::multiaddr::Protocol;
 use libp2p::swarm::behaviour::{ConnectionClosed, ConnectionEstablished, DialFailure, FromSwarm};
 use libp2p::swarm::dial_opts::{DialOpts, PeerCondition};
 use libp2p::swarm::dummy::ConnectionHandler;
-use libp2p::swarm::{ConnectionDenied, ConnectionId, NetworkBehaviour, ToSwarm};
+use libp2p::swarm::{ConnectionDenied, ConnectionId, DialError, NetworkBehaviour, ToSwarm};
 pub use metrics::{set_gauge_vec, NAT_OPEN};
 use slog::{debug, error, trace};
 use types::EthSpec;
@@ -20,7 +20,10 @@ use crate::discovery::enr_ext::EnrExt;
 use crate::types::SyncState;
 use crate::{metrics, ClearDialError};
 
-use super::{ConnectingType, PeerManager, PeerManagerEvent};
+use super::{
+    dial_multiaddrs, multiaddr_transport, AddressFamily, ConnectingType, PeerManager,
+    PeerManagerEvent,
+};
 
 impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
     type ConnectionHandler = ConnectionHandler;
@@ -94,18 +97,15 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             self.events.shrink_to_fit();
         }
 
-        if let Some(enr) = self.peers_to_dial.pop() {
+        while let Some(enr) = self.peers_to_dial.pop() {
+            let multiaddrs =
+                dial_multiaddrs(&enr, self.quic_enabled, &self.dial_address_families());
+            if multiaddrs.is_empty() {
+                debug!(self.log, "Peer has no address in a dialable address family"; "peer_id" => %enr.peer_id());
+                continue;
+            }
             self.inject_peer_connection(&enr.peer_id(), ConnectingType::Dialing, Some(enr.clone()));
 
-            // Prioritize Quic connections over Tcp ones.
-            let multiaddrs = [
-                self.quic_enabled
-                    .then_some(enr.multiaddr_quic())
-                    .unwrap_or_default(),
-                enr.multiaddr_tcp(),
-            ]
-            .concat();
-
             debug!(self.log, "Dialing peer"; "peer_id"=> %enr.peer_id(), "multiaddrs" => ?multiaddrs);
             return Poll::Ready(ToSwarm::Dial {
                 opts: DialOpts::peer_id(enr.peer_id())
@@ -115,6 +115,20 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             });
         }
 
+        if let Some(peer_id) = self.trusted_peers_to_dial.pop() {
+            if let Some(multiaddrs) = self.trusted_peer_addresses.get(&peer_id).cloned() {
+                self.inject_peer_connection(&peer_id, ConnectingType::Dialing, None);
+
+                debug!(self.log, "Dialing trusted peer"; "peer_id"=> %peer_id, "multiaddrs" => ?multiaddrs);
+                return Poll::Ready(ToSwarm::Dial {
+                    opts: DialOpts::peer_id(peer_id)
+                        .condition(PeerCondition::Disconnected)
+                        .addresses(multiaddrs)
+                        .build(),
+                });
+            }
+        }
+
         Poll::Pending
     }
 
@@ -139,6 +153,20 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
                 connection_id: _,
             }) => {
                 debug!(self.log, "Failed to dial peer"; "peer_id"=> ?peer_id, "error" => %ClearDialError(error));
+                if self.metrics_enabled {
+                    if let DialError::Transport(errors) = error {
+                        for (multiaddr, _) in errors {
+                            metrics::inc_counter_vec(
+                                &metrics::DIAL_FAILURES_PER_TRANSPORT,
+                                &[multiaddr_transport(multiaddr).unwrap_or("unknown")],
+                            );
+                            metrics::inc_counter_vec(
+                                &metrics::DIAL_FAILURES_PER_ADDRESS_FAMILY,
+                                &[AddressFamily::of(multiaddr).map_or("unknown", Into::into)],
+                            );
+                        }
+                    }
+                }
                 self.on_dial_failure(peer_id);
             }
             _ => {
@@ -263,6 +291,23 @@ impl<E: EthSpec> PeerManager<E> {
         // Update the prometheus metrics
         if self.metrics_enabled {
             metrics::inc_counter(&metrics::PEER_CONNECT_EVENT_COUNT);
+            let transport = multiaddr_transport(endpoint.get_remote_address());
+            match transport {
+                Some("quic") => metrics::inc_gauge(&metrics::QUIC_PEERS_CONNECTED),
+                Some("tcp") => metrics::inc_gauge(&metrics::TCP_PEERS_CONNECTED),
+                _ => {}
+            }
+            metrics::inc_counter_vec(
+                &metrics::CONNECTIONS_ESTABLISHED_PER_TRANSPORT,
+                &[transport.unwrap_or("unknown")],
+            );
+            let family =
+                AddressFamily::of(endpoint.get_remote_address()).map_or("unknown", Into::into);
+            metrics::inc_gauge_vec(&metrics::PEERS_CONNECTED_PER_ADDRESS_FAMILY, &[family]);
+            metrics::inc_counter_vec(
+                &metrics::CONNECTIONS_ESTABLISHED_PER_ADDRESS_FAMILY,
+                &[family],
+            );
 
             self.update_peer_count_metrics();
         }
@@ -276,6 +321,10 @@ impl<E: EthSpec> PeerManager<E> {
                     .push(PeerManagerEvent::PeerConnectedIncoming(peer_id));
             }
             ConnectedPoint::Dialer { address, .. } => {
+                // Dial the family that last worked first, as happy eyeballs caches the winner.
+                if let Some(family) = AddressFamily::of(address) {
+                    self.preferred_address_family = family;
+                }
                 self.inject_connect_outgoing(&peer_id, address.clone(), None);
                 self.events
                     .push(PeerManagerEvent::PeerConnectedOutgoing(peer_id));
@@ -286,9 +335,21 @@ impl<E: EthSpec> PeerManager<E> {
     fn on_connection_closed(
         &mut self,
         peer_id: PeerId,
-        _endpoint: &ConnectedPoint,
+        endpoint: &ConnectedPoint,
         remaining_established: usize,
     ) {
+        if self.metrics_enabled {
+            match multiaddr_transport(endpoint.get_remote_address()) {
+                Some("quic") => metrics::dec_gauge(&metrics::QUIC_PEERS_CONNECTED),
+                Some("tcp") => metrics::dec_gauge(&metrics::TCP_PEERS_CONNECTED),
+                _ => {}
+            }
+            metrics::dec_gauge_vec(
+                &metrics::PEERS_CONNECTED_PER_ADDRESS_FAMILY,
+                &[AddressFamily::of(endpoint.get_remote_address()).map_or("unknown", Into::into)],
+            );
+        }
+
         if remaining_established > 0 {
             return;
         }
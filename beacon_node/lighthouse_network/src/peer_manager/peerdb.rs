@@ -3,10 +3,10 @@ use crate::discovery::{peer_id_to_node_id, CombinedKey};
 use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, EnrExt, Gossipsub, PeerId};
 use itertools::Itertools;
 use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
-use score::{PeerAction, ReportSource, Score, ScoreState};
+use score::{PeerAction, PenaltyRecord, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::net::IpAddr;
-use std::time::Instant;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::{cmp::Ordering, fmt::Display};
 use std::{
     collections::{HashMap, HashSet},
@@ -437,19 +437,22 @@ impl<E: EthSpec> PeerDB<E> {
             .iter_mut()
             .filter(|(_peer_id, info)| info.is_connected())
             .filter_map(|(peer_id, info)| {
-                gossipsub
-                    .peer_score(peer_id)
-                    .map(|score| (peer_id, info, score))
+                gossipsub.peer_score(peer_id).map(|score| {
+                    let breakdown = gossipsub.peer_score_breakdown(peer_id).map(Into::into);
+                    (peer_id, info, score, breakdown)
+                })
             })
             .collect();
 
         // sort descending by score
-        peers.sort_unstable_by(|(.., s1), (.., s2)| s2.partial_cmp(s1).unwrap_or(Ordering::Equal));
+        peers.sort_unstable_by(|(_, _, s1, _), (_, _, s2, _)| {
+            s2.partial_cmp(s1).unwrap_or(Ordering::Equal)
+        });
 
         let mut to_ignore_negative_peers =
             (target_peers as f32 * ALLOWED_NEGATIVE_GOSSIPSUB_FACTOR).ceil() as usize;
 
-        for (peer_id, info, score) in peers {
+        for (peer_id, info, score, breakdown) in peers {
             let previous_state = info.score_state();
             info.update_gossipsub_score(
                 score,
@@ -461,6 +464,7 @@ impl<E: EthSpec> PeerDB<E> {
                 } else {
                     false
                 },
+                breakdown,
             );
 
             actions.push((
@@ -532,10 +536,20 @@ impl<E: EthSpec> PeerDB<E> {
             Some(info) => {
                 let previous_state = info.score_state();
                 info.apply_peer_action_to_score(action);
+                let source: &'static str = source.into();
                 metrics::inc_counter_vec(
                     &metrics::PEER_ACTION_EVENTS_PER_CLIENT,
-                    &[info.client().kind.as_ref(), action.as_ref(), source.into()],
+                    &[info.client().kind.as_ref(), action.as_ref(), source],
                 );
+                info.record_penalty(PenaltyRecord {
+                    action: action.into(),
+                    source,
+                    msg,
+                    timestamp: SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs()),
+                    score: info.score().score(),
+                });
                 let result =
                     Self::handle_score_transition(previous_state, peer_id, info, &self.log);
                 if previous_state == info.score_state() {
@@ -1,5 +1,5 @@
 use super::client::Client;
-use super::score::{PeerAction, Score, ScoreState};
+use super::score::{GossipsubScoreBreakdown, PeerAction, PenaltyRecord, Score, ScoreState};
 use super::sync_status::SyncStatus;
 use crate::discovery::Eth2Enr;
 use crate::{rpc::MetaData, types::Subnet};
@@ -9,13 +9,16 @@ use serde::{
     ser::{SerializeStruct, Serializer},
     Serialize,
 };
-use std::collections::HashSet;
+use std::collections::{HashSet, VecDeque};
 use std::net::IpAddr;
 use std::time::Instant;
 use strum::AsRefStr;
 use types::{DataColumnSubnetId, EthSpec};
 use PeerConnectionStatus::*;
 
+/// The number of penalties retained for each peer, for diagnostics.
+const MAX_PENALTY_HISTORY: usize = 32;
+
 /// Information about a given connected peer.
 #[derive(Clone, Debug, Serialize)]
 #[serde(bound = "E: EthSpec")]
@@ -56,6 +59,12 @@ pub struct PeerInfo<E: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The components of the peer's gossipsub score, as of the last gossipsub score update.
+    #[serde(skip)]
+    gossipsub_score_breakdown: Option<GossipsubScoreBreakdown>,
+    /// The most recent penalties applied to the peer's score, oldest first.
+    #[serde(skip)]
+    penalties: VecDeque<PenaltyRecord>,
 }
 
 impl<E: EthSpec> Default for PeerInfo<E> {
@@ -74,6 +83,8 @@ impl<E: EthSpec> Default for PeerInfo<E> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            gossipsub_score_breakdown: None,
+            penalties: VecDeque::new(),
         }
     }
 }
@@ -273,10 +284,21 @@ impl<E: EthSpec> PeerInfo<E> {
     }
 
     /// Returns the state of the peer based on the score.
-    pub(crate) fn score_state(&self) -> ScoreState {
+    pub fn score_state(&self) -> ScoreState {
         self.score.state()
     }
 
+    /// Returns the components of the peer's gossipsub score, as of the last gossipsub score
+    /// update.
+    pub fn gossipsub_score_breakdown(&self) -> Option<&GossipsubScoreBreakdown> {
+        self.gossipsub_score_breakdown.as_ref()
+    }
+
+    /// Returns the most recent penalties applied to the peer's score, oldest first.
+    pub fn penalties(&self) -> impl Iterator<Item = &PenaltyRecord> {
+        self.penalties.iter()
+    }
+
     /// Returns true if the gossipsub score is sufficient.
     pub fn is_good_gossipsub_peer(&self) -> bool {
         self.score.is_good_gossipsub_peer()
@@ -413,9 +435,24 @@ impl<E: EthSpec> PeerInfo<E> {
         }
     }
 
+    /// Records a penalty applied to the peer's score, discarding the oldest penalty if
+    /// `MAX_PENALTY_HISTORY` penalties are already recorded.
+    pub(in crate::peer_manager) fn record_penalty(&mut self, penalty: PenaltyRecord) {
+        if self.penalties.len() >= MAX_PENALTY_HISTORY {
+            self.penalties.pop_front();
+        }
+        self.penalties.push_back(penalty);
+    }
+
     /// Updates the gossipsub score with a new score. Optionally ignore the gossipsub score.
-    pub(super) fn update_gossipsub_score(&mut self, new_score: f64, ignore: bool) {
+    pub(super) fn update_gossipsub_score(
+        &mut self,
+        new_score: f64,
+        ignore: bool,
+        breakdown: Option<GossipsubScoreBreakdown>,
+    ) {
         self.score.update_gossipsub_score(new_score, ignore);
+        self.gossipsub_score_breakdown = breakdown;
     }
 
     #[cfg(test)]
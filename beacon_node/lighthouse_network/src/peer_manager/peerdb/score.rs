@@ -10,7 +10,7 @@ use serde::Serialize;
 use std::cmp::Ordering;
 use std::sync::LazyLock;
 use std::time::Instant;
-use strum::AsRefStr;
+use strum::{AsRefStr, IntoStaticStr};
 use tokio::time::Duration;
 
 static HALFLIFE_DECAY: LazyLock<f64> = LazyLock::new(|| -(2.0f64.ln()) / SCORE_HALFLIFE);
@@ -43,7 +43,7 @@ const GOSSIPSUB_POSITIVE_SCORE_WEIGHT: f64 = GOSSIPSUB_NEGATIVE_SCORE_WEIGHT;
 /// Each variant has an associated score change.
 // To easily assess the behaviour of scores changes the number of variants should stay low, and
 // somewhat generic.
-#[derive(Debug, Clone, Copy, AsRefStr)]
+#[derive(Debug, Clone, Copy, AsRefStr, IntoStaticStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum PeerAction {
     /// We should not communicate more with this peer.
@@ -65,6 +65,76 @@ pub enum PeerAction {
     HighToleranceError,
 }
 
+/// A penalty applied to a peer's score, retained for diagnostics.
+#[derive(Clone, Debug, Serialize)]
+pub struct PenaltyRecord {
+    /// The action the peer was penalized for.
+    pub action: &'static str,
+    /// The service which reported the peer.
+    pub source: &'static str,
+    /// The reason given for the report.
+    pub msg: &'static str,
+    /// The time of the report, in seconds since the UNIX epoch.
+    pub timestamp: u64,
+    /// The peer's score after the penalty was applied.
+    pub score: f64,
+}
+
+/// The components of a peer's gossipsub score, as of the last gossipsub score update.
+#[derive(Clone, Debug, Serialize)]
+pub struct GossipsubScoreBreakdown {
+    /// The components of the score of each scored topic, before the topic weight is applied.
+    pub topics: Vec<TopicScoreBreakdown>,
+    /// The sum of the topic scores, after applying each topic's weight and the topic score cap.
+    pub topic_score: f64,
+    /// The weighted application-specific score.
+    pub application_score: f64,
+    /// The weighted penalty for sharing an IP address with too many other peers.
+    pub ip_colocation_penalty: f64,
+    /// The weighted penalty for protocol misbehaviour.
+    pub behaviour_penalty: f64,
+    /// The weighted penalty for being slow to receive messages.
+    pub slow_peer_penalty: f64,
+    /// The gossipsub score.
+    pub score: f64,
+}
+
+/// The weighted components of a peer's gossipsub score in a single topic.
+#[derive(Clone, Debug, Serialize)]
+pub struct TopicScoreBreakdown {
+    pub topic: String,
+    pub time_in_mesh: f64,
+    pub first_message_deliveries: f64,
+    pub mesh_message_deliveries: f64,
+    pub mesh_failure_penalty: f64,
+    pub invalid_message_deliveries: f64,
+}
+
+impl From<gossipsub::PeerScoreBreakdown> for GossipsubScoreBreakdown {
+    fn from(breakdown: gossipsub::PeerScoreBreakdown) -> Self {
+        Self {
+            topics: breakdown
+                .topics
+                .into_iter()
+                .map(|(topic, topic_score)| TopicScoreBreakdown {
+                    topic: topic.into_string(),
+                    time_in_mesh: topic_score.time_in_mesh,
+                    first_message_deliveries: topic_score.first_message_deliveries,
+                    mesh_message_deliveries: topic_score.mesh_message_deliveries,
+                    mesh_failure_penalty: topic_score.mesh_failure_penalty,
+                    invalid_message_deliveries: topic_score.invalid_message_deliveries,
+                })
+                .collect(),
+            topic_score: breakdown.topic_score,
+            application_score: breakdown.application_score,
+            ip_colocation_penalty: breakdown.ip_colocation_penalty,
+            behaviour_penalty: breakdown.behaviour_penalty,
+            slow_peer_penalty: breakdown.slow_peer_penalty,
+            score: breakdown.score,
+        }
+    }
+}
+
 /// Service reporting a `PeerAction` for a peer.
 #[derive(Debug)]
 pub enum ReportSource {
@@ -99,8 +169,8 @@ impl std::fmt::Display for PeerAction {
 }
 
 /// The expected state of the peer given the peer's score.
-#[derive(Debug, PartialEq, Clone, Copy)]
-pub(crate) enum ScoreState {
+#[derive(Debug, PartialEq, Clone, Copy, Serialize)]
+pub enum ScoreState {
     /// We are content with the peers performance. We permit connections and messages.
     Healthy,
     /// The peer should be disconnected. We allow re-connections if the peer is persistent.
@@ -0,0 +1,293 @@
+//! Bounds the number of by-range response chunks that are produced for a peer but not yet written
+//! to its substream.
+//!
+//! By-range responses are streamed to the peer by a network task. Before producing a chunk the
+//! producer acquires a slot for the connection the request arrived on, and that connection's RPC
+//! handler releases the slot once the chunk has been written to the peer (or dropped). A peer that
+//! does not read its responses therefore stalls the producer rather than growing the handler's
+//! queue without bound.
+//!
+//! Slots are accounted per connection because a chunk can only ever be released by the handler of
+//! the connection it was sent on: when a connection closes its slots are freed along with it, even
+//! if chunks were still on their way to its handler. The bytes written are accounted per peer,
+//! across all of its connections.
+//!
+//! Producers waiting for a slot are woken by `release`, so waiting never occupies a thread.
+
+use crate::metrics;
+use libp2p::swarm::ConnectionId;
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// The default number of by-range chunks that can be in flight on a single connection.
+pub const DEFAULT_MAX_IN_FLIGHT_CHUNKS: usize = 16;
+
+/// How long a producer waits for a free slot before giving up on the response.
+const ACQUIRE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reasons a slot could not be acquired for a connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowControlError {
+    /// The connection is closed.
+    Disconnected,
+    /// The peer did not read the in-flight chunks within `ACQUIRE_TIMEOUT`.
+    Timeout,
+}
+
+#[derive(Default)]
+struct ConnectionFlow {
+    /// Chunks produced for this connection that have not been written yet.
+    in_flight: usize,
+    /// Wakes producers waiting for a slot when one is released or the connection closes.
+    notify: Arc<Notify>,
+}
+
+#[derive(Default)]
+struct PeerFlow {
+    /// The open connections to this peer.
+    connections: HashMap<ConnectionId, ConnectionFlow>,
+    /// Total SSZ bytes of the by-range chunks written to this peer.
+    bytes_sent: u64,
+}
+
+/// Shared between the RPC handlers and the producers of by-range responses.
+pub struct ResponseFlowControl {
+    max_in_flight: usize,
+    peers: Mutex<HashMap<PeerId, PeerFlow>>,
+}
+
+impl Default for ResponseFlowControl {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_IN_FLIGHT_CHUNKS)
+    }
+}
+
+impl ResponseFlowControl {
+    pub fn new(max_in_flight: usize) -> Self {
+        Self {
+            max_in_flight,
+            peers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts tracking a connection. Calling this for a tracked connection is a no-op.
+    pub fn add_connection(&self, peer_id: PeerId, connection_id: ConnectionId) {
+        self.peers
+            .lock()
+            .entry(peer_id)
+            .or_default()
+            .connections
+            .entry(connection_id)
+            .or_default();
+    }
+
+    /// Stops tracking a closed connection, freeing its slots. The peer is forgotten once all of
+    /// its connections have closed.
+    pub fn remove_connection(&self, peer_id: &PeerId, connection_id: &ConnectionId) {
+        let mut peers = self.peers.lock();
+        let Some(flow) = peers.get_mut(peer_id) else {
+            return;
+        };
+        if let Some(connection) = flow.connections.remove(connection_id) {
+            // Waiting producers observe the disconnection rather than timing out.
+            connection.notify.notify_waiters();
+        }
+        if flow.connections.is_empty() {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Attempts to take a slot on a connection without waiting. Returns `Ok(false)` if all slots
+    /// are in use.
+    pub fn try_acquire(
+        &self,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+    ) -> Result<bool, FlowControlError> {
+        let mut peers = self.peers.lock();
+        let connection = peers
+            .get_mut(peer_id)
+            .and_then(|flow| flow.connections.get_mut(connection_id))
+            .ok_or(FlowControlError::Disconnected)?;
+        if connection.in_flight >= self.max_in_flight {
+            return Ok(false);
+        }
+        connection.in_flight += 1;
+        Ok(true)
+    }
+
+    /// Waits for a slot on a connection, until the handler releases one or the connection closes
+    /// or `ACQUIRE_TIMEOUT` elapses.
+    pub async fn acquire(
+        &self,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+    ) -> Result<(), FlowControlError> {
+        let deadline = Instant::now() + ACQUIRE_TIMEOUT;
+        loop {
+            let notify = self
+                .peers
+                .lock()
+                .get(peer_id)
+                .and_then(|flow| flow.connections.get(connection_id))
+                .map(|connection| connection.notify.clone())
+                .ok_or(FlowControlError::Disconnected)?;
+
+            // Register for a wake-up before checking for a slot so that a release in between is
+            // not missed.
+            let notified = notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.try_acquire(peer_id, connection_id)? {
+                return Ok(());
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                metrics::inc_counter(&metrics::RPC_RANGE_RESPONSE_FLOW_CONTROL_TIMEOUTS);
+                return Err(FlowControlError::Timeout);
+            }
+        }
+    }
+
+    /// Releases a slot on a connection, recording `bytes_sent` bytes written to the peer. Chunks
+    /// that were dropped without being written release their slot with zero bytes.
+    pub fn release(&self, peer_id: &PeerId, connection_id: &ConnectionId, bytes_sent: usize) {
+        if let Some(flow) = self.peers.lock().get_mut(peer_id) {
+            flow.bytes_sent = flow.bytes_sent.saturating_add(bytes_sent as u64);
+            if let Some(connection) = flow.connections.get_mut(connection_id) {
+                connection.in_flight = connection.in_flight.saturating_sub(1);
+                connection.notify.notify_one();
+            }
+        }
+    }
+
+    /// Returns the number of chunks currently in flight for `peer_id`, across all of its
+    /// connections.
+    pub fn in_flight(&self, peer_id: &PeerId) -> usize {
+        self.peers.lock().get(peer_id).map_or(0, |flow| {
+            flow.connections
+                .values()
+                .map(|connection| connection.in_flight)
+                .sum()
+        })
+    }
+
+    /// Returns the total bytes of by-range chunks written to `peer_id` while it has been
+    /// connected.
+    pub fn bytes_sent(&self, peer_id: &PeerId) -> u64 {
+        self.peers
+            .lock()
+            .get(peer_id)
+            .map_or(0, |flow| flow.bytes_sent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slots_are_bounded_per_connection() {
+        let flow_control = ResponseFlowControl::new(2);
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+        let conn = ConnectionId::new_unchecked(0);
+        let other_conn = ConnectionId::new_unchecked(1);
+
+        assert_eq!(
+            flow_control.try_acquire(&peer_id, &conn),
+            Err(FlowControlError::Disconnected)
+        );
+
+        flow_control.add_connection(peer_id, conn);
+        flow_control.add_connection(other_peer_id, other_conn);
+        assert_eq!(flow_control.try_acquire(&peer_id, &conn), Ok(true));
+        assert_eq!(flow_control.try_acquire(&peer_id, &conn), Ok(true));
+        assert_eq!(flow_control.try_acquire(&peer_id, &conn), Ok(false));
+        // Other peers have their own slots.
+        assert_eq!(
+            flow_control.try_acquire(&other_peer_id, &other_conn),
+            Ok(true)
+        );
+
+        flow_control.release(&peer_id, &conn, 100);
+        assert_eq!(flow_control.in_flight(&peer_id), 1);
+        assert_eq!(flow_control.bytes_sent(&peer_id), 100);
+        assert_eq!(flow_control.try_acquire(&peer_id, &conn), Ok(true));
+
+        flow_control.remove_connection(&peer_id, &conn);
+        assert_eq!(flow_control.in_flight(&peer_id), 0);
+        assert_eq!(
+            flow_control.try_acquire(&peer_id, &conn),
+            Err(FlowControlError::Disconnected)
+        );
+    }
+
+    #[test]
+    fn closed_connection_frees_its_slots() {
+        let flow_control = ResponseFlowControl::new(1);
+        let peer_id = PeerId::random();
+        let conn = ConnectionId::new_unchecked(0);
+        let other_conn = ConnectionId::new_unchecked(1);
+        flow_control.add_connection(peer_id, conn);
+        flow_control.add_connection(peer_id, other_conn);
+
+        // Each connection has its own slots, and the bytes are accounted for the peer.
+        assert_eq!(flow_control.try_acquire(&peer_id, &conn), Ok(true));
+        assert_eq!(flow_control.try_acquire(&peer_id, &other_conn), Ok(true));
+        assert_eq!(flow_control.in_flight(&peer_id), 2);
+        flow_control.release(&peer_id, &other_conn, 10);
+        assert_eq!(flow_control.try_acquire(&peer_id, &other_conn), Ok(true));
+
+        // A chunk that never reached the handler of a closed connection doesn't leak a slot.
+        flow_control.remove_connection(&peer_id, &conn);
+        assert_eq!(flow_control.in_flight(&peer_id), 1);
+        assert_eq!(flow_control.bytes_sent(&peer_id), 10);
+
+        flow_control.remove_connection(&peer_id, &other_conn);
+        assert_eq!(flow_control.bytes_sent(&peer_id), 0);
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_for_release() {
+        let flow_control = Arc::new(ResponseFlowControl::new(1));
+        let peer_id = PeerId::random();
+        let conn = ConnectionId::new_unchecked(0);
+        flow_control.add_connection(peer_id, conn);
+        flow_control.acquire(&peer_id, &conn).await.unwrap();
+
+        let waiter = {
+            let flow_control = flow_control.clone();
+            tokio::spawn(async move { flow_control.acquire(&peer_id, &conn).await })
+        };
+        tokio::task::yield_now().await;
+        assert!(!waiter.is_finished());
+
+        flow_control.release(&peer_id, &conn, 0);
+        assert_eq!(waiter.await.unwrap(), Ok(()));
+        assert_eq!(flow_control.in_flight(&peer_id), 1);
+    }
+
+    #[tokio::test]
+    async fn acquire_fails_on_disconnect() {
+        let flow_control = Arc::new(ResponseFlowControl::new(1));
+        let peer_id = PeerId::random();
+        let conn = ConnectionId::new_unchecked(0);
+        flow_control.add_connection(peer_id, conn);
+        flow_control.acquire(&peer_id, &conn).await.unwrap();
+
+        let waiter = {
+            let flow_control = flow_control.clone();
+            tokio::spawn(async move { flow_control.acquire(&peer_id, &conn).await })
+        };
+        tokio::task::yield_now().await;
+
+        flow_control.remove_connection(&peer_id, &conn);
+        assert_eq!(waiter.await.unwrap(), Err(FlowControlError::Disconnected));
+    }
+}
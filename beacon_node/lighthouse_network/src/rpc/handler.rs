@@ -1,11 +1,13 @@
 #![allow(clippy::type_complexity)]
 #![allow(clippy::cognitive_complexity)]
 
+use super::flow_control::ResponseFlowControl;
 use super::methods::{GoodbyeReason, RpcErrorResponse, RpcResponse};
 use super::outbound::OutboundRequestContainer;
 use super::protocol::{InboundOutput, Protocol, RPCError, RPCProtocol, RequestType};
 use super::RequestId;
 use super::{RPCReceived, RPCSend, ReqId, Request};
+use crate::metrics;
 use crate::rpc::outbound::OutboundFramed;
 use crate::rpc::protocol::InboundFramed;
 use fnv::FnvHashMap;
@@ -15,7 +17,8 @@ use libp2p::swarm::handler::{
     ConnectionEvent, ConnectionHandler, ConnectionHandlerEvent, DialUpgradeError,
     FullyNegotiatedInbound, FullyNegotiatedOutbound, StreamUpgradeError, SubstreamProtocol,
 };
-use libp2p::swarm::Stream;
+use libp2p::swarm::{ConnectionId, Stream};
+use libp2p::PeerId;
 use slog::{crit, debug, trace};
 use smallvec::SmallVec;
 use std::{
@@ -140,6 +143,15 @@ where
 
     /// Timeout that will me used for inbound and outbound responses.
     resp_timeout: Duration,
+
+    /// The peer this handler is connected to.
+    peer_id: PeerId,
+
+    /// The connection this handler manages.
+    connection_id: ConnectionId,
+
+    /// Slots of by-range response chunks in flight on this connection, shared with the producers.
+    response_flow_control: Arc<ResponseFlowControl>,
 }
 
 enum HandlerState {
@@ -170,6 +182,64 @@ struct InboundInfo<E: EthSpec> {
     request_start_time: Instant,
     /// Key to keep track of the substream's timeout via `self.inbound_substreams_delay`.
     delay_key: Option<delay_queue::Key>,
+    /// Number of flow-controlled chunks queued or being written on this substream. Each holds a
+    /// slot in `ResponseFlowControl` until it is written or dropped.
+    flow_controlled_chunks: usize,
+    /// SSZ length of the flow-controlled chunk currently being written, if any.
+    sending_chunk_len: Option<usize>,
+}
+
+impl<E: EthSpec> InboundInfo<E> {
+    /// Takes the next queued response, keeping track of whether it holds a flow control slot.
+    fn pop_pending_item(&mut self) -> Option<RpcResponse<E>> {
+        let message = self.pending_items.pop_front()?;
+        self.sending_chunk_len = range_chunk_ssz_len(&message);
+        Some(message)
+    }
+
+    /// Releases the slot of the chunk that just finished sending, successfully or not.
+    fn finish_sending_chunk(
+        &mut self,
+        flow_control: &ResponseFlowControl,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+        written: bool,
+    ) {
+        if let Some(len) = self.sending_chunk_len.take() {
+            self.flow_controlled_chunks = self.flow_controlled_chunks.saturating_sub(1);
+            if written {
+                flow_control.release(peer_id, connection_id, len);
+                metrics::inc_counter_vec_by(
+                    &metrics::RPC_RANGE_RESPONSE_BYTES,
+                    &[self.protocol.as_ref()],
+                    len as u64,
+                );
+            } else {
+                flow_control.release(peer_id, connection_id, 0);
+            }
+        }
+    }
+
+    /// Releases the slots of all chunks that will never be written.
+    fn release_unsent_chunks(
+        &mut self,
+        flow_control: &ResponseFlowControl,
+        peer_id: &PeerId,
+        connection_id: &ConnectionId,
+    ) {
+        for _ in 0..std::mem::take(&mut self.flow_controlled_chunks) {
+            flow_control.release(peer_id, connection_id, 0);
+        }
+        self.sending_chunk_len = None;
+    }
+}
+
+/// Returns the SSZ length of a response chunk that is subject to flow control.
+fn range_chunk_ssz_len<E: EthSpec>(response: &RpcResponse<E>) -> Option<usize> {
+    match response {
+        RpcResponse::Success(success) => success.range_chunk_ssz_len(),
+        RpcResponse::Error(..) | RpcResponse::StreamTermination(..) => None,
+    }
 }
 
 /// Contains the information the handler keeps on established outbound substreams.
@@ -223,6 +293,9 @@ where
         fork_context: Arc<ForkContext>,
         log: &slog::Logger,
         resp_timeout: Duration,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        response_flow_control: Arc<ResponseFlowControl>,
     ) -> Self {
         RPCHandler {
             listen_protocol,
@@ -242,6 +315,9 @@ where
             waker: None,
             log: log.clone(),
             resp_timeout,
+            peer_id,
+            connection_id,
+            response_flow_control,
         }
     }
 
@@ -293,8 +369,13 @@ where
     // NOTE: If the substream has closed due to inactivity, or the substream is in the
     // wrong state a response will fail silently.
     fn send_response(&mut self, inbound_id: SubstreamId, response: RpcResponse<E>) {
+        let is_flow_controlled = range_chunk_ssz_len(&response).is_some();
         // check if the stream matching the response still exists
         let Some(inbound_info) = self.inbound_substreams.get_mut(&inbound_id) else {
+            if is_flow_controlled {
+                self.response_flow_control
+                    .release(&self.peer_id, &self.connection_id, 0);
+            }
             if !matches!(response, RpcResponse::StreamTermination(..)) {
                 // the stream is closed after sending the expected number of responses
                 trace!(self.log, "Inbound stream has expired. Response not sent";
@@ -315,8 +396,15 @@ where
             // we no longer send responses after the handler is deactivated
             debug!(self.log, "Response not sent. Deactivated handler";
                 "response" => %response, "id" => inbound_id);
+            if is_flow_controlled {
+                self.response_flow_control
+                    .release(&self.peer_id, &self.connection_id, 0);
+            }
             return;
         }
+        if is_flow_controlled {
+            inbound_info.flow_controlled_chunks += 1;
+        }
         inbound_info.pending_items.push_back(response);
     }
 }
@@ -444,7 +532,7 @@ where
                     // sending process.
                     InboundState::Idle(substream) if !deactivated => {
                         // Process one more message if one exists.
-                        if let Some(message) = info.pending_items.pop_front() {
+                        if let Some(message) = info.pop_pending_item() {
                             // If this is the last chunk, terminate the stream.
                             let last_chunk = info.max_remaining_chunks <= 1;
                             let fut =
@@ -510,6 +598,12 @@ where
                             Poll::Ready(Ok((substream, substream_was_closed)))
                                 if !substream_was_closed =>
                             {
+                                info.finish_sending_chunk(
+                                    &self.response_flow_control,
+                                    &self.peer_id,
+                                    &self.connection_id,
+                                    true,
+                                );
                                 // The substream is still active, decrement the remaining
                                 // chunks expected.
                                 info.max_remaining_chunks =
@@ -526,7 +620,7 @@ where
                                 // elements
                                 if !deactivated && !info.pending_items.is_empty() {
                                     // Process one more message if one exists.
-                                    if let Some(message) = info.pending_items.pop_front() {
+                                    if let Some(message) = info.pop_pending_item() {
                                         // If this is the last chunk, terminate the stream.
                                         let last_chunk = info.max_remaining_chunks <= 1;
                                         let fut = send_message_to_inbound_substream(
@@ -546,6 +640,12 @@ where
                             // The pending messages have been sent successfully and the stream has
                             // terminated
                             Poll::Ready(Ok((_substream, _substream_was_closed))) => {
+                                info.finish_sending_chunk(
+                                    &self.response_flow_control,
+                                    &self.peer_id,
+                                    &self.connection_id,
+                                    true,
+                                );
                                 // The substream has closed. Remove the timeout related to the
                                 // substream.
                                 substreams_to_remove.push(*id);
@@ -569,6 +669,12 @@ where
                             // An error occurred when trying to send a response.
                             // This means we terminate the substream.
                             Poll::Ready(Err(error)) => {
+                                info.finish_sending_chunk(
+                                    &self.response_flow_control,
+                                    &self.peer_id,
+                                    &self.connection_id,
+                                    false,
+                                );
                                 // Remove the stream timeout from the mapping
                                 substreams_to_remove.push(*id);
                                 if let Some(ref delay_key) = info.delay_key {
@@ -604,7 +710,13 @@ where
 
         // Remove closed substreams
         for inbound_id in substreams_to_remove {
-            self.inbound_substreams.remove(&inbound_id);
+            if let Some(mut info) = self.inbound_substreams.remove(&inbound_id) {
+                info.release_unsent_chunks(
+                    &self.response_flow_control,
+                    &self.peer_id,
+                    &self.connection_id,
+                );
+            }
         }
 
         // drive outbound streams that need to be processed
@@ -876,6 +988,8 @@ where
                         protocol: req.versioned_protocol().protocol(),
                         request_start_time: Instant::now(),
                         max_remaining_chunks: max_responses,
+                        flow_controlled_chunks: 0,
+                        sending_chunk_len: None,
                     },
                 );
             } else {
@@ -1002,6 +1116,19 @@ where
     }
 }
 
+impl<Id, E: EthSpec> Drop for RPCHandler<Id, E> {
+    fn drop(&mut self) {
+        // Chunks still queued when the connection closes will never be written.
+        for info in self.inbound_substreams.values_mut() {
+            info.release_unsent_chunks(
+                &self.response_flow_control,
+                &self.peer_id,
+                &self.connection_id,
+            );
+        }
+    }
+}
+
 impl slog::Value for SubstreamId {
     fn serialize(
         &self,
@@ -677,6 +677,17 @@ impl<E: EthSpec> RpcSuccessResponse<E> {
             RpcSuccessResponse::LightClientUpdatesByRange(_) => Protocol::LightClientUpdatesByRange,
         }
     }
+
+    /// Returns the SSZ length of a by-range block, blob or data column chunk. These are the only
+    /// responses subject to `ResponseFlowControl`, every other response returns `None`.
+    pub fn range_chunk_ssz_len(&self) -> Option<usize> {
+        match self {
+            RpcSuccessResponse::BlocksByRange(block) => Some(block.ssz_bytes_len()),
+            RpcSuccessResponse::BlobsByRange(blob) => Some(blob.ssz_bytes_len()),
+            RpcSuccessResponse::DataColumnsByRange(column) => Some(column.ssz_bytes_len()),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for RpcErrorResponse {
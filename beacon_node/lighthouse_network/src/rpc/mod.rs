@@ -11,7 +11,9 @@ use libp2p::swarm::{
     handler::ConnectionHandler, CloseConnection, ConnectionId, NetworkBehaviour, NotifyHandler,
     ToSwarm,
 };
-use libp2p::swarm::{ConnectionClosed, FromSwarm, SubstreamProtocol, THandlerInEvent};
+use libp2p::swarm::{
+    ConnectionClosed, ConnectionEstablished, FromSwarm, SubstreamProtocol, THandlerInEvent,
+};
 use libp2p::PeerId;
 use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
 use slog::{crit, debug, o, trace};
@@ -28,6 +30,7 @@ pub(crate) use methods::{
 };
 pub use protocol::RequestType;
 
+pub use flow_control::{FlowControlError, ResponseFlowControl};
 pub use handler::SubstreamId;
 pub use methods::{
     BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason, LightClientBootstrapRequest,
@@ -41,6 +44,7 @@ use self::self_limiter::SelfRateLimiter;
 
 pub(crate) mod codec;
 pub mod config;
+mod flow_control;
 mod handler;
 pub mod methods;
 mod outbound;
@@ -165,6 +169,8 @@ pub struct RPC<Id: ReqId, E: EthSpec> {
     network_params: NetworkParams,
     /// A sequential counter indicating when data gets modified.
     seq_number: u64,
+    /// Bounds the by-range response chunks in flight per peer.
+    response_flow_control: Arc<ResponseFlowControl>,
 }
 
 impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
@@ -176,6 +182,7 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
         log: slog::Logger,
         network_params: NetworkParams,
         seq_number: u64,
+        response_flow_control: Arc<ResponseFlowControl>,
     ) -> Self {
         let log = log.new(o!("service" => "libp2p_rpc"));
 
@@ -198,6 +205,7 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
             log,
             network_params,
             seq_number,
+            response_flow_control,
         }
     }
 
@@ -298,6 +306,9 @@ where
             self.fork_context.clone(),
             &log,
             self.network_params.resp_timeout,
+            peer_id,
+            connection_id,
+            self.response_flow_control.clone(),
         );
 
         Ok(handler)
@@ -331,6 +342,9 @@ where
             self.fork_context.clone(),
             &log,
             self.network_params.resp_timeout,
+            peer_id,
+            connection_id,
+            self.response_flow_control.clone(),
         );
 
         Ok(handler)
@@ -343,6 +357,17 @@ where
         // limits. If a peer disconnects whilst we are self-rate limiting, we want to terminate any
         // pending requests and return an error response to the application.
 
+        if let FromSwarm::ConnectionEstablished(ConnectionEstablished {
+            peer_id,
+            connection_id,
+            ..
+        }) = event
+        {
+            self.response_flow_control
+                .add_connection(peer_id, connection_id);
+            return;
+        }
+
         if let FromSwarm::ConnectionClosed(ConnectionClosed {
             peer_id,
             remaining_established,
@@ -350,6 +375,8 @@ where
             ..
         }) = event
         {
+            self.response_flow_control
+                .remove_connection(&peer_id, &connection_id);
             // If there are still connections remaining, do nothing.
             if remaining_established > 0 {
                 return;
@@ -4,8 +4,8 @@ use crate::discovery::{
     subnet_predicate, DiscoveredPeers, Discovery, FIND_NODE_QUERY_CLOSEST_PEERS,
 };
 use crate::peer_manager::{
-    config::Config as PeerManagerCfg, peerdb::score::PeerAction, peerdb::score::ReportSource,
-    ConnectionDirection, PeerManager, PeerManagerEvent,
+    config::Config as PeerManagerCfg, dial_multiaddrs, peerdb::score::PeerAction,
+    peerdb::score::ReportSource, AddressFamily, ConnectionDirection, PeerManager, PeerManagerEvent,
 };
 use crate::peer_manager::{MIN_OUTBOUND_ONLY_FACTOR, PEER_EXCESS_FACTOR, PRIORITY_PEER_EXCESS};
 use crate::rpc::methods::MetadataRequest;
@@ -32,8 +32,9 @@ use libp2p::multiaddr::{self, Multiaddr, Protocol as MProtocol};
 use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::{NetworkBehaviour, Swarm, SwarmEvent};
 use libp2p::upnp::tokio::Behaviour as Upnp;
-use libp2p::{identify, PeerId, SwarmBuilder};
+use libp2p::{autonat, identify, PeerId, SwarmBuilder};
 use slog::{crit, debug, info, o, trace, warn};
+use std::collections::HashMap;
 use std::num::{NonZeroU8, NonZeroUsize};
 use std::path::PathBuf;
 use std::pin::Pin;
@@ -43,11 +44,13 @@ use types::{
     consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, EnrForkId, EthSpec, ForkContext, Slot, SubnetId,
 };
 use types::{ChainSpec, ForkName};
-use utils::{build_transport, strip_peer_id, Context as ServiceContext};
+use utils::{build_transport, strip_peer_id, BoxedTransport, Context as ServiceContext};
 
 pub mod api_types;
 mod gossip_cache;
 pub mod gossipsub_scoring_parameters;
+#[cfg(feature = "simulated-link")]
+pub mod simulated_link;
 pub mod utils;
 /// The number of peers we target per subnet for discovery queries.
 pub const TARGET_SUBNET_PEERS: usize = 3;
@@ -135,6 +138,8 @@ where
     pub identify: identify::Behaviour,
     /// Libp2p UPnP port mapping.
     pub upnp: Toggle<Upnp>,
+    /// Probes whether our listening addresses are reachable by asking peers to dial us back.
+    pub autonat: Toggle<autonat::Behaviour>,
     /// The routing pub-sub mechanism for eth2.
     pub gossipsub: Gossipsub,
 }
@@ -168,9 +173,39 @@ pub struct Network<E: EthSpec> {
 /// Implements the combined behaviour for the libp2p service.
 impl<E: EthSpec> Network<E> {
     pub async fn new(
+        executor: task_executor::TaskExecutor,
+        ctx: ServiceContext<'_>,
+        log: &slog::Logger,
+    ) -> Result<(Self, Arc<NetworkGlobals<E>>), String> {
+        Self::new_with_transport(executor, ctx, log, |local_keypair, config| {
+            build_transport(local_keypair, !config.disable_quic_support)
+        })
+        .await
+    }
+
+    /// Starts a node which connects to other nodes over the in-memory links of a simulated
+    /// network instead of TCP and QUIC.
+    #[cfg(feature = "simulated-link")]
+    pub async fn new_simulated(
+        executor: task_executor::TaskExecutor,
+        ctx: ServiceContext<'_>,
+        transport: simulated_link::SimulatedTransport,
+        log: &slog::Logger,
+    ) -> Result<(Self, Arc<NetworkGlobals<E>>), String> {
+        Self::new_with_transport(executor, ctx, log, |local_keypair, _| {
+            Ok(utils::build_simulated_transport(local_keypair, transport))
+        })
+        .await
+    }
+
+    async fn new_with_transport(
         executor: task_executor::TaskExecutor,
         mut ctx: ServiceContext<'_>,
         log: &slog::Logger,
+        make_transport: impl FnOnce(
+            libp2p::identity::Keypair,
+            &crate::NetworkConfig,
+        ) -> std::io::Result<BoxedTransport>,
     ) -> Result<(Self, Arc<NetworkGlobals<E>>), String> {
         let log = log.new(o!("service"=> "libp2p"));
 
@@ -181,11 +216,7 @@ impl<E: EthSpec> Network<E> {
 
         // Trusted peers will also be marked as explicit in GossipSub.
         // Cfr. https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md#explicit-peering-agreements
-        let trusted_peers: Vec<PeerId> = config
-            .trusted_peers
-            .iter()
-            .map(|x| PeerId::from(x.clone()))
-            .collect();
+        let trusted_peers = config.trusted_peer_ids();
 
         // set up a collection of variables accessible outside of the network crate
         // Create an ENR or load from disk if appropriate
@@ -211,7 +242,7 @@ impl<E: EthSpec> Network<E> {
         let globals = NetworkGlobals::new(
             enr,
             meta_data,
-            trusted_peers,
+            trusted_peers.clone(),
             config.disable_peer_scoring,
             &log,
             config.clone(),
@@ -330,9 +361,10 @@ impl<E: EthSpec> Network<E> {
                 .with_peer_score(params, thresholds)
                 .expect("Valid score params and thresholds");
 
-            // Mark trusted peers as explicit.
-            for explicit_peer in config.trusted_peers.iter() {
-                gossipsub.add_explicit_peer(&PeerId::from(explicit_peer.clone()));
+            // Mark trusted peers as explicit. Explicit peers are kept out of the mesh and are sent
+            // every message instead, so they can never be pruned from it.
+            for explicit_peer in trusted_peers.iter() {
+                gossipsub.add_explicit_peer(explicit_peer);
             }
 
             // If we are using metrics, then register which topics we want to make sure to keep
@@ -367,6 +399,7 @@ impl<E: EthSpec> Network<E> {
             log.clone(),
             network_params,
             seq_number,
+            network_globals.response_flow_control.clone(),
         );
 
         let discovery = {
@@ -401,11 +434,38 @@ impl<E: EthSpec> Network<E> {
         };
 
         let peer_manager = {
+            // Collect the addresses of the trusted peers, which are always dialed.
+            let mut trusted_peer_addresses: HashMap<PeerId, Vec<Multiaddr>> = HashMap::new();
+            for enr in config.trusted_peers_enr.iter() {
+                trusted_peer_addresses
+                    .entry(enr.peer_id())
+                    .or_default()
+                    .extend(dial_multiaddrs(
+                        enr,
+                        !config.disable_quic_support,
+                        // Trusted peers are configured explicitly, so every family is dialed.
+                        &[AddressFamily::Ipv6, AddressFamily::Ipv4],
+                    ));
+            }
+            for multiaddr in config.trusted_peers_multiaddr.iter() {
+                let Some(peer_id) = PeerId::try_from_multiaddr(multiaddr) else {
+                    warn!(log, "Trusted peer address has no peer id"; "address" => %multiaddr);
+                    continue;
+                };
+                let mut multiaddr = multiaddr.clone();
+                strip_peer_id(&mut multiaddr);
+                trusted_peer_addresses
+                    .entry(peer_id)
+                    .or_default()
+                    .push(multiaddr);
+            }
+
             let peer_manager_cfg = PeerManagerCfg {
                 discovery_enabled: !config.disable_discovery,
                 quic_enabled: !config.disable_quic_support,
                 metrics_enabled: config.metrics_enabled,
                 target_peer_count: config.target_peers,
+                trusted_peer_addresses,
                 ..Default::default()
             };
             PeerManager::new(peer_manager_cfg, network_globals.clone(), &log)?
@@ -437,6 +497,11 @@ impl<E: EthSpec> Network<E> {
                 .upnp_enabled
                 .then(libp2p::upnp::tokio::Behaviour::default),
         );
+        // A private node does not advertise its addresses, so there is nothing to probe.
+        let autonat = Toggle::from(
+            (!config.private)
+                .then(|| autonat::Behaviour::new(local_peer_id, autonat::Config::default())),
+        );
         let behaviour = {
             Behaviour {
                 gossipsub,
@@ -446,11 +511,12 @@ impl<E: EthSpec> Network<E> {
                 peer_manager,
                 connection_limits,
                 upnp,
+                autonat,
             }
         };
 
         // Set up the transport - tcp/quic with noise and mplex
-        let transport = build_transport(local_keypair.clone(), !config.disable_quic_support)
+        let transport = make_transport(local_keypair.clone(), &config)
             .map_err(|e| format!("Failed to build transport: {:?}", e))?;
 
         // use the executor for libp2p
@@ -566,43 +632,6 @@ impl<E: EthSpec> Network<E> {
             dial(multiaddr.clone());
         }
 
-        // attempt to connect to any specified boot-nodes
-        let mut boot_nodes = config.boot_nodes_enr.clone();
-        boot_nodes.dedup();
-
-        for bootnode_enr in boot_nodes {
-            // If QUIC is enabled, attempt QUIC connections first
-            if !config.disable_quic_support {
-                for quic_multiaddr in &bootnode_enr.multiaddr_quic() {
-                    if !self
-                        .network_globals
-                        .peers
-                        .read()
-                        .is_connected_or_dialing(&bootnode_enr.peer_id())
-                    {
-                        dial(quic_multiaddr.clone());
-                    }
-                }
-            }
-
-            for multiaddr in &bootnode_enr.multiaddr() {
-                // ignore udp multiaddr if it exists
-                let components = multiaddr.iter().collect::<Vec<_>>();
-                if let MProtocol::Udp(_) = components[1] {
-                    continue;
-                }
-
-                if !self
-                    .network_globals
-                    .peers
-                    .read()
-                    .is_connected_or_dialing(&bootnode_enr.peer_id())
-                {
-                    dial(multiaddr.clone());
-                }
-            }
-        }
-
         for multiaddr in &config.boot_nodes_multiaddr {
             // check TCP support for dialing
             if multiaddr
@@ -613,6 +642,15 @@ impl<E: EthSpec> Network<E> {
             }
         }
 
+        // attempt to connect to any specified boot-nodes. These are dialed by the peer manager,
+        // which prefers QUIC and falls back to TCP.
+        let mut boot_nodes = config.boot_nodes_enr.clone();
+        boot_nodes.dedup();
+
+        for bootnode_enr in boot_nodes {
+            self.peer_manager_mut().dial_peer(bootnode_enr);
+        }
+
         let mut subscribed_topics: Vec<GossipKind> = vec![];
 
         for topic_kind in &config.topics {
@@ -1033,6 +1071,11 @@ impl<E: EthSpec> Network<E> {
             .goodbye_peer(peer_id, reason, source);
     }
 
+    /// Gracefully disconnect a peer without banning it.
+    pub fn disconnect_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
+        self.peer_manager_mut().disconnect_peer(peer_id, reason);
+    }
+
     /// Hard (ungraceful) disconnect for testing purposes only
     /// Use goodbye_peer for disconnections, do not use this function.
     pub fn __hard_disconnect_testing_only(&mut self, peer_id: PeerId) {
@@ -1090,14 +1133,15 @@ impl<E: EthSpec> Network<E> {
                     .read()
                     .good_peers_on_subnet(s.subnet)
                     .count();
-                if peers_on_subnet >= TARGET_SUBNET_PEERS {
+                let target_subnet_peers = self.network_globals.target_subnet_peers(&s.subnet);
+                if peers_on_subnet >= target_subnet_peers {
                     trace!(
                         self.log,
                         "Discovery query ignored";
                         "subnet" => ?s.subnet,
                         "reason" => "Already connected to desired peers",
                         "connected_peers_on_subnet" => peers_on_subnet,
-                        "target_subnet_peers" => TARGET_SUBNET_PEERS,
+                        "target_subnet_peers" => target_subnet_peers,
                     );
                     false
                 // Queue an outgoing connection request to the cached peers that are on `s.subnet_id`.
@@ -1792,6 +1836,16 @@ impl<E: EthSpec> Network<E> {
         }
     }
 
+    fn inject_autonat_event(&mut self, event: autonat::Event) {
+        match event {
+            autonat::Event::StatusChanged { old, new } => {
+                debug!(self.log, "AutoNAT status changed"; "old" => ?old, "new" => ?new);
+                self.peer_manager_mut().autonat_status_changed(&new);
+            }
+            autonat::Event::InboundProbe(_) | autonat::Event::OutboundProbe(_) => {}
+        }
+    }
+
     /* Networking polling */
 
     pub async fn next_event(&mut self) -> NetworkEvent<E> {
@@ -1851,6 +1905,10 @@ impl<E: EthSpec> Network<E> {
                     self.inject_upnp_event(e);
                     None
                 }
+                BehaviourEvent::Autonat(e) => {
+                    self.inject_autonat_event(e);
+                    None
+                }
                 #[allow(unreachable_patterns)]
                 BehaviourEvent::ConnectionLimits(le) => void::unreachable(le),
             },
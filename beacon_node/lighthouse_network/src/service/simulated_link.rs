@@ -0,0 +1,524 @@
+//! In-memory links with simulated network conditions, for testing swarms in-process.
+//!
+//! A [`SimulatedNetwork`] connects the [`SimulatedTransport`]s created from it without opening
+//! any sockets. The transport accepts TCP multiaddrs so that nodes are configured as usual, but
+//! only the port is used to find the listener. Listening on port 0 allocates the next port of the
+//! network, so ports are assigned in the order the nodes start listening. Inbound connections
+//! appear to come from the loopback address.
+//!
+//! Every write to a connection is delivered to the other end as one message. The receiving node
+//! delays each message by the latency of its link. A lost message is modelled the way a reliable
+//! transport experiences it: the message (and, due to head-of-line blocking, everything after it)
+//! arrives a retransmission delay later. Whether a message is lost is drawn from an RNG seeded
+//! from the link conditions, so the delays do not depend on how the receiver reads the data.
+//!
+//! All delays are measured with the tokio clock. Running the nodes on a runtime with a paused
+//! clock makes them independent of the wall clock and of the speed of the machine.
+
+use futures::channel::mpsc;
+use futures::future::{self, BoxFuture, Ready};
+use futures::{AsyncRead, AsyncWrite, Future, FutureExt, StreamExt};
+use libp2p::core::transport::{DialOpts, ListenerId, Transport, TransportError, TransportEvent};
+use libp2p::multiaddr::{Multiaddr, Protocol};
+use parking_lot::Mutex;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::net::{IpAddr, Ipv4Addr};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::time::{sleep_until, Instant, Sleep};
+
+/// The number of messages that can be in flight in each direction of a connection before the
+/// writer has to wait.
+const MAX_IN_FLIGHT_MESSAGES: usize = 4096;
+
+/// The first port allocated by a [`SimulatedNetwork`].
+const FIRST_PORT: u16 = 10_000;
+
+/// The conditions of the link over which a node receives data.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkConditions {
+    /// The one-way latency added to all received messages.
+    pub latency: Duration,
+    /// The probability, between 0 and 1, that a message is lost.
+    pub loss_rate: f64,
+    /// The extra delay a lost message incurs before it is delivered.
+    pub retransmission_delay: Duration,
+    /// Seed of the RNG deciding which messages are lost.
+    pub seed: u64,
+}
+
+impl Default for LinkConditions {
+    fn default() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            loss_rate: 0.0,
+            retransmission_delay: Duration::from_millis(200),
+            seed: 0,
+        }
+    }
+}
+
+/// A connection handed by a dialer to a listener, along with the port of the dialer.
+type IncomingLink = (Link, u16);
+
+/// An in-memory network connecting the [`SimulatedTransport`]s created from it.
+#[derive(Clone, Default)]
+pub struct SimulatedNetwork {
+    hub: Arc<Mutex<Hub>>,
+}
+
+#[derive(Default)]
+struct Hub {
+    listeners: HashMap<u16, mpsc::UnboundedSender<IncomingLink>>,
+    allocated_ports: u16,
+}
+
+impl Hub {
+    fn allocate_port(&mut self) -> u16 {
+        let port = FIRST_PORT.wrapping_add(self.allocated_ports);
+        self.allocated_ports = self.allocated_ports.wrapping_add(1);
+        port
+    }
+}
+
+impl SimulatedNetwork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a transport for a node whose received data is subject to `conditions`.
+    pub fn transport(&self, conditions: LinkConditions) -> SimulatedTransport {
+        SimulatedTransport {
+            network: self.clone(),
+            rng: StdRng::seed_from_u64(conditions.seed),
+            conditions,
+            listeners: Vec::new(),
+        }
+    }
+
+    /// Hands `link` to the node listening on `port`, returning the port allocated to the dialer.
+    fn connect(&self, port: u16, link: Link) -> io::Result<u16> {
+        let mut hub = self.hub.lock();
+        let dial_port = hub.allocate_port();
+        hub.listeners
+            .get(&port)
+            .ok_or(io::ErrorKind::ConnectionRefused)?
+            .unbounded_send((link, dial_port))
+            .map_err(|_| io::ErrorKind::ConnectionRefused)?;
+        Ok(dial_port)
+    }
+}
+
+/// A [`Transport`] over the in-memory links of a [`SimulatedNetwork`].
+pub struct SimulatedTransport {
+    network: SimulatedNetwork,
+    conditions: LinkConditions,
+    /// Seeds the loss decisions of each connection, in the order the connections are made.
+    rng: StdRng,
+    listeners: Vec<Listener>,
+}
+
+struct Listener {
+    id: ListenerId,
+    port: u16,
+    addr: Multiaddr,
+    receiver: mpsc::UnboundedReceiver<IncomingLink>,
+    /// Set until the listen address has been reported to the swarm.
+    report_addr: bool,
+}
+
+impl SimulatedTransport {
+    fn stream(&mut self, link: Link) -> SimulatedStream {
+        SimulatedStream::new(link, self.conditions.clone(), self.rng.gen())
+    }
+}
+
+impl Transport for SimulatedTransport {
+    type Output = SimulatedStream;
+    type Error = io::Error;
+    type ListenerUpgrade = Ready<Result<Self::Output, Self::Error>>;
+    type Dial = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn listen_on(
+        &mut self,
+        id: ListenerId,
+        addr: Multiaddr,
+    ) -> Result<(), TransportError<Self::Error>> {
+        let Some((ip, port)) = parse_tcp_addr(&addr) else {
+            return Err(TransportError::MultiaddrNotSupported(addr));
+        };
+
+        let mut hub = self.network.hub.lock();
+        let port = if port == 0 { hub.allocate_port() } else { port };
+        if hub.listeners.contains_key(&port) {
+            return Err(TransportError::Other(io::ErrorKind::AddrInUse.into()));
+        }
+        let (sender, receiver) = mpsc::unbounded();
+        hub.listeners.insert(port, sender);
+
+        self.listeners.push(Listener {
+            id,
+            port,
+            addr: tcp_addr(ip, port),
+            receiver,
+            report_addr: true,
+        });
+        Ok(())
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        let Some(listener) = self.listeners.iter_mut().find(|listener| listener.id == id) else {
+            return false;
+        };
+        self.network.hub.lock().listeners.remove(&listener.port);
+        // The listener is dropped once `poll` has reported it as closed.
+        listener.receiver.close();
+        true
+    }
+
+    fn dial(
+        &mut self,
+        addr: Multiaddr,
+        _opts: DialOpts,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let Some((_, port)) = parse_tcp_addr(&addr) else {
+            return Err(TransportError::MultiaddrNotSupported(addr));
+        };
+
+        let (local, remote) = Link::pair();
+        let stream = self.stream(local);
+        let network = self.network.clone();
+        Ok(async move {
+            network.connect(port, remote)?;
+            Ok(stream)
+        }
+        .boxed())
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        let this = &mut *self;
+        for index in 0..this.listeners.len() {
+            let listener = &mut this.listeners[index];
+            let listener_id = listener.id;
+            if listener.report_addr {
+                listener.report_addr = false;
+                return Poll::Ready(TransportEvent::NewAddress {
+                    listener_id,
+                    listen_addr: listener.addr.clone(),
+                });
+            }
+
+            match listener.receiver.poll_next_unpin(cx) {
+                Poll::Ready(Some((link, dial_port))) => {
+                    let local_addr = listener.addr.clone();
+                    let stream = this.stream(link);
+                    return Poll::Ready(TransportEvent::Incoming {
+                        listener_id,
+                        upgrade: future::ready(Ok(stream)),
+                        local_addr,
+                        send_back_addr: tcp_addr(Ipv4Addr::LOCALHOST.into(), dial_port),
+                    });
+                }
+                Poll::Ready(None) => {
+                    this.listeners.remove(index);
+                    return Poll::Ready(TransportEvent::ListenerClosed {
+                        listener_id,
+                        reason: Ok(()),
+                    });
+                }
+                Poll::Pending => {}
+            }
+        }
+        Poll::Pending
+    }
+}
+
+/// If `addr` is `/ip4/<ip>/tcp/<port>` or `/ip6/<ip>/tcp/<port>`, returns the IP and port.
+fn parse_tcp_addr(addr: &Multiaddr) -> Option<(IpAddr, u16)> {
+    let mut protocols = addr.iter();
+    let ip = match protocols.next()? {
+        Protocol::Ip4(ip) => IpAddr::V4(ip),
+        Protocol::Ip6(ip) => IpAddr::V6(ip),
+        _ => return None,
+    };
+    let Protocol::Tcp(port) = protocols.next()? else {
+        return None;
+    };
+    match protocols.next() {
+        None | Some(Protocol::P2p(_)) => Some((ip, port)),
+        _ => None,
+    }
+}
+
+fn tcp_addr(ip: IpAddr, port: u16) -> Multiaddr {
+    Multiaddr::from(ip).with(Protocol::Tcp(port))
+}
+
+/// One end of an in-memory connection.
+struct Link {
+    incoming: mpsc::Receiver<Vec<u8>>,
+    outgoing: mpsc::Sender<Vec<u8>>,
+}
+
+impl Link {
+    fn pair() -> (Self, Self) {
+        let (a_tx, a_rx) = mpsc::channel(MAX_IN_FLIGHT_MESSAGES);
+        let (b_tx, b_rx) = mpsc::channel(MAX_IN_FLIGHT_MESSAGES);
+        (
+            Self {
+                incoming: a_rx,
+                outgoing: b_tx,
+            },
+            Self {
+                incoming: b_rx,
+                outgoing: a_tx,
+            },
+        )
+    }
+}
+
+/// One end of a connection, which delivers received messages according to [`LinkConditions`].
+pub struct SimulatedStream {
+    link: Link,
+    conditions: LinkConditions,
+    rng: StdRng,
+    /// Received messages, in order, along with the time they become readable.
+    pending: VecDeque<(Instant, Vec<u8>)>,
+    /// The number of bytes of the message at the front of `pending` that have been read.
+    read_offset: usize,
+    /// The delivery time of the most recently received message. Messages are never delivered
+    /// before messages received earlier.
+    last_delivery: Instant,
+    /// Set once the remote has closed the connection.
+    eof: bool,
+    /// Wakes the reader when the message at the front of `pending` becomes readable.
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl SimulatedStream {
+    fn new(link: Link, conditions: LinkConditions, seed: u64) -> Self {
+        Self {
+            link,
+            conditions,
+            rng: StdRng::seed_from_u64(seed),
+            pending: VecDeque::new(),
+            read_offset: 0,
+            last_delivery: Instant::now(),
+            eof: false,
+            delay: None,
+        }
+    }
+
+    /// Returns the time a message received now becomes readable.
+    fn delivery_time(&mut self) -> Instant {
+        let mut delay = self.conditions.latency;
+        if self.conditions.loss_rate > 0.0 && self.rng.gen_bool(self.conditions.loss_rate.min(1.0))
+        {
+            delay += self.conditions.retransmission_delay;
+        }
+        self.last_delivery = std::cmp::max(Instant::now() + delay, self.last_delivery);
+        self.last_delivery
+    }
+}
+
+impl AsyncRead for SimulatedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        // Stamp each received message with the time it becomes readable.
+        while !this.eof {
+            match this.link.incoming.poll_next_unpin(cx) {
+                Poll::Ready(Some(message)) => {
+                    let delivery_time = this.delivery_time();
+                    this.pending.push_back((delivery_time, message));
+                }
+                Poll::Ready(None) => this.eof = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let Some((delivery_time, message)) = this.pending.front() else {
+            // The channel has registered the waker if it has not been closed.
+            return if this.eof {
+                Poll::Ready(Ok(0))
+            } else {
+                Poll::Pending
+            };
+        };
+
+        if *delivery_time > Instant::now() {
+            let delay = this
+                .delay
+                .get_or_insert_with(|| Box::pin(sleep_until(*delivery_time)));
+            if delay.deadline() != *delivery_time {
+                delay.as_mut().reset(*delivery_time);
+            }
+            if delay.as_mut().poll(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        let unread = &message[this.read_offset..];
+        let read = std::cmp::min(buf.len(), unread.len());
+        buf[..read].copy_from_slice(&unread[..read]);
+        this.read_offset += read;
+        if this.read_offset == message.len() {
+            this.pending.pop_front();
+            this.read_offset = 0;
+        }
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncWrite for SimulatedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let outgoing = &mut self.link.outgoing;
+        match outgoing.poll_ready(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(_)) => return Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+            Poll::Pending => return Poll::Pending,
+        }
+        outgoing
+            .start_send(buf.to_vec())
+            .map_err(|_| io::ErrorKind::BrokenPipe)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.link.outgoing.close_channel();
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::{AsyncReadExt, AsyncWriteExt};
+
+    /// Returns the times, relative to when they were written, at which each of `count` messages
+    /// is read, reading `chunk_size` bytes at a time.
+    async fn delivery_times(
+        conditions: LinkConditions,
+        count: usize,
+        chunk_size: usize,
+    ) -> Vec<Duration> {
+        let (local, remote) = Link::pair();
+        let mut writer = SimulatedStream::new(local, LinkConditions::default(), 0);
+        let mut reader = SimulatedStream::new(remote, conditions.clone(), conditions.seed);
+
+        let start = Instant::now();
+        for _ in 0..count {
+            writer.write_all(&[0; 64]).await.unwrap();
+        }
+
+        let mut times = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut message = vec![0; 64];
+            for chunk in message.chunks_mut(chunk_size) {
+                reader.read_exact(chunk).await.unwrap();
+            }
+            times.push(start.elapsed());
+        }
+        times
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn messages_are_delayed_by_latency() {
+        let conditions = LinkConditions {
+            latency: Duration::from_millis(100),
+            ..Default::default()
+        };
+        let times = delivery_times(conditions, 3, 64).await;
+        assert_eq!(times, vec![Duration::from_millis(100); 3]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn lost_messages_are_delayed_by_retransmission() {
+        let conditions = LinkConditions {
+            latency: Duration::from_millis(100),
+            loss_rate: 1.0,
+            retransmission_delay: Duration::from_millis(200),
+            seed: 42,
+        };
+        let times = delivery_times(conditions, 3, 64).await;
+        assert_eq!(times, vec![Duration::from_millis(300); 3]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn losses_do_not_depend_on_read_size() {
+        let conditions = LinkConditions {
+            latency: Duration::from_millis(100),
+            loss_rate: 0.5,
+            retransmission_delay: Duration::from_millis(200),
+            seed: 7,
+        };
+        let whole_messages = delivery_times(conditions.clone(), 32, 64).await;
+        let small_reads = delivery_times(conditions, 32, 8).await;
+        assert_eq!(whole_messages, small_reads);
+        assert!(whole_messages.contains(&Duration::from_millis(300)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn transports_connect_over_the_network() {
+        let network = SimulatedNetwork::new();
+        let mut listener = network.transport(LinkConditions::default());
+        let mut dialer = network.transport(LinkConditions::default());
+
+        listener
+            .listen_on(ListenerId::next(), "/ip4/127.0.0.1/tcp/0".parse().unwrap())
+            .unwrap();
+        let TransportEvent::NewAddress { listen_addr, .. } =
+            future::poll_fn(|cx| Pin::new(&mut listener).poll(cx)).await
+        else {
+            panic!("listener should report its address");
+        };
+        assert_eq!(
+            listen_addr,
+            tcp_addr(Ipv4Addr::LOCALHOST.into(), FIRST_PORT)
+        );
+
+        let opts = DialOpts {
+            role: libp2p::core::Endpoint::Dialer,
+            port_use: Default::default(),
+        };
+        let mut outbound = dialer.dial(listen_addr, opts).unwrap().await.unwrap();
+        let TransportEvent::Incoming { upgrade, .. } =
+            future::poll_fn(|cx| Pin::new(&mut listener).poll(cx)).await
+        else {
+            panic!("listener should accept the connection");
+        };
+        let mut inbound = upgrade.await.unwrap();
+
+        outbound.write_all(b"hello").await.unwrap();
+        outbound.close().await.unwrap();
+        let mut received = Vec::new();
+        inbound.read_to_end(&mut received).await.unwrap();
+        assert_eq!(received, b"hello");
+
+        // Nothing listens on other ports.
+        assert!(dialer
+            .dial(tcp_addr(Ipv4Addr::LOCALHOST.into(), 1), opts)
+            .unwrap()
+            .await
+            .is_err());
+    }
+}
@@ -1,6 +1,8 @@
 use crate::multiaddr::Protocol;
 use crate::rpc::methods::MetaDataV3;
 use crate::rpc::{MetaData, MetaDataV1, MetaDataV2};
+#[cfg(feature = "simulated-link")]
+use crate::service::simulated_link::SimulatedTransport;
 use crate::types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield, GossipEncoding, GossipKind};
 use crate::{GossipTopic, NetworkConfig};
 use futures::future::Either;
@@ -33,7 +35,7 @@ pub struct Context<'a> {
     pub libp2p_registry: Option<&'a mut Registry>,
 }
 
-type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
+pub(crate) type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
 
 /// The implementation supports TCP/IP, QUIC (experimental) over UDP, noise as the encryption layer, and
 /// mplex/yamux as the multiplexing layer (when using TCP).
@@ -41,11 +43,6 @@ pub fn build_transport(
     local_private_key: Keypair,
     quic_support: bool,
 ) -> std::io::Result<BoxedTransport> {
-    // mplex config
-    let mut mplex_config = libp2p_mplex::MplexConfig::new();
-    mplex_config.set_max_buffer_size(256);
-    mplex_config.set_max_buffer_behaviour(libp2p_mplex::MaxBufferBehaviour::Block);
-
     // yamux config
     let yamux_config = yamux::Config::default();
     // Creates the TCP transport layer
@@ -54,7 +51,7 @@ pub fn build_transport(
         .authenticate(generate_noise_config(&local_private_key))
         .multiplex(core::upgrade::SelectUpgrade::new(
             yamux_config,
-            mplex_config,
+            mplex_config(),
         ))
         .timeout(Duration::from_secs(10));
     let transport = if quic_support {
@@ -79,6 +76,32 @@ pub fn build_transport(
     Ok(transport)
 }
 
+/// Builds a transport over the in-memory links of a simulated network, with the same encryption
+/// and multiplexing layers as the TCP transport.
+#[cfg(feature = "simulated-link")]
+pub(crate) fn build_simulated_transport(
+    local_private_key: Keypair,
+    transport: SimulatedTransport,
+) -> BoxedTransport {
+    transport
+        .upgrade(core::upgrade::Version::V1)
+        .authenticate(generate_noise_config(&local_private_key))
+        .multiplex(core::upgrade::SelectUpgrade::new(
+            yamux::Config::default(),
+            mplex_config(),
+        ))
+        .timeout(Duration::from_secs(10))
+        .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+        .boxed()
+}
+
+fn mplex_config() -> libp2p_mplex::MplexConfig {
+    let mut mplex_config = libp2p_mplex::MplexConfig::new();
+    mplex_config.set_max_buffer_size(256);
+    mplex_config.set_max_buffer_behaviour(libp2p_mplex::MaxBufferBehaviour::Block);
+    mplex_config
+}
+
 // Useful helper functions for debugging. Currently not used in the client.
 #[allow(dead_code)]
 fn keypair_from_hex(hex_bytes: &str) -> Result<Keypair, String> {
@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use strum::AsRefStr;
+
+/// The role of a beacon node which is one of a pair configured for failover.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum FailoverRole {
+    /// The node subscribes to subnets and publishes messages on behalf of validators.
+    ///
+    /// Nodes which are not configured for failover are always active.
+    #[default]
+    Active,
+    /// The node follows the chain, leaving subnet subscriptions and publishing to its peer.
+    Standby,
+}
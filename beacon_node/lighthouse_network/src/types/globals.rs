@@ -1,7 +1,8 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
-use crate::rpc::{MetaData, MetaDataV3};
-use crate::types::{BackFillState, SyncState};
+use crate::rpc::{MetaData, MetaDataV3, ResponseFlowControl};
+use crate::service::TARGET_SUBNET_PEERS;
+use crate::types::{BackFillState, FailoverRole, Reachability, Subnet, SyncState};
 use crate::{Client, Enr, EnrExt, GossipTopic, Multiaddr, NetworkConfig, PeerId};
 use itertools::Itertools;
 use parking_lot::RwLock;
@@ -26,6 +27,12 @@ pub struct NetworkGlobals<E: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// The current estimate of whether other nodes can contact us.
+    pub reachability: RwLock<Reachability>,
+    /// Whether this node or its failover peer is responsible for subscriptions and publishing.
+    pub failover_role: RwLock<FailoverRole>,
+    /// Bounds the by-range response chunks in flight per peer and accounts the bytes sent.
+    pub response_flow_control: Arc<ResponseFlowControl>,
     /// The computed sampling subnets and columns is stored to avoid re-computing.
     pub sampling_subnets: Vec<DataColumnSubnetId>,
     pub sampling_columns: Vec<ColumnIndex>,
@@ -83,6 +90,9 @@ impl<E: EthSpec> NetworkGlobals<E> {
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::Paused),
+            reachability: RwLock::new(Reachability::Unknown),
+            failover_role: RwLock::new(FailoverRole::Active),
+            response_flow_control: Arc::new(ResponseFlowControl::default()),
             sampling_subnets,
             sampling_columns,
             config,
@@ -106,6 +116,15 @@ impl<E: EthSpec> NetworkGlobals<E> {
         self.listen_multiaddrs.read().clone()
     }
 
+    /// Returns the number of peers to maintain on `subnet`, which is raised for backbone subnets.
+    pub fn target_subnet_peers(&self, subnet: &Subnet) -> usize {
+        if self.config.is_backbone_subnet(subnet) {
+            std::cmp::max(self.config.backbone_subnet_peers, TARGET_SUBNET_PEERS)
+        } else {
+            TARGET_SUBNET_PEERS
+        }
+    }
+
     /// Returns the number of libp2p connected peers.
     pub fn connected_peers(&self) -> usize {
         self.peers.read().connected_peer_ids().count()
@@ -136,6 +155,21 @@ impl<E: EthSpec> NetworkGlobals<E> {
         self.backfill_state.read().clone()
     }
 
+    /// Returns the current estimate of whether other nodes can contact us.
+    pub fn reachability(&self) -> Reachability {
+        *self.reachability.read()
+    }
+
+    /// Returns the current failover role of the node.
+    pub fn failover_role(&self) -> FailoverRole {
+        *self.failover_role.read()
+    }
+
+    /// Returns true if the node is on standby for its failover peer.
+    pub fn is_standby(&self) -> bool {
+        self.failover_role() == FailoverRole::Standby
+    }
+
     /// Returns a `Client` type if one is known for the `PeerId`.
     pub fn client(&self, peer_id: &PeerId) -> Client {
         self.peers
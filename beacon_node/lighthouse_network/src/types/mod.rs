@@ -1,5 +1,7 @@
+mod failover_role;
 mod globals;
 mod pubsub;
+mod reachability;
 mod subnet;
 mod sync_state;
 mod topics;
@@ -11,8 +13,10 @@ pub type EnrSyncCommitteeBitfield<E> = BitVector<<E as EthSpec>::SyncCommitteeSu
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
+pub use failover_role::FailoverRole;
 pub use globals::NetworkGlobals;
 pub use pubsub::{PubsubMessage, SnappyTransform};
+pub use reachability::Reachability;
 pub use subnet::{Subnet, SubnetDiscovery};
 pub use sync_state::{BackFillState, SyncState};
 pub use topics::{
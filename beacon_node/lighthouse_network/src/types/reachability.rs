@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use strum::{AsRefStr, EnumIter};
+
+/// An estimate of whether the local node can be contacted by other nodes.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, AsRefStr, EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Reachability {
+    /// Other nodes have contacted us, either over discovery or libp2p.
+    Public,
+    /// We have been connected to the network for long enough to expect other nodes to have
+    /// contacted us, but none have. The node is most likely behind a NAT or firewall.
+    BehindNat,
+    /// There has not been enough activity to estimate our reachability.
+    #[default]
+    Unknown,
+}
@@ -103,6 +103,16 @@ pub async fn build_libp2p_instance(
     chain_spec: Arc<ChainSpec>,
 ) -> Libp2pInstance {
     let config = build_config(boot_nodes);
+    build_libp2p_instance_with_config(rt, config, log, fork_name, chain_spec).await
+}
+
+pub async fn build_libp2p_instance_with_config(
+    rt: Weak<Runtime>,
+    config: Arc<NetworkConfig>,
+    log: slog::Logger,
+    fork_name: ForkName,
+    chain_spec: Arc<ChainSpec>,
+) -> Libp2pInstance {
     // launch libp2p service
 
     let (signal, exit) = async_channel::bounded(1);
@@ -0,0 +1,57 @@
+#![cfg(test)]
+
+mod common;
+
+use lighthouse_network::discv5::enr::CombinedKey;
+use lighthouse_network::{Enr, EnrExt, Multiaddr, PeerId};
+use slog::Level;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use types::{EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+// Trusted peers given as an ENR or a multiaddr are registered as explicit gossipsub peers, which
+// are sent every message rather than occupying mesh slots that could be pruned.
+#[test]
+fn trusted_peers_are_explicit_gossipsub_peers() {
+    let log = common::build_log(Level::Debug, false);
+    let rt = Arc::new(Runtime::new().unwrap());
+    let spec = Arc::new(E::default_spec());
+
+    let enr: Enr = Enr::builder()
+        .ip4(std::net::Ipv4Addr::LOCALHOST)
+        .tcp4(9000)
+        .build(&CombinedKey::generate_secp256k1())
+        .unwrap();
+    let multiaddr_peer = PeerId::random();
+    let multiaddr: Multiaddr = format!("/ip4/127.0.0.1/tcp/9000/p2p/{multiaddr_peer}")
+        .parse()
+        .unwrap();
+
+    let mut config = (*common::build_config(vec![])).clone();
+    config.trusted_peers_enr = vec![enr.clone()];
+    config.trusted_peers_multiaddr = vec![multiaddr];
+
+    rt.block_on(async {
+        let node = common::build_libp2p_instance_with_config(
+            Arc::downgrade(&rt),
+            Arc::new(config),
+            log,
+            ForkName::Base,
+            spec,
+        )
+        .await;
+
+        let explicit_peers = node
+            .gossipsub()
+            .explicit_peers()
+            .copied()
+            .collect::<HashSet<_>>();
+        assert_eq!(
+            explicit_peers,
+            HashSet::from([enr.peer_id(), multiaddr_peer])
+        );
+    });
+}
@@ -0,0 +1,449 @@
+//! Records a sample of the gossip messages received by this node, for studying message propagation
+//! and identifying slow peers.
+//!
+//! A sampled message is traced from the moment it is received until its validation result is
+//! reported to gossipsub. Each completed trace is written as a single JSON line to
+//! `gossip_trace.ndjson` in the trace directory. When the file exceeds `MAX_FILE_SIZE` it is rotated
+//! to `gossip_trace.ndjson.1` (and so on), keeping at most `MAX_ROTATED_FILES` old files.
+//!
+//! Traces are written by a dedicated thread, so that file I/O never blocks the network event loop.
+//! Traces are dropped if the writer falls `TRACE_CHANNEL_SIZE` traces behind.
+use lighthouse_network::{MessageAcceptance, MessageId, PeerId, PubsubMessage};
+use serde::Serialize;
+use slog::{debug, warn, Logger};
+use slot_clock::SlotClock;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use types::{EthSpec, Slot};
+
+/// The name of the file currently being written.
+const TRACE_FILE_NAME: &str = "gossip_trace.ndjson";
+/// The size at which the trace file is rotated.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// The number of rotated files to keep, in addition to the current file.
+const MAX_ROTATED_FILES: usize = 4;
+/// The maximum time that completed traces are buffered before being written to disk.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+/// The maximum number of sampled messages awaiting a validation result. Messages are not sampled
+/// while this many are outstanding, which bounds memory if results are never reported.
+const MAX_PENDING_TRACES: usize = 4_096;
+/// The age at which a sampled message whose validation result hasn't been reported is forgotten.
+const PENDING_TRACE_TIMEOUT: Duration = Duration::from_secs(60);
+/// The number of completed traces which may be waiting to be written before traces are dropped.
+const TRACE_CHANNEL_SIZE: usize = 1_024;
+
+/// A sampled message which is awaiting its validation result.
+struct PendingTrace {
+    topic: String,
+    peer_id: PeerId,
+    slot: Option<Slot>,
+    /// The time the message was received, since the UNIX epoch.
+    arrival: Duration,
+    /// The time between the start of the message's slot (or the current slot, for messages without
+    /// a slot) and the arrival of the message.
+    slot_delay: Option<Duration>,
+}
+
+/// A completed trace, as written to the trace file.
+#[derive(Serialize)]
+struct GossipTrace {
+    topic: String,
+    message_id: String,
+    peer_id: String,
+    slot: Option<Slot>,
+    arrival_ms: u128,
+    slot_delay_ms: Option<u128>,
+    validation_ms: u128,
+    result: &'static str,
+}
+
+pub struct GossipTracer {
+    /// The fraction of received messages to trace.
+    sample_rate: f64,
+    pending: HashMap<MessageId, PendingTrace>,
+    /// Sends completed traces to the writer thread. Dropped to stop the thread.
+    trace_tx: Option<SyncSender<GossipTrace>>,
+    writer_handle: Option<JoinHandle<()>>,
+    /// The number of traces dropped since the last warning, because the writer fell behind.
+    dropped_traces: usize,
+    log: Logger,
+}
+
+impl GossipTracer {
+    /// Creates a tracer which writes to `dir`, creating the directory if required.
+    pub fn new(dir: &Path, sample_rate: f64, log: Logger) -> io::Result<Self> {
+        let writer = RotatingWriter::open(dir, MAX_FILE_SIZE)?;
+        let (trace_tx, trace_rx) = mpsc::sync_channel(TRACE_CHANNEL_SIZE);
+        let writer_log = log.clone();
+        let writer_handle = thread::Builder::new()
+            .name("gossip_tracer".into())
+            .spawn(move || writer.run(trace_rx, writer_log))?;
+
+        Ok(Self {
+            sample_rate,
+            pending: HashMap::new(),
+            trace_tx: Some(trace_tx),
+            writer_handle: Some(writer_handle),
+            dropped_traces: 0,
+            log,
+        })
+    }
+
+    /// Starts tracing `message` if it is selected by sampling.
+    pub fn on_message<E: EthSpec, S: SlotClock>(
+        &mut self,
+        message_id: &MessageId,
+        peer_id: &PeerId,
+        message: &PubsubMessage<E>,
+        slot_clock: &S,
+    ) {
+        if rand::random::<f64>() >= self.sample_rate {
+            return;
+        }
+        let Some(arrival) = slot_clock.now_duration() else {
+            return;
+        };
+
+        let slot = message_slot(message);
+        let slot_delay = slot
+            .or_else(|| slot_clock.now())
+            .and_then(|slot| slot_clock.start_of(slot))
+            .and_then(|slot_start| arrival.checked_sub(slot_start));
+
+        self.insert_pending(
+            message_id.clone(),
+            PendingTrace {
+                topic: message.kind().to_string(),
+                peer_id: *peer_id,
+                slot,
+                arrival,
+                slot_delay,
+            },
+        );
+    }
+
+    /// Starts a trace, unless `MAX_PENDING_TRACES` are outstanding after forgetting those older
+    /// than `PENDING_TRACE_TIMEOUT`.
+    fn insert_pending(&mut self, message_id: MessageId, trace: PendingTrace) {
+        if self.pending.len() >= MAX_PENDING_TRACES {
+            let before = self.pending.len();
+            self.pending.retain(|_, pending| {
+                trace.arrival.saturating_sub(pending.arrival) < PENDING_TRACE_TIMEOUT
+            });
+            debug!(
+                self.log,
+                "Expired pending gossip traces";
+                "count" => before - self.pending.len(),
+            );
+            if self.pending.len() >= MAX_PENDING_TRACES {
+                return;
+            }
+        }
+        self.pending.insert(message_id, trace);
+    }
+
+    /// Completes the trace of the message with `message_id`, if it was sampled.
+    pub fn on_validation_result<S: SlotClock>(
+        &mut self,
+        message_id: &MessageId,
+        validation_result: &MessageAcceptance,
+        slot_clock: &S,
+    ) {
+        let Some(pending) = self.pending.remove(message_id) else {
+            return;
+        };
+
+        let validation_time = slot_clock
+            .now_duration()
+            .and_then(|now| now.checked_sub(pending.arrival))
+            .unwrap_or_default();
+        let trace = GossipTrace {
+            topic: pending.topic,
+            message_id: message_id.to_string(),
+            peer_id: pending.peer_id.to_string(),
+            slot: pending.slot,
+            arrival_ms: pending.arrival.as_millis(),
+            slot_delay_ms: pending.slot_delay.map(|delay| delay.as_millis()),
+            validation_ms: validation_time.as_millis(),
+            result: match validation_result {
+                MessageAcceptance::Accept => "accept",
+                MessageAcceptance::Reject => "reject",
+                MessageAcceptance::Ignore => "ignore",
+            },
+        };
+
+        let Some(trace_tx) = &self.trace_tx else {
+            return;
+        };
+        match trace_tx.try_send(trace) {
+            Ok(()) => {
+                if self.dropped_traces > 0 {
+                    warn!(
+                        self.log,
+                        "Dropped gossip traces";
+                        "count" => self.dropped_traces,
+                        "reason" => "trace writer is behind",
+                    );
+                    self.dropped_traces = 0;
+                }
+            }
+            Err(TrySendError::Full(_)) => self.dropped_traces += 1,
+            Err(TrySendError::Disconnected(_)) => {
+                warn!(
+                    self.log,
+                    "Gossip trace writer stopped";
+                    "msg" => "no further traces will be written",
+                );
+                self.trace_tx = None;
+            }
+        }
+    }
+}
+
+impl Drop for GossipTracer {
+    /// Stops the writer thread once it has written and flushed the outstanding traces.
+    fn drop(&mut self) {
+        drop(self.trace_tx.take());
+        if let Some(handle) = self.writer_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Returns the slot that `message` was produced for, if any.
+fn message_slot<E: EthSpec>(message: &PubsubMessage<E>) -> Option<Slot> {
+    match message {
+        PubsubMessage::BeaconBlock(block) => Some(block.slot()),
+        PubsubMessage::BlobSidecar(data) => Some(data.1.slot()),
+        PubsubMessage::DataColumnSidecar(data) => Some(data.1.slot()),
+        PubsubMessage::AggregateAndProofAttestation(aggregate) => {
+            Some(aggregate.message().aggregate().data().slot)
+        }
+        PubsubMessage::Attestation(data) => Some(data.1.data().slot),
+        PubsubMessage::SignedContributionAndProof(contribution) => {
+            Some(contribution.message.contribution.slot)
+        }
+        PubsubMessage::SyncCommitteeMessage(data) => Some(data.1.slot),
+        PubsubMessage::VoluntaryExit(_)
+        | PubsubMessage::ProposerSlashing(_)
+        | PubsubMessage::AttesterSlashing(_)
+        | PubsubMessage::BlsToExecutionChange(_)
+        | PubsubMessage::LightClientFinalityUpdate(_)
+        | PubsubMessage::LightClientOptimisticUpdate(_) => None,
+    }
+}
+
+/// Writes newline-delimited JSON to a file which is rotated when it grows too large.
+struct RotatingWriter {
+    dir: PathBuf,
+    file: BufWriter<File>,
+    size: u64,
+    max_file_size: u64,
+    last_flush: Instant,
+}
+
+impl RotatingWriter {
+    fn open(dir: &Path, max_file_size: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = Self::open_file(dir)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            file: BufWriter::new(file),
+            size,
+            max_file_size,
+            last_flush: Instant::now(),
+        })
+    }
+
+    /// Writes traces from `trace_rx` until the sender is dropped, flushing at least every
+    /// `FLUSH_INTERVAL`.
+    fn run(mut self, trace_rx: mpsc::Receiver<GossipTrace>, log: Logger) {
+        loop {
+            let result = match trace_rx.recv_timeout(FLUSH_INTERVAL) {
+                Ok(trace) => self.write_trace(&trace),
+                Err(RecvTimeoutError::Timeout) => self.flush(),
+                Err(RecvTimeoutError::Disconnected) => break,
+            };
+            if let Err(e) = result {
+                warn!(
+                    log,
+                    "Failed to write gossip trace";
+                    "error" => ?e,
+                );
+            }
+        }
+
+        if let Err(e) = self.file.flush() {
+            warn!(
+                log,
+                "Failed to flush gossip traces";
+                "error" => ?e,
+            );
+        }
+    }
+
+    fn open_file(dir: &Path) -> io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(TRACE_FILE_NAME))
+    }
+
+    fn write_trace(&mut self, trace: &GossipTrace) -> io::Result<()> {
+        let mut line = serde_json::to_vec(trace)?;
+        line.push(b'\n');
+
+        if self.size > 0 && self.size + line.len() as u64 > self.max_file_size {
+            self.rotate()?;
+        }
+
+        self.file.write_all(&line)?;
+        self.size += line.len() as u64;
+
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.last_flush = Instant::now();
+        self.file.flush()
+    }
+
+    /// Moves the current file to `gossip_trace.ndjson.1`, shifting older files along and deleting
+    /// the oldest.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        let rotated = |i: usize| self.dir.join(format!("{TRACE_FILE_NAME}.{i}"));
+        for i in (1..MAX_ROTATED_FILES).rev() {
+            if rotated(i).exists() {
+                fs::rename(rotated(i), rotated(i + 1))?;
+            }
+        }
+        fs::rename(self.dir.join(TRACE_FILE_NAME), rotated(1))?;
+
+        self.file = BufWriter::new(Self::open_file(&self.dir)?);
+        self.size = 0;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::test_logger;
+    use slot_clock::ManualSlotClock;
+    use tempfile::tempdir;
+
+    fn pending_trace(arrival: Duration) -> PendingTrace {
+        PendingTrace {
+            topic: "beacon_block".into(),
+            peer_id: PeerId::random(),
+            slot: Some(Slot::new(1)),
+            arrival,
+            slot_delay: Some(Duration::from_millis(500)),
+        }
+    }
+
+    fn message_id(i: usize) -> MessageId {
+        MessageId::new(&i.to_le_bytes())
+    }
+
+    fn read_traces(dir: &Path) -> Vec<serde_json::Value> {
+        fs::read_to_string(dir.join(TRACE_FILE_NAME))
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writes_completed_traces() {
+        let dir = tempdir().unwrap();
+        let slot_clock =
+            ManualSlotClock::new(Slot::new(0), Duration::ZERO, Duration::from_secs(12));
+        let mut tracer = GossipTracer::new(dir.path(), 1.0, test_logger()).unwrap();
+
+        slot_clock.set_current_time(Duration::from_secs(12));
+        tracer.insert_pending(message_id(0), pending_trace(Duration::from_secs(12)));
+        tracer.insert_pending(message_id(1), pending_trace(Duration::from_secs(12)));
+
+        slot_clock.set_current_time(Duration::from_millis(12_250));
+        tracer.on_validation_result(&message_id(0), &MessageAcceptance::Reject, &slot_clock);
+        // Results for messages which weren't sampled are ignored.
+        tracer.on_validation_result(&message_id(2), &MessageAcceptance::Accept, &slot_clock);
+        // Dropping the tracer flushes the outstanding traces.
+        drop(tracer);
+
+        let traces = read_traces(dir.path());
+        assert_eq!(traces.len(), 1);
+        assert_eq!(traces[0]["topic"], "beacon_block");
+        assert_eq!(traces[0]["slot"], "1");
+        assert_eq!(traces[0]["arrival_ms"], 12_000);
+        assert_eq!(traces[0]["slot_delay_ms"], 500);
+        assert_eq!(traces[0]["validation_ms"], 250);
+        assert_eq!(traces[0]["result"], "reject");
+    }
+
+    #[test]
+    fn expires_pending_traces_by_age() {
+        let dir = tempdir().unwrap();
+        let mut tracer = GossipTracer::new(dir.path(), 1.0, test_logger()).unwrap();
+
+        for i in 0..MAX_PENDING_TRACES {
+            tracer.insert_pending(message_id(i), pending_trace(Duration::from_secs(1)));
+        }
+        // No trace has expired, so no new trace is started.
+        let next = message_id(MAX_PENDING_TRACES);
+        tracer.insert_pending(next.clone(), pending_trace(Duration::from_secs(2)));
+        assert_eq!(tracer.pending.len(), MAX_PENDING_TRACES);
+        assert!(!tracer.pending.contains_key(&next));
+
+        // Once they have expired, the outstanding traces are forgotten.
+        let arrival = Duration::from_secs(1) + PENDING_TRACE_TIMEOUT;
+        tracer.insert_pending(next.clone(), pending_trace(arrival));
+        assert_eq!(tracer.pending.len(), 1);
+        assert!(tracer.pending.contains_key(&next));
+    }
+
+    #[test]
+    fn rotates_trace_files() {
+        let dir = tempdir().unwrap();
+        let trace = GossipTrace {
+            topic: "beacon_block".into(),
+            message_id: "00".into(),
+            peer_id: PeerId::random().to_string(),
+            slot: None,
+            arrival_ms: 0,
+            slot_delay_ms: None,
+            validation_ms: 0,
+            result: "accept",
+        };
+        let line_len = serde_json::to_vec(&trace).unwrap().len() as u64 + 1;
+
+        // Fit two traces in each file.
+        let mut writer = RotatingWriter::open(dir.path(), line_len * 2).unwrap();
+        for _ in 0..(2 * (MAX_ROTATED_FILES + 2) + 1) {
+            writer.write_trace(&trace).unwrap();
+        }
+        writer.flush().unwrap();
+
+        assert_eq!(read_traces(dir.path()).len(), 1);
+        for i in 1..=MAX_ROTATED_FILES {
+            let rotated = dir.path().join(format!("{TRACE_FILE_NAME}.{i}"));
+            assert_eq!(fs::metadata(rotated).unwrap().len(), line_len * 2);
+        }
+        assert!(!dir
+            .path()
+            .join(format!("{TRACE_FILE_NAME}.{}", MAX_ROTATED_FILES + 1))
+            .exists());
+    }
+}
@@ -1,6 +1,7 @@
 /// This crate provides the network server for Lighthouse.
 pub mod service;
 
+mod gossip_tracer;
 mod metrics;
 mod nat;
 mod network_beacon_processor;
@@ -58,6 +58,13 @@ pub static SYNC_COMMITTEE_SUBSCRIPTION_REQUESTS: LazyLock<Result<IntCounter>> =
             "Count of validator sync committee subscription requests.",
         )
     });
+pub static PREDICTED_SUBNET_DISCOVERIES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "subnet_predicted_discoveries_total",
+        "Count of subnets whose peers were discovered ahead of the duties of local validators",
+        &["subnet_kind"],
+    )
+});
 
 /*
  * Gossip processor
@@ -403,6 +410,20 @@ pub static SYNCING_CHAIN_BATCH_AWAITING_PROCESSING: LazyLock<Result<Histogram>>
             ]),
         )
     });
+pub static SYNC_BATCH_DOWNLOAD_TIME: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "sync_batch_download_seconds",
+        "Time taken by peers to serve range and backfill sync batches",
+        Ok(vec![0.1, 0.2, 0.5, 1.0, 2.0, 4.0, 8.0, 15.0, 30.0]),
+    )
+});
+pub static SYNC_PEER_REQUEST_WINDOW: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "sync_peer_request_window",
+        "Number of range or backfill sync batches that may be requested from a peer at once",
+        Ok(vec![1.0, 2.0, 3.0, 4.0]),
+    )
+});
 pub static SYNC_SINGLE_BLOCK_LOOKUPS: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
         "sync_single_block_lookups",
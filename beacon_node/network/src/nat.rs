@@ -2,13 +2,16 @@
 //!
 //! Currently supported strategies:
 //! - UPnP
+//! - PCP, falling back to NAT-PMP for gateways which only support the older protocol
 
-use anyhow::{bail, Context, Error};
+use anyhow::{anyhow, bail, Context, Error};
 use igd_next::{aio::tokio as igd, PortMappingProtocol};
+use lighthouse_network::ListenAddr;
 use slog::debug;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
-use tokio::time::sleep;
+use tokio::net::UdpSocket;
+use tokio::time::{sleep, timeout};
 
 /// The duration in seconds of a port mapping on the gateway.
 const MAPPING_DURATION: u32 = 3600;
@@ -58,3 +61,397 @@ pub async fn construct_upnp_mappings(
         sleep(Duration::from_secs(MAPPING_TIMEOUT)).await;
     }
 }
+
+/// The port PCP and NAT-PMP gateways listen on.
+const GATEWAY_PORT: u16 = 5351;
+
+/// The time we wait for the first gateway response, doubled on each retry.
+const GATEWAY_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// The number of times a request is sent before giving up on the gateway.
+const GATEWAY_ATTEMPTS: u32 = 4;
+
+/// The maximum size of a PCP message.
+const MAX_GATEWAY_RESPONSE_LEN: usize = 1100;
+
+/// The NAT-PMP opcode for requesting the gateway's external address.
+const NATPMP_OP_EXTERNAL_ADDRESS: u8 = 0;
+
+/// The NAT-PMP result code returned by gateways which don't support the requested version.
+const NATPMP_RESULT_UNSUPPORTED_VERSION: u16 = 1;
+
+/// The PCP protocol version.
+const PCP_VERSION: u8 = 2;
+
+/// The PCP opcode for creating a port mapping.
+const PCP_OP_MAP: u8 = 1;
+
+/// The PCP result code returned by gateways which don't support the requested version.
+const PCP_RESULT_UNSUPPORTED_VERSION: u8 = 1;
+
+/// The length of PCP MAP requests and responses.
+const PCP_MAP_LEN: usize = 60;
+
+/// A protocol that can be mapped with PCP or NAT-PMP.
+#[derive(Debug, Clone, Copy)]
+enum MappingProtocol {
+    Udp,
+    Tcp,
+}
+
+impl MappingProtocol {
+    fn natpmp_opcode(self) -> u8 {
+        match self {
+            MappingProtocol::Udp => 1,
+            MappingProtocol::Tcp => 2,
+        }
+    }
+
+    /// The IANA protocol number used by PCP.
+    fn pcp_protocol(self) -> u8 {
+        match self {
+            MappingProtocol::Udp => 17,
+            MappingProtocol::Tcp => 6,
+        }
+    }
+}
+
+/// Returned when the gateway does not support PCP, in which case NAT-PMP is used instead.
+#[derive(Debug)]
+struct PcpUnsupported;
+
+impl std::fmt::Display for PcpUnsupported {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Gateway does not support PCP")
+    }
+}
+
+impl std::error::Error for PcpUnsupported {}
+
+/// Attempts to map the Discovery, libp2p TCP and (if enabled) QUIC ports with PCP, falling back
+/// to NAT-PMP for gateways which only support the older protocol.
+///
+/// This is used as a fallback for gateways which do not support UPnP.
+pub async fn construct_pcp_mappings(
+    listen_addr: ListenAddr<Ipv4Addr>,
+    quic_enabled: bool,
+    log: slog::Logger,
+) -> Result<(), Error> {
+    let gateway = default_gateway().context("Could not find the default gateway")?;
+    let socket = UdpSocket::bind((listen_addr.addr, 0))
+        .await
+        .context("Could not bind a socket for PCP")?;
+    socket
+        .connect((gateway, GATEWAY_PORT))
+        .await
+        .context("Could not connect to the gateway")?;
+    // PCP requests carry the address the gateway should forward to, which is the address of the
+    // interface facing the gateway.
+    let client_address = match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) => ip,
+        IpAddr::V6(_) => bail!("Could not find an IPv4 address facing the gateway"),
+    };
+
+    let mut mappings = vec![
+        (MappingProtocol::Udp, listen_addr.disc_port, "Discovery"),
+        (MappingProtocol::Tcp, listen_addr.tcp_port, "libp2p"),
+    ];
+    if quic_enabled {
+        mappings.push((MappingProtocol::Udp, listen_addr.quic_port, "QUIC"));
+    }
+    // PCP identifies a mapping by its nonce, which must stay the same when it is renewed.
+    let nonces = mappings
+        .iter()
+        .map(|_| rand::random::<[u8; 12]>())
+        .collect::<Vec<_>>();
+
+    let (protocol, port, _) = mappings[0];
+    let use_pcp = match pcp_map(&socket, client_address, &nonces[0], protocol, port).await {
+        Ok(external_address) => {
+            check_external_address(external_address)?;
+            true
+        }
+        Err(e) if e.is::<PcpUnsupported>() => {
+            debug!(log, "Gateway does not support PCP, using NAT-PMP");
+            let response = natpmp_request(&socket, &[0, NATPMP_OP_EXTERNAL_ADDRESS], 12)
+                .await
+                .context("Gateway does not support PCP or NAT-PMP")?;
+            check_external_address(Ipv4Addr::new(
+                response[8],
+                response[9],
+                response[10],
+                response[11],
+            ))?;
+            false
+        }
+        Err(e) => return Err(e.context("Gateway does not support PCP")),
+    };
+
+    loop {
+        for ((protocol, port, name), nonce) in mappings.iter().copied().zip(&nonces) {
+            if use_pcp {
+                pcp_map(&socket, client_address, nonce, protocol, port)
+                    .await
+                    .with_context(|| format!("Could not PCP map {} port: {}", name, port))?;
+                debug!(log, "PCP port mapped"; "protocol" => name, "port" => %port);
+            } else {
+                let external_port = natpmp_map(&socket, protocol, port)
+                    .await
+                    .with_context(|| format!("Could not NAT-PMP map {} port: {}", name, port))?;
+                debug!(log, "NAT-PMP port mapped"; "protocol" => name, "port" => %port, "external_port" => %external_port);
+            }
+        }
+        sleep(Duration::from_secs(MAPPING_TIMEOUT)).await;
+    }
+}
+
+fn check_external_address(external_address: Ipv4Addr) -> Result<(), Error> {
+    if external_address.is_private() {
+        bail!(
+            "Gateway's external address is a private address: {}",
+            external_address
+        );
+    }
+    Ok(())
+}
+
+/// Requests a PCP mapping of `port` to the same external port, returning the gateway's external
+/// address.
+async fn pcp_map(
+    socket: &UdpSocket,
+    client_address: Ipv4Addr,
+    nonce: &[u8; 12],
+    protocol: MappingProtocol,
+    port: u16,
+) -> Result<Ipv4Addr, Error> {
+    let request = pcp_map_request(client_address, nonce, protocol, port);
+    gateway_request(socket, &request, |response| {
+        parse_pcp_map_response(response, nonce)
+    })
+    .await
+}
+
+/// Builds a PCP MAP request, as described in RFC 6887.
+fn pcp_map_request(
+    client_address: Ipv4Addr,
+    nonce: &[u8; 12],
+    protocol: MappingProtocol,
+    port: u16,
+) -> [u8; PCP_MAP_LEN] {
+    let mut request = [0u8; PCP_MAP_LEN];
+    // Common request header.
+    request[0] = PCP_VERSION;
+    request[1] = PCP_OP_MAP;
+    request[4..8].copy_from_slice(&MAPPING_DURATION.to_be_bytes());
+    request[8..24].copy_from_slice(&client_address.to_ipv6_mapped().octets());
+    // MAP opcode payload. The suggested external address is left as all zeros.
+    request[24..36].copy_from_slice(nonce);
+    request[36] = protocol.pcp_protocol();
+    request[40..42].copy_from_slice(&port.to_be_bytes());
+    request[42..44].copy_from_slice(&port.to_be_bytes());
+    request
+}
+
+/// Parses a response to a PCP MAP request, returning `None` if it is not a response to the
+/// request with `nonce`.
+fn parse_pcp_map_response(response: &[u8], nonce: &[u8; 12]) -> Option<Result<Ipv4Addr, Error>> {
+    // A NAT-PMP gateway replies to any request with a version other than zero with an error.
+    if response.len() >= 4 && response[0] == 0 {
+        let result_code = u16::from_be_bytes([response[2], response[3]]);
+        return (result_code == NATPMP_RESULT_UNSUPPORTED_VERSION)
+            .then(|| Err(PcpUnsupported.into()));
+    }
+    if response.len() < 4 || response[1] != PCP_OP_MAP | 0x80 {
+        return None;
+    }
+    match response[3] {
+        0 => {}
+        PCP_RESULT_UNSUPPORTED_VERSION => return Some(Err(PcpUnsupported.into())),
+        result_code => {
+            return Some(Err(anyhow!(
+                "Gateway returned PCP result code {}",
+                result_code
+            )))
+        }
+    }
+    if response.len() != PCP_MAP_LEN || response[24..36] != nonce[..] {
+        return None;
+    }
+    let external_address = <[u8; 16]>::try_from(&response[44..60]).ok()?;
+    Some(
+        std::net::Ipv6Addr::from(external_address)
+            .to_ipv4_mapped()
+            .ok_or_else(|| anyhow!("Gateway returned an IPv6 external address")),
+    )
+}
+
+/// Requests a NAT-PMP mapping of `port` to the same external port, returning the external port
+/// chosen by the gateway.
+async fn natpmp_map(
+    socket: &UdpSocket,
+    protocol: MappingProtocol,
+    port: u16,
+) -> Result<u16, Error> {
+    let mut request = [0u8; 12];
+    request[1] = protocol.natpmp_opcode();
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&port.to_be_bytes());
+    request[8..12].copy_from_slice(&MAPPING_DURATION.to_be_bytes());
+
+    let response = natpmp_request(socket, &request, 16).await?;
+    Ok(u16::from_be_bytes([response[10], response[11]]))
+}
+
+/// Sends a NAT-PMP request to the gateway and returns the response once its result code has been
+/// checked.
+async fn natpmp_request(
+    socket: &UdpSocket,
+    request: &[u8],
+    response_len: usize,
+) -> Result<Vec<u8>, Error> {
+    gateway_request(socket, request, |response| {
+        // Ignore malformed or unrelated responses.
+        if response.len() != response_len || response[1] != request[1] | 0x80 {
+            return None;
+        }
+        let result_code = u16::from_be_bytes([response[2], response[3]]);
+        if result_code != 0 {
+            return Some(Err(anyhow!(
+                "Gateway returned NAT-PMP result code {}",
+                result_code
+            )));
+        }
+        Some(Ok(response.to_vec()))
+    })
+    .await
+}
+
+/// Sends a request to the gateway, retrying with an exponential backoff as described in RFC 6886
+/// and RFC 6887, until `parse` accepts a response.
+async fn gateway_request<T>(
+    socket: &UdpSocket,
+    request: &[u8],
+    parse: impl Fn(&[u8]) -> Option<Result<T, Error>>,
+) -> Result<T, Error> {
+    let mut response = [0u8; MAX_GATEWAY_RESPONSE_LEN];
+    let mut wait = GATEWAY_INITIAL_TIMEOUT;
+
+    for _ in 0..GATEWAY_ATTEMPTS {
+        socket.send(request).await?;
+        match timeout(wait, socket.recv(&mut response)).await {
+            Ok(Ok(len)) => {
+                if let Some(result) = parse(&response[..len]) {
+                    return result;
+                }
+            }
+            Ok(Err(e)) => return Err(e.into()),
+            Err(_) => wait *= 2,
+        }
+    }
+
+    Err(anyhow!("No response from the gateway"))
+}
+
+/// Returns the IPv4 address of the default gateway.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Result<Ipv4Addr, Error> {
+    let routes = std::fs::read_to_string("/proc/net/route")?;
+    // Each route is of the form `Iface Destination Gateway Flags ...`, with addresses written
+    // as hex in native byte order.
+    routes
+        .lines()
+        .skip(1)
+        .filter_map(|route| {
+            let fields = route.split_whitespace().collect::<Vec<_>>();
+            let destination = u32::from_str_radix(fields.get(1)?, 16).ok()?;
+            let gateway = u32::from_str_radix(fields.get(2)?, 16).ok()?;
+            (destination == 0 && gateway != 0).then(|| Ipv4Addr::from(gateway.to_ne_bytes()))
+        })
+        .next()
+        .ok_or_else(|| anyhow!("No default route"))
+}
+
+/// Returns the IPv4 address of the default gateway.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Result<Ipv4Addr, Error> {
+    bail!("Finding the default gateway is not supported on this platform")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NONCE: [u8; 12] = [7; 12];
+
+    fn pcp_map_response(result_code: u8, nonce: &[u8; 12], external: Ipv4Addr) -> Vec<u8> {
+        let mut response = vec![0u8; PCP_MAP_LEN];
+        response[0] = PCP_VERSION;
+        response[1] = PCP_OP_MAP | 0x80;
+        response[3] = result_code;
+        response[24..36].copy_from_slice(nonce);
+        response[44..60].copy_from_slice(&external.to_ipv6_mapped().octets());
+        response
+    }
+
+    #[test]
+    fn pcp_map_request_layout() {
+        let request = pcp_map_request(
+            Ipv4Addr::new(192, 168, 1, 2),
+            &NONCE,
+            MappingProtocol::Tcp,
+            9000,
+        );
+        assert_eq!(request[0], PCP_VERSION);
+        assert_eq!(request[1], PCP_OP_MAP);
+        assert_eq!(request[4..8], MAPPING_DURATION.to_be_bytes());
+        assert_eq!(
+            request[8..24],
+            Ipv4Addr::new(192, 168, 1, 2).to_ipv6_mapped().octets()
+        );
+        assert_eq!(request[24..36], NONCE);
+        assert_eq!(request[36], 6);
+        assert_eq!(request[40..42], 9000u16.to_be_bytes());
+        assert_eq!(request[42..44], 9000u16.to_be_bytes());
+    }
+
+    #[test]
+    fn pcp_map_responses() {
+        let external = Ipv4Addr::new(1, 2, 3, 4);
+        assert_eq!(
+            parse_pcp_map_response(&pcp_map_response(0, &NONCE, external), &NONCE)
+                .unwrap()
+                .unwrap(),
+            external
+        );
+
+        // Responses to other requests are ignored.
+        assert!(parse_pcp_map_response(&pcp_map_response(0, &[8; 12], external), &NONCE).is_none());
+
+        assert!(
+            parse_pcp_map_response(&pcp_map_response(2, &NONCE, external), &NONCE)
+                .unwrap()
+                .is_err()
+        );
+
+        // Gateways which only support NAT-PMP reject the PCP version.
+        let natpmp_response = [
+            0,
+            0x81,
+            0,
+            NATPMP_RESULT_UNSUPPORTED_VERSION as u8,
+            0,
+            0,
+            0,
+            0,
+        ];
+        assert!(parse_pcp_map_response(&natpmp_response, &NONCE)
+            .unwrap()
+            .unwrap_err()
+            .is::<PcpUnsupported>());
+        let pcp_response = pcp_map_response(PCP_RESULT_UNSUPPORTED_VERSION, &NONCE, external);
+        assert!(parse_pcp_map_response(&pcp_response, &NONCE)
+            .unwrap()
+            .unwrap_err()
+            .is::<PcpUnsupported>());
+    }
+}
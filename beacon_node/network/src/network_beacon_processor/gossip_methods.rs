@@ -19,6 +19,7 @@ use beacon_chain::{
     AvailabilityProcessingStatus, BeaconChainError, BeaconChainTypes, BlockError, ForkChoiceError,
     GossipVerifiedBlock, NotifyExecutionLayer,
 };
+use eth2::lighthouse::HeadTimelineEventKind;
 use lighthouse_network::{Client, MessageAcceptance, MessageId, PeerAction, PeerId, ReportSource};
 use operation_pool::ReceivedPreCapella;
 use slog::{crit, debug, error, info, trace, warn, Logger};
@@ -31,6 +32,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use store::hot_cold_store::HotColdDBError;
 use tokio::sync::mpsc;
+use tracing::{info_span, Instrument};
 use types::{
     beacon_block::BlockImportSource, Attestation, AttestationRef, AttesterSlashing, BlobSidecar,
     DataColumnSidecar, DataColumnSubnetId, EthSpec, Hash256, IndexedAttestation,
@@ -1070,6 +1072,46 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         duplicate_cache: DuplicateCache,
         invalid_block_storage: InvalidBlockStorage,
         seen_duration: Duration,
+    ) {
+        // The root of the trace of this block's import, which is exported when telemetry is
+        // enabled.
+        let span = info_span!(
+            "gossip_block",
+            slot = %block.slot(),
+            proposer_index = block.message().proposer_index(),
+            peer_id = %peer_id,
+            seen_delay_ms = get_block_delay_ms(
+                seen_duration,
+                block.message(),
+                &self.chain.slot_clock
+            )
+            .as_millis() as u64,
+        );
+        self.process_gossip_block_inner(
+            message_id,
+            peer_id,
+            peer_client,
+            block,
+            reprocess_tx,
+            duplicate_cache,
+            invalid_block_storage,
+            seen_duration,
+        )
+        .instrument(span)
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn process_gossip_block_inner(
+        self: Arc<Self>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        peer_client: Client,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        reprocess_tx: mpsc::Sender<ReprocessQueueMessage>,
+        duplicate_cache: DuplicateCache,
+        invalid_block_storage: InvalidBlockStorage,
+        seen_duration: Duration,
     ) {
         if let Some(gossip_verified_block) = self
             .process_gossip_unverified_block(
@@ -1080,6 +1122,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 reprocess_tx.clone(),
                 seen_duration,
             )
+            .instrument(info_span!("gossip_verification"))
             .await
         {
             let block_root = gossip_verified_block.block_root;
@@ -1092,6 +1135,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     invalid_block_storage,
                     seen_duration,
                 )
+                .instrument(info_span!("process_block", block_root = %block_root))
                 .await;
                 // Drop the handle to remove the entry from the cache
                 drop(handle);
@@ -1149,6 +1193,13 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Some(peer_id.to_string()),
             Some(peer_client.to_string()),
         );
+        self.chain.record_head_timeline_event_at(
+            seen_duration,
+            HeadTimelineEventKind::BlockObserved {
+                block_root,
+                slot: block.slot(),
+            },
+        );
 
         let verified_block = match verification_result {
             Ok(verified_block) => {
@@ -1449,7 +1500,7 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let publish_blobs = true;
         let self_clone = self.clone();
         let block_clone = block.clone();
-        self.executor.spawn(
+        let fetch_blobs_handle = self.executor.spawn_handle(
             async move {
                 self_clone
                     .fetch_engine_blobs_and_publish(block_clone, block_root, publish_blobs)
@@ -1494,7 +1545,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "peer_id" => %peer_id
                 );
 
-                self.chain.recompute_head_at_current_slot().await;
+                self.chain
+                    .recompute_head_at_current_slot()
+                    .instrument(info_span!("recompute_head"))
+                    .await;
 
                 metrics::set_gauge(
                     &metrics::BEACON_BLOCK_DELAY_FULL_VERIFICATION,
@@ -1508,6 +1562,32 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "slot" => slot,
                     "block_root" => %block_root,
                 );
+
+                // Sync waits for the blobs from the EL before requesting the remaining blobs from
+                // peers, so only notify it once the fetch has completed. Wait on a separate task
+                // rather than holding this worker for an EL round trip.
+                if let Some(handle) = fetch_blobs_handle {
+                    let self_clone = self.clone();
+                    let block_root = *block_root;
+                    self.executor.spawn(
+                        async move {
+                            if let Err(e) = handle.await {
+                                debug!(
+                                    self_clone.log,
+                                    "Fetch blobs task failed";
+                                    "error" => ?e,
+                                    "block_root" => %block_root,
+                                );
+                            }
+                            self_clone.send_sync_message(SyncMessage::GossipBlockProcessResult {
+                                block_root,
+                                imported: false,
+                            });
+                        },
+                        "fetch_blobs_gossip_sync_notify",
+                    );
+                    return;
+                }
             }
             Err(BlockError::ParentUnknown { .. }) => {
                 // This should not occur. It should be checked by `should_forward_block`.
@@ -2338,6 +2418,27 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
                 return;
             }
+            AttnError::OwnKeyConflict(conflict) => {
+                /*
+                 * The attestation is slashable for one of our own validators. The conflict has
+                 * already been reported to the operator, and the attestation must not be
+                 * propagated.
+                 *
+                 * The peer is not necessarily faulty.
+                 */
+                debug!(
+                    self.log,
+                    "Ignoring attestation conflicting with own key";
+                    "peer_id" => %peer_id,
+                    "block" => ?beacon_block_root,
+                    "conflict" => ?conflict,
+                    "type" => ?attestation_type,
+                );
+
+                self.propagate_validation_result(message_id, peer_id, MessageAcceptance::Ignore);
+
+                return;
+            }
             AttnError::ValidatorIndexTooHigh(_) => {
                 /*
                  * The aggregator index (or similar field) was higher than the maximum
@@ -1208,6 +1208,7 @@ impl<E: EthSpec> NetworkBeaconProcessor<TestBeaconChainType<E>> {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx: _work_reprocessing_rx,
+            queue_monitor: _,
         } = <_>::default();
 
         let (network_tx, _network_rx) = mpsc::unbounded_channel();
@@ -2,7 +2,7 @@ use crate::network_beacon_processor::{NetworkBeaconProcessor, FUTURE_SLOT_TOLERA
 use crate::service::NetworkMessage;
 use crate::status::ToStatusMessage;
 use crate::sync::SyncMessage;
-use beacon_chain::{BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
 use itertools::process_results;
 use lighthouse_network::discovery::ConnectionId;
 use lighthouse_network::rpc::methods::{
@@ -618,6 +618,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 
     /// Handle a `BlocksByRange` request from the peer.
+    ///
+    /// The request is resolved to block roots on the beacon processor, then the blocks are
+    /// streamed to the peer from a network task so that waiting on a slow peer never holds a
+    /// worker.
     pub async fn handle_blocks_by_range_request(
         self: Arc<Self>,
         peer_id: PeerId,
@@ -626,33 +630,54 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         request_id: RequestId,
         req: BlocksByRangeRequest,
     ) {
-        self.terminate_response_stream(
-            peer_id,
-            connection_id,
-            substream_id,
-            request_id,
-            self.clone()
-                .handle_blocks_by_range_request_inner(
+        let block_roots = match self.blocks_by_range_block_roots(peer_id, &req) {
+            Ok(block_roots) => block_roots,
+            Err(e) => {
+                self.terminate_response_stream(
                     peer_id,
                     connection_id,
                     substream_id,
                     request_id,
-                    req,
-                )
-                .await,
-            Response::BlocksByRange,
+                    Err(e),
+                    Response::BlocksByRange,
+                );
+                return;
+            }
+        };
+
+        let processor = self.clone();
+        self.executor.spawn(
+            async move {
+                let result = processor
+                    .send_blocks_by_range(
+                        peer_id,
+                        connection_id,
+                        substream_id,
+                        request_id,
+                        req,
+                        block_roots,
+                    )
+                    .await;
+                processor.terminate_response_stream(
+                    peer_id,
+                    connection_id,
+                    substream_id,
+                    request_id,
+                    result,
+                    Response::BlocksByRange,
+                );
+            },
+            "blocks_by_range_response",
         );
     }
 
-    /// Handle a `BlocksByRange` request from the peer.
-    pub async fn handle_blocks_by_range_request_inner(
-        self: Arc<Self>,
+    /// Returns the roots of the blocks requested by a `BlocksByRange` request, excluding skip
+    /// slots.
+    fn blocks_by_range_block_roots(
+        &self,
         peer_id: PeerId,
-        connection_id: ConnectionId,
-        substream_id: SubstreamId,
-        request_id: RequestId,
-        req: BlocksByRangeRequest,
-    ) -> Result<(), (RpcErrorResponse, &'static str)> {
+        req: &BlocksByRangeRequest,
+    ) -> Result<Vec<Hash256>, (RpcErrorResponse, &'static str)> {
         debug!(self.log, "Received BlocksByRange Request";
             "peer_id" => %peer_id,
             "count" => req.count(),
@@ -734,8 +759,20 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
 
         // remove all skip slots
-        let block_roots = block_roots.into_iter().flatten().collect::<Vec<_>>();
+        Ok(block_roots.into_iter().flatten().collect())
+    }
 
+    /// Sends the blocks of a `BlocksByRange` request to the peer, waiting for the peer to read
+    /// earlier chunks before producing more.
+    async fn send_blocks_by_range(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        substream_id: SubstreamId,
+        request_id: RequestId,
+        req: BlocksByRangeRequest,
+        block_roots: Vec<Hash256>,
+    ) -> Result<(), (RpcErrorResponse, &'static str)> {
         let current_slot = self
             .chain
             .slot()
@@ -784,6 +821,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     if block.slot() >= *req.start_slot()
                         && block.slot() < req.start_slot() + req.count()
                     {
+                        // Wait until the peer has read enough of the previous chunks.
+                        if let Err(e) = self
+                            .network_globals
+                            .response_flow_control
+                            .acquire(&peer_id, &connection_id)
+                            .await
+                        {
+                            log_results(req, peer_id, blocks_sent);
+                            return Err(flow_control_error(e));
+                        }
                         blocks_sent += 1;
                         self.send_network_message(NetworkMessage::SendResponse {
                             peer_id,
@@ -851,6 +898,8 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 
     /// Handle a `BlobsByRange` request from the peer.
+    ///
+    /// Like `BlocksByRange`, the sidecars are streamed to the peer from a network task.
     pub fn handle_blobs_by_range_request(
         self: Arc<Self>,
         peer_id: PeerId,
@@ -859,31 +908,53 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         request_id: RequestId,
         req: BlobsByRangeRequest,
     ) {
-        self.terminate_response_stream(
-            peer_id,
-            connection_id,
-            substream_id,
-            request_id,
-            self.handle_blobs_by_range_request_inner(
-                peer_id,
-                connection_id,
-                substream_id,
-                request_id,
-                req,
-            ),
-            Response::BlobsByRange,
+        let block_roots = match self.blobs_by_range_block_roots(peer_id, &req) {
+            Ok(block_roots) => block_roots,
+            Err(e) => {
+                self.terminate_response_stream(
+                    peer_id,
+                    connection_id,
+                    substream_id,
+                    request_id,
+                    Err(e),
+                    Response::BlobsByRange,
+                );
+                return;
+            }
+        };
+
+        let processor = self.clone();
+        self.executor.spawn(
+            async move {
+                let result = processor
+                    .send_blobs_by_range(
+                        peer_id,
+                        connection_id,
+                        substream_id,
+                        request_id,
+                        req,
+                        block_roots,
+                    )
+                    .await;
+                processor.terminate_response_stream(
+                    peer_id,
+                    connection_id,
+                    substream_id,
+                    request_id,
+                    result,
+                    Response::BlobsByRange,
+                );
+            },
+            "blobs_by_range_response",
         );
     }
 
-    /// Handle a `BlobsByRange` request from the peer.
-    fn handle_blobs_by_range_request_inner(
+    /// Returns the roots of the blocks covered by a `BlobsByRange` request, excluding skip slots.
+    fn blobs_by_range_block_roots(
         &self,
         peer_id: PeerId,
-        connection_id: ConnectionId,
-        substream_id: SubstreamId,
-        request_id: RequestId,
-        req: BlobsByRangeRequest,
-    ) -> Result<(), (RpcErrorResponse, &'static str)> {
+        req: &BlobsByRangeRequest,
+    ) -> Result<Vec<Hash256>, (RpcErrorResponse, &'static str)> {
         debug!(self.log, "Received BlobsByRange Request";
             "peer_id" => %peer_id,
             "count" => req.count,
@@ -996,6 +1067,21 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             }
         };
 
+        // remove all skip slots
+        Ok(block_roots.into_iter().flatten().collect())
+    }
+
+    /// Sends the blob sidecars of a `BlobsByRange` request to the peer, waiting for the peer to
+    /// read earlier chunks before producing more.
+    async fn send_blobs_by_range(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        substream_id: SubstreamId,
+        request_id: RequestId,
+        req: BlobsByRangeRequest,
+        block_roots: Vec<Hash256>,
+    ) -> Result<(), (RpcErrorResponse, &'static str)> {
         let current_slot = self
             .chain
             .slot()
@@ -1013,14 +1099,24 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             );
         };
 
-        // remove all skip slots
-        let block_roots = block_roots.into_iter().flatten();
         let mut blobs_sent = 0;
 
         for root in block_roots {
-            match self.chain.get_blobs(&root) {
+            match self
+                .read_from_store(move |chain| chain.get_blobs(&root), "blobs_by_range_read")
+                .await
+            {
                 Ok(blob_sidecar_list) => {
                     for blob_sidecar in blob_sidecar_list.iter() {
+                        if let Err(e) = self
+                            .network_globals
+                            .response_flow_control
+                            .acquire(&peer_id, &connection_id)
+                            .await
+                        {
+                            log_results(peer_id, req, blobs_sent);
+                            return Err(flow_control_error(e));
+                        }
                         blobs_sent += 1;
                         self.send_network_message(NetworkMessage::SendResponse {
                             peer_id,
@@ -1054,39 +1150,64 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
     }
 
     /// Handle a `DataColumnsByRange` request from the peer.
+    ///
+    /// Like `BlocksByRange`, the sidecars are streamed to the peer from a network task.
     pub fn handle_data_columns_by_range_request(
-        &self,
+        self: &Arc<Self>,
         peer_id: PeerId,
         connection_id: ConnectionId,
         substream_id: SubstreamId,
         request_id: RequestId,
         req: DataColumnsByRangeRequest,
     ) {
-        self.terminate_response_stream(
-            peer_id,
-            connection_id,
-            substream_id,
-            request_id,
-            self.handle_data_columns_by_range_request_inner(
-                peer_id,
-                connection_id,
-                substream_id,
-                request_id,
-                req,
-            ),
-            Response::DataColumnsByRange,
+        let block_roots = match self.data_columns_by_range_block_roots(peer_id, &req) {
+            Ok(block_roots) => block_roots,
+            Err(e) => {
+                self.terminate_response_stream(
+                    peer_id,
+                    connection_id,
+                    substream_id,
+                    request_id,
+                    Err(e),
+                    Response::DataColumnsByRange,
+                );
+                return;
+            }
+        };
+
+        let processor = self.clone();
+        self.executor.spawn(
+            async move {
+                let result = processor
+                    .send_data_columns_by_range(
+                        peer_id,
+                        connection_id,
+                        substream_id,
+                        request_id,
+                        req,
+                        block_roots,
+                    )
+                    .await;
+                processor.terminate_response_stream(
+                    peer_id,
+                    connection_id,
+                    substream_id,
+                    request_id,
+                    result,
+                    Response::DataColumnsByRange,
+                );
+            },
+            "data_columns_by_range_response",
         );
     }
 
-    /// Handle a `DataColumnsByRange` request from the peer.
-    pub fn handle_data_columns_by_range_request_inner(
+    /// Returns the roots of the blocks covered by a `DataColumnsByRange` request, excluding skip
+    /// slots.
+    fn data_columns_by_range_block_roots(
         &self,
         peer_id: PeerId,
-        connection_id: ConnectionId,
-        substream_id: SubstreamId,
-        request_id: RequestId,
-        req: DataColumnsByRangeRequest,
-    ) -> Result<(), (RpcErrorResponse, &'static str)> {
+        req: &DataColumnsByRangeRequest,
+    ) -> Result<Vec<Hash256>, (RpcErrorResponse, &'static str)> {
         debug!(self.log, "Received DataColumnsByRange Request";
             "peer_id" => %peer_id,
             "count" => req.count,
@@ -1201,60 +1322,113 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
 
         // remove all skip slots
-        let block_roots = block_roots.into_iter().flatten();
+        Ok(block_roots.into_iter().flatten().collect())
+    }
+
+    /// Sends the data column sidecars of a `DataColumnsByRange` request to the peer, waiting for
+    /// the peer to read earlier chunks before producing more.
+    async fn send_data_columns_by_range(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        substream_id: SubstreamId,
+        request_id: RequestId,
+        req: DataColumnsByRangeRequest,
+        block_roots: Vec<Hash256>,
+    ) -> Result<(), (RpcErrorResponse, &'static str)> {
+        let current_slot = self
+            .chain
+            .slot()
+            .unwrap_or_else(|_| self.chain.slot_clock.genesis_slot());
+
+        let log_results = |peer_id, req: &DataColumnsByRangeRequest, data_columns_sent| {
+            debug!(
+                self.log,
+                "DataColumnsByRange Response processed";
+                "peer" => %peer_id,
+                "start_slot" => req.start_slot,
+                "current_slot" => current_slot,
+                "requested" => req.count,
+                "returned" => data_columns_sent
+            );
+        };
+
         let mut data_columns_sent = 0;
 
         for root in block_roots {
-            for index in &req.columns {
-                match self.chain.get_data_column(&root, index) {
-                    Ok(Some(data_column_sidecar)) => {
+            let columns = req.columns.clone();
+            let read = move |chain: &BeaconChain<T>| {
+                columns
+                    .iter()
+                    .filter_map(|index| chain.get_data_column(&root, index).transpose())
+                    .collect::<Result<Vec<_>, _>>()
+            };
+            match self
+                .read_from_store(read, "data_columns_by_range_read")
+                .await
+            {
+                Ok(data_column_sidecars) => {
+                    for data_column_sidecar in data_column_sidecars {
+                        if let Err(e) = self
+                            .network_globals
+                            .response_flow_control
+                            .acquire(&peer_id, &connection_id)
+                            .await
+                        {
+                            log_results(peer_id, &req, data_columns_sent);
+                            return Err(flow_control_error(e));
+                        }
                         data_columns_sent += 1;
                         self.send_network_message(NetworkMessage::SendResponse {
                             peer_id,
                             request_id,
-                            response: Response::DataColumnsByRange(Some(
-                                data_column_sidecar.clone(),
-                            )),
+                            response: Response::DataColumnsByRange(Some(data_column_sidecar)),
                             id: (connection_id, substream_id),
                         });
                     }
-                    Ok(None) => {} // no-op
-                    Err(e) => {
-                        error!(
-                            self.log,
-                            "Error fetching data columns block root";
-                            "request" => ?req,
-                            "peer" => %peer_id,
-                            "block_root" => ?root,
-                            "error" => ?e
-                        );
-                        return Err((
-                            RpcErrorResponse::ServerError,
-                            "No data columns and failed fetching corresponding block",
-                        ));
-                    }
+                }
+                Err(e) => {
+                    error!(
+                        self.log,
+                        "Error fetching data columns block root";
+                        "request" => ?req,
+                        "peer" => %peer_id,
+                        "block_root" => ?root,
+                        "error" => ?e
+                    );
+                    log_results(peer_id, &req, data_columns_sent);
+                    return Err((
+                        RpcErrorResponse::ServerError,
+                        "No data columns and failed fetching corresponding block",
+                    ));
                 }
             }
         }
 
-        let current_slot = self
-            .chain
-            .slot()
-            .unwrap_or_else(|_| self.chain.slot_clock.genesis_slot());
-
-        debug!(
-            self.log,
-            "DataColumnsByRange Response processed";
-            "peer" => %peer_id,
-            "start_slot" => req.start_slot,
-            "current_slot" => current_slot,
-            "requested" => req.count,
-            "returned" => data_columns_sent
-        );
+        log_results(peer_id, &req, data_columns_sent);
 
         Ok(())
     }
 
+    /// Runs a database read for a by-range response on a blocking thread, keeping it off the
+    /// network task that streams the response.
+    async fn read_from_store<R, F>(
+        &self,
+        read: F,
+        name: &'static str,
+    ) -> Result<R, BeaconChainError>
+    where
+        F: FnOnce(&BeaconChain<T>) -> Result<R, BeaconChainError> + Send + 'static,
+        R: Send + 'static,
+    {
+        let chain = self.chain.clone();
+        self.executor
+            .spawn_blocking_handle(move || read(&chain), name)
+            .ok_or(BeaconChainError::RuntimeShutdown)?
+            .await
+            .map_err(BeaconChainError::TokioJoin)?
+    }
+
     /// Helper function to ensure single item protocol always end with either a single chunk or an
     /// error
     fn terminate_response_single_item<R, F: Fn(R) -> Response<T::EthSpec>>(
@@ -1320,3 +1494,14 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         }
     }
 }
+
+/// Maps a failure to acquire a response slot to the error sent to the peer.
+fn flow_control_error(error: FlowControlError) -> (RpcErrorResponse, &'static str) {
+    match error {
+        FlowControlError::Disconnected => (RpcErrorResponse::ServerError, "Peer disconnected"),
+        FlowControlError::Timeout => (
+            RpcErrorResponse::ResourceUnavailable,
+            "Peer is not reading responses",
+        ),
+    }
+}
@@ -193,6 +193,7 @@ impl TestRig {
             beacon_processor_rx,
             work_reprocessing_tx,
             work_reprocessing_rx,
+            queue_monitor,
         } = BeaconProcessorChannels::new(&beacon_processor_config);
 
         let (sync_tx, _sync_rx) = mpsc::unbounded_channel();
@@ -240,6 +241,7 @@ impl TestRig {
             executor,
             current_workers: 0,
             config: beacon_processor_config,
+            queue_monitor,
             log: log.clone(),
         }
         .spawn_manager(
@@ -356,10 +358,10 @@ impl TestRig {
         }
     }
 
-    pub fn enqueue_blobs_by_range_request(&self, count: u64) {
+    pub fn enqueue_blobs_by_range_request(&self, peer_id: PeerId, count: u64) {
         self.network_beacon_processor
             .send_blobs_by_range_request(
-                PeerId::random(),
+                peer_id,
                 ConnectionId::new_unchecked(42),
                 SubstreamId::new(24),
                 RequestId::new_unchecked(0),
@@ -1121,7 +1123,16 @@ async fn test_blobs_by_range() {
     };
     let mut rig = TestRig::new(64).await;
     let slot_count = 32;
-    rig.enqueue_blobs_by_range_request(slot_count);
+    let peer_id = PeerId::random();
+    let flow_control = rig
+        .network_beacon_processor
+        .network_globals
+        .response_flow_control
+        .clone();
+    // The connection that `enqueue_blobs_by_range_request` uses.
+    let connection_id = ConnectionId::new_unchecked(42);
+    flow_control.add_connection(peer_id, connection_id);
+    rig.enqueue_blobs_by_range_request(peer_id, slot_count);
 
     let mut blob_count = 0;
     for slot in 0..slot_count {
@@ -1144,6 +1155,8 @@ async fn test_blobs_by_range() {
         {
             if blob.is_some() {
                 actual_count += 1;
+                // Act as the RPC handler writing the chunk to the peer.
+                flow_control.release(&peer_id, &connection_id, 0);
             } else {
                 break;
             }
@@ -1,3 +1,4 @@
+use crate::gossip_tracer::GossipTracer;
 use crate::metrics;
 use crate::nat;
 use crate::network_beacon_processor::InvalidBlockStorage;
@@ -101,6 +102,10 @@ pub enum NetworkMessage<E: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Gracefully disconnect a peer without banning it.
+    DisconnectPeer { peer_id: PeerId },
+    /// Unsubscribe from and then re-subscribe to all current gossipsub topics.
+    ResubscribeTopics,
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -118,6 +123,9 @@ pub enum ValidatorSubscriptionMessage {
     SyncCommitteeSubscribe {
         subscriptions: Vec<SyncCommitteeSubscription>,
     },
+    /// Registers validators attached to this node, so that peers on the subnets of their upcoming
+    /// duties can be discovered in advance.
+    LocalValidators { validator_indices: Vec<u64> },
 }
 
 #[derive(Clone)]
@@ -197,6 +205,8 @@ pub struct NetworkService<T: BeaconChainTypes> {
     enable_light_client_server: bool,
     /// The logger for the network service.
     fork_context: Arc<ForkContext>,
+    /// Traces a sample of received gossip messages, if enabled.
+    gossip_tracer: Option<GossipTracer>,
     log: slog::Logger,
 }
 
@@ -233,6 +243,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         ) {
             let nw = network_log.clone();
             let v4 = v4.clone();
+            let quic_enabled = !config.disable_quic_support;
             executor.spawn(
                 async move {
                     info!(nw, "UPnP Attempting to initialise routes");
@@ -240,6 +251,13 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                         nat::construct_upnp_mappings(v4.addr, v4.disc_port, nw.clone()).await
                     {
                         info!(nw, "Could not UPnP map Discovery port"; "error" => %e);
+                        // Fall back to PCP or NAT-PMP for gateways which do not support UPnP.
+                        info!(nw, "PCP Attempting to initialise routes");
+                        if let Err(e) =
+                            nat::construct_pcp_mappings(v4, quic_enabled, nw.clone()).await
+                        {
+                            info!(nw, "Could not PCP or NAT-PMP map ports"; "error" => %e);
+                        }
                     }
                 },
                 "UPnP",
@@ -334,6 +352,21 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             validator_subscription_recv,
         } = network_receivers;
 
+        let gossip_tracer = config
+            .gossip_trace_dir
+            .as_ref()
+            .map(|dir| {
+                info!(
+                    network_log,
+                    "Gossip message tracing enabled";
+                    "dir" => ?dir,
+                    "sample_rate" => config.gossip_trace_sample_rate,
+                );
+                GossipTracer::new(dir, config.gossip_trace_sample_rate, network_log.clone())
+            })
+            .transpose()
+            .map_err(|e| format!("Unable to open gossip trace file: {:?}", e))?;
+
         // create the network service and spawn the task
         let network_log = network_log.new(o!("service" => "network"));
         let network_service = NetworkService {
@@ -355,6 +388,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             metrics_update,
             gossipsub_parameter_update,
             fork_context,
+            gossip_tracer,
             log: network_log,
             enable_light_client_server: config.enable_light_client_server,
         };
@@ -540,6 +574,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 message,
                 ..
             } => {
+                if let Some(tracer) = &mut self.gossip_tracer {
+                    tracer.on_message(&id, &source, &message, &self.beacon_chain.slot_clock);
+                }
                 match message {
                     // attestation information gets processed in the attestation service
                     PubsubMessage::Attestation(ref subnet_and_attestation) => {
@@ -642,6 +679,13 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                     "message_id" => %message_id,
                     "validation_result" => ?validation_result
                 );
+                if let Some(tracer) = &mut self.gossip_tracer {
+                    tracer.on_validation_result(
+                        &message_id,
+                        &validation_result,
+                        &self.beacon_chain.slot_clock,
+                    );
+                }
                 self.libp2p.report_message_validation_result(
                     &propagation_source,
                     message_id,
@@ -674,6 +718,26 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::DisconnectPeer { peer_id } => {
+                info!(self.log, "Disconnecting peer"; "peer_id" => %peer_id);
+                self.libp2p.disconnect_peer(peer_id, GoodbyeReason::Unknown);
+            }
+            NetworkMessage::ResubscribeTopics => {
+                let subscriptions = self.network_globals.gossipsub_subscriptions.read().clone();
+                for topic in &subscriptions {
+                    self.libp2p.unsubscribe(topic.clone());
+                }
+                for topic in &subscriptions {
+                    if !self.libp2p.subscribe(topic.clone()) {
+                        warn!(self.log, "Could not resubscribe to topic"; "topic" => %topic);
+                    }
+                }
+                info!(
+                    self.log,
+                    "Resubscribed to gossip topics";
+                    "count" => subscriptions.len(),
+                );
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -823,6 +887,15 @@ impl<T: BeaconChainTypes> NetworkService<T> {
     /// Handle a message sent to the network service.
     async fn on_validator_subscription_msg(&mut self, msg: ValidatorSubscriptionMessage) {
         match msg {
+            // Subnet subscriptions are left to the active node of a failover pair. They are
+            // renewed by validator clients ahead of each duty, so the node subscribes on its own
+            // duties soon after taking over.
+            ValidatorSubscriptionMessage::AttestationSubscribe { .. }
+            | ValidatorSubscriptionMessage::SyncCommitteeSubscribe { .. }
+                if self.network_globals.is_standby() =>
+            {
+                debug!(self.log, "Ignoring subnet subscriptions on standby");
+            }
             ValidatorSubscriptionMessage::AttestationSubscribe { subscriptions } => {
                 let subscriptions = subscriptions.into_iter().map(Subscription::Attestation);
                 self.subnet_service.validator_subscriptions(subscriptions)
@@ -831,6 +904,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 let subscriptions = subscriptions.into_iter().map(Subscription::SyncCommittee);
                 self.subnet_service.validator_subscriptions(subscriptions)
             }
+            ValidatorSubscriptionMessage::LocalValidators { validator_indices } => self
+                .subnet_service
+                .register_local_validators(validator_indices),
         }
     }
 
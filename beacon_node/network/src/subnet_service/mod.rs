@@ -8,6 +8,7 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
 use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio::time::Instant;
 
 use beacon_chain::{BeaconChain, BeaconChainTypes};
@@ -17,8 +18,8 @@ use lighthouse_network::{discv5::enr::NodeId, NetworkConfig, Subnet, SubnetDisco
 use slog::{debug, error, o, warn};
 use slot_clock::SlotClock;
 use types::{
-    Attestation, EthSpec, Slot, SubnetId, SyncCommitteeSubscription, SyncSubnetId,
-    ValidatorSubscription,
+    Attestation, ChainSpec, Epoch, EthSpec, Slot, SubnetId, SyncCommitteeSubscription,
+    SyncSubnetId, ValidatorSubscription,
 };
 
 #[cfg(test)]
@@ -53,6 +54,14 @@ const ADVANCE_SUBSCRIBE_SLOT_FRACTION: u32 = 1;
 /// `aggregate_validators_on_subnet` delay map.
 const UNSUBSCRIBE_AFTER_AGGREGATOR_DUTY: u32 = 2;
 
+/// The number of epochs before a sync committee period starts that we begin discovering peers on
+/// the sync committee subnets of local validators.
+const SYNC_COMMITTEE_PREDICTION_LOOK_AHEAD_EPOCHS: u64 = 2;
+
+/// The number of epochs a validator is considered local after it was last registered. Validator
+/// clients register their validators every epoch.
+const LOCAL_VALIDATOR_EXPIRY_EPOCHS: u64 = 2;
+
 /// A particular subnet at a given slot. This is used for Attestation subnets and not for sync
 /// committee subnets because the logic for handling subscriptions between these types is different.
 #[derive(PartialEq, Eq, Hash, Clone, Debug, Copy)]
@@ -90,12 +99,28 @@ pub struct SubnetService<T: BeaconChainTypes> {
 
     /// A list of permanent subnets that this node is subscribed to.
     // TODO: Shift this to a dynamic bitfield
-    permanent_attestation_subscriptions: HashSet<Subnet>,
+    permanent_subscriptions: HashSet<Subnet>,
 
     /// A collection timeouts to track the existence of aggregate validator subscriptions at an
     /// `ExactSubnet`.
     aggregate_validators_on_subnet: Option<HashSetDelay<ExactSubnet>>,
 
+    /// The validators attached to this node, with the epoch in which they were last registered.
+    local_validators: HashMap<u64, Epoch>,
+
+    /// The validators whose attestation duties have been predicted, and the epoch of those duties.
+    predicted_attestation_duties: (Epoch, HashSet<u64>),
+
+    /// The validators whose sync committee duties have been predicted, and the first epoch of the
+    /// sync committee period of those duties.
+    predicted_sync_committee_duties: (Epoch, HashSet<u64>),
+
+    /// Sends the subnets of predicted duties back from the blocking tasks computing them.
+    predicted_subnets_tx: mpsc::UnboundedSender<PredictedSubnets>,
+
+    /// Receives the subnets of predicted duties.
+    predicted_subnets_rx: mpsc::UnboundedReceiver<PredictedSubnets>,
+
     /// The waker for the current thread.
     waker: Option<std::task::Waker>,
 
@@ -132,12 +157,11 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
         // Build the list of known permanent subscriptions, so that we know not to subscribe or
         // discover them.
-        let mut permanent_attestation_subscriptions = HashSet::default();
+        let mut permanent_subscriptions = HashSet::default();
         if config.subscribe_all_subnets {
             // We are subscribed to all subnets, set all the bits to true.
             for index in 0..beacon_chain.spec.attestation_subnet_count {
-                permanent_attestation_subscriptions
-                    .insert(Subnet::Attestation(SubnetId::from(index)));
+                permanent_subscriptions.insert(Subnet::Attestation(SubnetId::from(index)));
             }
         } else {
             // Not subscribed to all subnets, so just calculate the required subnets from the node
@@ -145,8 +169,15 @@ impl<T: BeaconChainTypes> SubnetService<T> {
             for subnet_id in
                 SubnetId::compute_attestation_subnets(node_id.raw(), &beacon_chain.spec)
             {
-                permanent_attestation_subscriptions.insert(Subnet::Attestation(subnet_id));
+                permanent_subscriptions.insert(Subnet::Attestation(subnet_id));
             }
+
+            // Add the subnets this node serves as a backbone for.
+            let backbone_subnets = config.backbone_subnets().collect::<Vec<_>>();
+            if !backbone_subnets.is_empty() {
+                slog::info!(log, "Subscribing to backbone subnets"; "subnets" => ?backbone_subnets);
+            }
+            permanent_subscriptions.extend(backbone_subnets);
         }
 
         // Set up the sync committee subscriptions
@@ -163,10 +194,10 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
         let mut events = VecDeque::with_capacity(10);
 
-        // Queue discovery queries for the permanent attestation subnets
+        // Queue discovery queries for the permanent subnets
         if !config.disable_discovery {
             events.push_back(SubnetServiceMessage::DiscoverPeers(
-                permanent_attestation_subscriptions
+                permanent_subscriptions
                     .iter()
                     .cloned()
                     .map(|subnet| SubnetDiscovery {
@@ -178,18 +209,25 @@ impl<T: BeaconChainTypes> SubnetService<T> {
         }
 
         // Pre-populate the events with permanent subscriptions
-        for subnet in permanent_attestation_subscriptions.iter() {
+        for subnet in permanent_subscriptions.iter() {
             events.push_back(SubnetServiceMessage::Subscribe(*subnet));
             events.push_back(SubnetServiceMessage::EnrAdd(*subnet));
         }
 
+        let (predicted_subnets_tx, predicted_subnets_rx) = mpsc::unbounded_channel();
+
         SubnetService {
             events,
             beacon_chain,
             subscriptions: HashSetDelay::new(default_sync_committee_duration),
-            permanent_attestation_subscriptions,
+            permanent_subscriptions,
             scheduled_subscriptions: HashSetDelay::default(),
             aggregate_validators_on_subnet,
+            local_validators: HashMap::new(),
+            predicted_attestation_duties: (Epoch::new(0), HashSet::new()),
+            predicted_sync_committee_duties: (Epoch::new(0), HashSet::new()),
+            predicted_subnets_tx,
+            predicted_subnets_rx,
             waker: None,
             discovery_disabled: config.disable_discovery,
             subscribe_all_subnets: config.subscribe_all_subnets,
@@ -206,14 +244,13 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
     #[cfg(test)]
     pub fn permanent_subscriptions(&self) -> impl Iterator<Item = &Subnet> {
-        self.permanent_attestation_subscriptions.iter()
+        self.permanent_subscriptions.iter()
     }
 
     /// Returns whether we are subscribed to a subnet for testing purposes.
     #[cfg(test)]
     pub(crate) fn is_subscribed(&self, subnet: &Subnet) -> bool {
-        self.subscriptions.contains_key(subnet)
-            || self.permanent_attestation_subscriptions.contains(subnet)
+        self.subscriptions.contains_key(subnet) || self.permanent_subscriptions.contains(subnet)
     }
 
     /// Processes a list of validator subscriptions.
@@ -358,6 +395,128 @@ impl<T: BeaconChainTypes> SubnetService<T> {
         }
     }
 
+    /// Registers the validators attached to this node and discovers peers on the subnets of their
+    /// upcoming duties.
+    ///
+    /// Validator clients only subscribe to the subnets of their duties shortly before the duties
+    /// are due. Instead, we compute the attestation duties of local validators for the next epoch,
+    /// and their sync committee duties shortly before a new sync committee period starts, so that
+    /// peers on these subnets are found minutes in advance.
+    pub fn register_local_validators(&mut self, validator_indices: Vec<u64>) {
+        if self.proposer_only || self.discovery_disabled {
+            return;
+        }
+
+        let Some(current_slot) = self.beacon_chain.slot_clock.now() else {
+            return;
+        };
+        let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
+
+        for validator_index in validator_indices {
+            self.local_validators.insert(validator_index, current_epoch);
+        }
+        self.local_validators
+            .retain(|_, epoch| *epoch + LOCAL_VALIDATOR_EXPIRY_EPOCHS >= current_epoch);
+
+        let attestation_duties =
+            self.take_unpredicted_validators(PredictedDuty::Attestation, current_epoch + 1);
+        let sync_committee_duties =
+            next_sync_committee_period_start::<T::EthSpec>(current_epoch, &self.beacon_chain.spec)
+                .and_then(|period_start| {
+                    self.take_unpredicted_validators(PredictedDuty::SyncCommittee, period_start)
+                });
+        if attestation_duties.is_none() && sync_committee_duties.is_none() {
+            return;
+        }
+
+        // Computing duties may require a state to be loaded and advanced, so it must not block the
+        // network thread. The predicted subnets are sent back and discovered in `poll_next`.
+        let chain = self.beacon_chain.clone();
+        let predicted_subnets_tx = self.predicted_subnets_tx.clone();
+        let log = self.log.clone();
+        self.beacon_chain.task_executor.spawn_blocking(
+            move || {
+                let mut predicted = PredictedSubnets::default();
+                if let Some((epoch, validator_indices)) = attestation_duties {
+                    if !predict_attestation_subnets(
+                        &chain,
+                        epoch,
+                        &validator_indices,
+                        &mut predicted.subnets,
+                        &log,
+                    ) {
+                        predicted.failed.push((
+                            PredictedDuty::Attestation,
+                            epoch,
+                            validator_indices,
+                        ));
+                    }
+                }
+                if let Some((period_start, validator_indices)) = sync_committee_duties {
+                    if !predict_sync_committee_subnets(
+                        &chain,
+                        period_start,
+                        &validator_indices,
+                        &mut predicted.subnets,
+                        &log,
+                    ) {
+                        predicted.failed.push((
+                            PredictedDuty::SyncCommittee,
+                            period_start,
+                            validator_indices,
+                        ));
+                    }
+                }
+                // The receiver lives as long as the service, so this only fails on shutdown.
+                let _ = predicted_subnets_tx.send(predicted);
+            },
+            "predict_validator_subnets",
+        );
+    }
+
+    /// Discovers peers on the subnets predicted by `register_local_validators`, and allows the
+    /// duties that could not be predicted to be predicted again.
+    fn discover_predicted_subnets(&mut self, predicted: PredictedSubnets) {
+        let PredictedSubnets {
+            subnets: mut subnets_to_discover,
+            failed,
+        } = predicted;
+
+        for (duty, epoch, validator_indices) in failed {
+            let (predicted_epoch, predicted_validators) = self.predicted_duties(duty);
+            if *predicted_epoch == epoch {
+                for validator_index in validator_indices {
+                    predicted_validators.remove(&validator_index);
+                }
+            }
+        }
+
+        let Some(current_slot) = self.beacon_chain.slot_clock.now() else {
+            return;
+        };
+        // Only discover peers for duties that leave enough time for a discovery search.
+        subnets_to_discover.retain(|_, slot| {
+            *slot >= current_slot.saturating_add(MIN_PEER_DISCOVERY_SLOT_LOOK_AHEAD)
+        });
+        if subnets_to_discover.is_empty() {
+            return;
+        }
+
+        for subnet in subnets_to_discover.keys() {
+            let subnet_kind = match subnet {
+                Subnet::Attestation(_) => "attestation",
+                Subnet::SyncCommittee(_) => "sync_committee",
+                Subnet::DataColumn(_) => "data_column",
+            };
+            metrics::inc_counter_vec(&metrics::PREDICTED_SUBNET_DISCOVERIES, &[subnet_kind]);
+        }
+        debug!(self.log, "Discovering peers for predicted subnets"; "subnets" => ?subnets_to_discover);
+
+        if let Err(e) = self.discover_peers_request(subnets_to_discover.into_iter()) {
+            warn!(self.log, "Discovery lookup request error"; "error" => e);
+        }
+    }
+
     /// Checks if we have subscribed aggregate validators for the subnet. If not, checks the gossip
     /// verification, re-propagates and returns false.
     pub fn should_process_attestation(
@@ -382,6 +541,38 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
     /* Internal private functions */
 
+    /// Returns the predicted validators for `duty`.
+    fn predicted_duties(&mut self, duty: PredictedDuty) -> &mut (Epoch, HashSet<u64>) {
+        match duty {
+            PredictedDuty::Attestation => &mut self.predicted_attestation_duties,
+            PredictedDuty::SyncCommittee => &mut self.predicted_sync_committee_duties,
+        }
+    }
+
+    /// Returns the local validators whose `duty` in `epoch` has not been predicted yet, marking
+    /// them as predicted.
+    fn take_unpredicted_validators(
+        &mut self,
+        duty: PredictedDuty,
+        epoch: Epoch,
+    ) -> Option<(Epoch, Vec<u64>)> {
+        let (predicted_epoch, predicted_validators) = match duty {
+            PredictedDuty::Attestation => &mut self.predicted_attestation_duties,
+            PredictedDuty::SyncCommittee => &mut self.predicted_sync_committee_duties,
+        };
+        if *predicted_epoch != epoch {
+            *predicted_epoch = epoch;
+            predicted_validators.clear();
+        }
+        let validator_indices = self
+            .local_validators
+            .keys()
+            .filter(|validator_index| predicted_validators.insert(**validator_index))
+            .copied()
+            .collect::<Vec<_>>();
+        (!validator_indices.is_empty()).then_some((epoch, validator_indices))
+    }
+
     /// Adds an event to the event queue and notifies that this service is ready to be polled
     /// again.
     fn queue_event(&mut self, ev: SubnetServiceMessage) {
@@ -447,8 +638,7 @@ impl<T: BeaconChainTypes> SubnetService<T> {
         ExactSubnet { subnet, slot }: ExactSubnet,
     ) -> Result<(), &'static str> {
         // If the subnet is one of our permanent subnets, we do not need to subscribe.
-        if self.subscribe_all_subnets || self.permanent_attestation_subscriptions.contains(&subnet)
-        {
+        if self.subscribe_all_subnets || self.permanent_subscriptions.contains(&subnet) {
             return Ok(());
         }
 
@@ -500,8 +690,8 @@ impl<T: BeaconChainTypes> SubnetService<T> {
         duration_to_unsubscribe: Duration,
         slot_required_until: Slot,
     ) {
-        // Return if we have subscribed to all subnets
-        if self.subscribe_all_subnets {
+        // Return if we have subscribed to all subnets or this is a backbone subnet
+        if self.subscribe_all_subnets || self.permanent_subscriptions.contains(&subnet) {
             return;
         }
 
@@ -607,6 +797,115 @@ impl<T: BeaconChainTypes> SubnetService<T> {
     }
 }
 
+/// The kinds of duties of local validators that are predicted.
+#[derive(Debug, Clone, Copy)]
+enum PredictedDuty {
+    Attestation,
+    SyncCommittee,
+}
+
+/// The subnets of the upcoming duties of local validators, predicted on a blocking task.
+#[derive(Default)]
+struct PredictedSubnets {
+    /// The subnets to discover peers on, with the last slot they are required at.
+    subnets: HashMap<Subnet, Slot>,
+    /// The duties, with their epoch and validators, that could not be predicted.
+    failed: Vec<(PredictedDuty, Epoch, Vec<u64>)>,
+}
+
+/// Returns the first epoch of the next sync committee period, if it starts within
+/// `SYNC_COMMITTEE_PREDICTION_LOOK_AHEAD_EPOCHS` of `current_epoch`.
+fn next_sync_committee_period_start<E: EthSpec>(
+    current_epoch: Epoch,
+    spec: &ChainSpec,
+) -> Option<Epoch> {
+    spec.altair_fork_epoch?;
+    let current_period = current_epoch.sync_committee_period(spec).ok()?;
+    let next_period_start = Epoch::new(
+        current_period
+            .saturating_add(1)
+            .saturating_mul(spec.epochs_per_sync_committee_period.as_u64()),
+    );
+    (current_epoch + SYNC_COMMITTEE_PREDICTION_LOOK_AHEAD_EPOCHS >= next_period_start)
+        .then_some(next_period_start)
+}
+
+/// Adds the attestation subnets of the duties of `validator_indices` in `epoch` to
+/// `subnets_to_discover`. Returns `false` if the duties could not be computed.
+fn predict_attestation_subnets<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    epoch: Epoch,
+    validator_indices: &[u64],
+    subnets_to_discover: &mut HashMap<Subnet, Slot>,
+    log: &slog::Logger,
+) -> bool {
+    let head_block_root = chain.canonical_head.cached_head().head_block_root();
+    let duties = match chain.validator_attestation_duties(validator_indices, epoch, head_block_root)
+    {
+        Ok((duties, _, _)) => duties,
+        Err(e) => {
+            debug!(log, "Unable to predict attestation duties"; "epoch" => epoch, "error" => ?e);
+            return false;
+        }
+    };
+
+    for duty in duties.into_iter().flatten() {
+        match SubnetId::compute_subnet::<T::EthSpec>(
+            duty.slot,
+            duty.index,
+            duty.committees_at_slot,
+            &chain.spec,
+        ) {
+            Ok(subnet_id) => {
+                let slot = subnets_to_discover
+                    .entry(Subnet::Attestation(subnet_id))
+                    .or_insert(duty.slot);
+                *slot = std::cmp::max(*slot, duty.slot);
+            }
+            Err(e) => {
+                debug!(log, "Unable to compute predicted attestation subnet"; "error" => ?e)
+            }
+        }
+    }
+    true
+}
+
+/// Adds the sync committee subnets of the duties of `validator_indices` in the sync committee
+/// period starting at `period_start` to `subnets_to_discover`. Returns `false` if the duties
+/// could not be computed.
+fn predict_sync_committee_subnets<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    period_start: Epoch,
+    validator_indices: &[u64],
+    subnets_to_discover: &mut HashMap<Subnet, Slot>,
+    log: &slog::Logger,
+) -> bool {
+    let duties = match chain.sync_committee_duties_from_head(period_start, validator_indices) {
+        Ok(duties) => duties,
+        Err(e) => {
+            debug!(log, "Unable to predict sync committee duties"; "epoch" => period_start, "error" => ?e);
+            return false;
+        }
+    };
+
+    let period_start_slot = period_start.start_slot(T::EthSpec::slots_per_epoch());
+    for duty in duties.into_iter().flatten().flatten() {
+        match SyncSubnetId::compute_subnets_for_sync_committee::<T::EthSpec>(
+            &duty.validator_sync_committee_indices,
+        ) {
+            Ok(subnet_ids) => {
+                for subnet_id in subnet_ids {
+                    subnets_to_discover.insert(Subnet::SyncCommittee(subnet_id), period_start_slot);
+                }
+            }
+            Err(e) => {
+                debug!(log, "Unable to compute predicted sync committee subnets"; "error" => ?e)
+            }
+        }
+    }
+    true
+}
+
 impl<T: BeaconChainTypes> Stream for SubnetService<T> {
     type Item = SubnetServiceMessage;
 
@@ -620,6 +919,11 @@ impl<T: BeaconChainTypes> Stream for SubnetService<T> {
             self.waker = Some(cx.waker().clone());
         }
 
+        // Discover peers on the subnets of predicted duties.
+        while let Poll::Ready(Some(predicted)) = self.predicted_subnets_rx.poll_recv(cx) {
+            self.discover_predicted_subnets(predicted);
+        }
+
         // Send out any generated events.
         if let Some(event) = self.events.pop_front() {
             return Poll::Ready(Some(event));
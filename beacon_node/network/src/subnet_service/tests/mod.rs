@@ -119,15 +119,18 @@ fn get_logger(log_level: Option<slog::Level>) -> Logger {
 static CHAIN: LazyLock<TestBeaconChain> = LazyLock::new(TestBeaconChain::new_with_system_clock);
 
 fn get_subnet_service() -> SubnetService<TestBeaconChainType> {
+    get_subnet_service_with_config(&NetworkConfig::default())
+}
+
+fn get_subnet_service_with_config(config: &NetworkConfig) -> SubnetService<TestBeaconChainType> {
     let log = test_logger();
-    let config = NetworkConfig::default();
 
     let beacon_chain = CHAIN.chain.clone();
 
     SubnetService::new(
         beacon_chain,
         lighthouse_network::discv5::enr::NodeId::random(),
-        &config,
+        config,
         &log,
     )
 }
@@ -699,6 +702,40 @@ mod test {
         assert_eq!(subnet_service.subscriptions().count(), 0);
     }
 
+    #[tokio::test]
+    async fn backbone_subnets_are_permanent() {
+        let config = NetworkConfig {
+            backbone_attestation_subnets: vec![5],
+            backbone_sync_committee_subnets: vec![0],
+            ..NetworkConfig::default()
+        };
+        let mut subnet_service = get_subnet_service_with_config(&config);
+        let backbone_subnets = [
+            Subnet::Attestation(SubnetId::new(5)),
+            Subnet::SyncCommittee(SyncSubnetId::new(0)),
+        ];
+
+        let events = get_events(&mut subnet_service, None, 0).await;
+        for subnet in backbone_subnets {
+            assert!(subnet_service
+                .permanent_subscriptions()
+                .any(|permanent| *permanent == subnet));
+            assert!(events.contains(&SubnetServiceMessage::Subscribe(subnet)));
+            assert!(events.contains(&SubnetServiceMessage::EnrAdd(subnet)));
+        }
+
+        // A sync committee duty on a backbone subnet doesn't add a short-lived subscription, so
+        // the subnet is never unsubscribed.
+        subnet_service.validator_subscriptions(std::iter::once(Subscription::SyncCommittee(
+            SyncCommitteeSubscription {
+                validator_index: 1,
+                sync_committee_indices: vec![1],
+                until_epoch: Epoch::new(1),
+            },
+        )));
+        assert_eq!(subnet_service.subscriptions().count(), 0);
+    }
+
     #[tokio::test]
     async fn same_subscription_with_lower_until_epoch() {
         // subscription config
@@ -762,4 +799,60 @@ mod test {
             .count();
         assert_eq!(sync_committee_subscriptions, 1);
     }
+
+    #[cfg(not(windows))]
+    #[tokio::test]
+    async fn discover_peers_for_predicted_attestation_duties() {
+        let mut subnet_service = get_subnet_service();
+        let validator_index = 0;
+        let current_slot = subnet_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+        let next_epoch = current_slot.epoch(MainnetEthSpec::slots_per_epoch()) + 1;
+
+        // Compute the duty of the validator in the next epoch.
+        let head_block_root = subnet_service
+            .beacon_chain
+            .canonical_head
+            .cached_head()
+            .head_block_root();
+        let (duties, _, _) = subnet_service
+            .beacon_chain
+            .validator_attestation_duties(&[validator_index], next_epoch, head_block_root)
+            .unwrap();
+        let duty = duties[0].expect("validator should have a duty");
+        let expected_subnet = Subnet::Attestation(
+            SubnetId::compute_subnet::<MainnetEthSpec>(
+                duty.slot,
+                duty.index,
+                duty.committees_at_slot,
+                &subnet_service.beacon_chain.spec,
+            )
+            .unwrap(),
+        );
+
+        // Drain the events for the permanent subscriptions.
+        let _ = get_events(&mut subnet_service, None, 1).await;
+
+        subnet_service.register_local_validators(vec![validator_index]);
+        let events = get_events(&mut subnet_service, None, 1).await;
+        let predicted_discovery = events.iter().any(|event| {
+            matches!(event, SubnetServiceMessage::DiscoverPeers(discoveries)
+                if discoveries.iter().any(|d| d.subnet == expected_subnet && d.min_ttl.is_some()))
+        });
+        // The duty may be too close for a discovery search if the epoch is about to end.
+        if duty.slot >= current_slot + MIN_PEER_DISCOVERY_SLOT_LOOK_AHEAD + 1 {
+            assert!(predicted_discovery);
+        }
+
+        // Registering the validator again in the same epoch doesn't repeat the discovery.
+        subnet_service.register_local_validators(vec![validator_index]);
+        let events = get_events(&mut subnet_service, None, 1).await;
+        let still_same_epoch = subnet_service.beacon_chain.epoch().unwrap() + 1 == next_epoch;
+        if still_same_epoch {
+            assert!(events.is_empty());
+        }
+    }
 }
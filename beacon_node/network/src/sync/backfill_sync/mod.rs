@@ -7,11 +7,15 @@
 //!
 //! If a batch fails, the backfill sync cannot progress. In this scenario, we mark the backfill
 //! sync as failed, log an error and attempt to retry once a new peer joins the node.
+//!
+//! As in range sync, peers that serve batches quickly are sent several batch requests at once,
+//! bounded by a per-peer window (see [`PeerRequests`]).
 
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::manager::BatchProcessResult;
 use crate::sync::network_context::RangeRequestId;
 use crate::sync::network_context::SyncNetworkContext;
+use crate::sync::peer_requests::{PeerRequests, MAX_WINDOW};
 use crate::sync::range_sync::{
     BatchConfig, BatchId, BatchInfo, BatchOperationOutcome, BatchProcessingResult, BatchState,
 };
@@ -121,8 +125,9 @@ pub struct BackFillSync<T: BeaconChainTypes> {
     /// Sorted map of batches undergoing some kind of processing.
     batches: BTreeMap<BatchId, BatchInfo<T::EthSpec, BackFillBatchConfig>>,
 
-    /// List of peers we are currently awaiting a response for.
-    active_requests: HashMap<PeerId, HashSet<BatchId>>,
+    /// The batches requested from each peer and the number of batches that may be requested from
+    /// it at once.
+    active_requests: HashMap<PeerId, PeerRequests>,
 
     /// The current processing batch, if any.
     current_processing_batch: Option<BatchId>,
@@ -299,9 +304,9 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             return Ok(());
         }
 
-        if let Some(batch_ids) = self.active_requests.remove(peer_id) {
+        if let Some(requests) = self.active_requests.remove(peer_id) {
             // fail the batches.
-            for id in batch_ids {
+            for id in requests.batch_ids() {
                 if let Some(batch) = self.batches.get_mut(&id) {
                     match batch.download_failed(false) {
                         Ok(BatchOperationOutcome::Failed { blacklist: _ }) => {
@@ -358,7 +363,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             }
             debug!(self.log, "Batch failed"; "batch_epoch" => batch_id, "error" => "rpc_error");
             if let Some(active_requests) = self.active_requests.get_mut(peer_id) {
-                active_requests.remove(&batch_id);
+                active_requests.download_failed(&batch_id);
             }
             match batch.download_failed(true) {
                 Err(e) => self.fail_sync(BackFillError::BatchInvalidState(batch_id, e.0)),
@@ -412,13 +417,13 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
-            // Remove the request from the peer's active batches
-            self.active_requests
-                .get_mut(peer_id)
-                .map(|active_requests| active_requests.remove(&batch_id));
-
             match batch.download_completed(blocks) {
                 Ok(received) => {
+                    // Remove the request from the peer's active batches, growing its window if
+                    // it served the batch quickly
+                    if let Some(active_requests) = self.active_requests.get_mut(peer_id) {
+                        active_requests.download_completed(&batch_id);
+                    }
                     let awaiting_batches =
                         self.processing_target.saturating_sub(batch_id) / BACKFILL_EPOCHS_PER_BATCH;
                     debug!(self.log, "Completed batch received"; "epoch" => batch_id, "blocks" => received, "awaiting_batches" => awaiting_batches);
@@ -436,6 +441,9 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
                         }
                         Ok(v) => v,
                     };
+                    if let Some(active_requests) = self.active_requests.get_mut(peer_id) {
+                        active_requests.download_failed(&batch_id);
+                    }
                     warn!(self.log, "Batch received out of range blocks"; "expected_boundary" => expected_boundary, "received_boundary" => received_boundary,
                         "peer_id" => %peer_id, batch);
 
@@ -1029,21 +1037,34 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
 
         // randomize the peers for load balancing
         let mut rng = rand::thread_rng();
-        let mut idle_peers = self
+        let mut peers = self
             .network_globals
             .peers
             .read()
             .synced_peers()
-            .filter(|peer_id| {
-                self.active_requests
+            .map(|peer_id| {
+                let free_slots = self
+                    .active_requests
                     .get(peer_id)
-                    .map(|requests| requests.is_empty())
-                    .unwrap_or(true)
+                    .map(|requests| requests.free_slots())
+                    .unwrap_or_else(|| PeerRequests::default().free_slots());
+                (*peer_id, free_slots)
             })
-            .cloned()
+            .filter(|(_, free_slots)| *free_slots > 0)
             .collect::<Vec<_>>();
-
-        idle_peers.shuffle(&mut rng);
+        peers.shuffle(&mut rng);
+
+        // hand out batches round-robin, so that every peer gets a request before any peer gets a
+        // second one. Peers are popped from the back.
+        let mut idle_peers = (0..MAX_WINDOW)
+            .flat_map(|round| {
+                peers
+                    .iter()
+                    .filter(move |(_, free_slots)| *free_slots > round)
+                    .map(|(peer, _)| *peer)
+            })
+            .collect::<Vec<_>>();
+        idle_peers.reverse();
 
         while let Some(peer) = idle_peers.pop() {
             if let Some(batch_id) = self.include_next_batch(network) {
@@ -6,6 +6,7 @@ mod block_lookups;
 mod block_sidecar_coupling;
 pub mod manager;
 mod network_context;
+mod peer_requests;
 mod peer_sampling;
 mod peer_sync_info;
 mod range_sync;
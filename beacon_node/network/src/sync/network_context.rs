@@ -626,6 +626,20 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         block_root: Hash256,
         expected_blobs: usize,
     ) -> Result<LookupRequestResult, RpcRequestSendError> {
+        // While the block is processing, the missing blobs are being fetched from the EL's blob
+        // pool. Wait for the result so that only the blobs still missing are requested from peers.
+        if let BlockProcessStatus::NotValidated { .. } =
+            self.chain.get_block_process_status(&block_root)
+        {
+            // Lookup sync event safety: If the block is currently in the processing cache, we are
+            // guaranteed to receive a `SyncMessage::GossipBlockProcessResult` or a
+            // `SyncMessage::BlockComponentProcessed` after the engine blobs have been processed,
+            // which will make progress on this lookup
+            return Ok(LookupRequestResult::Pending(
+                "block in processing cache, awaiting engine blobs",
+            ));
+        }
+
         let imported_blob_indexes = self
             .chain
             .data_availability_checker
@@ -0,0 +1,135 @@
+//! Tracks the batches requested from each peer by range and backfill sync and adapts how many
+//! batches may be requested from a peer at once.
+//!
+//! Each peer has a congestion window, in the style of TCP's AIMD (additive increase,
+//! multiplicative decrease). Every batch downloaded quickly grows the window by one, allowing
+//! more requests to be pipelined to fast peers. A slow download, a failed download or an RPC error
+//! halves the window, so that slow or faulty peers are quickly limited to a single batch.
+//!
+//! Only the number of in-flight batches adapts. Batches themselves remain one epoch long, as
+//! required to align requests with the Deneb fork boundary (see `SyncNetworkContext::batch_type`).
+
+use super::range_sync::BatchId;
+use crate::metrics;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The number of batches that may initially be requested from a peer at once.
+const INITIAL_WINDOW: usize = 1;
+
+/// The maximum number of batches that may be requested from a peer at once.
+pub const MAX_WINDOW: usize = 4;
+
+/// Batches downloaded faster than this grow the peer's window, slower downloads shrink it.
+const TARGET_DOWNLOAD_TIME: Duration = Duration::from_secs(4);
+
+/// The batches being downloaded from a peer and the number of batches that may be downloaded from
+/// it at once.
+#[derive(Debug)]
+pub struct PeerRequests {
+    /// The batches being downloaded from the peer, with the time the download was requested.
+    active: HashMap<BatchId, Instant>,
+    /// The maximum number of batches that may be downloaded from the peer at once.
+    window: usize,
+}
+
+impl Default for PeerRequests {
+    fn default() -> Self {
+        Self {
+            active: HashMap::new(),
+            window: INITIAL_WINDOW,
+        }
+    }
+}
+
+impl PeerRequests {
+    /// Registers a batch being requested from the peer.
+    pub fn insert(&mut self, batch_id: BatchId) {
+        self.active.insert(batch_id, Instant::now());
+    }
+
+    /// Removes a batch without adapting the window, e.g. because the chain advanced past it.
+    pub fn remove(&mut self, batch_id: &BatchId) {
+        self.active.remove(batch_id);
+    }
+
+    /// Removes a batch that the peer successfully served, adapting the window to how quickly it
+    /// was downloaded.
+    pub fn download_completed(&mut self, batch_id: &BatchId) {
+        if let Some(requested_at) = self.active.remove(batch_id) {
+            let download_time = requested_at.elapsed();
+            metrics::observe_duration(&metrics::SYNC_BATCH_DOWNLOAD_TIME, download_time);
+            self.on_download_time(download_time);
+            metrics::observe(&metrics::SYNC_PEER_REQUEST_WINDOW, self.window as f64);
+        }
+    }
+
+    /// Removes a batch that the peer failed to serve, shrinking the window.
+    pub fn download_failed(&mut self, batch_id: &BatchId) {
+        if self.active.remove(batch_id).is_some() {
+            self.shrink_window();
+        }
+    }
+
+    /// Returns the number of further batches that may be requested from the peer.
+    pub fn free_slots(&self) -> usize {
+        self.window.saturating_sub(self.active.len())
+    }
+
+    /// Returns the maximum number of batches that may be downloaded from the peer at once.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the number of batches being downloaded from the peer.
+    pub fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Returns the batches being downloaded from the peer.
+    pub fn batch_ids(&self) -> impl Iterator<Item = BatchId> + '_ {
+        self.active.keys().copied()
+    }
+
+    fn on_download_time(&mut self, download_time: Duration) {
+        if download_time <= TARGET_DOWNLOAD_TIME {
+            self.window = std::cmp::min(self.window + 1, MAX_WINDOW);
+        } else {
+            self.shrink_window();
+        }
+    }
+
+    fn shrink_window(&mut self) {
+        self.window = std::cmp::max(self.window / 2, INITIAL_WINDOW);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_grows_additively_and_shrinks_multiplicatively() {
+        let mut requests = PeerRequests::default();
+        assert_eq!(requests.free_slots(), INITIAL_WINDOW);
+
+        for _ in 0..MAX_WINDOW + 1 {
+            requests.on_download_time(Duration::from_millis(100));
+        }
+        assert_eq!(requests.window(), MAX_WINDOW);
+
+        requests.on_download_time(TARGET_DOWNLOAD_TIME * 2);
+        assert_eq!(requests.window(), MAX_WINDOW / 2);
+
+        let batch_id = BatchId::new(1);
+        requests.insert(batch_id);
+        assert_eq!(requests.free_slots(), MAX_WINDOW / 2 - 1);
+        requests.download_failed(&batch_id);
+        assert_eq!(requests.window(), INITIAL_WINDOW);
+        assert_eq!(requests.free_slots(), INITIAL_WINDOW);
+
+        // Batches that were never requested don't affect the window.
+        requests.download_failed(&batch_id);
+        assert_eq!(requests.window(), INITIAL_WINDOW);
+    }
+}
@@ -4,6 +4,7 @@ use crate::metrics;
 use crate::metrics::PEERS_PER_COLUMN_SUBNET;
 use crate::network_beacon_processor::ChainSegmentProcessId;
 use crate::sync::network_context::RangeRequestId;
+use crate::sync::peer_requests::{PeerRequests, MAX_WINDOW};
 use crate::sync::{network_context::SyncNetworkContext, BatchOperationOutcome, BatchProcessResult};
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::BeaconChainTypes;
@@ -30,6 +31,10 @@ pub const EPOCHS_PER_BATCH: u64 = 1;
 /// The maximum number of batches to queue before requesting more.
 const BATCH_BUFFER_SIZE: u8 = 5;
 
+/// The maximum number of batches to queue when peers have grown their request windows. The buffer
+/// grows beyond `BATCH_BUFFER_SIZE` by the extra batches peers can have in flight.
+const MAX_BATCH_BUFFER_SIZE: usize = 16;
+
 /// A return type for functions that act on a `Chain` which informs the caller whether the chain
 /// has been completed and should be removed or to be kept if further processing is
 /// required.
@@ -91,7 +96,7 @@ pub struct SyncingChain<T: BeaconChainTypes> {
     /// The peers that agree on the `target_head_slot` and `target_head_root` as a canonical chain
     /// and thus available to download this chain from, as well as the batches we are currently
     /// requesting.
-    peers: FnvHashMap<PeerId, HashSet<BatchId>>,
+    peers: FnvHashMap<PeerId, PeerRequests>,
 
     /// Starting epoch of the next batch that needs to be downloaded.
     to_be_downloaded: BatchId,
@@ -202,9 +207,9 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         peer_id: &PeerId,
         network: &mut SyncNetworkContext<T>,
     ) -> ProcessingResult {
-        if let Some(batch_ids) = self.peers.remove(peer_id) {
+        if let Some(requests) = self.peers.remove(peer_id) {
             // fail the batches.
-            for id in batch_ids {
+            for id in requests.batch_ids() {
                 if let Some(batch) = self.batches.get_mut(&id) {
                     if let BatchOperationOutcome::Failed { blacklist } =
                         batch.download_failed(true)?
@@ -270,13 +275,13 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
-            // Remove the request from the peer's active batches
-            self.peers
-                .get_mut(peer_id)
-                .map(|active_requests| active_requests.remove(&batch_id));
-
             match batch.download_completed(blocks) {
                 Ok(received) => {
+                    // Remove the request from the peer's active batches, growing its window if
+                    // it served the batch quickly
+                    if let Some(active_requests) = self.peers.get_mut(peer_id) {
+                        active_requests.download_completed(&batch_id);
+                    }
                     let awaiting_batches = batch_id
                         .saturating_sub(self.optimistic_start.unwrap_or(self.processing_target))
                         / EPOCHS_PER_BATCH;
@@ -288,6 +293,9 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 }
                 Err(result) => {
                     let (expected_boundary, received_boundary, outcome) = result?;
+                    if let Some(active_requests) = self.peers.get_mut(peer_id) {
+                        active_requests.download_failed(&batch_id);
+                    }
                     warn!(self.log, "Batch received out of range blocks"; "expected_boundary" => expected_boundary, "received_boundary" => received_boundary,
                         "peer_id" => %peer_id, batch);
 
@@ -849,8 +857,8 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         peer_id: PeerId,
     ) -> ProcessingResult {
         // add the peer without overwriting its active requests
-        if self.peers.entry(peer_id).or_default().is_empty() {
-            // Either new or not, this peer is idle, try to request more batches
+        if self.peers.entry(peer_id).or_default().free_slots() > 0 {
+            // Either new or not, this peer can take more requests, try to request more batches
             self.request_batches(network)
         } else {
             Ok(KeepChain)
@@ -896,7 +904,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 "batch_state" => batch_state
             );
             if let Some(active_requests) = self.peers.get_mut(peer_id) {
-                active_requests.remove(&batch_id);
+                active_requests.download_failed(&batch_id);
             }
             if let BatchOperationOutcome::Failed { blacklist } = batch.download_failed(true)? {
                 return Err(RemoveChain::ChainFailed {
@@ -1059,18 +1067,25 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
 
         // randomize the peers for load balancing
         let mut rng = rand::thread_rng();
-        let mut idle_peers = self
+        let mut peers = self
             .peers
             .iter()
-            .filter_map(|(peer, requests)| {
-                if requests.is_empty() {
-                    Some(*peer)
-                } else {
-                    None
-                }
+            .map(|(peer, requests)| (*peer, requests.free_slots()))
+            .filter(|(_, free_slots)| *free_slots > 0)
+            .collect::<Vec<_>>();
+        peers.shuffle(&mut rng);
+
+        // hand out batches round-robin, so that every peer gets a request before any peer gets a
+        // second one. Peers are popped from the back.
+        let mut idle_peers = (0..MAX_WINDOW)
+            .flat_map(|round| {
+                peers
+                    .iter()
+                    .filter(move |(_, free_slots)| *free_slots > round)
+                    .map(|(peer, _)| *peer)
             })
             .collect::<Vec<_>>();
-        idle_peers.shuffle(&mut rng);
+        idle_peers.reverse();
 
         // check if we have the batch for our optimistic start. If not, request it first.
         // We wait for this batch before requesting any other batches.
@@ -1141,6 +1156,20 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         }
     }
 
+    /// Returns the maximum number of batches to queue before requesting more. Peers that have grown
+    /// their request windows can have more batches in flight, so the buffer grows with them.
+    fn batch_buffer_size(&self) -> usize {
+        let extra_slots = self
+            .peers
+            .values()
+            .map(|requests| requests.window().saturating_sub(1))
+            .sum::<usize>();
+        std::cmp::min(
+            BATCH_BUFFER_SIZE as usize + extra_slots,
+            MAX_BATCH_BUFFER_SIZE,
+        )
+    }
+
     /// Creates the next required batch from the chain. If there are no more batches required,
     /// `false` is returned.
     fn include_next_batch(&mut self, network: &mut SyncNetworkContext<T>) -> Option<BatchId> {
@@ -1166,7 +1195,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
             .iter()
             .filter(|&(_epoch, batch)| in_buffer(batch))
             .count()
-            > BATCH_BUFFER_SIZE as usize
+            > self.batch_buffer_size()
         {
             return None;
         }
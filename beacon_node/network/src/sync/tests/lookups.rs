@@ -1948,13 +1948,13 @@ fn block_in_processing_cache_becomes_invalid() {
     let peer_id = r.new_connected_peer();
     r.insert_block_to_processing_cache(block.clone().into());
     r.trigger_unknown_block_from_attestation(block_root, peer_id);
-    // Should trigger blob request
-    let id = r.expect_blob_lookup_request(block_root);
-    // Should not trigger block request
+    // Should not trigger block or blob request while the block is processing, as the blobs may be
+    // fetched from the EL
     r.expect_empty_network();
     // Simulate invalid block, removing it from processing cache
     r.simulate_block_gossip_processing_becomes_invalid(block_root);
-    // Should download block, then issue blobs request
+    // Should issue blobs request, and download block
+    let id = r.expect_blob_lookup_request(block_root);
     r.complete_lookup_block_download(block);
     // Should not trigger block or blob request
     r.expect_empty_network();
@@ -1974,13 +1974,13 @@ fn block_in_processing_cache_becomes_valid_imported() {
     let peer_id = r.new_connected_peer();
     r.insert_block_to_processing_cache(block.clone().into());
     r.trigger_unknown_block_from_attestation(block_root, peer_id);
-    // Should trigger blob request
-    let id = r.expect_blob_lookup_request(block_root);
-    // Should not trigger block request
+    // Should not trigger block or blob request while the block is processing, as the blobs may be
+    // fetched from the EL
     r.expect_empty_network();
     // Resolve the block from processing step
     r.simulate_block_gossip_processing_becomes_valid_missing_components(block.into());
-    // Should not trigger block or blob request
+    // Should request the blobs still missing, but not the block
+    let id = r.expect_blob_lookup_request(block_root);
     r.expect_empty_network();
     // Resolve blob and expect lookup completed
     r.complete_single_lookup_blob_lookup_valid(id, peer_id, blobs, true);
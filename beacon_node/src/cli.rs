@@ -113,6 +113,41 @@ pub fn cli_app() -> Command {
                        This will also advertise the beacon node as being long-lived subscribed to all subnets.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("backbone-attestation-subnets")
+                .long("backbone-attestation-subnets")
+                .value_name("SUBNETS")
+                .help("A comma-separated list of attestation subnets to subscribe to for the lifetime \
+                       of the node, in addition to those computed from the node id. The subnets are \
+                       advertised in the ENR and metadata, and the node maintains \
+                       --backbone-subnet-peers peers on each of them. Used to run dedicated \
+                       propagation nodes without subscribing to all subnets.")
+                .action(ArgAction::Set)
+                .conflicts_with("subscribe-all-subnets")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("backbone-sync-committee-subnets")
+                .long("backbone-sync-committee-subnets")
+                .value_name("SUBNETS")
+                .help("A comma-separated list of sync committee subnets to subscribe to for the \
+                       lifetime of the node. The subnets are advertised in the ENR and metadata, and \
+                       the node maintains --backbone-subnet-peers peers on each of them.")
+                .action(ArgAction::Set)
+                .conflicts_with("subscribe-all-subnets")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("backbone-subnet-peers")
+                .long("backbone-subnet-peers")
+                .value_name("COUNT")
+                .help("The number of peers to maintain on each of the subnets set by \
+                       --backbone-attestation-subnets and --backbone-sync-committee-subnets. Peers \
+                       are not pruned from a backbone subnet which is at or below this count.")
+                .action(ArgAction::Set)
+                .default_value("6")
+                .display_order(0)
+        )
         .arg(
             Arg::new("import-all-attestations")
                 .long("import-all-attestations")
@@ -231,6 +266,17 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("bls-batch-size")
+                .long("bls-batch-size")
+                .value_name("SETS")
+                .help("The maximum number of signature sets to verify in a single batch. Larger \
+                       collections of sets are split into batches which are verified in parallel. \
+                       Zero places no limit on the size of a batch.")
+                .action(ArgAction::Set)
+                .default_value("0")
+                .display_order(0)
+        )
         .arg(
             Arg::new("boot-nodes")
                 .long("boot-nodes")
@@ -398,7 +444,11 @@ pub fn cli_app() -> Command {
             Arg::new("trusted-peers")
                 .long("trusted-peers")
                 .value_name("TRUSTED_PEERS")
-                .help("One or more comma-delimited trusted peer ids which always have the highest score according to the peer scoring system.")
+                .help("One or more comma-delimited trusted peers, given as peer ids, ENRs or multiaddrs \
+                       including a peer id. Trusted peers always have the highest score according to \
+                       the peer scoring system, are never banned or pruned and are marked as explicit \
+                       peers in gossipsub. Trusted peers given as ENRs or multiaddrs are always dialed \
+                       and redialed whenever they disconnect.")
                 .action(ArgAction::Set)
                 .display_order(0)
                 .display_order(0)
@@ -574,6 +624,87 @@ pub fn cli_app() -> Command {
                        Increasing this value can prevent messages from being dropped.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-sse-replay-window")
+                .long("http-sse-replay-window")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .default_value_if("enable_http", ArgPredicate::IsPresent, "0")
+                .value_name("SECONDS")
+                .help("Number of seconds for which head, finalized checkpoint, chain reorg and \
+                       blob sidecar server-sent events (SSE) are retained, so that clients which \
+                       reconnect with a Last-Event-ID header receive the events they missed. \
+                       Events are retained even when no clients are subscribed. Replay is \
+                       disabled by default, or when set to 0.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-rate-limit")
+                .long("http-rate-limit")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("REQUESTS_PER_SECOND")
+                .help("Limit the number of HTTP API requests per second from each client IP \
+                       address. Requests made by validator clients to perform their duties are \
+                       not limited. Requests beyond the limit receive a 429 response.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-rate-limit-burst")
+                .long("http-rate-limit-burst")
+                .requires("http-rate-limit")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("The number of HTTP API requests a client may make in a burst before \
+                       being limited by --http-rate-limit. Defaults to the rate limit.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-max-concurrent-state-requests")
+                .long("http-max-concurrent-state-requests")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("The maximum number of HTTP API state queries (e.g. /eth/v1/beacon/states) \
+                       to process concurrently. Further requests are queued, and rejected if the \
+                       queue is full.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-max-concurrent-requests")
+                .long("http-max-concurrent-requests")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("N")
+                .help("The maximum number of HTTP API requests, other than state queries and \
+                       validator duty requests, to process concurrently. Further requests are \
+                       queued, and rejected if the queue is full.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-admin-token-file")
+                .long("http-admin-token-file")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("PATH")
+                .help("A file containing a token which enables the /lighthouse/admin endpoints. \
+                       Requests to these endpoints must include the token in an \
+                       `Authorization: Bearer <TOKEN>` header. The admin endpoints are disabled \
+                       if this flag is not supplied.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-compression-level")
+                .long("http-compression-level")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("LEVEL")
+                .help("Compress HTTP API responses for clients which advertise support for zstd, \
+                       gzip or deflate in their Accept-Encoding header, at the given level from 1 \
+                       (fastest) to 9 (smallest). Responses are not compressed unless this flag \
+                       is supplied.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-duplicate-block-status")
                 .long("http-duplicate-block-status")
@@ -648,6 +779,18 @@ pub fn cli_app() -> Command {
             .action(ArgAction::Set)
             .display_order(0)
         )
+        .arg(
+            Arg::new("head-state-pool-size")
+                .long("head-state-pool-size")
+                .value_name("SIZE")
+                .help("The number of clones of the head state to make in advance for HTTP API \
+                       requests and duty computations which need a mutable head state. Each \
+                       clone shares most of its memory with the head state. Set to 0 to clone \
+                       the head state on demand.")
+                .default_value("2")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("idontwant-message-size-threshold")
                 .long("idontwant-message-size-threshold")
@@ -685,6 +828,91 @@ pub fn cli_app() -> Command {
                 .display_order(0)
         )
 
+        /*
+         * Telemetry
+         */
+        .arg(
+            Arg::new("telemetry-collector-url")
+                .long("telemetry-collector-url")
+                .value_name("URL")
+                .help("Enables exporting OpenTelemetry traces of the block import pipeline, from \
+                       gossip receipt to the head update, to the OTLP gRPC endpoint at this URL.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("telemetry-sample-rate")
+                .long("telemetry-sample-rate")
+                .value_name("FRACTION")
+                .help("The fraction of blocks whose import is traced when \
+                       --telemetry-collector-url is set.")
+                .default_value("1.0")
+                .requires("telemetry-collector-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("telemetry-service-name")
+                .long("telemetry-service-name")
+                .value_name("NAME")
+                .help("The service name that traces are attributed to in the collector.")
+                .default_value("lighthouse-bn")
+                .requires("telemetry-collector-url")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+
+        /*
+         * Failover
+         */
+        .arg(
+            Arg::new("failover-peer")
+                .long("failover-peer")
+                .value_name("ADDRESS")
+                .help("Run as one of a pair of beacon nodes, where only the active node publishes \
+                       messages and subscribes to subnets for validator clients. The \
+                       standby node stays synced and takes over if the active node becomes \
+                       unhealthy. The value is the HTTP API of the other node, which must also \
+                       be run with this flag. Validator clients should be configured with both \
+                       nodes.")
+                .requires("failover-priority")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("failover-priority")
+                .long("failover-priority")
+                .value_name("INTEGER")
+                .help("The priority of this node in the failover pair. Of two healthy nodes, the \
+                       one with the lowest priority is active. Each node must have a distinct \
+                       priority.")
+                .requires("failover-peer")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("failover-check-interval")
+                .long("failover-check-interval")
+                .value_name("SECONDS")
+                .help("The number of seconds between checks of the failover peer's health.")
+                .requires("failover-peer")
+                .default_value("4")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("failover-threshold")
+                .long("failover-threshold")
+                .value_name("COUNT")
+                .help("The number of consecutive failed checks of the failover peer after which \
+                       this node takes over.")
+                .requires("failover-peer")
+                .default_value("3")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+
         /*
          * Standard staking flags
          */
@@ -786,6 +1014,27 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("fast-restart")
+                .long("fast-restart")
+                .help("Persist the head state and its caches to the database at shutdown, and load \
+                       them at start-up. This avoids replaying blocks and rebuilding caches on \
+                       restart, at the cost of writing a full state to disk at shutdown.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("fast-restart-interval")
+                .long("fast-restart-interval")
+                .value_name("EPOCHS")
+                .requires("fast-restart")
+                .help("Also persist the head state every EPOCHS epochs, so that restarts after a \
+                       crash or unclean shutdown only replay the blocks since. Each persisted \
+                       state is a full state write, done in the background.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("block-cache-size")
                 .long("block-cache-size")
@@ -837,7 +1086,8 @@ pub fn cli_app() -> Command {
                 .help("Server endpoint for an execution layer JWT-authenticated HTTP \
                        JSON-RPC connection. Uses the same endpoint to populate the \
                        deposit cache.")
-                .required(true)
+                .required_unless_present("unsafe-light-mode")
+                .conflicts_with("unsafe-light-mode")
                 .action(ArgAction::Set)
                 .display_order(0)
         )
@@ -864,6 +1114,19 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("execution-jwt-next")
+                .long("execution-jwt-next")
+                .value_name("EXECUTION-JWT-NEXT")
+                .help("File path which contains the hex-encoded JWT secret the execution \
+                       endpoint will use after its secret is rotated. Requests rejected by the \
+                       execution endpoint are retried with this secret. Changes to the \
+                       --execution-jwt file are picked up without a restart, with the previous \
+                       secret remaining accepted until the execution endpoint is restarted.")
+                .requires("execution-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("execution-jwt-id")
                 .long("execution-jwt-id")
@@ -904,7 +1167,11 @@ pub fn cli_app() -> Command {
                 .long("builder")
                 .alias("payload-builder")
                 .alias("payload-builders")
-                .help("The URL of a service compatible with the MEV-boost API.")
+                .help("The URL of a service compatible with the MEV-boost API. Multiple \
+                       comma-separated relays may be provided, in which case all available \
+                       relays are queried and the most valuable valid bid is used. Relays that \
+                       return invalid bids or fail to reveal payloads in time are blacklisted \
+                       for an hour.")
                 .requires("execution-endpoint")
                 .action(ArgAction::Set)
                 .display_order(0)
@@ -918,6 +1185,58 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("record-engine-calls")
+                .long("record-engine-calls")
+                .value_name("DIR")
+                .help("Record every request to the execution endpoints, along with its \
+                       latency and response, to rotating files in this directory. The \
+                       recording can be replayed against an execution client with \
+                       `lcli replay-engine-calls`. Intended for debugging only, recordings \
+                       can use up to 512MB of disk space.")
+                .requires("execution-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("redundant-execution-endpoints")
+                .long("redundant-execution-endpoints")
+                .value_name("EXECUTION-ENDPOINTS")
+                .help("Comma-separated list of additional execution endpoints that are sent \
+                       every newPayload and forkchoiceUpdated call to cross-check the payload \
+                       status returned by --execution-endpoint. Endpoints are listed in order \
+                       of priority, after --execution-endpoint. An INVALID status from \
+                       --execution-endpoint is ignored while any of these endpoints returns \
+                       VALID. Payloads are never built by these endpoints.")
+                .requires("execution-endpoint")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("redundant-execution-jwts")
+                .long("redundant-execution-jwts")
+                .value_name("EXECUTION-JWTS")
+                .help("Comma-separated list of file paths which contain the hex-encoded JWT \
+                       secrets for the endpoints provided in --redundant-execution-endpoints, \
+                       in the same order. The secret of --execution-endpoint is used for all \
+                       of them if not set.")
+                .requires("redundant-execution-endpoints")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("execution-payload-status-quorum")
+                .long("execution-payload-status-quorum")
+                .value_name("NUM")
+                .help("The number of execution endpoints that must agree for a payload to be \
+                       considered VALID or INVALID. Every endpoint is awaited and payloads \
+                       without a quorum are imported optimistically. If not set, the status of \
+                       the highest priority endpoint that returns VALID or INVALID is used, \
+                       except that INVALID is ignored while any other endpoint returns VALID.")
+                .requires("redundant-execution-endpoints")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("builder-header-timeout")
                 .long("builder-header-timeout")
@@ -983,6 +1302,37 @@ pub fn cli_app() -> Command {
                        It is generally not recommended unless auto-compaction is disabled.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("archive-orphaned-blocks")
+                .long("archive-orphaned-blocks")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, blocks from forks abandoned by finalization are copied to an \
+                       archive in the database rather than being deleted. Archived blocks can be \
+                       retrieved from the /lighthouse/orphans HTTP API endpoint.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("orphan-archive-retention-epochs")
+                .long("orphan-archive-retention-epochs")
+                .value_name("EPOCHS")
+                .help("The number of epochs before the finalized epoch for which archived \
+                       orphaned blocks and attestations are kept. Older entries are deleted \
+                       from the archive.")
+                .action(ArgAction::Set)
+                .default_value("8192")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("repair-db-inconsistencies")
+                .long("repair-db-inconsistencies")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, inconsistencies found by the database consistency check at \
+                       startup are repaired by replaying blocks, rather than only being \
+                       reported.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("auto-compact-db")
                 .long("auto-compact-db")
@@ -1032,6 +1382,17 @@ pub fn cli_app() -> Command {
                 .default_value("0")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("column-size-estimate-interval")
+                .long("column-size-estimate-interval")
+                .value_name("MINUTES")
+                .help("Estimate the size of each database column every MINUTES minutes and \
+                       publish it in the store_disk_db_column_size_bytes metric. Each estimate \
+                       iterates over every key in the database, which can take a long time on archive nodes. \
+                       Disabled by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Misc.
          */
@@ -1046,6 +1407,21 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("graffiti-file")
+                .long("graffiti-file")
+                .help(
+                    "Path to a file mapping validator public keys to graffiti, with lines of the \
+                    form `<pubkey>: <graffiti>` or `default: <graffiti>`. Listing a key more than \
+                    once rotates through its graffiti in turn, and the placeholders {slot}, \
+                    {epoch} and {timestamp} are substituted at proposal time. The file is \
+                    reloaded whenever it changes. Graffiti from the validator client takes \
+                    precedence."
+                )
+                .value_name("GRAFFITI-FILE")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("max-skip-slots")
                 .long("max-skip-slots")
@@ -1095,6 +1471,19 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("slasher-autotune")
+                .long("slasher-autotune")
+                .help(
+                    "Adapt the number of attestations processed per batch and the period between \
+                     batches to the slasher's backlog and database latency. The \
+                     slasher-update-period becomes the longest period between batches."
+                )
+                .requires("slasher")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("slasher-slot-offset")
                 .long("slasher-slot-offset")
@@ -1183,6 +1572,17 @@ pub fn cli_app() -> Command {
                 .requires("slasher")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("slasher-watch-list")
+                .long("slasher-watch-list")
+                .value_name("VALIDATORS")
+                .help("Comma-separated list of validator indices or 0x-prefixed public keys. The \
+                       slasher keeps the attestations of these validators indefinitely, beyond \
+                       the history length, and raises an alert on any equivocation by them.")
+                .action(ArgAction::Set)
+                .requires("slasher")
+                .display_order(0)
+        )
         .arg(
             Arg::new("wss-checkpoint")
                 .long("wss-checkpoint")
@@ -1256,6 +1656,19 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("import-era-dir")
+                .long("import-era-dir")
+                .help("Import the history prior to the checkpoint from the era files in this \
+                       directory at startup, before syncing, so that it need not be backfilled \
+                       from peers. Each era is verified against the historical summaries of the \
+                       checkpoint state. The blocks and the state at the end of each era are \
+                       imported, so that historic states can be served by replaying blocks. \
+                       Files which can't be imported are skipped.")
+                .value_name("DIR")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("reconstruct-historic-states")
                 .long("reconstruct-historic-states")
@@ -1285,6 +1698,39 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("own-key-guard-pubkeys")
+                .long("own-key-guard-pubkeys")
+                .help("A comma-separated list of 0x-prefixed public keys of validators run by \
+                        this operator. Attestations and blocks from these validators which \
+                        conflict with one already seen by this node are refused publication \
+                        and logged as critical, independently of the validator client's \
+                        slashing protection.")
+                .value_name("PUBKEYS")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("payload-forensics-dir")
+                .long("payload-forensics-dir")
+                .value_name("DIR")
+                .help("The directory to write a forensic bundle to whenever the execution engine \
+                        returns INVALID for a payload. Each bundle contains the block, its parent's \
+                        fork choice record, the engine request and response, and a fork choice \
+                        snapshot. Defaults to `payload_forensics` in the beacon node data directory.")
+                .action(ArgAction::Set)
+                .conflicts_with("disable-payload-forensics")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("disable-payload-forensics")
+                .long("disable-payload-forensics")
+                .help("Don't write a forensic bundle when the execution engine returns INVALID for \
+                        a payload.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validator-monitor-file")
                 .long("validator-monitor-file")
@@ -1306,6 +1752,26 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("validator-monitor-bucket-size")
+                .long("validator-monitor-bucket-size")
+                .help("When the validator monitor is above the individual tracking threshold, \
+                    additionally aggregate its metrics into buckets of this many contiguous \
+                    validator indices. This provides more detail than the `total` metrics \
+                    whilst keeping Prometheus cardinality bounded. Must be at least 64.")
+                .value_name("INTEGER")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("validator-monitor-client-fingerprinting")
+                .long("validator-monitor-client-fingerprinting")
+                .help("Infer the consensus client of each monitored validator from the graffiti \
+                    of its blocks and report missed and late attestations per client.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-proposer-reorgs")
                 .long("disable-proposer-reorgs")
@@ -1314,6 +1780,26 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("disable-shuffling-cache-warming")
+                .long("disable-shuffling-cache-warming")
+                .help("Do not pre-compute attester shufflings for the current and next epoch \
+                       during the quiet part of each slot. Disabling this may cause slow duties \
+                       requests immediately after an epoch transition.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("early-attester-cache-pending-blobs")
+                .long("early-attester-cache-pending-blobs")
+                .help("Allow attesting to a block from the current slot as soon as it has been \
+                       verified, whilst its blobs are still being received. If the blobs do not \
+                       arrive within the slot the block is no longer attested to.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("proposer-reorg-threshold")
                 .long("proposer-reorg-threshold")
@@ -1347,6 +1833,7 @@ pub fn cli_app() -> Command {
         .arg(
             Arg::new("proposer-reorg-cutoff")
                 .long("proposer-reorg-cutoff")
+                .alias("proposer-reorg-cutoff-ms")
                 .value_name("MILLISECONDS")
                 .action(ArgAction::Set)
                 .help("Maximum delay after the start of the slot at which to propose a reorging \
@@ -1370,6 +1857,27 @@ pub fn cli_app() -> Command {
                 .conflicts_with("disable-proposer-reorgs")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposer-reorg-fork-boundary-epochs")
+                .long("proposer-reorg-fork-boundary-epochs")
+                .action(ArgAction::Set)
+                .value_name("EPOCHS")
+                .help("Do not attempt proposer reorgs within this many epochs of a scheduled \
+                       fork, in either direction. A value of 0 avoids reorgs during the fork \
+                       epoch only. By default reorgs are not restricted around forks.")
+                .conflicts_with("disable-proposer-reorgs")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("proposer-reorg-protected-proposers")
+                .long("proposer-reorg-protected-proposers")
+                .action(ArgAction::Set)
+                .value_name("N1,N2,...")
+                .help("Comma-separated list of validator indices whose blocks will never be \
+                       reorged by proposer reorgs, even if they arrive late.")
+                .conflicts_with("disable-proposer-reorgs")
+                .display_order(0)
+        )
         .arg(
             Arg::new("prepare-payload-lookahead")
                 .long("prepare-payload-lookahead")
@@ -1393,6 +1901,30 @@ pub fn cli_app() -> Command {
                 .display_order(0)
                 .requires("suggested-fee-recipient")
         )
+        .arg(
+            Arg::new("suppress-payload-attributes")
+                .long("suppress-payload-attributes")
+                .help("Never send payload attributes to the execution node, even for registered \
+                       proposers. Payloads are instead built when a block is requested. Intended \
+                       for beacon nodes which are not used for block production.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+                .conflicts_with("always-prepare-payload")
+        )
+        .arg(
+            Arg::new("payload-attributes-window")
+                .long("payload-attributes-window")
+                .value_name("EPOCHS")
+                .help("Only send payload attributes for registered proposers if this node has \
+                       produced a block, or served proposer duties which include a registered \
+                       proposer, within this many epochs of the proposal. Useful when a validator \
+                       client registers its proposers with several beacon nodes but only fetches \
+                       duties from and produces blocks with one of them.")
+                .action(ArgAction::Set)
+                .display_order(0)
+                .conflicts_with("always-prepare-payload")
+        )
         .arg(
             Arg::new("fork-choice-before-proposal-timeout")
                 .long("fork-choice-before-proposal-timeout")
@@ -1403,6 +1935,19 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("block-production-budgets")
+                .long("block-production-budgets")
+                .value_name("STAGE=MILLIS,...")
+                .help("Comma-separated list of soft time budgets for the stages of block \
+                       production, e.g. `attestation_packing=500,state_root=250`. Valid stages \
+                       are slot_processing, attestation_packing, payload_fetch and state_root. \
+                       When attestation packing overruns its budget the node packs fewer \
+                       attestations instead of missing the proposal. Overruns of the other \
+                       stages are only reported.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("paranoid-block-proposal")
                 .long("paranoid-block-proposal")
@@ -1501,6 +2046,18 @@ pub fn cli_app() -> Command {
                        Lighthouse and only passed to the EL if initial verification fails.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("unsafe-light-mode")
+                .long("unsafe-light-mode")
+                .value_name("TRUSTED_BEACON_NODE")
+                .help("Run without an execution engine, trusting the beacon node at this URL to \
+                       report which execution payloads are valid. The chain is followed \
+                       optimistically and all validator duties are disabled. Only intended for \
+                       serving data, as a compromised or faulty trusted node can lead this node \
+                       onto an invalid chain.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("light-client-server")
                 .long("light-client-server")
@@ -1530,6 +2087,29 @@ pub fn cli_app() -> Command {
                     filling up their disks.")
             .display_order(0)
         )
+        .arg(
+            Arg::new("gossip-trace-dir")
+                .long("gossip-trace-dir")
+                .value_name("DIR")
+                .help("If present, a sample of received gossip messages are traced to rotating \
+                       newline-delimited JSON files in this directory. Each trace records the \
+                       topic, peer, arrival time, delay from the start of the slot and validation \
+                       result of a message. Intended for studying message propagation and \
+                       identifying slow peers.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-trace-sample-rate")
+                .long("gossip-trace-sample-rate")
+                .value_name("FRACTION")
+                .help("The fraction of received gossip messages to trace when --gossip-trace-dir \
+                       is set.")
+                .default_value("0.01")
+                .requires("gossip-trace-dir")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("beacon-processor-max-workers")
                 .long("beacon-processor-max-workers")
@@ -1542,6 +2122,19 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-processor-gossip-workers")
+                .long("beacon-processor-gossip-workers")
+                .value_name("INTEGER")
+                .help("Specifies the maximum number of tasks which may concurrently validate \
+                        gossip aggregates, attestations, blobs and data columns. Keeping this \
+                        below the maximum number of workers ensures gossip blocks can be \
+                        processed whilst the node is flooded with attestations. The default \
+                        value is one less than the maximum number of workers.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("beacon-processor-work-queue-len")
                 .long("beacon-processor-work-queue-len")
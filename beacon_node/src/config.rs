@@ -5,18 +5,22 @@ use beacon_chain::chain_config::{
     DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
 use beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_chain::validator_monitor::MIN_AGGREGATE_BUCKET_SIZE;
 use beacon_chain::TrustedSetup;
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use clap_utils::{parse_flag, parse_required};
-use client::{ClientConfig, ClientGenesis};
+use client::{ClientConfig, ClientGenesis, FailoverConfig};
 use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use environment::RuntimeContext;
 use execution_layer::DEFAULT_JWT_FILE;
 use genesis::Eth1Endpoint;
 use http_api::TlsConfig;
 use lighthouse_network::ListenAddress;
-use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use lighthouse_network::{
+    multiaddr::Protocol, Enr, EnrExt, Multiaddr, NetworkConfig, PeerIdSerialized,
+};
+use logging::TelemetryConfig;
 use sensitive_url::SensitiveUrl;
 use slog::{info, warn, Logger};
 use std::cmp::max;
@@ -29,6 +33,7 @@ use std::num::NonZeroU16;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
+use types::consts::altair::SYNC_COMMITTEE_SUBNET_COUNT;
 use types::graffiti::GraffitiString;
 use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes};
 
@@ -114,6 +119,19 @@ pub fn get_config<E: EthSpec>(
 
     set_network_config(&mut client_config.network, cli_args, &data_dir_ref, log)?;
 
+    if let Some(subnet) = client_config
+        .network
+        .backbone_attestation_subnets
+        .iter()
+        .find(|subnet| **subnet >= spec.attestation_subnet_count)
+    {
+        return Err(format!(
+            "Invalid --backbone-attestation-subnets value: {} is not below the attestation \
+             subnet count of {}",
+            subnet, spec.attestation_subnet_count
+        ));
+    }
+
     /*
      * Staking flag
      * Note: the config values set here can be overwritten by other more specific cli params
@@ -169,6 +187,43 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.sse_capacity_multiplier =
             parse_required(cli_args, "http-sse-capacity-multiplier")?;
 
+        client_config.http_api.sse_replay_window_secs =
+            parse_required(cli_args, "http-sse-replay-window")?;
+
+        client_config.http_api.rate_limit =
+            clap_utils::parse_optional(cli_args, "http-rate-limit")?;
+        client_config.http_api.rate_limit_burst =
+            clap_utils::parse_optional(cli_args, "http-rate-limit-burst")?;
+        client_config.http_api.max_concurrent_state_requests =
+            clap_utils::parse_optional(cli_args, "http-max-concurrent-state-requests")?;
+        client_config.http_api.max_concurrent_requests =
+            clap_utils::parse_optional(cli_args, "http-max-concurrent-requests")?;
+        if client_config.http_api.rate_limit == Some(0) {
+            return Err("--http-rate-limit must be a non-zero value".to_string());
+        }
+        if client_config.http_api.max_concurrent_state_requests == Some(0) {
+            return Err(
+                "--http-max-concurrent-state-requests must be a non-zero value".to_string(),
+            );
+        }
+        if client_config.http_api.max_concurrent_requests == Some(0) {
+            return Err("--http-max-concurrent-requests must be a non-zero value".to_string());
+        }
+
+        client_config.http_api.admin_token_file =
+            clap_utils::parse_optional(cli_args, "http-admin-token-file")?;
+
+        if let Some(level) = clap_utils::parse_optional::<u32>(cli_args, "http-compression-level")?
+        {
+            if !(1..=http_api::MAX_COMPRESSION_LEVEL).contains(&level) {
+                return Err(format!(
+                    "--http-compression-level must be between 1 and {}",
+                    http_api::MAX_COMPRESSION_LEVEL
+                ));
+            }
+            client_config.http_api.compression_level = Some(level);
+        }
+
         client_config.http_api.enable_beacon_processor =
             parse_required(cli_args, "http-enable-beacon-processor")?;
 
@@ -187,6 +242,9 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.shuffling_cache_size = cache_size;
     }
 
+    client_config.chain.head_state_pool_size =
+        clap_utils::parse_required(cli_args, "head-state-pool-size")?;
+
     if cli_args.get_flag("enable-sampling") {
         client_config.chain.enable_sampling = true;
     }
@@ -232,6 +290,18 @@ pub fn get_config<E: EthSpec>(
     /*
      * Explorer metrics
      */
+    if let Some(collector_url) = cli_args.get_one::<String>("telemetry-collector-url") {
+        let sample_rate: f64 = clap_utils::parse_required(cli_args, "telemetry-sample-rate")?;
+        if !(0.0..=1.0).contains(&sample_rate) {
+            return Err("--telemetry-sample-rate must be between 0 and 1".to_string());
+        }
+        client_config.telemetry = Some(TelemetryConfig {
+            collector_url: collector_url.clone(),
+            service_name: clap_utils::parse_required(cli_args, "telemetry-service-name")?,
+            sample_rate,
+        });
+    }
+
     if let Some(monitoring_endpoint) = cli_args.get_one::<String>("monitoring-endpoint") {
         let update_period_secs =
             clap_utils::parse_optional(cli_args, "monitoring-endpoint-period")?;
@@ -244,6 +314,26 @@ pub fn get_config<E: EthSpec>(
         });
     }
 
+    /*
+     * Failover
+     */
+    if let Some(peer) = clap_utils::parse_optional(cli_args, "failover-peer")? {
+        let priority = clap_utils::parse_required(cli_args, "failover-priority")?;
+        let check_interval_secs: u64 =
+            clap_utils::parse_required(cli_args, "failover-check-interval")?;
+        if check_interval_secs == 0 {
+            return Err("--failover-check-interval must be at least 1".to_string());
+        }
+
+        client_config.http_api.failover_priority = Some(priority);
+        client_config.failover = Some(FailoverConfig {
+            peer,
+            priority,
+            check_interval: Duration::from_secs(check_interval_secs),
+            failure_threshold: clap_utils::parse_required(cli_args, "failover-threshold")?,
+        });
+    }
+
     // Log a warning indicating an open HTTP server if it wasn't specified explicitly
     // (e.g. using the --staking flag).
     if cli_args.get_flag("staking") {
@@ -286,83 +376,154 @@ pub fn get_config<E: EthSpec>(
         client_config.eth1.cache_follow_distance = Some(follow_distance);
     }
 
-    // `--execution-endpoint` is required now.
-    let endpoints: String = clap_utils::parse_required(cli_args, "execution-endpoint")?;
-    let mut el_config = execution_layer::Config::default();
+    if let Some(provider) = cli_args.get_one::<String>("unsafe-light-mode") {
+        let provider = SensitiveUrl::parse(provider)
+            .map_err(|e| format!("Invalid --unsafe-light-mode URL: {:?}", e))?;
+        warn!(
+            log,
+            "Running in light mode without an execution engine";
+            "info" => "payload validity is trusted from another node, validator duties are disabled",
+            "provider" => %provider,
+        );
+        client_config.chain.light_mode_provider = Some(provider);
+        // The deposit contract can't be followed without an execution engine.
+        client_config.sync_eth1_chain = false;
+    } else {
+        // `--execution-endpoint` is required unless running in light mode.
+        let endpoints: String = clap_utils::parse_required(cli_args, "execution-endpoint")?;
+        let mut el_config = execution_layer::Config::default();
+
+        // Parse a single execution endpoint, logging warnings if multiple endpoints are supplied.
+        let execution_endpoint = parse_only_one_value(
+            endpoints.as_str(),
+            SensitiveUrl::parse,
+            "--execution-endpoint",
+            log,
+        )?;
+
+        // JWTs are required if `--execution-endpoint` is supplied. They can be either passed via
+        // file_path or directly as string.
 
-    // Parse a single execution endpoint, logging warnings if multiple endpoints are supplied.
-    let execution_endpoint = parse_only_one_value(
-        endpoints.as_str(),
-        SensitiveUrl::parse,
-        "--execution-endpoint",
-        log,
-    )?;
-
-    // JWTs are required if `--execution-endpoint` is supplied. They can be either passed via
-    // file_path or directly as string.
-
-    let secret_file: PathBuf;
-    // Parse a single JWT secret from a given file_path, logging warnings if multiple are supplied.
-    if let Some(secret_files) = cli_args.get_one::<String>("execution-jwt") {
-        secret_file =
-            parse_only_one_value(secret_files, PathBuf::from_str, "--execution-jwt", log)?;
-
-    // Check if the JWT secret key is passed directly via cli flag and persist it to the default
-    // file location.
-    } else if let Some(jwt_secret_key) = cli_args.get_one::<String>("execution-jwt-secret-key") {
-        use std::fs::File;
-        use std::io::Write;
-        secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
-        let mut jwt_secret_key_file = File::create(secret_file.clone())
-            .map_err(|e| format!("Error while creating jwt_secret_key file: {:?}", e))?;
-        jwt_secret_key_file
-            .write_all(jwt_secret_key.as_bytes())
-            .map_err(|e| {
-                format!(
-                    "Error occurred while writing to jwt_secret_key file: {:?}",
-                    e
+        let secret_file: PathBuf;
+        // Parse a single JWT secret from a given file_path, logging warnings if multiple are supplied.
+        if let Some(secret_files) = cli_args.get_one::<String>("execution-jwt") {
+            secret_file =
+                parse_only_one_value(secret_files, PathBuf::from_str, "--execution-jwt", log)?;
+
+        // Check if the JWT secret key is passed directly via cli flag and persist it to the default
+        // file location.
+        } else if let Some(jwt_secret_key) = cli_args.get_one::<String>("execution-jwt-secret-key")
+        {
+            use std::fs::File;
+            use std::io::Write;
+            secret_file = client_config.data_dir().join(DEFAULT_JWT_FILE);
+            let mut jwt_secret_key_file = File::create(secret_file.clone())
+                .map_err(|e| format!("Error while creating jwt_secret_key file: {:?}", e))?;
+            jwt_secret_key_file
+                .write_all(jwt_secret_key.as_bytes())
+                .map_err(|e| {
+                    format!(
+                        "Error occurred while writing to jwt_secret_key file: {:?}",
+                        e
+                    )
+                })?;
+        } else {
+            return Err("Error! Please set either --execution-jwt file_path or --execution-jwt-secret-key directly via cli when using --execution-endpoint".to_string());
+        }
+        el_config.next_secret_file = clap_utils::parse_optional(cli_args, "execution-jwt-next")?;
+
+        // Parse and set the payload builder, if any.
+        if let Some(endpoints) = cli_args.get_one::<String>("builder") {
+            el_config.builder_urls = endpoints
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Invalid --builder: {:?}", e))?;
+
+            el_config.builder_user_agent =
+                clap_utils::parse_optional(cli_args, "builder-user-agent")?;
+
+            el_config.builder_header_timeout =
+                clap_utils::parse_optional(cli_args, "builder-header-timeout")?
+                    .map(Duration::from_millis);
+        }
+
+        // Set config values from parse values.
+        el_config.secret_file = Some(secret_file.clone());
+        el_config.execution_endpoint = Some(execution_endpoint.clone());
+        el_config.suggested_fee_recipient =
+            clap_utils::parse_optional(cli_args, "suggested-fee-recipient")?;
+        el_config.jwt_id = clap_utils::parse_optional(cli_args, "execution-jwt-id")?;
+        el_config.jwt_version = clap_utils::parse_optional(cli_args, "execution-jwt-version")?;
+        el_config
+            .default_datadir
+            .clone_from(client_config.data_dir());
+        let execution_timeout_multiplier =
+            clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
+        el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
+        el_config.engine_call_recording_dir =
+            clap_utils::parse_optional(cli_args, "record-engine-calls")?;
+
+        if let Some(endpoints) = cli_args.get_one::<String>("redundant-execution-endpoints") {
+            let urls = endpoints
+                .split(',')
+                .map(SensitiveUrl::parse)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| format!("Invalid --redundant-execution-endpoints: {:?}", e))?;
+            let secret_files = match cli_args.get_one::<String>("redundant-execution-jwts") {
+                Some(secret_files) => {
+                    let secret_files = secret_files
+                        .split(',')
+                        .map(|path| Some(PathBuf::from(path)))
+                        .collect::<Vec<_>>();
+                    if secret_files.len() != urls.len() {
+                        return Err(format!(
+                            "--redundant-execution-jwts has {} entries but \
+                            --redundant-execution-endpoints has {}",
+                            secret_files.len(),
+                            urls.len()
+                        ));
+                    }
+                    secret_files
+                }
+                None => vec![None; urls.len()],
+            };
+            el_config.redundant_execution_endpoints = urls
+                .into_iter()
+                .zip(secret_files)
+                .map(
+                    |(url, secret_file)| execution_layer::RedundantExecutionEndpoint {
+                        url,
+                        secret_file,
+                    },
                 )
-            })?;
-    } else {
-        return Err("Error! Please set either --execution-jwt file_path or --execution-jwt-secret-key directly via cli when using --execution-endpoint".to_string());
-    }
-
-    // Parse and set the payload builder, if any.
-    if let Some(endpoint) = cli_args.get_one::<String>("builder") {
-        let payload_builder =
-            parse_only_one_value(endpoint, SensitiveUrl::parse, "--builder", log)?;
-        el_config.builder_url = Some(payload_builder);
-
-        el_config.builder_user_agent = clap_utils::parse_optional(cli_args, "builder-user-agent")?;
-
-        el_config.builder_header_timeout =
-            clap_utils::parse_optional(cli_args, "builder-header-timeout")?
-                .map(Duration::from_millis);
-    }
-
-    // Set config values from parse values.
-    el_config.secret_file = Some(secret_file.clone());
-    el_config.execution_endpoint = Some(execution_endpoint.clone());
-    el_config.suggested_fee_recipient =
-        clap_utils::parse_optional(cli_args, "suggested-fee-recipient")?;
-    el_config.jwt_id = clap_utils::parse_optional(cli_args, "execution-jwt-id")?;
-    el_config.jwt_version = clap_utils::parse_optional(cli_args, "execution-jwt-version")?;
-    el_config
-        .default_datadir
-        .clone_from(client_config.data_dir());
-    let execution_timeout_multiplier =
-        clap_utils::parse_required(cli_args, "execution-timeout-multiplier")?;
-    el_config.execution_timeout_multiplier = Some(execution_timeout_multiplier);
-
-    client_config.eth1.endpoint = Eth1Endpoint::Auth {
-        endpoint: execution_endpoint,
-        jwt_path: secret_file,
-        jwt_id: el_config.jwt_id.clone(),
-        jwt_version: el_config.jwt_version.clone(),
-    };
+                .collect();
+
+            if let Some(quorum) =
+                clap_utils::parse_optional::<usize>(cli_args, "execution-payload-status-quorum")?
+            {
+                let engines = el_config.redundant_execution_endpoints.len() + 1;
+                if quorum == 0 || quorum > engines {
+                    return Err(format!(
+                        "--execution-payload-status-quorum must be between 1 and {}",
+                        engines
+                    ));
+                }
+                el_config.payload_status_policy =
+                    execution_layer::PayloadStatusPolicy::Quorum(quorum);
+            }
+        }
+
+        client_config.eth1.endpoint = Eth1Endpoint::Auth {
+            endpoint: execution_endpoint,
+            jwt_path: secret_file,
+            jwt_id: el_config.jwt_id.clone(),
+            jwt_version: el_config.jwt_version.clone(),
+        };
 
-    // Store the EL config in the client config.
-    client_config.execution_layer = Some(el_config);
+        // Store the EL config in the client config.
+        client_config.execution_layer = Some(el_config);
+    }
 
     // 4844 params
     if let Some(trusted_setup) = context
@@ -418,6 +579,13 @@ pub fn get_config<E: EthSpec>(
     }
 
     client_config.store.compact_on_init = cli_args.get_flag("compact-db");
+    client_config.store.archive_orphaned_blocks = cli_args.get_flag("archive-orphaned-blocks");
+    if let Some(retention_epochs) =
+        clap_utils::parse_optional(cli_args, "orphan-archive-retention-epochs")?
+    {
+        client_config.store.orphan_archive_retention_epochs = retention_epochs;
+    }
+    client_config.store.repair_on_init = cli_args.get_flag("repair-db-inconsistencies");
     if let Some(compact_on_prune) = cli_args.get_one::<String>("auto-compact-db") {
         client_config.store.compact_on_prune = compact_on_prune
             .parse()
@@ -427,6 +595,11 @@ pub fn get_config<E: EthSpec>(
     if let Some(prune_payloads) = clap_utils::parse_optional(cli_args, "prune-payloads")? {
         client_config.store.prune_payloads = prune_payloads;
     }
+    // Payloads can't be reconstructed without an execution engine, so they must be kept in order
+    // to serve full blocks.
+    if client_config.chain.light_mode_provider.is_some() {
+        client_config.store.prune_payloads = false;
+    }
 
     if clap_utils::parse_optional::<u64>(cli_args, "slots-per-restore-point")?.is_some() {
         warn!(log, "The slots-per-restore-point flag is deprecated");
@@ -442,6 +615,15 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.epochs_per_migration = epochs_per_migration;
     }
 
+    client_config.chain.fast_restart = cli_args.get_flag("fast-restart");
+
+    if let Some(interval) = clap_utils::parse_optional::<u64>(cli_args, "fast-restart-interval")? {
+        if interval == 0 {
+            return Err("--fast-restart-interval must be at least 1".to_string());
+        }
+        client_config.chain.fast_restart_interval_epochs = Some(interval);
+    }
+
     if let Some(prune_blobs) = clap_utils::parse_optional(cli_args, "prune-blobs")? {
         client_config.store.prune_blobs = prune_blobs;
     }
@@ -458,6 +640,15 @@ pub fn get_config<E: EthSpec>(
         client_config.store.blob_prune_margin_epochs = blob_prune_margin_epochs;
     }
 
+    if let Some(minutes) =
+        clap_utils::parse_optional::<u64>(cli_args, "column-size-estimate-interval")?
+    {
+        if minutes == 0 {
+            return Err("--column-size-estimate-interval must be at least 1".to_string());
+        }
+        client_config.store.column_size_estimate_interval = Some(Duration::from_secs(minutes * 60));
+    }
+
     if let Some(malicious_withhold_count) =
         clap_utils::parse_optional(cli_args, "malicious-withhold-count")?
     {
@@ -587,6 +778,8 @@ pub fn get_config<E: EthSpec>(
         ClientGenesis::DepositContract
     };
 
+    client_config.import_era_dir = clap_utils::parse_optional(cli_args, "import-era-dir")?;
+
     if cli_args.get_flag("reconstruct-historic-states") {
         client_config.chain.reconstruct_historic_states = true;
         client_config.chain.genesis_backfill = true;
@@ -602,6 +795,7 @@ pub fn get_config<E: EthSpec>(
         GraffitiOrigin::default()
     };
     client_config.beacon_graffiti = beacon_graffiti;
+    client_config.graffiti_file = clap_utils::parse_optional(cli_args, "graffiti-file")?;
 
     if let Some(wss_checkpoint) = cli_args.get_one::<String>("wss-checkpoint") {
         let mut split = wss_checkpoint.split(':');
@@ -716,6 +910,15 @@ pub fn get_config<E: EthSpec>(
             slasher_config.backend = backend;
         }
 
+        slasher_config.autotune = cli_args.get_flag("slasher-autotune");
+
+        if let Some(watch_list) = cli_args.get_one::<String>("slasher-watch-list") {
+            slasher_config.watch_list = watch_list
+                .split(',')
+                .map(|validator| validator.trim().parse())
+                .collect::<Result<_, _>>()?;
+        }
+
         client_config.slasher = Some(slasher_config);
     }
 
@@ -735,6 +938,21 @@ pub fn get_config<E: EthSpec>(
             .extend_from_slice(&pubkeys);
     }
 
+    if let Some(pubkeys) = cli_args.get_one::<String>("own-key-guard-pubkeys") {
+        client_config.chain.own_key_guard_pubkeys = pubkeys
+            .split(',')
+            .map(PublicKeyBytes::from_str)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid --own-key-guard-pubkeys value: {:?}", e))?;
+    }
+
+    if !cli_args.get_flag("disable-payload-forensics") {
+        client_config.chain.payload_forensics_dir = Some(
+            clap_utils::parse_optional(cli_args, "payload-forensics-dir")?
+                .unwrap_or_else(|| client_config.data_dir().join("payload_forensics")),
+        );
+    }
+
     if let Some(path) = cli_args.get_one::<String>("validator-monitor-file") {
         let string = fs::read(path)
             .map_err(|e| format!("Unable to read --validator-monitor-file: {}", e))
@@ -762,6 +980,27 @@ pub fn get_config<E: EthSpec>(
             .individual_tracking_threshold = count;
     }
 
+    if let Some(bucket_size) =
+        clap_utils::parse_optional(cli_args, "validator-monitor-bucket-size")?
+    {
+        if bucket_size < MIN_AGGREGATE_BUCKET_SIZE {
+            return Err(format!(
+                "--validator-monitor-bucket-size must be at least {}",
+                MIN_AGGREGATE_BUCKET_SIZE
+            ));
+        }
+        client_config.validator_monitor.aggregate_bucket_size = Some(bucket_size);
+    }
+
+    client_config.validator_monitor.client_fingerprinting =
+        cli_args.get_flag("validator-monitor-client-fingerprinting");
+
+    client_config.chain.shuffling_cache_warming =
+        !cli_args.get_flag("disable-shuffling-cache-warming");
+
+    client_config.chain.early_attester_cache_pending_blobs =
+        cli_args.get_flag("early-attester-cache-pending-blobs");
+
     if cli_args.get_flag("disable-proposer-reorgs") {
         client_config.chain.re_org_head_threshold = None;
         client_config.chain.re_org_parent_threshold = None;
@@ -797,6 +1036,22 @@ pub fn get_config<E: EthSpec>(
                 DisallowedReOrgOffsets::new::<E>(disallowed_offsets)
                     .map_err(|e| format!("invalid disallowed-offsets: {e:?}"))?;
         }
+
+        client_config.chain.re_org_fork_boundary_epochs =
+            clap_utils::parse_optional(cli_args, "proposer-reorg-fork-boundary-epochs")?;
+
+        if let Some(protected_proposers_str) =
+            clap_utils::parse_optional::<String>(cli_args, "proposer-reorg-protected-proposers")?
+        {
+            client_config.chain.re_org_protected_proposers = protected_proposers_str
+                .split(',')
+                .map(|s| {
+                    s.trim()
+                        .parse()
+                        .map_err(|e| format!("invalid protected-proposers: {e:?}"))
+                })
+                .collect::<Result<Vec<u64>, _>>()?;
+        }
     }
 
     // Note: This overrides any previous flags that enable this option.
@@ -813,6 +1068,10 @@ pub fn get_config<E: EthSpec>(
             });
 
     client_config.chain.always_prepare_payload = cli_args.get_flag("always-prepare-payload");
+    client_config.chain.suppress_payload_attributes =
+        cli_args.get_flag("suppress-payload-attributes");
+    client_config.chain.payload_attributes_window_epochs =
+        clap_utils::parse_optional(cli_args, "payload-attributes-window")?;
 
     if let Some(timeout) =
         clap_utils::parse_optional(cli_args, "fork-choice-before-proposal-timeout")?
@@ -820,6 +1079,10 @@ pub fn get_config<E: EthSpec>(
         client_config.chain.fork_choice_before_proposal_timeout_ms = timeout;
     }
 
+    if let Some(budget) = clap_utils::parse_optional(cli_args, "block-production-budgets")? {
+        client_config.chain.block_production_budget = budget;
+    }
+
     client_config.chain.always_reset_payload_statuses = cli_args.get_flag("reset-payload-statuses");
 
     client_config.chain.paranoid_block_proposal = cli_args.get_flag("paranoid-block-proposal");
@@ -861,6 +1124,14 @@ pub fn get_config<E: EthSpec>(
         client_config.network.invalid_block_storage = Some(path);
     }
 
+    client_config.network.gossip_trace_dir =
+        clap_utils::parse_optional(cli_args, "gossip-trace-dir")?;
+    client_config.network.gossip_trace_sample_rate =
+        clap_utils::parse_required(cli_args, "gossip-trace-sample-rate")?;
+    if !(0.0..=1.0).contains(&client_config.network.gossip_trace_sample_rate) {
+        return Err("--gossip-trace-sample-rate must be between 0 and 1".to_string());
+    }
+
     if let Some(max_workers) = clap_utils::parse_optional(cli_args, "beacon-processor-max-workers")?
     {
         client_config.beacon_processor.max_workers = max_workers;
@@ -870,6 +1141,13 @@ pub fn get_config<E: EthSpec>(
         return Err("--beacon-processor-max-workers must be a non-zero value".to_string());
     }
 
+    client_config.beacon_processor.max_gossip_workers =
+        clap_utils::parse_optional(cli_args, "beacon-processor-gossip-workers")?;
+
+    if client_config.beacon_processor.max_gossip_workers == Some(0) {
+        return Err("--beacon-processor-gossip-workers must be a non-zero value".to_string());
+    }
+
     client_config.beacon_processor.max_work_event_queue_len =
         clap_utils::parse_required(cli_args, "beacon-processor-work-queue-len")?;
     client_config.beacon_processor.max_scheduled_work_queue_len =
@@ -1132,6 +1410,35 @@ pub fn set_network_config(
         config.subscribe_all_subnets = true;
     }
 
+    if let Some(subnets) = cli_args.get_one::<String>("backbone-attestation-subnets") {
+        config.backbone_attestation_subnets = subnets
+            .split(',')
+            .map(|subnet| subnet.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid --backbone-attestation-subnets value: {:?}", e))?;
+    }
+
+    if let Some(subnets) = cli_args.get_one::<String>("backbone-sync-committee-subnets") {
+        config.backbone_sync_committee_subnets = subnets
+            .split(',')
+            .map(|subnet| subnet.parse::<u64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("Invalid --backbone-sync-committee-subnets value: {:?}", e))?;
+        if let Some(subnet) = config
+            .backbone_sync_committee_subnets
+            .iter()
+            .find(|subnet| **subnet >= SYNC_COMMITTEE_SUBNET_COUNT)
+        {
+            return Err(format!(
+                "Invalid --backbone-sync-committee-subnets value: {} is not below the sync \
+                 committee subnet count of {}",
+                subnet, SYNC_COMMITTEE_SUBNET_COUNT
+            ));
+        }
+    }
+
+    config.backbone_subnet_peers = parse_required(cli_args, "backbone-subnet-peers")?;
+
     if parse_flag(cli_args, "import-all-attestations") {
         config.import_all_attestations = true;
     }
@@ -1197,14 +1504,39 @@ pub fn set_network_config(
     }
 
     if let Some(trusted_peers_str) = cli_args.get_one::<String>("trusted-peers") {
-        config.trusted_peers = trusted_peers_str
-            .split(',')
-            .map(|peer_id| {
-                peer_id
+        let mut peer_ids: Vec<PeerIdSerialized> = vec![];
+        let mut enrs: Vec<Enr> = vec![];
+        let mut multiaddrs: Vec<Multiaddr> = vec![];
+        for peer in trusted_peers_str.split(',') {
+            if peer.starts_with("enr:") {
+                let enr: Enr = peer
                     .parse()
-                    .map_err(|_| format!("Invalid trusted peer id: {}", peer_id))
-            })
-            .collect::<Result<Vec<PeerIdSerialized>, _>>()?;
+                    .map_err(|_| format!("Invalid trusted peer ENR: {}", peer))?;
+                peer_ids.push(enr.peer_id().into());
+                enrs.push(enr);
+            } else if peer.starts_with('/') {
+                let multi: Multiaddr = peer
+                    .parse()
+                    .map_err(|_| format!("Invalid trusted peer Multiaddr: {}", peer))?;
+                let peer_id = multi
+                    .iter()
+                    .find_map(|proto| match proto {
+                        Protocol::P2p(peer_id) => Some(peer_id),
+                        _ => None,
+                    })
+                    .ok_or_else(|| format!("Missing P2P in trusted peer Multiaddr: {}", peer))?;
+                peer_ids.push(peer_id.into());
+                multiaddrs.push(multi);
+            } else {
+                peer_ids.push(
+                    peer.parse()
+                        .map_err(|_| format!("Invalid trusted peer id: {}", peer))?,
+                );
+            }
+        }
+        config.trusted_peers = peer_ids;
+        config.trusted_peers_enr = enrs;
+        config.trusted_peers_multiaddr = multiaddrs;
         if config.trusted_peers.len() >= config.target_peers {
             slog::warn!(log, "More trusted peers than the target peer limit. This will prevent efficient peer selection criteria."; "target_peers" => config.target_peers, "trusted_peers" => config.trusted_peers.len());
         }
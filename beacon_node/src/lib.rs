@@ -165,10 +165,23 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
         let discv5_executor = Discv5Executor(executor);
         client_config.network.discv5_config.executor = Some(Box::new(discv5_executor));
 
+        let builder = builder.build_beacon_chain()?;
+        // Import era files before starting the network, so that backfill sync starts from the
+        // oldest block imported.
+        let builder = if let Some(import_era_dir) = &client_config.import_era_dir {
+            builder.import_era_dir(import_era_dir).await?
+        } else {
+            builder
+        };
+        let builder = builder.network(Arc::new(client_config.network)).await?;
+
+        let builder = if let Some(failover_config) = &client_config.failover {
+            builder.failover_service(failover_config)?
+        } else {
+            builder
+        };
+
         builder
-            .build_beacon_chain()?
-            .network(Arc::new(client_config.network))
-            .await?
             .notifier()?
             .http_metrics_config(client_config.http_metrics.clone())
             .build()
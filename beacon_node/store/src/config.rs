@@ -5,6 +5,7 @@ use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::io::Write;
 use std::num::NonZeroUsize;
+use std::time::Duration;
 use superstruct::superstruct;
 use types::non_zero_usize::new_non_zero_usize;
 use types::EthSpec;
@@ -20,6 +21,7 @@ pub const DEFAULT_HDIFF_BUFFER_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(16)
 const EST_COMPRESSION_FACTOR: usize = 2;
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_ORPHAN_ARCHIVE_RETENTION_EPOCHS: u64 = 8192;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -49,6 +51,15 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Whether to archive blocks from abandoned forks rather than deleting them during pruning.
+    pub archive_orphaned_blocks: bool,
+    /// The number of epochs before the finalized epoch for which archived orphans are kept.
+    /// Older entries are deleted from the archive on finalization.
+    pub orphan_archive_retention_epochs: u64,
+    /// Whether to repair inconsistencies found by the consistency check on initialization.
+    pub repair_on_init: bool,
+    /// How often to estimate the size of each database column for metrics, if at all.
+    pub column_size_estimate_interval: Option<Duration>,
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -108,6 +119,10 @@ impl Default for StoreConfig {
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            archive_orphaned_blocks: false,
+            orphan_archive_retention_epochs: DEFAULT_ORPHAN_ARCHIVE_RETENTION_EPOCHS,
+            repair_on_init: false,
+            column_size_estimate_interval: None,
         }
     }
 }
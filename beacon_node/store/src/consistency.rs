@@ -0,0 +1,415 @@
+//! Consistency checks for the split, the anchor and recently frozen data.
+//!
+//! An interrupted migration or a partially restored database can leave the freezer inconsistent
+//! with the split and the anchor. Without these checks such inconsistencies only surface later as
+//! opaque `MissingState`/`MissingSnapshot` errors when a historic state is requested.
+//!
+//! The checks are limited to the `SLOTS_PER_HISTORICAL_ROOT` slots prior to the split, which is
+//! where an interrupted migration leaves gaps, and which can be repaired using the roots stored in
+//! the split state. Missing snapshots and diffs are repaired by replaying blocks on top of the
+//! preceding frozen state.
+use crate::hdiff::StorageStrategy;
+use crate::hot_cold_store::{no_state_root_iter, HotColdDB, HotColdDBError};
+use crate::impls::beacon_state::{get_full_state, store_full_state};
+use crate::metadata::{ANCHOR_UNINITIALIZED, STATE_UPPER_LIMIT_NO_RETAIN};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStore, KeyValueStoreOp};
+use slog::{crit, debug, info, warn};
+use std::cmp::max;
+use std::fmt;
+use types::{BeaconState, EthSpec, Hash256, Slot};
+
+/// An inconsistency between the split, the anchor and the contents of the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConsistencyIssue {
+    /// The oldest block is newer than the anchor, so the blocks between them are missing.
+    OldestBlockAfterAnchor {
+        oldest_block_slot: Slot,
+        anchor_slot: Slot,
+    },
+    /// The anchor is newer than the split, so the states between them are missing.
+    AnchorAfterSplit { anchor_slot: Slot, split_slot: Slot },
+    /// The full state at the split is missing from the hot database.
+    MissingSplitState { slot: Slot, state_root: Hash256 },
+    /// Frozen block roots are missing for the slots in `start_slot..end_slot`.
+    MissingFrozenBlockRoots { start_slot: Slot, end_slot: Slot },
+    /// Frozen state roots are missing for the slots in `start_slot..end_slot`.
+    MissingFrozenStateRoots { start_slot: Slot, end_slot: Slot },
+    /// The snapshot or diff for the frozen state at `slot` is missing.
+    MissingFrozenState { slot: Slot },
+}
+
+impl ConsistencyIssue {
+    /// Returns `true` if the issue can be repaired without external data.
+    pub fn is_repairable(&self) -> bool {
+        !matches!(
+            self,
+            ConsistencyIssue::OldestBlockAfterAnchor { .. }
+                | ConsistencyIssue::AnchorAfterSplit { .. }
+        )
+    }
+
+    /// Advice to the operator on how to resolve the issue.
+    pub fn advice(&self) -> &'static str {
+        match self {
+            ConsistencyIssue::OldestBlockAfterAnchor { .. }
+            | ConsistencyIssue::AnchorAfterSplit { .. } => {
+                "the database metadata is corrupt, re-sync the database using checkpoint sync"
+            }
+            _ => "restart with --repair-db-inconsistencies to repair the database by replaying blocks",
+        }
+    }
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConsistencyIssue::OldestBlockAfterAnchor {
+                oldest_block_slot,
+                anchor_slot,
+            } => write!(
+                f,
+                "oldest block slot {oldest_block_slot} is after the anchor slot {anchor_slot}"
+            ),
+            ConsistencyIssue::AnchorAfterSplit {
+                anchor_slot,
+                split_slot,
+            } => write!(
+                f,
+                "anchor slot {anchor_slot} is after the split slot {split_slot}"
+            ),
+            ConsistencyIssue::MissingSplitState { slot, state_root } => write!(
+                f,
+                "full state {state_root:?} at split slot {slot} is missing"
+            ),
+            ConsistencyIssue::MissingFrozenBlockRoots {
+                start_slot,
+                end_slot,
+            } => write!(
+                f,
+                "frozen block roots are missing for slots {start_slot}..{end_slot}"
+            ),
+            ConsistencyIssue::MissingFrozenStateRoots {
+                start_slot,
+                end_slot,
+            } => write!(
+                f,
+                "frozen state roots are missing for slots {start_slot}..{end_slot}"
+            ),
+            ConsistencyIssue::MissingFrozenState { slot } => {
+                write!(f, "frozen state at slot {slot} is missing")
+            }
+        }
+    }
+}
+
+/// The outcome of repairing a list of `ConsistencyIssue`s.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Issues which were repaired.
+    pub repaired: Vec<ConsistencyIssue>,
+    /// Issues which remain, because they can't be repaired or their repair failed.
+    pub unrepaired: Vec<ConsistencyIssue>,
+}
+
+impl ConsistencyReport {
+    /// Returns `true` if no issues remain.
+    pub fn is_consistent(&self) -> bool {
+        self.unrepaired.is_empty()
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Check the split, the anchor and recently frozen data for inconsistencies.
+    ///
+    /// Issues are returned in the order in which they should be repaired.
+    pub fn check_consistency(&self) -> Result<Vec<ConsistencyIssue>, Error> {
+        let split = self.get_split_info();
+        let anchor = self.get_anchor_info();
+        let mut issues = vec![];
+
+        // Nothing has been frozen yet.
+        if split.slot == 0 || anchor == ANCHOR_UNINITIALIZED {
+            return Ok(issues);
+        }
+
+        if anchor.oldest_block_slot > anchor.anchor_slot {
+            issues.push(ConsistencyIssue::OldestBlockAfterAnchor {
+                oldest_block_slot: anchor.oldest_block_slot,
+                anchor_slot: anchor.anchor_slot,
+            });
+        }
+        if anchor.anchor_slot > split.slot {
+            issues.push(ConsistencyIssue::AnchorAfterSplit {
+                anchor_slot: anchor.anchor_slot,
+                split_slot: split.slot,
+            });
+        }
+
+        if !self
+            .hot_db
+            .key_exists(DBColumn::BeaconState.into(), split.state_root.as_slice())?
+        {
+            issues.push(ConsistencyIssue::MissingSplitState {
+                slot: split.slot,
+                state_root: split.state_root,
+            });
+        }
+
+        let window_start = split
+            .slot
+            .saturating_sub(E::slots_per_historical_root() as u64);
+
+        let block_roots_start = max(anchor.oldest_block_slot, window_start);
+        for (start_slot, end_slot) in
+            self.missing_slot_ranges(DBColumn::BeaconBlockRoots, block_roots_start, split.slot)?
+        {
+            issues.push(ConsistencyIssue::MissingFrozenBlockRoots {
+                start_slot,
+                end_slot,
+            });
+        }
+
+        // States (and their roots) are not stored below the state upper limit.
+        if anchor.state_upper_limit == STATE_UPPER_LIMIT_NO_RETAIN {
+            return Ok(issues);
+        }
+        let states_start = max(anchor.state_upper_limit, window_start);
+
+        for (start_slot, end_slot) in
+            self.missing_slot_ranges(DBColumn::BeaconStateRoots, states_start, split.slot)?
+        {
+            issues.push(ConsistencyIssue::MissingFrozenStateRoots {
+                start_slot,
+                end_slot,
+            });
+        }
+
+        for slot in (states_start.as_u64()..split.slot.as_u64()).map(Slot::new) {
+            let column = match self.hierarchy.storage_strategy(slot)? {
+                StorageStrategy::Snapshot => DBColumn::BeaconStateSnapshot,
+                StorageStrategy::DiffFrom(_) => DBColumn::BeaconStateDiff,
+                StorageStrategy::ReplayFrom(_) => continue,
+            };
+            if !self
+                .cold_db
+                .key_exists(column.into(), &slot.as_u64().to_be_bytes())?
+            {
+                issues.push(ConsistencyIssue::MissingFrozenState { slot });
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Attempt to repair each of the `issues`, in order.
+    ///
+    /// A failed repair is logged and reported as unrepaired, rather than returned as an error, so
+    /// that the remaining issues are still attempted.
+    pub fn repair_consistency(&self, issues: Vec<ConsistencyIssue>) -> ConsistencyReport {
+        let mut report = ConsistencyReport::default();
+
+        for issue in issues {
+            if !issue.is_repairable() {
+                report.unrepaired.push(issue);
+                continue;
+            }
+
+            match self.repair_issue(&issue) {
+                Ok(()) => {
+                    info!(
+                        self.log,
+                        "Repaired database inconsistency";
+                        "issue" => %issue,
+                    );
+                    report.repaired.push(issue);
+                }
+                Err(e) => {
+                    warn!(
+                        self.log,
+                        "Failed to repair database inconsistency";
+                        "issue" => %issue,
+                        "error" => ?e,
+                    );
+                    report.unrepaired.push(issue);
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Run the consistency check on start-up, repairing issues if configured to, and log a report.
+    pub(crate) fn check_consistency_on_init(&self) -> Result<(), Error> {
+        let issues = self.check_consistency()?;
+
+        if issues.is_empty() {
+            debug!(self.log, "Database consistency check passed");
+            return Ok(());
+        }
+
+        let report = if self.config.repair_on_init {
+            info!(
+                self.log,
+                "Repairing database inconsistencies";
+                "count" => issues.len(),
+            );
+            self.repair_consistency(issues)
+        } else {
+            ConsistencyReport {
+                repaired: vec![],
+                unrepaired: issues,
+            }
+        };
+
+        for issue in &report.unrepaired {
+            crit!(
+                self.log,
+                "Database inconsistency detected";
+                "issue" => %issue,
+                "advice" => issue.advice(),
+            );
+        }
+        if !report.repaired.is_empty() {
+            info!(
+                self.log,
+                "Database repair complete";
+                "repaired" => report.repaired.len(),
+                "unrepaired" => report.unrepaired.len(),
+            );
+        }
+
+        Ok(())
+    }
+
+    fn repair_issue(&self, issue: &ConsistencyIssue) -> Result<(), Error> {
+        match *issue {
+            ConsistencyIssue::MissingSplitState { .. } => self.repair_split_state(),
+            ConsistencyIssue::MissingFrozenBlockRoots {
+                start_slot,
+                end_slot,
+            } => {
+                let split_state = self.load_split_state()?;
+                let mut ops = vec![];
+                for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+                    let block_root = split_state.get_block_root(slot)?;
+                    ops.push(KeyValueStoreOp::PutKeyValue(
+                        get_key_for_col(
+                            DBColumn::BeaconBlockRoots.into(),
+                            &slot.as_u64().to_be_bytes(),
+                        ),
+                        block_root.as_slice().to_vec(),
+                    ));
+                }
+                self.cold_db.do_atomically(ops)
+            }
+            ConsistencyIssue::MissingFrozenStateRoots {
+                start_slot,
+                end_slot,
+            } => {
+                let split_state = self.load_split_state()?;
+                let mut ops = vec![];
+                for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+                    let state_root = split_state.get_state_root(slot)?;
+                    self.store_cold_state_summary(state_root, slot, &mut ops)?;
+                }
+                self.cold_db.do_atomically(ops)
+            }
+            ConsistencyIssue::MissingFrozenState { slot } => self.repair_frozen_state(slot),
+            ConsistencyIssue::OldestBlockAfterAnchor { .. }
+            | ConsistencyIssue::AnchorAfterSplit { .. } => Err(Error::DBError {
+                message: format!("unrepairable database inconsistency: {issue}"),
+            }),
+        }
+    }
+
+    /// Reconstruct the full split state by replaying the split block(s) on the frozen state at
+    /// the slot prior to the split.
+    fn repair_split_state(&self) -> Result<(), Error> {
+        let split = self.get_split_info();
+        let base_slot = split.slot - 1;
+
+        let base_state = self.load_cold_state_by_slot(base_slot)?;
+        let blocks = self.load_blocks_to_replay(base_slot, split.slot, split.block_root)?;
+        let mut state =
+            self.replay_blocks(base_state, blocks, split.slot, no_state_root_iter(), None)?;
+
+        let state_root = state.update_tree_hash_cache()?;
+        if state_root != split.state_root {
+            return Err(Error::StateReconstructionRootMismatch {
+                slot: split.slot,
+                expected: split.state_root,
+                computed: state_root,
+            });
+        }
+
+        let mut ops = vec![];
+        store_full_state(&split.state_root, &state, &mut ops)?;
+        self.hot_db.do_atomically(ops)
+    }
+
+    /// Reconstruct the frozen state at `slot` by replaying the block at `slot` on the frozen
+    /// state at the slot prior, and store it as a snapshot or diff.
+    fn repair_frozen_state(&self, slot: Slot) -> Result<(), Error> {
+        let expected_state_root = self
+            .cold_db
+            .get_bytes(
+                DBColumn::BeaconStateRoots.into(),
+                &slot.as_u64().to_be_bytes(),
+            )?
+            .map(|bytes| Hash256::from_slice(&bytes))
+            .ok_or(HotColdDBError::MissingFrozenStateRoot(slot))?;
+
+        let base_state = self.load_cold_state_by_slot(slot - 1)?;
+        let mut state = self.load_cold_state_by_slot_using_replay(base_state, slot)?;
+
+        let state_root = state.update_tree_hash_cache()?;
+        if state_root != expected_state_root {
+            return Err(Error::StateReconstructionRootMismatch {
+                slot,
+                expected: expected_state_root,
+                computed: state_root,
+            });
+        }
+
+        let mut ops = vec![];
+        self.store_cold_state(&state_root, &state, &mut ops)?;
+        self.cold_db.do_atomically(ops)
+    }
+
+    /// Load the full split state, which holds the roots of the slots prior to the split.
+    fn load_split_state(&self) -> Result<BeaconState<E>, Error> {
+        let split = self.get_split_info();
+        get_full_state(&self.hot_db, &split.state_root, &self.spec)?
+            .ok_or_else(|| HotColdDBError::MissingSplitState(split.state_root, split.slot).into())
+    }
+
+    /// Return the ranges of slots in `start_slot..end_slot` which are missing from `column`.
+    fn missing_slot_ranges(
+        &self,
+        column: DBColumn,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<(Slot, Slot)>, Error> {
+        let mut ranges: Vec<(Slot, Slot)> = vec![];
+
+        for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+            if self
+                .cold_db
+                .key_exists(column.into(), &slot.as_u64().to_be_bytes())?
+            {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some((_, end)) if *end == slot => *end = slot + 1,
+                _ => ranges.push((slot, slot + 1)),
+            }
+        }
+
+        Ok(ranges)
+    }
+}
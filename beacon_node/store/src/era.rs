@@ -0,0 +1,720 @@
+//! Export and import of finalized history in the era file format.
+//!
+//! An era file contains the canonical blocks of one era (`SLOTS_PER_HISTORICAL_ROOT` slots) and
+//! the state at the end of that era, so that history can be archived and exchanged with other
+//! clients without the networking stack. Era `N` contains the blocks in the slots
+//! `[(N - 1) * SLOTS_PER_HISTORICAL_ROOT, N * SLOTS_PER_HISTORICAL_ROOT)` and the state at slot
+//! `N * SLOTS_PER_HISTORICAL_ROOT`. Era 0 only contains the genesis state.
+//!
+//! Files are named `<config-name>-<era-number>-<short-era-root>.era`, where the era root is the
+//! historical root (or historical summary root) committing to the blocks of the era.
+//!
+//! Era files are a sequence of e2store entries, each consisting of an 8-byte header (a 2-byte
+//! type, a 4-byte little-endian length and 2 reserved bytes) followed by the entry data:
+//!
+//! ```text
+//! Version | block* | state | block-index? | state-index
+//! ```
+//!
+//! Blocks and states are SSZ encoded and snappy framed. Each index records the starting slot, the
+//! offset of each slot's entry relative to the start of the index (0 for empty slots) and the
+//! number of slots, all as little-endian 64-bit integers.
+//!
+//! The execution-layer history format (era1) is not produced.
+//!
+//! Imported eras are verified against the historical roots (or historical summaries) of the split
+//! state, so that a checkpoint synced node can fill in its history from era files supplied by an
+//! untrusted source.
+//!
+//! Besides the blocks, the state at the end of each imported era is kept in the
+//! `BeaconEraState` column, along with the state roots of the era, so that historic states which
+//! are not stored in the freezer DB can be served by replaying blocks from the preceding era state.
+use crate::hot_cold_store::{HotColdDB, HotColdDBError};
+use crate::state_load_plan::{self, StateLoadPlan, StateLoadSource};
+use crate::{get_key_for_col, AnchorInfo, DBColumn, Error, ItemStore, KeyValueStoreOp};
+use slog::{debug, warn};
+use ssz::Encode;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::{BeaconState, ChainSpec, EthSpec, Hash256, HistoricalSummary, SignedBeaconBlock, Slot};
+
+pub const VERSION: [u8; 2] = [0x65, 0x32];
+pub const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+pub const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+pub const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+/// The length of an e2store entry header.
+const HEADER_LENGTH: usize = 8;
+
+#[derive(Debug)]
+pub enum EraError {
+    Io(std::io::Error),
+    /// The era ends after the finalized history in the freezer database.
+    NotFinalized {
+        era: u64,
+        split_slot: Slot,
+    },
+    MissingVersion,
+    MissingState,
+    MissingEraRoot(u64),
+    /// The era state is not at the end of an era.
+    UnalignedState(Slot),
+    /// The block and state roots of the era state don't match the root recorded by the split state.
+    EraRootMismatch {
+        era: u64,
+        era_root: Hash256,
+        expected_era_root: Hash256,
+    },
+    MissingGenesisBlock,
+    TruncatedEntry {
+        offset: usize,
+    },
+    EntryTooLong(usize),
+    /// A block doesn't match the block roots of the era state.
+    UnexpectedBlock {
+        slot: Slot,
+        block_root: Hash256,
+    },
+    /// The blocks of the era don't descend to the oldest block in the database. Era files must be
+    /// imported from newest to oldest.
+    DisjointHistory {
+        block_root: Hash256,
+        expected_block_root: Hash256,
+    },
+    /// The state at `slot` lies between the historic states stored in the freezer DB, and the era
+    /// state it would be replayed from has not been imported.
+    MissingEraState {
+        slot: Slot,
+    },
+}
+
+/// The outcome of importing a directory of era files.
+#[derive(Debug, Default)]
+pub struct EraImportSummary {
+    /// The number of blocks imported.
+    pub blocks: usize,
+    /// The number of era states imported.
+    pub states: usize,
+    /// Era files which could not be imported, with the reason.
+    pub skipped: Vec<(PathBuf, Error)>,
+}
+
+impl From<EraError> for Error {
+    fn from(e: EraError) -> Self {
+        Error::Era(e)
+    }
+}
+
+/// The decoded contents of an era file.
+pub struct Era<E: EthSpec> {
+    /// The blocks of the era, in ascending slot order.
+    pub blocks: Vec<SignedBeaconBlock<E>>,
+    /// The state at the end of the era.
+    pub state: BeaconState<E>,
+}
+
+impl<E: EthSpec> Era<E> {
+    pub fn from_bytes(bytes: &[u8], spec: &ChainSpec) -> Result<Self, Error> {
+        let mut entries = read_entries(bytes)?.into_iter();
+        if entries.next().map(|(entry_type, _)| entry_type) != Some(VERSION) {
+            return Err(EraError::MissingVersion.into());
+        }
+
+        let mut blocks = vec![];
+        let mut state = None;
+        for (entry_type, data) in entries {
+            match entry_type {
+                COMPRESSED_SIGNED_BEACON_BLOCK => {
+                    let bytes = decompress(data)?;
+                    blocks.push(SignedBeaconBlock::from_ssz_bytes(&bytes, spec)?);
+                }
+                COMPRESSED_BEACON_STATE => {
+                    let bytes = decompress(data)?;
+                    state = Some(BeaconState::from_ssz_bytes(&bytes, spec)?);
+                }
+                // The indices are only required for random access.
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            blocks,
+            state: state.ok_or(EraError::MissingState)?,
+        })
+    }
+}
+
+/// Builds an e2store file in memory.
+#[derive(Default)]
+struct E2StoreWriter {
+    bytes: Vec<u8>,
+}
+
+impl E2StoreWriter {
+    /// Appends an entry, returning its offset in the file.
+    fn write_entry(&mut self, entry_type: [u8; 2], data: &[u8]) -> Result<usize, EraError> {
+        let offset = self.bytes.len();
+        let length = u32::try_from(data.len()).map_err(|_| EraError::EntryTooLong(data.len()))?;
+        self.bytes.extend_from_slice(&entry_type);
+        self.bytes.extend_from_slice(&length.to_le_bytes());
+        self.bytes.extend_from_slice(&[0, 0]);
+        self.bytes.extend_from_slice(data);
+        Ok(offset)
+    }
+
+    /// Appends a slot index for the entries at `offsets`, one per slot from `starting_slot`.
+    fn write_slot_index(
+        &mut self,
+        starting_slot: Slot,
+        offsets: &[Option<usize>],
+    ) -> Result<(), EraError> {
+        let index_offset = self.bytes.len() as i64;
+        let mut data = Vec::with_capacity((offsets.len() + 2) * 8);
+        data.extend_from_slice(&(starting_slot.as_u64() as i64).to_le_bytes());
+        for offset in offsets {
+            let relative_offset = offset.map_or(0, |offset| offset as i64 - index_offset);
+            data.extend_from_slice(&relative_offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(offsets.len() as i64).to_le_bytes());
+        self.write_entry(SLOT_INDEX, &data)?;
+        Ok(())
+    }
+}
+
+/// Splits an e2store file into `(entry_type, data)` pairs.
+fn read_entries(bytes: &[u8]) -> Result<Vec<([u8; 2], &[u8])>, EraError> {
+    let mut entries = vec![];
+    let mut offset = 0;
+    while offset < bytes.len() {
+        let header = bytes
+            .get(offset..offset + HEADER_LENGTH)
+            .ok_or(EraError::TruncatedEntry { offset })?;
+        let entry_type = [header[0], header[1]];
+        let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+        let data_offset = offset + HEADER_LENGTH;
+        let data = bytes
+            .get(data_offset..data_offset + length)
+            .ok_or(EraError::TruncatedEntry { offset })?;
+        entries.push((entry_type, data));
+        offset = data_offset + length;
+    }
+    Ok(entries)
+}
+
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut encoder = snap::write::FrameEncoder::new(vec![]);
+    encoder.write_all(bytes).map_err(EraError::Io)?;
+    encoder
+        .into_inner()
+        .map_err(|e| EraError::Io(std::io::Error::new(e.error().kind(), e.error().to_string())))
+}
+
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut decompressed = vec![];
+    snap::read::FrameDecoder::new(bytes)
+        .read_to_end(&mut decompressed)
+        .map_err(EraError::Io)?;
+    Ok(decompressed)
+}
+
+/// Returns the slot of the era state that the state at `slot` can be replayed from.
+fn era_start_slot<E: EthSpec>(slot: Slot) -> Slot {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    Slot::new(slot.as_u64() / slots_per_era * slots_per_era)
+}
+
+/// Returns the root committing to the blocks of `era`, from a state at or after its end.
+fn era_root<E: EthSpec>(state: &BeaconState<E>, era: u64) -> Result<Hash256, Error> {
+    let Some(index) = era.checked_sub(1) else {
+        return Ok(state.genesis_validators_root());
+    };
+    let historical_roots = state.historical_roots();
+    if let Some(root) = historical_roots.get(index as usize) {
+        return Ok(*root);
+    }
+    state
+        .historical_summaries()
+        .ok()
+        .and_then(|summaries| summaries.get(index as usize - historical_roots.len()))
+        .map(|summary| summary.tree_hash_root())
+        .ok_or_else(|| EraError::MissingEraRoot(era).into())
+}
+
+/// Checks that the block and state roots of `era_state` are those committed to by `trusted_state`.
+///
+/// Eras which end after `trusted_state` aren't committed to by a historical root yet, so the block
+/// roots they share with `trusted_state` are compared instead.
+pub fn verify_era_state<E: EthSpec>(
+    era_state: &mut BeaconState<E>,
+    trusted_state: &BeaconState<E>,
+) -> Result<(), Error> {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let end_slot = era_state.slot();
+    if end_slot % slots_per_era != 0 {
+        return Err(EraError::UnalignedState(end_slot).into());
+    }
+    let era = end_slot.as_u64() / slots_per_era;
+
+    if end_slot > trusted_state.slot() {
+        let start_slot = end_slot.saturating_sub(slots_per_era);
+        for slot in (start_slot.as_u64()..trusted_state.slot().as_u64()).map(Slot::new) {
+            let block_root = *era_state.get_block_root(slot)?;
+            if block_root != *trusted_state.get_block_root(slot)? {
+                return Err(EraError::UnexpectedBlock { slot, block_root }.into());
+            }
+        }
+        return Ok(());
+    }
+
+    let expected_era_root = era_root(trusted_state, era)?;
+    let era_root = match era.checked_sub(1) {
+        None => era_state.genesis_validators_root(),
+        Some(index) if (index as usize) < trusted_state.historical_roots().len() => {
+            era_state.historical_batch()?.tree_hash_root()
+        }
+        Some(_) => HistoricalSummary::new(era_state).tree_hash_root(),
+    };
+    if era_root != expected_era_root {
+        return Err(EraError::EraRootMismatch {
+            era,
+            era_root,
+            expected_era_root,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Writes the era file for `era` to `output_dir`, returning its path.
+    ///
+    /// The era must be finalized, and its blocks (including execution payloads) and the state at
+    /// its end must be available in the database.
+    pub fn export_era(&self, era: u64, output_dir: &Path) -> Result<PathBuf, Error> {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let end_slot = Slot::new(era * slots_per_era);
+        let split_slot = self.get_split_slot();
+        if end_slot >= split_slot {
+            return Err(EraError::NotFinalized { era, split_slot }.into());
+        }
+
+        let state = self.load_cold_state_by_slot(end_slot)?;
+
+        let mut writer = E2StoreWriter::default();
+        writer.write_entry(VERSION, &[])?;
+
+        let mut block_offsets = vec![];
+        if era > 0 {
+            let start_slot = end_slot - slots_per_era;
+            for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+                let block_root = *state.get_block_root(slot)?;
+                let blinded_block = self
+                    .get_blinded_block(&block_root)?
+                    .ok_or(Error::BlockNotFound(block_root))?;
+                // Skipped slots repeat the previous block root. The genesis block is not included
+                // as it is not signed.
+                if blinded_block.slot() != slot || slot == 0 {
+                    block_offsets.push(None);
+                    continue;
+                }
+                let block = self.make_full_block(&block_root, blinded_block)?;
+                let offset = writer.write_entry(
+                    COMPRESSED_SIGNED_BEACON_BLOCK,
+                    &compress(&block.as_ssz_bytes())?,
+                )?;
+                block_offsets.push(Some(offset));
+            }
+        }
+
+        let state_offset =
+            writer.write_entry(COMPRESSED_BEACON_STATE, &compress(&state.as_ssz_bytes())?)?;
+        if era > 0 {
+            writer.write_slot_index(end_slot - slots_per_era, &block_offsets)?;
+        }
+        writer.write_slot_index(end_slot, &[Some(state_offset)])?;
+
+        let short_era_root = era_root(&state, era)?.as_slice()[..4]
+            .iter()
+            .map(|byte| format!("{byte:02x}"))
+            .collect::<String>();
+        let file_name = format!(
+            "{}-{:05}-{}.era",
+            self.spec.config_name.as_deref().unwrap_or("unknown"),
+            era,
+            short_era_root
+        );
+        let path = output_dir.join(file_name);
+        // Write to a temporary file first, so that an interrupted export doesn't leave a truncated
+        // era file behind.
+        let tmp_path = path.with_extension("era.tmp");
+        std::fs::write(&tmp_path, writer.bytes).map_err(EraError::Io)?;
+        std::fs::rename(&tmp_path, &path).map_err(EraError::Io)?;
+
+        debug!(
+            self.log,
+            "Exported era";
+            "era" => era,
+            "blocks" => block_offsets.iter().flatten().count(),
+            "path" => %path.display(),
+        );
+
+        Ok(path)
+    }
+
+    /// Backfills the blocks of the era file at `path` which are older than the oldest block in the
+    /// database, returning the number of blocks imported.
+    ///
+    /// The era state is kept if it is not already stored in the freezer DB, see
+    /// `Self::load_era_state_by_slot`.
+    ///
+    /// The era state is verified against the split state, each block is checked against the block
+    /// roots of the era state, and the imported blocks must descend to the oldest block in the
+    /// database, so era files must be imported from newest to oldest. As blocks are linked by their
+    /// roots, proposer signatures are not verified.
+    ///
+    /// Blocks are stored with their execution payloads, so that they can be served in full until
+    /// payloads are pruned.
+    pub fn import_era(&self, path: &Path) -> Result<usize, Error> {
+        let trusted_state = self.load_split_state()?;
+        let (num_blocks, _) = self.import_era_with_trusted_state(path, &trusted_state)?;
+        Ok(num_blocks)
+    }
+
+    /// Loads the split state, which era files are verified against.
+    fn load_split_state(&self) -> Result<BeaconState<E>, Error> {
+        let split = self.get_split_info();
+        self.get_state(&split.state_root, Some(split.slot))?
+            .ok_or_else(|| HotColdDBError::MissingSplitState(split.state_root, split.slot).into())
+    }
+
+    /// Imports the era file at `path`, verifying it against `trusted_state`.
+    ///
+    /// Returns the number of blocks imported, and whether the era state was imported.
+    fn import_era_with_trusted_state(
+        &self,
+        path: &Path,
+        trusted_state: &BeaconState<E>,
+    ) -> Result<(usize, bool), Error> {
+        let bytes = std::fs::read(path).map_err(EraError::Io)?;
+        let mut era = Era::<E>::from_bytes(&bytes, &self.spec)?;
+        verify_era_state(&mut era.state, trusted_state)?;
+
+        let block_roots = era
+            .blocks
+            .iter()
+            .map(|block| {
+                let block_root = block.canonical_root();
+                if *era.state.get_block_root(block.slot())? != block_root {
+                    return Err(EraError::UnexpectedBlock {
+                        slot: block.slot(),
+                        block_root,
+                    }
+                    .into());
+                }
+                Ok(block_root)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let anchor_info = self.get_anchor_info();
+        let genesis_block_root = self
+            .get_blinded_block(&Hash256::zero())?
+            .ok_or(EraError::MissingGenesisBlock)?
+            .canonical_root();
+
+        let mut expected_block_root = anchor_info.oldest_block_parent;
+        let mut prev_block_slot = anchor_info.oldest_block_slot;
+        let mut hot_batch = vec![];
+        let mut cold_batch = vec![];
+        let mut num_imported = 0;
+
+        for (block, block_root) in era.blocks.into_iter().zip(block_roots).rev() {
+            let block_slot = block.slot();
+            if block_slot >= prev_block_slot {
+                continue;
+            }
+            if block_root != expected_block_root {
+                return Err(EraError::DisjointHistory {
+                    block_root,
+                    expected_block_root,
+                }
+                .into());
+            }
+            let parent_root = block.message().parent_root();
+
+            self.block_as_kv_store_ops(&block_root, block, &mut hot_batch)?;
+            // Store block roots, including at all skip slots in the freezer DB.
+            for slot in (block_slot.as_u64()..prev_block_slot.as_u64()).rev() {
+                cold_batch.push(KeyValueStoreOp::PutKeyValue(
+                    get_key_for_col(DBColumn::BeaconBlockRoots.into(), &slot.to_be_bytes()),
+                    block_root.as_slice().to_vec(),
+                ));
+            }
+
+            prev_block_slot = block_slot;
+            expected_block_root = parent_root;
+            num_imported += 1;
+
+            // Once genesis is reached, fill in the genesis block root and mark history complete.
+            if expected_block_root == genesis_block_root {
+                for slot in 0..prev_block_slot.as_u64() {
+                    cold_batch.push(KeyValueStoreOp::PutKeyValue(
+                        get_key_for_col(DBColumn::BeaconBlockRoots.into(), &slot.to_be_bytes()),
+                        genesis_block_root.as_slice().to_vec(),
+                    ));
+                }
+                prev_block_slot = Slot::new(0);
+                expected_block_root = Hash256::zero();
+                break;
+            }
+        }
+
+        let state_imported = self.era_state_as_kv_store_ops(&mut era.state, &mut cold_batch)?;
+
+        if num_imported == 0 && cold_batch.is_empty() {
+            return Ok((0, false));
+        }
+
+        // Write the blocks before their roots, as for backfill sync.
+        self.hot_db.do_atomically(hot_batch)?;
+        self.cold_db.do_atomically(cold_batch)?;
+
+        if num_imported > 0 {
+            let new_anchor = AnchorInfo {
+                oldest_block_slot: prev_block_slot,
+                oldest_block_parent: expected_block_root,
+                ..anchor_info.clone()
+            };
+            self.compare_and_set_anchor_info_with_write(anchor_info, new_anchor)?;
+        }
+
+        debug!(
+            self.log,
+            "Imported era";
+            "blocks" => num_imported,
+            "state" => state_imported,
+            "oldest_block_slot" => self.get_oldest_block_slot(),
+            "path" => %path.display(),
+        );
+
+        Ok((num_imported, state_imported))
+    }
+
+    /// Adds the state roots of the era which the freezer DB doesn't store to `ops`, along with the
+    /// era state itself if it is one of them. Returns `true` if the era state was added.
+    ///
+    /// Only states prior to the split are kept, the split state and later states are in the hot DB.
+    fn era_state_as_kv_store_ops(
+        &self,
+        era_state: &mut BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<bool, Error> {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let end_slot = era_state.slot();
+        let split_slot = self.get_split_slot();
+        let anchor = self.get_anchor_info();
+        let is_missing_state = |slot: Slot| {
+            slot > anchor.state_lower_limit && slot < anchor.state_upper_limit && slot < split_slot
+        };
+
+        for slot in (end_slot.saturating_sub(slots_per_era).as_u64()..end_slot.as_u64())
+            .map(Slot::new)
+            .filter(|slot| is_missing_state(*slot))
+        {
+            self.store_cold_state_summary(era_state.get_state_root(slot)?, slot, ops)?;
+        }
+
+        if !is_missing_state(end_slot) || self.era_state_exists(end_slot)? {
+            return Ok(false);
+        }
+        let state_root = era_state.canonical_root()?;
+        self.store_cold_state_summary(&state_root, end_slot, ops)?;
+        ops.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(
+                DBColumn::BeaconEraState.into(),
+                &end_slot.as_u64().to_be_bytes(),
+            ),
+            compress(&era_state.as_ssz_bytes())?,
+        ));
+        Ok(true)
+    }
+
+    /// Returns `true` if the state at the end of the era ending at `end_slot` has been imported.
+    fn era_state_exists(&self, end_slot: Slot) -> Result<bool, Error> {
+        self.cold_db.key_exists(
+            DBColumn::BeaconEraState.into(),
+            &end_slot.as_u64().to_be_bytes(),
+        )
+    }
+
+    fn state_root_exists(&self, slot: Slot) -> Result<bool, Error> {
+        self.cold_db.key_exists(
+            DBColumn::BeaconStateRoots.into(),
+            &slot.as_u64().to_be_bytes(),
+        )
+    }
+
+    /// Returns the exclusive upper bound of the state roots available from `start_slot`, for a
+    /// `start_slot` that lies between the historic states stored in the freezer DB.
+    ///
+    /// State roots are available for the consecutive eras imported from era files. If they reach
+    /// the states stored in the freezer DB, the state roots continue up to the split.
+    pub(crate) fn era_state_roots_upper_bound(
+        &self,
+        start_slot: Slot,
+    ) -> Result<Option<Slot>, Error> {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let state_upper_limit = self.get_anchor_info().state_upper_limit;
+
+        // The state roots of an era are imported together, so it's enough to check the last slot
+        // of each era.
+        let mut slot = start_slot;
+        while slot < state_upper_limit {
+            let last_slot_of_era = slot - slot % slots_per_era + slots_per_era - 1;
+            if !self.state_root_exists(std::cmp::min(last_slot_of_era, state_upper_limit - 1))? {
+                // The root of the state at the end of the last imported era is stored with it.
+                if slot > start_slot && self.state_root_exists(slot)? {
+                    return Ok(Some(slot + 1));
+                }
+                return Ok((slot > start_slot).then_some(slot));
+            }
+            slot = last_slot_of_era + 1;
+        }
+        Ok(Some(self.get_split_slot()))
+    }
+
+    /// Plans the load of a state which lies between the historic states stored in the freezer DB,
+    /// from the imported era state at or before `slot`.
+    pub(crate) fn plan_era_state_load(&self, slot: Slot) -> Result<StateLoadPlan, Error> {
+        // The state roots of an era are only stored if the era was imported, and replay requires
+        // the blocks of the era and the state at the end of the previous era, unless that state is
+        // stored in the freezer DB.
+        let base_slot = era_start_slot::<E>(slot);
+        if !self.state_root_exists(slot)?
+            || self.get_oldest_block_slot() > base_slot
+            || (base_slot > self.get_anchor_info().state_lower_limit
+                && !self.era_state_exists(base_slot)?)
+        {
+            return Err(EraError::MissingEraState { slot }.into());
+        }
+        Ok(StateLoadPlan::new(
+            StateLoadSource::EraState,
+            slot,
+            base_slot,
+            0,
+            state_load_plan::cost::SNAPSHOT_LOAD,
+        ))
+    }
+
+    /// Loads a state which lies between the historic states stored in the freezer DB, by
+    /// replaying blocks from the imported era state at or before `slot`.
+    pub(crate) fn load_era_state_by_slot(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
+        let base_slot = era_start_slot::<E>(slot);
+        let base_state = if base_slot <= self.get_anchor_info().state_lower_limit {
+            self.load_cold_state_by_slot(base_slot)?
+        } else {
+            let bytes = self
+                .cold_db
+                .get_bytes(
+                    DBColumn::BeaconEraState.into(),
+                    &base_slot.as_u64().to_be_bytes(),
+                )?
+                .ok_or(EraError::MissingEraState { slot })?;
+            BeaconState::from_ssz_bytes(&decompress(&bytes)?, &self.spec)?
+        };
+        self.load_cold_state_by_slot_using_replay(base_state, slot)
+    }
+
+    /// Imports the era files in `dir` from newest to oldest.
+    ///
+    /// A file which can't be imported is skipped and reported in the returned summary, so that the
+    /// remaining files are still imported. Note that a skipped file leaves a gap in the blocks,
+    /// which causes older files to be skipped too.
+    pub fn import_era_dir(&self, dir: &Path) -> Result<EraImportSummary, Error> {
+        let mut paths = std::fs::read_dir(dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.path()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(EraError::Io)?;
+        paths.retain(|path| path.extension().is_some_and(|extension| extension == "era"));
+        // Era numbers are zero-padded, so sorting by name sorts by era.
+        paths.sort_unstable_by(|a, b| b.cmp(a));
+
+        // The split state may be large, so it's loaded once for all of the files.
+        let trusted_state = self.load_split_state()?;
+        let mut summary = EraImportSummary::default();
+        for path in paths {
+            match self.import_era_with_trusted_state(&path, &trusted_state) {
+                Ok((num_blocks, state_imported)) => {
+                    summary.blocks += num_blocks;
+                    summary.states += usize::from(state_imported);
+                }
+                Err(e) => {
+                    warn!(
+                        self.log,
+                        "Skipping era file";
+                        "error" => ?e,
+                        "path" => %path.display(),
+                    );
+                    summary.skipped.push((path, e));
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip() {
+        let mut writer = E2StoreWriter::default();
+        writer.write_entry(VERSION, &[]).unwrap();
+        let block_offset = writer
+            .write_entry(
+                COMPRESSED_SIGNED_BEACON_BLOCK,
+                &compress(&[1, 2, 3]).unwrap(),
+            )
+            .unwrap();
+        writer
+            .write_slot_index(Slot::new(64), &[None, Some(block_offset)])
+            .unwrap();
+
+        let entries = read_entries(&writer.bytes).unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0], (VERSION, &[][..]));
+        assert_eq!(decompress(entries[1].1).unwrap(), vec![1, 2, 3]);
+
+        let (entry_type, index) = entries[2];
+        assert_eq!(entry_type, SLOT_INDEX);
+        let values = index
+            .chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let index_offset = (writer.bytes.len() - HEADER_LENGTH - index.len()) as i64;
+        assert_eq!(values, vec![64, 0, block_offset as i64 - index_offset, 2]);
+    }
+
+    #[test]
+    fn truncated_entry() {
+        let mut writer = E2StoreWriter::default();
+        writer.write_entry(VERSION, &[]).unwrap();
+        writer
+            .write_entry(COMPRESSED_BEACON_STATE, &[0; 16])
+            .unwrap();
+        let bytes = &writer.bytes[..writer.bytes.len() - 1];
+        assert!(matches!(
+            read_entries(bytes),
+            Err(EraError::TruncatedEntry { offset: 8 })
+        ));
+    }
+}
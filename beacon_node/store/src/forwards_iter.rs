@@ -75,7 +75,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         if column == DBColumn::BeaconBlockRoots {
             Ok(self.freezer_upper_bound_for_block_roots(start_slot))
         } else if column == DBColumn::BeaconStateRoots {
-            Ok(self.freezer_upper_bound_for_state_roots(start_slot))
+            self.freezer_upper_bound_for_state_roots(start_slot)
         } else {
             Err(Error::ForwardsIterInvalidColumn(column))
         }
@@ -97,21 +97,21 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
-    fn freezer_upper_bound_for_state_roots(&self, start_slot: Slot) -> Option<Slot> {
+    fn freezer_upper_bound_for_state_roots(&self, start_slot: Slot) -> Result<Option<Slot>> {
         let split_slot = self.get_split_slot();
         let anchor = self.get_anchor_info();
 
         if start_slot >= anchor.state_upper_limit {
             // Starting slot is after the upper limit, so the split is the upper limit.
             // The split state's root is not available in the freezer so this is exclusive.
-            Some(split_slot)
+            Ok(Some(split_slot))
         } else if start_slot <= anchor.state_lower_limit {
             // Starting slot is prior to lower limit, so that's the upper limit. We can't
             // iterate past the lower limit into the gap. The +1 accounts for exclusivity.
-            Some(anchor.state_lower_limit + 1)
+            Ok(Some(anchor.state_lower_limit + 1))
         } else {
-            // In the gap, nothing is available.
-            None
+            // In the gap, only the state roots of eras imported from era files are available.
+            self.era_state_roots_upper_bound(start_slot)
         }
     }
 }
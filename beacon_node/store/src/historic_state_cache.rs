@@ -64,6 +64,16 @@ impl<E: EthSpec> HistoricStateCache<E> {
         }
     }
 
+    /// Returns `true` if a deserialized state is cached for `slot`, without updating the LRU.
+    pub fn contains_state(&self, slot: Slot) -> bool {
+        self.states.contains(&slot)
+    }
+
+    /// Returns `true` if a diff buffer is cached for `slot`, without updating the LRU.
+    pub fn contains_hdiff_buffer(&self, slot: Slot) -> bool {
+        self.hdiff_buffers.contains(&slot)
+    }
+
     pub fn put_state(&mut self, slot: Slot, state: BeaconState<E>) {
         self.states.put(slot, state);
     }
@@ -77,6 +87,11 @@ impl<E: EthSpec> HistoricStateCache<E> {
         self.put_hdiff_buffer(slot, buffer);
     }
 
+    pub fn clear(&mut self) {
+        self.hdiff_buffers.clear();
+        self.states.clear();
+    }
+
     pub fn metrics(&self) -> Metrics {
         let hdiff_byte_size = self
             .hdiff_buffers
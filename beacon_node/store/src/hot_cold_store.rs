@@ -12,7 +12,12 @@ use crate::metadata::{
     COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, DATA_COLUMN_INFO_KEY,
     PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
 };
+use crate::orphaned_blocks::{
+    get_orphaned_attestation_key, get_orphaned_block_key, parse_orphan_key_slot,
+    parse_orphaned_attestation_key, parse_orphaned_block_key, OrphanedBlock,
+};
 use crate::state_cache::{PutStateOutcome, StateCache};
+use crate::state_load_plan::{self, StateLoadPlan, StateLoadSource};
 use crate::{
     get_data_column_key, get_key_for_col, DBColumn, DatabaseBlock, Error, ItemStore,
     KeyValueStoreOp, StoreItem, StoreOp,
@@ -39,6 +44,7 @@ use std::num::NonZeroUsize;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
+use types::attestation::{AttestationOnDisk, AttestationRefOnDisk};
 use types::data_column_sidecar::{ColumnIndex, DataColumnSidecar, DataColumnSidecarList};
 use types::*;
 use zstd::{Decoder, Encoder};
@@ -140,6 +146,11 @@ impl<E: EthSpec> BlockCache<E> {
         let _ = self.block_cache.pop(block_root);
         let _ = self.blob_cache.pop(block_root);
     }
+    pub fn clear(&mut self) {
+        self.block_cache.clear();
+        self.blob_cache.clear();
+        self.data_column_cache.clear();
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -170,6 +181,7 @@ pub enum HotColdDBError {
     MissingAnchorInfo,
     MissingFrozenBlockSlot(Hash256),
     MissingFrozenBlock(Slot),
+    MissingFrozenStateRoot(Slot),
     MissingPathToBlobsDatabase,
     BlobsPreviouslyInDefaultStore,
     HotStateSummaryError(BeaconStateError),
@@ -396,6 +408,10 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         // Run a garbage collection pass.
         db.remove_garbage()?;
 
+        // Check the split, anchor and recently frozen data for inconsistencies, repairing them if
+        // configured to.
+        db.check_consistency_on_init()?;
+
         // If configured, run a foreground compaction pass.
         if db.config.compact_on_init {
             info!(db.log, "Running foreground compaction");
@@ -763,6 +779,108 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Create an op which adds `orphaned_block` to the orphaned block archive.
+    pub fn orphaned_block_archive_op(
+        &self,
+        block_root: &Hash256,
+        orphaned_block: &OrphanedBlock<E>,
+    ) -> KeyValueStoreOp {
+        let key = get_key_for_col(
+            DBColumn::BeaconOrphanedBlock.into(),
+            &get_orphaned_block_key(orphaned_block.block.slot(), block_root),
+        );
+        KeyValueStoreOp::PutKeyValue(key, orphaned_block.as_store_bytes())
+    }
+
+    /// Load the archived orphaned blocks with slots in the range `start_slot..=end_slot`.
+    pub fn get_orphaned_blocks(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<(Hash256, OrphanedBlock<E>)>, Error> {
+        let mut orphaned_blocks = vec![];
+        for res in self.hot_db.iter_column_from::<Vec<u8>>(
+            DBColumn::BeaconOrphanedBlock,
+            &start_slot.as_u64().to_be_bytes(),
+        ) {
+            let (key, bytes) = res?;
+            let (slot, block_root) = parse_orphaned_block_key(&key)?;
+            if slot > end_slot {
+                break;
+            }
+            orphaned_blocks.push((
+                block_root,
+                OrphanedBlock::from_store_bytes(&bytes, &self.spec)?,
+            ));
+        }
+        Ok(orphaned_blocks)
+    }
+
+    /// Create an op which adds `attestation`, the attestation at `index` in the orphaned block
+    /// `block_root`, to the orphaned attestation archive.
+    pub fn orphaned_attestation_archive_op(
+        &self,
+        block_root: &Hash256,
+        index: u64,
+        attestation: AttestationRef<E>,
+    ) -> KeyValueStoreOp {
+        let key = get_key_for_col(
+            DBColumn::BeaconOrphanedAttestation.into(),
+            &get_orphaned_attestation_key(attestation.data().slot, block_root, index),
+        );
+        KeyValueStoreOp::PutKeyValue(key, AttestationRefOnDisk::from(attestation).as_ssz_bytes())
+    }
+
+    /// Load the archived orphaned attestations for slots in the range `start_slot..=end_slot`,
+    /// along with the roots of the orphaned blocks which included them.
+    pub fn get_orphaned_attestations(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<Vec<(Hash256, Attestation<E>)>, Error> {
+        let mut orphaned_attestations = vec![];
+        for res in self.hot_db.iter_column_from::<Vec<u8>>(
+            DBColumn::BeaconOrphanedAttestation,
+            &start_slot.as_u64().to_be_bytes(),
+        ) {
+            let (key, bytes) = res?;
+            let (slot, block_root, _) = parse_orphaned_attestation_key(&key)?;
+            if slot > end_slot {
+                break;
+            }
+            orphaned_attestations.push((
+                block_root,
+                AttestationOnDisk::from_ssz_bytes(&bytes)?.into(),
+            ));
+        }
+        Ok(orphaned_attestations)
+    }
+
+    /// Create ops which delete every orphaned block and attestation archived for a slot prior to
+    /// `before_slot`.
+    pub fn orphan_archive_prune_ops(
+        &self,
+        before_slot: Slot,
+    ) -> Result<Vec<KeyValueStoreOp>, Error> {
+        let mut ops = vec![];
+        for column in [
+            DBColumn::BeaconOrphanedBlock,
+            DBColumn::BeaconOrphanedAttestation,
+        ] {
+            for res in self.hot_db.iter_column_keys::<Vec<u8>>(column) {
+                let key = res?;
+                if parse_orphan_key_slot(&key)? >= before_slot {
+                    break;
+                }
+                ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    column.into(),
+                    &key,
+                )));
+            }
+        }
+        Ok(ops)
+    }
+
     pub fn get_light_client_update(
         &self,
         sync_committee_period: u64,
@@ -787,32 +905,23 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(None)
     }
 
+    /// Returns the best light client update of each of the `count` sync committee periods from
+    /// `start_period`, stopping at the first period without an update.
+    ///
+    /// Updates are keyed by the little-endian bytes of their period, which don't sort in period
+    /// order, so each period is looked up individually rather than iterating the column.
     pub fn get_light_client_updates(
         &self,
         start_period: u64,
         count: u64,
     ) -> Result<Vec<LightClientUpdate<E>>, Error> {
-        let column = DBColumn::LightClientUpdate;
         let mut light_client_updates = vec![];
-        for res in self
-            .hot_db
-            .iter_column_from::<Vec<u8>>(column, &start_period.to_le_bytes())
-        {
-            let (sync_committee_bytes, light_client_update_bytes) = res?;
-            let sync_committee_period = u64::from_ssz_bytes(&sync_committee_bytes)?;
-            let epoch = sync_committee_period
-                .safe_mul(self.spec.epochs_per_sync_committee_period.into())?;
-
-            let fork_name = self.spec.fork_name_at_epoch(epoch.into());
-
-            let light_client_update =
-                LightClientUpdate::from_ssz_bytes(&light_client_update_bytes, &fork_name)?;
-
-            light_client_updates.push(light_client_update);
-
-            if sync_committee_period >= start_period + count {
+        for sync_committee_period in start_period..start_period.saturating_add(count) {
+            let Some(light_client_update) = self.get_light_client_update(sync_committee_period)?
+            else {
                 break;
-            }
+            };
+            light_client_updates.push(light_client_update);
         }
         Ok(light_client_updates)
     }
@@ -929,6 +1038,27 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Add a hot state which was restored from outside the state cache to the state cache.
+    ///
+    /// If the state is not in the hot database (e.g. an advanced state whose temporary flag caused
+    /// it to be garbage collected at start-up) then it is stored again as a temporary state, so that
+    /// descendants of the state can be stored relative to it.
+    pub fn put_restored_hot_state(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        if self.load_hot_state_summary(state_root)?.is_none() {
+            return self.put_state_possibly_temporary(state_root, state, true);
+        }
+
+        let block_root = state.get_latest_block_root(*state_root);
+        self.state_cache
+            .lock()
+            .put_state(*state_root, block_root, state)?;
+        Ok(())
+    }
+
     /// Fetch a state from the store.
     ///
     /// If `slot` is provided then it will be used as a hint as to which database should
@@ -1497,6 +1627,14 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             );
         }
 
+        self.load_and_cache_hot_state(state_root)
+    }
+
+    /// Load a post-finalization state from the hot database and add it to the state cache.
+    fn load_and_cache_hot_state(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<BeaconState<E>>, Error> {
         let state_from_disk = self.load_hot_state(state_root)?;
 
         if let Some((mut state, block_root)) = state_from_disk {
@@ -1751,37 +1889,46 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Load a pre-finalization state from the freezer database.
     ///
-    /// Will reconstruct the state if it lies between restore points.
+    /// Will reconstruct the state if it lies between restore points, using the cheapest plan
+    /// according to `Self::plan_cold_state_load`.
     pub fn load_cold_state_by_slot(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
-        let storage_strategy = self.hierarchy.storage_strategy(slot)?;
+        let plan = self.plan_cold_state_load(slot)?;
+        self.load_cold_state_with_plan(&plan)
+    }
 
-        // Search for a state from this slot or a recent prior slot in the historic state cache.
-        let mut historic_state_cache = self.historic_state_cache.lock();
+    /// Load a pre-finalization state by following a `plan` from `Self::plan_cold_state_load`.
+    ///
+    /// If the cached state that the plan starts from has since been evicted, the state will be
+    /// loaded from the freezer instead.
+    pub fn load_cold_state_with_plan(&self, plan: &StateLoadPlan) -> Result<BeaconState<E>, Error> {
+        let slot = plan.target_slot;
+
+        if plan.source == StateLoadSource::HistoricCache {
+            let cached_state = self
+                .historic_state_cache
+                .lock()
+                .get_state(plan.base_slot, &self.spec)?;
 
-        let cached_state = itertools::process_results(
-            storage_strategy
-                .replay_from_range(slot)
-                .rev()
-                .map(|prior_slot| historic_state_cache.get_state(prior_slot, &self.spec)),
-            |mut iter| iter.find_map(|cached_state| cached_state),
-        )?;
-        drop(historic_state_cache);
+            if let Some(cached_state) = cached_state {
+                if cached_state.slot() == slot {
+                    metrics::inc_counter(&metrics::STORE_BEACON_HISTORIC_STATE_CACHE_HIT);
+                    return Ok(cached_state);
+                }
+                metrics::inc_counter(&metrics::STORE_BEACON_HISTORIC_STATE_CACHE_MISS);
 
-        if let Some(cached_state) = cached_state {
-            if cached_state.slot() == slot {
-                metrics::inc_counter(&metrics::STORE_BEACON_HISTORIC_STATE_CACHE_HIT);
-                return Ok(cached_state);
+                return self.load_cold_state_by_slot_using_replay(cached_state, slot);
             }
-            metrics::inc_counter(&metrics::STORE_BEACON_HISTORIC_STATE_CACHE_MISS);
-
-            return self.load_cold_state_by_slot_using_replay(cached_state, slot);
         }
 
         metrics::inc_counter(&metrics::STORE_BEACON_HISTORIC_STATE_CACHE_MISS);
 
-        // Load using the diff hierarchy. For states that require replay we recurse into this
-        // function so that we can try to get their pre-state *as a state* rather than an hdiff
-        // buffer.
+        if plan.source == StateLoadSource::EraState {
+            return self.load_era_state_by_slot(slot);
+        }
+
+        // Load using the diff hierarchy. For states that require replay we recurse into
+        // `load_cold_state_by_slot` so that we can try to get their pre-state *as a state* rather
+        // than an hdiff buffer.
         match self.hierarchy.storage_strategy(slot)? {
             StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_) => {
                 let buffer_timer =
@@ -1796,15 +1943,167 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                 Ok(state)
             }
             StorageStrategy::ReplayFrom(from) => {
-                // No prior state found in cache (above), need to load by diffing and then
-                // replaying.
                 let base_state = self.load_cold_state_by_slot(from)?;
                 self.load_cold_state_by_slot_using_replay(base_state, slot)
             }
         }
     }
 
-    fn load_cold_state_by_slot_using_replay(
+    /// Choose the cheapest way to load the pre-finalization state at `slot`.
+    ///
+    /// The candidates are the diff hierarchy (taking into account any diff buffers which are
+    /// already cached) and replaying blocks on top of any state or diff buffer held in the
+    /// historic state cache at or before `slot`. Cached states are considered as far back as
+    /// replaying from them remains cheaper than using the diff hierarchy, which avoids
+    /// reconstructing a deep stack of diffs when a state a few slots earlier is already in
+    /// memory.
+    pub fn plan_cold_state_load(&self, slot: Slot) -> Result<StateLoadPlan, Error> {
+        let freezer_plan = self.plan_freezer_state_load(slot)?;
+
+        let max_lookback = freezer_plan.cost / state_load_plan::cost::REPLAY_SLOT;
+        let min_slot = slot.saturating_sub(max_lookback);
+
+        let historic_state_cache = self.historic_state_cache.lock();
+        let cached_plan = (min_slot.as_u64()..=slot.as_u64())
+            .rev()
+            .map(Slot::new)
+            .find_map(|prior_slot| {
+                let base_cost = if historic_state_cache.contains_state(prior_slot) {
+                    0
+                } else if historic_state_cache.contains_hdiff_buffer(prior_slot) {
+                    state_load_plan::cost::BUFFER_TO_STATE
+                } else {
+                    return None;
+                };
+                Some(StateLoadPlan::new(
+                    StateLoadSource::HistoricCache,
+                    slot,
+                    prior_slot,
+                    0,
+                    base_cost,
+                ))
+            });
+        drop(historic_state_cache);
+
+        Ok(match cached_plan {
+            Some(cached_plan) => cached_plan.cheapest(freezer_plan),
+            None => freezer_plan,
+        })
+    }
+
+    /// Plan the load of the state at `slot` using only the diff hierarchy and block replay.
+    ///
+    /// States which the freezer database doesn't store are planned from the imported era states
+    /// instead, see `Self::plan_era_state_load`.
+    fn plan_freezer_state_load(&self, slot: Slot) -> Result<StateLoadPlan, Error> {
+        let anchor = self.get_anchor_info();
+        if slot > anchor.state_lower_limit && slot < anchor.state_upper_limit {
+            return self.plan_era_state_load(slot);
+        }
+        let base_slot = match self.hierarchy.storage_strategy(slot)? {
+            StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_) => slot,
+            StorageStrategy::ReplayFrom(from) => from,
+        };
+        let (diffs, buffer_cost) = self.plan_hdiff_buffer_load(base_slot)?;
+        Ok(StateLoadPlan::new(
+            StateLoadSource::Freezer,
+            slot,
+            base_slot,
+            diffs,
+            buffer_cost.saturating_add(state_load_plan::cost::BUFFER_TO_STATE),
+        ))
+    }
+
+    /// Estimate the number of diffs and the cost of loading the diff buffer for `slot`.
+    ///
+    /// Mirrors the recursion in `Self::load_hdiff_buffer_for_slot`.
+    fn plan_hdiff_buffer_load(&self, slot: Slot) -> Result<(usize, u64), Error> {
+        if self.historic_state_cache.lock().contains_hdiff_buffer(slot) {
+            return Ok((0, 0));
+        }
+        match self.hierarchy.storage_strategy(slot)? {
+            StorageStrategy::Snapshot => Ok((0, state_load_plan::cost::SNAPSHOT_LOAD)),
+            StorageStrategy::DiffFrom(from) => {
+                let (diffs, cost) = self.plan_hdiff_buffer_load(from)?;
+                Ok((
+                    diffs + 1,
+                    cost.saturating_add(state_load_plan::cost::DIFF_APPLY),
+                ))
+            }
+            StorageStrategy::ReplayFrom(from) => self.plan_hdiff_buffer_load(from),
+        }
+    }
+
+    /// Choose how to load the hot state with `state_root`.
+    ///
+    /// Return `None` if no hot state with `state_root` is known.
+    pub fn plan_hot_state_load(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<StateLoadPlan>, Error> {
+        if let Some(slot) = self.state_cache.lock().peek_slot_by_state_root(*state_root) {
+            return Ok(Some(StateLoadPlan::new(
+                StateLoadSource::HotCache,
+                slot,
+                slot,
+                0,
+                0,
+            )));
+        }
+
+        self.plan_hot_replay_state_load(state_root)
+    }
+
+    /// Load a post-finalization state by following a `plan` from `Self::plan_hot_state_load`.
+    ///
+    /// If the plan starts from a cached state which has since been evicted, the state is loaded
+    /// from disk instead. Return the state along with the plan that was actually followed, or
+    /// `None` if no hot state with `state_root` is known.
+    pub fn load_hot_state_with_plan(
+        &self,
+        state_root: &Hash256,
+        plan: &StateLoadPlan,
+    ) -> Result<Option<(BeaconState<E>, StateLoadPlan)>, Error> {
+        let plan = if plan.source == StateLoadSource::HotCache {
+            if let Some(state) = self.state_cache.lock().get_by_state_root(*state_root) {
+                return Ok(Some((state, plan.clone())));
+            }
+            match self.plan_hot_replay_state_load(state_root)? {
+                Some(plan) => plan,
+                None => return Ok(None),
+            }
+        } else {
+            plan.clone()
+        };
+
+        Ok(self
+            .load_and_cache_hot_state(state_root)?
+            .map(|state| (state, plan)))
+    }
+
+    /// Plan the load of the hot state with `state_root` by replaying blocks on top of its epoch
+    /// boundary state from disk.
+    fn plan_hot_replay_state_load(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<StateLoadPlan>, Error> {
+        let Some(summary) = self.load_hot_state_summary(state_root)? else {
+            return Ok(None);
+        };
+        let base_slot = summary
+            .slot
+            .epoch(E::slots_per_epoch())
+            .start_slot(E::slots_per_epoch());
+        Ok(Some(StateLoadPlan::new(
+            StateLoadSource::HotReplay,
+            summary.slot,
+            base_slot,
+            0,
+            state_load_plan::cost::HOT_BOUNDARY_LOAD,
+        )))
+    }
+
+    pub(crate) fn load_cold_state_by_slot_using_replay(
         &self,
         mut base_state: BeaconState<E>,
         slot: Slot,
@@ -2067,6 +2366,19 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Fetch the SSZ bytes of the blobs for a given block from the store.
+    ///
+    /// Blobs read from disk are returned as stored, without decoding them or populating the cache.
+    pub fn get_blobs_ssz_bytes(&self, block_root: &Hash256) -> Result<Option<Vec<u8>>, Error> {
+        if let Some(blobs) = self.block_cache.lock().get_blobs(block_root) {
+            metrics::inc_counter(&metrics::BEACON_BLOBS_CACHE_HIT_COUNT);
+            return Ok(Some(blobs.as_ssz_bytes()));
+        }
+
+        self.blobs_db
+            .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_slice())
+    }
+
     /// Fetch all keys in the data_column column with prefix `block_root`
     pub fn get_data_column_keys(&self, block_root: Hash256) -> Result<Vec<ColumnIndex>, Error> {
         self.blobs_db
@@ -2416,6 +2728,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         &self.config
     }
 
+    /// Estimate the size of each column of the hot, cold and blobs databases for metrics.
+    ///
+    /// This iterates over every key in the databases, so it takes a long time on large databases.
+    pub fn scrape_column_sizes(&self) {
+        let _timer = metrics::start_timer(&metrics::DISK_DB_COLUMN_SIZE_ESTIMATE_TIMES);
+        metrics::scrape_column_sizes("hot", &self.hot_db);
+        metrics::scrape_column_sizes("cold", &self.cold_db);
+        metrics::scrape_column_sizes("blobs", &self.blobs_db);
+    }
+
     /// Load previously-stored config from disk.
     fn load_config(&self) -> Result<Option<OnDiskStoreConfig>, Error> {
         self.hot_db.get(&CONFIG_KEY)
@@ -2478,6 +2800,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.get(state_root)
     }
 
+    /// Empty the block and historic state caches, and cull the hot state cache down to its most
+    /// recently used states.
+    pub fn flush_caches(&self) {
+        self.block_cache.lock().clear();
+        self.historic_state_cache.lock().clear();
+
+        let mut state_cache = self.state_cache.lock();
+        let len = state_cache.len();
+        state_cache.cull(len);
+        info!(
+            self.log,
+            "Flushed database caches";
+            "hot_states_retained" => state_cache.len(),
+        );
+    }
+
     /// Run a compaction pass to free up space used by deleted states.
     pub fn compact(&self) -> Result<(), Error> {
         self.hot_db.compact()?;
@@ -3238,7 +3576,7 @@ impl StoreItem for Split {
 }
 
 /// Type hint.
-fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error>>> {
+pub(crate) fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error>>> {
     None
 }
 
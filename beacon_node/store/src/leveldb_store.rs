@@ -40,6 +40,13 @@ impl<E: EthSpec> LevelDB<E> {
         ReadOptions::new()
     }
 
+    /// Options for bulk reads which shouldn't evict recently used blocks from the cache.
+    fn uncached_read_options(&self) -> ReadOptions<BytesKey> {
+        let mut opts = ReadOptions::new();
+        opts.fill_cache = false;
+        opts
+    }
+
     fn write_options(&self) -> WriteOptions {
         WriteOptions::new()
     }
@@ -62,6 +69,7 @@ impl<E: EthSpec> LevelDB<E> {
         metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[col]);
         metrics::inc_counter_vec_by(&metrics::DISK_DB_WRITE_BYTES, &[col], val.len() as u64);
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+        let _column_timer = metrics::start_timer_vec(&metrics::DISK_DB_COLUMN_WRITE_TIMES, &[col]);
 
         self.db
             .put(opts, BytesKey::from_vec(column_key), val)
@@ -93,6 +101,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
         metrics::inc_counter_vec(&metrics::DISK_DB_READ_COUNT, &[col]);
         let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+        let _column_timer = metrics::start_timer_vec(&metrics::DISK_DB_COLUMN_READ_TIMES, &[col]);
 
         self.db
             .get(self.read_options(), BytesKey::from_vec(column_key))
@@ -158,6 +167,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         }
 
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+        let _batch_timer = metrics::start_timer(&metrics::DISK_DB_BATCH_WRITE_TIMES);
 
         self.db.write(self.write_options(), &leveldb_batch)?;
         Ok(())
@@ -247,6 +257,25 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                 }),
         )
     }
+
+    /// Estimate the size of `column` without filling the block cache with its entries.
+    fn estimate_column_size(&self, column: DBColumn) -> Result<ColumnSizeEstimate, Error> {
+        let start_key = BytesKey::from_vec(get_key_for_col(column.into(), &[]));
+
+        let iter = self.db.keys_iter(self.uncached_read_options());
+        iter.seek(&start_key);
+        let keys = iter
+            .take_while(move |key| key.matches_column(column))
+            .map(move |bytes_key| Ok(bytes_key.key[column.as_bytes().len()..].to_vec()));
+
+        estimate_column_size(Box::new(keys), |key| {
+            let column_key = BytesKey::from_vec(get_key_for_col(column.into(), key));
+            Ok(self
+                .db
+                .get(self.uncached_read_options(), column_key)?
+                .map(|value| value.len()))
+        })
+    }
 }
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
@@ -11,6 +11,8 @@ pub mod chunked_iter;
 pub mod chunked_vector;
 pub mod config;
 pub mod consensus_context;
+pub mod consistency;
+pub mod era;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
@@ -22,9 +24,11 @@ mod leveldb_store;
 mod memory_store;
 pub mod metadata;
 pub mod metrics;
+pub mod orphaned_blocks;
 pub mod partial_beacon_state;
 pub mod reconstruct;
 pub mod state_cache;
+pub mod state_load_plan;
 
 pub mod iter;
 
@@ -40,10 +44,14 @@ pub use metadata::AnchorInfo;
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
 use std::sync::Arc;
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoStaticStr};
 pub use types::*;
 
 const DATA_COLUMN_DB_KEY_SIZE: usize = 32 + 8;
+const ORPHANED_BLOCK_DB_KEY_SIZE: usize = 8 + 32;
+const ORPHANED_ATTESTATION_DB_KEY_SIZE: usize = 8 + 32 + 8;
+/// Read the value of one in this many keys when estimating the size of a column.
+const COLUMN_SIZE_SAMPLE_INTERVAL: u64 = 64;
 
 pub type ColumnIter<'a, K> = Box<dyn Iterator<Item = Result<(K, Vec<u8>), Error>> + 'a>;
 pub type ColumnKeyIter<'a, K> = Box<dyn Iterator<Item = Result<K, Error>> + 'a>;
@@ -114,6 +122,53 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
 
     /// Iterate through all keys in a particular column.
     fn iter_column_keys<K: Key>(&self, column: DBColumn) -> ColumnKeyIter<K>;
+
+    /// Estimate the number of keys in `column` and the total size of its keys and values.
+    fn estimate_column_size(&self, column: DBColumn) -> Result<ColumnSizeEstimate, Error> {
+        let col = column.as_str();
+        estimate_column_size(self.iter_raw_keys(column, &[]), |key| {
+            Ok(self.get_bytes(col, key)?.map(|value| value.len()))
+        })
+    }
+}
+
+/// The estimated size of a database column, see `KeyValueStore::estimate_column_size`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ColumnSizeEstimate {
+    pub keys: u64,
+    /// The total size of the keys and values, before compression.
+    pub bytes: u64,
+}
+
+/// Count the `keys` of a column and estimate its size, reading only a sample of the values.
+///
+/// `value_size` returns the length of the value for a key, if it still exists.
+fn estimate_column_size(
+    keys: RawKeyIter,
+    mut value_size: impl FnMut(&[u8]) -> Result<Option<usize>, Error>,
+) -> Result<ColumnSizeEstimate, Error> {
+    let mut key_bytes = 0;
+    let mut key_count = 0;
+    let mut sampled_values = 0;
+    let mut sampled_value_bytes = 0;
+    for key in keys {
+        let key = key?;
+        if key_count % COLUMN_SIZE_SAMPLE_INTERVAL == 0 {
+            if let Some(size) = value_size(&key)? {
+                sampled_values += 1;
+                sampled_value_bytes += size as u64;
+            }
+        }
+        key_count += 1;
+        key_bytes += key.len() as u64;
+    }
+    let value_bytes = sampled_value_bytes
+        .checked_div(sampled_values)
+        .map_or(0, |average| average * key_count);
+    Ok(ColumnSizeEstimate {
+        keys: key_count,
+        bytes: key_bytes + value_bytes,
+    })
 }
 
 pub trait Key: Sized + 'static {
@@ -245,7 +300,7 @@ pub enum StoreOp<'a, E: EthSpec> {
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -349,6 +404,26 @@ pub enum DBColumn {
     /// For helping persist eagerly computed light client bootstrap data
     #[strum(serialize = "scm")]
     SyncCommittee,
+    /// Archive of blocks pruned from the hot database because they lost fork choice.
+    ///
+    /// - Key: 8-byte big-endian slot followed by the `Hash256` block root.
+    /// - Value: `OrphanedBlock`.
+    #[strum(serialize = "bob")]
+    BeaconOrphanedBlock,
+    /// Archive of the attestations included in archived orphaned blocks.
+    ///
+    /// - Key: 8-byte big-endian attestation slot, the `Hash256` root of the orphaned block and the
+    ///   8-byte big-endian index of the attestation within that block.
+    /// - Value: `AttestationOnDisk`.
+    #[strum(serialize = "boa")]
+    BeaconOrphanedAttestation,
+    /// States at the end of each era imported from era files, used to serve historic states
+    /// which are not stored in the freezer DB.
+    ///
+    /// - Key: 8-byte big-endian slot of the state.
+    /// - Value: snappy-framed SSZ-encoded `BeaconState`.
+    #[strum(serialize = "bes")]
+    BeaconEraState,
 }
 
 /// A block from the database, which might have an execution payload or not.
@@ -397,10 +472,13 @@ impl DBColumn {
             | Self::BeaconRandaoMixes
             | Self::BeaconStateSnapshot
             | Self::BeaconStateDiff
+            | Self::BeaconEraState
             | Self::SyncCommittee
             | Self::SyncCommitteeBranch
             | Self::LightClientUpdate => 8,
             Self::BeaconDataColumn => DATA_COLUMN_DB_KEY_SIZE,
+            Self::BeaconOrphanedBlock => ORPHANED_BLOCK_DB_KEY_SIZE,
+            Self::BeaconOrphanedAttestation => ORPHANED_ATTESTATION_DB_KEY_SIZE,
         }
     }
 }
@@ -487,6 +565,31 @@ mod tests {
         test_impl(store);
     }
 
+    fn test_estimate_column_size(store: impl ItemStore<MinimalEthSpec>) {
+        let item = StorableThing { a: 1, b: 42 };
+        for _ in 0..COLUMN_SIZE_SAMPLE_INTERVAL * 2 + 1 {
+            store.put(&Hash256::random(), &item).unwrap();
+        }
+
+        let estimate = store.estimate_column_size(DBColumn::BeaconBlock).unwrap();
+        assert_eq!(estimate.keys, COLUMN_SIZE_SAMPLE_INTERVAL * 2 + 1);
+        assert_eq!(
+            estimate.bytes,
+            estimate.keys * (32 + item.as_ssz_bytes().len() as u64)
+        );
+        assert_eq!(
+            store.estimate_column_size(DBColumn::BeaconState).unwrap(),
+            ColumnSizeEstimate::default()
+        );
+    }
+
+    #[test]
+    fn column_size_estimates() {
+        let dir = tempdir().unwrap();
+        test_estimate_column_size(LevelDB::open(dir.path()).unwrap());
+        test_estimate_column_size(MemoryStore::open());
+    }
+
     #[test]
     fn exists() {
         let store = MemoryStore::<MinimalEthSpec>::open();
@@ -1,8 +1,11 @@
 pub use metrics::{set_gauge, try_create_int_gauge, *};
 
+use crate::{DBColumn, KeyValueStore};
 use directory::size_of_dir;
 use std::path::Path;
 use std::sync::LazyLock;
+use strum::IntoEnumIterator;
+use types::EthSpec;
 
 /*
  * General
@@ -73,6 +76,46 @@ pub static DISK_DB_DELETE_COUNT: LazyLock<Result<IntCounterVec>> = LazyLock::new
         &["col"],
     )
 });
+pub static DISK_DB_COLUMN_READ_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "store_disk_db_column_read_seconds",
+        "Time taken to read a value from each column of the on-disk DBs",
+        &["col"],
+    )
+});
+pub static DISK_DB_COLUMN_WRITE_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "store_disk_db_column_write_seconds",
+        "Time taken to write a single value to each column of the on-disk DBs",
+        &["col"],
+    )
+});
+pub static DISK_DB_BATCH_WRITE_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_disk_db_batch_write_seconds",
+        "Time taken to atomically write a batch to the on-disk DBs",
+    )
+});
+pub static DISK_DB_COLUMN_SIZE: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "store_disk_db_column_size_bytes",
+        "Estimated size of each column of the on-disk DBs, before compression (bytes)",
+        &["db", "col"],
+    )
+});
+pub static DISK_DB_COLUMN_KEYS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "store_disk_db_column_keys",
+        "Number of keys in each column of the on-disk DBs",
+        &["db", "col"],
+    )
+});
+pub static DISK_DB_COLUMN_SIZE_ESTIMATE_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_disk_db_column_size_estimate_seconds",
+        "Time taken to estimate the size of every column of the on-disk DBs",
+    )
+});
 /*
  * Anchor Info
  */
@@ -374,3 +417,24 @@ pub fn scrape_for_metrics(db_path: &Path, freezer_db_path: &Path) {
     let freezer_db_size = size_of_dir(freezer_db_path);
     set_gauge(&FREEZER_DB_SIZE, freezer_db_size as i64);
 }
+
+/// Updates the estimated size and number of keys of each column in `db`.
+///
+/// The size is the total length of the keys and values, which LevelDB compresses on disk.
+pub fn scrape_column_sizes<E: EthSpec, S: KeyValueStore<E>>(db_name: &str, db: &S) {
+    for column in DBColumn::iter() {
+        let Ok(estimate) = db.estimate_column_size(column) else {
+            continue;
+        };
+        set_gauge_vec(
+            &DISK_DB_COLUMN_KEYS,
+            &[db_name, column.as_str()],
+            estimate.keys as i64,
+        );
+        set_gauge_vec(
+            &DISK_DB_COLUMN_SIZE,
+            &[db_name, column.as_str()],
+            estimate.bytes as i64,
+        );
+    }
+}
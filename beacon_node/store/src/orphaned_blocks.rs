@@ -0,0 +1,147 @@
+//! An archive of blocks which lost fork choice and were pruned from the hot database.
+//!
+//! Rather than discarding abandoned forks entirely, the migrator can retain a copy of each pruned
+//! block (including the attestations it carried) in the `BeaconOrphanedBlock` column. Entries are
+//! keyed by slot and then block root so that they can be queried efficiently by slot range.
+//!
+//! The attestations of each archived block are also stored individually in the
+//! `BeaconOrphanedAttestation` column, keyed by the slot they attest to, so that they can be
+//! looked up without knowing which orphaned block included them.
+//!
+//! Entries older than the configured retention period are deleted on finalization.
+use crate::{DBColumn, Error};
+use serde::{Deserialize, Serialize};
+use ssz::Encode;
+use types::{BlindedPayload, ChainSpec, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// The reason a block was archived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrphanReason {
+    /// The block was on a fork which was abandoned by finalization.
+    Orphaned,
+    /// The block was one of several blocks by the same proposer for the same slot, and the other
+    /// block became canonical.
+    Equivocation,
+}
+
+impl OrphanReason {
+    fn as_u8(self) -> u8 {
+        match self {
+            OrphanReason::Orphaned => 0,
+            OrphanReason::Equivocation => 1,
+        }
+    }
+
+    fn from_u8(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(OrphanReason::Orphaned),
+            1 => Ok(OrphanReason::Equivocation),
+            _ => Err(Error::InvalidBytes),
+        }
+    }
+}
+
+/// A non-canonical block retained in the orphaned block archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrphanedBlock<E: EthSpec> {
+    pub reason: OrphanReason,
+    pub block: SignedBeaconBlock<E, BlindedPayload<E>>,
+}
+
+impl<E: EthSpec> OrphanedBlock<E> {
+    /// Encode as a single reason byte followed by the SSZ bytes of the blinded block.
+    pub fn as_store_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.reason.as_u8()];
+        bytes.extend_from_slice(&self.block.as_ssz_bytes());
+        bytes
+    }
+
+    pub fn from_store_bytes(bytes: &[u8], spec: &ChainSpec) -> Result<Self, Error> {
+        let (reason, block_bytes) = bytes.split_first().ok_or(Error::InvalidBytes)?;
+        Ok(Self {
+            reason: OrphanReason::from_u8(*reason)?,
+            block: SignedBeaconBlock::from_ssz_bytes(block_bytes, spec)?,
+        })
+    }
+}
+
+/// Key for the `BeaconOrphanedBlock` column: the big-endian slot followed by the block root.
+pub fn get_orphaned_block_key(slot: Slot, block_root: &Hash256) -> Vec<u8> {
+    let mut key = slot.as_u64().to_be_bytes().to_vec();
+    key.extend_from_slice(block_root.as_slice());
+    key
+}
+
+pub fn parse_orphaned_block_key(key: &[u8]) -> Result<(Slot, Hash256), Error> {
+    if key.len() != DBColumn::BeaconOrphanedBlock.key_size() {
+        return Err(Error::InvalidKey);
+    }
+    parse_key_prefix(key)
+}
+
+/// Key for the `BeaconOrphanedAttestation` column: the big-endian attestation slot, the root of
+/// the orphaned block and the big-endian index of the attestation within the block.
+pub fn get_orphaned_attestation_key(slot: Slot, block_root: &Hash256, index: u64) -> Vec<u8> {
+    let mut key = get_orphaned_block_key(slot, block_root);
+    key.extend_from_slice(&index.to_be_bytes());
+    key
+}
+
+pub fn parse_orphaned_attestation_key(key: &[u8]) -> Result<(Slot, Hash256, u64), Error> {
+    if key.len() != DBColumn::BeaconOrphanedAttestation.key_size() {
+        return Err(Error::InvalidKey);
+    }
+    let (block_key, index_bytes) = key.split_at(8 + 32);
+    let (slot, block_root) = parse_key_prefix(block_key)?;
+    let index = u64::from_be_bytes(index_bytes.try_into().map_err(|_| Error::InvalidKey)?);
+    Ok((slot, block_root, index))
+}
+
+/// Parse the slot from the start of a key in either orphan archive column.
+pub fn parse_orphan_key_slot(key: &[u8]) -> Result<Slot, Error> {
+    let slot_bytes = key.get(..8).ok_or(Error::InvalidKey)?;
+    let slot = u64::from_be_bytes(slot_bytes.try_into().map_err(|_| Error::InvalidKey)?);
+    Ok(Slot::new(slot))
+}
+
+fn parse_key_prefix(key: &[u8]) -> Result<(Slot, Hash256), Error> {
+    let slot = parse_orphan_key_slot(key)?;
+    Ok((slot, Hash256::from_slice(&key[8..])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn key_round_trip() {
+        let slot = Slot::new(0x0102_0304);
+        let block_root = Hash256::repeat_byte(0xab);
+        let key = get_orphaned_block_key(slot, &block_root);
+        assert_eq!(key.len(), DBColumn::BeaconOrphanedBlock.key_size());
+        assert_eq!(parse_orphaned_block_key(&key).unwrap(), (slot, block_root));
+        assert!(parse_orphaned_block_key(&key[1..]).is_err());
+    }
+
+    #[test]
+    fn attestation_key_round_trip() {
+        let slot = Slot::new(0x0102_0304);
+        let block_root = Hash256::repeat_byte(0xab);
+        let key = get_orphaned_attestation_key(slot, &block_root, 7);
+        assert_eq!(key.len(), DBColumn::BeaconOrphanedAttestation.key_size());
+        assert_eq!(
+            parse_orphaned_attestation_key(&key).unwrap(),
+            (slot, block_root, 7)
+        );
+        assert_eq!(parse_orphan_key_slot(&key).unwrap(), slot);
+        assert!(parse_orphaned_attestation_key(&key[1..]).is_err());
+    }
+
+    #[test]
+    fn keys_sort_by_slot() {
+        let earlier = get_orphaned_block_key(Slot::new(255), &Hash256::repeat_byte(0xff));
+        let later = get_orphaned_block_key(Slot::new(256), &Hash256::zero());
+        assert!(earlier < later);
+    }
+}
@@ -174,6 +174,16 @@ impl<E: EthSpec> StateCache<E> {
         self.states.get(&state_root).cloned()
     }
 
+    /// Returns the slot of the state with `state_root` if it is cached, without updating the LRU.
+    pub fn peek_slot_by_state_root(&self, state_root: Hash256) -> Option<Slot> {
+        if let Some(ref finalized_state) = self.finalized_state {
+            if state_root == finalized_state.state_root {
+                return Some(finalized_state.state.slot());
+            }
+        }
+        self.states.peek(&state_root).map(|state| state.slot())
+    }
+
     pub fn get_by_block_root(
         &mut self,
         block_root: Hash256,
@@ -0,0 +1,137 @@
+//! Types describing how a `BeaconState` at a given slot will be (or was) obtained.
+//!
+//! A state can be reconstructed in several ways, e.g. by applying hierarchical diffs to a freezer
+//! snapshot, or by replaying blocks on top of a nearby state which is already cached. The planner
+//! in `HotColdDB` estimates the cost of each candidate path using the cost model below and picks
+//! the cheapest.
+use serde::{Deserialize, Serialize};
+use types::Slot;
+
+/// Relative costs of the operations involved in loading a state.
+///
+/// The units are arbitrary but are roughly proportional to milliseconds on mainnet.
+pub mod cost {
+    /// Reading and decoding a full snapshot from the freezer database.
+    pub const SNAPSHOT_LOAD: u64 = 1_000;
+    /// Reading a hierarchical diff and applying it to a diff buffer.
+    pub const DIFF_APPLY: u64 = 150;
+    /// Converting a diff buffer into a `BeaconState`.
+    pub const BUFFER_TO_STATE: u64 = 300;
+    /// Reading the epoch boundary state of a hot state from disk.
+    pub const HOT_BOUNDARY_LOAD: u64 = 500;
+    /// Replaying a single slot (and the block at that slot, if any).
+    pub const REPLAY_SLOT: u64 = 40;
+}
+
+/// The starting point of a state load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StateLoadSource {
+    /// The head state, possibly advanced through empty slots.
+    Head,
+    /// A state held in the hot state cache.
+    HotCache,
+    /// An epoch boundary state from the hot database, with blocks replayed on top.
+    HotReplay,
+    /// A state held in the historic state cache, with blocks replayed on top.
+    HistoricCache,
+    /// A freezer snapshot with hierarchical diffs applied, and possibly blocks replayed on top.
+    Freezer,
+    /// A state imported from an era file, with blocks replayed on top.
+    EraState,
+}
+
+impl StateLoadSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StateLoadSource::Head => "head",
+            StateLoadSource::HotCache => "hot_cache",
+            StateLoadSource::HotReplay => "hot_replay",
+            StateLoadSource::HistoricCache => "historic_cache",
+            StateLoadSource::Freezer => "freezer",
+            StateLoadSource::EraState => "era_state",
+        }
+    }
+}
+
+/// A plan for loading the state at `target_slot`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateLoadPlan {
+    pub source: StateLoadSource,
+    pub target_slot: Slot,
+    /// The slot of the state that loading starts from.
+    pub base_slot: Slot,
+    /// The number of hierarchical diffs applied to the base state.
+    pub diffs: usize,
+    /// The number of slots replayed to reach `target_slot`.
+    pub replay_slots: u64,
+    /// The estimated cost of the plan, see `cost`.
+    pub cost: u64,
+}
+
+impl StateLoadPlan {
+    pub fn new(
+        source: StateLoadSource,
+        target_slot: Slot,
+        base_slot: Slot,
+        diffs: usize,
+        base_cost: u64,
+    ) -> Self {
+        let replay_slots = target_slot.saturating_sub(base_slot).as_u64();
+        Self {
+            source,
+            target_slot,
+            base_slot,
+            diffs,
+            replay_slots,
+            cost: base_cost.saturating_add(replay_slots.saturating_mul(cost::REPLAY_SLOT)),
+        }
+    }
+
+    /// Returns the cheaper of two plans, preferring `self` in the event of a tie.
+    pub fn cheapest(self, other: Self) -> Self {
+        if other.cost < self.cost {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_cost() {
+        let plan = StateLoadPlan::new(
+            StateLoadSource::Freezer,
+            Slot::new(40),
+            Slot::new(32),
+            2,
+            cost::SNAPSHOT_LOAD,
+        );
+        assert_eq!(plan.replay_slots, 8);
+        assert_eq!(plan.cost, cost::SNAPSHOT_LOAD + 8 * cost::REPLAY_SLOT);
+    }
+
+    #[test]
+    fn cheapest_prefers_cached_state_nearby() {
+        let freezer = StateLoadPlan::new(
+            StateLoadSource::Freezer,
+            Slot::new(63),
+            Slot::new(32),
+            1,
+            cost::SNAPSHOT_LOAD + cost::DIFF_APPLY + cost::BUFFER_TO_STATE,
+        );
+        let cached = StateLoadPlan::new(
+            StateLoadSource::HistoricCache,
+            Slot::new(63),
+            Slot::new(60),
+            0,
+            0,
+        );
+        assert_eq!(freezer.clone().cheapest(cached.clone()), cached);
+        assert_eq!(cached.clone().cheapest(freezer), cached);
+    }
+}
@@ -21,6 +21,7 @@ use std::{
 };
 use zeroize::Zeroize;
 
+pub mod password_source;
 pub mod validator_definitions;
 
 pub use eth2_keystore;
@@ -0,0 +1,199 @@
+//! Secret storage backends for validator keystore passwords.
+//!
+//! These are an alternative to storing passwords in plaintext, either in the
+//! `validator_definitions.yml` file or in a password file alongside the keystores. A backend is
+//! selected per validator definition with the `voting_keystore_password_source` field.
+
+use crate::ZeroizeString;
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::PublicKey;
+use zeroize::Zeroizing;
+
+/// The timeout for requests to a Vault server.
+const VAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The key of the password within a Vault secret, if none is specified.
+pub const DEFAULT_VAULT_KEY: &str = "password";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The password could not be read from the operating system's keyring.
+    Keyring(keyring::Error),
+    /// A file required by the backend could not be read.
+    UnableToReadFile(PathBuf, io::Error),
+    /// The age bundle is encrypted with a passphrase, rather than to an identity.
+    AgeBundleWithPassphrase,
+    /// The age identity file or bundle could not be decrypted.
+    AgeDecrypt(age::DecryptError),
+    /// The decrypted age bundle is not a YAML map of public keys to passwords.
+    InvalidAgeBundle(serde_yaml::Error),
+    /// The request to the Vault server failed.
+    Vault(String),
+    /// The backend doesn't hold a password for the validator.
+    PasswordNotFound,
+}
+
+/// A secret storage backend which holds the password for a validator's voting keystore.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "snake_case")]
+pub enum PasswordSource {
+    /// A password stored in the operating system's keyring: the Keychain on macOS, the Credential
+    /// Manager on Windows or the kernel keyring on Linux.
+    Keyring {
+        service: String,
+        /// The username of the keyring entry, which defaults to the `0x`-prefixed voting public
+        /// key.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        username: Option<String>,
+    },
+    /// A password in an age-encrypted bundle, which is a YAML map of `0x`-prefixed voting public
+    /// keys to passwords.
+    ///
+    /// https://age-encryption.org
+    AgeBundle {
+        bundle_path: PathBuf,
+        /// Path to an age identity file, containing a secret key which the bundle is encrypted to.
+        identity_path: PathBuf,
+    },
+    /// A password stored in a key/value (version 2) secrets engine of a HashiCorp Vault server.
+    Vault {
+        url: SensitiveUrl,
+        /// The path at which the secrets engine is mounted, e.g. `secret`.
+        mount: String,
+        /// The path of the secret within the secrets engine.
+        path: String,
+        /// The key of the password within the secret, which defaults to `password`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        key: Option<String>,
+        /// Path to a file containing the Vault token.
+        token_path: PathBuf,
+    },
+}
+
+impl PasswordSource {
+    /// Reads the password for the validator with `voting_public_key` from the backend.
+    ///
+    /// This function blocks on I/O, including network requests to a Vault server, so it must not
+    /// be called from an async context.
+    pub fn read_password(&self, voting_public_key: &PublicKey) -> Result<ZeroizeString, Error> {
+        match self {
+            PasswordSource::Keyring { service, username } => {
+                let username = username
+                    .clone()
+                    .unwrap_or_else(|| voting_public_key.as_hex_string());
+                keyring::Entry::new(service, &username)
+                    .and_then(|entry| entry.get_password())
+                    .map(ZeroizeString::from)
+                    .map_err(|e| match e {
+                        keyring::Error::NoEntry => Error::PasswordNotFound,
+                        e => Error::Keyring(e),
+                    })
+            }
+            PasswordSource::AgeBundle {
+                bundle_path,
+                identity_path,
+            } => read_age_bundle(bundle_path, identity_path)?
+                .remove(&voting_public_key.as_hex_string())
+                .ok_or(Error::PasswordNotFound),
+            PasswordSource::Vault {
+                url,
+                mount,
+                path,
+                key,
+                token_path,
+            } => read_vault_secret(
+                url,
+                mount,
+                path,
+                key.as_deref().unwrap_or(DEFAULT_VAULT_KEY),
+                token_path,
+            ),
+        }
+    }
+}
+
+/// Decrypts the age bundle at `bundle_path` with the identities in `identity_path`.
+fn read_age_bundle(
+    bundle_path: &Path,
+    identity_path: &Path,
+) -> Result<HashMap<String, ZeroizeString>, Error> {
+    let identities = age::IdentityFile::from_file(identity_path.to_string_lossy().into_owned())
+        .map_err(|e| Error::UnableToReadFile(identity_path.to_path_buf(), e))?
+        .into_identities()
+        .map_err(Error::AgeDecrypt)?;
+
+    let bundle =
+        fs::read(bundle_path).map_err(|e| Error::UnableToReadFile(bundle_path.to_path_buf(), e))?;
+    let decryptor = match age::Decryptor::new(bundle.as_slice()).map_err(Error::AgeDecrypt)? {
+        age::Decryptor::Recipients(decryptor) => decryptor,
+        age::Decryptor::Passphrase(_) => return Err(Error::AgeBundleWithPassphrase),
+    };
+
+    let mut plaintext = Zeroizing::new(vec![]);
+    decryptor
+        .decrypt(
+            identities
+                .iter()
+                .map(|identity| identity.as_ref() as &dyn age::Identity),
+        )
+        .map_err(Error::AgeDecrypt)?
+        .read_to_end(&mut plaintext)
+        .map_err(|e| Error::UnableToReadFile(bundle_path.to_path_buf(), e))?;
+
+    let passwords: HashMap<String, ZeroizeString> =
+        serde_yaml::from_slice(&plaintext).map_err(Error::InvalidAgeBundle)?;
+    // Public keys are compared in lowercase, as produced by `PublicKey::as_hex_string`.
+    Ok(passwords
+        .into_iter()
+        .map(|(pubkey, password)| (pubkey.to_lowercase(), password))
+        .collect())
+}
+
+/// Reads `key` from the secret at `path` in the key/value secrets engine mounted at `mount`.
+fn read_vault_secret(
+    url: &SensitiveUrl,
+    mount: &str,
+    path: &str,
+    key: &str,
+    token_path: &Path,
+) -> Result<ZeroizeString, Error> {
+    let token = fs::read_to_string(token_path)
+        .map(ZeroizeString::from)
+        .map_err(|e| Error::UnableToReadFile(token_path.to_path_buf(), e))?
+        .without_newlines();
+
+    let mut secret_url = url.full.clone();
+    secret_url
+        .path_segments_mut()
+        .map_err(|()| Error::Vault(format!("invalid Vault URL: {}", url)))?
+        .pop_if_empty()
+        .extend(["v1", mount, "data"])
+        .extend(path.split('/').filter(|segment| !segment.is_empty()));
+
+    let response = reqwest::blocking::Client::new()
+        .get(secret_url)
+        .header("X-Vault-Token", token.as_str())
+        .timeout(VAULT_REQUEST_TIMEOUT)
+        .send()
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| Error::Vault(e.without_url().to_string()))?;
+    if response.status() == reqwest::StatusCode::NO_CONTENT {
+        return Err(Error::PasswordNotFound);
+    }
+
+    let body: serde_json::Value = response
+        .json()
+        .map_err(|e| Error::Vault(e.without_url().to_string()))?;
+    body.get("data")
+        .and_then(|data| data.get("data"))
+        .and_then(|secret| secret.get(key))
+        .and_then(|password| password.as_str())
+        .map(|password| ZeroizeString::from(password.to_string()))
+        .ok_or(Error::PasswordNotFound)
+}
@@ -3,18 +3,23 @@
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
 
+use crate::password_source::PasswordSource;
 use crate::{
     default_keystore_password_path, read_password_string, write_file_via_temporary, ZeroizeString,
 };
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
 use regex::Regex;
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use slog::{error, Logger};
 use std::collections::HashSet;
+use std::fmt;
 use std::fs::{self, File};
 use std::io;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 use types::{graffiti::GraffitiString, Address, PublicKey};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
@@ -47,6 +52,9 @@ pub enum Error {
     UnableToCreateValidatorDir(PathBuf),
     UnableToReadKeystorePassword(String),
     KeystoreWithoutPassword,
+    /// The password is held by a `PasswordSource`, which must be read with the validator's public
+    /// key.
+    KeystorePasswordInSource,
 }
 
 /// Defines how a password for a validator keystore will be persisted.
@@ -82,6 +90,56 @@ pub struct Web3SignerDefinition {
     pub client_identity_password: Option<String>,
 }
 
+/// A remote signer which holds one share of a distributed validator's secret key.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct CoSignerDefinition {
+    /// The URL of a server which implements the Web3Signer signing API.
+    pub url: String,
+    /// The non-zero index of the key share, as assigned when the secret key was split.
+    pub share_index: u64,
+    /// The public key of the key share, used to verify partial signatures.
+    pub share_public_key: PublicKey,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct DistributedSignerDefinition {
+    /// The number of partial signatures required to produce a signature.
+    pub threshold: usize,
+    pub co_signers: Vec<CoSignerDefinition>,
+    /// Path to a .pem file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_certificate_path: Option<PathBuf>,
+    /// Specifies a request timeout for each co-signer.
+    ///
+    /// Co-signers which don't respond within the timeout are ignored, as long as `threshold`
+    /// others respond.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+}
+
+impl DistributedSignerDefinition {
+    /// Check that the threshold can be met and that the share indices are valid.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold == 0 || self.threshold > self.co_signers.len() {
+            return Err(format!(
+                "threshold {} must be between 1 and the number of co-signers ({})",
+                self.threshold,
+                self.co_signers.len()
+            ));
+        }
+        let mut indices = HashSet::new();
+        for co_signer in &self.co_signers {
+            if co_signer.share_index == 0 || !indices.insert(co_signer.share_index) {
+                return Err(format!(
+                    "share index {} is zero or duplicated",
+                    co_signer.share_index
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Defines how the validator client should attempt to sign messages for this validator.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -94,12 +152,20 @@ pub enum SigningDefinition {
         voting_keystore_password_path: Option<PathBuf>,
         #[serde(skip_serializing_if = "Option::is_none")]
         voting_keystore_password: Option<ZeroizeString>,
+        /// A secret storage backend which holds the password, used in place of
+        /// `voting_keystore_password_path`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        voting_keystore_password_source: Option<PasswordSource>,
     },
     /// A validator that defers to a Web3Signer HTTP server for signing.
     ///
     /// https://github.com/ConsenSys/web3signer
     #[serde(rename = "web3signer")]
     Web3Signer(Web3SignerDefinition),
+    /// A distributed validator whose secret key is split between several co-signers, a threshold
+    /// of which must sign each message.
+    #[serde(rename = "distributed")]
+    DistributedSigner(DistributedSignerDefinition),
 }
 
 impl SigningDefinition {
@@ -113,6 +179,10 @@ impl SigningDefinition {
                 voting_keystore_password: Some(password),
                 ..
             } => Ok(Some(password.clone())),
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: Some(_),
+                ..
+            } => Err(Error::KeystorePasswordInSource),
             SigningDefinition::LocalKeystore {
                 voting_keystore_password_path: Some(path),
                 ..
@@ -121,7 +191,7 @@ impl SigningDefinition {
                 .map(Option::Some)
                 .map_err(Error::UnableToReadKeystorePassword),
             SigningDefinition::LocalKeystore { .. } => Err(Error::KeystoreWithoutPassword),
-            SigningDefinition::Web3Signer(_) => Ok(None),
+            SigningDefinition::Web3Signer(_) | SigningDefinition::DistributedSigner(_) => Ok(None),
         }
     }
 
@@ -136,6 +206,95 @@ impl SigningDefinition {
     }
 }
 
+/// When a validator publishes its attestations during the slot.
+///
+/// Offsets are measured from the start of the slot. If no offset is given, 1/3 of the way through
+/// the slot is used (as per the spec). Offsets are capped at 2/3 of the way through the slot, when
+/// aggregates are produced.
+///
+/// Represented as a string of the form `fixed[:MILLIS]` or `block[:MILLIS]`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum AttestationTiming {
+    /// Attest at a fixed offset into the slot.
+    #[default]
+    Fixed(Option<Duration>),
+    /// Attest as soon as the beacon node imports a block for the slot, or at the offset if no
+    /// block has been imported by then.
+    Block(Option<Duration>),
+}
+
+impl AttestationTiming {
+    /// A short name for the strategy, used as a metrics label.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AttestationTiming::Fixed(_) => "fixed",
+            AttestationTiming::Block(_) => "block",
+        }
+    }
+
+    /// Returns the offset into a slot of `slot_duration` by which attestations must be produced.
+    pub fn deadline(&self, slot_duration: Duration) -> Duration {
+        let offset = match self {
+            AttestationTiming::Fixed(offset) | AttestationTiming::Block(offset) => *offset,
+        };
+        offset
+            .unwrap_or(slot_duration / 3)
+            .min(slot_duration * 2 / 3)
+    }
+}
+
+impl FromStr for AttestationTiming {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, offset) = match s.split_once(':') {
+            Some((name, millis)) => {
+                let millis = millis
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid attestation timing offset {}: {}", millis, e))?;
+                (name, Some(Duration::from_millis(millis)))
+            }
+            None => (s, None),
+        };
+        match name {
+            "fixed" => Ok(AttestationTiming::Fixed(offset)),
+            "block" => Ok(AttestationTiming::Block(offset)),
+            other => Err(format!(
+                "Unknown attestation timing {}, expected \"fixed\" or \"block\"",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for AttestationTiming {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttestationTiming::Fixed(None) | AttestationTiming::Block(None) => {
+                write!(f, "{}", self.name())
+            }
+            AttestationTiming::Fixed(Some(offset)) | AttestationTiming::Block(Some(offset)) => {
+                write!(f, "{}:{}", self.name(), offset.as_millis())
+            }
+        }
+    }
+}
+
+impl TryFrom<String> for AttestationTiming {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<AttestationTiming> for String {
+    fn from(timing: AttestationTiming) -> String {
+        timing.to_string()
+    }
+}
+
 /// A validator that may be initialized by this validator client.
 ///
 /// Presently there is only a single variant, however we expect more variants to arise (e.g.,
@@ -153,7 +312,7 @@ pub struct ValidatorDefinition {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gas_limit: Option<u64>,
-    #[serde(default)]
+    #[serde(default, alias = "builder_enabled")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub builder_proposals: Option<bool>,
     #[serde(default)]
@@ -162,6 +321,18 @@ pub struct ValidatorDefinition {
     #[serde(default)]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefer_builder_proposals: Option<bool>,
+    /// The beacon nodes which should be used for this validator's block proposals, most preferred
+    /// first.
+    ///
+    /// Nodes which are not listed in the validator client's `--beacon-nodes` or
+    /// `--proposer-nodes` are ignored.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub beacon_nodes: Option<Vec<SensitiveUrl>>,
+    /// Overrides the validator client's `--attestation-timing` for this validator.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attestation_timing: Option<AttestationTiming>,
     #[serde(default)]
     pub description: String,
     #[serde(flatten)]
@@ -207,10 +378,13 @@ impl ValidatorDefinition {
             builder_proposals,
             builder_boost_factor,
             prefer_builder_proposals,
+            beacon_nodes: None,
+            attestation_timing: None,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
                 voting_keystore_password_path,
                 voting_keystore_password,
+                voting_keystore_password_source: None,
             },
         })
     }
@@ -218,7 +392,7 @@ impl ValidatorDefinition {
 
 /// A list of `ValidatorDefinition` that serves as a serde-able configuration file which defines a
 /// list of validators to be initialized by this validator client.
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ValidatorDefinitions(Vec<ValidatorDefinition>);
 
 impl From<Vec<ValidatorDefinition>> for ValidatorDefinitions {
@@ -283,8 +457,9 @@ impl ValidatorDefinitions {
                     voting_keystore_path,
                     ..
                 } => Some(voting_keystore_path),
-                // A Web3Signer validator does not use a local keystore file.
-                SigningDefinition::Web3Signer { .. } => None,
+                // Remote signer validators do not use a local keystore file.
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::DistributedSigner { .. } => None,
             })
             .collect();
 
@@ -357,10 +532,13 @@ impl ValidatorDefinitions {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    beacon_nodes: None,
+                    attestation_timing: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
                         voting_keystore_password: None,
+                        voting_keystore_password_source: None,
                     },
                 })
             })
@@ -490,7 +668,6 @@ pub fn is_voting_keystore(file_name: &str) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::str::FromStr;
 
     #[test]
     fn voting_keystore_filename_lighthouse() {
@@ -687,4 +864,148 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_proposals).unwrap();
         assert_eq!(def.builder_proposals, Some(true));
     }
+
+    #[test]
+    fn distributed_signer_checks() {
+        let distributed = r#"---
+        description: ""
+        enabled: true
+        type: distributed
+        threshold: 2
+        request_timeout_ms: 2000
+        co_signers:
+          - url: "http://signer-1:9000"
+            share_index: 1
+            share_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+          - url: "http://signer-2:9000"
+            share_index: 2
+            share_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(distributed).unwrap();
+        let SigningDefinition::DistributedSigner(mut distributed) = def.signing_definition else {
+            panic!("expected a distributed signer");
+        };
+        assert_eq!(distributed.co_signers.len(), 2);
+        assert_eq!(distributed.request_timeout_ms, Some(2000));
+        assert!(distributed.validate().is_ok());
+
+        distributed.threshold = 3;
+        assert!(distributed.validate().is_err());
+
+        distributed.threshold = 2;
+        distributed.co_signers[1].share_index = 1;
+        assert!(distributed.validate().is_err());
+    }
+
+    #[test]
+    fn attestation_timing_checks() {
+        let slot_duration = Duration::from_secs(12);
+
+        let fixed = AttestationTiming::from_str("fixed").unwrap();
+        assert_eq!(fixed, AttestationTiming::default());
+        assert_eq!(fixed.deadline(slot_duration), Duration::from_secs(4));
+
+        let block = AttestationTiming::from_str("block:3500").unwrap();
+        assert_eq!(
+            block,
+            AttestationTiming::Block(Some(Duration::from_millis(3500)))
+        );
+        assert_eq!(block.deadline(slot_duration), Duration::from_millis(3500));
+        assert_eq!(block.to_string(), "block:3500");
+
+        let late = AttestationTiming::from_str("fixed:11000").unwrap();
+        assert_eq!(late.deadline(slot_duration), Duration::from_secs(8));
+
+        assert!(AttestationTiming::from_str("early").is_err());
+        assert!(AttestationTiming::from_str("block:soon").is_err());
+
+        let def = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        attestation_timing: "block:3500"
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(def).unwrap();
+        assert_eq!(def.attestation_timing, Some(block));
+    }
+
+    #[test]
+    fn password_source_checks() {
+        let no_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(no_source).unwrap();
+        assert!(matches!(
+            def.signing_definition,
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: None,
+                ..
+            }
+        ));
+
+        let vault_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_keystore_password_source:
+          backend: vault
+          url: "https://vault.example.com:8200"
+          mount: secret
+          path: validators/0xaf3c
+          token_path: /run/secrets/vault-token
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(vault_source).unwrap();
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_password_source: Some(source),
+            ..
+        } = &def.signing_definition
+        else {
+            panic!("expected a password source");
+        };
+        assert_eq!(
+            *source,
+            PasswordSource::Vault {
+                url: SensitiveUrl::parse("https://vault.example.com:8200").unwrap(),
+                mount: "secret".into(),
+                path: "validators/0xaf3c".into(),
+                key: None,
+                token_path: "/run/secrets/vault-token".into(),
+            }
+        );
+        assert!(matches!(
+            def.signing_definition.voting_keystore_password(),
+            Err(Error::KeystorePasswordInSource)
+        ));
+
+        let keyring_source = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_keystore_password_source:
+          backend: keyring
+          service: lighthouse
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(keyring_source).unwrap();
+        assert!(matches!(
+            def.signing_definition,
+            SigningDefinition::LocalKeystore {
+                voting_keystore_password_source: Some(PasswordSource::Keyring {
+                    username: None,
+                    ..
+                }),
+                ..
+            }
+        ));
+    }
 }
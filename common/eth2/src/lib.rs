@@ -29,7 +29,7 @@ use reqwest::{
 pub use reqwest::{StatusCode, Url};
 pub use sensitive_url::{SensitiveError, SensitiveUrl};
 use serde::{de::DeserializeOwned, Serialize};
-use ssz::Encode;
+use ssz::{Decode, Encode};
 use std::fmt;
 use std::future::Future;
 use std::path::PathBuf;
@@ -44,6 +44,8 @@ pub const CONSENSUS_VERSION_HEADER: &str = "Eth-Consensus-Version";
 pub const EXECUTION_PAYLOAD_BLINDED_HEADER: &str = "Eth-Execution-Payload-Blinded";
 pub const EXECUTION_PAYLOAD_VALUE_HEADER: &str = "Eth-Execution-Payload-Value";
 pub const CONSENSUS_BLOCK_VALUE_HEADER: &str = "Eth-Consensus-Block-Value";
+pub const EXECUTION_OPTIMISTIC_HEADER: &str = "Eth-Execution-Optimistic";
+pub const FINALIZED_HEADER: &str = "Eth-Finalized";
 
 pub const CONTENT_TYPE_HEADER: &str = "Content-Type";
 pub const SSZ_CONTENT_TYPE_HEADER: &str = "application/octet-stream";
@@ -284,6 +286,20 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Perform a HTTP GET request for an SSZ-encoded object, returning `None` on a 404 error.
+    async fn get_ssz_opt<T: Decode, U: IntoUrl>(&self, url: U) -> Result<Option<T>, Error> {
+        let opt_response = self
+            .get_response(url, |b| b.accept(Accept::Ssz))
+            .await
+            .optional()?;
+        match opt_response {
+            Some(resp) => T::from_ssz_bytes(&resp.bytes().await?)
+                .map(Some)
+                .map_err(Error::InvalidSsz),
+            None => Ok(None),
+        }
+    }
+
     /// Perform a HTTP GET request using an 'accept' header, returning `None` on a 404 error.
     pub async fn get_response_with_response_headers<U: IntoUrl, F, T>(
         &self,
@@ -493,6 +509,25 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/states/{state_id}/root` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_root_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Hash256>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("root");
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET beacon/states/{state_id}/fork`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -512,6 +547,25 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/states/{state_id}/fork` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_fork_ssz(
+        &self,
+        state_id: StateId,
+    ) -> Result<Option<Fork>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("fork");
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET beacon/states/{state_id}/finality_checkpoints`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -643,6 +697,58 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET v2/beacon/states/{state_id}/validators?id,status,page_token,limit`
+    ///
+    /// Returns a single page of at most `limit` validators, starting from `page_token`.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_states_validators_page(
+        &self,
+        state_id: StateId,
+        ids: Option<&[ValidatorId]>,
+        statuses: Option<&[ValidatorStatus]>,
+        page_token: Option<&str>,
+        limit: Option<usize>,
+    ) -> Result<Option<ValidatorsPage>, Error> {
+        let mut path = self.eth_path(V2)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("validators");
+
+        if let Some(ids) = ids {
+            let id_string = ids
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut().append_pair("id", &id_string);
+        }
+
+        if let Some(statuses) = statuses {
+            let status_string = statuses
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut().append_pair("status", &status_string);
+        }
+
+        if let Some(page_token) = page_token {
+            path.query_pairs_mut().append_pair("page_token", page_token);
+        }
+
+        if let Some(limit) = limit {
+            path.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+
+        self.get_opt(path).await
+    }
+
     /// `POST beacon/states/{state_id}/validators`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -894,6 +1000,24 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/headers/{block_id}` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_headers_block_id_ssz(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<SignedBeaconBlockHeader>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("headers")
+            .push(&block_id.to_string());
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `POST beacon/blocks`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -1166,6 +1290,28 @@ impl BeaconNodeHttpClient {
         Ok(Some(response.json().await?))
     }
 
+    /// `GET v1/beacon/blob_sidecars/{block_id}` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_blobs_ssz<E: EthSpec>(
+        &self,
+        block_id: BlockId,
+        indices: Option<&[u64]>,
+    ) -> Result<Option<BlobSidecarList<E>>, Error> {
+        let mut path = self.get_blobs_path(block_id)?;
+        if let Some(indices) = indices {
+            let indices_string = indices
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut()
+                .append_pair("indices", &indices_string);
+        }
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET v1/beacon/blinded_blocks/{block_id}`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -1255,6 +1401,25 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET beacon/blocks/{block_id}/root` as SSZ
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_blocks_root_ssz(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Hash256>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("blocks")
+            .push(&block_id.to_string())
+            .push("root");
+
+        self.get_ssz_opt(path).await
+    }
+
     /// `GET v1/beacon/blocks/{block_id}/attestations`
     ///
     /// Returns `Ok(None)` on a 404 error.
@@ -1294,6 +1459,51 @@ impl BeaconNodeHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET v2/beacon/blocks/{block_id}/attestations` as SSZ
+    ///
+    /// The attestations are decoded according to the fork in the `Eth-Consensus-Version` header.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_beacon_blocks_attestations_ssz<E: EthSpec>(
+        &self,
+        block_id: BlockId,
+    ) -> Result<Option<Vec<Attestation<E>>>, Error> {
+        let mut path = self.eth_path(V2)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("beacon")
+            .push("blocks")
+            .push(&block_id.to_string())
+            .push("attestations");
+
+        let Some(response) = self
+            .get_response(path, |b| b.accept(Accept::Ssz))
+            .await
+            .optional()?
+        else {
+            return Ok(None);
+        };
+        let fork_name = response
+            .headers()
+            .get(CONSENSUS_VERSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<ForkName>().ok())
+            .ok_or_else(|| {
+                Error::InvalidHeaders(format!("missing or invalid {CONSENSUS_VERSION_HEADER}"))
+            })?;
+        let bytes = response.bytes().await?;
+
+        let attestations = if fork_name.electra_enabled() {
+            Vec::<AttestationElectra<E>>::from_ssz_bytes(&bytes)
+                .map(|atts| atts.into_iter().map(Attestation::Electra).collect())
+        } else {
+            Vec::<AttestationBase<E>>::from_ssz_bytes(&bytes)
+                .map(|atts| atts.into_iter().map(Attestation::Base).collect())
+        };
+        attestations.map(Some).map_err(Error::InvalidSsz)
+    }
+
     /// `POST v1/beacon/pool/attestations`
     pub async fn post_beacon_pool_attestations_v1<E: EthSpec>(
         &self,
@@ -1574,36 +1784,40 @@ impl BeaconNodeHttpClient {
             .map(|opt| opt.map(|r| r.data))
     }
 
-    /// `POST beacon/rewards/sync_committee`
+    /// `POST beacon/rewards/sync_committee/{block_id}`
     pub async fn post_beacon_rewards_sync_committee(
         &self,
-        rewards: &[Option<Vec<lighthouse::SyncCommitteeReward>>],
-    ) -> Result<(), Error> {
+        block_id: BlockId,
+        validators: &[ValidatorId],
+    ) -> Result<
+        ExecutionOptimisticFinalizedResponse<Option<Vec<lighthouse::SyncCommitteeReward>>>,
+        Error,
+    > {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("sync_committee");
-
-        self.post(path, &rewards).await?;
+            .push("sync_committee")
+            .push(&block_id.to_string());
 
-        Ok(())
+        self.post_with_response(path, &validators).await
     }
 
-    /// `GET beacon/rewards/blocks`
-    pub async fn get_beacon_rewards_blocks(&self, epoch: Epoch) -> Result<(), Error> {
+    /// `GET beacon/rewards/blocks/{block_id}`
+    pub async fn get_beacon_rewards_blocks(
+        &self,
+        block_id: BlockId,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<lighthouse::StandardBlockReward>, Error> {
         let mut path = self.eth_path(V1)?;
 
         path.path_segments_mut()
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("beacon")
             .push("rewards")
-            .push("blocks");
-
-        path.query_pairs_mut()
-            .append_pair("epoch", &epoch.to_string());
+            .push("blocks")
+            .push(&block_id.to_string());
 
         self.get(path).await
     }
@@ -1643,6 +1857,30 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    // GET builder/states/{state_id}/expected_withdrawals?proof=true
+    pub async fn get_expected_withdrawals_with_proof(
+        &self,
+        state_id: &StateId,
+        proposal_slot: Option<Slot>,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<ExpectedWithdrawalsWithProof>, Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("builder")
+            .push("states")
+            .push(&state_id.to_string())
+            .push("expected_withdrawals");
+
+        path.query_pairs_mut().append_pair("proof", "true");
+        if let Some(proposal_slot) = proposal_slot {
+            path.query_pairs_mut()
+                .append_pair("proposal_slot", &proposal_slot.to_string());
+        }
+
+        self.get(path).await
+    }
+
     /// `POST validator/contribution_and_proofs`
     pub async fn post_validator_contribution_and_proofs<E: EthSpec>(
         &self,
@@ -2604,6 +2842,51 @@ impl BeaconNodeHttpClient {
             }))
     }
 
+    /// `GET events?topics`, resuming after `last_event_id` if the server retains missed events.
+    ///
+    /// Each event is returned with its ID, if the server sent one.
+    pub async fn get_events_since<E: EthSpec>(
+        &self,
+        topic: &[EventTopic],
+        last_event_id: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<(Option<u64>, EventKind<E>), Error>>, Error> {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("events");
+
+        let topic_string = topic
+            .iter()
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        path.query_pairs_mut().append_pair("topics", &topic_string);
+
+        let mut request = self.client.get(path);
+        if let Some(last_event_id) = last_event_id {
+            request = request.header("Last-Event-ID", last_event_id.to_string());
+        }
+
+        Ok(request.send().await?.bytes_stream().flat_map(|next| {
+            // Replayed events may arrive together, so parse every event in the chunk.
+            let events = match next {
+                Ok(bytes) => String::from_utf8_lossy(bytes.as_ref())
+                    .split("\n\n")
+                    .filter(|event| !event.trim().is_empty())
+                    .map(|event| {
+                        let id = event
+                            .lines()
+                            .find_map(|line| line.strip_prefix("id:"))
+                            .and_then(|id| id.trim().parse().ok());
+                        Ok((id, EventKind::from_sse_bytes(event.as_bytes())?))
+                    })
+                    .collect::<Vec<_>>(),
+                Err(e) => vec![Err(Error::HttpClient(e.into()))],
+            };
+            futures::stream::iter(events)
+        }))
+    }
+
     /// `POST validator/duties/sync/{epoch}`
     pub async fn post_validator_duties_sync(
         &self,
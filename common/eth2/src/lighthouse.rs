@@ -1,24 +1,37 @@
 //! This module contains endpoints that are non-standard and only available on Lighthouse servers.
 
+mod admin;
 mod attestation_performance;
 pub mod attestation_rewards;
 mod block_packing_efficiency;
 mod block_rewards;
+mod committee_assignment;
+mod dry_run_block;
+mod fork_choice_graph;
+mod head_timeline;
+mod optimistic_status;
+mod orphans;
 mod standard_block_rewards;
 mod sync_committee_rewards;
+mod validator_duties;
+mod validator_projection;
 
 use crate::{
+    ok_or_error,
     types::{
         DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
 use proto_array::core::ProtoArray;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
 use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
 
+pub use admin::{
+    BeaconProcessorQueueUpdate, LogLevel, ValidatorMonitorRequest, ValidatorMonitorStatus,
+};
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
@@ -27,9 +40,23 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use committee_assignment::CommitteeAssignmentWithProof;
+pub use dry_run_block::{DryRunBlock, DryRunBlockQuery};
+pub use fork_choice_graph::{
+    ForkChoiceGraph, ForkChoiceGraphFormat, ForkChoiceGraphNode, ForkChoiceGraphQuery,
+};
+pub use head_timeline::{HeadTimelineEvent, HeadTimelineEventKind};
+pub use lighthouse_network::{
+    types::{FailoverRole, SyncState},
+    GossipsubScoreBreakdown, PeerInfo, PenaltyRecord, Score, ScoreState, Subnet,
+    TopicScoreBreakdown,
+};
+pub use optimistic_status::{OptimisticStatus, OptimisticStatusQuery};
+pub use orphans::{OrphanedAttestationInfo, OrphanedBlockInfo, OrphansQuery};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
+pub use validator_duties::{EpochDuties, ValidatorDutiesRequest};
+pub use validator_projection::{ProjectedBalance, ValidatorProjection, ValidatorProjectionRequest};
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
 // selector.
@@ -47,6 +74,71 @@ pub struct Peer<E: EthSpec> {
     pub peer_info: PeerInfo<E>,
 }
 
+/// Information returned by `peers/{peer_id}/score_breakdown`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PeerScoreBreakdown {
+    /// The Peer's ID
+    pub peer_id: String,
+    /// The peer's overall score.
+    pub score: Score,
+    /// The state the peer's score places it in.
+    pub score_state: ScoreState,
+    /// The components of the peer's gossipsub score, if the peer is scored by gossipsub.
+    pub gossipsub: Option<GossipsubScoreBreakdown>,
+    /// The most recent penalties applied to the peer's score, oldest first.
+    pub penalties: Vec<PenaltyRecord>,
+    /// The subnets the peer is subscribed to.
+    pub subnets: Vec<Subnet>,
+}
+
+/// Information returned by `peers/score_summary`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PeerScoreSummary {
+    /// The number of connected peers.
+    pub connected: usize,
+    /// The number of known peers whose score is healthy.
+    pub healthy: usize,
+    /// The number of known peers whose score warrants a disconnection.
+    pub disconnect: usize,
+    /// The number of known peers whose score warrants a ban.
+    pub banned: usize,
+    /// The lowest score among connected peers.
+    pub min_connected_score: Option<f64>,
+    /// The mean score among connected peers.
+    pub mean_connected_score: Option<f64>,
+}
+
+/// Information returned by `failover`, used by the nodes of a failover pair to elect a leader.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailoverStatus {
+    /// The current role of the node.
+    pub role: FailoverRole,
+    /// The priority of the node, or `None` if it is not configured for failover. Of two healthy
+    /// nodes, the one with the lowest priority is active.
+    pub priority: Option<u64>,
+    /// Whether the node is synced and its execution layer is online.
+    pub is_healthy: bool,
+}
+
+/// The state of a beacon processor queue, as returned by `beacon_processor`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorQueue {
+    /// The type of work held in the queue.
+    pub name: String,
+    pub length: usize,
+    pub max_length: usize,
+    /// Whether workers have been prevented from processing the queue via the admin API.
+    pub paused: bool,
+    /// Whether the queue has been moved ahead of the others via the admin API.
+    pub prioritized: bool,
+    /// The number of items dropped because the queue was full.
+    pub dropped: u64,
+    /// The number of items taken from the queue by a worker.
+    pub processed: u64,
+    /// The mean time processed items spent waiting in the queue, in milliseconds.
+    pub mean_wait_ms: f64,
+}
+
 /// The results of validators voting during an epoch.
 ///
 /// Provides information about the current and previous epochs.
@@ -365,6 +457,25 @@ pub struct DatabaseInfo {
     pub blob_info: BlobInfo,
 }
 
+/// The result of comparing the age of the node's finalized checkpoint to its weak subjectivity
+/// period.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct WeakSubjectivityStatus {
+    pub current_epoch: Epoch,
+    /// The epoch of the finalized checkpoint that the node is anchored to.
+    pub anchor_epoch: Epoch,
+    /// The number of epochs between the anchor epoch and the current epoch.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub anchor_age_epochs: u64,
+    /// The weak subjectivity period in epochs, computed from the head state's validator set.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub weak_subjectivity_period: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub active_validator_count: u64,
+    /// False if the anchor is older than the weak subjectivity period.
+    pub within_weak_subjectivity_period: bool,
+}
+
 impl BeaconNodeHttpClient {
     /// `GET lighthouse/health`
     pub async fn get_lighthouse_health(&self) -> Result<GenericResponse<Health>, Error> {
@@ -390,6 +501,32 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/failover`
+    pub async fn get_lighthouse_failover(&self) -> Result<GenericResponse<FailoverStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("failover");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon_processor`
+    pub async fn get_lighthouse_beacon_processor(
+        &self,
+    ) -> Result<GenericResponse<Vec<BeaconProcessorQueue>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor");
+
+        self.get(path).await
+    }
+
     /*
      * Note:
      *
@@ -410,6 +547,217 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/fork_choice/graph`
+    pub async fn get_lighthouse_fork_choice_graph(
+        &self,
+    ) -> Result<GenericResponse<ForkChoiceGraph>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("graph");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/fork_choice/graph?format=dot`
+    pub async fn get_lighthouse_fork_choice_graph_dot(&self) -> Result<String, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("graph");
+
+        path.query_pairs_mut().append_pair("format", "dot");
+
+        Ok(self.get_response(path, |b| b).await?.text().await?)
+    }
+
+    /// `GET lighthouse/optimistic_status`
+    ///
+    /// Returns the status of the head block if `block_root` is `None`.
+    pub async fn get_lighthouse_optimistic_status(
+        &self,
+        block_root: Option<Hash256>,
+    ) -> Result<GenericResponse<OptimisticStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("optimistic_status");
+
+        if let Some(block_root) = block_root {
+            path.query_pairs_mut()
+                .append_pair("block_root", &format!("{:?}", block_root));
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/weak_subjectivity`
+    pub async fn get_lighthouse_weak_subjectivity(
+        &self,
+    ) -> Result<GenericResponse<WeakSubjectivityStatus>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("weak_subjectivity");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/head_timeline/{slot}`
+    pub async fn get_lighthouse_head_timeline(
+        &self,
+        slot: Slot,
+    ) -> Result<GenericResponse<Vec<HeadTimelineEvent>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("head_timeline")
+            .push(&slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/validator/dry_run_block/{slot}?skip_payload`
+    pub async fn get_lighthouse_validator_dry_run_block<E: EthSpec>(
+        &self,
+        slot: Slot,
+        skip_payload: bool,
+    ) -> Result<GenericResponse<DryRunBlock<E>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator")
+            .push("dry_run_block")
+            .push(&slot.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("skip_payload", &skip_payload.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/orphans?start_slot,end_slot`
+    pub async fn get_lighthouse_orphans(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<GenericResponse<Vec<OrphanedBlockInfo>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("orphans");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/orphans/attestations?start_slot,end_slot`
+    pub async fn get_lighthouse_orphaned_attestations<E: EthSpec>(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+    ) -> Result<GenericResponse<Vec<OrphanedAttestationInfo<E>>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("orphans")
+            .push("attestations");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validators/duties`
+    pub async fn post_lighthouse_validators_duties(
+        &self,
+        indices: &[u64],
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<GenericResponse<Vec<EpochDuties>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("duties");
+
+        self.post_with_response(
+            path,
+            &ValidatorDutiesRequest {
+                indices: indices.to_vec(),
+                start_epoch,
+                end_epoch,
+            },
+        )
+        .await
+    }
+
+    /// `GET lighthouse/committee_assignment/{epoch}/{validator_id}`
+    pub async fn get_lighthouse_committee_assignment(
+        &self,
+        epoch: Epoch,
+        validator_id: &ValidatorId,
+    ) -> Result<GenericResponse<CommitteeAssignmentWithProof>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("committee_assignment")
+            .push(&epoch.to_string())
+            .push(&validator_id.to_string());
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/validator_projection`
+    pub async fn post_lighthouse_validator_projection(
+        &self,
+        indices: &[u64],
+        epochs: u64,
+    ) -> Result<GenericResponse<Vec<ValidatorProjection>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator_projection");
+
+        self.post_with_response(
+            path,
+            &ValidatorProjectionRequest {
+                indices: indices.to_vec(),
+                epochs,
+            },
+        )
+        .await
+    }
+
     /// `GET lighthouse/validator_inclusion/{epoch}/global`
     pub async fn get_lighthouse_validator_inclusion_global(
         &self,
@@ -528,6 +876,164 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /*
+     Admin endpoints.
+    */
+
+    /// Perform a HTTP POST request to `lighthouse/admin/{path}`, authenticated with `token`.
+    async fn post_lighthouse_admin<T: Serialize, R: DeserializeOwned>(
+        &self,
+        token: &str,
+        path_segments: &[&str],
+        body: &T,
+    ) -> Result<R, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("admin")
+            .extend(path_segments);
+
+        let response = self
+            .client
+            .post(path)
+            .bearer_auth(token)
+            .json(body)
+            .send()
+            .await?;
+        ok_or_error(response)
+            .await?
+            .json()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Perform a HTTP GET request to `lighthouse/admin/{path}`, authenticated with `token`.
+    async fn get_lighthouse_admin(
+        &self,
+        token: &str,
+        path_segments: &[&str],
+    ) -> Result<reqwest::Response, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("admin")
+            .extend(path_segments);
+
+        let response = self.client.get(path).bearer_auth(token).send().await?;
+        ok_or_error(response).await
+    }
+
+    /// `POST lighthouse/admin/prune`
+    pub async fn post_lighthouse_admin_prune(&self, token: &str) -> Result<String, Error> {
+        self.post_lighthouse_admin(token, &["prune"], &()).await
+    }
+
+    /// `POST lighthouse/admin/peers/{peer_id}/disconnect`
+    pub async fn post_lighthouse_admin_peer_disconnect(
+        &self,
+        token: &str,
+        peer_id: &str,
+    ) -> Result<String, Error> {
+        self.post_lighthouse_admin(token, &["peers", peer_id, "disconnect"], &())
+            .await
+    }
+
+    /// `POST lighthouse/admin/resubscribe`
+    pub async fn post_lighthouse_admin_resubscribe(&self, token: &str) -> Result<String, Error> {
+        self.post_lighthouse_admin(token, &["resubscribe"], &())
+            .await
+    }
+
+    /// `POST lighthouse/admin/flush_caches`
+    pub async fn post_lighthouse_admin_flush_caches(&self, token: &str) -> Result<String, Error> {
+        self.post_lighthouse_admin(token, &["flush_caches"], &())
+            .await
+    }
+
+    /// `POST lighthouse/admin/log_level`
+    pub async fn post_lighthouse_admin_log_level(
+        &self,
+        token: &str,
+        level: &str,
+    ) -> Result<GenericResponse<LogLevel>, Error> {
+        let request = LogLevel {
+            level: level.to_string(),
+            module: None,
+        };
+        self.post_lighthouse_admin(token, &["log_level"], &request)
+            .await
+    }
+
+    /// `POST lighthouse/admin/log_level` for a single module.
+    pub async fn post_lighthouse_admin_module_log_level(
+        &self,
+        token: &str,
+        module: &str,
+        level: &str,
+    ) -> Result<GenericResponse<LogLevel>, Error> {
+        let request = LogLevel {
+            level: level.to_string(),
+            module: Some(module.to_string()),
+        };
+        self.post_lighthouse_admin(token, &["log_level"], &request)
+            .await
+    }
+
+    /// `POST lighthouse/admin/beacon_processor`
+    pub async fn post_lighthouse_admin_beacon_processor(
+        &self,
+        token: &str,
+        update: &BeaconProcessorQueueUpdate,
+    ) -> Result<GenericResponse<BeaconProcessorQueue>, Error> {
+        self.post_lighthouse_admin(token, &["beacon_processor"], update)
+            .await
+    }
+
+    /// `POST lighthouse/admin/validator_monitor`
+    pub async fn post_lighthouse_admin_validator_monitor(
+        &self,
+        token: &str,
+        auto_register: bool,
+    ) -> Result<GenericResponse<ValidatorMonitorStatus>, Error> {
+        self.post_lighthouse_admin(
+            token,
+            &["validator_monitor"],
+            &ValidatorMonitorRequest { auto_register },
+        )
+        .await
+    }
+
+    /// `GET lighthouse/admin/payload_forensics`
+    pub async fn get_lighthouse_admin_payload_forensics(
+        &self,
+        token: &str,
+    ) -> Result<GenericResponse<Vec<String>>, Error> {
+        self.get_lighthouse_admin(token, &["payload_forensics"])
+            .await?
+            .json()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// `GET lighthouse/admin/payload_forensics/{bundle}/{file}`
+    pub async fn get_lighthouse_admin_payload_forensics_file(
+        &self,
+        token: &str,
+        bundle: &str,
+        file: &str,
+    ) -> Result<Vec<u8>, Error> {
+        let bytes = self
+            .get_lighthouse_admin(token, &["payload_forensics", bundle, file])
+            .await?
+            .bytes()
+            .await?;
+        Ok(bytes.to_vec())
+    }
+
     /*
      Analysis endpoints.
     */
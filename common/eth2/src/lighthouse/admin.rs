@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// The level of the beacon node's terminal logs, as used by `POST lighthouse/admin/log_level`.
+///
+/// One of `crit`, `error`, `warn`, `info`, `debug` or `trace`. If `module` is set, the level only
+/// applies to that module and its submodules (e.g. `network::sync`), and `default` removes the
+/// override for the module.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LogLevel {
+    pub level: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub module: Option<String>,
+}
+
+/// Request body for `POST lighthouse/admin/beacon_processor`.
+///
+/// Fields which are `None` are left unchanged.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct BeaconProcessorQueueUpdate {
+    /// The name of the queue, as returned by `GET lighthouse/beacon_processor`.
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub paused: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prioritized: Option<bool>,
+}
+
+/// Request body for `POST lighthouse/admin/validator_monitor`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMonitorRequest {
+    /// Whether validators which use this beacon node should be monitored automatically.
+    pub auto_register: bool,
+}
+
+/// The state of the validator monitor after a `POST lighthouse/admin/validator_monitor`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorMonitorStatus {
+    pub auto_register: bool,
+    pub monitored_validators: usize,
+}
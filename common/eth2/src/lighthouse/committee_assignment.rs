@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use types::{AttestationDuty, Epoch, Hash256, Slot, StateProof};
+
+/// Response for the `/lighthouse/committee_assignment/{epoch}/{validator_id}` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct CommitteeAssignmentWithProof {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub epoch: Epoch,
+    /// The assignment, or `None` if the validator is not active in the epoch.
+    pub duty: Option<AttestationDuty>,
+    /// The root and slot of the head state which the proofs are against.
+    pub state_root: Hash256,
+    pub state_slot: Slot,
+    /// The seed of the shuffling for the epoch.
+    pub seed: Hash256,
+    /// A proof of the RANDAO mix from which the seed is generated.
+    pub randao_mix: StateProof,
+    /// A proof of the validator's record.
+    pub validator: StateProof,
+}
@@ -0,0 +1,34 @@
+use crate::types::SseBlockProductionStage;
+use serde::{Deserialize, Serialize};
+use types::{BeaconBlock, EthSpec};
+
+/// Query parameters for the `/lighthouse/validator/dry_run_block/{slot}` endpoint.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct DryRunBlockQuery {
+    /// Skip requesting a payload from the execution layer, using a placeholder payload instead.
+    #[serde(default)]
+    pub skip_payload: bool,
+}
+
+/// A block produced without being signed or published, as returned by
+/// `/lighthouse/validator/dry_run_block/{slot}`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct DryRunBlock<E: EthSpec> {
+    /// The unsigned block, which must not be published.
+    pub block: BeaconBlock<E>,
+    /// `true` if the execution payload was fetched from the execution layer, rather than being a
+    /// placeholder.
+    pub payload_fetched: bool,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub consensus_block_value: u64,
+    /// The time spent loading the parent state, in milliseconds.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub state_load_ms: u64,
+    /// The total time spent producing the block, including loading the parent state, in
+    /// milliseconds.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_ms: u64,
+    /// The time spent in each stage of producing the block on the parent state.
+    pub stages: Vec<SseBlockProductionStage>,
+}
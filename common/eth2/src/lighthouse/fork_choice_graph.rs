@@ -0,0 +1,170 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use types::{Checkpoint, Epoch, Hash256, Slot};
+
+/// A snapshot of the fork choice block tree, suitable for visualising forks.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForkChoiceGraph {
+    pub head_root: Hash256,
+    pub justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+    pub nodes: Vec<ForkChoiceGraphNode>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForkChoiceGraphNode {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub parent_root: Option<Hash256>,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub weight: u64,
+    /// The execution status of the block, e.g. `valid`, `optimistic` or `invalid`.
+    pub execution_status: String,
+    pub justified_epoch: Epoch,
+    pub finalized_epoch: Epoch,
+    /// True if the block is the fork choice justified checkpoint block.
+    pub is_justified: bool,
+    /// True if the block is the fork choice finalized checkpoint block.
+    pub is_finalized: bool,
+    /// True if the block is the head of the chain.
+    pub is_head: bool,
+}
+
+impl ForkChoiceGraphNode {
+    pub fn is_invalid(&self) -> bool {
+        self.execution_status == "invalid"
+    }
+}
+
+impl ForkChoiceGraph {
+    /// Render the graph in the Graphviz DOT language.
+    ///
+    /// Edges point from each block to its parent. The head is drawn in bold, the justified and
+    /// finalized checkpoint blocks are drawn as double octagons and invalid blocks are coloured
+    /// red.
+    pub fn to_dot(&self) -> String {
+        let mut dot =
+            String::from("digraph fork_choice {\n    rankdir=RL;\n    node [shape=box];\n");
+
+        for node in &self.nodes {
+            let mut markers = vec![];
+            if node.is_head {
+                markers.push("head");
+            }
+            if node.is_justified {
+                markers.push("justified");
+            }
+            if node.is_finalized {
+                markers.push("finalized");
+            }
+
+            let mut attributes = vec![format!(
+                "label=\"{}\\nslot {}\\nweight {}\\n{}{}\"",
+                short_root(node.block_root),
+                node.slot,
+                node.weight,
+                node.execution_status,
+                if markers.is_empty() {
+                    String::new()
+                } else {
+                    format!("\\n{}", markers.join(", "))
+                }
+            )];
+            if node.is_head {
+                attributes.push("style=bold".into());
+            }
+            if node.is_justified || node.is_finalized {
+                attributes.push("shape=doubleoctagon".into());
+            }
+            if node.is_invalid() {
+                attributes.push("color=red".into());
+            }
+
+            let _ = writeln!(
+                dot,
+                "    \"{:?}\" [{}];",
+                node.block_root,
+                attributes.join(", ")
+            );
+            if let Some(parent_root) = node.parent_root {
+                let _ = writeln!(
+                    dot,
+                    "    \"{:?}\" -> \"{:?}\";",
+                    node.block_root, parent_root
+                );
+            }
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Format the first four bytes of `root` in hex, which is enough to distinguish blocks visually.
+fn short_root(root: Hash256) -> String {
+    let mut short = String::from("0x");
+    for byte in &root.as_slice()[..4] {
+        let _ = write!(short, "{byte:02x}");
+    }
+    short
+}
+
+/// The format in which to return the fork choice graph.
+#[derive(Debug, Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForkChoiceGraphFormat {
+    #[default]
+    Json,
+    Dot,
+}
+
+/// Query parameters for the `/lighthouse/fork_choice/graph` endpoint.
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ForkChoiceGraphQuery {
+    #[serde(default)]
+    pub format: ForkChoiceGraphFormat,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dot_contains_nodes_and_edges() {
+        let genesis = Hash256::repeat_byte(0xaa);
+        let head = Hash256::repeat_byte(0xbb);
+        let checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: genesis,
+        };
+        let node = |block_root, parent_root, slot, is_head| ForkChoiceGraphNode {
+            slot: Slot::new(slot),
+            block_root,
+            parent_root,
+            weight: 32,
+            execution_status: "valid".into(),
+            justified_epoch: Epoch::new(0),
+            finalized_epoch: Epoch::new(0),
+            is_justified: block_root == genesis,
+            is_finalized: block_root == genesis,
+            is_head,
+        };
+        let graph = ForkChoiceGraph {
+            head_root: head,
+            justified_checkpoint: checkpoint,
+            finalized_checkpoint: checkpoint,
+            nodes: vec![
+                node(genesis, None, 0, false),
+                node(head, Some(genesis), 1, true),
+            ],
+        };
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph fork_choice {"));
+        assert!(dot.contains(&format!("\"{head:?}\" -> \"{genesis:?}\";")));
+        assert!(dot.contains("0xbbbbbbbb"));
+        assert!(dot.contains("justified, finalized"));
+        assert!(dot.contains("style=bold"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// An event which may change the head, as returned by `/lighthouse/head_timeline/{slot}`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct HeadTimelineEvent {
+    /// The number of milliseconds between the start of the slot and the event.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub delay_ms: u64,
+    #[serde(flatten)]
+    pub kind: HeadTimelineEventKind,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HeadTimelineEventKind {
+    /// A block was received (e.g., from gossip), before it was verified.
+    BlockObserved { block_root: Hash256, slot: Slot },
+    /// A block was imported into fork choice.
+    BlockImported { block_root: Hash256, slot: Slot },
+    /// Proposer boost was applied to a timely block.
+    ProposerBoostApplied { block_root: Hash256 },
+    /// Proposer boost was removed from a block, at the start of the next slot.
+    ProposerBoostExpired { block_root: Hash256 },
+    /// Fork choice selected a new head.
+    HeadChanged {
+        previous_head_root: Hash256,
+        head_root: Hash256,
+        head_slot: Slot,
+        /// `true` if the new head does not descend from the previous head, i.e., the attestation
+        /// weight of another chain has overtaken that of the previous head.
+        reorg: bool,
+        /// The fork choice weight of the previous head, including any proposer boost.
+        #[serde(with = "serde_utils::quoted_u64")]
+        previous_head_weight: u64,
+        /// The fork choice weight of the new head, including any proposer boost.
+        #[serde(with = "serde_utils::quoted_u64")]
+        head_weight: u64,
+    },
+}
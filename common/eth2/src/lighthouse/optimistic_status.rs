@@ -0,0 +1,33 @@
+use crate::types::SseOptimisticTransition;
+use serde::{Deserialize, Serialize};
+use types::{ExecutionBlockHash, Hash256, Slot};
+
+/// The execution status of a block in fork choice, as returned by
+/// `/lighthouse/optimistic_status`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OptimisticStatus {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub execution_block_hash: Option<ExecutionBlockHash>,
+    /// One of `valid`, `invalid`, `optimistic` or `irrelevant`.
+    pub execution_status: String,
+    /// `true` if the block is the current head of the chain.
+    pub is_head: bool,
+    /// The closest ancestor (or the block itself) which is not optimistic, if it is still in fork
+    /// choice.
+    pub latest_valid_ancestor_root: Option<Hash256>,
+    pub latest_valid_ancestor_slot: Option<Slot>,
+    /// The number of slots between the block and its latest valid ancestor. This is `0` for
+    /// blocks which are not optimistic.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub optimistic_distance: u64,
+    /// The most recent change of the block's execution status, if one has been observed.
+    pub last_transition: Option<SseOptimisticTransition>,
+}
+
+/// Query parameters for the `/lighthouse/optimistic_status` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OptimisticStatusQuery {
+    /// The block to return the status of. Defaults to the head block.
+    pub block_root: Option<Hash256>,
+}
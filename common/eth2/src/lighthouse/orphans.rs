@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use store::orphaned_blocks::OrphanReason;
+use types::{Attestation, AttestationData, EthSpec, Hash256, SignedBeaconBlockHeader, Slot};
+
+/// A block which lost fork choice and was moved to the orphaned block archive.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OrphanedBlockInfo {
+    pub block_root: Hash256,
+    pub reason: OrphanReason,
+    /// The signed header of the block, sufficient to construct a proposer slashing.
+    pub signed_header: SignedBeaconBlockHeader,
+    /// The data of each attestation included in the block.
+    pub attestations: Vec<AttestationData>,
+}
+
+impl OrphanedBlockInfo {
+    pub fn slot(&self) -> Slot {
+        self.signed_header.message.slot
+    }
+}
+
+/// An attestation included in a block which was moved to the orphaned block archive.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct OrphanedAttestationInfo<E: EthSpec> {
+    /// The root of the orphaned block which included the attestation.
+    pub block_root: Hash256,
+    pub attestation: Attestation<E>,
+}
+
+/// Query parameters for the `/lighthouse/orphans` endpoints.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct OrphansQuery {
+    /// Lower slot limit for orphaned blocks or attestations returned (inclusive).
+    pub start_slot: Slot,
+    /// Upper slot limit for orphaned blocks or attestations returned (inclusive).
+    pub end_slot: Slot,
+}
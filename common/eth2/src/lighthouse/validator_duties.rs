@@ -0,0 +1,24 @@
+use crate::types::{AttesterData, DutiesResponse, ExecutionOptimisticResponse, ProposerData};
+use serde::{Deserialize, Serialize};
+use types::{Epoch, SyncDuty};
+
+/// Request body for the `/lighthouse/validators/duties` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorDutiesRequest {
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub indices: Vec<u64>,
+    /// The first epoch to return duties for (inclusive).
+    pub start_epoch: Epoch,
+    /// The last epoch to return duties for (inclusive).
+    pub end_epoch: Epoch,
+}
+
+/// The duties of the requested validators during a single epoch.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct EpochDuties {
+    pub epoch: Epoch,
+    pub attester: DutiesResponse<Vec<AttesterData>>,
+    /// Proposer duties, restricted to the requested validators.
+    pub proposer: DutiesResponse<Vec<ProposerData>>,
+    pub sync: ExecutionOptimisticResponse<Vec<SyncDuty>>,
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use types::Epoch;
+
+/// Request body for the `/lighthouse/validator_projection` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorProjectionRequest {
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub indices: Vec<u64>,
+    /// The number of epochs following the current epoch to project.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub epochs: u64,
+}
+
+/// The projected balances of a single validator.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorProjection {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// The balances at the head, followed by the balances at the start of each projected epoch.
+    pub balances: Vec<ProjectedBalance>,
+}
+
+/// The projected balances of a validator at the start of an epoch.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProjectedBalance {
+    pub epoch: Epoch,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub balance: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub effective_balance: u64,
+    /// The sum of the deposits to the validator which are yet to be processed.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub pending_deposits: u64,
+}
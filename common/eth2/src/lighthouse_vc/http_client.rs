@@ -680,6 +680,129 @@ impl ValidatorClientHttpClient {
         let url = self.make_graffiti_url(pubkey)?;
         self.delete(url).await
     }
+
+    fn make_doppelganger_url(&self) -> Result<Url, Error> {
+        let mut url = self.server.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("doppelganger");
+        Ok(url)
+    }
+
+    /// `GET lighthouse/doppelganger`
+    pub async fn get_lighthouse_doppelganger(
+        &self,
+    ) -> Result<GenericResponse<Vec<DoppelgangerStatusData>>, Error> {
+        let url = self.make_doppelganger_url()?;
+        self.get(url).await
+    }
+
+    /// `GET lighthouse/doppelganger/{validator_pubkey}`
+    pub async fn get_lighthouse_doppelganger_pubkey(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+    ) -> Result<Option<GenericResponse<DoppelgangerStatusData>>, Error> {
+        let mut url = self.make_doppelganger_url()?;
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push(&validator_pubkey.to_string());
+        self.get_opt(url).await
+    }
+
+    /// `POST lighthouse/doppelganger/{validator_pubkey}`
+    pub async fn post_lighthouse_doppelganger_pubkey(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        remaining_epochs: u64,
+    ) -> Result<GenericResponse<DoppelgangerStatusData>, Error> {
+        let mut url = self.make_doppelganger_url()?;
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push(&validator_pubkey.to_string());
+        self.post(url, &UpdateDoppelgangerRequest { remaining_epochs })
+            .await
+    }
+
+    fn make_exits_url(&self) -> Result<Url, Error> {
+        let mut url = self.server.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("exits");
+        Ok(url)
+    }
+
+    /// `GET lighthouse/exits`
+    pub async fn get_lighthouse_exits(
+        &self,
+    ) -> Result<GenericResponse<Vec<PresignedExitData>>, Error> {
+        let url = self.make_exits_url()?;
+        self.get(url).await
+    }
+
+    /// `POST lighthouse/exits`
+    pub async fn post_lighthouse_exits(
+        &self,
+        req: &PresignExitsRequest,
+    ) -> Result<GenericResponse<Vec<PresignedExitData>>, Error> {
+        let url = self.make_exits_url()?;
+        self.post(url, req).await
+    }
+
+    /// `POST lighthouse/exits/broadcast`
+    pub async fn post_lighthouse_exits_broadcast(
+        &self,
+        req: &BroadcastExitsRequest,
+    ) -> Result<GenericResponse<Vec<PresignedExitData>>, Error> {
+        let mut url = self.make_exits_url()?;
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("broadcast");
+        self.post(url, req).await
+    }
+
+    /// `DELETE lighthouse/exits`
+    pub async fn delete_lighthouse_exits(
+        &self,
+        req: &DeletePresignedExitsRequest,
+    ) -> Result<GenericResponse<Vec<PublicKeyBytes>>, Error> {
+        let url = self.make_exits_url()?;
+        self.delete_with_unsigned_response(url, req).await
+    }
+
+    fn make_slashing_protection_url(&self) -> Result<Url, Error> {
+        let mut url = self.server.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slashing_protection");
+        Ok(url)
+    }
+
+    /// `POST lighthouse/slashing_protection/export`
+    pub async fn post_lighthouse_slashing_protection_export(
+        &self,
+        req: &ExportSlashingProtectionRequest,
+    ) -> Result<GenericResponse<Interchange>, Error> {
+        let mut url = self.make_slashing_protection_url()?;
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("export");
+        self.post(url, req).await
+    }
+
+    /// `POST lighthouse/slashing_protection/check`
+    pub async fn post_lighthouse_slashing_protection_check(
+        &self,
+        req: &Interchange,
+    ) -> Result<GenericResponse<Vec<PublicKeyBytes>>, Error> {
+        let mut url = self.make_slashing_protection_url()?;
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("check");
+        self.post(url, req).await
+    }
 }
 
 /// Returns `Ok(response)` if the response is a `200 OK` response or a
@@ -198,3 +198,105 @@ pub struct SingleExportKeystoresResponse {
 pub struct SetGraffitiRequest {
     pub graffiti: GraffitiString,
 }
+
+/// The progress of a validator through doppelganger protection.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoppelgangerProtectionStatus {
+    /// The validator may sign, either because detection has completed or because doppelganger
+    /// protection is disabled.
+    SigningEnabled,
+    /// The validator is waiting for the detection window to elapse before signing.
+    SigningDisabled,
+    /// Another instance of the validator was detected on the network.
+    DoppelgangerDetected,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoppelgangerStatusData {
+    pub voting_pubkey: PublicKeyBytes,
+    pub status: DoppelgangerProtectionStatus,
+    /// The first epoch in which activity of the validator is considered a doppelganger.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_check_epoch: Option<Epoch>,
+    /// The number of epochs which must pass without activity before the validator may sign.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining_epochs: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateDoppelgangerRequest {
+    /// The number of further epochs to check for doppelgangers. Zero skips detection.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub remaining_epochs: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DoppelgangerEventKind {
+    /// Another instance of the validator was detected. The validator client is shutting down.
+    DoppelgangerDetected,
+    /// Detection completed without finding a doppelganger. The validator has begun signing.
+    DetectionComplete,
+}
+
+/// An event published on `GET lighthouse/doppelganger/events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DoppelgangerEvent {
+    pub kind: DoppelgangerEventKind,
+    pub voting_pubkey: PublicKeyBytes,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub epoch: Epoch,
+}
+
+/// A voluntary exit which has been signed in advance and stored encrypted by the validator client.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresignedExitData {
+    pub voting_pubkey: PublicKeyBytes,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// The epoch of the exit message.
+    pub epoch: Epoch,
+    /// The epoch at which the exit will be broadcast, if broadcasting has been requested.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_epoch: Option<Epoch>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresignExitsRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+    /// The epoch of the exit messages, defaulting to the current epoch.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub epoch: Option<Epoch>,
+    /// The passphrase used to encrypt the signed exits.
+    pub passphrase: ZeroizeString,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct BroadcastExitsRequest {
+    /// The validators to exit, or all validators with a pre-signed exit if `None`.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pubkeys: Option<Vec<PublicKeyBytes>>,
+    /// The passphrase used to encrypt the signed exits.
+    pub passphrase: ZeroizeString,
+    /// The epoch at which to broadcast the exits, defaulting to the current epoch.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeletePresignedExitsRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportSlashingProtectionRequest {
+    pub pubkeys: Vec<PublicKeyBytes>,
+}
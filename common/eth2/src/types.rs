@@ -5,7 +5,10 @@ use crate::{
     Error as ServerError, CONSENSUS_BLOCK_VALUE_HEADER, CONSENSUS_VERSION_HEADER,
     EXECUTION_PAYLOAD_BLINDED_HEADER, EXECUTION_PAYLOAD_VALUE_HEADER,
 };
-use lighthouse_network::{ConnectionDirection, Enr, Multiaddr, PeerConnectionStatus};
+use lighthouse_network::{
+    types::Reachability as NetworkReachability, ConnectionDirection, Enr, Multiaddr,
+    PeerConnectionStatus,
+};
 use mediatype::{names, MediaType, MediaTypeList};
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -501,6 +504,35 @@ pub struct ValidatorsQuery {
     pub id: Option<Vec<ValidatorId>>,
     #[serde(default, deserialize_with = "option_query_vec")]
     pub status: Option<Vec<ValidatorStatus>>,
+    /// Return a trace of the operations performed to serve the request, see `X-Lighthouse-Trace`.
+    #[serde(default)]
+    pub trace: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorsPageQuery {
+    #[serde(default, deserialize_with = "option_query_vec")]
+    pub id: Option<Vec<ValidatorId>>,
+    #[serde(default, deserialize_with = "option_query_vec")]
+    pub status: Option<Vec<ValidatorStatus>>,
+    /// The `next_page_token` of the previous page, or `None` for the first page.
+    pub page_token: Option<String>,
+    /// The maximum number of validators to return.
+    pub limit: Option<usize>,
+    /// Return a trace of the operations performed to serve the request, see `X-Lighthouse-Trace`.
+    #[serde(default)]
+    pub trace: Option<bool>,
+}
+
+/// A single page of the response to `GET /eth/v2/beacon/states/{state_id}/validators`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ValidatorsPage {
+    pub execution_optimistic: Option<bool>,
+    pub finalized: Option<bool>,
+    /// The token to request the next page with, or `None` if this is the last page.
+    pub next_page_token: Option<String>,
+    pub data: Vec<ValidatorData>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -582,6 +614,28 @@ pub struct IdentityData {
     pub p2p_addresses: Vec<Multiaddr>,
     pub discovery_addresses: Vec<Multiaddr>,
     pub metadata: MetaData,
+    /// Non-standard: an estimate of whether other nodes can contact the node.
+    #[serde(default)]
+    pub reachability: Reachability,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reachability {
+    Public,
+    BehindNat,
+    #[default]
+    Unknown,
+}
+
+impl Reachability {
+    pub fn from_network_reachability(reachability: NetworkReachability) -> Self {
+        match reachability {
+            NetworkReachability::Public => Reachability::Public,
+            NetworkReachability::BehindNat => Reachability::BehindNat,
+            NetworkReachability::Unknown => Reachability::Unknown,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -609,6 +663,30 @@ pub struct SyncingData {
 #[derive(Serialize, Deserialize)]
 pub struct ExpectedWithdrawalsQuery {
     pub proposal_slot: Option<Slot>,
+    /// Return Merkle proofs of the withdrawals against the state root, see
+    /// `ExpectedWithdrawalsWithProof`.
+    #[serde(default)]
+    pub proof: Option<bool>,
+}
+
+/// The response to `GET builder/states/{state_id}/expected_withdrawals?proof=true`.
+///
+/// The proofs cover the withdrawal sweep position and the validator record and balance of each
+/// withdrawal's validator, from which the withdrawal can be recomputed. They do not prove that
+/// the validators skipped by the sweep are ineligible for withdrawal.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ExpectedWithdrawalsWithProof {
+    pub withdrawals: Vec<Withdrawal>,
+    /// The root and slot of the state which the proofs are against.
+    pub state_root: Hash256,
+    pub state_slot: Slot,
+    pub next_withdrawal_index: StateProof,
+    pub next_withdrawal_validator_index: StateProof,
+    /// A proof of the validator record of each withdrawal's validator, in the order of
+    /// `withdrawals`.
+    pub validators: Vec<StateProof>,
+    /// A proof of the balance of each withdrawal's validator, in the order of `withdrawals`.
+    pub balances: Vec<StateProof>,
 }
 
 #[derive(Clone, PartialEq, Debug, Deserialize)]
@@ -675,6 +753,9 @@ impl<T: FromStr> From<QueryVec<T>> for Vec<T> {
 pub struct ValidatorBalancesQuery {
     #[serde(default, deserialize_with = "option_query_vec")]
     pub id: Option<Vec<ValidatorId>>,
+    /// Return a trace of the operations performed to serve the request, see `X-Lighthouse-Trace`.
+    #[serde(default)]
+    pub trace: Option<bool>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -688,6 +769,9 @@ pub struct ValidatorBalancesRequestBody {
 pub struct BlobIndicesQuery {
     #[serde(default, deserialize_with = "option_query_vec")]
     pub indices: Option<Vec<u64>>,
+    /// Return a trace of the operations performed to serve the request, see `X-Lighthouse-Trace`.
+    #[serde(default)]
+    pub trace: Option<bool>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -1023,6 +1107,74 @@ pub struct SseLateHead {
     pub execution_optimistic: bool,
 }
 
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseBlockProductionStage {
+    pub stage: String,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub duration_ms: u64,
+    #[serde(with = "quoted_u64_opt")]
+    pub budget_ms: Option<u64>,
+    pub degraded: bool,
+}
+
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseBlockProduction {
+    pub slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_ms: u64,
+    pub stages: Vec<SseBlockProductionStage>,
+}
+
+/// Serializes an optional `u64` as a quoted integer, or `null`.
+mod quoted_u64_opt {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde_utils::quoted_u64::Quoted;
+
+    pub fn serialize<S>(value: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        value.map(|value| Quoted { value }).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<Quoted<u64>>::deserialize(deserializer)?.map(|quoted| quoted.value))
+    }
+}
+
+/// Emitted when the execution status of a block in fork choice changes, e.g. when an optimistic
+/// block is found to be valid or invalid.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseOptimisticTransition {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub execution_block_hash: ExecutionBlockHash,
+    pub previous_status: String,
+    pub status: String,
+    /// The slot of the clock when the transition was observed.
+    pub observed_slot: Slot,
+}
+
+/// Emitted when the slasher finds an equivocation by a validator on its watch list.
+///
+/// Either both attestations or the slot and both block roots are set, depending on the `kind`.
+#[derive(PartialEq, Debug, Serialize, Deserialize, Clone)]
+pub struct SseSlasherAlert {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    pub kind: String,
+    pub attestation_1: Option<AttestationData>,
+    pub attestation_2: Option<AttestationData>,
+    pub slot: Option<Slot>,
+    pub block_root_1: Option<Hash256>,
+    pub block_root_2: Option<Hash256>,
+}
+
 #[superstruct(
     variants(V1, V2, V3),
     variant_attributes(derive(Clone, Debug, Eq, Hash, PartialEq, Serialize, Deserialize))
@@ -1127,6 +1279,9 @@ pub enum EventKind<E: EthSpec> {
     AttesterSlashing(Box<AttesterSlashing<E>>),
     BlsToExecutionChange(Box<SignedBlsToExecutionChange>),
     BlockGossip(Box<BlockGossip>),
+    BlockProduction(Box<SseBlockProduction>),
+    OptimisticTransition(Box<SseOptimisticTransition>),
+    SlasherAlert(Box<SseSlasherAlert>),
 }
 
 impl<E: EthSpec> EventKind<E> {
@@ -1150,6 +1305,9 @@ impl<E: EthSpec> EventKind<E> {
             EventKind::AttesterSlashing(_) => "attester_slashing",
             EventKind::BlsToExecutionChange(_) => "bls_to_execution_change",
             EventKind::BlockGossip(_) => "block_gossip",
+            EventKind::BlockProduction(_) => "block_production",
+            EventKind::OptimisticTransition(_) => "optimistic_transition",
+            EventKind::SlasherAlert(_) => "slasher_alert",
         }
     }
 
@@ -1248,6 +1406,21 @@ impl<E: EthSpec> EventKind<E> {
             "block_gossip" => Ok(EventKind::BlockGossip(serde_json::from_str(data).map_err(
                 |e| ServerError::InvalidServerSentEvent(format!("Block Gossip: {:?}", e)),
             )?)),
+            "block_production" => Ok(EventKind::BlockProduction(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Block Production: {:?}", e))
+                })?,
+            )),
+            "optimistic_transition" => Ok(EventKind::OptimisticTransition(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Optimistic Transition: {:?}", e))
+                })?,
+            )),
+            "slasher_alert" => Ok(EventKind::SlasherAlert(
+                serde_json::from_str(data).map_err(|e| {
+                    ServerError::InvalidServerSentEvent(format!("Slasher Alert: {:?}", e))
+                })?,
+            )),
             _ => Err(ServerError::InvalidServerSentEvent(
                 "Could not parse event tag".to_string(),
             )),
@@ -1283,6 +1456,9 @@ pub enum EventTopic {
     ProposerSlashing,
     BlsToExecutionChange,
     BlockGossip,
+    BlockProduction,
+    OptimisticTransition,
+    SlasherAlert,
 }
 
 impl FromStr for EventTopic {
@@ -1308,6 +1484,9 @@ impl FromStr for EventTopic {
             "proposer_slashing" => Ok(EventTopic::ProposerSlashing),
             "bls_to_execution_change" => Ok(EventTopic::BlsToExecutionChange),
             "block_gossip" => Ok(EventTopic::BlockGossip),
+            "block_production" => Ok(EventTopic::BlockProduction),
+            "optimistic_transition" => Ok(EventTopic::OptimisticTransition),
+            "slasher_alert" => Ok(EventTopic::SlasherAlert),
             _ => Err("event topic cannot be parsed.".to_string()),
         }
     }
@@ -1334,6 +1513,9 @@ impl fmt::Display for EventTopic {
             EventTopic::ProposerSlashing => write!(f, "proposer_slashing"),
             EventTopic::BlsToExecutionChange => write!(f, "bls_to_execution_change"),
             EventTopic::BlockGossip => write!(f, "block_gossip"),
+            EventTopic::BlockProduction => write!(f, "block_production"),
+            EventTopic::OptimisticTransition => write!(f, "optimistic_transition"),
+            EventTopic::SlasherAlert => write!(f, "slasher_alert"),
         }
     }
 }
@@ -91,4 +91,24 @@ impl LockedWallet {
 
         Ok(keystores)
     }
+
+    /// Calls `Wallet::next_validators` on the underlying `wallet`.
+    ///
+    /// Ensures that the wallet JSON file is updated after each call.
+    ///
+    /// ## Errors
+    ///
+    /// - If there is an error generating the validator keys.
+    /// - If there is a file-system error.
+    pub fn next_validators(
+        &mut self,
+        wallet_password: &[u8],
+        passwords: &[(&[u8], &[u8])],
+    ) -> Result<Vec<ValidatorKeystores>, Error> {
+        let keystores = self.wallet.next_validators(wallet_password, passwords)?;
+
+        update(&self.wallet_dir, &self.wallet)?;
+
+        Ok(keystores)
+    }
 }
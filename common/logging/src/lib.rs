@@ -8,16 +8,21 @@ use std::time::{Duration, Instant};
 use tracing_appender::non_blocking::NonBlocking;
 use tracing_appender::rolling::{RollingFileAppender, Rotation};
 use tracing_logging_layer::LoggingLayer;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Layer};
 
 pub const MAX_MESSAGE_WIDTH: usize = 40;
 
 pub mod async_record;
+mod runtime_log_level;
 mod sse_logging_components;
+mod telemetry;
 mod tracing_logging_layer;
 mod tracing_metrics_layer;
 
+pub use runtime_log_level::{RuntimeLevelFilter, RuntimeLogLevel};
 pub use sse_logging_components::SSELoggingComponents;
+pub use telemetry::{shutdown_telemetry, telemetry_layer, TelemetryConfig};
 pub use tracing_metrics_layer::MetricsLayer;
 
 /// The minimum interval between log messages indicating that a queue is full.
@@ -214,7 +219,32 @@ impl TimeLatch {
     }
 }
 
-pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
+/// Initializes the global `tracing` subscriber, which writes libp2p and discv5 logs to files in
+/// `base_tracing_log_path` and, if `telemetry` is provided, exports spans to an OpenTelemetry
+/// collector.
+pub fn create_tracing_layer(base_tracing_log_path: PathBuf, telemetry: Option<&TelemetryConfig>) {
+    let telemetry_layer = telemetry.and_then(|config| match telemetry_layer(config) {
+        Ok(layer) => Some(layer),
+        Err(e) => {
+            eprintln!("Failed to initialize telemetry {e}");
+            None
+        }
+    });
+
+    if let Err(e) = tracing_subscriber::registry()
+        .with(dependency_logging_layer(base_tracing_log_path))
+        .with(telemetry_layer)
+        .try_init()
+    {
+        eprintln!("Failed to initialize dependency logging {e}");
+    }
+}
+
+/// Returns a layer which writes the logs of libp2p and discv5 to rolling files.
+fn dependency_logging_layer<S>(base_tracing_log_path: PathBuf) -> Option<impl Layer<S>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
     let mut tracing_log_path = PathBuf::new();
 
     // Ensure that `tracing_log_path` only contains directories.
@@ -234,7 +264,7 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         Ok(filter) => filter,
         Err(e) => {
             eprintln!("Failed to initialize dependency logging {e}");
-            return;
+            return None;
         }
     };
 
@@ -246,7 +276,7 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         .build(tracing_log_path.clone())
     else {
         eprintln!("Failed to initialize libp2p rolling file appender");
-        return;
+        return None;
     };
 
     let Ok(discv5_writer) = RollingFileAppender::builder()
@@ -257,7 +287,7 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         .build(tracing_log_path)
     else {
         eprintln!("Failed to initialize discv5 rolling file appender");
-        return;
+        return None;
     };
 
     let (libp2p_non_blocking_writer, _libp2p_guard) = NonBlocking::new(libp2p_writer);
@@ -270,16 +300,13 @@ pub fn create_tracing_layer(base_tracing_log_path: PathBuf) {
         _discv5_guard,
     };
 
-    if let Err(e) = tracing_subscriber::fmt()
-        .with_env_filter(filter_layer)
-        .with_writer(std::io::sink)
-        .finish()
-        .with(MetricsLayer)
-        .with(custom_layer)
-        .try_init()
-    {
-        eprintln!("Failed to initialize dependency logging {e}");
-    }
+    // The filter only applies to these layers, so that it doesn't disable the spans exported by
+    // the telemetry layer.
+    Some(
+        MetricsLayer
+            .and_then(custom_layer)
+            .with_filter(filter_layer),
+    )
 }
 
 /// Return a logger suitable for test usage.
@@ -0,0 +1,149 @@
+//! This module provides an implementation of `slog::Drain` which filters records by a level that
+//! can be changed while the program is running.
+//!
+//! The level may be overridden for individual modules, such as `network::sync`. An override
+//! applies to the module and all of its submodules, with the most specific override taking
+//! priority.
+//!
+//! The overrides are read for every record, so they're swapped in as a whole on change rather than
+//! being behind a lock.
+
+use arc_swap::ArcSwap;
+use slog::{Drain, Level, OwnedKVList, Record};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// A shared handle to a log level which can be changed at runtime.
+#[derive(Debug, Clone)]
+pub struct RuntimeLogLevel {
+    level: Arc<AtomicUsize>,
+    module_levels: Arc<ArcSwap<BTreeMap<String, Level>>>,
+}
+
+impl RuntimeLogLevel {
+    pub fn new(level: Level) -> Self {
+        Self {
+            level: Arc::new(AtomicUsize::new(level.as_usize())),
+            module_levels: <_>::default(),
+        }
+    }
+
+    pub fn get(&self) -> Level {
+        Level::from_usize(self.level.load(Ordering::Relaxed)).unwrap_or(Level::Info)
+    }
+
+    pub fn set(&self, level: Level) {
+        self.level.store(level.as_usize(), Ordering::Relaxed);
+    }
+
+    /// Overrides the level of `module` and its submodules, or removes the override if `level` is
+    /// `None`.
+    pub fn set_module(&self, module: &str, level: Option<Level>) {
+        self.module_levels.rcu(|module_levels| {
+            let mut module_levels = BTreeMap::clone(module_levels);
+            match level {
+                Some(level) => module_levels.insert(module.to_string(), level),
+                None => module_levels.remove(module),
+            };
+            module_levels
+        });
+    }
+
+    /// Returns the per-module overrides.
+    pub fn module_levels(&self) -> BTreeMap<String, Level> {
+        BTreeMap::clone(&self.module_levels.load())
+    }
+
+    /// Returns the level that applies to records from `module`.
+    pub fn get_for_module(&self, module: &str) -> Level {
+        let module_levels = self.module_levels.load();
+        if module_levels.is_empty() {
+            return self.get();
+        }
+        module_levels
+            .iter()
+            .filter(|(prefix, _)| is_submodule(module, prefix))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(_, level)| *level)
+            .unwrap_or_else(|| self.get())
+    }
+
+    /// Returns the most verbose level of any module.
+    fn most_verbose(&self) -> Level {
+        self.module_levels
+            .load()
+            .values()
+            .fold(self.get(), |most_verbose, level| {
+                if level.is_at_least(most_verbose) {
+                    most_verbose
+                } else {
+                    *level
+                }
+            })
+    }
+
+    /// Wrap `drain` so that it only receives records at or above this level.
+    pub fn filter<D: Drain>(&self, drain: D) -> RuntimeLevelFilter<D> {
+        RuntimeLevelFilter {
+            drain,
+            level: self.clone(),
+        }
+    }
+}
+
+/// A drain which discards records below the level of a `RuntimeLogLevel`.
+pub struct RuntimeLevelFilter<D> {
+    drain: D,
+    level: RuntimeLogLevel,
+}
+
+impl<D: Drain> Drain for RuntimeLevelFilter<D> {
+    type Ok = Option<D::Ok>;
+    type Err = D::Err;
+
+    fn log(&self, record: &Record, logger_values: &OwnedKVList) -> Result<Self::Ok, Self::Err> {
+        if record
+            .level()
+            .is_at_least(self.level.get_for_module(record.module()))
+        {
+            self.drain.log(record, logger_values).map(Some)
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn is_enabled(&self, level: Level) -> bool {
+        level.is_at_least(self.level.most_verbose()) && self.drain.is_enabled(level)
+    }
+}
+
+/// Returns `true` if `module` is `parent` or one of its submodules.
+fn is_submodule(module: &str, parent: &str) -> bool {
+    module
+        .strip_prefix(parent)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn module_overrides() {
+        let level = RuntimeLogLevel::new(Level::Info);
+        level.set_module("network", Some(Level::Debug));
+        level.set_module("network::sync", Some(Level::Trace));
+
+        assert_eq!(level.get_for_module("beacon_chain"), Level::Info);
+        assert_eq!(level.get_for_module("network"), Level::Debug);
+        assert_eq!(level.get_for_module("network::router"), Level::Debug);
+        assert_eq!(level.get_for_module("network::sync::manager"), Level::Trace);
+        assert_eq!(level.get_for_module("networking"), Level::Info);
+        assert_eq!(level.most_verbose(), Level::Trace);
+
+        level.set_module("network::sync", None);
+        assert_eq!(level.get_for_module("network::sync::manager"), Level::Debug);
+        assert_eq!(level.most_verbose(), Level::Debug);
+    }
+}
@@ -0,0 +1,73 @@
+//! Exports `tracing` spans to an OpenTelemetry collector over OTLP.
+//!
+//! Only spans from the crates listed in `TRACED_CRATES` are exported, so that operators can see
+//! where time is spent importing each block without the volume of dependency spans.
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{Config, Sampler};
+use opentelemetry_sdk::{runtime, Resource};
+use serde::{Deserialize, Serialize};
+use tracing::{Level, Subscriber};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// The crates whose spans are exported.
+const TRACED_CRATES: &[&str] = &["beacon_chain", "network"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryConfig {
+    /// The URL of the OTLP gRPC endpoint of the collector.
+    pub collector_url: String,
+    /// The name that spans are attributed to in the collector.
+    pub service_name: String,
+    /// The fraction of traces to export. Child spans follow the decision of their root span.
+    pub sample_rate: f64,
+}
+
+/// Returns a layer which exports spans to the collector in `config`.
+///
+/// Must be called from within a Tokio runtime, which is used to export spans in batches.
+pub fn telemetry_layer<S>(config: &TelemetryConfig) -> Result<impl Layer<S>, String>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    let exporter = opentelemetry_otlp::new_exporter()
+        .tonic()
+        .with_endpoint(&config.collector_url);
+
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(
+            Config::default()
+                .with_sampler(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(
+                    config.sample_rate,
+                ))))
+                .with_resource(Resource::new(vec![KeyValue::new(
+                    "service.name",
+                    config.service_name.clone(),
+                )])),
+        )
+        .install_batch(runtime::Tokio)
+        .map_err(|e| format!("Unable to start OpenTelemetry exporter: {e}"))?;
+
+    let tracer = provider.tracer("lighthouse");
+    opentelemetry::global::set_tracer_provider(provider);
+
+    let filter = TRACED_CRATES
+        .iter()
+        .fold(Targets::new(), |targets, target| {
+            targets.with_target(*target, Level::INFO)
+        });
+
+    Ok(tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(filter))
+}
+
+/// Exports any buffered spans and stops the exporter.
+pub fn shutdown_telemetry() {
+    opentelemetry::global::shutdown_tracer_provider();
+}
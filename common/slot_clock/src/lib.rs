@@ -1,11 +1,13 @@
 mod manual_slot_clock;
 mod metrics;
+mod sourced_slot_clock;
 mod system_time_slot_clock;
 
 use std::time::Duration;
 
 pub use crate::manual_slot_clock::ManualSlotClock as TestingSlotClock;
 pub use crate::manual_slot_clock::ManualSlotClock;
+pub use crate::sourced_slot_clock::{SourcedSlotClock, SystemTimeSource, TimeSource};
 pub use crate::system_time_slot_clock::SystemTimeSlotClock;
 pub use metrics::scrape_for_metrics;
 use types::consts::bellatrix::INTERVALS_PER_SLOT;
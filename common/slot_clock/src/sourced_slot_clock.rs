@@ -0,0 +1,129 @@
+use super::{ManualSlotClock, SlotClock};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use types::Slot;
+
+/// A source of the present time, e.g. the system clock or a clock disciplined by a time server.
+pub trait TimeSource: Send + Sync {
+    /// Returns the present time as a duration since the UNIX epoch.
+    fn now(&self) -> Option<Duration>;
+}
+
+/// Reads the present time from the system clock.
+pub struct SystemTimeSource;
+
+impl TimeSource for SystemTimeSource {
+    fn now(&self) -> Option<Duration> {
+        SystemTime::now().duration_since(UNIX_EPOCH).ok()
+    }
+}
+
+/// Determines the present slot based upon the time read from a `TimeSource`.
+///
+/// The system clock is used until another source is provided with `Self::with_time_source`.
+#[derive(Clone)]
+pub struct SourcedSlotClock {
+    clock: ManualSlotClock,
+    source: Arc<dyn TimeSource>,
+}
+
+impl SourcedSlotClock {
+    /// Reads the present time from `source`, rather than the system clock.
+    pub fn with_time_source(mut self, source: Arc<dyn TimeSource>) -> Self {
+        self.source = source;
+        self
+    }
+}
+
+impl SlotClock for SourcedSlotClock {
+    fn new(genesis_slot: Slot, genesis_duration: Duration, slot_duration: Duration) -> Self {
+        Self {
+            clock: ManualSlotClock::new(genesis_slot, genesis_duration, slot_duration),
+            source: Arc::new(SystemTimeSource),
+        }
+    }
+
+    fn now(&self) -> Option<Slot> {
+        let now = self.source.now()?;
+        self.clock.slot_of(now)
+    }
+
+    fn is_prior_to_genesis(&self) -> Option<bool> {
+        let now = self.source.now()?;
+        Some(now < *self.clock.genesis_duration())
+    }
+
+    fn now_duration(&self) -> Option<Duration> {
+        self.source.now()
+    }
+
+    fn slot_of(&self, now: Duration) -> Option<Slot> {
+        self.clock.slot_of(now)
+    }
+
+    fn duration_to_next_slot(&self) -> Option<Duration> {
+        let now = self.source.now()?;
+        self.clock.duration_to_next_slot_from(now)
+    }
+
+    fn duration_to_next_epoch(&self, slots_per_epoch: u64) -> Option<Duration> {
+        let now = self.source.now()?;
+        self.clock.duration_to_next_epoch_from(now, slots_per_epoch)
+    }
+
+    fn slot_duration(&self) -> Duration {
+        self.clock.slot_duration()
+    }
+
+    fn duration_to_slot(&self, slot: Slot) -> Option<Duration> {
+        let now = self.source.now()?;
+        self.clock.duration_to_slot(slot, now)
+    }
+
+    fn start_of(&self, slot: Slot) -> Option<Duration> {
+        self.clock.start_of(slot)
+    }
+
+    fn genesis_slot(&self) -> Slot {
+        self.clock.genesis_slot()
+    }
+
+    fn genesis_duration(&self) -> Duration {
+        *self.clock.genesis_duration()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FixedTimeSource(Duration);
+
+    impl TimeSource for FixedTimeSource {
+        fn now(&self) -> Option<Duration> {
+            Some(self.0)
+        }
+    }
+
+    #[test]
+    fn reads_time_source() {
+        let clock = SourcedSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(100),
+            Duration::from_secs(12),
+        )
+        .with_time_source(Arc::new(FixedTimeSource(Duration::from_secs(130))));
+
+        assert_eq!(clock.now(), Some(Slot::new(2)));
+        assert_eq!(clock.is_prior_to_genesis(), Some(false));
+        assert_eq!(clock.now_duration(), Some(Duration::from_secs(130)));
+        assert_eq!(clock.duration_to_next_slot(), Some(Duration::from_secs(6)));
+        assert_eq!(
+            clock.duration_to_slot(Slot::new(4)),
+            Some(Duration::from_secs(18))
+        );
+
+        let clock = clock.with_time_source(Arc::new(FixedTimeSource(Duration::from_secs(99))));
+        assert_eq!(clock.is_prior_to_genesis(), Some(true));
+    }
+}
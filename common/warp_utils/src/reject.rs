@@ -127,6 +127,24 @@ pub fn not_synced(msg: String) -> warp::reject::Rejection {
     warp::reject::custom(NotSynced(msg))
 }
 
+#[derive(Debug)]
+pub struct Standby(pub String);
+
+impl Reject for Standby {}
+
+pub fn standby(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(Standby(msg))
+}
+
+#[derive(Debug)]
+pub struct TooManyRequests(pub String);
+
+impl Reject for TooManyRequests {}
+
+pub fn too_many_requests(msg: String) -> warp::reject::Rejection {
+    warp::reject::custom(TooManyRequests(msg))
+}
+
 #[derive(Debug)]
 pub struct InvalidAuthorization(pub String);
 
@@ -225,6 +243,12 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
     } else if let Some(e) = err.find::<crate::reject::NotSynced>() {
         code = StatusCode::SERVICE_UNAVAILABLE;
         message = format!("SERVICE_UNAVAILABLE: beacon node is syncing: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::Standby>() {
+        code = StatusCode::SERVICE_UNAVAILABLE;
+        message = format!("SERVICE_UNAVAILABLE: beacon node is on standby: {}", e.0);
+    } else if let Some(e) = err.find::<crate::reject::TooManyRequests>() {
+        code = StatusCode::TOO_MANY_REQUESTS;
+        message = format!("TOO_MANY_REQUESTS: {}", e.0);
     } else if let Some(e) = err.find::<crate::reject::InvalidAuthorization>() {
         code = StatusCode::FORBIDDEN;
         message = format!("FORBIDDEN: Invalid auth token: {}", e.0);
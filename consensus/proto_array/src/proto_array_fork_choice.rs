@@ -270,6 +270,10 @@ pub enum DoNotReOrg {
     HeadNotLate,
     NotProposing,
     ReOrgsDisabled,
+    /// Vetoed by the node's re-org policy, for a reason given by the policy.
+    RejectedByPolicy {
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for DoNotReOrg {
@@ -319,6 +323,9 @@ impl std::fmt::Display for DoNotReOrg {
             Self::ReOrgsDisabled => {
                 write!(f, "re-orgs disabled in config")
             }
+            Self::RejectedByPolicy { reason } => {
+                write!(f, "rejected by re-org policy: {reason}")
+            }
         }
     }
 }
@@ -0,0 +1,74 @@
+//! Computes the committee assignment of a validator for an epoch, along with Merkle proofs against
+//! the state root of the state fields from which it is computed.
+//!
+//! An assignment is determined by the shuffling of the active validators for the epoch, which is
+//! seeded by a RANDAO mix. The proofs cover that RANDAO mix and the record of the validator, which
+//! determines whether it is active in the epoch. This allows the assignment to be verified against
+//! a trusted state root (e.g., by a delegator dashboard) without trusting the beacon node.
+//!
+//! Recomputing the shuffling also requires the indices of all active validators, which must be
+//! obtained from the validators list of the same state.
+use types::{
+    AttestationDuty, BeaconState, BeaconStateError as Error, ChainSpec, CollectionProofTree,
+    Domain, Epoch, EthSpec, Hash256, RelativeEpoch, Slot, StateProof,
+};
+
+/// The committee assignment of a validator for an epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommitteeAssignment {
+    pub validator_index: u64,
+    pub epoch: Epoch,
+    /// The assignment, or `None` if the validator is not active in the epoch.
+    pub duty: Option<AttestationDuty>,
+    /// The root and slot of the state which the proofs are against.
+    pub state_root: Hash256,
+    pub state_slot: Slot,
+    /// The seed of the shuffling for the epoch.
+    pub seed: Hash256,
+    /// A proof of the RANDAO mix from which the seed is generated.
+    pub randao_mix_proof: StateProof,
+    /// A proof of the validator's record.
+    pub validator_proof: StateProof,
+}
+
+/// Returns the committee assignment of the validator with `validator_index` for `epoch`, which
+/// must be the previous, current or next epoch of `state`.
+///
+/// The validator's proof is generated from `validators_proof_tree`, which must be the tree of the
+/// validator registry of `state` (see `BeaconState::validators_proof_tree`). Building it hashes the
+/// whole registry, so callers should keep it for as long as the state is in use.
+pub fn get_committee_assignment<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    validator_index: u64,
+    epoch: Epoch,
+    validators_proof_tree: &CollectionProofTree,
+    spec: &ChainSpec,
+) -> Result<CommitteeAssignment, Error> {
+    let relative_epoch = RelativeEpoch::from_epoch(state.current_epoch(), epoch)?;
+    state.build_committee_cache(relative_epoch, spec)?;
+
+    let duty = state.get_attestation_duties(validator_index as usize, relative_epoch)?;
+    let seed = state.get_seed(epoch, Domain::BeaconAttester, spec)?;
+    let randao_mix_index = state.get_seed_randao_mix_index(epoch, spec)?;
+
+    let state_root = state.canonical_root()?;
+    let randao_mix_proof = state.compute_randao_mix_state_proof(randao_mix_index)?;
+    if validator_index as usize >= state.validators().len() {
+        return Err(Error::UnknownValidator(validator_index as usize));
+    }
+    let validator_proof = validators_proof_tree
+        .proofs(&[validator_index as usize])?
+        .pop()
+        .ok_or(Error::UnknownValidator(validator_index as usize))?;
+
+    Ok(CommitteeAssignment {
+        validator_index,
+        epoch,
+        duty,
+        state_root,
+        state_slot: state.slot(),
+        seed,
+        randao_mix_proof,
+        validator_proof,
+    })
+}
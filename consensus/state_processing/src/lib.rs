@@ -18,6 +18,7 @@ mod metrics;
 
 pub mod all_caches;
 pub mod block_replayer;
+pub mod committee_assignment;
 pub mod common;
 pub mod consensus_context;
 pub mod epoch_cache;
@@ -27,10 +28,12 @@ pub mod per_epoch_processing;
 pub mod per_slot_processing;
 pub mod state_advance;
 pub mod upgrade;
+pub mod validator_projection;
 pub mod verify_operation;
 
 pub use all_caches::AllCaches;
 pub use block_replayer::{BlockReplayError, BlockReplayer};
+pub use committee_assignment::{get_committee_assignment, CommitteeAssignment};
 pub use consensus_context::{ConsensusContext, ContextError};
 pub use genesis::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
@@ -45,4 +48,5 @@ pub use per_epoch_processing::{
 };
 pub use per_slot_processing::{per_slot_processing, Error as SlotProcessingError};
 pub use types::{EpochCache, EpochCacheError, EpochCacheKey};
+pub use validator_projection::{project_validator_balances, ProjectedBalance, ValidatorProjection};
 pub use verify_operation::{SigVerifiedOp, TransformPersist, VerifyOperation, VerifyOperationAt};
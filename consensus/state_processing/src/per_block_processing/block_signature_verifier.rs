@@ -3,7 +3,7 @@
 use super::signature_sets::{Error as SignatureSetError, *};
 use crate::per_block_processing::errors::{AttestationInvalid, BlockOperationError};
 use crate::{ConsensusContext, ContextError};
-use bls::{verify_signature_sets, PublicKey, PublicKeyBytes, SignatureSet};
+use bls::{verify_signature_sets_batched, PublicKey, PublicKeyBytes, SignatureSet};
 use std::borrow::Cow;
 use types::{
     AbstractExecPayload, BeaconState, BeaconStateError, ChainSpec, EthSpec, Hash256,
@@ -389,9 +389,10 @@ impl<'a> ParallelSignatureSets<'a> {
     /// _at least one_ was invalid.
     ///
     /// Blst library spreads the signature verification work across multiple available cores, so
-    /// this function is already parallelized.
+    /// this function is already parallelized. If a BLS batch size is configured, the sets are
+    /// additionally split into batches which are verified in parallel.
     #[must_use]
     pub fn verify(self) -> bool {
-        verify_signature_sets(self.sets.iter())
+        verify_signature_sets_batched(&self.sets)
     }
 }
@@ -0,0 +1,453 @@
+//! Projects the balances of validators over the coming epochs, taking into account the Electra
+//! queues of pending balance deposits, consolidations and partial withdrawals.
+//!
+//! The queues are processed as they would be by block and epoch processing, assuming that:
+//!
+//! - No rewards or penalties are applied, and no validators are slashed, ejected or exited.
+//! - No new deposits, consolidations or withdrawal requests are included on chain.
+//! - Every slot has a block, which processes pending partial withdrawals.
+//! - The churn limit remains that of the current epoch.
+//!
+//! The withdrawal sweep (which withdraws balance in excess of the maximum effective balance) is
+//! not modelled, since when it reaches a validator depends on the rest of the registry.
+//!
+//! The projection therefore indicates when queued balance changes take effect (e.g., for staking
+//! dashboards), rather than predicting balances exactly.
+use crate::per_epoch_processing::errors::EpochProcessingError as Error;
+use safe_arith::SafeArith;
+use std::cmp::min;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use types::{
+    BeaconState, ChainSpec, Epoch, EthSpec, ForkName, PendingBalanceDeposit, PendingConsolidation,
+    PendingPartialWithdrawal, Validator,
+};
+
+/// The balances of a validator at the start of an epoch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectedBalance {
+    pub epoch: Epoch,
+    pub balance: u64,
+    pub effective_balance: u64,
+    /// The sum of the pending balance deposits to the validator which are yet to be processed.
+    pub pending_deposits: u64,
+}
+
+/// The projected balances of a single validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidatorProjection {
+    pub validator_index: u64,
+    /// The balances at the state's slot, followed by the balances at the start of each projected
+    /// epoch.
+    pub balances: Vec<ProjectedBalance>,
+}
+
+/// Returns the projected balances of each of the `validator_indices` over the `num_epochs` epochs
+/// following the current epoch of `state`.
+///
+/// The `state` must have its total active balance cache built.
+pub fn project_validator_balances<E: EthSpec>(
+    state: &BeaconState<E>,
+    validator_indices: &[u64],
+    num_epochs: u64,
+    spec: &ChainSpec,
+) -> Result<Vec<ValidatorProjection>, Error> {
+    let mut projection = Projection::new(state, spec)?;
+    let mut projections = validator_indices
+        .iter()
+        .map(|&validator_index| ValidatorProjection {
+            validator_index,
+            balances: Vec::with_capacity(num_epochs.saturating_add(1) as usize),
+        })
+        .collect::<Vec<_>>();
+
+    let slots_per_epoch = E::slots_per_epoch();
+    let mut epoch = state.current_epoch();
+    // The block at the state's slot has already been processed.
+    let mut remaining_slots = epoch
+        .end_slot(slots_per_epoch)
+        .as_u64()
+        .safe_sub(state.slot().as_u64())?;
+
+    projection.record(epoch, &mut projections)?;
+    for _ in 0..num_epochs {
+        for _ in 0..remaining_slots {
+            projection.process_pending_partial_withdrawals(epoch)?;
+        }
+        projection.process_epoch(epoch)?;
+
+        epoch.safe_add_assign(1)?;
+        remaining_slots = slots_per_epoch;
+        projection.record(epoch, &mut projections)?;
+    }
+
+    Ok(projections)
+}
+
+/// The parts of a validator which are relevant to the projection.
+struct ProjectedValidator {
+    balance: u64,
+    effective_balance: u64,
+    max_effective_balance: u64,
+    /// `true` if the validator would be switched to compounding credentials by a consolidation.
+    has_eth1_withdrawal_credential: bool,
+    exit_epoch: Epoch,
+    withdrawable_epoch: Epoch,
+    slashed: bool,
+}
+
+impl ProjectedValidator {
+    fn new(validator: &Validator, balance: u64, spec: &ChainSpec, fork_name: ForkName) -> Self {
+        Self {
+            balance,
+            effective_balance: validator.effective_balance,
+            max_effective_balance: validator.get_max_effective_balance(spec, fork_name),
+            has_eth1_withdrawal_credential: validator.has_eth1_withdrawal_credential(spec),
+            exit_epoch: validator.exit_epoch,
+            withdrawable_epoch: validator.withdrawable_epoch,
+            slashed: validator.slashed,
+        }
+    }
+}
+
+/// The queues of a state, and the validators which they (or the caller) have touched.
+struct Projection<'a, E: EthSpec> {
+    state: &'a BeaconState<E>,
+    spec: &'a ChainSpec,
+    fork_name: ForkName,
+    validators: HashMap<usize, ProjectedValidator>,
+    pending_balance_deposits: VecDeque<PendingBalanceDeposit>,
+    deposit_balance_to_consume: u64,
+    pending_consolidations: VecDeque<PendingConsolidation>,
+    pending_partial_withdrawals: VecDeque<PendingPartialWithdrawal>,
+    activation_exit_churn_limit: u64,
+}
+
+impl<'a, E: EthSpec> Projection<'a, E> {
+    fn new(state: &'a BeaconState<E>, spec: &'a ChainSpec) -> Result<Self, Error> {
+        let fork_name = state.fork_name_unchecked();
+        // Prior to Electra the queues don't exist, and balances only change due to rewards,
+        // penalties and the withdrawal sweep.
+        let activation_exit_churn_limit = if fork_name.electra_enabled() {
+            state.get_activation_exit_churn_limit(spec)?
+        } else {
+            0
+        };
+
+        Ok(Self {
+            state,
+            spec,
+            fork_name,
+            validators: HashMap::new(),
+            pending_balance_deposits: state
+                .pending_balance_deposits()
+                .map(|deposits| deposits.iter().cloned().collect())
+                .unwrap_or_default(),
+            deposit_balance_to_consume: state.deposit_balance_to_consume().unwrap_or(0),
+            pending_consolidations: state
+                .pending_consolidations()
+                .map(|consolidations| consolidations.iter().cloned().collect())
+                .unwrap_or_default(),
+            pending_partial_withdrawals: state
+                .pending_partial_withdrawals()
+                .map(|withdrawals| withdrawals.iter().cloned().collect())
+                .unwrap_or_default(),
+            activation_exit_churn_limit,
+        })
+    }
+
+    /// Returns the projected validator at `index`, loading it from the state if it hasn't been
+    /// touched yet.
+    fn validator(&mut self, index: usize) -> Result<&mut ProjectedValidator, Error> {
+        let (state, spec, fork_name) = (self.state, self.spec, self.fork_name);
+        match self.validators.entry(index) {
+            Entry::Occupied(entry) => Ok(entry.into_mut()),
+            Entry::Vacant(entry) => {
+                let validator = state.get_validator(index)?;
+                let balance = state.get_balance(index)?;
+                Ok(entry.insert(ProjectedValidator::new(validator, balance, spec, fork_name)))
+            }
+        }
+    }
+
+    fn record(
+        &mut self,
+        epoch: Epoch,
+        projections: &mut [ValidatorProjection],
+    ) -> Result<(), Error> {
+        let mut pending_deposits = HashMap::<u64, u64>::new();
+        for deposit in &self.pending_balance_deposits {
+            pending_deposits
+                .entry(deposit.index)
+                .or_insert(0)
+                .safe_add_assign(deposit.amount)?;
+        }
+
+        for projection in projections {
+            let validator = self.validator(projection.validator_index as usize)?;
+            projection.balances.push(ProjectedBalance {
+                epoch,
+                balance: validator.balance,
+                effective_balance: validator.effective_balance,
+                pending_deposits: pending_deposits
+                    .get(&projection.validator_index)
+                    .copied()
+                    .unwrap_or(0),
+            });
+        }
+        Ok(())
+    }
+
+    /// Processes the pending partial withdrawals of a single block in `epoch`, as per
+    /// `get_expected_withdrawals` and `process_withdrawals`.
+    fn process_pending_partial_withdrawals(&mut self, epoch: Epoch) -> Result<(), Error> {
+        let min_activation_balance = self.spec.min_activation_balance;
+        let far_future_epoch = self.spec.far_future_epoch;
+        let max_withdrawals = self.spec.max_pending_partials_per_withdrawals_sweep as usize;
+
+        let mut num_withdrawals: usize = 0;
+        for i in 0..self.pending_partial_withdrawals.len() {
+            let Some(withdrawal) = self.pending_partial_withdrawals.get(i).cloned() else {
+                break;
+            };
+            if withdrawal.withdrawable_epoch > epoch || num_withdrawals == max_withdrawals {
+                break;
+            }
+
+            let validator = self.validator(withdrawal.index as usize)?;
+            if validator.exit_epoch == far_future_epoch
+                && validator.effective_balance >= min_activation_balance
+                && validator.balance > min_activation_balance
+            {
+                let amount = min(
+                    validator.balance.safe_sub(min_activation_balance)?,
+                    withdrawal.amount,
+                );
+                validator.balance.safe_sub_assign(amount)?;
+                num_withdrawals.safe_add_assign(1)?;
+            }
+        }
+
+        self.pending_partial_withdrawals.drain(..num_withdrawals);
+        Ok(())
+    }
+
+    /// Processes the queues at the end of `epoch`, followed by the effective balance updates.
+    fn process_epoch(&mut self, epoch: Epoch) -> Result<(), Error> {
+        let next_epoch = epoch.safe_add(1)?;
+        self.process_pending_balance_deposits(next_epoch)?;
+        self.process_pending_consolidations(next_epoch)?;
+        self.process_effective_balance_updates()
+    }
+
+    fn process_pending_balance_deposits(&mut self, next_epoch: Epoch) -> Result<(), Error> {
+        let far_future_epoch = self.spec.far_future_epoch;
+        let available_for_processing = self
+            .deposit_balance_to_consume
+            .safe_add(self.activation_exit_churn_limit)?;
+        let mut processed_amount: u64 = 0;
+        let mut next_deposit_index: usize = 0;
+        let mut deposits_to_postpone = vec![];
+
+        for i in 0..self.pending_balance_deposits.len() {
+            let Some(deposit) = self.pending_balance_deposits.get(i).cloned() else {
+                break;
+            };
+            let validator = self.validator(deposit.index as usize)?;
+            if validator.exit_epoch < far_future_epoch {
+                if next_epoch <= validator.withdrawable_epoch {
+                    deposits_to_postpone.push(deposit);
+                } else {
+                    // Deposited balance will never become active, so it doesn't consume churn.
+                    validator.balance.safe_add_assign(deposit.amount)?;
+                }
+            } else {
+                if processed_amount.safe_add(deposit.amount)? > available_for_processing {
+                    break;
+                }
+                validator.balance.safe_add_assign(deposit.amount)?;
+                processed_amount.safe_add_assign(deposit.amount)?;
+            }
+            next_deposit_index.safe_add_assign(1)?;
+        }
+
+        self.deposit_balance_to_consume =
+            if next_deposit_index == self.pending_balance_deposits.len() {
+                0
+            } else {
+                available_for_processing.safe_sub(processed_amount)?
+            };
+        self.pending_balance_deposits.drain(..next_deposit_index);
+        self.pending_balance_deposits.extend(deposits_to_postpone);
+        Ok(())
+    }
+
+    fn process_pending_consolidations(&mut self, next_epoch: Epoch) -> Result<(), Error> {
+        let mut next_pending_consolidation: usize = 0;
+
+        for i in 0..self.pending_consolidations.len() {
+            let Some(consolidation) = self.pending_consolidations.get(i).cloned() else {
+                break;
+            };
+            let source = self.validator(consolidation.source_index as usize)?;
+            if source.slashed {
+                next_pending_consolidation.safe_add_assign(1)?;
+                continue;
+            }
+            if source.withdrawable_epoch > next_epoch {
+                break;
+            }
+
+            let active_balance = min(source.balance, source.max_effective_balance);
+            source.balance = source.balance.saturating_sub(active_balance);
+
+            self.switch_to_compounding_validator(consolidation.target_index as usize)?;
+            self.validator(consolidation.target_index as usize)?
+                .balance
+                .safe_add_assign(active_balance)?;
+
+            next_pending_consolidation.safe_add_assign(1)?;
+        }
+
+        self.pending_consolidations
+            .drain(..next_pending_consolidation);
+        Ok(())
+    }
+
+    /// Switches the validator at `index` to compounding credentials, queueing its balance in
+    /// excess of the minimum activation balance as a deposit.
+    fn switch_to_compounding_validator(&mut self, index: usize) -> Result<(), Error> {
+        let min_activation_balance = self.spec.min_activation_balance;
+        let max_effective_balance_electra = self.spec.max_effective_balance_electra;
+
+        let validator = self.validator(index)?;
+        if !validator.has_eth1_withdrawal_credential {
+            return Ok(());
+        }
+        validator.has_eth1_withdrawal_credential = false;
+        validator.max_effective_balance = max_effective_balance_electra;
+
+        if validator.balance > min_activation_balance {
+            let excess_balance = validator.balance.safe_sub(min_activation_balance)?;
+            validator.balance = min_activation_balance;
+            self.pending_balance_deposits
+                .push_back(PendingBalanceDeposit {
+                    index: index as u64,
+                    amount: excess_balance,
+                });
+        }
+        Ok(())
+    }
+
+    /// Updates the effective balances of the validators touched by the projection.
+    ///
+    /// The balances of all other validators are unchanged, so their effective balances are too.
+    fn process_effective_balance_updates(&mut self) -> Result<(), Error> {
+        let increment = self.spec.effective_balance_increment;
+        let hysteresis_increment = increment.safe_div(self.spec.hysteresis_quotient)?;
+        let downward_threshold =
+            hysteresis_increment.safe_mul(self.spec.hysteresis_downward_multiplier)?;
+        let upward_threshold =
+            hysteresis_increment.safe_mul(self.spec.hysteresis_upward_multiplier)?;
+
+        for validator in self.validators.values_mut() {
+            let balance = validator.balance;
+            if balance.safe_add(downward_threshold)? < validator.effective_balance
+                || validator.effective_balance.safe_add(upward_threshold)? < balance
+            {
+                validator.effective_balance = min(
+                    balance.safe_sub(balance.safe_rem(increment)?)?,
+                    validator.max_effective_balance,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_chain::test_utils::BeaconChainHarness;
+    use std::sync::Arc;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    const NUM_VALIDATORS: usize = 8;
+
+    fn electra_state() -> (BeaconState<E>, ChainSpec) {
+        let spec = ForkName::Electra.make_genesis_spec(E::default_spec());
+        let harness = BeaconChainHarness::builder(E::default())
+            .spec(Arc::new(spec.clone()))
+            .deterministic_keypairs(NUM_VALIDATORS)
+            .fresh_ephemeral_store()
+            .build();
+        let mut state = harness.get_current_state();
+        state.build_total_active_balance_cache(&spec).unwrap();
+        (state, spec)
+    }
+
+    #[test]
+    fn constant_without_queues() {
+        let (state, spec) = electra_state();
+        let projections = project_validator_balances(&state, &[0, 1], 4, &spec).unwrap();
+
+        assert_eq!(projections.len(), 2);
+        for projection in projections {
+            let index = projection.validator_index as usize;
+            assert_eq!(projection.balances.len(), 5);
+            for (i, projected) in projection.balances.iter().enumerate() {
+                assert_eq!(projected.epoch, state.current_epoch() + i as u64);
+                assert_eq!(
+                    projected.balance,
+                    state.balances().get(index).copied().unwrap()
+                );
+                assert_eq!(
+                    projected.effective_balance,
+                    state.get_validator(index).unwrap().effective_balance
+                );
+                assert_eq!(projected.pending_deposits, 0);
+            }
+        }
+    }
+
+    #[test]
+    fn processes_deposits_within_churn() {
+        let (mut state, spec) = electra_state();
+        let churn = state.get_activation_exit_churn_limit(&spec).unwrap();
+        let initial_balance = state.get_balance(0).unwrap();
+
+        // Two deposits which each fill the churn of an epoch.
+        for _ in 0..2 {
+            state
+                .pending_balance_deposits_mut()
+                .unwrap()
+                .push(PendingBalanceDeposit {
+                    index: 0,
+                    amount: churn,
+                })
+                .unwrap();
+        }
+
+        let balances = project_validator_balances(&state, &[0], 3, &spec)
+            .unwrap()
+            .remove(0)
+            .balances;
+        let pending = balances
+            .iter()
+            .map(|projected| projected.pending_deposits)
+            .collect::<Vec<_>>();
+        let deposited = balances
+            .iter()
+            .map(|projected| projected.balance - initial_balance)
+            .collect::<Vec<_>>();
+        assert_eq!(pending, vec![2 * churn, churn, 0, 0]);
+        assert_eq!(deposited, vec![0, churn, 2 * churn, 2 * churn]);
+
+        // The validator doesn't have compounding credentials, so its effective balance is capped.
+        assert!(balances
+            .iter()
+            .all(|projected| projected.effective_balance == spec.min_activation_balance));
+    }
+}
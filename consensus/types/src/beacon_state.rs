@@ -7,7 +7,7 @@ use compare_fields::CompareFields;
 use compare_fields_derive::CompareFields;
 use derivative::Derivative;
 use ethereum_hashing::hash;
-use int_to_bytes::{int_to_bytes4, int_to_bytes8};
+use int_to_bytes::{int_to_bytes4, int_to_bytes8, int_to_fixed_bytes32};
 use metastruct::{metastruct, NumFields};
 pub use pubkey_cache::PubkeyCache;
 use safe_arith::{ArithError, SafeArith};
@@ -158,6 +158,7 @@ pub enum Error {
     IndexNotSupported(usize),
     InvalidFlagIndex(usize),
     MerkleTreeError(merkle_proof::MerkleTreeError),
+    ProofChunkOutOfBounds(usize),
     PartialWithdrawalCountInvalid(usize),
     NonExecutionAddresWithdrawalCredential,
     NoCommitteeFound(CommitteeIndex),
@@ -1498,6 +1499,19 @@ impl<E: EthSpec> BeaconState<E> {
             .ok_or(Error::BalancesOutOfBounds(validator_index))
     }
 
+    /// Return the index in `randao_mixes` of the mix from which the seed for `epoch` is generated.
+    pub fn get_seed_randao_mix_index(
+        &self,
+        epoch: Epoch,
+        spec: &ChainSpec,
+    ) -> Result<usize, Error> {
+        let i = epoch
+            .safe_add(E::EpochsPerHistoricalVector::to_u64())?
+            .safe_sub(spec.min_seed_lookahead)?
+            .safe_sub(1)?;
+        Ok(i.as_usize().safe_rem(self.randao_mixes().len())?)
+    }
+
     /// Generate a seed for the given `epoch`.
     pub fn get_seed(
         &self,
@@ -1508,14 +1522,10 @@ impl<E: EthSpec> BeaconState<E> {
         // Bypass the safe getter for RANDAO so we can gracefully handle the scenario where `epoch
         // == 0`.
         let mix = {
-            let i = epoch
-                .safe_add(E::EpochsPerHistoricalVector::to_u64())?
-                .safe_sub(spec.min_seed_lookahead)?
-                .safe_sub(1)?;
-            let i_mod = i.as_usize().safe_rem(self.randao_mixes().len())?;
+            let i = self.get_seed_randao_mix_index(epoch, spec)?;
             self.randao_mixes()
-                .get(i_mod)
-                .ok_or(Error::RandaoMixesOutOfBounds(i_mod))?
+                .get(i)
+                .ok_or(Error::RandaoMixesOutOfBounds(i))?
         };
         let domain_bytes = int_to_bytes4(spec.get_domain_constant(domain_type));
         let epoch_bytes = int_to_bytes8(epoch.as_u64());
@@ -2551,6 +2561,131 @@ impl<E: EthSpec> BeaconState<E> {
         Ok(proof)
     }
 
+    /// Computes a proof of the top-level field at `field_index` against the state root.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn compute_field_state_proof(&self, field_index: usize) -> Result<StateProof, Error> {
+        let leaves = self.get_beacon_state_leaves();
+        let depth = self.num_fields_pow2().ilog2() as usize;
+        let tree = merkle_proof::MerkleTree::create(&leaves, depth);
+        let (leaf, branch) = tree.generate_proof(field_index, depth)?;
+        Ok(StateProof {
+            gindex: (1u64 << depth) | field_index as u64,
+            leaf,
+            branch,
+        })
+    }
+
+    /// Computes proofs of the validator records at each of `indices` against the state root.
+    ///
+    /// This builds the tree of the whole registry, so callers which need proofs against the same
+    /// state repeatedly should keep the tree from `Self::validators_proof_tree` instead.
+    pub fn compute_validator_state_proofs(
+        &self,
+        indices: &[usize],
+    ) -> Result<Vec<StateProof>, Error> {
+        let validators_len = self.validators().len();
+        if let Some(&index) = indices.iter().find(|&&index| index >= validators_len) {
+            return Err(Error::UnknownValidator(index));
+        }
+        self.validators_proof_tree()?.proofs(indices)
+    }
+
+    /// Builds the tree of the validator registry, from which proofs of validator records against
+    /// the state root are generated.
+    pub fn validators_proof_tree(&self) -> Result<CollectionProofTree, Error> {
+        let validators = self.validators();
+        let chunks = validators
+            .iter()
+            .map(|validator| validator.tree_hash_root())
+            .collect::<Vec<_>>();
+        self.collection_proof_tree(
+            state_proof::VALIDATORS_FIELD_INDEX,
+            &chunks,
+            E::ValidatorRegistryLimit::to_usize(),
+            Some(validators.len()),
+        )
+    }
+
+    /// Computes proofs of the balances at each of `indices` against the state root.
+    ///
+    /// Balances are packed four to a leaf, so the leaf of each proof contains the balance at
+    /// `index % 4` along with three of its neighbours.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn compute_balance_state_proofs(
+        &self,
+        indices: &[usize],
+    ) -> Result<Vec<StateProof>, Error> {
+        const BALANCES_PER_CHUNK: usize = 4;
+
+        let balances = self.balances();
+        if let Some(&index) = indices.iter().find(|&&index| index >= balances.len()) {
+            return Err(Error::UnknownValidator(index));
+        }
+        let chunks = balances
+            .iter()
+            .collect::<Vec<_>>()
+            .chunks(BALANCES_PER_CHUNK)
+            .map(|balances| {
+                let mut chunk = [0; 32];
+                for (bytes, balance) in chunk.chunks_mut(8).zip(balances) {
+                    bytes.copy_from_slice(&balance.to_le_bytes());
+                }
+                Hash256::from_slice(&chunk)
+            })
+            .collect::<Vec<_>>();
+        let chunk_indices = indices
+            .iter()
+            .map(|index| index / BALANCES_PER_CHUNK)
+            .collect::<Vec<_>>();
+        self.collection_proof_tree(
+            state_proof::BALANCES_FIELD_INDEX,
+            &chunks,
+            E::ValidatorRegistryLimit::to_usize().div_ceil(BALANCES_PER_CHUNK),
+            Some(balances.len()),
+        )?
+        .proofs(&chunk_indices)
+    }
+
+    /// Computes a proof of the RANDAO mix at `index` against the state root.
+    pub fn compute_randao_mix_state_proof(&self, index: usize) -> Result<StateProof, Error> {
+        let randao_mixes = self.randao_mixes();
+        if index >= randao_mixes.len() {
+            return Err(Error::RandaoMixesOutOfBounds(index));
+        }
+        let chunks = randao_mixes.iter().copied().collect::<Vec<_>>();
+        self.collection_proof_tree(
+            state_proof::RANDAO_MIXES_FIELD_INDEX,
+            &chunks,
+            E::EpochsPerHistoricalVector::to_usize(),
+            None,
+        )?
+        .proofs(&[index])?
+        .pop()
+        .ok_or(Error::RandaoMixesOutOfBounds(index))
+    }
+
+    /// Builds the tree of `chunks`, the chunks of the list or vector at `field_index`.
+    ///
+    /// A list has a `len`, which is mixed in to the root of the collection.
+    #[allow(clippy::arithmetic_side_effects)]
+    fn collection_proof_tree(
+        &self,
+        field_index: usize,
+        chunks: &[Hash256],
+        chunk_limit: usize,
+        len: Option<usize>,
+    ) -> Result<CollectionProofTree, Error> {
+        let field_proof = self.compute_field_state_proof(field_index)?;
+        let depth = chunk_limit.next_power_of_two().ilog2() as usize;
+        Ok(CollectionProofTree {
+            tree: merkle_proof::MerkleTree::create(chunks, depth),
+            depth,
+            num_chunks: chunks.len(),
+            length: len.map(|len| Hash256::from_slice(&int_to_fixed_bytes32(len as u64))),
+            field_proof,
+        })
+    }
+
     fn generate_proof(
         &self,
         field_index: usize,
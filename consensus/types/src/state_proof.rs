@@ -0,0 +1,90 @@
+use crate::{BeaconStateError, Hash256};
+use merkle_proof::MerkleTree;
+use serde::{Deserialize, Serialize};
+
+/// The indices of top-level fields of the `BeaconState`, which are the same in all forks in which
+/// the field exists.
+pub const VALIDATORS_FIELD_INDEX: usize = 11;
+pub const BALANCES_FIELD_INDEX: usize = 12;
+pub const RANDAO_MIXES_FIELD_INDEX: usize = 13;
+pub const NEXT_WITHDRAWAL_INDEX_FIELD_INDEX: usize = 25;
+pub const NEXT_WITHDRAWAL_VALIDATOR_INDEX_FIELD_INDEX: usize = 26;
+
+/// A Merkle proof of a single leaf of the `BeaconState` tree against the state root.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateProof {
+    /// The generalized index of the leaf within the state tree.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub gindex: u64,
+    /// The leaf, which is the tree hash root of a field or list element. Basic types such as
+    /// balances are packed several to a leaf.
+    pub leaf: Hash256,
+    /// The sibling of each node on the path from the leaf to the state root, leaf first.
+    pub branch: Vec<Hash256>,
+}
+
+impl StateProof {
+    /// Returns `true` if the proof is valid against `state_root`.
+    pub fn verify(&self, state_root: Hash256) -> bool {
+        let Some(depth) = self.gindex.checked_ilog2() else {
+            return false;
+        };
+        // The index of the leaf within its row is the generalized index without its leading bit.
+        let index = self.gindex ^ 1u64.wrapping_shl(depth);
+        merkle_proof::verify_merkle_proof(
+            self.leaf,
+            &self.branch,
+            depth as usize,
+            index as usize,
+            state_root,
+        )
+    }
+}
+
+/// A Merkle tree of the chunks of a list or vector field of the `BeaconState`, from which proofs of
+/// chunks against the state root are generated.
+///
+/// Building the tree hashes every chunk of the field, which for the validator registry is far more
+/// expensive than generating proofs from it, so it's worth keeping while proofs against the same
+/// state are requested.
+pub struct CollectionProofTree {
+    pub(crate) tree: MerkleTree,
+    pub(crate) depth: usize,
+    pub(crate) num_chunks: usize,
+    /// The length mixed in to the root of a list, or `None` for a vector.
+    pub(crate) length: Option<Hash256>,
+    /// The proof of the field against the state root.
+    pub(crate) field_proof: StateProof,
+}
+
+impl CollectionProofTree {
+    /// Generates proofs of the chunks at each of `chunk_indices` against the state root.
+    ///
+    /// The proof of each chunk runs up to the root of the collection, through the length mix-in of
+    /// a list, and on to the state root.
+    #[allow(clippy::arithmetic_side_effects)]
+    pub fn proofs(&self, chunk_indices: &[usize]) -> Result<Vec<StateProof>, BeaconStateError> {
+        let field_depth = if self.length.is_some() {
+            self.depth + 1
+        } else {
+            self.depth
+        };
+
+        chunk_indices
+            .iter()
+            .map(|&chunk_index| {
+                if chunk_index >= self.num_chunks {
+                    return Err(BeaconStateError::ProofChunkOutOfBounds(chunk_index));
+                }
+                let (leaf, mut branch) = self.tree.generate_proof(chunk_index, self.depth)?;
+                branch.extend(self.length);
+                branch.extend_from_slice(&self.field_proof.branch);
+                Ok(StateProof {
+                    gindex: (self.field_proof.gindex << field_depth) | chunk_index as u64,
+                    leaf,
+                    branch,
+                })
+            })
+            .collect()
+    }
+}
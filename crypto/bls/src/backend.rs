@@ -0,0 +1,170 @@
+//! Detection of the BLS backend at startup.
+//!
+//! The instructions used by `blst` are fixed when it is compiled:
+//!
+//! - `supranational-portable` builds run on any CPU, using ADX instructions only if the CPU
+//!   supports them.
+//! - `supranational-force-adx` builds always use ADX instructions, and crash with `SIGILL` on CPUs
+//!   which don't support them.
+//! - Other builds use the instructions supported by the CPU of the machine they were built on.
+//!
+//! Rather than discovering an incompatible build through a crash, `init` probes the features of
+//! the CPU at startup, compares them with the build and reports the backend in use. The backend
+//! itself can only be changed by rebuilding.
+//!
+//! `init` also configures the batch size used by `verify_signature_sets_batched`.
+use rayon::prelude::*;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+/// The backend detected by `init`.
+static SELECTED_BACKEND: OnceLock<Backend> = OnceLock::new();
+
+/// The maximum number of signature sets verified in a single batch, or zero for no limit.
+static BATCH_SIZE: AtomicUsize = AtomicUsize::new(0);
+
+/// The CPU features which determine the code path used by `blst`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CpuFeatures {
+    pub adx: bool,
+    pub bmi2: bool,
+}
+
+impl CpuFeatures {
+    /// Probes the features of the CPU that this process is running on.
+    pub fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        return Self {
+            adx: std::is_x86_feature_detected!("adx"),
+            bmi2: std::is_x86_feature_detected!("bmi2"),
+        };
+
+        #[cfg(not(target_arch = "x86_64"))]
+        return Self::default();
+    }
+
+    /// Returns `true` if the CPU can run the ADX-optimized code path.
+    pub fn supports_adx(&self) -> bool {
+        self.adx && self.bmi2
+    }
+}
+
+/// The way in which the BLS library was compiled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuildVariant {
+    /// Runs on any CPU, detecting ADX support at runtime.
+    Portable,
+    /// Always uses ADX instructions.
+    ForceAdx,
+    /// Uses the instructions supported by the CPU of the build machine, which included ADX if
+    /// `adx` is set.
+    Native { adx: bool },
+    /// Signatures are not verified, for testing only.
+    FakeCrypto,
+}
+
+impl BuildVariant {
+    /// Returns the variant that this crate was compiled as.
+    pub fn compiled() -> Self {
+        if cfg!(feature = "fake_crypto") {
+            BuildVariant::FakeCrypto
+        } else if cfg!(feature = "supranational-portable") {
+            BuildVariant::Portable
+        } else if cfg!(feature = "supranational-force-adx") {
+            BuildVariant::ForceAdx
+        } else {
+            BuildVariant::Native {
+                adx: cfg!(target_feature = "adx") && cfg!(target_feature = "bmi2"),
+            }
+        }
+    }
+}
+
+impl fmt::Display for BuildVariant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildVariant::Portable => write!(f, "portable"),
+            BuildVariant::ForceAdx => write!(f, "force-adx"),
+            BuildVariant::Native { adx: false } => write!(f, "native"),
+            BuildVariant::Native { adx: true } => write!(f, "native-adx"),
+            BuildVariant::FakeCrypto => write!(f, "fake-crypto"),
+        }
+    }
+}
+
+/// The backend used to verify signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Generic assembly, without ADX instructions.
+    Portable,
+    /// Assembly optimized with the ADX and BMI2 instructions.
+    Adx,
+    /// Signatures are not verified, for testing only.
+    FakeCrypto,
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Backend::Portable => write!(f, "portable"),
+            Backend::Adx => write!(f, "adx"),
+            Backend::FakeCrypto => write!(f, "fake-crypto"),
+        }
+    }
+}
+
+/// Returns the backend that the `build` uses on the `cpu`.
+///
+/// Returns an error if the build uses instructions that the CPU doesn't support.
+pub fn select_backend(build: BuildVariant, cpu: CpuFeatures) -> Result<Backend, String> {
+    match build {
+        BuildVariant::FakeCrypto => Ok(Backend::FakeCrypto),
+        // Portable builds are compiled with both code paths, and `blst` runs the ADX one if the
+        // CPU supports it.
+        BuildVariant::Portable if cpu.supports_adx() => Ok(Backend::Adx),
+        BuildVariant::Portable | BuildVariant::Native { adx: false } => Ok(Backend::Portable),
+        BuildVariant::ForceAdx | BuildVariant::Native { adx: true } if cpu.supports_adx() => {
+            Ok(Backend::Adx)
+        }
+        BuildVariant::ForceAdx | BuildVariant::Native { adx: true } => Err(format!(
+            "this {build} build of the BLS library requires the ADX and BMI2 instructions, \
+             which this CPU doesn't support. Use a portable build instead."
+        )),
+    }
+}
+
+/// Checks that the compiled backend can run on this CPU and sets the maximum number of signature
+/// sets to verify in a single batch (zero for no limit).
+///
+/// Should be called once at startup, before any signatures are verified. Subsequent calls only
+/// update the batch size.
+pub fn init(batch_size: usize) -> Result<Backend, String> {
+    let backend = select_backend(BuildVariant::compiled(), CpuFeatures::detect())?;
+    BATCH_SIZE.store(batch_size, Ordering::Relaxed);
+    Ok(*SELECTED_BACKEND.get_or_init(|| backend))
+}
+
+/// Returns the backend detected by `init`, if any.
+pub fn selected_backend() -> Option<Backend> {
+    SELECTED_BACKEND.get().copied()
+}
+
+/// Returns the maximum number of signature sets verified in a single batch, or zero for no limit.
+pub fn batch_size() -> usize {
+    BATCH_SIZE.load(Ordering::Relaxed)
+}
+
+/// Verifies `items` using `verify` in batches of at most `batch_size()` items, verifying the
+/// batches in parallel.
+///
+/// Returns `false` if there are no items, for consistency with `verify_signature_sets`.
+pub(crate) fn verify_in_batches<T: Sync>(
+    items: &[T],
+    verify: impl Fn(&[T]) -> bool + Send + Sync,
+) -> bool {
+    match batch_size() {
+        size if size == 0 || size >= items.len() => verify(items),
+        size => items.par_chunks(size).all(verify),
+    }
+}
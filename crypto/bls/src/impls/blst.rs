@@ -20,6 +20,7 @@ pub mod types {
     pub use super::blst_core::PublicKey;
     pub use super::blst_core::SecretKey;
     pub use super::blst_core::Signature;
+    pub use super::combine_signature_shares;
     pub use super::verify_signature_sets;
     pub use super::BlstAggregatePublicKey as AggregatePublicKey;
     pub use super::BlstAggregateSignature as AggregateSignature;
@@ -153,6 +154,97 @@ impl TPublicKey for blst_core::PublicKey {
     }
 }
 
+/// Combine signatures from the shares of a secret key using Lagrange interpolation at zero.
+pub fn combine_signature_shares(
+    shares: &[(u64, &blst_core::Signature)],
+) -> Result<blst_core::Signature, Error> {
+    if shares.is_empty() {
+        return Err(Error::NoSignatureShares);
+    }
+
+    let indices = shares.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    let mut combined = blst::blst_p2::default();
+
+    for (i, (_, signature)) in shares.iter().enumerate() {
+        let coefficient = lagrange_coefficient(&indices, i)?;
+        let compressed = signature.compress();
+
+        let mut affine = blst::blst_p2_affine::default();
+        let mut point = blst::blst_p2::default();
+        let mut term = blst::blst_p2::default();
+        let combined_ptr: *mut blst::blst_p2 = &mut combined;
+
+        // TODO: remove this `unsafe` code-block once we get a safe option from `blst`.
+        unsafe {
+            let result = blst::blst_p2_uncompress(&mut affine, compressed.as_ptr());
+            if result != BLST_ERROR::BLST_SUCCESS {
+                return Err(result.into());
+            }
+            blst::blst_p2_from_affine(&mut point, &affine);
+            blst::blst_p2_mult(&mut term, &point, coefficient.b.as_ptr(), 255);
+            blst::blst_p2_add_or_double(combined_ptr, combined_ptr, &term);
+        }
+    }
+
+    let mut bytes = [0; SIGNATURE_BYTES_LEN];
+    // TODO: remove this `unsafe` code-block once we get a safe option from `blst`.
+    unsafe {
+        blst::blst_p2_compress(bytes.as_mut_ptr(), &combined);
+    }
+    blst_core::Signature::from_bytes(&bytes).map_err(Into::into)
+}
+
+/// Returns the Lagrange coefficient at zero of the share at position `i` of `indices`.
+fn lagrange_coefficient(indices: &[u64], i: usize) -> Result<blst_scalar, Error> {
+    let fr_from_u64 = |value: u64| {
+        let mut fr = blst::blst_fr::default();
+        // TODO: remove this `unsafe` code-block once we get a safe option from `blst`.
+        unsafe {
+            blst::blst_fr_from_uint64(&mut fr, [value, 0, 0, 0].as_ptr());
+        }
+        fr
+    };
+
+    let index_i = indices[i];
+    if index_i == 0 {
+        return Err(Error::InvalidSignatureShareIndex(index_i));
+    }
+    let x_i = fr_from_u64(index_i);
+    let mut numerator = fr_from_u64(1);
+    let mut denominator = fr_from_u64(1);
+
+    for (j, index_j) in indices.iter().enumerate() {
+        if j == i {
+            continue;
+        }
+        if *index_j == index_i {
+            return Err(Error::InvalidSignatureShareIndex(index_i));
+        }
+        let x_j = fr_from_u64(*index_j);
+        let mut difference = blst::blst_fr::default();
+        let numerator_ptr: *mut blst::blst_fr = &mut numerator;
+        let denominator_ptr: *mut blst::blst_fr = &mut denominator;
+
+        // TODO: remove this `unsafe` code-block once we get a safe option from `blst`.
+        unsafe {
+            blst::blst_fr_mul(numerator_ptr, numerator_ptr, &x_j);
+            blst::blst_fr_sub(&mut difference, &x_j, &x_i);
+            blst::blst_fr_mul(denominator_ptr, denominator_ptr, &difference);
+        }
+    }
+
+    let mut inverse = blst::blst_fr::default();
+    let mut coefficient = blst::blst_fr::default();
+    let mut scalar = blst_scalar::default();
+    // TODO: remove this `unsafe` code-block once we get a safe option from `blst`.
+    unsafe {
+        blst::blst_fr_inverse(&mut inverse, &denominator);
+        blst::blst_fr_mul(&mut coefficient, &numerator, &inverse);
+        blst::blst_scalar_from_fr(&mut scalar, &coefficient);
+    }
+    Ok(scalar)
+}
+
 /// A wrapper that allows for `PartialEq` and `Clone` impls.
 pub struct BlstAggregatePublicKey(blst_core::AggregatePublicKey);
 
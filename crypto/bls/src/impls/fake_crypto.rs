@@ -11,6 +11,7 @@ use crate::{
 
 /// Provides the externally-facing, core BLS types.
 pub mod types {
+    pub use super::combine_signature_shares;
     pub use super::verify_signature_sets;
     pub use super::AggregatePublicKey;
     pub use super::AggregateSignature;
@@ -34,6 +35,13 @@ pub fn verify_signature_sets<'a>(
     true
 }
 
+pub fn combine_signature_shares(shares: &[(u64, &Signature)]) -> Result<Signature, Error> {
+    shares
+        .first()
+        .map(|(_, signature)| (*signature).clone())
+        .ok_or(Error::NoSignatureShares)
+}
+
 #[derive(Clone)]
 pub struct PublicKey([u8; PUBLIC_KEY_BYTES_LEN]);
 
@@ -31,6 +31,7 @@ mod generic_signature_set;
 mod get_withdrawal_credentials;
 mod zeroize_hash;
 
+pub mod backend;
 pub mod impls;
 
 pub use generic_public_key::{
@@ -60,6 +61,12 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// No signature shares were provided to combine.
+    NoSignatureShares,
+    /// A signature share has an index of zero or the same index as another share.
+    InvalidSignatureShareIndex(u64),
+    /// A signature share is the "empty" signature, which can't be combined.
+    EmptySignatureShare(u64),
 }
 
 #[cfg(feature = "supranational")]
@@ -123,6 +130,40 @@ macro_rules! define_mod {
                 bls_variant::SecretKey,
                 bls_variant::Signature,
             >;
+
+            /// Verify `signature_sets` in batches of at most `backend::batch_size()` sets, with
+            /// the batches verified in parallel.
+            ///
+            /// Equivalent to `verify_signature_sets` when the batch size is unbounded (the
+            /// default). Smaller batches bound the latency of verifying large numbers of sets, at
+            /// the cost of some of the efficiency of batch verification.
+            pub fn verify_signature_sets_batched(signature_sets: &[SignatureSet]) -> bool {
+                crate::backend::verify_in_batches(signature_sets, |batch| {
+                    verify_signature_sets(batch.iter())
+                })
+            }
+
+            /// Combine the signatures produced by the shares of a threshold secret key into a
+            /// signature from the secret key itself.
+            ///
+            /// Each share is identified by its index: the non-zero point at which the polynomial
+            /// used to split the secret key was evaluated. The result is only valid if at least
+            /// the threshold number of shares are provided.
+            pub fn combine_signature_shares(
+                shares: &[(u64, Signature)],
+            ) -> Result<Signature, crate::Error> {
+                let points = shares
+                    .iter()
+                    .map(|(index, signature)| {
+                        signature
+                            .point()
+                            .map(|point| (*index, point))
+                            .ok_or(crate::Error::EmptySignatureShare(*index))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let point = bls_variant::combine_signature_shares(&points)?;
+                Signature::deserialize(&crate::generic_signature::TSignature::serialize(&point))
+            }
         }
     };
 }
@@ -19,6 +19,34 @@ macro_rules! test_suite {
             SecretKey::deserialize(&secret_bytes).unwrap()
         }
 
+        #[test]
+        fn combine_signature_shares_2_of_3() {
+            // Split the secret key 5 with the polynomial `f(x) = 5 + 7x`.
+            let secret = secret_from_u64(4);
+            let share = |index: u64| secret_from_u64(4 + 7 * index);
+            let msg = Hash256::from_low_u64_be(42);
+
+            let shares = (1..=3)
+                .map(|index| (index, share(index).sign(msg)))
+                .collect::<Vec<_>>();
+
+            // Any two shares are sufficient.
+            for pair in [[0, 1], [0, 2], [2, 1]] {
+                let pair = pair.map(|i| shares[i].clone());
+                let combined = combine_signature_shares(&pair).unwrap();
+                assert_eq!(combined, secret.sign(msg));
+                assert!(combined.verify(&secret.public_key(), msg));
+            }
+
+            // A single share is not.
+            let combined = combine_signature_shares(&shares[..1]).unwrap();
+            assert!(!combined.verify(&secret.public_key(), msg));
+
+            assert!(combine_signature_shares(&[]).is_err());
+            assert!(combine_signature_shares(&[shares[0].clone(), shares[0].clone()]).is_err());
+            assert!(combine_signature_shares(&[(0, shares[0].1.clone())]).is_err());
+        }
+
         #[test]
         fn invalid_zero_secret_key() {
             assert!(SecretKey::deserialize(&[0; SECRET_KEY_BYTES_LEN]).is_err());
@@ -449,6 +477,10 @@ macro_rules! test_suite {
                     verify_signature_sets(signature_sets.iter()),
                     should_be_valid
                 );
+                assert_eq!(
+                    verify_signature_sets_batched(&signature_sets),
+                    should_be_valid
+                );
             }
         }
 
@@ -508,9 +540,83 @@ macro_rules! test_suite {
                 .push_valid_set(2)
                 .run_checks()
         }
+
+        #[test]
+        fn signature_set_batched_with_1_invalid_set() {
+            bls::backend::init(2).unwrap();
+            SignatureSetTester::default()
+                .push_valid_set(1)
+                .push_valid_set(2)
+                .push_valid_set(3)
+                .push_invalid_set()
+                .push_valid_set(1)
+                .run_checks()
+        }
     };
 }
 
 mod blst {
     test_suite!(blst_implementations);
 }
+
+mod backend {
+    use bls::backend::{select_backend, Backend, BuildVariant, CpuFeatures};
+
+    const ADX: CpuFeatures = CpuFeatures {
+        adx: true,
+        bmi2: true,
+    };
+    const NO_ADX: CpuFeatures = CpuFeatures {
+        adx: false,
+        bmi2: false,
+    };
+
+    #[test]
+    fn portable_build_uses_adx_if_supported() {
+        assert_eq!(
+            select_backend(BuildVariant::Portable, ADX),
+            Ok(Backend::Adx)
+        );
+        assert_eq!(
+            select_backend(BuildVariant::Portable, NO_ADX),
+            Ok(Backend::Portable)
+        );
+    }
+
+    #[test]
+    fn native_build_uses_compiled_instructions() {
+        for cpu in [ADX, NO_ADX] {
+            assert_eq!(
+                select_backend(BuildVariant::Native { adx: false }, cpu),
+                Ok(Backend::Portable)
+            );
+        }
+        assert_eq!(
+            select_backend(BuildVariant::Native { adx: true }, ADX),
+            Ok(Backend::Adx)
+        );
+        assert!(select_backend(BuildVariant::Native { adx: true }, NO_ADX).is_err());
+    }
+
+    #[test]
+    fn force_adx_build_requires_adx_cpu() {
+        assert_eq!(
+            select_backend(BuildVariant::ForceAdx, ADX),
+            Ok(Backend::Adx)
+        );
+        assert!(select_backend(BuildVariant::ForceAdx, NO_ADX).is_err());
+        let only_adx = CpuFeatures {
+            adx: true,
+            bmi2: false,
+        };
+        assert!(select_backend(BuildVariant::ForceAdx, only_adx).is_err());
+    }
+
+    #[test]
+    fn fake_crypto_build_ignores_cpu() {
+        assert_eq!(
+            select_backend(BuildVariant::FakeCrypto, NO_ADX),
+            Ok(Backend::FakeCrypto)
+        );
+    }
+}
@@ -13,6 +13,7 @@ use eth2_keystore::{
 };
 pub use eth2_keystore::{Error as KeystoreError, PlainText};
 use rand::prelude::*;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
 pub use uuid::Uuid;
@@ -215,6 +216,51 @@ impl Wallet {
         Ok(keystores)
     }
 
+    /// Produces the `Keystore`s for the next `passwords.len()` validators, with the voting and
+    /// withdrawal keystores of each validator encrypted with the corresponding pair of passwords.
+    ///
+    /// Equivalent to calling `Self::next_validator` once per pair of passwords, except that the
+    /// seed is only decrypted once and the keystores are encrypted in parallel. `nextaccount` is
+    /// only incremented if all keystores are generated successfully.
+    pub fn next_validators(
+        &mut self,
+        wallet_password: &[u8],
+        passwords: &[(&[u8], &[u8])],
+    ) -> Result<Vec<ValidatorKeystores>, Error> {
+        let first_account = self.json.nextaccount;
+        let next_account = u32::try_from(passwords.len())
+            .ok()
+            .and_then(|count| first_account.checked_add(count))
+            .ok_or(Error::PathExhausted)?;
+        let seed = self.decrypt_seed(wallet_password)?;
+
+        let derive = |index: u32, key_type: KeyType, password: &[u8]| -> Result<Keystore, Error> {
+            let (secret, path) =
+                recover_validator_secret_from_mnemonic(seed.as_bytes(), index, key_type)?;
+
+            let keypair = keypair_from_secret(secret.as_bytes())?;
+
+            KeystoreBuilder::new(&keypair, password, format!("{}", path))?
+                .build()
+                .map_err(Into::into)
+        };
+
+        let keystores = passwords
+            .par_iter()
+            .zip(first_account..next_account)
+            .map(|((voting_password, withdrawal_password), index)| {
+                Ok(ValidatorKeystores {
+                    voting: derive(index, KeyType::Voting, voting_password)?,
+                    withdrawal: derive(index, KeyType::Withdrawal, withdrawal_password)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.json.nextaccount = next_account;
+
+        Ok(keystores)
+    }
+
     /// Returns the value of the JSON wallet `nextaccount` field.
     ///
     /// This will be the index of the next wallet generated with `Self::next_validator`.
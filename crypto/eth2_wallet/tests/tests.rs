@@ -279,3 +279,65 @@ fn key_derivation_from_seed() {
         assert_eq!(wallet.nextaccount(), i + 1, "updated nextaccount");
     }
 }
+
+#[test]
+fn batch_key_derivation_from_seed() {
+    let mut wallet = wallet_from_seed();
+
+    // Start part-way through the wallet to check the batch is offset by `nextaccount`.
+    wallet
+        .next_validator(
+            WALLET_PASSWORD,
+            VOTING_KEYSTORE_PASSWORD,
+            WITHDRAWAL_KEYSTORE_PASSWORD,
+        )
+        .expect("should generate keystores");
+
+    let passwords = vec![(VOTING_KEYSTORE_PASSWORD, WITHDRAWAL_KEYSTORE_PASSWORD); 3];
+    let keystores = wallet
+        .next_validators(WALLET_PASSWORD, &passwords)
+        .expect("should generate keystores");
+
+    assert_eq!(wallet.nextaccount(), 4, "updated nextaccount");
+
+    for (keystores, i) in keystores.iter().zip(1..) {
+        assert_eq!(
+            keystores.voting.path().unwrap(),
+            format!("m/12381/3600/{}/0/0", i),
+            "voting path should match"
+        );
+
+        let voting_keypair = keystores
+            .voting
+            .decrypt_keypair(VOTING_KEYSTORE_PASSWORD)
+            .expect("should decrypt voting keypair");
+
+        assert_eq!(
+            voting_keypair.sk.serialize().as_ref(),
+            &manually_derived_voting_key(i)[..],
+            "voting secret should match manually derived"
+        );
+
+        let withdrawal_keypair = keystores
+            .withdrawal
+            .decrypt_keypair(WITHDRAWAL_KEYSTORE_PASSWORD)
+            .expect("should decrypt withdrawal keypair");
+
+        assert_eq!(
+            withdrawal_keypair.sk.serialize().as_ref(),
+            &manually_derived_withdrawal_key(i)[..],
+            "withdrawal secret should match manually derived"
+        );
+    }
+
+    assert_eq!(
+        wallet.next_validators(&[43; 1], &passwords).err(),
+        Some(Error::KeystoreError(KeystoreError::InvalidPassword)),
+        "should not derive keys with an incorrect wallet password"
+    );
+    assert_eq!(
+        wallet.nextaccount(),
+        4,
+        "nextaccount unchanged after failure"
+    );
+}
@@ -81,6 +81,8 @@ pub enum DatabaseManagerSubcommand {
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
     Compact(Compact),
+    ExportEra(ExportEra),
+    ImportEra(ImportEra),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -192,6 +194,54 @@ pub struct PruneStates {
     pub confirm: bool,
 }
 
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Export finalized blocks and states to era files. Requires the execution payloads \
+             and the historic states at era boundaries to be stored.",
+    alias = "export_era"
+)]
+pub struct ExportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory in which to write the era files.",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "ERA",
+        default_value_t = 0,
+        help = "The first era to export.",
+        display_order = 0
+    )]
+    pub start_era: u64,
+
+    #[clap(
+        long,
+        value_name = "ERA",
+        help = "The last era to export. Defaults to the last finalized era.",
+        display_order = 0
+    )]
+    pub end_era: Option<u64>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Backfill blocks from era files, from newest to oldest.",
+    alias = "import_era"
+)]
+pub struct ImportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory containing the era files to import.",
+        display_order = 0
+    )]
+    pub input_dir: PathBuf,
+}
+
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
 #[clap(about = "Compact database manually.")]
 pub struct Compact {
@@ -9,7 +9,7 @@ use beacon_chain::{
 use beacon_node::{get_data_dir, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
-use cli::{Compact, Inspect};
+use cli::{Compact, ExportEra, ImportEra, Inspect};
 use environment::{Environment, RuntimeContext};
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
@@ -461,6 +461,91 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+pub fn export_era<E: EthSpec>(
+    export_config: &ExportEra,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    // The state at the end of an era must be in the freezer database.
+    let split_slot = db.get_split_slot();
+    if split_slot == 0 {
+        return Err("Error: no finalized history to export".into());
+    }
+    let last_era = (split_slot.as_u64() - 1) / E::slots_per_historical_root() as u64;
+    let end_era = export_config.end_era.unwrap_or(last_era);
+    if end_era > last_era {
+        return Err(format!(
+            "Error: era {end_era} is not finalized, the last finalized era is {last_era}"
+        ));
+    }
+
+    fs::create_dir_all(&export_config.output_dir)
+        .map_err(|e| format!("Unable to create output directory: {e:?}"))?;
+
+    for era in export_config.start_era..=end_era {
+        let path = db
+            .export_era(era, &export_config.output_dir)
+            .map_err(|e| format!("Unable to export era {era}: {e:?}"))?;
+        info!(log, "Exported era"; "era" => era, "path" => %path.display());
+    }
+
+    Ok(())
+}
+
+pub fn import_era<E: EthSpec>(
+    import_config: &ImportEra,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let summary = db
+        .import_era_dir(&import_config.input_dir)
+        .map_err(|e| format!("Unable to import era files: {e:?}"))?;
+
+    info!(
+        log,
+        "Era import complete";
+        "blocks" => summary.blocks,
+        "states" => summary.states,
+        "skipped_files" => summary.skipped.len(),
+        "oldest_block_slot" => db.get_oldest_block_slot(),
+    );
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -522,5 +607,11 @@ pub fn run<E: EthSpec>(
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::ExportEra(export_config) => {
+            export_era(export_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::ImportEra(import_config) => {
+            import_era(import_config, client_config, &context, log)
+        }
     }
 }
@@ -0,0 +1,586 @@
+//! # Attack Node
+//!
+//! Dials a beacon node over libp2p and sends it malformed or abusive traffic, reporting how the
+//! node responds to each scenario. Intended for operators to check the DoS hardening of their own
+//! nodes before exposing them to the public network. Never run this against nodes you do not
+//! operate.
+//!
+//! Each scenario uses a fresh connection, so the outcome of one scenario doesn't depend on how
+//! the node reacted to the previous one. Note that the target will likely penalise and eventually
+//! ban the peer id of this tool, which is randomly generated on each run.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli attack-node \
+//!     --target /ip4/127.0.0.1/tcp/9000 \
+//!     --scenarios rpc-garbage,rpc-oversized-length,slow-handshake
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use futures::future::{poll_fn, select, Either};
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use lighthouse_network::libp2p::core::muxing::{StreamMuxerBox, StreamMuxerExt};
+use lighthouse_network::libp2p::core::transport::{DialOpts, PortUse};
+use lighthouse_network::libp2p::core::Endpoint;
+use lighthouse_network::libp2p::identity::Keypair;
+use lighthouse_network::libp2p::Transport;
+use lighthouse_network::multiaddr::Protocol;
+use lighthouse_network::rpc::methods::StatusMessage;
+use lighthouse_network::{build_transport, Multiaddr};
+use rand::RngCore;
+use ssz::Encode;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt as _;
+use tokio::net::TcpStream;
+use types::{Epoch, EthSpec, FixedBytesExtended, Hash256, Slot};
+
+const MULTISTREAM_PROTOCOL: &str = "/multistream/1.0.0";
+const STATUS_PROTOCOL: &str = "/eth2/beacon_chain/req/status/1/ssz_snappy";
+const GOSSIPSUB_PROTOCOL: &str = "/meshsub/1.1.0";
+
+/// A length prefix far larger than any RPC request the node should accept.
+const OVERSIZED_LENGTH: u64 = 1 << 30;
+/// The size of the payload of an oversized gossip message, larger than the 10 MiB gossip limit.
+const OVERSIZED_GOSSIP_BYTES: usize = 12 * 1024 * 1024;
+/// The interval between bytes sent in the slow-loris scenarios.
+const SLOW_BYTE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Scenario {
+    /// A valid status request, as a baseline and to learn the node's fork digest.
+    Status,
+    /// Random bytes on the status protocol.
+    RpcGarbage,
+    /// A status request whose length prefix is far larger than the maximum RPC size.
+    RpcOversizedLength,
+    /// A status request with a valid length prefix but an invalid snappy frame.
+    RpcInvalidSnappy,
+    /// A status request with valid snappy framing but SSZ of the wrong length.
+    RpcInvalidSsz,
+    /// A gossip message larger than the maximum gossip message size.
+    GossipOversized,
+    /// A blob sidecar of random bytes, published on the blob sidecar topic.
+    GossipInvalidBlobSidecar,
+    /// A connection which sends the multistream header one byte at a time.
+    SlowHandshake,
+    /// A status request which is sent one byte at a time.
+    SlowRequest,
+}
+
+impl Scenario {
+    const ALL: [Scenario; 9] = [
+        Scenario::Status,
+        Scenario::RpcGarbage,
+        Scenario::RpcOversizedLength,
+        Scenario::RpcInvalidSnappy,
+        Scenario::RpcInvalidSsz,
+        Scenario::GossipOversized,
+        Scenario::GossipInvalidBlobSidecar,
+        Scenario::SlowHandshake,
+        Scenario::SlowRequest,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Scenario::Status => "status",
+            Scenario::RpcGarbage => "rpc-garbage",
+            Scenario::RpcOversizedLength => "rpc-oversized-length",
+            Scenario::RpcInvalidSnappy => "rpc-invalid-snappy",
+            Scenario::RpcInvalidSsz => "rpc-invalid-ssz",
+            Scenario::GossipOversized => "gossip-oversized",
+            Scenario::GossipInvalidBlobSidecar => "gossip-invalid-blob-sidecar",
+            Scenario::SlowHandshake => "slow-handshake",
+            Scenario::SlowRequest => "slow-request",
+        }
+    }
+}
+
+impl FromStr for Scenario {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scenario::ALL
+            .into_iter()
+            .find(|scenario| scenario.name() == s)
+            .ok_or_else(|| format!("Unknown scenario: {}", s))
+    }
+}
+
+/// How the node responded to a scenario.
+#[derive(Debug)]
+enum Outcome {
+    /// The node sent an RPC response with this response code.
+    Responded(u8),
+    /// The node closed the stream without responding.
+    StreamClosed,
+    /// The stream failed, e.g. because the node reset it.
+    StreamReset(String),
+    /// The node refused to negotiate the protocol.
+    ProtocolRefused,
+    /// The node closed the connection.
+    ConnectionClosed(String),
+    /// The connection was still open after the message was sent.
+    ConnectionOpen,
+    /// The node didn't respond or close the stream before the timeout.
+    Timeout,
+    /// The scenario couldn't be run.
+    Error(String),
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Responded(0) => write!(f, "responded (success)"),
+            Outcome::Responded(1) => write!(f, "responded (invalid request)"),
+            Outcome::Responded(2) => write!(f, "responded (server error)"),
+            Outcome::Responded(3) => write!(f, "responded (resource unavailable)"),
+            Outcome::Responded(code) => write!(f, "responded (code {})", code),
+            Outcome::StreamClosed => write!(f, "stream closed"),
+            Outcome::StreamReset(e) => write!(f, "stream reset ({})", e),
+            Outcome::ProtocolRefused => write!(f, "protocol refused"),
+            Outcome::ConnectionClosed(e) => write!(f, "connection closed ({})", e),
+            Outcome::ConnectionOpen => write!(f, "connection still open"),
+            Outcome::Timeout => write!(f, "no response before timeout"),
+            Outcome::Error(e) => write!(f, "error ({})", e),
+        }
+    }
+}
+
+struct Context {
+    target: Multiaddr,
+    timeout: Duration,
+    fork_digest: Option<[u8; 4]>,
+    blob_sidecar_size: usize,
+}
+
+pub fn run<E: EthSpec>(env: Environment<E>, matches: &ArgMatches) -> Result<(), String> {
+    let target: Multiaddr = parse_required(matches, "target")?;
+    let scenarios: Option<String> = parse_optional(matches, "scenarios")?;
+    let timeout = Duration::from_secs(parse_required(matches, "timeout")?);
+    let fork_digest = parse_optional::<String>(matches, "fork-digest")?
+        .map(|digest| {
+            let bytes = hex::decode(digest.trim_start_matches("0x"))
+                .map_err(|e| format!("Invalid fork digest: {:?}", e))?;
+            <[u8; 4]>::try_from(bytes).map_err(|_| "Fork digest must be 4 bytes".to_string())
+        })
+        .transpose()?;
+
+    let scenarios = match scenarios {
+        Some(scenarios) => scenarios
+            .split(',')
+            .map(|scenario| scenario.trim().parse())
+            .collect::<Result<Vec<Scenario>, _>>()?,
+        None => Scenario::ALL.to_vec(),
+    };
+
+    eprintln!(
+        "This tool sends malformed and abusive traffic to {}. Only use it against nodes you \
+        operate, never against the public network.",
+        target
+    );
+
+    let mut ctx = Context {
+        target,
+        timeout,
+        fork_digest,
+        blob_sidecar_size: E::bytes_per_blob() + E::max_blob_commitments_per_block() * 32 + 1024,
+    };
+
+    env.core_context()
+        .executor
+        .handle()
+        .ok_or("shutdown in progress")?
+        .block_on(async move {
+            let mut unexpected = 0;
+            for scenario in scenarios {
+                let start = Instant::now();
+                let outcome = run_scenario(&mut ctx, scenario).await;
+                let expected = is_expected(scenario, &outcome);
+                if !expected {
+                    unexpected += 1;
+                }
+                println!(
+                    "{:<28} {:<40} {:>8.1}s {}",
+                    scenario.name(),
+                    outcome.to_string(),
+                    start.elapsed().as_secs_f64(),
+                    if expected { "ok" } else { "UNEXPECTED" }
+                );
+            }
+            println!("{} scenarios had unexpected outcomes", unexpected);
+        });
+
+    Ok(())
+}
+
+/// Returns `true` if `outcome` is how a hardened node should respond to `scenario`.
+fn is_expected(scenario: Scenario, outcome: &Outcome) -> bool {
+    match scenario {
+        Scenario::Status => matches!(outcome, Outcome::Responded(0)),
+        Scenario::RpcGarbage
+        | Scenario::RpcOversizedLength
+        | Scenario::RpcInvalidSnappy
+        | Scenario::RpcInvalidSsz
+        | Scenario::SlowRequest => matches!(
+            outcome,
+            Outcome::Responded(1)
+                | Outcome::StreamClosed
+                | Outcome::StreamReset(_)
+                | Outcome::ConnectionClosed(_)
+        ),
+        Scenario::GossipOversized | Scenario::GossipInvalidBlobSidecar => {
+            !matches!(outcome, Outcome::Error(_))
+        }
+        Scenario::SlowHandshake => matches!(outcome, Outcome::ConnectionClosed(_)),
+    }
+}
+
+async fn run_scenario(ctx: &mut Context, scenario: Scenario) -> Outcome {
+    match scenario {
+        Scenario::Status => {
+            let request = snappy_frame(&status_request(ctx.fork_digest.unwrap_or_default()));
+            let (outcome, response) = rpc_request(ctx, &request, false).await;
+            // Remember the node's fork digest for the gossip scenarios.
+            if let Some(digest) = response.and_then(|status| status.get(..4)?.try_into().ok()) {
+                if ctx.fork_digest.is_none() {
+                    ctx.fork_digest = Some(digest);
+                }
+            }
+            outcome
+        }
+        Scenario::RpcGarbage => {
+            let mut garbage = vec![0; 1024];
+            rand::thread_rng().fill_bytes(&mut garbage);
+            rpc_request(ctx, &garbage, false).await.0
+        }
+        Scenario::RpcOversizedLength => {
+            let mut request = varint(OVERSIZED_LENGTH);
+            request.extend_from_slice(&[0xff; 64]);
+            rpc_request(ctx, &request, false).await.0
+        }
+        Scenario::RpcInvalidSnappy => {
+            let status = status_request(ctx.fork_digest.unwrap_or_default());
+            let mut request = varint(status.len() as u64);
+            request.extend_from_slice(&[0xff, 0x06, 0x00, 0x00, 0x6e, 0x6f, 0x70, 0x65]);
+            request.extend_from_slice(&[0xab; 84]);
+            rpc_request(ctx, &request, false).await.0
+        }
+        Scenario::RpcInvalidSsz => rpc_request(ctx, &snappy_frame(&[0xab; 10]), false).await.0,
+        Scenario::SlowRequest => {
+            let request = snappy_frame(&status_request(ctx.fork_digest.unwrap_or_default()));
+            rpc_request(ctx, &request, true).await.0
+        }
+        Scenario::GossipOversized => {
+            let Some(topic) = gossip_topic(ctx, "beacon_block") else {
+                return no_fork_digest();
+            };
+            let mut data = vec![0; OVERSIZED_GOSSIP_BYTES];
+            rand::thread_rng().fill_bytes(&mut data);
+            publish(ctx, &topic, &data).await
+        }
+        Scenario::GossipInvalidBlobSidecar => {
+            let Some(topic) = gossip_topic(ctx, "blob_sidecar_0") else {
+                return no_fork_digest();
+            };
+            let mut sidecar = vec![0; ctx.blob_sidecar_size];
+            rand::thread_rng().fill_bytes(&mut sidecar);
+            let data = match snap::raw::Encoder::new().compress_vec(&sidecar) {
+                Ok(data) => data,
+                Err(e) => return Outcome::Error(format!("{:?}", e)),
+            };
+            publish(ctx, &topic, &data).await
+        }
+        Scenario::SlowHandshake => slow_handshake(ctx).await,
+    }
+}
+
+fn no_fork_digest() -> Outcome {
+    Outcome::Error("fork digest unknown, run the status scenario or use --fork-digest".into())
+}
+
+fn gossip_topic(ctx: &Context, kind: &str) -> Option<String> {
+    let digest = ctx.fork_digest?;
+    Some(format!("/eth2/{}/{}/ssz_snappy", hex::encode(digest), kind))
+}
+
+fn status_request(fork_digest: [u8; 4]) -> Vec<u8> {
+    StatusMessage {
+        fork_digest,
+        finalized_root: Hash256::zero(),
+        finalized_epoch: Epoch::new(0),
+        head_root: Hash256::zero(),
+        head_slot: Slot::new(0),
+    }
+    .as_ssz_bytes()
+}
+
+/// Encodes `bytes` as an `ssz_snappy` RPC payload: the uncompressed length followed by the
+/// snappy frames.
+fn snappy_frame(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = varint(bytes.len() as u64);
+    let mut writer = snap::write::FrameEncoder::new(&mut encoded);
+    // Writing to a `Vec` can't fail.
+    let _ = std::io::Write::write_all(&mut writer, bytes);
+    drop(writer);
+    encoded
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut bytes = vec![];
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return bytes;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+async fn read_varint<S: AsyncRead + Unpin>(stream: &mut S) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        stream.read_exact(&mut byte).await?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "varint overflow",
+    ))
+}
+
+/// Negotiates `protocol` on `stream` using multistream-select, returning `false` if the node
+/// doesn't support it.
+async fn negotiate<S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    protocol: &str,
+) -> io::Result<bool> {
+    for message in [MULTISTREAM_PROTOCOL, protocol] {
+        stream.write_all(&varint(message.len() as u64 + 1)).await?;
+        stream.write_all(message.as_bytes()).await?;
+        stream.write_all(b"\n").await?;
+    }
+    stream.flush().await?;
+
+    let mut responses = vec![];
+    for _ in 0..2 {
+        let len = read_varint(stream).await?;
+        if len > 1024 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "multistream message too long",
+            ));
+        }
+        let mut message = vec![0; len as usize];
+        stream.read_exact(&mut message).await?;
+        responses.push(message);
+    }
+    Ok(responses[1].strip_suffix(b"\n") == Some(protocol.as_bytes()))
+}
+
+/// Dials the target, returning the stream muxer of the connection.
+async fn connect(target: &Multiaddr) -> Result<StreamMuxerBox, String> {
+    let mut transport = build_transport(Keypair::generate_secp256k1(), false, None)
+        .map_err(|e| format!("Unable to build transport: {:?}", e))?;
+    let dial = transport
+        .dial(
+            target.clone(),
+            DialOpts {
+                role: Endpoint::Dialer,
+                port_use: PortUse::New,
+            },
+        )
+        .map_err(|e| format!("Unable to dial: {:?}", e))?;
+    let (_peer_id, muxer) = dial
+        .await
+        .map_err(|e| format!("Unable to connect: {:?}", e))?;
+    Ok(muxer)
+}
+
+/// Drives `muxer` until the connection closes, dropping any streams opened by the node.
+async fn drive_connection(muxer: &mut StreamMuxerBox) -> io::Error {
+    poll_fn(|cx| loop {
+        match muxer.poll_unpin(cx) {
+            Poll::Ready(Ok(_)) => continue,
+            Poll::Ready(Err(e)) => return Poll::Ready(e),
+            Poll::Pending => {}
+        }
+        match muxer.poll_inbound_unpin(cx) {
+            Poll::Ready(Ok(_)) => continue,
+            Poll::Ready(Err(e)) => return Poll::Ready(e),
+            Poll::Pending => return Poll::Pending,
+        }
+    })
+    .await
+}
+
+/// Sends `request` on the status protocol, returning the outcome and the decompressed response
+/// if the node responded successfully.
+///
+/// If `slow` is set the request is sent one byte at a time.
+async fn rpc_request(ctx: &Context, request: &[u8], slow: bool) -> (Outcome, Option<Vec<u8>>) {
+    let mut muxer = match connect(&ctx.target).await {
+        Ok(muxer) => muxer,
+        Err(e) => return (Outcome::Error(e), None),
+    };
+    let mut stream = match muxer.next_outbound().await {
+        Ok(stream) => stream,
+        Err(e) => return (Outcome::ConnectionClosed(e.to_string()), None),
+    };
+
+    let exchange = async {
+        if !negotiate(&mut stream, STATUS_PROTOCOL).await? {
+            return Ok((Outcome::ProtocolRefused, None));
+        }
+        if slow {
+            for byte in request {
+                stream.write_all(&[*byte]).await?;
+                stream.flush().await?;
+                tokio::time::sleep(SLOW_BYTE_INTERVAL).await;
+            }
+        } else {
+            stream.write_all(request).await?;
+        }
+        stream.close().await?;
+
+        let mut code = [0];
+        if stream.read(&mut code).await? == 0 {
+            return Ok((Outcome::StreamClosed, None));
+        }
+        let response = if code[0] == 0 {
+            let len = read_varint(&mut stream).await?;
+            let mut compressed = vec![];
+            stream.read_to_end(&mut compressed).await?;
+            let mut decoded = Vec::with_capacity(len.min(1024) as usize);
+            std::io::Read::read_to_end(
+                &mut snap::read::FrameDecoder::new(compressed.as_slice()),
+                &mut decoded,
+            )
+            .ok()
+            .map(|_| decoded)
+        } else {
+            None
+        };
+        Ok::<_, io::Error>((Outcome::Responded(code[0]), response))
+    };
+    let exchange = tokio::time::timeout(ctx.timeout, exchange);
+
+    match select(Box::pin(exchange), Box::pin(drive_connection(&mut muxer))).await {
+        Either::Left((Ok(Ok(result)), _)) => result,
+        Either::Left((Ok(Err(e)), _)) if e.kind() == io::ErrorKind::UnexpectedEof => {
+            (Outcome::StreamClosed, None)
+        }
+        Either::Left((Ok(Err(e)), _)) => (Outcome::StreamReset(e.to_string()), None),
+        Either::Left((Err(_), _)) => (Outcome::Timeout, None),
+        Either::Right((e, _)) => (Outcome::ConnectionClosed(e.to_string()), None),
+    }
+}
+
+/// Publishes an unsigned gossipsub message with `data` on `topic`, then checks whether the
+/// connection is still open after the timeout.
+async fn publish(ctx: &Context, topic: &str, data: &[u8]) -> Outcome {
+    let mut muxer = match connect(&ctx.target).await {
+        Ok(muxer) => muxer,
+        Err(e) => return Outcome::Error(e),
+    };
+    let mut stream = match muxer.next_outbound().await {
+        Ok(stream) => stream,
+        Err(e) => return Outcome::ConnectionClosed(e.to_string()),
+    };
+
+    let exchange = async {
+        if !negotiate(&mut stream, GOSSIPSUB_PROTOCOL).await? {
+            return Ok(Outcome::ProtocolRefused);
+        }
+        let rpc = gossipsub_rpc(topic, data);
+        stream.write_all(&varint(rpc.len() as u64)).await?;
+        stream.write_all(&rpc).await?;
+        stream.flush().await?;
+        // Gossipsub never responds on the same stream, so wait for the node to act on the
+        // message and then check that the connection is still usable.
+        tokio::time::sleep(ctx.timeout).await;
+        Ok::<_, io::Error>(Outcome::ConnectionOpen)
+    };
+
+    match select(Box::pin(exchange), Box::pin(drive_connection(&mut muxer))).await {
+        Either::Left((Ok(outcome), _)) => outcome,
+        Either::Left((Err(e), _)) => Outcome::StreamReset(e.to_string()),
+        Either::Right((e, _)) => Outcome::ConnectionClosed(e.to_string()),
+    }
+}
+
+/// Encodes a gossipsub RPC which subscribes to `topic` and publishes `data` on it.
+fn gossipsub_rpc(topic: &str, data: &[u8]) -> Vec<u8> {
+    fn field(tag: u64, bytes: &[u8]) -> Vec<u8> {
+        let mut encoded = varint((tag << 3) | 2);
+        encoded.extend(varint(bytes.len() as u64));
+        encoded.extend_from_slice(bytes);
+        encoded
+    }
+
+    // SubOpts { subscribe: true, topic_id }
+    let mut subscription = varint(1 << 3);
+    subscription.push(1);
+    subscription.extend(field(2, topic.as_bytes()));
+
+    // Message { data, topic }, with no author or signature as in eth2's anonymous mode.
+    let mut message = field(2, data);
+    message.extend(field(4, topic.as_bytes()));
+
+    let mut rpc = field(1, &subscription);
+    rpc.extend(field(2, &message));
+    rpc
+}
+
+/// Opens a TCP connection and sends the multistream header one byte at a time, returning once
+/// the node closes the connection or the timeout elapses.
+async fn slow_handshake(ctx: &Context) -> Outcome {
+    let mut ip = None;
+    let mut port = None;
+    for protocol in ctx.target.iter() {
+        match protocol {
+            Protocol::Ip4(addr) => ip = Some(IpAddr::V4(addr)),
+            Protocol::Ip6(addr) => ip = Some(IpAddr::V6(addr)),
+            Protocol::Tcp(tcp_port) => port = Some(tcp_port),
+            _ => {}
+        }
+    }
+    let (Some(ip), Some(port)) = (ip, port) else {
+        return Outcome::Error("target must be an IP and TCP port".into());
+    };
+
+    let mut stream = match TcpStream::connect(SocketAddr::new(ip, port)).await {
+        Ok(stream) => stream,
+        Err(e) => return Outcome::Error(e.to_string()),
+    };
+
+    let mut header = varint(MULTISTREAM_PROTOCOL.len() as u64 + 1);
+    header.extend_from_slice(MULTISTREAM_PROTOCOL.as_bytes());
+    header.push(b'\n');
+
+    let trickle = async {
+        // Repeat the header byte by byte; a hardened node should give up on the handshake long
+        // before the timeout.
+        for byte in header.iter().cycle() {
+            stream.write_all(&[*byte]).await?;
+            tokio::time::sleep(SLOW_BYTE_INTERVAL).await;
+        }
+        Ok::<_, io::Error>(())
+    };
+    match tokio::time::timeout(ctx.timeout, trickle).await {
+        Ok(Err(e)) => Outcome::ConnectionClosed(e.to_string()),
+        Ok(Ok(())) | Err(_) => Outcome::Timeout,
+    }
+}
@@ -1,14 +1,20 @@
+mod attack_node;
 mod block_root;
 mod check_deposit_data;
 mod generate_bootnode_enr;
 mod http_sync;
 mod indexed_attestations;
 mod mnemonic_validators;
+mod mock_builder;
 mod mock_el;
+mod new_testnet;
 mod parse_ssz;
+mod replay_engine_calls;
+mod shadow_fork;
 mod skip_slots;
 mod state_root;
 mod transition_blocks;
+mod verify_deposit_data;
 
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use clap_utils::{parse_optional, FLAG_HEADER};
@@ -85,7 +91,8 @@ fn main() {
                         .long("beacon-url")
                         .value_name("URL")
                         .action(ArgAction::Set)
-                        .help("URL to a beacon-API provider.")
+                        .help("URL to a beacon-API provider. When provided, the fork schedule \
+                            of the beacon node's network is used instead of --network.")
                         .display_order(0)
                 )
                 .arg(
@@ -97,6 +104,17 @@ fn main() {
                         .help("Identifier for a state as per beacon-API standards (slot, root, etc.)")
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new("block-id")
+                        .long("block-id")
+                        .value_name("BLOCK_ID")
+                        .action(ArgAction::Set)
+                        .requires("beacon-url")
+                        .conflicts_with("state-id")
+                        .help("Identifier for a block as per beacon-API standards (slot, root, \
+                            etc.). The post-state of this block is used.")
+                        .display_order(0)
+                )
                 .arg(
                     Arg::new("runs")
                         .long("runs")
@@ -139,21 +157,63 @@ fn main() {
                         .long("pre-state-path")
                         .value_name("PATH")
                         .action(ArgAction::Set)
-                        .conflicts_with("beacon-url")
-                        .requires("block-path")
                         .help("Path to load a BeaconState from as SSZ.")
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new("pre-state-id")
+                        .long("pre-state-id")
+                        .value_name("STATE_ID")
+                        .action(ArgAction::Set)
+                        .requires("beacon-url")
+                        .conflicts_with_all(["pre-state-path", "blocks-dir", "start-slot"])
+                        .help("Identifier for the pre-state as per beacon-API standards (slot, \
+                            root, etc.). Defaults to the post-state of the block's parent when \
+                            using --beacon-url without --pre-state-path.")
+                        .display_order(0)
+                )
                 .arg(
                     Arg::new("block-path")
                         .long("block-path")
                         .value_name("PATH")
                         .action(ArgAction::Set)
-                        .conflicts_with("beacon-url")
-                        .requires("pre-state-path")
+                        .conflicts_with("block-id")
                         .help("Path to load a SignedBeaconBlock from as SSZ.")
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new("blocks-dir")
+                        .long("blocks-dir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .conflicts_with_all(["beacon-url", "block-path"])
+                        .requires("pre-state-path")
+                        .help("Path to a directory of SignedBeaconBlocks as SSZ (with an .ssz \
+                            extension). The blocks are applied to the pre-state in slot order \
+                            and timed as a batch.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("start-slot")
+                        .long("start-slot")
+                        .value_name("SLOT")
+                        .action(ArgAction::Set)
+                        .conflicts_with("block-id")
+                        .help("The first slot of the batch of blocks. Required with --beacon-url \
+                            when applying a batch of blocks, optional with --blocks-dir.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("end-slot")
+                        .long("end-slot")
+                        .value_name("SLOT")
+                        .action(ArgAction::Set)
+                        .conflicts_with("block-id")
+                        .help("The last slot (inclusive) of the batch of blocks. Required with \
+                            --beacon-url when applying a batch of blocks, optional with \
+                            --blocks-dir.")
+                        .display_order(0)
+                )
                 .arg(
                     Arg::new("post-state-output-path")
                         .long("post-state-output-path")
@@ -183,7 +243,8 @@ fn main() {
                         .long("beacon-url")
                         .value_name("URL")
                         .action(ArgAction::Set)
-                        .help("URL to a beacon-API provider.")
+                        .help("URL to a beacon-API provider. When provided, the fork schedule \
+                            of the beacon node's network is used instead of --network.")
                         .display_order(0)
                 )
                 .arg(
@@ -204,6 +265,35 @@ fn main() {
                         .help("Number of repeat runs, useful for benchmarking.")
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new("report-output-path")
+                        .long("report-output-path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("Path to output a benchmark report with the timings of each stage \
+                            of each run. Only used when applying a batch of blocks.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("report-format")
+                        .long("report-format")
+                        .value_name("FORMAT")
+                        .action(ArgAction::Set)
+                        .value_parser(["json", "csv"])
+                        .default_value("json")
+                        .help("Format of the benchmark report.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("flamegraph-output-path")
+                        .long("flamegraph-output-path")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("Path to output a flamegraph (SVG) of all runs. Only used when \
+                            applying a batch of blocks. Requires lcli to be built with the \
+                            `flamegraph` feature.")
+                        .display_order(0)
+                )
                 .arg(
                     Arg::new("no-signature-verification")
                         .long("no-signature-verification")
@@ -288,6 +378,24 @@ fn main() {
                         .display_order(0)
                 ),
         )
+        .subcommand(
+            Command::new("verify-deposit-data")
+                .about(
+                    "Verifies the signature, withdrawal credentials and amount of every deposit \
+                    in a deposit_data.json file, as used by the Ethereum Launchpad, against the \
+                    spec of --network or --testnet-dir. Files ending in .csv are read as CSV, \
+                    with a header row naming the fields of the JSON format.",
+                )
+                .arg(
+                    Arg::new("deposit-data")
+                        .index(1)
+                        .value_name("FILE")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the deposit_data.json or CSV file")
+                        .display_order(0)
+                ),
+        )
         .subcommand(
             Command::new("generate-bootnode-enr")
                 .about("Generates an ENR address to be used as a pre-genesis boot node.")
@@ -553,6 +661,371 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("mock-builder")
+                .about("Creates a mock builder API server which builds payloads with a local \
+                execution engine. Supports fault injection for testing builder integrations. \
+                This is NOT SAFE and should only be used for testing and development on \
+                testnets. Do not use on mainnet.")
+                .arg(
+                    Arg::new("execution-endpoint")
+                        .long("execution-endpoint")
+                        .value_name("URL")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("URL of the engine API of the execution engine used to build payloads.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-jwt")
+                        .long("execution-jwt")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("Path to the JWT secret of the execution engine.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("beacon-url")
+                        .long("beacon-url")
+                        .value_name("URL")
+                        .action(ArgAction::Set)
+                        .default_value("http://localhost:5052")
+                        .help("URL of a beacon node used to follow the head of the chain.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("listen-address")
+                        .long("listen-address")
+                        .value_name("IP_ADDRESS")
+                        .action(ArgAction::Set)
+                        .help("The server will listen on this address.")
+                        .default_value("127.0.0.1")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("listen-port")
+                        .long("listen-port")
+                        .value_name("PORT")
+                        .action(ArgAction::Set)
+                        .help("The server will listen on this port.")
+                        .default_value("18550")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("bid-value")
+                        .long("bid-value")
+                        .value_name("WEI")
+                        .action(ArgAction::Set)
+                        .help("The value of every bid, in wei. Defaults to 0.02 ETH.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("censor-validators")
+                        .long("censor-validators")
+                        .value_name("PUBKEYS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated list of validator public keys which are never \
+                            offered a bid, simulating a builder which censors their blocks.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("withhold-payloads")
+                        .long("withhold-payloads")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Never reveal the payload of a signed blinded block, simulating a \
+                            builder which withholds payloads after the proposer has signed.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("invalid-signatures")
+                        .long("invalid-signatures")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Sign every bid with an invalid signature.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("attack-node")
+                .about("Dials a beacon node and sends it malformed RPC requests, oversized \
+                gossip messages, invalid blob sidecars and slow-loris streams, reporting how the \
+                node responds. Only use against nodes you operate, never against the public \
+                network.")
+                .arg(
+                    Arg::new("target")
+                        .long("target")
+                        .value_name("MULTIADDR")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The libp2p TCP address of the beacon node, e.g. \
+                            /ip4/127.0.0.1/tcp/9000.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("scenarios")
+                        .long("scenarios")
+                        .value_name("SCENARIOS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated list of scenarios to run. Defaults to all of: \
+                            status, rpc-garbage, rpc-oversized-length, rpc-invalid-snappy, \
+                            rpc-invalid-ssz, gossip-oversized, gossip-invalid-blob-sidecar, \
+                            slow-handshake, slow-request.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("timeout")
+                        .long("timeout")
+                        .value_name("SECONDS")
+                        .action(ArgAction::Set)
+                        .default_value("30")
+                        .help("How long to wait for the node to respond to each scenario.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("fork-digest")
+                        .long("fork-digest")
+                        .value_name("HEX")
+                        .action(ArgAction::Set)
+                        .help("The fork digest of the node, used for the gossip topics. If not \
+                            provided it is learned from the status scenario.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("new-testnet")
+                .about("Produces a testnet directory with an interop genesis state at any fork, \
+                    optionally with pending Electra deposits, withdrawals and consolidations. \
+                    The fork schedule starts from the config of --network or --testnet-dir. An \
+                    execution genesis config matching the schedule is written alongside. Blob \
+                    limits are fixed by the --spec preset.")
+                .arg(
+                    Arg::new("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The directory in which to write the testnet.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Overwrite any existing testnet in the output directory.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-fork")
+                        .long("genesis-fork")
+                        .value_name("FORK")
+                        .action(ArgAction::Set)
+                        .help("The fork of the genesis state. This fork and all prior forks \
+                            activate at epoch 0.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("fork-epochs")
+                        .long("fork-epochs")
+                        .value_name("FORK=EPOCH,...")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated activation epochs of forks, e.g. \
+                            deneb=0,electra=4. Applied after --genesis-fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("validator-count")
+                        .long("validator-count")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .default_value("64")
+                        .help("The number of interop validators in the genesis state.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-time")
+                        .long("genesis-time")
+                        .value_name("UNIX_SECONDS")
+                        .action(ArgAction::Set)
+                        .help("The genesis time. Defaults to now.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-genesis-block")
+                        .long("execution-genesis-block")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("A JSON file containing the execution genesis block, as returned \
+                            by eth_getBlockByNumber. Its timestamp must equal the genesis time.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("pending-balance-deposits")
+                        .long("pending-balance-deposits")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The number of pending balance deposits in an Electra genesis \
+                            state.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("pending-partial-withdrawals")
+                        .long("pending-partial-withdrawals")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The number of pending partial withdrawals in an Electra genesis \
+                            state.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("pending-consolidations")
+                        .long("pending-consolidations")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The number of pending consolidations in an Electra genesis state. \
+                            Each consolidation uses two validators.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("pending-amount")
+                        .long("pending-amount")
+                        .value_name("GWEI")
+                        .action(ArgAction::Set)
+                        .default_value("1000000000")
+                        .help("The amount of each pending balance deposit and partial \
+                            withdrawal.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("shadow-fork")
+                .about("Produces a testnet directory with a genesis state derived from a state of \
+                    an existing chain, rebased to slot 0 with the validator set and balances \
+                    intact. The fork schedule starts from the config of --network or \
+                    --testnet-dir, which must also be able to decode the state.")
+                .arg(
+                    Arg::new("state")
+                        .long("state")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("An SSZ state of the chain to fork, e.g. from \
+                            /eth/v2/debug/beacon/states/finalized.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("output-dir")
+                        .long("output-dir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The directory in which to write the testnet.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Overwrite any existing testnet in the output directory.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("fork-epochs")
+                        .long("fork-epochs")
+                        .value_name("FORK=EPOCH,...")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated activation epochs of forks after the fork of the \
+                            state, e.g. electra=10. The fork of the state and all prior forks \
+                            activate at epoch 0.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-time")
+                        .long("genesis-time")
+                        .value_name("UNIX_SECONDS")
+                        .action(ArgAction::Set)
+                        .help("The genesis time of the shadow fork. Defaults to now.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("key-swaps")
+                        .long("key-swaps")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("A YAML file mapping the pubkeys of existing validators to \
+                            replacement pubkeys, so that they can be run by the operators of \
+                            the shadow fork.")
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new("replay-engine-calls")
+                .about("Re-issues engine API calls recorded by a beacon node running with \
+                    --record-engine-calls against an execution client, and reports where the \
+                    responses differ from the recording.")
+                .arg(
+                    Arg::new("recording")
+                        .long("recording")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("A recording file, or a recording directory to replay all of its \
+                            files in order.")
+                        .required(true)
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-endpoint")
+                        .long("execution-endpoint")
+                        .value_name("URL")
+                        .action(ArgAction::Set)
+                        .help("The engine API endpoint of the execution client.")
+                        .required(true)
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-jwt")
+                        .long("execution-jwt")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("File path which contains the hex-encoded JWT secret for the \
+                            execution endpoint.")
+                        .required(true)
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("methods")
+                        .long("methods")
+                        .value_name("METHODS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated list of methods to replay, e.g. \
+                            engine_newPayloadV3,engine_forkchoiceUpdatedV3. All methods are \
+                            replayed if not set.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("preserve-timing")
+                        .long("preserve-timing")
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Wait between calls for as long as the recording did.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("timeout-ms")
+                        .long("timeout-ms")
+                        .value_name("MILLISECONDS")
+                        .action(ArgAction::Set)
+                        .help("The timeout of each replayed call.")
+                        .default_value("12000")
+                        .display_order(0)
+                )
+        )
         .subcommand(
             Command::new("http-sync")
                 .about("Manual sync")
@@ -659,6 +1132,7 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
             compression: false,
             is_restricted: true,
             sse_logging: false, // No SSE Logging in LCLI
+            non_blocking: false,
         })
         .map_err(|e| format!("should start logger: {:?}", e))?
         .build()
@@ -707,6 +1181,11 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
         }
         Some(("check-deposit-data", matches)) => check_deposit_data::run(matches)
             .map_err(|e| format!("Failed to run check-deposit-data command: {}", e)),
+        Some(("verify-deposit-data", matches)) => {
+            let network_config = get_network_config()?;
+            verify_deposit_data::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run verify-deposit-data command: {}", e))
+        }
         Some(("generate-bootnode-enr", matches)) => {
             generate_bootnode_enr::run::<E>(matches, &env.eth2_config.spec)
                 .map_err(|e| format!("Failed to run generate-bootnode-enr command: {}", e))
@@ -727,6 +1206,25 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
         }
         Some(("mock-el", matches)) => mock_el::run::<E>(env, matches)
             .map_err(|e| format!("Failed to run mock-el command: {}", e)),
+        Some(("attack-node", matches)) => attack_node::run::<E>(env, matches)
+            .map_err(|e| format!("Failed to run attack-node command: {}", e)),
+        Some(("mock-builder", matches)) => {
+            let network_config = get_network_config()?;
+            mock_builder::run::<E>(env, network_config, matches)
+                .map_err(|e| format!("Failed to run mock-builder command: {}", e))
+        }
+        Some(("new-testnet", matches)) => {
+            let network_config = get_network_config()?;
+            new_testnet::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run new-testnet command: {}", e))
+        }
+        Some(("shadow-fork", matches)) => {
+            let network_config = get_network_config()?;
+            shadow_fork::run::<E>(network_config, matches)
+                .map_err(|e| format!("Failed to run shadow-fork command: {}", e))
+        }
+        Some(("replay-engine-calls", matches)) => replay_engine_calls::run::<E>(env, matches)
+            .map_err(|e| format!("Failed to run replay-engine-calls command: {}", e)),
         Some(("http-sync", matches)) => {
             let network_config = get_network_config()?;
             http_sync::run::<E>(env, network_config, matches)
@@ -0,0 +1,83 @@
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use eth2_network_config::Eth2NetworkConfig;
+use execution_layer::{
+    test_utils::{serve_mock_builder, MockBuilder},
+    Config, ExecutionLayer,
+};
+use std::net::Ipv4Addr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use types::*;
+
+const BEACON_NODE_TIMEOUT: Duration = Duration::from_secs(4);
+
+pub fn run<E: EthSpec>(
+    mut env: Environment<E>,
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let execution_endpoint: SensitiveUrl = parse_required(matches, "execution-endpoint")?;
+    let jwt_path: PathBuf = parse_required(matches, "execution-jwt")?;
+    let beacon_url: SensitiveUrl = parse_required(matches, "beacon-url")?;
+    let listen_addr: Ipv4Addr = parse_required(matches, "listen-address")?;
+    let listen_port: u16 = parse_required(matches, "listen-port")?;
+    let bid_value: Option<Uint256> = parse_optional(matches, "bid-value")?;
+    let censored_validators: Option<String> = parse_optional(matches, "censor-validators")?;
+    let withhold_payloads = matches.get_flag("withhold-payloads");
+    let invalid_signatures = matches.get_flag("invalid-signatures");
+
+    let spec = Arc::new(network_config.chain_spec::<E>()?);
+    let executor = env.core_context().executor;
+
+    // The builder builds its payloads with the local execution engine.
+    let config = Config {
+        execution_endpoint: Some(execution_endpoint),
+        secret_file: Some(jwt_path),
+        suggested_fee_recipient: None,
+        ..Default::default()
+    };
+    let el = ExecutionLayer::from_config(config, executor.clone(), executor.log().clone())
+        .map_err(|e| format!("Unable to connect to the execution engine: {:?}", e))?;
+    let beacon_client =
+        BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(BEACON_NODE_TIMEOUT));
+    let builder = MockBuilder::new(el, beacon_client, spec);
+
+    if let Some(bid_value) = bid_value {
+        builder.set_bid_value(bid_value);
+    }
+    for pubkey in censored_validators.iter().flat_map(|keys| keys.split(',')) {
+        let pubkey = pubkey
+            .trim()
+            .parse::<PublicKeyBytes>()
+            .map_err(|e| format!("Invalid pubkey {}: {:?}", pubkey, e))?;
+        builder.censor_validator(pubkey);
+    }
+    if withhold_payloads {
+        eprintln!("Payloads will be withheld from all signed blinded blocks.");
+        builder.withhold_payloads();
+    }
+    if invalid_signatures {
+        eprintln!("All bids will have invalid signatures.");
+        builder.invalid_signatures();
+    }
+
+    let (listen_socket, server) = serve_mock_builder(listen_addr, listen_port, builder)
+        .map_err(|e| format!("Unable to start server: {:?}", e))?;
+    executor.spawn(server, "mock_builder");
+
+    eprintln!(
+        "This tool is for TESTING PURPOSES ONLY. Do not use in production or on mainnet. \
+        Proposers using this builder may miss blocks."
+    );
+    eprintln!("Server listening on {}", listen_socket);
+
+    let shutdown_reason = env.block_until_shutdown_requested()?;
+
+    eprintln!("Shutting down: {:?}", shutdown_reason);
+
+    Ok(())
+}
@@ -0,0 +1,472 @@
+//! # New Testnet
+//!
+//! Generates a testnet directory containing a `config.yaml` and an interop `genesis.ssz`, for
+//! spinning up local and feature devnets.
+//!
+//! The genesis state can be at any fork, including Deneb or Electra from genesis. At Electra the
+//! queues of pending balance deposits, partial withdrawals and consolidations can be
+//! pre-populated, so that their processing is exercised from the first epoch.
+//!
+//! ## Execution genesis
+//!
+//! Post-merge forks require the genesis state to commit to the genesis block of the execution
+//! chain. The execution genesis is produced in two steps:
+//!
+//! 1. Run this command without `--execution-genesis-block`. Alongside the testnet files it writes
+//!    `execution-genesis-config.json`, the `config` section of an execution genesis file with fork
+//!    timestamps and a blob schedule matching the consensus config.
+//! 2. Combine that section with the desired `alloc` (which must include the deposit contract and
+//!    the Electra system contracts), initialise the execution client, fetch its genesis block with
+//!    `eth_getBlockByNumber("0x0", false)` and run this command again, passing the block with
+//!    `--execution-genesis-block` and the same `--genesis-time`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli --spec minimal new-testnet \
+//!     --output-dir /tmp/devnet \
+//!     --genesis-fork electra \
+//!     --validator-count 64 \
+//!     --genesis-time 1700000000 \
+//!     --pending-consolidations 4 \
+//!     --execution-genesis-block /tmp/el-genesis-block.json
+//! ```
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use eth2_network_config::{Eth2NetworkConfig, GenesisStateSource};
+use genesis::{interop_genesis_state, DEFAULT_ETH1_BLOCK_HASH};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ssz::Encode;
+use std::fs::File;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tree_hash::TreeHash;
+use types::{
+    test_utils::generate_deterministic_keypairs, Address, BeaconState, ChainSpec, Config, Epoch,
+    EthSpec, ExecutionBlockHash, ExecutionPayloadHeader, ExecutionPayloadHeaderBellatrix,
+    ExecutionPayloadHeaderCapella, ExecutionPayloadHeaderDeneb, ExecutionPayloadHeaderElectra,
+    FixedVector, ForkName, Hash256, PendingBalanceDeposit, PendingConsolidation,
+    PendingPartialWithdrawal, Uint256, VariableList,
+};
+
+/// The name of the file containing the execution genesis config section.
+const EXECUTION_GENESIS_CONFIG_FILE: &str = "execution-genesis-config.json";
+
+/// The `BLOB_BASE_FEE_UPDATE_FRACTION` used by execution clients since Cancun.
+const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3_338_477;
+
+/// An execution block, as returned by `eth_getBlockByNumber`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionGenesisBlock {
+    hash: ExecutionBlockHash,
+    parent_hash: ExecutionBlockHash,
+    miner: Address,
+    state_root: Hash256,
+    receipts_root: Hash256,
+    logs_bloom: String,
+    mix_hash: Hash256,
+    #[serde(with = "serde_utils::u64_hex_be")]
+    number: u64,
+    #[serde(with = "serde_utils::u64_hex_be")]
+    gas_limit: u64,
+    #[serde(with = "serde_utils::u64_hex_be")]
+    gas_used: u64,
+    #[serde(with = "serde_utils::u64_hex_be")]
+    timestamp: u64,
+    extra_data: String,
+    #[serde(with = "serde_utils::u256_hex_be")]
+    base_fee_per_gas: Uint256,
+    transactions_root: Hash256,
+    withdrawals_root: Option<Hash256>,
+    #[serde(default, with = "serde_utils::u64_hex_be_opt")]
+    blob_gas_used: Option<u64>,
+    #[serde(default, with = "serde_utils::u64_hex_be_opt")]
+    excess_blob_gas: Option<u64>,
+}
+
+impl ExecutionGenesisBlock {
+    fn into_payload_header<E: EthSpec>(
+        self,
+        fork: ForkName,
+    ) -> Result<ExecutionPayloadHeader<E>, String> {
+        let decode = |field: &str, value: &str| {
+            hex::decode(value.trim_start_matches("0x"))
+                .map_err(|e| format!("Invalid {}: {:?}", field, e))
+        };
+        let logs_bloom = FixedVector::new(decode("logsBloom", &self.logs_bloom)?)
+            .map_err(|e| format!("Invalid logsBloom: {:?}", e))?;
+        let extra_data = VariableList::new(decode("extraData", &self.extra_data)?)
+            .map_err(|e| format!("Invalid extraData: {:?}", e))?;
+        let withdrawals_root = || {
+            self.withdrawals_root
+                .ok_or("Execution genesis block is missing withdrawalsRoot")
+        };
+        let blob_gas_used = || {
+            self.blob_gas_used
+                .ok_or("Execution genesis block is missing blobGasUsed")
+        };
+        let excess_blob_gas = || {
+            self.excess_blob_gas
+                .ok_or("Execution genesis block is missing excessBlobGas")
+        };
+
+        let bellatrix = ExecutionPayloadHeaderBellatrix {
+            parent_hash: self.parent_hash,
+            fee_recipient: self.miner,
+            state_root: self.state_root,
+            receipts_root: self.receipts_root,
+            logs_bloom,
+            prev_randao: self.mix_hash,
+            block_number: self.number,
+            gas_limit: self.gas_limit,
+            gas_used: self.gas_used,
+            timestamp: self.timestamp,
+            extra_data,
+            base_fee_per_gas: self.base_fee_per_gas,
+            block_hash: self.hash,
+            transactions_root: self.transactions_root,
+        };
+
+        Ok(match fork {
+            ForkName::Base | ForkName::Altair => {
+                return Err(format!("The execution genesis is not used at {}", fork))
+            }
+            ForkName::Bellatrix => ExecutionPayloadHeader::Bellatrix(bellatrix),
+            ForkName::Capella => ExecutionPayloadHeader::Capella(ExecutionPayloadHeaderCapella {
+                parent_hash: bellatrix.parent_hash,
+                fee_recipient: bellatrix.fee_recipient,
+                state_root: bellatrix.state_root,
+                receipts_root: bellatrix.receipts_root,
+                logs_bloom: bellatrix.logs_bloom,
+                prev_randao: bellatrix.prev_randao,
+                block_number: bellatrix.block_number,
+                gas_limit: bellatrix.gas_limit,
+                gas_used: bellatrix.gas_used,
+                timestamp: bellatrix.timestamp,
+                extra_data: bellatrix.extra_data,
+                base_fee_per_gas: bellatrix.base_fee_per_gas,
+                block_hash: bellatrix.block_hash,
+                transactions_root: bellatrix.transactions_root,
+                withdrawals_root: withdrawals_root()?,
+            }),
+            ForkName::Deneb => ExecutionPayloadHeader::Deneb(ExecutionPayloadHeaderDeneb {
+                parent_hash: bellatrix.parent_hash,
+                fee_recipient: bellatrix.fee_recipient,
+                state_root: bellatrix.state_root,
+                receipts_root: bellatrix.receipts_root,
+                logs_bloom: bellatrix.logs_bloom,
+                prev_randao: bellatrix.prev_randao,
+                block_number: bellatrix.block_number,
+                gas_limit: bellatrix.gas_limit,
+                gas_used: bellatrix.gas_used,
+                timestamp: bellatrix.timestamp,
+                extra_data: bellatrix.extra_data,
+                base_fee_per_gas: bellatrix.base_fee_per_gas,
+                block_hash: bellatrix.block_hash,
+                transactions_root: bellatrix.transactions_root,
+                withdrawals_root: withdrawals_root()?,
+                blob_gas_used: blob_gas_used()?,
+                excess_blob_gas: excess_blob_gas()?,
+            }),
+            ForkName::Electra => ExecutionPayloadHeader::Electra(ExecutionPayloadHeaderElectra {
+                parent_hash: bellatrix.parent_hash,
+                fee_recipient: bellatrix.fee_recipient,
+                state_root: bellatrix.state_root,
+                receipts_root: bellatrix.receipts_root,
+                logs_bloom: bellatrix.logs_bloom,
+                prev_randao: bellatrix.prev_randao,
+                block_number: bellatrix.block_number,
+                gas_limit: bellatrix.gas_limit,
+                gas_used: bellatrix.gas_used,
+                timestamp: bellatrix.timestamp,
+                extra_data: bellatrix.extra_data,
+                base_fee_per_gas: bellatrix.base_fee_per_gas,
+                block_hash: bellatrix.block_hash,
+                transactions_root: bellatrix.transactions_root,
+                withdrawals_root: withdrawals_root()?,
+                blob_gas_used: blob_gas_used()?,
+                excess_blob_gas: excess_blob_gas()?,
+            }),
+        })
+    }
+}
+
+/// The blob parameters of a fork, in the format of an execution genesis `blobSchedule`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobScheduleEntry {
+    target: usize,
+    max: usize,
+    base_fee_update_fraction: u64,
+}
+
+/// Schedules `genesis_fork` and all prior forks at genesis, then applies the comma-separated
+/// `FORK=EPOCH` overrides in `fork_epochs`.
+pub fn build_fork_schedule(
+    spec: &mut ChainSpec,
+    genesis_fork: Option<ForkName>,
+    fork_epochs: Option<&str>,
+) -> Result<(), String> {
+    if let Some(genesis_fork) = genesis_fork {
+        for fork in ForkName::list_all() {
+            if fork <= genesis_fork {
+                set_fork_epoch(spec, fork, Some(Epoch::new(0)));
+            } else if spec.fork_epoch(fork) == Some(Epoch::new(0)) {
+                // Later forks can't also activate at genesis.
+                set_fork_epoch(spec, fork, None);
+            }
+        }
+    }
+    for fork_epoch in fork_epochs.iter().flat_map(|epochs| epochs.split(',')) {
+        let (fork, epoch) = fork_epoch
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid fork epoch {}, expected FORK=EPOCH", fork_epoch))?;
+        let fork: ForkName = fork.trim().parse()?;
+        let epoch = epoch
+            .trim()
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid epoch for {}: {:?}", fork, e))?;
+        set_fork_epoch(spec, fork, Some(Epoch::new(epoch)));
+    }
+    Ok(())
+}
+
+fn set_fork_epoch(spec: &mut ChainSpec, fork: ForkName, epoch: Option<Epoch>) {
+    match fork {
+        ForkName::Base => {}
+        ForkName::Altair => spec.altair_fork_epoch = epoch,
+        ForkName::Bellatrix => spec.bellatrix_fork_epoch = epoch,
+        ForkName::Capella => spec.capella_fork_epoch = epoch,
+        ForkName::Deneb => spec.deneb_fork_epoch = epoch,
+        ForkName::Electra => spec.electra_fork_epoch = epoch,
+    }
+}
+
+pub fn run<E: EthSpec>(base: Eth2NetworkConfig, matches: &ArgMatches) -> Result<(), String> {
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let force = matches.get_flag("force");
+    let genesis_fork: Option<ForkName> = parse_optional(matches, "genesis-fork")?;
+    let fork_epochs: Option<String> = parse_optional(matches, "fork-epochs")?;
+    let validator_count: usize = parse_required(matches, "validator-count")?;
+    let genesis_time: Option<u64> = parse_optional(matches, "genesis-time")?;
+    let execution_genesis_block: Option<PathBuf> =
+        parse_optional(matches, "execution-genesis-block")?;
+    let pending_balance_deposits: usize = parse_required(matches, "pending-balance-deposits")?;
+    let pending_partial_withdrawals: usize =
+        parse_required(matches, "pending-partial-withdrawals")?;
+    let pending_consolidations: usize = parse_required(matches, "pending-consolidations")?;
+    let pending_amount: u64 = parse_required(matches, "pending-amount")?;
+
+    /*
+     * Build the fork schedule.
+     */
+
+    let mut spec = base.chain_spec::<E>()?;
+    build_fork_schedule(&mut spec, genesis_fork, fork_epochs.as_deref())?;
+    let genesis_fork = spec.fork_name_at_epoch(Epoch::new(0));
+
+    let genesis_time = match genesis_time {
+        Some(genesis_time) => genesis_time,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs(),
+    };
+
+    /*
+     * Build the genesis state.
+     */
+
+    let execution_payload_header = execution_genesis_block
+        .map(|path| {
+            let file = File::open(&path)
+                .map_err(|e| format!("Unable to open {}: {:?}", path.display(), e))?;
+            let block: ExecutionGenesisBlock = serde_json::from_reader(file)
+                .map_err(|e| format!("Unable to parse {}: {:?}", path.display(), e))?;
+            if block.timestamp != genesis_time {
+                return Err(format!(
+                    "Execution genesis timestamp {} does not match the genesis time {}",
+                    block.timestamp, genesis_time
+                ));
+            }
+            block.into_payload_header::<E>(genesis_fork)
+        })
+        .transpose()?;
+    if execution_payload_header.is_none() && genesis_fork >= ForkName::Bellatrix {
+        eprintln!(
+            "No --execution-genesis-block was provided, the genesis state will not be linked to \
+            an execution chain."
+        );
+    }
+    let eth1_block_hash = execution_payload_header
+        .as_ref()
+        .map_or(Hash256::from_slice(DEFAULT_ETH1_BLOCK_HASH), |header| {
+            header.block_hash().into_root()
+        });
+
+    let keypairs = generate_deterministic_keypairs(validator_count);
+    let mut state = interop_genesis_state::<E>(
+        &keypairs,
+        genesis_time,
+        eth1_block_hash,
+        execution_payload_header,
+        &spec,
+    )?;
+
+    if pending_balance_deposits + pending_partial_withdrawals + pending_consolidations > 0 {
+        populate_pending_queues(
+            &mut state,
+            pending_balance_deposits,
+            pending_partial_withdrawals,
+            pending_consolidations,
+            pending_amount,
+            &spec,
+        )?;
+    }
+
+    /*
+     * Write the testnet directory.
+     */
+
+    let network_config = Eth2NetworkConfig {
+        deposit_contract_deploy_block: 0,
+        boot_enr: None,
+        genesis_state_source: GenesisStateSource::IncludedBytes,
+        genesis_state_bytes: Some(state.as_ssz_bytes().into()),
+        config: Config::from_chain_spec::<E>(&spec),
+        kzg_trusted_setup: base.kzg_trusted_setup,
+    };
+    network_config.write_to_file(output_dir.clone(), force)?;
+
+    let execution_config_path = output_dir.join(EXECUTION_GENESIS_CONFIG_FILE);
+    let execution_config_file = File::create(&execution_config_path).map_err(|e| {
+        format!(
+            "Unable to create {}: {:?}",
+            EXECUTION_GENESIS_CONFIG_FILE, e
+        )
+    })?;
+    serde_json::to_writer_pretty(
+        execution_config_file,
+        &execution_genesis_config::<E>(&spec, genesis_time),
+    )
+    .map_err(|e| format!("Unable to write {}: {:?}", EXECUTION_GENESIS_CONFIG_FILE, e))?;
+
+    eprintln!(
+        "Wrote {} testnet with {} validators at genesis fork {} to {}",
+        E::spec_name(),
+        validator_count,
+        genesis_fork,
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Adds entries to the pending balance deposit, partial withdrawal and consolidation queues of
+/// an Electra genesis state.
+///
+/// The source validators of consolidations are exiting, as if they had submitted a consolidation
+/// request in the genesis block.
+fn populate_pending_queues<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    balance_deposits: usize,
+    partial_withdrawals: usize,
+    consolidations: usize,
+    amount: u64,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    if !state.fork_name_unchecked().electra_enabled() {
+        return Err("Pending deposits, withdrawals and consolidations require Electra".into());
+    }
+    let validator_count = state.validators().len() as u64;
+    if validator_count == 0 {
+        return Err("Pending queues require at least one validator".into());
+    }
+    if consolidations as u64 * 2 > validator_count {
+        return Err(format!(
+            "{} consolidations require at least {} validators",
+            consolidations,
+            consolidations * 2
+        ));
+    }
+
+    for index in (0..balance_deposits as u64).map(|i| i % validator_count) {
+        state
+            .pending_balance_deposits_mut()
+            .map_err(|e| format!("{:?}", e))?
+            .push(PendingBalanceDeposit { index, amount })
+            .map_err(|e| format!("Too many pending balance deposits: {:?}", e))?;
+    }
+
+    let withdrawable_epoch = spec.min_validator_withdrawability_delay;
+    for index in (0..partial_withdrawals as u64).map(|i| i % validator_count) {
+        state
+            .pending_partial_withdrawals_mut()
+            .map_err(|e| format!("{:?}", e))?
+            .push(PendingPartialWithdrawal {
+                index,
+                amount,
+                withdrawable_epoch,
+            })
+            .map_err(|e| format!("Too many pending partial withdrawals: {:?}", e))?;
+    }
+
+    let exit_epoch = spec
+        .compute_activation_exit_epoch(Epoch::new(0))
+        .map_err(|e| format!("{:?}", e))?;
+    for i in 0..consolidations as u64 {
+        let (source_index, target_index) = (2 * i, 2 * i + 1);
+        let source = state
+            .get_validator_mut(source_index as usize)
+            .map_err(|e| format!("{:?}", e))?;
+        source.exit_epoch = exit_epoch;
+        source.withdrawable_epoch = exit_epoch + spec.min_validator_withdrawability_delay;
+        state
+            .pending_consolidations_mut()
+            .map_err(|e| format!("{:?}", e))?
+            .push(PendingConsolidation {
+                source_index,
+                target_index,
+            })
+            .map_err(|e| format!("Too many pending consolidations: {:?}", e))?;
+    }
+
+    // The genesis validators root commits to the validators as modified above.
+    *state.genesis_validators_root_mut() = state.validators().tree_hash_root();
+    state
+        .drop_all_caches()
+        .map_err(|e| format!("Unable to drop caches: {:?}", e))?;
+
+    Ok(())
+}
+
+/// Returns the `config` section of an execution genesis file matching `spec`.
+fn execution_genesis_config<E: EthSpec>(spec: &ChainSpec, genesis_time: u64) -> serde_json::Value {
+    let fork_time = |fork: ForkName| {
+        spec.fork_epoch(fork).map(|epoch| {
+            genesis_time + epoch.as_u64() * E::slots_per_epoch() * spec.seconds_per_slot
+        })
+    };
+    // Blob counts are fixed by the preset, so every fork uses the same parameters.
+    let blobs = || BlobScheduleEntry {
+        target: E::max_blobs_per_block() / 2,
+        max: E::max_blobs_per_block(),
+        base_fee_update_fraction: BLOB_BASE_FEE_UPDATE_FRACTION,
+    };
+
+    json!({
+        "chainId": spec.deposit_chain_id,
+        "terminalTotalDifficulty": 0,
+        "terminalTotalDifficultyPassed": true,
+        "depositContractAddress": spec.deposit_contract_address,
+        "shanghaiTime": fork_time(ForkName::Capella),
+        "cancunTime": fork_time(ForkName::Deneb),
+        "pragueTime": fork_time(ForkName::Electra),
+        "blobSchedule": {
+            "cancun": blobs(),
+            "prague": blobs(),
+        },
+    })
+}
@@ -0,0 +1,141 @@
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::SensitiveUrl;
+use execution_layer::auth::{strip_prefix, Auth, JwtKey};
+use execution_layer::recorder::{
+    params_hash, read_records, EngineCallRecord, RecordedResult, RECORDING_FILE_NAME,
+};
+use execution_layer::HttpJsonRpc;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use types::EthSpec;
+
+pub fn run<E: EthSpec>(env: Environment<E>, matches: &ArgMatches) -> Result<(), String> {
+    let executor = env.core_context().executor;
+    executor
+        .handle()
+        .ok_or("shutdown in progress")?
+        .block_on(async move { run_async(matches).await })
+}
+
+/// Returns the records in `path`, oldest first. If `path` is a recording directory, the rotated
+/// files are read before the current one.
+fn load_records(path: &Path) -> Result<Vec<EngineCallRecord>, String> {
+    if !path.is_dir() {
+        return read_records(path);
+    }
+
+    let mut rotated = std::fs::read_dir(path)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", path, e))?
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            let index = file_name
+                .strip_prefix(RECORDING_FILE_NAME)?
+                .strip_prefix('.')?
+                .parse::<usize>()
+                .ok()?;
+            Some((index, path.join(file_name)))
+        })
+        .collect::<Vec<_>>();
+    rotated.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    let mut records = vec![];
+    for (_, file) in rotated {
+        records.extend(read_records(&file)?);
+    }
+    let current = path.join(RECORDING_FILE_NAME);
+    if current.exists() {
+        records.extend(read_records(&current)?);
+    }
+    Ok(records)
+}
+
+async fn run_async(matches: &ArgMatches) -> Result<(), String> {
+    let recording: PathBuf = parse_required(matches, "recording")?;
+    let endpoint: SensitiveUrl = parse_required(matches, "execution-endpoint")?;
+    let jwt_path: PathBuf = parse_required(matches, "execution-jwt")?;
+    let methods: Option<String> = parse_optional(matches, "methods")?;
+    let preserve_timing = matches.get_flag("preserve-timing");
+    let timeout = Duration::from_millis(parse_required(matches, "timeout-ms")?);
+
+    let jwt_secret = std::fs::read_to_string(&jwt_path)
+        .map_err(|e| format!("Unable to read JWT secret: {:?}", e))?;
+    let jwt_key = JwtKey::from_slice(
+        &hex::decode(strip_prefix(jwt_secret.trim_end()))
+            .map_err(|e| format!("Invalid JWT secret: {:?}", e))?,
+    )?;
+    let api = HttpJsonRpc::new_with_auth(endpoint, Auth::new(jwt_key, None, None), None)
+        .map_err(|e| format!("Unable to create engine client: {:?}", e))?;
+
+    let methods = methods.map(|methods| methods.split(',').map(str::to_string).collect::<Vec<_>>());
+    let records = load_records(&recording)?
+        .into_iter()
+        .filter(|record| {
+            methods
+                .as_ref()
+                .map_or(true, |methods| methods.contains(&record.method))
+        })
+        .collect::<Vec<_>>();
+    println!("Replaying {} engine calls", records.len());
+
+    let mut mismatches = 0;
+    let mut previous_timestamp_ms = None;
+    for (index, record) in records.iter().enumerate() {
+        if preserve_timing {
+            if let Some(previous_timestamp_ms) = previous_timestamp_ms {
+                let gap = record.timestamp_ms.saturating_sub(previous_timestamp_ms);
+                tokio::time::sleep(Duration::from_millis(gap)).await;
+            }
+            previous_timestamp_ms = Some(record.timestamp_ms);
+        }
+
+        if params_hash(&record.params) != record.params_hash {
+            return Err(format!(
+                "Record {} ({}) has been modified: params do not match params_hash",
+                index, record.method
+            ));
+        }
+
+        let start = Instant::now();
+        let result = match api
+            .rpc_request::<serde_json::Value>(&record.method, record.params.clone(), timeout)
+            .await
+        {
+            Ok(value) => RecordedResult::Ok(value),
+            Err(e) => RecordedResult::Err(format!("{:?}", e)),
+        };
+        let latency = start.elapsed();
+
+        let matched = match (&record.result, &result) {
+            (RecordedResult::Ok(recorded), RecordedResult::Ok(replayed)) => recorded == replayed,
+            // Error messages differ between clients, only the failure itself is compared.
+            (RecordedResult::Err(_), RecordedResult::Err(_)) => true,
+            _ => false,
+        };
+        if !matched {
+            mismatches += 1;
+        }
+
+        println!(
+            "{:>6} {} params_hash={} recorded_latency={}ms replayed_latency={}ms {}",
+            index,
+            record.method,
+            record.params_hash,
+            record.latency_ms,
+            latency.as_millis(),
+            if matched { "match" } else { "MISMATCH" }
+        );
+        if !matched {
+            println!("       recorded: {:?}", record.result);
+            println!("       replayed: {:?}", result);
+        }
+    }
+
+    println!(
+        "Replayed {} engine calls, {} results differed from the recording",
+        records.len(),
+        mismatches
+    );
+    Ok(())
+}
@@ -0,0 +1,307 @@
+//! # Shadow Fork
+//!
+//! Generates a testnet directory whose genesis state is derived from a state of an existing chain
+//! (e.g., mainnet), so that a shadow fork can be run with the real validator set, balances and
+//! execution payload header.
+//!
+//! The snapshot is rebased to slot 0 of the new chain:
+//!
+//! - Validator epochs are shifted so that the snapshot epoch becomes the genesis epoch. Validators
+//!   active at the snapshot remain active, whilst exited and pending validators keep their
+//!   relative exit and activation schedule.
+//! - Balances, eth1 data, withdrawal indices and Electra queues are kept.
+//! - History, participation, finality, slashings and inactivity scores are reset as at genesis,
+//!   and the RANDAO mixes are seeded with the mix of the snapshot epoch.
+//! - Sync committees are recomputed and the genesis validators root is set from the (key
+//!   swapped) registry, so that messages signed for the original chain are invalid on the fork.
+//!
+//! The execution chain must be shadow forked from the block in the latest execution payload
+//! header of the snapshot.
+//!
+//! ## Key swaps
+//!
+//! The keys of existing validators can be replaced with keys controlled by the operators of the
+//! shadow fork, using a YAML (or JSON) map from existing pubkeys to replacement pubkeys:
+//!
+//! ```yaml
+//! "0x<existing pubkey>": "0x<replacement pubkey>"
+//! ```
+//!
+//! ## Example
+//!
+//! ```ignore
+//! lcli --spec mainnet --network mainnet shadow-fork \
+//!     --state /tmp/mainnet-state.ssz \
+//!     --output-dir /tmp/shadow-fork \
+//!     --fork-epochs electra=10 \
+//!     --key-swaps /tmp/key-swaps.yaml
+//! ```
+use crate::new_testnet::build_fork_schedule;
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use eth2_network_config::{Eth2NetworkConfig, GenesisStateSource};
+use ssz::Encode;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tree_hash::TreeHash;
+use types::{
+    BeaconBlock, BeaconState, BitVector, ChainSpec, Checkpoint, Config, Epoch, EthSpec, Fork, List,
+    ParticipationFlags, PublicKeyBytes, Slot, Vector,
+};
+
+pub fn run<E: EthSpec>(base: Eth2NetworkConfig, matches: &ArgMatches) -> Result<(), String> {
+    let state_path: PathBuf = parse_required(matches, "state")?;
+    let output_dir: PathBuf = parse_required(matches, "output-dir")?;
+    let force = matches.get_flag("force");
+    let fork_epochs: Option<String> = parse_optional(matches, "fork-epochs")?;
+    let genesis_time: Option<u64> = parse_optional(matches, "genesis-time")?;
+    let key_swaps_path: Option<PathBuf> = parse_optional(matches, "key-swaps")?;
+
+    /*
+     * Load the snapshot using the fork schedule of the original chain.
+     */
+
+    let base_spec = base.chain_spec::<E>()?;
+    let state_bytes = std::fs::read(&state_path)
+        .map_err(|e| format!("Unable to read {}: {:?}", state_path.display(), e))?;
+    let mut state = BeaconState::<E>::from_ssz_bytes(&state_bytes, &base_spec)
+        .map_err(|e| format!("Unable to decode {}: {:?}", state_path.display(), e))?;
+    let snapshot_slot = state.slot();
+    let snapshot_fork = state.fork_name_unchecked();
+    if !snapshot_fork.altair_enabled() {
+        return Err("Shadow forks require a snapshot from Altair or later".into());
+    }
+
+    let key_swaps: HashMap<PublicKeyBytes, PublicKeyBytes> = key_swaps_path
+        .map(|path| {
+            let file = File::open(&path)
+                .map_err(|e| format!("Unable to open {}: {:?}", path.display(), e))?;
+            serde_yaml::from_reader(file)
+                .map_err(|e| format!("Unable to parse {}: {:?}", path.display(), e))
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    /*
+     * Build the fork schedule, starting at the fork of the snapshot.
+     */
+
+    let mut spec = base_spec;
+    build_fork_schedule(&mut spec, Some(snapshot_fork), fork_epochs.as_deref())?;
+    if spec.fork_name_at_epoch(Epoch::new(0)) != snapshot_fork {
+        return Err(format!(
+            "The fork epochs must schedule {} (the fork of the snapshot) at genesis",
+            snapshot_fork
+        ));
+    }
+
+    let genesis_time = match genesis_time {
+        Some(genesis_time) => genesis_time,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("Unable to read system time: {:?}", e))?
+            .as_secs(),
+    };
+
+    /*
+     * Rebase the snapshot to genesis.
+     */
+
+    swap_keys(&mut state, key_swaps)?;
+    rebase_to_genesis(&mut state, genesis_time, &spec)?;
+
+    /*
+     * Write the testnet directory.
+     */
+
+    let network_config = Eth2NetworkConfig {
+        deposit_contract_deploy_block: base.deposit_contract_deploy_block,
+        boot_enr: None,
+        genesis_state_source: GenesisStateSource::IncludedBytes,
+        genesis_state_bytes: Some(state.as_ssz_bytes().into()),
+        config: Config::from_chain_spec::<E>(&spec),
+        kzg_trusted_setup: base.kzg_trusted_setup,
+    };
+    network_config.write_to_file(output_dir.clone(), force)?;
+
+    eprintln!(
+        "Wrote shadow fork of slot {} with {} validators at genesis fork {} to {}",
+        snapshot_slot,
+        state.validators().len(),
+        snapshot_fork,
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+/// Replaces the pubkeys of the validators in `key_swaps`.
+///
+/// Returns an error if an existing key is not in the registry, or if the swaps would leave two
+/// validators with the same key.
+fn swap_keys<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    mut key_swaps: HashMap<PublicKeyBytes, PublicKeyBytes>,
+) -> Result<(), String> {
+    if key_swaps.is_empty() {
+        return Ok(());
+    }
+
+    let mut pubkeys = HashSet::with_capacity(state.validators().len());
+    for validator in state.validators().iter() {
+        let pubkey = key_swaps
+            .get(&validator.pubkey)
+            .unwrap_or(&validator.pubkey);
+        if !pubkeys.insert(*pubkey) {
+            return Err(format!("Key swaps produce a duplicate pubkey {:?}", pubkey));
+        }
+    }
+
+    {
+        let mut validators = state.validators_mut().iter_cow();
+        while let Some((_, validator)) = validators.next_cow() {
+            if let Some(pubkey) = key_swaps.remove(&validator.pubkey) {
+                validator.into_mut().map_err(|e| format!("{:?}", e))?.pubkey = pubkey;
+            }
+        }
+    }
+
+    match key_swaps.keys().next() {
+        Some(pubkey) => Err(format!("Unable to swap unknown validator {:?}", pubkey)),
+        None => Ok(()),
+    }
+}
+
+/// Rebases `state` to slot 0 of a chain starting at `genesis_time`.
+///
+/// `spec` must schedule the fork of `state` at genesis.
+fn rebase_to_genesis<E: EthSpec>(
+    state: &mut BeaconState<E>,
+    genesis_time: u64,
+    spec: &ChainSpec,
+) -> Result<(), String> {
+    let snapshot_epoch = state.current_epoch();
+    let shift = |epoch: Epoch| {
+        if epoch == spec.far_future_epoch {
+            epoch
+        } else {
+            epoch.saturating_sub(snapshot_epoch)
+        }
+    };
+    let mix = *state
+        .get_randao_mix(snapshot_epoch)
+        .map_err(|e| format!("Unable to read RANDAO mix: {:?}", e))?;
+
+    /*
+     * Versioning
+     */
+
+    let fork_version = spec.fork_version_for_name(state.fork_name_unchecked());
+    *state.genesis_time_mut() = genesis_time;
+    *state.slot_mut() = Slot::new(0);
+    *state.fork_mut() = Fork {
+        previous_version: fork_version,
+        current_version: fork_version,
+        epoch: Epoch::new(0),
+    };
+
+    /*
+     * History
+     */
+
+    *state.latest_block_header_mut() = BeaconBlock::<E>::empty(spec).temporary_block_header();
+    *state.block_roots_mut() = Vector::default();
+    *state.state_roots_mut() = Vector::default();
+    *state.historical_roots_mut() = List::default();
+    if let Ok(historical_summaries) = state.historical_summaries_mut() {
+        *historical_summaries = List::default();
+    }
+    *state.eth1_data_votes_mut() = List::default();
+
+    state
+        .fill_randao_mixes_with(mix)
+        .map_err(|e| format!("Unable to fill RANDAO mixes: {:?}", e))?;
+    *state.slashings_mut() = Vector::default();
+
+    /*
+     * Registry
+     */
+
+    {
+        let mut validators = state.validators_mut().iter_cow();
+        while let Some((_, validator)) = validators.next_cow() {
+            let validator = validator.into_mut().map_err(|e| format!("{:?}", e))?;
+            validator.activation_eligibility_epoch = shift(validator.activation_eligibility_epoch);
+            validator.activation_epoch = shift(validator.activation_epoch);
+            validator.exit_epoch = shift(validator.exit_epoch);
+            validator.withdrawable_epoch = shift(validator.withdrawable_epoch);
+        }
+    }
+
+    if let Ok(earliest_exit_epoch) = state.earliest_exit_epoch_mut() {
+        *earliest_exit_epoch = shift(*earliest_exit_epoch);
+    }
+    if let Ok(earliest_consolidation_epoch) = state.earliest_consolidation_epoch_mut() {
+        *earliest_consolidation_epoch = shift(*earliest_consolidation_epoch);
+    }
+    if let Ok(pending_partial_withdrawals) = state.pending_partial_withdrawals_mut() {
+        let shifted = pending_partial_withdrawals
+            .iter()
+            .map(|withdrawal| {
+                let mut withdrawal = withdrawal.clone();
+                withdrawal.withdrawable_epoch = shift(withdrawal.withdrawable_epoch);
+                withdrawal
+            })
+            .collect();
+        *pending_partial_withdrawals =
+            List::new(shifted).map_err(|e| format!("Invalid partial withdrawals: {:?}", e))?;
+    }
+
+    /*
+     * Participation and finality
+     */
+
+    let validator_count = state.validators().len();
+    let empty_participation = List::repeat(ParticipationFlags::default(), validator_count)
+        .map_err(|e| format!("Invalid participation: {:?}", e))?;
+    *state
+        .previous_epoch_participation_mut()
+        .map_err(|e| format!("{:?}", e))? = empty_participation.clone();
+    *state
+        .current_epoch_participation_mut()
+        .map_err(|e| format!("{:?}", e))? = empty_participation;
+    *state
+        .inactivity_scores_mut()
+        .map_err(|e| format!("{:?}", e))? = List::repeat(0, validator_count)
+        .map_err(|e| format!("Invalid inactivity scores: {:?}", e))?;
+
+    *state.justification_bits_mut() = BitVector::new();
+    *state.previous_justified_checkpoint_mut() = Checkpoint::default();
+    *state.current_justified_checkpoint_mut() = Checkpoint::default();
+    *state.finalized_checkpoint_mut() = Checkpoint::default();
+
+    /*
+     * Sync committees and the genesis validators root, which commit to the swapped keys.
+     */
+
+    state
+        .drop_all_caches()
+        .map_err(|e| format!("Unable to drop caches: {:?}", e))?;
+    let sync_committee = Arc::new(
+        state
+            .get_next_sync_committee(spec)
+            .map_err(|e| format!("Unable to compute sync committee: {:?}", e))?,
+    );
+    *state
+        .current_sync_committee_mut()
+        .map_err(|e| format!("{:?}", e))? = sync_committee.clone();
+    *state
+        .next_sync_committee_mut()
+        .map_err(|e| format!("{:?}", e))? = sync_committee;
+    *state.genesis_validators_root_mut() = state.validators().tree_hash_root();
+
+    Ok(())
+}
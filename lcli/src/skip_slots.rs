@@ -21,6 +21,9 @@
 //!     --runs 2
 //! ```
 //!
+//! The fork schedule of the beacon node's network is used to decode the state, so `--network`
+//! only needs to be provided when loading states from file.
+//!
 //! ### Example 2.
 //!
 //! Download a state to a SSZ file (without modifying it):
@@ -44,11 +47,16 @@
 //!     --slots 32 \
 //!     --runs 2
 //! ```
-use crate::transition_blocks::load_from_ssz_with;
+use crate::transition_blocks::{
+    download_block, download_state, load_from_ssz_with, remote_chain_spec,
+};
 use clap::ArgMatches;
 use clap_utils::{parse_optional, parse_required};
 use environment::Environment;
-use eth2::{types::StateId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use eth2::{
+    types::{BlockId, StateId},
+    BeaconNodeHttpClient, SensitiveUrl, Timeouts,
+};
 use eth2_network_config::Eth2NetworkConfig;
 use log::info;
 use ssz::Encode;
@@ -67,8 +75,8 @@ pub fn run<E: EthSpec>(
     network_config: Eth2NetworkConfig,
     matches: &ArgMatches,
 ) -> Result<(), String> {
-    let spec = &network_config.chain_spec::<E>()?;
     let executor = env.core_context().executor;
+    let handle = executor.handle().ok_or("shutdown in progress")?;
 
     let output_path: Option<PathBuf> = parse_optional(matches, "output-path")?;
     let state_path: Option<PathBuf> = parse_optional(matches, "pre-state-path")?;
@@ -78,31 +86,34 @@ pub fn run<E: EthSpec>(
     let cli_state_root: Option<Hash256> = parse_optional(matches, "state-root")?;
     let partial: bool = matches.get_flag("partial-state-advance");
 
+    // States from a beaconAPI are decoded with the fork schedule of that beacon node.
+    let client =
+        beacon_url.map(|url| BeaconNodeHttpClient::new(url, Timeouts::set_all(HTTP_TIMEOUT)));
+    let spec = &match &client {
+        Some(client) => handle.block_on(remote_chain_spec::<E>(client))?,
+        None => network_config.chain_spec::<E>()?,
+    };
+
     info!("Using {} spec", E::spec_name());
     info!("Advancing {} slots", slots);
     info!("Doing {} runs", runs);
 
-    let (mut state, state_root) = match (state_path, beacon_url) {
+    let (mut state, state_root) = match (state_path, &client) {
         (Some(state_path), None) => {
             info!("State path: {:?}", state_path);
             let state = load_from_ssz_with(&state_path, spec, BeaconState::from_ssz_bytes)?;
             (state, None)
         }
-        (None, Some(beacon_url)) => {
-            let state_id: StateId = parse_required(matches, "state-id")?;
-            let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
-            let state = executor
-                .handle()
-                .ok_or("shutdown in progress")?
-                .block_on(async move {
-                    client
-                        .get_debug_beacon_states::<E>(state_id)
-                        .await
-                        .map_err(|e| format!("Failed to download state: {:?}", e))
-                })
-                .map_err(|e| format!("Failed to complete task: {:?}", e))?
-                .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?
-                .data;
+        (None, Some(client)) => {
+            let block_id: Option<BlockId> = parse_optional(matches, "block-id")?;
+            let state_id = match block_id {
+                Some(block_id) => {
+                    let block = handle.block_on(download_block::<E>(client, block_id, spec))?;
+                    StateId::Root(block.state_root())
+                }
+                None => parse_required(matches, "state-id")?,
+            };
+            let state = handle.block_on(download_state(client, state_id, spec))?;
             let state_root = match state_id {
                 StateId::Root(root) => Some(root),
                 _ => None,
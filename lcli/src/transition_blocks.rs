@@ -24,6 +24,10 @@
 //!     --runs 10
 //! ```
 //!
+//! The block and pre-state are downloaded as SSZ and decoded with the fork schedule of the
+//! beaconAPI's network. Either can be loaded from file instead, e.g. `--pre-state-path` with
+//! `--block-id`, or `--pre-state-id` to start from a state other than the parent's.
+//!
 //! ### Download a block and pre-state from a beaconAPI to the filesystem
 //!
 //! Download a block and pre-state to the filesystem, without performing any transitions:
@@ -61,6 +65,13 @@
 //!     --exclude-cache-builds \
 //!     --exclude-post-block-thc
 //! ```
+//!
+//! ### Benchmark a batch of blocks
+//!
+//! Supplying `--blocks-dir` (or `--start-slot` and `--end-slot` with `--beacon-url`) applies a
+//! sequence of blocks instead of a single block. See the `batch` module for details.
+mod batch;
+
 use beacon_chain::{
     test_utils::EphemeralHarnessType, validator_pubkey_cache::ValidatorPubkeyCache,
 };
@@ -103,26 +114,50 @@ pub fn run<E: EthSpec>(
     network_config: Eth2NetworkConfig,
     matches: &ArgMatches,
 ) -> Result<(), String> {
-    let spec = Arc::new(network_config.chain_spec::<E>()?);
+    let config = Config {
+        no_signature_verification: matches.get_flag("no-signature-verification"),
+        exclude_cache_builds: matches.get_flag("exclude-cache-builds"),
+        exclude_post_block_thc: matches.get_flag("exclude-post-block-thc"),
+    };
+
     let executor = env.core_context().executor;
+    let handle = executor.handle().ok_or("shutdown in progress")?;
+
+    // Objects from a beaconAPI are decoded with the fork schedule of that beacon node.
+    let beacon_url: Option<SensitiveUrl> = parse_optional(matches, "beacon-url")?;
+    let client =
+        beacon_url.map(|url| BeaconNodeHttpClient::new(url, Timeouts::set_all(HTTP_TIMEOUT)));
+    let spec = Arc::new(match &client {
+        Some(client) => handle.block_on(remote_chain_spec::<E>(client))?,
+        None => network_config.chain_spec::<E>()?,
+    });
+
+    if batch::is_batch(matches) {
+        info!("Using {} spec", E::spec_name());
+        info!("{:?}", &config);
+        return batch::run(env, &spec, &config, matches);
+    }
+    if matches.contains_id("report-output-path") || matches.contains_id("flamegraph-output-path") {
+        return Err(
+            "--report-output-path and --flamegraph-output-path require --blocks-dir or \
+             --start-slot"
+                .into(),
+        );
+    }
 
     /*
      * Parse (most) CLI arguments.
      */
 
     let pre_state_path: Option<PathBuf> = parse_optional(matches, "pre-state-path")?;
+    let pre_state_id: Option<StateId> = parse_optional(matches, "pre-state-id")?;
     let block_path: Option<PathBuf> = parse_optional(matches, "block-path")?;
+    let block_id: Option<BlockId> = parse_optional(matches, "block-id")?;
     let post_state_output_path: Option<PathBuf> =
         parse_optional(matches, "post-state-output-path")?;
     let pre_state_output_path: Option<PathBuf> = parse_optional(matches, "pre-state-output-path")?;
     let block_output_path: Option<PathBuf> = parse_optional(matches, "block-output-path")?;
-    let beacon_url: Option<SensitiveUrl> = parse_optional(matches, "beacon-url")?;
     let runs: usize = parse_required(matches, "runs")?;
-    let config = Config {
-        no_signature_verification: matches.get_flag("no-signature-verification"),
-        exclude_cache_builds: matches.get_flag("exclude-cache-builds"),
-        exclude_post_block_thc: matches.get_flag("exclude-post-block-thc"),
-    };
 
     info!("Using {} spec", E::spec_name());
     info!("Doing {} runs", runs);
@@ -130,60 +165,46 @@ pub fn run<E: EthSpec>(
 
     /*
      * Load the block and pre-state from disk or beaconAPI URL.
+     *
+     * Each may come from either source. Without a pre-state path or ID, the post-state of the
+     * block's parent is downloaded.
      */
 
-    let (mut pre_state, mut state_root_opt, block) = match (pre_state_path, block_path, beacon_url)
-    {
-        (Some(pre_state_path), Some(block_path), None) => {
+    let block = match (block_path, block_id, &client) {
+        (Some(block_path), None, _) => {
             info!("Block path: {:?}", block_path);
+            load_from_ssz_with(&block_path, &spec, SignedBeaconBlock::from_ssz_bytes)?
+        }
+        (None, Some(block_id), Some(client)) => {
+            handle.block_on(download_block(client, block_id, &spec))?
+        }
+        _ => return Err("must supply either --block-path or --beacon-url with --block-id".into()),
+    };
+
+    let (mut pre_state, mut state_root_opt) = match (pre_state_path, pre_state_id, &client) {
+        (Some(pre_state_path), None, _) => {
             info!("Pre-state path: {:?}", pre_state_path);
             let pre_state =
                 load_from_ssz_with(&pre_state_path, &spec, BeaconState::from_ssz_bytes)?;
-            let block = load_from_ssz_with(&block_path, &spec, SignedBeaconBlock::from_ssz_bytes)?;
-            (pre_state, None, block)
+            (pre_state, None)
+        }
+        (None, Some(state_id), Some(client)) => {
+            let pre_state = handle.block_on(download_state(client, state_id, &spec))?;
+            let state_root = match state_id {
+                StateId::Root(root) => Some(root),
+                _ => None,
+            };
+            (pre_state, state_root)
         }
-        (None, None, Some(beacon_url)) => {
-            let block_id: BlockId = parse_required(matches, "block-id")?;
-            let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
-            let inner_spec = spec.clone();
-            executor
-                .handle()
-                .ok_or("shutdown in progress")?
-                .block_on(async move {
-                    let block = client
-                        .get_beacon_blocks(block_id)
-                        .await
-                        .map_err(|e| format!("Failed to download block: {:?}", e))?
-                        .ok_or_else(|| format!("Unable to locate block at {:?}", block_id))?
-                        .data;
-
-                    if block.slot() == inner_spec.genesis_slot {
-                        return Err("Cannot run on the genesis block".to_string());
-                    }
-
-                    let parent_block: SignedBeaconBlock<E> = client
-                        .get_beacon_blocks(BlockId::Root(block.parent_root()))
-                        .await
-                        .map_err(|e| format!("Failed to download parent block: {:?}", e))?
-                        .ok_or_else(|| format!("Unable to locate parent block at {:?}", block_id))?
-                        .data;
-
-                    let state_root = parent_block.state_root();
-                    let state_id = StateId::Root(state_root);
-                    let pre_state = client
-                        .get_debug_beacon_states::<E>(state_id)
-                        .await
-                        .map_err(|e| format!("Failed to download state: {:?}", e))?
-                        .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?
-                        .data;
-
-                    Ok((pre_state, Some(state_root), block))
-                })
-                .map_err(|e| format!("Failed to complete task: {:?}", e))?
+        (None, None, Some(client)) => {
+            let (pre_state, state_root) =
+                handle.block_on(download_parent_state(client, &block, &spec))?;
+            (pre_state, Some(state_root))
         }
         _ => {
             return Err(
-                "must supply *both* --pre-state-path and --block-path *or* only --beacon-url"
+                "must supply either --pre-state-path or --beacon-url (optionally with \
+                 --pre-state-id)"
                     .into(),
             )
         }
@@ -438,3 +459,82 @@ pub fn load_from_ssz_with<T>(
     debug!("SSZ decoding {}: {:?}", path.display(), t.elapsed());
     result
 }
+
+/// Download the config of the beacon node at `client` and return its `ChainSpec`.
+///
+/// Objects downloaded from the beacon node are decoded with this spec, so that their forks are
+/// detected using the network the beacon node is following rather than `--network`.
+pub async fn remote_chain_spec<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+) -> Result<ChainSpec, String> {
+    let remote_config = client
+        .get_config_spec::<types::Config>()
+        .await
+        .map_err(|e| format!("Failed to download spec: {:?}", e))?
+        .data;
+    let spec = remote_config
+        .apply_to_chain_spec::<E>(&E::default_spec())
+        .ok_or_else(|| {
+            format!(
+                "Beacon node uses the {} preset, which is incompatible with --spec {}",
+                remote_config.preset_base,
+                E::spec_name()
+            )
+        })?;
+    info!(
+        "Using fork schedule of beacon node network: {}",
+        remote_config.config_name.as_deref().unwrap_or("unknown")
+    );
+    Ok(spec)
+}
+
+/// Download a block as SSZ.
+pub async fn download_block<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    block_id: BlockId,
+    spec: &ChainSpec,
+) -> Result<SignedBeaconBlock<E>, String> {
+    let t = Instant::now();
+    let block = client
+        .get_beacon_blocks_ssz::<E>(block_id, spec)
+        .await
+        .map_err(|e| format!("Failed to download block: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate block at {:?}", block_id))?;
+    debug!("Downloaded block {}: {:?}", block_id, t.elapsed());
+    Ok(block)
+}
+
+/// Download a state as SSZ.
+pub async fn download_state<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    state_id: StateId,
+    spec: &ChainSpec,
+) -> Result<BeaconState<E>, String> {
+    let t = Instant::now();
+    let state = client
+        .get_debug_beacon_states_ssz::<E>(state_id, spec)
+        .await
+        .map_err(|e| format!("Failed to download state: {:?}", e))?
+        .ok_or_else(|| format!("Unable to locate state at {:?}", state_id))?;
+    debug!("Downloaded state {}: {:?}", state_id, t.elapsed());
+    Ok(state)
+}
+
+/// Download the post-state of the parent of `block`, which is the pre-state of `block`, along
+/// with its state root.
+pub async fn download_parent_state<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    block: &SignedBeaconBlock<E>,
+    spec: &ChainSpec,
+) -> Result<(BeaconState<E>, Hash256), String> {
+    if block.slot() == spec.genesis_slot {
+        return Err("Cannot run on the genesis block".to_string());
+    }
+
+    let parent_block_id = BlockId::Root(block.parent_root());
+    let parent_block: SignedBeaconBlock<E> = download_block(client, parent_block_id, spec).await?;
+    let state_root = parent_block.state_root();
+    let pre_state = download_state(client, StateId::Root(state_root), spec).await?;
+
+    Ok((pre_state, state_root))
+}
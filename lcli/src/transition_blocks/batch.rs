@@ -0,0 +1,533 @@
+//! Batch benchmarking for `transition-blocks`.
+//!
+//! Applies a sequence of blocks to a pre-state with `BlockReplayer`, repeating the replay
+//! `--runs` times and timing each stage (slot processing, epoch processing, block processing
+//! and tree hashing) separately. The timings can be written to a JSON or CSV report so that
+//! changes to epoch or block processing can be compared between builds.
+//!
+//! ## Examples
+//!
+//! Replay a directory of SSZ blocks atop a pre-state, writing a CSV report:
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --pre-state-path /tmp/pre-state.ssz \
+//!     --blocks-dir /tmp/blocks \
+//!     --runs 5 \
+//!     --report-output-path /tmp/report.csv \
+//!     --report-format csv
+//! ```
+//!
+//! Download a range of blocks and their pre-state from a beaconAPI, capturing a flamegraph
+//! (requires `lcli` to be built with the `flamegraph` feature):
+//!
+//! ```ignore
+//! lcli transition-blocks \
+//!     --beacon-url http://localhost:5052 \
+//!     --start-slot 9000000 \
+//!     --end-slot 9000064 \
+//!     --flamegraph-output-path /tmp/flamegraph.svg
+//! ```
+use super::{download_parent_state, load_from_ssz_with, Config, HTTP_TIMEOUT};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required};
+use environment::Environment;
+use eth2::{types::BlockId, BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+use log::{debug, info};
+use serde::Serialize;
+use ssz::Encode;
+use state_processing::{AllCaches, BlockReplayError, BlockReplayer};
+use std::cell::RefCell;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use types::{BeaconState, ChainSpec, EthSpec, SignedBeaconBlock, SignedBlindedBeaconBlock, Slot};
+
+/// The format of the benchmark report.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReportFormat {
+    Json,
+    Csv,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            other => Err(format!("Unknown report format: {}", other)),
+        }
+    }
+}
+
+/// The time spent in each stage of a single run.
+#[derive(Debug, Default, Clone, Serialize)]
+struct RunReport {
+    run: usize,
+    cache_builds_ms: f64,
+    slot_processing_ms: f64,
+    epoch_processing_ms: f64,
+    block_processing_ms: f64,
+    tree_hash_ms: f64,
+    total_ms: f64,
+    slots: u64,
+    epochs: u64,
+}
+
+impl RunReport {
+    const CSV_HEADER: &'static str = "run,cache_builds_ms,slot_processing_ms,\
+        epoch_processing_ms,block_processing_ms,tree_hash_ms,total_ms,slots,epochs";
+
+    /// The timed stages, in the order in which they appear in the report.
+    fn stages(&self) -> [(&'static str, f64); 6] {
+        [
+            ("cache_builds", self.cache_builds_ms),
+            ("slot_processing", self.slot_processing_ms),
+            ("epoch_processing", self.epoch_processing_ms),
+            ("block_processing", self.block_processing_ms),
+            ("tree_hash", self.tree_hash_ms),
+            ("total", self.total_ms),
+        ]
+    }
+
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{}",
+            self.run,
+            self.cache_builds_ms,
+            self.slot_processing_ms,
+            self.epoch_processing_ms,
+            self.block_processing_ms,
+            self.tree_hash_ms,
+            self.total_ms,
+            self.slots,
+            self.epochs
+        )
+    }
+}
+
+/// The minimum, mean and maximum time spent in a stage across all runs.
+#[derive(Debug, Serialize)]
+struct StageSummary {
+    stage: &'static str,
+    min_ms: f64,
+    mean_ms: f64,
+    max_ms: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct BenchmarkReport {
+    spec: String,
+    first_slot: Slot,
+    last_slot: Slot,
+    blocks: usize,
+    signature_verification: bool,
+    runs: Vec<RunReport>,
+    summary: Vec<StageSummary>,
+}
+
+impl BenchmarkReport {
+    fn summarize(runs: &[RunReport]) -> Vec<StageSummary> {
+        let Some(first) = runs.first() else {
+            return vec![];
+        };
+        first
+            .stages()
+            .iter()
+            .enumerate()
+            .map(|(i, (stage, _))| {
+                let times = runs.iter().map(|run| run.stages()[i].1).collect::<Vec<_>>();
+                StageSummary {
+                    stage: *stage,
+                    min_ms: times.iter().copied().fold(f64::INFINITY, f64::min),
+                    mean_ms: times.iter().sum::<f64>() / times.len() as f64,
+                    max_ms: times.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                }
+            })
+            .collect()
+    }
+
+    fn write(&self, path: &Path, format: ReportFormat) -> Result<(), String> {
+        let file =
+            File::create(path).map_err(|e| format!("Unable to create report file: {:?}", e))?;
+        let mut writer = BufWriter::new(file);
+        match format {
+            ReportFormat::Json => serde_json::to_writer_pretty(&mut writer, self)
+                .map_err(|e| format!("Unable to write report: {:?}", e))?,
+            ReportFormat::Csv => {
+                writeln!(writer, "{}", RunReport::CSV_HEADER)
+                    .and_then(|()| {
+                        self.runs
+                            .iter()
+                            .try_for_each(|run| writeln!(writer, "{}", run.csv_row()))
+                    })
+                    .map_err(|e| format!("Unable to write report: {:?}", e))?;
+            }
+        }
+        writer
+            .flush()
+            .map_err(|e| format!("Unable to write report: {:?}", e))
+    }
+}
+
+/// Accumulates the time spent in each stage from within the `BlockReplayer` hooks.
+#[derive(Default)]
+struct StageTimer {
+    started: Option<Instant>,
+    report: RunReport,
+}
+
+impl StageTimer {
+    fn start(&mut self) {
+        self.started = Some(Instant::now());
+    }
+
+    fn elapsed(&mut self) -> Duration {
+        self.started.take().map_or(Duration::ZERO, |t| t.elapsed())
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Returns `true` if `matches` requests a batch of blocks rather than a single block.
+pub fn is_batch(matches: &ArgMatches) -> bool {
+    matches.contains_id("blocks-dir") || matches.contains_id("start-slot")
+}
+
+pub fn run<E: EthSpec>(
+    env: Environment<E>,
+    spec: &ChainSpec,
+    config: &Config,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let pre_state_path: Option<PathBuf> = parse_optional(matches, "pre-state-path")?;
+    let blocks_dir: Option<PathBuf> = parse_optional(matches, "blocks-dir")?;
+    let beacon_url: Option<SensitiveUrl> = parse_optional(matches, "beacon-url")?;
+    let start_slot: Option<Slot> = parse_optional(matches, "start-slot")?;
+    let end_slot: Option<Slot> = parse_optional(matches, "end-slot")?;
+    let pre_state_output_path: Option<PathBuf> = parse_optional(matches, "pre-state-output-path")?;
+    let post_state_output_path: Option<PathBuf> =
+        parse_optional(matches, "post-state-output-path")?;
+    let report_output_path: Option<PathBuf> = parse_optional(matches, "report-output-path")?;
+    let report_format: ReportFormat = parse_required(matches, "report-format")?;
+    let flamegraph_output_path: Option<PathBuf> =
+        parse_optional(matches, "flamegraph-output-path")?;
+    let runs: usize = parse_required(matches, "runs")?;
+
+    let in_range = |slot: Slot| {
+        start_slot.map_or(true, |start| slot >= start) && end_slot.map_or(true, |end| slot <= end)
+    };
+
+    let (mut pre_state, blocks) = match (pre_state_path, blocks_dir, beacon_url) {
+        (Some(pre_state_path), Some(blocks_dir), None) => {
+            info!("Blocks dir: {:?}", blocks_dir);
+            info!("Pre-state path: {:?}", pre_state_path);
+            let pre_state = load_from_ssz_with(&pre_state_path, spec, BeaconState::from_ssz_bytes)?;
+            let mut blocks = load_blocks_dir::<E>(&blocks_dir, spec)?;
+            blocks.retain(|block| in_range(block.slot()));
+            (pre_state, blocks)
+        }
+        (pre_state_path, None, Some(beacon_url)) => {
+            let start_slot = start_slot.ok_or("--start-slot is required with --beacon-url")?;
+            let end_slot = end_slot.ok_or("--end-slot is required with --beacon-url")?;
+            let client = BeaconNodeHttpClient::new(beacon_url, Timeouts::set_all(HTTP_TIMEOUT));
+            let handle = env
+                .core_context()
+                .executor
+                .handle()
+                .ok_or("shutdown in progress")?;
+            let blocks = handle.block_on(download_blocks(&client, start_slot, end_slot, spec))?;
+            let pre_state = if let Some(pre_state_path) = pre_state_path {
+                info!("Pre-state path: {:?}", pre_state_path);
+                load_from_ssz_with(&pre_state_path, spec, BeaconState::from_ssz_bytes)?
+            } else {
+                let first_block = blocks.first().ok_or("No blocks in range")?;
+                handle
+                    .block_on(download_parent_state(&client, first_block, spec))?
+                    .0
+            };
+            (pre_state, blocks)
+        }
+        _ => {
+            return Err(
+                "must supply *both* --pre-state-path and --blocks-dir *or* --beacon-url with \
+                 --start-slot and --end-slot"
+                    .into(),
+            )
+        }
+    };
+
+    let (first_slot, last_slot) = match (blocks.first(), blocks.last()) {
+        (Some(first), Some(last)) => (first.slot(), last.slot()),
+        _ => return Err("No blocks to apply".into()),
+    };
+    if pre_state.slot() > first_slot {
+        return Err(format!(
+            "Pre-state slot {} is after the first block at slot {}",
+            pre_state.slot(),
+            first_slot
+        ));
+    }
+    info!(
+        "Applying {} blocks from slot {} to {}",
+        blocks.len(),
+        first_slot,
+        last_slot
+    );
+
+    if let Some(path) = pre_state_output_path {
+        write_ssz(&path, &pre_state.as_ssz_bytes())?;
+    }
+
+    if config.exclude_cache_builds {
+        pre_state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+        pre_state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build THC: {:?}", e))?;
+    }
+
+    let profiler = flamegraph_output_path
+        .as_ref()
+        .map(|_| flamegraph::start())
+        .transpose()?;
+
+    let blinded_blocks = blocks
+        .iter()
+        .map(SignedBeaconBlock::clone_as_blinded)
+        .collect::<Vec<_>>();
+    let mut run_reports = Vec::with_capacity(runs);
+    let mut output_post_state = None;
+    for i in 0..runs {
+        let (post_state, mut report) =
+            replay_blocks(pre_state.clone(), blinded_blocks.clone(), config, spec)?;
+        report.run = i;
+        info!("Run {}: {:.3}ms", i, report.total_ms);
+        debug!("{:?}", report);
+
+        if output_post_state.is_none() {
+            output_post_state = Some(post_state);
+        }
+        run_reports.push(report);
+    }
+
+    if let (Some(profiler), Some(path)) = (profiler, &flamegraph_output_path) {
+        flamegraph::finish(profiler, path)?;
+        info!("Flamegraph written to {:?}", path);
+    }
+
+    if let Some(path) = report_output_path {
+        let report = BenchmarkReport {
+            spec: E::spec_name().to_string(),
+            first_slot,
+            last_slot,
+            blocks: blocks.len(),
+            signature_verification: !config.no_signature_verification,
+            summary: BenchmarkReport::summarize(&run_reports),
+            runs: run_reports,
+        };
+        report.write(&path, report_format)?;
+        info!("Report written to {:?}", path);
+    }
+
+    if let Some(path) = post_state_output_path {
+        let output_post_state = output_post_state.ok_or_else(|| {
+            format!(
+                "Post state was not computed, cannot save to disk (runs = {})",
+                runs
+            )
+        })?;
+        write_ssz(&path, &output_post_state.as_ssz_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Apply `blocks` atop `state`, returning the post-state and the time spent in each stage.
+fn replay_blocks<E: EthSpec>(
+    mut state: BeaconState<E>,
+    blocks: Vec<SignedBlindedBeaconBlock<E>>,
+    config: &Config,
+    spec: &ChainSpec,
+) -> Result<(BeaconState<E>, RunReport), String> {
+    let total = Instant::now();
+    let last_block = blocks
+        .last()
+        .map(|block| (block.slot(), block.state_root()));
+    let timer = RefCell::new(StageTimer::default());
+
+    if !config.exclude_cache_builds {
+        let t = Instant::now();
+        state
+            .build_all_caches(spec)
+            .map_err(|e| format!("Unable to build caches: {:?}", e))?;
+        state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
+        timer.borrow_mut().report.cache_builds_ms = millis(t.elapsed());
+    }
+
+    let mut replayer = BlockReplayer::<E, BlockReplayError, _>::new(state, spec)
+        .no_state_root_iter()
+        .pre_slot_hook(Box::new(|_, _| {
+            timer.borrow_mut().start();
+            Ok(())
+        }))
+        .post_slot_hook(Box::new(|_, summary, _| {
+            let mut timer = timer.borrow_mut();
+            let elapsed = millis(timer.elapsed());
+            timer.report.slots += 1;
+            // The slot which crosses an epoch boundary includes epoch processing.
+            if summary.is_some() {
+                timer.report.epochs += 1;
+                timer.report.epoch_processing_ms += elapsed;
+            } else {
+                timer.report.slot_processing_ms += elapsed;
+            }
+            Ok(())
+        }))
+        .pre_block_hook(Box::new(|_, _| {
+            timer.borrow_mut().start();
+            Ok(())
+        }))
+        .post_block_hook(Box::new(|_, _| {
+            let mut timer = timer.borrow_mut();
+            timer.report.block_processing_ms += millis(timer.elapsed());
+            Ok(())
+        }));
+    if config.no_signature_verification {
+        replayer = replayer.no_signature_verification();
+    }
+    let mut state = replayer
+        .apply_blocks(blocks, None)
+        .map_err(|e| format!("Failed to apply blocks: {:?}", e))?
+        .into_state();
+
+    if !config.exclude_post_block_thc {
+        let t = Instant::now();
+        let state_root = state
+            .update_tree_hash_cache()
+            .map_err(|e| format!("Unable to build tree hash cache: {:?}", e))?;
+        timer.borrow_mut().report.tree_hash_ms = millis(t.elapsed());
+
+        // Intermediate state roots are taken from the blocks, so check the final one.
+        if let Some((slot, expected)) = last_block {
+            if state.slot() == slot && state_root != expected {
+                return Err(format!(
+                    "State root mismatch at slot {}! Expected {}, computed {}",
+                    slot, expected, state_root
+                ));
+            }
+        }
+    }
+
+    let mut report = timer.into_inner().report;
+    report.total_ms = millis(total.elapsed());
+    Ok((state, report))
+}
+
+/// Load all `.ssz` blocks in `dir`, sorted by slot.
+fn load_blocks_dir<E: EthSpec>(
+    dir: &Path,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBeaconBlock<E>>, String> {
+    let mut blocks = fs::read_dir(dir)
+        .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .map_err(|e| format!("Unable to read {:?}: {:?}", dir, e))
+        })
+        .filter(|path| {
+            path.as_ref().map_or(true, |path| {
+                path.extension().map_or(false, |ext| ext == "ssz")
+            })
+        })
+        .map(|path| load_from_ssz_with(&path?, spec, SignedBeaconBlock::from_ssz_bytes))
+        .collect::<Result<Vec<_>, _>>()?;
+    blocks.sort_by_key(|block| block.slot());
+    Ok(blocks)
+}
+
+/// Download the blocks from `start_slot` to `end_slot` (inclusive), skipping empty slots.
+async fn download_blocks<E: EthSpec>(
+    client: &BeaconNodeHttpClient,
+    start_slot: Slot,
+    end_slot: Slot,
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBeaconBlock<E>>, String> {
+    let mut blocks = vec![];
+    for slot in start_slot.as_u64()..=end_slot.as_u64() {
+        let block_id = BlockId::Slot(Slot::new(slot));
+        if let Some(block) = client
+            .get_beacon_blocks_ssz::<E>(block_id, spec)
+            .await
+            .map_err(|e| format!("Failed to download block at slot {}: {:?}", slot, e))?
+        {
+            blocks.push(block);
+        }
+    }
+    debug!("Downloaded {} blocks", blocks.len());
+
+    Ok(blocks)
+}
+
+fn write_ssz(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    let mut output_file =
+        File::create(path).map_err(|e| format!("Unable to create output file: {:?}", e))?;
+    output_file
+        .write_all(bytes)
+        .map_err(|e| format!("Unable to write to output file: {:?}", e))
+}
+
+#[cfg(feature = "flamegraph")]
+mod flamegraph {
+    use std::fs::File;
+    use std::path::Path;
+
+    /// The sampling frequency of the profiler, in Hz.
+    const FREQUENCY: i32 = 1000;
+
+    pub type Profiler = pprof::ProfilerGuard<'static>;
+
+    pub fn start() -> Result<Profiler, String> {
+        pprof::ProfilerGuardBuilder::default()
+            .frequency(FREQUENCY)
+            .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+            .build()
+            .map_err(|e| format!("Unable to start profiler: {:?}", e))
+    }
+
+    pub fn finish(profiler: Profiler, path: &Path) -> Result<(), String> {
+        let report = profiler
+            .report()
+            .build()
+            .map_err(|e| format!("Unable to build profile: {:?}", e))?;
+        let file =
+            File::create(path).map_err(|e| format!("Unable to create flamegraph file: {:?}", e))?;
+        report
+            .flamegraph(file)
+            .map_err(|e| format!("Unable to write flamegraph: {:?}", e))
+    }
+}
+
+#[cfg(not(feature = "flamegraph"))]
+mod flamegraph {
+    use std::path::Path;
+
+    pub enum Profiler {}
+
+    pub fn start() -> Result<Profiler, String> {
+        Err("lcli must be built with the `flamegraph` feature to capture a flamegraph".into())
+    }
+
+    pub fn finish(profiler: Profiler, _: &Path) -> Result<(), String> {
+        match profiler {}
+    }
+}
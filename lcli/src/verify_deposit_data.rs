@@ -0,0 +1,252 @@
+//! Verifies every deposit in a `deposit_data.json` file, as produced by the `staking-deposit-cli`
+//! and consumed by the Ethereum Launchpad, or in an equivalent CSV file.
+//!
+//! A CSV file must have a header row naming its columns, which are the fields of the JSON format.
+//! The `pubkey`, `withdrawal_credentials`, `amount` and `signature` columns are required, and the
+//! `fork_version`, `deposit_message_root` and `deposit_data_root` columns are checked if present.
+use clap::ArgMatches;
+use clap_utils::parse_required;
+use eth2_network_config::Eth2NetworkConfig;
+use rayon::prelude::*;
+use state_processing::per_block_processing::is_valid_deposit_signature;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tree_hash::TreeHash;
+use types::{ChainSpec, DepositData, EthSpec, Hash256, PublicKeyBytes, SignatureBytes};
+use validator_manager::common::StandardDepositDataJson;
+
+/// A deposit, along with the optional fields of the file which are derived from it.
+struct DepositEntry {
+    deposit_data: DepositData,
+    fork_version: Option<[u8; 4]>,
+    deposit_message_root: Option<Hash256>,
+    deposit_data_root: Option<Hash256>,
+}
+
+impl From<StandardDepositDataJson> for DepositEntry {
+    fn from(json: StandardDepositDataJson) -> Self {
+        Self {
+            deposit_data: DepositData {
+                pubkey: json.pubkey,
+                withdrawal_credentials: json.withdrawal_credentials,
+                amount: json.amount,
+                signature: json.signature,
+            },
+            fork_version: Some(json.fork_version),
+            deposit_message_root: Some(json.deposit_message_root),
+            deposit_data_root: Some(json.deposit_data_root),
+        }
+    }
+}
+
+pub fn run<E: EthSpec>(
+    network_config: Eth2NetworkConfig,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let spec = network_config.chain_spec::<E>()?;
+    let path: PathBuf = parse_required(matches, "deposit-data")?;
+
+    let contents = fs::read_to_string(&path)
+        .map_err(|e| format!("Unable to read {}: {:?}", path.display(), e))?;
+    let entries = if path.extension().is_some_and(|extension| extension == "csv") {
+        parse_csv(&contents)?
+    } else {
+        serde_json::from_str::<Vec<StandardDepositDataJson>>(&contents)
+            .map_err(|e| format!("Unable to parse {}: {:?}", path.display(), e))?
+            .into_iter()
+            .map(DepositEntry::from)
+            .collect()
+    };
+
+    let failures = entries
+        .par_iter()
+        .enumerate()
+        .map(|(i, entry)| (i, entry, verify_deposit(entry, &spec)))
+        .filter(|(_, _, failures)| !failures.is_empty())
+        .collect::<Vec<_>>();
+
+    for (i, entry, reasons) in &failures {
+        println!(
+            "Deposit {} ({:?}) is invalid:",
+            i, entry.deposit_data.pubkey
+        );
+        for reason in reasons {
+            println!("  - {}", reason);
+        }
+    }
+
+    if failures.is_empty() {
+        println!("All {} deposits are valid", entries.len());
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {} deposits are invalid",
+            failures.len(),
+            entries.len()
+        ))
+    }
+}
+
+/// Returns the reasons that `entry` is invalid, if any.
+fn verify_deposit(entry: &DepositEntry, spec: &ChainSpec) -> Vec<String> {
+    let deposit_data = &entry.deposit_data;
+    let mut failures = vec![];
+
+    if let Err(e) = is_valid_deposit_signature(deposit_data, spec) {
+        failures.push(format!("invalid signature: {:?}", e));
+    }
+
+    let credentials = deposit_data.withdrawal_credentials.as_slice();
+    let max_amount = match credentials.first() {
+        Some(&prefix) if prefix == spec.bls_withdrawal_prefix_byte => spec.max_effective_balance,
+        Some(&prefix)
+            if prefix == spec.eth1_address_withdrawal_prefix_byte
+                || prefix == spec.compounding_withdrawal_prefix_byte =>
+        {
+            if credentials
+                .get(1..12)
+                .is_some_and(|padding| padding.iter().any(|&b| b != 0))
+            {
+                failures.push(format!(
+                    "withdrawal credentials {:?} are not an address padded with zeros",
+                    deposit_data.withdrawal_credentials
+                ));
+            }
+            if prefix == spec.compounding_withdrawal_prefix_byte {
+                spec.max_effective_balance_electra
+            } else {
+                spec.max_effective_balance
+            }
+        }
+        _ => {
+            failures.push(format!(
+                "withdrawal credentials {:?} have an unknown prefix",
+                deposit_data.withdrawal_credentials
+            ));
+            spec.max_effective_balance
+        }
+    };
+
+    if deposit_data.amount < spec.min_deposit_amount || deposit_data.amount > max_amount {
+        failures.push(format!(
+            "amount {} gwei is outside of the range {} to {} gwei",
+            deposit_data.amount, spec.min_deposit_amount, max_amount
+        ));
+    }
+
+    if let Some(fork_version) = entry.fork_version {
+        if fork_version != spec.genesis_fork_version {
+            failures.push(format!(
+                "fork version 0x{} does not match the genesis fork version 0x{}",
+                hex::encode(fork_version),
+                hex::encode(spec.genesis_fork_version)
+            ));
+        }
+    }
+    if let Some(root) = entry.deposit_message_root {
+        let expected = deposit_data.as_deposit_message().tree_hash_root();
+        if root != expected {
+            failures.push(format!(
+                "deposit message root {:?} does not match the computed root {:?}",
+                root, expected
+            ));
+        }
+    }
+    if let Some(root) = entry.deposit_data_root {
+        let expected = deposit_data.tree_hash_root();
+        if root != expected {
+            failures.push(format!(
+                "deposit data root {:?} does not match the computed root {:?}",
+                root, expected
+            ));
+        }
+    }
+
+    failures
+}
+
+/// Parses a CSV file with a header row into deposits.
+///
+/// Hex values may be given with or without a `0x` prefix.
+fn parse_csv(contents: &str) -> Result<Vec<DepositEntry>, String> {
+    let mut lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty());
+    let header = lines.next().ok_or("The CSV file is empty")?;
+    let columns = header.split(',').map(str::trim).collect::<Vec<_>>();
+
+    lines
+        .enumerate()
+        .map(|(i, line)| {
+            let values = line.split(',').map(str::trim).collect::<Vec<_>>();
+            if values.len() != columns.len() {
+                return Err(format!(
+                    "Row {} has {} values, expected {}",
+                    i,
+                    values.len(),
+                    columns.len()
+                ));
+            }
+            let row = columns
+                .iter()
+                .copied()
+                .zip(values)
+                .collect::<HashMap<_, _>>();
+            parse_csv_row(&row).map_err(|e| format!("Row {} is invalid: {}", i, e))
+        })
+        .collect()
+}
+
+fn parse_csv_row(row: &HashMap<&str, &str>) -> Result<DepositEntry, String> {
+    let required = |column: &str| {
+        row.get(column)
+            .copied()
+            .ok_or_else(|| format!("missing {} column", column))
+    };
+    let with_prefix = |value: &str| format!("0x{}", value.trim_start_matches("0x"));
+    let parse_root = |column: &str| {
+        row.get(column)
+            .map(|value| {
+                Hash256::from_str(&with_prefix(value))
+                    .map_err(|e| format!("invalid {}: {:?}", column, e))
+            })
+            .transpose()
+    };
+
+    let pubkey = PublicKeyBytes::from_str(&with_prefix(required("pubkey")?))
+        .map_err(|e| format!("invalid pubkey: {}", e))?;
+    let withdrawal_credentials =
+        Hash256::from_str(&with_prefix(required("withdrawal_credentials")?))
+            .map_err(|e| format!("invalid withdrawal_credentials: {:?}", e))?;
+    let amount = required("amount")?
+        .parse()
+        .map_err(|e| format!("invalid amount: {:?}", e))?;
+    let signature = SignatureBytes::from_str(&with_prefix(required("signature")?))
+        .map_err(|e| format!("invalid signature: {}", e))?;
+    let fork_version = row
+        .get("fork_version")
+        .map(|value| {
+            hex::decode(value.trim_start_matches("0x"))
+                .map_err(|e| format!("{:?}", e))
+                .and_then(|bytes| {
+                    <[u8; 4]>::try_from(bytes).map_err(|_| "expected 4 bytes".to_string())
+                })
+                .map_err(|e| format!("invalid fork_version: {}", e))
+        })
+        .transpose()?;
+
+    Ok(DepositEntry {
+        deposit_data: DepositData {
+            pubkey,
+            withdrawal_credentials,
+            amount,
+            signature,
+        },
+        fork_version,
+        deposit_message_root: parse_root("deposit_message_root")?,
+        deposit_data_root: parse_root("deposit_data_root")?,
+    })
+}
@@ -12,18 +12,22 @@ use eth2_network_config::Eth2NetworkConfig;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::{future, StreamExt};
 
-use logging::{test_logger, SSELoggingComponents};
+use logging::{test_logger, RuntimeLogLevel, SSELoggingComponents};
+use rotating_file::RotatingFile;
 use serde::{Deserialize, Serialize};
 use slog::{error, info, o, warn, Drain, Duplicate, Level, Logger};
 use sloggers::{file::FileLoggerBuilder, types::Format, types::Severity, Build};
 use std::fs::create_dir_all;
-use std::io::{Result as IOResult, Write};
+use std::io::{LineWriter, Result as IOResult, Write};
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use task_executor::{ShutdownReason, TaskExecutor};
 use tokio::runtime::{Builder as RuntimeBuilder, Runtime};
+use tracing_appender::non_blocking::{NonBlockingBuilder, WorkerGuard};
 use types::{EthSpec, GnosisEthSpec, MainnetEthSpec, MinimalEthSpec};
 
+mod rotating_file;
+
 #[cfg(target_family = "unix")]
 use {
     futures::Future,
@@ -36,6 +40,8 @@ use {futures::channel::oneshot, std::cell::RefCell};
 
 const LOG_CHANNEL_SIZE: usize = 16384;
 const SSE_LOG_CHANNEL_SIZE: usize = 2048;
+/// The number of lines buffered by the non-blocking file logger before records are dropped.
+const NON_BLOCKING_LOG_QUEUE_SIZE: usize = 65_536;
 /// The maximum time in seconds the client will wait for all internal tasks to shutdown.
 const MAXIMUM_SHUTDOWN_TIME: u64 = 15;
 
@@ -58,6 +64,10 @@ pub struct LoggerConfig {
     pub compression: bool,
     pub is_restricted: bool,
     pub sse_logging: bool,
+    /// Write the log file from a bounded queue, dropping records rather than blocking when the
+    /// disk can't keep up.
+    #[serde(default)]
+    pub non_blocking: bool,
 }
 impl Default for LoggerConfig {
     fn default() -> Self {
@@ -74,6 +84,7 @@ impl Default for LoggerConfig {
             compression: false,
             is_restricted: true,
             sse_logging: false,
+            non_blocking: false,
         }
     }
 }
@@ -89,6 +100,8 @@ pub struct RuntimeContext<E: EthSpec> {
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
     pub sse_logging_components: Option<SSELoggingComponents>,
+    /// The level of the terminal logs, which can be changed at runtime.
+    pub log_level: Option<RuntimeLogLevel>,
 }
 
 impl<E: EthSpec> RuntimeContext<E> {
@@ -102,6 +115,7 @@ impl<E: EthSpec> RuntimeContext<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_level: self.log_level.clone(),
         }
     }
 
@@ -121,6 +135,8 @@ pub struct EnvironmentBuilder<E: EthSpec> {
     runtime: Option<Arc<Runtime>>,
     log: Option<Logger>,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_level: Option<RuntimeLogLevel>,
+    log_guard: Option<WorkerGuard>,
     eth_spec_instance: E,
     eth2_config: Eth2Config,
     eth2_network_config: Option<Eth2NetworkConfig>,
@@ -133,6 +149,8 @@ impl EnvironmentBuilder<MinimalEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_level: None,
+            log_guard: None,
             eth_spec_instance: MinimalEthSpec,
             eth2_config: Eth2Config::minimal(),
             eth2_network_config: None,
@@ -147,6 +165,8 @@ impl EnvironmentBuilder<MainnetEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_level: None,
+            log_guard: None,
             eth_spec_instance: MainnetEthSpec,
             eth2_config: Eth2Config::mainnet(),
             eth2_network_config: None,
@@ -161,6 +181,8 @@ impl EnvironmentBuilder<GnosisEthSpec> {
             runtime: None,
             log: None,
             sse_logging_components: None,
+            log_level: None,
+            log_guard: None,
             eth_spec_instance: GnosisEthSpec,
             eth2_config: Eth2Config::gnosis(),
             eth2_network_config: None,
@@ -233,15 +255,18 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
                 .build()
         };
 
-        let stdout_drain = match config.debug_level.as_str() {
-            "info" => stdout_drain.filter_level(Level::Info),
-            "debug" => stdout_drain.filter_level(Level::Debug),
-            "trace" => stdout_drain.filter_level(Level::Trace),
-            "warn" => stdout_drain.filter_level(Level::Warning),
-            "error" => stdout_drain.filter_level(Level::Error),
-            "crit" => stdout_drain.filter_level(Level::Critical),
+        let stdout_level = match config.debug_level.as_str() {
+            "info" => Level::Info,
+            "debug" => Level::Debug,
+            "trace" => Level::Trace,
+            "warn" => Level::Warning,
+            "error" => Level::Error,
+            "crit" => Level::Critical,
             unknown => return Err(format!("Unknown debug-level: {}", unknown)),
         };
+        let log_level = RuntimeLogLevel::new(stdout_level);
+        let stdout_drain = log_level.filter(stdout_drain);
+        self.log_level = Some(log_level);
 
         let stdout_logger = Logger::root(stdout_drain.fuse(), o!());
 
@@ -252,7 +277,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         }
 
         // Disable file logging if no path is specified.
-        let Some(path) = config.path else {
+        let Some(path) = config.path.clone() else {
             self.log = Some(stdout_logger);
             return Ok(self);
         };
@@ -292,19 +317,26 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             unknown => return Err(format!("Unknown loglevel-debug-level: {}", unknown)),
         };
 
-        let file_logger = FileLoggerBuilder::new(&path)
-            .level(logfile_level)
-            .channel_size(LOG_CHANNEL_SIZE)
-            .format(match config.logfile_format.as_deref() {
-                Some("JSON") => Format::Json,
-                _ => Format::default(),
-            })
-            .rotate_size(config.max_log_size)
-            .rotate_keep(config.max_log_number)
-            .rotate_compress(config.compression)
-            .restrict_permissions(config.is_restricted)
-            .build()
-            .map_err(|e| format!("Unable to build file logger: {}", e))?;
+        let file_logger = if config.non_blocking {
+            let (file_logger, guard) =
+                Self::non_blocking_file_logger(&config, path.clone(), logfile_level)?;
+            self.log_guard = Some(guard);
+            file_logger
+        } else {
+            FileLoggerBuilder::new(&path)
+                .level(logfile_level)
+                .channel_size(LOG_CHANNEL_SIZE)
+                .format(match config.logfile_format.as_deref() {
+                    Some("JSON") => Format::Json,
+                    _ => Format::default(),
+                })
+                .rotate_size(config.max_log_size)
+                .rotate_keep(config.max_log_number)
+                .rotate_compress(config.compression)
+                .restrict_permissions(config.is_restricted)
+                .build()
+                .map_err(|e| format!("Unable to build file logger: {}", e))?
+        };
 
         let mut log = Logger::root(Duplicate::new(stdout_logger, file_logger).fuse(), o!());
 
@@ -327,6 +359,45 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
         Ok(self)
     }
 
+    /// Builds a logger which writes to the file at `path` from a bounded queue on a background
+    /// thread.
+    ///
+    /// The file is rotated, compressed and permissioned as by the blocking file logger. Records are
+    /// dropped rather than blocking the caller if the queue is full. The returned guard flushes the
+    /// queue when dropped.
+    fn non_blocking_file_logger(
+        config: &LoggerConfig,
+        path: PathBuf,
+        level: Severity,
+    ) -> Result<(Logger, WorkerGuard), String> {
+        let file = RotatingFile::new(
+            path,
+            config.max_log_size,
+            config.max_log_number,
+            config.compression,
+            config.is_restricted,
+        )
+        .map_err(|e| format!("Unable to build file logger: {}", e))?;
+        let (writer, guard) = NonBlockingBuilder::default()
+            .buffered_lines_limit(NON_BLOCKING_LOG_QUEUE_SIZE)
+            .lossy(true)
+            .finish(file);
+        // Buffer each record so that it's sent to the queue as a single line.
+        let writer = LineWriter::new(writer);
+
+        let level = level.as_level();
+        let logger = if config.logfile_format.as_deref() == Some("JSON") {
+            let drain = slog_json::Json::default(writer).fuse();
+            Logger::root(Mutex::new(drain).filter_level(level).fuse(), o!())
+        } else {
+            let decorator = slog_term::PlainSyncDecorator::new(writer);
+            let drain = slog_term::FullFormat::new(decorator).build().fuse();
+            Logger::root(drain.filter_level(level).fuse(), o!())
+        };
+
+        Ok((logger, guard))
+    }
+
     /// Adds a network configuration to the environment.
     pub fn eth2_network_config(
         mut self,
@@ -353,6 +424,8 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             exit,
             log: self.log.ok_or("Cannot build environment without log")?,
             sse_logging_components: self.sse_logging_components,
+            log_level: self.log_level,
+            _log_guard: self.log_guard,
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
@@ -372,6 +445,9 @@ pub struct Environment<E: EthSpec> {
     exit: async_channel::Receiver<()>,
     log: Logger,
     sse_logging_components: Option<SSELoggingComponents>,
+    log_level: Option<RuntimeLogLevel>,
+    /// Flushes the non-blocking file logger, if any, when dropped.
+    _log_guard: Option<WorkerGuard>,
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
@@ -399,6 +475,7 @@ impl<E: EthSpec> Environment<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_level: self.log_level.clone(),
         }
     }
 
@@ -415,6 +492,7 @@ impl<E: EthSpec> Environment<E> {
             eth2_config: self.eth2_config.clone(),
             eth2_network_config: self.eth2_network_config.clone(),
             sse_logging_components: self.sse_logging_components.clone(),
+            log_level: self.log_level.clone(),
         }
     }
 
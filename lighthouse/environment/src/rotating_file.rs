@@ -0,0 +1,154 @@
+//! A log file writer which rotates the file by size, for use behind the non-blocking file logger.
+//!
+//! Rotated files are named and compressed in the same way as by the `sloggers` file logger used for
+//! blocking file logging, so that the choice of logger doesn't change the log files on disk:
+//! `<path>.1` is the most recently rotated file, or `<path>.1.gz` if compression is enabled.
+use flate2::{write::GzEncoder, Compression};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::thread::{self, JoinHandle};
+
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    /// The number of bytes in the current file.
+    size: u64,
+    max_size: u64,
+    max_files: usize,
+    compress: bool,
+    restricted: bool,
+    /// The compression of the most recently rotated file, if any.
+    compression: Option<JoinHandle<()>>,
+}
+
+impl RotatingFile {
+    /// Opens the file at `path` for appending, creating it if necessary.
+    ///
+    /// If `restricted` is set the file is only readable and writable by its owner.
+    pub fn new(
+        path: PathBuf,
+        max_size: u64,
+        max_files: usize,
+        compress: bool,
+        restricted: bool,
+    ) -> io::Result<Self> {
+        let file = open(&path, restricted)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_size,
+            max_files,
+            compress,
+            restricted,
+            compression: None,
+        })
+    }
+
+    fn rotated_path(&self, i: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{}", i));
+        if self.compress {
+            path.push(".gz");
+        }
+        path.into()
+    }
+
+    /// Moves the current file to `<path>.1`, shifting the previously rotated files along and
+    /// deleting the oldest, then opens a new file at `path`.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        // Wait for the previous compression to finish, as it reads from `<path>.1`.
+        if let Some(compression) = self.compression.take() {
+            let _ = compression.join();
+        }
+
+        let oldest = self.rotated_path(self.max_files);
+        if oldest.exists() {
+            fs::remove_file(oldest)?;
+        }
+        for i in (1..self.max_files).rev() {
+            let from = self.rotated_path(i);
+            if from.exists() {
+                fs::rename(from, self.rotated_path(i + 1))?;
+            }
+        }
+
+        if self.compress {
+            // Compress on a separate thread so that the queue of records keeps draining.
+            let mut plain_path = self.path.clone().into_os_string();
+            plain_path.push(".1");
+            let plain_path = PathBuf::from(plain_path);
+            fs::rename(&self.path, &plain_path)?;
+            let compressed_path = self.rotated_path(1);
+            let restricted = self.restricted;
+            self.compression = Some(
+                thread::Builder::new()
+                    .name("log-compression".into())
+                    .spawn(move || {
+                        if let Err(e) = compress(&plain_path, &compressed_path, restricted) {
+                            eprintln!("Unable to compress log file {:?}: {}", plain_path, e);
+                        }
+                    })?,
+            );
+        } else {
+            fs::rename(&self.path, self.rotated_path(1))?;
+        }
+
+        self.file = open(&self.path, self.restricted)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+fn open(path: &Path, restricted: bool) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    if restricted {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let file = options.open(path)?;
+
+    // The mode only applies to new files, so also restrict a pre-existing file.
+    if restricted {
+        filesystem::restrict_file_permissions(path)
+            .map_err(|e| io::Error::other(format!("{:?}", e)))?;
+    }
+    Ok(file)
+}
+
+fn compress(plain_path: &Path, compressed_path: &Path, restricted: bool) -> io::Result<()> {
+    let mut temp_path = compressed_path.to_path_buf().into_os_string();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    if temp_path.exists() {
+        fs::remove_file(&temp_path)?;
+    }
+
+    let mut encoder = GzEncoder::new(open(&temp_path, restricted)?, Compression::default());
+    io::copy(&mut BufReader::new(File::open(plain_path)?), &mut encoder)?;
+    encoder.finish()?.sync_all()?;
+
+    fs::rename(temp_path, compressed_path)?;
+    fs::remove_file(plain_path)
+}
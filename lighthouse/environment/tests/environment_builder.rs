@@ -49,3 +49,89 @@ mod setup_eth2_config {
         }
     }
 }
+
+mod non_blocking_file_logger {
+    use super::*;
+    use environment::{Environment, LoggerConfig};
+    use slog::info;
+    use std::fs;
+    use std::path::Path;
+    use std::thread::sleep;
+    use std::time::{Duration, Instant};
+    use tempfile::tempdir;
+
+    fn environment(path: &Path, compression: bool) -> Environment<MainnetEthSpec> {
+        EnvironmentBuilder::mainnet()
+            .multi_threaded_tokio_runtime()
+            .expect("should set runtime")
+            .initialize_logger(LoggerConfig {
+                path: Some(path.to_path_buf()),
+                debug_level: "crit".into(),
+                max_log_size: 4096,
+                max_log_number: 2,
+                compression,
+                non_blocking: true,
+                ..LoggerConfig::default()
+            })
+            .expect("should set logger")
+            .build()
+            .expect("should build environment")
+    }
+
+    fn log_records(environment: Environment<MainnetEthSpec>) {
+        for index in 0..200 {
+            info!(environment.log(), "Test record"; "index" => index);
+        }
+        // Dropping the environment flushes the queue.
+        drop(environment);
+    }
+
+    #[test]
+    fn rotates_log_file_by_size() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        log_records(environment(&path, false));
+
+        let log = fs::read_to_string(&path).unwrap();
+        assert!(log.contains("index: 199"));
+        for rotated in ["beacon.log", "beacon.log.1", "beacon.log.2"] {
+            let size = fs::metadata(dir.path().join(rotated)).unwrap().len();
+            assert!(size > 0 && size <= 4096, "{rotated} has size {size}");
+        }
+        assert!(!dir.path().join("beacon.log.3").exists());
+    }
+
+    #[test]
+    fn compresses_rotated_log_files() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        log_records(environment(&path, true));
+
+        // Rotated files are compressed in the background.
+        let compressed = dir.path().join("beacon.log.1.gz");
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !compressed.exists() && Instant::now() < deadline {
+            sleep(Duration::from_millis(50));
+        }
+        assert!(compressed.exists());
+        assert!(fs::read_to_string(&path).unwrap().contains("index: 199"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn restricts_log_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("beacon.log");
+        log_records(environment(&path, false));
+
+        for rotated in ["beacon.log", "beacon.log.1"] {
+            let mode = fs::metadata(dir.path().join(rotated))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600, "{rotated} has mode {mode:o}");
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use crate::slasher_manager::SlasherManager;
 use clap::Parser;
 use database_manager::cli::DatabaseManager;
 use serde::{Deserialize, Serialize};
@@ -6,4 +7,6 @@ use serde::{Deserialize, Serialize};
 pub enum LighthouseSubcommands {
     #[clap(name = "database_manager")]
     DatabaseManager(DatabaseManager),
+    #[clap(name = "slasher")]
+    SlasherManager(SlasherManager),
 }
@@ -1,5 +1,6 @@
 mod cli;
 mod metrics;
+mod slasher_manager;
 
 use account_utils::STDIN_INPUTS_FLAG;
 use beacon_node::ProductionBeaconNode;
@@ -198,6 +199,19 @@ fn main() {
                 .global(true)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("logfile-non-blocking")
+                .long("logfile-non-blocking")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help(
+                    "If present, log records are written to the log file from a bounded queue on \
+                    a background thread, and are dropped rather than blocking when the disk can't \
+                    keep up. Log files are rotated, compressed and permissioned as without this \
+                    flag.")
+                .global(true)
+                .display_order(0)
+        )
         .arg(
             Arg::new("log-format")
                 .long("log-format")
@@ -524,6 +538,8 @@ fn run<E: EthSpec>(
 
     let logfile_restricted = !matches.get_flag("logfile-no-restricted-perms");
 
+    let logfile_non_blocking = matches.get_flag("logfile-non-blocking");
+
     // Construct the path to the log file.
     let mut log_path: Option<PathBuf> = clap_utils::parse_optional(matches, "logfile")?;
     if log_path.is_none() {
@@ -576,6 +592,7 @@ fn run<E: EthSpec>(
         compression: logfile_compress,
         is_restricted: logfile_restricted,
         sse_logging,
+        non_blocking: logfile_non_blocking,
     };
 
     let builder = environment_builder.initialize_logger(logger_config.clone())?;
@@ -608,14 +625,24 @@ fn run<E: EthSpec>(
     // Allow Prometheus access to the version and commit of the Lighthouse build.
     metrics::expose_lighthouse_version();
 
-    #[cfg(all(feature = "modern", target_arch = "x86_64"))]
-    if !std::is_x86_feature_detected!("adx") {
-        slog::warn!(
-            log,
-            "CPU seems incompatible with optimized Lighthouse build";
-            "advice" => "If you get a SIGILL, please try Lighthouse portable build"
-        );
-    }
+    // Check the BLS backend before any signatures are verified, refusing to start rather than
+    // crashing with a SIGILL if this build is incompatible with the CPU. Only the beacon node
+    // verifies signatures in bulk, so the other subcommands don't limit the batch size.
+    let bls_batch_size = match matches.subcommand() {
+        Some(("beacon_node", bn_matches)) => {
+            clap_utils::parse_required(bn_matches, "bls-batch-size")?
+        }
+        _ => 0,
+    };
+    let bls_backend = bls::backend::init(bls_batch_size)
+        .map_err(|e| format!("Unable to start with this BLS backend: {}", e))?;
+    info!(
+        log,
+        "BLS backend";
+        "backend" => %bls_backend,
+        "build" => %bls::backend::BuildVariant::compiled(),
+        "batch_size" => bls_batch_size,
+    );
 
     // Warn for DEPRECATED global flags. This code should be removed when we finish deleting these
     // flags.
@@ -677,6 +704,14 @@ fn run<E: EthSpec>(
         return Ok(());
     };
 
+    if let Ok(LighthouseSubcommands::SlasherManager(slasher_manager_config)) =
+        LighthouseSubcommands::from_arg_matches(matches)
+    {
+        info!(log, "Running slasher manager for {} network", network_name);
+        slasher_manager::run(matches, &slasher_manager_config, environment)?;
+        return Ok(());
+    };
+
     info!(log, "Lighthouse started"; "version" => VERSION);
     info!(
         log,
@@ -713,7 +748,10 @@ fn run<E: EthSpec>(
 
             let path = tracing_log_path.clone().unwrap();
 
-            logging::create_tracing_layer(path);
+            // The telemetry exporter spawns its background task on the runtime.
+            let runtime_handle = executor.handle();
+            let _runtime_guard = runtime_handle.as_ref().map(|handle| handle.enter());
+            logging::create_tracing_layer(path, config.telemetry.as_ref());
 
             executor.clone().spawn(
                 async move {
@@ -776,6 +814,8 @@ fn run<E: EthSpec>(
     // Shutdown the environment once all tasks have completed.
     environment.shutdown_on_idle();
 
+    logging::shutdown_telemetry();
+
     match shutdown_reason {
         ShutdownReason::Success(_) => Ok(()),
         ShutdownReason::Failure(msg) => Err(msg.to_string()),
@@ -0,0 +1,284 @@
+use beacon_node::get_data_dir;
+use clap::{ArgMatches, Parser};
+use clap_utils::{get_color_style, FLAG_HEADER};
+use environment::Environment;
+use eth2::types::StateId;
+use eth2::{BeaconNodeHttpClient, Timeouts};
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slasher::{DatabaseBackend, SignatureVerifier};
+use slog::info;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use types::{ChainSpec, EthSpec};
+
+/// Timeout for fetching the validator set, which may be large.
+const HTTP_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    name = "slasher",
+    about = "Manage a slasher database.",
+    styles = get_color_style(),
+    next_line_help = true,
+    term_width = 80,
+    disable_help_flag = true,
+    disable_help_subcommand = true,
+    display_order = 0,
+)]
+pub struct SlasherManager {
+    #[clap(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Set the slasher's database directory. Defaults to the `slasher_db` directory \
+                of the beacon node's data directory.",
+        display_order = 0
+    )]
+    pub slasher_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        global = true,
+        value_name = "GIGABYTES",
+        help = "Maximum size of the MDBX or LMDB database used by the slasher.",
+        display_order = 0
+    )]
+    pub slasher_max_db_size: Option<usize>,
+
+    #[clap(
+        long,
+        global = true,
+        help = "Prints help information",
+        action = clap::ArgAction::HelpLong,
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    help: Option<bool>,
+
+    #[clap(subcommand)]
+    pub subcommand: SlasherManagerSubcommand,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum SlasherManagerSubcommand {
+    MigrateBackend(MigrateBackend),
+    DeleteBackup(DeleteBackup),
+    Ingest(Ingest),
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Copy the slasher database to a different backend without re-ingesting its \
+             history. The beacon node must be stopped while the migration runs, online \
+             migration is not supported. The copy is verified against the original, which is \
+             kept in a backup directory until it is deleted with `delete-backup`.",
+    alias = "migrate_backend"
+)]
+pub struct MigrateBackend {
+    #[clap(
+        long,
+        value_name = "BACKEND",
+        help = "The backend of the existing slasher database.",
+        display_order = 0
+    )]
+    pub from: DatabaseBackend,
+
+    #[clap(
+        long,
+        value_name = "BACKEND",
+        help = "The backend to migrate the slasher database to. Start the beacon node with \
+                `--slasher-backend` set to this value once the migration has completed.",
+        display_order = 0
+    )]
+    pub to: DatabaseBackend,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Delete the backup of a slasher database left by `migrate-backend`. Only do so once \
+             the slasher has run successfully with the new backend.",
+    alias = "delete_backup"
+)]
+pub struct DeleteBackup {
+    #[clap(
+        long,
+        value_name = "BACKEND",
+        help = "The backend which the slasher database was migrated from.",
+        display_order = 0
+    )]
+    pub backend: DatabaseBackend,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Search attestations and blocks from an external source, such as an archive, for \
+             slashable offences. Offences are reported even if they can no longer be included \
+             in a block, and nothing is broadcast. A new database is created in a temporary \
+             directory, so `--slasher-dir` is ignored. Messages are discarded if their \
+             signatures can't be verified against the validators of the beacon node."
+)]
+pub struct Ingest {
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "JSON file containing the `attestations` (as indexed attestations) and \
+                `block_headers` (as signed block headers) to search. The file is read \
+                incrementally, so it may be larger than the available memory.",
+        display_order = 0
+    )]
+    pub input: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File to write the JSON report of slashable offences to. Defaults to stdout.",
+        display_order = 0
+    )]
+    pub output: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory in which to create the temporary database. Defaults to the system's \
+                temporary directory.",
+        display_order = 0
+    )]
+    pub scratch_dir: Option<PathBuf>,
+
+    #[clap(
+        long,
+        value_name = "EPOCHS",
+        help = "Only detect offences between messages fewer than this many epochs apart. \
+                Defaults to the slasher's default history length.",
+        display_order = 0
+    )]
+    pub history_length: Option<usize>,
+
+    #[clap(
+        long,
+        value_name = "NETWORK_ADDRESS",
+        default_value = "http://localhost:5052",
+        help = "Beacon node to fetch the genesis validators root and the public keys of the \
+                validators from, to verify the signatures of the input.",
+        display_order = 0
+    )]
+    pub beacon_node: String,
+
+    #[clap(
+        long,
+        help = "Don't verify the signatures of the input. Offences may then be reported \
+                which were never committed.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub skip_signature_verification: bool,
+}
+
+/// Fetch the genesis validators root and the public keys of all validators from `beacon_node`.
+async fn signature_verifier(
+    beacon_node: &str,
+    spec: Arc<ChainSpec>,
+) -> Result<SignatureVerifier, String> {
+    let url = SensitiveUrl::parse(beacon_node)
+        .map_err(|e| format!("Invalid beacon node URL: {:?}", e))?;
+    let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(HTTP_TIMEOUT));
+    let genesis_validators_root = client
+        .get_beacon_genesis()
+        .await
+        .map_err(|e| format!("Unable to fetch genesis: {:?}", e))?
+        .data
+        .genesis_validators_root;
+    let mut validators = client
+        .get_beacon_states_validators(StateId::Head, None, None)
+        .await
+        .map_err(|e| format!("Unable to fetch validators: {:?}", e))?
+        .ok_or("Head state not found")?
+        .data;
+    validators.sort_unstable_by_key(|validator| validator.index);
+    let pubkeys = validators
+        .into_iter()
+        .map(|validator| validator.validator.pubkey)
+        .collect();
+    Ok(SignatureVerifier::new(
+        pubkeys,
+        genesis_validators_root,
+        spec,
+    ))
+}
+
+pub fn run<E: EthSpec>(
+    cli_args: &ArgMatches,
+    slasher_manager_config: &SlasherManager,
+    env: Environment<E>,
+) -> Result<(), String> {
+    let context = env.core_context();
+    let log = context.log().clone();
+    let slasher_dir = slasher_manager_config
+        .slasher_dir
+        .clone()
+        .unwrap_or_else(|| get_data_dir(cli_args).join("slasher_db"));
+    let mut config = slasher::Config::new(slasher_dir);
+    if let Some(max_db_size_gbs) = slasher_manager_config.slasher_max_db_size {
+        config.max_db_size_mbs = max_db_size_gbs * 1024;
+    }
+
+    match &slasher_manager_config.subcommand {
+        SlasherManagerSubcommand::MigrateBackend(migrate_config) => {
+            slasher::migrate_backend(&config, migrate_config.from, migrate_config.to, &log)
+                .map_err(|e| format!("Fatal error: {:?}", e))
+        }
+        SlasherManagerSubcommand::DeleteBackup(delete_config) => {
+            slasher::delete_backup(&config, delete_config.backend, &log)
+                .map_err(|e| format!("Fatal error: {:?}", e))
+        }
+        SlasherManagerSubcommand::Ingest(ingest_config) => {
+            let input_file = File::open(&ingest_config.input)
+                .map_err(|e| format!("Unable to open {:?}: {:?}", ingest_config.input, e))?;
+            let input = slasher::read_offline_input::<E, _>(BufReader::new(input_file));
+
+            let spec = context.eth2_config.spec.clone();
+            let verifier = if ingest_config.skip_signature_verification {
+                None
+            } else {
+                Some(
+                    env.runtime()
+                        .block_on(signature_verifier(&ingest_config.beacon_node, spec.clone()))?,
+                )
+            };
+
+            let scratch_dir = match &ingest_config.scratch_dir {
+                Some(scratch_dir) => tempfile::tempdir_in(scratch_dir),
+                None => tempfile::tempdir(),
+            }
+            .map_err(|e| format!("Unable to create temporary directory: {:?}", e))?;
+            config.database_path = scratch_dir.path().into();
+            if let Some(history_length) = ingest_config.history_length {
+                config.history_length = history_length;
+            }
+
+            let report = slasher::detect_offline(config, spec, input, verifier, log.clone())
+                .map_err(|e| format!("Fatal error: {:?}", e))?;
+
+            match &ingest_config.output {
+                Some(output) => {
+                    let output_file = File::create(output)
+                        .map_err(|e| format!("Unable to create {:?}: {:?}", output, e))?;
+                    serde_json::to_writer_pretty(output_file, &report)
+                        .map_err(|e| format!("Unable to write report: {:?}", e))?;
+                    info!(log, "Wrote slashing report"; "path" => ?output);
+                }
+                None => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&report)
+                        .map_err(|e| format!("Unable to serialize report: {:?}", e))?
+                ),
+            }
+            Ok(())
+        }
+    }
+}
@@ -399,6 +399,88 @@ fn validator_create() {
     assert_eq!(dir_validator_count(validator_dir.path()), 6);
 }
 
+#[test]
+fn validator_create_shards() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let shards_dir = tempdir().unwrap();
+    let deposit_data_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let output = output_result(
+        validator_cmd()
+            .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+            .arg(validator_dir.path().as_os_str())
+            .arg(CREATE_CMD)
+            .arg(format!("--{}", WALLETS_DIR_FLAG))
+            .arg(wallet.base_dir().into_os_string())
+            .arg(format!("--{}", WALLET_NAME_FLAG))
+            .arg(&wallet.name)
+            .arg(format!("--{}", WALLET_PASSWORD_FLAG))
+            .arg(wallet.password_path().into_os_string())
+            .arg(format!("--{}", COUNT_FLAG))
+            .arg("5")
+            .arg(format!("--{}", SHARDS_FLAG))
+            .arg("2")
+            .arg(format!("--{}", SHARDS_DIR_FLAG))
+            .arg(shards_dir.path().as_os_str())
+            .arg(format!("--{}", DEPOSIT_DATA_DIR_FLAG))
+            .arg(deposit_data_dir.path().as_os_str())
+            .arg(format!("--{}", DEPOSIT_BATCH_SIZE_FLAG))
+            .arg("2"),
+    )
+    .unwrap();
+    assert_eq!(from_utf8(&output.stdout).unwrap().lines().count(), 5);
+
+    // The default validator dir is untouched.
+    assert_eq!(dir_child_count(validator_dir.path()), 0);
+
+    // Consecutive validators are split between the shards, each with their own secrets and
+    // slashing protection.
+    for (shard, expected_count) in [(0, 3), (1, 2)] {
+        let shard_dir = shards_dir.path().join(format!("shard-{}", shard));
+        let shard_validator_dir = shard_dir.join("validators");
+        let shard_secrets_dir = shard_dir.join("secrets");
+        assert_eq!(dir_validator_count(&shard_validator_dir), expected_count);
+
+        let slashing_db =
+            SlashingDatabase::open(&shard_validator_dir.join(SLASHING_PROTECTION_FILENAME))
+                .unwrap();
+        for entry in fs::read_dir(&shard_validator_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.is_dir() {
+                continue;
+            }
+            let dir = ValidatorDir::open(&path).unwrap();
+            let keypair = dir.voting_keypair(&shard_secrets_dir).unwrap();
+            slashing_db
+                .get_validator_id(&keypair.pk.compress())
+                .unwrap();
+        }
+    }
+
+    // Deposits are batched per shard, in both JSON and CSV.
+    for (file_stem, expected_count) in [
+        ("deposit_data-shard-0-0", 2),
+        ("deposit_data-shard-0-1", 1),
+        ("deposit_data-shard-1-0", 2),
+    ] {
+        let json: Vec<serde_json::Value> = serde_json::from_reader(
+            File::open(deposit_data_dir.path().join(format!("{}.json", file_stem))).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(json.len(), expected_count);
+        assert_eq!(json[0]["network_name"], "mainnet");
+
+        let csv =
+            fs::read_to_string(deposit_data_dir.path().join(format!("{}.csv", file_stem))).unwrap();
+        assert_eq!(csv.lines().count(), expected_count + 1);
+    }
+    assert_eq!(dir_child_count(deposit_data_dir.path()), 6);
+}
+
 #[test]
 fn validator_import_launchpad() {
     const PASSWORD: &str = "cats";
@@ -494,11 +576,14 @@ fn validator_import_launchpad() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        beacon_nodes: None,
+        attestation_timing: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -618,11 +703,14 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        beacon_nodes: None,
+        attestation_timing: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: None,
+            voting_keystore_password_source: None,
         },
     };
 
@@ -646,11 +734,14 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        beacon_nodes: None,
+        attestation_timing: None,
         voting_public_key: keystore.public_key().unwrap(),
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path: dst_keystore_dir.join(KEYSTORE_NAME),
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
@@ -750,10 +841,13 @@ fn validator_import_launchpad_password_file() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        beacon_nodes: None,
+        attestation_timing: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
             voting_keystore_password: Some(ZeroizeString::from(PASSWORD.to_string())),
+            voting_keystore_password_source: None,
         },
     };
 
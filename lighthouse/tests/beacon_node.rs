@@ -8,7 +8,7 @@ use beacon_node::beacon_chain::chain_config::{
 use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
 use beacon_processor::BeaconProcessorConfig;
 use eth1::Eth1Endpoint;
-use lighthouse_network::PeerId;
+use lighthouse_network::{Multiaddr, PeerId};
 use lighthouse_version;
 use std::fs::File;
 use std::io::{Read, Write};
@@ -213,6 +213,71 @@ fn fork_choice_before_proposal_timeout_zero() {
         .with_config(|config| assert_eq!(config.chain.fork_choice_before_proposal_timeout_ms, 0));
 }
 
+#[test]
+fn bls_batch_size_flag() {
+    CommandLineTest::new()
+        .flag("bls-batch-size", Some("64"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn block_production_budgets_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.block_production_budget,
+                beacon_node::beacon_chain::chain_config::BlockProductionBudget::default()
+            )
+        });
+}
+
+#[test]
+fn block_production_budgets_flag() {
+    CommandLineTest::new()
+        .flag(
+            "block-production-budgets",
+            Some("attestation_packing=500,state_root=250"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let budget = &config.chain.block_production_budget;
+            assert_eq!(budget.attestation_packing, Some(Duration::from_millis(500)));
+            assert_eq!(budget.state_root, Some(Duration::from_millis(250)));
+            assert_eq!(budget.payload_fetch, None);
+        });
+}
+
+#[test]
+fn shuffling_cache_warming_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.shuffling_cache_warming));
+}
+
+#[test]
+fn disable_shuffling_cache_warming_flag() {
+    CommandLineTest::new()
+        .flag("disable-shuffling-cache-warming", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.shuffling_cache_warming));
+}
+
+#[test]
+fn early_attester_cache_pending_blobs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.early_attester_cache_pending_blobs));
+}
+
+#[test]
+fn early_attester_cache_pending_blobs_flag() {
+    CommandLineTest::new()
+        .flag("early-attester-cache-pending-blobs", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.early_attester_cache_pending_blobs));
+}
+
 #[test]
 fn checkpoint_sync_url_timeout_flag() {
     CommandLineTest::new()
@@ -282,6 +347,32 @@ fn always_prepare_payload_override() {
         .with_config(|config| assert!(config.chain.always_prepare_payload));
 }
 
+#[test]
+fn suppress_payload_attributes_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.chain.suppress_payload_attributes);
+            assert_eq!(config.chain.payload_attributes_window_epochs, None);
+        });
+}
+
+#[test]
+fn suppress_payload_attributes_flag() {
+    CommandLineTest::new()
+        .flag("suppress-payload-attributes", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.suppress_payload_attributes));
+}
+
+#[test]
+fn payload_attributes_window_flag() {
+    CommandLineTest::new()
+        .flag("payload-attributes-window", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.payload_attributes_window_epochs, Some(4)));
+}
+
 #[test]
 fn paranoid_block_proposal_default() {
     CommandLineTest::new()
@@ -357,6 +448,26 @@ fn default_graffiti() {
         });
 }
 
+#[test]
+fn graffiti_file_flag() {
+    CommandLineTest::new()
+        .flag("graffiti-file", Some("/tmp/graffiti.txt"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.graffiti_file,
+                Some(PathBuf::from("/tmp/graffiti.txt"))
+            )
+        });
+}
+
+#[test]
+fn graffiti_file_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.graffiti_file, None));
+}
+
 #[test]
 fn trusted_peers_flag() {
     let peers = vec![PeerId::random(), PeerId::random()];
@@ -378,6 +489,32 @@ fn trusted_peers_flag() {
         });
 }
 
+#[test]
+fn trusted_peers_flag_multiaddr() {
+    let peers = vec![PeerId::random(), PeerId::random()];
+    let multiaddr = format!("/ip4/127.0.0.1/tcp/9000/p2p/{}", peers[1]);
+    CommandLineTest::new()
+        .flag(
+            "trusted-peers",
+            Some(format!("{},{}", peers[0], multiaddr).as_str()),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                PeerId::from(config.network.trusted_peers[0].clone()),
+                peers[0]
+            );
+            assert_eq!(
+                PeerId::from(config.network.trusted_peers[1].clone()),
+                peers[1]
+            );
+            assert_eq!(
+                config.network.trusted_peers_multiaddr,
+                vec![multiaddr.parse::<Multiaddr>().unwrap()]
+            );
+        });
+}
+
 #[test]
 fn genesis_backfill_flag() {
     CommandLineTest::new()
@@ -514,6 +651,28 @@ fn run_execution_jwt_secret_key_is_persisted() {
         });
 }
 #[test]
+fn execution_jwt_next_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new_with_no_execution_endpoint()
+        .flag("execution-endpoint", Some("http://localhost:8551/"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag(
+            "execution-jwt-next",
+            dir.path().join("jwt-next-file").as_os_str().to_str(),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(
+                config.next_secret_file,
+                Some(dir.path().join("jwt-next-file"))
+            );
+        });
+}
+#[test]
 fn execution_timeout_multiplier_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
     CommandLineTest::new_with_no_execution_endpoint()
@@ -530,6 +689,85 @@ fn execution_timeout_multiplier_flag() {
         });
 }
 #[test]
+fn unsafe_light_mode_flag() {
+    CommandLineTest::new_with_no_execution_endpoint()
+        .flag("unsafe-light-mode", Some("http://localhost:5052/"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.execution_layer.is_none());
+            assert_eq!(
+                config
+                    .chain
+                    .light_mode_provider
+                    .as_ref()
+                    .map(|url| url.full.to_string()),
+                Some("http://localhost:5052/".to_string())
+            );
+            assert!(!config.store.prune_payloads);
+            assert!(!config.sync_eth1_chain);
+        });
+}
+#[test]
+fn redundant_execution_endpoints_flags() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new_with_no_execution_endpoint()
+        .flag("execution-endpoint", Some("http://localhost:8551/"))
+        .flag(
+            "execution-jwt",
+            dir.path().join("jwt-file").as_os_str().to_str(),
+        )
+        .flag(
+            "redundant-execution-endpoints",
+            Some("http://localhost:8552/,http://localhost:8553/"),
+        )
+        .flag("execution-payload-status-quorum", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            let urls = config
+                .redundant_execution_endpoints
+                .iter()
+                .map(|endpoint| endpoint.url.full.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(urls, ["http://localhost:8552/", "http://localhost:8553/"]);
+            assert!(config
+                .redundant_execution_endpoints
+                .iter()
+                .all(|endpoint| endpoint.secret_file.is_none()));
+            assert_eq!(
+                config.payload_status_policy,
+                execution_layer::PayloadStatusPolicy::Quorum(2)
+            );
+        });
+}
+#[test]
+fn record_engine_calls_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("record-engine-calls", dir.path().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert_eq!(
+                config.engine_call_recording_dir.as_deref(),
+                Some(dir.path())
+            );
+        });
+}
+#[test]
+fn execution_payload_status_policy_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            let config = config.execution_layer.as_ref().unwrap();
+            assert!(config.redundant_execution_endpoints.is_empty());
+            assert_eq!(
+                config.payload_status_policy,
+                execution_layer::PayloadStatusPolicy::Priority
+            );
+        });
+}
+#[test]
 fn bellatrix_execution_endpoints_flag() {
     run_bellatrix_execution_endpoints_flag_test("execution-endpoints")
 }
@@ -593,9 +831,7 @@ fn run_payload_builder_flag_test(flag: &str, builders: &str) {
         .collect();
     run_payload_builder_flag_test_with_config(flag, builders, None, None, |config| {
         let config = config.execution_layer.as_ref().unwrap();
-        // Only first provided endpoint is parsed as we don't support
-        // redundancy.
-        assert_eq!(config.builder_url, all_builders.get(0).cloned());
+        assert_eq!(config.builder_urls, all_builders);
     })
 }
 fn run_payload_builder_flag_test_with_config<F: Fn(&Config)>(
@@ -850,6 +1086,36 @@ fn network_subscribe_all_subnets_flag() {
         .with_config(|config| assert!(config.network.subscribe_all_subnets));
 }
 #[test]
+fn network_backbone_subnets_flags() {
+    CommandLineTest::new()
+        .flag("backbone-attestation-subnets", Some("0,17,63"))
+        .flag("backbone-sync-committee-subnets", Some("1"))
+        .flag("backbone-subnet-peers", Some("10"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.backbone_attestation_subnets, vec![0, 17, 63]);
+            assert_eq!(config.network.backbone_sync_committee_subnets, vec![1]);
+            assert_eq!(config.network.backbone_subnet_peers, 10);
+        });
+}
+#[test]
+fn network_backbone_subnets_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.backbone_attestation_subnets.is_empty());
+            assert!(config.network.backbone_sync_committee_subnets.is_empty());
+            assert_eq!(config.network.backbone_subnet_peers, 6);
+        });
+}
+#[test]
+#[should_panic]
+fn network_backbone_attestation_subnets_out_of_range() {
+    CommandLineTest::new()
+        .flag("backbone-attestation-subnets", Some("64"))
+        .run_with_zero_port();
+}
+#[test]
 fn network_import_all_attestations_flag() {
     CommandLineTest::new()
         .flag("import-all-attestations", None)
@@ -1779,6 +2045,54 @@ fn validator_monitor_pubkeys_flag() {
         });
 }
 #[test]
+fn own_key_guard_pubkeys_flag() {
+    CommandLineTest::new()
+        .flag("own-key-guard-pubkeys", Some("0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef,\
+                                            0xbeefdeadbeefdeaddeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.own_key_guard_pubkeys.len(), 2);
+            assert_eq!(config.chain.own_key_guard_pubkeys[0].to_string(), "0xdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeefdeadbeef");
+        });
+}
+#[test]
+fn own_key_guard_pubkeys_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.own_key_guard_pubkeys.is_empty()));
+}
+#[test]
+fn payload_forensics_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.payload_forensics_dir,
+                Some(config.data_dir().join("payload_forensics"))
+            )
+        });
+}
+#[test]
+fn payload_forensics_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("payload-forensics-dir", dir.path().as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.payload_forensics_dir,
+                Some(dir.path().to_path_buf())
+            )
+        });
+}
+#[test]
+fn disable_payload_forensics_flag() {
+    CommandLineTest::new()
+        .flag("disable-payload-forensics", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.payload_forensics_dir, None));
+}
+#[test]
 fn validator_monitor_file_flag() {
     let dir = TempDir::new().expect("Unable to create temporary directory");
     let mut file = File::create(dir.path().join("pubkeys.txt")).expect("Unable to create file");
@@ -1818,6 +2132,29 @@ fn validator_monitor_metrics_threshold_custom() {
             assert_eq!(config.validator_monitor.individual_tracking_threshold, 42)
         });
 }
+#[test]
+fn validator_monitor_bucket_size_flag() {
+    CommandLineTest::new()
+        .flag("validator-monitor-bucket-size", Some("128"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.validator_monitor.aggregate_bucket_size, Some(128))
+        });
+}
+#[test]
+#[should_panic]
+fn validator_monitor_bucket_size_flag_too_small() {
+    CommandLineTest::new()
+        .flag("validator-monitor-bucket-size", Some("1"))
+        .run_with_zero_port();
+}
+#[test]
+fn validator_monitor_client_fingerprinting_flag() {
+    CommandLineTest::new()
+        .flag("validator-monitor-client-fingerprinting", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.validator_monitor.client_fingerprinting));
+}
 
 // Tests for Store flags.
 // DEPRECATED but should still be accepted.
@@ -1909,6 +2246,45 @@ fn compact_db_flag() {
         .with_config(|config| assert!(config.store.compact_on_init));
 }
 #[test]
+fn archive_orphaned_blocks_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.archive_orphaned_blocks));
+}
+#[test]
+fn archive_orphaned_blocks_flag() {
+    CommandLineTest::new()
+        .flag("archive-orphaned-blocks", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.archive_orphaned_blocks));
+}
+#[test]
+fn orphan_archive_retention_epochs_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.orphan_archive_retention_epochs, 8192));
+}
+#[test]
+fn orphan_archive_retention_epochs_flag() {
+    CommandLineTest::new()
+        .flag("orphan-archive-retention-epochs", Some("64"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.orphan_archive_retention_epochs, 64));
+}
+#[test]
+fn repair_db_inconsistencies_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.repair_on_init));
+}
+#[test]
+fn repair_db_inconsistencies_flag() {
+    CommandLineTest::new()
+        .flag("repair-db-inconsistencies", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.repair_on_init));
+}
+#[test]
 fn prune_payloads_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -1961,6 +2337,42 @@ fn blob_prune_margin_epochs_on_startup_ten() {
         .with_config(|config| assert!(config.store.blob_prune_margin_epochs == 10));
 }
 #[test]
+fn column_size_estimate_interval_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.column_size_estimate_interval, None));
+}
+#[test]
+fn column_size_estimate_interval_flag() {
+    CommandLineTest::new()
+        .flag("column-size-estimate-interval", Some("60"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.column_size_estimate_interval,
+                Some(Duration::from_secs(3600))
+            )
+        });
+}
+#[test]
+fn head_state_pool_size_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.head_state_pool_size,
+                beacon_node::beacon_chain::head_state_pool::DEFAULT_HEAD_STATE_POOL_SIZE
+            )
+        });
+}
+#[test]
+fn head_state_pool_size_flag() {
+    CommandLineTest::new()
+        .flag("head-state-pool-size", Some("0"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.head_state_pool_size, 0));
+}
+#[test]
 fn reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .flag("reconstruct-historic-states", None)
@@ -1968,6 +2380,14 @@ fn reconstruct_historic_states_flag() {
         .with_config(|config| assert!(config.chain.reconstruct_historic_states));
 }
 #[test]
+fn import_era_dir_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("import-era-dir", dir.path().as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.import_era_dir.as_deref(), Some(dir.path())));
+}
+#[test]
 fn no_reconstruct_historic_states_flag() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -1992,6 +2412,34 @@ fn epochs_per_migration_override() {
         .with_config(|config| assert_eq!(config.chain.epochs_per_migration, 128));
 }
 #[test]
+fn fast_restart_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.chain.fast_restart);
+            assert_eq!(config.chain.fast_restart_interval_epochs, None);
+        });
+}
+#[test]
+fn fast_restart_flag() {
+    CommandLineTest::new()
+        .flag("fast-restart", None)
+        .flag("fast-restart-interval", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.chain.fast_restart);
+            assert_eq!(config.chain.fast_restart_interval_epochs, Some(8));
+        });
+}
+#[test]
+#[should_panic]
+fn fast_restart_interval_zero() {
+    CommandLineTest::new()
+        .flag("fast-restart", None)
+        .flag("fast-restart-interval", Some("0"))
+        .run_with_zero_port();
+}
+#[test]
 fn malicious_withhold_count_flag() {
     CommandLineTest::new()
         .flag("malicious-withhold-count", Some("128"))
@@ -2050,6 +2498,54 @@ fn slasher_update_period_flag() {
         });
 }
 #[test]
+fn slasher_autotune_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-autotune", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert!(slasher_config.autotune);
+        });
+}
+#[test]
+fn slasher_autotune_default() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert!(!slasher_config.autotune);
+        });
+}
+#[test]
+fn slasher_watch_list_flag() {
+    let pubkey = format!("0x{}", "aa".repeat(48));
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-watch-list", Some(&format!("5,{}", pubkey)))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config
+                .slasher
+                .as_ref()
+                .expect("Unable to parse Slasher config");
+            assert_eq!(
+                slasher_config.watch_list,
+                vec![slasher::WatchedValidator::Index(5), pubkey.parse().unwrap(),]
+            );
+        });
+}
+#[test]
 fn slasher_slot_offset_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -2334,6 +2830,38 @@ fn proposer_re_org_cutoff() {
         });
 }
 
+#[test]
+fn proposer_re_org_cutoff_ms_alias() {
+    CommandLineTest::new()
+        .flag("proposer-reorg-cutoff-ms", Some("750"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.re_org_cutoff(12), Duration::from_millis(750))
+        });
+}
+
+#[test]
+fn proposer_re_org_fork_boundary_epochs() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_fork_boundary_epochs, None));
+    CommandLineTest::new()
+        .flag("proposer-reorg-fork-boundary-epochs", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_fork_boundary_epochs, Some(2)));
+}
+
+#[test]
+fn proposer_re_org_protected_proposers() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.re_org_protected_proposers.is_empty()));
+    CommandLineTest::new()
+        .flag("proposer-reorg-protected-proposers", Some("4,8, 15"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_protected_proposers, vec![4, 8, 15]));
+}
+
 #[test]
 fn proposer_re_org_disallowed_offsets_default() {
     CommandLineTest::new()
@@ -2380,6 +2908,62 @@ fn monitoring_endpoint() {
         });
 }
 
+#[test]
+fn telemetry_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.telemetry, None));
+}
+
+#[test]
+fn telemetry_flags() {
+    CommandLineTest::new()
+        .flag("telemetry-collector-url", Some("http://localhost:4317"))
+        .flag("telemetry-sample-rate", Some("0.25"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let telemetry = config.telemetry.as_ref().unwrap();
+            assert_eq!(telemetry.collector_url, "http://localhost:4317");
+            assert_eq!(telemetry.service_name, "lighthouse-bn");
+            assert_eq!(telemetry.sample_rate, 0.25);
+        });
+}
+
+#[test]
+fn failover_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("failover-peer", Some("http://example:5052"))
+        .flag("failover-priority", Some("1"))
+        .flag("failover-threshold", Some("5"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let failover = config.failover.as_ref().unwrap();
+            assert_eq!(failover.peer.full.as_str(), "http://example:5052/");
+            assert_eq!(failover.priority, 1);
+            assert_eq!(failover.check_interval, Duration::from_secs(4));
+            assert_eq!(failover.failure_threshold, 5);
+            assert_eq!(config.http_api.failover_priority, Some(1));
+        });
+}
+#[test]
+fn failover_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.failover.is_none());
+            assert_eq!(config.http_api.failover_priority, None);
+        });
+}
+#[test]
+#[should_panic]
+fn failover_peer_without_priority() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("failover-peer", Some("http://example:5052"))
+        .run_with_zero_port();
+}
+
 // Tests for Logger flags.
 #[test]
 fn default_log_color_flag() {
@@ -2433,6 +3017,19 @@ fn logfile_no_restricted_perms_flag() {
         });
 }
 #[test]
+fn logfile_non_blocking_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.logger_config.non_blocking));
+}
+#[test]
+fn logfile_non_blocking_flag() {
+    CommandLineTest::new()
+        .flag("logfile-non-blocking", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.logger_config.non_blocking));
+}
+#[test]
 fn logfile_format_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2595,6 +3192,41 @@ fn invalid_gossip_verified_blocks_path() {
         });
 }
 
+#[test]
+fn gossip_trace_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.gossip_trace_dir, None);
+            assert_eq!(config.network.gossip_trace_sample_rate, 0.01);
+        });
+}
+
+#[test]
+fn gossip_trace_dir_and_sample_rate() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("gossip-trace-dir", dir.path().as_os_str().to_str())
+        .flag("gossip-trace-sample-rate", Some("0.5"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.gossip_trace_dir,
+                Some(dir.path().to_path_buf())
+            );
+            assert_eq!(config.network.gossip_trace_sample_rate, 0.5);
+        });
+}
+
+#[test]
+#[should_panic]
+fn gossip_trace_sample_rate_out_of_range() {
+    CommandLineTest::new()
+        .flag("gossip-trace-dir", Some("/tmp/gossip-traces"))
+        .flag("gossip-trace-sample-rate", Some("1.5"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn beacon_processor() {
     CommandLineTest::new()
@@ -2603,6 +3235,7 @@ fn beacon_processor() {
 
     CommandLineTest::new()
         .flag("beacon-processor-max-workers", Some("1"))
+        .flag("beacon-processor-gossip-workers", Some("6"))
         .flag("beacon-processor-work-queue-len", Some("2"))
         .flag("beacon-processor-reprocess-queue-len", Some("3"))
         .flag("beacon-processor-attestation-batch-size", Some("4"))
@@ -2614,6 +3247,7 @@ fn beacon_processor() {
                 config.beacon_processor,
                 BeaconProcessorConfig {
                     max_workers: 1,
+                    max_gossip_workers: Some(6),
                     max_work_event_queue_len: 2,
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
@@ -2624,6 +3258,25 @@ fn beacon_processor() {
         });
 }
 
+#[test]
+fn beacon_processor_gossip_workers_default() {
+    CommandLineTest::new()
+        .flag("beacon-processor-max-workers", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.beacon_processor.max_gossip_workers, None);
+            assert_eq!(config.beacon_processor.gossip_worker_limit(), 3);
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_zero_gossip_workers() {
+    CommandLineTest::new()
+        .flag("beacon-processor-gossip-workers", Some("0"))
+        .run_with_zero_port();
+}
+
 #[test]
 #[should_panic]
 fn beacon_processor_zero_workers() {
@@ -2648,6 +3301,119 @@ fn http_sse_capacity_multiplier_override() {
         .with_config(|config| assert_eq!(config.http_api.sse_capacity_multiplier, 10));
 }
 
+#[test]
+fn http_sse_replay_window_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.sse_replay_window_secs, 0));
+}
+
+#[test]
+fn http_sse_replay_window_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-sse-replay-window", Some("300"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.sse_replay_window_secs, 300));
+}
+
+#[test]
+fn http_rate_limit_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_api.rate_limit, None);
+            assert_eq!(config.http_api.rate_limit_burst, None);
+            assert_eq!(config.http_api.max_concurrent_state_requests, None);
+            assert_eq!(config.http_api.max_concurrent_requests, None);
+        });
+}
+
+#[test]
+fn http_rate_limit_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-rate-limit", Some("50"))
+        .flag("http-rate-limit-burst", Some("100"))
+        .flag("http-max-concurrent-state-requests", Some("4"))
+        .flag("http-max-concurrent-requests", Some("64"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_api.rate_limit, Some(50));
+            assert_eq!(config.http_api.rate_limit_burst, Some(100));
+            assert_eq!(config.http_api.max_concurrent_state_requests, Some(4));
+            assert_eq!(config.http_api.max_concurrent_requests, Some(64));
+        });
+}
+
+#[test]
+#[should_panic]
+fn http_max_concurrent_state_requests_zero() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-max-concurrent-state-requests", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+#[should_panic]
+fn http_max_concurrent_requests_zero() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-max-concurrent-requests", Some("0"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn http_compression_level_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.compression_level, None));
+}
+
+#[test]
+fn http_compression_level_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-compression-level", Some("3"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.compression_level, Some(3)));
+}
+
+#[test]
+#[should_panic]
+fn http_compression_level_invalid() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-compression-level", Some("10"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn http_admin_token_file_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.admin_token_file, None));
+}
+
+#[test]
+fn http_admin_token_file_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-admin-token-file", Some("/tmp/admin-token.txt"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.admin_token_file,
+                Some(PathBuf::from("/tmp/admin-token.txt"))
+            )
+        });
+}
+
 #[test]
 fn http_duplicate_block_status_default() {
     CommandLineTest::new()
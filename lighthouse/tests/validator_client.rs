@@ -13,8 +13,8 @@ use std::str::FromStr;
 use std::string::ToString;
 use std::time::Duration;
 use tempfile::TempDir;
-use types::{Address, Slot};
-use validator_client::Config;
+use types::{Address, Slot, Uint256};
+use validator_client::{clock_source::ClockSource, Config};
 
 /// Returns the `lighthouse validator_client` command.
 fn base_cmd() -> Command {
@@ -113,6 +113,17 @@ fn disable_auto_discover_flag() {
         .with_config(|config| assert!(config.disable_auto_discover));
 }
 
+#[test]
+fn watch_validator_definitions_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.watch_validator_definitions));
+    CommandLineTest::new()
+        .flag("watch-validator-definitions", None)
+        .run()
+        .with_config(|config| assert!(config.watch_validator_definitions));
+}
+
 #[test]
 fn init_slashing_protections_flag() {
     CommandLineTest::new()
@@ -246,6 +257,36 @@ fn fee_recipient_flag() {
         });
 }
 
+#[test]
+fn proposer_config_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("proposer_config.yml");
+    std::fs::write(
+        &path,
+        "default:\n  fee_recipient: \"0x00000000219ab540356cbb839cbe05303d7705fa\"\n",
+    )
+    .expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("proposer-config-file", path.as_os_str().to_str())
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.validator_store.proposer_config_file,
+                Some(path.clone())
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn proposer_config_file_flag_invalid() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("proposer_config.yml");
+    std::fs::write(&path, "default:\n  unknown_field: true\n").expect("Unable to write to file");
+    CommandLineTest::new()
+        .flag("proposer-config-file", path.as_os_str().to_str())
+        .run();
+}
+
 // Tests for HTTP flags.
 #[test]
 fn http_flag() {
@@ -461,12 +502,77 @@ fn builder_boost_factor_flag() {
         .with_config(|config| assert_eq!(config.validator_store.builder_boost_factor, Some(150)));
 }
 #[test]
+fn attestation_timing_flag() {
+    CommandLineTest::new()
+        .flag("attestation-timing", Some("block:3000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.validator_store.attestation_timing.to_string(),
+                "block:3000"
+            )
+        });
+}
+#[test]
+fn attestation_timing_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.validator_store.attestation_timing.to_string(),
+            "fixed"
+        )
+    });
+}
+#[test]
+#[should_panic]
+fn attestation_timing_invalid() {
+    CommandLineTest::new()
+        .flag("attestation-timing", Some("early"))
+        .run();
+}
+#[test]
 fn no_builder_boost_factor_flag() {
     CommandLineTest::new()
         .run()
         .with_config(|config| assert_eq!(config.validator_store.builder_boost_factor, None));
 }
 #[test]
+fn builder_min_bid_flag() {
+    CommandLineTest::new()
+        .flag("builder-min-bid", Some("1000000000000000000"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.validator_store.builder_policy.min_bid,
+                Some(Uint256::from(1_000_000_000_000_000_000u64))
+            )
+        });
+}
+#[test]
+fn builder_local_value_factor_flag() {
+    CommandLineTest::new()
+        .flag("builder-local-value-factor", Some("110"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.validator_store.builder_policy.local_value_factor,
+                Some(110)
+            )
+        });
+}
+#[test]
+fn no_builder_policy_flags() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.validator_store.builder_policy.is_enabled()));
+}
+#[test]
+#[should_panic]
+fn builder_local_value_factor_flag_invalid() {
+    CommandLineTest::new()
+        .flag("builder-local-value-factor", Some("ten"))
+        .run();
+}
+#[test]
 fn prefer_builder_proposals_flag() {
     CommandLineTest::new()
         .flag("prefer-builder-proposals", None)
@@ -576,6 +682,28 @@ fn beacon_nodes_sync_tolerances_flag() {
         });
 }
 
+#[test]
+fn max_optimistic_head_slots_flag_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.beacon_node_fallback.max_optimistic_head_distance,
+            None
+        )
+    });
+}
+#[test]
+fn max_optimistic_head_slots_flag() {
+    CommandLineTest::new()
+        .flag("max-optimistic-head-slots", Some("16"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_node_fallback.max_optimistic_head_distance,
+                Some(16)
+            )
+        });
+}
+
 #[test]
 #[should_panic(expected = "Unknown API topic")]
 fn wrong_broadcast_flag() {
@@ -621,6 +749,61 @@ fn validator_registration_batch_size_zero_value() {
         .run();
 }
 
+#[test]
+fn proposal_rehearsal_lead_time() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.proposal_rehearsal_lead_time, None);
+    });
+    CommandLineTest::new()
+        .flag("proposal-rehearsal-lead-time", Some("120"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.proposal_rehearsal_lead_time,
+                Some(Duration::from_secs(120))
+            );
+        });
+}
+
+#[test]
+fn clock_source() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.clock_source, ClockSource::System);
+        assert_eq!(config.clock_skew_threshold, Duration::from_millis(500));
+    });
+    CommandLineTest::new()
+        .flag("clock-source", Some("ntp:time.example.com:1123,10.0.0.1"))
+        .flag("clock-skew-threshold", Some("100"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.clock_source,
+                ClockSource::Ntp(vec![
+                    "time.example.com:1123".to_string(),
+                    "10.0.0.1".to_string()
+                ])
+            );
+            assert_eq!(config.clock_skew_threshold, Duration::from_millis(100));
+        });
+    CommandLineTest::new()
+        .flag("clock-source", Some("ptp:/dev/ptp0"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.clock_source,
+                ClockSource::Ptp(PathBuf::from("/dev/ptp0"))
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn clock_source_invalid() {
+    CommandLineTest::new()
+        .flag("clock-source", Some("gps:/dev/ttyS0"))
+        .run();
+}
+
 #[test]
 fn validator_disable_web3_signer_slashing_protection_default() {
     CommandLineTest::new().run().with_config(|config| {
@@ -661,6 +844,48 @@ fn validator_web3_signer_keep_alive_override() {
         });
 }
 
+#[test]
+fn validator_web3_signer_max_concurrent_requests() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config
+                .initialized_validators
+                .web3_signer_max_concurrent_requests,
+            None
+        );
+    });
+    CommandLineTest::new()
+        .flag("web3-signer-max-concurrent-requests", Some("64"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config
+                    .initialized_validators
+                    .web3_signer_max_concurrent_requests,
+                Some(64)
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn validator_web3_signer_max_concurrent_requests_zero() {
+    CommandLineTest::new()
+        .flag("web3-signer-max-concurrent-requests", Some("0"))
+        .run();
+}
+
+#[test]
+fn validator_web3_signer_http2() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.initialized_validators.web3_signer_http2));
+    CommandLineTest::new()
+        .flag("web3-signer-http2", None)
+        .run()
+        .with_config(|config| assert!(config.initialized_validators.web3_signer_http2));
+}
+
 #[test]
 fn validator_proposer_nodes_default_empty() {
     CommandLineTest::new().run().with_config(|config| {
@@ -8,11 +8,13 @@ use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
+    broadcast_exits::BroadcastExitsConfig,
     create_validators::CreateConfig,
     delete_validators::DeleteConfig,
     import_validators::ImportConfig,
     list_validators::ListConfig,
     move_validators::{MoveConfig, PasswordSource, Validators},
+    presign_exits::PresignExitsConfig,
 };
 
 const EXAMPLE_ETH1_ADDRESS: &str = "0x00000000219ab540356cBB839Cbe05303d7705Fa";
@@ -119,6 +121,18 @@ impl CommandLineTest<DeleteConfig> {
     }
 }
 
+impl CommandLineTest<PresignExitsConfig> {
+    fn presign_exits() -> Self {
+        Self::default().flag("presign-exits", None)
+    }
+}
+
+impl CommandLineTest<BroadcastExitsConfig> {
+    fn broadcast_exits() -> Self {
+        Self::default().flag("broadcast-exits", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -302,6 +316,8 @@ pub fn validator_move_defaults() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
+                safety_delay_epochs: 1,
             };
             assert_eq!(expected, config);
         });
@@ -338,6 +354,8 @@ pub fn validator_move_misc_flags_0() {
                 fee_recipient: Some(Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap()),
                 gas_limit: Some(1337),
                 password_source: PasswordSource::Interactive { stdin_inputs: true },
+                dry_run: false,
+                safety_delay_epochs: 1,
             };
             assert_eq!(expected, config);
         });
@@ -370,6 +388,8 @@ pub fn validator_move_misc_flags_1() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
+                safety_delay_epochs: 1,
             };
             assert_eq!(expected, config);
         });
@@ -402,6 +422,8 @@ pub fn validator_move_misc_flags_2() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
+                safety_delay_epochs: 1,
             };
             assert_eq!(expected, config);
         });
@@ -430,11 +452,57 @@ pub fn validator_move_count() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                dry_run: false,
+                safety_delay_epochs: 1,
             };
             assert_eq!(expected, config);
         });
 }
 
+#[test]
+pub fn validator_move_dry_run_and_safety_delay() {
+    CommandLineTest::validators_move()
+        .flag("--src-vc-url", Some("http://localhost:1"))
+        .flag("--src-vc-token", Some("./1.json"))
+        .flag("--dest-vc-url", Some("http://localhost:2"))
+        .flag("--dest-vc-token", Some("./2.json"))
+        .flag("--validators", Some("all"))
+        .flag("--dry-run", None)
+        .flag("--safety-delay-epochs", Some("3"))
+        .assert_success(|config| {
+            let expected = MoveConfig {
+                src_vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
+                src_vc_token_path: PathBuf::from("./1.json"),
+                dest_vc_url: SensitiveUrl::parse("http://localhost:2").unwrap(),
+                dest_vc_token_path: PathBuf::from("./2.json"),
+                validators: Validators::All,
+                builder_proposals: None,
+                builder_boost_factor: None,
+                prefer_builder_proposals: None,
+                fee_recipient: None,
+                gas_limit: None,
+                password_source: PasswordSource::Interactive {
+                    stdin_inputs: cfg!(windows) || false,
+                },
+                dry_run: true,
+                safety_delay_epochs: 3,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn validator_move_zero_safety_delay() {
+    CommandLineTest::validators_move()
+        .flag("--src-vc-url", Some("http://localhost:1"))
+        .flag("--src-vc-token", Some("./1.json"))
+        .flag("--dest-vc-url", Some("http://localhost:2"))
+        .flag("--dest-vc-token", Some("./2.json"))
+        .flag("--validators", Some("all"))
+        .flag("--safety-delay-epochs", Some("0"))
+        .assert_failed();
+}
+
 #[test]
 pub fn validator_list_defaults() {
     CommandLineTest::validators_list()
@@ -468,3 +536,71 @@ pub fn validator_delete_defaults() {
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn presign_exits_defaults() {
+    CommandLineTest::presign_exits()
+        .flag(
+            "--validators",
+            Some(&format!("{},{}", EXAMPLE_PUBKEY_0, EXAMPLE_PUBKEY_1)),
+        )
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--passphrase-file", Some("./passphrase.txt"))
+        .assert_success(|config| {
+            let expected = PresignExitsConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: vec![
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap(),
+                    PublicKeyBytes::from_str(EXAMPLE_PUBKEY_1).unwrap(),
+                ],
+                passphrase_path: PathBuf::from("./passphrase.txt"),
+                epoch: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn presign_exits_missing_passphrase() {
+    CommandLineTest::presign_exits()
+        .flag("--validators", Some(EXAMPLE_PUBKEY_0))
+        .flag("--vc-token", Some("./token.json"))
+        .assert_failed();
+}
+
+#[test]
+pub fn broadcast_exits_defaults() {
+    CommandLineTest::broadcast_exits()
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--passphrase-file", Some("./passphrase.txt"))
+        .assert_success(|config| {
+            let expected = BroadcastExitsConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: None,
+                passphrase_path: PathBuf::from("./passphrase.txt"),
+                broadcast_epoch: None,
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn broadcast_exits_misc_flags() {
+    CommandLineTest::broadcast_exits()
+        .flag("--validators", Some(EXAMPLE_PUBKEY_0))
+        .flag("--vc-token", Some("./token.json"))
+        .flag("--passphrase-file", Some("./passphrase.txt"))
+        .flag("--broadcast-epoch", Some("1024"))
+        .assert_success(|config| {
+            let expected = BroadcastExitsConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                validators: Some(vec![PublicKeyBytes::from_str(EXAMPLE_PUBKEY_0).unwrap()]),
+                passphrase_path: PathBuf::from("./passphrase.txt"),
+                broadcast_epoch: Some(Epoch::new(1024)),
+            };
+            assert_eq!(expected, config);
+        });
+}
@@ -2,13 +2,14 @@ use beacon_chain::{
     observed_operations::ObservationOutcome, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use directory::size_of_dir;
+use eth2::types::{EventKind, SseSlasherAlert};
 use lighthouse_network::PubsubMessage;
 use network::NetworkMessage;
 use slasher::{
     metrics::{self, SLASHER_DATABASE_SIZE, SLASHER_RUN_TIME},
-    Slasher,
+    Slasher, WatchAlertEvidence, WatchedValidator,
 };
-use slog::{debug, error, info, trace, warn, Logger};
+use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use state_processing::{
     per_block_processing::errors::{
@@ -20,8 +21,8 @@ use std::sync::mpsc::{sync_channel, Receiver, SyncSender, TrySendError};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc::UnboundedSender;
-use tokio::time::{interval_at, Duration, Instant};
-use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
+use tokio::time::{sleep_until, Duration, Instant};
+use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing, PublicKeyBytes};
 
 pub struct SlasherService<T: BeaconChainTypes> {
     beacon_chain: Arc<BeaconChain<T>>,
@@ -49,12 +50,16 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             .ok_or("No slasher is configured")?;
         let log = slasher.log().clone();
 
-        info!(log, "Starting slasher"; "broadcast" => slasher.config().broadcast);
+        info!(
+            log,
+            "Starting slasher";
+            "broadcast" => slasher.config().broadcast,
+            "autotune" => slasher.config().autotune,
+        );
 
         // Buffer just a single message in the channel. If the receiver is still processing, we
         // don't need to burden them with more work (we can wait).
         let (notif_sender, notif_receiver) = sync_channel(1);
-        let update_period = slasher.config().update_period;
         let slot_offset = slasher.config().slot_offset;
         let beacon_chain = self.beacon_chain.clone();
         let network_sender = self.network_sender.clone();
@@ -62,7 +67,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         executor.spawn(
             Self::run_notifier(
                 beacon_chain.clone(),
-                update_period,
+                slasher.clone(),
                 slot_offset,
                 notif_sender,
                 log,
@@ -79,9 +84,11 @@ impl<T: BeaconChainTypes> SlasherService<T> {
     }
 
     /// Run the async notifier which periodically prompts the processor to run.
+    ///
+    /// The period is read from the slasher before each wait, as it may be autotuned.
     async fn run_notifier(
         beacon_chain: Arc<BeaconChain<T>>,
-        update_period: u64,
+        slasher: Arc<Slasher<T::EthSpec>>,
         slot_offset: f64,
         notif_sender: SyncSender<Epoch>,
         log: Logger,
@@ -94,10 +101,11 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                 error!(log, "Error aligning slasher to slot clock");
                 Instant::now()
             };
-        let mut interval = interval_at(start_instant, Duration::from_secs(update_period));
+        let mut next_update = start_instant;
 
         loop {
-            interval.tick().await;
+            sleep_until(next_update).await;
+            next_update = (next_update + slasher.update_period()).max(Instant::now());
             if let Some(current_slot) = beacon_chain.slot_clock.now() {
                 let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
                 if let Err(TrySendError::Disconnected(_)) = notif_sender.try_send(current_epoch) {
@@ -117,9 +125,23 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
     ) {
         let log = slasher.log();
+        let mut unresolved_pubkeys = slasher
+            .config()
+            .watch_list
+            .iter()
+            .filter_map(|validator| match validator {
+                WatchedValidator::Pubkey(pubkey) => Some(*pubkey),
+                WatchedValidator::Index(_) => None,
+            })
+            .collect::<Vec<_>>();
+
         while let Ok(current_epoch) = notif_receiver.recv() {
             let t = Instant::now();
 
+            if !unresolved_pubkeys.is_empty() {
+                Self::resolve_watched_pubkeys(&beacon_chain, &slasher, &mut unresolved_pubkeys);
+            }
+
             let batch_timer = metrics::start_timer(&SLASHER_RUN_TIME);
             let stats = match slasher.process_queued(current_epoch) {
                 Ok(stats) => Some(stats),
@@ -149,6 +171,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
 
             // Provide slashings to the beacon chain, and optionally publish them.
             Self::process_slashings(&beacon_chain, &slasher, &network_sender);
+            Self::process_watch_alerts(&beacon_chain, &slasher);
 
             let database_size = size_of_dir(&slasher.config().database_path);
             metrics::set_gauge(&SLASHER_DATABASE_SIZE, database_size as i64);
@@ -160,12 +183,91 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                     "epoch" => current_epoch,
                     "time_taken" => format!("{}ms", t.elapsed().as_millis()),
                     "num_attestations" => stats.attestation_stats.num_processed,
+                    "num_remaining" => stats.attestation_stats.num_remaining,
+                    "detection_lag" => stats.attestation_stats.detection_lag,
                     "num_blocks" => stats.block_stats.num_processed,
                 );
             }
         }
     }
 
+    /// Add the watched validators whose pubkeys are now known to the slasher's watch list.
+    fn resolve_watched_pubkeys(
+        beacon_chain: &BeaconChain<T>,
+        slasher: &Slasher<T::EthSpec>,
+        unresolved_pubkeys: &mut Vec<PublicKeyBytes>,
+    ) {
+        let log = slasher.log();
+        unresolved_pubkeys.retain(|pubkey| match beacon_chain.validator_index(pubkey) {
+            Ok(Some(validator_index)) => {
+                info!(
+                    log,
+                    "Watching validator";
+                    "validator_index" => validator_index,
+                    "pubkey" => ?pubkey,
+                );
+                slasher.watch_validators([validator_index as u64]);
+                false
+            }
+            Ok(None) => true,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to look up watched validator";
+                    "pubkey" => ?pubkey,
+                    "error" => ?e,
+                );
+                true
+            }
+        });
+    }
+
+    /// Log the alerts raised for watched validators and emit them as server-sent events.
+    fn process_watch_alerts(beacon_chain: &BeaconChain<T>, slasher: &Slasher<T::EthSpec>) {
+        let log = slasher.log();
+        for alert in slasher.get_watch_alerts() {
+            let kind: &'static str = alert.kind.into();
+            crit!(
+                log,
+                "Equivocation by watched validator";
+                "validator_index" => alert.validator_index,
+                "kind" => kind,
+            );
+
+            let Some(event_handler) = beacon_chain.event_handler.as_ref() else {
+                continue;
+            };
+            if !event_handler.has_slasher_alert_subscribers() {
+                continue;
+            }
+            let mut event = SseSlasherAlert {
+                validator_index: alert.validator_index,
+                kind: kind.to_string(),
+                attestation_1: None,
+                attestation_2: None,
+                slot: None,
+                block_root_1: None,
+                block_root_2: None,
+            };
+            match alert.evidence {
+                WatchAlertEvidence::Attestations { previous, new } => {
+                    event.attestation_1 = Some(*previous);
+                    event.attestation_2 = Some(*new);
+                }
+                WatchAlertEvidence::Proposals {
+                    slot,
+                    previous_block_root,
+                    new_block_root,
+                } => {
+                    event.slot = Some(slot);
+                    event.block_root_1 = Some(previous_block_root);
+                    event.block_root_2 = Some(new_block_root);
+                }
+            }
+            event_handler.register(EventKind::SlasherAlert(Box::new(event)));
+        }
+    }
+
     /// Push any slashings found to the beacon chain, optionally publishing them on the network.
     fn process_slashings(
         beacon_chain: &BeaconChain<T>,
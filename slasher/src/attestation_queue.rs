@@ -98,6 +98,17 @@ impl<E: EthSpec> AttestationQueue<E> {
         std::mem::take(&mut self.queue.lock())
     }
 
+    /// Dequeue at most `limit` of the oldest attestations, returning them along with the number
+    /// of attestations left in the queue.
+    pub fn dequeue_up_to(&self, limit: usize) -> (SimpleBatch<E>, usize) {
+        let mut queue = self.queue.lock();
+        if queue.len() <= limit {
+            return (std::mem::take(&mut queue), 0);
+        }
+        let batch = queue.drain(..limit).collect();
+        (batch, queue.len())
+    }
+
     pub fn requeue(&self, batch: SimpleBatch<E>) {
         self.queue.lock().extend(batch);
     }
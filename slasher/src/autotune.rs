@@ -0,0 +1,100 @@
+//! Adaptive scheduling of slasher batch processing.
+//!
+//! The chunk sizes of the min-max arrays are fixed once the database has been created, so under
+//! load the slasher instead adapts the number of attestations processed per batch and the period
+//! between batches. Batches which take too long to process are shrunk, and a backlog of
+//! attestations that didn't fit in the last batch shortens the period until it has been cleared.
+use std::time::Duration;
+
+/// The shortest period between batches.
+pub const MIN_UPDATE_PERIOD: Duration = Duration::from_secs(1);
+/// The smallest number of attestations processed in a single batch.
+pub const MIN_BATCH_SIZE: usize = 4096;
+
+#[derive(Debug)]
+pub struct Autotuner {
+    max_update_period: Duration,
+    update_period: Duration,
+    /// The maximum number of attestations to process per batch, or `None` if unlimited.
+    batch_size: Option<usize>,
+}
+
+impl Autotuner {
+    /// Create a new autotuner which never waits longer than `max_update_period` between batches.
+    pub fn new(max_update_period: Duration) -> Self {
+        Self {
+            max_update_period,
+            update_period: max_update_period,
+            batch_size: None,
+        }
+    }
+
+    pub fn update_period(&self) -> Duration {
+        self.update_period
+    }
+
+    pub fn batch_size(&self) -> Option<usize> {
+        self.batch_size
+    }
+
+    /// Adapt to a batch which processed `num_processed` attestations in `run_time`, leaving
+    /// `backlog` attestations queued for a later batch.
+    pub fn observe(&mut self, num_processed: usize, backlog: usize, run_time: Duration) {
+        // Batches should finish well within the longest period so that a backlog can be cleared.
+        if run_time > self.max_update_period / 2 {
+            self.batch_size = Some((num_processed / 2).max(MIN_BATCH_SIZE));
+        } else if backlog > 0 {
+            self.batch_size = self
+                .batch_size
+                .map(|batch_size| batch_size.saturating_add(batch_size / 4));
+        }
+
+        self.update_period = if backlog > 0 {
+            (self.update_period / 2).max(MIN_UPDATE_PERIOD)
+        } else {
+            (self.update_period * 2).min(self.max_update_period)
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MAX_PERIOD: Duration = Duration::from_secs(12);
+
+    #[test]
+    fn slow_batch_shrinks_batch_size() {
+        let mut autotuner = Autotuner::new(MAX_PERIOD);
+        assert_eq!(autotuner.batch_size(), None);
+
+        autotuner.observe(100_000, 0, Duration::from_secs(10));
+        assert_eq!(autotuner.batch_size(), Some(50_000));
+        assert_eq!(autotuner.update_period(), MAX_PERIOD);
+
+        autotuner.observe(50_000, 50_000, Duration::from_secs(8));
+        assert_eq!(autotuner.batch_size(), Some(25_000));
+        assert_eq!(autotuner.update_period(), MAX_PERIOD / 2);
+
+        autotuner.observe(1000, 0, Duration::from_secs(7));
+        assert_eq!(autotuner.batch_size(), Some(MIN_BATCH_SIZE));
+    }
+
+    #[test]
+    fn backlog_shortens_period() {
+        let mut autotuner = Autotuner::new(MAX_PERIOD);
+        autotuner.observe(100_000, 0, Duration::from_secs(7));
+
+        for _ in 0..10 {
+            autotuner.observe(50_000, 1_000_000, Duration::from_secs(1));
+        }
+        assert_eq!(autotuner.update_period(), MIN_UPDATE_PERIOD);
+        assert!(autotuner.batch_size().unwrap() > 50_000);
+
+        // The period recovers once the backlog has been cleared.
+        for _ in 0..10 {
+            autotuner.observe(1000, 0, Duration::from_millis(100));
+        }
+        assert_eq!(autotuner.update_period(), MAX_PERIOD);
+    }
+}
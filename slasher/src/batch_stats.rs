@@ -13,4 +13,8 @@ pub struct BlockStats {
 #[derive(Debug)]
 pub struct AttestationStats {
     pub num_processed: usize,
+    /// Number of attestations left in the queue because they didn't fit in the batch.
+    pub num_remaining: usize,
+    /// Number of epochs between the oldest attestation processed and the current epoch.
+    pub detection_lag: u64,
 }
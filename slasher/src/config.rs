@@ -1,4 +1,4 @@
-use crate::Error;
+use crate::{Error, WatchedValidator};
 use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
@@ -14,6 +14,7 @@ pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 512 * 1024; // 512 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(100_000);
 pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_AUTOTUNE: bool = false;
 
 #[cfg(all(feature = "mdbx", not(any(feature = "lmdb", feature = "redb"))))]
 pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Mdbx;
@@ -26,6 +27,7 @@ pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Disabled;
 
 pub const MAX_HISTORY_LENGTH: usize = 1 << 16;
 pub const MEGABYTE: usize = 1 << 20;
+pub const LMDB_DATA_FILENAME: &str = "data.mdb";
 pub const MDBX_DATA_FILENAME: &str = "mdbx.dat";
 pub const REDB_DATA_FILENAME: &str = "slasher.redb";
 
@@ -37,6 +39,8 @@ pub struct Config {
     /// Number of epochs of history to keep.
     pub history_length: usize,
     /// Update frequency in seconds.
+    ///
+    /// This is the longest period between updates if `autotune` is enabled.
     pub update_period: u64,
     /// Offset from the start of the slot to begin processing.
     pub slot_offset: f64,
@@ -48,6 +52,10 @@ pub struct Config {
     pub broadcast: bool,
     /// Database backend to use.
     pub backend: DatabaseBackend,
+    /// Whether to adapt the batch size and update period to the attestation backlog.
+    pub autotune: bool,
+    /// Validators whose attestations are retained indefinitely and checked for equivocations.
+    pub watch_list: Vec<WatchedValidator>,
 }
 
 /// Immutable configuration parameters which are stored on disk and checked for consistency.
@@ -92,6 +100,8 @@ impl Config {
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
             broadcast: DEFAULT_BROADCAST,
             backend: DEFAULT_BACKEND,
+            autotune: DEFAULT_AUTOTUNE,
+            watch_list: vec![],
         }
     }
 
@@ -176,6 +186,20 @@ impl Config {
             .copied()
     }
 
+    /// The path of the main data file of the configured backend, or `None` if it is disabled.
+    pub fn database_file(&self) -> Option<PathBuf> {
+        let filename = match self.backend {
+            #[cfg(feature = "mdbx")]
+            DatabaseBackend::Mdbx => MDBX_DATA_FILENAME,
+            #[cfg(feature = "lmdb")]
+            DatabaseBackend::Lmdb => LMDB_DATA_FILENAME,
+            #[cfg(feature = "redb")]
+            DatabaseBackend::Redb => REDB_DATA_FILENAME,
+            DatabaseBackend::Disabled => return None,
+        };
+        Some(self.database_path.join(filename))
+    }
+
     pub fn override_backend(&mut self) -> DatabaseBackendOverride {
         let mdbx_path = self.database_path.join(MDBX_DATA_FILENAME);
 
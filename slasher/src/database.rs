@@ -5,7 +5,7 @@ mod redb_impl;
 
 use crate::{
     metrics, AttesterRecord, AttesterSlashingStatus, CompactAttesterRecord, Config, Database,
-    Error, ProposerSlashingStatus,
+    Error, ProposerSlashingStatus, WatchedHistory,
 };
 use byteorder::{BigEndian, ByteOrder};
 use interface::{Environment, OpenDatabases, RwTransaction};
@@ -51,9 +51,13 @@ const MAX_TARGETS_DB: &str = "max_targets";
 const CURRENT_EPOCHS_DB: &str = "current_epochs";
 /// Map from `(slot, validator_index)` to `SignedBeaconBlockHeader`.
 const PROPOSERS_DB: &str = "proposers";
+/// Map from `(validator_index, target_epoch)` to the `IndexedAttestation` of a watched validator.
+///
+/// Unlike the other tables this one is never pruned.
+const WATCHED_ATTESTATIONS_DB: &str = "watched_attestations";
 
 /// The number of DBs for MDBX to use (equal to the number of DBs defined above).
-const MAX_NUM_DBS: usize = 9;
+const MAX_NUM_DBS: usize = 10;
 
 /// Constant key under which the schema version is stored in the `metadata_db`.
 const METADATA_VERSION_KEY: &[u8] = &[0];
@@ -65,6 +69,7 @@ const PROPOSER_KEY_SIZE: usize = 16;
 const CURRENT_EPOCH_KEY_SIZE: usize = 8;
 const INDEXED_ATTESTATION_ID_SIZE: usize = 6;
 const INDEXED_ATTESTATION_ID_KEY_SIZE: usize = 40;
+const WATCHED_ATTESTATION_KEY_SIZE: usize = 16;
 
 #[derive(Debug)]
 pub struct SlasherDB<E: EthSpec> {
@@ -145,6 +150,39 @@ impl AsRef<[u8]> for ProposerKey {
     }
 }
 
+/// Database key for the `watched_attestations` database.
+///
+/// Stored as big-endian `(validator_index, target_epoch)`.
+#[derive(Debug)]
+pub struct WatchedAttestationKey {
+    data: [u8; WATCHED_ATTESTATION_KEY_SIZE],
+}
+
+impl WatchedAttestationKey {
+    pub fn new(validator_index: u64, target_epoch: Epoch) -> Self {
+        let mut data = [0; WATCHED_ATTESTATION_KEY_SIZE];
+        data[0..8].copy_from_slice(&validator_index.to_be_bytes());
+        data[8..WATCHED_ATTESTATION_KEY_SIZE].copy_from_slice(&target_epoch.as_u64().to_be_bytes());
+        WatchedAttestationKey { data }
+    }
+
+    pub fn parse(data: Cow<[u8]>) -> Result<(u64, Epoch), Error> {
+        if data.len() == WATCHED_ATTESTATION_KEY_SIZE {
+            let validator_index = BigEndian::read_u64(&data[..8]);
+            let target_epoch = Epoch::new(BigEndian::read_u64(&data[8..]));
+            Ok((validator_index, target_epoch))
+        } else {
+            Err(Error::WatchedAttestationKeyCorrupt { length: data.len() })
+        }
+    }
+}
+
+impl AsRef<[u8]> for WatchedAttestationKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 /// Key containing a validator index
 pub struct CurrentEpochKey {
     validator_index: [u8; CURRENT_EPOCH_KEY_SIZE],
@@ -539,6 +577,64 @@ impl<E: EthSpec> SlasherDB<E> {
         indexed_attestation_on_disk.into_indexed_attestation(&self.spec)
     }
 
+    /// Store an attestation by a watched validator, which will never be pruned.
+    pub fn store_watched_attestation(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        validator_index: u64,
+        indexed_attestation: &IndexedAttestation<E>,
+    ) -> Result<(), Error> {
+        let key =
+            WatchedAttestationKey::new(validator_index, indexed_attestation.data().target.epoch);
+        txn.put(
+            &self.databases.watched_attestations_db,
+            key,
+            indexed_attestation.as_ssz_bytes(),
+        )?;
+        Ok(())
+    }
+
+    pub fn get_watched_attestation(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        validator_index: u64,
+        target_epoch: Epoch,
+    ) -> Result<Option<IndexedAttestation<E>>, Error> {
+        let key = WatchedAttestationKey::new(validator_index, target_epoch);
+        txn.get(&self.databases.watched_attestations_db, key.as_ref())?
+            .map(|bytes| {
+                let indexed_attestation_on_disk: IndexedAttestationOnDisk = ssz_decode(bytes)?;
+                indexed_attestation_on_disk.into_indexed_attestation(&self.spec)
+            })
+            .transpose()
+    }
+
+    /// Load a summary of all the attestations of watched validators.
+    pub fn load_watched_history(
+        &self,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<WatchedHistory, Error> {
+        let mut history = WatchedHistory::default();
+        let mut cursor = txn.cursor(&self.databases.watched_attestations_db)?;
+
+        let mut has_entry = cursor.first_key()?.is_some();
+        while has_entry {
+            let (key, value) = cursor.get_current()?.ok_or(Error::MissingKey)?;
+            let (validator_index, target_epoch) = WatchedAttestationKey::parse(key)?;
+            let indexed_attestation_on_disk: IndexedAttestationOnDisk = ssz_decode(value)?;
+            let data = &indexed_attestation_on_disk.data;
+            history.insert(
+                validator_index,
+                data.source.epoch,
+                target_epoch,
+                data.tree_hash_root(),
+            );
+            has_entry = cursor.next_key()?.is_some();
+        }
+
+        Ok(history)
+    }
+
     fn get_attestation_data_root(
         &self,
         txn: &mut RwTransaction<'_>,
@@ -833,6 +929,7 @@ impl<E: EthSpec> SlasherDB<E> {
             current_epochs_db,
             proposers_db,
             metadata_db,
+            watched_attestations_db,
         } = &self.databases;
         let mut txn = self.begin_rw_txn()?;
         self.reset_db(&mut txn, indexed_attestation_db)?;
@@ -844,6 +941,7 @@ impl<E: EthSpec> SlasherDB<E> {
         self.reset_db(&mut txn, current_epochs_db)?;
         self.reset_db(&mut txn, proposers_db)?;
         self.reset_db(&mut txn, metadata_db)?;
+        self.reset_db(&mut txn, watched_attestations_db)?;
         txn.commit()
     }
 
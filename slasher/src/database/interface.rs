@@ -1,3 +1,8 @@
+use crate::database::{
+    ATTESTERS_DB, ATTESTERS_MAX_TARGETS_DB, CURRENT_EPOCHS_DB, INDEXED_ATTESTATION_DB,
+    INDEXED_ATTESTATION_ID_DB, MAX_NUM_DBS, MAX_TARGETS_DB, METADATA_DB, MIN_TARGETS_DB,
+    PROPOSERS_DB, WATCHED_ATTESTATIONS_DB,
+};
 use crate::{Config, DatabaseBackend, Error};
 use std::borrow::Cow;
 use std::marker::PhantomData;
@@ -54,6 +59,7 @@ pub struct OpenDatabases<'env> {
     pub current_epochs_db: Database<'env>,
     pub proposers_db: Database<'env>,
     pub metadata_db: Database<'env>,
+    pub watched_attestations_db: Database<'env>,
 }
 
 #[derive(Debug)]
@@ -67,6 +73,24 @@ pub enum Cursor<'env> {
     Disabled(PhantomData<&'env ()>),
 }
 
+impl<'env> OpenDatabases<'env> {
+    /// All of the databases, alongside their names.
+    pub fn all(&self) -> [(&'static str, &Database<'env>); MAX_NUM_DBS] {
+        [
+            (INDEXED_ATTESTATION_DB, &self.indexed_attestation_db),
+            (INDEXED_ATTESTATION_ID_DB, &self.indexed_attestation_id_db),
+            (ATTESTERS_DB, &self.attesters_db),
+            (ATTESTERS_MAX_TARGETS_DB, &self.attesters_max_targets_db),
+            (MIN_TARGETS_DB, &self.min_targets_db),
+            (MAX_TARGETS_DB, &self.max_targets_db),
+            (CURRENT_EPOCHS_DB, &self.current_epochs_db),
+            (PROPOSERS_DB, &self.proposers_db),
+            (METADATA_DB, &self.metadata_db),
+            (WATCHED_ATTESTATIONS_DB, &self.watched_attestations_db),
+        ]
+    }
+}
+
 pub type Key<'a> = Cow<'a, [u8]>;
 pub type Value<'a> = Cow<'a, [u8]>;
 
@@ -219,6 +243,19 @@ impl Cursor<'_> {
         }
     }
 
+    /// Return the key and value at the cursor's position.
+    pub fn get_current(&mut self) -> Result<Option<(Key, Value)>, Error> {
+        match self {
+            #[cfg(feature = "mdbx")]
+            Cursor::Mdbx(cursor) => cursor.get_current(),
+            #[cfg(feature = "lmdb")]
+            Cursor::Lmdb(cursor) => cursor.get_current(),
+            #[cfg(feature = "redb")]
+            Cursor::Redb(cursor) => cursor.get_current(),
+            _ => Err(Error::MismatchedDatabaseVariant),
+        }
+    }
+
     pub fn next_key(&mut self) -> Result<Option<Key>, Error> {
         match self {
             #[cfg(feature = "mdbx")]
@@ -1,7 +1,7 @@
 #![cfg(feature = "lmdb")]
 
 use crate::{
-    config::MEGABYTE,
+    config::{LMDB_DATA_FILENAME, MEGABYTE},
     database::{
         interface::{Key, Value},
         *,
@@ -59,6 +59,9 @@ impl Environment {
             .create_db(Some(CURRENT_EPOCHS_DB), Self::db_flags())?;
         let proposers_db = self.env.create_db(Some(PROPOSERS_DB), Self::db_flags())?;
         let metadata_db = self.env.create_db(Some(METADATA_DB), Self::db_flags())?;
+        let watched_attestations_db = self
+            .env
+            .create_db(Some(WATCHED_ATTESTATIONS_DB), Self::db_flags())?;
 
         let wrap = |db| {
             crate::Database::Lmdb(Database {
@@ -77,6 +80,7 @@ impl Environment {
             current_epochs_db: wrap(current_epochs_db),
             proposers_db: wrap(proposers_db),
             metadata_db: wrap(metadata_db),
+            watched_attestations_db: wrap(watched_attestations_db),
         })
     }
 
@@ -87,7 +91,7 @@ impl Environment {
 
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         vec![
-            config.database_path.join("data.mdb"),
+            config.database_path.join(LMDB_DATA_FILENAME),
             config.database_path.join("lock.mdb"),
         ]
     }
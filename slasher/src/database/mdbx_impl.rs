@@ -1,7 +1,7 @@
 #![cfg(feature = "mdbx")]
 
 use crate::{
-    config::MEGABYTE,
+    config::{MDBX_DATA_FILENAME, MEGABYTE},
     database::{
         interface::{Key, OpenDatabases, Value},
         *,
@@ -55,6 +55,7 @@ impl Environment {
         txn.create_db(CURRENT_EPOCHS_DB)?;
         txn.create_db(PROPOSERS_DB)?;
         txn.create_db(METADATA_DB)?;
+        txn.create_db(WATCHED_ATTESTATIONS_DB)?;
 
         // This is all rather nasty
         let (_, mut databases) = txn.txn.commit_and_rebind_open_dbs()?;
@@ -74,6 +75,7 @@ impl Environment {
             current_epochs_db: next_db(),
             proposers_db: next_db(),
             metadata_db: next_db(),
+            watched_attestations_db: next_db(),
         })
     }
 
@@ -84,7 +86,7 @@ impl Environment {
 
     pub fn filenames(&self, config: &Config) -> Vec<PathBuf> {
         vec![
-            config.database_path.join("mdbx.dat"),
+            config.database_path.join(MDBX_DATA_FILENAME),
             config.database_path.join("mdbx.lck"),
         ]
     }
@@ -9,7 +9,7 @@ use crate::{
 };
 use derivative::Derivative;
 use redb::{ReadableTable, TableDefinition};
-use std::{borrow::Cow, path::PathBuf};
+use std::{borrow::Cow, ops::Bound, path::PathBuf};
 
 #[derive(Debug)]
 pub struct Environment {
@@ -61,6 +61,7 @@ impl Environment {
         let current_epochs_db = self.create_table(CURRENT_EPOCHS_DB)?;
         let proposers_db = self.create_table(PROPOSERS_DB)?;
         let metadata_db = self.create_table(METADATA_DB)?;
+        let watched_attestations_db = self.create_table(WATCHED_ATTESTATIONS_DB)?;
 
         Ok(OpenDatabases {
             indexed_attestation_db,
@@ -72,6 +73,7 @@ impl Environment {
             current_epochs_db,
             proposers_db,
             metadata_db,
+            watched_attestations_db,
         })
     }
 
@@ -207,7 +209,9 @@ impl<'env> Cursor<'env> {
             TableDefinition::new(&self.db.table_name);
         let table = self.txn.open_table(table_definition)?;
         if let Some(current_key) = &self.current_key {
-            let range: std::ops::RangeFrom<&[u8]> = current_key..;
+            // Exclude the current key so that the cursor advances.
+            let range: (Bound<&[u8]>, Bound<&[u8]>) =
+                (Bound::Excluded(current_key.as_ref()), Bound::Unbounded);
 
             let next = table
                 .range(range)?
@@ -1,5 +1,6 @@
-use crate::config::{Config, DiskConfig};
+use crate::config::{Config, DatabaseBackend, DiskConfig};
 use std::io;
+use std::path::PathBuf;
 use types::Epoch;
 
 #[derive(Debug)]
@@ -58,6 +59,9 @@ pub enum Error {
     IndexedAttestationIdKeyCorrupt {
         length: usize,
     },
+    WatchedAttestationKeyCorrupt {
+        length: usize,
+    },
     IndexedAttestationIdCorrupt {
         length: usize,
     },
@@ -70,6 +74,26 @@ pub enum Error {
     MissingIndexedAttestationIdKey,
     InconsistentAttestationDataRoot,
     MissingKey,
+    MissingWatchedAttestation {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+    BackendMigrationUnchanged(DatabaseBackend),
+    BackendMigrationSourceMissing(PathBuf),
+    BackendMigrationTargetExists(PathBuf),
+    /// The migrated database has a different number of entries to the original.
+    BackendMigrationIncomplete {
+        database: &'static str,
+        source_entries: usize,
+        target_entries: usize,
+    },
+    /// The migrated database has a different value for `key` to the original.
+    BackendMigrationMismatch {
+        database: &'static str,
+        key: Vec<u8>,
+    },
+    OfflineDatabaseExists(PathBuf),
+    OfflineInputInvalid(String),
 }
 
 #[cfg(feature = "mdbx")]
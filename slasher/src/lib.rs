@@ -7,6 +7,7 @@
 mod array;
 mod attestation_queue;
 mod attester_record;
+mod autotune;
 mod batch_stats;
 mod block_queue;
 pub mod config;
@@ -14,12 +15,16 @@ mod database;
 mod error;
 pub mod metrics;
 mod migrate;
+mod migrate_backend;
+mod offline;
 mod slasher;
 pub mod test_utils;
+mod watch;
 
 pub use crate::slasher::Slasher;
 pub use attestation_queue::{AttestationBatch, AttestationQueue, SimpleBatch};
 pub use attester_record::{AttesterRecord, CompactAttesterRecord, IndexedAttesterRecord};
+pub use autotune::Autotuner;
 pub use block_queue::BlockQueue;
 pub use config::{Config, DatabaseBackend, DatabaseBackendOverride};
 pub use database::{
@@ -27,6 +32,14 @@ pub use database::{
     IndexedAttestationId, SlasherDB,
 };
 pub use error::Error;
+pub use migrate_backend::{backup_dir, delete_backup, migrate_backend};
+pub use offline::{
+    detect_offline, read_offline_input, OfflineInput, OfflineMessage, OfflineReport,
+    SignatureVerifier,
+};
+pub use watch::{
+    WatchAlert, WatchAlertEvidence, WatchAlertKind, WatchList, WatchedHistory, WatchedValidator,
+};
 
 use types::{AttesterSlashing, AttesterSlashingBase, AttesterSlashingElectra};
 use types::{EthSpec, IndexedAttestation, ProposerSlashing};
@@ -38,6 +38,37 @@ pub static SLASHER_NUM_ATTESTATIONS_STORED_PER_BATCH: LazyLock<Result<IntGauge>>
             "Number of attestations stored per batch",
         )
     });
+pub static SLASHER_NUM_ATTESTATIONS_BACKLOG: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "slasher_num_attestations_backlog",
+        "Number of attestations left queued after each batch",
+    )
+});
+pub static SLASHER_DETECTION_LAG: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "slasher_detection_lag_epochs",
+        "Number of epochs between the oldest attestation in each batch and the current epoch",
+    )
+});
+pub static SLASHER_BATCH_SIZE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "slasher_batch_size",
+        "Maximum number of attestations per batch chosen by the autotuner (0 if unlimited)",
+    )
+});
+pub static SLASHER_UPDATE_PERIOD: LazyLock<Result<Gauge>> = LazyLock::new(|| {
+    try_create_float_gauge(
+        "slasher_update_period_seconds",
+        "Period between batches chosen by the autotuner",
+    )
+});
+pub static SLASHER_WATCH_ALERTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "slasher_watch_alerts_total",
+        "Number of equivocations by watched validators",
+        &["kind"],
+    )
+});
 pub static SLASHER_NUM_BLOCKS_PROCESSED: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
         "slasher_num_blocks_processed",
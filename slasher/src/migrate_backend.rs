@@ -0,0 +1,223 @@
+//! Conversion of an existing slasher database from one backend to another.
+//!
+//! The databases are copied entry by entry, so the history which the slasher has already
+//! ingested is retained without reprocessing any attestations or blocks. Every entry of the copy
+//! is checked against the original, which is then moved to a backup directory rather than deleted,
+//! until the operator confirms that the new database works by deleting the backup.
+//!
+//! Migration is offline only: the slasher must not be running. Migrating online would require the
+//! slasher to write to both backends until the copy caught up, for a one-off operation which only
+//! takes minutes with the node stopped, so it is not supported.
+use crate::database::interface::{Database, Environment};
+use crate::{Config, DatabaseBackend, Error};
+use slog::{info, Logger};
+use std::fs;
+use std::path::PathBuf;
+
+/// The number of entries to write to the new database in each transaction.
+const BATCH_SIZE: usize = 50_000;
+
+/// The directory within `config.database_path` that the files of `backend` are moved to once they
+/// have been migrated.
+pub fn backup_dir(config: &Config, backend: DatabaseBackend) -> PathBuf {
+    config.database_path.join(format!("{}_backup", backend))
+}
+
+/// Copy the slasher database at `config.database_path` from the `from` backend to the `to`
+/// backend, verify the copy, then move the `from` database to its `backup_dir`.
+///
+/// The slasher must not be running whilst the migration takes place. The `to` database and the
+/// backup directory must not already exist.
+pub fn migrate_backend(
+    config: &Config,
+    from: DatabaseBackend,
+    to: DatabaseBackend,
+    log: &Logger,
+) -> Result<(), Error> {
+    if from == to {
+        return Err(Error::BackendMigrationUnchanged(from));
+    }
+
+    let source_config = Config {
+        backend: from,
+        ..config.clone()
+    };
+    let target_config = Config {
+        backend: to,
+        ..config.clone()
+    };
+
+    let source_file = source_config
+        .database_file()
+        .ok_or(Error::SlasherDatabaseBackendDisabled)?;
+    let target_file = target_config
+        .database_file()
+        .ok_or(Error::SlasherDatabaseBackendDisabled)?;
+    if !source_file.exists() {
+        return Err(Error::BackendMigrationSourceMissing(source_file));
+    }
+    if target_file.exists() {
+        return Err(Error::BackendMigrationTargetExists(target_file));
+    }
+    let backup_dir = backup_dir(config, from);
+    if backup_dir.exists() {
+        return Err(Error::BackendMigrationTargetExists(backup_dir));
+    }
+
+    info!(
+        log,
+        "Migrating slasher database";
+        "from" => %from,
+        "to" => %to,
+        "path" => ?config.database_path,
+    );
+
+    let source_env = Environment::new(&source_config)?;
+    let target_env = Environment::new(&target_config)?;
+    let result = copy_databases(&source_env, &target_env, log);
+    let source_files = source_env.filenames(&source_config);
+    drop(source_env);
+
+    if let Err(e) = result {
+        // Remove the partial copy so that the migration can be retried.
+        for file in target_env.filenames(&target_config) {
+            let _ = fs::remove_file(file);
+        }
+        return Err(e);
+    }
+    drop(target_env);
+
+    // Keep the original database out of the way of the beacon node, which would otherwise use an
+    // MDBX database in preference to the new backend.
+    fs::create_dir(&backup_dir)?;
+    for file in source_files {
+        if let Some(file_name) = file.file_name().filter(|_| file.exists()) {
+            fs::rename(&file, backup_dir.join(file_name))?;
+        }
+    }
+
+    info!(
+        log,
+        "Slasher database migration complete";
+        "backend" => %to,
+        "backup" => ?backup_dir,
+        "msg" => "delete the backup with `slasher delete-backup` once the slasher has run \
+                  successfully with the new backend",
+    );
+    Ok(())
+}
+
+/// Delete the backup of the `backend` database left by `migrate_backend`.
+pub fn delete_backup(config: &Config, backend: DatabaseBackend, log: &Logger) -> Result<(), Error> {
+    let backup_dir = backup_dir(config, backend);
+    if !backup_dir.is_dir() {
+        return Err(Error::BackendMigrationSourceMissing(backup_dir));
+    }
+    fs::remove_dir_all(&backup_dir)?;
+    info!(log, "Deleted slasher database backup"; "backup" => ?backup_dir);
+    Ok(())
+}
+
+fn copy_databases(
+    source_env: &Environment,
+    target_env: &Environment,
+    log: &Logger,
+) -> Result<(), Error> {
+    let source_dbs = source_env.create_databases()?;
+    let target_dbs = target_env.create_databases()?;
+
+    for ((name, source_db), (_, target_db)) in source_dbs.all().into_iter().zip(target_dbs.all()) {
+        let source_entries = copy_database(source_env, source_db, target_env, target_db)?;
+        verify_database(name, source_env, source_db, target_env, target_db)?;
+        info!(
+            log,
+            "Migrated slasher table";
+            "table" => name,
+            "entries" => source_entries,
+        );
+    }
+    Ok(())
+}
+
+/// Copy every entry of `source_db` to `target_db`, returning the number of entries copied.
+fn copy_database(
+    source_env: &Environment,
+    source_db: &Database,
+    target_env: &Environment,
+    target_db: &Database,
+) -> Result<usize, Error> {
+    // The source transaction is only read from, and is aborted when dropped.
+    let mut source_txn = source_env.begin_rw_txn()?;
+    let mut cursor = source_txn.cursor(source_db)?;
+    let mut target_txn = target_env.begin_rw_txn()?;
+
+    let mut copied = 0;
+    let mut has_entry = cursor.first_key()?.is_some();
+    while has_entry {
+        let (key, value) = cursor.get_current()?.ok_or(Error::MissingKey)?;
+        target_txn.put(target_db, key, value)?;
+        copied += 1;
+
+        if copied % BATCH_SIZE == 0 {
+            target_txn.commit()?;
+            target_txn = target_env.begin_rw_txn()?;
+        }
+        has_entry = cursor.next_key()?.is_some();
+    }
+    target_txn.commit()?;
+
+    Ok(copied)
+}
+
+/// Check that `target_db` holds exactly the entries of `source_db`.
+fn verify_database(
+    name: &'static str,
+    source_env: &Environment,
+    source_db: &Database,
+    target_env: &Environment,
+    target_db: &Database,
+) -> Result<(), Error> {
+    // Both transactions are only read from, and are aborted when dropped.
+    let mut source_txn = source_env.begin_rw_txn()?;
+    let mut cursor = source_txn.cursor(source_db)?;
+    let target_txn = target_env.begin_rw_txn()?;
+
+    let mut source_entries = 0;
+    let mut has_entry = cursor.first_key()?.is_some();
+    while has_entry {
+        let (key, value) = cursor.get_current()?.ok_or(Error::MissingKey)?;
+        if target_txn.get(target_db, key.as_ref())?.as_deref() != Some(value.as_ref()) {
+            return Err(Error::BackendMigrationMismatch {
+                database: name,
+                key: key.to_vec(),
+            });
+        }
+        source_entries += 1;
+        has_entry = cursor.next_key()?.is_some();
+    }
+    drop(target_txn);
+
+    // Every source entry is in the target, so the copy is exact if it has no other entries.
+    let target_entries = count_entries(target_env, target_db)?;
+    if source_entries != target_entries {
+        return Err(Error::BackendMigrationIncomplete {
+            database: name,
+            source_entries,
+            target_entries,
+        });
+    }
+    Ok(())
+}
+
+fn count_entries(env: &Environment, db: &Database) -> Result<usize, Error> {
+    let mut txn = env.begin_rw_txn()?;
+    let mut cursor = txn.cursor(db)?;
+
+    let mut count = 0;
+    let mut has_entry = cursor.first_key()?.is_some();
+    while has_entry {
+        count += 1;
+        has_entry = cursor.next_key()?.is_some();
+    }
+    Ok(count)
+}
@@ -0,0 +1,394 @@
+//! Offline detection of slashable offences in attestations and blocks from external sources, such
+//! as an archive or another node's feed of orphaned blocks.
+//!
+//! The offences found are only reported. They are never broadcast or added to the op pool, so
+//! offences which are too old to be included in a block are still detected.
+//!
+//! The input is parsed one message at a time, so it doesn't have to fit in memory. Messages with
+//! invalid signatures are discarded, so that they can't be reported as evidence of an offence.
+use crate::{Config, Error, Slasher};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+use slog::{debug, info, Logger};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::io::Read;
+use std::marker::PhantomData;
+use std::sync::mpsc::{sync_channel, SyncSender};
+use std::sync::Arc;
+use tree_hash::TreeHash;
+use types::{
+    AttesterSlashing, ChainSpec, Domain, Epoch, EthSpec, Hash256, IndexedAttestation,
+    ProposerSlashing, PublicKey, PublicKeyBytes, SignedBeaconBlockHeader, SignedRoot,
+};
+
+/// The number of messages which are buffered and sorted by epoch before being processed.
+const BATCH_SIZE: usize = 65_536;
+
+/// The number of parsed messages which may be waiting to be processed.
+const CHANNEL_CAPACITY: usize = 1_024;
+
+/// Attestations and block headers to search for slashable offences.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct OfflineInput<E: EthSpec> {
+    #[serde(default)]
+    pub attestations: Vec<IndexedAttestation<E>>,
+    #[serde(default)]
+    pub block_headers: Vec<SignedBeaconBlockHeader>,
+}
+
+impl<E: EthSpec> OfflineInput<E> {
+    pub fn into_messages(self) -> impl Iterator<Item = Result<OfflineMessage<E>, Error>> {
+        self.attestations
+            .into_iter()
+            .map(OfflineMessage::Attestation)
+            .chain(
+                self.block_headers
+                    .into_iter()
+                    .map(OfflineMessage::BlockHeader),
+            )
+            .map(Ok)
+    }
+}
+
+/// A single message of an `OfflineInput`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OfflineMessage<E: EthSpec> {
+    Attestation(IndexedAttestation<E>),
+    BlockHeader(SignedBeaconBlockHeader),
+}
+
+impl<E: EthSpec> OfflineMessage<E> {
+    fn epoch(&self) -> Epoch {
+        match self {
+            Self::Attestation(attestation) => attestation.data().target.epoch,
+            Self::BlockHeader(block_header) => {
+                block_header.message.slot.epoch(E::slots_per_epoch())
+            }
+        }
+    }
+}
+
+/// The slashable offences found in an `OfflineInput`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct OfflineReport<E: EthSpec> {
+    pub num_attestations: usize,
+    pub num_block_headers: usize,
+    /// Messages which were discarded because their signatures are invalid.
+    pub num_invalid_signatures: usize,
+    /// Attestations which were discarded because they are more than the history length older than
+    /// a message which had already been processed.
+    pub num_too_old: usize,
+    /// The range of epochs covered by the input, or `None` if the input was empty.
+    pub epochs: Option<(Epoch, Epoch)>,
+    pub attester_slashings: Vec<AttesterSlashing<E>>,
+    pub proposer_slashings: Vec<ProposerSlashing>,
+}
+
+/// Parse the messages of a JSON `OfflineInput` from `reader` on a separate thread, yielding them
+/// one at a time rather than reading the whole input into memory.
+pub fn read_offline_input<E: EthSpec, R: Read + Send + 'static>(
+    reader: R,
+) -> impl Iterator<Item = Result<OfflineMessage<E>, Error>> {
+    let (tx, rx) = sync_channel(CHANNEL_CAPACITY);
+    std::thread::spawn(move || {
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let result = InputSeed::<E> {
+            tx: tx.clone(),
+            _phantom: PhantomData,
+        }
+        .deserialize(&mut deserializer)
+        .and_then(|()| deserializer.end());
+        // The error is dropped if the receiver has already given up on the input.
+        if let Err(e) = result {
+            let _ = tx.send(Err(Error::OfflineInputInvalid(e.to_string())));
+        }
+    });
+    rx.into_iter()
+}
+
+/// Deserializes an `OfflineInput`, sending each of its messages to `tx`.
+struct InputSeed<E: EthSpec> {
+    tx: SyncSender<Result<OfflineMessage<E>, Error>>,
+    _phantom: PhantomData<E>,
+}
+
+impl<'de, E: EthSpec> DeserializeSeed<'de> for InputSeed<E> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, E: EthSpec> Visitor<'de> for InputSeed<E> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an object with lists of attestations and block headers")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "attestations" => map.next_value_seed(MessagesSeed {
+                    tx: &self.tx,
+                    into_message: OfflineMessage::Attestation,
+                })?,
+                "block_headers" => map.next_value_seed(MessagesSeed {
+                    tx: &self.tx,
+                    into_message: OfflineMessage::BlockHeader,
+                })?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes a list of messages of type `T`, sending each of them to `tx`.
+struct MessagesSeed<'a, E: EthSpec, T> {
+    tx: &'a SyncSender<Result<OfflineMessage<E>, Error>>,
+    into_message: fn(T) -> OfflineMessage<E>,
+}
+
+impl<'de, E: EthSpec, T: Deserialize<'de>> DeserializeSeed<'de> for MessagesSeed<'_, E, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, E: EthSpec, T: Deserialize<'de>> Visitor<'de> for MessagesSeed<'_, E, T> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of messages")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(message) = seq.next_element::<T>()? {
+            self.tx
+                .send(Ok((self.into_message)(message)))
+                .map_err(|_| de::Error::custom("input no longer required"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the signatures of messages against the public keys of the validators.
+pub struct SignatureVerifier {
+    /// The public keys of the validators, by validator index.
+    pubkeys: Vec<PublicKeyBytes>,
+    /// Public keys which have already been decompressed, to avoid decompressing every key up front.
+    decompressed: HashMap<u64, PublicKey>,
+    genesis_validators_root: Hash256,
+    spec: Arc<ChainSpec>,
+}
+
+impl SignatureVerifier {
+    pub fn new(
+        pubkeys: Vec<PublicKeyBytes>,
+        genesis_validators_root: Hash256,
+        spec: Arc<ChainSpec>,
+    ) -> Self {
+        Self {
+            pubkeys,
+            decompressed: HashMap::new(),
+            genesis_validators_root,
+            spec,
+        }
+    }
+
+    fn pubkey(&mut self, validator_index: u64) -> Option<PublicKey> {
+        if let Some(pubkey) = self.decompressed.get(&validator_index) {
+            return Some(pubkey.clone());
+        }
+        let pubkey = self
+            .pubkeys
+            .get(validator_index as usize)?
+            .decompress()
+            .ok()?;
+        self.decompressed.insert(validator_index, pubkey.clone());
+        Some(pubkey)
+    }
+
+    /// Returns `true` if the signature of `message` is valid.
+    pub fn verify<E: EthSpec>(&mut self, message: &OfflineMessage<E>) -> bool {
+        let epoch = message.epoch();
+        let fork = self.spec.fork_at_epoch(epoch);
+        match message {
+            OfflineMessage::Attestation(attestation) => {
+                let Some(pubkeys) = attestation
+                    .attesting_indices_iter()
+                    .map(|index| self.pubkey(*index))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return false;
+                };
+                let domain = self.spec.get_domain(
+                    epoch,
+                    Domain::BeaconAttester,
+                    &fork,
+                    self.genesis_validators_root,
+                );
+                let message = attestation.data().signing_root(domain);
+                attestation
+                    .signature()
+                    .fast_aggregate_verify(message, &pubkeys.iter().collect::<Vec<_>>())
+            }
+            OfflineMessage::BlockHeader(block_header) => {
+                let Some(pubkey) = self.pubkey(block_header.message.proposer_index) else {
+                    return false;
+                };
+                block_header.verify_signature::<E>(
+                    &pubkey,
+                    &fork,
+                    self.genesis_validators_root,
+                    &self.spec,
+                )
+            }
+        }
+    }
+}
+
+/// Find all slashable offences in `messages` using a new slasher database at
+/// `config.database_path`.
+///
+/// The messages are processed in batches, each sorted by epoch, as if they had been received live.
+/// Offences are only detected between messages less than `config.history_length` epochs apart,
+/// and attestations older than that relative to an earlier batch are discarded, so input ordered
+/// by epoch is searched most thoroughly. Messages are discarded if their signatures are invalid,
+/// unless no `verifier` is provided. The database must not already exist, to avoid modifying the
+/// database of a running slasher.
+pub fn detect_offline<E: EthSpec>(
+    config: Config,
+    spec: Arc<ChainSpec>,
+    messages: impl IntoIterator<Item = Result<OfflineMessage<E>, Error>>,
+    mut verifier: Option<SignatureVerifier>,
+    log: Logger,
+) -> Result<OfflineReport<E>, Error> {
+    if let Some(file) = config.database_file() {
+        if file.exists() {
+            return Err(Error::OfflineDatabaseExists(file));
+        }
+    }
+
+    info!(
+        log,
+        "Searching for slashable offences";
+        "verify_signatures" => verifier.is_some(),
+    );
+
+    let history_length = config.history_length as u64;
+    let slasher = Slasher::<E>::open(config, spec, log.clone())?;
+
+    let mut num_attestations = 0;
+    let mut num_block_headers = 0;
+    let mut num_invalid_signatures = 0;
+    let mut num_too_old = 0;
+    let mut epochs: Option<(Epoch, Epoch)> = None;
+    // The latest epoch processed, which the slasher must never move back from.
+    let mut current_epoch: Option<Epoch> = None;
+
+    let mut batch = BTreeMap::<Epoch, Vec<OfflineMessage<E>>>::new();
+    let mut batch_len = 0;
+    let mut messages = messages.into_iter().peekable();
+    while let Some(message) = messages.next() {
+        let message = message?;
+        match &message {
+            OfflineMessage::Attestation(_) => num_attestations += 1,
+            OfflineMessage::BlockHeader(_) => num_block_headers += 1,
+        }
+
+        if verifier
+            .as_mut()
+            .is_some_and(|verifier| !verifier.verify(&message))
+        {
+            num_invalid_signatures += 1;
+        } else {
+            let epoch = message.epoch();
+            epochs = Some(epochs.map_or((epoch, epoch), |(low, high)| {
+                (low.min(epoch), high.max(epoch))
+            }));
+            batch.entry(epoch).or_default().push(message);
+            batch_len += 1;
+        }
+
+        if batch_len < BATCH_SIZE && messages.peek().is_some() {
+            continue;
+        }
+
+        debug!(log, "Processing batch of offline messages"; "messages" => batch_len);
+        for (epoch, messages) in std::mem::take(&mut batch) {
+            let epoch = current_epoch.map_or(epoch, |current| current.max(epoch));
+            for message in messages {
+                match message {
+                    OfflineMessage::Attestation(attestation) => {
+                        // The slasher would silently drop this attestation.
+                        if attestation.data().source.epoch + history_length <= epoch {
+                            num_too_old += 1;
+                            continue;
+                        }
+                        slasher.accept_attestation(attestation);
+                    }
+                    OfflineMessage::BlockHeader(block_header) => {
+                        slasher.accept_block_header(block_header);
+                    }
+                }
+            }
+            slasher.process_queued(epoch)?;
+            slasher.prune_database(epoch)?;
+            current_epoch = Some(epoch);
+        }
+        batch_len = 0;
+    }
+
+    let mut attester_slashings = slasher
+        .get_attester_slashings()
+        .into_iter()
+        .collect::<Vec<_>>();
+    attester_slashings.sort_by_key(|slashing| {
+        (
+            slashing.attestation_1().data().target.epoch,
+            slashing.tree_hash_root(),
+        )
+    });
+    let mut proposer_slashings = slasher
+        .get_proposer_slashings()
+        .into_iter()
+        .collect::<Vec<_>>();
+    proposer_slashings.sort_by_key(|slashing| {
+        (
+            slashing.signed_header_1.message.slot,
+            slashing.signed_header_1.message.proposer_index,
+        )
+    });
+
+    info!(
+        log,
+        "Finished searching for slashable offences";
+        "attestations" => num_attestations,
+        "block_headers" => num_block_headers,
+        "invalid_signatures" => num_invalid_signatures,
+        "too_old" => num_too_old,
+        "epochs" => ?epochs,
+        "attester_slashings" => attester_slashings.len(),
+        "proposer_slashings" => proposer_slashings.len(),
+    );
+
+    Ok(OfflineReport {
+        num_attestations,
+        num_block_headers,
+        num_invalid_signatures,
+        num_too_old,
+        epochs,
+        attester_slashings,
+        proposer_slashings,
+    })
+}
@@ -1,17 +1,21 @@
 use crate::batch_stats::{AttestationStats, BatchStats, BlockStats};
 use crate::metrics::{
-    self, SLASHER_NUM_ATTESTATIONS_DEFERRED, SLASHER_NUM_ATTESTATIONS_DROPPED,
+    self, SLASHER_BATCH_SIZE, SLASHER_DETECTION_LAG, SLASHER_NUM_ATTESTATIONS_BACKLOG,
+    SLASHER_NUM_ATTESTATIONS_DEFERRED, SLASHER_NUM_ATTESTATIONS_DROPPED,
     SLASHER_NUM_ATTESTATIONS_STORED_PER_BATCH, SLASHER_NUM_ATTESTATIONS_VALID,
-    SLASHER_NUM_BLOCKS_PROCESSED,
+    SLASHER_NUM_BLOCKS_PROCESSED, SLASHER_UPDATE_PERIOD,
 };
 use crate::{
-    array, AttestationBatch, AttestationQueue, AttesterRecord, BlockQueue, Config, Error,
-    IndexedAttestationId, ProposerSlashingStatus, RwTransaction, SimpleBatch, SlasherDB,
+    array, AttestationBatch, AttestationQueue, AttesterRecord, Autotuner, BlockQueue, Config,
+    Error, IndexedAttestationId, IndexedAttesterRecord, ProposerSlashingStatus, RwTransaction,
+    SimpleBatch, SlasherDB, WatchAlert, WatchAlertEvidence, WatchAlertKind, WatchList,
+    WatchedValidator,
 };
 use parking_lot::Mutex;
-use slog::{debug, error, info, Logger};
+use slog::{debug, error, info, warn, Logger};
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use types::{
     AttesterSlashing, ChainSpec, Epoch, EthSpec, IndexedAttestation, ProposerSlashing,
     SignedBeaconBlockHeader,
@@ -24,6 +28,9 @@ pub struct Slasher<E: EthSpec> {
     block_queue: BlockQueue,
     attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
     proposer_slashings: Mutex<HashSet<ProposerSlashing>>,
+    /// Adapts the batch size and update period to the load, if enabled.
+    autotuner: Option<Mutex<Autotuner>>,
+    watch_list: Mutex<WatchList>,
     config: Arc<Config>,
     log: Logger,
 }
@@ -50,12 +57,33 @@ impl<E: EthSpec> Slasher<E> {
         let proposer_slashings = Mutex::new(HashSet::new());
         let attestation_queue = AttestationQueue::default();
         let block_queue = BlockQueue::default();
+        let autotuner = config
+            .autotune
+            .then(|| Mutex::new(Autotuner::new(Duration::from_secs(config.update_period))));
+
+        let mut txn = db.begin_rw_txn()?;
+        let watch_list = Mutex::new(WatchList {
+            indices: config
+                .watch_list
+                .iter()
+                .filter_map(|validator| match validator {
+                    WatchedValidator::Index(index) => Some(*index),
+                    WatchedValidator::Pubkey(_) => None,
+                })
+                .collect(),
+            history: db.load_watched_history(&mut txn)?,
+            alerts: vec![],
+        });
+        txn.commit()?;
+
         Ok(Self {
             db,
             attestation_queue,
             block_queue,
             attester_slashings,
             proposer_slashings,
+            autotuner,
+            watch_list,
             config,
             log,
         })
@@ -76,6 +104,16 @@ impl<E: EthSpec> Slasher<E> {
         std::mem::take(&mut self.proposer_slashings.lock())
     }
 
+    /// Harvest all alerts raised for watched validators, removing them from the slasher.
+    pub fn get_watch_alerts(&self) -> Vec<WatchAlert> {
+        std::mem::take(&mut self.watch_list.lock().alerts)
+    }
+
+    /// Add validators to the watch list, e.g. once the index of a watched pubkey is known.
+    pub fn watch_validators(&self, validator_indices: impl IntoIterator<Item = u64>) {
+        self.watch_list.lock().indices.extend(validator_indices);
+    }
+
     pub fn config(&self) -> &Config {
         &self.config
     }
@@ -84,6 +122,14 @@ impl<E: EthSpec> Slasher<E> {
         &self.log
     }
 
+    /// The period to wait before processing the next batch.
+    pub fn update_period(&self) -> Duration {
+        self.autotuner.as_ref().map_or_else(
+            || Duration::from_secs(self.config.update_period),
+            |autotuner| autotuner.lock().update_period(),
+        )
+    }
+
     /// Accept an attestation from the network and queue it for processing.
     pub fn accept_attestation(&self, attestation: IndexedAttestation<E>) {
         self.attestation_queue.queue(attestation);
@@ -95,11 +141,37 @@ impl<E: EthSpec> Slasher<E> {
     }
 
     /// Apply queued blocks and attestations to the on-disk database, and detect slashings!
+    ///
+    /// If autotuning is enabled then only part of the queued attestations may be processed.
     pub fn process_queued(&self, current_epoch: Epoch) -> Result<BatchStats, Error> {
+        let t = Instant::now();
+        let batch_size = self
+            .autotuner
+            .as_ref()
+            .and_then(|autotuner| autotuner.lock().batch_size());
+
         let mut txn = self.db.begin_rw_txn()?;
         let block_stats = self.process_blocks(&mut txn)?;
-        let attestation_stats = self.process_attestations(current_epoch, &mut txn)?;
+        let attestation_stats = self.process_attestations(current_epoch, batch_size, &mut txn)?;
         txn.commit()?;
+
+        if let Some(autotuner) = &self.autotuner {
+            let mut autotuner = autotuner.lock();
+            autotuner.observe(
+                attestation_stats.num_processed,
+                attestation_stats.num_remaining,
+                t.elapsed(),
+            );
+            metrics::set_gauge(
+                &SLASHER_BATCH_SIZE,
+                autotuner.batch_size().unwrap_or(0) as i64,
+            );
+            metrics::set_float_gauge(
+                &SLASHER_UPDATE_PERIOD,
+                autotuner.update_period().as_secs_f64(),
+            );
+        }
+
         Ok(BatchStats {
             block_stats,
             attestation_stats,
@@ -120,6 +192,21 @@ impl<E: EthSpec> Slasher<E> {
             if let ProposerSlashingStatus::DoubleVote(slashing) =
                 self.db.check_or_insert_block_proposal(txn, block)?
             {
+                let proposer_index = slashing.signed_header_1.message.proposer_index;
+                let mut watch_list = self.watch_list.lock();
+                if watch_list.indices.contains(&proposer_index) {
+                    self.raise_watch_alert(
+                        &mut watch_list,
+                        proposer_index,
+                        WatchAlertKind::DoubleProposal,
+                        WatchAlertEvidence::Proposals {
+                            slot: slashing.signed_header_1.message.slot,
+                            previous_block_root: slashing.signed_header_1.message.canonical_root(),
+                            new_block_root: slashing.signed_header_2.message.canonical_root(),
+                        },
+                    );
+                }
+                drop(watch_list);
                 slashings.push(*slashing);
             }
         }
@@ -141,20 +228,40 @@ impl<E: EthSpec> Slasher<E> {
     }
 
     /// Apply queued attestations to the on-disk database.
+    ///
+    /// At most `batch_size` attestations are dequeued, if provided.
     pub fn process_attestations(
         &self,
         current_epoch: Epoch,
+        batch_size: Option<usize>,
         txn: &mut RwTransaction<'_>,
     ) -> Result<AttestationStats, Error> {
-        let snapshot = self.attestation_queue.dequeue();
+        let (snapshot, num_remaining) = match batch_size {
+            Some(batch_size) => self.attestation_queue.dequeue_up_to(batch_size),
+            None => (self.attestation_queue.dequeue(), 0),
+        };
         let num_processed = snapshot.len();
 
         // Filter attestations for relevance.
-        let (snapshot, deferred, num_dropped) = self.validate(snapshot, current_epoch);
+        let (snapshot, deferred, too_old, num_invalid) = self.validate(snapshot, current_epoch);
         let num_valid = snapshot.len();
         let num_deferred = deferred.len();
+        let num_dropped = too_old.len() + num_invalid;
         self.attestation_queue.requeue(deferred);
 
+        // Attestations which are too old for the slasher are still relevant to watched validators.
+        self.check_watched_attestations(txn, snapshot.iter().chain(&too_old))?;
+
+        let detection_lag = snapshot
+            .iter()
+            .map(|indexed_record| {
+                current_epoch
+                    .saturating_sub(indexed_record.indexed.data().target.epoch)
+                    .as_u64()
+            })
+            .max()
+            .unwrap_or(0);
+
         debug!(
             self.log,
             "Pre-processing attestations for slasher";
@@ -165,6 +272,8 @@ impl<E: EthSpec> Slasher<E> {
         metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_VALID, num_valid as i64);
         metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_DEFERRED, num_deferred as i64);
         metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_DROPPED, num_dropped as i64);
+        metrics::set_gauge(&SLASHER_NUM_ATTESTATIONS_BACKLOG, num_remaining as i64);
+        metrics::set_gauge(&SLASHER_DETECTION_LAG, detection_lag as i64);
 
         // De-duplicate attestations and sort by validator index.
         let mut batch = AttestationBatch::default();
@@ -216,7 +325,104 @@ impl<E: EthSpec> Slasher<E> {
             self.db.attestation_root_cache_size() as i64,
         );
 
-        Ok(AttestationStats { num_processed })
+        Ok(AttestationStats {
+            num_processed,
+            num_remaining,
+            detection_lag,
+        })
+    }
+
+    /// Check the attestations of watched validators against their full history, and store them.
+    fn check_watched_attestations<'a>(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        indexed_records: impl Iterator<Item = &'a Arc<IndexedAttesterRecord<E>>>,
+    ) -> Result<(), Error> {
+        let mut watch_list = self.watch_list.lock();
+        if watch_list.indices.is_empty() {
+            return Ok(());
+        }
+
+        for indexed_record in indexed_records {
+            let watched_indices = indexed_record
+                .indexed
+                .attesting_indices_iter()
+                .copied()
+                .filter(|validator_index| watch_list.indices.contains(validator_index))
+                .collect::<Vec<_>>();
+            for validator_index in watched_indices {
+                self.check_watched_attestation(
+                    txn,
+                    &mut watch_list,
+                    validator_index,
+                    indexed_record,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn check_watched_attestation(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        watch_list: &mut WatchList,
+        validator_index: u64,
+        indexed_record: &IndexedAttesterRecord<E>,
+    ) -> Result<(), Error> {
+        let data = indexed_record.indexed.data();
+        let (conflicts, is_new) = watch_list.history.check_and_insert(
+            validator_index,
+            data,
+            indexed_record.record.attestation_data_hash,
+        );
+
+        for (kind, target_epoch) in conflicts {
+            let previous = self
+                .db
+                .get_watched_attestation(txn, validator_index, target_epoch)?
+                .ok_or(Error::MissingWatchedAttestation {
+                    validator_index,
+                    target_epoch,
+                })?;
+            self.raise_watch_alert(
+                watch_list,
+                validator_index,
+                kind,
+                WatchAlertEvidence::Attestations {
+                    previous: Box::new(previous.data().clone()),
+                    new: Box::new(data.clone()),
+                },
+            );
+        }
+
+        if is_new {
+            self.db
+                .store_watched_attestation(txn, validator_index, &indexed_record.indexed)?;
+        }
+        Ok(())
+    }
+
+    fn raise_watch_alert(
+        &self,
+        watch_list: &mut WatchList,
+        validator_index: u64,
+        kind: WatchAlertKind,
+        evidence: WatchAlertEvidence,
+    ) {
+        let kind_str: &'static str = kind.into();
+        warn!(
+            self.log,
+            "Watched validator equivocated";
+            "validator_index" => validator_index,
+            "kind" => kind_str,
+            "evidence" => ?evidence,
+        );
+        metrics::inc_counter_vec(&metrics::SLASHER_WATCH_ALERTS, &[kind_str]);
+        watch_list.alerts.push(WatchAlert {
+            validator_index,
+            kind,
+            evidence,
+        });
     }
 
     /// Process a batch of attestations for a range of validator indices.
@@ -329,28 +535,31 @@ impl<E: EthSpec> Slasher<E> {
 
     /// Validate the attestations in `batch` for ingestion during `current_epoch`.
     ///
-    /// Drop any attestations that are too old to ever be relevant, and return any attestations
-    /// that might be valid in the future.
+    /// Drop any attestations that are invalid or too old to ever be relevant, and return any
+    /// attestations that might be valid in the future.
     ///
-    /// Returns `(valid, deferred, num_dropped)`.
+    /// Returns `(valid, deferred, too_old, num_invalid)`.
     fn validate(
         &self,
         batch: SimpleBatch<E>,
         current_epoch: Epoch,
-    ) -> (SimpleBatch<E>, SimpleBatch<E>, usize) {
+    ) -> (SimpleBatch<E>, SimpleBatch<E>, SimpleBatch<E>, usize) {
         let mut keep = Vec::with_capacity(batch.len());
         let mut defer = vec![];
-        let mut drop_count = 0;
+        let mut too_old = vec![];
+        let mut invalid_count = 0;
 
         for indexed_record in batch {
             let attestation = &indexed_record.indexed;
             let target_epoch = attestation.data().target.epoch;
             let source_epoch = attestation.data().source.epoch;
 
-            if source_epoch > target_epoch
-                || source_epoch + self.config.history_length as u64 <= current_epoch
-            {
-                drop_count += 1;
+            if source_epoch > target_epoch {
+                invalid_count += 1;
+                continue;
+            }
+            if source_epoch + self.config.history_length as u64 <= current_epoch {
+                too_old.push(indexed_record);
                 continue;
             }
 
@@ -364,7 +573,7 @@ impl<E: EthSpec> Slasher<E> {
             }
         }
 
-        (keep, defer, drop_count)
+        (keep, defer, too_old, invalid_count)
     }
 
     /// Prune unnecessary attestations and blocks from the on-disk database.
@@ -0,0 +1,217 @@
+//! Watch list of validators for which the slasher retains attestations indefinitely and raises
+//! alerts on equivocations, regardless of the history length.
+//!
+//! The attestations of watched validators are stored in a separate table which is never pruned,
+//! and a compact summary of them is kept in memory so that they can be checked quickly.
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::str::FromStr;
+use strum::IntoStaticStr;
+use types::{AttestationData, Epoch, Hash256, PublicKeyBytes, Slot};
+
+/// A validator on the watch list, identified by index or by public key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WatchedValidator {
+    Index(u64),
+    Pubkey(PublicKeyBytes),
+}
+
+impl FromStr for WatchedValidator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with("0x") {
+            PublicKeyBytes::from_str(s)
+                .map(Self::Pubkey)
+                .map_err(|e| format!("Invalid validator pubkey {}: {}", s, e))
+        } else {
+            s.parse()
+                .map(Self::Index)
+                .map_err(|e| format!("Invalid validator index {}: {}", s, e))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum WatchAlertKind {
+    /// Two different attestations with the same target epoch.
+    DoubleVote,
+    /// An attestation which surrounds an earlier attestation.
+    SurroundingVote,
+    /// An attestation which is surrounded by an earlier attestation.
+    SurroundedVote,
+    /// Two different blocks at the same slot.
+    DoubleProposal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchAlertEvidence {
+    Attestations {
+        previous: Box<AttestationData>,
+        new: Box<AttestationData>,
+    },
+    Proposals {
+        slot: Slot,
+        previous_block_root: Hash256,
+        new_block_root: Hash256,
+    },
+}
+
+/// Equivocation-adjacent behaviour of a watched validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchAlert {
+    pub validator_index: u64,
+    pub kind: WatchAlertKind,
+    pub evidence: WatchAlertEvidence,
+}
+
+/// Summary of the attestations of watched validators, indexed by target epoch.
+#[derive(Debug, Default)]
+pub struct WatchedHistory {
+    /// Map from `validator_index` to `target_epoch` to `(source_epoch, attestation_data_root)`.
+    records: HashMap<u64, BTreeMap<Epoch, (Epoch, Hash256)>>,
+}
+
+impl WatchedHistory {
+    /// Record an attestation which was previously stored without checking it.
+    pub fn insert(&mut self, validator_index: u64, source: Epoch, target: Epoch, root: Hash256) {
+        self.records
+            .entry(validator_index)
+            .or_default()
+            .insert(target, (source, root));
+    }
+
+    /// Check an attestation by `validator_index` against the validator's history.
+    ///
+    /// Returns the kind of each conflict and the target epoch of the conflicting attestation,
+    /// along with whether the attestation is new and should be stored. Only the first attestation
+    /// for each target epoch is recorded.
+    pub fn check_and_insert(
+        &mut self,
+        validator_index: u64,
+        data: &AttestationData,
+        data_root: Hash256,
+    ) -> (Vec<(WatchAlertKind, Epoch)>, bool) {
+        let source = data.source.epoch;
+        let target = data.target.epoch;
+        let history = self.records.entry(validator_index).or_default();
+        let mut conflicts = vec![];
+
+        if let Some((_, existing_root)) = history.get(&target) {
+            if *existing_root != data_root {
+                conflicts.push((WatchAlertKind::DoubleVote, target));
+            }
+        }
+
+        // Earlier attestations with a later target and an earlier source surround this one.
+        conflicts.extend(
+            history
+                .range(target + 1..)
+                .filter(|(_, (existing_source, _))| *existing_source < source)
+                .map(|(existing_target, _)| (WatchAlertKind::SurroundedVote, *existing_target)),
+        );
+        // Earlier attestations within the source and target of this one are surrounded by it.
+        if source + 1 < target {
+            conflicts.extend(
+                history
+                    .range(source + 1..target)
+                    .filter(|(_, (existing_source, _))| *existing_source > source)
+                    .map(|(existing_target, _)| {
+                        (WatchAlertKind::SurroundingVote, *existing_target)
+                    }),
+            );
+        }
+
+        let is_new = !history.contains_key(&target);
+        if is_new {
+            history.insert(target, (source, data_root));
+        }
+        (conflicts, is_new)
+    }
+}
+
+/// The watched validators, and the state required to check their messages.
+#[derive(Debug, Default)]
+pub struct WatchList {
+    pub(crate) indices: HashSet<u64>,
+    pub(crate) history: WatchedHistory,
+    pub(crate) alerts: Vec<WatchAlert>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use types::Checkpoint;
+
+    fn data(source: u64, target: u64, root: u64) -> (AttestationData, Hash256) {
+        let data = AttestationData {
+            slot: Slot::new(0),
+            index: 0,
+            beacon_block_root: Hash256::from_low_u64_be(root),
+            source: Checkpoint {
+                epoch: Epoch::new(source),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target),
+                root: Hash256::zero(),
+            },
+        };
+        (data, Hash256::from_low_u64_be(root))
+    }
+
+    fn check(
+        history: &mut WatchedHistory,
+        source: u64,
+        target: u64,
+        root: u64,
+    ) -> Vec<(WatchAlertKind, u64)> {
+        let (data, data_root) = data(source, target, root);
+        history
+            .check_and_insert(0, &data, data_root)
+            .0
+            .into_iter()
+            .map(|(kind, epoch)| (kind, epoch.as_u64()))
+            .collect()
+    }
+
+    #[test]
+    fn double_vote() {
+        let mut history = WatchedHistory::default();
+        assert_eq!(check(&mut history, 1, 2, 0), vec![]);
+        assert_eq!(check(&mut history, 1, 2, 0), vec![]);
+        assert_eq!(
+            check(&mut history, 1, 2, 1),
+            vec![(WatchAlertKind::DoubleVote, 2)]
+        );
+    }
+
+    #[test]
+    fn surround_votes() {
+        let mut history = WatchedHistory::default();
+        assert_eq!(check(&mut history, 10, 11, 0), vec![]);
+        assert_eq!(
+            check(&mut history, 1, 100_000, 0),
+            vec![(WatchAlertKind::SurroundingVote, 11)]
+        );
+        assert_eq!(
+            check(&mut history, 50_000, 50_001, 0),
+            vec![(WatchAlertKind::SurroundedVote, 100_000)]
+        );
+        // Other validators are unaffected.
+        let (data, data_root) = data(50, 51, 0);
+        assert!(history.check_and_insert(1, &data, data_root).0.is_empty());
+    }
+
+    #[test]
+    fn parse_watched_validator() {
+        assert_eq!("5".parse(), Ok(WatchedValidator::Index(5)));
+        assert!(matches!(
+            format!("0x{}", "aa".repeat(48)).parse::<WatchedValidator>(),
+            Ok(WatchedValidator::Pubkey(_))
+        ));
+        assert!("0x01".parse::<WatchedValidator>().is_err());
+        assert!("abc".parse::<WatchedValidator>().is_err());
+    }
+}
@@ -0,0 +1,42 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use slasher::{
+    test_utils::{chain_spec, indexed_att, E},
+    Config, Slasher,
+};
+use tempfile::tempdir;
+use types::Epoch;
+
+#[test]
+fn autotuned_batch_size() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.autotune = true;
+    // Every batch takes longer than half of the update period, so the batch size is halved.
+    config.update_period = 0;
+    let slasher = Slasher::<E>::open(config, chain_spec(), test_logger()).unwrap();
+    let current_epoch = Epoch::new(3);
+
+    for validator_index in 0..10_000 {
+        slasher.accept_attestation(indexed_att([validator_index], 0, 1, 0));
+    }
+    let stats = slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(stats.attestation_stats.num_processed, 10_000);
+    assert_eq!(stats.attestation_stats.num_remaining, 0);
+    assert_eq!(stats.attestation_stats.detection_lag, 2);
+
+    for validator_index in 0..10_000 {
+        slasher.accept_attestation(indexed_att([validator_index], 1, 2, 0));
+    }
+    let stats = slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(stats.attestation_stats.num_processed, 5_000);
+    assert_eq!(stats.attestation_stats.num_remaining, 5_000);
+    assert_eq!(stats.attestation_stats.detection_lag, 1);
+
+    // The batch size never drops below the minimum.
+    let stats = slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(stats.attestation_stats.num_processed, 4_096);
+    assert_eq!(stats.attestation_stats.num_remaining, 904);
+    assert!(slasher.get_attester_slashings().is_empty());
+}
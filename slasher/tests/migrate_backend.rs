@@ -0,0 +1,92 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use slasher::{
+    backup_dir, delete_backup, migrate_backend, Config, DatabaseBackend, Environment, Error,
+};
+use tempfile::tempdir;
+
+#[test]
+fn migrate_to_same_backend() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    assert!(matches!(
+        migrate_backend(&config, config.backend, config.backend, &test_logger()),
+        Err(Error::BackendMigrationUnchanged(_))
+    ));
+}
+
+#[test]
+#[cfg(all(feature = "lmdb", feature = "redb"))]
+fn migrate_missing_source() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    assert!(matches!(
+        migrate_backend(
+            &config,
+            DatabaseBackend::Lmdb,
+            DatabaseBackend::Redb,
+            &test_logger()
+        ),
+        Err(Error::BackendMigrationSourceMissing(_))
+    ));
+}
+
+#[test]
+#[cfg(all(feature = "lmdb", feature = "redb"))]
+fn migrate_lmdb_to_redb() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.backend = DatabaseBackend::Lmdb;
+
+    let entries = (0..1000u64)
+        .map(|i| (i.to_be_bytes(), (i * 2).to_le_bytes()))
+        .collect::<Vec<_>>();
+
+    {
+        let env = Environment::new(&config).unwrap();
+        let dbs = env.create_databases().unwrap();
+        let mut txn = env.begin_rw_txn().unwrap();
+        for (key, value) in &entries {
+            txn.put(&dbs.attesters_db, key, value).unwrap();
+        }
+        txn.put(&dbs.metadata_db, [0u8], [3u8]).unwrap();
+        txn.commit().unwrap();
+    }
+
+    migrate_backend(
+        &config,
+        DatabaseBackend::Lmdb,
+        DatabaseBackend::Redb,
+        &test_logger(),
+    )
+    .unwrap();
+    // The original database is kept in the backup directory.
+    let lmdb_file = config.database_file().unwrap();
+    let backup_dir = backup_dir(&config, DatabaseBackend::Lmdb);
+    assert!(!lmdb_file.exists());
+    assert!(backup_dir.join(lmdb_file.file_name().unwrap()).exists());
+
+    config.backend = DatabaseBackend::Redb;
+    let env = Environment::new(&config).unwrap();
+    let dbs = env.create_databases().unwrap();
+    let txn = env.begin_rw_txn().unwrap();
+    for (key, value) in &entries {
+        assert_eq!(
+            txn.get(&dbs.attesters_db, key).unwrap().unwrap().as_ref(),
+            value
+        );
+    }
+    assert_eq!(
+        txn.get(&dbs.metadata_db, &[0u8]).unwrap().unwrap().as_ref(),
+        [3]
+    );
+    assert_eq!(txn.get(&dbs.proposers_db, &[0u8]).unwrap(), None);
+
+    delete_backup(&config, DatabaseBackend::Lmdb, &test_logger()).unwrap();
+    assert!(!backup_dir.exists());
+    assert!(matches!(
+        delete_backup(&config, DatabaseBackend::Lmdb, &test_logger()),
+        Err(Error::BackendMigrationSourceMissing(_))
+    ));
+}
@@ -0,0 +1,223 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use maplit::hashset;
+use slasher::{
+    detect_offline, read_offline_input,
+    test_utils::{block, chain_spec, indexed_att, slashed_validators_from_slashings, E},
+    Config, Error, OfflineInput, SignatureVerifier, Slasher,
+};
+use std::io::Cursor;
+use tempfile::tempdir;
+use types::{
+    test_utils::generate_deterministic_keypairs, AggregateSignature, Domain, Epoch, Hash256,
+    IndexedAttestation, SignedRoot,
+};
+
+#[test]
+fn offline_detection() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    // Supply the input out of order: it should be processed by epoch.
+    let input = OfflineInput::<E> {
+        attestations: vec![
+            indexed_att([1], 1, 10, 0),
+            indexed_att([0], 2, 3, 1),
+            indexed_att([1], 2, 9, 0),
+            indexed_att([0], 2, 3, 2),
+            indexed_att([2], 4, 5, 0),
+        ],
+        block_headers: vec![block(70, 3, 1), block(5, 4, 0), block(70, 3, 2)],
+    };
+
+    let report = detect_offline(
+        config,
+        chain_spec(),
+        input.into_messages(),
+        None,
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(report.num_attestations, 5);
+    assert_eq!(report.num_block_headers, 3);
+    assert_eq!(report.epochs, Some((Epoch::new(0), Epoch::new(10))));
+
+    let attester_slashings = report.attester_slashings.into_iter().collect();
+    assert_eq!(
+        slashed_validators_from_slashings(&attester_slashings),
+        hashset! {0, 1}
+    );
+    assert_eq!(report.proposer_slashings.len(), 1);
+    assert_eq!(
+        report.proposer_slashings[0]
+            .signed_header_1
+            .message
+            .proposer_index,
+        3
+    );
+}
+
+#[test]
+fn offline_detection_empty() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let report = detect_offline(
+        config,
+        chain_spec(),
+        OfflineInput::<E>::default().into_messages(),
+        None,
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(report.epochs, None);
+    assert!(report.attester_slashings.is_empty());
+    assert!(report.proposer_slashings.is_empty());
+}
+
+#[test]
+fn offline_detection_existing_database() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let slasher = Slasher::<E>::open(config.clone(), chain_spec(), test_logger()).unwrap();
+    drop(slasher);
+
+    assert!(matches!(
+        detect_offline(
+            config,
+            chain_spec(),
+            OfflineInput::<E>::default().into_messages(),
+            None,
+            test_logger()
+        ),
+        Err(Error::OfflineDatabaseExists(_))
+    ));
+}
+
+#[test]
+fn offline_detection_streamed_input() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let input = OfflineInput::<E> {
+        attestations: vec![indexed_att([0], 2, 3, 1), indexed_att([0], 2, 3, 2)],
+        block_headers: vec![block(70, 3, 1), block(70, 3, 2)],
+    };
+    let json = serde_json::to_vec(&input).unwrap();
+
+    let report = detect_offline(
+        config,
+        chain_spec(),
+        read_offline_input::<E, _>(Cursor::new(json)),
+        None,
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(report.num_attestations, 2);
+    assert_eq!(report.num_block_headers, 2);
+    assert_eq!(report.attester_slashings.len(), 1);
+    assert_eq!(report.proposer_slashings.len(), 1);
+}
+
+#[test]
+fn offline_detection_invalid_input() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+
+    let json = br#"{"attestations": [], "block_headers": [{"message": 1}]}"#.to_vec();
+    assert!(matches!(
+        detect_offline(
+            config,
+            chain_spec(),
+            read_offline_input::<E, _>(Cursor::new(json)),
+            None,
+            test_logger()
+        ),
+        Err(Error::OfflineInputInvalid(_))
+    ));
+}
+
+#[test]
+fn offline_detection_verifies_signatures() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let spec = chain_spec();
+    let keypairs = generate_deterministic_keypairs(4);
+    let genesis_validators_root = Hash256::repeat_byte(1);
+    let fork = spec.fork_at_epoch(Epoch::new(0));
+
+    let sign_attestation = |mut attestation: IndexedAttestation<E>| {
+        let domain = spec.get_domain(
+            attestation.data().target.epoch,
+            Domain::BeaconAttester,
+            &fork,
+            genesis_validators_root,
+        );
+        let message = attestation.data().signing_root(domain);
+        let mut signature = AggregateSignature::infinity();
+        for index in attestation.attesting_indices_iter() {
+            signature.add_assign(&keypairs[*index as usize].sk.sign(message));
+        }
+        *attestation.signature_mut() = signature;
+        attestation
+    };
+    let sign_block = |slot, proposer_index: u64, block_root| {
+        block(slot, proposer_index, block_root).message.sign::<E>(
+            &keypairs[proposer_index as usize].sk,
+            &fork,
+            genesis_validators_root,
+            &spec,
+        )
+    };
+
+    // Validators 0 and 1 sign their offences, validators 2 and 3 don't sign one of theirs.
+    let input = OfflineInput::<E> {
+        attestations: vec![
+            sign_attestation(indexed_att([0, 1], 2, 3, 1)),
+            sign_attestation(indexed_att([0, 1], 2, 3, 2)),
+            sign_attestation(indexed_att([2], 2, 3, 1)),
+            indexed_att([2], 2, 3, 2),
+        ],
+        block_headers: vec![
+            sign_block(5, 1, 1),
+            sign_block(5, 1, 2),
+            sign_block(6, 3, 1),
+            block(6, 3, 2),
+        ],
+    };
+    let verifier = SignatureVerifier::new(
+        keypairs
+            .iter()
+            .map(|keypair| keypair.pk.compress())
+            .collect(),
+        genesis_validators_root,
+        spec.clone(),
+    );
+
+    let report = detect_offline(
+        config,
+        spec.clone(),
+        input.into_messages(),
+        Some(verifier),
+        test_logger(),
+    )
+    .unwrap();
+    assert_eq!(report.num_attestations, 4);
+    assert_eq!(report.num_block_headers, 4);
+    assert_eq!(report.num_invalid_signatures, 2);
+
+    let attester_slashings = report.attester_slashings.into_iter().collect();
+    assert_eq!(
+        slashed_validators_from_slashings(&attester_slashings),
+        hashset! {0, 1}
+    );
+    assert_eq!(report.proposer_slashings.len(), 1);
+    assert_eq!(
+        report.proposer_slashings[0]
+            .signed_header_1
+            .message
+            .proposer_index,
+        1
+    );
+}
@@ -0,0 +1,83 @@
+#![cfg(any(feature = "mdbx", feature = "lmdb", feature = "redb"))]
+
+use logging::test_logger;
+use slasher::{
+    test_utils::{block, chain_spec, indexed_att, E},
+    Config, Slasher, WatchAlertEvidence, WatchAlertKind, WatchedValidator,
+};
+use tempfile::tempdir;
+use types::Epoch;
+
+fn alerts(slasher: &Slasher<E>) -> Vec<(u64, WatchAlertKind)> {
+    slasher
+        .get_watch_alerts()
+        .into_iter()
+        .map(|alert| (alert.validator_index, alert.kind))
+        .collect()
+}
+
+#[test]
+fn watched_equivocations() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.validator_chunk_size = 1;
+    config.chunk_size = 16;
+    config.history_length = 16;
+    config.watch_list = vec![WatchedValidator::Index(0)];
+
+    let slasher = Slasher::<E>::open(config.clone(), chain_spec(), test_logger()).unwrap();
+
+    // Only the watched validator raises an alert for a double vote.
+    slasher.accept_attestation(indexed_att([0, 1], 1, 2, 0));
+    slasher.process_queued(Epoch::new(3)).unwrap();
+    slasher.accept_attestation(indexed_att([0, 1], 1, 2, 1));
+    slasher.process_queued(Epoch::new(3)).unwrap();
+    assert_eq!(alerts(&slasher), vec![(0, WatchAlertKind::DoubleVote)]);
+    assert_eq!(slasher.get_attester_slashings().len(), 1);
+
+    // Surrounds are detected beyond the history length of the slasher.
+    let current_epoch = Epoch::new(100);
+    slasher.accept_attestation(indexed_att([0, 1], 0, 99, 0));
+    slasher.process_queued(current_epoch).unwrap();
+    slasher.prune_database(current_epoch).unwrap();
+    let watch_alerts = slasher.get_watch_alerts();
+    assert_eq!(watch_alerts.len(), 1);
+    assert_eq!(watch_alerts[0].kind, WatchAlertKind::SurroundingVote);
+    match &watch_alerts[0].evidence {
+        WatchAlertEvidence::Attestations { previous, new } => {
+            assert_eq!(previous.target.epoch, 2);
+            assert_eq!(new.target.epoch, 99);
+        }
+        evidence => panic!("unexpected evidence: {:?}", evidence),
+    }
+    assert!(slasher.get_attester_slashings().is_empty());
+
+    // The history of watched validators survives a restart.
+    drop(slasher);
+    let slasher = Slasher::<E>::open(config, chain_spec(), test_logger()).unwrap();
+    slasher.accept_attestation(indexed_att([0], 50, 51, 0));
+    slasher.process_queued(current_epoch).unwrap();
+    assert_eq!(alerts(&slasher), vec![(0, WatchAlertKind::SurroundedVote)]);
+
+    // Double proposals by watched validators raise alerts.
+    slasher.accept_block_header(block(4000, 0, 1));
+    slasher.accept_block_header(block(4000, 0, 2));
+    slasher.accept_block_header(block(4000, 1, 1));
+    slasher.accept_block_header(block(4000, 1, 2));
+    slasher.process_queued(Epoch::new(125)).unwrap();
+    assert_eq!(alerts(&slasher), vec![(0, WatchAlertKind::DoubleProposal)]);
+    assert_eq!(slasher.get_proposer_slashings().len(), 2);
+}
+
+#[test]
+fn watch_validators_after_open() {
+    let tempdir = tempdir().unwrap();
+    let config = Config::new(tempdir.path().into());
+    let slasher = Slasher::<E>::open(config, chain_spec(), test_logger()).unwrap();
+
+    slasher.watch_validators([3]);
+    slasher.accept_attestation(indexed_att([3], 1, 2, 0));
+    slasher.accept_attestation(indexed_att([3], 1, 2, 1));
+    slasher.process_queued(Epoch::new(3)).unwrap();
+    assert_eq!(alerts(&slasher), vec![(3, WatchAlertKind::DoubleVote)]);
+}
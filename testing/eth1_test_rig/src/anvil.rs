@@ -1,4 +1,7 @@
-use ethers_core::utils::{Anvil, AnvilInstance};
+use ethers_core::{
+    types::U256,
+    utils::{Anvil, AnvilInstance},
+};
 use ethers_providers::{Http, Middleware, Provider};
 use serde_json::json;
 use unused_port::unused_tcp4_port;
@@ -93,6 +96,23 @@ impl AnvilCliInstance {
                     .to_string()
             })
     }
+
+    /// Takes a snapshot of the chain, which can be restored with `evm_revert`.
+    pub async fn evm_snapshot(&self) -> Result<U256, String> {
+        self.client
+            .request("evm_snapshot", ())
+            .await
+            .map_err(|e| format!("Failed to snapshot EVM (is this anvil?): {:?}", e))
+    }
+
+    /// Reverts the chain to `snapshot`, discarding all later blocks.
+    pub async fn evm_revert(&self, snapshot: U256) -> Result<(), String> {
+        self.client
+            .request("evm_revert", vec![snapshot])
+            .await
+            .map(|_: bool| ())
+            .map_err(|e| format!("Failed to revert EVM (is this anvil?): {:?}", e))
+    }
 }
 
 fn endpoint(port: u16) -> String {
@@ -0,0 +1,614 @@
+//! Runs a number of `lighthouse_network` nodes in-process, connected over the in-memory links of
+//! a simulated network.
+//!
+//! The harness is intended for testing gossip propagation, peer scoring, RPC and other
+//! swarm-level behaviour in CI without external infrastructure. Each node runs its own `Network`
+//! in a task; the test drives the nodes through [`NetworkTestHarness`] and observes them through
+//! the [`HarnessEvent`]s it collects.
+//!
+//! No sockets are opened, and the nodes are meant to run on the runtime returned by
+//! [`build_runtime`], whose clock is paused. The tokio clock then only advances when every node is
+//! idle, jumping straight to the next timer, so link latencies, timeouts and event timestamps are
+//! independent of the wall clock and of the load on the machine. Node keys and message losses are
+//! derived from the seed of the link conditions. The few libp2p timers which are not driven by
+//! tokio, such as the gossipsub heartbeat, still run in real time, so tests should not depend on
+//! them.
+
+use lighthouse_network::rpc::{self, RPCError, RequestType};
+use lighthouse_network::service::api_types::AppRequestId;
+use lighthouse_network::service::Network;
+use lighthouse_network::types::GossipKind;
+use lighthouse_network::{
+    GossipTopic, LinkConditions, MessageAcceptance, MessageId, Multiaddr, NetworkConfig,
+    NetworkEvent, NetworkGlobals, PeerId, PeerRequestId, PubsubMessage, Response, SimulatedNetwork,
+    Topic, TopicHash, NETWORK_KEY_FILENAME,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use slog::{debug, o, Logger};
+use std::net::Ipv4Addr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::runtime::Runtime;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use types::{ChainSpec, EnrForkId, EthSpec, FixedBytesExtended, ForkContext, Hash256, Slot};
+
+/// How often conditions on the nodes are re-checked while waiting for them.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Builds a single-threaded runtime with a paused clock to run a [`NetworkTestHarness`] on.
+pub fn build_runtime() -> Runtime {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()
+        .expect("should build runtime")
+}
+
+/// Decides whether `node` accepts a gossip message, and therefore whether it is forwarded.
+pub type MessageValidator<E> =
+    Arc<dyn Fn(usize, &PubsubMessage<E>) -> MessageAcceptance + Send + Sync>;
+
+type Command<E> = Box<dyn FnOnce(&mut Network<E>) + Send>;
+
+/// Configuration of a [`NetworkTestHarness`].
+pub struct HarnessConfig<E: EthSpec> {
+    /// The number of nodes to run.
+    pub node_count: usize,
+    /// The gossip topics every node subscribes to.
+    pub topics: Vec<GossipKind>,
+    /// The conditions of the links to every node. Node `i` uses `seed + i` as its seed, which also
+    /// determines its key.
+    pub link_conditions: LinkConditions,
+    /// The network configuration each node starts from. The listening addresses, network
+    /// directory, discovery, QUIC and UPnP are overridden by the harness.
+    pub network_config: NetworkConfig,
+    /// Validates the gossip messages received by the nodes. All messages are accepted if `None`.
+    pub validator: Option<MessageValidator<E>>,
+}
+
+impl<E: EthSpec> Default for HarnessConfig<E> {
+    fn default() -> Self {
+        Self {
+            node_count: 2,
+            topics: vec![GossipKind::BeaconBlock],
+            link_conditions: LinkConditions::default(),
+            network_config: NetworkConfig::default(),
+            validator: None,
+        }
+    }
+}
+
+/// The shape of the connections between the nodes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Node `i` is connected to node `i + 1`.
+    Line,
+    /// A line where the last node is also connected to the first.
+    Ring,
+    /// Node 0 is connected to every other node.
+    Star,
+    /// Every node is connected to every other node.
+    FullMesh,
+}
+
+impl Topology {
+    /// Returns the connections of the topology over `node_count` nodes, as `(dialer, listener)`
+    /// pairs.
+    pub fn edges(&self, node_count: usize) -> Vec<(usize, usize)> {
+        match self {
+            Topology::Line => (1..node_count).map(|i| (i - 1, i)).collect(),
+            Topology::Ring => {
+                let mut edges = Topology::Line.edges(node_count);
+                if node_count > 2 {
+                    edges.push((node_count - 1, 0));
+                }
+                edges
+            }
+            Topology::Star => (1..node_count).map(|i| (0, i)).collect(),
+            Topology::FullMesh => (0..node_count)
+                .flat_map(|i| (i + 1..node_count).map(move |j| (i, j)))
+                .collect(),
+        }
+    }
+}
+
+/// A message published by a node at a point in a script.
+pub struct ScriptedPublish<E: EthSpec> {
+    /// The time, relative to the start of the script, at which the message is published.
+    pub at: Duration,
+    /// The publishing node.
+    pub node: usize,
+    pub message: PubsubMessage<E>,
+}
+
+/// Something observed on one of the nodes. `at` is the time since the harness was started.
+#[derive(Debug)]
+pub enum HarnessEvent<E: EthSpec> {
+    PeerConnected {
+        node: usize,
+        peer_id: PeerId,
+        at: Duration,
+    },
+    PeerDisconnected {
+        node: usize,
+        peer_id: PeerId,
+        at: Duration,
+    },
+    MessageReceived {
+        node: usize,
+        id: MessageId,
+        /// The peer that forwarded the message to the node.
+        source: PeerId,
+        topic: TopicHash,
+        message: PubsubMessage<E>,
+        at: Duration,
+    },
+    RequestReceived {
+        node: usize,
+        peer_id: PeerId,
+        id: PeerRequestId,
+        request: rpc::Request<E>,
+        at: Duration,
+    },
+    ResponseReceived {
+        node: usize,
+        peer_id: PeerId,
+        response: Response<E>,
+        at: Duration,
+    },
+    RequestFailed {
+        node: usize,
+        peer_id: PeerId,
+        error: RPCError,
+        at: Duration,
+    },
+}
+
+struct Node<E: EthSpec> {
+    peer_id: PeerId,
+    listen_addr: Multiaddr,
+    network_globals: Arc<NetworkGlobals<E>>,
+    commands: mpsc::UnboundedSender<Command<E>>,
+    /// Holds the node's network directory until the harness is dropped.
+    _network_dir: TempDir,
+}
+
+/// A set of in-process nodes. Dropping the harness stops all of them.
+pub struct NetworkTestHarness<E: EthSpec> {
+    nodes: Vec<Node<E>>,
+    events: mpsc::UnboundedReceiver<HarnessEvent<E>>,
+    enr_fork_id: EnrForkId,
+    start: Instant,
+    log: Logger,
+    /// Keeps the nodes' executor running until the harness is dropped.
+    _exit: async_channel::Sender<()>,
+}
+
+impl<E: EthSpec> NetworkTestHarness<E> {
+    /// Starts `config.node_count` nodes on `runtime`, which should be built with
+    /// [`build_runtime`], and waits for all of them to listen. The nodes are not connected to each
+    /// other.
+    pub async fn new(
+        runtime: Weak<Runtime>,
+        config: HarnessConfig<E>,
+        spec: Arc<ChainSpec>,
+        log: Logger,
+    ) -> Result<Self, String> {
+        let (exit_tx, exit_rx) = async_channel::bounded(1);
+        let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+        let executor = task_executor::TaskExecutor::new(runtime, exit_rx, log.clone(), shutdown_tx);
+
+        let fork_context = Arc::new(ForkContext::new::<E>(Slot::new(0), Hash256::zero(), &spec));
+        let enr_fork_id = spec.enr_fork_id::<E>(Slot::new(0), Hash256::zero());
+        let start = Instant::now();
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let simulated_network = SimulatedNetwork::new();
+
+        let mut nodes = Vec::with_capacity(config.node_count);
+        for index in 0..config.node_count {
+            let node_log = log.new(o!("node" => index));
+
+            let network_dir = tempfile::Builder::new()
+                .prefix(&format!("network_test_harness_{}", index))
+                .tempdir()
+                .map_err(|e| format!("Unable to create network directory: {:?}", e))?;
+            let mut network_config = config.network_config.clone();
+            network_config.set_ipv4_listening_address(Ipv4Addr::LOCALHOST, 0, 0, 0);
+            network_config.enr_address = (Some(Ipv4Addr::LOCALHOST), None);
+            network_config.network_dir = network_dir.path().to_path_buf();
+            network_config.disable_discovery = true;
+            network_config.disable_quic_support = true;
+            network_config.upnp_enabled = false;
+
+            let seed = config.link_conditions.seed.wrapping_add(index as u64);
+            write_network_key(&network_config, seed)?;
+            let transport = simulated_network.transport(LinkConditions {
+                seed,
+                ..config.link_conditions.clone()
+            });
+            let context = lighthouse_network::Context {
+                config: Arc::new(network_config),
+                enr_fork_id: enr_fork_id.clone(),
+                fork_context: fork_context.clone(),
+                chain_spec: spec.clone(),
+                libp2p_registry: None,
+            };
+            let (mut network, network_globals) =
+                Network::new_simulated(executor.clone(), context, transport, &node_log).await?;
+
+            for kind in &config.topics {
+                network.subscribe_kind(kind.clone());
+            }
+
+            let listen_addr = loop {
+                if let NetworkEvent::NewListenAddr(addr) = network.next_event().await {
+                    break addr;
+                }
+            };
+            debug!(node_log, "Test node listening"; "address" => %listen_addr);
+
+            let (commands, commands_rx) = mpsc::unbounded_channel();
+            let peer_id = network_globals.local_peer_id();
+            executor.spawn(
+                run_node(
+                    index,
+                    network,
+                    commands_rx,
+                    events_tx.clone(),
+                    config.validator.clone(),
+                    start,
+                ),
+                "network_test_node",
+            );
+
+            nodes.push(Node {
+                peer_id,
+                listen_addr,
+                network_globals,
+                commands,
+                _network_dir: network_dir,
+            });
+        }
+
+        Ok(Self {
+            nodes,
+            events,
+            enr_fork_id,
+            start,
+            log,
+            _exit: exit_tx,
+        })
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn peer_id(&self, node: usize) -> PeerId {
+        self.nodes[node].peer_id
+    }
+
+    pub fn network_globals(&self, node: usize) -> &Arc<NetworkGlobals<E>> {
+        &self.nodes[node].network_globals
+    }
+
+    /// Returns the index of the node with `peer_id`.
+    pub fn node_index(&self, peer_id: &PeerId) -> Option<usize> {
+        self.nodes.iter().position(|node| node.peer_id == *peer_id)
+    }
+
+    /// Returns the time elapsed since the harness was started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Returns the hash of the topic of `kind`, as used by the nodes.
+    pub fn topic_hash(&self, kind: GossipKind) -> TopicHash {
+        let topic: Topic =
+            GossipTopic::new(kind, Default::default(), self.enr_fork_id.fork_digest).into();
+        topic.hash()
+    }
+
+    /// Runs `f` on the network of `node` and returns its result.
+    pub async fn with_node<R, F>(&self, node: usize, f: F) -> R
+    where
+        R: Send + 'static,
+        F: FnOnce(&mut Network<E>) -> R + Send + 'static,
+    {
+        let (result_tx, result_rx) = oneshot::channel();
+        let command: Command<E> = Box::new(move |network| {
+            let _ = result_tx.send(f(network));
+        });
+        self.nodes[node]
+            .commands
+            .send(command)
+            .unwrap_or_else(|_| panic!("node {} has stopped", node));
+        result_rx
+            .await
+            .unwrap_or_else(|_| panic!("node {} has stopped", node))
+    }
+
+    /// Makes `dialer` dial `listener` and waits for both to consider the other connected.
+    pub async fn connect(
+        &self,
+        dialer: usize,
+        listener: usize,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let addr = self.nodes[listener].listen_addr.clone();
+        self.with_node(dialer, move |network| network.testing_dial(addr))
+            .await
+            .map_err(|e| format!("Node {} failed to dial node {}: {:?}", dialer, listener, e))?;
+
+        let dialer_peer_id = self.peer_id(dialer);
+        let listener_peer_id = self.peer_id(listener);
+        self.wait_until(timeout, || {
+            self.network_globals(dialer)
+                .peers
+                .read()
+                .is_connected(&listener_peer_id)
+                && self
+                    .network_globals(listener)
+                    .peers
+                    .read()
+                    .is_connected(&dialer_peer_id)
+        })
+        .await
+        .map_err(|_| format!("Node {} did not connect to node {}", dialer, listener))
+    }
+
+    /// Connects the nodes according to `topology`.
+    pub async fn connect_topology(
+        &self,
+        topology: Topology,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        for (dialer, listener) in topology.edges(self.node_count()) {
+            self.connect(dialer, listener, timeout).await?;
+        }
+        Ok(())
+    }
+
+    /// Waits until every node has at least `min_mesh_peers` peers in its gossipsub mesh for the
+    /// topic of `kind`.
+    pub async fn wait_for_mesh(
+        &self,
+        kind: GossipKind,
+        min_mesh_peers: usize,
+        timeout: Duration,
+    ) -> Result<(), String> {
+        let deadline = Instant::now() + timeout;
+        let topic_hash = self.topic_hash(kind);
+        loop {
+            let mut ready = true;
+            for node in 0..self.node_count() {
+                let topic_hash = topic_hash.clone();
+                let mesh_peers = self
+                    .with_node(node, move |network| {
+                        network.gossipsub().mesh_peers(&topic_hash).count()
+                    })
+                    .await;
+                ready &= mesh_peers >= min_mesh_peers;
+            }
+            if ready {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "Gossipsub mesh for {} did not form in {:?}",
+                    topic_hash, timeout
+                ));
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Sends `request` from `node` to `peer`. The response is reported as a
+    /// [`HarnessEvent::ResponseReceived`] on `node`.
+    pub async fn send_request(
+        &self,
+        node: usize,
+        peer: usize,
+        request: RequestType<E>,
+    ) -> Result<(), String> {
+        let peer_id = self.peer_id(peer);
+        self.with_node(node, move |network| {
+            network.send_request(peer_id, AppRequestId::Router, request)
+        })
+        .await
+        .map_err(|(_, e)| {
+            format!(
+                "Node {} failed to send a request to node {}: {:?}",
+                node, peer, e
+            )
+        })
+    }
+
+    /// Responds from `node` to the request `request_id` it received from `peer_id` as `id`.
+    pub async fn send_response(
+        &self,
+        node: usize,
+        peer_id: PeerId,
+        id: PeerRequestId,
+        request_id: rpc::RequestId,
+        response: Response<E>,
+    ) {
+        self.with_node(node, move |network| {
+            network.send_response(peer_id, id, request_id, response)
+        })
+        .await
+    }
+
+    /// Publishes `message` from `node`.
+    pub async fn publish(&self, node: usize, message: PubsubMessage<E>) {
+        self.with_node(node, move |network| network.publish(vec![message]))
+            .await
+    }
+
+    /// Publishes each message of `script` at its scheduled time, relative to now.
+    pub async fn run_script(&self, mut script: Vec<ScriptedPublish<E>>) {
+        script.sort_by_key(|publish| publish.at);
+        let script_start = Instant::now();
+        for publish in script {
+            tokio::time::sleep_until(script_start + publish.at).await;
+            debug!(self.log, "Publishing scripted message"; "node" => publish.node, "kind" => publish.message.kind().to_string());
+            self.publish(publish.node, publish.message).await;
+        }
+    }
+
+    /// Returns the next event observed on any node, or `None` if nothing happens for `timeout`.
+    pub async fn next_event(&mut self, timeout: Duration) -> Option<HarnessEvent<E>> {
+        tokio::time::timeout(timeout, self.events.recv())
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Waits until `count` messages have been received across the nodes, discarding any other
+    /// events. Returns `(node, at, message)` for each received message.
+    pub async fn wait_for_messages(
+        &mut self,
+        count: usize,
+        timeout: Duration,
+    ) -> Result<Vec<(usize, Duration, PubsubMessage<E>)>, String> {
+        let deadline = Instant::now() + timeout;
+        let mut messages = Vec::with_capacity(count);
+        while messages.len() < count {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.next_event(remaining).await {
+                Some(HarnessEvent::MessageReceived {
+                    node, message, at, ..
+                }) => messages.push((node, at, message)),
+                Some(_) => {}
+                None => {
+                    return Err(format!(
+                        "Received {} of {} messages in {:?}",
+                        messages.len(),
+                        count,
+                        timeout
+                    ))
+                }
+            }
+        }
+        Ok(messages)
+    }
+
+    async fn wait_until(&self, timeout: Duration, condition: impl Fn() -> bool) -> Result<(), ()> {
+        let deadline = Instant::now() + timeout;
+        while !condition() {
+            if Instant::now() >= deadline {
+                return Err(());
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+}
+
+/// Writes a key derived from `seed` to the network directory of `config`, so that the identity of
+/// each node is the same on every run.
+fn write_network_key(config: &NetworkConfig, seed: u64) -> Result<(), String> {
+    let key: [u8; 32] = StdRng::seed_from_u64(seed).gen();
+    std::fs::write(config.network_dir.join(NETWORK_KEY_FILENAME), key)
+        .map_err(|e| format!("Unable to write network key: {:?}", e))
+}
+
+/// Drives the network of a single node until the harness is dropped.
+async fn run_node<E: EthSpec>(
+    index: usize,
+    mut network: Network<E>,
+    mut commands: mpsc::UnboundedReceiver<Command<E>>,
+    events: mpsc::UnboundedSender<HarnessEvent<E>>,
+    validator: Option<MessageValidator<E>>,
+    start: Instant,
+) {
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(command) => command(&mut network),
+                None => return,
+            },
+            event = network.next_event() => {
+                let at = start.elapsed();
+                let event = match event {
+                    NetworkEvent::PeerConnectedOutgoing(peer_id)
+                    | NetworkEvent::PeerConnectedIncoming(peer_id) => HarnessEvent::PeerConnected {
+                        node: index,
+                        peer_id,
+                        at,
+                    },
+                    NetworkEvent::PeerDisconnected(peer_id) => HarnessEvent::PeerDisconnected {
+                        node: index,
+                        peer_id,
+                        at,
+                    },
+                    NetworkEvent::PubsubMessage {
+                        id,
+                        source,
+                        topic,
+                        message,
+                    } => {
+                        let acceptance = validator
+                            .as_ref()
+                            .map_or(MessageAcceptance::Accept, |validator| {
+                                validator(index, &message)
+                            });
+                        network.report_message_validation_result(&source, id.clone(), acceptance);
+                        HarnessEvent::MessageReceived {
+                            node: index,
+                            id,
+                            source,
+                            topic,
+                            message,
+                            at,
+                        }
+                    }
+                    NetworkEvent::RequestReceived {
+                        peer_id,
+                        id,
+                        request,
+                    } => HarnessEvent::RequestReceived {
+                        node: index,
+                        peer_id,
+                        id,
+                        request,
+                        at,
+                    },
+                    NetworkEvent::ResponseReceived {
+                        peer_id, response, ..
+                    } => HarnessEvent::ResponseReceived {
+                        node: index,
+                        peer_id,
+                        response,
+                        at,
+                    },
+                    NetworkEvent::RPCFailed { peer_id, error, .. } => HarnessEvent::RequestFailed {
+                        node: index,
+                        peer_id,
+                        error,
+                        at,
+                    },
+                    _ => continue,
+                };
+                // The receiver is gone once the harness is being dropped.
+                let _ = events.send(event);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topology_edges() {
+        assert_eq!(Topology::Line.edges(3), vec![(0, 1), (1, 2)]);
+        assert_eq!(Topology::Ring.edges(3), vec![(0, 1), (1, 2), (2, 0)]);
+        assert_eq!(Topology::Ring.edges(2), vec![(0, 1)]);
+        assert_eq!(Topology::Star.edges(3), vec![(0, 1), (0, 2)]);
+        assert_eq!(Topology::FullMesh.edges(3), vec![(0, 1), (0, 2), (1, 2)]);
+    }
+}
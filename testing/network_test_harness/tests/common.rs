@@ -0,0 +1,51 @@
+#![allow(dead_code)]
+
+use lighthouse_network::types::GossipKind;
+use lighthouse_network::PubsubMessage;
+use network_test_harness::{HarnessConfig, NetworkTestHarness, Topology};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+use types::{
+    BeaconBlock, ChainSpec, EthSpec, ForkName, MinimalEthSpec, Signature, SignedBeaconBlock, Slot,
+};
+
+pub type E = MinimalEthSpec;
+
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn spec() -> Arc<ChainSpec> {
+    Arc::new(ForkName::Base.make_genesis_spec(E::default_spec()))
+}
+
+/// Returns a block message that is unique for each `slot`.
+pub fn block_message(slot: u64, spec: &ChainSpec) -> PubsubMessage<E> {
+    let mut block = BeaconBlock::<E>::empty(spec);
+    *block.slot_mut() = Slot::new(slot);
+    PubsubMessage::BeaconBlock(Arc::new(SignedBeaconBlock::from_block(
+        block,
+        Signature::empty(),
+    )))
+}
+
+/// Starts the nodes of `config`, connects them according to `topology` and waits for the block
+/// gossip mesh to form.
+pub async fn build_harness(
+    rt: &Arc<Runtime>,
+    config: HarnessConfig<E>,
+    topology: Topology,
+) -> NetworkTestHarness<E> {
+    let harness =
+        NetworkTestHarness::new(Arc::downgrade(rt), config, spec(), logging::test_logger())
+            .await
+            .expect("should start nodes");
+    harness
+        .connect_topology(topology, TIMEOUT)
+        .await
+        .expect("should connect nodes");
+    harness
+        .wait_for_mesh(GossipKind::BeaconBlock, 1, TIMEOUT)
+        .await
+        .expect("should form mesh");
+    harness
+}
@@ -0,0 +1,130 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{block_message, build_harness, spec, E, TIMEOUT};
+use lighthouse_network::types::GossipKind;
+use lighthouse_network::{LinkConditions, MessageAcceptance, PubsubMessage};
+use network_test_harness::{build_runtime, HarnessConfig, HarnessEvent, ScriptedPublish, Topology};
+use std::sync::Arc;
+use std::time::Duration;
+
+// A block published at one end of a line reaches every node, with each hop adding the link
+// latency of the receiving node.
+#[test]
+fn block_propagates_along_line() {
+    let rt = Arc::new(build_runtime());
+    let latency = Duration::from_millis(50);
+
+    rt.block_on(async {
+        let config = HarnessConfig {
+            node_count: 4,
+            link_conditions: LinkConditions {
+                latency,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut harness = build_harness(&rt, config, Topology::Line).await;
+
+        let published_at = harness.elapsed();
+        harness.publish(0, block_message(1, &spec())).await;
+
+        let mut messages = harness
+            .wait_for_messages(3, TIMEOUT)
+            .await
+            .expect("all nodes should receive the block");
+        messages.sort_by_key(|(node, _, _)| *node);
+
+        let nodes: Vec<_> = messages.iter().map(|(node, _, _)| *node).collect();
+        assert_eq!(nodes, vec![1, 2, 3]);
+        for (node, at, message) in messages {
+            assert_eq!(message.kind(), GossipKind::BeaconBlock);
+            assert_eq!(
+                at - published_at,
+                latency * node as u32,
+                "node {} received the block too late",
+                node
+            );
+        }
+    });
+}
+
+// Lost messages delay the block by the retransmission delay, identically on every run with the
+// same seed.
+#[test]
+fn lossy_links_are_repeatable() {
+    let arrival_times = || {
+        let rt = Arc::new(build_runtime());
+        rt.block_on(async {
+            let config = HarnessConfig {
+                node_count: 3,
+                link_conditions: LinkConditions {
+                    latency: Duration::from_millis(20),
+                    loss_rate: 0.3,
+                    retransmission_delay: Duration::from_millis(200),
+                    seed: 1234,
+                },
+                ..Default::default()
+            };
+            let mut harness = build_harness(&rt, config, Topology::Line).await;
+
+            let published_at = harness.elapsed();
+            for slot in 1..=5 {
+                harness.publish(0, block_message(slot, &spec())).await;
+            }
+            let mut messages = harness
+                .wait_for_messages(10, TIMEOUT)
+                .await
+                .expect("all nodes should receive the blocks");
+            messages.sort_by_key(|(node, at, _)| (*node, *at));
+            messages
+                .into_iter()
+                .map(|(node, at, _)| (node, at - published_at))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    assert_eq!(arrival_times(), arrival_times());
+}
+
+// Messages rejected by a node are not forwarded by it.
+#[test]
+fn rejected_block_is_not_forwarded() {
+    let rt = Arc::new(build_runtime());
+
+    rt.block_on(async {
+        let config = HarnessConfig {
+            node_count: 3,
+            validator: Some(Arc::new(|node: usize, _: &PubsubMessage<E>| {
+                if node == 1 {
+                    MessageAcceptance::Reject
+                } else {
+                    MessageAcceptance::Accept
+                }
+            })),
+            ..Default::default()
+        };
+        let mut harness = build_harness(&rt, config, Topology::Line).await;
+
+        harness
+            .run_script(vec![ScriptedPublish {
+                at: Duration::from_millis(100),
+                node: 0,
+                message: block_message(1, &spec()),
+            }])
+            .await;
+
+        let messages = harness
+            .wait_for_messages(1, TIMEOUT)
+            .await
+            .expect("node 1 should receive the block");
+        assert_eq!(messages[0].0, 1);
+
+        while let Some(event) = harness.next_event(Duration::from_secs(3)).await {
+            if let HarnessEvent::MessageReceived { node, .. } = event {
+                panic!("node {} received a rejected block", node);
+            }
+        }
+    });
+}
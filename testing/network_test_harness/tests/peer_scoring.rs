@@ -0,0 +1,67 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{block_message, build_harness, spec, E, TIMEOUT};
+use lighthouse_network::{MessageAcceptance, PubsubMessage};
+use network_test_harness::{build_runtime, HarnessConfig, Topology};
+use std::sync::Arc;
+use std::time::Duration;
+
+// A peer whose messages are rejected gets a negative gossipsub score, which the peer manager picks
+// up at its next score update.
+#[test]
+fn rejected_messages_lower_gossipsub_score() {
+    let rt = Arc::new(build_runtime());
+
+    rt.block_on(async {
+        let config = HarnessConfig {
+            node_count: 2,
+            validator: Some(Arc::new(|node: usize, _: &PubsubMessage<E>| {
+                if node == 1 {
+                    MessageAcceptance::Reject
+                } else {
+                    MessageAcceptance::Accept
+                }
+            })),
+            ..Default::default()
+        };
+        let mut harness = build_harness(&rt, config, Topology::Line).await;
+        let peer_0 = harness.peer_id(0);
+
+        let good_before = harness
+            .network_globals(1)
+            .peers
+            .read()
+            .peer_info(&peer_0)
+            .expect("node 1 should know node 0")
+            .is_good_gossipsub_peer();
+        assert!(good_before);
+
+        for slot in 1..=3 {
+            harness.publish(0, block_message(slot, &spec())).await;
+        }
+        harness
+            .wait_for_messages(3, TIMEOUT)
+            .await
+            .expect("node 1 should receive the blocks");
+
+        let gossipsub_score = harness
+            .with_node(1, move |network| network.gossipsub().peer_score(&peer_0))
+            .await
+            .expect("peer scoring should be enabled");
+        assert!(gossipsub_score < 0.0, "score is {}", gossipsub_score);
+
+        // The peer manager copies gossipsub scores once per slot.
+        tokio::time::sleep(Duration::from_secs(spec().seconds_per_slot + 1)).await;
+
+        let good_after = harness
+            .network_globals(1)
+            .peers
+            .read()
+            .peer_info(&peer_0)
+            .expect("node 1 should know node 0")
+            .is_good_gossipsub_peer();
+        assert!(!good_after);
+    });
+}
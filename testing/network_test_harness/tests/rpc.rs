@@ -0,0 +1,99 @@
+#![cfg(test)]
+
+mod common;
+
+use common::{build_harness, E, TIMEOUT};
+use lighthouse_network::rpc::{RequestType, StatusMessage};
+use lighthouse_network::{LinkConditions, Response};
+use network_test_harness::{build_runtime, HarnessConfig, HarnessEvent, Topology};
+use std::sync::Arc;
+use std::time::Duration;
+use types::{Epoch, FixedBytesExtended, Hash256, Slot};
+
+fn status_message() -> StatusMessage {
+    StatusMessage {
+        fork_digest: [0; 4],
+        finalized_root: Hash256::zero(),
+        finalized_epoch: Epoch::new(1),
+        head_root: Hash256::zero(),
+        head_slot: Slot::new(1),
+    }
+}
+
+// A request takes at least the link latency to arrive, since protocol negotiation adds round
+// trips, and its response arrives exactly one link latency after it was sent.
+#[test]
+fn status_request_round_trip() {
+    let rt = Arc::new(build_runtime());
+    let latency = Duration::from_millis(50);
+
+    rt.block_on(async {
+        let config = HarnessConfig {
+            node_count: 2,
+            link_conditions: LinkConditions {
+                latency,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut harness = build_harness(&rt, config, Topology::Line).await;
+        let peer_0 = harness.peer_id(0);
+        let peer_1 = harness.peer_id(1);
+
+        let sent_at = harness.elapsed();
+        harness
+            .send_request(0, 1, RequestType::Status(status_message()))
+            .await
+            .expect("should send request");
+
+        let (peer_id, id, request, received_at) = loop {
+            match harness.next_event(TIMEOUT).await {
+                Some(HarnessEvent::RequestReceived {
+                    node: 1,
+                    peer_id,
+                    id,
+                    request,
+                    at,
+                }) => {
+                    assert!(at - sent_at >= latency);
+                    break (peer_id, id, request, at);
+                }
+                Some(_) => {}
+                None => panic!("node 1 did not receive the request"),
+            }
+        };
+        assert_eq!(peer_id, peer_0);
+        assert_eq!(request.r#type, RequestType::Status(status_message()));
+
+        harness
+            .send_response(
+                1,
+                peer_id,
+                id,
+                request.id,
+                Response::Status(status_message()),
+            )
+            .await;
+
+        loop {
+            match harness.next_event(TIMEOUT).await {
+                Some(HarnessEvent::ResponseReceived {
+                    node: 0,
+                    peer_id,
+                    response,
+                    at,
+                }) => {
+                    assert_eq!(peer_id, peer_1);
+                    assert!(matches!(response, Response::Status(s) if s == status_message()));
+                    assert_eq!(at - received_at, latency);
+                    break;
+                }
+                Some(HarnessEvent::RequestFailed { error, .. }) => {
+                    panic!("request failed: {:?}", error)
+                }
+                Some(_) => {}
+                None => panic!("node 0 did not receive the response"),
+            }
+        }
+    });
+}
@@ -95,6 +95,7 @@ pub fn run_basic_sim(matches: &ArgMatches) -> Result<(), String> {
             compression: false,
             is_restricted: true,
             sse_logging: false,
+            non_blocking: false,
         })?
         .multi_threaded_tokio_runtime()?
         .build()?;
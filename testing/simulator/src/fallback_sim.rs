@@ -102,6 +102,7 @@ pub fn run_fallback_sim(matches: &ArgMatches) -> Result<(), String> {
             compression: false,
             is_restricted: true,
             sse_logging: false,
+            non_blocking: false,
         })?
         .multi_threaded_tokio_runtime()?
         .build()?;
@@ -423,11 +423,14 @@ mod tests {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    beacon_nodes: None,
+                    attestation_timing: None,
                     description: String::default(),
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path: signer_rig.keystore_path.clone(),
                         voting_keystore_password_path: None,
                         voting_keystore_password: Some(KEYSTORE_PASSWORD.to_string().into()),
+                        voting_keystore_password_source: None,
                     },
                 };
                 ValidatorStoreRig::new(
@@ -449,6 +452,8 @@ mod tests {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    beacon_nodes: None,
+                    attestation_timing: None,
                     description: String::default(),
                     signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
                         url: signer_rig.url.to_string(),
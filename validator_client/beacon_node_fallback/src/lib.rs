@@ -8,12 +8,13 @@ use beacon_node_health::{
     IsOptimistic, SyncDistanceTier,
 };
 use environment::RuntimeContext;
-use eth2::BeaconNodeHttpClient;
+use eth2::{BeaconNodeHttpClient, SensitiveUrl};
 use futures::future;
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use slog::{debug, error, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
@@ -45,6 +46,9 @@ const FUTURE_SLOT_TOLERANCE: Slot = Slot::new(1);
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     pub sync_tolerances: BeaconNodeSyncDistanceTiers,
+    /// Stop sending requests to a beacon node whose head has been optimistic for more than this
+    /// many slots, so that nothing is signed on top of it.
+    pub max_optimistic_head_distance: Option<u64>,
 }
 
 /// Indicates a measurement of latency between the VC and a BN.
@@ -136,6 +140,7 @@ pub enum CandidateError {
     Offline,
     Incompatible,
     TimeDiscrepancy,
+    OptimisticHead,
 }
 
 impl std::fmt::Display for CandidateError {
@@ -146,6 +151,7 @@ impl std::fmt::Display for CandidateError {
             CandidateError::Offline => write!(f, "Offline"),
             CandidateError::Incompatible => write!(f, "Incompatible"),
             CandidateError::TimeDiscrepancy => write!(f, "TimeDiscrepancy"),
+            CandidateError::OptimisticHead => write!(f, "OptimisticHead"),
         }
     }
 }
@@ -218,6 +224,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
     pub async fn refresh_health<T: SlotClock>(
         &self,
         distance_tiers: &BeaconNodeSyncDistanceTiers,
+        max_optimistic_head_distance: Option<u64>,
         slot_clock: Option<&T>,
         spec: &ChainSpec,
         log: &Logger,
@@ -254,6 +261,13 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
                     };
 
                     let optimistic_status = if is_optimistic {
+                        if let Some(max_distance) = max_optimistic_head_distance {
+                            if let Err(e) = self.check_optimistic_distance(max_distance, log).await
+                            {
+                                *self.health.write().await = Err(e);
+                                return Err(e);
+                            }
+                        }
                         IsOptimistic::Yes
                     } else {
                         IsOptimistic::No
@@ -285,6 +299,48 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         }
     }
 
+    /// Checks that the optimistic head of the node is no more than `max_distance` slots ahead
+    /// of its latest valid ancestor.
+    ///
+    /// Nodes which are unable to report the distance (e.g. non-Lighthouse nodes) pass the check,
+    /// and are ranked solely on the `is_optimistic` status from `/eth/v1/node/syncing`.
+    async fn check_optimistic_distance(
+        &self,
+        max_distance: u64,
+        log: &Logger,
+    ) -> Result<(), CandidateError> {
+        let status = match self
+            .beacon_node
+            .get_lighthouse_optimistic_status(None)
+            .await
+        {
+            Ok(response) => response.data,
+            Err(e) => {
+                debug!(
+                    log,
+                    "Unable to read optimistic distance from beacon node";
+                    "error" => %e,
+                    "endpoint" => %self.beacon_node,
+                );
+                return Ok(());
+            }
+        };
+
+        if status.optimistic_distance > max_distance {
+            debug!(
+                log,
+                "Beacon node head is too far ahead of its latest valid block";
+                "endpoint" => %self.beacon_node,
+                "head_slot" => status.slot,
+                "latest_valid_slot" => ?status.latest_valid_ancestor_slot,
+                "optimistic_distance" => status.optimistic_distance,
+                "max_distance" => max_distance,
+            );
+            return Err(CandidateError::OptimisticHead);
+        }
+        Ok(())
+    }
+
     /// Checks if the node has the correct specification.
     async fn is_compatible(&self, spec: &ChainSpec, log: &Logger) -> Result<(), CandidateError> {
         let config = self
@@ -367,6 +423,39 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
     }
 }
 
+/// An ordered list of beacon nodes which should be tried first for a request, most preferred first.
+///
+/// A preferred node is only tried ahead of the others whilst it is as healthy as the healthiest
+/// candidate, so that a validator pinned to a node which is syncing or has lost its execution
+/// engine falls back to the usual health-based order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BeaconNodePreference {
+    /// The redacted URLs of the preferred nodes.
+    nodes: Vec<String>,
+}
+
+impl BeaconNodePreference {
+    pub fn new(beacon_nodes: &[SensitiveUrl]) -> Self {
+        Self {
+            nodes: beacon_nodes
+                .iter()
+                .map(|url| url.as_ref().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the position of `beacon_node` in the preference list, if it is listed.
+    fn rank(&self, beacon_node: &BeaconNodeHttpClient) -> Option<usize> {
+        self.nodes
+            .iter()
+            .position(|node| node.as_str() == beacon_node.as_ref())
+    }
+}
+
 /// A collection of `CandidateBeaconNode` that can be used to perform requests with "fallback"
 /// behaviour, where the failure of one candidate results in the next candidate receiving an
 /// identical query.
@@ -374,8 +463,11 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 pub struct BeaconNodeFallback<T, E> {
     pub candidates: Arc<RwLock<Vec<CandidateBeaconNode<E>>>>,
     distance_tiers: BeaconNodeSyncDistanceTiers,
+    max_optimistic_head_distance: Option<u64>,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
+    /// The beacon node which last succeeded for each sticky session, see `first_success_sticky`.
+    sticky_sessions: Arc<RwLock<HashMap<u64, String>>>,
     spec: Arc<ChainSpec>,
     log: Logger,
 }
@@ -392,8 +484,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Self {
             candidates: Arc::new(RwLock::new(candidates)),
             distance_tiers,
+            max_optimistic_head_distance: config.max_optimistic_head_distance,
             slot_clock: None,
             broadcast_topics,
+            sticky_sessions: Arc::new(RwLock::new(HashMap::new())),
             spec,
             log,
         }
@@ -476,6 +570,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         for candidate in candidates.iter() {
             futures.push(candidate.refresh_health(
                 &self.distance_tiers,
+                self.max_optimistic_head_distance,
                 self.slot_clock.as_ref(),
                 &self.spec,
                 &self.log,
@@ -557,54 +652,150 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         R: Future<Output = Result<O, Err>>,
         Err: Debug,
     {
-        let mut errors = vec![];
+        self.first_success_preferring(&BeaconNodePreference::default(), func)
+            .await
+    }
 
-        // First pass: try `func` on all candidates. Candidate order has already been set in
-        // `update_all_candidates`. This ensures the most suitable node is always tried first.
-        let candidates = self.candidates.read().await;
-        let mut futures = vec![];
+    /// As `first_success`, but try the candidates in `preference` first.
+    ///
+    /// See `BeaconNodePreference` for when a preferred candidate is tried ahead of the others.
+    pub async fn first_success_preferring<F, O, Err, R>(
+        &self,
+        preference: &BeaconNodePreference,
+        func: F,
+    ) -> Result<O, Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        self.first_success_with_node(preference, func)
+            .await
+            .map(|(val, _)| val)
+    }
 
-        // Run `func` using a `candidate`, returning the value or capturing errors.
-        for candidate in candidates.iter() {
-            futures.push(Self::run_on_candidate(
-                candidate.beacon_node.clone(),
-                &func,
-                &self.log,
-            ));
-        }
-        drop(candidates);
+    /// As `first_success`, but try the candidate which last succeeded for `session` first.
+    ///
+    /// This keeps consecutive requests belonging to the same duty (e.g. the messages of a sync
+    /// committee period) on the same beacon node whilst it remains healthy. Sessions older than
+    /// `session - 1` are forgotten.
+    pub async fn first_success_sticky<F, O, Err, R>(
+        &self,
+        session: u64,
+        func: F,
+    ) -> Result<O, Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        let preference = BeaconNodePreference {
+            nodes: self
+                .sticky_sessions
+                .read()
+                .await
+                .get(&session)
+                .cloned()
+                .into_iter()
+                .collect(),
+        };
 
-        for future in futures {
-            match future.await {
-                Ok(val) => return Ok(val),
-                Err(e) => errors.push(e),
-            }
-        }
+        let (val, node) = self.first_success_with_node(&preference, func).await?;
+
+        let mut sticky_sessions = self.sticky_sessions.write().await;
+        sticky_sessions.retain(|existing, _| existing.saturating_add(1) >= session);
+        sticky_sessions.insert(session, node);
 
+        Ok(val)
+    }
+
+    /// Run `func` against each candidate in the order given by `preference`, returning the result
+    /// and the identifier of the candidate which produced it.
+    async fn first_success_with_node<F, O, Err, R>(
+        &self,
+        preference: &BeaconNodePreference,
+        func: F,
+    ) -> Result<(O, String), Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        let mut errors = vec![];
+
+        // First pass: try `func` on all candidates. Candidate order has already been set in
+        // `update_all_candidates`, and is adjusted here by `preference`. This ensures the most
+        // suitable node is always tried first.
+        //
         // Second pass. No candidates returned successfully. Try again with the same order.
         // This will duplicate errors.
-        let candidates = self.candidates.read().await;
-        let mut futures = vec![];
+        for _ in 0..2 {
+            let candidates = self.ordered_candidates(preference).await;
+
+            // Run `func` using a `candidate`, returning the value or capturing errors.
+            for candidate in candidates {
+                let node = candidate.as_ref().to_string();
+                match Self::run_on_candidate(candidate, &func, &self.log).await {
+                    Ok(val) => return Ok((val, node)),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
 
-        // Run `func` using a `candidate`, returning the value or capturing errors.
-        for candidate in candidates.iter() {
-            futures.push(Self::run_on_candidate(
-                candidate.beacon_node.clone(),
-                &func,
-                &self.log,
-            ));
+        // No candidates returned successfully.
+        Err(Errors(errors))
+    }
+
+    /// Returns the candidates in the order they should be tried, given `preference`.
+    ///
+    /// Preferred candidates which are as healthy as the healthiest candidate are moved to the
+    /// front, in order of preference. All other candidates follow in order of health. Candidates
+    /// whose head is too optimistic are never tried.
+    async fn ordered_candidates(
+        &self,
+        preference: &BeaconNodePreference,
+    ) -> Vec<BeaconNodeHttpClient> {
+        let all_candidates = self.candidates.read().await;
+        let mut candidates = Vec::with_capacity(all_candidates.len());
+        let mut healths = Vec::with_capacity(all_candidates.len());
+        for candidate in all_candidates.iter() {
+            let health = candidate.health().await;
+            if health != Err(CandidateError::OptimisticHead) {
+                candidates.push(candidate);
+                healths.push(health);
+            }
         }
-        drop(candidates);
 
-        for future in futures {
-            match future.await {
-                Ok(val) => return Ok(val),
-                Err(e) => errors.push(e),
+        if preference.is_empty() {
+            return candidates
+                .iter()
+                .map(|candidate| candidate.beacon_node.clone())
+                .collect();
+        }
+        let best_tier = healths
+            .iter()
+            .filter_map(|health| health.as_ref().ok())
+            .map(|health| health.health_tier.tier)
+            .min();
+
+        let mut preferred = vec![];
+        let mut others = vec![];
+        for (candidate, health) in candidates.iter().zip(healths) {
+            let rank = preference
+                .rank(&candidate.beacon_node)
+                .filter(|_| health.is_ok_and(|health| Some(health.health_tier.tier) <= best_tier));
+            match rank {
+                Some(rank) => preferred.push((rank, candidate.beacon_node.clone())),
+                None => others.push(candidate.beacon_node.clone()),
             }
         }
+        preferred.sort_by_key(|(rank, _)| *rank);
 
-        // No candidates returned successfully.
-        Err(Errors(errors))
+        preferred
+            .into_iter()
+            .map(|(_, beacon_node)| beacon_node)
+            .chain(others)
+            .collect()
     }
 
     /// Run the future `func` on `candidate` while reporting metrics.
@@ -678,6 +869,23 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
     /// Call `func` on first beacon node that returns success or on all beacon nodes
     /// depending on the `topic` and configuration.
     pub async fn request<F, Err, R>(&self, topic: ApiTopic, func: F) -> Result<(), Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<(), Err>>,
+        Err: Debug,
+    {
+        self.request_preferring(topic, &BeaconNodePreference::default(), func)
+            .await
+    }
+
+    /// As `request`, but try the candidates in `preference` first when the `topic` is not
+    /// broadcast.
+    pub async fn request_preferring<F, Err, R>(
+        &self,
+        topic: ApiTopic,
+        preference: &BeaconNodePreference,
+        func: F,
+    ) -> Result<(), Errors<Err>>
     where
         F: Fn(BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<(), Err>>,
@@ -686,7 +894,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         if self.broadcast_topics.contains(&topic) {
             self.broadcast(func).await
         } else {
-            self.first_success(func).await?;
+            self.first_success_preferring(preference, func).await?;
             Ok(())
         }
     }
@@ -739,7 +947,6 @@ impl ApiTopic {
 mod tests {
     use super::*;
     use crate::beacon_node_health::BeaconNodeHealthTier;
-    use eth2::SensitiveUrl;
     use eth2::Timeouts;
     use std::str::FromStr;
     use strum::VariantNames;
@@ -867,4 +1074,113 @@ mod tests {
 
         assert_eq!(candidates, expected_candidates);
     }
+
+    #[tokio::test]
+    async fn preferred_and_sticky_candidates() {
+        let new_candidate = |index: usize, tier: u8| {
+            let beacon_node = BeaconNodeHttpClient::new(
+                SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap(),
+                Timeouts::set_all(Duration::from_secs(1)),
+            );
+            let candidate = CandidateBeaconNode::<E>::new(beacon_node, index);
+            let health = BeaconNodeHealth {
+                user_index: index,
+                head: Slot::new(99),
+                optimistic_status: IsOptimistic::No,
+                execution_status: ExecutionEngineHealth::Healthy,
+                health_tier: BeaconNodeHealthTier::new(
+                    tier,
+                    Slot::new(0),
+                    SyncDistanceTier::Synced,
+                ),
+            };
+            *candidate.health.try_write().unwrap() = Ok(health);
+            candidate
+        };
+        let url =
+            |index: usize| SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap();
+
+        let fallback = BeaconNodeFallback::<slot_clock::TestingSlotClock, E>::new(
+            vec![
+                new_candidate(0, 1),
+                new_candidate(1, 1),
+                new_candidate(2, 2),
+            ],
+            Config::default(),
+            vec![],
+            Arc::new(E::default_spec()),
+            slog::Logger::root(slog::Discard, slog::o!()),
+        );
+        let order = |preference: BeaconNodePreference| {
+            let fallback = fallback.clone();
+            async move {
+                fallback
+                    .ordered_candidates(&preference)
+                    .await
+                    .iter()
+                    .map(|beacon_node| beacon_node.as_ref().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+        let expected = |indices: &[usize]| {
+            indices
+                .iter()
+                .map(|index| url(*index).redacted)
+                .collect::<Vec<_>>()
+        };
+
+        // Without a preference, the candidates are tried in order of health.
+        assert_eq!(
+            order(BeaconNodePreference::default()).await,
+            expected(&[0, 1, 2])
+        );
+
+        // Preferred candidates are tried first, unless they are less healthy than the others.
+        assert_eq!(
+            order(BeaconNodePreference::new(&[url(2), url(1)])).await,
+            expected(&[1, 0, 2])
+        );
+
+        // Sticky sessions return to the candidate which last succeeded.
+        let succeed_on = |index: usize| {
+            let target = url(index).redacted;
+            move |beacon_node: BeaconNodeHttpClient| {
+                let success = beacon_node.as_ref() == target;
+                async move {
+                    if success {
+                        Ok(())
+                    } else {
+                        Err(())
+                    }
+                }
+            }
+        };
+        fallback
+            .first_success_sticky(1, succeed_on(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            fallback.sticky_sessions.read().await.get(&1),
+            Some(&url(1).redacted)
+        );
+        let first_tried = Arc::new(std::sync::Mutex::new(None));
+        fallback
+            .first_success_sticky(1, |beacon_node: BeaconNodeHttpClient| {
+                first_tried
+                    .lock()
+                    .unwrap()
+                    .get_or_insert(beacon_node.as_ref().to_string());
+                async { Ok::<_, ()>(()) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(*first_tried.lock().unwrap(), Some(url(1).redacted));
+
+        // Old sessions are forgotten.
+        fallback
+            .first_success_sticky(3, succeed_on(0))
+            .await
+            .unwrap();
+        assert!(fallback.sticky_sessions.read().await.get(&1).is_none());
+    }
 }
@@ -31,14 +31,18 @@
 
 use beacon_node_fallback::BeaconNodeFallback;
 use environment::RuntimeContext;
+use eth2::lighthouse_vc::types::{
+    DoppelgangerEvent, DoppelgangerEventKind, DoppelgangerProtectionStatus, DoppelgangerStatusData,
+};
 use eth2::types::LivenessResponseData;
 use parking_lot::RwLock;
-use slog::{crit, error, info, Logger};
+use slog::{crit, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::sync::Arc;
 use task_executor::ShutdownReason;
+use tokio::sync::broadcast;
 use tokio::time::sleep;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 
@@ -113,6 +117,18 @@ struct LivenessResponses {
 /// validators on the network.
 pub const DEFAULT_REMAINING_DETECTION_EPOCHS: u64 = 1;
 
+/// The number of events which are buffered for slow subscribers of `DoppelgangerService::subscribe`.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Reasons why the detection window of a validator can't be changed.
+#[derive(Debug, PartialEq)]
+pub enum UpdateError {
+    /// The validator is unknown to the doppelganger service.
+    UnknownValidator(PublicKeyBytes),
+    /// A doppelganger has already been detected, so all validators must remain disabled.
+    DoppelgangerDetected,
+}
+
 /// This crate cannot depend on ValidatorStore as validator_store depends on this crate and
 /// initialises the doppelganger protection. For this reason, we abstract the validator store
 /// functions this service needs through the following trait
@@ -273,6 +289,9 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
 
 pub struct DoppelgangerService {
     doppelganger_states: RwLock<HashMap<PublicKeyBytes, DoppelgangerState>>,
+    /// The validators which have been observed on the network by another instance.
+    detected: RwLock<HashSet<PublicKeyBytes>>,
+    events: broadcast::Sender<DoppelgangerEvent>,
     log: Logger,
 }
 
@@ -280,10 +299,33 @@ impl DoppelgangerService {
     pub fn new(log: Logger) -> Self {
         Self {
             doppelganger_states: <_>::default(),
+            detected: <_>::default(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
             log,
         }
     }
 
+    /// Subscribe to events emitted when doppelganger detection flags or clears a validator.
+    pub fn subscribe(&self) -> broadcast::Receiver<DoppelgangerEvent> {
+        self.events.subscribe()
+    }
+
+    fn publish_event(
+        &self,
+        kind: DoppelgangerEventKind,
+        voting_pubkey: PublicKeyBytes,
+        validator_index: u64,
+        epoch: Epoch,
+    ) {
+        // An error only indicates that there are no subscribers.
+        let _ = self.events.send(DoppelgangerEvent {
+            kind,
+            voting_pubkey,
+            validator_index,
+            epoch,
+        });
+    }
+
     /// Starts a reoccurring future which will try to keep the doppelganger service updated each
     /// slot.
     pub fn start_update_service<E, T, V>(
@@ -396,6 +438,74 @@ impl DoppelgangerService {
             })
     }
 
+    /// Returns the progress of `validator` through doppelganger protection, or `None` if it is
+    /// unknown to the doppelganger service.
+    pub fn validator_status_data(
+        &self,
+        validator: PublicKeyBytes,
+    ) -> Option<DoppelgangerStatusData> {
+        let detected = self.detected.read().contains(&validator);
+        let states = self.doppelganger_states.read();
+        let state = states.get(&validator)?;
+
+        let status = if detected {
+            DoppelgangerProtectionStatus::DoppelgangerDetected
+        } else if state.requires_further_checks() {
+            DoppelgangerProtectionStatus::SigningDisabled
+        } else {
+            DoppelgangerProtectionStatus::SigningEnabled
+        };
+
+        Some(DoppelgangerStatusData {
+            voting_pubkey: validator,
+            status,
+            next_check_epoch: Some(state.next_check_epoch),
+            remaining_epochs: Some(state.remaining_epochs),
+        })
+    }
+
+    /// Set the number of further epochs for which `validator` must be checked before it may sign.
+    ///
+    /// Setting `remaining_epochs` to zero skips detection for the validator, allowing it to sign
+    /// immediately. The window can't be changed once a doppelganger has been detected, since the
+    /// validator client is expected to shut down.
+    pub fn set_remaining_epochs(
+        &self,
+        validator: PublicKeyBytes,
+        remaining_epochs: u64,
+    ) -> Result<(), UpdateError> {
+        if !self.detected.read().is_empty() {
+            return Err(UpdateError::DoppelgangerDetected);
+        }
+
+        let mut states = self.doppelganger_states.write();
+        let state = states
+            .get_mut(&validator)
+            .ok_or(UpdateError::UnknownValidator(validator))?;
+        let previous_remaining_epochs = state.remaining_epochs;
+        state.remaining_epochs = remaining_epochs;
+        drop(states);
+
+        if remaining_epochs < previous_remaining_epochs {
+            warn!(
+                self.log,
+                "Doppelganger detection window shortened";
+                "msg" => "this reduces the protection against running duplicate validators",
+                "remaining_epochs" => remaining_epochs,
+                "pubkey" => ?validator,
+            );
+        } else {
+            info!(
+                self.log,
+                "Doppelganger detection window extended";
+                "remaining_epochs" => remaining_epochs,
+                "pubkey" => ?validator,
+            );
+        }
+
+        Ok(())
+    }
+
     /// Register a new validator with the doppelganger service.
     ///
     /// Validators added during the genesis epoch will not have doppelganger protection applied to
@@ -590,7 +700,20 @@ impl DoppelgangerService {
                     offences. Ensure that you are not running a duplicate or overlapping \
                     validator client",
                 "doppelganger_indices" => ?violators
-            )
+            );
+
+            let mut detected = self.detected.write();
+            for index in &violators {
+                if let Some(pubkey) = indices_map.get(index) {
+                    detected.insert(*pubkey);
+                    self.publish_event(
+                        DoppelgangerEventKind::DoppelgangerDetected,
+                        *pubkey,
+                        *index,
+                        request_epoch,
+                    );
+                }
+            }
         }
 
         // The concept of "epoch satisfaction" is that for some epoch `e` we are *satisfied* that
@@ -679,6 +802,12 @@ impl DoppelgangerService {
                         "msg" => "starting validator",
                         "validator_index" => response.index
                     );
+                    self.publish_event(
+                        DoppelgangerEventKind::DetectionComplete,
+                        *pubkey,
+                        response.index,
+                        previous_epoch,
+                    );
                 }
             }
         }
@@ -1130,6 +1259,78 @@ mod test {
             });
     }
 
+    #[test]
+    fn status_events_and_overrides() {
+        let starting_epoch = genesis_epoch() + 1;
+        let starting_slot = starting_epoch.start_slot(E::slots_per_epoch());
+
+        let checking_epoch = starting_epoch + 2;
+        let checking_slot = checking_epoch.start_slot(E::slots_per_epoch());
+
+        let scenario = TestBuilder::default()
+            .build()
+            .set_slot(starting_slot)
+            .register_all_in_doppelganger_protection_if_enabled()
+            .assert_all_disabled();
+        let mut events = scenario.doppelganger.subscribe();
+
+        // Skipping detection allows a single validator to sign immediately.
+        let skipped = scenario.validators[0];
+        scenario
+            .doppelganger
+            .set_remaining_epochs(skipped, 0)
+            .unwrap();
+        assert_eq!(
+            scenario.doppelganger.validator_status(skipped),
+            DoppelgangerStatus::SigningEnabled(skipped)
+        );
+        assert_eq!(
+            scenario
+                .doppelganger
+                .validator_status_data(skipped)
+                .unwrap()
+                .status,
+            DoppelgangerProtectionStatus::SigningEnabled
+        );
+        assert_eq!(
+            scenario
+                .doppelganger
+                .set_remaining_epochs(PublicKeyBytes::empty(), 0),
+            Err(UpdateError::UnknownValidator(PublicKeyBytes::empty()))
+        );
+
+        // A doppelganger is reported with an event and can't be overridden.
+        let scenario = scenario.simulate_detect_doppelgangers(
+            checking_slot,
+            ShouldShutdown::Yes,
+            |current_epoch, detection_indices: Vec<_>| {
+                let mut liveness_responses = get_false_responses(current_epoch, &detection_indices);
+                liveness_responses.current_epoch_responses[0].is_live = true;
+                future::ready(liveness_responses)
+            },
+        );
+
+        let event = events.try_recv().unwrap();
+        assert_eq!(event.kind, DoppelgangerEventKind::DoppelgangerDetected);
+        assert_eq!(event.epoch, checking_epoch);
+        let detected = scenario.validators[event.validator_index as usize];
+        assert_eq!(event.voting_pubkey, detected);
+        assert!(events.try_recv().is_err());
+
+        assert_eq!(
+            scenario
+                .doppelganger
+                .validator_status_data(detected)
+                .unwrap()
+                .status,
+            DoppelgangerProtectionStatus::DoppelgangerDetected
+        );
+        assert_eq!(
+            scenario.doppelganger.set_remaining_epochs(detected, 0),
+            Err(UpdateError::DoppelgangerDetected)
+        );
+    }
+
     #[test]
     fn detect_after_genesis_with_current_epoch_doppelganger() {
         detect_after_genesis_test(|liveness_responses| {
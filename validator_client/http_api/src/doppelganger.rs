@@ -0,0 +1,92 @@
+use bls::PublicKeyBytes;
+use doppelganger_service::UpdateError;
+use eth2::lighthouse_vc::types::{
+    DoppelgangerProtectionStatus, DoppelgangerStatusData, UpdateDoppelgangerRequest,
+};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use types::EthSpec;
+use validator_store::ValidatorStore;
+
+fn status_data<T: 'static + SlotClock + Clone, E: EthSpec>(
+    validator_pubkey: PublicKeyBytes,
+    validator_store: &ValidatorStore<T, E>,
+) -> DoppelgangerStatusData {
+    match validator_store.doppelganger_service() {
+        Some(doppelganger_service) => doppelganger_service
+            .validator_status_data(validator_pubkey)
+            // Validators unknown to the doppelganger service are never permitted to sign.
+            .unwrap_or(DoppelgangerStatusData {
+                voting_pubkey: validator_pubkey,
+                status: DoppelgangerProtectionStatus::SigningDisabled,
+                next_check_epoch: None,
+                remaining_epochs: None,
+            }),
+        None => DoppelgangerStatusData {
+            voting_pubkey: validator_pubkey,
+            status: DoppelgangerProtectionStatus::SigningEnabled,
+            next_check_epoch: None,
+            remaining_epochs: None,
+        },
+    }
+}
+
+pub fn get_doppelganger_statuses<T: 'static + SlotClock + Clone, E: EthSpec>(
+    validator_store: Arc<ValidatorStore<T, E>>,
+) -> Vec<DoppelgangerStatusData> {
+    // Collect the pubkeys first to avoid interleaving locks on the validators and the
+    // doppelganger service.
+    let pubkeys = validator_store
+        .initialized_validators()
+        .read()
+        .iter_voting_pubkeys()
+        .cloned()
+        .collect::<Vec<_>>();
+
+    pubkeys
+        .into_iter()
+        .map(|pubkey| status_data(pubkey, &validator_store))
+        .collect()
+}
+
+pub fn get_doppelganger_status<T: 'static + SlotClock + Clone, E: EthSpec>(
+    validator_pubkey: PublicKeyBytes,
+    validator_store: Arc<ValidatorStore<T, E>>,
+) -> Result<DoppelgangerStatusData, warp::Rejection> {
+    if validator_store
+        .initialized_validators()
+        .read()
+        .validator(&validator_pubkey)
+        .is_none()
+    {
+        return Err(warp_utils::reject::custom_not_found(
+            "The key was not found on the server".to_string(),
+        ));
+    }
+
+    Ok(status_data(validator_pubkey, &validator_store))
+}
+
+pub fn set_doppelganger_remaining_epochs<T: 'static + SlotClock + Clone, E: EthSpec>(
+    validator_pubkey: PublicKeyBytes,
+    request: UpdateDoppelgangerRequest,
+    validator_store: Arc<ValidatorStore<T, E>>,
+) -> Result<DoppelgangerStatusData, warp::Rejection> {
+    let doppelganger_service = validator_store.doppelganger_service().ok_or_else(|| {
+        warp_utils::reject::custom_bad_request("Doppelganger protection is disabled".to_string())
+    })?;
+
+    doppelganger_service
+        .set_remaining_epochs(validator_pubkey, request.remaining_epochs)
+        .map_err(|e| match e {
+            UpdateError::UnknownValidator(_) => warp_utils::reject::custom_not_found(
+                "The key was not found on the server".to_string(),
+            ),
+            UpdateError::DoppelgangerDetected => warp_utils::reject::custom_bad_request(
+                "A doppelganger has been detected, the validator client must be restarted"
+                    .to_string(),
+            ),
+        })?;
+
+    Ok(status_data(validator_pubkey, &validator_store))
+}
@@ -43,7 +43,9 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
                         ref voting_keystore,
                         ..
                     } => (voting_keystore.path(), Some(false)),
-                    SigningMethod::Web3Signer { .. } => (None, Some(true)),
+                    SigningMethod::Web3Signer { .. } | SigningMethod::DistributedSigner { .. } => {
+                        (None, Some(true))
+                    }
                 });
 
             SingleKeystoreResponse {
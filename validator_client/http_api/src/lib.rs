@@ -1,8 +1,10 @@
 mod api_secret;
 mod create_signed_voluntary_exit;
 mod create_validator;
+mod doppelganger;
 mod graffiti;
 mod keystores;
+mod presigned_exits;
 mod remotekeys;
 mod tests;
 
@@ -11,8 +13,14 @@ pub mod test_utils;
 use graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
 use create_signed_voluntary_exit::create_signed_voluntary_exit;
+use doppelganger::{
+    get_doppelganger_status, get_doppelganger_statuses, set_doppelganger_remaining_epochs,
+};
 use graffiti_file::{determine_graffiti, GraffitiFile};
-use validator_store::ValidatorStore;
+use presigned_exits::{
+    broadcast_exits, delete_presigned_exits, get_presigned_exits, presign_exits,
+};
+use validator_store::{presigned_exits::PresignedExits, ValidatorStore};
 
 use account_utils::{
     mnemonic_from_phrase,
@@ -78,6 +86,7 @@ pub struct Context<T: SlotClock, E: EthSpec> {
     pub api_secret: ApiSecret,
     pub block_service: Option<BlockService<T, E>>,
     pub validator_store: Option<Arc<ValidatorStore<T, E>>>,
+    pub presigned_exits: Option<Arc<PresignedExits>>,
     pub validator_dir: Option<PathBuf>,
     pub secrets_dir: Option<PathBuf>,
     pub graffiti_file: Option<GraffitiFile>,
@@ -196,6 +205,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    let inner_presigned_exits = ctx.presigned_exits.clone();
+    let presigned_exits_filter = warp::any()
+        .map(move || inner_presigned_exits.clone())
+        .and_then(|presigned_exits: Option<_>| async move {
+            presigned_exits.ok_or_else(|| {
+                warp_utils::reject::custom_not_found(
+                    "pre-signed exits are not initialized.".to_string(),
+                )
+            })
+        });
+
     let inner_task_executor = ctx.task_executor.clone();
     let task_executor_filter = warp::any().map(move || inner_task_executor.clone());
 
@@ -448,6 +468,224 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             blocking_json_task(move || Ok(api_types::GenericResponse::from(result))).await
         });
 
+    // GET lighthouse/doppelganger
+    let get_lighthouse_doppelganger = warp::path("lighthouse")
+        .and(warp::path("doppelganger"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .then(|validator_store: Arc<ValidatorStore<T, E>>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(get_doppelganger_statuses(
+                    validator_store,
+                )))
+            })
+        });
+
+    // GET lighthouse/doppelganger/{validator_pubkey}
+    let get_lighthouse_doppelganger_pubkey = warp::path("lighthouse")
+        .and(warp::path("doppelganger"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .then(
+            |validator_pubkey: PublicKey, validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    get_doppelganger_status(validator_pubkey.compress(), validator_store)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/doppelganger/{validator_pubkey}
+    let post_lighthouse_doppelganger_pubkey = warp::path("lighthouse")
+        .and(warp::path("doppelganger"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .then(
+            |validator_pubkey: PublicKey,
+             request: api_types::UpdateDoppelgangerRequest,
+             validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    set_doppelganger_remaining_epochs(
+                        validator_pubkey.compress(),
+                        request,
+                        validator_store,
+                    )
+                    .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/exits
+    let get_lighthouse_exits = warp::path("lighthouse")
+        .and(warp::path("exits"))
+        .and(warp::path::end())
+        .and(presigned_exits_filter.clone())
+        .then(|presigned_exits: Arc<PresignedExits>| {
+            blocking_json_task(move || {
+                Ok(api_types::GenericResponse::from(get_presigned_exits(
+                    presigned_exits,
+                )))
+            })
+        });
+
+    // POST lighthouse/exits
+    let post_lighthouse_exits = warp::path("lighthouse")
+        .and(warp::path("exits"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(presigned_exits_filter.clone())
+        .and(validator_store_filter.clone())
+        .and(slot_clock_filter.clone())
+        .and(log_filter.clone())
+        .and(task_executor_filter.clone())
+        .then(
+            |request: api_types::PresignExitsRequest,
+             presigned_exits: Arc<PresignedExits>,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             slot_clock: T,
+             log,
+             task_executor: TaskExecutor| {
+                blocking_json_task(move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let exits = handle.block_on(presign_exits(
+                            request,
+                            presigned_exits,
+                            validator_store,
+                            slot_clock,
+                            log,
+                        ))?;
+                        Ok(api_types::GenericResponse::from(exits))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
+    // POST lighthouse/exits/broadcast
+    let post_lighthouse_exits_broadcast = warp::path("lighthouse")
+        .and(warp::path("exits"))
+        .and(warp::path("broadcast"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(presigned_exits_filter.clone())
+        .and(slot_clock_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |request: api_types::BroadcastExitsRequest,
+             presigned_exits: Arc<PresignedExits>,
+             slot_clock: T,
+             log| {
+                blocking_json_task(move || {
+                    broadcast_exits::<T, E>(request, presigned_exits, slot_clock, log)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // DELETE lighthouse/exits
+    let delete_lighthouse_exits = warp::path("lighthouse")
+        .and(warp::path("exits"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(presigned_exits_filter)
+        .then(
+            |request: api_types::DeletePresignedExitsRequest,
+             presigned_exits: Arc<PresignedExits>| {
+                blocking_json_task(move || {
+                    delete_presigned_exits(request, presigned_exits)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // POST lighthouse/slashing_protection/export
+    let post_lighthouse_slashing_protection_export = warp::path("lighthouse")
+        .and(warp::path("slashing_protection"))
+        .and(warp::path("export"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .then(
+            |request: api_types::ExportSlashingProtectionRequest,
+             validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    validator_store
+                        .copy_slashing_protection_for_keys(&request.pubkeys)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "error exporting slashing protection: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        );
+
+    // POST lighthouse/slashing_protection/check
+    let post_lighthouse_slashing_protection_check = warp::path("lighthouse")
+        .and(warp::path("slashing_protection"))
+        .and(warp::path("check"))
+        .and(warp::path::end())
+        .and(warp::body::json())
+        .and(validator_store_filter.clone())
+        .then(
+            |interchange: api_types::Interchange, validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    let pubkeys = interchange
+                        .data
+                        .iter()
+                        .map(|data| data.pubkey)
+                        .collect::<Vec<_>>();
+                    validator_store
+                        .check_slashing_protection_import(interchange)
+                        .map(|()| api_types::GenericResponse::from(pubkeys))
+                        .map_err(|e| {
+                            warp_utils::reject::custom_bad_request(format!(
+                                "slashing protection import would fail: {:?}",
+                                e
+                            ))
+                        })
+                })
+            },
+        );
+
+    // Subscribe to doppelganger detection events via Server side events
+    // GET lighthouse/doppelganger/events
+    let get_lighthouse_doppelganger_events = warp::path("lighthouse")
+        .and(warp::path("doppelganger"))
+        .and(warp::path("events"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and_then(|validator_store: Arc<ValidatorStore<T, E>>| {
+            warp_utils::task::blocking_task(move || {
+                let doppelganger_service =
+                    validator_store.doppelganger_service().ok_or_else(|| {
+                        warp_utils::reject::custom_not_found(
+                            "Doppelganger protection is disabled".to_string(),
+                        )
+                    })?;
+                let s =
+                    BroadcastStream::new(doppelganger_service.subscribe()).map(|msg| match msg {
+                        Ok(event) => Event::default().json_data(event).map_err(|e| {
+                            warp_utils::reject::server_sent_event_error(format!("{:?}", e))
+                        }),
+                        Err(e) => Err(warp_utils::reject::server_sent_event_error(format!(
+                            "Unable to receive event {}",
+                            e
+                        ))),
+                    });
+
+                Ok::<_, warp::Rejection>(warp::sse::reply(warp::sse::keep_alive().stream(s)))
+            })
+        });
+
     // POST lighthouse/validators/
     let post_validators = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -655,6 +893,8 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 builder_proposals: web3signer.builder_proposals,
                                 builder_boost_factor: web3signer.builder_boost_factor,
                                 prefer_builder_proposals: web3signer.prefer_builder_proposals,
+                                beacon_nodes: None,
+                                attestation_timing: None,
                                 description: web3signer.description,
                                 signing_definition: SigningDefinition::Web3Signer(
                                     Web3SignerDefinition {
@@ -1304,6 +1544,10 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_lighthouse_ui_fallback_health)
+                        .or(get_lighthouse_doppelganger_events.boxed())
+                        .or(get_lighthouse_doppelganger)
+                        .or(get_lighthouse_doppelganger_pubkey)
+                        .or(get_lighthouse_exits)
                         .or(get_fee_recipient)
                         .or(get_gas_limit)
                         .or(get_graffiti)
@@ -1322,6 +1566,11 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_std_keystores)
                         .or(post_std_remotekeys)
                         .or(post_graffiti)
+                        .or(post_lighthouse_doppelganger_pubkey)
+                        .or(post_lighthouse_exits)
+                        .or(post_lighthouse_exits_broadcast)
+                        .or(post_lighthouse_slashing_protection_export)
+                        .or(post_lighthouse_slashing_protection_check)
                         .recover(warp_utils::reject::handle_rejection),
                 ))
                 .or(warp::patch()
@@ -1333,6 +1582,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(delete_std_keystores)
                         .or(delete_std_remotekeys)
                         .or(delete_graffiti)
+                        .or(delete_lighthouse_exits)
                         .recover(warp_utils::reject::handle_rejection),
                 )),
         )
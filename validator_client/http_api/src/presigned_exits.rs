@@ -0,0 +1,143 @@
+use crate::create_signed_voluntary_exit::create_signed_voluntary_exit;
+use bls::PublicKeyBytes;
+use eth2::lighthouse_vc::types::{
+    BroadcastExitsRequest, DeletePresignedExitsRequest, PresignExitsRequest, PresignedExitData,
+};
+use slog::{info, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use types::{Epoch, EthSpec};
+use validator_store::presigned_exits::{Error, PresignedExitInfo, PresignedExits};
+use validator_store::ValidatorStore;
+use warp::Rejection;
+
+fn exit_data(info: PresignedExitInfo, broadcast_epoch: Option<Epoch>) -> PresignedExitData {
+    PresignedExitData {
+        voting_pubkey: info.pubkey,
+        validator_index: info.validator_index,
+        epoch: info.epoch,
+        broadcast_epoch,
+    }
+}
+
+fn convert_error(e: Error) -> Rejection {
+    match e {
+        Error::IncorrectPassphrase => warp_utils::reject::custom_bad_request(
+            "the passphrase does not decrypt any of the requested exits".to_string(),
+        ),
+        e => warp_utils::reject::custom_server_error(format!(
+            "unable to access pre-signed exits: {:?}",
+            e
+        )),
+    }
+}
+
+fn current_epoch<T: SlotClock, E: EthSpec>(slot_clock: &T) -> Result<Epoch, Rejection> {
+    slot_clock
+        .now()
+        .map(|slot| slot.epoch(E::slots_per_epoch()))
+        .ok_or_else(|| {
+            warp_utils::reject::custom_server_error("Unable to determine current epoch".to_string())
+        })
+}
+
+pub fn get_presigned_exits(presigned_exits: Arc<PresignedExits>) -> Vec<PresignedExitData> {
+    presigned_exits
+        .list()
+        .into_iter()
+        .map(|(info, broadcast_epoch)| exit_data(info, broadcast_epoch))
+        .collect()
+}
+
+pub async fn presign_exits<T: 'static + SlotClock + Clone, E: EthSpec>(
+    request: PresignExitsRequest,
+    presigned_exits: Arc<PresignedExits>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    slot_clock: T,
+    log: Logger,
+) -> Result<Vec<PresignedExitData>, Rejection> {
+    if request.passphrase.as_str().is_empty() {
+        return Err(warp_utils::reject::custom_bad_request(
+            "the passphrase must not be empty".to_string(),
+        ));
+    }
+
+    let epoch = match request.epoch {
+        Some(epoch) => epoch,
+        None => current_epoch::<T, E>(&slot_clock)?,
+    };
+
+    let mut exits = Vec::with_capacity(request.pubkeys.len());
+    for pubkey_bytes in request.pubkeys {
+        let pubkey = pubkey_bytes.decompress().map_err(|e| {
+            warp_utils::reject::custom_bad_request(format!("invalid pubkey: {:?}", e))
+        })?;
+        let signed_exit = create_signed_voluntary_exit(
+            pubkey,
+            Some(epoch),
+            validator_store.clone(),
+            slot_clock.clone(),
+            log.clone(),
+        )
+        .await?
+        .data;
+        exits.push((pubkey_bytes, signed_exit));
+    }
+
+    let data = exits
+        .iter()
+        .map(|(pubkey, exit)| PresignedExitData {
+            voting_pubkey: *pubkey,
+            validator_index: exit.message.validator_index,
+            epoch: exit.message.epoch,
+            broadcast_epoch: None,
+        })
+        .collect();
+
+    presigned_exits
+        .add(exits, request.passphrase.as_ref())
+        .map_err(convert_error)?;
+
+    Ok(data)
+}
+
+pub fn broadcast_exits<T: SlotClock, E: EthSpec>(
+    request: BroadcastExitsRequest,
+    presigned_exits: Arc<PresignedExits>,
+    slot_clock: T,
+    log: Logger,
+) -> Result<Vec<PresignedExitData>, Rejection> {
+    let broadcast_epoch = match request.broadcast_epoch {
+        Some(epoch) => epoch,
+        None => current_epoch::<T, E>(&slot_clock)?,
+    };
+
+    let armed = presigned_exits
+        .arm(
+            request.pubkeys.as_deref(),
+            request.passphrase.as_ref(),
+            broadcast_epoch,
+        )
+        .map_err(convert_error)?;
+
+    info!(
+        log,
+        "Scheduled broadcast of pre-signed exits";
+        "count" => armed.len(),
+        "broadcast_epoch" => broadcast_epoch,
+    );
+
+    Ok(armed
+        .into_iter()
+        .map(|info| exit_data(info, Some(broadcast_epoch)))
+        .collect())
+}
+
+pub fn delete_presigned_exits(
+    request: DeletePresignedExitsRequest,
+    presigned_exits: Arc<PresignedExits>,
+) -> Result<Vec<PublicKeyBytes>, Rejection> {
+    presigned_exits
+        .delete(&request.pubkeys)
+        .map_err(convert_error)
+}
@@ -33,7 +33,10 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
             let validating_pubkey = def.voting_public_key.compress();
 
             match &def.signing_definition {
-                SigningDefinition::LocalKeystore { .. } => None,
+                // Distributed validators have several remote signers, which can't be represented
+                // in the response.
+                SigningDefinition::LocalKeystore { .. }
+                | SigningDefinition::DistributedSigner(_) => None,
                 SigningDefinition::Web3Signer(Web3SignerDefinition { url, .. }) => {
                     Some(SingleListRemotekeysResponse {
                         pubkey: validating_pubkey,
@@ -128,6 +131,8 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        beacon_nodes: None,
+        attestation_timing: None,
         description: String::from("Added by remotekey API"),
         signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
             url,
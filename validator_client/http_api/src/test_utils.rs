@@ -27,7 +27,9 @@ use std::time::Duration;
 use task_executor::test_utils::TestRuntime;
 use tempfile::{tempdir, TempDir};
 use tokio::sync::oneshot;
-use validator_store::{Config as ValidatorStoreConfig, ValidatorStore};
+use validator_store::{
+    presigned_exits::PresignedExits, Config as ValidatorStoreConfig, ValidatorStore,
+};
 
 pub const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -128,6 +130,9 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            presigned_exits: Some(Arc::new(
+                PresignedExits::open_or_create(validator_dir.path()).unwrap(),
+            )),
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec,
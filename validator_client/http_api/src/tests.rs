@@ -8,8 +8,10 @@ use initialized_validators::{Config as InitializedValidatorsConfig, InitializedV
 
 use crate::{ApiSecret, Config as HttpConfig, Context};
 use account_utils::{
-    eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, validator_definitions::ValidatorDefinitions, ZeroizeString,
+    eth2_wallet::WalletBuilder,
+    mnemonic_from_phrase, random_mnemonic, random_password, random_password_string,
+    validator_definitions::{SigningDefinition, ValidatorDefinitions},
+    ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
 use eth2::{
@@ -21,7 +23,9 @@ use eth2_keystore::KeystoreBuilder;
 use logging::test_logger;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
-use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slashing_protection::{
+    interchange::InterchangeMetadata, SlashingDatabase, SLASHING_PROTECTION_FILENAME,
+};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::future::Future;
 use std::marker::PhantomData;
@@ -32,7 +36,9 @@ use std::time::Duration;
 use task_executor::test_utils::TestRuntime;
 use tempfile::{tempdir, TempDir};
 use types::graffiti::GraffitiString;
-use validator_store::{Config as ValidatorStoreConfig, ValidatorStore};
+use validator_store::{
+    presigned_exits::PresignedExits, Config as ValidatorStoreConfig, ValidatorStore,
+};
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -116,6 +122,9 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
+            presigned_exits: Some(Arc::new(
+                PresignedExits::open_or_create(validator_dir.path()).unwrap(),
+            )),
             graffiti_file: None,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec().into(),
@@ -519,6 +528,86 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_presigned_exits(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let pubkey = validator.voting_pubkey;
+        self.initialized_validators.write().set_index(&pubkey, 0);
+
+        let passphrase = ZeroizeString::from("correct horse".to_string());
+        let signed = self
+            .client
+            .post_lighthouse_exits(&PresignExitsRequest {
+                pubkeys: vec![pubkey],
+                epoch: Some(Epoch::new(256)),
+                passphrase: passphrase.clone(),
+            })
+            .await
+            .unwrap()
+            .data;
+        let expected = PresignedExitData {
+            voting_pubkey: pubkey,
+            validator_index: 0,
+            epoch: Epoch::new(256),
+            broadcast_epoch: None,
+        };
+        assert_eq!(signed, vec![expected.clone()]);
+        assert_eq!(
+            self.client.get_lighthouse_exits().await.unwrap().data,
+            vec![expected.clone()]
+        );
+
+        // The wrong passphrase can't arm the exit.
+        self.client
+            .post_lighthouse_exits_broadcast(&BroadcastExitsRequest {
+                pubkeys: None,
+                passphrase: ZeroizeString::from("wrong".to_string()),
+                broadcast_epoch: None,
+            })
+            .await
+            .unwrap_err();
+
+        let armed = PresignedExitData {
+            broadcast_epoch: Some(Epoch::new(1024)),
+            ..expected
+        };
+        assert_eq!(
+            self.client
+                .post_lighthouse_exits_broadcast(&BroadcastExitsRequest {
+                    pubkeys: Some(vec![pubkey]),
+                    passphrase,
+                    broadcast_epoch: Some(Epoch::new(1024)),
+                })
+                .await
+                .unwrap()
+                .data,
+            vec![armed.clone()]
+        );
+        assert_eq!(
+            self.client.get_lighthouse_exits().await.unwrap().data,
+            vec![armed]
+        );
+
+        assert_eq!(
+            self.client
+                .delete_lighthouse_exits(&DeletePresignedExitsRequest {
+                    pubkeys: vec![pubkey],
+                })
+                .await
+                .unwrap()
+                .data,
+            vec![pubkey]
+        );
+        assert!(self
+            .client
+            .get_lighthouse_exits()
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+
+        self
+    }
+
     fn get_current_epoch(&self) -> Epoch {
         self.slot_clock
             .now()
@@ -809,6 +898,115 @@ impl ApiTester {
 
         self
     }
+
+    pub async fn reload_definitions<F>(self, modify: F, expected_failures: usize) -> Self
+    where
+        F: FnOnce(&mut ValidatorDefinitions),
+    {
+        let mut definitions = ValidatorDefinitions::open(self._validator_dir.path()).unwrap();
+        modify(&mut definitions);
+
+        let reload = self
+            .validator_store
+            .reload_validator_definitions(definitions)
+            .await
+            .unwrap();
+        assert_eq!(reload.failed.len(), expected_failures);
+
+        self
+    }
+
+    /// Reload the definitions with `graffiti` set for every validator, checking that the enabled
+    /// validators keep their signing methods rather than being re-initialized.
+    pub async fn reload_graffiti(self, graffiti: &str) -> Self {
+        let pubkeys = ValidatorDefinitions::open(self._validator_dir.path())
+            .unwrap()
+            .as_slice()
+            .iter()
+            .filter(|def| def.enabled)
+            .map(|def| def.voting_public_key.compress())
+            .collect::<Vec<_>>();
+        let signing_methods = |tester: &Self| {
+            let validators = tester.validator_store.initialized_validators();
+            let validators = validators.read();
+            pubkeys
+                .iter()
+                .map(|pubkey| validators.signing_method(pubkey).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        let before = signing_methods(&self);
+        let graffiti_string = GraffitiString::from_str(graffiti).unwrap();
+        let tester = self
+            .reload_definitions(
+                |definitions| {
+                    for def in definitions.as_mut_slice() {
+                        def.graffiti = Some(graffiti_string.clone());
+                    }
+                },
+                0,
+            )
+            .await;
+        let after = signing_methods(&tester);
+
+        assert_eq!(before.len(), after.len());
+        for (before, after) in before.iter().zip(&after) {
+            assert!(Arc::ptr_eq(before, after));
+        }
+
+        tester
+    }
+
+    pub async fn set_doppelganger_remaining_epochs(
+        self,
+        index: usize,
+        remaining_epochs: u64,
+    ) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        let status = self
+            .client
+            .post_lighthouse_doppelganger_pubkey(&validator.voting_pubkey, remaining_epochs)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(status.remaining_epochs, Some(remaining_epochs));
+
+        self
+    }
+
+    pub async fn assert_doppelganger_status(
+        self,
+        index: usize,
+        expected_status: DoppelgangerProtectionStatus,
+    ) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        let status = self
+            .client
+            .get_lighthouse_doppelganger_pubkey(&validator.voting_pubkey)
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        assert_eq!(status.status, expected_status);
+
+        let all_statuses = self
+            .client
+            .get_lighthouse_doppelganger()
+            .await
+            .unwrap()
+            .data;
+        assert!(all_statuses.contains(&status));
+
+        assert_eq!(
+            self.validator_store
+                .doppelganger_protection_allows_signing(validator.voting_pubkey),
+            expected_status == DoppelgangerProtectionStatus::SigningEnabled
+        );
+
+        self
+    }
 }
 
 struct HdValidatorScenario {
@@ -960,6 +1158,62 @@ async fn routes_with_invalid_auth() {
                 .set_graffiti(&PublicKeyBytes::empty(), GraffitiString::default())
                 .await
         })
+        .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_doppelganger().await })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_doppelganger_pubkey(&PublicKeyBytes::empty(), 0)
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_exits().await })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_exits(&PresignExitsRequest {
+                    pubkeys: vec![],
+                    epoch: None,
+                    passphrase: String::default().into(),
+                })
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_exits_broadcast(&BroadcastExitsRequest {
+                    pubkeys: None,
+                    passphrase: String::default().into(),
+                    broadcast_epoch: None,
+                })
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .delete_lighthouse_exits(&DeletePresignedExitsRequest { pubkeys: vec![] })
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_slashing_protection_export(&ExportSlashingProtectionRequest {
+                    pubkeys: vec![],
+                })
+                .await
+        })
+        .await
+        .test_with_invalid_auth(|client| async move {
+            client
+                .post_lighthouse_slashing_protection_check(&Interchange {
+                    metadata: InterchangeMetadata {
+                        interchange_format_version: 5,
+                        genesis_validators_root: Hash256::zero(),
+                    },
+                    data: vec![],
+                })
+                .await
+        })
         .await;
 }
 
@@ -1029,6 +1283,21 @@ async fn validator_exit() {
         .await;
 }
 
+#[tokio::test]
+async fn presigned_validator_exit() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_presigned_exits(1)
+        .await;
+}
+
 #[tokio::test]
 async fn validator_enabling() {
     ApiTester::new()
@@ -1052,6 +1321,62 @@ async fn validator_enabling() {
         .assert_validators_count(2);
 }
 
+#[tokio::test]
+async fn validator_definitions_reload() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 3,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .assert_enabled_validators_count(3)
+        .assert_validators_count(3)
+        // Disable one validator and remove another.
+        .reload_definitions(
+            |definitions| {
+                definitions.as_mut_slice()[0].enabled = false;
+                let removed = definitions.as_slice()[2].voting_public_key.clone();
+                definitions.retain(|def| def.voting_public_key != removed);
+            },
+            0,
+        )
+        .await
+        .assert_enabled_validators_count(1)
+        .assert_validators_count(2)
+        // A validator which fails to initialize doesn't prevent the others from being enabled.
+        .reload_definitions(
+            |definitions| {
+                let definitions = definitions.as_mut_slice();
+                definitions[0].enabled = true;
+                if let SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } = &mut definitions[1].signing_definition
+                {
+                    voting_keystore_path.set_file_name("missing.json");
+                }
+            },
+            1,
+        )
+        .await
+        .assert_enabled_validators_count(1)
+        .assert_validators_count(2)
+        // Reloading the unmodified file restores all of the validators.
+        .reload_definitions(|_| (), 0)
+        .await
+        .assert_enabled_validators_count(3)
+        .assert_validators_count(3)
+        // A graffiti change is applied without re-initializing the validators.
+        .reload_graffiti("reloaded")
+        .await
+        .test_get_graffiti(0, "reloaded")
+        .await
+        .assert_enabled_validators_count(3);
+}
+
 #[tokio::test]
 async fn validator_gas_limit() {
     ApiTester::new()
@@ -1314,6 +1639,32 @@ async fn validator_graffiti_api() {
         .await;
 }
 
+#[tokio::test]
+async fn validator_doppelganger() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        // Validators registered at genesis have no doppelganger protection.
+        .assert_doppelganger_status(0, DoppelgangerProtectionStatus::SigningEnabled)
+        .await
+        .set_doppelganger_remaining_epochs(0, 2)
+        .await
+        .assert_doppelganger_status(0, DoppelgangerProtectionStatus::SigningDisabled)
+        .await
+        .assert_doppelganger_status(1, DoppelgangerProtectionStatus::SigningEnabled)
+        .await
+        .set_doppelganger_remaining_epochs(0, 0)
+        .await
+        .assert_doppelganger_status(0, DoppelgangerProtectionStatus::SigningEnabled)
+        .await;
+}
+
 #[tokio::test]
 async fn keystore_validator_creation() {
     ApiTester::new()
@@ -1410,6 +1410,76 @@ async fn delete_then_reimport() {
     .await
 }
 
+#[tokio::test]
+async fn export_and_check_slashing_protection() {
+    run_dual_vc_test(|src, dest| async move {
+        let password = random_password_string();
+        let keystores = (0..2)
+            .map(|_| new_keystore(password.clone()))
+            .collect::<Vec<_>>();
+        let pubkeys = keystores.iter().map(keystore_pubkey).collect::<Vec<_>>();
+
+        let import_res = src
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: keystores.clone(),
+                passwords: vec![password.clone(); keystores.len()],
+                slashing_protection: None,
+            })
+            .await
+            .unwrap();
+        check_keystore_import_response(&import_res, all_imported(keystores.len()));
+
+        // Exporting a copy of the slashing protection leaves the keystores on the source VC.
+        let interchange = src
+            .client
+            .post_lighthouse_slashing_protection_export(&ExportSlashingProtectionRequest {
+                pubkeys: pubkeys.clone(),
+            })
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            interchange
+                .data
+                .iter()
+                .map(|data| data.pubkey)
+                .collect::<Vec<_>>(),
+            pubkeys
+        );
+        let get_res = src.client.get_keystores().await.unwrap();
+        check_keystore_get_response(&get_res, &keystores);
+
+        // The destination VC accepts the interchange without importing it.
+        let checked = dest
+            .client
+            .post_lighthouse_slashing_protection_check(&interchange)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(checked, pubkeys);
+        assert!(dest
+            .client
+            .post_lighthouse_slashing_protection_export(&ExportSlashingProtectionRequest {
+                pubkeys: pubkeys.clone(),
+            })
+            .await
+            .unwrap()
+            .data
+            .data
+            .is_empty());
+
+        // An interchange which can't be imported is rejected.
+        let mut invalid = interchange;
+        invalid.metadata.genesis_validators_root = Hash256::repeat_byte(0xff);
+        dest.client
+            .post_lighthouse_slashing_protection_check(&invalid)
+            .await
+            .unwrap_err();
+    })
+    .await
+}
+
 #[tokio::test]
 async fn get_empty_remotekeys() {
     run_test(|tester| async move {
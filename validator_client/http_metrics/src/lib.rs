@@ -7,7 +7,7 @@ use malloc_utils::scrape_allocator_metrics;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use slog::{crit, info, Logger};
-use slot_clock::{SlotClock, SystemTimeSlotClock};
+use slot_clock::{SlotClock, SourcedSlotClock};
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
@@ -37,8 +37,8 @@ impl From<String> for Error {
 
 /// Contains objects which have shared access from inside/outside of the metrics server.
 pub struct Shared<E: EthSpec> {
-    pub validator_store: Option<Arc<ValidatorStore<SystemTimeSlotClock, E>>>,
-    pub duties_service: Option<Arc<DutiesService<SystemTimeSlotClock, E>>>,
+    pub validator_store: Option<Arc<ValidatorStore<SourcedSlotClock, E>>>,
+    pub duties_service: Option<Arc<DutiesService<SourcedSlotClock, E>>>,
     pub genesis_time: Option<u64>,
 }
 
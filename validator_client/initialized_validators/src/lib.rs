@@ -9,27 +9,32 @@
 pub mod key_cache;
 
 use account_utils::{
+    password_source::{self, PasswordSource},
     read_password, read_password_from_user, read_password_string,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
-        CONFIG_FILENAME,
+        self, AttestationTiming, SigningDefinition, ValidatorDefinition, ValidatorDefinitions,
+        Web3SignerDefinition, CONFIG_FILENAME,
     },
     ZeroizeString,
 };
-use eth2_keystore::Keystore;
+use eth2_keystore::json_keystore::Kdf;
+use eth2_keystore::{Keystore, PlainText};
 use lockfile::{Lockfile, LockfileError};
 use metrics::set_gauge;
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
-use signing_method::SigningMethod;
+use signing_method::{CoSigner, RetiredClient, SigningMethod, Web3SignerHttpClient};
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 use types::graffiti::GraffitiString;
 use types::{Address, Graffiti, Keypair, PublicKey, PublicKeyBytes};
 use url::{ParseError, Url};
@@ -43,6 +48,16 @@ use key_cache::KeyCache;
 /// that time is outside the synchronous assumptions of Eth2.
 const DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT: Duration = Duration::from_secs(12);
 
+/// The maximum memory used by the key derivation functions of keystores being decrypted in
+/// parallel, in MiB.
+///
+/// A keystore using the default scrypt parameters needs 256 MiB, so this allows up to 8 of them to
+/// be decrypted at once.
+const KEYSTORE_DECRYPTION_MEMORY_BUDGET_MIB: u32 = 2048;
+
+/// The minimum time between progress logs while decrypting keystores.
+const KEYSTORE_DECRYPTION_LOG_INTERVAL: Duration = Duration::from_secs(5);
+
 // Use TTY instead of stdin to capture passwords from users.
 const USE_STDIN: bool = false;
 
@@ -53,6 +68,11 @@ pub const DEFAULT_WEB3SIGNER_KEEP_ALIVE: Option<Duration> = Some(Duration::from_
 pub struct Config {
     pub web3_signer_keep_alive_timeout: Option<Duration>,
     pub web3_signer_max_idle_connections: Option<usize>,
+    /// The maximum number of requests in flight to each Web3Signer instance, or unlimited if
+    /// `None`.
+    pub web3_signer_max_concurrent_requests: Option<usize>,
+    /// Use HTTP/2 for all requests to Web3Signer instances, without first negotiating it.
+    pub web3_signer_http2: bool,
 }
 
 impl Default for Config {
@@ -60,6 +80,8 @@ impl Default for Config {
         Config {
             web3_signer_keep_alive_timeout: DEFAULT_WEB3SIGNER_KEEP_ALIVE,
             web3_signer_max_idle_connections: None,
+            web3_signer_max_concurrent_requests: None,
+            web3_signer_http2: false,
         }
     }
 }
@@ -72,6 +94,432 @@ pub enum OnDecryptFailure {
     Error,
 }
 
+/// The action to take when an enabled validator fails to initialize.
+#[derive(Clone, Copy, PartialEq)]
+enum OnInitFailure {
+    /// Return the error, leaving any validators after the failed one uninitialized.
+    Exit,
+    /// Skip the validator and carry on initializing the others. Validators without a password are
+    /// skipped too, rather than blocking while prompting for one.
+    Skip,
+}
+
+/// The outcome of `InitializedValidators::reload_definitions`.
+#[derive(Debug, Default)]
+pub struct DefinitionsReload {
+    /// Validators which were enabled by the reload.
+    pub added: Vec<PublicKeyBytes>,
+    /// Validators which were disabled or removed by the reload.
+    pub removed: Vec<PublicKeyBytes>,
+    /// Enabled validators which failed to initialize, and are not performing duties.
+    pub failed: Vec<(PublicKey, Error)>,
+}
+
+/// Loads and decrypts the keystores of a set of validator definitions.
+///
+/// Decryption is expensive, so `InitializedValidators::reload_keystore_loader` provides a loader
+/// which can be run without holding a lock on the `InitializedValidators`.
+pub struct KeystoreLoader {
+    definitions: ValidatorDefinitions,
+    /// Validators which are already initialized, whose keystores aren't decrypted.
+    initialized: HashSet<PublicKeyBytes>,
+    validators_dir: PathBuf,
+    log: Logger,
+}
+
+/// The keystores loaded by a `KeystoreLoader`.
+pub struct LoadedKeystores {
+    /// The definitions the keystores were loaded for.
+    definitions: ValidatorDefinitions,
+    key_cache: KeyCache,
+    key_stores: HashMap<PathBuf, Keystore>,
+    has_local_definitions: bool,
+}
+
+impl KeystoreLoader {
+    /// Decrypt the key cache and the keystores of the uninitialized validators, holding the lock
+    /// file of the key cache while it is read.
+    pub async fn load(self) -> Result<LoadedKeystores, Error> {
+        let key_cache_path = KeyCache::cache_file_path(&self.validators_dir);
+        let cache_lockfile_path =
+            get_lockfile_path(&key_cache_path).ok_or(Error::BadKeyCachePath(key_cache_path))?;
+        let cache_lockfile = Lockfile::new(cache_lockfile_path)?;
+        let cache =
+            KeyCache::open_or_create(&self.validators_dir).map_err(Error::UnableToOpenKeyCache)?;
+        drop(cache_lockfile);
+
+        self.decrypt(cache).await
+    }
+
+    /// As `load`, for a caller which already holds the lock file of the key cache.
+    async fn load_unlocked(self) -> Result<LoadedKeystores, Error> {
+        let cache =
+            KeyCache::open_or_create(&self.validators_dir).map_err(Error::UnableToOpenKeyCache)?;
+        self.decrypt(cache).await
+    }
+
+    async fn decrypt(self, cache: KeyCache) -> Result<LoadedKeystores, Error> {
+        //use key cache if available
+        let mut key_stores = HashMap::new();
+
+        // Check if there is at least one local definition.
+        let has_local_definitions = self.definitions.as_slice().iter().any(|def| {
+            matches!(
+                def.signing_definition,
+                SigningDefinition::LocalKeystore { .. }
+            )
+        });
+
+        // Only decrypt cache when there is at least one local definition.
+        // Decrypting cache is a very expensive operation which is never used for web3signer.
+        let mut key_cache = if has_local_definitions {
+            self.decrypt_key_cache(cache, &mut key_stores, OnDecryptFailure::CreateNew)
+                .await?
+        } else {
+            // Assign an empty KeyCache if all definitions are of the Web3Signer type.
+            KeyCache::new()
+        };
+
+        if has_local_definitions {
+            self.decrypt_keystores_in_parallel(&mut key_cache, &mut key_stores)
+                .await;
+        }
+
+        Ok(LoadedKeystores {
+            definitions: self.definitions,
+            key_cache,
+            key_stores,
+            has_local_definitions,
+        })
+    }
+
+    /// Tries to decrypt the key cache.
+    ///
+    /// Returns the decrypted cache if decryption was successful, or an error if a required password
+    /// wasn't provided and couldn't be read interactively.
+    ///
+    /// In the case that the cache contains UUIDs for unknown validator definitions then it cannot
+    /// be decrypted and will be replaced by a new empty cache.
+    ///
+    /// The mutable `key_stores` argument will be used to accelerate decyption by bypassing
+    /// filesystem accesses for keystores that are already known. In the case that a keystore
+    /// from the validator definitions is not yet in this map, it will be loaded from disk and
+    /// inserted into the map.
+    pub async fn decrypt_key_cache(
+        &self,
+        mut cache: KeyCache,
+        key_stores: &mut HashMap<PathBuf, Keystore>,
+        on_failure: OnDecryptFailure,
+    ) -> Result<KeyCache, Error> {
+        // Read relevant key stores from the filesystem.
+        let mut definitions_map = HashMap::new();
+        for def in self.definitions.as_slice().iter().filter(|def| def.enabled) {
+            match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_path,
+                    ..
+                } => {
+                    use std::collections::hash_map::Entry::*;
+                    let key_store = match key_stores.entry(voting_keystore_path.clone()) {
+                        Vacant(entry) => entry.insert(open_keystore(voting_keystore_path)?),
+                        Occupied(entry) => entry.into_mut(),
+                    };
+                    definitions_map.insert(*key_store.uuid(), def);
+                }
+                // Remote signer validators don't interact with the key cache.
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::DistributedSigner { .. } => (),
+            }
+        }
+
+        //check if all paths are in the definitions_map
+        for uuid in cache.uuids() {
+            if !definitions_map.contains_key(uuid) {
+                debug!(
+                    self.log,
+                    "Resetting the key cache";
+                    "keystore_uuid" => %uuid,
+                    "reason" => "impossible to decrypt due to missing keystore",
+                );
+                return Ok(KeyCache::new());
+            }
+        }
+
+        //collect passwords
+        let mut passwords = Vec::new();
+        let mut public_keys = Vec::new();
+        for uuid in cache.uuids() {
+            let def = definitions_map.get(uuid).expect("Existence checked before");
+            match &def.signing_definition {
+                SigningDefinition::LocalKeystore {
+                    voting_keystore_password_path,
+                    voting_keystore_password,
+                    voting_keystore_path,
+                    voting_keystore_password_source,
+                } => {
+                    let pw = if let Some(p) = voting_keystore_password {
+                        p.as_ref().to_vec().into()
+                    } else if let Some(source) = voting_keystore_password_source {
+                        read_password_from_source(source, &def.voting_public_key)
+                            .await?
+                            .as_ref()
+                            .to_vec()
+                            .into()
+                    } else if let Some(path) = voting_keystore_password_path {
+                        read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
+                    } else {
+                        let keystore = open_keystore(voting_keystore_path)?;
+                        unlock_keystore_via_stdin_password(&keystore, voting_keystore_path)?
+                            .0
+                            .as_ref()
+                            .to_vec()
+                            .into()
+                    };
+                    passwords.push(pw);
+                    public_keys.push(def.voting_public_key.clone());
+                }
+                // Remote signer validators don't interact with the key cache.
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::DistributedSigner { .. } => (),
+            };
+        }
+
+        //decrypt
+        tokio::task::spawn_blocking(move || match cache.decrypt(passwords, public_keys) {
+            Ok(_) | Err(key_cache::Error::AlreadyDecrypted) => Ok(cache),
+            _ if matches!(on_failure, OnDecryptFailure::CreateNew) => Ok(KeyCache::new()),
+            Err(e) => Err(e),
+        })
+        .await
+        .map_err(Error::TokioJoin)?
+        .map_err(Error::UnableToDecryptKeyCache)
+    }
+
+    /// Decrypt the keystores of the enabled, uninitialized local validators which are missing from
+    /// `key_cache`, adding them to the cache.
+    ///
+    /// Keystores are decrypted across all CPU cores, limited by
+    /// `KEYSTORE_DECRYPTION_MEMORY_BUDGET_MIB`. Keystores without a password, or which fail to
+    /// decrypt, are skipped here and left to `InitializedValidator::from_definition` to prompt for a
+    /// password or report the error.
+    async fn decrypt_keystores_in_parallel(
+        &self,
+        key_cache: &mut KeyCache,
+        key_stores: &mut HashMap<PathBuf, Keystore>,
+    ) {
+        use std::collections::hash_map::Entry::*;
+
+        let mut pending = vec![];
+        for def in self.definitions.as_slice() {
+            if !def.enabled || self.initialized.contains(&def.voting_public_key.compress()) {
+                continue;
+            }
+            let SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+                voting_keystore_password_source,
+            } = &def.signing_definition
+            else {
+                continue;
+            };
+
+            let keystore = match key_stores.entry(voting_keystore_path.clone()) {
+                Vacant(entry) => match open_keystore(voting_keystore_path) {
+                    Ok(keystore) => entry.insert(keystore),
+                    Err(_) => continue,
+                },
+                Occupied(entry) => entry.into_mut(),
+            };
+            if key_cache.get(keystore.uuid()).is_some() {
+                continue;
+            }
+
+            let password = match (
+                voting_keystore_password_path,
+                voting_keystore_password,
+                voting_keystore_password_source,
+            ) {
+                (_, Some(password), _) => PlainText::from(password.as_ref().to_vec()),
+                (_, None, Some(source)) => {
+                    match read_password_from_source(source, &def.voting_public_key).await {
+                        Ok(password) => PlainText::from(password.as_ref().to_vec()),
+                        Err(_) => continue,
+                    }
+                }
+                (Some(path), None, None) => match read_password(path) {
+                    Ok(password) => password,
+                    Err(_) => continue,
+                },
+                (None, None, None) => continue,
+            };
+            pending.push((keystore.clone(), password));
+        }
+
+        let total = pending.len();
+        if total == 0 {
+            return;
+        }
+
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        info!(
+            self.log,
+            "Decrypting validator keystores";
+            "count" => total,
+            "threads" => threads,
+        );
+        set_gauge(&validator_metrics::KEYSTORES_TO_DECRYPT_COUNT, total as i64);
+        set_gauge(&validator_metrics::KEYSTORES_DECRYPTED_COUNT, 0);
+
+        let thread_permits = Arc::new(Semaphore::new(threads));
+        let memory_permits = Arc::new(Semaphore::new(
+            KEYSTORE_DECRYPTION_MEMORY_BUDGET_MIB as usize,
+        ));
+        let mut tasks = JoinSet::new();
+        for (keystore, password) in pending {
+            let thread_permits = thread_permits.clone();
+            let memory_permits = memory_permits.clone();
+            tasks.spawn(async move {
+                let _thread_permit = thread_permits.acquire_owned().await.ok()?;
+                let _memory_permit = memory_permits
+                    .acquire_many_owned(decryption_memory_mib(&keystore))
+                    .await
+                    .ok()?;
+                tokio::task::spawn_blocking(move || {
+                    let _timer = validator_metrics::start_timer(
+                        &validator_metrics::KEYSTORE_DECRYPTION_TIMES,
+                    );
+                    let keypair = keystore.decrypt_keypair(password.as_bytes()).ok()?;
+                    Some((*keystore.uuid(), keypair, password))
+                })
+                .await
+                .ok()
+                .flatten()
+            });
+        }
+
+        let started = Instant::now();
+        let mut last_log = started;
+        let mut completed = 0;
+        let mut decrypted = 0;
+        while let Some(result) = tasks.join_next().await {
+            completed += 1;
+            if let Ok(Some((uuid, keypair, password))) = result {
+                key_cache.add(keypair, &uuid, password);
+                decrypted += 1;
+            }
+            set_gauge(
+                &validator_metrics::KEYSTORES_DECRYPTED_COUNT,
+                completed as i64,
+            );
+
+            if completed < total && last_log.elapsed() >= KEYSTORE_DECRYPTION_LOG_INTERVAL {
+                let eta = started.elapsed() / completed as u32 * (total - completed) as u32;
+                info!(
+                    self.log,
+                    "Decrypting validator keystores";
+                    "completed" => completed,
+                    "total" => total,
+                    "eta_secs" => eta.as_secs(),
+                );
+                last_log = Instant::now();
+            }
+        }
+
+        info!(
+            self.log,
+            "Decrypted validator keystores";
+            "decrypted" => decrypted,
+            "failed" => total - decrypted,
+            "duration_secs" => started.elapsed().as_secs(),
+        );
+    }
+}
+
+/// The client shared by all validators which use the same Web3Signer instance.
+#[derive(Clone)]
+struct Web3SignerClient {
+    client: ReloadableClient,
+    request_permits: Option<Arc<Semaphore>>,
+}
+
+/// An HTTP client for Web3Signer-compatible servers and the TLS files it was built from, so that
+/// it can be rebuilt when they change on disk.
+#[derive(Clone)]
+struct ReloadableClient {
+    http_client: Web3SignerHttpClient,
+    /// The URLs of the servers the client is used for.
+    url: String,
+    root_certificate_path: Option<PathBuf>,
+    client_identity_path: Option<PathBuf>,
+    client_identity_password: Option<String>,
+    request_timeout: Duration,
+    /// The modification times of the TLS files the client was built from.
+    tls_files_modified: Vec<Option<SystemTime>>,
+}
+
+impl ReloadableClient {
+    fn build(
+        url: String,
+        root_certificate_path: Option<PathBuf>,
+        client_identity_path: Option<PathBuf>,
+        client_identity_password: Option<String>,
+        request_timeout: Duration,
+        config: &Config,
+    ) -> Result<Self, Error> {
+        let tls_files_modified =
+            tls_files_modified(&[&root_certificate_path, &client_identity_path]);
+        let http_client = build_web3_signer_client(
+            root_certificate_path.clone(),
+            client_identity_path.clone(),
+            client_identity_password.clone(),
+            request_timeout,
+            config,
+        )?;
+        Ok(Self {
+            http_client: Web3SignerHttpClient::new(http_client),
+            url,
+            root_certificate_path,
+            client_identity_path,
+            client_identity_password,
+            request_timeout,
+            tls_files_modified,
+        })
+    }
+
+    fn current_tls_files_modified(&self) -> Vec<Option<SystemTime>> {
+        tls_files_modified(&[&self.root_certificate_path, &self.client_identity_path])
+    }
+
+    fn tls_files_changed(&self) -> bool {
+        self.current_tls_files_modified() != self.tls_files_modified
+    }
+
+    /// Rebuild the client from the current TLS files, returning the previous client.
+    fn reload(&mut self, config: &Config) -> Result<RetiredClient, Error> {
+        let tls_files_modified = self.current_tls_files_modified();
+        let http_client = build_web3_signer_client(
+            self.root_certificate_path.clone(),
+            self.client_identity_path.clone(),
+            self.client_identity_password.clone(),
+            self.request_timeout,
+            config,
+        )?;
+        self.tls_files_modified = tls_files_modified;
+        Ok(self.http_client.replace(http_client))
+    }
+}
+
+/// A Web3Signer client which was rebuilt because its TLS files changed.
+pub struct ReloadedWeb3SignerClient {
+    /// The URLs of the servers the client is used for.
+    pub url: String,
+    /// The previous client, which may still have requests in flight.
+    pub retired: RetiredClient,
+    /// The request timeout of the client, by which any requests in flight will have finished.
+    pub request_timeout: Duration,
+}
+
 pub struct KeystoreAndPassword {
     pub keystore: Keystore,
     pub password: Option<ZeroizeString>,
@@ -100,6 +548,10 @@ pub enum Error {
     UnableToDecryptKeystore(eth2_keystore::Error),
     /// There was a filesystem error when reading the keystore password from disk.
     UnableToReadVotingKeystorePassword(io::Error),
+    /// The definition has no keystore password and prompting for one is not permitted.
+    MissingVotingKeystorePassword,
+    /// The keystore password could not be read from its secret storage backend.
+    UnableToReadVotingKeystorePasswordFromSource(password_source::Error),
     /// There was an error updating the on-disk validator definitions file.
     UnableToSaveDefinitions(validator_definitions::Error),
     /// It is not legal to try and initialize a disabled validator definition.
@@ -129,6 +581,8 @@ pub enum Error {
     InvalidWeb3SignerClientIdentityCertificateFile(io::Error),
     InvalidWeb3SignerClientIdentityCertificate(ReqwestError),
     UnableToBuildWeb3SignerClient(ReqwestError),
+    /// The threshold or co-signers of a distributed validator are invalid.
+    InvalidDistributedSignerDefinition(String),
     /// Unable to apply an action to a validator.
     InvalidActionOnValidator,
     UnableToReadValidatorPassword(String),
@@ -153,6 +607,11 @@ pub struct InitializedValidator {
     builder_proposals: Option<bool>,
     builder_boost_factor: Option<u64>,
     prefer_builder_proposals: Option<bool>,
+    /// The beacon nodes to use for this validator's duties, most preferred first.
+    beacon_nodes: Vec<SensitiveUrl>,
+    attestation_timing: Option<AttestationTiming>,
+    /// The client used to reach the co-signers of a distributed validator.
+    co_signer_client: Option<ReloadableClient>,
     /// The validators index in `state.validators`, to be updated by an external service.
     index: Option<u64>,
 }
@@ -168,8 +627,8 @@ impl InitializedValidator {
                 option_lockfile.as_mut()
             })
             .ok(),
-            // Web3Signer validators do not have any lockfiles.
-            SigningMethod::Web3Signer { .. } => None,
+            // Remote signer validators do not have any lockfiles.
+            SigningMethod::Web3Signer { .. } | SigningMethod::DistributedSigner { .. } => None,
         }
     }
 
@@ -200,6 +659,10 @@ impl InitializedValidator {
     pub fn get_graffiti(&self) -> Option<Graffiti> {
         self.graffiti
     }
+
+    pub fn get_beacon_nodes(&self) -> &[SensitiveUrl] {
+        &self.beacon_nodes
+    }
 }
 
 fn open_keystore(path: &Path) -> Result<Keystore, Error> {
@@ -207,6 +670,35 @@ fn open_keystore(path: &Path) -> Result<Keystore, Error> {
     Keystore::from_json_reader(keystore_file).map_err(Error::UnableToParseVotingKeystore)
 }
 
+/// Reads a voting keystore password from its secret storage backend.
+///
+/// The backends block on I/O, so the password is read off the core executor.
+async fn read_password_from_source(
+    source: &PasswordSource,
+    voting_public_key: &PublicKey,
+) -> Result<ZeroizeString, Error> {
+    let source = source.clone();
+    let voting_public_key = voting_public_key.clone();
+    tokio::task::spawn_blocking(move || source.read_password(&voting_public_key))
+        .await
+        .map_err(Error::TokioJoin)?
+        .map_err(Error::UnableToReadVotingKeystorePasswordFromSource)
+}
+
+/// Returns the memory required to derive the decryption key of `keystore`, in MiB.
+fn decryption_memory_mib(keystore: &Keystore) -> u32 {
+    match keystore.kdf() {
+        // Scrypt requires `128 * r * n` bytes.
+        Kdf::Scrypt(scrypt) => {
+            let bytes = 128 * u64::from(scrypt.r) * u64::from(scrypt.n);
+            bytes
+                .div_ceil(1 << 20)
+                .min(u64::from(KEYSTORE_DECRYPTION_MEMORY_BUDGET_MIB)) as u32
+        }
+        Kdf::Pbkdf2(_) => 0,
+    }
+}
+
 fn get_lockfile_path(file_path: &Path) -> Option<PathBuf> {
     file_path
         .file_name()
@@ -227,13 +719,15 @@ impl InitializedValidator {
         def: ValidatorDefinition,
         key_cache: &mut KeyCache,
         key_stores: &mut HashMap<PathBuf, Keystore>,
-        web3_signer_client_map: &mut Option<HashMap<Web3SignerDefinition, Client>>,
+        web3_signer_client_map: &mut Option<HashMap<Web3SignerDefinition, Web3SignerClient>>,
         config: &Config,
+        prompt_for_password: bool,
     ) -> Result<Self, Error> {
         if !def.enabled {
             return Err(Error::UnableToInitializeDisabledValidator);
         }
 
+        let mut co_signer_client = None;
         let signing_method = match def.signing_definition {
             // Load the keystore, password, decrypt the keypair and create a lockfile for a
             // EIP-2335 keystore on the local filesystem.
@@ -241,6 +735,7 @@ impl InitializedValidator {
                 voting_keystore_path,
                 voting_keystore_password_path,
                 voting_keystore_password,
+                voting_keystore_password_source,
             } => {
                 use std::collections::hash_map::Entry::*;
                 let voting_keystore = match key_stores.entry(voting_keystore_path.clone()) {
@@ -253,22 +748,39 @@ impl InitializedValidator {
                 } else {
                     let keystore = voting_keystore.clone();
                     let keystore_path = voting_keystore_path.clone();
+                    let voting_public_key = def.voting_public_key.clone();
                     // Decoding a local keystore can take several seconds, therefore it's best
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
                     let (password, keypair) = tokio::task::spawn_blocking(move || {
                         Result::<_, Error>::Ok(
-                            match (voting_keystore_password_path, voting_keystore_password) {
+                            match (
+                                voting_keystore_password_path,
+                                voting_keystore_password,
+                                voting_keystore_password_source,
+                            ) {
                                 // If the password is supplied, use it and ignore the path
                                 // (if supplied).
-                                (_, Some(password)) => (
+                                (_, Some(password), _) => (
                                     password.as_ref().to_vec().into(),
                                     keystore
                                         .decrypt_keypair(password.as_ref())
                                         .map_err(Error::UnableToDecryptKeystore)?,
                                 ),
+                                // If a secret storage backend is supplied, use it and ignore the
+                                // path (if supplied).
+                                (_, None, Some(source)) => {
+                                    let password =
+                                        source.read_password(&voting_public_key).map_err(
+                                            Error::UnableToReadVotingKeystorePasswordFromSource,
+                                        )?;
+                                    let keypair = keystore
+                                        .decrypt_keypair(password.as_ref())
+                                        .map_err(Error::UnableToDecryptKeystore)?;
+                                    (password.as_ref().to_vec().into(), keypair)
+                                }
                                 // If only the path is supplied, use the path.
-                                (Some(path), None) => {
+                                (Some(path), None, None) => {
                                     let password = read_password(path)
                                         .map_err(Error::UnableToReadVotingKeystorePassword)?;
                                     let keypair = keystore
@@ -276,8 +788,11 @@ impl InitializedValidator {
                                         .map_err(Error::UnableToDecryptKeystore)?;
                                     (password, keypair)
                                 }
+                                (None, None, None) if !prompt_for_password => {
+                                    return Err(Error::MissingVotingKeystorePassword)
+                                }
                                 // If there is no password available, maybe prompt for a password.
-                                (None, None) => {
+                                (None, None, None) => {
                                     let (password, keypair) = unlock_keystore_via_stdin_password(
                                         &keystore,
                                         &keystore_path,
@@ -317,47 +832,82 @@ impl InitializedValidator {
                 let signing_url = build_web3_signer_url(&web3_signer.url, &def.voting_public_key)
                     .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
 
-                let request_timeout = web3_signer
-                    .request_timeout_ms
-                    .map(Duration::from_millis)
-                    .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
-
                 // Check if a client has already been initialized for this remote signer url.
-                let http_client = if let Some(client_map) = web3_signer_client_map {
-                    match client_map.get(&web3_signer) {
-                        Some(client) => client.clone(),
-                        None => {
-                            let client = build_web3_signer_client(
+                let client_map = web3_signer_client_map.get_or_insert_with(HashMap::new);
+                let client = match client_map.get(&web3_signer) {
+                    Some(client) => client.clone(),
+                    None => {
+                        let client = Web3SignerClient {
+                            client: ReloadableClient::build(
+                                web3_signer.url.clone(),
                                 web3_signer.root_certificate_path.clone(),
                                 web3_signer.client_identity_path.clone(),
                                 web3_signer.client_identity_password.clone(),
-                                request_timeout,
-                                config.web3_signer_keep_alive_timeout,
-                                config.web3_signer_max_idle_connections,
-                            )?;
-                            client_map.insert(web3_signer, client.clone());
-                            client
-                        }
+                                web3_signer_request_timeout(&web3_signer),
+                                config,
+                            )?,
+                            request_permits: config
+                                .web3_signer_max_concurrent_requests
+                                .map(|n| Arc::new(Semaphore::new(n))),
+                        };
+                        client_map.insert(web3_signer, client.clone());
+                        client
                     }
-                } else {
-                    // There are no clients in the map.
-                    let mut new_web3_signer_client_map: HashMap<Web3SignerDefinition, Client> =
-                        HashMap::new();
-                    let client = build_web3_signer_client(
-                        web3_signer.root_certificate_path.clone(),
-                        web3_signer.client_identity_path.clone(),
-                        web3_signer.client_identity_password.clone(),
-                        request_timeout,
-                        config.web3_signer_keep_alive_timeout,
-                        config.web3_signer_max_idle_connections,
-                    )?;
-                    new_web3_signer_client_map.insert(web3_signer, client.clone());
-                    *web3_signer_client_map = Some(new_web3_signer_client_map);
-                    client
                 };
 
                 SigningMethod::Web3Signer {
                     signing_url,
+                    http_client: client.client.http_client,
+                    request_permits: client.request_permits,
+                    voting_public_key: def.voting_public_key,
+                }
+            }
+            SigningDefinition::DistributedSigner(distributed) => {
+                distributed
+                    .validate()
+                    .map_err(Error::InvalidDistributedSignerDefinition)?;
+
+                let request_timeout = distributed
+                    .request_timeout_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
+
+                let client = ReloadableClient::build(
+                    distributed
+                        .co_signers
+                        .iter()
+                        .map(|co_signer| co_signer.url.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    distributed.root_certificate_path,
+                    None,
+                    None,
+                    request_timeout,
+                    config,
+                )?;
+
+                // Each co-signer is a Web3Signer-compatible server which signs with its key share.
+                let co_signers = distributed
+                    .co_signers
+                    .into_iter()
+                    .map(|co_signer| {
+                        let signing_url =
+                            build_web3_signer_url(&co_signer.url, &co_signer.share_public_key)
+                                .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
+                        Ok(CoSigner {
+                            signing_url,
+                            share_index: co_signer.share_index,
+                            share_public_key: co_signer.share_public_key,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                let http_client = client.http_client.clone();
+                co_signer_client = Some(client);
+
+                SigningMethod::DistributedSigner {
+                    co_signers,
+                    threshold: distributed.threshold,
                     http_client,
                     voting_public_key: def.voting_public_key,
                 }
@@ -372,16 +922,34 @@ impl InitializedValidator {
             builder_proposals: def.builder_proposals,
             builder_boost_factor: def.builder_boost_factor,
             prefer_builder_proposals: def.prefer_builder_proposals,
+            beacon_nodes: def.beacon_nodes.unwrap_or_default(),
+            attestation_timing: def.attestation_timing,
+            co_signer_client,
             index: None,
         })
     }
 
+    /// Update the fields of `self` which don't affect signing from `def`.
+    fn update_metadata(&mut self, def: &ValidatorDefinition) {
+        self.graffiti = def.graffiti.clone().map(Into::into);
+        self.suggested_fee_recipient = def.suggested_fee_recipient;
+        self.gas_limit = def.gas_limit;
+        self.builder_proposals = def.builder_proposals;
+        self.builder_boost_factor = def.builder_boost_factor;
+        self.prefer_builder_proposals = def.prefer_builder_proposals;
+        self.beacon_nodes = def.beacon_nodes.clone().unwrap_or_default();
+        self.attestation_timing = def.attestation_timing;
+    }
+
     /// Returns the voting public key for this validator.
     pub fn voting_public_key(&self) -> &PublicKey {
         match self.signing_method.as_ref() {
             SigningMethod::LocalKeystore { voting_keypair, .. } => &voting_keypair.pk,
             SigningMethod::Web3Signer {
                 voting_public_key, ..
+            }
+            | SigningMethod::DistributedSigner {
+                voting_public_key, ..
             } => voting_public_key,
         }
     }
@@ -413,18 +981,48 @@ fn build_web3_signer_url(base_url: &str, voting_public_key: &PublicKey) -> Resul
     Url::parse(base_url)?.join(&format!("api/v1/eth2/sign/{}", voting_public_key))
 }
 
-fn build_web3_signer_client(
-    root_certificate_path: Option<PathBuf>,
+fn web3_signer_request_timeout(web3_signer: &Web3SignerDefinition) -> Duration {
+    web3_signer
+        .request_timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT)
+}
+
+/// Returns the modification times of the TLS files at `paths`, or `None` for files which are not
+/// configured or can't be read.
+fn tls_files_modified(paths: &[&Option<PathBuf>]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| {
+            path.as_ref()
+                .and_then(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+        })
+        .collect()
+}
+
+fn build_web3_signer_client(
+    root_certificate_path: Option<PathBuf>,
     client_identity_path: Option<PathBuf>,
     client_identity_password: Option<String>,
     request_timeout: Duration,
-    keep_alive_timeout: Option<Duration>,
-    max_idle_connections: Option<usize>,
+    config: &Config,
 ) -> Result<Client, Error> {
     let builder = Client::builder()
         .timeout(request_timeout)
-        .pool_idle_timeout(keep_alive_timeout)
-        .pool_max_idle_per_host(max_idle_connections.unwrap_or(usize::MAX));
+        .pool_idle_timeout(config.web3_signer_keep_alive_timeout)
+        .pool_max_idle_per_host(
+            config
+                .web3_signer_max_idle_connections
+                .unwrap_or(usize::MAX),
+        );
+
+    // Multiplex all requests over a single connection, rather than opening a connection for each
+    // concurrent request.
+    let builder = if config.web3_signer_http2 {
+        builder.http2_prior_knowledge().http2_adaptive_window(true)
+    } else {
+        builder
+    };
 
     let builder = if let Some(path) = root_certificate_path {
         let certificate = load_pem_certificate(path)?;
@@ -458,12 +1056,13 @@ fn unlock_keystore_via_stdin_password(
 ) -> Result<(ZeroizeString, Keypair), Error> {
     eprintln!();
     eprintln!(
-        "The {} file does not contain either of the following fields for {:?}:",
+        "The {} file does not contain any of the following fields for {:?}:",
         CONFIG_FILENAME, keystore_path
     );
     eprintln!();
     eprintln!(" - voting_keystore_password");
     eprintln!(" - voting_keystore_password_path");
+    eprintln!(" - voting_keystore_password_source");
     eprintln!();
     eprintln!(
         "You may exit and update {} or enter a password. \
@@ -502,7 +1101,7 @@ pub struct InitializedValidators {
     /// The canonical set of validators.
     validators: HashMap<PublicKeyBytes, InitializedValidator>,
     /// The clients used for communications with a remote signer.
-    web3_signer_client_map: Option<HashMap<Web3SignerDefinition, Client>>,
+    web3_signer_client_map: Option<HashMap<Web3SignerDefinition, Web3SignerClient>>,
     /// For logging via `slog`.
     log: Logger,
     config: Config,
@@ -553,6 +1152,65 @@ impl InitializedValidators {
             .map(|v| v.signing_method.clone())
     }
 
+    /// Returns `true` if the TLS files of any Web3Signer instance or distributed validator have
+    /// been modified since its client was built.
+    pub fn web3_signer_tls_changed(&self) -> bool {
+        self.web3_signer_client_map
+            .iter()
+            .flat_map(|client_map| client_map.values())
+            .map(|client| &client.client)
+            .chain(
+                self.validators
+                    .values()
+                    .filter_map(|validator| validator.co_signer_client.as_ref()),
+            )
+            .any(ReloadableClient::tls_files_changed)
+    }
+
+    /// Rebuild the HTTP client of each Web3Signer instance and distributed validator whose root
+    /// certificate or client identity file has been modified since the client was built, so that
+    /// rotated certificates are used without restarting the validator client.
+    ///
+    /// If a client can't be rebuilt (e.g. the new files are incomplete) the previous client
+    /// remains in use and the rebuild is retried on the next call.
+    ///
+    /// Returns the clients which were rebuilt, whose previous clients should be drained.
+    pub fn reload_web3_signer_tls(&mut self) -> Vec<ReloadedWeb3SignerClient> {
+        let clients = self
+            .web3_signer_client_map
+            .iter_mut()
+            .flat_map(|client_map| client_map.values_mut())
+            .map(|client| &mut client.client)
+            .chain(
+                self.validators
+                    .values_mut()
+                    .filter_map(|validator| validator.co_signer_client.as_mut()),
+            );
+
+        let mut reloaded = vec![];
+        for client in clients {
+            if !client.tls_files_changed() {
+                continue;
+            }
+
+            match client.reload(&self.config) {
+                Ok(retired) => reloaded.push(ReloadedWeb3SignerClient {
+                    url: client.url.clone(),
+                    retired,
+                    request_timeout: client.request_timeout,
+                }),
+                Err(e) => warn!(
+                    self.log,
+                    "Unable to reload Web3Signer certificates";
+                    "url" => &client.url,
+                    "error" => ?e,
+                    "info" => "the previous certificates remain in use",
+                ),
+            }
+        }
+        reloaded
+    }
+
     /// Add a validator definition to `self`, replacing any disabled definition with the same
     /// voting public key.
     ///
@@ -620,17 +1278,24 @@ impl InitializedValidators {
                     voting_keystore_path,
                     voting_keystore_password,
                     voting_keystore_password_path,
-                    ..
+                    voting_keystore_password_source,
                 } if is_local_keystore => {
-                    let password = match (voting_keystore_password, voting_keystore_password_path) {
-                        (Some(password), _) => Some(password.clone()),
-                        (_, Some(path)) => {
+                    let password = match (
+                        voting_keystore_password,
+                        voting_keystore_password_source,
+                        voting_keystore_password_path,
+                    ) {
+                        (Some(password), _, _) => Some(password.clone()),
+                        (None, Some(source), _) => {
+                            Some(read_password_from_source(source, pubkey).await?)
+                        }
+                        (None, None, Some(path)) => {
                             password_path_opt = Some(path.clone());
                             read_password_string(path)
                                 .map(Option::Some)
                                 .map_err(Error::UnableToReadValidatorPassword)?
                         }
-                        (None, None) => None,
+                        (None, None, None) => None,
                     };
                     let keystore = Keystore::from_json_file(voting_keystore_path)
                         .map_err(Error::UnableToReadKeystoreFile)?;
@@ -761,41 +1426,77 @@ impl InitializedValidators {
         self.validators.get(public_key).and_then(|v| v.graffiti)
     }
 
-    /// Sets the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
-    ///
-    /// ## Notes
-    ///
-    /// Setting a validator `graffiti` will cause `self.definitions` to be updated and saved to
-    /// disk.
+    /// Returns the preferred beacon nodes for a given public key specified in the
+    /// `ValidatorDefinitions`.
+    pub fn beacon_nodes(&self, public_key: &PublicKeyBytes) -> Vec<SensitiveUrl> {
+        self.validators
+            .get(public_key)
+            .map(|v| v.beacon_nodes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Applies `update_definition` to the `ValidatorDefinition` and `update_validator` to the
+    /// `InitializedValidator` of `voting_public_key`.
     ///
-    /// Saves the `ValidatorDefinitions` to file, even if no definitions were changed.
-    pub fn set_graffiti(
+    /// The definitions are saved to disk before either is modified in memory, so that a failure to
+    /// save leaves the validator unchanged rather than diverging from the file.
+    fn update_definition(
         &mut self,
         voting_public_key: &PublicKey,
-        graffiti: GraffitiString,
+        update_definition: impl FnOnce(&mut ValidatorDefinition),
+        update_validator: impl FnOnce(&mut InitializedValidator),
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
+        let mut definitions = self.definitions.clone();
+        if let Some(def) = definitions
             .as_mut_slice()
             .iter_mut()
             .find(|def| def.voting_public_key == *voting_public_key)
         {
-            def.graffiti = Some(graffiti.clone());
+            update_definition(def);
         }
+        definitions
+            .save(&self.validators_dir)
+            .map_err(Error::UnableToSaveDefinitions)?;
+        self.definitions = definitions;
 
         if let Some(val) = self
             .validators
             .get_mut(&PublicKeyBytes::from(voting_public_key))
         {
-            val.graffiti = Some(graffiti.into());
+            update_validator(val);
         }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
         Ok(())
     }
 
+    /// Returns the `attestation_timing` for a given public key specified in the
+    /// `ValidatorDefinitions`.
+    pub fn attestation_timing(&self, public_key: &PublicKeyBytes) -> Option<AttestationTiming> {
+        self.validators
+            .get(public_key)
+            .and_then(|v| v.attestation_timing)
+    }
+
+    /// Sets the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
+    ///
+    /// ## Notes
+    ///
+    /// Setting a validator `graffiti` will cause `self.definitions` to be updated and saved to
+    /// disk.
+    ///
+    /// Saves the `ValidatorDefinitions` to file, even if no definitions were changed.
+    pub fn set_graffiti(
+        &mut self,
+        voting_public_key: &PublicKey,
+        graffiti: GraffitiString,
+    ) -> Result<(), Error> {
+        let validator_graffiti = graffiti.clone().into();
+        self.update_definition(
+            voting_public_key,
+            |def| def.graffiti = Some(graffiti),
+            |val| val.graffiti = Some(validator_graffiti),
+        )
+    }
+
     /// Removes the `InitializedValidator` and `ValidatorDefinition` `graffiti` values.
     ///
     /// ## Notes
@@ -806,27 +1507,11 @@ impl InitializedValidators {
     ///
     /// Saves the `ValidatorDefinitions` to file, even if no definitions were changed.
     pub fn delete_graffiti(&mut self, voting_public_key: &PublicKey) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            def.graffiti = None;
-        }
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            val.graffiti = None;
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
-
-        Ok(())
+        self.update_definition(
+            voting_public_key,
+            |def| def.graffiti = None,
+            |val| val.graffiti = None,
+        )
     }
 
     /// Returns a `HashMap` of `public_key` -> `graffiti` for all initialized validators.
@@ -907,62 +1592,53 @@ impl InitializedValidators {
         prefer_builder_proposals: Option<bool>,
         graffiti: Option<GraffitiString>,
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            // Don't overwrite fields if they are not set in this request.
-            if let Some(enabled) = enabled {
-                def.enabled = enabled;
-            }
-            if let Some(gas_limit) = gas_limit {
-                def.gas_limit = Some(gas_limit);
-            }
-            if let Some(builder_proposals) = builder_proposals {
-                def.builder_proposals = Some(builder_proposals);
-            }
-            if let Some(graffiti) = graffiti.clone() {
-                def.graffiti = Some(graffiti);
-            }
-            if let Some(builder_boost_factor) = builder_boost_factor {
-                def.builder_boost_factor = Some(builder_boost_factor);
-            }
-            if let Some(prefer_builder_proposals) = prefer_builder_proposals {
-                def.prefer_builder_proposals = Some(prefer_builder_proposals);
-            }
-        }
-
-        self.update_validators().await?;
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            // Don't overwrite fields if they are not set in this request.
-            if let Some(gas_limit) = gas_limit {
-                val.gas_limit = Some(gas_limit);
-            }
-            if let Some(builder_proposals) = builder_proposals {
-                val.builder_proposals = Some(builder_proposals);
-            }
-            if let Some(graffiti) = graffiti {
-                val.graffiti = Some(graffiti.into());
-            }
-            if let Some(builder_boost_factor) = builder_boost_factor {
-                val.builder_boost_factor = Some(builder_boost_factor);
-            }
-            if let Some(prefer_builder_proposals) = prefer_builder_proposals {
-                val.prefer_builder_proposals = Some(prefer_builder_proposals);
-            }
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
+        let validator_graffiti = graffiti.clone().map(Into::into);
+        self.update_definition(
+            voting_public_key,
+            |def| {
+                // Don't overwrite fields if they are not set in this request.
+                if let Some(enabled) = enabled {
+                    def.enabled = enabled;
+                }
+                if let Some(gas_limit) = gas_limit {
+                    def.gas_limit = Some(gas_limit);
+                }
+                if let Some(builder_proposals) = builder_proposals {
+                    def.builder_proposals = Some(builder_proposals);
+                }
+                if let Some(graffiti) = graffiti {
+                    def.graffiti = Some(graffiti);
+                }
+                if let Some(builder_boost_factor) = builder_boost_factor {
+                    def.builder_boost_factor = Some(builder_boost_factor);
+                }
+                if let Some(prefer_builder_proposals) = prefer_builder_proposals {
+                    def.prefer_builder_proposals = Some(prefer_builder_proposals);
+                }
+            },
+            |val| {
+                // Don't overwrite fields if they are not set in this request.
+                if let Some(gas_limit) = gas_limit {
+                    val.gas_limit = Some(gas_limit);
+                }
+                if let Some(builder_proposals) = builder_proposals {
+                    val.builder_proposals = Some(builder_proposals);
+                }
+                if let Some(graffiti) = validator_graffiti {
+                    val.graffiti = Some(graffiti);
+                }
+                if let Some(builder_boost_factor) = builder_boost_factor {
+                    val.builder_boost_factor = Some(builder_boost_factor);
+                }
+                if let Some(prefer_builder_proposals) = prefer_builder_proposals {
+                    val.prefer_builder_proposals = Some(prefer_builder_proposals);
+                }
+            },
+        )?;
 
-        Ok(())
+        // Initialize or remove the validator if it has been enabled or disabled. A newly enabled
+        // validator takes its fields from the updated definition.
+        self.update_validators().await
     }
 
     /// Sets the `InitializedValidator` and `ValidatorDefinition` `suggested_fee_recipient` values.
@@ -978,27 +1654,11 @@ impl InitializedValidators {
         voting_public_key: &PublicKey,
         fee_recipient: Address,
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            def.suggested_fee_recipient = Some(fee_recipient);
-        }
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            val.suggested_fee_recipient = Some(fee_recipient);
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
-
-        Ok(())
+        self.update_definition(
+            voting_public_key,
+            |def| def.suggested_fee_recipient = Some(fee_recipient),
+            |val| val.suggested_fee_recipient = Some(fee_recipient),
+        )
     }
 
     /// Removes the `InitializedValidator` and `ValidatorDefinition` `suggested_fee_recipient` values.
@@ -1014,27 +1674,11 @@ impl InitializedValidators {
         &mut self,
         voting_public_key: &PublicKey,
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            def.suggested_fee_recipient = None;
-        }
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            val.suggested_fee_recipient = None;
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
-
-        Ok(())
+        self.update_definition(
+            voting_public_key,
+            |def| def.suggested_fee_recipient = None,
+            |val| val.suggested_fee_recipient = None,
+        )
     }
 
     /// Sets the `InitializedValidator` and `ValidatorDefinition` `gas_limit` values.
@@ -1050,27 +1694,11 @@ impl InitializedValidators {
         voting_public_key: &PublicKey,
         gas_limit: u64,
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            def.gas_limit = Some(gas_limit);
-        }
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            val.gas_limit = Some(gas_limit);
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
-
-        Ok(())
+        self.update_definition(
+            voting_public_key,
+            |def| def.gas_limit = Some(gas_limit),
+            |val| val.gas_limit = Some(gas_limit),
+        )
     }
 
     /// Removes the `InitializedValidator` and `ValidatorDefinition` `gas_limit` values.
@@ -1086,120 +1714,72 @@ impl InitializedValidators {
         &mut self,
         voting_public_key: &PublicKey,
     ) -> Result<(), Error> {
-        if let Some(def) = self
-            .definitions
-            .as_mut_slice()
-            .iter_mut()
-            .find(|def| def.voting_public_key == *voting_public_key)
-        {
-            def.gas_limit = None;
-        }
-
-        if let Some(val) = self
-            .validators
-            .get_mut(&PublicKeyBytes::from(voting_public_key))
-        {
-            val.gas_limit = None;
-        }
-
-        self.definitions
-            .save(&self.validators_dir)
-            .map_err(Error::UnableToSaveDefinitions)?;
-
-        Ok(())
+        self.update_definition(
+            voting_public_key,
+            |def| def.gas_limit = None,
+            |val| val.gas_limit = None,
+        )
     }
 
-    /// Tries to decrypt the key cache.
-    ///
-    /// Returns the decrypted cache if decryption was successful, or an error if a required password
-    /// wasn't provided and couldn't be read interactively.
-    ///
-    /// In the case that the cache contains UUIDs for unknown validator definitions then it cannot
-    /// be decrypted and will be replaced by a new empty cache.
-    ///
-    /// The mutable `key_stores` argument will be used to accelerate decyption by bypassing
-    /// filesystem accesses for keystores that are already known. In the case that a keystore
-    /// from the validator definitions is not yet in this map, it will be loaded from disk and
-    /// inserted into the map.
+    /// Tries to decrypt the key cache, see `KeystoreLoader::decrypt_key_cache`.
     pub async fn decrypt_key_cache(
         &self,
-        mut cache: KeyCache,
+        cache: KeyCache,
         key_stores: &mut HashMap<PathBuf, Keystore>,
         on_failure: OnDecryptFailure,
     ) -> Result<KeyCache, Error> {
-        // Read relevant key stores from the filesystem.
-        let mut definitions_map = HashMap::new();
-        for def in self.definitions.as_slice().iter().filter(|def| def.enabled) {
-            match &def.signing_definition {
-                SigningDefinition::LocalKeystore {
-                    voting_keystore_path,
-                    ..
-                } => {
-                    use std::collections::hash_map::Entry::*;
-                    let key_store = match key_stores.entry(voting_keystore_path.clone()) {
-                        Vacant(entry) => entry.insert(open_keystore(voting_keystore_path)?),
-                        Occupied(entry) => entry.into_mut(),
-                    };
-                    definitions_map.insert(*key_store.uuid(), def);
-                }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
-            }
-        }
+        self.keystore_loader(self.definitions.clone(), HashSet::new())
+            .decrypt_key_cache(cache, key_stores, on_failure)
+            .await
+    }
 
-        //check if all paths are in the definitions_map
-        for uuid in cache.uuids() {
-            if !definitions_map.contains_key(uuid) {
-                debug!(
-                    self.log,
-                    "Resetting the key cache";
-                    "keystore_uuid" => %uuid,
-                    "reason" => "impossible to decrypt due to missing keystore",
-                );
-                return Ok(KeyCache::new());
-            }
+    /// Returns a `KeystoreLoader` for the keystores of `definitions`, skipping those of the
+    /// `initialized` validators.
+    fn keystore_loader(
+        &self,
+        definitions: ValidatorDefinitions,
+        initialized: HashSet<PublicKeyBytes>,
+    ) -> KeystoreLoader {
+        KeystoreLoader {
+            definitions,
+            initialized,
+            validators_dir: self.validators_dir.clone(),
+            log: self.log.clone(),
         }
+    }
 
-        //collect passwords
-        let mut passwords = Vec::new();
-        let mut public_keys = Vec::new();
-        for uuid in cache.uuids() {
-            let def = definitions_map.get(uuid).expect("Existence checked before");
-            match &def.signing_definition {
-                SigningDefinition::LocalKeystore {
-                    voting_keystore_password_path,
-                    voting_keystore_password,
-                    voting_keystore_path,
-                } => {
-                    let pw = if let Some(p) = voting_keystore_password {
-                        p.as_ref().to_vec().into()
-                    } else if let Some(path) = voting_keystore_password_path {
-                        read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
-                    } else {
-                        let keystore = open_keystore(voting_keystore_path)?;
-                        unlock_keystore_via_stdin_password(&keystore, voting_keystore_path)?
-                            .0
-                            .as_ref()
-                            .to_vec()
-                            .into()
-                    };
-                    passwords.push(pw);
-                    public_keys.push(def.voting_public_key.clone());
-                }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
-            };
-        }
+    /// Returns a `KeystoreLoader` which decrypts the keystores that `reload_definitions` would
+    /// need for `definitions`.
+    ///
+    /// The loader doesn't borrow `self`, so that keystores can be decrypted without holding a lock
+    /// on `self`, which would stall signing.
+    pub fn reload_keystore_loader(&self, definitions: &ValidatorDefinitions) -> KeystoreLoader {
+        let initialized = self
+            .unchanged_signers(definitions)
+            .map(|def| def.voting_public_key.compress())
+            .collect();
+        self.keystore_loader(definitions.clone(), initialized)
+    }
 
-        //decrypt
-        tokio::task::spawn_blocking(move || match cache.decrypt(passwords, public_keys) {
-            Ok(_) | Err(key_cache::Error::AlreadyDecrypted) => Ok(cache),
-            _ if matches!(on_failure, OnDecryptFailure::CreateNew) => Ok(KeyCache::new()),
-            Err(e) => Err(e),
+    /// Returns the enabled definitions in `definitions` whose validators are initialized with the
+    /// same signing definition, such that only their metadata (e.g. graffiti) may differ.
+    fn unchanged_signers<'a>(
+        &'a self,
+        definitions: &'a ValidatorDefinitions,
+    ) -> impl Iterator<Item = &'a ValidatorDefinition> {
+        let signers = self
+            .definitions
+            .as_slice()
+            .iter()
+            .filter(|def| def.enabled)
+            .map(|def| (def.voting_public_key.compress(), &def.signing_definition))
+            .collect::<HashMap<_, _>>();
+        definitions.as_slice().iter().filter(move |def| {
+            let pubkey = def.voting_public_key.compress();
+            def.enabled
+                && self.validators.contains_key(&pubkey)
+                && signers.get(&pubkey) == Some(&&def.signing_definition)
         })
-        .await
-        .map_err(Error::TokioJoin)?
-        .map_err(Error::UnableToDecryptKeyCache)
     }
 
     /// Scans `self.definitions` and attempts to initialize and validators which are not already
@@ -1214,37 +1794,112 @@ impl InitializedValidators {
     /// I.e., if there are two different definitions with the same public key then the second will
     /// be ignored.
     pub async fn update_validators(&mut self) -> Result<(), Error> {
-        //use key cache if available
-        let mut key_stores = HashMap::new();
+        self.update_validators_inner(OnInitFailure::Exit, None)
+            .await
+            .map(|_| ())
+    }
+
+    /// Replace the definitions of `self` with `definitions`, e.g. after the definitions file has
+    /// been modified by another process.
+    ///
+    /// Validators which are no longer enabled are removed, and validators whose signing
+    /// definitions have changed are re-initialized. Validators whose signing definitions are
+    /// unchanged only have their metadata updated, without decrypting their keystores again. Unlike
+    /// `update_validators`, a validator which fails to initialize doesn't prevent the others from
+    /// being updated. It is reported in the returned `DefinitionsReload` and will be retried by the
+    /// next update.
+    ///
+    /// The `keystores` loaded by `reload_keystore_loader` are used if the definitions of `self`
+    /// haven't been modified since, otherwise the keystores are loaded again.
+    ///
+    /// The on-disk representation of the definitions is not modified.
+    pub async fn reload_definitions(
+        &mut self,
+        definitions: ValidatorDefinitions,
+        keystores: Option<LoadedKeystores>,
+    ) -> Result<DefinitionsReload, Error> {
+        let previous = self
+            .validators
+            .iter()
+            .map(|(pubkey, validator)| (*pubkey, validator.get_index()))
+            .collect::<HashMap<_, _>>();
 
+        let unchanged = self
+            .unchanged_signers(&definitions)
+            .map(|def| (def.voting_public_key.compress(), def.clone()))
+            .collect::<HashMap<_, _>>();
+        self.validators
+            .retain(|pubkey, validator| match unchanged.get(pubkey) {
+                Some(def) => {
+                    validator.update_metadata(def);
+                    true
+                }
+                None => false,
+            });
+        self.definitions = definitions;
+
+        let failed = self
+            .update_validators_inner(OnInitFailure::Skip, keystores)
+            .await?;
+
+        // Re-initialized validators keep their index, rather than waiting for it to be resolved
+        // again.
+        for (pubkey, validator) in self.validators.iter_mut() {
+            if let Some(Some(index)) = previous.get(pubkey) {
+                validator.index.get_or_insert(*index);
+            }
+        }
+
+        Ok(DefinitionsReload {
+            added: self
+                .validators
+                .keys()
+                .filter(|pubkey| !previous.contains_key(pubkey))
+                .copied()
+                .collect(),
+            removed: previous
+                .keys()
+                .filter(|pubkey| !self.validators.contains_key(pubkey))
+                .copied()
+                .collect(),
+            failed,
+        })
+    }
+
+    async fn update_validators_inner(
+        &mut self,
+        on_failure: OnInitFailure,
+        keystores: Option<LoadedKeystores>,
+    ) -> Result<Vec<(PublicKey, Error)>, Error> {
         // Create a lock file for the cache
         let key_cache_path = KeyCache::cache_file_path(&self.validators_dir);
         let cache_lockfile_path =
             get_lockfile_path(&key_cache_path).ok_or(Error::BadKeyCachePath(key_cache_path))?;
         let _cache_lockfile = Lockfile::new(cache_lockfile_path)?;
 
-        let cache =
-            KeyCache::open_or_create(&self.validators_dir).map_err(Error::UnableToOpenKeyCache)?;
-
-        // Check if there is at least one local definition.
-        let has_local_definitions = self.definitions.as_slice().iter().any(|def| {
-            matches!(
-                def.signing_definition,
-                SigningDefinition::LocalKeystore { .. }
-            )
-        });
-
-        // Only decrypt cache when there is at least one local definition.
-        // Decrypting cache is a very expensive operation which is never used for web3signer.
-        let mut key_cache = if has_local_definitions {
-            self.decrypt_key_cache(cache, &mut key_stores, OnDecryptFailure::CreateNew)
+        // Keystores which were loaded for other definitions, e.g. because the definitions were
+        // modified concurrently, are discarded.
+        let LoadedKeystores {
+            mut key_cache,
+            mut key_stores,
+            has_local_definitions,
+            ..
+        } = match keystores {
+            Some(keystores) if keystores.definitions.as_slice() == self.definitions.as_slice() => {
+                keystores
+            }
+            _ => {
+                self.keystore_loader(
+                    self.definitions.clone(),
+                    self.validators.keys().copied().collect(),
+                )
+                .load_unlocked()
                 .await?
-        } else {
-            // Assign an empty KeyCache if all definitions are of the Web3Signer type.
-            KeyCache::new()
+            }
         };
 
         let mut disabled_uuids = HashSet::new();
+        let mut failed = vec![];
         for def in self.definitions.as_slice() {
             if def.enabled {
                 let pubkey_bytes = def.voting_public_key.compress();
@@ -1268,6 +1923,7 @@ impl InitializedValidators {
                             &mut key_stores,
                             &mut None,
                             &self.config,
+                            on_failure == OnInitFailure::Exit,
                         )
                         .await
                         {
@@ -1307,18 +1963,23 @@ impl InitializedValidators {
                                     "validator" => format!("{:?}", def.voting_public_key)
                                 );
 
-                                // Exit on an invalid validator.
-                                return Err(e);
+                                if on_failure == OnInitFailure::Exit {
+                                    // Exit on an invalid validator.
+                                    return Err(e);
+                                }
+                                failed.push((def.voting_public_key.clone(), e));
                             }
                         }
                     }
-                    SigningDefinition::Web3Signer(Web3SignerDefinition { .. }) => {
+                    SigningDefinition::Web3Signer(Web3SignerDefinition { .. })
+                    | SigningDefinition::DistributedSigner(_) => {
                         match InitializedValidator::from_definition(
                             def.clone(),
                             &mut key_cache,
                             &mut key_stores,
                             &mut self.web3_signer_client_map,
                             &self.config,
+                            on_failure == OnInitFailure::Exit,
                         )
                         .await
                         {
@@ -1342,8 +2003,11 @@ impl InitializedValidators {
                                     "validator" => format!("{:?}", def.voting_public_key)
                                 );
 
-                                // Exit on an invalid validator.
-                                return Err(e);
+                                if on_failure == OnInitFailure::Exit {
+                                    // Exit on an invalid validator.
+                                    return Err(e);
+                                }
+                                failed.push((def.voting_public_key.clone(), e));
                             }
                         }
                     }
@@ -1360,7 +2024,8 @@ impl InitializedValidators {
                         }
                     }
                     // Remote signers do not interact with the key cache.
-                    SigningDefinition::Web3Signer { .. } => (),
+                    SigningDefinition::Web3Signer { .. }
+                    | SigningDefinition::DistributedSigner { .. } => (),
                 }
 
                 info!(
@@ -1406,7 +2071,7 @@ impl InitializedValidators {
             &validator_metrics::TOTAL_VALIDATORS_COUNT,
             self.num_total() as i64,
         );
-        Ok(())
+        Ok(failed)
     }
 
     pub fn get_index(&self, pubkey: &PublicKeyBytes) -> Option<u64> {
@@ -1439,7 +2104,8 @@ impl InitializedValidators {
                     }
                 }
                 // Remote signers don't have passwords.
-                SigningDefinition::Web3Signer { .. } => (),
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::DistributedSigner { .. } => (),
             };
         }
 
@@ -1456,3 +2122,269 @@ impl InitializedValidators {
         self.definitions.as_mut_slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2_keystore::json_keystore::{Pbkdf2, Prf, Scrypt};
+    use eth2_keystore::{KeystoreBuilder, DKLEN};
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    const WEB3SIGNER_URL: &str = "https://localhost:9000";
+
+    fn set_modified(path: &Path, seconds: u64) {
+        File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(seconds))
+            .unwrap();
+    }
+
+    fn web3_signer_definition(
+        voting_public_key: PublicKey,
+        root_certificate_path: PathBuf,
+    ) -> ValidatorDefinition {
+        ValidatorDefinition {
+            enabled: true,
+            voting_public_key,
+            graffiti: None,
+            suggested_fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            builder_boost_factor: None,
+            prefer_builder_proposals: None,
+            beacon_nodes: None,
+            attestation_timing: None,
+            description: String::new(),
+            signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
+                url: WEB3SIGNER_URL.to_string(),
+                root_certificate_path: Some(root_certificate_path),
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            }),
+        }
+    }
+
+    /// A cheap key derivation function, so that tests don't spend seconds in scrypt.
+    fn test_kdf() -> Kdf {
+        Kdf::Pbkdf2(Pbkdf2 {
+            dklen: DKLEN,
+            c: 2,
+            prf: Prf::HmacSha256,
+            salt: vec![42; 32].into(),
+        })
+    }
+
+    fn local_keystore_definition(
+        dir: &Path,
+        keypair: &Keypair,
+        password: Option<&str>,
+    ) -> (ValidatorDefinition, Keystore) {
+        let keystore = KeystoreBuilder::new(keypair, b"password", String::new())
+            .unwrap()
+            .kdf(test_kdf())
+            .build()
+            .unwrap();
+        let voting_keystore_path = dir.join(format!("{}.json", keystore.uuid()));
+        keystore
+            .to_json_writer(File::create(&voting_keystore_path).unwrap())
+            .unwrap();
+
+        let definition = ValidatorDefinition {
+            enabled: true,
+            voting_public_key: keypair.pk.clone(),
+            graffiti: None,
+            suggested_fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            builder_boost_factor: None,
+            prefer_builder_proposals: None,
+            beacon_nodes: None,
+            attestation_timing: None,
+            description: String::new(),
+            signing_definition: SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path: None,
+                voting_keystore_password: password.map(|p| ZeroizeString::from(p.to_string())),
+                voting_keystore_password_source: None,
+            },
+        };
+        (definition, keystore)
+    }
+
+    #[test]
+    fn decryption_memory_depends_on_kdf() {
+        let keypair = Keypair::random();
+        let keystore = |kdf| {
+            KeystoreBuilder::new(&keypair, b"password", String::new())
+                .unwrap()
+                .kdf(kdf)
+                .build()
+                .unwrap()
+        };
+
+        let default_scrypt = keystore(Kdf::Scrypt(Scrypt::default_scrypt(vec![42; 32])));
+        assert_eq!(decryption_memory_mib(&default_scrypt), 256);
+        assert_eq!(decryption_memory_mib(&keystore(test_kdf())), 0);
+    }
+
+    #[tokio::test]
+    async fn keystores_are_decrypted_in_parallel() {
+        let dir = tempdir().unwrap();
+        let mut validators = InitializedValidators::from_definitions(
+            ValidatorDefinitions::default(),
+            dir.path().to_path_buf(),
+            Config::default(),
+            Logger::root(slog::Discard, slog::o!()),
+        )
+        .await
+        .unwrap();
+
+        let keypairs = (0..4).map(|_| Keypair::random()).collect::<Vec<_>>();
+        let (decryptable, mut keystores): (Vec<_>, Vec<_>) = keypairs[..2]
+            .iter()
+            .map(|keypair| local_keystore_definition(dir.path(), keypair, Some("password")))
+            .unzip();
+        // Keystores with a wrong or missing password are left for `from_definition` to report.
+        let (wrong_password, wrong_password_keystore) =
+            local_keystore_definition(dir.path(), &keypairs[2], Some("wrong"));
+        let (no_password, no_password_keystore) =
+            local_keystore_definition(dir.path(), &keypairs[3], None);
+        keystores.extend([wrong_password_keystore, no_password_keystore]);
+        validators.definitions = decryptable
+            .into_iter()
+            .chain([wrong_password, no_password])
+            .collect::<Vec<_>>()
+            .into();
+
+        let mut key_cache = KeyCache::new();
+        let mut key_stores = HashMap::new();
+        validators
+            .decrypt_keystores_in_parallel(&mut key_cache, &mut key_stores)
+            .await;
+
+        assert_eq!(key_stores.len(), 4);
+        let decrypted = keystores
+            .iter()
+            .map(|keystore| key_cache.get(keystore.uuid()).map(|keypair| keypair.pk))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            decrypted,
+            vec![
+                Some(keypairs[0].pk.clone()),
+                Some(keypairs[1].pk.clone()),
+                None,
+                None
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_save_leaves_validator_unchanged() {
+        let dir = tempdir().unwrap();
+        let keypair = Keypair::random();
+        let (definition, _) = local_keystore_definition(dir.path(), &keypair, Some("password"));
+        let mut validators = InitializedValidators::from_definitions(
+            vec![definition].into(),
+            dir.path().to_path_buf(),
+            Config::default(),
+            Logger::root(slog::Discard, slog::o!()),
+        )
+        .await
+        .unwrap();
+        let pubkey = keypair.pk.compress();
+        let graffiti = |validators: &InitializedValidators| {
+            (
+                validators.graffiti(&pubkey),
+                validators.validator_definitions()[0].graffiti.clone(),
+            )
+        };
+
+        let first = GraffitiString::from_str("first").unwrap();
+        validators.set_graffiti(&keypair.pk, first.clone()).unwrap();
+        assert_eq!(
+            graffiti(&validators),
+            (Some(first.clone().into()), Some(first.clone()))
+        );
+        let saved = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert_eq!(saved.as_slice()[0].graffiti, Some(first.clone()));
+
+        // Neither the definition nor the validator is changed if the definitions can't be saved.
+        validators.validators_dir = dir.path().join("missing");
+        let second = GraffitiString::from_str("second").unwrap();
+        assert!(matches!(
+            validators.set_graffiti(&keypair.pk, second),
+            Err(Error::UnableToSaveDefinitions(_))
+        ));
+        assert!(matches!(
+            validators.delete_graffiti(&keypair.pk),
+            Err(Error::UnableToSaveDefinitions(_))
+        ));
+        assert_eq!(
+            graffiti(&validators),
+            (Some(first.clone().into()), Some(first))
+        );
+    }
+
+    #[tokio::test]
+    async fn web3_signer_client_is_rebuilt_when_certificate_changes() {
+        let dir = tempdir().unwrap();
+        let certificate_path = dir.path().join("web3signer.pem");
+        fs::copy(
+            Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("../../testing/web3signer_tests/tls/web3signer/cert.pem"),
+            &certificate_path,
+        )
+        .unwrap();
+        set_modified(&certificate_path, 1);
+
+        let voting_public_key = Keypair::random().pk;
+        let mut validators = InitializedValidators::from_definitions(
+            vec![web3_signer_definition(
+                voting_public_key.clone(),
+                certificate_path.clone(),
+            )]
+            .into(),
+            dir.path().to_path_buf(),
+            Config::default(),
+            Logger::root(slog::Discard, slog::o!()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(validators.num_enabled(), 1);
+
+        // Nothing is rebuilt while the files are unchanged.
+        assert!(!validators.web3_signer_tls_changed());
+        assert!(validators.reload_web3_signer_tls().is_empty());
+
+        let signing_method = validators
+            .signing_method(&voting_public_key.compress())
+            .unwrap();
+        let SigningMethod::Web3Signer { http_client, .. } = signing_method.as_ref() else {
+            panic!("should be a Web3Signer validator");
+        };
+        let in_flight = http_client.current();
+
+        set_modified(&certificate_path, 2);
+        assert!(validators.web3_signer_tls_changed());
+        let reloaded = validators.reload_web3_signer_tls();
+        assert_eq!(reloaded.len(), 1);
+        assert_eq!(reloaded[0].url, WEB3SIGNER_URL);
+        assert!(!validators.web3_signer_tls_changed());
+
+        // The previous client is drained once the request in flight on it finishes.
+        drop(in_flight);
+        for reloaded_client in reloaded {
+            reloaded_client.retired.drain().await;
+        }
+
+        // A client which can't be rebuilt stays in use, and the rebuild is retried.
+        fs::remove_file(&certificate_path).unwrap();
+        assert!(validators.web3_signer_tls_changed());
+        assert!(validators.reload_web3_signer_tls().is_empty());
+        assert!(validators.web3_signer_tls_changed());
+    }
+}
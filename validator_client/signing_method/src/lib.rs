@@ -2,14 +2,18 @@
 //!
 //! - Via a local `Keypair`.
 //! - Via a remote signer (Web3Signer)
+//! - Via a threshold of co-signers holding shares of a distributed validator's key.
 
 use eth2_keystore::Keystore;
+use futures::stream::{FuturesUnordered, StreamExt};
 use lockfile::Lockfile;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use reqwest::{header::ACCEPT, Client};
+use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::Arc;
 use task_executor::TaskExecutor;
+use tokio::sync::{mpsc, Semaphore};
 use types::*;
 use url::Url;
 use web3signer::{ForkInfo, SigningRequest, SigningResponse};
@@ -26,6 +30,13 @@ pub enum Error {
     },
     Web3SignerRequestFailed(String),
     Web3SignerJsonParsingFailed(String),
+    /// Fewer than `threshold` co-signers returned a valid partial signature.
+    CoSignerQuorumNotReached {
+        threshold: usize,
+        errors: Vec<String>,
+    },
+    /// The partial signatures could not be combined into a valid signature.
+    PartialSignatureAggregationFailed(String),
     ShuttingDown,
     TokioJoin(String),
     MergeForkNotSupported,
@@ -89,11 +100,114 @@ pub enum SigningMethod {
     /// See: https://docs.web3signer.consensys.net/en/latest/
     Web3Signer {
         signing_url: Url,
-        http_client: Client,
+        http_client: Web3SignerHttpClient,
+        /// Limits the number of requests in flight to the Web3Signer instance, which is shared by
+        /// every validator using the same instance.
+        request_permits: Option<Arc<Semaphore>>,
+        voting_public_key: PublicKey,
+    },
+    /// A distributed validator whose secret key is split between `co_signers`, each of which
+    /// runs a Web3Signer-compatible server holding one key share.
+    ///
+    /// Partial signatures are requested from all co-signers concurrently and combined as soon as
+    /// `threshold` valid partial signatures have been received, so slow or unavailable co-signers
+    /// are tolerated as long as a quorum responds within the request timeout.
+    DistributedSigner {
+        co_signers: Vec<CoSigner>,
+        threshold: usize,
+        http_client: Web3SignerHttpClient,
         voting_public_key: PublicKey,
     },
 }
 
+/// The HTTP client for a Web3Signer instance, which may be replaced while the validator client is
+/// running when its TLS certificates are rotated.
+#[derive(Clone)]
+pub struct Web3SignerHttpClient(Arc<RwLock<TrackedClient>>);
+
+/// A client along with the means to tell when no requests are in flight on it.
+///
+/// Each request holds a clone of `in_flight`, so `drained` completes once the client has been
+/// replaced and every request using it has finished.
+struct TrackedClient {
+    client: Client,
+    in_flight: mpsc::Sender<()>,
+    drained: mpsc::Receiver<()>,
+}
+
+impl TrackedClient {
+    fn new(client: Client) -> Self {
+        let (in_flight, drained) = mpsc::channel(1);
+        Self {
+            client,
+            in_flight,
+            drained,
+        }
+    }
+}
+
+/// A handle to the client used by one request.
+pub struct InFlightClient {
+    client: Client,
+    _in_flight: mpsc::Sender<()>,
+}
+
+impl Deref for InFlightClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        &self.client
+    }
+}
+
+/// A client which has been replaced, but may still have requests in flight.
+pub struct RetiredClient {
+    drained: mpsc::Receiver<()>,
+}
+
+impl RetiredClient {
+    /// Wait for the requests in flight on the client to finish. The client, and with it its
+    /// connections, is dropped once they have.
+    pub async fn drain(mut self) {
+        // Nothing is ever sent, so this only returns once every request has dropped its sender.
+        while self.drained.recv().await.is_some() {}
+    }
+}
+
+impl Web3SignerHttpClient {
+    pub fn new(client: Client) -> Self {
+        Self(Arc::new(RwLock::new(TrackedClient::new(client))))
+    }
+
+    /// Returns the client to use for a new request. The client is considered in flight until the
+    /// returned handle is dropped.
+    pub fn current(&self) -> InFlightClient {
+        let tracked = self.0.read();
+        InFlightClient {
+            client: tracked.client.clone(),
+            _in_flight: tracked.in_flight.clone(),
+        }
+    }
+
+    /// Use `client` for all subsequent requests, returning the previous client so that its
+    /// connections can be drained.
+    pub fn replace(&self, client: Client) -> RetiredClient {
+        let previous = std::mem::replace(&mut *self.0.write(), TrackedClient::new(client));
+        RetiredClient {
+            drained: previous.drained,
+        }
+    }
+}
+
+/// A co-signer of a distributed validator.
+pub struct CoSigner {
+    pub signing_url: Url,
+    /// The index of the key share, used to combine partial signatures.
+    pub share_index: u64,
+    /// The public key of the key share, used to verify partial signatures.
+    pub share_public_key: PublicKey,
+}
+
 /// The additional information used to construct a signature. Mostly used for protection from replay
 /// attacks.
 pub struct SigningContext {
@@ -127,6 +241,9 @@ impl SigningMethod {
             // Slashing protection is only required for remote signer keys when the configuration
             // dictates that it is desired.
             SigningMethod::Web3Signer { .. } => enable_web3signer_slashing_protection,
+            // Co-signers may not apply slashing protection, so it is always required for
+            // distributed validators.
+            SigningMethod::DistributedSigner { .. } => true,
         }
     }
 
@@ -138,15 +255,35 @@ impl SigningMethod {
         spec: &ChainSpec,
         executor: &TaskExecutor,
     ) -> Result<Signature, Error> {
-        let domain_hash = signing_context.domain_hash(spec);
+        let signing_root = signable_message.signing_root(signing_context.domain_hash(spec));
+
+        self.get_signature_with_signing_root(
+            signable_message,
+            signing_context,
+            signing_root,
+            executor,
+        )
+        .await
+    }
+
+    /// As for `get_signature`, but with a `signing_root` which has already been computed.
+    ///
+    /// Many validators often sign the same message (e.g. the members of a committee), in which
+    /// case the signing root only needs to be computed once. The `signing_root` must be the
+    /// signing root of `signable_message` with respect to `signing_context`.
+    pub async fn get_signature_with_signing_root<E: EthSpec, Payload: AbstractExecPayload<E>>(
+        &self,
+        signable_message: SignableMessage<'_, E, Payload>,
+        signing_context: SigningContext,
+        signing_root: Hash256,
+        executor: &TaskExecutor,
+    ) -> Result<Signature, Error> {
         let SigningContext {
             fork,
             genesis_validators_root,
             ..
         } = signing_context;
 
-        let signing_root = signable_message.signing_root(domain_hash);
-
         let fork_info = Some(ForkInfo {
             fork,
             genesis_validators_root,
@@ -186,79 +323,209 @@ impl SigningMethod {
             SigningMethod::Web3Signer {
                 signing_url,
                 http_client,
+                request_permits,
                 ..
             } => {
+                let request = web3signer_request(signable_message, signing_root, fork_info)?;
+
+                // Wait for a permit before starting the timer, so that the signing times reflect
+                // the latency of the Web3Signer rather than the length of the queue.
+                let _permit = match request_permits {
+                    Some(request_permits) => {
+                        let _timer = validator_metrics::start_timer(
+                            &validator_metrics::WEB3SIGNER_REQUEST_QUEUE_TIMES,
+                        );
+                        Some(
+                            request_permits
+                                .acquire()
+                                .await
+                                .map_err(|_| Error::ShuttingDown)?,
+                        )
+                    }
+                    None => None,
+                };
+
                 let _timer = validator_metrics::start_timer_vec(
                     &validator_metrics::SIGNING_TIMES,
                     &[validator_metrics::WEB3SIGNER],
                 );
+                request_signature(&http_client.current(), signing_url, &request).await
+            }
+            SigningMethod::DistributedSigner {
+                co_signers,
+                threshold,
+                http_client,
+                voting_public_key,
+            } => {
+                let _timer = validator_metrics::start_timer_vec(
+                    &validator_metrics::SIGNING_TIMES,
+                    &[validator_metrics::DISTRIBUTED_SIGNER],
+                );
 
-                // Map the message into a Web3Signer type.
-                let object = match signable_message {
-                    SignableMessage::RandaoReveal(epoch) => {
-                        Web3SignerObject::RandaoReveal { epoch }
-                    }
-                    SignableMessage::BeaconBlock(block) => Web3SignerObject::beacon_block(block)?,
-                    SignableMessage::AttestationData(a) => Web3SignerObject::Attestation(a),
-                    SignableMessage::SignedAggregateAndProof(a) => {
-                        Web3SignerObject::AggregateAndProof(a)
-                    }
-                    SignableMessage::SelectionProof(slot) => {
-                        Web3SignerObject::AggregationSlot { slot }
-                    }
-                    SignableMessage::SyncSelectionProof(s) => {
-                        Web3SignerObject::SyncAggregatorSelectionData(s)
-                    }
-                    SignableMessage::SyncCommitteeSignature {
-                        beacon_block_root,
-                        slot,
-                    } => Web3SignerObject::SyncCommitteeMessage {
-                        beacon_block_root,
-                        slot,
-                    },
-                    SignableMessage::SignedContributionAndProof(c) => {
-                        Web3SignerObject::ContributionAndProof(c)
-                    }
-                    SignableMessage::ValidatorRegistration(v) => {
-                        Web3SignerObject::ValidatorRegistration(v)
-                    }
-                    SignableMessage::VoluntaryExit(e) => Web3SignerObject::VoluntaryExit(e),
-                };
+                let request = web3signer_request(signable_message, signing_root, fork_info)?;
+                let http_client = http_client.current();
 
-                // Determine the Web3Signer message type.
-                let message_type = object.message_type();
+                let mut pending = co_signers
+                    .iter()
+                    .map(|co_signer| {
+                        let request = &request;
+                        let http_client = &http_client;
+                        async move {
+                            let result =
+                                request_signature(http_client, &co_signer.signing_url, request)
+                                    .await
+                                    .and_then(|signature| {
+                                        if signature
+                                            .verify(&co_signer.share_public_key, signing_root)
+                                        {
+                                            Ok(signature)
+                                        } else {
+                                            Err(Error::Web3SignerRequestFailed(
+                                                "invalid partial signature".to_string(),
+                                            ))
+                                        }
+                                    });
+                            (co_signer, result)
+                        }
+                    })
+                    .collect::<FuturesUnordered<_>>();
 
-                if matches!(
-                    object,
-                    Web3SignerObject::Deposit { .. } | Web3SignerObject::ValidatorRegistration(_)
-                ) && fork_info.is_some()
-                {
-                    return Err(Error::GenesisForkVersionRequired);
-                }
+                let mut partial_signatures = Vec::with_capacity(*threshold);
+                let mut errors = vec![];
 
-                let request = SigningRequest {
-                    message_type,
-                    fork_info,
-                    signing_root,
-                    object,
-                };
+                while let Some((co_signer, result)) = pending.next().await {
+                    match result {
+                        Ok(signature) => {
+                            partial_signatures.push((co_signer.share_index, signature));
+                            if partial_signatures.len() >= *threshold {
+                                break;
+                            }
+                        }
+                        Err(e) => {
+                            validator_metrics::inc_counter_vec(
+                                &validator_metrics::CO_SIGNER_FAILURES_TOTAL,
+                                &[&co_signer.share_index.to_string()],
+                            );
+                            errors.push(format!("{}: {:?}", co_signer.signing_url, e));
+                        }
+                    }
+                }
 
-                // Request a signature from the Web3Signer instance via HTTP(S).
-                let response: SigningResponse = http_client
-                    .post(signing_url.clone())
-                    .header(ACCEPT, "application/json")
-                    .json(&request)
-                    .send()
-                    .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .error_for_status()
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
-                    .json()
-                    .await
-                    .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+                if partial_signatures.len() < *threshold {
+                    return Err(Error::CoSignerQuorumNotReached {
+                        threshold: *threshold,
+                        errors,
+                    });
+                }
 
-                Ok(response.signature)
+                let signature = bls::combine_signature_shares(&partial_signatures)
+                    .map_err(|e| Error::PartialSignatureAggregationFailed(format!("{e:?}")))?;
+                if !signature.verify(voting_public_key, signing_root) {
+                    return Err(Error::PartialSignatureAggregationFailed(
+                        "combined signature is invalid for the voting public key".to_string(),
+                    ));
+                }
+                Ok(signature)
             }
         }
     }
 }
+
+/// Build a Web3Signer request for `signable_message`.
+fn web3signer_request<'a, E: EthSpec, Payload: AbstractExecPayload<E>>(
+    signable_message: SignableMessage<'a, E, Payload>,
+    signing_root: Hash256,
+    fork_info: Option<ForkInfo>,
+) -> Result<SigningRequest<'a, E, Payload>, Error> {
+    // Map the message into a Web3Signer type.
+    let object = match signable_message {
+        SignableMessage::RandaoReveal(epoch) => Web3SignerObject::RandaoReveal { epoch },
+        SignableMessage::BeaconBlock(block) => Web3SignerObject::beacon_block(block)?,
+        SignableMessage::AttestationData(a) => Web3SignerObject::Attestation(a),
+        SignableMessage::SignedAggregateAndProof(a) => Web3SignerObject::AggregateAndProof(a),
+        SignableMessage::SelectionProof(slot) => Web3SignerObject::AggregationSlot { slot },
+        SignableMessage::SyncSelectionProof(s) => Web3SignerObject::SyncAggregatorSelectionData(s),
+        SignableMessage::SyncCommitteeSignature {
+            beacon_block_root,
+            slot,
+        } => Web3SignerObject::SyncCommitteeMessage {
+            beacon_block_root,
+            slot,
+        },
+        SignableMessage::SignedContributionAndProof(c) => Web3SignerObject::ContributionAndProof(c),
+        SignableMessage::ValidatorRegistration(v) => Web3SignerObject::ValidatorRegistration(v),
+        SignableMessage::VoluntaryExit(e) => Web3SignerObject::VoluntaryExit(e),
+    };
+
+    // Determine the Web3Signer message type.
+    let message_type = object.message_type();
+
+    if matches!(
+        object,
+        Web3SignerObject::Deposit { .. } | Web3SignerObject::ValidatorRegistration(_)
+    ) && fork_info.is_some()
+    {
+        return Err(Error::GenesisForkVersionRequired);
+    }
+
+    Ok(SigningRequest {
+        message_type,
+        fork_info,
+        signing_root,
+        object,
+    })
+}
+
+/// Request a signature from the Web3Signer instance at `signing_url` via HTTP(S).
+async fn request_signature<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    http_client: &Client,
+    signing_url: &Url,
+    request: &SigningRequest<'_, E, Payload>,
+) -> Result<Signature, Error> {
+    let response: SigningResponse = http_client
+        .post(signing_url.clone())
+        .header(ACCEPT, "application/json")
+        .json(request)
+        .send()
+        .await
+        .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+        .error_for_status()
+        .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
+
+    Ok(response.signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::FutureExt;
+
+    #[tokio::test]
+    async fn replaced_client_without_requests_is_drained() {
+        let http_client = Web3SignerHttpClient::new(Client::new());
+        let retired = http_client.replace(Client::new());
+        assert!(retired.drain().now_or_never().is_some());
+    }
+
+    #[tokio::test]
+    async fn replaced_client_is_drained_once_requests_finish() {
+        let http_client = Web3SignerHttpClient::new(Client::new());
+        let first_request = http_client.current();
+        let second_request = http_client.current();
+
+        let retired = http_client.replace(Client::new());
+        let mut drain = Box::pin(retired.drain());
+        assert!((&mut drain).now_or_never().is_none());
+
+        // Requests using the new client don't hold up draining the previous one.
+        let new_request = http_client.current();
+        drop(first_request);
+        assert!((&mut drain).now_or_never().is_none());
+        drop(second_request);
+        assert!(drain.now_or_never().is_some());
+        drop(new_request);
+    }
+}
@@ -1,9 +1,10 @@
 #![cfg(test)]
 
+use crate::interchange::{Interchange, InterchangeData, InterchangeMetadata};
 use crate::test_utils::pubkey;
 use crate::*;
 use tempfile::tempdir;
-use types::FixedBytesExtended;
+use types::{Epoch, FixedBytesExtended, Slot};
 
 #[test]
 fn export_non_existent_key() {
@@ -74,3 +75,268 @@ fn export_same_key_twice() {
         export_double.minify().unwrap()
     );
 }
+
+#[test]
+fn prune_all_validators_keeps_latest() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+    let slots_per_epoch = 32;
+
+    let active = pubkey(1);
+    let exited = pubkey(2);
+    slashing_db
+        .register_validators([&active, &exited].into_iter())
+        .unwrap();
+
+    for epoch in 1..=10 {
+        let root = SigningRoot::from(Hash256::from_low_u64_be(epoch));
+        slashing_db
+            .check_and_insert_attestation_signing_root(
+                &active,
+                Epoch::new(epoch - 1),
+                Epoch::new(epoch),
+                root,
+            )
+            .unwrap();
+        slashing_db
+            .check_and_insert_block_signing_root(&active, Slot::new(epoch * slots_per_epoch), root)
+            .unwrap();
+    }
+    // A validator which stopped signing long ago keeps its latest messages.
+    let root = SigningRoot::from(Hash256::from_low_u64_be(1));
+    slashing_db
+        .check_and_insert_attestation_signing_root(&exited, Epoch::new(0), Epoch::new(1), root)
+        .unwrap();
+    slashing_db
+        .check_and_insert_block_signing_root(&exited, Slot::new(1), root)
+        .unwrap();
+
+    assert_eq!(
+        slashing_db.max_attestation_target().unwrap(),
+        Some(Epoch::new(10))
+    );
+
+    let summary = slashing_db
+        .prune_all_validators(Epoch::new(8), slots_per_epoch)
+        .unwrap();
+    assert_eq!(
+        summary,
+        PruneSummary {
+            attestations_removed: 7,
+            blocks_removed: 7,
+        }
+    );
+
+    let interchange = slashing_db
+        .export_interchange_info(Hash256::zero(), Some(&[active, exited]))
+        .unwrap();
+    let active_data = &interchange.data[0];
+    assert_eq!(active_data.pubkey, active);
+    assert_eq!(active_data.signed_attestations.len(), 3);
+    assert_eq!(active_data.signed_blocks.len(), 3);
+    let exited_data = &interchange.data[1];
+    assert_eq!(exited_data.pubkey, exited);
+    assert_eq!(exited_data.signed_attestations.len(), 1);
+    assert_eq!(exited_data.signed_blocks.len(), 1);
+
+    // Slashable messages are still rejected after pruning and compaction.
+    slashing_db.compact().unwrap();
+    assert_eq!(slashing_db.free_page_fraction().unwrap(), 0.0);
+    slashing_db.compact_incremental(100).unwrap();
+    assert!(slashing_db
+        .check_and_insert_attestation_signing_root(
+            &active,
+            Epoch::new(9),
+            Epoch::new(10),
+            SigningRoot::from(Hash256::repeat_byte(0xff)),
+        )
+        .is_err());
+    assert!(slashing_db
+        .check_and_insert_block_signing_root(
+            &exited,
+            Slot::new(1),
+            SigningRoot::from(Hash256::repeat_byte(0xff))
+        )
+        .is_err());
+}
+
+fn interchange_json(interchange: &Interchange) -> Vec<u8> {
+    let mut json = vec![];
+    interchange.stream_to(&mut json).unwrap();
+    json
+}
+
+#[test]
+fn streaming_export_matches_export() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let keys = (0..3).map(pubkey).collect::<Vec<_>>();
+    slashing_db.register_validators(keys.iter()).unwrap();
+    for (i, key) in keys.iter().enumerate() {
+        let root = SigningRoot::from(Hash256::from_low_u64_be(i as u64 + 1));
+        slashing_db
+            .check_and_insert_attestation_signing_root(key, Epoch::new(1), Epoch::new(2), root)
+            .unwrap();
+        slashing_db
+            .check_and_insert_block_signing_root(key, Slot::new(i as u64), root)
+            .unwrap();
+    }
+
+    let interchange = slashing_db
+        .export_all_interchange_info(Hash256::zero())
+        .unwrap();
+    let mut streamed = vec![];
+    let num_records = slashing_db
+        .export_interchange_info_to_writer(Hash256::zero(), None, &mut streamed)
+        .unwrap();
+    assert_eq!(num_records, 3);
+    assert_eq!(streamed, serde_json::to_vec(&interchange).unwrap());
+    assert_eq!(
+        Interchange::from_json_reader(streamed.as_slice()).unwrap(),
+        interchange
+    );
+}
+
+#[test]
+fn streaming_import_gzip() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    let interchange = Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root: Hash256::zero(),
+        },
+        data: (0..3)
+            .map(|i| InterchangeData {
+                pubkey: pubkey(i),
+                signed_blocks: vec![interchange::SignedBlock {
+                    slot: Slot::new(i as u64),
+                    signing_root: None,
+                }],
+                signed_attestations: vec![],
+            })
+            .collect(),
+    };
+    let mut encoder = GzEncoder::new(vec![], Compression::default());
+    encoder.write_all(&interchange_json(&interchange)).unwrap();
+    let compressed = encoder.finish().unwrap();
+
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+    let outcomes = slashing_db
+        .import_interchange_from_reader(compressed.as_slice(), Hash256::zero())
+        .unwrap();
+    assert_eq!(outcomes.len(), 3);
+    assert!(outcomes.iter().all(|outcome| !outcome.failed()));
+    assert_eq!(
+        slashing_db
+            .export_all_interchange_info(Hash256::zero())
+            .unwrap(),
+        interchange
+    );
+}
+
+#[test]
+fn streaming_import_metadata_last() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let json = |genesis_validators_root: Hash256| {
+        format!(
+            r#"{{"data":[{{"pubkey":{},"signed_blocks":[{{"slot":"5"}}],"signed_attestations":[]}}],
+                "metadata":{{"interchange_format_version":"5","genesis_validators_root":{}}}}}"#,
+            serde_json::to_string(&pubkey(0)).unwrap(),
+            serde_json::to_string(&genesis_validators_root).unwrap(),
+        )
+    };
+
+    // A mismatched genesis validators root after the data aborts the whole import.
+    let err = slashing_db
+        .import_interchange_from_reader(json(Hash256::repeat_byte(1)).as_bytes(), Hash256::zero())
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        InterchangeError::GenesisValidatorsMismatch { .. }
+    ));
+    assert_eq!(slashing_db.num_validator_rows().unwrap(), 0);
+
+    let outcomes = slashing_db
+        .import_interchange_from_reader(json(Hash256::zero()).as_bytes(), Hash256::zero())
+        .unwrap();
+    assert_eq!(outcomes.len(), 1);
+    assert_eq!(slashing_db.num_validator_rows().unwrap(), 1);
+}
+
+#[test]
+fn streaming_import_invalid_json() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    for json in [
+        r#"{"data":[]}"#,
+        r#"{"metadata":{"interchange_format_version":"5","genesis_validators_root":"0x0000000000000000000000000000000000000000000000000000000000000000"},"data":[]"#,
+        r#"{"metadata":{"interchange_format_version":"5","genesis_validators_root":"0x0000000000000000000000000000000000000000000000000000000000000000"},"data":[]} trailing"#,
+    ] {
+        let err = slashing_db
+            .import_interchange_from_reader(json.as_bytes(), Hash256::zero())
+            .unwrap_err();
+        assert!(
+            matches!(err, InterchangeError::SerdeJsonError(_)),
+            "{}",
+            json
+        );
+    }
+}
+
+#[test]
+fn check_interchange_does_not_modify_database() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let interchange = |genesis_validators_root: Hash256| Interchange {
+        metadata: InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        },
+        data: (0..2)
+            .map(|i| InterchangeData {
+                pubkey: pubkey(i),
+                signed_blocks: vec![interchange::SignedBlock {
+                    slot: Slot::new(i as u64),
+                    signing_root: None,
+                }],
+                signed_attestations: vec![],
+            })
+            .collect(),
+    };
+
+    // A successful check reports the same outcomes as an import, but leaves the database empty.
+    let outcomes = slashing_db
+        .check_interchange_info(interchange(Hash256::zero()), Hash256::zero())
+        .unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.iter().all(|outcome| !outcome.failed()));
+    assert_eq!(slashing_db.num_validator_rows().unwrap(), 0);
+
+    // A failed check reports the same error as an import.
+    let err = slashing_db
+        .check_interchange_info(interchange(Hash256::repeat_byte(1)), Hash256::zero())
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        InterchangeError::GenesisValidatorsMismatch { .. }
+    ));
+
+    slashing_db
+        .import_interchange_info(interchange(Hash256::zero()), Hash256::zero())
+        .unwrap();
+    assert_eq!(slashing_db.num_validator_rows().unwrap(), 2);
+}
@@ -1,10 +1,19 @@
 use crate::InterchangeError;
+use flate2::read::MultiGzDecoder;
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
-use std::io;
+use std::fmt;
+use std::io::{self, BufRead, BufReader, Read, Write};
 use types::{Epoch, Hash256, PublicKeyBytes, Slot};
 
+/// The first two bytes of any gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Size of the buffer used when streaming an interchange file.
+const READ_BUFFER_SIZE: usize = 1 << 20;
+
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 #[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
@@ -57,18 +66,54 @@ impl Interchange {
         serde_json::from_str(json)
     }
 
-    pub fn from_json_reader(mut reader: impl std::io::Read) -> Result<Self, io::Error> {
+    /// Read an interchange file into memory, decompressing it if it is gzipped.
+    ///
+    /// Large files should be processed with `stream_from_reader` instead.
+    pub fn from_json_reader(reader: impl Read) -> Result<Self, io::Error> {
         // We read the entire file into memory first, as this is *a lot* faster than using
         // `serde_json::from_reader`. See https://github.com/serde-rs/json/issues/160
         let mut json_str = String::new();
-        reader.read_to_string(&mut json_str)?;
+        maybe_decompress(reader)?.read_to_string(&mut json_str)?;
         Ok(Interchange::from_json_str(&json_str)?)
     }
 
+    /// Deserialize an interchange file one record at a time, passing each part to `sink`.
+    ///
+    /// Only a single record is held in memory at once, so arbitrarily large files can be
+    /// processed. Gzipped input is decompressed transparently. The metadata is passed to `sink`
+    /// wherever it appears in the file, which may be after some or all of the records.
+    pub fn stream_from_reader<S: InterchangeSink>(
+        reader: impl Read,
+        sink: &mut S,
+    ) -> Result<(), S::Error> {
+        let reader = maybe_decompress(reader).map_err(serde_json::Error::io)?;
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let mut stream = InterchangeStream { sink, error: None };
+        let result = (&mut stream)
+            .deserialize(&mut deserializer)
+            .and_then(|()| deserializer.end());
+
+        // An error from the sink takes precedence over the deserialization error it caused.
+        match (stream.error, result) {
+            (Some(e), _) => Err(e),
+            (None, Err(e)) => Err(e.into()),
+            (None, Ok(())) => Ok(()),
+        }
+    }
+
     pub fn write_to(&self, writer: impl std::io::Write) -> Result<(), serde_json::Error> {
         serde_json::to_writer(writer, self)
     }
 
+    /// Write the interchange one record at a time, as `stream_from_reader` would read it.
+    pub fn stream_to(&self, writer: impl Write) -> Result<(), serde_json::Error> {
+        let mut writer = InterchangeWriter::new(writer, &self.metadata)?;
+        for record in &self.data {
+            writer.write_record(record)?;
+        }
+        writer.finish().map(drop)
+    }
+
     /// Do these two `Interchange`s contain the same data (ignoring ordering)?
     pub fn equiv(&self, other: &Self) -> bool {
         let self_set = self.data.iter().collect::<HashSet<_>>();
@@ -157,3 +202,168 @@ impl Interchange {
         })
     }
 }
+
+/// Wrap `reader` in a gzip decoder if its contents are gzip-compressed.
+fn maybe_decompress<'a>(reader: impl Read + 'a) -> io::Result<Box<dyn Read + 'a>> {
+    let mut reader = BufReader::with_capacity(READ_BUFFER_SIZE, reader);
+    if reader.fill_buf()?.starts_with(&GZIP_MAGIC) {
+        Ok(Box::new(BufReader::with_capacity(
+            READ_BUFFER_SIZE,
+            MultiGzDecoder::new(reader),
+        )))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Receives the parts of an interchange file as they are deserialized.
+pub trait InterchangeSink {
+    type Error: From<serde_json::Error>;
+
+    fn metadata(&mut self, metadata: InterchangeMetadata) -> Result<(), Self::Error>;
+
+    fn record(&mut self, record: InterchangeData) -> Result<(), Self::Error>;
+}
+
+/// Deserializes an `Interchange` into an `InterchangeSink`.
+struct InterchangeStream<'a, S: InterchangeSink> {
+    sink: &'a mut S,
+    /// The error returned by `sink`, if any, which aborted deserialization.
+    error: Option<S::Error>,
+}
+
+impl<S: InterchangeSink> InterchangeStream<'_, S> {
+    fn forward<E: de::Error>(&mut self, result: Result<(), S::Error>) -> Result<(), E> {
+        result.map_err(|e| {
+            self.error = Some(e);
+            E::custom("aborted by interchange sink")
+        })
+    }
+}
+
+impl<'de, S: InterchangeSink> DeserializeSeed<'de> for &mut InterchangeStream<'_, S> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, S: InterchangeSink> Visitor<'de> for &mut InterchangeStream<'_, S> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an interchange object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut seen_metadata = false;
+        let mut seen_data = false;
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "metadata" => {
+                    if seen_metadata {
+                        return Err(de::Error::duplicate_field("metadata"));
+                    }
+                    seen_metadata = true;
+                    let metadata = map.next_value()?;
+                    let result = self.sink.metadata(metadata);
+                    self.forward(result)?;
+                }
+                "data" => {
+                    if seen_data {
+                        return Err(de::Error::duplicate_field("data"));
+                    }
+                    seen_data = true;
+                    map.next_value_seed(RecordsSeed(&mut *self))?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !seen_metadata {
+            return Err(de::Error::missing_field("metadata"));
+        }
+        if !seen_data {
+            return Err(de::Error::missing_field("data"));
+        }
+        Ok(())
+    }
+}
+
+/// Deserializes the `data` list of an interchange, passing each record to the sink.
+struct RecordsSeed<'b, 'a, S: InterchangeSink>(&'b mut InterchangeStream<'a, S>);
+
+impl<'de, S: InterchangeSink> DeserializeSeed<'de> for RecordsSeed<'_, '_, S> {
+    type Value = ();
+
+    fn deserialize<D: de::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, S: InterchangeSink> Visitor<'de> for RecordsSeed<'_, '_, S> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "a list of interchange records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(record) = seq.next_element()? {
+            let result = self.0.sink.record(record);
+            self.0.forward(result)?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes an interchange file one record at a time.
+///
+/// The output is identical to serializing the equivalent `Interchange` with `serde_json`.
+pub struct InterchangeWriter<W: Write> {
+    writer: W,
+    num_records: usize,
+}
+
+impl<W: Write> InterchangeWriter<W> {
+    pub fn new(mut writer: W, metadata: &InterchangeMetadata) -> Result<Self, serde_json::Error> {
+        writer
+            .write_all(b"{\"metadata\":")
+            .map_err(serde_json::Error::io)?;
+        serde_json::to_writer(&mut writer, metadata)?;
+        writer
+            .write_all(b",\"data\":[")
+            .map_err(serde_json::Error::io)?;
+        Ok(Self {
+            writer,
+            num_records: 0,
+        })
+    }
+
+    pub fn write_record(&mut self, record: &InterchangeData) -> Result<(), serde_json::Error> {
+        if self.num_records > 0 {
+            self.writer.write_all(b",").map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// The number of records written so far.
+    pub fn num_records(&self) -> usize {
+        self.num_records
+    }
+
+    /// Terminate the interchange, flush the writer and return it.
+    pub fn finish(mut self) -> Result<W, serde_json::Error> {
+        self.writer
+            .write_all(b"]}")
+            .map_err(serde_json::Error::io)?;
+        self.writer.flush().map_err(serde_json::Error::io)?;
+        Ok(self.writer)
+    }
+}
@@ -76,15 +76,19 @@ impl MultiTestCase {
             // we should be capable of importing no matter what.
             let allow_import_failure = false;
 
-            let interchange = if minify {
+            let import_result = if minify {
                 let minified = test_case.interchange.minify().unwrap();
                 check_minification_invariants(&test_case.interchange, &minified);
-                minified
+                slashing_db.import_interchange_info(minified, self.genesis_validators_root)
             } else {
-                test_case.interchange.clone()
+                // Exercise the streaming import with the unmodified interchange.
+                let mut json = vec![];
+                test_case.interchange.stream_to(&mut json).unwrap();
+                slashing_db
+                    .import_interchange_from_reader(json.as_slice(), self.genesis_validators_root)
             };
 
-            match slashing_db.import_interchange_info(interchange, self.genesis_validators_root) {
+            match import_result {
                 Ok(import_outcomes) => {
                     let none_failed = import_outcomes.iter().all(|o| !o.failed());
                     assert!(
@@ -1,6 +1,6 @@
 use crate::interchange::{
-    Interchange, InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
-    SignedBlock as InterchangeBlock,
+    Interchange, InterchangeData, InterchangeMetadata, InterchangeSink, InterchangeWriter,
+    SignedAttestation as InterchangeAttestation, SignedBlock as InterchangeBlock,
 };
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
@@ -9,6 +9,7 @@ use filesystem::restrict_file_permissions;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
 use std::fs::File;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
 use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
@@ -60,6 +61,10 @@ impl SlashingDatabase {
         let conn_pool = Self::open_conn_pool(path)?;
         let mut conn = conn_pool.get()?;
 
+        // Allow the space freed by pruning to be reclaimed by `compact_incremental`. This must be
+        // set before any tables are created.
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+
         conn.execute(
             "CREATE TABLE validators (
                 id INTEGER PRIMARY KEY,
@@ -679,44 +684,85 @@ impl SlashingDatabase {
         interchange: Interchange,
         genesis_validators_root: Hash256,
     ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
-        let version = interchange.metadata.interchange_format_version;
-        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
-            return Err(InterchangeError::UnsupportedVersion(version));
-        }
+        self.import_interchange_with(genesis_validators_root, |importer| {
+            importer.metadata(interchange.metadata)?;
+            for record in interchange.data {
+                importer.record(record)?;
+            }
+            Ok(())
+        })
+    }
 
-        if genesis_validators_root != interchange.metadata.genesis_validators_root {
-            return Err(InterchangeError::GenesisValidatorsMismatch {
-                client: genesis_validators_root,
-                interchange_file: interchange.metadata.genesis_validators_root,
-            });
-        }
+    /// Import slashing protection from an interchange file without loading it into memory.
+    ///
+    /// The file may be gzipped. Like `import_interchange_info`, the import is atomic.
+    pub fn import_interchange_from_reader(
+        &self,
+        reader: impl Read,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
+        self.import_interchange_with(genesis_validators_root, |importer| {
+            Interchange::stream_from_reader(reader, importer)
+        })
+    }
+
+    /// Check that an interchange could be imported, without modifying the database.
+    ///
+    /// The records are imported into a transaction which is always rolled back, so this performs
+    /// exactly the same checks as `import_interchange_info`.
+    pub fn check_interchange_info(
+        &self,
+        interchange: Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
+        self.import_interchange_with_persist(genesis_validators_root, false, |importer| {
+            importer.metadata(interchange.metadata)?;
+            for record in interchange.data {
+                importer.record(record)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn import_interchange_with(
+        &self,
+        genesis_validators_root: Hash256,
+        import: impl FnOnce(&mut InterchangeImporter) -> Result<(), InterchangeError>,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
+        self.import_interchange_with_persist(genesis_validators_root, true, import)
+    }
 
+    fn import_interchange_with_persist(
+        &self,
+        genesis_validators_root: Hash256,
+        persist: bool,
+        import: impl FnOnce(&mut InterchangeImporter) -> Result<(), InterchangeError>,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
         // Create a single transaction for the entire batch, which will only be committed if
         // all records are imported successfully.
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
 
-        let mut import_outcomes = vec![];
-        let mut commit = true;
-
-        for record in interchange.data {
-            let pubkey = record.pubkey;
-            match self.import_interchange_record(record, &txn) {
-                Ok(summary) => {
-                    import_outcomes.push(InterchangeImportOutcome::Success { pubkey, summary });
-                }
-                Err(error) => {
-                    import_outcomes.push(InterchangeImportOutcome::Failure { pubkey, error });
-                    commit = false;
-                }
-            }
-        }
+        let mut importer = InterchangeImporter {
+            db: self,
+            txn: &txn,
+            genesis_validators_root,
+            outcomes: vec![],
+            commit: true,
+        };
+        import(&mut importer)?;
+        let InterchangeImporter {
+            outcomes, commit, ..
+        } = importer;
 
         if commit {
-            txn.commit()?;
-            Ok(import_outcomes)
+            // Dropping the transaction without committing it rolls it back.
+            if persist {
+                txn.commit()?;
+            }
+            Ok(outcomes)
         } else {
-            Err(InterchangeError::AtomicBatchAborted(import_outcomes))
+            Err(InterchangeError::AtomicBatchAborted(outcomes))
         }
     }
 
@@ -815,32 +861,10 @@ impl SlashingDatabase {
         selected_pubkeys: Option<&[PublicKeyBytes]>,
         txn: &Transaction,
     ) -> Result<Interchange, InterchangeError> {
-        // Determine the validator IDs and public keys to export data for.
-        let to_export = if let Some(selected_pubkeys) = selected_pubkeys {
-            selected_pubkeys
-                .iter()
-                .map(|pubkey| {
-                    let id = self.get_validator_id_ignoring_status(txn, pubkey)?;
-                    Ok((id, *pubkey))
-                })
-                .collect::<Result<_, InterchangeError>>()?
-        } else {
-            self.list_all_registered_validators(txn)?
-        };
-
-        let data = to_export
+        let data = self
+            .validators_to_export(selected_pubkeys, txn)?
             .into_iter()
-            .map(|(validator_id, pubkey)| {
-                let signed_blocks =
-                    self.export_interchange_blocks_for_validator(validator_id, txn)?;
-                let signed_attestations =
-                    self.export_interchange_attestations_for_validator(validator_id, txn)?;
-                Ok(InterchangeData {
-                    pubkey,
-                    signed_blocks,
-                    signed_attestations,
-                })
-            })
+            .map(|(validator_id, pubkey)| self.export_interchange_record(validator_id, pubkey, txn))
             .collect::<Result<_, InterchangeError>>()?;
 
         let metadata = InterchangeMetadata {
@@ -851,6 +875,70 @@ impl SlashingDatabase {
         Ok(Interchange { metadata, data })
     }
 
+    /// Export an interchange file to `writer` one validator at a time, without building the
+    /// whole interchange in memory.
+    ///
+    /// Returns the number of records written.
+    pub fn export_interchange_info_to_writer(
+        &self,
+        genesis_validators_root: Hash256,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+        writer: impl Write,
+    ) -> Result<usize, InterchangeError> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = &conn.transaction()?;
+
+        let metadata = InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        };
+        let mut writer = InterchangeWriter::new(writer, &metadata)?;
+
+        for (validator_id, pubkey) in self.validators_to_export(selected_pubkeys, txn)? {
+            let record = self.export_interchange_record(validator_id, pubkey, txn)?;
+            writer.write_record(&record)?;
+        }
+
+        let num_records = writer.num_records();
+        writer.finish()?;
+        Ok(num_records)
+    }
+
+    /// Determine the validator IDs and public keys to export data for.
+    fn validators_to_export(
+        &self,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+        txn: &Transaction,
+    ) -> Result<Vec<(i64, PublicKeyBytes)>, InterchangeError> {
+        if let Some(selected_pubkeys) = selected_pubkeys {
+            selected_pubkeys
+                .iter()
+                .map(|pubkey| {
+                    let id = self.get_validator_id_ignoring_status(txn, pubkey)?;
+                    Ok((id, *pubkey))
+                })
+                .collect()
+        } else {
+            self.list_all_registered_validators(txn)
+        }
+    }
+
+    fn export_interchange_record(
+        &self,
+        validator_id: i64,
+        pubkey: PublicKeyBytes,
+        txn: &Transaction,
+    ) -> Result<InterchangeData, InterchangeError> {
+        let signed_blocks = self.export_interchange_blocks_for_validator(validator_id, txn)?;
+        let signed_attestations =
+            self.export_interchange_attestations_for_validator(validator_id, txn)?;
+        Ok(InterchangeData {
+            pubkey,
+            signed_blocks,
+            signed_attestations,
+        })
+    }
+
     fn export_interchange_blocks_for_validator(
         &self,
         validator_id: i64,
@@ -1012,6 +1100,95 @@ impl SlashingDatabase {
         Ok(())
     }
 
+    /// Remove the signing history of all validators, enabled or not, prior to `new_min_target`.
+    ///
+    /// Attestations with targets prior to `new_min_target` and blocks with slots prior to its start
+    /// slot are removed. As with `prune_all_signed_attestations` and `prune_all_signed_blocks`, the
+    /// latest attestation and block of each validator are always retained, so the pruned database
+    /// continues to protect against slashable messages and can be exported to a valid interchange
+    /// file.
+    pub fn prune_all_validators(
+        &self,
+        new_min_target: Epoch,
+        slots_per_epoch: u64,
+    ) -> Result<PruneSummary, NotSafe> {
+        let new_min_slot = new_min_target.start_slot(slots_per_epoch);
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+
+        let attestations_removed = txn.execute(
+            "DELETE FROM signed_attestations
+             WHERE
+                target_epoch < ?1 AND
+                target_epoch < (SELECT MAX(latest.target_epoch)
+                                FROM signed_attestations AS latest
+                                WHERE latest.validator_id = signed_attestations.validator_id)",
+            params![new_min_target],
+        )?;
+        let blocks_removed = txn.execute(
+            "DELETE FROM signed_blocks
+             WHERE
+                slot < ?1 AND
+                slot < (SELECT MAX(latest.slot)
+                        FROM signed_blocks AS latest
+                        WHERE latest.validator_id = signed_blocks.validator_id)",
+            params![new_min_slot],
+        )?;
+
+        txn.commit()?;
+        Ok(PruneSummary {
+            attestations_removed,
+            blocks_removed,
+        })
+    }
+
+    /// Returns the greatest attestation target epoch of any validator, or `None` if no attestations
+    /// have been signed.
+    pub fn max_attestation_target(&self) -> Result<Option<Epoch>, NotSafe> {
+        let conn = self.conn_pool.get()?;
+        let max_target = conn.query_row(
+            "SELECT MAX(target_epoch) FROM signed_attestations",
+            params![],
+            |row| row.get(0),
+        )?;
+        Ok(max_target)
+    }
+
+    /// Returns the fraction of the pages in the database file which are unused, e.g. because the
+    /// records which occupied them have been pruned.
+    pub fn free_page_fraction(&self) -> Result<f64, NotSafe> {
+        let conn = self.conn_pool.get()?;
+        let page_count: i64 = conn.query_row("PRAGMA page_count", params![], |row| row.get(0))?;
+        let freelist_count: i64 =
+            conn.query_row("PRAGMA freelist_count", params![], |row| row.get(0))?;
+        if page_count == 0 {
+            return Ok(0.0);
+        }
+        Ok(freelist_count as f64 / page_count as f64)
+    }
+
+    /// Rebuild the database file, returning the space freed by pruning to the filesystem.
+    ///
+    /// This also enables incremental vacuuming on databases created before it was the default, so
+    /// that `compact_incremental` can reclaim space from them in future.
+    ///
+    /// Compacting a large database may take several minutes, during which nothing can be signed.
+    pub fn compact(&self) -> Result<(), NotSafe> {
+        let conn = self.conn_pool.get()?;
+        conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")?;
+        conn.execute_batch("VACUUM")?;
+        Ok(())
+    }
+
+    /// Return up to `max_pages` unused pages to the filesystem, without rebuilding the database.
+    ///
+    /// This has no effect on databases without incremental vacuuming enabled, see `compact`.
+    pub fn compact_incremental(&self, max_pages: u32) -> Result<(), NotSafe> {
+        let conn = self.conn_pool.get()?;
+        conn.execute_batch(&format!("PRAGMA incremental_vacuum({max_pages})"))?;
+        Ok(())
+    }
+
     pub fn num_validator_rows(&self) -> Result<u32, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
@@ -1104,6 +1281,13 @@ impl ValidatorSummary {
     }
 }
 
+/// The number of records removed by `SlashingDatabase::prune_all_validators`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PruneSummary {
+    pub attestations_removed: usize,
+    pub blocks_removed: usize,
+}
+
 /// Take the maximum of `opt_x` and `y`, returning `y` if `opt_x` is `None`.
 fn max_or<T: Copy + Ord>(opt_x: Option<T>, y: T) -> T {
     opt_x.map_or(y, |x| std::cmp::max(x, y))
@@ -1118,6 +1302,51 @@ fn monotonic<T: PartialOrd>(new: Option<T>, prev: Option<T>) -> bool {
     })
 }
 
+/// Imports the records of an interchange into a single transaction, as they are deserialized.
+struct InterchangeImporter<'a> {
+    db: &'a SlashingDatabase,
+    txn: &'a Transaction<'a>,
+    genesis_validators_root: Hash256,
+    outcomes: Vec<InterchangeImportOutcome>,
+    /// Whether every record so far has been imported successfully.
+    commit: bool,
+}
+
+impl InterchangeSink for InterchangeImporter<'_> {
+    type Error = InterchangeError;
+
+    fn metadata(&mut self, metadata: InterchangeMetadata) -> Result<(), InterchangeError> {
+        let version = metadata.interchange_format_version;
+        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(version));
+        }
+
+        if self.genesis_validators_root != metadata.genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsMismatch {
+                client: self.genesis_validators_root,
+                interchange_file: metadata.genesis_validators_root,
+            });
+        }
+        Ok(())
+    }
+
+    fn record(&mut self, record: InterchangeData) -> Result<(), InterchangeError> {
+        let pubkey = record.pubkey;
+        match self.db.import_interchange_record(record, self.txn) {
+            Ok(summary) => {
+                self.outcomes
+                    .push(InterchangeImportOutcome::Success { pubkey, summary });
+            }
+            Err(error) => {
+                self.outcomes
+                    .push(InterchangeImportOutcome::Failure { pubkey, error });
+                self.commit = false;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// The result of importing a single entry from an interchange file.
 #[derive(Debug)]
 pub enum InterchangeImportOutcome {
@@ -104,6 +104,20 @@ pub fn cli_app() -> Command {
             )
             .display_order(0)
         )
+        .arg(
+            Arg::new("watch-validator-definitions")
+            .long("watch-validator-definitions")
+            .action(ArgAction::SetTrue)
+            .help_heading(FLAG_HEADER)
+            .help(
+                "If present, reload the validator_definitions.yml file whenever it is modified, \
+                so that validators can be added, removed or updated without a restart. Unless \
+                --disable-auto-discover is present, keystores added to the validators-dir are \
+                also discovered. Newly added validators are registered with the slashing \
+                protection database automatically."
+            )
+            .display_order(0)
+        )
         .arg(
             Arg::new("use-long-timeouts")
                 .long("use-long-timeouts")
@@ -153,6 +167,18 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposer-config-file")
+                .long("proposer-config-file")
+                .help("Path to a YAML file of per-validator fee recipients, gas limits and builder \
+                       settings. The file is reloaded when it is modified, and the new settings \
+                       apply from the next proposal or validator registration. Per-validator \
+                       entries take priority over the validator definitions, which take priority \
+                       over the file's defaults.")
+                .value_name("FILE")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("distributed")
                 .long("distributed")
@@ -381,6 +407,45 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("proposal-rehearsal-lead-time")
+                .long("proposal-rehearsal-lead-time")
+                .value_name("SECONDS")
+                .help("If present, each upcoming block proposal is rehearsed on every beacon node \
+                    (and proposer node) at least this many seconds before it is due. The beacon \
+                    nodes produce a block without signing or publishing it, and a critical log is \
+                    emitted for each one that fails, leaving time to fix the problem before the \
+                    proposal. Requires Lighthouse beacon nodes. Must not exceed one epoch.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("clock-source")
+                .long("clock-source")
+                .value_name("SOURCE")
+                .help("The source of the time used to schedule duties. \"system\" reads the \
+                    system clock. \"ntp:HOST[:PORT][,HOST[:PORT]...]\" corrects the system \
+                    clock every 64 seconds by the offset measured from the NTP servers, using \
+                    the response with the lowest round-trip delay. \"ptp:DEVICE\" (e.g., \
+                    \"ptp:/dev/ptp0\") corrects it by the offset measured from a PTP hardware \
+                    clock, which is only supported on Linux and requires the kernel TAI offset \
+                    to be set. The offset and its jitter are published as metrics.")
+                .default_value("system")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("clock-skew-threshold")
+                .long("clock-skew-threshold")
+                .value_name("MILLIS")
+                .help("Offsets measured from the --clock-source above this many milliseconds \
+                    are not applied. Instead, a critical error is logged and the system clock is \
+                    used. The same tolerance is allowed when checking the local clock against \
+                    the clocks of the beacon nodes at startup.")
+                .default_value("500")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validator-registration-batch-size")
                 .long("validator-registration-batch-size")
@@ -404,6 +469,25 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("builder-min-bid")
+                .long("builder-min-bid")
+                .value_name("WEI")
+                .help("The minimum execution payload value, in wei, of a builder block. \
+                    Builder blocks with a lower bid are replaced by a locally built block.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("builder-local-value-factor")
+                .long("builder-local-value-factor")
+                .value_name("PERCENTAGE")
+                .help("If set, a locally built block is also requested whenever the beacon \
+                    node returns a builder block. The builder block is only proposed if its \
+                    payload value exceeds this percentage of the local payload value.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("prefer-builder-proposals")
                 .long("prefer-builder-proposals")
@@ -413,6 +497,21 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("attestation-timing")
+                .long("attestation-timing")
+                .value_name("TIMING")
+                .help("When to publish attestations during the slot. \"fixed\" attests at a \
+                    fixed offset into the slot, whilst \"block\" attests as soon as the beacon \
+                    node imports the block for the slot, or at the offset if no block arrives. \
+                    The offset in milliseconds may be given after a colon (e.g., \"block:3000\"), \
+                    otherwise 1/3 of the way through the slot is used. Offsets are capped at 2/3 \
+                    of the way through the slot. Can be overridden for each validator with \
+                    `attestation_timing` in the validator definitions.")
+                .default_value("fixed")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("beacon-nodes-sync-tolerances")
                 .long("beacon-nodes-sync-tolerances")
@@ -440,6 +539,18 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("max-optimistic-head-slots")
+                .long("max-optimistic-head-slots")
+                .value_name("SLOTS")
+                .help("Stop using a beacon node once its head block has been optimistic for more \
+                    than this many slots, measured from its latest block with a verified execution \
+                    payload. No messages will be signed using data from such a beacon node. \
+                    Beacon nodes which cannot report this distance are instead ranked below \
+                    non-optimistic beacon nodes whilst their head is optimistic.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-slashing-protection-web3signer")
                 .long("disable-slashing-protection-web3signer")
@@ -475,4 +586,24 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("web3-signer-max-concurrent-requests")
+                .long("web3-signer-max-concurrent-requests")
+                .value_name("COUNT")
+                .help("Maximum number of signing requests in flight to each web3signer host. \
+                       Further requests are queued until an earlier request completes. Default \
+                       is unlimited.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("web3-signer-http2")
+                .long("web3-signer-http2")
+                .help("If present, use HTTP/2 for all requests to web3signer hosts, so that \
+                       concurrent requests share a single connection. The web3signer must \
+                       support HTTP/2.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
 }
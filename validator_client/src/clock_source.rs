@@ -0,0 +1,814 @@
+//! Clock sources for the validator client's slot clock.
+//!
+//! By default the slot clock reads the system clock. It can instead be disciplined by NTP servers
+//! or a PTP hardware clock, in which case the offset of the system clock from the source is
+//! measured periodically and added to the system time whenever the slot clock is read. This keeps
+//! duties on time even if the host's own time synchronisation drifts, which is otherwise only
+//! noticed once attestations are missed.
+//!
+//! Only offsets within the clock skew threshold are applied. A larger offset means that either the
+//! system clock or the clock source is badly wrong, so rather than trusting the clock source a
+//! critical error is logged and the system clock is used until the offset is back within the
+//! threshold. The offset and its jitter are exposed as metrics.
+//!
+//! Regardless of the clock source, the slot clock is checked against the clocks of the beacon
+//! nodes at startup.
+use beacon_node_fallback::BeaconNodeFallback;
+use environment::RuntimeContext;
+use serde::{Deserialize, Serialize};
+use slog::{crit, debug, info, warn, Logger};
+use slot_clock::{SlotClock, SystemTimeSource, TimeSource};
+use std::collections::VecDeque;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use types::EthSpec;
+use types::Slot;
+
+/// The default offset of the clock source from the system clock above which it is not applied.
+pub const DEFAULT_CLOCK_SKEW_THRESHOLD: Duration = Duration::from_millis(500);
+
+/// The time between measurements of the offset of the clock source.
+const CLOCK_POLL_INTERVAL: Duration = Duration::from_secs(64);
+
+/// The number of recent measurements from which the jitter is computed.
+const JITTER_SAMPLES: usize = 8;
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+const NTP_PORT: u16 = 123;
+const NTP_PACKET_LEN: usize = 48;
+const NTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The number of requests sent to each NTP server per measurement.
+const NTP_SAMPLES: usize = 4;
+/// The seconds between the NTP epoch (1900) and the UNIX epoch (1970).
+const NTP_UNIX_EPOCH_OFFSET: i128 = 2_208_988_800;
+
+/// The number of readings of a PTP hardware clock, of which the one bracketed most closely by
+/// readings of the system clock is used.
+#[cfg(target_os = "linux")]
+const PTP_SAMPLES: usize = 5;
+
+/// The source of the time read by the slot clock.
+///
+/// Represented as a string of the form `system`, `ntp:HOST[:PORT][,HOST[:PORT]...]` or
+/// `ptp:DEVICE`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum ClockSource {
+    /// The system clock.
+    #[default]
+    System,
+    /// The system clock, corrected by the offset measured from the NTP servers.
+    Ntp(Vec<String>),
+    /// The system clock, corrected by the offset measured from a PTP hardware clock, such as
+    /// `/dev/ptp0`. Only supported on Linux.
+    Ptp(PathBuf),
+}
+
+impl ClockSource {
+    /// Measures the offset of the clock source from the system clock in nanoseconds, which is
+    /// positive if the system clock is behind.
+    ///
+    /// This function blocks on I/O, so it must not be called from an async context.
+    pub fn measure_offset(&self) -> Result<i64, String> {
+        match self {
+            ClockSource::System => Ok(0),
+            ClockSource::Ntp(servers) => measure_ntp_offset(servers),
+            ClockSource::Ptp(device) => measure_ptp_offset(device),
+        }
+    }
+}
+
+impl FromStr for ClockSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            None if s == "system" => Ok(ClockSource::System),
+            Some(("ntp", servers)) if servers.split(',').all(|server| !server.is_empty()) => Ok(
+                ClockSource::Ntp(servers.split(',').map(str::to_string).collect()),
+            ),
+            Some(("ptp", device)) if !device.is_empty() => Ok(ClockSource::Ptp(device.into())),
+            _ => Err(format!(
+                "Unknown clock source {}, expected \"system\", \
+                 \"ntp:HOST[:PORT][,HOST[:PORT]...]\" or \"ptp:DEVICE\"",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ClockSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockSource::System => write!(f, "system"),
+            ClockSource::Ntp(servers) => write!(f, "ntp:{}", servers.join(",")),
+            ClockSource::Ptp(device) => write!(f, "ptp:{}", device.display()),
+        }
+    }
+}
+
+impl TryFrom<String> for ClockSource {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<ClockSource> for String {
+    fn from(source: ClockSource) -> String {
+        source.to_string()
+    }
+}
+
+/// A time source which reads the system clock and adds the offset last measured from a
+/// `ClockSource`.
+#[derive(Default)]
+pub struct DisciplinedTimeSource {
+    offset_nanos: AtomicI64,
+}
+
+impl DisciplinedTimeSource {
+    /// Returns the offset added to the system clock, in nanoseconds.
+    pub fn offset_nanos(&self) -> i64 {
+        self.offset_nanos.load(Ordering::Relaxed)
+    }
+}
+
+impl TimeSource for DisciplinedTimeSource {
+    fn now(&self) -> Option<Duration> {
+        apply_offset(SystemTimeSource.now()?, self.offset_nanos())
+    }
+}
+
+fn apply_offset(now: Duration, offset_nanos: i64) -> Option<Duration> {
+    let offset = Duration::from_nanos(offset_nanos.unsigned_abs());
+    if offset_nanos >= 0 {
+        now.checked_add(offset)
+    } else {
+        now.checked_sub(offset)
+    }
+}
+
+/// Measures the offset of `clock_source` and returns a time source which applies it, after
+/// spawning a service which measures the offset every `CLOCK_POLL_INTERVAL`.
+///
+/// If the first measurement fails or exceeds `skew_threshold`, the system clock is used until a
+/// measurement succeeds within the threshold.
+pub async fn start_clock_source_service<E: EthSpec>(
+    context: &RuntimeContext<E>,
+    clock_source: ClockSource,
+    skew_threshold: Duration,
+) -> Arc<DisciplinedTimeSource> {
+    let time_source = Arc::new(DisciplinedTimeSource::default());
+    let mut discipline = ClockDiscipline {
+        clock_source,
+        time_source: time_source.clone(),
+        skew_threshold,
+        offsets: VecDeque::with_capacity(JITTER_SAMPLES),
+        log: context.log().clone(),
+    };
+
+    discipline.update(context).await;
+    info!(
+        context.log(),
+        "Using clock source";
+        "clock_source" => %discipline.clock_source,
+        "offset_ms" => time_source.offset_nanos() as f64 / 1e6,
+    );
+
+    let service_context = context.clone();
+    let future = async move {
+        loop {
+            sleep(CLOCK_POLL_INTERVAL).await;
+            discipline.update(&service_context).await;
+        }
+    };
+    context.executor.spawn(future, "clock_source");
+
+    time_source
+}
+
+/// The state of the clock source service.
+struct ClockDiscipline {
+    clock_source: ClockSource,
+    time_source: Arc<DisciplinedTimeSource>,
+    skew_threshold: Duration,
+    /// The most recent offsets measured, in nanoseconds.
+    offsets: VecDeque<i64>,
+    log: Logger,
+}
+
+impl ClockDiscipline {
+    /// Measures the offset of the clock source and applies it to the time source.
+    async fn update<E: EthSpec>(&mut self, context: &RuntimeContext<E>) {
+        let clock_source = self.clock_source.clone();
+        let result = match context
+            .executor
+            .spawn_blocking_handle(move || clock_source.measure_offset(), "clock_source")
+        {
+            Some(handle) => handle
+                .await
+                .map_err(|e| format!("Clock measurement task failed: {:?}", e))
+                .and_then(|result| result),
+            None => return,
+        };
+
+        let offset_nanos = match result {
+            Ok(offset_nanos) => offset_nanos,
+            Err(e) => {
+                validator_metrics::inc_counter(&validator_metrics::VC_CLOCK_MEASUREMENT_ERRORS);
+                warn!(
+                    self.log,
+                    "Unable to measure clock offset";
+                    "error" => e,
+                    "clock_source" => %self.clock_source,
+                );
+                return;
+            }
+        };
+
+        if self.offsets.len() == JITTER_SAMPLES {
+            self.offsets.pop_front();
+        }
+        self.offsets.push_back(offset_nanos);
+        let jitter_nanos = standard_deviation(&self.offsets);
+
+        validator_metrics::set_float_gauge(
+            &validator_metrics::VC_CLOCK_OFFSET,
+            offset_nanos as f64 / 1e9,
+        );
+        validator_metrics::set_float_gauge(&validator_metrics::VC_CLOCK_JITTER, jitter_nanos / 1e9);
+
+        let applied_offset_nanos = applied_offset(offset_nanos, self.skew_threshold);
+        self.time_source
+            .offset_nanos
+            .store(applied_offset_nanos.unwrap_or(0), Ordering::Relaxed);
+        validator_metrics::set_gauge(
+            &validator_metrics::VC_CLOCK_SKEW_EXCEEDED,
+            applied_offset_nanos.is_none() as i64,
+        );
+        if applied_offset_nanos.is_none() {
+            crit!(
+                self.log,
+                "Clock source offset exceeds threshold";
+                "msg" => "using the system clock instead. Check the time synchronisation of this \
+                    host and the clock source",
+                "offset_ms" => offset_nanos as f64 / 1e6,
+                "threshold_ms" => self.skew_threshold.as_millis(),
+                "clock_source" => %self.clock_source,
+            );
+        } else {
+            debug!(
+                self.log,
+                "Measured clock offset";
+                "offset_ms" => offset_nanos as f64 / 1e6,
+                "jitter_ms" => jitter_nanos / 1e6,
+                "clock_source" => %self.clock_source,
+            );
+        }
+    }
+}
+
+/// Returns the offset to apply to the system clock given the measured `offset_nanos`, or `None` if
+/// it exceeds `skew_threshold` and the system clock should be used as is.
+fn applied_offset(offset_nanos: i64, skew_threshold: Duration) -> Option<i64> {
+    (u128::from(offset_nanos.unsigned_abs()) <= skew_threshold.as_nanos()).then_some(offset_nanos)
+}
+
+fn standard_deviation(samples: &VecDeque<i64>) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let n = samples.len() as f64;
+    let mean = samples.iter().map(|&x| x as f64).sum::<f64>() / n;
+    let variance = samples
+        .iter()
+        .map(|&x| (x as f64 - mean).powi(2))
+        .sum::<f64>()
+        / n;
+    variance.sqrt()
+}
+
+/// Checks that the slot clock agrees with the clocks of the beacon nodes, logging a critical error
+/// if they differ by more than `skew_threshold`.
+///
+/// Beacon nodes only report their current slot, which on its own bounds the offset of their clock
+/// to a whole slot. The beacon nodes are therefore asked for their current slot `skew_threshold`
+/// before and after the start of the next slot, where the slot they report bounds their offset to
+/// within the threshold (plus the duration of the request).
+///
+/// This takes up to a slot, so it should be spawned rather than awaited.
+pub async fn check_clock_against_beacon_nodes<T: SlotClock + 'static, E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<T, E>,
+    slot_clock: &T,
+    skew_threshold: Duration,
+    log: &Logger,
+) {
+    let Some(slot_start) = slot_clock
+        .now()
+        .and_then(|slot| slot_clock.start_of(slot + 1))
+    else {
+        return;
+    };
+
+    let mut bounds = OffsetBounds::default();
+    for query_time in [
+        slot_start.saturating_sub(skew_threshold),
+        slot_start + skew_threshold,
+    ] {
+        if let Some(delay) = slot_clock
+            .now_duration()
+            .and_then(|now| query_time.checked_sub(now))
+        {
+            sleep(delay).await;
+        }
+
+        let Some(before) = slot_clock.now_duration() else {
+            return;
+        };
+        let beacon_node_slot = match beacon_nodes
+            .first_success(|beacon_node| async move { beacon_node.get_node_syncing().await })
+            .await
+        {
+            // The current slot of the beacon node, according to its own clock.
+            Ok(response) => response.data.head_slot + response.data.sync_distance,
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to check clock against beacon nodes";
+                    "error" => %e,
+                );
+                return;
+            }
+        };
+        let Some(after) = slot_clock.now_duration() else {
+            return;
+        };
+        let Some(query_bounds) =
+            OffsetBounds::from_reported_slot(slot_clock, beacon_node_slot, before, after)
+        else {
+            return;
+        };
+        bounds = bounds.intersect(query_bounds);
+    }
+
+    let threshold_nanos = skew_threshold.as_nanos() as i128;
+    if bounds.lower > threshold_nanos || bounds.upper < -threshold_nanos {
+        crit!(
+            log,
+            "Local clock disagrees with beacon node";
+            "msg" => "check the time synchronisation of both hosts, or use --clock-source",
+            "min_offset_ms" => bounds.lower as f64 / 1e6,
+            "max_offset_ms" => bounds.upper as f64 / 1e6,
+            "threshold_ms" => skew_threshold.as_millis(),
+        );
+    } else {
+        debug!(
+            log,
+            "Local clock agrees with beacon node";
+            "min_offset_ms" => bounds.lower as f64 / 1e6,
+            "max_offset_ms" => bounds.upper as f64 / 1e6,
+        );
+    }
+}
+
+/// Bounds on the offset of a beacon node's clock from the slot clock, in nanoseconds, which is
+/// positive if the beacon node's clock is ahead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct OffsetBounds {
+    lower: i128,
+    upper: i128,
+}
+
+impl Default for OffsetBounds {
+    fn default() -> Self {
+        Self {
+            lower: i128::MIN,
+            upper: i128::MAX,
+        }
+    }
+}
+
+impl OffsetBounds {
+    /// Bounds the offset given that the beacon node reported `slot` as its current slot in
+    /// response to a request sent at `before` and answered at `after`, both read from
+    /// `slot_clock`.
+    ///
+    /// The beacon node's clock was within `slot` at some time between `before` and `after`, so
+    /// its offset is at least `start_of(slot) - after` and less than `start_of(slot + 1) - before`.
+    fn from_reported_slot<T: SlotClock>(
+        slot_clock: &T,
+        slot: Slot,
+        before: Duration,
+        after: Duration,
+    ) -> Option<Self> {
+        let slot_start = slot_clock.start_of(slot)?.as_nanos() as i128;
+        let next_slot_start = slot_clock.start_of(slot + 1)?.as_nanos() as i128;
+        Some(Self {
+            lower: slot_start - after.as_nanos() as i128,
+            upper: next_slot_start - before.as_nanos() as i128,
+        })
+    }
+
+    fn intersect(self, other: Self) -> Self {
+        Self {
+            lower: self.lower.max(other.lower),
+            upper: self.upper.min(other.upper),
+        }
+    }
+}
+
+/// A measurement of the offset of an NTP server.
+#[derive(Debug, Clone, Copy)]
+struct NtpSample {
+    /// The offset of the server's clock from the system clock, in nanoseconds.
+    offset: i128,
+    /// The round-trip delay of the request, excluding the time spent by the server.
+    delay: i128,
+}
+
+/// Measures the offset from the NTP `servers`, each of which may include a port.
+///
+/// `NTP_SAMPLES` requests are sent to each server and the offset of the sample with the lowest
+/// round-trip delay is used, as it is the least affected by asymmetric network delays.
+fn measure_ntp_offset(servers: &[String]) -> Result<i64, String> {
+    let mut best: Option<NtpSample> = None;
+    let mut errors = vec![];
+    for server in servers {
+        let address = match ntp_address(server) {
+            Ok(address) => address,
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        for _ in 0..NTP_SAMPLES {
+            match ntp_sample(address) {
+                Ok(sample) => {
+                    if best.map_or(true, |best| sample.delay < best.delay) {
+                        best = Some(sample);
+                    }
+                }
+                Err(e) => {
+                    // Don't wait for an unresponsive server more than once.
+                    errors.push(e);
+                    break;
+                }
+            }
+        }
+    }
+
+    let best = best.ok_or_else(|| errors.join(", "))?;
+    i64::try_from(best.offset).map_err(|_| "NTP offset is out of range".to_string())
+}
+
+/// Measures the offset of the NTP server at `address` with a single SNTP request.
+fn ntp_sample(address: SocketAddr) -> Result<NtpSample, String> {
+    let local_address: SocketAddr = if address.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let socket = UdpSocket::bind(local_address)
+        .and_then(|socket| {
+            socket.set_read_timeout(Some(NTP_TIMEOUT))?;
+            socket.connect(address)?;
+            Ok(socket)
+        })
+        .map_err(|e| format!("Unable to open socket to {}: {}", address, e))?;
+
+    let mut request = [0; NTP_PACKET_LEN];
+    // Leap indicator 0, version 4, mode 3 (client).
+    request[0] = 0b00_100_011;
+    let sent = system_nanos()?;
+    // The server echoes the transmit timestamp, which identifies its response to this request.
+    request[40..48].copy_from_slice(&to_ntp_timestamp(sent));
+    socket
+        .send(&request)
+        .map_err(|e| format!("Unable to send NTP request to {}: {}", address, e))?;
+
+    let mut response = [0; NTP_PACKET_LEN];
+    let len = socket
+        .recv(&mut response)
+        .map_err(|e| format!("No NTP response from {}: {}", address, e))?;
+    let received = system_nanos()?;
+
+    if len < NTP_PACKET_LEN {
+        return Err(format!("NTP response from {} is too short", address));
+    }
+    // Mode 4 (server).
+    if response[0] & 0b111 != 4 || response[24..32] != request[40..48] {
+        return Err(format!("Unexpected NTP response from {}", address));
+    }
+    if response[1] == 0 {
+        return Err(format!(
+            "NTP server {} refused the request: {}",
+            address,
+            String::from_utf8_lossy(&response[12..16])
+        ));
+    }
+    // Leap indicator 3 means the server's clock is unsynchronised.
+    if response[0] >> 6 == 3 {
+        return Err(format!("NTP server {} is unsynchronised", address));
+    }
+
+    let server_received = from_ntp_timestamp(&response[32..40]);
+    let server_sent = from_ntp_timestamp(&response[40..48]);
+    Ok(NtpSample {
+        offset: ((server_received - sent) + (server_sent - received)) / 2,
+        delay: (received - sent) - (server_sent - server_received),
+    })
+}
+
+/// Resolves `server`, using the default NTP port if none is given.
+fn ntp_address(server: &str) -> Result<SocketAddr, String> {
+    let address = if server.parse::<SocketAddr>().is_ok() {
+        server.to_string()
+    } else if let Ok(ip) = server.parse::<IpAddr>() {
+        SocketAddr::new(ip, NTP_PORT).to_string()
+    } else if server.contains(':') {
+        server.to_string()
+    } else {
+        format!("{}:{}", server, NTP_PORT)
+    };
+    address
+        .to_socket_addrs()
+        .map_err(|e| format!("Unable to resolve NTP server {}: {}", server, e))?
+        .next()
+        .ok_or_else(|| format!("NTP server {} has no addresses", server))
+}
+
+/// Reads the system clock in nanoseconds since the UNIX epoch.
+fn system_nanos() -> Result<i128, String> {
+    SystemTimeSource
+        .now()
+        .map(|now| now.as_nanos() as i128)
+        .ok_or_else(|| "Unable to read system time".to_string())
+}
+
+/// Converts nanoseconds since the UNIX epoch to an NTP timestamp.
+fn to_ntp_timestamp(nanos: i128) -> [u8; 8] {
+    let seconds = nanos.div_euclid(NANOS_PER_SEC) + NTP_UNIX_EPOCH_OFFSET;
+    let fraction = (nanos.rem_euclid(NANOS_PER_SEC) << 32) / NANOS_PER_SEC;
+    let mut timestamp = [0; 8];
+    timestamp[..4].copy_from_slice(&(seconds as u32).to_be_bytes());
+    timestamp[4..].copy_from_slice(&(fraction as u32).to_be_bytes());
+    timestamp
+}
+
+/// Converts an NTP timestamp in the current NTP era to nanoseconds since the UNIX epoch.
+fn from_ntp_timestamp(timestamp: &[u8]) -> i128 {
+    let seconds = u32::from_be_bytes([timestamp[0], timestamp[1], timestamp[2], timestamp[3]]);
+    let fraction = u32::from_be_bytes([timestamp[4], timestamp[5], timestamp[6], timestamp[7]]);
+    (i128::from(seconds) - NTP_UNIX_EPOCH_OFFSET) * NANOS_PER_SEC
+        + ((i128::from(fraction) * NANOS_PER_SEC) >> 32)
+}
+
+/// Measures the offset of the PTP hardware clock at `device`, e.g. `/dev/ptp0`.
+#[cfg(target_os = "linux")]
+fn measure_ptp_offset(device: &Path) -> Result<i64, String> {
+    use std::fs::File;
+    use std::os::fd::AsRawFd;
+
+    let file = File::open(device)
+        .map_err(|e| format!("Unable to open PTP device {}: {}", device.display(), e))?;
+    // PTP hardware clocks keep TAI, which is ahead of UTC by the number of leap seconds.
+    let tai_offset = kernel_tai_offset()?;
+    // The dynamic clock ID of the device, as defined by `FD_TO_CLOCKID` in the kernel.
+    let clock_id = ((!file.as_raw_fd()) << 3) | 3;
+
+    let (_, offset) = (0..PTP_SAMPLES)
+        .map(|_| {
+            let before = read_clock(libc::CLOCK_REALTIME)?;
+            let ptp = read_clock(clock_id)
+                .map_err(|e| format!("Unable to read PTP device {}: {}", device.display(), e))?;
+            let after = read_clock(libc::CLOCK_REALTIME)?;
+            Ok((after - before, ptp - tai_offset - (before + after) / 2))
+        })
+        .collect::<Result<Vec<_>, String>>()?
+        .into_iter()
+        .min_by_key(|(gap, _)| *gap)
+        .ok_or("No PTP readings")?;
+    i64::try_from(offset)
+        .map_err(|_| format!("PTP offset from {} is out of range", device.display()))
+}
+
+#[cfg(not(target_os = "linux"))]
+fn measure_ptp_offset(_device: &Path) -> Result<i64, String> {
+    Err("PTP clock sources are only supported on Linux".to_string())
+}
+
+/// Returns the offset of TAI from UTC in nanoseconds, as set in the kernel by the host's time
+/// synchronisation (e.g. `ptp4l`, `phc2sys` or `chronyd`).
+#[cfg(target_os = "linux")]
+fn kernel_tai_offset() -> Result<i128, String> {
+    let realtime = read_clock(libc::CLOCK_REALTIME)?;
+    let tai = read_clock(libc::CLOCK_TAI)?;
+    // The offset is a whole number of seconds, so round away the time between the two readings.
+    let offset = (tai - realtime + NANOS_PER_SEC / 2).div_euclid(NANOS_PER_SEC) * NANOS_PER_SEC;
+    if offset == 0 {
+        return Err(
+            "The kernel TAI offset is not set, which is required to read a PTP clock".to_string(),
+        );
+    }
+    Ok(offset)
+}
+
+/// Reads the clock with `clock_id` in nanoseconds.
+#[cfg(target_os = "linux")]
+fn read_clock(clock_id: libc::clockid_t) -> Result<i128, String> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // Safe because `time` is a valid `timespec` which outlives the call.
+    if unsafe { libc::clock_gettime(clock_id, &mut time) } != 0 {
+        return Err(std::io::Error::last_os_error().to_string());
+    }
+    Ok(i128::from(time.tv_sec) * NANOS_PER_SEC + i128::from(time.tv_nsec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::ManualSlotClock;
+
+    #[test]
+    fn parse_clock_source() {
+        for (s, source) in [
+            ("system", ClockSource::System),
+            (
+                "ntp:pool.ntp.org",
+                ClockSource::Ntp(vec!["pool.ntp.org".into()]),
+            ),
+            (
+                "ntp:10.0.0.1:1123",
+                ClockSource::Ntp(vec!["10.0.0.1:1123".into()]),
+            ),
+            ("ntp:[::1]:123", ClockSource::Ntp(vec!["[::1]:123".into()])),
+            (
+                "ntp:time.example.com,[::1]:123",
+                ClockSource::Ntp(vec!["time.example.com".into(), "[::1]:123".into()]),
+            ),
+            ("ptp:/dev/ptp0", ClockSource::Ptp("/dev/ptp0".into())),
+        ] {
+            assert_eq!(s.parse::<ClockSource>(), Ok(source.clone()));
+            assert_eq!(source.to_string(), s);
+        }
+        for s in [
+            "",
+            "ntp",
+            "ntp:",
+            "ntp:a,",
+            "ntp:,b",
+            "ptp:",
+            "gps:/dev/ttyS0",
+            "system:1",
+        ] {
+            assert!(s.parse::<ClockSource>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn ntp_timestamps() {
+        for nanos in [0, 1_700_000_000_123_456_789, 1_999_999_999_999_999_999] {
+            let timestamp = to_ntp_timestamp(nanos);
+            // NTP fractions have a resolution of less than a nanosecond, but truncate.
+            assert!((nanos - from_ntp_timestamp(&timestamp)).abs() <= 1);
+        }
+        // 1 January 1970 in the NTP era which began in 1900.
+        assert_eq!(to_ntp_timestamp(0), [0x83, 0xaa, 0x7e, 0x80, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn offsets() {
+        let now = Duration::from_secs(100);
+        assert_eq!(
+            apply_offset(now, 1_500_000_000),
+            Some(Duration::from_millis(101_500))
+        );
+        assert_eq!(
+            apply_offset(now, -1_500_000_000),
+            Some(Duration::from_millis(98_500))
+        );
+        assert_eq!(apply_offset(now, -101_000_000_000), None);
+
+        assert_eq!(standard_deviation(&VecDeque::new()), 0.0);
+        assert_eq!(standard_deviation(&[2, 4, 4, 4, 5, 5, 7, 9].into()), 2.0);
+
+        let threshold = Duration::from_millis(500);
+        assert_eq!(applied_offset(-500_000_000, threshold), Some(-500_000_000));
+        assert_eq!(applied_offset(500_000_001, threshold), None);
+        assert_eq!(applied_offset(-500_000_001, threshold), None);
+    }
+
+    /// Serves NTP requests on a local socket, replying after `delay` with the system time plus
+    /// `offset_nanos`.
+    fn mock_ntp_server(offset_nanos: i128, delay: Duration) -> String {
+        let socket = UdpSocket::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+        let address = socket.local_addr().unwrap();
+        std::thread::spawn(move || {
+            let mut request = [0; NTP_PACKET_LEN];
+            while let Ok((_, peer)) = socket.recv_from(&mut request) {
+                std::thread::sleep(delay);
+                let now = to_ntp_timestamp(system_nanos().unwrap() + offset_nanos);
+                let mut response = [0; NTP_PACKET_LEN];
+                // Leap indicator 0, version 4, mode 4 (server), stratum 1.
+                response[0] = 0b00_100_100;
+                response[1] = 1;
+                response[24..32].copy_from_slice(&request[40..48]);
+                response[32..40].copy_from_slice(&now);
+                response[40..48].copy_from_slice(&now);
+                socket.send_to(&response, peer).unwrap();
+            }
+        });
+        address.to_string()
+    }
+
+    fn assert_offset_near(offset_nanos: i64, expected: Duration) {
+        let error = (i128::from(offset_nanos) - expected.as_nanos() as i128).abs();
+        assert!(
+            error < Duration::from_millis(50).as_nanos() as i128,
+            "offset {}ns, expected {:?}",
+            offset_nanos,
+            expected
+        );
+    }
+
+    #[test]
+    fn ntp_exchange() {
+        let server = mock_ntp_server(2 * NANOS_PER_SEC, Duration::ZERO);
+        let offset = measure_ntp_offset(&[server]).unwrap();
+        assert_offset_near(offset, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn ntp_uses_lowest_delay_sample() {
+        // Nothing listens on port 1, so requests to it fail without waiting for the timeout.
+        let unreachable = "127.0.0.1:1".to_string();
+        let slow = mock_ntp_server(5 * NANOS_PER_SEC, Duration::from_millis(200));
+        let fast = mock_ntp_server(NANOS_PER_SEC, Duration::ZERO);
+
+        let offset = measure_ntp_offset(&[unreachable.clone(), slow, fast]).unwrap();
+        assert_offset_near(offset, Duration::from_secs(1));
+
+        assert!(measure_ntp_offset(&[unreachable]).is_err());
+    }
+
+    #[test]
+    fn beacon_node_offset_bounds() {
+        let slot_clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        );
+        let millis = |millis: u64| Duration::from_millis(millis);
+        let nanos = |millis: i128| millis * 1_000_000;
+
+        // Queried 500ms before the start of slot 10, and answered 100ms later.
+        let before_start = |slot: u64| {
+            OffsetBounds::from_reported_slot(
+                &slot_clock,
+                Slot::new(slot),
+                millis(119_500),
+                millis(119_600),
+            )
+            .unwrap()
+        };
+        // Queried 500ms after the start of slot 10, and answered 100ms later.
+        let after_start = |slot: u64| {
+            OffsetBounds::from_reported_slot(
+                &slot_clock,
+                Slot::new(slot),
+                millis(120_500),
+                millis(120_600),
+            )
+            .unwrap()
+        };
+
+        // A beacon node in sync with the slot clock is within the threshold.
+        let bounds = before_start(9).intersect(after_start(10));
+        assert_eq!(
+            bounds,
+            OffsetBounds {
+                lower: nanos(-600),
+                upper: nanos(500),
+            }
+        );
+
+        // A beacon node which had already reached slot 10 is ahead by at least 400ms.
+        assert_eq!(before_start(10).lower, nanos(400));
+        // A beacon node which had not yet reached slot 10 is behind by more than 500ms.
+        assert_eq!(after_start(9).upper, nanos(-500));
+
+        assert_eq!(OffsetBounds::default().intersect(bounds), bounds);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::clock_source::{ClockSource, DEFAULT_CLOCK_SKEW_THRESHOLD};
 use beacon_node_fallback::{beacon_node_health::BeaconNodeSyncDistanceTiers, ApiTopic};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -19,7 +20,7 @@ use std::time::Duration;
 use types::{Address, GRAFFITI_BYTES_LEN};
 use validator_http_api;
 use validator_http_metrics;
-use validator_store::Config as ValidatorStoreConfig;
+use validator_store::{proposer_config::ProposerConfig, Config as ValidatorStoreConfig};
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
 
@@ -44,6 +45,8 @@ pub struct Config {
     pub allow_unsynced_beacon_node: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
+    /// If true, reload the validator definitions when they are modified on disk.
+    pub watch_validator_definitions: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
     /// If true, use longer timeouts for requests made to the beacon node.
@@ -79,6 +82,12 @@ pub struct Config {
     pub enable_latency_measurement_service: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
+    /// If set, upcoming proposals are rehearsed on the beacon nodes this long before they are due.
+    pub proposal_rehearsal_lead_time: Option<Duration>,
+    /// The source of the time read by the slot clock.
+    pub clock_source: ClockSource,
+    /// The offset of the clock source from the system clock above which a warning is logged.
+    pub clock_skew_threshold: Duration,
     /// Whether we are running with distributed network support.
     pub distributed: bool,
     /// Configuration for the initialized validators
@@ -108,6 +117,7 @@ impl Default for Config {
             proposer_nodes: Vec::new(),
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
+            watch_validator_definitions: false,
             init_slashing_protection: false,
             use_long_timeouts: false,
             graffiti: None,
@@ -123,6 +133,9 @@ impl Default for Config {
             broadcast_topics: vec![ApiTopic::Subscriptions],
             enable_latency_measurement_service: true,
             validator_registration_batch_size: 500,
+            proposal_rehearsal_lead_time: None,
+            clock_source: ClockSource::System,
+            clock_skew_threshold: DEFAULT_CLOCK_SKEW_THRESHOLD,
             distributed: false,
             initialized_validators: <_>::default(),
         }
@@ -185,6 +198,7 @@ impl Config {
         }
 
         config.disable_auto_discover = cli_args.get_flag("disable-auto-discover");
+        config.watch_validator_definitions = cli_args.get_flag("watch-validator-definitions");
         config.init_slashing_protection = cli_args.get_flag("init-slashing-protection");
         config.use_long_timeouts = cli_args.get_flag("use-long-timeouts");
 
@@ -197,6 +211,13 @@ impl Config {
             info!(log, "Successfully loaded graffiti file"; "path" => graffiti_file_path);
         }
 
+        if let Some(proposer_config_path) = cli_args.get_one::<String>("proposer-config-file") {
+            let path = PathBuf::from(proposer_config_path);
+            ProposerConfig::from_file(&path)?;
+            config.validator_store.proposer_config_file = Some(path);
+            info!(log, "Successfully loaded proposer config file"; "path" => proposer_config_path);
+        }
+
         if let Some(input_graffiti) = cli_args.get_one::<String>("graffiti") {
             let graffiti_bytes = input_graffiti.as_bytes();
             if graffiti_bytes.len() > GRAFFITI_BYTES_LEN {
@@ -251,6 +272,8 @@ impl Config {
         } else {
             config.beacon_node_fallback.sync_tolerances = BeaconNodeSyncDistanceTiers::default();
         }
+        config.beacon_node_fallback.max_optimistic_head_distance =
+            parse_optional(cli_args, "max-optimistic-head-slots")?;
 
         /*
          * Web3 signer
@@ -269,6 +292,15 @@ impl Config {
                 .initialized_validators
                 .web3_signer_max_idle_connections = Some(n);
         }
+        if let Some(n) = parse_optional::<usize>(cli_args, "web3-signer-max-concurrent-requests")? {
+            if n == 0 {
+                return Err("web3-signer-max-concurrent-requests cannot be 0".to_string());
+            }
+            config
+                .initialized_validators
+                .web3_signer_max_concurrent_requests = Some(n);
+        }
+        config.initialized_validators.web3_signer_http2 = cli_args.get_flag("web3-signer-http2");
 
         /*
          * Http API server
@@ -396,8 +428,14 @@ impl Config {
             );
         }
 
+        config.validator_store.attestation_timing = parse_required(cli_args, "attestation-timing")?;
+
         config.validator_store.builder_boost_factor =
             parse_optional(cli_args, "builder-boost-factor")?;
+        config.validator_store.builder_policy.min_bid =
+            parse_optional(cli_args, "builder-min-bid")?;
+        config.validator_store.builder_policy.local_value_factor =
+            parse_optional(cli_args, "builder-local-value-factor")?;
 
         config.enable_latency_measurement_service =
             !cli_args.get_flag("disable-latency-measurement-service");
@@ -408,6 +446,13 @@ impl Config {
             return Err("validator-registration-batch-size cannot be 0".to_string());
         }
 
+        config.proposal_rehearsal_lead_time =
+            parse_optional(cli_args, "proposal-rehearsal-lead-time")?.map(Duration::from_secs);
+
+        config.clock_source = parse_required(cli_args, "clock-source")?;
+        config.clock_skew_threshold =
+            Duration::from_millis(parse_required(cli_args, "clock-skew-threshold")?);
+
         config.validator_store.enable_web3signer_slashing_protection =
             if cli_args.get_flag("disable-slashing-protection-web3signer") {
                 warn!(
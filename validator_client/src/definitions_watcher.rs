@@ -0,0 +1,179 @@
+use crate::ProductionValidatorClient;
+use account_utils::validator_definitions::{
+    is_voting_keystore, ValidatorDefinitions, CONFIG_FILENAME,
+};
+use notify::{Event, RecursiveMode, Watcher};
+use slog::{debug, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, Duration};
+use types::EthSpec;
+use validator_store::ValidatorStore;
+
+/// Wait until the validators directory has been quiet for this long before reloading, so that a
+/// reload doesn't observe a partially copied set of keystores.
+const DEBOUNCE_DELAY: Duration = Duration::from_secs(2);
+
+/// Spawns a service which reloads the validator definitions whenever `validator_definitions.yml`,
+/// or a voting keystore in the validators directory if auto-discovery is enabled, is modified.
+pub fn spawn_definitions_watcher<E: EthSpec>(
+    client: &ProductionValidatorClient<E>,
+) -> Result<(), String> {
+    let context = client.context.service_context("definitions_watcher".into());
+    let executor = context.executor.clone();
+    let validator_store = client.validator_store.clone();
+    let validator_dir = client.config.validator_dir.clone();
+    let secrets_dir = client.config.secrets_dir.clone();
+    let auto_discover = !client.config.disable_auto_discover;
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher_log = context.log().clone();
+    let mut watcher =
+        notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+            Ok(event) => {
+                if event
+                    .paths
+                    .iter()
+                    .any(|path| is_watched_file(path, auto_discover))
+                {
+                    let _ = tx.send(());
+                }
+            }
+            Err(e) => warn!(
+                watcher_log,
+                "Error watching validators directory";
+                "error" => format!("{:?}", e)
+            ),
+        })
+        .map_err(|e| format!("Unable to create validators directory watcher: {:?}", e))?;
+    watcher
+        .watch(&validator_dir, RecursiveMode::Recursive)
+        .map_err(|e| {
+            format!(
+                "Unable to watch validators directory {}: {:?}",
+                validator_dir.display(),
+                e
+            )
+        })?;
+
+    let watcher_fut = async move {
+        // The watcher stops sending events when it is dropped.
+        let _watcher = watcher;
+        let log = context.log();
+
+        while rx.recv().await.is_some() {
+            loop {
+                sleep(DEBOUNCE_DELAY).await;
+                let mut modified = false;
+                while rx.try_recv().is_ok() {
+                    modified = true;
+                }
+                if !modified {
+                    break;
+                }
+            }
+
+            reload_definitions(
+                &validator_store,
+                &validator_dir,
+                &secrets_dir,
+                auto_discover,
+                log,
+            )
+            .await;
+        }
+    };
+
+    executor.spawn(watcher_fut, "definitions_watcher");
+    Ok(())
+}
+
+/// Returns `true` if a modification of `path` may change the validator definitions.
+///
+/// Other files in the validators directory, such as the slashing protection database and the key
+/// cache, are modified frequently by the validator client itself and must be ignored.
+fn is_watched_file(path: &Path, auto_discover: bool) -> bool {
+    path.file_name()
+        .and_then(|file_name| file_name.to_str())
+        .is_some_and(|file_name| {
+            file_name == CONFIG_FILENAME || (auto_discover && is_voting_keystore(file_name))
+        })
+}
+
+/// Load the validator definitions from the filesystem and apply any changes to `validator_store`.
+async fn reload_definitions<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: &Arc<ValidatorStore<T, E>>,
+    validator_dir: &Path,
+    secrets_dir: &Path,
+    auto_discover: bool,
+    log: &Logger,
+) {
+    let mut definitions = match ValidatorDefinitions::open(validator_dir) {
+        Ok(definitions) => definitions,
+        Err(e) => {
+            error!(
+                log,
+                "Unable to reload validator definitions";
+                "error" => format!("{:?}", e)
+            );
+            return;
+        }
+    };
+
+    // Discovered keystores are not saved to the definitions file, to avoid overwriting any
+    // concurrent modifications made via the HTTP API. They will be saved by the next modification,
+    // or discovered again after a restart.
+    if auto_discover {
+        if let Err(e) = definitions.discover_local_keystores(validator_dir, secrets_dir, log) {
+            warn!(
+                log,
+                "Unable to discover local validator keystores";
+                "error" => format!("{:?}", e)
+            );
+        }
+    }
+
+    if definitions.as_slice()
+        == validator_store
+            .initialized_validators()
+            .read()
+            .validator_definitions()
+    {
+        debug!(log, "Validator definitions unchanged");
+        return;
+    }
+
+    match validator_store
+        .reload_validator_definitions(definitions)
+        .await
+    {
+        Ok(reload) if reload.failed.is_empty() => info!(
+            log,
+            "Reloaded validator definitions";
+            "added" => reload.added.len(),
+            "removed" => reload.removed.len(),
+        ),
+        Ok(reload) => {
+            let failed = reload
+                .failed
+                .iter()
+                .map(|(pubkey, _)| format!("{:?}", pubkey))
+                .collect::<Vec<_>>();
+            warn!(
+                log,
+                "Some validators failed to reload";
+                "msg" => "the remaining validators have been updated, see the errors above",
+                "failed" => failed.join(", "),
+                "added" => reload.added.len(),
+                "removed" => reload.removed.len(),
+            )
+        }
+        Err(e) => error!(
+            log,
+            "Unable to reload validator definitions";
+            "error" => e
+        ),
+    }
+}
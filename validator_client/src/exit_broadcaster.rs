@@ -0,0 +1,149 @@
+use crate::ProductionValidatorClient;
+use beacon_node_fallback::{Error as FallbackError, Errors};
+use slog::{error, info, warn};
+use slot_clock::SlotClock;
+use tokio::time::{sleep, Duration};
+use types::EthSpec;
+
+/// Spawns a service which publishes the pre-signed exits that have been armed for broadcast once
+/// their broadcast epoch is reached.
+///
+/// Exits which can't be published are retried every slot until they succeed or are deleted, unless
+/// every beacon node rejects them with a 4xx response, in which case they are disarmed.
+pub fn spawn_exit_broadcaster<E: EthSpec>(
+    client: &ProductionValidatorClient<E>,
+) -> Result<(), String> {
+    let context = client.context.service_context("exit_broadcaster".into());
+    let executor = context.executor.clone();
+    let presigned_exits = client.presigned_exits.clone();
+    let beacon_nodes = client.beacon_nodes.clone();
+    let slot_clock = client.slot_clock.clone();
+
+    let slot_duration = Duration::from_secs(context.eth2_config.spec.seconds_per_slot);
+
+    let broadcast_fut = async move {
+        let log = context.log();
+
+        loop {
+            let Some(duration_to_next_slot) = slot_clock.duration_to_next_slot() else {
+                error!(log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+                continue;
+            };
+            sleep(duration_to_next_slot).await;
+
+            let Some(current_epoch) = slot_clock
+                .now()
+                .map(|slot| slot.epoch(E::slots_per_epoch()))
+            else {
+                continue;
+            };
+
+            for (pubkey, exit) in presigned_exits.due_for_broadcast(current_epoch) {
+                let exit = &exit;
+                match beacon_nodes
+                    .first_success(|beacon_node| async move {
+                        beacon_node.post_beacon_pool_voluntary_exits(exit).await
+                    })
+                    .await
+                {
+                    Ok(()) => {
+                        info!(
+                            log,
+                            "Published pre-signed voluntary exit";
+                            "validator" => ?pubkey,
+                            "validator_index" => exit.message.validator_index,
+                            "epoch" => exit.message.epoch,
+                        );
+                        if let Err(e) = presigned_exits.broadcast_complete(pubkey) {
+                            error!(
+                                log,
+                                "Failed to remove published exit";
+                                "validator" => ?pubkey,
+                                "error" => ?e,
+                            );
+                        }
+                    }
+                    Err(e) if is_rejected(&e) => {
+                        error!(
+                            log,
+                            "Pre-signed voluntary exit rejected";
+                            "validator" => ?pubkey,
+                            "error" => %e,
+                            "info" => "the exit will not be retried, it can be armed again once \
+                                the problem is resolved",
+                        );
+                        if let Err(e) = presigned_exits.broadcast_rejected(pubkey) {
+                            error!(
+                                log,
+                                "Failed to disarm rejected exit";
+                                "validator" => ?pubkey,
+                                "error" => ?e,
+                            );
+                        }
+                    }
+                    Err(e) => warn!(
+                        log,
+                        "Failed to publish pre-signed voluntary exit";
+                        "validator" => ?pubkey,
+                        "error" => %e,
+                        "info" => "the exit will be retried next slot",
+                    ),
+                }
+            }
+        }
+    };
+
+    executor.spawn(broadcast_fut, "exit_broadcaster");
+    Ok(())
+}
+
+/// Returns `true` if every beacon node rejected the exit as invalid, rather than failing to process
+/// it. Retrying a rejected exit won't succeed.
+fn is_rejected(errors: &Errors<eth2::Error>) -> bool {
+    !errors.0.is_empty()
+        && errors.0.iter().all(|(_, error)| match error {
+            FallbackError::RequestFailed(e) => e.status().is_some_and(|s| s.is_client_error()),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth2::types::ErrorMessage;
+    use reqwest::StatusCode;
+
+    fn request_failed(status: StatusCode) -> (String, FallbackError<eth2::Error>) {
+        (
+            "node".to_string(),
+            FallbackError::RequestFailed(eth2::Error::ServerMessage(ErrorMessage {
+                code: status.as_u16(),
+                message: "error".to_string(),
+                stacktraces: vec![],
+            })),
+        )
+    }
+
+    #[test]
+    fn client_errors_are_rejections() {
+        assert!(is_rejected(&Errors(vec![request_failed(
+            StatusCode::BAD_REQUEST
+        )])));
+        assert!(is_rejected(&Errors(vec![
+            request_failed(StatusCode::BAD_REQUEST),
+            request_failed(StatusCode::NOT_FOUND),
+        ])));
+
+        // The exit is retried if any node might still accept it.
+        assert!(!is_rejected(&Errors(vec![
+            request_failed(StatusCode::BAD_REQUEST),
+            request_failed(StatusCode::INTERNAL_SERVER_ERROR),
+        ])));
+        assert!(!is_rejected(&Errors(vec![(
+            "node".to_string(),
+            FallbackError::RequestFailed(eth2::Error::StatusCode(StatusCode::SERVICE_UNAVAILABLE)),
+        )])));
+        assert!(!is_rejected(&Errors(vec![])));
+    }
+}
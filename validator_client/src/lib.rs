@@ -1,7 +1,12 @@
 mod cli;
+pub mod clock_source;
 pub mod config;
+mod definitions_watcher;
+mod exit_broadcaster;
 mod latency;
 mod notifier;
+mod proposal_rehearsal;
+mod web3signer_tls;
 
 pub use cli::cli_app;
 pub use config::Config;
@@ -17,16 +22,20 @@ use beacon_node_fallback::{
 
 use account_utils::validator_definitions::ValidatorDefinitions;
 use clap::ArgMatches;
+use clock_source::ClockSource;
+use definitions_watcher::spawn_definitions_watcher;
 use doppelganger_service::DoppelgangerService;
 use environment::RuntimeContext;
 use eth2::{reqwest::ClientBuilder, BeaconNodeHttpClient, StatusCode, Timeouts};
+use exit_broadcaster::spawn_exit_broadcaster;
 use initialized_validators::Error::UnableToOpenVotingKeystore;
 use notifier::spawn_notifier;
 use parking_lot::RwLock;
+use proposal_rehearsal::spawn_proposal_rehearsal;
 use reqwest::Certificate;
 use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
-use slot_clock::SystemTimeSlotClock;
+use slot_clock::SourcedSlotClock;
 use std::fs::File;
 use std::io::Read;
 use std::marker::PhantomData;
@@ -48,7 +57,8 @@ use validator_services::{
     sync::SyncDutiesMap,
     sync_committee_service::SyncCommitteeService,
 };
-use validator_store::ValidatorStore;
+use validator_store::{presigned_exits::PresignedExits, ValidatorStore};
+use web3signer_tls::spawn_web3signer_tls_reloader;
 
 /// The interval between attempts to contact the beacon node during startup.
 const RETRY_DELAY: Duration = Duration::from_secs(2);
@@ -76,17 +86,19 @@ const DOPPELGANGER_SERVICE_NAME: &str = "doppelganger";
 #[derive(Clone)]
 pub struct ProductionValidatorClient<E: EthSpec> {
     context: RuntimeContext<E>,
-    duties_service: Arc<DutiesService<SystemTimeSlotClock, E>>,
-    block_service: BlockService<SystemTimeSlotClock, E>,
-    attestation_service: AttestationService<SystemTimeSlotClock, E>,
-    sync_committee_service: SyncCommitteeService<SystemTimeSlotClock, E>,
+    duties_service: Arc<DutiesService<SourcedSlotClock, E>>,
+    block_service: BlockService<SourcedSlotClock, E>,
+    attestation_service: AttestationService<SourcedSlotClock, E>,
+    sync_committee_service: SyncCommitteeService<SourcedSlotClock, E>,
     doppelganger_service: Option<Arc<DoppelgangerService>>,
-    preparation_service: PreparationService<SystemTimeSlotClock, E>,
-    validator_store: Arc<ValidatorStore<SystemTimeSlotClock, E>>,
-    slot_clock: SystemTimeSlotClock,
+    preparation_service: PreparationService<SourcedSlotClock, E>,
+    validator_store: Arc<ValidatorStore<SourcedSlotClock, E>>,
+    presigned_exits: Arc<PresignedExits>,
+    slot_clock: SourcedSlotClock,
     http_api_listen_addr: Option<SocketAddr>,
     config: Config,
-    beacon_nodes: Arc<BeaconNodeFallback<SystemTimeSlotClock, E>>,
+    beacon_nodes: Arc<BeaconNodeFallback<SourcedSlotClock, E>>,
+    proposer_nodes: Option<Arc<BeaconNodeFallback<SourcedSlotClock, E>>>,
     genesis_time: u64,
 }
 
@@ -255,6 +267,11 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             })
         }?;
 
+        let presigned_exits = Arc::new(
+            PresignedExits::open_or_create(&config.validator_dir)
+                .map_err(|e| format!("Failed to open pre-signed exits: {:?}", e))?,
+        );
+
         // Check validator registration with slashing protection, or auto-register all validators.
         if config.init_slashing_protection {
             slashing_protection
@@ -411,11 +428,20 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             ctx.shared.write().genesis_time = Some(genesis_time);
         }
 
-        let slot_clock = SystemTimeSlotClock::new(
+        let mut slot_clock = SourcedSlotClock::new(
             context.eth2_config.spec.genesis_slot,
             Duration::from_secs(genesis_time),
             Duration::from_secs(context.eth2_config.spec.seconds_per_slot),
         );
+        if config.clock_source != ClockSource::System {
+            let time_source = clock_source::start_clock_source_service(
+                &context,
+                config.clock_source.clone(),
+                config.clock_skew_threshold,
+            )
+            .await;
+            slot_clock = slot_clock.with_time_source(time_source);
+        }
 
         beacon_nodes.set_slot_clock(slot_clock.clone());
         proposer_nodes.set_slot_clock(slot_clock.clone());
@@ -469,6 +495,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         let duties_service = Arc::new(DutiesService {
             attesters: <_>::default(),
             proposers: <_>::default(),
+            alternate_attesters: <_>::default(),
+            attester_retry_backoff: <_>::default(),
             sync_duties: SyncDutiesMap::new(config.distributed),
             slot_clock: slot_clock.clone(),
             beacon_nodes: beacon_nodes.clone(),
@@ -535,11 +563,13 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             doppelganger_service,
             preparation_service,
             validator_store,
+            presigned_exits,
             config,
             slot_clock,
             http_api_listen_addr: None,
             genesis_time,
             beacon_nodes,
+            proposer_nodes: (proposer_nodes_num > 0).then_some(proposer_nodes),
         })
     }
 
@@ -559,6 +589,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 api_secret,
                 block_service: Some(self.block_service.clone()),
                 validator_store: Some(self.validator_store.clone()),
+                presigned_exits: Some(self.presigned_exits.clone()),
                 validator_dir: Some(self.config.validator_dir.clone()),
                 secrets_dir: Some(self.config.secrets_dir.clone()),
                 graffiti_file: self.config.graffiti_file.clone(),
@@ -590,6 +621,23 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         // Wait until genesis has occurred.
         wait_for_genesis(&self.beacon_nodes, self.genesis_time, &self.context).await?;
 
+        let beacon_nodes = self.beacon_nodes.clone();
+        let slot_clock = self.slot_clock.clone();
+        let clock_skew_threshold = self.config.clock_skew_threshold;
+        let clock_check_log = log.clone();
+        self.context.executor.spawn(
+            async move {
+                clock_source::check_clock_against_beacon_nodes(
+                    &beacon_nodes,
+                    &slot_clock,
+                    clock_skew_threshold,
+                    &clock_check_log,
+                )
+                .await
+            },
+            "clock_check",
+        );
+
         duties_service::start_update_service(self.duties_service.clone(), block_service_tx);
 
         self.block_service
@@ -628,6 +676,22 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        spawn_exit_broadcaster(self)
+            .map_err(|e| format!("Failed to start exit broadcaster: {}", e))?;
+
+        spawn_web3signer_tls_reloader(self)
+            .map_err(|e| format!("Failed to start Web3Signer TLS reloader: {}", e))?;
+
+        if self.config.watch_validator_definitions {
+            spawn_definitions_watcher(self)
+                .map_err(|e| format!("Failed to start definitions watcher: {}", e))?;
+        }
+
+        if let Some(lead_time) = self.config.proposal_rehearsal_lead_time {
+            spawn_proposal_rehearsal(self, lead_time)
+                .map_err(|e| format!("Failed to start proposal rehearsal: {}", e))?;
+        }
+
         if self.config.enable_latency_measurement_service {
             latency::start_latency_service(
                 self.context.clone(),
@@ -641,8 +705,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
 }
 
 async fn init_from_beacon_node<E: EthSpec>(
-    beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
-    proposer_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    beacon_nodes: &BeaconNodeFallback<SourcedSlotClock, E>,
+    proposer_nodes: &BeaconNodeFallback<SourcedSlotClock, E>,
     context: &RuntimeContext<E>,
 ) -> Result<(u64, Hash256), String> {
     loop {
@@ -733,7 +797,7 @@ async fn init_from_beacon_node<E: EthSpec>(
 }
 
 async fn wait_for_genesis<E: EthSpec>(
-    beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    beacon_nodes: &BeaconNodeFallback<SourcedSlotClock, E>,
     genesis_time: u64,
     context: &RuntimeContext<E>,
 ) -> Result<(), String> {
@@ -780,7 +844,7 @@ async fn wait_for_genesis<E: EthSpec>(
 /// Request the version from the node, looping back and trying again on failure. Exit once the node
 /// has been contacted.
 async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
-    beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    beacon_nodes: &BeaconNodeFallback<SourcedSlotClock, E>,
     genesis_time: Duration,
     log: &Logger,
 ) -> Result<(), String> {
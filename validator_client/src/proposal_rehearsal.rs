@@ -0,0 +1,195 @@
+use crate::ProductionValidatorClient;
+use beacon_node_fallback::BeaconNodeFallback;
+use eth2::types::SseBlockProductionStage;
+use slog::{crit, error, info, warn, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+use types::{EthSpec, PublicKeyBytes, Slot};
+
+/// Spawns a service which rehearses each upcoming block proposal `lead_time` before it is due.
+///
+/// A rehearsal asks every beacon node (and proposer node) to produce a block for the proposal slot
+/// using the Lighthouse dry run endpoint. The block is never signed or published, but producing it
+/// runs the same stages as the real proposal, including fetching a payload from the execution
+/// layer. A `crit` is logged for every failed rehearsal so that the operator has time to fix the
+/// problem before the proposal.
+pub fn spawn_proposal_rehearsal<E: EthSpec>(
+    client: &ProductionValidatorClient<E>,
+    lead_time: Duration,
+) -> Result<(), String> {
+    let context = client.context.service_context("proposal_rehearsal".into());
+    let executor = context.executor.clone();
+    let attempt_executor = executor.clone();
+    let duties_service = client.duties_service.clone();
+    let slot_clock = client.slot_clock.clone();
+    let beacon_nodes = client.beacon_nodes.clone();
+    let proposer_nodes = client.proposer_nodes.clone();
+
+    let seconds_per_slot = context.eth2_config.spec.seconds_per_slot;
+    let slot_duration = Duration::from_secs(seconds_per_slot);
+    // Rehearse at the start of the slot which is at least `lead_time` before the proposal.
+    let lead_slots = lead_time.as_secs().div_ceil(seconds_per_slot).max(1);
+    // Beacon nodes only produce dry run blocks up to one epoch ahead of the current slot.
+    if lead_slots > E::slots_per_epoch() {
+        return Err(format!(
+            "lead time of {}s exceeds one epoch",
+            lead_time.as_secs()
+        ));
+    }
+
+    let rehearsal_fut = async move {
+        let log = context.log();
+
+        loop {
+            let Some(duration_to_next_slot) = slot_clock.duration_to_next_slot() else {
+                error!(log, "Failed to read slot clock");
+                // If we can't read the slot clock, just wait another slot.
+                sleep(slot_duration).await;
+                continue;
+            };
+            sleep(duration_to_next_slot).await;
+
+            let Some(current_slot) = slot_clock.now() else {
+                continue;
+            };
+            let slot = current_slot + lead_slots;
+
+            let block_proposers = duties_service.block_proposers(slot);
+            let proposers = duties_service
+                .proposers
+                .read()
+                .get(&slot.epoch(E::slots_per_epoch()))
+                .map(|(_, proposers)| {
+                    proposers
+                        .iter()
+                        .filter(|proposer| {
+                            proposer.slot == slot && block_proposers.contains(&proposer.pubkey)
+                        })
+                        .map(|proposer| (proposer.pubkey, proposer.validator_index))
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            for (pubkey, validator_index) in proposers {
+                let beacon_nodes = beacon_nodes.clone();
+                let proposer_nodes = proposer_nodes.clone();
+                let log = log.clone();
+                attempt_executor.spawn(
+                    async move {
+                        rehearse_proposal(
+                            &beacon_nodes,
+                            "beacon_nodes",
+                            slot,
+                            pubkey,
+                            validator_index,
+                            &log,
+                        )
+                        .await;
+                        if let Some(proposer_nodes) = proposer_nodes {
+                            rehearse_proposal(
+                                &proposer_nodes,
+                                "proposer_nodes",
+                                slot,
+                                pubkey,
+                                validator_index,
+                                &log,
+                            )
+                            .await;
+                        }
+                    },
+                    "proposal_rehearsal_attempt",
+                );
+            }
+        }
+    };
+
+    executor.spawn(rehearsal_fut, "proposal_rehearsal");
+    Ok(())
+}
+
+/// Rehearses the proposal at `slot` on every node in `nodes`, logging the outcome.
+async fn rehearse_proposal<T: SlotClock, E: EthSpec>(
+    nodes: &BeaconNodeFallback<T, E>,
+    nodes_kind: &'static str,
+    slot: Slot,
+    pubkey: PublicKeyBytes,
+    validator_index: u64,
+    log: &Logger,
+) {
+    let result = nodes
+        .broadcast(|beacon_node| async move {
+            let dry_run = beacon_node
+                .get_lighthouse_validator_dry_run_block::<E>(slot, false)
+                .await
+                .map_err(|e| format!("failed to produce block: {e}"))?
+                .data;
+
+            // A different proposer means the beacon node disagrees with our duties.
+            if dry_run.block.proposer_index() != validator_index {
+                return Err(format!(
+                    "block produced for proposer {}, expected {}",
+                    dry_run.block.proposer_index(),
+                    validator_index
+                ));
+            }
+
+            info!(
+                log,
+                "Proposal rehearsal succeeded";
+                "beacon_node" => %beacon_node,
+                "slot" => slot,
+                "validator_index" => validator_index,
+                "payload_fetched" => dry_run.payload_fetched,
+                "state_load_ms" => dry_run.state_load_ms,
+                "total_ms" => dry_run.total_ms,
+            );
+
+            let degraded = degraded_stages(&dry_run.stages);
+            if !degraded.is_empty() {
+                warn!(
+                    log,
+                    "Proposal rehearsal exceeded stage budgets";
+                    "beacon_node" => %beacon_node,
+                    "slot" => slot,
+                    "stages" => degraded,
+                );
+            }
+
+            Ok(())
+        })
+        .await;
+
+    match result {
+        Ok(()) => validator_metrics::inc_counter_vec(
+            &validator_metrics::PROPOSAL_REHEARSALS_TOTAL,
+            &[nodes_kind, validator_metrics::SUCCESS],
+        ),
+        Err(e) => {
+            validator_metrics::inc_counter_vec(
+                &validator_metrics::PROPOSAL_REHEARSALS_TOTAL,
+                &[nodes_kind, validator_metrics::FAILURE],
+            );
+            crit!(
+                log,
+                "Proposal rehearsal failed";
+                "slot" => slot,
+                "validator" => ?pubkey,
+                "validator_index" => validator_index,
+                "error" => %e,
+                "advice" => "the upcoming proposal is likely to fail, check the logs of the \
+                    beacon node and execution client",
+            );
+        }
+    }
+}
+
+/// Returns a summary of the stages which exceeded their budget, e.g. `"attestation_packing=512ms"`.
+fn degraded_stages(stages: &[SseBlockProductionStage]) -> String {
+    stages
+        .iter()
+        .filter(|stage| stage.degraded)
+        .map(|stage| format!("{}={}ms", stage.stage, stage.duration_ms))
+        .collect::<Vec<_>>()
+        .join(",")
+}
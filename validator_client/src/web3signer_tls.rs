@@ -0,0 +1,74 @@
+use crate::ProductionValidatorClient;
+use parking_lot::RwLockUpgradableReadGuard;
+use slog::{debug, info, warn};
+use tokio::time::{interval, timeout, Duration, MissedTickBehavior};
+use types::EthSpec;
+
+/// How often the TLS files of Web3Signer instances are checked for modifications.
+const TLS_RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Spawns a service which rebuilds the clients of Web3Signer instances and distributed validator
+/// co-signers when their root certificate or client identity files change on disk, allowing
+/// short-lived certificates to be rotated without restarting the validator client.
+///
+/// New signing requests use the rebuilt client straight away. Requests already in flight complete
+/// on the previous client, which is dropped along with its connections once they have finished.
+pub fn spawn_web3signer_tls_reloader<E: EthSpec>(
+    client: &ProductionValidatorClient<E>,
+) -> Result<(), String> {
+    let context = client.context.service_context("web3signer_tls".into());
+    let executor = context.executor.clone();
+    let validator_store = client.validator_store.clone();
+
+    let reload_fut = async move {
+        let log = context.log();
+        let mut interval = interval(TLS_RELOAD_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            // Only block signing while the clients are rebuilt, not while checking the files.
+            let initialized_validators = validator_store.initialized_validators();
+            let validators = initialized_validators.upgradable_read();
+            if !validators.web3_signer_tls_changed() {
+                continue;
+            }
+            let reloaded = RwLockUpgradableReadGuard::upgrade(validators).reload_web3_signer_tls();
+
+            for reloaded_client in reloaded {
+                info!(
+                    log,
+                    "Reloaded Web3Signer certificates";
+                    "url" => &reloaded_client.url,
+                );
+
+                let log = log.clone();
+                context.executor.spawn(
+                    async move {
+                        // In-flight requests can't outlast the request timeout, but allow a little
+                        // longer so that their responses can be read.
+                        let drain_timeout = reloaded_client.request_timeout * 2;
+                        match timeout(drain_timeout, reloaded_client.retired.drain()).await {
+                            Ok(()) => debug!(
+                                log,
+                                "Drained previous Web3Signer client";
+                                "url" => &reloaded_client.url,
+                            ),
+                            Err(_) => warn!(
+                                log,
+                                "Requests still in flight on previous Web3Signer client";
+                                "url" => &reloaded_client.url,
+                                "timeout" => ?drain_timeout,
+                            ),
+                        }
+                    },
+                    "web3signer_tls_drain",
+                );
+            }
+        }
+    };
+
+    executor.spawn(reload_fut, "web3signer_tls");
+    Ok(())
+}
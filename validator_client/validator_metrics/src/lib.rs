@@ -1,6 +1,7 @@
 use std::sync::LazyLock;
 
 pub const SUCCESS: &str = "success";
+pub const FAILURE: &str = "failure";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
@@ -30,8 +31,16 @@ pub const SUBSCRIPTIONS_HTTP_POST: &str = "subscriptions_http_post";
 pub const UPDATE_PROPOSERS: &str = "update_proposers";
 pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
+pub const SYNC_COMMITTEE_BLOCK_ROOT_HTTP_GET: &str = "sync_committee_block_root_http_get";
+pub const SYNC_COMMITTEE_MESSAGES: &str = "sync_committee_messages";
+pub const SYNC_COMMITTEE_MESSAGES_HTTP_POST: &str = "sync_committee_messages_http_post";
+pub const SYNC_CONTRIBUTIONS: &str = "sync_contributions";
+pub const SYNC_CONTRIBUTIONS_HTTP_GET: &str = "sync_contributions_http_get";
+pub const SYNC_CONTRIBUTIONS_HTTP_POST: &str = "sync_contributions_http_post";
+pub const SYNC_SELECTION_PROOFS: &str = "sync_selection_proofs";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+pub const DISTRIBUTED_SIGNER: &str = "distributed_signer";
 
 pub use metrics::*;
 
@@ -135,6 +144,60 @@ pub static ATTESTATION_SERVICE_TIMES: LazyLock<Result<HistogramVec>> = LazyLock:
         &["task"],
     )
 });
+pub static ATTESTATION_PUBLISH_DELAY: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec_with_buckets(
+        "vc_attestation_publish_delay_seconds",
+        "Time into the slot at which attestations were published, by attestation timing",
+        linear_buckets(0.25, 0.25, 32),
+        &["timing"],
+    )
+});
+pub static ATTESTATION_BLOCK_ARRIVAL_DELAY: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "vc_attestation_block_arrival_delay_seconds",
+        "Time into the slot at which the beacon node imported the block for the slot",
+        linear_buckets(0.25, 0.25, 32),
+    )
+});
+pub static ATTESTATION_DELAY_AFTER_BLOCK: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "vc_attestation_delay_after_block_seconds",
+        "Time between the block for the slot being imported and attestations being published",
+        linear_buckets(0.1, 0.1, 40),
+    )
+});
+pub static ATTESTATION_BLOCK_WAIT_TIMEOUTS_TOTAL: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "vc_attestation_block_wait_timeouts_total",
+            "Total count of attestations produced at the deadline without a block for the slot",
+        )
+    });
+pub static KEYSTORE_DECRYPTION_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "vc_keystore_decryption_times_seconds",
+        "Duration to decrypt a validator keystore",
+    )
+});
+pub static KEYSTORES_TO_DECRYPT_COUNT: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "vc_keystores_to_decrypt_count",
+        "Number of validator keystores being decrypted in parallel",
+    )
+});
+pub static KEYSTORES_DECRYPTED_COUNT: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "vc_keystores_decrypted_count",
+        "Number of validator keystores decrypted in parallel so far",
+    )
+});
+pub static SYNC_COMMITTEE_SERVICE_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec(
+        "vc_sync_committee_service_task_times_seconds",
+        "Duration to perform sync committee service tasks",
+        &["task"],
+    )
+});
 pub static SLASHING_PROTECTION_PRUNE_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
     try_create_histogram(
         "vc_slashing_protection_prune_times_seconds",
@@ -237,6 +300,27 @@ pub static BLOCK_SIGNING_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
         "Duration to obtain a signature for a block",
     )
 });
+pub static WEB3SIGNER_REQUEST_QUEUE_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "vc_web3signer_request_queue_times_seconds",
+        "Duration spent waiting for a Web3Signer request permit",
+    )
+});
+pub static CO_SIGNER_FAILURES_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_co_signer_failures_total",
+        "Total count of failed partial signature requests to distributed validator co-signers",
+        &["co_signer"],
+    )
+});
+
+pub static PROPOSAL_REHEARSALS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_proposal_rehearsals_total",
+        "Total count of proposals rehearsed on the beacon nodes or proposer nodes, by result",
+        &["nodes", "result"],
+    )
+});
 
 pub static ATTESTATION_DUTY: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
@@ -262,3 +346,30 @@ pub static VC_BEACON_NODE_LATENCY_PRIMARY_ENDPOINT: LazyLock<Result<Histogram>>
             "Round-trip latency for the primary BN endpoint",
         )
     });
+/*
+ * Clock source
+ */
+pub static VC_CLOCK_OFFSET: LazyLock<Result<Gauge>> = LazyLock::new(|| {
+    try_create_float_gauge(
+        "vc_clock_offset_seconds",
+        "Latest measured offset of the clock source from the system clock",
+    )
+});
+pub static VC_CLOCK_JITTER: LazyLock<Result<Gauge>> = LazyLock::new(|| {
+    try_create_float_gauge(
+        "vc_clock_jitter_seconds",
+        "Standard deviation of the recent offsets measured from the clock source",
+    )
+});
+pub static VC_CLOCK_MEASUREMENT_ERRORS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "vc_clock_measurement_errors_total",
+        "Total count of failed attempts to measure the offset of the clock source",
+    )
+});
+pub static VC_CLOCK_SKEW_EXCEEDED: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "vc_clock_skew_exceeded",
+        "Set to 1 if the latest offset of the clock source exceeds the clock skew threshold",
+    )
+});
@@ -1,17 +1,24 @@
 use crate::duties_service::{DutiesService, DutyAndProof};
+use account_utils::validator_definitions::AttestationTiming;
 use beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
 use environment::RuntimeContext;
-use futures::future::join_all;
+use eth2::types::{BlockId, EventKind, EventTopic};
+use futures::future::{join_all, BoxFuture, Shared};
+use futures::{FutureExt, StreamExt};
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
-use tokio::time::{sleep, sleep_until, Duration, Instant};
+use tokio::time::{sleep, sleep_until, timeout_at, Duration, Instant};
 use tree_hash::TreeHash;
 use types::{Attestation, AttestationData, ChainSpec, CommitteeIndex, EthSpec, Slot};
 use validator_store::{Error as ValidatorStoreError, ValidatorStore};
 
+/// Resolves to the time into the slot at which the beacon node imported the block for the slot,
+/// or `None` if this could not be determined.
+type BlockArrival = Shared<BoxFuture<'static, Option<Duration>>>;
+
 /// Builds an `AttestationService`.
 #[derive(Default)]
 pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
@@ -90,7 +97,8 @@ pub struct Inner<T, E: EthSpec> {
     context: RuntimeContext<E>,
 }
 
-/// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
+/// Attempts to produce attestations for all known validators in each slot, at the time given by
+/// their `AttestationTiming` (by default, 1/3rd of the way through the slot).
 ///
 /// If any validators are on the same committee, a single attestation will be downloaded and
 /// returned to the beacon node. This attestation will have a signature from each of the
@@ -137,7 +145,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         let interval_fut = async move {
             loop {
                 if let Some(duration_to_next_slot) = self.slot_clock.duration_to_next_slot() {
-                    sleep(duration_to_next_slot + slot_duration / 3).await;
+                    // Wake at the start of the slot, each attestation task then waits until its
+                    // validators are due to attest.
+                    sleep(duration_to_next_slot).await;
                     let log = self.context.log();
 
                     if let Err(e) = self.spawn_attestation_tasks(slot_duration) {
@@ -174,6 +184,10 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .duration_to_next_slot()
             .ok_or("Unable to determine duration to next slot")?;
 
+        let slot_start = Instant::now()
+            .checked_sub(slot_duration.saturating_sub(duration_to_next_slot))
+            .ok_or("Unable to determine the start of the slot")?;
+
         // If a validator needs to publish an aggregate attestation, they must do so at 2/3
         // through the slot. This delay triggers at this time
         let aggregate_production_instant = Instant::now()
@@ -181,35 +195,61 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 .checked_sub(slot_duration / 3)
                 .unwrap_or_else(|| Duration::from_secs(0));
 
-        let duties_by_committee_index: HashMap<CommitteeIndex, Vec<DutyAndProof>> = self
-            .duties_service
-            .attesters(slot)
-            .into_iter()
-            .fold(HashMap::new(), |mut map, duty_and_proof| {
-                map.entry(duty_and_proof.duty.committee_index)
+        // Validators in the same committee share an `Attestation` if they attest at the same time.
+        let duties_by_committee_and_timing: HashMap<
+            (CommitteeIndex, AttestationTiming),
+            Vec<DutyAndProof>,
+        > = self.duties_service.attesters(slot).into_iter().fold(
+            HashMap::new(),
+            |mut map, duty_and_proof| {
+                let timing = self
+                    .validator_store
+                    .attestation_timing(&duty_and_proof.duty.pubkey);
+                map.entry((duty_and_proof.duty.committee_index, timing))
                     .or_default()
                     .push(duty_and_proof);
                 map
-            });
+            },
+        );
+
+        // The block is only waited for (once, for all committees) if some validator attests on its
+        // arrival.
+        let block_arrival = self
+            .clone()
+            .wait_for_block(slot, slot_start)
+            .boxed()
+            .shared();
 
-        // For each committee index for this slot:
+        // For each committee index and timing for this slot:
         //
+        // - Wait until the validators are due to attest.
         // - Create and publish an `Attestation` for all required validators.
         // - Create and publish `SignedAggregateAndProof` for all aggregating validators.
-        duties_by_committee_index
-            .into_iter()
-            .for_each(|(committee_index, validator_duties)| {
+        duties_by_committee_and_timing.into_iter().for_each(
+            |((committee_index, timing), validator_duties)| {
+                let service = self.clone();
+                let block_arrival = block_arrival.clone();
                 // Spawn a separate task for each attestation.
                 self.inner.context.executor.spawn_ignoring_error(
-                    self.clone().publish_attestations_and_aggregates(
-                        slot,
-                        committee_index,
-                        validator_duties,
-                        aggregate_production_instant,
-                    ),
+                    async move {
+                        let block_arrival = service
+                            .wait_to_attest(timing, slot_start, slot_duration, block_arrival)
+                            .await;
+                        service
+                            .publish_attestations_and_aggregates(
+                                slot,
+                                committee_index,
+                                timing,
+                                block_arrival,
+                                validator_duties,
+                                aggregate_production_instant,
+                            )
+                            .await
+                    },
                     "attestation publish",
                 );
-            });
+            },
+        );
 
         // Schedule pruning of the slashing protection database once all unaggregated
         // attestations have (hopefully) been signed, i.e. at the same time as aggregate
@@ -219,6 +259,94 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         Ok(())
     }
 
+    /// Waits until the validators with the given `timing` are due to attest, returning the time
+    /// into the slot at which the block for the slot arrived, if it was waited for.
+    async fn wait_to_attest(
+        &self,
+        timing: AttestationTiming,
+        slot_start: Instant,
+        slot_duration: Duration,
+        block_arrival: BlockArrival,
+    ) -> Option<Duration> {
+        let deadline = slot_start + timing.deadline(slot_duration);
+        match timing {
+            AttestationTiming::Fixed(_) => {
+                sleep_until(deadline).await;
+                None
+            }
+            AttestationTiming::Block(_) => match timeout_at(deadline, block_arrival).await {
+                Ok(Some(arrival)) => Some(arrival),
+                // The block could not be waited for, fall back to attesting at the deadline.
+                Ok(None) => {
+                    sleep_until(deadline).await;
+                    None
+                }
+                Err(_) => {
+                    validator_metrics::inc_counter(
+                        &validator_metrics::ATTESTATION_BLOCK_WAIT_TIMEOUTS_TOTAL,
+                    );
+                    None
+                }
+            },
+        }
+    }
+
+    /// Waits for the beacon node to import a block at `slot`, returning the time into the slot at
+    /// which it was observed.
+    async fn wait_for_block(self, slot: Slot, slot_start: Instant) -> Option<Duration> {
+        let log = self.context.log();
+        let result = self
+            .beacon_nodes
+            .first_success(|beacon_node| async move {
+                // Subscribe before checking the head, so that a block imported in between is not
+                // missed.
+                let mut events = beacon_node
+                    .get_events::<E>(&[EventTopic::Head])
+                    .await
+                    .map_err(|e| format!("Failed to subscribe to head events: {:?}", e))?;
+
+                let head_slot = beacon_node
+                    .get_beacon_headers_block_id(BlockId::Head)
+                    .await
+                    .map_err(|e| format!("Failed to get head: {:?}", e))?
+                    .map(|head| head.data.header.message.slot);
+                if head_slot.is_some_and(|head_slot| head_slot >= slot) {
+                    return Ok(());
+                }
+
+                while let Some(event) = events.next().await {
+                    match event {
+                        Ok(EventKind::Head(head)) if head.slot >= slot => return Ok(()),
+                        Ok(_) => {}
+                        Err(e) => return Err(format!("Head event stream failed: {:?}", e)),
+                    }
+                }
+                Err("Head event stream ended".to_string())
+            })
+            .await;
+
+        match result {
+            Ok(()) => {
+                let arrival = slot_start.elapsed();
+                validator_metrics::observe_duration(
+                    &validator_metrics::ATTESTATION_BLOCK_ARRIVAL_DELAY,
+                    arrival,
+                );
+                Some(arrival)
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to wait for block before attesting";
+                    "info" => "attesting at the deadline instead",
+                    "error" => %e,
+                    "slot" => slot,
+                );
+                None
+            }
+        }
+    }
+
     /// Performs the first step of the attesting process: downloading `Attestation` objects,
     /// signing them and returning them to the validator.
     ///
@@ -228,10 +356,13 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
     ///
     /// The given `validator_duties` should already be filtered to only contain those that match
     /// `slot` and `committee_index`. Critical errors will be logged if this is not the case.
+    #[allow(clippy::too_many_arguments)]
     async fn publish_attestations_and_aggregates(
         self,
         slot: Slot,
         committee_index: CommitteeIndex,
+        timing: AttestationTiming,
+        block_arrival: Option<Duration>,
         validator_duties: Vec<DutyAndProof>,
         aggregate_production_instant: Instant,
     ) -> Result<(), ()> {
@@ -251,7 +382,13 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         //
         // Download, sign and publish an `Attestation` for each validator.
         let attestation_opt = self
-            .produce_and_publish_attestations(slot, committee_index, &validator_duties)
+            .produce_and_publish_attestations(
+                slot,
+                committee_index,
+                timing,
+                block_arrival,
+                &validator_duties,
+            )
             .await
             .map_err(move |e| {
                 crit!(
@@ -320,6 +457,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         &self,
         slot: Slot,
         committee_index: CommitteeIndex,
+        timing: AttestationTiming,
+        block_arrival: Option<Duration>,
         validator_duties: &[DutyAndProof],
     ) -> Result<Option<AttestationData>, String> {
         let log = self.context.log();
@@ -350,6 +489,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .await
             .map_err(|e| e.to_string())?;
 
+        // Every validator signs the same data, so compute its signing root once up front.
+        let signing_root = self
+            .validator_store
+            .attestation_signing_root(&attestation_data);
+
         // Create futures to produce signed `Attestation` objects.
         let attestation_data_ref = &attestation_data;
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
@@ -392,16 +536,30 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 }
             };
 
-            match self
-                .validator_store
-                .sign_attestation(
-                    duty.pubkey,
-                    duty.validator_committee_index as usize,
-                    &mut attestation,
-                    current_epoch,
-                )
-                .await
-            {
+            // The signing root is only valid if the attestation contains exactly the data which
+            // was downloaded.
+            let signing_result = if attestation.data() == attestation_data {
+                self.validator_store
+                    .sign_attestation_with_signing_root(
+                        duty.pubkey,
+                        duty.validator_committee_index as usize,
+                        &mut attestation,
+                        current_epoch,
+                        signing_root,
+                    )
+                    .await
+            } else {
+                self.validator_store
+                    .sign_attestation(
+                        duty.pubkey,
+                        duty.validator_committee_index as usize,
+                        &mut attestation,
+                        current_epoch,
+                    )
+                    .await
+            };
+
+            match signing_result {
                 Ok(()) => Some((attestation, duty.validator_index)),
                 Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
                     // A pubkey can be missing when a validator was recently
@@ -468,16 +626,31 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             })
             .await
         {
-            Ok(()) => info!(
-                log,
-                "Successfully published attestations";
-                "count" => attestations.len(),
-                "validator_indices" => ?validator_indices,
-                "head_block" => ?attestation_data.beacon_block_root,
-                "committee_index" => attestation_data.index,
-                "slot" => attestation_data.slot.as_u64(),
-                "type" => "unaggregated",
-            ),
+            Ok(()) => {
+                info!(
+                    log,
+                    "Successfully published attestations";
+                    "count" => attestations.len(),
+                    "validator_indices" => ?validator_indices,
+                    "head_block" => ?attestation_data.beacon_block_root,
+                    "committee_index" => attestation_data.index,
+                    "slot" => attestation_data.slot.as_u64(),
+                    "type" => "unaggregated",
+                );
+                if let Some(delay) = self.slot_clock.seconds_from_current_slot_start() {
+                    validator_metrics::observe_timer_vec(
+                        &validator_metrics::ATTESTATION_PUBLISH_DELAY,
+                        &[timing.name()],
+                        delay,
+                    );
+                    if let Some(block_arrival) = block_arrival {
+                        validator_metrics::observe_duration(
+                            &validator_metrics::ATTESTATION_DELAY_AFTER_BLOCK,
+                            delay.saturating_sub(block_arrival),
+                        );
+                    }
+                }
+            }
             Err(e) => error!(
                 log,
                 "Unable to publish attestations";
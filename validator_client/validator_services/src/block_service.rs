@@ -1,4 +1,6 @@
-use beacon_node_fallback::{ApiTopic, BeaconNodeFallback, Error as FallbackError, Errors};
+use beacon_node_fallback::{
+    ApiTopic, BeaconNodeFallback, BeaconNodePreference, Error as FallbackError, Errors,
+};
 use bls::SignatureBytes;
 use environment::RuntimeContext;
 use eth2::types::{FullBlockContents, PublishBlockRequest};
@@ -14,7 +16,7 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 use types::{
     BlindedBeaconBlock, BlockType, EthSpec, Graffiti, PublicKeyBytes, SignedBlindedBeaconBlock,
-    Slot,
+    Slot, Uint256,
 };
 use validator_store::{Error as ValidatorStoreError, ValidatorStore};
 
@@ -126,10 +128,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
 }
 
 // Combines a set of non-block-proposing `beacon_nodes` and only-block-proposing
-// `proposer_nodes`.
+// `proposer_nodes`, trying the proposer's preferred nodes first within each set.
 pub struct ProposerFallback<T, E: EthSpec> {
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     proposer_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
+    preference: BeaconNodePreference,
 }
 
 impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
@@ -143,7 +146,7 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         // If there are proposer nodes, try calling `func` on them and return early if they are successful.
         if let Some(proposer_nodes) = &self.proposer_nodes {
             if proposer_nodes
-                .request(ApiTopic::Blocks, func.clone())
+                .request_preferring(ApiTopic::Blocks, &self.preference, func.clone())
                 .await
                 .is_ok()
             {
@@ -152,7 +155,9 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         }
 
         // If the proposer nodes failed, try on the non-proposer nodes.
-        self.beacon_nodes.request(ApiTopic::Blocks, func).await
+        self.beacon_nodes
+            .request_preferring(ApiTopic::Blocks, &self.preference, func)
+            .await
     }
 
     // Try `func` on `self.beacon_nodes` first. If that doesn't work, try `self.proposer_nodes`.
@@ -163,7 +168,10 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         Err: Debug,
     {
         // Try running `func` on the non-proposer beacon nodes.
-        let beacon_nodes_result = self.beacon_nodes.first_success(func.clone()).await;
+        let beacon_nodes_result = self
+            .beacon_nodes
+            .first_success_preferring(&self.preference, func.clone())
+            .await;
 
         match (beacon_nodes_result, &self.proposer_nodes) {
             // The non-proposer node call succeed, return the result.
@@ -171,7 +179,11 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
             // The non-proposer node call failed, but we don't have any proposer nodes. Return an error.
             (Err(e), None) => Err(e),
             // The non-proposer node call failed, try the same call on the proposer nodes.
-            (Err(_), Some(proposer_nodes)) => proposer_nodes.first_success(func).await,
+            (Err(_), Some(proposer_nodes)) => {
+                proposer_nodes
+                    .first_success_preferring(&self.preference, func)
+                    .await
+            }
         }
     }
 }
@@ -459,19 +471,97 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let proposer_fallback = ProposerFallback {
             beacon_nodes: self.beacon_nodes.clone(),
             proposer_nodes: self.proposer_nodes.clone(),
+            preference: BeaconNodePreference::new(
+                &self.validator_store.beacon_nodes(&validator_pubkey),
+            ),
+        };
+
+        // Validators which always prefer builder blocks are exempt from the builder policy, and
+        // validators which never use the builder have no bids to check.
+        let apply_builder_policy = self.validator_store.builder_policy().is_enabled()
+            && !matches!(builder_boost_factor, Some(0) | Some(u64::MAX));
+
+        let (unsigned_block, payload_value, local_block) = if apply_builder_policy {
+            // Request a locally built block alongside the usual block, so that a builder bid can
+            // be checked against the policy without waiting for another block to be produced.
+            // A boost factor of zero instructs the beacon node to build the block locally.
+            let (block, local_block) = tokio::join!(
+                self.request_block(
+                    &proposer_fallback,
+                    slot,
+                    randao_reveal_ref,
+                    graffiti,
+                    proposer_index,
+                    builder_boost_factor,
+                ),
+                self.request_block(
+                    &proposer_fallback,
+                    slot,
+                    randao_reveal_ref,
+                    graffiti,
+                    proposer_index,
+                    Some(0),
+                ),
+            );
+            let (unsigned_block, payload_value) = block?;
+            (unsigned_block, payload_value, Some(local_block))
+        } else {
+            let (unsigned_block, payload_value) = self
+                .request_block(
+                    &proposer_fallback,
+                    slot,
+                    randao_reveal_ref,
+                    graffiti,
+                    proposer_index,
+                    builder_boost_factor,
+                )
+                .await?;
+            (unsigned_block, payload_value, None)
         };
 
+        let unsigned_block = match (unsigned_block, local_block) {
+            (unsigned_block @ UnsignedBlock::Blinded(_), Some(local_block)) => {
+                self.apply_builder_policy(slot, unsigned_block, payload_value, local_block)
+            }
+            (unsigned_block, _) => unsigned_block,
+        };
+
+        self_ref
+            .sign_and_publish_block(
+                proposer_fallback,
+                slot,
+                graffiti,
+                &validator_pubkey,
+                unsigned_block,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    /// Request an unsigned block from the first responsive beacon node, returning the block and
+    /// the value of its execution payload.
+    ///
+    /// Try the proposer nodes last, since it's likely that they don't have a great view of
+    /// attestations on the network.
+    async fn request_block(
+        &self,
+        proposer_fallback: &ProposerFallback<T, E>,
+        slot: Slot,
+        randao_reveal: &SignatureBytes,
+        graffiti: Option<Graffiti>,
+        proposer_index: Option<u64>,
+        builder_boost_factor: Option<u64>,
+    ) -> Result<(UnsignedBlock<E>, Uint256), BlockError> {
+        let log = self.context.log();
+
         info!(
             log,
             "Requesting unsigned block";
             "slot" => slot.as_u64(),
         );
 
-        // Request block from first responsive beacon node.
-        //
-        // Try the proposer nodes last, since it's likely that they don't have a
-        // great view of attestations on the network.
-        let unsigned_block = proposer_fallback
+        let block = proposer_fallback
             .request_proposers_last(|beacon_node| async move {
                 let _get_timer = validator_metrics::start_timer_vec(
                     &validator_metrics::BLOCK_SERVICE_TIMES,
@@ -480,7 +570,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 Self::get_validator_block(
                     &beacon_node,
                     slot,
-                    randao_reveal_ref,
+                    randao_reveal,
                     graffiti,
                     proposer_index,
                     builder_boost_factor,
@@ -496,17 +586,76 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             })
             .await?;
 
-        self_ref
-            .sign_and_publish_block(
-                proposer_fallback,
-                slot,
-                graffiti,
-                &validator_pubkey,
-                unsigned_block,
-            )
-            .await?;
+        Ok(block)
+    }
 
-        Ok(())
+    /// Check the builder block against the builder policy, replacing it with the locally built
+    /// block if the bid is refused.
+    ///
+    /// If a local block couldn't be obtained the builder block is used, since a missed proposal is
+    /// worse than a low bid.
+    fn apply_builder_policy(
+        &self,
+        slot: Slot,
+        builder_block: UnsignedBlock<E>,
+        builder_value: Uint256,
+        local_block: Result<(UnsignedBlock<E>, Uint256), BlockError>,
+    ) -> UnsignedBlock<E> {
+        let log = self.context.log();
+        let policy = self.validator_store.builder_policy();
+
+        let local_block = match local_block {
+            Ok((block @ UnsignedBlock::Full(_), value)) => Some((block, value)),
+            Ok((UnsignedBlock::Blinded(_), _)) => {
+                warn!(
+                    log,
+                    "Beacon node returned a builder block instead of a local block";
+                    "slot" => slot.as_u64(),
+                );
+                None
+            }
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to produce a local block for comparison";
+                    "error" => ?e,
+                    "slot" => slot.as_u64(),
+                );
+                None
+            }
+        };
+
+        let local_value = local_block.as_ref().map(|(_, value)| *value);
+        info!(
+            log,
+            "Checking builder bid";
+            "slot" => slot.as_u64(),
+            "builder_value_wei" => %builder_value,
+            "local_value_wei" => local_value.map_or_else(|| "unknown".to_string(), |v| v.to_string()),
+        );
+
+        match (policy.check_bid(builder_value, local_value), local_block) {
+            (Ok(()), _) => builder_block,
+            (Err(rejection), Some((local_block, _))) => {
+                info!(
+                    log,
+                    "Refused builder bid, proposing local block";
+                    "reason" => %rejection,
+                    "slot" => slot.as_u64(),
+                );
+                local_block
+            }
+            (Err(rejection), None) => {
+                warn!(
+                    log,
+                    "Proposing refused builder bid";
+                    "reason" => %rejection,
+                    "info" => "no local block is available",
+                    "slot" => slot.as_u64(),
+                );
+                builder_block
+            }
+        }
     }
 
     async fn publish_signed_block_contents(
@@ -549,8 +698,8 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         proposer_index: Option<u64>,
         builder_boost_factor: Option<u64>,
         log: &Logger,
-    ) -> Result<UnsignedBlock<E>, BlockError> {
-        let (block_response, _) = beacon_node
+    ) -> Result<(UnsignedBlock<E>, Uint256), BlockError> {
+        let (block_response, metadata) = beacon_node
             .get_validator_blocks_v3::<E>(
                 slot,
                 randao_reveal_ref,
@@ -581,7 +730,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             ));
         }
 
-        Ok::<_, BlockError>(unsigned_block)
+        Ok::<_, BlockError>((unsigned_block, metadata.execution_payload_value))
     }
 
     /// Returns the builder boost factor of the given public key.
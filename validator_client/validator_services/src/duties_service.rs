@@ -1,7 +1,9 @@
 //! The `DutiesService` contains the attester/proposer duties for all local validators.
 //!
 //! It learns of the local validator via the `crate::ValidatorStore` struct. It keeps the duties
-//! up-to-date by polling the beacon node on regular intervals.
+//! up-to-date by polling the beacon node on regular intervals, and by following the head of the
+//! beacon node so that attester duties are switched as soon as a re-org changes their dependent
+//! root.
 //!
 //! The `DutiesService` is also responsible for sending events to the `BlockService` which trigger
 //! block production.
@@ -13,7 +15,8 @@ use beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
 use doppelganger_service::DoppelgangerStatus;
 use environment::RuntimeContext;
 use eth2::types::{
-    AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
+    AttesterData, BeaconCommitteeSubscription, DutiesResponse, EventKind, EventTopic, ProposerData,
+    StateId, ValidatorId,
 };
 use futures::{stream, StreamExt};
 use parking_lot::RwLock;
@@ -54,6 +57,16 @@ const VALIDATOR_METRICS_MIN_COUNT: usize = 64;
 /// reduces the amount of data that needs to be transferred.
 const INITIAL_DUTIES_QUERY_SIZE: usize = 1;
 
+/// The number of dependent roots for which superseded attester duties are retained per epoch.
+///
+/// When the head oscillates between forks with different dependent roots, the duties for each
+/// fork are restored from memory rather than downloaded again.
+const MAX_ALTERNATE_DEPENDENT_ROOTS: usize = 2;
+
+/// The maximum number of slots to wait before retrying a failed attester duties download for an
+/// epoch whose duties are already known.
+const MAX_DUTIES_RETRY_BACKOFF_SLOTS: u64 = 8;
+
 /// Offsets from the attestation duty slot at which a subscription should be sent.
 const ATTESTATION_SUBSCRIPTION_OFFSETS: [u64; 8] = [3, 4, 5, 6, 7, 8, 16, 32];
 
@@ -204,6 +217,31 @@ type DependentRoot = Hash256;
 
 type AttesterMap = HashMap<PublicKeyBytes, HashMap<Epoch, (DependentRoot, DutyAndProof)>>;
 type ProposerMap = HashMap<Epoch, (DependentRoot, Vec<ProposerData>)>;
+type AlternateAttesterMap =
+    HashMap<Epoch, Vec<(DependentRoot, HashMap<PublicKeyBytes, DutyAndProof>)>>;
+
+/// Tracks consecutive failures to download the attester duties for an epoch, so that retries back
+/// off exponentially rather than hitting the beacon node every slot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryBackoff {
+    failures: u32,
+    next_attempt: Slot,
+}
+
+impl RetryBackoff {
+    /// Returns the backoff after another failure at `current_slot`.
+    fn failed(previous: Option<Self>, current_slot: Slot) -> Self {
+        let failures = previous.map_or(0, |backoff| backoff.failures) + 1;
+        let delay = 1_u64
+            .checked_shl(failures - 1)
+            .unwrap_or(u64::MAX)
+            .min(MAX_DUTIES_RETRY_BACKOFF_SLOTS);
+        Self {
+            failures,
+            next_attempt: current_slot + delay,
+        }
+    }
+}
 
 /// See the module-level documentation.
 pub struct DutiesService<T, E: EthSpec> {
@@ -212,6 +250,11 @@ pub struct DutiesService<T, E: EthSpec> {
     /// Maps an epoch to all *local* proposers in this epoch. Notably, this does not contain
     /// proposals for any validators which are not registered locally.
     pub proposers: RwLock<ProposerMap>,
+    /// Attester duties which were superseded by a re-org, retained so that they can be restored
+    /// without a download if the chain re-orgs back to their dependent root.
+    pub alternate_attesters: RwLock<AlternateAttesterMap>,
+    /// Backoff for epochs whose attester duties failed to download.
+    pub attester_retry_backoff: RwLock<HashMap<Epoch, RetryBackoff>>,
     /// Map from validator index to sync committee duties.
     pub sync_duties: SyncDutiesMap<E>,
     /// Provides the canonical list of locally-managed validators.
@@ -432,6 +475,34 @@ pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
         "duties_service_attesters",
     );
 
+    /*
+     * Spawn the task which switches attester duties as soon as the head changes their dependent
+     * root.
+     */
+    let duties_service = core_duties_service.clone();
+    let log = core_duties_service.context.log().clone();
+    core_duties_service.context.executor.spawn(
+        async move {
+            loop {
+                if let Err(e) = follow_head_dependent_roots(&duties_service).await {
+                    debug!(
+                        log,
+                        "Unable to follow head for attester duties";
+                        "error" => %e
+                    );
+                }
+
+                // Wait until the next slot before subscribing again.
+                if let Some(duration) = duties_service.slot_clock.duration_to_next_slot() {
+                    sleep(duration).await;
+                } else {
+                    sleep(duties_service.slot_clock.slot_duration()).await;
+                }
+            }
+        },
+        "duties_service_head_events",
+    );
+
     // Spawn the task which keeps track of local sync committee duties.
     let duties_service = core_duties_service.clone();
     let log = core_duties_service.context.log().clone();
@@ -616,44 +687,17 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
     let current_epoch = current_slot.epoch(E::slots_per_epoch());
     let next_epoch = current_epoch + 1;
 
-    // Collect *all* pubkeys, even those undergoing doppelganger protection.
-    //
-    // We must know the duties for doppelganger validators so that we can subscribe to their subnets
-    // and get more information about other running instances.
-    let local_pubkeys: HashSet<_> = duties_service
-        .validator_store
-        .voting_pubkeys(DoppelgangerStatus::ignored);
-
-    let local_indices = {
-        let mut local_indices = Vec::with_capacity(local_pubkeys.len());
-
-        let vals_ref = duties_service.validator_store.initialized_validators();
-        let vals = vals_ref.read();
-        for &pubkey in &local_pubkeys {
-            if let Some(validator_index) = vals.get_index(&pubkey) {
-                local_indices.push(validator_index)
-            }
-        }
-        local_indices
-    };
+    let (local_pubkeys, local_indices) = local_attesters(duties_service);
 
     // Download the duties and update the duties for the current epoch.
-    if let Err(e) = poll_beacon_attesters_for_epoch(
+    poll_beacon_attesters_with_backoff(
         duties_service,
         current_epoch,
+        current_slot,
         &local_indices,
         &local_pubkeys,
     )
-    .await
-    {
-        error!(
-            log,
-            "Failed to download attester duties";
-            "current_epoch" => current_epoch,
-            "request_epoch" => current_epoch,
-            "err" => ?e,
-        )
-    }
+    .await;
 
     update_per_validator_duty_metrics::<T, E>(duties_service, current_epoch, current_slot);
 
@@ -664,18 +708,14 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
     );
 
     // Download the duties and update the duties for the next epoch.
-    if let Err(e) =
-        poll_beacon_attesters_for_epoch(duties_service, next_epoch, &local_indices, &local_pubkeys)
-            .await
-    {
-        error!(
-            log,
-            "Failed to download attester duties";
-            "current_epoch" => current_epoch,
-            "request_epoch" => next_epoch,
-            "err" => ?e,
-        )
-    }
+    poll_beacon_attesters_with_backoff(
+        duties_service,
+        next_epoch,
+        current_slot,
+        &local_indices,
+        &local_pubkeys,
+    )
+    .await;
 
     update_per_validator_duty_metrics::<T, E>(duties_service, next_epoch, current_slot);
 
@@ -791,10 +831,299 @@ async fn poll_beacon_attesters<T: SlotClock + 'static, E: EthSpec>(
         .for_each(|(_, map)| {
             map.retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch)
         });
+    duties_service
+        .alternate_attesters
+        .write()
+        .retain(|&epoch, _| epoch + HISTORICAL_DUTIES_EPOCHS >= current_epoch);
+    duties_service
+        .attester_retry_backoff
+        .write()
+        .retain(|&epoch, _| epoch >= current_epoch);
 
     Ok(())
 }
 
+/// Returns the pubkeys of *all* local validators, even those undergoing doppelganger protection,
+/// along with the indices of those which are known.
+///
+/// We must know the duties for doppelganger validators so that we can subscribe to their subnets
+/// and get more information about other running instances.
+fn local_attesters<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+) -> (HashSet<PublicKeyBytes>, Vec<u64>) {
+    let local_pubkeys: HashSet<_> = duties_service
+        .validator_store
+        .voting_pubkeys(DoppelgangerStatus::ignored);
+
+    let local_indices = {
+        let mut local_indices = Vec::with_capacity(local_pubkeys.len());
+
+        let vals_ref = duties_service.validator_store.initialized_validators();
+        let vals = vals_ref.read();
+        for &pubkey in &local_pubkeys {
+            if let Some(validator_index) = vals.get_index(&pubkey) {
+                local_indices.push(validator_index)
+            }
+        }
+        local_indices
+    };
+
+    (local_pubkeys, local_indices)
+}
+
+/// Follow the head of the beacon node, bringing the attester duties for the head's epoch and the
+/// next epoch in line with the dependent roots of each new head.
+///
+/// Near an epoch boundary the head may oscillate between a late block and its parent, which give
+/// the next epoch two plausible dependent roots. The first time the head switches to either root
+/// its duties are downloaded straight away, rather than at the next poll, and the duties they
+/// replace are retained. From then on the head switching between the two forks only swaps the
+/// retained duties back in, without any further requests to the beacon node.
+async fn follow_head_dependent_roots<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &Arc<DutiesService<T, E>>,
+) -> Result<(), String> {
+    duties_service
+        .beacon_nodes
+        .first_success(|beacon_node| async move {
+            let mut events = beacon_node
+                .get_events::<E>(&[EventTopic::Head])
+                .await
+                .map_err(|e| format!("Failed to subscribe to head events: {:?}", e))?;
+
+            while let Some(event) = events.next().await {
+                match event {
+                    Ok(EventKind::Head(head)) => {
+                        let head_epoch = head.slot.epoch(E::slots_per_epoch());
+                        // The duties for the head's epoch depend on the block prior to the
+                        // previous epoch, and those for the next epoch on the block prior to the
+                        // head's epoch.
+                        for (epoch, dependent_root) in [
+                            (head_epoch, head.previous_duty_dependent_root),
+                            (head_epoch + 1, head.current_duty_dependent_root),
+                        ] {
+                            update_attesters_for_dependent_root(
+                                duties_service,
+                                epoch,
+                                dependent_root,
+                            )
+                            .await;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => return Err(format!("Head event stream failed: {:?}", e)),
+                }
+            }
+
+            Err("Head event stream ended".to_string())
+        })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Switch the attester duties for `epoch` to those for `dependent_root`, restoring them from the
+/// retained duties if they were seen before and downloading them otherwise.
+async fn update_attesters_for_dependent_root<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &Arc<DutiesService<T, E>>,
+    epoch: Epoch,
+    dependent_root: DependentRoot,
+) {
+    let log = duties_service.context.log();
+
+    let Some(current_slot) = duties_service.slot_clock.now() else {
+        return;
+    };
+    let (local_pubkeys, local_indices) = local_attesters(duties_service);
+
+    let stale_roots = |duties_service: &DutiesService<T, E>| {
+        let mut roots =
+            attester_dependent_roots(&duties_service.attesters.read(), epoch, &local_pubkeys);
+        roots.remove(&dependent_root);
+        roots
+    };
+
+    // Duties which haven't been downloaded yet are left to the regular poll.
+    if stale_roots(duties_service).is_empty() {
+        return;
+    }
+
+    let num_restored = restore_alternate_attesters(duties_service, epoch, dependent_root);
+    if num_restored > 0 {
+        debug!(
+            log,
+            "Restored attester duties for new head";
+            "dependent_root" => %dependent_root,
+            "epoch" => epoch,
+            "num_restored" => num_restored,
+        );
+        if stale_roots(duties_service).is_empty() {
+            return;
+        }
+    }
+
+    debug!(
+        log,
+        "Downloading attester duties for new head";
+        "dependent_root" => %dependent_root,
+        "epoch" => epoch,
+    );
+    poll_beacon_attesters_with_backoff(
+        duties_service,
+        epoch,
+        current_slot,
+        &local_indices,
+        &local_pubkeys,
+    )
+    .await;
+}
+
+/// Returns the dependent roots of the duties held for `local_pubkeys` in `epoch`.
+fn attester_dependent_roots(
+    attesters: &AttesterMap,
+    epoch: Epoch,
+    local_pubkeys: &HashSet<PublicKeyBytes>,
+) -> HashSet<DependentRoot> {
+    local_pubkeys
+        .iter()
+        .filter_map(|pubkey| attesters.get(pubkey)?.get(&epoch))
+        .map(|(dependent_root, _)| *dependent_root)
+        .collect()
+}
+
+/// Download the attester duties for `epoch` by calling `poll_beacon_attesters_for_epoch`, unless a
+/// previous download for the epoch failed recently and the duties of all local validators are
+/// already known.
+///
+/// Consecutive failures double the number of slots before the next attempt, up to
+/// `MAX_DUTIES_RETRY_BACKOFF_SLOTS`. Validators without duties for the epoch are always retried.
+async fn poll_beacon_attesters_with_backoff<T: SlotClock + 'static, E: EthSpec>(
+    duties_service: &Arc<DutiesService<T, E>>,
+    epoch: Epoch,
+    current_slot: Slot,
+    local_indices: &[u64],
+    local_pubkeys: &HashSet<PublicKeyBytes>,
+) {
+    let log = duties_service.context.log();
+
+    let backoff = duties_service
+        .attester_retry_backoff
+        .read()
+        .get(&epoch)
+        .copied();
+    if let Some(backoff) = backoff {
+        if current_slot < backoff.next_attempt
+            && get_uninitialized_validators(duties_service, &epoch, local_pubkeys).is_empty()
+        {
+            debug!(
+                log,
+                "Delaying attester duties download";
+                "request_epoch" => epoch,
+                "failures" => backoff.failures,
+                "next_attempt" => backoff.next_attempt,
+            );
+            return;
+        }
+    }
+
+    match poll_beacon_attesters_for_epoch(duties_service, epoch, local_indices, local_pubkeys).await
+    {
+        Ok(()) => {
+            if backoff.is_some() {
+                duties_service.attester_retry_backoff.write().remove(&epoch);
+            }
+        }
+        Err(e) => {
+            let backoff = RetryBackoff::failed(backoff, current_slot);
+            duties_service
+                .attester_retry_backoff
+                .write()
+                .insert(epoch, backoff);
+            error!(
+                log,
+                "Failed to download attester duties";
+                "current_epoch" => current_slot.epoch(E::slots_per_epoch()),
+                "request_epoch" => epoch,
+                "next_attempt" => backoff.next_attempt,
+                "err" => ?e,
+            )
+        }
+    }
+}
+
+/// Restore the duties for `epoch` which were computed for `dependent_root` and superseded by a
+/// re-org, returning the number of validators whose duties were restored.
+///
+/// The duties they replace are retained in turn, keyed by their own dependent root.
+fn restore_alternate_attesters<T, E: EthSpec>(
+    duties_service: &DutiesService<T, E>,
+    epoch: Epoch,
+    dependent_root: DependentRoot,
+) -> usize {
+    let mut attesters = duties_service.attesters.write();
+    let mut alternate_attesters = duties_service.alternate_attesters.write();
+
+    let Some(alternates) = alternate_attesters.get_mut(&epoch) else {
+        return 0;
+    };
+    let Some(position) = alternates
+        .iter()
+        .position(|(root, _)| *root == dependent_root)
+    else {
+        return 0;
+    };
+    let (_, restored) = alternates.remove(position);
+
+    let mut superseded: HashMap<DependentRoot, HashMap<PublicKeyBytes, DutyAndProof>> =
+        HashMap::new();
+    let mut num_restored = 0;
+    for (pubkey, duty_and_proof) in restored {
+        let attester_map = attesters.entry(pubkey).or_default();
+        match attester_map.entry(epoch) {
+            hash_map::Entry::Occupied(mut occupied) => {
+                if occupied.get().0 == dependent_root {
+                    continue;
+                }
+                let (prior_root, prior_duty) = occupied.insert((dependent_root, duty_and_proof));
+                superseded
+                    .entry(prior_root)
+                    .or_default()
+                    .insert(pubkey, prior_duty);
+            }
+            hash_map::Entry::Vacant(vacant) => {
+                vacant.insert((dependent_root, duty_and_proof));
+            }
+        }
+        num_restored += 1;
+    }
+
+    for (root, duties) in superseded {
+        retain_alternate_attesters(&mut alternate_attesters, epoch, root, duties);
+    }
+
+    num_restored
+}
+
+/// Retain `duties` which have been superseded by a re-org, so that they may be restored if the
+/// chain re-orgs back to `dependent_root`.
+fn retain_alternate_attesters(
+    alternate_attesters: &mut AlternateAttesterMap,
+    epoch: Epoch,
+    dependent_root: DependentRoot,
+    duties: HashMap<PublicKeyBytes, DutyAndProof>,
+) {
+    let alternates = alternate_attesters.entry(epoch).or_default();
+    match alternates
+        .iter_mut()
+        .find(|(root, _)| *root == dependent_root)
+    {
+        Some((_, existing)) => existing.extend(duties),
+        None => alternates.push((dependent_root, duties)),
+    }
+    // Discard the least recently superseded duties.
+    while alternates.len() > MAX_ALTERNATE_DEPENDENT_ROOTS {
+        alternates.remove(0);
+    }
+}
+
 /// For the given `local_indices` and `local_pubkeys`, download the duties for the given `epoch` and
 /// store them in `duties_service.attesters`.
 async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
@@ -836,6 +1165,19 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
         post_validator_duties_attester(duties_service, epoch, initial_indices_to_request).await?;
     let dependent_root = response.dependent_root;
 
+    // If the chain has re-orged back to a dependent root we've seen before, restore the duties we
+    // downloaded for it rather than downloading them again.
+    let num_restored = restore_alternate_attesters(duties_service, epoch, dependent_root);
+    if num_restored > 0 {
+        debug!(
+            log,
+            "Restored attester duties after re-org";
+            "dependent_root" => %dependent_root,
+            "epoch" => epoch,
+            "num_restored" => num_restored,
+        );
+    }
+
     // Find any validators which have conflicting (epoch, dependent_root) values or missing duties for the epoch.
     let validators_to_update: Vec<_> = {
         // Avoid holding the read-lock for any longer than required.
@@ -897,6 +1239,7 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
 
     // Update the duties service with the new `DutyAndProof` messages.
     let mut attesters = duties_service.attesters.write();
+    let mut superseded = HashMap::new();
     let mut already_warned = Some(());
     let current_slot = duties_service
         .slot_clock
@@ -941,13 +1284,33 @@ async fn poll_beacon_attesters_for_epoch<T: SlotClock + 'static, E: EthSpec>(
                         "note" => "this may happen from time to time"
                     )
                 }
-                *mut_value = (dependent_root, duty_and_proof);
+                let (prior_dependent_root, prior_duty_and_proof) =
+                    std::mem::replace(mut_value, (dependent_root, duty_and_proof));
+                if prior_dependent_root != dependent_root {
+                    superseded
+                        .entry(prior_dependent_root)
+                        .or_insert_with(HashMap::new)
+                        .insert(duty.pubkey, prior_duty_and_proof);
+                }
             }
             hash_map::Entry::Vacant(vacant) => {
                 vacant.insert((dependent_root, duty_and_proof));
             }
         }
     }
+    // Retain the superseded duties in case the chain re-orgs back to them. Lock the alternates
+    // while still holding the attesters lock, consistent with `restore_alternate_attesters`.
+    if !superseded.is_empty() {
+        let mut alternate_attesters = duties_service.alternate_attesters.write();
+        for (prior_dependent_root, duties) in superseded {
+            retain_alternate_attesters(
+                &mut alternate_attesters,
+                epoch,
+                prior_dependent_root,
+                duties,
+            );
+        }
+    }
     drop(attesters);
 
     // Spawn the background task to compute selection proofs.
@@ -1391,6 +1754,7 @@ async fn notify_block_production_service<T: SlotClock + 'static, E: EthSpec>(
 #[cfg(test)]
 mod test {
     use super::*;
+    use types::FixedBytesExtended;
 
     #[test]
     fn subscription_slots_exact() {
@@ -1471,4 +1835,108 @@ mod test {
         assert_eq!(subscription_slots.slots.len(), 1);
         assert!(subscription_slots.should_send_subscription_at(current_slot + 1),);
     }
+
+    #[test]
+    fn retry_backoff_doubles_up_to_max() {
+        let current_slot = Slot::new(100);
+        let mut backoff = None;
+        for expected_delay in [1, 2, 4, 8, 8, 8] {
+            let next = RetryBackoff::failed(backoff, current_slot);
+            assert_eq!(next.next_attempt, current_slot + expected_delay);
+            backoff = Some(next);
+        }
+        assert_eq!(backoff.unwrap().failures, 6);
+    }
+
+    #[test]
+    fn alternate_attesters_retain_recent_roots() {
+        let epoch = Epoch::new(1);
+        let pubkey = PublicKeyBytes::empty();
+        let duties = |validator_index| {
+            let duty = AttesterData {
+                pubkey,
+                validator_index,
+                committees_at_slot: 1,
+                committee_index: 0,
+                committee_length: 1,
+                validator_committee_index: 0,
+                slot: Slot::new(32),
+            };
+            HashMap::from([(
+                pubkey,
+                DutyAndProof::new_without_selection_proof(duty, Slot::new(0)),
+            )])
+        };
+
+        let mut alternates = AlternateAttesterMap::new();
+        for root in 0..=MAX_ALTERNATE_DEPENDENT_ROOTS as u64 {
+            retain_alternate_attesters(
+                &mut alternates,
+                epoch,
+                Hash256::from_low_u64_le(root),
+                duties(root),
+            );
+        }
+
+        // The oldest root is discarded.
+        let roots = alternates[&epoch]
+            .iter()
+            .map(|(root, duties)| {
+                assert_eq!(duties[&pubkey].duty.validator_index, root.to_low_u64_le());
+                root.to_low_u64_le()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            roots,
+            (1..=MAX_ALTERNATE_DEPENDENT_ROOTS as u64).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn attester_dependent_roots_of_local_validators() {
+        let epoch = Epoch::new(1);
+        let duty_and_proof = |validator_index| {
+            let duty = AttesterData {
+                pubkey: PublicKeyBytes::empty(),
+                validator_index,
+                committees_at_slot: 1,
+                committee_index: 0,
+                committee_length: 1,
+                validator_committee_index: 0,
+                slot: Slot::new(32),
+            };
+            DutyAndProof::new_without_selection_proof(duty, Slot::new(0))
+        };
+        let pubkey = |byte| PublicKeyBytes::deserialize(&[byte; 48]).unwrap();
+        let root = Hash256::from_low_u64_le;
+
+        let mut attesters = AttesterMap::new();
+        attesters.insert(
+            pubkey(1),
+            HashMap::from([
+                (epoch, (root(1), duty_and_proof(1))),
+                (epoch + 1, (root(3), duty_and_proof(1))),
+            ]),
+        );
+        attesters.insert(
+            pubkey(2),
+            HashMap::from([(epoch, (root(2), duty_and_proof(2)))]),
+        );
+        attesters.insert(
+            pubkey(3),
+            HashMap::from([(epoch, (root(4), duty_and_proof(3)))]),
+        );
+
+        // Only the duties of local validators in the requested epoch are considered.
+        let local_pubkeys = HashSet::from([pubkey(1), pubkey(2), pubkey(4)]);
+        assert_eq!(
+            attester_dependent_roots(&attesters, epoch, &local_pubkeys),
+            HashSet::from([root(1), root(2)])
+        );
+        assert_eq!(
+            attester_dependent_roots(&attesters, epoch + 1, &local_pubkeys),
+            HashSet::from([root(3)])
+        );
+        assert!(attester_dependent_roots(&attesters, epoch + 2, &local_pubkeys).is_empty());
+    }
 }
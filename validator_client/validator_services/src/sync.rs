@@ -111,6 +111,33 @@ impl<E: EthSpec> SyncDutiesMap<E> {
         }
     }
 
+    /// Returns `true` if selection proofs for `committee_period` should be computed at
+    /// `current_slot`, i.e. if the period starts within the lookahead.
+    fn should_pre_compute(
+        &self,
+        committee_period: u64,
+        current_slot: Slot,
+        spec: &ChainSpec,
+    ) -> bool {
+        (current_slot + self.aggregation_pre_compute_slots())
+            .epoch(E::slots_per_epoch())
+            .sync_committee_period(spec)
+            .map_or(false, |period| period >= committee_period)
+    }
+
+    /// The slot up to which selection proofs for `committee_period` should be computed.
+    fn aggregation_pre_compute_slot(
+        &self,
+        committee_period: u64,
+        current_slot: Slot,
+        spec: &ChainSpec,
+    ) -> Slot {
+        std::cmp::min(
+            current_slot + self.aggregation_pre_compute_slots(),
+            last_slot_of_period::<E>(committee_period, spec),
+        )
+    }
+
     /// Prepare for pre-computation of selection proofs for `committee_period`.
     ///
     /// Return the slot up to which proofs should be pre-computed, as well as a vec of
@@ -126,11 +153,8 @@ impl<E: EthSpec> SyncDutiesMap<E> {
             current_slot,
             first_slot_of_period::<E>(committee_period, spec),
         );
-        let pre_compute_lookahead_slots = self.aggregation_pre_compute_slots();
-        let pre_compute_slot = std::cmp::min(
-            current_slot + pre_compute_lookahead_slots,
-            last_slot_of_period::<E>(committee_period, spec),
-        );
+        let pre_compute_slot =
+            self.aggregation_pre_compute_slot(committee_period, current_slot, spec);
 
         let pre_compute_duties = self.committees.read().get(&committee_period).map_or_else(
             Vec::new,
@@ -378,12 +402,7 @@ pub async fn poll_sync_committee_duties<T: SlotClock + 'static, E: EthSpec>(
     }
 
     // Pre-compute aggregator selection proofs for the next period.
-    let aggregate_pre_compute_lookahead_slots = sync_duties.aggregation_pre_compute_slots();
-    if (current_slot + aggregate_pre_compute_lookahead_slots)
-        .epoch(E::slots_per_epoch())
-        .sync_committee_period(spec)?
-        == next_sync_committee_period
-    {
+    if sync_duties.should_pre_compute(next_sync_committee_period, current_slot, spec) {
         let (pre_compute_slot, new_pre_compute_duties) = sync_duties
             .prepare_for_aggregator_pre_compute(next_sync_committee_period, current_slot, spec);
 
@@ -521,6 +540,18 @@ pub async fn fill_in_aggregation_proofs<T: SlotClock + 'static, E: EthSpec>(
 
     // Generate selection proofs for each validator at each slot, one slot at a time.
     for slot in (current_slot.as_u64()..=pre_compute_slot.as_u64()).map(Slot::new) {
+        // Proofs are already known at this slot for all validators.
+        if pre_compute_duties
+            .iter()
+            .all(|(validator_start_slot, _)| slot < *validator_start_slot)
+        {
+            continue;
+        }
+
+        let _timer = validator_metrics::start_timer_vec(
+            &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[validator_metrics::SYNC_SELECTION_PROOFS],
+        );
         let mut validator_proofs = vec![];
         for (validator_start_slot, duty) in pre_compute_duties {
             // Proofs are already known at this slot for this validator.
@@ -647,3 +678,82 @@ pub async fn fill_in_aggregation_proofs<T: SlotClock + 'static, E: EthSpec>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn spec() -> ChainSpec {
+        ChainSpec::mainnet()
+    }
+
+    fn slots_per_period(spec: &ChainSpec) -> u64 {
+        spec.epochs_per_sync_committee_period.as_u64() * E::slots_per_epoch()
+    }
+
+    #[test]
+    fn pre_compute_lookahead_is_bounded() {
+        let spec = spec();
+        let duties = SyncDutiesMap::<E>::new(false);
+        let lookahead = E::slots_per_epoch() * AGGREGATION_PRE_COMPUTE_EPOCHS;
+        let period_start = first_slot_of_period::<E>(1, &spec);
+
+        // Proofs are only computed `AGGREGATION_PRE_COMPUTE_EPOCHS` ahead, not for the whole
+        // period.
+        assert_eq!(
+            duties.aggregation_pre_compute_slot(1, period_start, &spec),
+            period_start + lookahead
+        );
+        assert_eq!(
+            duties.aggregation_pre_compute_slot(1, period_start + 100, &spec),
+            period_start + 100 + lookahead
+        );
+
+        // The lookahead is capped at the end of the period.
+        let last_slot = last_slot_of_period::<E>(1, &spec);
+        assert_eq!(
+            duties.aggregation_pre_compute_slot(1, last_slot - 1, &spec),
+            last_slot
+        );
+    }
+
+    #[test]
+    fn next_period_pre_compute_starts_within_lookahead() {
+        let spec = spec();
+        let next_period_start = first_slot_of_period::<E>(1, &spec);
+        assert_eq!(next_period_start, Slot::new(slots_per_period(&spec)));
+
+        for (distributed, lookahead) in [
+            (false, E::slots_per_epoch() * AGGREGATION_PRE_COMPUTE_EPOCHS),
+            (true, AGGREGATION_PRE_COMPUTE_SLOTS_DISTRIBUTED),
+        ] {
+            let duties = SyncDutiesMap::<E>::new(distributed);
+
+            // Too early in the current period to compute proofs for the next one.
+            assert!(!duties.should_pre_compute(1, Slot::new(0), &spec));
+            assert!(!duties.should_pre_compute(1, next_period_start - lookahead - 1, &spec));
+
+            // Once the next period is within the lookahead, compute proofs up to the lookahead.
+            let slot = next_period_start - lookahead;
+            assert!(duties.should_pre_compute(1, slot, &spec));
+            assert_eq!(
+                duties.aggregation_pre_compute_slot(1, slot, &spec),
+                next_period_start
+            );
+        }
+    }
+
+    #[test]
+    fn distributed_pre_compute_slot() {
+        let spec = spec();
+        let duties = SyncDutiesMap::<E>::new(true);
+        let slot = first_slot_of_period::<E>(1, &spec) + 10;
+        assert_eq!(
+            duties.aggregation_pre_compute_slot(1, slot, &spec),
+            slot + AGGREGATION_PRE_COMPUTE_SLOTS_DISTRIBUTED
+        );
+    }
+}
@@ -12,8 +12,9 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::time::{sleep, sleep_until, Duration, Instant};
 use types::{
-    ChainSpec, EthSpec, Hash256, PublicKeyBytes, Slot, SyncCommitteeSubscription,
-    SyncContributionData, SyncDuty, SyncSelectionProof, SyncSubnetId,
+    ChainSpec, EthSpec, Hash256, PublicKeyBytes, SignedContributionAndProof, Slot,
+    SyncCommitteeContribution, SyncCommitteeSubscription, SyncContributionData, SyncDuty,
+    SyncSelectionProof, SyncSubnetId,
 };
 use validator_store::{Error as ValidatorStoreError, ValidatorStore};
 
@@ -85,6 +86,17 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .unwrap_or(false)
     }
 
+    /// Returns the sync committee period of `slot`, which is used as the sticky session for
+    /// requests made to the beacon nodes.
+    ///
+    /// Keeping each period on one beacon node avoids the messages and contributions of a committee
+    /// being split between nodes with different views of the head.
+    fn sync_committee_session(&self, slot: Slot) -> u64 {
+        slot.epoch(E::slots_per_epoch())
+            .sync_committee_period(&self.duties_service.spec)
+            .unwrap_or_default()
+    }
+
     pub fn start_update_service(self, spec: &ChainSpec) -> Result<(), String> {
         let log = self.context.log().clone();
         let slot_duration = Duration::from_secs(spec.seconds_per_slot);
@@ -176,8 +188,13 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         // Fetch `block_root` with non optimistic execution for `SyncCommitteeContribution`.
         let response = self
             .beacon_nodes
-            .first_success(
+            .first_success_sticky(
+                self.sync_committee_session(slot),
                 |beacon_node| async move {
+                    let _timer = validator_metrics::start_timer_vec(
+                        &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[validator_metrics::SYNC_COMMITTEE_BLOCK_ROOT_HTTP_GET],
+                    );
                     match beacon_node.get_beacon_blocks_root(BlockId::Head).await {
                         Ok(Some(block)) if block.execution_optimistic == Some(false) => {
                             Ok(block)
@@ -248,6 +265,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
     ) -> Result<(), ()> {
         let log = self.context.log();
 
+        let signing_timer = validator_metrics::start_timer_vec(
+            &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[validator_metrics::SYNC_COMMITTEE_MESSAGES],
+        );
+
         // Create futures to produce sync committee signatures.
         let signature_futures = validator_duties.iter().map(|duty| async move {
             match self
@@ -292,9 +314,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        drop(signing_timer);
 
         self.beacon_nodes
             .request(ApiTopic::SyncCommittee, |beacon_node| async move {
+                let _timer = validator_metrics::start_timer_vec(
+                    &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                    &[validator_metrics::SYNC_COMMITTEE_MESSAGES_HTTP_POST],
+                );
                 beacon_node
                     .post_beacon_pool_sync_committee_signatures(committee_signatures)
                     .await
@@ -361,17 +388,24 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         let contribution = &self
             .beacon_nodes
-            .first_success(|beacon_node| async move {
-                let sync_contribution_data = SyncContributionData {
-                    slot,
-                    beacon_block_root,
-                    subcommittee_index: subnet_id.into(),
-                };
+            .first_success_sticky(
+                self.sync_committee_session(slot),
+                |beacon_node| async move {
+                    let _timer = validator_metrics::start_timer_vec(
+                        &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[validator_metrics::SYNC_CONTRIBUTIONS_HTTP_GET],
+                    );
+                    let sync_contribution_data = SyncContributionData {
+                        slot,
+                        beacon_block_root,
+                        subcommittee_index: subnet_id.into(),
+                    };
 
-                beacon_node
-                    .get_validator_sync_committee_contribution::<E>(&sync_contribution_data)
-                    .await
-            })
+                    beacon_node
+                        .get_validator_sync_committee_contribution::<E>(&sync_contribution_data)
+                        .await
+                },
+            )
             .await
             .map_err(|e| {
                 crit!(
@@ -392,58 +426,58 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             })?
             .data;
 
-        // Create futures to produce signed contributions.
-        let signature_futures = subnet_aggregators.into_iter().map(
-            |(aggregator_index, aggregator_pk, selection_proof)| async move {
-                match self
-                    .validator_store
-                    .produce_signed_contribution_and_proof(
-                        aggregator_index,
-                        aggregator_pk,
-                        contribution.clone(),
-                        selection_proof,
-                    )
-                    .await
-                {
-                    Ok(signed_contribution) => Some(signed_contribution),
-                    Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                        // A pubkey can be missing when a validator was recently
-                        // removed via the API.
-                        debug!(
-                            log,
-                            "Missing pubkey for sync contribution";
-                            "pubkey" => ?pubkey,
-                            "slot" => slot,
-                        );
-                        None
-                    }
-                    Err(e) => {
-                        crit!(
-                            log,
-                            "Unable to sign sync committee contribution";
-                            "slot" => slot,
-                            "error" => ?e,
-                        );
-                        None
-                    }
-                }
-            },
+        let signing_timer = validator_metrics::start_timer_vec(
+            &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+            &[validator_metrics::SYNC_CONTRIBUTIONS],
         );
 
-        // Execute all the futures in parallel, collecting any successful results.
-        let signed_contributions = &join_all(signature_futures)
+        // Sign the contribution for each aggregator in its own task, so that the aggregators of all
+        // subnets are spread across the runtime's worker threads rather than being driven by this
+        // task alone.
+        let signing_handles = subnet_aggregators
+            .into_iter()
+            .filter_map(|(aggregator_index, aggregator_pk, selection_proof)| {
+                let service = self.clone();
+                let contribution = contribution.clone();
+                self.context.executor.spawn_handle(
+                    async move {
+                        service
+                            .sign_contribution(
+                                slot,
+                                aggregator_index,
+                                aggregator_pk,
+                                contribution,
+                                selection_proof,
+                            )
+                            .await
+                    },
+                    "sync_committee_contribution_sign",
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Wait for all the signing tasks, collecting any successful results.
+        let signed_contributions = &join_all(signing_handles)
             .await
             .into_iter()
-            .flatten()
+            .filter_map(|result| result.ok().flatten().flatten())
             .collect::<Vec<_>>();
+        drop(signing_timer);
 
         // Publish to the beacon node.
         self.beacon_nodes
-            .first_success(|beacon_node| async move {
-                beacon_node
-                    .post_validator_contribution_and_proofs(signed_contributions)
-                    .await
-            })
+            .first_success_sticky(
+                self.sync_committee_session(slot),
+                |beacon_node| async move {
+                    let _timer = validator_metrics::start_timer_vec(
+                        &validator_metrics::SYNC_COMMITTEE_SERVICE_TIMES,
+                        &[validator_metrics::SYNC_CONTRIBUTIONS_HTTP_POST],
+                    );
+                    beacon_node
+                        .post_validator_contribution_and_proofs(signed_contributions)
+                        .await
+                },
+            )
             .await
             .map_err(|e| {
                 error!(
@@ -466,6 +500,50 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         Ok(())
     }
 
+    /// Sign `contribution` on behalf of a single aggregator.
+    async fn sign_contribution(
+        &self,
+        slot: Slot,
+        aggregator_index: u64,
+        aggregator_pk: PublicKeyBytes,
+        contribution: SyncCommitteeContribution<E>,
+        selection_proof: SyncSelectionProof,
+    ) -> Option<SignedContributionAndProof<E>> {
+        let log = self.context.log();
+        match self
+            .validator_store
+            .produce_signed_contribution_and_proof(
+                aggregator_index,
+                aggregator_pk,
+                contribution,
+                selection_proof,
+            )
+            .await
+        {
+            Ok(signed_contribution) => Some(signed_contribution),
+            Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                // A pubkey can be missing when a validator was recently
+                // removed via the API.
+                debug!(
+                    log,
+                    "Missing pubkey for sync contribution";
+                    "pubkey" => ?pubkey,
+                    "slot" => slot,
+                );
+                None
+            }
+            Err(e) => {
+                crit!(
+                    log,
+                    "Unable to sign sync committee contribution";
+                    "slot" => slot,
+                    "error" => ?e,
+                );
+                None
+            }
+        }
+    }
+
     fn spawn_subscription_tasks(&self) {
         let service = self.clone();
         let log = self.context.log().clone();
@@ -0,0 +1,128 @@
+//! The conditions a builder bid must satisfy before the validator client signs a blinded block.
+//!
+//! The beacon node chooses between the builder and local payloads using the builder boost factor.
+//! This policy is enforced by the validator client on top of that choice, so that it holds
+//! regardless of the beacon node's configuration.
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use types::Uint256;
+
+/// The percentage denominator used by `local_value_factor`.
+const PERCENT: u64 = 100;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BuilderPolicy {
+    /// The minimum execution payload value of a builder bid, in wei.
+    pub min_bid: Option<Uint256>,
+    /// If set, a locally built block is also requested whenever the beacon node returns a builder
+    /// block, and the builder bid must exceed this percentage of the local execution payload
+    /// value.
+    pub local_value_factor: Option<u64>,
+}
+
+/// The reason a builder bid was refused.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BidRejection {
+    BelowMinimum {
+        bid: Uint256,
+        min_bid: Uint256,
+    },
+    BelowLocalValue {
+        bid: Uint256,
+        local_value: Uint256,
+        factor: u64,
+    },
+}
+
+impl fmt::Display for BidRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BidRejection::BelowMinimum { bid, min_bid } => {
+                write!(f, "bid of {bid} wei is below the minimum of {min_bid} wei")
+            }
+            BidRejection::BelowLocalValue {
+                bid,
+                local_value,
+                factor,
+            } => write!(
+                f,
+                "bid of {bid} wei does not exceed {factor}% of the local payload value of \
+                {local_value} wei"
+            ),
+        }
+    }
+}
+
+impl BuilderPolicy {
+    /// Returns `true` if the policy places any conditions on builder bids.
+    pub fn is_enabled(&self) -> bool {
+        self.min_bid.is_some() || self.local_value_factor.is_some()
+    }
+
+    /// Returns `true` if the value of a locally built block is required to check a bid.
+    pub fn compares_local_value(&self) -> bool {
+        self.local_value_factor.is_some()
+    }
+
+    /// Check a builder `bid` against the policy. `local_value` is the execution payload value of
+    /// the locally built block, if one was obtained.
+    pub fn check_bid(
+        &self,
+        bid: Uint256,
+        local_value: Option<Uint256>,
+    ) -> Result<(), BidRejection> {
+        if let Some(min_bid) = self.min_bid {
+            if bid < min_bid {
+                return Err(BidRejection::BelowMinimum { bid, min_bid });
+            }
+        }
+
+        if let (Some(factor), Some(local_value)) = (self.local_value_factor, local_value) {
+            let scaled_bid = bid.saturating_mul(Uint256::from(PERCENT));
+            let scaled_local_value = local_value.saturating_mul(Uint256::from(factor));
+            if scaled_bid <= scaled_local_value {
+                return Err(BidRejection::BelowLocalValue {
+                    bid,
+                    local_value,
+                    factor,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_bid() {
+        let policy = BuilderPolicy {
+            min_bid: Some(Uint256::from(1_000u64)),
+            local_value_factor: Some(110),
+        };
+
+        assert!(policy.check_bid(Uint256::from(999u64), None).is_err());
+        assert!(policy.check_bid(Uint256::from(1_000u64), None).is_ok());
+
+        // The bid must exceed 110% of the local value.
+        let local_value = Some(Uint256::from(1_000u64));
+        assert_eq!(
+            policy.check_bid(Uint256::from(1_100u64), local_value),
+            Err(BidRejection::BelowLocalValue {
+                bid: Uint256::from(1_100u64),
+                local_value: Uint256::from(1_000u64),
+                factor: 110,
+            })
+        );
+        assert!(policy
+            .check_bid(Uint256::from(1_101u64), local_value)
+            .is_ok());
+
+        assert!(BuilderPolicy::default()
+            .check_bid(Uint256::from(0u64), local_value)
+            .is_ok());
+    }
+}
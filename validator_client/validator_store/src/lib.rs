@@ -1,28 +1,38 @@
-use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use account_utils::validator_definitions::{
+    AttestationTiming, PasswordStorage, ValidatorDefinition, ValidatorDefinitions,
+};
+use builder_policy::BuilderPolicy;
 use doppelganger_service::{DoppelgangerService, DoppelgangerStatus, DoppelgangerValidatorStore};
-use initialized_validators::InitializedValidators;
+use initialized_validators::{DefinitionsReload, InitializedValidators};
 use parking_lot::{Mutex, RwLock};
+use proposer_config::{ProposerConfigFile, ProposerSettings};
+use sensitive_url::SensitiveUrl;
 use serde::{Deserialize, Serialize};
 use signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod};
 use slashing_protection::{
     interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
+    SLASHING_PROTECTION_HISTORY_EPOCHS,
 };
-use slog::{crit, error, info, warn, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use task_executor::TaskExecutor;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
-    AggregateAndProof, Attestation, BeaconBlock, BlindedPayload, ChainSpec, ContributionAndProof,
-    Domain, Epoch, EthSpec, Fork, Graffiti, Hash256, PublicKeyBytes, SelectionProof, Signature,
-    SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SignedRoot,
-    SignedValidatorRegistrationData, SignedVoluntaryExit, Slot, SyncAggregatorSelectionData,
-    SyncCommitteeContribution, SyncCommitteeMessage, SyncSelectionProof, SyncSubnetId,
-    ValidatorRegistrationData, VoluntaryExit,
+    AggregateAndProof, Attestation, AttestationData, BeaconBlock, BlindedPayload, ChainSpec,
+    ContributionAndProof, Domain, Epoch, EthSpec, Fork, Graffiti, Hash256, PublicKeyBytes,
+    SelectionProof, Signature, SignedAggregateAndProof, SignedBeaconBlock,
+    SignedContributionAndProof, SignedRoot, SignedValidatorRegistrationData, SignedVoluntaryExit,
+    Slot, SyncAggregatorSelectionData, SyncCommitteeContribution, SyncCommitteeMessage,
+    SyncSelectionProof, SyncSubnetId, ValidatorRegistrationData, VoluntaryExit,
 };
 
+pub mod builder_policy;
+pub mod presigned_exits;
+pub mod proposer_config;
+
 #[derive(Debug, PartialEq)]
 pub enum Error {
     DoppelgangerProtected(PublicKeyBytes),
@@ -56,6 +66,14 @@ pub struct Config {
     pub prefer_builder_proposals: bool,
     /// Specifies the boost factor, a percentage multiplier to apply to the builder's payload value.
     pub builder_boost_factor: Option<u64>,
+    /// The conditions a builder bid must satisfy before a blinded block is signed.
+    #[serde(default)]
+    pub builder_policy: BuilderPolicy,
+    /// A file of per-validator proposer settings which is reloaded whenever it is modified.
+    pub proposer_config_file: Option<PathBuf>,
+    /// When attestations are published, unless overridden for a validator.
+    #[serde(default)]
+    pub attestation_timing: AttestationTiming,
 }
 
 /// A helper struct, used for passing data from the validator store to services.
@@ -66,10 +84,18 @@ pub struct ProposalData {
     pub builder_proposals: bool,
 }
 
-/// Number of epochs of slashing protection history to keep.
+/// The maximum number of unused pages returned to the filesystem after each pruning of the slashing
+/// protection database.
+///
+/// This bounds the time spent compacting, during which nothing can be signed. With the default page
+/// size of 4 KiB this reclaims up to 40 MiB per pruning.
+const SLASHING_PROTECTION_COMPACTION_PAGES: u32 = 10_240;
+
+/// The fraction of the slashing protection database's pages which must be unused before it is
+/// compacted after pruning.
 ///
-/// This acts as a maximum safe-guard against clock drift.
-const SLASHING_PROTECTION_HISTORY_EPOCHS: u64 = 512;
+/// Pruning usually frees only a few pages, which are reused by new records without compaction.
+const SLASHING_PROTECTION_COMPACTION_THRESHOLD: f64 = 0.1;
 
 /// Currently used as the default gas limit in execution clients.
 ///
@@ -91,6 +117,9 @@ pub struct ValidatorStore<T, E: EthSpec> {
     enable_web3signer_slashing_protection: bool,
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
+    builder_policy: BuilderPolicy,
+    proposer_config_file: Option<ProposerConfigFile>,
+    attestation_timing: AttestationTiming,
     task_executor: TaskExecutor,
     _phantom: PhantomData<E>,
 }
@@ -116,6 +145,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         task_executor: TaskExecutor,
         log: Logger,
     ) -> Self {
+        let proposer_config_file = config
+            .proposer_config_file
+            .clone()
+            .map(|path| ProposerConfigFile::new(path, log.clone()));
+
         Self {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
@@ -131,6 +165,9 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             enable_web3signer_slashing_protection: config.enable_web3signer_slashing_protection,
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
+            builder_policy: config.builder_policy.clone(),
+            proposer_config_file,
+            attestation_timing: config.attestation_timing,
             task_executor,
             _phantom: PhantomData,
         }
@@ -155,6 +192,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.doppelganger_service.is_some()
     }
 
+    /// Returns the doppelganger service, if doppelganger protection is enabled.
+    pub fn doppelganger_service(&self) -> Option<&Arc<DoppelgangerService>> {
+        self.doppelganger_service.as_ref()
+    }
+
     pub fn initialized_validators(&self) -> Arc<RwLock<InitializedValidators>> {
         self.validators.clone()
     }
@@ -232,6 +274,52 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Replace the validator definitions of `self` with `definitions`, which have been loaded from
+    /// the filesystem.
+    ///
+    /// As with `add_validator`, newly enabled validators are registered with the slashing
+    /// protection database and the doppelganger service before they start performing duties.
+    // FIXME: ignore this clippy lint until the validator store is refactored to use async locks
+    #[allow(clippy::await_holding_lock)]
+    pub async fn reload_validator_definitions(
+        &self,
+        definitions: ValidatorDefinitions,
+    ) -> Result<DefinitionsReload, String> {
+        let new_pubkeys = {
+            let validators = self.validators.read();
+            definitions
+                .as_slice()
+                .iter()
+                .filter(|def| def.enabled)
+                .map(|def| def.voting_public_key.compress())
+                .filter(|pubkey| validators.validator(pubkey).is_none())
+                .collect::<Vec<_>>()
+        };
+
+        self.slashing_protection
+            .register_validators(new_pubkeys.iter())
+            .map_err(|e| format!("failed to register validators: {:?}", e))?;
+
+        if let Some(doppelganger_service) = &self.doppelganger_service {
+            for pubkey in &new_pubkeys {
+                doppelganger_service.register_new_validator::<E, _>(*pubkey, &self.slot_clock)?;
+            }
+        }
+
+        // Decrypt the keystores before taking the write lock, which would stall signing.
+        let keystore_loader = self.validators.read().reload_keystore_loader(&definitions);
+        let keystores = keystore_loader
+            .load()
+            .await
+            .map_err(|e| format!("Unable to load keystores: {:?}", e))?;
+
+        self.validators
+            .write()
+            .reload_definitions(definitions, Some(keystores))
+            .await
+            .map_err(|e| format!("Unable to reload definitions: {:?}", e))
+    }
+
     /// Returns `ProposalData` for the provided `pubkey` if it exists in `InitializedValidators`.
     /// `ProposalData` fields include defaulting logic described in `get_fee_recipient_defaulting`,
     /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`.
@@ -241,14 +329,50 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .validator(pubkey)
             .map(|validator| ProposalData {
                 validator_index: validator.get_index(),
-                fee_recipient: self
-                    .get_fee_recipient_defaulting(validator.get_suggested_fee_recipient()),
-                gas_limit: self.get_gas_limit_defaulting(validator.get_gas_limit()),
-                builder_proposals: self
-                    .get_builder_proposals_defaulting(validator.get_builder_proposals()),
+                fee_recipient: self.get_fee_recipient_defaulting(self.proposer_setting(
+                    pubkey,
+                    validator.get_suggested_fee_recipient(),
+                    |settings| settings.fee_recipient,
+                )),
+                gas_limit: self.get_gas_limit_defaulting(self.proposer_setting(
+                    pubkey,
+                    validator.get_gas_limit(),
+                    |settings| settings.gas_limit,
+                )),
+                builder_proposals: self.get_builder_proposals_defaulting(self.proposer_setting(
+                    pubkey,
+                    validator.get_builder_proposals(),
+                    |settings| settings.builder_enabled,
+                )),
             })
     }
 
+    /// Returns a proposer setting for `pubkey`, given its value in `validator_definitions.yml`. The
+    /// priority order for fetching the setting is:
+    ///
+    /// 1. the validator's entry in the proposer config file
+    /// 2. validator_definitions.yml
+    /// 3. the default in the proposer config file
+    ///
+    /// If none of these are set, the caller should fall back to the process level value.
+    fn proposer_setting<V>(
+        &self,
+        pubkey: &PublicKeyBytes,
+        definition_value: Option<V>,
+        setting: impl Fn(&ProposerSettings) -> Option<V>,
+    ) -> Option<V> {
+        let Some(file) = &self.proposer_config_file else {
+            return definition_value;
+        };
+        let config = file.config();
+        config
+            .validators
+            .get(pubkey)
+            .and_then(&setting)
+            .or(definition_value)
+            .or_else(|| setting(&config.default))
+    }
+
     /// Attempts to resolve the pubkey to a validator index.
     ///
     /// It may return `None` if the `pubkey` is:
@@ -431,14 +555,34 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().graffiti(validator_pubkey)
     }
 
+    /// Returns the beacon nodes which should be preferred for the duties of `validator_pubkey`,
+    /// most preferred first. An empty list indicates no preference.
+    pub fn beacon_nodes(&self, validator_pubkey: &PublicKeyBytes) -> Vec<SensitiveUrl> {
+        self.validators.read().beacon_nodes(validator_pubkey)
+    }
+
+    /// Returns when `validator_pubkey` should publish its attestations. The priority order is:
+    /// 1. validator_definitions.yml
+    /// 2. process level attestation timing
+    pub fn attestation_timing(&self, validator_pubkey: &PublicKeyBytes) -> AttestationTiming {
+        self.validators
+            .read()
+            .attestation_timing(validator_pubkey)
+            .unwrap_or(self.attestation_timing)
+    }
+
     /// Returns the fee recipient for the given public key. The priority order for fetching
     /// the fee recipient is:
-    /// 1. validator_definitions.yml
+    /// 1. proposer config file and validator_definitions.yml, see `proposer_setting`
     /// 2. process level fee recipient
     pub fn get_fee_recipient(&self, validator_pubkey: &PublicKeyBytes) -> Option<Address> {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_fee_recipient_defaulting(self.suggested_fee_recipient(validator_pubkey))
+        self.get_fee_recipient_defaulting(self.proposer_setting(
+            validator_pubkey,
+            self.suggested_fee_recipient(validator_pubkey),
+            |settings| settings.fee_recipient,
+        ))
     }
 
     pub fn get_fee_recipient_defaulting(&self, fee_recipient: Option<Address>) -> Option<Address> {
@@ -457,11 +601,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns the gas limit for the given public key. The priority order for fetching
     /// the gas limit is:
     ///
-    /// 1. validator_definitions.yml
+    /// 1. proposer config file and validator_definitions.yml, see `proposer_setting`
     /// 2. process level gas limit
     /// 3. `DEFAULT_GAS_LIMIT`
     pub fn get_gas_limit(&self, validator_pubkey: &PublicKeyBytes) -> u64 {
-        self.get_gas_limit_defaulting(self.validators.read().gas_limit(validator_pubkey))
+        let gas_limit = self.validators.read().gas_limit(validator_pubkey);
+        self.get_gas_limit_defaulting(self.proposer_setting(
+            validator_pubkey,
+            gas_limit,
+            |settings| settings.gas_limit,
+        ))
     }
 
     fn get_gas_limit_defaulting(&self, gas_limit: Option<u64>) -> u64 {
@@ -477,27 +626,47 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     /// Returns a `bool` for the given public key that denotes whether this validator should use the
     /// builder API. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
+    /// 1. proposer config file and validator_definitions.yml, see `proposer_setting`
     /// 2. process level flag
     pub fn get_builder_proposals(&self, validator_pubkey: &PublicKeyBytes) -> bool {
         // If there is a `suggested_fee_recipient` in the validator definitions yaml
         // file, use that value.
-        self.get_builder_proposals_defaulting(
-            self.validators.read().builder_proposals(validator_pubkey),
-        )
+        self.get_builder_proposals_defaulting(self.validator_builder_proposals(validator_pubkey))
     }
 
     /// Returns a `u64` for the given public key that denotes the builder boost factor. The priority order for fetching this value is:
     ///
-    /// 1. validator_definitions.yml
+    /// 1. proposer config file and validator_definitions.yml, see `proposer_setting`
     /// 2. process level flag
     pub fn get_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
-        self.validators
-            .read()
-            .builder_boost_factor(validator_pubkey)
+        self.validator_builder_boost_factor(validator_pubkey)
             .or(self.builder_boost_factor)
     }
 
+    /// Returns the conditions a builder bid must satisfy before a blinded block is signed.
+    pub fn builder_policy(&self) -> &BuilderPolicy {
+        &self.builder_policy
+    }
+
+    /// Returns the per-validator `builder_proposals` setting, without the process level default.
+    fn validator_builder_proposals(&self, validator_pubkey: &PublicKeyBytes) -> Option<bool> {
+        let builder_proposals = self.validators.read().builder_proposals(validator_pubkey);
+        self.proposer_setting(validator_pubkey, builder_proposals, |settings| {
+            settings.builder_enabled
+        })
+    }
+
+    /// Returns the per-validator `builder_boost_factor`, without the process level default.
+    fn validator_builder_boost_factor(&self, validator_pubkey: &PublicKeyBytes) -> Option<u64> {
+        let builder_boost_factor = self
+            .validators
+            .read()
+            .builder_boost_factor(validator_pubkey);
+        self.proposer_setting(validator_pubkey, builder_boost_factor, |settings| {
+            settings.builder_boost_factor
+        })
+    }
+
     /// Returns a `bool` for the given public key that denotes whether this validator should prefer a
     /// builder payload. The priority order for fetching this value is:
     ///
@@ -537,12 +706,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             return Some(u64::MAX);
         }
 
-        self.validators
-            .read()
-            .builder_boost_factor(validator_pubkey)
+        self.validator_builder_boost_factor(validator_pubkey)
             .or_else(|| {
                 if matches!(
-                    self.validators.read().builder_proposals(validator_pubkey),
+                    self.validator_builder_proposals(validator_pubkey),
                     Some(false)
                 ) {
                     return Some(0);
@@ -674,6 +841,37 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_committee_position: usize,
         attestation: &mut Attestation<E>,
         current_epoch: Epoch,
+    ) -> Result<(), Error> {
+        let signing_root = self.attestation_signing_root(attestation.data());
+        self.sign_attestation_with_signing_root(
+            validator_pubkey,
+            validator_committee_position,
+            attestation,
+            current_epoch,
+            signing_root,
+        )
+        .await
+    }
+
+    /// Returns the signing root of `attestation_data`, which is the same for every validator.
+    pub fn attestation_signing_root(&self, attestation_data: &AttestationData) -> Hash256 {
+        let signing_context =
+            self.signing_context(Domain::BeaconAttester, attestation_data.target.epoch);
+        attestation_data.signing_root(signing_context.domain_hash(&self.spec))
+    }
+
+    /// As for `sign_attestation`, but with a `signing_root` which has already been computed using
+    /// `attestation_signing_root`.
+    ///
+    /// All members of a committee sign the same `AttestationData`, so computing its signing root
+    /// once for the whole committee avoids repeating the work for every validator.
+    pub async fn sign_attestation_with_signing_root(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        validator_committee_position: usize,
+        attestation: &mut Attestation<E>,
+        current_epoch: Epoch,
+        signing_root: Hash256,
     ) -> Result<(), Error> {
         // Make sure the target epoch is not higher than the current epoch to avoid potential attacks.
         if attestation.data().target.epoch > current_epoch {
@@ -689,15 +887,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         // Checking for slashing conditions.
         let signing_epoch = attestation.data().target.epoch;
         let signing_context = self.signing_context(Domain::BeaconAttester, signing_epoch);
-        let domain_hash = signing_context.domain_hash(&self.spec);
         let slashing_status = if signing_method
             .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
         {
-            self.slashing_protection.check_and_insert_attestation(
-                &validator_pubkey,
-                attestation.data(),
-                domain_hash,
-            )
+            self.slashing_protection
+                .check_and_insert_attestation_signing_root(
+                    &validator_pubkey,
+                    attestation.data().source.epoch,
+                    attestation.data().target.epoch,
+                    signing_root.into(),
+                )
         } else {
             Ok(Safe::Valid)
         };
@@ -706,10 +905,10 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             // We can safely sign this attestation.
             Ok(Safe::Valid) => {
                 let signature = signing_method
-                    .get_signature::<E, BlindedPayload<E>>(
+                    .get_signature_with_signing_root::<E, BlindedPayload<E>>(
                         SignableMessage::AttestationData(attestation.data()),
                         signing_context,
-                        &self.spec,
+                        signing_root,
                         &self.task_executor,
                     )
                     .await?;
@@ -1018,6 +1217,41 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(())
     }
 
+    /// Check that slashing protection data could be imported, without modifying the database.
+    pub fn check_slashing_protection_import(
+        &self,
+        interchange: Interchange,
+    ) -> Result<(), InterchangeError> {
+        self.slashing_protection
+            .check_interchange_info(interchange, self.genesis_validators_root)?;
+        Ok(())
+    }
+
+    /// Export slashing protection data for the given keys, leaving them enabled in the database.
+    ///
+    /// Keys which are unknown to the slashing protection database are omitted from the result.
+    pub fn copy_slashing_protection_for_keys(
+        &self,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<Interchange, InterchangeError> {
+        self.slashing_protection.with_transaction(|txn| {
+            let known_pubkeys = pubkeys
+                .iter()
+                .filter(|pubkey| {
+                    self.slashing_protection
+                        .get_validator_id_ignoring_status(txn, pubkey)
+                        .is_ok()
+                })
+                .copied()
+                .collect::<Vec<_>>();
+            self.slashing_protection.export_interchange_info_in_txn(
+                self.genesis_validators_root,
+                Some(&known_pubkeys),
+                txn,
+            )
+        })
+    }
+
     /// Export slashing protection data while also disabling the given keys in the database.
     ///
     /// If any key is unknown to the slashing protection database it will be silently omitted
@@ -1112,5 +1346,36 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         *last_prune = current_epoch;
 
         info!(self.log, "Completed pruning of slashing protection DB");
+
+        let free_page_fraction = match self.slashing_protection.free_page_fraction() {
+            Ok(free_page_fraction) => free_page_fraction,
+            Err(e) => {
+                warn!(
+                    self.log,
+                    "Error reading free space of slashing protection DB";
+                    "error" => ?e,
+                );
+                return;
+            }
+        };
+        if free_page_fraction < SLASHING_PROTECTION_COMPACTION_THRESHOLD {
+            return;
+        }
+
+        debug!(
+            self.log,
+            "Compacting slashing protection DB";
+            "free_page_fraction" => free_page_fraction,
+        );
+        if let Err(e) = self
+            .slashing_protection
+            .compact_incremental(SLASHING_PROTECTION_COMPACTION_PAGES)
+        {
+            warn!(
+                self.log,
+                "Error during compaction of slashing protection DB";
+                "error" => ?e,
+            );
+        }
     }
 }
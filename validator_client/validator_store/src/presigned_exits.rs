@@ -0,0 +1,417 @@
+//! Voluntary exits which are signed in advance and stored encrypted, ready to be broadcast later.
+//!
+//! Exits are signed in batches, each of which is encrypted with a passphrase chosen by the operator
+//! using the same scheme as EIP-2335 keystores. The public keys and epochs of the exits are stored
+//! in the clear so that they can be listed, but the signed messages can only be recovered with the
+//! passphrase.
+//!
+//! Broadcasting is armed by supplying the passphrase again, either for immediate broadcast or for
+//! broadcast at a future epoch. Armed exits are stored decrypted alongside the batches, so that a
+//! scheduled broadcast survives a restart. They are then as exposed as the voting keystore
+//! passwords stored in the validator definitions, and are deleted once broadcast.
+use account_utils::eth2_keystore::json_keystore::Crypto;
+use account_utils::eth2_keystore::{decrypt, encrypt, Error as KeystoreError};
+use account_utils::write_file_via_temporary;
+use filesystem::Error as FsError;
+use initialized_validators::key_cache::KeyCache;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use types::{Epoch, PublicKeyBytes, SignedVoluntaryExit};
+
+/// The file name for the pre-signed exits, in the validators directory.
+pub const PRESIGNED_EXITS_FILENAME: &str = "presigned_exits.json";
+
+/// The file name for the temporary file written while saving the pre-signed exits.
+const TEMP_PRESIGNED_EXITS_FILENAME: &str = ".presigned_exits.json.tmp";
+
+#[derive(Debug)]
+pub enum Error {
+    UnableToOpenFile(io::Error),
+    UnableToParseFile(serde_json::Error),
+    UnableToEncodeFile(serde_json::Error),
+    UnableToWriteFile(FsError),
+    UnableToEncrypt(KeystoreError),
+    UnableToDecrypt(KeystoreError),
+    UnableToEncodeExits(serde_json::Error),
+    UnableToDecodeExits(serde_json::Error),
+    /// None of the batches containing the requested exits could be decrypted with the passphrase.
+    IncorrectPassphrase,
+}
+
+/// The unencrypted details of a pre-signed exit.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PresignedExitInfo {
+    pub pubkey: PublicKeyBytes,
+    pub validator_index: u64,
+    pub epoch: Epoch,
+}
+
+/// A set of exits encrypted with the same passphrase.
+#[derive(Clone, Serialize, Deserialize)]
+struct Batch {
+    /// The exits in the batch which have not been deleted or broadcast.
+    exits: Vec<PresignedExitInfo>,
+    /// The encrypted list of `(pubkey, SignedVoluntaryExit)` pairs.
+    crypto: Crypto,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PresignedExitsFile {
+    batches: Vec<Batch>,
+    /// The decrypted exits which are waiting to be broadcast.
+    #[serde(default)]
+    armed: Vec<ArmedExit>,
+}
+
+/// A decrypted exit which will be broadcast at `broadcast_epoch`.
+#[derive(Clone, Serialize, Deserialize)]
+struct ArmedExit {
+    pubkey: PublicKeyBytes,
+    broadcast_epoch: Epoch,
+    exit: SignedVoluntaryExit,
+}
+
+/// The pre-signed exits of a validator client.
+pub struct PresignedExits {
+    validators_dir: PathBuf,
+    file: Mutex<PresignedExitsFile>,
+}
+
+impl PresignedExits {
+    /// Open the pre-signed exits in `validators_dir`, or start with none if the file doesn't exist.
+    pub fn open_or_create(validators_dir: &Path) -> Result<Self, Error> {
+        let path = validators_dir.join(PRESIGNED_EXITS_FILENAME);
+        let file = if path.exists() {
+            let file = File::open(&path).map_err(Error::UnableToOpenFile)?;
+            serde_json::from_reader(file).map_err(Error::UnableToParseFile)?
+        } else {
+            PresignedExitsFile::default()
+        };
+
+        Ok(Self {
+            validators_dir: validators_dir.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Returns the details of all stored exits, along with the epoch at which each will be
+    /// broadcast if it has been armed.
+    pub fn list(&self) -> Vec<(PresignedExitInfo, Option<Epoch>)> {
+        let file = self.file.lock();
+        file.batches
+            .iter()
+            .flat_map(|batch| batch.exits.iter().cloned())
+            .map(|info| {
+                let broadcast_epoch = file
+                    .armed
+                    .iter()
+                    .find(|armed| armed.pubkey == info.pubkey)
+                    .map(|armed| armed.broadcast_epoch);
+                (info, broadcast_epoch)
+            })
+            .collect()
+    }
+
+    /// Encrypt `exits` with `passphrase` and save them as a new batch, replacing any exits already
+    /// stored for the same validators.
+    ///
+    /// This function is slow since it derives a key from the passphrase.
+    pub fn add(
+        &self,
+        exits: Vec<(PublicKeyBytes, SignedVoluntaryExit)>,
+        passphrase: &[u8],
+    ) -> Result<(), Error> {
+        let plain_text = serde_json::to_vec(&exits).map_err(Error::UnableToEncodeExits)?;
+        let mut crypto = KeyCache::init_crypto();
+        let (cipher_text, checksum) = encrypt(
+            &plain_text,
+            passphrase,
+            &crypto.kdf.params,
+            &crypto.cipher.params,
+        )
+        .map_err(Error::UnableToEncrypt)?;
+        crypto.cipher.message = cipher_text.into();
+        crypto.checksum.message = checksum.to_vec().into();
+
+        let batch = Batch {
+            exits: exits
+                .iter()
+                .map(|(pubkey, exit)| PresignedExitInfo {
+                    pubkey: *pubkey,
+                    validator_index: exit.message.validator_index,
+                    epoch: exit.message.epoch,
+                })
+                .collect(),
+            crypto,
+        };
+
+        let pubkeys = exits.iter().map(|(pubkey, _)| *pubkey).collect::<Vec<_>>();
+        let mut file = self.file.lock();
+        remove_exits(&mut file, &pubkeys);
+        file.batches.push(batch);
+        self.save(&file)
+    }
+
+    /// Decrypt the stored exits for `pubkeys`, or all stored exits if `None`, and arm them for
+    /// broadcast at `broadcast_epoch`.
+    ///
+    /// Batches which can't be decrypted with `passphrase` are skipped. Returns the details of the
+    /// armed exits, or an error if none of the requested exits could be decrypted.
+    ///
+    /// This function is slow since it derives a key from the passphrase for each batch.
+    pub fn arm(
+        &self,
+        pubkeys: Option<&[PublicKeyBytes]>,
+        passphrase: &[u8],
+        broadcast_epoch: Epoch,
+    ) -> Result<Vec<PresignedExitInfo>, Error> {
+        let is_requested = |pubkey: &PublicKeyBytes| pubkeys.map_or(true, |p| p.contains(pubkey));
+
+        let batches = self
+            .file
+            .lock()
+            .batches
+            .iter()
+            .filter(|batch| batch.exits.iter().any(|info| is_requested(&info.pubkey)))
+            .cloned()
+            .collect::<Vec<_>>();
+        if batches.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut decrypted = vec![];
+        for batch in batches {
+            let plain_text = match decrypt(passphrase, &batch.crypto) {
+                Ok(plain_text) => plain_text,
+                Err(KeystoreError::InvalidPassword) => continue,
+                Err(e) => return Err(Error::UnableToDecrypt(e)),
+            };
+            let exits: Vec<(PublicKeyBytes, SignedVoluntaryExit)> =
+                serde_json::from_slice(plain_text.as_bytes())
+                    .map_err(Error::UnableToDecodeExits)?;
+            decrypted.extend(
+                exits
+                    .into_iter()
+                    .filter(|(pubkey, _)| is_requested(pubkey))
+                    // Ignore exits which have been deleted or broadcast since the batch was saved.
+                    .filter(|(pubkey, _)| batch.exits.iter().any(|info| info.pubkey == *pubkey)),
+            );
+        }
+        if decrypted.is_empty() {
+            return Err(Error::IncorrectPassphrase);
+        }
+
+        // Exits which were deleted while decrypting are not armed.
+        let mut file = self.file.lock();
+        let mut armed_infos = vec![];
+        for (pubkey, exit) in decrypted {
+            let Some(info) = file
+                .batches
+                .iter()
+                .flat_map(|batch| batch.exits.iter())
+                .find(|info| info.pubkey == pubkey)
+                .cloned()
+            else {
+                continue;
+            };
+            file.armed.retain(|armed| armed.pubkey != pubkey);
+            file.armed.push(ArmedExit {
+                pubkey,
+                broadcast_epoch,
+                exit,
+            });
+            armed_infos.push(info);
+        }
+        self.save(&file)?;
+        Ok(armed_infos)
+    }
+
+    /// Delete the stored exits for `pubkeys`, returning the public keys of the deleted exits.
+    ///
+    /// The encrypted exits are removed from the file once every exit in their batch has been
+    /// deleted or broadcast.
+    pub fn delete(&self, pubkeys: &[PublicKeyBytes]) -> Result<Vec<PublicKeyBytes>, Error> {
+        let mut file = self.file.lock();
+        let deleted = remove_exits(&mut file, pubkeys);
+        self.save(&file)?;
+        Ok(deleted)
+    }
+
+    /// Returns the armed exits which are due to be broadcast at `current_epoch`.
+    pub fn due_for_broadcast(
+        &self,
+        current_epoch: Epoch,
+    ) -> Vec<(PublicKeyBytes, SignedVoluntaryExit)> {
+        self.file
+            .lock()
+            .armed
+            .iter()
+            .filter(|armed| armed.broadcast_epoch <= current_epoch)
+            .map(|armed| (armed.pubkey, armed.exit.clone()))
+            .collect()
+    }
+
+    /// Delete the exit for `pubkey` after it has been broadcast.
+    pub fn broadcast_complete(&self, pubkey: PublicKeyBytes) -> Result<(), Error> {
+        self.delete(&[pubkey]).map(|_| ())
+    }
+
+    /// Stop broadcasting the exit for `pubkey` after the beacon node rejected it.
+    ///
+    /// The encrypted exit is kept, so it can be armed again if the rejection was temporary (e.g.
+    /// the exit was broadcast before its epoch).
+    pub fn broadcast_rejected(&self, pubkey: PublicKeyBytes) -> Result<(), Error> {
+        let mut file = self.file.lock();
+        file.armed.retain(|armed| armed.pubkey != pubkey);
+        self.save(&file)
+    }
+
+    fn save(&self, file: &PresignedExitsFile) -> Result<(), Error> {
+        let bytes = serde_json::to_vec(file).map_err(Error::UnableToEncodeFile)?;
+        write_file_via_temporary(
+            &self.validators_dir.join(PRESIGNED_EXITS_FILENAME),
+            &self.validators_dir.join(TEMP_PRESIGNED_EXITS_FILENAME),
+            &bytes,
+        )
+        .map_err(Error::UnableToWriteFile)
+    }
+}
+
+/// Remove the exits for `pubkeys` from `file`, dropping any batches left empty and disarming the
+/// removed exits. Returns the public keys of the removed exits.
+fn remove_exits(file: &mut PresignedExitsFile, pubkeys: &[PublicKeyBytes]) -> Vec<PublicKeyBytes> {
+    let mut removed = vec![];
+    for batch in &mut file.batches {
+        batch.exits.retain(|info| {
+            let remove = pubkeys.contains(&info.pubkey);
+            if remove {
+                removed.push(info.pubkey);
+            }
+            !remove
+        });
+    }
+    file.batches.retain(|batch| !batch.exits.is_empty());
+    file.armed.retain(|armed| !pubkeys.contains(&armed.pubkey));
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+    use types::{Signature, VoluntaryExit};
+
+    fn exit(validator_index: u64) -> (PublicKeyBytes, SignedVoluntaryExit) {
+        let mut pubkey = [0; 48];
+        pubkey[0] = validator_index as u8;
+        (
+            PublicKeyBytes::deserialize(&pubkey).unwrap(),
+            SignedVoluntaryExit {
+                message: VoluntaryExit {
+                    epoch: Epoch::new(10),
+                    validator_index,
+                },
+                signature: Signature::empty(),
+            },
+        )
+    }
+
+    #[test]
+    fn add_arm_and_broadcast() {
+        let dir = tempdir().unwrap();
+        let exits = PresignedExits::open_or_create(dir.path()).unwrap();
+        let (pubkey_0, exit_0) = exit(0);
+        let (pubkey_1, exit_1) = exit(1);
+
+        exits
+            .add(
+                vec![(pubkey_0, exit_0.clone()), (pubkey_1, exit_1)],
+                b"passphrase",
+            )
+            .unwrap();
+        assert_eq!(exits.list().len(), 2);
+
+        // Nothing is written to disk in the clear.
+        let contents = std::fs::read_to_string(dir.path().join(PRESIGNED_EXITS_FILENAME)).unwrap();
+        assert!(!contents.contains("signature"));
+
+        // The exits are persisted.
+        let exits = PresignedExits::open_or_create(dir.path()).unwrap();
+        assert!(exits.list().iter().all(|(_, armed)| armed.is_none()));
+
+        assert!(matches!(
+            exits.arm(Some(&[pubkey_0]), b"wrong", Epoch::new(12)),
+            Err(Error::IncorrectPassphrase)
+        ));
+        let armed = exits
+            .arm(Some(&[pubkey_0]), b"passphrase", Epoch::new(12))
+            .unwrap();
+        assert_eq!(armed.len(), 1);
+        assert_eq!(armed[0].pubkey, pubkey_0);
+
+        assert!(exits.due_for_broadcast(Epoch::new(11)).is_empty());
+        assert_eq!(
+            exits.due_for_broadcast(Epoch::new(12)),
+            vec![(pubkey_0, exit_0.clone())]
+        );
+
+        // The armed exit survives a restart.
+        let exits = PresignedExits::open_or_create(dir.path()).unwrap();
+        assert_eq!(
+            exits
+                .list()
+                .iter()
+                .find(|(info, _)| info.pubkey == pubkey_0)
+                .unwrap()
+                .1,
+            Some(Epoch::new(12))
+        );
+        assert_eq!(
+            exits.due_for_broadcast(Epoch::new(12)),
+            vec![(pubkey_0, exit_0)]
+        );
+
+        exits.broadcast_complete(pubkey_0).unwrap();
+        assert!(exits.due_for_broadcast(Epoch::new(12)).is_empty());
+        assert_eq!(exits.list().len(), 1);
+
+        // The broadcast exit can't be armed again, even though it remains in the encrypted batch.
+        let armed = exits.arm(None, b"passphrase", Epoch::new(12)).unwrap();
+        assert_eq!(armed.len(), 1);
+        assert_eq!(armed[0].pubkey, pubkey_1);
+
+        assert_eq!(exits.delete(&[pubkey_1]).unwrap(), vec![pubkey_1]);
+        assert!(exits.list().is_empty());
+        assert!(exits.due_for_broadcast(Epoch::new(12)).is_empty());
+    }
+
+    #[test]
+    fn rejected_exit_is_disarmed() {
+        let dir = tempdir().unwrap();
+        let exits = PresignedExits::open_or_create(dir.path()).unwrap();
+        let (pubkey, exit) = exit(0);
+
+        exits
+            .add(vec![(pubkey, exit.clone())], b"passphrase")
+            .unwrap();
+        exits.arm(None, b"passphrase", Epoch::new(12)).unwrap();
+        exits.broadcast_rejected(pubkey).unwrap();
+
+        // The exit is no longer broadcast, including after a restart.
+        assert!(exits.due_for_broadcast(Epoch::new(12)).is_empty());
+        let exits = PresignedExits::open_or_create(dir.path()).unwrap();
+        assert!(exits.due_for_broadcast(Epoch::new(12)).is_empty());
+
+        // The encrypted exit is kept and can be armed again.
+        assert_eq!(exits.list().len(), 1);
+        assert!(exits.list().iter().all(|(_, armed)| armed.is_none()));
+        exits.arm(None, b"passphrase", Epoch::new(13)).unwrap();
+        assert_eq!(
+            exits.due_for_broadcast(Epoch::new(13)),
+            vec![(pubkey, exit)]
+        );
+    }
+}
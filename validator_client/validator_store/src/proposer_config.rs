@@ -0,0 +1,165 @@
+//! Per-validator proposer settings loaded from a file which may be modified while the validator
+//! client is running.
+//!
+//! The file is expected to have the following structure, where every field is optional:
+//!
+//! ```yaml
+//! default:
+//!   fee_recipient: "0x..."
+//!   gas_limit: 30000000
+//!   builder_enabled: true
+//!   builder_boost_factor: 100
+//! validators:
+//!   "0x<voting public key>":
+//!     fee_recipient: "0x..."
+//!     builder_enabled: false
+//! ```
+use parking_lot::Mutex;
+use serde::Deserialize;
+use slog::{info, warn, Logger};
+use std::collections::HashMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use types::{Address, PublicKeyBytes};
+
+/// The settings applied to the block proposals of a validator.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerSettings {
+    #[serde(default)]
+    pub fee_recipient: Option<Address>,
+    #[serde(default)]
+    pub gas_limit: Option<u64>,
+    #[serde(default)]
+    pub builder_enabled: Option<bool>,
+    #[serde(default)]
+    pub builder_boost_factor: Option<u64>,
+}
+
+/// The contents of a proposer config file.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProposerConfig {
+    /// Settings for validators without an entry in `validators`, or for fields which their entry
+    /// leaves unset.
+    #[serde(default)]
+    pub default: ProposerSettings,
+    #[serde(default)]
+    pub validators: HashMap<PublicKeyBytes, ProposerSettings>,
+}
+
+impl ProposerConfig {
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let file = File::open(path)
+            .map_err(|e| format!("Unable to open proposer config file: {:?}", e))?;
+        serde_yaml::from_reader(file)
+            .map_err(|e| format!("Unable to parse proposer config file: {:?}", e))
+    }
+}
+
+struct LoadedConfig {
+    /// The modification time of the file when it was last loaded, or `None` if it hasn't been.
+    modified: Option<Option<SystemTime>>,
+    config: Arc<ProposerConfig>,
+}
+
+/// A proposer config file which is reloaded whenever it is modified.
+pub struct ProposerConfigFile {
+    path: PathBuf,
+    loaded: Mutex<LoadedConfig>,
+    log: Logger,
+}
+
+impl ProposerConfigFile {
+    /// Create a new `ProposerConfigFile`. The file is loaded when it is first read, so it should be
+    /// validated using `ProposerConfig::from_file` beforehand.
+    pub fn new(path: PathBuf, log: Logger) -> Self {
+        Self {
+            path,
+            loaded: Mutex::new(LoadedConfig {
+                modified: None,
+                config: Arc::new(ProposerConfig::default()),
+            }),
+            log,
+        }
+    }
+
+    /// Returns the contents of the file, reloading it first if it has been modified.
+    ///
+    /// If the modified file can't be read or is invalid, the previous contents continue to be used
+    /// until it is fixed.
+    pub fn config(&self) -> Arc<ProposerConfig> {
+        let mut loaded = self.loaded.lock();
+        let modified = modified_time(&self.path);
+        if loaded.modified != Some(modified) {
+            loaded.modified = Some(modified);
+            match ProposerConfig::from_file(&self.path) {
+                Ok(config) => {
+                    info!(
+                        self.log,
+                        "Loaded proposer config file";
+                        "path" => %self.path.display(),
+                        "validators" => config.validators.len(),
+                    );
+                    loaded.config = Arc::new(config);
+                }
+                Err(e) => warn!(
+                    self.log,
+                    "Failed to load proposer config file";
+                    "error" => e,
+                    "info" => "the previous configuration will be used until the file is fixed",
+                ),
+            }
+        }
+        loaded.config.clone()
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use logging::test_logger;
+    use std::str::FromStr;
+    use tempfile::tempdir;
+
+    const PK: &str = "0x800012708dc03f611751aad7a43a082142832b5c1aceed07ff9b543cf836381861352aa923c70eeb02018b638aa306aa";
+
+    #[test]
+    fn reload_on_modification() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("proposer_config.yml");
+        let pubkey = PublicKeyBytes::from_str(PK).unwrap();
+
+        std::fs::write(
+            &path,
+            format!(
+                "default:\n  gas_limit: 30000000\nvalidators:\n  \"{PK}\":\n    builder_enabled: false\n"
+            ),
+        )
+        .unwrap();
+        let file = ProposerConfigFile::new(path.clone(), test_logger());
+        let config = file.config();
+        assert_eq!(config.default.gas_limit, Some(30_000_000));
+        assert_eq!(config.validators[&pubkey].builder_enabled, Some(false));
+        assert_eq!(config.validators[&pubkey].gas_limit, None);
+
+        // Invalid files are ignored.
+        std::fs::write(&path, "default:\n  unknown_field: 1\n").unwrap();
+        file.loaded.lock().modified = None;
+        assert_eq!(file.config(), config);
+
+        std::fs::write(&path, "default:\n  builder_boost_factor: 50\n").unwrap();
+        file.loaded.lock().modified = None;
+        let config = file.config();
+        assert_eq!(config.default.builder_boost_factor, Some(50));
+        assert!(config.validators.is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+use account_utils::read_password_string;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use eth2::{lighthouse_vc::types::BroadcastExitsRequest, SensitiveUrl};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::{Epoch, PublicKeyBytes};
+
+use crate::{common::vc_http_client, DumpConfig};
+
+pub const CMD: &str = "broadcast-exits";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const PASSPHRASE_FILE_FLAG: &str = "passphrase-file";
+pub const BROADCAST_EPOCH_FLAG: &str = "broadcast-epoch";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Instructs a validator client to publish voluntary exits previously signed with \
+            the `presign-exits` command, either immediately or at a future epoch.",
+        )
+        .arg(
+            Arg::new(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help(
+                    "Comma-separated list of validators (pubkey) to exit. Defaults to every \
+                    validator with a pre-signed exit which can be decrypted with the passphrase.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(PASSPHRASE_FILE_FLAG)
+                .long(PASSPHRASE_FILE_FLAG)
+                .value_name("PATH")
+                .help("The file containing the passphrase used when the exits were signed.")
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(BROADCAST_EPOCH_FLAG)
+                .long(BROADCAST_EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch at which to publish the exits. Defaults to publishing them \
+                    immediately.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct BroadcastExitsConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: Option<Vec<PublicKeyBytes>>,
+    pub passphrase_path: PathBuf,
+    pub broadcast_epoch: Option<Epoch>,
+}
+
+impl BroadcastExitsConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = clap_utils::parse_optional::<String>(matches, VALIDATORS_FLAG)?
+            .map(|validators| {
+                validators
+                    .split(',')
+                    .map(|s| s.trim().parse())
+                    .collect::<Result<Vec<PublicKeyBytes>, _>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators,
+            passphrase_path: clap_utils::parse_required(matches, PASSPHRASE_FILE_FLAG)?,
+            broadcast_epoch: clap_utils::parse_optional(matches, BROADCAST_EPOCH_FLAG)?,
+        })
+    }
+}
+
+pub async fn cli_run(matches: &ArgMatches, dump_config: DumpConfig) -> Result<(), String> {
+    let config = BroadcastExitsConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: BroadcastExitsConfig) -> Result<(), String> {
+    let BroadcastExitsConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        passphrase_path,
+        broadcast_epoch,
+    } = config;
+
+    let passphrase = read_password_string(&passphrase_path)?;
+    let (http_client, _) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let exits = http_client
+        .post_lighthouse_exits_broadcast(&BroadcastExitsRequest {
+            pubkeys: validators.clone(),
+            passphrase,
+            broadcast_epoch,
+        })
+        .await
+        .map_err(|e| format!("Error scheduling exits: {:?}", e))?
+        .data;
+
+    if let Some(validators) = validators {
+        for validator in validators {
+            if !exits.iter().any(|exit| exit.voting_pubkey == validator) {
+                eprintln!("No pre-signed exit found for validator {:?}", validator);
+            }
+        }
+    }
+    for exit in &exits {
+        eprintln!(
+            "Exit for validator {:?} (index {}) will be published at epoch {}",
+            exit.voting_pubkey,
+            exit.validator_index,
+            exit.broadcast_epoch
+                .map_or_else(|| "unknown".to_string(), |epoch| epoch.to_string())
+        );
+    }
+    eprintln!("{} exit(s) scheduled for broadcast", exits.len());
+    Ok(())
+}
@@ -207,6 +207,11 @@ impl StandardDepositDataJson {
             deposit_data
         };
 
+        Self::from_deposit_data(deposit_data, spec)
+    }
+
+    /// Convert an already-signed `deposit_data` into the standard JSON format.
+    pub fn from_deposit_data(deposit_data: DepositData, spec: &ChainSpec) -> Result<Self, String> {
         let deposit_message_root = deposit_data.as_deposit_message().tree_hash_root();
         let deposit_data_root = deposit_data.tree_hash_root();
 
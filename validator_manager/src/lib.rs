@@ -6,12 +6,14 @@ use serde::Serialize;
 use std::path::PathBuf;
 use types::EthSpec;
 
+pub mod broadcast_exits;
 pub mod common;
 pub mod create_validators;
 pub mod delete_validators;
 pub mod import_validators;
 pub mod list_validators;
 pub mod move_validators;
+pub mod presign_exits;
 
 pub const CMD: &str = "validator_manager";
 
@@ -61,6 +63,8 @@ pub fn cli_app() -> Command {
         .subcommand(move_validators::cli_app())
         .subcommand(list_validators::cli_app())
         .subcommand(delete_validators::cli_app())
+        .subcommand(presign_exits::cli_app())
+        .subcommand(broadcast_exits::cli_app())
 }
 
 /// Run the account manager, returning an error if the operation did not succeed.
@@ -94,6 +98,12 @@ pub fn run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(),
                     Some((delete_validators::CMD, matches)) => {
                         delete_validators::cli_run(matches, dump_config).await
                     }
+                    Some((presign_exits::CMD, matches)) => {
+                        presign_exits::cli_run(matches, dump_config).await
+                    }
+                    Some((broadcast_exits::CMD, matches)) => {
+                        broadcast_exits::cli_run(matches, dump_config).await
+                    }
                     Some(("", _)) => Err("No command supplied. See --help.".to_string()),
                     Some((unknown, _)) => Err(format!(
                         "{} is not a valid {} command. See --help.",
@@ -2,13 +2,18 @@ use super::common::*;
 use crate::DumpConfig;
 use account_utils::{read_password_from_user, ZeroizeString};
 use clap::{Arg, ArgAction, ArgMatches, Command};
+use clap_utils::FLAG_HEADER;
 use eth2::{
     lighthouse_vc::{
+        http_client::ValidatorClientHttpClient,
         std_types::{
-            DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus, InterchangeJsonStr,
-            Status,
+            DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus,
+            ImportKeystoresRequest, Interchange, InterchangeJsonStr, KeystoreJsonStr, Status,
+        },
+        types::{
+            ExportKeystoresResponse, ExportSlashingProtectionRequest,
+            SingleExportKeystoresResponse, UpdateFeeRecipientRequest,
         },
-        types::{ExportKeystoresResponse, SingleExportKeystoresResponse},
     },
     SensitiveUrl,
 };
@@ -34,10 +39,22 @@ pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
 pub const BUILDER_PROPOSALS_FLAG: &str = "builder-proposals";
 pub const BUILDER_BOOST_FACTOR_FLAG: &str = "builder-boost-factor";
 pub const PREFER_BUILDER_PROPOSALS_FLAG: &str = "prefer-builder-proposals";
+pub const DRY_RUN_FLAG: &str = "dry-run";
+pub const SAFETY_DELAY_EPOCHS_FLAG: &str = "safety-delay-epochs";
 
 const NO_VALIDATORS_MSG: &str = "No validators present on source validator client";
 
-const UPLOAD_RETRY_WAIT: Duration = Duration::from_secs(5);
+/// The fields of the `lighthouse/spec` response required to move validators.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct VcSpec {
+    #[serde(with = "serde_utils::bytes_4_hex")]
+    genesis_fork_version: [u8; 4],
+    #[serde(with = "serde_utils::quoted_u64")]
+    seconds_per_slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    slots_per_epoch: u64,
+}
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum PasswordSource {
@@ -69,9 +86,13 @@ impl PasswordSource {
 pub fn cli_app() -> Command {
     Command::new(CMD)
         .about(
-            "Uploads validators to a validator client using the HTTP API. The validators \
-                are defined in a JSON file which can be generated using the \"create-validators\" \
-                command. This command only supports validators signing via a keystore on the local \
+            "Moves validators between two validator clients using the HTTP API. The \
+                validators are removed from the source validator client in a single request and \
+                uploaded to the destination validator client in a disabled state along with \
+                their slashing protection history, also in a single request. If any validator \
+                fails to move, all of them are returned to the source validator client. The \
+                validators are enabled once a safety delay has passed. \
+                This command only supports validators signing via a keystore on the local \
                 file system (i.e., not Web3Signer validators).",
         )
         .arg(
@@ -200,6 +221,31 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0),
         )
+        .arg(
+            Arg::new(DRY_RUN_FLAG)
+                .long(DRY_RUN_FLAG)
+                .help(
+                    "Check that the validators can be moved and that the destination validator \
+                    client accepts their slashing protection data, then print the plan without \
+                    removing any validators from the source validator client.",
+                )
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(SAFETY_DELAY_EPOCHS_FLAG)
+                .long(SAFETY_DELAY_EPOCHS_FLAG)
+                .value_name("EPOCHS")
+                .help(
+                    "The number of epochs to wait after removing the validators from the \
+                    source validator client before enabling them on the destination validator \
+                    client. Must be at least 1.",
+                )
+                .default_value("1")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -222,6 +268,8 @@ pub struct MoveConfig {
     pub fee_recipient: Option<Address>,
     pub gas_limit: Option<u64>,
     pub password_source: PasswordSource,
+    pub dry_run: bool,
+    pub safety_delay_epochs: u64,
 }
 
 impl MoveConfig {
@@ -246,6 +294,11 @@ impl MoveConfig {
             }
         };
 
+        let safety_delay_epochs = clap_utils::parse_required(matches, SAFETY_DELAY_EPOCHS_FLAG)?;
+        if safety_delay_epochs == 0 {
+            return Err(format!("--{SAFETY_DELAY_EPOCHS_FLAG} must be at least 1"));
+        }
+
         Ok(Self {
             src_vc_url: clap_utils::parse_required(matches, SRC_VC_URL_FLAG)?,
             src_vc_token_path: clap_utils::parse_required(matches, SRC_VC_TOKEN_FLAG)?,
@@ -263,6 +316,8 @@ impl MoveConfig {
             password_source: PasswordSource::Interactive {
                 stdin_inputs: cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG),
             },
+            dry_run: matches.get_flag(DRY_RUN_FLAG),
+            safety_delay_epochs,
         })
     }
 }
@@ -289,8 +344,14 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
         mut password_source,
         builder_boost_factor,
         prefer_builder_proposals,
+        dry_run,
+        safety_delay_epochs,
     } = config;
 
+    /*
+     * Validate the move without modifying either validator client.
+     */
+
     // Moving validators between the same VC is unlikely to be useful and probably indicates a user
     // error.
     if src_vc_url == dest_vc_url {
@@ -302,14 +363,26 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
 
     let (src_http_client, src_keystores) =
         vc_http_client(src_vc_url.clone(), &src_vc_token_path).await?;
-    let (dest_http_client, _dest_keystores) =
+    let (dest_http_client, dest_keystores) =
         vc_http_client(dest_vc_url.clone(), &dest_vc_token_path).await?;
 
     if src_keystores.is_empty() {
         return Err(NO_VALIDATORS_MSG.to_string());
     }
 
-    let pubkeys_to_move = match validators {
+    // Keystores and slashing protection are only meaningful on the network they came from.
+    let src_spec = vc_spec(&src_http_client, &src_vc_url).await?;
+    let dest_spec = vc_spec(&dest_http_client, &dest_vc_url).await?;
+    if src_spec.genesis_fork_version != dest_spec.genesis_fork_version {
+        return Err(format!(
+            "The source and destination validator clients are on different networks \
+            (genesis fork versions 0x{} and 0x{})",
+            hex::encode(src_spec.genesis_fork_version),
+            hex::encode(dest_spec.genesis_fork_version)
+        ));
+    }
+
+    let requested_pubkeys = match validators {
         Validators::All => src_keystores.iter().map(|v| v.validating_pubkey).collect(),
         Validators::Count(count) => {
             let mut viable_pubkeys: Vec<_> = src_keystores
@@ -356,69 +429,184 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
         .map(|k| (k.validating_pubkey, k))
         .collect();
 
-    let count = pubkeys_to_move.len();
-    for (i, &pubkey_to_move) in pubkeys_to_move.iter().enumerate() {
+    let mut pubkeys_to_move = Vec::with_capacity(requested_pubkeys.len());
+    for pubkey in requested_pubkeys {
         // Skip read-only validators rather than exiting. This makes it a bit easier to use the
         // "all" flag.
         if src_keystores_map
-            .get(&pubkey_to_move)
+            .get(&pubkey)
             .ok_or("Inconsistent src keystore map")?
             .readonly
             .unwrap_or(true)
         {
-            eprintln!("Skipping read-only validator {:?}", pubkey_to_move);
+            eprintln!("Skipping read-only validator {:?}", pubkey);
+        } else {
+            pubkeys_to_move.push(pubkey);
         }
+    }
 
-        let request = DeleteKeystoresRequest {
-            pubkeys: vec![pubkey_to_move],
-        };
-        let deleted = match src_http_client.delete_lighthouse_keystores(&request).await {
-            Ok(deleted) => deleted,
-            Err(e) => {
-                match src_http_client.get_keystores().await {
-                    Ok(response) => {
-                        if response
-                            .data
-                            .iter()
-                            .any(|v| v.validating_pubkey == pubkey_to_move)
-                        {
-                            eprintln!(
-                                "There was an error removing a validator, however the validator \
-                            is still present on the source validator client. The recommended \
-                            solution is to run this command again."
-                            );
-                        }
-                    }
-                    Err(_) => {
+    if pubkeys_to_move.is_empty() {
+        eprintln!("No validators to move.");
+        return Ok(());
+    }
+
+    let dest_pubkeys: HashSet<_> = dest_keystores.iter().map(|k| k.validating_pubkey).collect();
+    for pubkey in pubkeys_to_move
+        .iter()
+        .filter(|pubkey| dest_pubkeys.contains(pubkey))
+    {
+        eprintln!(
+            "Validator {:?} is already present on the destination validator client and may be \
+            active there. It will be disabled on the destination validator client when it is \
+            moved, and enabled again after the safety delay.",
+            pubkey
+        );
+    }
+
+    // The destination validator client refuses to import a keystore for a validator which it
+    // signs for remotely.
+    let dest_remotekeys = dest_http_client
+        .get_remotekeys()
+        .await
+        .map_err(|e| format!("Unable to list remote keys on {:?}: {:?}", dest_vc_url, e))?;
+    let remote_pubkeys = dest_remotekeys
+        .data
+        .iter()
+        .map(|k| k.pubkey)
+        .filter(|pubkey| pubkeys_to_move.contains(pubkey))
+        .collect::<Vec<_>>();
+    if !remote_pubkeys.is_empty() {
+        for pubkey in &remote_pubkeys {
+            eprintln!(
+                "Validator {:?} is a remote signer validator on the destination validator client",
+                pubkey
+            );
+        }
+        return Err(format!(
+            "{} validators are remote signer validators on {:?}",
+            remote_pubkeys.len(),
+            dest_vc_url
+        ));
+    }
+
+    let safety_delay = Duration::from_secs(
+        src_spec
+            .seconds_per_slot
+            .saturating_mul(src_spec.slots_per_epoch)
+            .saturating_mul(safety_delay_epochs),
+    );
+    let count = pubkeys_to_move.len();
+    eprintln!(
+        "Moving {} validators from {:?} to {:?}. The validators will be enabled on the \
+        destination validator client {} epoch(s) ({:?}) after they are removed from the source \
+        validator client.",
+        count, src_vc_url, dest_vc_url, safety_delay_epochs, safety_delay
+    );
+
+    if dry_run {
+        // Check the slashing protection data against the destination validator client using a
+        // copy exported from the source, so that neither validator client is modified.
+        let slashing_protection = src_http_client
+            .post_lighthouse_slashing_protection_export(&ExportSlashingProtectionRequest {
+                pubkeys: pubkeys_to_move.clone(),
+            })
+            .await
+            .map_err(|e| {
+                format!(
+                    "Unable to export slashing protection from {:?}: {:?}",
+                    src_vc_url, e
+                )
+            })?
+            .data;
+        for pubkey in pubkeys_to_move.iter().filter(|pubkey| {
+            !slashing_protection
+                .data
+                .iter()
+                .any(|data| data.pubkey == **pubkey)
+        }) {
+            eprintln!(
+                "Validator {:?} has no slashing protection data on the source validator client",
+                pubkey
+            );
+        }
+        dest_http_client
+            .post_lighthouse_slashing_protection_check(&slashing_protection)
+            .await
+            .map_err(|e| {
+                format!(
+                    "The slashing protection data would not be accepted by {:?}: {:?}",
+                    dest_vc_url, e
+                )
+            })?;
+
+        eprintln!("Dry run complete, no validators were moved.");
+        return Ok(());
+    }
+
+    /*
+     * Remove the validators from the source validator client.
+     */
+
+    // Delete all validators in a single request so the source validator client stops signing
+    // with all of them at once and exports one consistent slashing protection interchange.
+    let request = DeleteKeystoresRequest {
+        pubkeys: pubkeys_to_move.clone(),
+    };
+    let deleted = match src_http_client.delete_lighthouse_keystores(&request).await {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            match src_http_client.get_keystores().await {
+                Ok(response) => {
+                    if response
+                        .data
+                        .iter()
+                        .any(|v| pubkeys_to_move.contains(&v.validating_pubkey))
+                    {
                         eprintln!(
-                            "There was an error removing a validator and it's unclear if \
-                            the validator was removed or not. Manual user intervention is \
-                            required."
+                            "There was an error removing the validators, however some \
+                            validators are still present on the source validator client. The \
+                            recommended solution is to run this command again."
                         );
                     }
-                };
+                }
+                Err(_) => {
+                    eprintln!(
+                        "There was an error removing the validators and it's unclear if \
+                        the validators were removed or not. Manual user intervention is \
+                        required."
+                    );
+                }
+            };
 
-                return Err(format!("Deleting {:?} failed with {:?}", pubkey_to_move, e));
-            }
-        };
+            return Err(format!("Deleting validators failed with {:?}", e));
+        }
+    };
 
-        let ExportKeystoresResponse {
-            mut data,
-            slashing_protection,
-        } = deleted;
+    let ExportKeystoresResponse {
+        data,
+        slashing_protection,
+    } = deleted;
 
-        if data.len() != 1 {
-            return Err(format!(
-                "Too many deleted validators from VC: {}",
-                data.len()
-            ));
-        }
+    if data.len() != count {
+        eprintln!(
+            "The source validator client returned an unexpected response when removing the \
+            validators. Manual user intervention is required."
+        );
+        return Err(format!(
+            "Unexpected number of deleted validators from VC: {} (expected {})",
+            data.len(),
+            count
+        ));
+    }
 
-        let exported_validator = data
-            .pop()
-            .ok_or("VC responded with zero deleted validators")?;
+    // Collect every exported keystore before returning any error, so that all of them can be
+    // restored to the source validator client if the move is abandoned.
+    let mut keystores = Vec::with_capacity(count);
+    let mut passwords = Vec::with_capacity(count);
+    let mut errors = vec![];
 
-        let (voting_keystore, voting_keystore_password) = match exported_validator {
+    for (&pubkey_to_move, exported_validator) in pubkeys_to_move.iter().zip(data) {
+        match exported_validator {
             SingleExportKeystoresResponse {
                 status:
                     Status {
@@ -428,7 +616,10 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                 validating_keystore,
                 validating_keystore_password,
             } => match (validating_keystore, validating_keystore_password) {
-                (Some(keystore), Some(password)) => (keystore, password),
+                (Some(keystore), Some(password)) => {
+                    keystores.push(keystore);
+                    passwords.push(password);
+                }
                 (Some(keystore), None) => {
                     eprintln!(
                         "Validator {:?} requires a password, please provide it to continue \
@@ -445,13 +636,13 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                     );
 
                     // Read the password from the user, retrying if the password is incorrect.
-                    loop {
+                    let password = loop {
                         match password_source.read_password(&pubkey_to_move) {
                             Ok(password) => {
                                 if let Err(e) = keystore.decrypt_keypair(password.as_ref()) {
                                     eprintln!("Failed to decrypt keystore: {:?}", e);
                                 } else {
-                                    break (keystore, password);
+                                    break password;
                                 }
                             }
                             Err(e) => {
@@ -466,7 +657,9 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
 
                         // Add a sleep here to prevent spamming the console.
                         sleep(Duration::from_secs(1)).await;
-                    }
+                    };
+                    keystores.push(keystore);
+                    passwords.push(password);
                 }
                 (None, password_opt) => {
                     eprintln!(
@@ -478,8 +671,9 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                             from a mnemonic or backup.",
                         pubkey_to_move
                     );
-                    return Err(format!(
-                        "VC returned deleted but keystore not present (password {})",
+                    errors.push(format!(
+                        "VC returned deleted but keystore not present for {:?} (password {})",
+                        pubkey_to_move,
                         password_opt.is_some()
                     ));
                 }
@@ -495,14 +689,12 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                 eprintln!(
                     "Validator {:?} was not moved since it was not found or not active. This scenario \
                     is unexpected and might indicate that another process is also performing \
-                    an export from the source validator client. Exiting now for safety. \
-                    If there is definitely no other process exporting validators then it \
-                    may be safe to run this command again.",
+                    an export from the source validator client.",
                     pubkey_to_move
                 );
-                return Err(format!(
-                    "VC indicated that a previously known validator was {:?}",
-                    status,
+                errors.push(format!(
+                    "VC indicated that previously known validator {:?} was {:?}",
+                    pubkey_to_move, status,
                 ));
             }
             SingleExportKeystoresResponse {
@@ -515,148 +707,309 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                     required to recover from this scenario.",
                     pubkey_to_move
                 );
-                return Err(format!(
-                    "VC returned status {:?} with message {:?}",
-                    status, message
+                errors.push(format!(
+                    "VC returned status {:?} with message {:?} for {:?}",
+                    status, message, pubkey_to_move
                 ));
             }
-        };
+        }
+    }
 
-        let keystore_derivation_path = voting_keystore.0.path();
-
-        let validator_specification: ValidatorSpecification = ValidatorSpecification {
-            voting_keystore,
-            voting_keystore_password,
-            slashing_protection: Some(InterchangeJsonStr(slashing_protection)),
-            fee_recipient,
-            gas_limit,
-            builder_proposals,
-            builder_boost_factor,
-            prefer_builder_proposals,
-            // Allow the VC to choose a default "enabled" state. Since "enabled" is not part of
-            // the standard API, leaving this as `None` means we are not forced to use the
-            // non-standard API.
-            enabled: None,
-        };
+    let moved = Batch {
+        keystores,
+        passwords,
+        slashing_protection,
+    };
 
-        // We might as well just ignore validators that already exist on the destination machine,
-        // there doesn't appear to be much harm just adding them again and removing them from the
-        // source VC is an improvement.
-        let ignore_duplicates = true;
+    if !errors.is_empty() {
+        return Err(abandon_move(&src_http_client, &dest_http_client, &[], moved, errors).await);
+    }
 
-        loop {
-            match validator_specification
-                .clone()
-                .upload(&dest_http_client, ignore_duplicates)
-                .await
-            {
-                Ok(status) => {
-                    match status.status {
-                        ImportKeystoreStatus::Imported => {
-                            eprintln!("Moved keystore {} of {}", i + 1, count);
-                            break;
-                        }
-                        ImportKeystoreStatus::Duplicate => {
-                            eprintln!("Moved duplicate keystore {} of {} to the VC", i + 1, count);
-                            break;
-                        }
-                        ImportKeystoreStatus::Error => {
-                            eprintln!(
-                                "Upload of keystore {} of {} failed with message: {:?}.",
-                                i + 1,
-                                count,
-                                status.message,
-                            );
-                            // Retry uploading this validator.
-                            sleep_with_retry_message(
-                                &pubkey_to_move,
-                                keystore_derivation_path.as_deref(),
-                            )
-                            .await;
-                        }
-                    }
-                }
-                e @ Err(UploadError::InvalidPublicKey) => {
-                    eprintln!("Validator {} has an invalid public key", i);
-                    return Err(format!("{:?}", e));
-                }
-                Err(UploadError::DuplicateValidator(_)) => {
-                    return Err(
-                        "Duplicate validator detected when duplicates are ignored".to_string()
-                    );
-                }
-                Err(UploadError::FailedToListKeys(e)) => {
-                    eprintln!(
-                        "Failed to list keystores. Some keys may have been moved whilst \
-                        others may not. Error was {:?}",
-                        e
-                    );
-                    // Retry uploading this validator.
-                    sleep_with_retry_message(&pubkey_to_move, keystore_derivation_path.as_deref())
-                        .await;
-                }
-                Err(UploadError::KeyUploadFailed(e)) => {
-                    eprintln!(
-                        "Failed to upload keystore. Some keys may have been moved whilst \
-                        others may not. Error was {:?}",
-                        e
-                    );
-                    // Retry uploading this validator.
-                    sleep_with_retry_message(&pubkey_to_move, keystore_derivation_path.as_deref())
-                        .await;
-                }
-                Err(UploadError::IncorrectStatusCount(count)) => {
+    /*
+     * Upload the validators to the destination validator client, disabled.
+     */
+
+    // Import all validators in a single request, so that the destination validator client either
+    // accepts the slashing protection data for all of them or rejects the whole batch.
+    let request = ImportKeystoresRequest {
+        keystores: moved.keystores.clone(),
+        passwords: moved.passwords.clone(),
+        slashing_protection: Some(InterchangeJsonStr(moved.slashing_protection.clone())),
+    };
+    let statuses = match dest_http_client.post_keystores(&request).await {
+        Ok(response) => response.data,
+        Err(e) => {
+            // The validators may or may not have been imported, remove any that are new.
+            let imported = match dest_http_client.get_keystores().await {
+                Ok(response) => response
+                    .data
+                    .into_iter()
+                    .map(|k| k.validating_pubkey)
+                    .filter(|pubkey| {
+                        pubkeys_to_move.contains(pubkey) && !dest_pubkeys.contains(pubkey)
+                    })
+                    .collect::<Vec<_>>(),
+                Err(list_error) => {
                     eprintln!(
-                        "Keystore was uploaded, however the validator client returned an invalid response."
+                        "There was an error uploading the validators and it's unclear if they \
+                        were imported by the destination validator client or not. Manual user \
+                        intervention is required. The keystores have public keys {:?}.",
+                        pubkeys_to_move
                     );
                     return Err(format!(
-                        "Invalid status count in import response: {}",
-                        count
+                        "Uploading validators failed with {:?}, then listing validators failed \
+                        with {:?}",
+                        e, list_error
                     ));
                 }
-                Err(UploadError::FeeRecipientUpdateFailed(e)) => {
-                    eprintln!(
-                        "Failed to set fee recipient for validator {}. This value may need \
-                        to be set manually. Continuing with other validators. Error was {:?}",
-                        i, e
-                    );
-                    // Continue onto the next validator.
-                    break;
-                }
-                Err(UploadError::PatchValidatorFailed(e)) => {
+            };
+            let error = format!("Uploading validators failed with {:?}", e);
+            return Err(abandon_move(
+                &src_http_client,
+                &dest_http_client,
+                &imported,
+                moved,
+                vec![error],
+            )
+            .await);
+        }
+    };
+
+    if statuses.len() != count {
+        let error = format!(
+            "Invalid status count in import response: {} (expected {})",
+            statuses.len(),
+            count
+        );
+        let imported = pubkeys_to_move
+            .iter()
+            .filter(|pubkey| !dest_pubkeys.contains(pubkey))
+            .copied()
+            .collect::<Vec<_>>();
+        return Err(abandon_move(
+            &src_http_client,
+            &dest_http_client,
+            &imported,
+            moved,
+            vec![error],
+        )
+        .await);
+    }
+
+    let mut imported = vec![];
+    let mut errors = vec![];
+    for (&pubkey, status) in pubkeys_to_move.iter().zip(&statuses) {
+        match status.status {
+            ImportKeystoreStatus::Imported => imported.push(pubkey),
+            ImportKeystoreStatus::Duplicate => {
+                eprintln!(
+                    "Duplicate validators are ignored, ignoring {:?} which exists on the \
+                    destination validator client",
+                    pubkey
+                );
+            }
+            ImportKeystoreStatus::Error => errors.push(format!(
+                "Upload of {:?} failed with message: {:?}",
+                pubkey, status.message
+            )),
+        }
+    }
+
+    // Keep the validators disabled on the destination until the safety delay has passed, in case
+    // the source validator client signed a message which it didn't export.
+    if errors.is_empty() {
+        for &pubkey in &pubkeys_to_move {
+            if let Err(e) = dest_http_client
+                .patch_lighthouse_validators(
+                    &pubkey,
+                    Some(false),
+                    gas_limit,
+                    builder_proposals,
+                    builder_boost_factor,
+                    prefer_builder_proposals,
+                    None, // Grafitti field is not maintained between validator moves.
+                )
+                .await
+            {
+                errors.push(format!("Disabling {:?} failed with {:?}", pubkey, e));
+                break;
+            }
+
+            if let Some(fee_recipient) = fee_recipient {
+                if let Err(e) = dest_http_client
+                    .post_fee_recipient(
+                        &pubkey,
+                        &UpdateFeeRecipientRequest {
+                            ethaddress: fee_recipient,
+                        },
+                    )
+                    .await
+                {
                     eprintln!(
-                        "Failed to set some values on validator {} (e.g., builder, enabled or gas limit). \
-                        These values value may need to be set manually. Continuing with other validators. \
-                        Error was {:?}",
-                        i, e
+                        "Failed to set fee recipient for validator {:?}. This value may need \
+                        to be set manually. Error was {:?}",
+                        pubkey, e
                     );
-                    // Continue onto the next validator.
-                    break;
                 }
             }
+        }
+    }
+
+    if !errors.is_empty() {
+        return Err(abandon_move(
+            &src_http_client,
+            &dest_http_client,
+            &imported,
+            moved,
+            errors,
+        )
+        .await);
+    }
+    eprintln!(
+        "Uploaded {} validators to the destination validator client",
+        count
+    );
+
+    /*
+     * Wait for the safety delay, then enable the validators on the destination.
+     */
+
+    eprintln!(
+        "Waiting {:?} before enabling {} validators on the destination validator client. \
+        Exiting the application before it completes will leave the validators disabled, \
+        they can be enabled manually once the delay has passed.",
+        safety_delay, count
+    );
+    sleep(safety_delay).await;
+
+    let mut enable_errors = 0;
+    for pubkey in &pubkeys_to_move {
+        if let Err(e) = dest_http_client
+            .patch_lighthouse_validators(pubkey, Some(true), None, None, None, None, None)
+            .await
+        {
             eprintln!(
-                "Uploaded keystore {} of {} to the destination VC",
-                i + 1,
-                count
+                "Failed to enable validator {:?} on the destination validator client, it must \
+                be enabled manually. Error was {:?}",
+                pubkey, e
             );
+            enable_errors += 1;
         }
     }
 
+    if enable_errors > 0 {
+        return Err(format!(
+            "Failed to enable {} of {} validators on the destination validator client",
+            enable_errors, count
+        ));
+    }
+
     eprintln!("Done.");
 
     Ok(())
 }
 
-async fn sleep_with_retry_message(pubkey: &PublicKeyBytes, path: Option<&str>) {
-    let path = path.unwrap_or("<unspecified>");
-    eprintln!(
-        "Sleeping for {:?} before retrying. Exiting the application before it completes \
-        may result in the loss of a validator keystore. The keystore would need to be \
-        restored from a backup or mnemonic. The keystore which may be lost has a public \
-        key of {:?} and a derivation path of {}",
-        UPLOAD_RETRY_WAIT, pubkey, path
-    );
-    sleep(UPLOAD_RETRY_WAIT).await
+/// The keystores and slashing protection data removed from the source validator client.
+struct Batch {
+    keystores: Vec<KeystoreJsonStr>,
+    passwords: Vec<ZeroizeString>,
+    slashing_protection: Interchange,
+}
+
+/// Undo a move which failed part way through, by removing the validators in `imported` from the
+/// destination validator client and returning every validator in `batch` to the source validator
+/// client along with all of their slashing protection data.
+///
+/// Returns the error to report to the user.
+async fn abandon_move(
+    src_http_client: &ValidatorClientHttpClient,
+    dest_http_client: &ValidatorClientHttpClient,
+    imported: &[PublicKeyBytes],
+    batch: Batch,
+    errors: Vec<String>,
+) -> String {
+    for error in &errors {
+        eprintln!("{}", error);
+    }
+    eprintln!("Returning the validators to the source validator client.");
+
+    let Batch {
+        keystores,
+        passwords,
+        mut slashing_protection,
+    } = batch;
+
+    if !imported.is_empty() {
+        let request = DeleteKeystoresRequest {
+            pubkeys: imported.to_vec(),
+        };
+        match dest_http_client.delete_lighthouse_keystores(&request).await {
+            // Keep anything the destination signed whilst the validators were enabled there.
+            Ok(response) => slashing_protection
+                .data
+                .extend(response.slashing_protection.data),
+            Err(e) => {
+                eprintln!(
+                    "Failed to remove the validators {:?} from the destination validator client. \
+                    Manual user intervention is required to ensure that they are not active on \
+                    both validator clients. Error was {:?}",
+                    imported, e
+                );
+                return format!(
+                    "Encountered {} errors whilst moving validators, and failed to roll back",
+                    errors.len()
+                );
+            }
+        }
+    }
+
+    let count = keystores.len();
+    let request = ImportKeystoresRequest {
+        keystores,
+        passwords,
+        slashing_protection: Some(InterchangeJsonStr(slashing_protection)),
+    };
+    let restored = match src_http_client.post_keystores(&request).await {
+        Ok(response) => response
+            .data
+            .iter()
+            .filter(|status| status.status != ImportKeystoreStatus::Error)
+            .count(),
+        Err(e) => {
+            eprintln!("Failed to restore the validators: {:?}", e);
+            0
+        }
+    };
+
+    if restored == count {
+        format!(
+            "Encountered {} errors whilst moving validators, all validators were returned to the \
+            source validator client",
+            errors.len()
+        )
+    } else {
+        eprintln!(
+            "Only {} of {} validators were returned to the source validator client. The \
+            remaining keystores would need to be restored from a backup or mnemonic.",
+            restored, count
+        );
+        format!(
+            "Encountered {} errors whilst moving validators, and failed to roll back",
+            errors.len()
+        )
+    }
+}
+
+/// Fetches the parts of the spec of the validator client at `url` required to move validators.
+async fn vc_spec(
+    http_client: &ValidatorClientHttpClient,
+    url: &SensitiveUrl,
+) -> Result<VcSpec, String> {
+    http_client
+        .get_lighthouse_spec::<VcSpec>()
+        .await
+        .map(|response| response.data)
+        .map_err(|e| format!("Unable to read the spec from {:?}: {:?}", url, e))
 }
 
 // The tests use crypto and are too slow in debug.
@@ -687,6 +1040,7 @@ mod test {
         passwords: HashMap<PublicKeyBytes, Vec<String>>,
         use_password_files: bool,
         reuse_password_files: Option<usize>,
+        dry_run: bool,
     }
 
     impl TestBuilder {
@@ -704,9 +1058,15 @@ mod test {
                 passwords: <_>::default(),
                 use_password_files: false,
                 reuse_password_files: None,
+                dry_run: false,
             }
         }
 
+        fn dry_run(mut self) -> Self {
+            self.dry_run = true;
+            self
+        }
+
         fn move_back_again(mut self) -> Self {
             self.move_back_again = true;
             self
@@ -801,10 +1161,27 @@ mod test {
                 fee_recipient: None,
                 gas_limit: None,
                 password_source: PasswordSource::Testing(self.passwords.clone()),
+                dry_run: self.dry_run,
+                // Don't slow the tests down with a real safety delay.
+                safety_delay_epochs: 0,
             };
 
             let result = run(move_config).await;
 
+            if self.dry_run {
+                assert_eq!(
+                    src_vc_client.get_keystores().await.unwrap().data,
+                    src_vc_initial_keystores,
+                    "a dry run should not modify the source vc"
+                );
+                assert_eq!(
+                    dest_vc_client.get_keystores().await.unwrap().data,
+                    dest_vc_initial_keystores,
+                    "a dry run should not modify the dest vc"
+                );
+                return result;
+            }
+
             if result.is_ok() {
                 let src_vc_final_keystores = src_vc_client.get_keystores().await.unwrap().data;
                 let dest_vc_final_keystores = dest_vc_client.get_keystores().await.unwrap().data;
@@ -1264,6 +1641,97 @@ mod test {
             .assert_ok();
     }
 
+    #[tokio::test]
+    async fn two_validators_dry_run() {
+        TestBuilder::new()
+            .await
+            .with_src_validators(2, 0)
+            .await
+            .with_dest_validators(1, 10)
+            .await
+            .dry_run()
+            .run_test(|_| Validators::All)
+            .await
+            .assert_ok();
+    }
+
+    #[tokio::test]
+    async fn abandoned_move_returns_validators_to_source() {
+        let src_vc = ImportTestBuilder::new_with_http_config(ApiTester::default_http_config())
+            .await
+            .create_validators(2, 0)
+            .await
+            .run_test()
+            .await
+            .vc;
+        let dest_vc = ApiTester::new().await;
+        let src_initial_keystores = src_vc.client.get_keystores().await.unwrap().data;
+        let pubkeys: Vec<_> = src_initial_keystores
+            .iter()
+            .map(|k| k.validating_pubkey)
+            .collect();
+
+        // Remove the validators from the source and import them on the destination, as a move
+        // would before failing part way through.
+        let exported = src_vc
+            .client
+            .delete_lighthouse_keystores(&DeleteKeystoresRequest {
+                pubkeys: pubkeys.clone(),
+            })
+            .await
+            .unwrap();
+        let batch = Batch {
+            keystores: exported
+                .data
+                .iter()
+                .map(|v| v.validating_keystore.clone().unwrap())
+                .collect(),
+            passwords: exported
+                .data
+                .iter()
+                .map(|v| v.validating_keystore_password.clone().unwrap())
+                .collect(),
+            slashing_protection: exported.slashing_protection,
+        };
+        dest_vc
+            .client
+            .post_keystores(&ImportKeystoresRequest {
+                keystores: batch.keystores.clone(),
+                passwords: batch.passwords.clone(),
+                slashing_protection: Some(InterchangeJsonStr(batch.slashing_protection.clone())),
+            })
+            .await
+            .unwrap();
+
+        let error = abandon_move(
+            &src_vc.client,
+            &dest_vc.client,
+            &pubkeys,
+            batch,
+            vec!["error".to_string()],
+        )
+        .await;
+        assert_eq!(
+            error,
+            "Encountered 1 errors whilst moving validators, all validators were returned to the \
+            source validator client"
+        );
+
+        let src_final_keystores = src_vc.client.get_keystores().await.unwrap().data;
+        for keystore in &src_initial_keystores {
+            assert!(src_final_keystores.contains(keystore));
+        }
+        assert!(dest_vc
+            .client
+            .get_keystores()
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+        src_vc.ensure_key_cache_consistency().await;
+        dest_vc.ensure_key_cache_consistency().await;
+    }
+
     #[tokio::test]
     async fn one_validator_move_all_with_password_files() {
         TestBuilder::new()
@@ -0,0 +1,145 @@
+use account_utils::read_password_string;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use eth2::{lighthouse_vc::types::PresignExitsRequest, SensitiveUrl};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::{Epoch, PublicKeyBytes};
+
+use crate::{common::vc_http_client, DumpConfig};
+
+pub const CMD: &str = "presign-exits";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const VALIDATORS_FLAG: &str = "validators";
+pub const PASSPHRASE_FILE_FLAG: &str = "passphrase-file";
+pub const EPOCH_FLAG: &str = "epoch";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Signs voluntary exits for one or more validators using the HTTP API of a \
+            validator client. The exits are stored by the validator client, encrypted with a \
+            passphrase, and are only published once the `broadcast-exits` command is run \
+            with the same passphrase.",
+        )
+        .arg(
+            Arg::new(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VALIDATORS_FLAG)
+                .long(VALIDATORS_FLAG)
+                .value_name("STRING")
+                .help("Comma-separated list of validators (pubkey) to sign exits for.")
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(PASSPHRASE_FILE_FLAG)
+                .long(PASSPHRASE_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "The file containing the passphrase used to encrypt the signed exits. \
+                    The same passphrase is required to broadcast them.",
+                )
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(EPOCH_FLAG)
+                .long(EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch of the exit messages. Defaults to the current epoch of the \
+                    validator client.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct PresignExitsConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub validators: Vec<PublicKeyBytes>,
+    pub passphrase_path: PathBuf,
+    pub epoch: Option<Epoch>,
+}
+
+impl PresignExitsConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let validators = clap_utils::parse_required::<String>(matches, VALIDATORS_FLAG)?
+            .split(',')
+            .map(|s| s.trim().parse())
+            .collect::<Result<Vec<PublicKeyBytes>, _>>()?;
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            validators,
+            passphrase_path: clap_utils::parse_required(matches, PASSPHRASE_FILE_FLAG)?,
+            epoch: clap_utils::parse_optional(matches, EPOCH_FLAG)?,
+        })
+    }
+}
+
+pub async fn cli_run(matches: &ArgMatches, dump_config: DumpConfig) -> Result<(), String> {
+    let config = PresignExitsConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        run(config).await
+    }
+}
+
+async fn run(config: PresignExitsConfig) -> Result<(), String> {
+    let PresignExitsConfig {
+        vc_url,
+        vc_token_path,
+        validators,
+        passphrase_path,
+        epoch,
+    } = config;
+
+    let passphrase = read_password_string(&passphrase_path)?;
+    let (http_client, _) = vc_http_client(vc_url, &vc_token_path).await?;
+
+    let exits = http_client
+        .post_lighthouse_exits(&PresignExitsRequest {
+            pubkeys: validators,
+            epoch,
+            passphrase,
+        })
+        .await
+        .map_err(|e| format!("Error signing exits: {:?}", e))?
+        .data;
+
+    for exit in &exits {
+        eprintln!(
+            "Signed exit for validator {:?} (index {}) at epoch {}",
+            exit.voting_pubkey, exit.validator_index, exit.epoch
+        );
+    }
+    eprintln!(
+        "{} exit(s) signed and stored by the validator client",
+        exits.len()
+    );
+    Ok(())
+}